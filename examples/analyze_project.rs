@@ -0,0 +1,75 @@
+//! # Analyze a project with the library API
+//!
+//! Builds the capsule graph of a project and prints what ArchLens found:
+//! capsules per layer, relations and the most serious warnings.
+//!
+//! ```bash
+//! cargo run --example analyze_project                    # bundled fixtures
+//! cargo run --example analyze_project -- path/to/project
+//! ```
+
+use archlens::cli::handlers::build_project_graph;
+use archlens::types::Priority;
+use std::collections::BTreeMap;
+
+/// Fixture projects shipped next to the examples
+const FIXTURES: [&str; 3] = ["rust_shop", "ts_web", "py_service"];
+
+fn fixture(name: &str) -> String {
+    format!("{}/examples/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Text summary of one project
+fn analyze(project_path: &str) -> Result<String, String> {
+    let graph = build_project_graph(project_path)?;
+
+    let mut layers: BTreeMap<&str, usize> = BTreeMap::new();
+    for capsule in graph.capsules.values() {
+        *layers
+            .entry(capsule.layer.as_deref().unwrap_or("Unassigned"))
+            .or_default() += 1;
+    }
+
+    let mut out = format!(
+        "{}\n  capsules: {}, relations: {}\n",
+        project_path,
+        graph.capsules.len(),
+        graph.relations.len()
+    );
+    for (layer, count) in layers {
+        out.push_str(&format!("  layer {layer}: {count}\n"));
+    }
+    let serious = graph
+        .capsules
+        .values()
+        .flat_map(|c| &c.warnings)
+        .filter(|w| matches!(w.level, Priority::High | Priority::Critical))
+        .count();
+    out.push_str(&format!("  high/critical warnings: {serious}\n"));
+    Ok(out)
+}
+
+fn main() -> Result<(), String> {
+    let paths: Vec<String> = match std::env::args().nth(1) {
+        Some(path) => vec![path],
+        None => FIXTURES.iter().map(|name| fixture(name)).collect(),
+    };
+    for path in paths {
+        print!("{}", analyze(&path)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fixture_is_analyzed() {
+        for name in FIXTURES {
+            let summary = analyze(&fixture(name)).unwrap();
+            assert!(summary.contains("layer "), "{summary}");
+            assert!(!summary.contains("capsules: 0,"), "{summary}");
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! # Export reports
+//!
+//! Renders the AI-compact Markdown report and the JSON summary of a project,
+//! the same outputs as `archlens export <path> ai_compact` and the
+//! `export_ai_summary_json` MCP tool.
+//!
+//! ```bash
+//! cargo run --example export_reports                    # ts_web fixture
+//! cargo run --example export_reports -- path/to/project
+//! ```
+
+use archlens::cli::handlers::build_project_graph;
+use archlens::exporter::Exporter;
+
+fn fixture(name: &str) -> String {
+    format!("{}/examples/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// AI-compact report and pretty-printed JSON summary of one project
+fn export(project_path: &str) -> Result<(String, String), String> {
+    let graph = build_project_graph(project_path)?;
+    let exporter = Exporter::new();
+    let compact = exporter
+        .export_to_ai_compact(&graph)
+        .map_err(|e| e.to_string())?;
+    let summary = exporter
+        .export_to_ai_summary_json(&graph)
+        .map_err(|e| e.to_string())?;
+    let summary = serde_json::to_string_pretty(&summary).map_err(|e| e.to_string())?;
+    Ok((compact, summary))
+}
+
+fn main() -> Result<(), String> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| fixture("ts_web"));
+    let (compact, summary) = export(&path)?;
+    println!("{compact}");
+    println!("{summary}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_exports_both_formats() {
+        let (compact, summary) = export(&fixture("ts_web")).unwrap();
+        assert!(compact.contains("## "), "{compact}");
+        let summary: serde_json::Value = serde_json::from_str(&summary).unwrap();
+        assert!(summary["summary"]["components"].as_u64().unwrap() > 0);
+    }
+}
@@ -0,0 +1,15 @@
+from app.services.user_service import UserService
+
+service = UserService()
+
+
+def post_user(payload):
+    user = service.register(payload["id"], payload["email"])
+    return {"id": user.user_id, "domain": user.domain()}
+
+
+def get_user(user_id):
+    user = service.find(user_id)
+    if user is None:
+        return {"error": "not found"}
+    return {"id": user.user_id, "email": user.email}
@@ -0,0 +1,9 @@
+class User:
+    """Registered user."""
+
+    def __init__(self, user_id, email):
+        self.user_id = user_id
+        self.email = email
+
+    def domain(self):
+        return self.email.split("@")[-1]
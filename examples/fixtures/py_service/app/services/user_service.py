@@ -0,0 +1,18 @@
+from app.models.user import User
+
+
+class UserService:
+    """Registers and looks up users."""
+
+    def __init__(self):
+        self.users = {}
+
+    def register(self, user_id, email):
+        if user_id in self.users:
+            raise ValueError("user exists")
+        user = User(user_id, email)
+        self.users[user_id] = user
+        return user
+
+    def find(self, user_id):
+        return self.users.get(user_id)
@@ -0,0 +1,18 @@
+use crate::data::repository::OrderRepository;
+use crate::domain::order::Order;
+
+/// POST /orders
+pub fn create_order(repository: &mut OrderRepository, id: u32, total_cents: u64) -> u32 {
+    let order = Order::new(id, total_cents);
+    repository.save(order);
+    id
+}
+
+/// GET /orders/:id
+pub fn order_total(repository: &OrderRepository, id: u32) -> Option<u64> {
+    match repository.find(id) {
+        Some(order) if !order.is_free() => Some(order.total_cents),
+        Some(_) => Some(0),
+        None => None,
+    }
+}
@@ -0,0 +1,16 @@
+use crate::domain::order::Order;
+
+/// In-memory storage of orders
+pub struct OrderRepository {
+    orders: Vec<Order>,
+}
+
+impl OrderRepository {
+    pub fn save(&mut self, order: Order) {
+        self.orders.push(order);
+    }
+
+    pub fn find(&self, id: u32) -> Option<&Order> {
+        self.orders.iter().find(|order| order.id == id)
+    }
+}
@@ -0,0 +1,15 @@
+/// Order placed by a customer
+pub struct Order {
+    pub id: u32,
+    pub total_cents: u64,
+}
+
+impl Order {
+    pub fn new(id: u32, total_cents: u64) -> Self {
+        Self { id, total_cents }
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.total_cents == 0
+    }
+}
@@ -0,0 +1,9 @@
+import { CartItem } from '../services/cartService';
+
+export async function submitCart(items: CartItem[]): Promise<boolean> {
+  const response = await fetch('/api/cart', {
+    method: 'POST',
+    body: JSON.stringify(items),
+  });
+  return response.ok;
+}
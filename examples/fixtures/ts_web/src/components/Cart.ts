@@ -0,0 +1,18 @@
+import { CartService } from '../services/cartService';
+import { submitCart } from '../api/client';
+
+export class Cart {
+  constructor(private readonly service: CartService) {}
+
+  render(): string {
+    const total = this.service.totalCents();
+    if (total === 0) {
+      return 'Cart is empty';
+    }
+    return `Total: ${total / 100}`;
+  }
+
+  async checkout(): Promise<boolean> {
+    return submitCart([]);
+  }
+}
@@ -0,0 +1,17 @@
+export interface CartItem {
+  sku: string;
+  quantity: number;
+  priceCents: number;
+}
+
+export class CartService {
+  private items: CartItem[] = [];
+
+  add(item: CartItem): void {
+    this.items.push(item);
+  }
+
+  totalCents(): number {
+    return this.items.reduce((sum, item) => sum + item.quantity * item.priceCents, 0);
+  }
+}
@@ -0,0 +1,59 @@
+//! # Query the capsule graph
+//!
+//! Asks questions about a project the way `archlens ask` does:
+//! who uses a symbol, what a symbol uses and where it is defined.
+//!
+//! ```bash
+//! cargo run --example query_graph                                   # rust_shop fixture
+//! cargo run --example query_graph -- path/to/project "who uses Order?"
+//! ```
+
+use archlens::cli::handlers::build_project_graph;
+use archlens::graph_query::GraphIndex;
+use std::path::Path;
+
+fn fixture(name: &str) -> String {
+    format!("{}/examples/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// Answers to `questions` about one project
+fn ask(project_path: &str, questions: &[&str]) -> Result<String, String> {
+    let graph = build_project_graph(project_path)?;
+    let index = GraphIndex::new(&graph, Path::new(project_path));
+    let mut out = String::new();
+    for question in questions {
+        out.push_str(&format!(
+            "> {question}\n{}\n",
+            index.ask(question).to_text()
+        ));
+    }
+    Ok(out)
+}
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let answers = match (args.next(), args.next()) {
+        (Some(path), Some(question)) => ask(&path, &[&question])?,
+        _ => ask(
+            &fixture("rust_shop"),
+            &[
+                "who uses Order?",
+                "what does create_order use?",
+                "where is OrderRepository?",
+            ],
+        )?,
+    };
+    print!("{answers}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn questions_about_the_fixture_are_answered() {
+        let answers = ask(&fixture("rust_shop"), &["where is OrderRepository?"]).unwrap();
+        assert!(answers.contains("repository.rs"), "{answers}");
+    }
+}
@@ -0,0 +1,174 @@
+// Горячие точки: нормированная частота изменений (коммиты из git-истории
+// капсулы относительно самой изменяемой) × сложность. Сложный код, который
+// часто меняют, — первый кандидат на рефакторинг
+
+use super::AdvancedMetricsCalculator;
+use crate::code_origin::CodeOrigin;
+use crate::metadata_extractor::GIT_COMMITS_KEY;
+use crate::types::{Capsule, CapsuleGraph, CapsuleType};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Сколько горячих точек показывать по умолчанию
+pub const DEFAULT_HOTSPOT_LIMIT: usize = 10;
+
+/// Какая сложность умножается на частоту изменений
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityBasis {
+    /// Сложность капсулы из анализа (цикломатическая)
+    #[default]
+    Cyclomatic,
+    /// Когнитивная сложность по исходному коду капсулы
+    Cognitive,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hotspot {
+    pub component: String,
+    pub capsule_type: CapsuleType,
+    pub file: PathBuf,
+    pub line: usize,
+    pub commits: usize,
+    /// Коммиты относительно самой изменяемой капсулы (0..1)
+    pub churn: f32,
+    pub complexity: u32,
+    pub score: f32,
+}
+
+pub struct HotspotAnalyzer {
+    basis: ComplexityBasis,
+    limit: usize,
+}
+
+impl Default for HotspotAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotspotAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            basis: ComplexityBasis::Cyclomatic,
+            limit: DEFAULT_HOTSPOT_LIMIT,
+        }
+    }
+
+    pub fn with_basis(mut self, basis: ComplexityBasis) -> Self {
+        self.basis = basis;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Горячие точки по убыванию оценки. Учитываются производственные капсулы
+    /// с git-историей; пусто, если история не собиралась
+    pub fn rank(&self, graph: &CapsuleGraph) -> Vec<Hotspot> {
+        let churned: Vec<(&Capsule, usize)> = graph
+            .capsules
+            .values()
+            .filter(|c| CodeOrigin::of(c) == CodeOrigin::Production)
+            .filter_map(|c| Some((c, commits(c)?)))
+            .filter(|(_, commits)| *commits > 0)
+            .collect();
+        let Some(max_commits) = churned.iter().map(|(_, n)| *n).max() else {
+            return Vec::new();
+        };
+
+        let mut sources = HashMap::new();
+        let mut hotspots: Vec<Hotspot> = churned
+            .into_iter()
+            .map(|(capsule, commits)| {
+                let churn = commits as f32 / max_commits as f32;
+                let complexity = self.complexity(capsule, &mut sources);
+                Hotspot {
+                    component: capsule.name.clone(),
+                    capsule_type: capsule.capsule_type,
+                    file: capsule.file_path.clone(),
+                    line: capsule.line_start,
+                    commits,
+                    churn,
+                    complexity,
+                    score: churn * complexity as f32,
+                }
+            })
+            .filter(|h| h.score > 0.0)
+            .collect();
+        hotspots.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then(b.commits.cmp(&a.commits))
+                .then_with(|| a.component.cmp(&b.component))
+        });
+        hotspots.truncate(self.limit);
+        hotspots
+    }
+
+    fn complexity(&self, capsule: &Capsule, sources: &mut HashMap<PathBuf, String>) -> u32 {
+        match self.basis {
+            ComplexityBasis::Cyclomatic => capsule.complexity,
+            ComplexityBasis::Cognitive => {
+                let source = sources
+                    .entry(capsule.file_path.clone())
+                    .or_insert_with(|| read_source(&capsule.file_path));
+                let content = capsule_lines(source, capsule);
+                AdvancedMetricsCalculator::new()
+                    .calculate_metrics(capsule, &content)
+                    .map(|m| m.cognitive_complexity)
+                    .unwrap_or(capsule.complexity)
+            }
+        }
+    }
+}
+
+fn commits(capsule: &Capsule) -> Option<usize> {
+    capsule.metadata.get(GIT_COMMITS_KEY)?.parse().ok()
+}
+
+fn read_source(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
+
+fn capsule_lines(source: &str, capsule: &Capsule) -> String {
+    let start = capsule.line_start.max(1) - 1;
+    let count = capsule.line_end.max(capsule.line_start) - start;
+    source
+        .lines()
+        .skip(start)
+        .take(count)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Текстовый отчёт `archlens hotspots`
+pub fn hotspots_to_text(hotspots: &[Hotspot], basis: ComplexityBasis) -> String {
+    if hotspots.is_empty() {
+        return "Горячих точек нет: git-история капсул не найдена\n".to_string();
+    }
+    let basis = match basis {
+        ComplexityBasis::Cyclomatic => "cyclomatic",
+        ComplexityBasis::Cognitive => "cognitive",
+    };
+    let mut out = format!("Top Hotspots (churn × {basis} complexity)\n");
+    for (i, h) in hotspots.iter().enumerate() {
+        out.push_str(&format!(
+            "{:>2}. {} ({:?}) score {:.1} : {} commits, complexity {} — {}:{}\n",
+            i + 1,
+            h.component,
+            h.capsule_type,
+            h.score,
+            h.commits,
+            h.complexity,
+            h.file.display(),
+            h.line
+        ));
+    }
+    out
+}
@@ -6,6 +6,8 @@ use crate::types::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod hotspots;
+
 /// Калькулятор продвинутых метрик - композитный класс, использующий специализированные анализаторы
 #[derive(Debug)]
 pub struct AdvancedMetricsCalculator {
@@ -0,0 +1,297 @@
+// Публичный API проекта: экспортируемые символы (`pub` в Rust, `export` в JS/TS,
+// `__all__` или имена без `_` в Python, заглавные имена в Go, `public` в
+// Java/C#/PHP) привязываются к капсулам и перечисляются в экспорте, чтобы
+// документировать API и сравнивать его между версиями
+
+use crate::code_origin::CodeOrigin;
+use crate::dsm::common_root;
+use crate::types::{Capsule, CapsuleGraph};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Ключ метаданных: публичные символы капсулы (JSON-массив `ApiSymbol`)
+pub const API_SURFACE_KEY: &str = "api_surface";
+/// Сколько символов показывать в ai_compact
+const COMPACT_SHOWN: usize = 50;
+/// Длина сигнатуры в отчёте
+const SIGNATURE_LIMIT: usize = 120;
+
+/// Экспортируемый символ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiSymbol {
+    pub name: String,
+    /// Вид объявления: `fn`, `struct`, `class`, `const`, `export`…
+    pub kind: String,
+    pub line: usize,
+    /// Строка объявления без тела
+    pub signature: String,
+}
+
+struct LanguageRules {
+    /// Группы `kind` и `name`
+    declarations: Vec<Regex>,
+    /// Списки экспортируемых имён (`export { a, b as c }`, `__all__ = [...]`)
+    export_lists: Option<Regex>,
+}
+
+fn rules_for(extension: &str) -> Option<&'static LanguageRules> {
+    static RUST: OnceLock<LanguageRules> = OnceLock::new();
+    static SCRIPT: OnceLock<LanguageRules> = OnceLock::new();
+    static PYTHON: OnceLock<LanguageRules> = OnceLock::new();
+    static GO: OnceLock<LanguageRules> = OnceLock::new();
+    static PUBLIC_MODIFIER: OnceLock<LanguageRules> = OnceLock::new();
+    let compile = |patterns: &[&str], list: Option<&str>| LanguageRules {
+        declarations: patterns.iter().map(|p| Regex::new(p).unwrap()).collect(),
+        export_lists: list.map(|p| Regex::new(p).unwrap()),
+    };
+    let rules = match extension {
+        "rs" => RUST.get_or_init(|| {
+            compile(
+                &[r#"^\s*pub\s+(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*(?P<kind>fn|struct|enum|trait|type|const|static|mod|union)\s+(?P<name>\w+)"#],
+                None,
+            )
+        }),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => SCRIPT.get_or_init(|| {
+            compile(
+                &[r"^\s*export\s+(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?P<kind>function\*?|class|const|let|var|interface|type|enum|namespace)\s+(?P<name>[\w$]+)"],
+                Some(r"(?m)^\s*export\s*\{([^}]*)\}"),
+            )
+        }),
+        "py" => PYTHON.get_or_init(|| {
+            compile(
+                &[r"^(?:async\s+)?(?P<kind>def|class)\s+(?P<name>[A-Za-z]\w*)"],
+                Some(r"(?s)__all__\s*(?::[^=]*)?=\s*[\[(]([^\])]*)[\])]"),
+            )
+        }),
+        "go" => GO.get_or_init(|| {
+            compile(
+                &[
+                    r"^(?P<kind>func)\s+(?:\([^)]*\)\s*)?(?P<name>[A-Z]\w*)",
+                    r"^(?P<kind>type|const|var)\s+(?P<name>[A-Z]\w*)",
+                ],
+                None,
+            )
+        }),
+        "java" | "kt" | "cs" | "php" => PUBLIC_MODIFIER.get_or_init(|| {
+            compile(
+                &[
+                    r"^\s*public\s+(?:(?:static|final|abstract|sealed|partial|readonly)\s+)*(?P<kind>class|interface|enum|record|struct|trait)\s+(?P<name>\w+)",
+                    r"^\s*public\s+(?:(?:static|final|abstract|override|virtual|async|synchronized)\s+)*(?P<kind>function)\s+(?P<name>\w+)\s*\(",
+                    r"^\s*public\s+(?:(?:static|final|abstract|override|virtual|async|synchronized)\s+)*[\w<>\[\],.?]+\s+(?P<name>\w+)\s*\(",
+                ],
+                None,
+            )
+        }),
+        _ => return None,
+    };
+    Some(rules)
+}
+
+fn signature_of(line: &str) -> String {
+    let line = line.trim();
+    let line = line
+        .split_once('{')
+        .map_or(line, |(head, _)| head)
+        .trim_end()
+        .trim_end_matches(':');
+    line.chars().take(SIGNATURE_LIMIT).collect()
+}
+
+/// Публичные символы файла по расширению; неизвестный язык — пустой список
+pub fn scan_public_symbols(content: &str, extension: &str) -> Vec<ApiSymbol> {
+    let Some(rules) = rules_for(extension) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut symbols: Vec<ApiSymbol> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let cap = rules.declarations.iter().find_map(|r| r.captures(line))?;
+            Some(ApiSymbol {
+                name: cap["name"].to_string(),
+                kind: cap
+                    .name("kind")
+                    .map_or("method", |k| k.as_str().trim_end_matches('*'))
+                    .to_string(),
+                line: i + 1,
+                signature: signature_of(line),
+            })
+        })
+        .collect();
+
+    let Some(list) = &rules.export_lists else {
+        return symbols;
+    };
+    let mut listed = Vec::new();
+    for cap in list.captures_iter(content) {
+        let line = content[..cap.get(1).unwrap().start()].matches('\n').count() + 1;
+        for item in cap[1].split(',') {
+            // `a as b` экспортирует `b`; в `__all__` имена в кавычках
+            let name = item.rsplit(" as ").next().unwrap_or(item);
+            let name = name.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+            if !name.is_empty() {
+                listed.push((name.to_string(), line));
+            }
+        }
+    }
+    if extension == "py" && !listed.is_empty() {
+        // `__all__` задаёт API модуля целиком
+        let declared: HashMap<String, ApiSymbol> =
+            symbols.drain(..).map(|s| (s.name.clone(), s)).collect();
+        return listed
+            .into_iter()
+            .map(|(name, line)| {
+                declared.get(&name).cloned().unwrap_or(ApiSymbol {
+                    signature: signature_of(lines.get(line - 1).copied().unwrap_or_default()),
+                    kind: "export".into(),
+                    name,
+                    line,
+                })
+            })
+            .collect();
+    }
+    for (name, line) in listed {
+        if symbols.iter().all(|s| s.name != name) {
+            symbols.push(ApiSymbol {
+                signature: signature_of(lines.get(line - 1).copied().unwrap_or_default()),
+                kind: "export".into(),
+                name,
+                line,
+            });
+        }
+    }
+    symbols
+}
+
+/// Записывает публичные символы в метаданные капсул. Символ достаётся самой
+/// вложенной капсуле, содержащей строку; вне капсул — капсуле с тем же именем,
+/// иначе самой широкой капсуле файла. Возвращает число символов
+pub fn annotate_api_surface(capsules: &mut [Capsule]) -> usize {
+    let mut files: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+    for (i, capsule) in capsules.iter().enumerate() {
+        files.entry(capsule.file_path.clone()).or_default().push(i);
+    }
+    let mut found = 0;
+    let mut assigned: HashMap<usize, Vec<ApiSymbol>> = HashMap::new();
+    for (file, indices) in files {
+        let extension = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if rules_for(extension).is_none() {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        for symbol in scan_public_symbols(&content, extension) {
+            let span = |i: &usize| {
+                let c = &capsules[*i];
+                c.line_end.max(c.line_start) - c.line_start
+            };
+            let owner = indices
+                .iter()
+                .filter(|i| {
+                    let c = &capsules[**i];
+                    (c.line_start..=c.line_end.max(c.line_start)).contains(&symbol.line)
+                })
+                .min_by_key(|i| span(i))
+                .or_else(|| indices.iter().find(|i| capsules[**i].name == symbol.name))
+                .or_else(|| indices.iter().max_by_key(|i| span(i)));
+            if let Some(owner) = owner {
+                assigned.entry(*owner).or_default().push(symbol);
+                found += 1;
+            }
+        }
+    }
+    for (index, symbols) in assigned {
+        if let Ok(json) = serde_json::to_string(&symbols) {
+            capsules[index]
+                .metadata
+                .insert(API_SURFACE_KEY.to_string(), json);
+        }
+    }
+    found
+}
+
+/// Публичные символы капсулы из метаданных
+pub fn capsule_api_surface(capsule: &Capsule) -> Vec<ApiSymbol> {
+    capsule
+        .metadata
+        .get(API_SURFACE_KEY)
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Строка отчёта: символ с файлом и капсулой
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiEntry {
+    pub file: PathBuf,
+    pub line: usize,
+    pub kind: String,
+    pub name: String,
+    pub capsule: String,
+    pub signature: String,
+}
+
+/// Публичный API проекта без тестового кода, по файлам и строкам
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApiSurfaceReport {
+    pub entries: Vec<ApiEntry>,
+}
+
+impl ApiSurfaceReport {
+    /// Отчёт по размеченному графу; `None`, если публичных символов нет
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        let mut entries: Vec<ApiEntry> = graph
+            .capsules
+            .values()
+            .filter(|c| CodeOrigin::of(c) != CodeOrigin::Test)
+            .flat_map(|capsule| {
+                let file = capsule
+                    .file_path
+                    .strip_prefix(&root)
+                    .unwrap_or(&capsule.file_path)
+                    .to_path_buf();
+                capsule_api_surface(capsule)
+                    .into_iter()
+                    .map(move |s| ApiEntry {
+                        file: file.clone(),
+                        line: s.line,
+                        kind: s.kind,
+                        name: s.name,
+                        capsule: capsule.name.clone(),
+                        signature: s.signature,
+                    })
+            })
+            .collect();
+        if entries.is_empty() {
+            return None;
+        }
+        entries.sort_by(|a, b| (&a.file, a.line, &a.name).cmp(&(&b.file, b.line, &b.name)));
+        entries.dedup_by(|a, b| a.file == b.file && a.line == b.line && a.name == b.name);
+        Some(Self { entries })
+    }
+
+    /// Раздел ai_compact: символы по файлам
+    pub fn compact_section(&self) -> String {
+        let mut out = format!("\n## Public API ({} symbols)\n", self.entries.len());
+        let mut current: Option<&Path> = None;
+        for entry in self.entries.iter().take(COMPACT_SHOWN) {
+            if current != Some(entry.file.as_path()) {
+                out.push_str(&format!("- {}\n", entry.file.display()));
+                current = Some(entry.file.as_path());
+            }
+            out.push_str(&format!("  - {} {}\n", entry.kind, entry.name));
+        }
+        if self.entries.len() > COMPACT_SHOWN {
+            out.push_str(&format!(
+                "- ... and {} more\n",
+                self.entries.len() - COMPACT_SHOWN
+            ));
+        }
+        out
+    }
+}
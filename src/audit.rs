@@ -0,0 +1,334 @@
+// Журнал аудита (включается `ARCHLENS_AUDIT=1`): каждый запуск анализа/экспорта
+// (кто, когда, параметры, отпечаток проанализированного дерева) дописывается строкой
+// JSON в `audit.jsonl` каталога данных. Записи связаны цепочкой хешей, поэтому правка
+// или удаление строк обнаруживается `archlens audit --verify` (FNV-1a — защита от
+// случайных правок, не криптографическая гарантия)
+
+use crate::stable_hash::Fnv;
+use crate::types::{AnalysisError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Переменная окружения с каталогом данных ArchLens
+pub const DATA_DIR_ENV: &str = "ARCHLENS_DATA_DIR";
+/// `1`, `on` или `true` включают журнал аудита (по умолчанию выключен: запись
+/// обходит дерево проекта ради отпечатка и пишет в каталог данных)
+pub const AUDIT_ENV: &str = "ARCHLENS_AUDIT";
+/// `prev_hash` первой записи журнала
+const GENESIS_HASH: &str = "0000000000000000";
+/// Каталоги, не влияющие на отпечаток проекта
+const SKIPPED_DIRS: &[&str] = &[
+    ".git",
+    ".archlens",
+    "target",
+    "node_modules",
+    "dist",
+    "build",
+    "vendor",
+    ".venv",
+];
+
+/// Каталог данных: `$ARCHLENS_DATA_DIR`, иначе `$XDG_DATA_HOME/archlens`,
+/// `~/.local/share/archlens` (на Windows — `%APPDATA%\archlens`)
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    if cfg!(windows) {
+        if let Some(appdata) = std::env::var_os("APPDATA") {
+            return PathBuf::from(appdata).join("archlens");
+        }
+    }
+    if let Some(xdg) = std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        return PathBuf::from(xdg).join("archlens");
+    }
+    std::env::var_os("HOME")
+        .map(|home| {
+            PathBuf::from(home)
+                .join(".local")
+                .join("share")
+                .join("archlens")
+        })
+        .unwrap_or_else(|| std::env::temp_dir().join("archlens"))
+}
+
+pub fn audit_enabled() -> bool {
+    matches!(
+        std::env::var(AUDIT_ENV)
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str(),
+        "1" | "on" | "true" | "yes"
+    )
+}
+
+/// Пользователь ОС, запустивший команду
+pub fn current_user() -> String {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Данные запуска для записи в журнал
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub user: String,
+    pub command: String,
+    pub project: String,
+    /// Аргументы командной строки без имени программы
+    pub args: Vec<String>,
+    /// Отпечаток проанализированного дерева (см. [`project_fingerprint`])
+    pub fingerprint: String,
+}
+
+impl AuditRecord {
+    /// Запуск `command` над проектом текущим пользователем
+    pub fn new(command: &str, project: &Path, args: Vec<String>) -> Self {
+        let project_path = project.canonicalize().unwrap_or(project.to_path_buf());
+        Self {
+            user: current_user(),
+            command: command.to_string(),
+            fingerprint: project_fingerprint(&project_path),
+            project: project_path.to_string_lossy().into_owned(),
+            args,
+        }
+    }
+}
+
+/// Запись журнала
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp: String,
+    pub user: String,
+    pub command: String,
+    pub project: String,
+    pub args: Vec<String>,
+    pub fingerprint: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Хеш записи: все поля, кроме самого `hash`
+    fn compute_hash(&self) -> String {
+        let mut hasher = Fnv::new();
+        hasher.write(&self.seq.to_le_bytes());
+        for field in [
+            &self.timestamp,
+            &self.user,
+            &self.command,
+            &self.project,
+            &self.fingerprint,
+            &self.prev_hash,
+        ] {
+            hasher.write_str(field);
+        }
+        for arg in &self.args {
+            hasher.write_str(arg);
+        }
+        hasher.hex()
+    }
+}
+
+/// Фильтр просмотра журнала
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Подстрока пути проекта
+    pub project: Option<String>,
+    pub command: Option<String>,
+    pub user: Option<String>,
+    /// Последние N подходящих записей
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.project
+            .as_ref()
+            .is_none_or(|p| entry.project.contains(p.as_str()))
+            && self.command.as_ref().is_none_or(|c| entry.command == *c)
+            && self.user.as_ref().is_none_or(|u| entry.user == *u)
+    }
+}
+
+/// Журнал аудита в формате JSON Lines, только дописывание
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// `audit.jsonl` в каталоге данных
+    pub fn default_location() -> Self {
+        Self::new(&data_dir().join("audit.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Дописывает запись, продолжая цепочку хешей
+    pub fn append(&self, record: AuditRecord) -> Result<AuditEntry> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        }
+        let last = self.entries()?.pop();
+        let mut entry = AuditEntry {
+            seq: last.as_ref().map(|e| e.seq + 1).unwrap_or(1),
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            user: record.user,
+            command: record.command,
+            project: record.project,
+            args: record.args,
+            fingerprint: record.fingerprint,
+            prev_hash: last
+                .map(|e| e.hash)
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))?;
+        line.push('\n');
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Ok(entry)
+    }
+
+    /// Все записи; отсутствующий журнал — пустой
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AnalysisError::IoError(e.to_string())),
+        };
+        content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    AnalysisError::ParsingError(format!("audit log line {}: {e}", i + 1))
+                })
+            })
+            .collect()
+    }
+
+    pub fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let mut entries: Vec<AuditEntry> = self
+            .entries()?
+            .into_iter()
+            .filter(|e| filter.matches(e))
+            .collect();
+        if let Some(limit) = filter.limit {
+            let skip = entries.len().saturating_sub(limit);
+            entries.drain(..skip);
+        }
+        Ok(entries)
+    }
+
+    /// Нарушения цепочки: изменённые записи, пропуски и перестановки. Пусто — журнал цел
+    pub fn verify(&self) -> Result<Vec<String>> {
+        let mut problems = Vec::new();
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut expected_seq = 1;
+        for entry in self.entries()? {
+            if entry.seq != expected_seq {
+                problems.push(format!(
+                    "entry {}: expected sequence number {}",
+                    entry.seq, expected_seq
+                ));
+            }
+            if entry.prev_hash != prev_hash {
+                problems.push(format!(
+                    "entry {}: does not follow the previous entry",
+                    entry.seq
+                ));
+            }
+            if entry.compute_hash() != entry.hash {
+                problems.push(format!("entry {}: content was modified", entry.seq));
+            }
+            prev_hash = entry.hash;
+            expected_seq = entry.seq + 1;
+        }
+        Ok(problems)
+    }
+}
+
+/// Отпечаток дерева проекта: относительные пути, размеры и время изменения файлов
+/// (без build-артефактов и каталогов VCS). Для файла — его путь, размер и время
+pub fn project_fingerprint(project: &Path) -> String {
+    let mut files: Vec<(String, u64, u64)> = walkdir::WalkDir::new(project)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir()
+                && e.depth() > 0
+                && SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let relative = e
+                .path()
+                .strip_prefix(project)
+                .unwrap_or(e.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            Some((relative, metadata.len(), modified))
+        })
+        .collect();
+    files.sort();
+
+    let mut hasher = Fnv::new();
+    for (path, size, modified) in &files {
+        hasher.write_str(path);
+        hasher.write(&size.to_le_bytes());
+        hasher.write(&modified.to_le_bytes());
+    }
+    format!("{}:{}", files.len(), hasher.hex())
+}
+
+/// Таблица записей для терминала
+pub fn entries_text(entries: &[AuditEntry]) -> String {
+    if entries.is_empty() {
+        return "No audit entries.\n".to_string();
+    }
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&format!(
+            "#{:<5} {}  {:<12} {:<12} {}  [{}]\n",
+            entry.seq, entry.timestamp, entry.user, entry.command, entry.project, entry.fingerprint
+        ));
+        if !entry.args.is_empty() {
+            out.push_str(&format!("       args: {}\n", entry.args.join(" ")));
+        }
+    }
+    out
+}
@@ -17,6 +17,8 @@ use std::{
 use archlens::{
     cli::{self, diagram, export, stats},
     ensure_absolute_path,
+    export_estimate::DetailLevel,
+    graph_sampling::{GraphSampler, SamplingStrategy},
 };
 use regex::Regex;
 use std::cmp::Reverse;
@@ -29,7 +31,10 @@ pub struct AnalyzeArgs {
     #[serde(default = "default_project_path")]
     pub project_path: String,
     pub deep: Option<bool>,
-    #[serde(alias = "detail_level")] // summary|standard|full
+    /// Keep test capsules in the deep graph (metrics still cover production code only)
+    #[serde(alias = "include_tests")]
+    pub include_tests: Option<bool>,
+    #[serde(alias = "detail_level")] // auto (default)|summary|standard|full
     pub detail_level: Option<String>,
     #[serde(alias = "max_output_chars")]
     pub max_output_chars: Option<usize>,
@@ -41,7 +46,7 @@ pub struct ExportArgs {
     #[serde(alias = "project_path")]
     #[serde(default = "default_project_path")]
     pub project_path: String,
-    #[serde(alias = "detail_level")] // summary|standard|full
+    #[serde(alias = "detail_level")] // auto (default)|summary|standard|full
     pub detail_level: Option<String>,
     #[serde(alias = "max_output_chars")]
     pub max_output_chars: Option<usize>,
@@ -62,7 +67,7 @@ pub struct StructureArgs {
     #[serde(alias = "project_path")]
     #[serde(default = "default_project_path")]
     pub project_path: String,
-    #[serde(alias = "detail_level")] // summary|standard|full
+    #[serde(alias = "detail_level")] // auto (default)|summary|standard|full
     pub detail_level: Option<String>,
     #[serde(alias = "max_output_chars")]
     pub max_output_chars: Option<usize>,
@@ -75,12 +80,18 @@ pub struct DiagramArgs {
     #[serde(alias = "project_path")]
     #[serde(default = "default_project_path")]
     pub project_path: String,
-    #[serde(alias = "diagram_type")]
+    #[serde(alias = "diagram_type")] // mermaid (default)|condensed
     pub diagram_type: Option<String>,
-    #[serde(alias = "detail_level")] // summary|standard|full
+    #[serde(alias = "detail_level")] // auto (default)|summary|standard|full
     pub detail_level: Option<String>,
     #[serde(alias = "max_output_chars")]
     pub max_output_chars: Option<usize>,
+    #[serde(alias = "max_nodes")] // default depends on detail level
+    pub max_nodes: Option<usize>,
+    pub sampling: Option<String>, // centrality|stratified|problem-adjacent
+    /// Append every dependency cycle group with the file:line of each edge
+    #[serde(alias = "cycles_detail")]
+    pub cycles_detail: Option<bool>,
     pub etag: Option<String>,
 }
 
@@ -160,6 +171,8 @@ fn normalize_tool_name(name: &str) -> String {
         "structure_get" => "structure.get",
         "analyze_project" => "analyze.project",
         "ai_recommend" => "ai.recommend",
+        "impact_analyze" => "impact.analyze",
+        "path_find" => "path.find",
         // already dotted or unknown -> pass-through
         _ => name,
     }
@@ -212,6 +225,38 @@ pub struct AIRecommendArgs {
     pub focus: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactArgs {
+    #[serde(alias = "project_path")]
+    #[serde(default = "default_project_path")]
+    pub project_path: String,
+    /// Changed file (relative or absolute path) or capsule name
+    pub target: String,
+    /// Stop after this many dependency hops
+    #[serde(alias = "max_depth")]
+    pub max_depth: Option<usize>,
+    #[serde(alias = "max_output_chars")]
+    pub max_output_chars: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PathArgs {
+    #[serde(alias = "project_path")]
+    #[serde(default = "default_project_path")]
+    pub project_path: String,
+    /// Depending file or capsule name
+    pub from: String,
+    /// Depended-on file or capsule name
+    pub to: String,
+    /// How many shortest paths to list (default 5)
+    #[serde(alias = "max_paths")]
+    pub max_paths: Option<usize>,
+    #[serde(alias = "max_output_chars")]
+    pub max_output_chars: Option<usize>,
+}
+
 // Formatting limits
 const SUMMARY_LIMIT_CHARS: usize = 30_000;
 const MAX_OUTPUT_CHARS: usize = 1_000_000;
@@ -335,12 +380,21 @@ fn trim_bullets_in_sections(md: &str, top_n: Option<usize>) -> String {
     out
 }
 
-fn level(opt: &Option<String>) -> &str {
-    match opt.as_deref() {
-        Some("standard") => "standard",
-        Some("full") => "full",
-        _ => "summary",
+/// Explicit summary|standard|full, otherwise `auto`: picked from project size
+/// (file count) and the requested output budget, with a matching section limit
+fn resolve_level(
+    opt: &Option<String>,
+    project_path: &str,
+    budget: Option<usize>,
+) -> (&'static str, Option<usize>) {
+    if let Some(level) = opt.as_deref().and_then(DetailLevel::parse) {
+        return (level.as_str(), None);
     }
+    let size = stats::get_project_structure(project_path)
+        .map(|st| st.total_files)
+        .unwrap_or(0);
+    let auto = DetailLevel::auto(size, budget);
+    (auto.level.as_str(), auto.top_n)
 }
 
 fn format_analysis_result(
@@ -353,10 +407,35 @@ fn format_analysis_result(
     out.push_str(&format!("**Path:** {}\n", project_path));
     out.push_str(&format!("- Files: {}\n", ps.total_files));
     out.push_str(&format!("- Lines: {}\n", ps.total_lines));
+    let skipped = &ps.skipped_files;
+    if skipped.total() > 0 {
+        out.push_str(&format!(
+            "- Skipped: {} (too large {}, binary {}, minified {})\n",
+            skipped.total(),
+            skipped.too_large,
+            skipped.binary,
+            skipped.minified
+        ));
+    }
+    if !ps.auto_excluded.is_empty() {
+        let dirs = ps
+            .auto_excluded
+            .iter()
+            .take(5)
+            .map(|d| format!("{} ({:?})", d.path.display(), d.ecosystem).to_lowercase())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let more = ps.auto_excluded.len().saturating_sub(5);
+        out.push_str(&format!(
+            "- Auto-excluded build output: {}{}\n",
+            dirs,
+            if more > 0 { format!(" (+{})", more) } else { String::new() }
+        ));
+    }
     // file types sorted desc
     let mut types: Vec<(String, usize)> =
         ps.file_types.iter().map(|(k, v)| (k.clone(), *v)).collect();
-    types.sort_by(|a, b| b.1.cmp(&a.1));
+    types.sort_by_key(|t| Reverse(t.1));
     let take = match detail_level {
         "full" => types.len(),
         "standard" => types.len().min(10),
@@ -389,7 +468,7 @@ fn format_structure_result(
     // file types top N
     let mut types: Vec<(String, usize)> =
         st.file_types.iter().map(|(k, v)| (k.clone(), *v)).collect();
-    types.sort_by(|a, b| b.1.cmp(&a.1));
+    types.sort_by_key(|t| Reverse(t.1));
     let take = match detail_level {
         "full" => types.len(),
         "standard" => types.len().min(10),
@@ -455,6 +534,25 @@ fn format_export_markdown_with_controls(
     clamp_text_with_limit(&formatted, max_chars)
 }
 
+/// Node sampling for diagrams: explicit `max_nodes`/`sampling`, otherwise
+/// 60 nodes for summary, 200 for standard and no limit for full
+fn diagram_sampler(
+    detail_level: &str,
+    max_nodes: Option<usize>,
+    sampling: Option<&str>,
+) -> std::result::Result<Option<GraphSampler>, String> {
+    let strategy = sampling
+        .map(|s| s.parse::<SamplingStrategy>())
+        .transpose()?
+        .unwrap_or(SamplingStrategy::Centrality);
+    let limit = max_nodes.or(match detail_level {
+        "summary" => Some(60),
+        "standard" => Some(200),
+        _ => None,
+    });
+    Ok(limit.map(|limit| GraphSampler::new(strategy, limit)))
+}
+
 fn format_diagram_text(mmd: String, project_path: &str, detail_level: &str) -> String {
     let mut limit = 120_000usize;
     if detail_level == "summary" {
@@ -657,12 +755,89 @@ fn write_json_line<T: Serialize>(
             e
         )
     });
-    let mut stdout = io::stdout();
+    // lock: watch notifications are written from another thread
+    let mut stdout = io::stdout().lock();
     let _ = stdout.write_all(line.as_bytes());
     let _ = stdout.write_all(b"\n");
     let _ = stdout.flush();
 }
 
+/// JSON-RPC notification (no `id`, no response expected)
+fn write_notification(method: &str, params: serde_json::Value) {
+    let line = serde_json::json!({"jsonrpc": "2.0", "method": method, "params": params});
+    let mut stdout = io::stdout().lock();
+    let _ = writeln!(stdout, "{}", line);
+    let _ = stdout.flush();
+}
+
+/// Project to watch for changes (`ARCHLENS_WATCH`), unset by default
+fn env_watch_path() -> Option<String> {
+    std::env::var("ARCHLENS_WATCH")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+}
+
+/// Watches the project in a background thread. After every batch of file
+/// changes drops the project's cached outputs, re-analyzes it (refreshing the
+/// graph database snapshot and coverage, reusing unchanged parse trees) and
+/// tells clients to re-fetch (`notifications/resources/list_changed`)
+fn spawn_project_watch(project_path: String) {
+    use archlens::file_scanner::watcher::ProjectWatcher;
+
+    thread::spawn(move || {
+        let path = ensure_absolute_path(&project_path);
+        let watcher = cli::handlers::project_scanner(&path)
+            .and_then(|scanner| ProjectWatcher::new(&path, scanner).map_err(|e| e.to_string()));
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("watch disabled for {}: {}", path.display(), e);
+                return;
+            }
+        };
+        loop {
+            match watcher.next_changes(None) {
+                Ok(Some(changes)) => {
+                    let changed: Vec<String> = changes
+                        .changed
+                        .iter()
+                        .map(|f| f.path.to_string_lossy().to_string())
+                        .collect();
+                    let removed: Vec<String> = changes
+                        .removed
+                        .iter()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .collect();
+                    let root = watcher.root().to_string_lossy().to_string();
+                    let evicted = cache_evict_project(&cache_dir(), watcher.root());
+                    let capsules = match build_graph_for_path(&root) {
+                        Ok(graph) => Some(graph.capsules.len()),
+                        Err(e) => {
+                            eprintln!("re-analysis failed for {}: {}", root, e);
+                            None
+                        }
+                    };
+                    write_notification(
+                        "notifications/resources/list_changed",
+                        serde_json::json!({
+                            "project_path": root,
+                            "changed": changed,
+                            "removed": removed,
+                            "evicted_cache_entries": evicted,
+                            "capsules": capsules,
+                        }),
+                    );
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("watch stopped for {}: {}", path.display(), e);
+                    return;
+                }
+            }
+        }
+    });
+}
+
 fn tool_list_schema() -> Vec<ToolDescription> {
     let analyze_schema = schemars::schema_for!(AnalyzeArgs);
     let export_schema = schemars::schema_for!(ExportArgs);
@@ -670,6 +845,8 @@ fn tool_list_schema() -> Vec<ToolDescription> {
     let diagram_schema = schemars::schema_for!(DiagramArgs);
     let ai_summary_schema = schemars::schema_for!(AISummaryArgs);
     let ai_recommend_schema = schemars::schema_for!(AIRecommendArgs);
+    let impact_schema = schemars::schema_for!(ImpactArgs);
+    let path_schema = schemars::schema_for!(PathArgs);
 
     let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
     let schemas_dir = root.join("out").join("schemas");
@@ -688,7 +865,7 @@ fn tool_list_schema() -> Vec<ToolDescription> {
         },
         ToolDescription {
             name: "graph_build".into(),
-            description: "Build architecture diagram (mermaid)".into(),
+            description: "Build architecture diagram (mermaid; diagramType \"condensed\" collapses each cycle into one node)".into(),
             input_schema: serde_json::to_value(diagram_schema.schema).unwrap(),
             schema_uri: to_uri("diagram_args"),
         },
@@ -722,6 +899,18 @@ fn tool_list_schema() -> Vec<ToolDescription> {
             input_schema: serde_json::to_value(ai_recommend_schema.schema).unwrap(),
             schema_uri: to_uri("ai_recommend_args"),
         },
+        ToolDescription {
+            name: "impact_analyze".into(),
+            description: "What breaks if a file or capsule changes: transitive dependents with distance".into(),
+            input_schema: serde_json::to_value(impact_schema.schema).unwrap(),
+            schema_uri: to_uri("impact_args"),
+        },
+        ToolDescription {
+            name: "path_find".into(),
+            description: "Why does A depend on B: shortest dependency paths with each edge's kind and source location".into(),
+            input_schema: serde_json::to_value(path_schema.schema).unwrap(),
+            schema_uri: to_uri("path_args"),
+        },
     ]
 }
 
@@ -1009,18 +1198,45 @@ fn cache_get(key: &str, ttl_ms: u64) -> Option<(String, String)> {
     Some((etag, output))
 }
 
-fn cache_put(key: &str, etag: &str, output: &str) {
+fn cache_put(key: &str, project: &Path, etag: &str, output: &str) {
     let dir = cache_dir();
     let _ = fs::create_dir_all(&dir);
     let p = dir.join(format!("{}.json", key));
     let _ = fs::write(
         &p,
-        serde_json::json!({"etag":etag,"output":output}).to_string(),
+        serde_json::json!({"etag":etag,"output":output,"project":project.to_string_lossy()})
+            .to_string(),
     );
     // LRU eviction
     cache_trim_lru(&dir, env_cache_max_entries(), env_cache_max_bytes());
 }
 
+/// Removes the cached outputs of a project (compared after canonicalization);
+/// returns how many entries were dropped
+fn cache_evict_project(dir: &Path, project: &Path) -> usize {
+    let project = project
+        .canonicalize()
+        .unwrap_or_else(|_| project.to_path_buf());
+    let Ok(rd) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut evicted = 0;
+    for ent in rd.flatten() {
+        let p = ent.path();
+        let cached = fs::read_to_string(&p)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .and_then(|v| v.get("project")?.as_str().map(PathBuf::from));
+        let Some(cached) = cached else {
+            continue;
+        };
+        if cached.canonicalize().unwrap_or(cached) == project && fs::remove_file(&p).is_ok() {
+            evicted += 1;
+        }
+    }
+    evicted
+}
+
 fn presets_dir() -> PathBuf {
     std::env::current_dir()
         .unwrap_or_else(|_| PathBuf::from("."))
@@ -1035,65 +1251,156 @@ fn write_preset(name: &str, json: serde_json::Value) {
     let _ = fs::write(p, serde_json::to_vec_pretty(&json).unwrap());
 }
 
+/// Project graph; with a graph database (`.archlens/graph.db`) an unchanged
+/// working copy is read from it instead of being analyzed again
 fn build_graph_for_path(project_path: &str) -> Result<archlens::types::CapsuleGraph, String> {
+    let root = std::path::Path::new(project_path);
+    archlens::graph_db::cached_graph(root, archlens::graph_db::WORKTREE_SNAPSHOT, root, || {
+        analyze_graph_for_path(project_path)
+    })
+}
+
+/// Coverage of the last analysis per project: files skipped after a panic.
+/// A graph read from the graph database keeps the coverage of its analysis
+/// in this process, if there was one
+static ANALYSIS_COVERAGE: std::sync::OnceLock<
+    std::sync::Mutex<std::collections::HashMap<String, archlens::panic_isolation::AnalysisCoverage>>,
+> = std::sync::OnceLock::new();
+
+fn analysis_coverage(project_path: &str) -> Option<archlens::panic_isolation::AnalysisCoverage> {
+    let coverage = ANALYSIS_COVERAGE.get()?.lock().unwrap_or_else(|e| e.into_inner());
+    coverage.get(project_path).cloned()
+}
+
+fn analyze_graph_for_path(project_path: &str) -> Result<archlens::types::CapsuleGraph, String> {
     use archlens::capsule_constructor::CapsuleConstructor;
     use archlens::capsule_graph_builder::CapsuleGraphBuilder;
-    use archlens::file_scanner::FileScanner;
     use archlens::parser_ast::ParserAST;
+    use archlens::project_config::ProjectConfig;
     use archlens::types::Capsule;
     use archlens::validator_optimizer::ValidatorOptimizer;
     use std::path::Path;
 
-    let scanner = FileScanner::new(
-        vec![
-            "**/*.rs".into(),
-            "**/*.ts".into(),
-            "**/*.js".into(),
-            "**/*.py".into(),
-            "**/*.java".into(),
-            "**/*.go".into(),
-            "**/*.cpp".into(),
-            "**/*.c".into(),
-        ],
-        vec![
-            "**/target/**".into(),
-            "**/node_modules/**".into(),
-            "**/.git/**".into(),
-            "**/dist/**".into(),
-            "**/build/**".into(),
-        ],
-        Some(8),
-    )
-    .map_err(|e| e.to_string())?;
+    let config = ProjectConfig::for_project(Path::new(project_path)).map_err(|e| e.to_string())?;
+    let scanner = config.scanner(Some(8)).map_err(|e| e.to_string())?;
     let files = scanner
         .scan_files(Path::new(project_path))
         .map_err(|e| e.to_string())?;
 
-    let mut parser = ParserAST::new().map_err(|e| e.to_string())?;
-    let constructor = CapsuleConstructor::new();
+    // One incremental parser per server process: repeat analyses reuse unchanged
+    // files and re-parse edited ones from their previous trees
+    static PARSER: std::sync::OnceLock<std::sync::Mutex<ParserAST>> = std::sync::OnceLock::new();
+    let parser = match PARSER.get() {
+        Some(parser) => parser,
+        None => {
+            let parser = ParserAST::new().map_err(|e| e.to_string())?;
+            PARSER.get_or_init(|| std::sync::Mutex::new(parser.with_incremental(true)))
+        }
+    };
+    let mut parser = parser.lock().unwrap_or_else(|e| e.into_inner());
+    let constructor = CapsuleConstructor::new().with_project_root(project_path);
     let mut capsules: Vec<Capsule> = Vec::new();
+    // a panicking file is skipped (and logged) instead of taking the server down
+    let mut coverage = archlens::panic_isolation::AnalysisCoverage::default();
     for file in &files {
-        if let Ok(content) = std::fs::read_to_string(&file.path) {
-            if let Ok(nodes) = parser.parse_file(&file.path, &content, &file.file_type) {
-                let mut caps = constructor
-                    .create_capsules(&nodes, &file.path.clone())
-                    .map_err(|e| e.to_string())?;
-                capsules.append(&mut caps);
-            }
-        }
-    }
+        let mut caps = archlens::panic_isolation::parse_file_isolated(
+            &mut parser,
+            &constructor,
+            file,
+            &mut coverage,
+        )
+        .map_err(|e| e.to_string())?;
+        capsules.append(&mut caps);
+    }
+    drop(parser);
+    ANALYSIS_COVERAGE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(project_path.to_string(), coverage);
     if capsules.is_empty() {
         return Err("No capsules".into());
     }
+    config.apply_layers(Path::new(project_path), &mut capsules);
     let mut builder = CapsuleGraphBuilder::new();
     let graph = builder.build_graph(&capsules).map_err(|e| e.to_string())?;
-    let validator = ValidatorOptimizer::new();
-    let graph = validator
+    let validator = ValidatorOptimizer::with_thresholds(&config.thresholds);
+    let mut graph = validator
         .validate_and_optimize(&graph)
         .map_err(|e| e.to_string())?;
+    archlens::packages::PackageAggregator::new().annotate(&mut graph);
+    if let Some(external) =
+        archlens::external_deps::ExternalDependencies::for_project(Path::new(project_path))
+    {
+        external.annotate(&mut graph);
+    }
     Ok(graph)
 }
 
+/// Правила редактирования проекта (`.archlens/redaction.json` или `ARCHLENS_REDACTION`)
+fn project_redactor(project_path: &Path) -> Result<Option<archlens::redaction::Redactor>, String> {
+    archlens::redaction::Redactor::for_project(project_path).map_err(|e| e.to_string())
+}
+
+/// Применяет правила редактирования ко всем строкам JSON-ответа инструмента
+fn redact_json(
+    redactor: Option<&archlens::redaction::Redactor>,
+    value: serde_json::Value,
+) -> serde_json::Value {
+    use serde_json::Value;
+    let Some(redactor) = redactor else {
+        return value;
+    };
+    match value {
+        Value::String(s) => Value::String(redactor.redact_text(&s)),
+        Value::Array(items) => Value::Array(
+            items.into_iter().map(|v| redact_json(Some(redactor), v)).collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (redactor.redact_text(&k), redact_json(Some(redactor), v)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Добавляет в scorecard число устаревших зависимостей проекта
+fn attach_dependency_freshness(json: &mut serde_json::Value, project_path: &Path) {
+    use archlens::dependency_freshness::DependencyFreshnessAnalyzer;
+    let Ok(report) = DependencyFreshnessAnalyzer::new().analyze_project(project_path) else {
+        return;
+    };
+    if report.total_dependencies == 0 {
+        return;
+    }
+    if let Some(summary) = json.get_mut("summary").and_then(|s| s.as_object_mut()) {
+        summary.insert(
+            "outdated_dependencies".into(),
+            serde_json::json!(report.outdated_count()),
+        );
+    }
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert("dependency_freshness".into(), report.to_summary_json());
+    }
+}
+
+/// Records the run in the project's metric history (when the store exists)
+/// and adds per-capsule series of the last runs to the summary
+fn attach_metric_trends(
+    json: &mut serde_json::Value,
+    project_path: &Path,
+    graph: &archlens::types::CapsuleGraph,
+) {
+    use archlens::metric_history::MetricHistory;
+    if !matches!(MetricHistory::record(project_path, graph), Ok(true)) {
+        return;
+    }
+    if let Ok(Some(history)) = MetricHistory::load(project_path) {
+        history.attach_to_summary(json, graph);
+    }
+}
+
 fn trim_ai_summary_json(mut v: serde_json::Value, top_n: Option<usize>) -> serde_json::Value {
     let n = top_n.unwrap_or(0);
     if n == 0 {
@@ -1125,6 +1432,24 @@ fn trim_ai_summary_json(mut v: serde_json::Value, top_n: Option<usize>) -> serde
             arr.truncate(n);
         }
     }
+    if let Some(arr) = v.get_mut("key_components").and_then(|x| x.as_array_mut()) {
+        if arr.len() > n {
+            arr.truncate(n);
+        }
+    }
+    if let Some(arr) = v
+        .pointer_mut("/bus_factor/modules")
+        .and_then(|x| x.as_array_mut())
+    {
+        if arr.len() > n {
+            arr.truncate(n);
+        }
+    }
+    if let Some(arr) = v.pointer_mut("/todos/items").and_then(|x| x.as_array_mut()) {
+        if arr.len() > n {
+            arr.truncate(n);
+        }
+    }
     v
 }
 
@@ -1459,14 +1784,19 @@ fn handle_call(
                     let args: ExportArgs =
                         serde_json::from_value(args).map_err(|e| e.to_string())?;
                     let abspath = ensure_absolute_path(args.project_path);
-                    let lv = level(&args.detail_level).to_string();
+                    let (lv, auto_top_n) = resolve_level(
+                        &args.detail_level,
+                        abspath.to_string_lossy().as_ref(),
+                        args.max_output_chars,
+                    );
+                    let top_n = args.top_n.or(auto_top_n);
                     let use_cache = args.use_cache.unwrap_or(true) && !args.force.unwrap_or(false);
                     let ttl = args.cache_ttl_ms.unwrap_or_else(env_cache_ttl_ms);
                     let key = export_cache_key(
                         &abspath.to_string_lossy(),
-                        &lv,
+                        lv,
                         &args.sections,
-                        args.top_n,
+                        top_n,
                         args.max_output_chars,
                     );
 
@@ -1486,7 +1816,6 @@ fn handle_call(
 
                     // Possibly use fast path for huge repos
                     let mode = env_compact_mode();
-                    let lv = level(&args.detail_level).to_string();
                     let use_fast = if mode == "fast" {
                         true
                     } else if mode == "auto" {
@@ -1496,8 +1825,8 @@ fn handle_call(
                     } else { false };
 
                     let out = if use_fast {
-                        let qs = quick_scan_approx(abspath.to_string_lossy().as_ref(), env_fast_budget_ms(), env_fast_max_files(), args.top_n.unwrap_or(10));
-                        fast_compact_markdown(abspath.to_string_lossy().as_ref(), &qs, &lv, args.max_output_chars)
+                        let qs = quick_scan_approx(abspath.to_string_lossy().as_ref(), env_fast_budget_ms(), env_fast_max_files(), top_n.unwrap_or(10));
+                        fast_compact_markdown(abspath.to_string_lossy().as_ref(), &qs, lv, args.max_output_chars)
                     } else {
                         export::generate_ai_compact(abspath.to_string_lossy().as_ref())
                             .map_err(|e| e.to_string())?
@@ -1505,14 +1834,14 @@ fn handle_call(
 
                     let txt = format_export_markdown_with_controls(
                         out,
-                        &lv,
+                        lv,
                         &args.sections,
-                        args.top_n,
+                        top_n,
                         args.max_output_chars,
                     );
                     let etag = content_etag(&txt);
                     if args.use_cache.unwrap_or(true) {
-                        cache_put(&key, &etag, &txt);
+                        cache_put(&key, &abspath, &etag, &txt);
                     }
                     if args.etag.as_deref() == Some(&etag) {
                         Ok(serde_json::json!({"status":"not_modified","etag": etag}))
@@ -1559,19 +1888,36 @@ fn handle_call(
                         }
                     }
 
+                    let redactor = project_redactor(&abspath)?;
                     let mut json = if use_fast {
                         build_fast_ai_summary_json(abspath.to_string_lossy().as_ref(), args.top_n)?
                     } else {
                         let graph = build_graph_for_path(abspath.to_string_lossy().as_ref())?;
-                        let exporter = archlens::exporter::Exporter::new();
-                        exporter.export_to_ai_summary_json(&graph).map_err(|e| e.to_string())?
+                        let mut exporter = archlens::exporter::Exporter::new();
+                        if let Some(coverage) = analysis_coverage(abspath.to_string_lossy().as_ref()) {
+                            exporter = exporter.with_coverage(coverage);
+                        }
+                        let mut json = match &redactor {
+                            Some(redactor) => {
+                                let mut redacted = graph.clone();
+                                redactor.redact_graph(&mut redacted);
+                                exporter.export_to_ai_summary_json(&redacted)
+                            }
+                            None => exporter.export_to_ai_summary_json(&graph),
+                        }
+                        .map_err(|e| e.to_string())?;
+                        attach_metric_trends(&mut json, &abspath, &graph);
+                        json
                     };
 
                     json = trim_ai_summary_json(json, args.top_n);
+                    attach_dependency_freshness(&mut json, &abspath);
+                    // paths and internal names of the fast path and metric trends as well
+                    let json = redact_json(redactor.as_ref(), json);
                     let _txt = serde_json::to_string_pretty(&json).unwrap_or("{}".into());
                     let etag = content_etag(&_txt);
                     if args.use_cache.unwrap_or(true) {
-                        cache_put(&key, &etag, &_txt);
+                        cache_put(&key, &abspath, &etag, &_txt);
                     }
                     if args.etag.as_deref() == Some(&etag) {
                         Ok(serde_json::json!({"status":"not_modified","etag": etag}))
@@ -1588,11 +1934,12 @@ fn handle_call(
                     let path = ensure_absolute_path(args.project_path);
                     let st = stats::get_project_structure(path.to_string_lossy().as_ref())
                         .map_err(|e| e.to_string())?;
-                    let txt = format_structure_result(
+                    let (lv, _) = resolve_level(
+                        &args.detail_level,
                         path.to_string_lossy().as_ref(),
-                        &st,
-                        level(&args.detail_level),
+                        args.max_output_chars,
                     );
+                    let txt = format_structure_result(path.to_string_lossy().as_ref(), &st, lv);
                     let txt = clamp_text_with_limit(&txt, args.max_output_chars);
                     let etag = content_etag(&txt);
                     Ok(
@@ -1604,14 +1951,26 @@ fn handle_call(
                         serde_json::from_value(args).map_err(|e| e.to_string())?;
                     let path = ensure_absolute_path(args.project_path);
                     // Cache key includes diagram type and detail level
-                    let detail = level(&args.detail_level).to_string();
+                    let (detail, _) = resolve_level(
+                        &args.detail_level,
+                        path.to_string_lossy().as_ref(),
+                        args.max_output_chars,
+                    );
                     let diag_type = args.diagram_type.clone().unwrap_or_default();
+                    let sampler = diagram_sampler(detail, args.max_nodes, args.sampling.as_deref())?;
                     let key = export_cache_key(
                         &path.to_string_lossy(),
                         "diagram",
                         &Some(vec![
                             format!("diagram_type={}", diag_type),
                             format!("detail={}", detail),
+                            format!(
+                                "sampling={}",
+                                sampler
+                                    .map(|s| format!("{}:{}", s.strategy().as_str(), s.limit()))
+                                    .unwrap_or_default()
+                            ),
+                            format!("cycles_detail={}", args.cycles_detail.unwrap_or(false)),
                         ]),
                         None,
                         args.max_output_chars,
@@ -1626,18 +1985,29 @@ fn handle_call(
                     }
 
                     // Build mermaid
-                    let mmd = cli::handlers::build_graph_mermaid(path.to_string_lossy().as_ref())
+                    let mmd = cli::handlers::build_graph_mermaid_sampled(
+                        path.to_string_lossy().as_ref(),
+                        sampler.as_ref(),
+                        None,
+                        &[],
+                        diag_type == "condensed",
+                    )
                         .or_else(|_| {
                         diagram::generate_mermaid_diagram(path.to_string_lossy().as_ref())
                     })?;
-                    let txt = format_diagram_text(
+                    let mut txt = format_diagram_text(
                         mmd,
                         path.to_string_lossy().as_ref(),
-                        &detail,
+                        detail,
                     );
+                    if args.cycles_detail.unwrap_or(false) {
+                        txt.push_str(&cli::handlers::cycles_detail_text(
+                            path.to_string_lossy().as_ref(),
+                        )?);
+                    }
                     let txt = clamp_text_with_limit(&txt, args.max_output_chars);
                     let etag = content_etag(&txt);
-                    cache_put(&key, &etag, &txt);
+                    cache_put(&key, &path, &etag, &txt);
                     Ok(
                         serde_json::json!({"status":"ok","etag": etag, "content":[{"type":"text","text": txt}]}),
                     )
@@ -1647,9 +2017,16 @@ fn handle_call(
                         serde_json::from_value(args).map_err(|e| e.to_string())?;
                     let path = ensure_absolute_path(args.project_path);
                     if args.deep.unwrap_or(false) {
-                        let res = cli::handlers::run_deep_pipeline(path.to_string_lossy().as_ref())
-                            .map_err(|e| e.to_string())?;
-                        let lv = level(&args.detail_level);
+                        let res = cli::handlers::run_deep_pipeline(
+                            path.to_string_lossy().as_ref(),
+                            args.include_tests.unwrap_or(false),
+                        )
+                        .map_err(|e| e.to_string())?;
+                        let (lv, _) = resolve_level(
+                            &args.detail_level,
+                            path.to_string_lossy().as_ref(),
+                            args.max_output_chars,
+                        );
                         let txt = clamp_text(
                             &res,
                             if lv == "full" {
@@ -1668,7 +2045,11 @@ fn handle_call(
                     } else {
                         let s = stats::get_project_stats(path.to_string_lossy().as_ref())
                             .map_err(|e| e.to_string())?;
-                        let lv = level(&args.detail_level);
+                        let (lv, _) = resolve_level(
+                            &args.detail_level,
+                            path.to_string_lossy().as_ref(),
+                            args.max_output_chars,
+                        );
                         let txt = format_analysis_result(path.to_string_lossy().as_ref(), &s, lv);
                         let txt = clamp_text_with_limit(&txt, args.max_output_chars);
                         let etag = content_etag(&txt);
@@ -1688,6 +2069,44 @@ fn handle_call(
                     );
                     Ok(result)
                 }
+                "impact.analyze" => {
+                    let args: ImpactArgs =
+                        serde_json::from_value(args).map_err(|e| e.to_string())?;
+                    let path = ensure_absolute_path(args.project_path);
+                    let graph = build_graph_for_path(path.to_string_lossy().as_ref())?;
+                    let mut analyzer = archlens::graph::ImpactAnalyzer::new(&graph, &path);
+                    if let Some(depth) = args.max_depth {
+                        analyzer = analyzer.with_max_depth(depth);
+                    }
+                    let report = analyzer.analyze(&args.target);
+                    let redactor = project_redactor(&path)?;
+                    let txt = cli::handlers::redact_output(redactor.as_ref(), report.to_text());
+                    let txt = clamp_text_with_limit(&txt, args.max_output_chars);
+                    let etag = content_etag(&txt);
+                    let report = redact_json(redactor.as_ref(), serde_json::json!(report));
+                    Ok(
+                        serde_json::json!({"status":"ok","etag": etag, "impact": report, "content":[{"type":"text","text": txt}]}),
+                    )
+                }
+                "path.find" => {
+                    let args: PathArgs =
+                        serde_json::from_value(args).map_err(|e| e.to_string())?;
+                    let path = ensure_absolute_path(args.project_path);
+                    let graph = build_graph_for_path(path.to_string_lossy().as_ref())?;
+                    let mut finder = archlens::graph::PathFinder::new(&graph, &path);
+                    if let Some(max_paths) = args.max_paths {
+                        finder = finder.with_max_paths(max_paths);
+                    }
+                    let report = finder.find(&args.from, &args.to);
+                    let redactor = project_redactor(&path)?;
+                    let txt = cli::handlers::redact_output(redactor.as_ref(), report.to_text());
+                    let txt = clamp_text_with_limit(&txt, args.max_output_chars);
+                    let etag = content_etag(&txt);
+                    let report = redact_json(redactor.as_ref(), serde_json::json!(report));
+                    Ok(
+                        serde_json::json!({"status":"ok","etag": etag, "path": report, "content":[{"type":"text","text": txt}]}),
+                    )
+                }
                 _ => Err(format!("unknown tool: {}", name)),
             }
         }
@@ -1696,8 +2115,13 @@ fn handle_call(
 }
 
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<()> {
+    // `--single-thread` выбирает рантайм, поэтому читается до его создания
+    archlens::execution::configure_from_args(std::env::args());
+    archlens::execution::runtime()?.block_on(serve())
+}
+
+async fn serve() -> anyhow::Result<()> {
     // init tracing (env-controlled). Route logs to stderr so STDIO JSON stays clean
     let _ = tracing_subscriber::fmt().with_writer(std::io::stderr).try_init();
 
@@ -1718,6 +2142,8 @@ async fn main() -> anyhow::Result<()> {
         schemars::schema_for!(ResourceReadArgs),
     );
     write_schema("ai_recommend_args", schemars::schema_for!(AIRecommendArgs));
+    write_schema("impact_args", schemars::schema_for!(ImpactArgs));
+    write_schema("path_args", schemars::schema_for!(PathArgs));
     write_schema("prompt_get_args", schemars::schema_for!(PromptGetArgs));
     // Output models
     write_schema(
@@ -1751,6 +2177,11 @@ async fn main() -> anyhow::Result<()> {
         }),
     );
 
+    // 2) Наблюдение за проектом: клиенты получают listChanged после изменений файлов
+    if let Some(project_path) = env_watch_path() {
+        spawn_project_watch(project_path);
+    }
+
     // 3) STDIO JSON-RPC петля
     let (tx_lines, mut rx_lines) = tokio::sync::mpsc::unbounded_channel::<String>();
     std::thread::spawn(move || {
@@ -1871,6 +2302,96 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn default_project_path() -> String { ".".to_string() }
+
+fn env_fast_budget_ms() -> u64 { env_u64("ARCHLENS_FAST_BUDGET_MS", 5_000) }
+fn env_fast_max_files() -> usize { env_usize("ARCHLENS_FAST_MAX_FILES", 100_000) }
+
+fn is_code_ext(ext: &str) -> bool {
+    matches!(ext,
+        "rs"|"js"|"ts"|"jsx"|"tsx"|"py"|"java"|"cpp"|"c"|"h"|"hpp"|"cs"|"php"|"rb"|"go"|"swift"|"kt"|"scala"|"clj"|"hs"|"ml"|"fs"|"dart"|"lua"|"r"|"m"|"mm"|"vb"|"pas"|"pl"|"pm"|"sh"|"bash"|"zsh"|"fish"|"ps1"|"psm1"|"psd1"|"json"|"yaml"|"yml"|"toml"|"xml"|"html"|"css"|"scss"|"sass"|"less"|"styl"|"vue"|"svelte"|"elm"|"ex"|"exs"|"erl"|"hrl")
+}
+
+struct QuickScanItem { name: String, size: u64 }
+struct QuickScanResult {
+    total_files: usize,
+    layers: std::collections::HashMap<String, usize>,
+    exts: std::collections::HashMap<String, usize>,
+    top_files: Vec<QuickScanItem>,
+}
+
+fn quick_scan_approx(project_path: &str, budget_ms: u64, max_files: usize, top_n: usize) -> QuickScanResult {
+    use ignore::WalkBuilder;
+    use std::collections::{HashMap, BinaryHeap};
+    let start = std::time::Instant::now();
+    let mut layers: HashMap<String, usize> = HashMap::new();
+    let mut exts: HashMap<String, usize> = HashMap::new();
+    let mut total = 0usize;
+    let mut heap: BinaryHeap<Reverse<(u64, String)>> = BinaryHeap::new();
+
+    let walker = WalkBuilder::new(project_path)
+        .hidden(false)
+        .parents(true)
+        .git_ignore(true)
+        .ignore(true)
+        .git_exclude(true)
+        .max_depth(Some(10))
+        .build();
+
+    for dent in walker {
+        if start.elapsed().as_millis() as u64 >= budget_ms { break; }
+        if total >= max_files { break; }
+        let Ok(d) = dent else { continue; };
+        let p = d.path();
+        if let Ok(md) = d.metadata() {
+            if md.is_file() {
+                total += 1;
+                let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                if !ext.is_empty() { *exts.entry(ext.clone()).or_insert(0) += 1; }
+                if is_code_ext(&ext) {
+                    let size = md.len();
+                    let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+                    let layer = crate::cli::stats::determine_layer(p);
+                    *layers.entry(layer).or_insert(0) += 1;
+                    heap.push(Reverse((size, name)));
+                    if heap.len() > top_n { let _ = heap.pop(); }
+                }
+            }
+        }
+    }
+    let mut top: Vec<QuickScanItem> = heap.into_vec().into_iter().map(|Reverse((size, name))| QuickScanItem{ name, size }).collect();
+    top.sort_by(|a,b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+    QuickScanResult { total_files: total, layers, exts, top_files: top }
+}
+
+fn fast_compact_markdown(project_path: &str, qs: &QuickScanResult, detail_level: &str, max_chars: Option<usize>) -> String {
+    let mut out = String::new();
+    out.push_str("# 🔍 PROJECT ANALYSIS\n");
+    out.push_str(&format!("**Path:** {}\n", project_path));
+    out.push_str(&format!("- Files (scanned): {}\n", qs.total_files));
+    // top file types
+    let mut types: Vec<(String, usize)> = qs.exts.iter().map(|(k,v)|(k.clone(), *v)).collect();
+    types.sort_by(|a,b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let take = match detail_level { "full" => types.len(), "standard" => types.len().min(10), _ => types.len().min(5) };
+    if take>0 { let list = types.into_iter().take(take).map(|(ext,c)| format!(".{}:{}", ext, c)).collect::<Vec<_>>().join(", "); out.push_str(&format!("- Types: {}\n", list)); }
+    out.push_str("\n# 📁 STRUCTURE\n");
+    // layers
+    let mut layers: Vec<(String, usize)> = qs.layers.iter().map(|(k,v)|(k.clone(),*v)).collect();
+    layers.sort_by(|a,b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    if !layers.is_empty() { out.push_str(&format!("- Layers: {}\n", layers.into_iter().map(|(n,_)| n).collect::<Vec<_>>().join(", "))); }
+    // top components by size
+    if !qs.top_files.is_empty() && detail_level != "summary" {
+        out.push_str("\n## Top Complexity Components (approx)\n");
+        for f in qs.top_files.iter().take(10) {
+            out.push_str(&format!("- {} ({:.1}KB)\n", f.name, (f.size as f64)/1024.0));
+        }
+    }
+    clamp_text_with_limit(&out, max_chars)
+}
+
+fn env_compact_mode() -> String { env_str("ARCHLENS_COMPACT_MODE", "auto") }
+fn env_compact_auto_file_threshold() -> usize { env_usize("ARCHLENS_COMPACT_AUTO_FILES", 2000) }
+
 #[cfg(test)]
 mod tests {
     use super::compute_recommendations;
@@ -2083,121 +2604,36 @@ mod tests {
         assert!(total <= 80, "LRU should trim total bytes to the target");
         let _ = fs::remove_dir_all(&dir);
     }
-}
-
-fn default_project_path() -> String { ".".to_string() }
-
-fn export_cache_key_git(
-    path: &str,
-    lv: &str,
-    sections: &Option<Vec<String>>,
-    top_n: Option<usize>,
-    max_chars: Option<usize>,
-) -> String {
-    let mut elems = vec![
-        path.to_string(),
-        lv.to_string(),
-        format!("top_n={}", top_n.unwrap_or(0)),
-        format!("max={}", max_chars.unwrap_or(0)),
-    ];
-    if let Some(s) = sections {
-        let mut s2 = s.clone();
-        s2.sort();
-        elems.push(format!("sections={}", s2.join("|")));
-    }
-    let head = git_head_and_dirty(Path::new(path)).unwrap_or_else(|| "nogit".to_string());
-    elems.push(format!("head={}", head));
-    let joined = elems.join("::");
-    let mut h = DefaultHasher::new();
-    joined.hash(&mut h);
-    format!("{:016x}", h.finish())
-}
-
-fn env_fast_budget_ms() -> u64 { env_u64("ARCHLENS_FAST_BUDGET_MS", 5_000) }
-fn env_fast_max_files() -> usize { env_usize("ARCHLENS_FAST_MAX_FILES", 100_000) }
-
-fn is_code_ext(ext: &str) -> bool {
-    matches!(ext,
-        "rs"|"js"|"ts"|"jsx"|"tsx"|"py"|"java"|"cpp"|"c"|"h"|"hpp"|"cs"|"php"|"rb"|"go"|"swift"|"kt"|"scala"|"clj"|"hs"|"ml"|"fs"|"dart"|"lua"|"r"|"m"|"mm"|"vb"|"pas"|"pl"|"pm"|"sh"|"bash"|"zsh"|"fish"|"ps1"|"psm1"|"psd1"|"json"|"yaml"|"yml"|"toml"|"xml"|"html"|"css"|"scss"|"sass"|"less"|"styl"|"vue"|"svelte"|"elm"|"ex"|"exs"|"erl"|"hrl")
-}
-
-struct QuickScanItem { name: String, path: String, size: u64 }
-struct QuickScanResult {
-    total_files: usize,
-    layers: std::collections::HashMap<String, usize>,
-    exts: std::collections::HashMap<String, usize>,
-    top_files: Vec<QuickScanItem>,
-}
-
-fn quick_scan_approx(project_path: &str, budget_ms: u64, max_files: usize, top_n: usize) -> QuickScanResult {
-    use ignore::WalkBuilder;
-    use std::collections::{HashMap, BinaryHeap};
-    let start = std::time::Instant::now();
-    let mut layers: HashMap<String, usize> = HashMap::new();
-    let mut exts: HashMap<String, usize> = HashMap::new();
-    let mut total = 0usize;
-    let mut heap: BinaryHeap<Reverse<(u64, String, String)>> = BinaryHeap::new();
-
-    let walker = WalkBuilder::new(project_path)
-        .hidden(false)
-        .parents(true)
-        .git_ignore(true)
-        .ignore(true)
-        .git_exclude(true)
-        .max_depth(Some(10))
-        .build();
-
-    for dent in walker {
-        if start.elapsed().as_millis() as u64 >= budget_ms { break; }
-        if total >= max_files { break; }
-        let Ok(d) = dent else { continue; };
-        let p = d.path();
-        if let Ok(md) = d.metadata() {
-            if md.is_file() {
-                total += 1;
-                let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
-                if !ext.is_empty() { *exts.entry(ext.clone()).or_insert(0) += 1; }
-                if is_code_ext(&ext) {
-                    let size = md.len();
-                    let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
-                    let rel = p.to_string_lossy().to_string();
-                    let layer = crate::cli::stats::determine_layer(p);
-                    *layers.entry(layer).or_insert(0) += 1;
-                    heap.push(Reverse((size, name, rel)));
-                    if heap.len() > top_n { let _ = heap.pop(); }
-                }
-            }
-        }
-    }
-    let mut top: Vec<QuickScanItem> = heap.into_vec().into_iter().map(|Reverse((size, name, path))| QuickScanItem{ name, path, size }).collect();
-    top.sort_by(|a,b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
-    QuickScanResult { total_files: total, layers, exts, top_files: top }
-}
 
-fn fast_compact_markdown(project_path: &str, qs: &QuickScanResult, detail_level: &str, max_chars: Option<usize>) -> String {
-    let mut out = String::new();
-    out.push_str("# 🔍 PROJECT ANALYSIS\n");
-    out.push_str(&format!("**Path:** {}\n", project_path));
-    out.push_str(&format!("- Files (scanned): {}\n", qs.total_files));
-    // top file types
-    let mut types: Vec<(String, usize)> = qs.exts.iter().map(|(k,v)|(k.clone(), *v)).collect();
-    types.sort_by(|a,b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
-    let take = match detail_level { "full" => types.len(), "standard" => types.len().min(10), _ => types.len().min(5) };
-    if take>0 { let list = types.into_iter().take(take).map(|(ext,c)| format!(".{}:{}", ext, c)).collect::<Vec<_>>().join(", "); out.push_str(&format!("- Types: {}\n", list)); }
-    out.push_str("\n# 📁 STRUCTURE\n");
-    // layers
-    let mut layers: Vec<(String, usize)> = qs.layers.iter().map(|(k,v)|(k.clone(),*v)).collect();
-    layers.sort_by(|a,b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
-    if !layers.is_empty() { out.push_str(&format!("- Layers: {}\n", layers.into_iter().map(|(n,_)| n).collect::<Vec<_>>().join(", "))); }
-    // top components by size
-    if !qs.top_files.is_empty() && detail_level != "summary" {
-        out.push_str("\n## Top Complexity Components (approx)\n");
-        for f in qs.top_files.iter().take(10) {
-            out.push_str(&format!("- {} ({:.1}KB)\n", f.name, (f.size as f64)/1024.0));
+    #[test]
+    fn cache_evicts_only_the_changed_project() {
+        let dir = PathBuf::from("out/test_cache_evict");
+        let _ = fs::remove_dir_all(&dir);
+        let (changed, other) = (dir.join("changed"), dir.join("other"));
+        fs::create_dir_all(&changed).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let entries = dir.join("entries");
+        fs::create_dir_all(&entries).unwrap();
+        for (name, project) in [("a", &changed), ("b", &changed), ("c", &other)] {
+            let project = project.canonicalize().unwrap();
+            fs::write(
+                entries.join(format!("{}.json", name)),
+                serde_json::json!({"etag": name, "output": "", "project": project.to_string_lossy()})
+                    .to_string(),
+            )
+            .unwrap();
         }
+        fs::write(entries.join("legacy.json"), r#"{"etag":"e","output":""}"#).unwrap();
+
+        // a relative path names the same project as the stored absolute one
+        assert_eq!(super::cache_evict_project(&entries, &changed), 2);
+        let mut left: Vec<String> = fs::read_dir(&entries)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["c.json", "legacy.json"]);
+        let _ = fs::remove_dir_all(&dir);
     }
-    clamp_text_with_limit(&out, max_chars)
 }
-
-fn env_compact_mode() -> String { env_str("ARCHLENS_COMPACT_MODE", "auto") }
-fn env_compact_auto_file_threshold() -> usize { env_usize("ARCHLENS_COMPACT_AUTO_FILES", 2000) }
@@ -0,0 +1,194 @@
+// Трёхсторонний diff для долгоживущих веток: какие архитектурные изменения
+// приносит только ветка, а какие уже пришли в main от общей базы
+
+use crate::diff_analyzer::DiffAnalyzer;
+use crate::release_report::{push_list, ReleaseSide};
+use crate::types::{ArchitectureChange, ChangeType, DiffAnalysis, QualityTrend, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Изменение компонента, сделанное по-разному в main и в ветке
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivergedChange {
+    pub component: String,
+    pub on_main: String,
+    pub on_branch: String,
+}
+
+/// Сравнение base → main и base → branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreeWayDiff {
+    pub base: String,
+    pub main: String,
+    pub branch: String,
+    /// Изменения ветки, которых нет в main — то, что реально принесёт merge
+    pub unique_to_branch: Vec<ArchitectureChange>,
+    /// Изменения ветки, уже сделанные в main (cherry-pick, общий код)
+    pub already_on_main: Vec<ArchitectureChange>,
+    pub diverged: Vec<DivergedChange>,
+    /// Изменения main после базы, которых ветка ещё не видела
+    pub only_on_main: Vec<ArchitectureChange>,
+    pub branch_trend: QualityTrend,
+}
+
+/// Diff обеих сторон относительно базы (корни ревизий учитываются)
+pub fn three_way_diff(
+    base: &ReleaseSide,
+    main: &ReleaseSide,
+    branch: &ReleaseSide,
+) -> Result<ThreeWayDiff> {
+    let on_main = DiffAnalyzer::new()
+        .with_roots(&base.root, &main.root)
+        .analyze_diff(&main.graph, &base.graph)?;
+    let on_branch = DiffAnalyzer::new()
+        .with_roots(&base.root, &branch.root)
+        .analyze_diff(&branch.graph, &base.graph)?;
+    Ok(ThreeWayDiff::from_diffs(
+        &base.rev,
+        &main.rev,
+        &branch.rev,
+        on_main,
+        on_branch,
+    ))
+}
+
+impl ThreeWayDiff {
+    /// Сопоставляет изменения сторон по типу и компоненту (для связей — и цели);
+    /// совпавшие по ключу, но с другим описанием, считаются разошедшимися
+    pub fn from_diffs(
+        base: &str,
+        main: &str,
+        branch: &str,
+        on_main: DiffAnalysis,
+        on_branch: DiffAnalysis,
+    ) -> Self {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, change) in on_main.changes.iter().enumerate() {
+            index.entry(change_key(change)).or_default().push(i);
+        }
+        let mut consumed = vec![false; on_main.changes.len()];
+
+        let mut unique_to_branch = Vec::new();
+        let mut already_on_main = Vec::new();
+        let mut diverged = Vec::new();
+        for change in on_branch.changes {
+            let candidates: Vec<usize> = index
+                .get(&change_key(&change))
+                .map(|ids| ids.iter().copied().filter(|i| !consumed[*i]).collect())
+                .unwrap_or_default();
+            let same = candidates
+                .iter()
+                .copied()
+                .find(|i| on_main.changes[*i].description == change.description);
+            match (same, candidates.first()) {
+                (Some(i), _) => {
+                    consumed[i] = true;
+                    already_on_main.push(change);
+                }
+                (None, Some(&i)) => {
+                    consumed[i] = true;
+                    diverged.push(DivergedChange {
+                        component: change.component.clone(),
+                        on_main: on_main.changes[i].description.clone(),
+                        on_branch: change.description,
+                    });
+                }
+                (None, None) => unique_to_branch.push(change),
+            }
+        }
+        let only_on_main = on_main
+            .changes
+            .into_iter()
+            .zip(consumed)
+            .filter(|(_, used)| !used)
+            .map(|(change, _)| change)
+            .collect();
+
+        Self {
+            base: base.to_string(),
+            main: main.to_string(),
+            branch: branch.to_string(),
+            unique_to_branch,
+            already_on_main,
+            diverged,
+            only_on_main,
+            branch_trend: on_branch.quality_trend,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "## Branch diff: {} vs {} (base {})\n\n",
+            self.branch, self.main, self.base
+        );
+        md.push_str(&format!(
+            "_{} unique to branch, {} already on main, {} diverged, {} only on main; branch quality trend: {:?}_\n",
+            self.unique_to_branch.len(),
+            self.already_on_main.len(),
+            self.diverged.len(),
+            self.only_on_main.len(),
+            self.branch_trend
+        ));
+        push_list(
+            &mut md,
+            "Unique to branch",
+            self.unique_to_branch.iter().map(change_line).collect(),
+        );
+        push_list(
+            &mut md,
+            "Diverged from main",
+            self.diverged
+                .iter()
+                .map(|d| {
+                    format!(
+                        "`{}` — main: {}; branch: {}",
+                        d.component, d.on_main, d.on_branch
+                    )
+                })
+                .collect(),
+        );
+        push_list(
+            &mut md,
+            "Already on main",
+            self.already_on_main.iter().map(change_line).collect(),
+        );
+        push_list(
+            &mut md,
+            "Only on main (not yet in branch)",
+            self.only_on_main.iter().map(change_line).collect(),
+        );
+        md
+    }
+}
+
+/// Markdown двустороннего diff между ревизиями
+pub fn diff_markdown(from: &str, to: &str, diff: &DiffAnalysis) -> String {
+    let mut md = format!("## Architecture diff {}…{}\n\n", from, to);
+    md.push_str(&format!("_{}_\n", diff.summary));
+    push_list(
+        &mut md,
+        "Changes",
+        diff.changes.iter().map(change_line).collect(),
+    );
+    push_list(&mut md, "Recommendations", diff.recommendations.clone());
+    md
+}
+
+fn change_key(change: &ArchitectureChange) -> String {
+    // Для добавленных/изменённых компонентов related_components зависит от
+    // окружения в графе стороны, поэтому в ключ входит только у связей и переименований
+    let related = match change.change_type {
+        ChangeType::NewDependency | ChangeType::RemovedDependency | ChangeType::Renamed => {
+            change.related_components.join(",")
+        }
+        _ => String::new(),
+    };
+    format!("{:?}|{}|{}", change.change_type, change.component, related)
+}
+
+fn change_line(change: &ArchitectureChange) -> String {
+    format!(
+        "**{:?}** `{}` — {}",
+        change.change_type, change.component, change.description
+    )
+}
@@ -24,6 +24,13 @@ impl CapsuleGraphBuilder {
         }
     }
 
+    /// Builder without structural relations (shared directory or layer)
+    pub fn without_structural_relations(mut self) -> Self {
+        self.core_builder.relation_analyzer =
+            std::mem::take(&mut self.core_builder.relation_analyzer).without_structural_relations();
+        self
+    }
+
     /// Main graph building function - delegates to core builder
     pub fn build_graph(&mut self, capsules: &[Capsule]) -> Result<CapsuleGraph> {
         self.core_builder.build_graph(capsules)
@@ -65,7 +72,7 @@ impl CapsuleGraphBuilder {
     ) -> u32 {
         self.core_builder
             .metrics_calculator
-            .calculate_dependency_depth(capsule_id, relations, &mut Vec::new())
+            .calculate_dependency_depth(capsule_id, relations)
     }
 
     /// Update capsule dependencies based on relations
@@ -123,6 +130,11 @@ impl CapsuleGraphBuilder {
             .get_strongly_connected_components(graph)
     }
 
+    /// Collapse every strongly connected component into one node
+    pub fn condense_graph(&mut self, graph: &CapsuleGraph) -> CapsuleGraph {
+        self.core_builder.condense_graph(graph)
+    }
+
     /// Check if graph has cycles
     pub fn has_cycles(&mut self, graph: &CapsuleGraph) -> bool {
         self.core_builder.cycle_detector.has_cycles(graph)
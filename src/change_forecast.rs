@@ -0,0 +1,456 @@
+// Раннее предупреждение об эрозии: частота изменений капсул по истории git
+// (коммиты по интервалам окна), линейный тренд и прогноз на следующий интервал.
+// Модуль (файл) помечается, если изменения ускоряются и одновременно падает качество:
+// растёт доля исправлений ошибок или код быстро разрастается
+
+use crate::git::run_git;
+use crate::types::{Capsule, CapsuleGraph, Priority, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Тренд изменений одной капсулы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapsuleTrend {
+    pub name: String,
+    /// `путь:строка` относительно корня репозитория
+    pub location: String,
+    /// Коммитов по интервалам окна, от старых к новым
+    pub changes: Vec<usize>,
+    /// Наклон линейного тренда, коммитов за интервал
+    pub slope: f64,
+    /// Ожидаемое число коммитов в следующем интервале
+    pub forecast: f64,
+    /// Добавлено минус удалено строк за окно
+    pub net_growth: i64,
+}
+
+/// Прогноз изменений модуля (файла)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleForecast {
+    pub path: String,
+    pub changes: Vec<usize>,
+    pub slope: f64,
+    pub forecast: f64,
+    /// Изменения во второй половине окна заметно чаще, чем в первой
+    pub accelerating: bool,
+    /// Признаки падения качества
+    pub quality_signals: Vec<String>,
+    /// Ускорение + падение качества
+    pub erosion_warning: bool,
+    /// Капсулы с самым быстрым ростом изменений
+    pub capsules: Vec<CapsuleTrend>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErosionReport {
+    pub window_days: u32,
+    pub bucket_days: u32,
+    pub commits: usize,
+    /// Сначала предупреждения, затем по прогнозу
+    pub modules: Vec<ModuleForecast>,
+}
+
+/// Ханк коммита в координатах новой стороны
+#[derive(Debug, Clone)]
+struct Hunk {
+    start: usize,
+    end: usize,
+    added: usize,
+    removed: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CommitChange {
+    timestamp: i64,
+    fix: bool,
+    files: BTreeMap<String, Vec<Hunk>>,
+}
+
+/// Накопленная история одного файла или капсулы
+#[derive(Debug, Clone, Default)]
+struct History {
+    changes: Vec<usize>,
+    fixes: Vec<usize>,
+    added: usize,
+    removed: usize,
+}
+
+impl History {
+    fn new(buckets: usize) -> Self {
+        Self {
+            changes: vec![0; buckets],
+            fixes: vec![0; buckets],
+            ..Self::default()
+        }
+    }
+
+    fn record(&mut self, bucket: usize, fix: bool, hunks: &[&Hunk]) {
+        self.changes[bucket] += 1;
+        if fix {
+            self.fixes[bucket] += 1;
+        }
+        self.added += hunks.iter().map(|h| h.added).sum::<usize>();
+        self.removed += hunks.iter().map(|h| h.removed).sum::<usize>();
+    }
+
+    fn halves(values: &[usize]) -> (usize, usize) {
+        let mid = values.len() / 2;
+        (values[..mid].iter().sum(), values[mid..].iter().sum())
+    }
+}
+
+/// Прогноз частоты изменений по истории git
+#[derive(Debug)]
+pub struct ChangeForecaster {
+    repo: PathBuf,
+    /// Окно истории, дней
+    pub window_days: u32,
+    /// Число интервалов окна
+    pub buckets: usize,
+    /// Минимум коммитов во второй половине окна для ускорения
+    pub min_recent_changes: usize,
+    /// Во сколько раз вторая половина окна должна превышать первую
+    pub acceleration_ratio: f64,
+    /// Рост кода за окно (доля размера в начале окна), считающийся разрастанием
+    pub growth_ratio: f64,
+    pub top_n: usize,
+}
+
+impl ChangeForecaster {
+    pub fn new(repo: &Path) -> Self {
+        Self {
+            repo: repo.to_path_buf(),
+            window_days: 180,
+            buckets: 6,
+            min_recent_changes: 3,
+            acceleration_ratio: 1.5,
+            growth_ratio: 0.25,
+            top_n: 20,
+        }
+    }
+
+    pub fn with_window_days(mut self, days: u32) -> Self {
+        self.window_days = days.max(1);
+        self
+    }
+
+    pub fn analyze(&self, graph: &CapsuleGraph) -> Result<ErosionReport> {
+        let toplevel = PathBuf::from(
+            run_git(&self.repo, &["rev-parse", "--show-toplevel"])?
+                .trim()
+                .to_string(),
+        );
+        let toplevel = toplevel.canonicalize().unwrap_or(toplevel);
+        let commits = self.history()?;
+        let now = chrono::Utc::now().timestamp();
+        let buckets = self.buckets.max(2);
+        let bucket_secs = (self.window_days as i64 * 86_400 / buckets as i64).max(1);
+
+        // капсулы по пути файла относительно корня репозитория
+        let mut by_file: BTreeMap<String, Vec<&Capsule>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            let path = capsule
+                .file_path
+                .canonicalize()
+                .unwrap_or_else(|_| capsule.file_path.clone());
+            if let Ok(relative) = path.strip_prefix(&toplevel) {
+                by_file
+                    .entry(relative.to_string_lossy().replace('\\', "/"))
+                    .or_default()
+                    .push(capsule);
+            }
+        }
+
+        let mut files: BTreeMap<&str, History> = BTreeMap::new();
+        let mut capsules: BTreeMap<uuid::Uuid, History> = BTreeMap::new();
+        for commit in &commits {
+            let age = (now - commit.timestamp).max(0) / bucket_secs;
+            let bucket = buckets - 1 - (age as usize).min(buckets - 1);
+            for (path, hunks) in &commit.files {
+                let Some((path, members)) = by_file.get_key_value(path.as_str()) else {
+                    continue;
+                };
+                files
+                    .entry(path.as_str())
+                    .or_insert_with(|| History::new(buckets))
+                    .record(bucket, commit.fix, &hunks.iter().collect::<Vec<_>>());
+                for capsule in members {
+                    let touched: Vec<&Hunk> = hunks
+                        .iter()
+                        .filter(|h| h.start <= capsule.line_end && capsule.line_start <= h.end)
+                        .collect();
+                    if !touched.is_empty() {
+                        capsules
+                            .entry(capsule.id)
+                            .or_insert_with(|| History::new(buckets))
+                            .record(bucket, commit.fix, &touched);
+                    }
+                }
+            }
+        }
+
+        let complexity_average = graph.metrics.complexity_average as f64;
+        let mut modules: Vec<ModuleForecast> = files
+            .into_iter()
+            .map(|(path, history)| {
+                let members = &by_file[path];
+                let (slope, forecast) = linear_forecast(&history.changes);
+                let (earlier, recent) = History::halves(&history.changes);
+                let accelerating = slope > 0.0
+                    && recent >= self.min_recent_changes
+                    && recent as f64 >= earlier as f64 * self.acceleration_ratio;
+
+                let mut trends: Vec<CapsuleTrend> = members
+                    .iter()
+                    .filter_map(|c| {
+                        let h = capsules.get(&c.id)?;
+                        let (slope, forecast) = linear_forecast(&h.changes);
+                        Some(CapsuleTrend {
+                            name: c.name.clone(),
+                            location: format!("{}:{}", path, c.line_start),
+                            changes: h.changes.clone(),
+                            slope: round2(slope),
+                            forecast: round2(forecast),
+                            net_growth: h.added as i64 - h.removed as i64,
+                        })
+                    })
+                    .collect();
+                trends.sort_by(|a, b| {
+                    b.slope
+                        .total_cmp(&a.slope)
+                        .then_with(|| b.forecast.total_cmp(&a.forecast))
+                        .then_with(|| a.location.cmp(&b.location))
+                });
+                trends.truncate(5);
+
+                let quality_signals = self.quality_signals(&history, members, complexity_average);
+                ModuleForecast {
+                    path: path.to_string(),
+                    changes: history.changes,
+                    slope: round2(slope),
+                    forecast: round2(forecast),
+                    accelerating,
+                    erosion_warning: accelerating && !quality_signals.is_empty(),
+                    quality_signals,
+                    capsules: trends,
+                }
+            })
+            .collect();
+        modules.sort_by(|a, b| {
+            b.erosion_warning
+                .cmp(&a.erosion_warning)
+                .then_with(|| b.accelerating.cmp(&a.accelerating))
+                .then_with(|| b.forecast.total_cmp(&a.forecast))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        modules.truncate(self.top_n);
+
+        Ok(ErosionReport {
+            window_days: self.window_days,
+            bucket_days: (bucket_secs / 86_400) as u32,
+            commits: commits.len(),
+            modules,
+        })
+    }
+
+    /// Признаки падения качества модуля за окно
+    fn quality_signals(
+        &self,
+        history: &History,
+        members: &[&Capsule],
+        complexity_average: f64,
+    ) -> Vec<String> {
+        let mut signals = Vec::new();
+        let (earlier, recent) = History::halves(&history.changes);
+        let (earlier_fixes, recent_fixes) = History::halves(&history.fixes);
+        let share = |fixes: usize, total: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                fixes as f64 / total as f64
+            }
+        };
+        if recent_fixes >= 2 && share(recent_fixes, recent) > share(earlier_fixes, earlier) {
+            signals.push(format!(
+                "bug-fix share rose from {:.0}% to {:.0}%",
+                share(earlier_fixes, earlier) * 100.0,
+                share(recent_fixes, recent) * 100.0
+            ));
+        }
+
+        // размер файла — по последней строке его капсул
+        let size = members.iter().map(|c| c.line_end).max().unwrap_or(0);
+        let growth = history.added as i64 - history.removed as i64;
+        // файл, созданный внутри окна, не «разрастается»
+        let before = size as i64 - growth;
+        if growth > 0 && before > 0 && growth as f64 >= before as f64 * self.growth_ratio {
+            signals.push(format!(
+                "grew by {} lines (+{:.0}%)",
+                growth,
+                growth as f64 / before as f64 * 100.0
+            ));
+        }
+
+        let severe = members
+            .iter()
+            .flat_map(|c| &c.warnings)
+            .filter(|w| matches!(w.level, Priority::High | Priority::Critical))
+            .count();
+        let max_complexity = members.iter().map(|c| c.complexity).max().unwrap_or(0);
+        if severe > 0 && max_complexity as f64 > complexity_average {
+            signals.push(format!(
+                "{} high-severity finding(s), complexity {} above project average {:.1}",
+                severe, max_complexity, complexity_average
+            ));
+        }
+        signals
+    }
+
+    /// Коммиты окна с ханками `git log -U0` (без слияний)
+    fn history(&self) -> Result<Vec<CommitChange>> {
+        let out = run_git(
+            &self.repo,
+            &[
+                "log",
+                "--no-merges",
+                "--no-color",
+                "--no-ext-diff",
+                "--no-renames",
+                "-U0",
+                "--format=%x01%ct%x1f%s",
+                &format!("--since={} days ago", self.window_days),
+            ],
+        )?;
+        Ok(parse_log(&out))
+    }
+}
+
+/// Разбор вывода `git log -U0 --format=%x01%ct%x1f%s`
+fn parse_log(log: &str) -> Vec<CommitChange> {
+    let header = Regex::new(r"^@@ -\d+(?:,(\d+))? \+(\d+)(?:,(\d+))? @@").unwrap();
+    let fix = Regex::new(r"(?i)\b(fix(es|ed)?|bug|bugfix|hotfix|revert|regression)\b").unwrap();
+    let mut commits: Vec<CommitChange> = Vec::new();
+    let mut path: Option<String> = None;
+    for line in log.lines() {
+        if let Some(meta) = line.strip_prefix('\u{1}') {
+            let (timestamp, subject) = meta.split_once('\u{1f}').unwrap_or((meta, ""));
+            commits.push(CommitChange {
+                timestamp: timestamp.trim().parse().unwrap_or(0),
+                fix: fix.is_match(subject),
+                files: BTreeMap::new(),
+            });
+            path = None;
+        } else if let Some(p) = line.strip_prefix("+++ ") {
+            path = p.strip_prefix("b/").map(str::to_string);
+        } else if let Some(caps) = header.captures(line) {
+            let (Some(commit), Some(path)) = (commits.last_mut(), &path) else {
+                continue;
+            };
+            let count = |i: usize| -> usize {
+                caps.get(i)
+                    .map(|m| m.as_str().parse().unwrap_or(1))
+                    .unwrap_or(1)
+            };
+            let start: usize = caps[2].parse().unwrap_or(0);
+            let added = count(3);
+            commit.files.entry(path.clone()).or_default().push(Hunk {
+                start,
+                // чистое удаление (added == 0) — точка после строки start
+                end: start + added.saturating_sub(1),
+                added,
+                removed: count(1),
+            });
+        }
+    }
+    commits
+}
+
+/// Наклон МНК-прямой по интервалам и её значение на следующем интервале (не меньше 0)
+pub fn linear_forecast(values: &[usize]) -> (f64, f64) {
+    let n = values.len() as f64;
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<usize>() as f64 / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (x, y) in values.iter().enumerate() {
+        let dx = x as f64 - mean_x;
+        covariance += dx * (*y as f64 - mean_y);
+        variance += dx * dx;
+    }
+    let slope = if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    };
+    (slope, (mean_y + slope * (n - mean_x)).max(0.0))
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl ErosionReport {
+    pub fn warnings(&self) -> impl Iterator<Item = &ModuleForecast> {
+        self.modules.iter().filter(|m| m.erosion_warning)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "## Erosion early warning (last {} days, {}-day intervals, {} commits)\n\n",
+            self.window_days, self.bucket_days, self.commits
+        );
+        let flagged: Vec<&ModuleForecast> = self.warnings().collect();
+        if flagged.is_empty() {
+            md.push_str("_No module combines accelerating change with declining quality._\n");
+        } else {
+            md.push_str("| Module | Changes per interval | Forecast | Quality signals | Hot capsules |\n|---|---|---|---|---|\n");
+            for module in &flagged {
+                let hot: BTreeSet<&str> = module
+                    .capsules
+                    .iter()
+                    .filter(|c| c.slope > 0.0)
+                    .map(|c| c.name.as_str())
+                    .collect();
+                md.push_str(&format!(
+                    "| `{}` | {} | {:.1} | {} | {} |\n",
+                    module.path,
+                    sparkline(&module.changes),
+                    module.forecast,
+                    module.quality_signals.join("; "),
+                    hot.into_iter().collect::<Vec<_>>().join(", ")
+                ));
+            }
+        }
+
+        let watch: Vec<&ModuleForecast> = self
+            .modules
+            .iter()
+            .filter(|m| m.accelerating && !m.erosion_warning)
+            .collect();
+        if !watch.is_empty() {
+            md.push_str("\n### Accelerating change (quality stable)\n\n");
+            for module in watch {
+                md.push_str(&format!(
+                    "- `{}` {} forecast {:.1}\n",
+                    module.path,
+                    sparkline(&module.changes),
+                    module.forecast
+                ));
+            }
+        }
+        md
+    }
+}
+
+/// `1 → 0 → 2 → 5` — коммиты по интервалам, от старых к новым
+fn sparkline(values: &[usize]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
@@ -10,6 +10,23 @@ pub struct ModuleDependency {
     pub dependency_type: String,
 }
 
+/// Класс/структура/интерфейс для classDiagram
+#[derive(Debug, Default, Clone)]
+pub struct ClassInfo {
+    pub name: String,
+    /// Стереотип: struct, enum, interface, trait, class
+    pub kind: String,
+    pub fields: Vec<String>,
+    pub methods: Vec<String>,
+    /// Наследование (`<|--`)
+    pub extends: Vec<String>,
+    /// Реализация интерфейсов/трейтов (`<|..`)
+    pub implements: Vec<String>,
+}
+
+/// Максимум классов в одной диаграмме — дальше Mermaid становится нечитаемым
+const CLASS_DIAGRAM_LIMIT: usize = 150;
+
 #[derive(Debug)]
 enum NodeType {
     Core,
@@ -49,6 +66,25 @@ pub fn generate_mermaid_diagram(project_path: &str) -> std::result::Result<Strin
     Ok(output)
 }
 
+/// Generates Mermaid classDiagram for types under `scope` (relative to the project or absolute)
+pub fn generate_class_diagram(
+    project_path: &str,
+    scope: Option<&str>,
+) -> std::result::Result<String, String> {
+    let root = Path::new(project_path);
+    let scope_path = match scope {
+        Some(s) if Path::new(s).is_absolute() => Path::new(s).to_path_buf(),
+        Some(s) => root.join(s),
+        None => root.to_path_buf(),
+    };
+    if !scope_path.exists() {
+        return Err(format!("Path does not exist: {}", scope_path.display()));
+    }
+
+    let classes = collect_classes(&scope_path)?;
+    Ok(render_class_diagram(&classes))
+}
+
 /// Analyzes module dependencies in the project
 pub fn analyze_module_dependencies(
     project_path: &str,
@@ -179,6 +215,611 @@ fn add_subgraphs(output: &mut String, nodes: &HashMap<String, String>) {
     }
 }
 
+// ============================================================================
+// CLASS DIAGRAM
+// ============================================================================
+
+/// Собирает типы из исходников под `scope`; методы из `impl`/receiver-функций
+/// присоединяются к своему типу, даже если объявлены в другом файле
+pub fn collect_classes(scope: &Path) -> std::result::Result<Vec<ClassInfo>, String> {
+    let mut files: Vec<std::path::PathBuf> = if scope.is_file() {
+        vec![scope.to_path_buf()]
+    } else {
+        walkdir::WalkDir::new(scope)
+            .into_iter()
+            .filter_entry(|e| {
+                !(e.file_type().is_dir()
+                    && e.file_name().to_str().is_some_and(should_skip_directory))
+            })
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect()
+    };
+    files.sort();
+
+    let mut classes: Vec<ClassInfo> = Vec::new();
+    for file in files {
+        let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let parse: fn(&str, &mut Vec<ClassInfo>) = match ext {
+            "rs" => parse_rust_classes,
+            "py" => parse_python_classes,
+            "ts" | "tsx" | "js" | "jsx" | "java" => parse_braced_classes,
+            "go" => parse_go_classes,
+            _ => continue,
+        };
+        let content =
+            fs::read_to_string(&file).map_err(|e| format!("Failed to read file: {}", e))?;
+        parse(&content, &mut classes);
+    }
+
+    // Методы impl-блоков для типов, объявленных вне scope, не показываем
+    classes.retain(|c| !c.kind.is_empty());
+    Ok(classes)
+}
+
+fn render_class_diagram(classes: &[ClassInfo]) -> String {
+    let mut output = String::from("classDiagram\n");
+    output.push_str("    %% Type relationships\n");
+    let shown: Vec<&ClassInfo> = classes.iter().take(CLASS_DIAGRAM_LIMIT).collect();
+    if classes.len() > shown.len() {
+        output.push_str(&format!(
+            "    %% showing {} of {} types; narrow the scope for the rest\n",
+            shown.len(),
+            classes.len()
+        ));
+    }
+    let known: std::collections::HashSet<&str> = shown.iter().map(|c| c.name.as_str()).collect();
+
+    for class in &shown {
+        output.push_str(&format!("    class {} {{\n", class_id(&class.name)));
+        output.push_str(&format!("        <<{}>>\n", class.kind));
+        for field in &class.fields {
+            output.push_str(&format!(
+                "        {}\n",
+                mermaid_member(&field.replace('(', "[").replace(')', "]"))
+            ));
+        }
+        for method in &class.methods {
+            output.push_str(&format!("        {}\n", mermaid_member(method)));
+        }
+        output.push_str("    }\n");
+    }
+
+    // Связи только между типами диаграммы: внешние трейты (Debug, Default…) — шум
+    let mut edges = Vec::new();
+    for class in &shown {
+        for parent in &class.extends {
+            if known.contains(parent.as_str()) {
+                edges.push(format!(
+                    "    {} <|-- {}",
+                    class_id(parent),
+                    class_id(&class.name)
+                ));
+            }
+        }
+        for iface in &class.implements {
+            if known.contains(iface.as_str()) {
+                edges.push(format!(
+                    "    {} <|.. {}",
+                    class_id(iface),
+                    class_id(&class.name)
+                ));
+            }
+        }
+    }
+    edges.sort();
+    edges.dedup();
+    for edge in edges {
+        output.push_str(&edge);
+        output.push('\n');
+    }
+    output
+}
+
+fn class_entry<'a>(classes: &'a mut Vec<ClassInfo>, name: &str) -> &'a mut ClassInfo {
+    if let Some(pos) = classes.iter().position(|c| c.name == name) {
+        return &mut classes[pos];
+    }
+    classes.push(ClassInfo {
+        name: name.to_string(),
+        ..Default::default()
+    });
+    classes.last_mut().unwrap()
+}
+
+fn declare_class(classes: &mut Vec<ClassInfo>, name: &str, kind: &str) {
+    let class = class_entry(classes, name);
+    if class.kind.is_empty() {
+        class.kind = kind.to_string();
+    }
+}
+
+/// Имя типа без обобщений и пути: `crate::a::Foo<T>` → `Foo`
+fn base_type_name(raw: &str) -> String {
+    let raw = raw.trim().trim_start_matches('&').trim_start_matches('*');
+    let raw = raw.split(['<', '(', '[']).next().unwrap_or(raw);
+    raw.rsplit(['.', ':'])
+        .next()
+        .unwrap_or(raw)
+        .trim()
+        .to_string()
+}
+
+fn split_type_list(list: &str) -> Vec<String> {
+    // запятые внутри <...> не разделяют типы
+    let mut parts = Vec::new();
+    let (mut depth, mut current) = (0, String::new());
+    for ch in list.chars() {
+        match ch {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    parts.push(current);
+    parts
+        .iter()
+        .map(|p| base_type_name(p))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Скобки строки вне строковых литералов и `//`-комментариев
+fn brace_delta(line: &str) -> i32 {
+    let (mut delta, mut in_string, mut escaped) = (0, false, false);
+    let mut prev = ' ';
+    for ch in line.chars() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else {
+            match ch {
+                '"' => in_string = true,
+                '/' if prev == '/' => break,
+                '{' => delta += 1,
+                '}' => delta -= 1,
+                _ => {}
+            }
+        }
+        prev = ch;
+    }
+    delta
+}
+
+/// `fn name(&self, a: u32) -> Ret` → `+name(a: u32) Ret`
+fn method_signature(visibility: &str, name: &str, params: &str, ret: &str) -> String {
+    let params: Vec<&str> = params
+        .split(',')
+        .map(str::trim)
+        .filter(|p| {
+            !p.is_empty()
+                && !matches!(*p, "self" | "&self" | "&mut self" | "mut self" | "cls")
+                && !p.starts_with("self:")
+        })
+        .collect();
+    let ret = ret.trim();
+    if ret.is_empty() {
+        format!("{}{}({})", visibility, name, params.join(", "))
+    } else {
+        format!("{}{}({}) {}", visibility, name, params.join(", "), ret)
+    }
+}
+
+/// Mermaid использует `~T~` для обобщений и не любит фигурные скобки и кавычки;
+/// круглые скобки в поле превращают его в метод, поэтому кортежи полей — `[A, B]`
+fn mermaid_member(member: &str) -> String {
+    member
+        .replace(['<', '>'], "~")
+        .replace(['{', '}', '"', ';'], "")
+}
+
+fn class_id(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn parse_rust_classes(content: &str, classes: &mut Vec<ClassInfo>) {
+    let type_decl = regex::Regex::new(
+        r"^(pub(?:\([^)]*\))?\s+)?(struct|enum|trait)\s+([A-Za-z_]\w*)(?:<[^{]*?>)?\s*(?::\s*([^{]+?))?\s*(?:where[^{]*)?(\{|;|\()?\s*$",
+    )
+    .unwrap();
+    let impl_decl = regex::Regex::new(
+        r"^(?:unsafe\s+)?impl(?:<[^{]*?>)?\s+(?:([\w:<>, ]+?)\s+for\s+)?([\w:]+)(?:<[^{]*?>)?\s*(?:where[^{]*)?\{?\s*$",
+    )
+    .unwrap();
+    let method = regex::Regex::new(
+        r"^(pub(?:\([^)]*\))?\s+)?(?:const\s+|async\s+|unsafe\s+)*fn\s+(\w+)(?:<[^(]*>)?\s*\(([^)]*)\)\s*(?:->\s*([^{;]+?))?\s*(?:where.*)?[{;]?\s*$",
+    )
+    .unwrap();
+    let field =
+        regex::Regex::new(r"^(pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*(.+?),?\s*(//.*)?$").unwrap();
+    let variant = regex::Regex::new(r"^([A-Z]\w*)\b").unwrap();
+
+    // (тип, вид блока, глубина, на которой блок открыт)
+    let mut context: Option<(String, &str, i32)> = None;
+    let mut depth = 0;
+    for raw in content.lines() {
+        let line = raw.trim();
+        let delta = brace_delta(line);
+        if line.starts_with("//") || line.starts_with('#') {
+            continue;
+        }
+
+        match &context {
+            None => {
+                if let Some(caps) = type_decl.captures(line) {
+                    let kind = &caps[2];
+                    let name = caps[3].to_string();
+                    declare_class(classes, &name, kind);
+                    if kind == "trait" {
+                        if let Some(supers) = caps.get(4) {
+                            let supers: Vec<String> = supers
+                                .as_str()
+                                .split('+')
+                                .map(base_type_name)
+                                .filter(|s| !s.is_empty() && !s.starts_with('\''))
+                                .collect();
+                            class_entry(classes, &name).extends.extend(supers);
+                        }
+                    }
+                    if caps.get(5).map(|m| m.as_str()) == Some("{") {
+                        context = Some((name, kind_block(kind), depth));
+                    }
+                } else if let Some(caps) = impl_decl.captures(line) {
+                    let target = base_type_name(&caps[2]);
+                    if let Some(tr) = caps.get(1) {
+                        let tr = base_type_name(tr.as_str());
+                        let entry = class_entry(classes, &target);
+                        if !entry.implements.contains(&tr) {
+                            entry.implements.push(tr);
+                        }
+                    }
+                    if line.ends_with('{') {
+                        // методы реализации трейта публичны вместе с трейтом
+                        let block = if caps.get(1).is_some() {
+                            "trait_impl"
+                        } else {
+                            "impl"
+                        };
+                        context = Some((target, block, depth));
+                    }
+                }
+            }
+            Some((name, block, open_depth)) => {
+                // только члены первого уровня блока
+                if depth == open_depth + 1 {
+                    let entry = class_entry(classes, name);
+                    match *block {
+                        "struct" => {
+                            if let Some(caps) = field.captures(line) {
+                                let vis = if caps.get(1).is_some() { "+" } else { "-" };
+                                entry.fields.push(format!(
+                                    "{}{}: {}",
+                                    vis,
+                                    &caps[2],
+                                    caps[3].trim()
+                                ));
+                            }
+                        }
+                        "enum" => {
+                            if let Some(caps) = variant.captures(line) {
+                                entry.fields.push(caps[1].to_string());
+                            }
+                        }
+                        _ => {
+                            if let Some(caps) = method.captures(line) {
+                                let vis = if caps.get(1).is_some() || *block != "impl" {
+                                    "+"
+                                } else {
+                                    "-"
+                                };
+                                entry.methods.push(method_signature(
+                                    vis,
+                                    &caps[2],
+                                    &caps[3],
+                                    caps.get(4).map(|m| m.as_str()).unwrap_or(""),
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        depth += delta;
+        if let Some((_, _, open_depth)) = &context {
+            if depth <= *open_depth {
+                context = None;
+            }
+        }
+    }
+}
+
+fn kind_block(kind: &str) -> &'static str {
+    match kind {
+        "struct" => "struct",
+        "enum" => "enum",
+        _ => "trait",
+    }
+}
+
+/// TypeScript/JavaScript/Java: `class X extends Y implements Z {`, `interface I extends J {`
+fn parse_braced_classes(content: &str, classes: &mut Vec<ClassInfo>) {
+    let decl = regex::Regex::new(
+        r"^(?:export\s+)?(?:default\s+)?(?:public\s+|private\s+|protected\s+)?(?:abstract\s+|final\s+|static\s+)*(class|interface|enum)\s+([A-Za-z_$][\w$]*)(?:<[^{]*?>)?(?:\s+extends\s+([^{]+?))?(?:\s+implements\s+([^{]+?))?\s*(?:\{\s*\}?)?\s*$",
+    )
+    .unwrap();
+    let method = regex::Regex::new(
+        r"^((?:public|private|protected|static|async|abstract|final|readonly|override|synchronized|\s)*)(?:([\w<>\[\], ?]+?)\s+)?([A-Za-z_$][\w$]*)\s*(?:<[^(]*>)?\(([^)]*)\)\s*(?::\s*([^{;]+?))?\s*(?:throws [^{]+)?[{;]?\s*$",
+    )
+    .unwrap();
+    let field = regex::Regex::new(
+        r"^((?:public|private|protected|static|readonly|final|declare|\s)*)(?:([\w<>\[\], ?]+?)\s+)?([A-Za-z_$#][\w$]*)\??\s*(?::\s*([^=;]+?))?\s*(?:=[^;]*)?;\s*$",
+    )
+    .unwrap();
+    const KEYWORDS: &[&str] = &[
+        "if",
+        "for",
+        "while",
+        "switch",
+        "catch",
+        "return",
+        "new",
+        "constructor",
+        "super",
+        "else",
+        "throw",
+    ];
+
+    let mut context: Option<(String, i32)> = None;
+    let mut depth = 0;
+    for raw in content.lines() {
+        let line = raw.trim();
+        let delta = brace_delta(line);
+        if line.starts_with("//") || line.starts_with('*') || line.starts_with("/*") {
+            depth += delta;
+            continue;
+        }
+        match &context {
+            None => {
+                if let Some(caps) = decl.captures(line) {
+                    let (kind, name) = (&caps[1], caps[2].to_string());
+                    declare_class(classes, &name, kind);
+                    let entry = class_entry(classes, &name);
+                    if let Some(parents) = caps.get(3) {
+                        // у интерфейсов extends — тоже наследование интерфейсов
+                        entry.extends.extend(split_type_list(parents.as_str()));
+                    }
+                    if let Some(ifaces) = caps.get(4) {
+                        entry.implements.extend(split_type_list(ifaces.as_str()));
+                    }
+                    if line.ends_with('{') {
+                        context = Some((name, depth));
+                    }
+                }
+            }
+            Some((name, open_depth)) if depth == open_depth + 1 => {
+                let entry = class_entry(classes, name);
+                let visibility = |mods: &str| {
+                    if mods.contains("private") {
+                        "-"
+                    } else if mods.contains("protected") {
+                        "#"
+                    } else {
+                        "+"
+                    }
+                };
+                if let Some(caps) = method.captures(line) {
+                    let name = &caps[3];
+                    if !KEYWORDS.contains(&name) {
+                        // Java: тип перед именем, TS: после двоеточия
+                        let ret = caps
+                            .get(5)
+                            .or(caps.get(2))
+                            .map(|m| m.as_str())
+                            .unwrap_or("");
+                        entry.methods.push(method_signature(
+                            visibility(&caps[1]),
+                            name,
+                            &caps[4],
+                            ret,
+                        ));
+                    }
+                } else if let Some(caps) = field.captures(line) {
+                    let ty = caps.get(4).or(caps.get(2)).map(|m| m.as_str().trim());
+                    let name = caps[3].trim_start_matches('#');
+                    let vis = if caps[3].starts_with('#') {
+                        "-"
+                    } else {
+                        visibility(&caps[1])
+                    };
+                    entry.fields.push(match ty {
+                        Some(ty) => format!("{}{}: {}", vis, name, ty),
+                        None => format!("{}{}", vis, name),
+                    });
+                }
+            }
+            Some(_) => {}
+        }
+        depth += delta;
+        if let Some((_, open_depth)) = &context {
+            if depth <= *open_depth {
+                context = None;
+            }
+        }
+    }
+}
+
+fn parse_python_classes(content: &str, classes: &mut Vec<ClassInfo>) {
+    let decl = regex::Regex::new(r"^class\s+(\w+)\s*(?:\(([^)]*)\))?\s*:").unwrap();
+    let method =
+        regex::Regex::new(r"^(?:async\s+)?def\s+(\w+)\s*\(([^)]*)\)\s*(?:->\s*([^:]+))?:").unwrap();
+    let attribute = regex::Regex::new(r"^self\.(\w+)\s*(?::\s*([^=]+?))?\s*=").unwrap();
+    let class_field = regex::Regex::new(r"^(\w+)\s*:\s*([^=]+?)\s*(?:=.*)?$").unwrap();
+
+    // (класс, отступ объявления, отступ членов — по первой строке тела)
+    let mut context: Option<(String, usize, Option<usize>)> = None;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let indent = raw.len() - raw.trim_start().len();
+        if matches!(&context, Some((_, class_indent, _)) if indent <= *class_indent) {
+            context = None;
+        }
+
+        let Some((name, _, member_indent)) = &mut context else {
+            if let Some(caps) = decl.captures(line) {
+                let name = caps[1].to_string();
+                let bases = caps
+                    .get(2)
+                    .map(|b| split_type_list(b.as_str()))
+                    .unwrap_or_default();
+                let is_interface = bases.iter().any(|b| b == "ABC" || b == "Protocol");
+                declare_class(
+                    classes,
+                    &name,
+                    if is_interface { "interface" } else { "class" },
+                );
+                class_entry(classes, &name).extends.extend(
+                    bases.into_iter().filter(|b| !b.contains('=')).filter(|b| {
+                        !matches!(b.as_str(), "object" | "ABC" | "Protocol" | "Generic")
+                    }),
+                );
+                context = Some((name, indent, None));
+            }
+            continue;
+        };
+        let member_level = *member_indent.get_or_insert(indent) == indent;
+        let entry = class_entry(classes, name);
+
+        if let Some(caps) = attribute.captures(line) {
+            // self.x = ... в любом методе класса
+            let field = caps[1].to_string();
+            let known = entry
+                .fields
+                .iter()
+                .any(|f| f[1..].split(':').next() == Some(field.as_str()));
+            if !known {
+                let vis = if field.starts_with('_') { "-" } else { "+" };
+                entry.fields.push(match caps.get(2) {
+                    Some(ty) => format!("{}{}: {}", vis, field, ty.as_str().trim()),
+                    None => format!("{}{}", vis, field),
+                });
+            }
+        } else if !member_level {
+            continue;
+        } else if let Some(caps) = method.captures(line) {
+            let method_name = &caps[1];
+            if method_name != "__init__" {
+                let vis = if method_name.starts_with('_') && !method_name.starts_with("__") {
+                    "-"
+                } else {
+                    "+"
+                };
+                entry.methods.push(method_signature(
+                    vis,
+                    method_name,
+                    &caps[2],
+                    caps.get(3).map(|m| m.as_str()).unwrap_or(""),
+                ));
+            }
+        } else if let Some(caps) = class_field.captures(line) {
+            entry
+                .fields
+                .push(format!("+{}: {}", &caps[1], caps[2].trim()));
+        }
+    }
+}
+
+fn parse_go_classes(content: &str, classes: &mut Vec<ClassInfo>) {
+    let decl =
+        regex::Regex::new(r"^type\s+(\w+)(?:\[[^\]]*\])?\s+(struct|interface)\s*\{\s*$").unwrap();
+    let method = regex::Regex::new(
+        r"^func\s+\(\s*\w*\s*\*?(\w+)(?:\[[^\]]*\])?\s*\)\s*(\w+)\s*\(([^)]*)\)\s*([^{]*)\{?\s*$",
+    )
+    .unwrap();
+    let field = regex::Regex::new(r"^(\w+)\s+([^`/]+?)\s*(?:`.*`)?\s*(?://.*)?$").unwrap();
+    let embedded = regex::Regex::new(r"^\*?([\w.]+)\s*(?://.*)?$").unwrap();
+    let iface_method = regex::Regex::new(r"^(\w+)\s*\(([^)]*)\)\s*(.*)$").unwrap();
+
+    let mut context: Option<(String, String)> = None;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.starts_with("//") || line.is_empty() {
+            continue;
+        }
+        if let Some((name, kind)) = &context {
+            if line.starts_with('}') {
+                context = None;
+                continue;
+            }
+            let exported = |n: &str| {
+                if n.starts_with(|c: char| c.is_ascii_uppercase()) {
+                    "+"
+                } else {
+                    "-"
+                }
+            };
+            let entry = class_entry(classes, name);
+            if kind == "interface" {
+                if let Some(caps) = iface_method.captures(line) {
+                    entry.methods.push(method_signature(
+                        exported(&caps[1]),
+                        &caps[1],
+                        &caps[2],
+                        &caps[3],
+                    ));
+                } else if let Some(caps) = embedded.captures(line) {
+                    entry.extends.push(base_type_name(&caps[1]));
+                }
+            } else if let Some(caps) = field.captures(line) {
+                entry.fields.push(format!(
+                    "{}{}: {}",
+                    exported(&caps[1]),
+                    &caps[1],
+                    caps[2].trim()
+                ));
+            } else if let Some(caps) = embedded.captures(line) {
+                // встраивание структуры — ближайший аналог наследования
+                entry.extends.push(base_type_name(&caps[1]));
+            }
+            continue;
+        }
+        if let Some(caps) = decl.captures(line) {
+            declare_class(classes, &caps[1], &caps[2]);
+            context = Some((caps[1].to_string(), caps[2].to_string()));
+        } else if let Some(caps) = method.captures(line) {
+            let exported = if caps[2].starts_with(|c: char| c.is_ascii_uppercase()) {
+                "+"
+            } else {
+                "-"
+            };
+            class_entry(classes, &caps[1])
+                .methods
+                .push(method_signature(exported, &caps[2], &caps[3], &caps[4]));
+        }
+    }
+}
+
 // ============================================================================
 // DEPENDENCY ANALYSIS
 // ============================================================================
@@ -6,29 +6,90 @@ use std::path::Path;
 use crate::capsule_constructor::CapsuleConstructor;
 use crate::capsule_graph_builder::CapsuleGraphBuilder;
 use crate::exporter::Exporter;
+use crate::external_deps::ExternalDependencies;
 use crate::file_scanner::FileScanner;
+use crate::finding_caps::FindingCaps;
+use crate::overlays::OverlayInputs;
+use crate::panic_isolation::{isolate, AnalysisCoverage, PARSE_STAGE};
 use crate::parser_ast::ParserAST;
 use crate::validator_optimizer::ValidatorOptimizer;
 
 /// Generates an AI-readable compact analysis report
 /// Prefer full pipeline for high-quality compact output; fallback to lightweight scan if needed
 pub fn generate_ai_compact(project_path: &str) -> std::result::Result<String, String> {
+    generate_ai_compact_localized(
+        project_path,
+        None,
+        FindingCaps::default(),
+        false,
+        &OverlayInputs::default(),
+    )
+}
+
+/// AI Compact с находками на языке `lang` (каталоги сообщений `i18n`);
+/// `caps` ограничивает перечисляемые находки, тестовый код входит в метрики
+/// и рейтинги сложности/связности только с `include_tests`; `overlays` —
+/// отчёты покрытия и профиля CPU, накладываемые на граф
+pub fn generate_ai_compact_localized(
+    project_path: &str,
+    lang: Option<&str>,
+    caps: FindingCaps,
+    include_tests: bool,
+    overlays: &OverlayInputs,
+) -> std::result::Result<String, String> {
     if !Path::new(project_path).exists() {
         return Err("Path does not exist".to_string());
     }
+    let redactor = crate::redaction::Redactor::for_project(Path::new(project_path))
+        .map_err(|e| e.to_string())?;
 
     // Try full pipeline for maximum quality
-    match generate_ai_compact_from_graph(project_path) {
-        Ok(compact) => Ok(compact),
+    let mut compact = match generate_ai_compact_from_graph(
+        project_path,
+        lang,
+        redactor.as_ref(),
+        caps,
+        include_tests,
+        overlays,
+    ) {
+        Ok(compact) => compact,
         Err(err) => {
             eprintln!("⚠️ Full pipeline failed, using lightweight mode: {}", err);
             // Fallback to lightweight mode
-            generate_ai_compact_light(project_path)
+            generate_ai_compact_light(project_path)?
         }
+    };
+
+    // Свежесть зависимостей — часть архитектурного здоровья
+    if let Some(section) = dependency_freshness_section(project_path) {
+        compact.push('\n');
+        compact.push_str(&section);
     }
+    Ok(crate::cli::handlers::redact_output(
+        redactor.as_ref(),
+        compact,
+    ))
 }
 
-fn generate_ai_compact_from_graph(project_path: &str) -> std::result::Result<String, String> {
+fn dependency_freshness_section(project_path: &str) -> Option<String> {
+    use crate::dependency_freshness::DependencyFreshnessAnalyzer;
+    let report = DependencyFreshnessAnalyzer::new()
+        .analyze_project(Path::new(project_path))
+        .ok()?;
+    if report.total_dependencies == 0 {
+        return None;
+    }
+    Some(report.to_compact_section())
+}
+
+fn generate_ai_compact_from_graph(
+    project_path: &str,
+    lang: Option<&str>,
+    redactor: Option<&crate::redaction::Redactor>,
+    caps: FindingCaps,
+    include_tests: bool,
+    overlays: &OverlayInputs,
+) -> std::result::Result<String, String> {
     let scanner = FileScanner::new(
         vec![
             "**/*.rs".into(),
@@ -39,6 +100,8 @@ fn generate_ai_compact_from_graph(project_path: &str) -> std::result::Result<Str
             "**/*.go".into(),
             "**/*.cpp".into(),
             "**/*.c".into(),
+            "**/*.php".into(),
+            "**/*.rb".into(),
         ],
         vec![
             "**/target/**".into(),
@@ -56,15 +119,21 @@ fn generate_ai_compact_from_graph(project_path: &str) -> std::result::Result<Str
 
     let mut parser = ParserAST::new().map_err(|e| e.to_string())?;
     let mut all_nodes = Vec::new();
+    let mut coverage = AnalysisCoverage::default();
     for file in &files {
         if let Ok(content) = fs::read_to_string(&file.path) {
-            if let Ok(nodes) = parser.parse_file(&file.path, &content, &file.file_type) {
-                all_nodes.extend(nodes);
+            match isolate(|| parser.parse_file(&file.path, &content, &file.file_type)) {
+                Ok(Ok(nodes)) => {
+                    coverage.analyzed_files += 1;
+                    all_nodes.extend(nodes);
+                }
+                Ok(Err(_)) => {}
+                Err(message) => coverage.record_failure(file.path.clone(), PARSE_STAGE, message),
             }
         }
     }
 
-    let constructor = CapsuleConstructor::new();
+    let constructor = CapsuleConstructor::new().with_project_root(project_path);
     let mut capsules = Vec::new();
     for file in &files {
         // Привязываем узлы к файлам (простая эвристика по пути)
@@ -86,16 +155,45 @@ fn generate_ai_compact_from_graph(project_path: &str) -> std::result::Result<Str
     if capsules.is_empty() {
         return Err("No capsules created".to_string());
     }
+    crate::code_origin::classify_capsules(Path::new(project_path), &mut capsules);
+    if let Some(rules) = crate::constructor::TagRules::for_project(Path::new(project_path))
+        .map_err(|e| e.to_string())?
+    {
+        crate::constructor::CapsuleAnalyzer::apply_tag_rules(
+            Path::new(project_path),
+            &mut capsules,
+            &rules,
+        );
+    }
+    crate::api_surface::annotate_api_surface(&mut capsules);
+    crate::error_propagation::annotate_error_propagation(&mut capsules);
 
     let mut builder = CapsuleGraphBuilder::new();
     let mut graph = builder.build_graph(&capsules).map_err(|e| e.to_string())?;
+    crate::overlays::apply_configured(&mut graph, overlays).map_err(|e| e.to_string())?;
 
     let validator = ValidatorOptimizer::new();
     graph = validator
         .validate_and_optimize(&graph)
         .map_err(|e| e.to_string())?;
+    if !include_tests {
+        crate::code_origin::recalculate_production_metrics(&mut graph)
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(external) = ExternalDependencies::for_project(Path::new(project_path)) {
+        external.annotate(&mut graph);
+    }
+    crate::cli::handlers::apply_report_language(&mut graph, project_path, lang)?;
+    if let Some(redactor) = redactor {
+        redactor.redact_graph(&mut graph);
+    }
+    let overview = crate::cli::handlers::apply_llm_summaries(&mut graph, project_path, redactor);
 
-    let exporter = Exporter::new();
+    let exporter = Exporter::new()
+        .with_overview(overview)
+        .with_finding_caps(caps)
+        .with_test_code(include_tests)
+        .with_coverage(coverage);
     let compact = exporter
         .export_to_ai_compact(&graph)
         .map_err(|e| e.to_string())?;
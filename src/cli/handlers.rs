@@ -1,13 +1,26 @@
+use crate::graph_archive::{GraphArchive, ARCHIVE_FORMAT};
+use crate::graph_sampling::GraphSampler;
+use crate::hierarchy::{CapsuleHierarchy, HierarchyLevel};
+use crate::release_report::{ReleaseReportGenerator, ReleaseSide, RevisionCheckout};
 use crate::types::*;
 use std::path::Path;
 
 use super::parser;
 
 pub async fn handle_command(
-    command: parser::CliCommand,
+    mut command: parser::CliCommand,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
     use super::{diagram, export, stats};
 
+    record_audit(&command);
+    // клон живёт до конца команды: удаляется при выходе из функции или в exit_failure
+    let _remote = match checkout_remote(&mut command) {
+        Ok(remote) => remote,
+        Err(e) => {
+            eprintln!("❌ Ошибка клонирования репозитория: {}", e);
+            exit_failure();
+        }
+    };
     match command {
         parser::CliCommand::Help => {
             print_help();
@@ -18,8 +31,9 @@ pub async fn handle_command(
         parser::CliCommand::Analyze {
             project_path,
             verbose: _verbose,
-            include_tests: _include_tests,
+            include_tests,
             deep,
+            overlays,
         } => {
             eprintln!(
                 "🔍 Анализ проекта: {}{}",
@@ -28,10 +42,10 @@ pub async fn handle_command(
             );
             if !Path::new(&project_path).exists() {
                 eprintln!("❌ Путь не существует: {}", project_path);
-                std::process::exit(1);
+                exit_failure();
             }
             if deep {
-                match run_deep_pipeline(&project_path) {
+                match run_deep_pipeline_with(&project_path, include_tests, &overlays) {
                     Ok(json) => println!("{}", json),
                     Err(err) => {
                         eprintln!(
@@ -42,7 +56,7 @@ pub async fn handle_command(
                             Ok(s) => println!("{}", serde_json::to_string_pretty(&s)?),
                             Err(e) => {
                                 eprintln!("❌ Ошибка анализа: {}", e);
-                                std::process::exit(1);
+                                exit_failure();
                             }
                         }
                     }
@@ -55,7 +69,7 @@ pub async fn handle_command(
                     }
                     Err(err) => {
                         eprintln!("❌ Ошибка анализа: {}", err);
-                        std::process::exit(1);
+                        exit_failure();
                     }
                 }
             }
@@ -64,16 +78,58 @@ pub async fn handle_command(
             project_path,
             format,
             output,
-            options: _options,
+            options,
         } => {
+            if matches!(format, parser::ExportFormat::List) {
+                print!("{}", export_formats_list());
+                return Ok(());
+            }
             eprintln!(
                 "📤 Экспорт проекта: {} в формат: {:?}",
                 project_path, format
             );
+            let lang = options
+                .lang
+                .clone()
+                .or_else(|| std::env::var(crate::i18n::LANG_ENV).ok());
+            if let Some(lang) = lang.as_deref() {
+                if let Err(err) =
+                    crate::i18n::Localizer::for_language(lang, Some(Path::new(&project_path)))
+                {
+                    eprintln!("❌ Ошибка экспорта: {}", err);
+                    exit_failure();
+                }
+            }
+            if options.dry_run {
+                match run_export_estimate(&project_path, &format, &options) {
+                    Ok(estimate) => print!("{}", estimate.to_text()),
+                    Err(err) => {
+                        eprintln!("❌ Ошибка оценки экспорта: {}", err);
+                        exit_failure();
+                    }
+                }
+                return Ok(());
+            }
             match format {
                 parser::ExportFormat::AiCompact => {
-                    match export::generate_ai_compact(&project_path) {
+                    let caps = if options.all_findings {
+                        crate::finding_caps::FindingCaps::unlimited()
+                    } else {
+                        crate::finding_caps::FindingCaps::default()
+                    };
+                    match export::generate_ai_compact_localized(
+                        &project_path,
+                        lang.as_deref(),
+                        caps,
+                        options.include_tests,
+                        &options.overlays,
+                    ) {
                         Ok(content) => {
+                            let content = if options.plain_text {
+                                crate::plain_report::PlainTextRenderer::new().render(&content)
+                            } else {
+                                content
+                            };
                             if let Some(output_file) = output {
                                 std::fs::write(&output_file, &content)?;
                                 eprintln!("✅ AI Compact анализ сохранен в: {}", output_file);
@@ -83,16 +139,114 @@ pub async fn handle_command(
                         }
                         Err(err) => {
                             eprintln!("❌ Ошибка экспорта: {}", err);
-                            std::process::exit(1);
+                            exit_failure();
+                        }
+                    }
+                }
+                parser::ExportFormat::BacklogCsv => {
+                    match run_backlog_csv(&project_path, lang.as_deref()) {
+                        Ok(csv) => {
+                            if let Some(output_file) = output {
+                                std::fs::write(&output_file, &csv)?;
+                                eprintln!("✅ Бэклог исправлений сохранен в: {}", output_file);
+                            } else {
+                                print!("{}", csv);
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Ошибка экспорта: {}", err);
+                            exit_failure();
+                        }
+                    }
+                }
+                parser::ExportFormat::Html => {
+                    match run_html_report(&project_path, lang.as_deref(), options.sampling.as_ref())
+                    {
+                        Ok(html) => {
+                            if let Some(output_file) = output {
+                                std::fs::write(&output_file, &html)?;
+                                eprintln!("✅ HTML отчет сохранен в: {}", output_file);
+                            } else {
+                                println!("{}", html);
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Ошибка экспорта: {}", err);
+                            exit_failure();
+                        }
+                    }
+                }
+                parser::ExportFormat::Email => {
+                    match run_email_summary(&project_path, lang.as_deref()) {
+                        Ok(html) => {
+                            if let Some(output_file) = output {
+                                std::fs::write(&output_file, &html)?;
+                                eprintln!("✅ Email-сводка сохранена в: {}", output_file);
+                            } else {
+                                println!("{}", html);
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Ошибка экспорта: {}", err);
+                            exit_failure();
+                        }
+                    }
+                }
+                parser::ExportFormat::PromptPack => {
+                    let target = output.unwrap_or_else(|| "archlens-prompt-pack".to_string());
+                    match run_prompt_pack(
+                        &project_path,
+                        lang.as_deref(),
+                        &target,
+                        &options.overlays,
+                    ) {
+                        Ok(files) => {
+                            eprintln!(
+                                "✅ Пакет для AI-ревью ({} файлов) сохранен в: {}",
+                                files, target
+                            )
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Ошибка экспорта: {}", err);
+                            exit_failure();
+                        }
+                    }
+                }
+                parser::ExportFormat::Sqlite => {
+                    match run_sqlite_export(&project_path, output.as_deref()) {
+                        Ok(message) => eprintln!("✅ {}", message),
+                        Err(err) => {
+                            eprintln!("❌ Ошибка экспорта: {}", err);
+                            exit_failure();
+                        }
+                    }
+                }
+                parser::ExportFormat::Registry(name) => {
+                    // архив предназначен для передачи — пишется в файл и без `--output`
+                    let output = output.or_else(|| {
+                        (name == ARCHIVE_FORMAT).then(|| {
+                            crate::graph_archive::default_archive_name(Path::new(&project_path))
+                        })
+                    });
+                    match run_registry_export(&project_path, lang.as_deref(), &name) {
+                        Ok(content) => {
+                            if let Some(output_file) = output {
+                                std::fs::write(&output_file, &content)?;
+                                eprintln!("✅ Экспорт ({}) сохранен в: {}", name, output_file);
+                            } else {
+                                println!("{}", content);
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("❌ Ошибка экспорта: {}", err);
+                            exit_failure();
                         }
                     }
                 }
-                parser::ExportFormat::Json
-                | parser::ExportFormat::Markdown
-                | parser::ExportFormat::Html => {
+                parser::ExportFormat::Markdown | parser::ExportFormat::List => {
                     eprintln!("❌ Неподдерживаемый формат: {:?}", format);
-                    eprintln!("Доступные форматы: ai_compact");
-                    std::process::exit(1);
+                    eprintln!("Доступные форматы: archlens export <path> --format list");
+                    exit_failure();
                 }
             }
         }
@@ -104,7 +258,7 @@ pub async fn handle_command(
                 }
                 Err(err) => {
                     eprintln!("❌ Ошибка получения структуры: {}", err);
-                    std::process::exit(1);
+                    exit_failure();
                 }
             }
         }
@@ -113,6 +267,12 @@ pub async fn handle_command(
             diagram_type,
             output,
             include_metrics: _,
+            scope,
+            fresh_layout,
+            sampling,
+            level,
+            tags,
+            condensed,
         } => {
             eprintln!(
                 "📈 Генерация диаграммы: {} типа: {:?}",
@@ -122,11 +282,20 @@ pub async fn handle_command(
                 parser::DiagramType::Mermaid => "mermaid",
                 parser::DiagramType::Dot => "dot",
                 parser::DiagramType::Svg => "svg",
+                parser::DiagramType::Class => "class",
+                parser::DiagramType::Erd => "erd",
+                parser::DiagramType::ErdPlantUml => "erd-plantuml",
             };
             match diag_type {
                 "mermaid" => {
                     // Сначала попробуем построить граф и отдать мермайд на его основе
-                    match build_graph_mermaid(&project_path) {
+                    match build_graph_mermaid_sampled(
+                        &project_path,
+                        sampling.as_ref(),
+                        level,
+                        &tags,
+                        condensed,
+                    ) {
                         Ok(content) => {
                             if let Some(out) = output {
                                 std::fs::write(&out, &content)?;
@@ -148,143 +317,1786 @@ pub async fn handle_command(
                                 }
                                 Err(err) => {
                                     eprintln!("❌ Ошибка генерации диаграммы: {}", err);
-                                    std::process::exit(1);
+                                    exit_failure();
                                 }
                             }
                         }
                     }
                 }
+                "class" => match diagram::generate_class_diagram(&project_path, scope.as_deref()) {
+                    Ok(content) => {
+                        if let Some(out) = output {
+                            std::fs::write(&out, &content)?;
+                            eprintln!("✅ Mermaid classDiagram сохранена в: {}", out);
+                        } else {
+                            println!("{}", content);
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("❌ Ошибка генерации classDiagram: {}", err);
+                        exit_failure();
+                    }
+                },
+                "svg" | "dot" => match run_layout_diagram(
+                    &project_path,
+                    diag_type,
+                    fresh_layout,
+                    sampling.as_ref(),
+                    level,
+                    &tags,
+                    condensed,
+                ) {
+                    Ok(content) => {
+                        if let Some(out) = output {
+                            std::fs::write(&out, &content)?;
+                            eprintln!("✅ Диаграмма ({}) сохранена в: {}", diag_type, out);
+                        } else {
+                            println!("{}", content);
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("❌ Ошибка генерации диаграммы: {}", err);
+                        exit_failure();
+                    }
+                },
+                "erd" | "erd-plantuml" => {
+                    let root = match &scope {
+                        Some(scope) => std::path::Path::new(&project_path).join(scope),
+                        None => std::path::PathBuf::from(&project_path),
+                    };
+                    let model = match crate::data_models::DataModelDetector::new(&root).detect() {
+                        Ok(model) => model,
+                        Err(err) => {
+                            eprintln!("❌ Ошибка поиска моделей данных: {}", err);
+                            exit_failure();
+                        }
+                    };
+                    if model.is_empty() {
+                        eprintln!("⚠️ Модели данных (ORM, SQL-схемы) не обнаружены");
+                    } else {
+                        eprintln!(
+                            "🗃️ Сущностей: {}, связей: {}",
+                            model.entities.len(),
+                            model.relations.len()
+                        );
+                    }
+                    let content = if diag_type == "erd" {
+                        model.to_mermaid()
+                    } else {
+                        model.to_plantuml()
+                    };
+                    if let Some(out) = output {
+                        std::fs::write(&out, &content)?;
+                        eprintln!("✅ ER-диаграмма сохранена в: {}", out);
+                    } else {
+                        println!("{}", content);
+                    }
+                }
                 _ => {
                     eprintln!("❌ Неподдерживаемый тип диаграммы: {}", diag_type);
-                    eprintln!("Доступные типы: mermaid");
-                    std::process::exit(1);
+                    eprintln!("Доступные типы: mermaid, class, erd, erd-plantuml, svg, dot");
+                    exit_failure();
                 }
             }
         }
+        parser::CliCommand::Overlay {
+            project_path,
+            kind,
+            input,
+            output,
+        } => {
+            eprintln!("🧩 Оверлей {:?} для проекта: {}", kind, project_path);
+            match run_overlay(&project_path, &kind, input.as_deref()) {
+                Ok(json) => {
+                    if let Some(out) = output {
+                        std::fs::write(&out, &json)?;
+                        eprintln!("✅ Отчёт оверлея сохранен в: {}", out);
+                    } else {
+                        println!("{}", json);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка оверлея: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Release {
+            project_path,
+            from,
+            to,
+            output,
+        } => {
+            eprintln!(
+                "🏷️ Архитектурные изменения {}..{} в: {}",
+                from, to, project_path
+            );
+            match run_release_report(&project_path, &from, &to) {
+                Ok(markdown) => {
+                    if let Some(out) = output {
+                        std::fs::write(&out, &markdown)?;
+                        eprintln!("✅ Отчёт о релизе сохранен в: {}", out);
+                    } else {
+                        println!("{}", markdown);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка отчёта о релизе: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Diff {
+            project_path,
+            from,
+            to,
+            base,
+            output,
+        } => {
+            match &base {
+                Some(base) => eprintln!(
+                    "🔀 Трёхсторонний diff (base {}, main {}, ветка {}) в: {}",
+                    base, from, to, project_path
+                ),
+                None => eprintln!("🔀 Diff архитектуры {}..{} в: {}", from, to, project_path),
+            }
+            match run_diff(&project_path, &from, &to, base.as_deref()) {
+                Ok(markdown) => {
+                    if let Some(out) = output {
+                        std::fs::write(&out, &markdown)?;
+                        eprintln!("✅ Diff сохранен в: {}", out);
+                    } else {
+                        println!("{}", markdown);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка diff: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::MergeRisk {
+            project_path,
+            target,
+            branches,
+            json,
+            output,
+        } => {
+            eprintln!("⚔️ Риск конфликтов слияния в: {}", project_path);
+            match run_merge_risk(&project_path, target.as_deref(), &branches, json) {
+                Ok(report) => {
+                    if let Some(out) = output {
+                        std::fs::write(&out, &report)?;
+                        eprintln!("✅ Отчёт о рисках слияния сохранен в: {}", out);
+                    } else {
+                        println!("{}", report);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка прогноза конфликтов: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::ModuleDocs {
+            project_path,
+            stdout,
+        } => {
+            eprintln!("📚 Генерация MODULE.md для: {}", project_path);
+            match run_module_docs(&project_path, stdout) {
+                Ok(output) => println!("{}", output),
+                Err(err) => {
+                    eprintln!("❌ Ошибка генерации MODULE.md: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Conformance {
+            project_path,
+            template,
+            json,
+        } => {
+            eprintln!("📐 Соответствие шаблону: {}", project_path);
+            match run_conformance(&project_path, template.as_deref(), json) {
+                Ok((report, conformant)) => {
+                    print!("{}", report);
+                    if !conformant {
+                        exit_failure();
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка проверки шаблона: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Resemblance { project_path, json } => {
+            eprintln!("🧭 Сравнение с эталонными архитектурами: {}", project_path);
+            match run_resemblance(&project_path, json) {
+                Ok(report) => print!("{}", report),
+                Err(err) => {
+                    eprintln!("❌ Ошибка сравнения с эталонами: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::SelfCheck { project_path, json } => {
+            match run_self_check(project_path.as_deref(), json) {
+                Ok((report, passed)) => {
+                    print!("{}", report);
+                    if !passed {
+                        exit_failure();
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка самопроверки: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Audit {
+            project,
+            command,
+            user,
+            limit,
+            json,
+            verify,
+        } => {
+            let filter = crate::audit::AuditFilter {
+                project,
+                command,
+                user,
+                limit: Some(limit),
+            };
+            match run_audit(&filter, json, verify) {
+                Ok((report, intact)) => {
+                    print!("{}", report);
+                    if !intact {
+                        exit_failure();
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка чтения журнала аудита: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Erosion {
+            project_path,
+            days,
+            json,
+            output,
+        } => {
+            eprintln!("📉 Прогноз частоты изменений: {}", project_path);
+            match run_erosion(&project_path, days, json) {
+                Ok(report) => {
+                    if let Some(out) = output {
+                        std::fs::write(&out, &report)?;
+                        eprintln!("✅ Отчёт об эрозии сохранен в: {}", out);
+                    } else {
+                        println!("{}", report);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("❌ Ошибка прогноза изменений: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::EntryPoints { project_path, json } => {
+            eprintln!("🚪 Анализ точек входа: {}", project_path);
+            match run_entry_points(&project_path, json) {
+                Ok(report) => print!("{}", report),
+                Err(err) => {
+                    eprintln!("❌ Ошибка анализа точек входа: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Hotspots {
+            project_path,
+            top,
+            cognitive,
+            json,
+        } => {
+            eprintln!("🔥 Горячие точки: {}", project_path);
+            match run_hotspots(&project_path, top, cognitive, json) {
+                Ok(report) => print!("{}", report),
+                Err(err) => {
+                    eprintln!("❌ Ошибка анализа горячих точек: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Todos {
+            project_path,
+            layer,
+            json,
+        } => {
+            eprintln!("📝 TODO/FIXME: {}", project_path);
+            match run_todos(&project_path, layer.as_deref(), json) {
+                Ok(report) => print!("{}", report),
+                Err(err) => {
+                    eprintln!("❌ Ошибка сбора TODO/FIXME: {}", err);
+                    exit_failure();
+                }
+            }
+        }
+        parser::CliCommand::Watch {
+            project_path,
+            debounce_ms,
+            json,
+        } => {
+            eprintln!(
+                "👀 Наблюдение за проектом: {} (Ctrl+C — выход)",
+                project_path
+            );
+            if let Err(err) = run_watch(&project_path, debounce_ms, json) {
+                eprintln!("❌ Ошибка наблюдения: {}", err);
+                exit_failure();
+            }
+        }
+        parser::CliCommand::Init {
+            project_path,
+            yes,
+            force,
+        } => match run_init(&project_path, yes, force) {
+            Ok(message) => println!("{}", message),
+            Err(err) => {
+                eprintln!("❌ Ошибка настройки: {}", err);
+                exit_failure();
+            }
+        },
+        parser::CliCommand::View {
+            file,
+            format,
+            output,
+        } => match run_view(&file, format.as_deref()) {
+            Ok(content) => match output {
+                Some(output_file) => {
+                    std::fs::write(&output_file, &content)?;
+                    eprintln!("✅ Отчёт по архиву сохранен в: {}", output_file);
+                }
+                None => println!("{}", content),
+            },
+            Err(err) => {
+                eprintln!("❌ Ошибка открытия архива: {}", err);
+                exit_failure();
+            }
+        },
+        parser::CliCommand::Ask {
+            project_path,
+            question,
+            json,
+        } => match run_ask(&project_path, &question, json) {
+            Ok(answer) => print!("{}", answer),
+            Err(err) => {
+                eprintln!("❌ Ошибка ответа на вопрос: {}", err);
+                exit_failure();
+            }
+        },
+        parser::CliCommand::Impact {
+            project_path,
+            target,
+            depth,
+            json,
+        } => match run_impact(&project_path, &target, depth, json) {
+            Ok(report) => print!("{}", report),
+            Err(err) => {
+                eprintln!("❌ Ошибка анализа влияния: {}", err);
+                exit_failure();
+            }
+        },
+        parser::CliCommand::DependencyPath {
+            project_path,
+            from,
+            to,
+            max_paths,
+            json,
+        } => match run_dependency_path(&project_path, &from, &to, max_paths, json) {
+            Ok(report) => print!("{}", report),
+            Err(err) => {
+                eprintln!("❌ Ошибка поиска пути: {}", err);
+                exit_failure();
+            }
+        },
     }
     Ok(())
 }
 
-pub fn build_graph_mermaid(project_path: &str) -> std::result::Result<String, String> {
-    use crate::capsule_constructor::CapsuleConstructor;
-    use crate::capsule_graph_builder::CapsuleGraphBuilder;
+/// Накладывает внешний отчёт на граф проекта и возвращает JSON-отчёт
+pub fn run_overlay(
+    project_path: &str,
+    kind: &parser::OverlayKind,
+    input: Option<&str>,
+) -> std::result::Result<String, String> {
+    use crate::overlays::{
+        rust_crate_name, BinarySizeAnalyzer, BuildTimingsAnalyzer, CoverageOverlay,
+        FlakyBoundaryDetector, ProfileOverlay, TraceOverlay,
+    };
+
+    let mut graph = build_project_graph(project_path)?;
+    match kind {
+        parser::OverlayKind::Bloat => {
+            let analyzer = BinarySizeAnalyzer::new();
+            let raw = match input {
+                Some(file) => std::fs::read_to_string(file).map_err(|e| e.to_string())?,
+                None => analyzer
+                    .run_cargo_bloat(Path::new(project_path))
+                    .map_err(|e| e.to_string())?,
+            };
+            let report = analyzer.parse_report(&raw).map_err(|e| e.to_string())?;
+            let crate_name = rust_crate_name(Path::new(project_path))
+                .ok_or_else(|| "Не найден [package] name в Cargo.toml".to_string())?;
+            let result = analyzer.attribute(&mut graph, &report, &crate_name);
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+        parser::OverlayKind::Timings => {
+            let analyzer = BuildTimingsAnalyzer::new();
+            // По умолчанию — отчёт последнего `cargo build --timings`
+            let file = input.map(|f| f.to_string()).unwrap_or_else(|| {
+                Path::new(project_path)
+                    .join("target/cargo-timings/cargo-timing.html")
+                    .to_string_lossy()
+                    .to_string()
+            });
+            let raw = std::fs::read_to_string(&file).map_err(|e| format!("{}: {}", file, e))?;
+            let timings = analyzer.parse(&raw).map_err(|e| e.to_string())?;
+            let result = analyzer.attribute(&mut graph, &timings, Path::new(project_path));
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+        parser::OverlayKind::Tests => {
+            let detector = FlakyBoundaryDetector::new();
+            let input = input.ok_or_else(|| "Укажите --input <report.xml|dir>".to_string())?;
+            // Каталог — история прогонов: каждый *.xml считается отдельным прогоном
+            let mut files: Vec<std::path::PathBuf> = if Path::new(input).is_dir() {
+                std::fs::read_dir(input)
+                    .map_err(|e| e.to_string())?
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().map(|x| x == "xml").unwrap_or(false))
+                    .collect()
+            } else {
+                vec![input.into()]
+            };
+            files.sort();
+            let mut runs = Vec::new();
+            for file in &files {
+                let xml = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+                runs.push(detector.parse_junit(&xml).map_err(|e| e.to_string())?);
+            }
+            let result = detector.analyze(&mut graph, &runs);
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+        parser::OverlayKind::Traces => {
+            let overlay = TraceOverlay::new();
+            let input = input.ok_or_else(|| "Укажите --input <traces.json>".to_string())?;
+            let raw = std::fs::read_to_string(input).map_err(|e| e.to_string())?;
+            let spans = overlay.parse_otlp_json(&raw).map_err(|e| e.to_string())?;
+            let result = overlay.apply(&mut graph, &spans);
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+        parser::OverlayKind::Coverage => {
+            let overlay = CoverageOverlay::new();
+            let input =
+                input.ok_or_else(|| "Укажите --input <lcov.info|coverage.xml>".to_string())?;
+            let raw = std::fs::read_to_string(input).map_err(|e| e.to_string())?;
+            let files = overlay.parse(&raw).map_err(|e| e.to_string())?;
+            let result = overlay.apply(&mut graph, &files);
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+        parser::OverlayKind::Profile => {
+            let overlay = ProfileOverlay::new();
+            let input = input
+                .ok_or_else(|| "Укажите --input <perf.txt|stacks.folded|cpu.pb.gz>".to_string())?;
+            // pprof — бинарный (обычно gzip), поэтому читаем байты
+            let raw = std::fs::read(input).map_err(|e| e.to_string())?;
+            let samples = overlay.parse(&raw).map_err(|e| e.to_string())?;
+            let result = overlay.apply(&mut graph, &samples);
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Приоритизированный бэклог исправлений (CSV) по предупреждениям валидаторов
+pub fn run_backlog_csv(
+    project_path: &str,
+    lang: Option<&str>,
+) -> std::result::Result<String, String> {
+    use crate::remediation_backlog::RemediationPlanner;
+
+    let mut graph = build_project_graph(project_path)?;
+    apply_report_language(&mut graph, project_path, lang)?;
+    let redactor = apply_redaction(&mut graph, project_path)?;
+    let planner = RemediationPlanner::new().with_root(Path::new(project_path));
+    let items = planner.plan(&graph);
+    Ok(redact_output(redactor.as_ref(), planner.to_csv(&items)))
+}
+
+/// SVG/DOT со стабильной раскладкой: позиции узлов берутся из `.archlens/layouts`
+pub fn run_layout_diagram(
+    project_path: &str,
+    kind: &str,
+    fresh_layout: bool,
+    sampling: Option<&GraphSampler>,
+    level: Option<HierarchyLevel>,
+    tags: &[String],
+    condensed: bool,
+) -> std::result::Result<String, String> {
+    use crate::diagram_layout::{LayoutCache, LayoutPlanner};
     use crate::exporter::Exporter;
-    use crate::file_scanner::FileScanner;
-    use crate::parser_ast::ParserAST;
-    use crate::validator_optimizer::ValidatorOptimizer;
 
-    let scanner = FileScanner::new(
-        vec![
-            "**/*.rs".into(),
-            "**/*.ts".into(),
-            "**/*.js".into(),
-            "**/*.py".into(),
-            "**/*.java".into(),
-            "**/*.go".into(),
-            "**/*.cpp".into(),
-            "**/*.c".into(),
-        ],
-        vec![
-            "**/target/**".into(),
-            "**/node_modules/**".into(),
-            "**/.git/**".into(),
-            "**/dist/**".into(),
-            "**/build/**".into(),
-        ],
-        Some(6),
-    )
-    .map_err(|e| e.to_string())?;
-    let files = scanner
-        .scan_files(Path::new(project_path))
+    let graph = apply_level(apply_tags(build_project_graph(project_path)?, tags), level);
+    let graph = apply_condensed(graph, condensed);
+    let (graph, _) = apply_sampling(graph, sampling);
+    let root = Path::new(project_path);
+    let planner = LayoutPlanner::new().with_root(root);
+    let cache = LayoutCache::for_project(root);
+    let layout = if fresh_layout {
+        let layout = planner.compute(&graph, None);
+        cache.store(&layout).map_err(|e| e.to_string())?;
+        layout
+    } else {
+        cache
+            .layout_for(&planner, &graph)
+            .map_err(|e| e.to_string())?
+    };
+    let exporter = Exporter::new();
+    let content = if kind == "dot" {
+        exporter.export_to_dot_with_layout(&graph, &planner, &layout)
+    } else {
+        exporter.export_to_svg_with_layout(&graph, &planner, &layout)
+    };
+    content.map_err(|e| e.to_string())
+}
+
+/// HTML-отчет по капсулам (с диаграммами найденных автоматов состояний)
+pub fn run_html_report(
+    project_path: &str,
+    lang: Option<&str>,
+    sampling: Option<&GraphSampler>,
+) -> std::result::Result<String, String> {
+    let (mut graph, _) = apply_sampling(build_project_graph(project_path)?, sampling);
+    apply_report_language(&mut graph, project_path, lang)?;
+    let redactor = apply_redaction(&mut graph, project_path)?;
+    let overview = apply_llm_summaries(&mut graph, project_path, redactor.as_ref());
+    crate::exporter::Exporter::new()
+        .with_overview(overview)
+        .export_to_interactive_html(&graph)
+        .map(|content| redact_output(redactor.as_ref(), content))
+        .map_err(|e| e.to_string())
+}
+
+/// HTML-сводка для еженедельной email-рассылки
+pub fn run_email_summary(
+    project_path: &str,
+    lang: Option<&str>,
+) -> std::result::Result<String, String> {
+    let mut graph = build_project_graph(project_path)?;
+    apply_report_language(&mut graph, project_path, lang)?;
+    let redactor = apply_redaction(&mut graph, project_path)?;
+    let overview = apply_llm_summaries(&mut graph, project_path, redactor.as_ref());
+    crate::exporter::Exporter::new()
+        .with_overview(overview)
+        .export_to_email_summary(&graph)
+        .map(|content| redact_output(redactor.as_ref(), content))
+        .map_err(|e| e.to_string())
+}
+
+/// Экспорт графа в формат из `ExporterRegistry` (json, mermaid, graphml, ...)
+pub fn run_registry_export(
+    project_path: &str,
+    lang: Option<&str>,
+    format: &str,
+) -> std::result::Result<String, String> {
+    let (mut graph, coverage) = build_project_graph_covered(project_path, true)?;
+    apply_report_language(&mut graph, project_path, lang)?;
+    let redactor = apply_redaction(&mut graph, project_path)?;
+    let registry = crate::exporter::Exporter::new()
+        .with_coverage(coverage)
+        .registry();
+    let content = if registry.get(format).map(|e| e.name()) == Some(ARCHIVE_FORMAT) {
+        // пути капсул — относительно корня проекта, а не общего каталога файлов
+        GraphArchive::new(graph, Path::new(project_path)).to_json()
+    } else {
+        registry.export(format, &graph)
+    };
+    content
+        .map(|content| redact_output(redactor.as_ref(), content))
+        .map_err(|e| e.to_string())
+}
+
+/// Отчёт по архиву графа `.archlens.graph` без исходников: формат реестра
+/// экспортёров, по умолчанию компактная сводка для терминала
+pub fn run_view(file: &str, format: Option<&str>) -> std::result::Result<String, String> {
+    let archive = GraphArchive::load(Path::new(file)).map_err(|e| e.to_string())?;
+    eprintln!("📦 {}", archive.describe());
+    crate::exporter::Exporter::new()
+        .registry()
+        .export(format.unwrap_or("ai-compact"), archive.graph())
+        .map_err(|e| e.to_string())
+}
+
+/// Перечень форматов `archlens export`: отчёты CLI и форматы реестра экспортёров
+pub fn export_formats_list() -> String {
+    let mut out = String::from("Форматы отчётов:\n");
+    for (name, description) in [
+        ("ai_compact", "компактный анализ для AI (plain|markdown)"),
+        ("backlog", "бэклог исправлений в CSV"),
+        ("html", "интерактивный HTML-отчет"),
+        ("email", "HTML-сводка для рассылки"),
+        (
+            "prompt-pack",
+            "пакет для внешнего AI-ревью (папка или .zip)",
+        ),
+        (
+            "sqlite",
+            "граф в SQLite (.archlens/graph.db) для MCP и diff",
+        ),
+    ] {
+        out.push_str(&format!("  {:<20} {}\n", name, description));
+    }
+    out.push_str("Форматы графа:\n");
+    for exporter in crate::exporter::Exporter::new().registry().formats() {
+        out.push_str(&format!(
+            "  {:<20} {}\n",
+            exporter.name(),
+            exporter.description()
+        ));
+    }
+    out
+}
+
+/// Граф рабочей копии в базу SQLite (по умолчанию `.archlens/graph.db` проекта)
+/// с отпечатком дерева: пока файлы не изменились, MCP читает граф из базы
+pub fn run_sqlite_export(
+    project_path: &str,
+    output: Option<&str>,
+) -> std::result::Result<String, String> {
+    use crate::graph_db::{GraphDatabase, WORKTREE_SNAPSHOT};
+
+    let root = Path::new(project_path);
+    let graph = build_project_graph(project_path)?;
+    let path = output
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| crate::graph_db::default_path(root));
+    let mut database = GraphDatabase::open(&path).map_err(|e| e.to_string())?;
+    let fingerprint = crate::graph_db::snapshot_fingerprint(root, WORKTREE_SNAPSHOT);
+    database
+        .save(WORKTREE_SNAPSHOT, &graph, root, Some(&fingerprint))
         .map_err(|e| e.to_string())?;
+    Ok(format!(
+        "Граф ({} капсул, {} связей) сохранен в: {}",
+        graph.capsules.len(),
+        graph.relations.len(),
+        path.display()
+    ))
+}
 
-    let mut parser = ParserAST::new().map_err(|e| e.to_string())?;
-    let constructor = CapsuleConstructor::new();
-    let mut capsules: Vec<Capsule> = Vec::new();
+/// Пакет для внешнего AI-ревью; `output` с расширением `.zip` — архив, иначе папка.
+/// Возвращает число файлов пакета
+pub fn run_prompt_pack(
+    project_path: &str,
+    lang: Option<&str>,
+    output: &str,
+    overlays: &crate::overlays::OverlayInputs,
+) -> std::result::Result<usize, String> {
+    let (mut graph, _) = build_project_graph_overlaid(project_path, true, overlays)?;
+    apply_report_language(&mut graph, project_path, lang)?;
+    let redactor = apply_redaction(&mut graph, project_path)?;
+    let mut pack = crate::prompt_pack::PromptPackBuilder::new(Path::new(project_path))
+        .build(&graph)
+        .map_err(|e| e.to_string())?;
+    if let Some(redactor) = &redactor {
+        for content in pack.files.values_mut() {
+            *content = redactor.redact_text(content);
+        }
+    }
+    let target = Path::new(output);
+    if target
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        pack.write_zip(target).map_err(|e| e.to_string())?;
+    } else {
+        pack.write_dir(target).map_err(|e| e.to_string())?;
+    }
+    Ok(pack.files.len())
+}
 
-    for file in &files {
-        if let Ok(content) = std::fs::read_to_string(&file.path) {
-            if let Ok(nodes) = parser.parse_file(&file.path, &content, &file.file_type) {
-                let mut caps = constructor
-                    .create_capsules(&nodes, &file.path.clone())
+/// Оценка размера экспорта по уровням детализации: отчёт строится в памяти
+/// без LLM-сводок и ничего не записывается
+pub fn run_export_estimate(
+    project_path: &str,
+    format: &parser::ExportFormat,
+    options: &parser::ExportOptions,
+) -> std::result::Result<crate::export_estimate::ExportEstimate, String> {
+    use crate::exporter::Exporter;
+
+    let lang = options
+        .lang
+        .clone()
+        .or_else(|| std::env::var(crate::i18n::LANG_ENV).ok());
+    let (mut graph, _) = build_project_graph_overlaid(project_path, true, &options.overlays)?;
+    apply_report_language(&mut graph, project_path, lang.as_deref())?;
+    if matches!(format, parser::ExportFormat::Html) {
+        graph = apply_sampling(graph, options.sampling.as_ref()).0;
+    }
+    let redactor = apply_redaction(&mut graph, project_path)?;
+    let (name, content) = match format {
+        parser::ExportFormat::AiCompact => (
+            "ai_compact",
+            Exporter::new()
+                .export_to_ai_compact(&graph)
+                .map_err(|e| e.to_string())?,
+        ),
+        parser::ExportFormat::Html => (
+            "html",
+            Exporter::new()
+                .export_to_interactive_html(&graph)
+                .map_err(|e| e.to_string())?,
+        ),
+        parser::ExportFormat::Email => (
+            "email",
+            Exporter::new()
+                .export_to_email_summary(&graph)
+                .map_err(|e| e.to_string())?,
+        ),
+        parser::ExportFormat::BacklogCsv => {
+            let planner = crate::remediation_backlog::RemediationPlanner::new()
+                .with_root(Path::new(project_path));
+            ("backlog", planner.to_csv(&planner.plan(&graph)))
+        }
+        parser::ExportFormat::PromptPack => {
+            let pack = crate::prompt_pack::PromptPackBuilder::new(Path::new(project_path))
+                .build(&graph)
+                .map_err(|e| e.to_string())?;
+            (
+                "prompt-pack",
+                pack.files.into_values().collect::<Vec<_>>().join("\n"),
+            )
+        }
+        parser::ExportFormat::Registry(name) => (
+            name.as_str(),
+            Exporter::new()
+                .registry()
+                .export(name, &graph)
+                .map_err(|e| e.to_string())?,
+        ),
+        parser::ExportFormat::Markdown
+        | parser::ExportFormat::List
+        | parser::ExportFormat::Sqlite => {
+            return Err(format!("Неподдерживаемый формат: {:?}", format));
+        }
+    };
+    let content = redact_output(redactor.as_ref(), content);
+    Ok(crate::export_estimate::ExportEstimate::new(
+        name, &content, &graph,
+    ))
+}
+
+/// Переводит находки графа на язык отчёта (каталоги `locales/` и `.archlens/locales`)
+pub fn apply_report_language(
+    graph: &mut CapsuleGraph,
+    project_path: &str,
+    lang: Option<&str>,
+) -> std::result::Result<(), String> {
+    use crate::i18n::Localizer;
+
+    if let Some(lang) = lang {
+        Localizer::for_language(lang, Some(Path::new(project_path)))
+            .map_err(|e| e.to_string())?
+            .localize_graph(graph);
+    }
+    Ok(())
+}
+
+/// Маскирует граф по правилам `.archlens/redaction.json` (или `ARCHLENS_REDACTION`).
+/// Возвращает правила, чтобы применить их и к готовому отчёту
+pub fn apply_redaction(
+    graph: &mut CapsuleGraph,
+    project_path: &str,
+) -> std::result::Result<Option<crate::redaction::Redactor>, String> {
+    let redactor = crate::redaction::Redactor::for_project(Path::new(project_path))
+        .map_err(|e| e.to_string())?;
+    if let Some(redactor) = &redactor {
+        eprintln!("🕶️ Применены правила редактирования данных");
+        redactor.redact_graph(graph);
+    }
+    Ok(redactor)
+}
+
+/// Текст отчёта после правил редактирования (без правил — без изменений)
+pub fn redact_output(redactor: Option<&crate::redaction::Redactor>, content: String) -> String {
+    match redactor {
+        Some(redactor) => redactor.redact_text(&content),
+        None => content,
+    }
+}
+
+/// LLM-сводки капсул и обзор архитектуры, если в `.archlens/llm.json` включён endpoint.
+/// Запросы проходят через правила редактирования; ошибки LLM не прерывают экспорт
+pub fn apply_llm_summaries(
+    graph: &mut CapsuleGraph,
+    project_path: &str,
+    redactor: Option<&crate::redaction::Redactor>,
+) -> Option<String> {
+    use crate::llm::{LlmConfig, LlmSummarizer};
+
+    let root = Path::new(project_path);
+    let config = match LlmConfig::load(root) {
+        Ok(config) => config?,
+        Err(err) => {
+            eprintln!("⚠️ Конфигурация LLM не прочитана: {}", err);
+            return None;
+        }
+    };
+    eprintln!("🤖 LLM-сводки: {} ({})", config.endpoint, config.model);
+    let report = LlmSummarizer::new(config)
+        .with_redactor(redactor.cloned())
+        .with_project_cache(root)
+        .summarize_graph(graph);
+    eprintln!(
+        "🤖 Сводок: {} новых, {} из кэша",
+        report.summarized, report.cached
+    );
+    for err in &report.errors {
+        eprintln!("⚠️ LLM: {}", err);
+    }
+    report.overview
+}
+
+/// Генерирует MODULE.md модулей верхнего уровня; с `stdout` — только печатает их
+pub fn run_module_docs(project_path: &str, stdout: bool) -> std::result::Result<String, String> {
+    use crate::module_docs::ModuleDocGenerator;
+
+    let graph = build_project_graph(project_path)?;
+    let generator = ModuleDocGenerator::new(Path::new(project_path));
+    if stdout {
+        return Ok(generator
+            .modules(&graph)
+            .iter()
+            .map(|doc| doc.to_markdown())
+            .collect::<Vec<_>>()
+            .join("\n"));
+    }
+    let written = generator.write_all(&graph).map_err(|e| e.to_string())?;
+    if written.is_empty() {
+        return Ok("MODULE.md files are up to date".to_string());
+    }
+    Ok(written
+        .iter()
+        .map(|p| format!("updated {}", p.display()))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Ответ на структурный вопрос о графе проекта (без LLM)
+pub fn run_ask(
+    project_path: &str,
+    question: &str,
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::graph_query::GraphIndex;
+
+    let graph = build_project_graph(project_path)?;
+    let answer = GraphIndex::new(&graph, Path::new(project_path)).ask(question);
+    if json {
+        return serde_json::to_string_pretty(&answer)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string());
+    }
+    Ok(answer.to_text())
+}
+
+/// Транзитивные зависимые файла или капсулы: что затронет изменение
+pub fn run_impact(
+    project_path: &str,
+    target: &str,
+    depth: Option<usize>,
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::graph::ImpactAnalyzer;
+
+    let graph = build_project_graph(project_path)?;
+    let mut analyzer = ImpactAnalyzer::new(&graph, Path::new(project_path));
+    if let Some(depth) = depth {
+        analyzer = analyzer.with_max_depth(depth);
+    }
+    let report = analyzer.analyze(target);
+    if json {
+        return serde_json::to_string_pretty(&report)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string());
+    }
+    Ok(report.to_text())
+}
+
+/// Кратчайшие пути зависимостей: почему `from` зависит от `to`
+pub fn run_dependency_path(
+    project_path: &str,
+    from: &str,
+    to: &str,
+    max_paths: Option<usize>,
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::graph::PathFinder;
+
+    let graph = build_project_graph(project_path)?;
+    let mut finder = PathFinder::new(&graph, Path::new(project_path));
+    if let Some(max_paths) = max_paths {
+        finder = finder.with_max_paths(max_paths);
+    }
+    let report = finder.find(from, to);
+    if json {
+        return serde_json::to_string_pretty(&report)
+            .map(|json| json + "\n")
+            .map_err(|e| e.to_string());
+    }
+    Ok(report.to_text())
+}
+
+/// Markdown-раздел release notes: архитектурные изменения между двумя ревизиями
+pub fn run_release_report(
+    project_path: &str,
+    from: &str,
+    to: &str,
+) -> std::result::Result<String, String> {
+    let generator = ReleaseReportGenerator::new(Path::new(project_path));
+    let commits = generator.commits(from, to).map_err(|e| e.to_string())?;
+    let sides = checkout_sides(&generator, Path::new(project_path), &[from, to])?;
+    let report = generator
+        .generate(&sides[0].1, &sides[1].1, commits)
+        .map_err(|e| e.to_string())?;
+    Ok(report.to_markdown())
+}
+
+/// Markdown diff архитектуры двух ревизий; с `base` — трёхсторонний:
+/// что уникально для ветки `to`, а что уже есть в `from` (main)
+pub fn run_diff(
+    project_path: &str,
+    from: &str,
+    to: &str,
+    base: Option<&str>,
+) -> std::result::Result<String, String> {
+    use crate::branch_diff::{diff_markdown, three_way_diff};
+    use crate::decision_links::{
+        conflicts_markdown, DecisionChecker, DecisionConfig, DecisionSide,
+    };
+    use crate::diff_analyzer::DiffAnalyzer;
+
+    let generator = ReleaseReportGenerator::new(Path::new(project_path));
+    match base {
+        Some(base) => {
+            let sides = checkout_sides(&generator, Path::new(project_path), &[base, from, to])?;
+            let diff =
+                three_way_diff(&sides[0].1, &sides[1].1, &sides[2].1).map_err(|e| e.to_string())?;
+            Ok(diff.to_markdown())
+        }
+        None => {
+            let sides = checkout_sides(&generator, Path::new(project_path), &[from, to])?;
+            let (previous, current) = (&sides[0].1, &sides[1].1);
+            let diff = DiffAnalyzer::new()
+                .with_roots(&previous.root, &current.root)
+                .analyze_diff(&current.graph, &previous.graph)
+                .map_err(|e| e.to_string())?;
+            let mut markdown = diff_markdown(from, to, &diff);
+            // решения из конфигурации рабочей копии
+            if let Some(config) =
+                DecisionConfig::load(Path::new(project_path)).map_err(|e| e.to_string())?
+            {
+                let conflicts = DecisionChecker::new(Path::new(project_path), config)
+                    .check(
+                        DecisionSide {
+                            graph: &previous.graph,
+                            root: &previous.root,
+                        },
+                        DecisionSide {
+                            graph: &current.graph,
+                            root: &current.root,
+                        },
+                    )
                     .map_err(|e| e.to_string())?;
-                capsules.append(&mut caps);
+                markdown.push_str(&conflicts_markdown(&conflicts));
             }
+            Ok(markdown)
         }
     }
-    if capsules.is_empty() {
-        return Err("No capsules".into());
+}
+
+/// Файлы и капсулы с высоким риском конфликта между активными ветками
+pub fn run_merge_risk(
+    project_path: &str,
+    target: Option<&str>,
+    branches: &[String],
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::merge_risk::MergeRiskAnalyzer;
+
+    let analyzer = MergeRiskAnalyzer::new(Path::new(project_path));
+    let target = target
+        .map(str::to_string)
+        .unwrap_or_else(|| analyzer.default_target());
+    let branches = if branches.is_empty() {
+        analyzer
+            .active_branches(&target)
+            .map_err(|e| e.to_string())?
+    } else {
+        branches.to_vec()
+    };
+    // Граф рабочей копии нужен только для сверки имён капсул
+    let graph = build_project_graph(project_path).ok();
+    let report = analyzer
+        .analyze(&target, &branches, graph.as_ref())
+        .map_err(|e| e.to_string())?;
+    if json {
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    } else {
+        Ok(report.to_markdown())
     }
-    let mut builder = CapsuleGraphBuilder::new();
-    let graph = builder.build_graph(&capsules).map_err(|e| e.to_string())?;
-    let validator = ValidatorOptimizer::new();
-    let graph = validator
-        .validate_and_optimize(&graph)
+}
+
+/// Завершение с кодом 1; `process::exit` не вызывает `drop`, поэтому
+/// клон удалённого репозитория удаляется явно
+fn exit_failure() -> ! {
+    crate::remote_repo::remove_checkouts();
+    std::process::exit(1)
+}
+
+/// Заменяет git URL в пути проекта на неглубокий клон во временном каталоге
+fn checkout_remote(
+    command: &mut parser::CliCommand,
+) -> Result<Option<crate::remote_repo::RemoteCheckout>> {
+    use crate::remote_repo::{is_git_url, RemoteCheckout};
+
+    let Some(project_path) = command.snapshot_project_path_mut() else {
+        return Ok(None);
+    };
+    if !is_git_url(project_path) {
+        return Ok(None);
+    }
+    eprintln!("📥 Клонирование {}...", project_path);
+    let checkout = RemoteCheckout::clone(project_path)?;
+    *project_path = checkout.path().to_string_lossy().into_owned();
+    Ok(Some(checkout))
+}
+
+/// Запись запуска в журнал аудита; ошибка записи не прерывает команду
+fn record_audit(command: &parser::CliCommand) {
+    use crate::audit::{audit_enabled, AuditLog, AuditRecord};
+
+    let Some((name, project_path)) = command.audit_subject() else {
+        return;
+    };
+    if !audit_enabled() {
+        return;
+    }
+    let log = AuditLog::default_location();
+    let args = std::env::args().skip(1).collect();
+    if let Err(err) = log.append(AuditRecord::new(name, Path::new(project_path), args)) {
+        eprintln!(
+            "⚠️ Не удалось записать журнал аудита {}: {}",
+            log.path().display(),
+            err
+        );
+    }
+}
+
+/// Записи журнала аудита (текст или JSON); с `verify` — проверка цепочки хешей.
+/// Второй элемент — `false`, если проверка нашла нарушения
+pub fn run_audit(
+    filter: &crate::audit::AuditFilter,
+    json: bool,
+    verify: bool,
+) -> std::result::Result<(String, bool), String> {
+    use crate::audit::{entries_text, AuditLog};
+
+    let log = AuditLog::default_location();
+    if verify {
+        let problems = log.verify().map_err(|e| e.to_string())?;
+        let count = log.entries().map_err(|e| e.to_string())?.len();
+        if problems.is_empty() {
+            return Ok((
+                format!(
+                    "Audit log {} is intact ({} entries).\n",
+                    log.path().display(),
+                    count
+                ),
+                true,
+            ));
+        }
+        let mut report = format!("Audit log {} failed verification:\n", log.path().display());
+        for problem in problems {
+            report.push_str(&format!("- {}\n", problem));
+        }
+        return Ok((report, false));
+    }
+    let entries = log.query(filter).map_err(|e| e.to_string())?;
+    if json {
+        let json = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
+        return Ok((json + "\n", true));
+    }
+    Ok((entries_text(&entries), true))
+}
+
+/// Раннее предупреждение об эрозии по истории git за `days` дней
+pub fn run_erosion(
+    project_path: &str,
+    days: u32,
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::change_forecast::ChangeForecaster;
+
+    let graph = build_project_graph(project_path)?;
+    let report = ChangeForecaster::new(Path::new(project_path))
+        .with_window_days(days)
+        .analyze(&graph)
         .map_err(|e| e.to_string())?;
-    let exporter = Exporter::new();
-    exporter
-        .export_to_mermaid(&graph)
+    if json {
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    } else {
+        Ok(report.to_markdown())
+    }
+}
+
+/// Графы ревизий в их worktree, которые живут, пока жив результат. Если у
+/// проекта есть база графов (`.archlens/graph.db`), граф уже
+/// проанализированного коммита берётся из неё
+fn checkout_sides(
+    generator: &ReleaseReportGenerator,
+    project_root: &Path,
+    revs: &[&str],
+) -> std::result::Result<Vec<(RevisionCheckout, ReleaseSide)>, String> {
+    use crate::graph_db::{cached_graph, commit_snapshot};
+
+    let mut sides = Vec::new();
+    for rev in revs {
+        let checkout = generator.checkout(rev).map_err(|e| e.to_string())?;
+        let root = checkout.path().to_path_buf();
+        let analyze = || build_project_graph(&root.to_string_lossy());
+        let graph = match generator.resolve(rev) {
+            Ok(sha) => cached_graph(project_root, &commit_snapshot(&sha), &root, analyze)?,
+            Err(_) => analyze()?,
+        };
+        // worktree нужен и после построения графа: манифесты читаются из него
+        sides.push((
+            checkout,
+            ReleaseSide {
+                rev: rev.to_string(),
+                root,
+                graph,
+            },
+        ));
+    }
+    Ok(sides)
+}
+
+/// Сканер исходников проекта: шаблоны из `.archlens.toml` или поддерживаемые
+/// языки без каталогов сборки и зависимостей
+pub fn project_scanner(
+    project_root: &Path,
+) -> std::result::Result<crate::file_scanner::FileScanner, String> {
+    crate::project_config::ProjectConfig::for_project(project_root)
+        .and_then(|config| config.scanner(Some(10)))
         .map_err(|e| e.to_string())
 }
 
-pub fn run_deep_pipeline(project_path: &str) -> std::result::Result<String, String> {
+/// Мастер `archlens init`: осматривает проект, предлагает настройки и после
+/// подтверждения (или сразу с `yes`) пишет `.archlens.toml`
+pub fn run_init(project_path: &str, yes: bool, force: bool) -> std::result::Result<String, String> {
+    use crate::project_config::{confirm_interactively, ProjectConfig, CONFIG_FILE};
+
+    let root = Path::new(project_path);
+    if !root.is_dir() {
+        return Err(format!("Путь не существует: {}", project_path));
+    }
+    let path = root.join(CONFIG_FILE);
+    if path.exists() && !force {
+        return Err(format!(
+            "{} уже существует; --force перезапишет его",
+            path.display()
+        ));
+    }
+    let proposal = ProjectConfig::propose(root);
+    let config = if yes {
+        proposal
+    } else {
+        let stdin = std::io::stdin();
+        let mut stdout = std::io::stdout();
+        match confirm_interactively(proposal, &mut stdin.lock(), &mut stdout)
+            .map_err(|e| e.to_string())?
+        {
+            Some(config) => config,
+            None => return Ok("Отменено: файл не записан".to_string()),
+        }
+    };
+    let text = config.to_toml().map_err(|e| e.to_string())?;
+    std::fs::write(&path, text).map_err(|e| e.to_string())?;
+    Ok(format!("✅ Записан {}", path.display()))
+}
+
+/// Непрерывный анализ: полный проход, затем пересчёт по изменённым файлам
+pub fn run_watch(
+    project_path: &str,
+    debounce_ms: u64,
+    json: bool,
+) -> std::result::Result<(), String> {
+    use crate::file_scanner::watcher::{ChangeSet, IncrementalAnalysis, ProjectWatcher};
+
+    let mut watcher = ProjectWatcher::new(
+        Path::new(project_path),
+        project_scanner(Path::new(project_path))?,
+    )
+    .map_err(|e| e.to_string())?
+    .with_debounce(std::time::Duration::from_millis(debounce_ms));
+    let mut analysis = IncrementalAnalysis::new(watcher.root()).map_err(|e| e.to_string())?;
+    analysis.update(watcher.files());
+    println!(
+        "{}",
+        watch_update(&analysis, &ChangeSet::default(), watcher.root(), json)?
+    );
+
+    while let Some(changes) = watcher.next_changes(None).map_err(|e| e.to_string())? {
+        analysis.apply(&changes);
+        println!(
+            "{}",
+            watch_update(&analysis, &changes, watcher.root(), json)?
+        );
+    }
+    Ok(())
+}
+
+/// Сводка одного пересчёта `watch`: текст или строка JSON
+pub fn watch_update(
+    analysis: &crate::file_scanner::watcher::IncrementalAnalysis,
+    changes: &crate::file_scanner::watcher::ChangeSet,
+    root: &Path,
+    json: bool,
+) -> std::result::Result<String, String> {
+    let relative = |path: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    let changed: Vec<String> = changes.changed.iter().map(|f| relative(&f.path)).collect();
+    let removed: Vec<String> = changes.removed.iter().map(|p| relative(p)).collect();
+    let failed: Vec<String> = analysis
+        .coverage()
+        .failed
+        .iter()
+        .map(|f| relative(&f.path))
+        .collect();
+    let (capsules, relations, warnings) = if analysis.capsule_count() == 0 {
+        (0, 0, 0)
+    } else {
+        let graph = analysis.graph().map_err(|e| e.to_string())?;
+        let warnings = graph.capsules.values().map(|c| c.warnings.len()).sum();
+        (graph.capsules.len(), graph.relations.len(), warnings)
+    };
+    let time = chrono::Local::now().format("%H:%M:%S").to_string();
+    if json {
+        return serde_json::to_string(&serde_json::json!({
+            "time": time,
+            "changed": changed,
+            "removed": removed,
+            "capsules": capsules,
+            "relations": relations,
+            "warnings": warnings,
+            "failed_files": failed,
+        }))
+        .map_err(|e| e.to_string());
+    }
+    let mut line = format!(
+        "[{}] капсул: {}, связей: {}, предупреждений: {}",
+        time, capsules, relations, warnings
+    );
+    if !failed.is_empty() {
+        line.push_str(&format!(
+            ", пропущено из-за паники: {} ({})",
+            failed.len(),
+            failed.join(", ")
+        ));
+    }
+    if !changes.is_empty() {
+        let mut files = changed;
+        files.extend(removed.iter().map(|p| format!("-{}", p)));
+        line.push_str(&format!(
+            " ← изменено файлов: {} ({})",
+            changes.len(),
+            files.join(", ")
+        ));
+    }
+    Ok(line)
+}
+
+/// Полный пайплайн: сканирование → AST → капсулы → граф → валидация
+pub fn build_project_graph(project_path: &str) -> std::result::Result<CapsuleGraph, String> {
+    build_project_graph_with(project_path, true)
+}
+
+/// Граф проекта с разметкой тестового кода. Метрики графа считаются только по
+/// производственному коду; `include_tests = false` убирает тестовые капсулы из графа
+pub fn build_project_graph_with(
+    project_path: &str,
+    include_tests: bool,
+) -> std::result::Result<CapsuleGraph, String> {
+    build_project_graph_covered(project_path, include_tests).map(|(graph, _)| graph)
+}
+
+/// Граф проекта и покрытие анализа: файлы, разбор которых упал с паникой
+pub fn build_project_graph_covered(
+    project_path: &str,
+    include_tests: bool,
+) -> std::result::Result<(CapsuleGraph, crate::panic_isolation::AnalysisCoverage), String> {
+    build_project_graph_overlaid(
+        project_path,
+        include_tests,
+        &crate::overlays::OverlayInputs::default(),
+    )
+}
+
+/// Граф проекта с наложенными отчётами внешних инструментов (`--coverage`, `--profile`)
+pub fn build_project_graph_overlaid(
+    project_path: &str,
+    include_tests: bool,
+    overlays: &crate::overlays::OverlayInputs,
+) -> std::result::Result<(CapsuleGraph, crate::panic_isolation::AnalysisCoverage), String> {
+    build_project_graph_using(
+        project_path,
+        include_tests,
+        crate::capsule_graph_builder::CapsuleGraphBuilder::new(),
+        PROJECT_SCAN_DEPTH,
+        overlays,
+    )
+}
+
+/// Глубина обхода каталогов полного пайплайна
+pub const PROJECT_SCAN_DEPTH: usize = 10;
+/// Глубина обхода каталогов для Mermaid-диаграмм: более глубокие файлы
+/// перегружают диаграмму и в неё не попадают
+pub const MERMAID_SCAN_DEPTH: usize = 6;
+
+/// Полный пайплайн с заданным построителем графа, глубиной обхода каталогов
+/// и отчётами внешних инструментов
+fn build_project_graph_using(
+    project_path: &str,
+    include_tests: bool,
+    mut builder: crate::capsule_graph_builder::CapsuleGraphBuilder,
+    max_depth: usize,
+    overlays: &crate::overlays::OverlayInputs,
+) -> std::result::Result<(CapsuleGraph, crate::panic_isolation::AnalysisCoverage), String> {
     use crate::capsule_constructor::CapsuleConstructor;
-    use crate::capsule_graph_builder::CapsuleGraphBuilder;
-    use crate::file_scanner::FileScanner;
     use crate::parser_ast::ParserAST;
     use crate::validator_optimizer::ValidatorOptimizer;
 
-    let scanner = FileScanner::new(
-        vec![
-            "**/*.rs".into(),
-            "**/*.ts".into(),
-            "**/*.js".into(),
-            "**/*.py".into(),
-            "**/*.java".into(),
-            "**/*.go".into(),
-            "**/*.cpp".into(),
-            "**/*.c".into(),
-        ],
-        vec![
-            "**/target/**".into(),
-            "**/node_modules/**".into(),
-            "**/.git/**".into(),
-            "**/dist/**".into(),
-            "**/build/**".into(),
-        ],
-        Some(10),
-    )
-    .map_err(|e| e.to_string())?;
+    let config = crate::project_config::ProjectConfig::for_project(Path::new(project_path))
+        .map_err(|e| e.to_string())?;
+    let scanner = config.scanner(Some(max_depth)).map_err(|e| e.to_string())?;
     let files = scanner
         .scan_files(Path::new(project_path))
         .map_err(|e| e.to_string())?;
 
     let mut parser = ParserAST::new().map_err(|e| e.to_string())?;
-    let constructor = CapsuleConstructor::new();
+    let constructor = CapsuleConstructor::new().with_project_root(project_path);
     let mut capsules: Vec<Capsule> = Vec::new();
+    // упавшие файлы сообщаются в stderr и возвращаются вызывающему для отчётов
+    let mut coverage = crate::panic_isolation::AnalysisCoverage::default();
 
     for file in &files {
-        if let Ok(content) = std::fs::read_to_string(&file.path) {
-            if let Ok(nodes) = parser.parse_file(&file.path, &content, &file.file_type) {
-                let mut caps = constructor
-                    .create_capsules(&nodes, &file.path.clone())
-                    .map_err(|e| e.to_string())?;
-                capsules.append(&mut caps);
-            }
-        }
+        let mut caps = crate::panic_isolation::parse_file_isolated(
+            &mut parser,
+            &constructor,
+            file,
+            &mut coverage,
+        )
+        .map_err(|e| e.to_string())?;
+        capsules.append(&mut caps);
+    }
+    if capsules.is_empty() {
+        return Err("No capsules".into());
+    }
+    crate::code_origin::classify_capsules(Path::new(project_path), &mut capsules);
+    config.apply_layers(Path::new(project_path), &mut capsules);
+    if let Some(rules) = crate::constructor::TagRules::for_project(Path::new(project_path))
+        .map_err(|e| e.to_string())?
+    {
+        crate::constructor::CapsuleAnalyzer::apply_tag_rules(
+            Path::new(project_path),
+            &mut capsules,
+            &rules,
+        );
     }
+    let with_git = crate::metadata_extractor::git_metadata_enabled();
+    if with_git {
+        crate::metadata_extractor::MetadataExtractor::new()
+            .enrich_with_git_history(Path::new(project_path), &mut capsules);
+        crate::ownership::OwnershipAnalyzer::new(Path::new(project_path)).annotate(&mut capsules);
+    }
+    crate::todo_report::annotate_todos(Path::new(project_path), &mut capsules, with_git);
+    crate::api_surface::annotate_api_surface(&mut capsules);
+    crate::error_propagation::annotate_error_propagation(&mut capsules);
 
-    let mut builder = CapsuleGraphBuilder::new();
-    let graph = builder.build_graph(&capsules).map_err(|e| e.to_string())?;
-    let validator = ValidatorOptimizer::new();
-    let validated_graph = validator
+    let mut graph = builder.build_graph(&capsules).map_err(|e| e.to_string())?;
+    // измеренные покрытие и профиль — до проверок, оценок качества и метрик
+    crate::overlays::apply_configured(&mut graph, overlays).map_err(|e| e.to_string())?;
+    let validator = ValidatorOptimizer::with_thresholds(&config.thresholds);
+    let mut graph = validator
         .validate_and_optimize(&graph)
         .map_err(|e| e.to_string())?;
+    // недостижимые из объявленных точек входа капсулы получают предупреждения
+    if let Some(analyzer) =
+        crate::entry_points::EntryPointAnalyzer::for_project(Path::new(project_path))
+            .map_err(|e| e.to_string())?
+    {
+        analyzer.annotate(&mut graph);
+    }
+    if include_tests {
+        crate::code_origin::recalculate_production_metrics(&mut graph)
+    } else {
+        crate::code_origin::exclude_test_code(&mut graph).map(|_| ())
+    }
+    .map_err(|e| e.to_string())?;
+    // пакеты Java/C# и внешние пакеты добавляются после метрик: в метрики архитектуры они не входят
+    crate::packages::PackageAggregator::new().annotate(&mut graph);
+    if let Some(external) =
+        crate::external_deps::ExternalDependencies::for_project(Path::new(project_path))
+    {
+        external.annotate(&mut graph);
+    }
+    Ok((graph, coverage))
+}
+
+/// Горячие точки проекта: git-история капсул × сложность
+pub fn run_hotspots(
+    project_path: &str,
+    top: usize,
+    cognitive: bool,
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::advanced_metrics::hotspots::{hotspots_to_text, ComplexityBasis, HotspotAnalyzer};
+
+    let basis = if cognitive {
+        ComplexityBasis::Cognitive
+    } else {
+        ComplexityBasis::Cyclomatic
+    };
+    let hotspots = HotspotAnalyzer::new()
+        .with_basis(basis)
+        .with_limit(top)
+        .rank(&build_project_graph(project_path)?);
+    if json {
+        serde_json::to_string_pretty(&hotspots)
+            .map(|s| s + "\n")
+            .map_err(|e| e.to_string())
+    } else {
+        Ok(hotspots_to_text(&hotspots, basis))
+    }
+}
+
+/// Маркеры TODO/FIXME проекта по слоям; `layer` оставляет только один слой
+pub fn run_todos(
+    project_path: &str,
+    layer: Option<&str>,
+    json: bool,
+) -> std::result::Result<String, String> {
+    use crate::todo_report::{todos_to_text, TodoReport};
+
+    let mut report = TodoReport::from_graph(&build_project_graph(project_path)?);
+    if let (Some(layer), Some(r)) = (layer, report.as_mut()) {
+        r.items.retain(|item| item.layer == layer);
+        r.layers.retain(|l| l.layer == layer);
+    }
+    let report = report.filter(|r| !r.items.is_empty());
+    if json {
+        serde_json::to_string_pretty(&report.unwrap_or_default())
+            .map(|s| s + "\n")
+            .map_err(|e| e.to_string())
+    } else {
+        Ok(todos_to_text(report.as_ref()))
+    }
+}
+
+/// Отклонения каждого сервиса проекта от шаблона (`template` или
+/// `.archlens/template.json`); второй элемент — соответствуют ли все сервисы
+pub fn run_conformance(
+    project_path: &str,
+    template: Option<&str>,
+    json: bool,
+) -> std::result::Result<(String, bool), String> {
+    use crate::validation::{ProjectTemplate, TemplateValidator};
+
+    let root = Path::new(project_path);
+    let template = match template {
+        Some(file) => ProjectTemplate::from_file(Path::new(file)).map_err(|e| e.to_string())?,
+        None => ProjectTemplate::load(root)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| {
+                "шаблон не найден — создайте .archlens/template.json или укажите --template"
+                    .to_string()
+            })?,
+    };
+    let report =
+        TemplateValidator::new(template).validate(root, &build_project_graph(project_path)?);
+    let conformant = report.is_conformant();
+    let text = if json {
+        serde_json::to_string_pretty(&report)
+            .map(|s| s + "\n")
+            .map_err(|e| e.to_string())?
+    } else {
+        report.to_text()
+    };
+    Ok((text, conformant))
+}
+
+/// Сходство проекта со встроенными эталонными архитектурами и профилями из
+/// `.archlens/reference_architectures.json`; лучшие совпадения — первыми
+pub fn run_resemblance(project_path: &str, json: bool) -> std::result::Result<String, String> {
+    use crate::reference_architectures::ReferenceProfiles;
+
+    let profiles =
+        ReferenceProfiles::for_project(Path::new(project_path)).map_err(|e| e.to_string())?;
+    let report = profiles.compare(&build_project_graph(project_path)?);
+    if json {
+        serde_json::to_string_pretty(&report)
+            .map(|s| s + "\n")
+            .map_err(|e| e.to_string())
+    } else {
+        Ok(report.to_text())
+    }
+}
+
+/// Самопроверка: анализ исходников ArchLens (или `project_path`) и сверка с
+/// закреплёнными ожиданиями; второй элемент — пройдена ли проверка
+pub fn run_self_check(
+    project_path: Option<&str>,
+    json: bool,
+) -> std::result::Result<(String, bool), String> {
+    use crate::self_check::{own_source_dir, SelfCheckExpectations, SelfCheckReport};
+
+    let path = match project_path {
+        Some(path) => path.to_string(),
+        None => own_source_dir()
+            .ok_or_else(|| {
+                "исходники ArchLens не найдены — укажите путь: archlens self-check <path>"
+                    .to_string()
+            })?
+            .to_string_lossy()
+            .to_string(),
+    };
+    eprintln!("🐶 Самопроверка: {}", path);
+    // Структурные связи (директория, слой) намеренно не строятся: циклы они не
+    // образуют, а на крупном проекте связывают почти все пары капсул. Число
+    // связей поэтому меньше, чем в export, и сверяется с отдельным диапазоном
+    let (graph, _) = build_project_graph_using(
+        &path,
+        true,
+        crate::capsule_graph_builder::CapsuleGraphBuilder::new().without_structural_relations(),
+        PROJECT_SCAN_DEPTH,
+        &crate::overlays::OverlayInputs::default(),
+    )?;
+    let report = SelfCheckReport::evaluate(&graph, &SelfCheckExpectations::pinned());
+    let passed = report.passed();
+    let text = if json {
+        serde_json::to_string_pretty(&report)
+            .map(|s| s + "\n")
+            .map_err(|e| e.to_string())?
+    } else {
+        report.to_text()
+    };
+    Ok((text, passed))
+}
+
+/// Точки входа из `.archlens/entry_points.json` (и функции `main`): охват каждой
+/// и капсулы, недостижимые ни из одной
+pub fn run_entry_points(project_path: &str, json: bool) -> std::result::Result<String, String> {
+    use crate::entry_points::{EntryPointAnalyzer, EntryPointConfig};
+
+    let root = Path::new(project_path);
+    let config = EntryPointConfig::load(root)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    let analyzer = EntryPointAnalyzer::new(root, &config).map_err(|e| e.to_string())?;
+    let report = analyzer.analyze(&build_project_graph(project_path)?);
+    if json {
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    } else {
+        Ok(report.to_text())
+    }
+}
+
+pub fn build_graph_mermaid(project_path: &str) -> std::result::Result<String, String> {
+    build_graph_mermaid_sampled(project_path, None, None, &[], false)
+}
+
+/// Mermaid по графу; для больших графов — по выборке капсул с пояснением в комментарии
+pub fn build_graph_mermaid_sampled(
+    project_path: &str,
+    sampling: Option<&GraphSampler>,
+    level: Option<HierarchyLevel>,
+    tags: &[String],
+    condensed: bool,
+) -> std::result::Result<String, String> {
+    use crate::exporter::Exporter;
+
+    let (graph, _) = build_project_graph_using(
+        project_path,
+        true,
+        crate::capsule_graph_builder::CapsuleGraphBuilder::new(),
+        MERMAID_SCAN_DEPTH,
+        &crate::overlays::OverlayInputs::default(),
+    )?;
+    let graph = apply_level(apply_tags(graph, tags), level);
+    let graph = apply_condensed(graph, condensed);
+    let (graph, note) = apply_sampling(graph, sampling);
+    let mermaid = Exporter::new()
+        .export_to_mermaid(&graph)
+        .map_err(|e| e.to_string())?;
+    Ok(match note {
+        Some(note) => mermaid.replacen('\n', &format!("\n    %% {}\n", note), 1),
+        None => mermaid,
+    })
+}
+
+/// Раздел `Cycles Detail`: все группы циклов с местами их рёбер;
+/// пустая строка, если циклов нет
+pub fn cycles_detail_text(project_path: &str) -> std::result::Result<String, String> {
+    let graph = build_project_graph(project_path)?;
+    Ok(crate::graph::CyclesDetail::from_graph(&graph)
+        .map(|cycles| cycles.compact_section())
+        .unwrap_or_default())
+}
+
+/// Оставляет капсулы с любым из тегов и связи между ними; без тегов граф не меняется
+pub fn apply_tags(graph: CapsuleGraph, tags: &[String]) -> CapsuleGraph {
+    if tags.is_empty() {
+        return graph;
+    }
+    let selected: std::collections::HashSet<_> = graph
+        .capsules
+        .iter()
+        .filter(|(_, capsule)| capsule.tags.iter().any(|t| tags.contains(t)))
+        .map(|(id, _)| *id)
+        .collect();
+    eprintln!(
+        "🏷️ Теги {}: {} из {} капсул",
+        tags.join(", "),
+        selected.len(),
+        graph.capsules.len()
+    );
+    crate::graph_sampling::subgraph(&graph, &selected)
+}
+
+/// Сворачивает граф до уровня иерархии (каталоги, файлы, типы) для диаграмм
+pub fn apply_level(graph: CapsuleGraph, level: Option<HierarchyLevel>) -> CapsuleGraph {
+    match level {
+        Some(level) => {
+            let collapsed = CapsuleHierarchy::from_graph(&graph).collapse(&graph, level);
+            eprintln!(
+                "🗂️ Уровень {}: {} узлов из {} капсул",
+                level.as_str(),
+                collapsed.capsules.len(),
+                graph.capsules.len()
+            );
+            collapsed
+        }
+        None => graph,
+    }
+}
+
+/// Граф конденсации: каждая компонента сильной связности (цикл) — один узел.
+/// Применяется после уровня иерархии, поэтому сворачивает и циклы каталогов
+pub fn apply_condensed(graph: CapsuleGraph, condensed: bool) -> CapsuleGraph {
+    if !condensed {
+        return graph;
+    }
+    let condensed = crate::capsule_graph_builder::CapsuleGraphBuilder::new().condense_graph(&graph);
+    eprintln!(
+        "🧩 Граф конденсации: {} узлов из {} капсул",
+        condensed.capsules.len(),
+        graph.capsules.len()
+    );
+    condensed
+}
+
+/// Выборка капсул большого графа; без выборщика или в пределах лимита граф не меняется.
+/// Возвращает пояснение «показано N из M», если выборка применена
+pub fn apply_sampling(
+    graph: CapsuleGraph,
+    sampling: Option<&GraphSampler>,
+) -> (CapsuleGraph, Option<String>) {
+    match sampling.and_then(|sampler| sampler.sample(&graph)) {
+        Some(sample) => {
+            let note = sample.note();
+            eprintln!("✂️ Выборка капсул: {}", note);
+            (sample.graph, Some(note))
+        }
+        None => (graph, None),
+    }
+}
+
+pub fn run_deep_pipeline(
+    project_path: &str,
+    include_tests: bool,
+) -> std::result::Result<String, String> {
+    run_deep_pipeline_with(
+        project_path,
+        include_tests,
+        &crate::overlays::OverlayInputs::default(),
+    )
+}
+
+/// Deep-анализ с наложенными отчётами внешних инструментов (`--coverage`, `--profile`)
+pub fn run_deep_pipeline_with(
+    project_path: &str,
+    include_tests: bool,
+    overlays: &crate::overlays::OverlayInputs,
+) -> std::result::Result<String, String> {
+    let (validated_graph, coverage) =
+        build_project_graph_overlaid(project_path, include_tests, overlays)?;
+    if let Err(err) =
+        crate::metric_history::MetricHistory::record(Path::new(project_path), &validated_graph)
+    {
+        eprintln!("⚠️ История метрик не записана: {}", err);
+    }
+    let tests = crate::code_origin::test_code_summary(&validated_graph);
+    let mut recommendations = vec!["Граф построен с использованием полного пайплайна".to_string()];
+    if tests.test_capsules > 0 {
+        recommendations.push(format!(
+            "Тестовый код: {} капсул, {} связей — не учитываются в метриках",
+            tests.test_capsules, tests.test_relations
+        ));
+    }
+    let auxiliary = crate::code_origin::auxiliary_capsules(&validated_graph).len();
+    if auxiliary > 0 {
+        recommendations.push(format!(
+            "Бенчмарки и примеры: {} капсул — не учитываются в метриках ядра",
+            auxiliary
+        ));
+    }
+
+    // файлы, пропущенные из-за паники, — предупреждения анализа без капсулы
+    let warnings = coverage
+        .failed
+        .iter()
+        .map(|failed| AnalysisWarning {
+            message: format!(
+                "Файл пропущен из-за паники ({}): {}: {}",
+                failed.stage,
+                failed.path.display(),
+                failed.message
+            ),
+            level: Priority::High,
+            category: crate::panic_isolation::ANALYSIS_FAILURE_CATEGORY.to_string(),
+            capsule_id: None,
+            suggestion: None,
+        })
+        .collect();
 
     let result = AnalysisResult {
         graph: validated_graph,
-        warnings: Vec::new(),
-        recommendations: vec!["Граф построен с использованием полного пайплайна".to_string()],
+        warnings,
+        recommendations,
         export_formats: vec![
             ExportFormat::JSON,
             ExportFormat::Mermaid,
@@ -305,11 +2117,67 @@ pub fn print_help() {
     println!();
     println!("КОМАНДЫ:");
     println!(
-        "  analyze <path> [--verbose] [--include-tests] [--deep]  Анализ (deep — полный пайплайн)"
+        "  analyze <path> [--verbose] [--include-tests] [--deep]  Анализ (deep — полный пайплайн, тесты — с --include-tests)"
+    );
+    println!("                                                         Git-история капсул (git_commits, git_authors, git_last_modified) и владение по blame (bus factor); отключение: ARCHLENS_GIT_METADATA=0");
+    println!(
+        "  export <path> <format> [--output <file>]               Экспорт (ai_compact|backlog|html|email)"
+    );
+    println!("  export <path> prompt-pack [--output <dir|file.zip>]    Пакет для AI-ревью: summary.json, фрагменты кода, промпты");
+    println!("  export <path> ai_compact --format plain                Текст без эмодзи/цветов, таблицы фиксированной ширины");
+    println!(
+        "  export <path> <format> --lang en|ru|es|de              Язык находок (или ARCHLENS_LANG)"
     );
-    println!("  export <path> <format> [--output <file>]               Экспорт (ai_compact)");
+    println!("                                                         LLM-сводки: .archlens/llm.json (enabled, endpoint, model)");
+    println!("                                                         Редактирование: .archlens/redaction.json или ARCHLENS_REDACTION (paths, identifiers, snippets)");
+    println!("  export <path> <format> --dry-run                       Оценка размера по уровням детализации (без записи)");
+    println!("  export <path> ai_compact --all-findings                Все находки (по умолчанию ≤3 на компонент, ≤20 на категорию)");
+    println!("  export <path> ai_compact --include-tests               Тестовый код в метриках и рейтингах сложности/связности");
+    println!("  analyze|export <path> ... --coverage <lcov|xml>        Измеренное покрытие в капсулах и разделе Coverage Risk (ai_compact, prompt-pack, analyze --deep)");
+    println!("  analyze|export <path> ... --profile <perf|folded|pprof> Доли CPU в капсулах и раздел CPU Hotspots (ai_compact, prompt-pack, analyze --deep)");
     println!("  structure <path> [--max-depth N] [--show-metrics]      Структура проекта");
     println!("  diagram <path> <type> [--output <file>]               Диаграмма архитектуры");
+    println!("  diagram <path> class [--scope <subpath>] [--output]   Mermaid classDiagram типов");
+    println!("  diagram <path> erd|erd-plantuml [--scope <subpath>]   ER-диаграмма моделей данных");
+    println!("  diagram <path> svg|dot [--fresh-layout]               Диаграмма со стабильной раскладкой");
+    println!("  diagram <path> <type> --max-nodes N [--sample <s>]   Выборка узлов: centrality|stratified|problem-adjacent");
+    println!("  diagram <path> mermaid|svg|dot --level <l>            Свернуть до уровня: directory|file|type|function");
+    println!("  diagram <path> mermaid|svg|dot --tag <tag>            Только капсулы с тегом (.archlens/tags.json)");
+    println!("  diagram <path> mermaid|svg|dot --condensed            Цикл (SCC) — один узел");
+    println!("  overlay <path> <kind> [--input <file>] [--output <file>]  Оверлей отчёта (bloat|timings|tests|traces|coverage|profile)");
+    println!("  release <path> <from> [<to>] [--output <file>]          Архитектурные изменения релиза (markdown)");
+    println!("  diff <path> <main> [<branch>] [--base <rev>] [--output <file>]  Diff архитектуры; с --base — трёхсторонний");
+    println!("                                                         ADR-связи: .archlens/decisions.json (adr, capsules, forbid, require)");
+    println!("  merge-risk <path> [--target <branch>] [--branches a,b] [--json]  Риск конфликтов слияния между ветками");
+    println!("  erosion <path> [--days N] [--json] [--output <file>]   Прогноз изменений: ускорение + падение качества");
+    println!("  module-docs <path> [--stdout]                          MODULE.md для модулей верхнего уровня");
+    println!("  ask \"<question>\" [<path>] [--json]                     Вопрос о графе: who uses X? / what does X use? / where is X?");
+    println!("  impact <file|capsule> [<path>] [--depth N] [--json]   Что затронет изменение: транзитивные зависимые с расстоянием");
+    println!("  path <from> <to> [<path>] [--max-paths N] [--json]    Почему from зависит от to: кратчайшие пути");
+    println!("  entry-points <path> [--json]                          Охват точек входа и недостижимые капсулы");
+    println!("                                                         Точки входа: .archlens/entry_points.json (entry_points: name, file, capsule)");
+    println!("  hotspots <path> [--top N] [--cognitive] [--json]      Горячие точки: частота изменений × сложность");
+    println!("  todos <path> [--layer L] [--json]                     Маркеры TODO/FIXME/HACK по слоям и капсулам с возрастом");
+    println!("  watch <path> [--debounce-ms N] [--json]               Непрерывный анализ при изменении файлов");
+    println!("  init [<path>] [--yes] [--force]                       Мастер настройки: пишет .archlens.toml");
+    println!("  export <path> sqlite [--output <file>]                Граф в .archlens/graph.db: MCP и diff без повторного анализа");
+    println!("  export graph [<path>] --format archive [-o <file>]     Архив графа <проект>.archlens.graph для ревью");
+    println!("  view <file> [--html | --format <f>] [-o <file>]       Отчёт по архиву графа без исходников");
+    println!("  conformance <path> [--template <file>] [--json]      Отклонения сервисов от шаблона (.archlens/template.json)");
+    println!("                                                         Шаблон: required_dirs, required_layers, forbidden_top_level_dirs");
+    println!("  resemblance <path> [--json]                           На какую эталонную архитектуру похож проект и где отходит");
+    println!("                                                         Свои профили: .archlens/reference_architectures.json");
+    println!("  self-check [<path>] [--json]                          Самопроверка на исходниках ArchLens: число капсул и связей (без структурных), новые циклы");
+    println!("  audit [--project P] [--command C] [--user U] [--limit N] [--json] [--verify]  Журнал запусков при ARCHLENS_AUDIT=1 ($ARCHLENS_DATA_DIR/audit.jsonl)");
     println!("  version                                               Печать версии");
     println!("  help                                                  Показать эту справку");
+    println!();
+    println!(
+        "  Вместо <path> в analyze/export/structure/diagram/overlay/module-docs/ask/entry-points"
+    );
+    println!("  можно передать git URL (https://, ssh://, git@host:repo): репозиторий клонируется");
+    println!("  с --depth 1 во временный каталог, который удаляется после команды");
+    println!();
+    println!("  --single-thread (или ARCHLENS_SINGLE_THREAD=1) в любой позиции: детерминированный");
+    println!("  однопоточный прогон без пулов потоков и с фиксированным порядком файлов");
 }
@@ -1,6 +1,8 @@
 // Парсер командной строки
 
+use crate::graph_sampling::{GraphSampler, SamplingStrategy, DEFAULT_SAMPLE_LIMIT};
 use std::env;
+use std::path::PathBuf;
 
 /// Команды CLI
 #[derive(Debug, Clone)]
@@ -10,6 +12,8 @@ pub enum CliCommand {
         verbose: bool,
         include_tests: bool,
         deep: bool,
+        /// Отчёты, накладываемые на граф deep-анализа (`--coverage`, `--profile`)
+        overlays: crate::overlays::OverlayInputs,
     },
     Export {
         project_path: String,
@@ -27,18 +31,235 @@ pub enum CliCommand {
         diagram_type: DiagramType,
         output: Option<String>,
         include_metrics: bool,
+        /// Подпуть проекта для classDiagram и ERD
+        scope: Option<String>,
+        /// svg/dot: не брать позиции из кэша раскладок
+        fresh_layout: bool,
+        /// Выборка капсул для больших графов (`--max-nodes`, `--sample`)
+        sampling: Option<GraphSampler>,
+        /// Свернуть граф до уровня иерархии (`--level directory|file|type|function`)
+        level: Option<crate::hierarchy::HierarchyLevel>,
+        /// Оставить только капсулы с одним из тегов (`--tag payments`)
+        tags: Vec<String>,
+        /// Свернуть каждую компоненту сильной связности в один узел (`--condensed`)
+        condensed: bool,
+    },
+    Overlay {
+        project_path: String,
+        kind: OverlayKind,
+        input: Option<String>,
+        output: Option<String>,
+    },
+    Release {
+        project_path: String,
+        from: String,
+        to: String,
+        output: Option<String>,
+    },
+    /// Diff архитектуры `from` → `to`; с `base` — трёхсторонний (base, main=`from`, ветка=`to`)
+    Diff {
+        project_path: String,
+        from: String,
+        to: String,
+        base: Option<String>,
+        output: Option<String>,
+    },
+    /// Прогноз конфликтов слияния между активными ветками
+    MergeRisk {
+        project_path: String,
+        target: Option<String>,
+        branches: Vec<String>,
+        json: bool,
+        output: Option<String>,
+    },
+    /// MODULE.md для каждого модуля верхнего уровня
+    ModuleDocs {
+        project_path: String,
+        /// Печать в stdout вместо записи файлов
+        stdout: bool,
+    },
+    /// Раннее предупреждение об эрозии: ускорение изменений при падении качества
+    Erosion {
+        project_path: String,
+        days: u32,
+        json: bool,
+        output: Option<String>,
+    },
+    /// Структурный вопрос о графе (кто использует, от чего зависит, где определён)
+    Ask {
+        project_path: String,
+        question: String,
+        json: bool,
+    },
+    /// Что сломается при изменении файла или капсулы: транзитивные зависимые с расстоянием
+    Impact {
+        project_path: String,
+        /// Файл (относительный или абсолютный путь) или имя капсулы
+        target: String,
+        /// Ограничение числа шагов по зависимостям
+        depth: Option<usize>,
+        json: bool,
+    },
+    /// Кратчайшие пути зависимостей между двумя капсулами с местом каждой связи
+    DependencyPath {
+        project_path: String,
+        /// Файл или капсула, от которой идёт зависимость
+        from: String,
+        /// Файл или капсула, от которой зависят
+        to: String,
+        /// Сколько кратчайших путей показать
+        max_paths: Option<usize>,
+        json: bool,
+    },
+    /// Охват точек входа и капсулы, недостижимые ни из одной
+    EntryPoints {
+        project_path: String,
+        json: bool,
+    },
+    /// Горячие точки: частота изменений × сложность
+    Hotspots {
+        project_path: String,
+        top: usize,
+        /// Когнитивная сложность вместо цикломатической
+        cognitive: bool,
+        json: bool,
+    },
+    /// Маркеры TODO/FIXME по слоям и капсулам с возрастом из git
+    Todos {
+        project_path: String,
+        /// Только маркеры этого слоя
+        layer: Option<String>,
+        json: bool,
+    },
+    /// Непрерывный анализ: пересчёт при изменении файлов проекта
+    Watch {
+        project_path: String,
+        /// Пауза без событий перед пересчётом, мс
+        debounce_ms: u64,
+        /// Строка JSON на каждый пересчёт вместо текстовой сводки
+        json: bool,
+    },
+    /// Мастер настройки: предлагает шаблоны, слои и пороги и пишет `.archlens.toml`
+    Init {
+        project_path: String,
+        /// Записать предложение без вопросов
+        yes: bool,
+        /// Перезаписать существующий `.archlens.toml`
+        force: bool,
+    },
+    /// Просмотр архива графа `.archlens.graph` без исходников проекта
+    View {
+        file: String,
+        /// Формат реестра экспортёров; `None` — компактная сводка для терминала
+        format: Option<String>,
+        output: Option<String>,
+    },
+    /// Соответствие сервисов шаблону проекта организации
+    Conformance {
+        project_path: String,
+        /// Файл шаблона; по умолчанию `.archlens/template.json`
+        template: Option<String>,
+        json: bool,
+    },
+    /// Сходство проекта с эталонными архитектурами и отклонения от ближайшей
+    Resemblance {
+        project_path: String,
+        json: bool,
+    },
+    /// Самопроверка: анализ исходников ArchLens и сверка с закреплёнными ожиданиями
+    SelfCheck {
+        /// Исходники ArchLens; по умолчанию — каталог, из которого собран бинарник
+        project_path: Option<String>,
+        json: bool,
+    },
+    /// Просмотр журнала аудита
+    Audit {
+        project: Option<String>,
+        command: Option<String>,
+        user: Option<String>,
+        limit: usize,
+        json: bool,
+        verify: bool,
     },
     Version,
     Help,
 }
 
+impl CliCommand {
+    /// Имя команды и путь проекта для журнала аудита; `None` — команда не журналируется
+    pub fn audit_subject(&self) -> Option<(&'static str, &str)> {
+        let subject = match self {
+            CliCommand::Analyze { project_path, .. } => ("analyze", project_path),
+            CliCommand::Export { project_path, .. } => ("export", project_path),
+            CliCommand::Structure { project_path, .. } => ("structure", project_path),
+            CliCommand::Diagram { project_path, .. } => ("diagram", project_path),
+            CliCommand::Overlay { project_path, .. } => ("overlay", project_path),
+            CliCommand::Release { project_path, .. } => ("release", project_path),
+            CliCommand::Diff { project_path, .. } => ("diff", project_path),
+            CliCommand::MergeRisk { project_path, .. } => ("merge-risk", project_path),
+            CliCommand::ModuleDocs { project_path, .. } => ("module-docs", project_path),
+            CliCommand::Erosion { project_path, .. } => ("erosion", project_path),
+            CliCommand::Ask { project_path, .. } => ("ask", project_path),
+            CliCommand::Impact { project_path, .. } => ("impact", project_path),
+            CliCommand::DependencyPath { project_path, .. } => ("path", project_path),
+            CliCommand::EntryPoints { project_path, .. } => ("entry-points", project_path),
+            CliCommand::Hotspots { project_path, .. } => ("hotspots", project_path),
+            CliCommand::Todos { project_path, .. } => ("todos", project_path),
+            CliCommand::Watch { project_path, .. } => ("watch", project_path),
+            CliCommand::Init { project_path, .. } => ("init", project_path),
+            CliCommand::Conformance { project_path, .. } => ("conformance", project_path),
+            CliCommand::Resemblance { project_path, .. } => ("resemblance", project_path),
+            CliCommand::SelfCheck { .. }
+            | CliCommand::View { .. }
+            | CliCommand::Audit { .. }
+            | CliCommand::Version
+            | CliCommand::Help => return None,
+        };
+        Some((subject.0, subject.1.as_str()))
+    }
+
+    /// Путь проекта команд, анализирующих снимок кода, — его можно заменить
+    /// клоном удалённого репозитория. Команды по git-истории и `watch` не
+    /// входят: неглубокому клону нечего сравнивать и не за чем наблюдать
+    pub fn snapshot_project_path_mut(&mut self) -> Option<&mut String> {
+        match self {
+            CliCommand::Analyze { project_path, .. }
+            | CliCommand::Export { project_path, .. }
+            | CliCommand::Structure { project_path, .. }
+            | CliCommand::Diagram { project_path, .. }
+            | CliCommand::Overlay { project_path, .. }
+            | CliCommand::ModuleDocs { project_path, .. }
+            | CliCommand::Ask { project_path, .. }
+            | CliCommand::Impact { project_path, .. }
+            | CliCommand::DependencyPath { project_path, .. }
+            | CliCommand::EntryPoints { project_path, .. }
+            | CliCommand::Hotspots { project_path, .. }
+            | CliCommand::Todos { project_path, .. }
+            | CliCommand::Conformance { project_path, .. }
+            | CliCommand::Resemblance { project_path, .. } => Some(project_path),
+            _ => None,
+        }
+    }
+}
+
 /// Форматы экспорта
 #[derive(Debug, Clone)]
 pub enum ExportFormat {
     AiCompact,
-    Json,
     Markdown,
     Html,
+    /// Бэклог исправлений в CSV для планирования спринтов
+    BacklogCsv,
+    /// HTML-сводка для email-рассылки (inline CSS, без скриптов)
+    Email,
+    /// Пакет для внешнего AI-ревью: сводка, фрагменты кода, промпты (папка или .zip)
+    PromptPack,
+    /// Граф в SQLite (`.archlens/graph.db`): MCP и diff берут граф из базы без повторного анализа
+    Sqlite,
+    /// Формат графа из `exporter::ExporterRegistry` по имени (json, mermaid, graphml, ...)
+    Registry(String),
+    /// Перечень доступных форматов (`--format list`)
+    List,
 }
 
 /// Типы диаграмм
@@ -47,6 +268,43 @@ pub enum DiagramType {
     Mermaid,
     Dot,
     Svg,
+    /// Mermaid classDiagram: типы, поля, методы, наследование
+    Class,
+    /// ER-диаграмма моделей данных (Mermaid erDiagram)
+    Erd,
+    /// ER-диаграмма моделей данных в PlantUML
+    ErdPlantUml,
+}
+
+/// Виды внешних отчётов для наложения на граф
+#[derive(Debug, Clone)]
+pub enum OverlayKind {
+    Bloat,
+    Timings,
+    Tests,
+    Traces,
+    Coverage,
+    Profile,
+}
+
+/// `--max-nodes` и `--sample` до сборки выборщика
+#[derive(Debug, Clone, Copy, Default)]
+struct SamplingArgs {
+    limit: Option<usize>,
+    strategy: Option<SamplingStrategy>,
+}
+
+impl SamplingArgs {
+    /// Стратегия без лимита — лимит по умолчанию, лимит без стратегии — по связности
+    fn sampler(self) -> Option<GraphSampler> {
+        if self.limit.is_none() && self.strategy.is_none() {
+            return None;
+        }
+        Some(GraphSampler::new(
+            self.strategy.unwrap_or(SamplingStrategy::Centrality),
+            self.limit.unwrap_or(DEFAULT_SAMPLE_LIMIT),
+        ))
+    }
 }
 
 /// Опции экспорта
@@ -55,11 +313,28 @@ pub struct ExportOptions {
     pub focus_critical_only: bool,
     pub include_diff_analysis: bool,
     pub include_metrics: bool,
+    /// Простой текст без эмодзи, цветов и markdown (`--format plain`)
+    pub plain_text: bool,
+    /// Язык находок в отчёте (`--lang ru`); по умолчанию — `ARCHLENS_LANG`
+    pub lang: Option<String>,
+    /// Только оценить размер отчёта по уровням детализации (`--dry-run`)
+    pub dry_run: bool,
+    /// Выборка капсул графа в HTML-отчёте (`--max-nodes`, `--sample`)
+    pub sampling: Option<GraphSampler>,
+    /// Все находки в ai_compact без лимитов на капсулу и категорию
+    pub all_findings: bool,
+    /// Тестовые капсулы в метриках и рейтингах сложности/связности ai_compact
+    pub include_tests: bool,
+    /// Отчёты, накладываемые на граф ai_compact и prompt-pack (`--coverage`, `--profile`)
+    pub overlays: crate::overlays::OverlayInputs,
 }
 
 /// Парсинг аргументов командной строки
 pub fn parse_args() -> Result<CliCommand, String> {
-    let args: Vec<String> = env::args().collect();
+    // Режим выполнения включается до разбора команды (`execution::configure_from_args`)
+    let args: Vec<String> = env::args()
+        .filter(|a| a != crate::execution::SINGLE_THREAD_FLAG)
+        .collect();
 
     if args.len() < 2 {
         return Ok(CliCommand::Help);
@@ -83,13 +358,35 @@ impl ArgParser {
     fn parse(&mut self) -> Result<CliCommand, String> {
         let command = self
             .current()
+            .cloned()
             .ok_or_else(|| "Не указана команда".to_string())?;
+        // Аргументы команды начинаются после её имени
+        self.advance();
 
         match command.as_str() {
             "analyze" => self.parse_analyze(),
             "export" => self.parse_export(),
             "structure" => self.parse_structure(),
             "diagram" => self.parse_diagram(),
+            "overlay" => self.parse_overlay(),
+            "release" => self.parse_release(),
+            "diff" => self.parse_diff(),
+            "merge-risk" => self.parse_merge_risk(),
+            "module-docs" => self.parse_module_docs(),
+            "ask" => self.parse_ask(),
+            "impact" => self.parse_impact(),
+            "path" => self.parse_path(),
+            "erosion" => self.parse_erosion(),
+            "entry-points" => self.parse_entry_points(),
+            "hotspots" => self.parse_hotspots(),
+            "todos" => self.parse_todos(),
+            "watch" => self.parse_watch(),
+            "init" => self.parse_init(),
+            "view" => self.parse_view(),
+            "conformance" => self.parse_conformance(),
+            "resemblance" => self.parse_resemblance(),
+            "self-check" => self.parse_self_check(),
+            "audit" => self.parse_audit(),
             "version" | "--version" | "-V" => Ok(CliCommand::Version),
             "help" | "--help" | "-h" => Ok(CliCommand::Help),
             _ => Err(format!("Неизвестная команда: {}", command)),
@@ -103,6 +400,7 @@ impl ArgParser {
         let mut verbose = false;
         let mut include_tests = false;
         let mut deep = false;
+        let mut overlays = crate::overlays::OverlayInputs::default();
 
         // Парсим флаги
         while let Some(arg) = self.current() {
@@ -110,6 +408,14 @@ impl ArgParser {
                 "--verbose" | "-v" => verbose = true,
                 "--include-tests" => include_tests = true,
                 "--deep" => deep = true,
+                "--coverage" => {
+                    self.advance();
+                    overlays.coverage = self.current().map(PathBuf::from);
+                }
+                "--profile" => {
+                    self.advance();
+                    overlays.profile = self.current().map(PathBuf::from);
+                }
                 _ => break,
             }
             self.advance();
@@ -124,29 +430,60 @@ impl ArgParser {
             verbose,
             include_tests,
             deep,
+            overlays,
         })
     }
 
     fn parse_export(&mut self) -> Result<CliCommand, String> {
-        let project_path = self.current().cloned();
+        let mut project_path = self.current().cloned();
         self.advance();
 
-        let format_str = self
-            .current()
-            .ok_or_else(|| "Не указан формат экспорта".to_string())?;
+        // `export graph [<path>] --format archive` — граф проекта в формате реестра, по умолчанию JSON
+        let graph_subject = project_path.as_deref() == Some("graph");
+        if graph_subject {
+            project_path = self.current().filter(|arg| !arg.starts_with('-')).cloned();
+            if project_path.is_some() {
+                self.advance();
+            }
+        }
+
+        let format_str = match self.current().cloned() {
+            Some(format_str) => format_str,
+            None if graph_subject => "json".to_string(),
+            None => return Err("Не указан формат экспорта".to_string()),
+        };
 
-        let format = match format_str.as_str() {
-            "ai_compact" | "ai-compact" => ExportFormat::AiCompact,
-            "json" => ExportFormat::Json,
+        // `export <path> --format plain` — формат ai_compact подразумевается
+        let registry = crate::exporter::Exporter::new().registry();
+        let mut format = match format_str.as_str() {
+            "ai_compact" | "ai-compact" | "plain" | "text" => ExportFormat::AiCompact,
             "markdown" | "md" => ExportFormat::Markdown,
             "html" => ExportFormat::Html,
-            _ => return Err(format!("Неподдерживаемый формат: {}", format_str)),
+            "backlog" | "backlog-csv" | "csv" => ExportFormat::BacklogCsv,
+            "email" | "email-html" | "digest" => ExportFormat::Email,
+            "prompt-pack" | "prompt_pack" | "review-pack" => ExportFormat::PromptPack,
+            "sqlite" | "db" => ExportFormat::Sqlite,
+            "list" => ExportFormat::List,
+            flag if flag.starts_with('-') && graph_subject => {
+                ExportFormat::Registry("json".to_string())
+            }
+            flag if flag.starts_with('-') => ExportFormat::AiCompact,
+            name => match registry.get(name) {
+                Some(exporter) => ExportFormat::Registry(exporter.name().to_string()),
+                None => return Err(format!("Неподдерживаемый формат: {}", format_str)),
+            },
         };
-
-        self.advance();
+        let plain_text = matches!(format_str.as_str(), "plain" | "text");
+        if !format_str.starts_with('-') {
+            self.advance();
+        }
 
         let mut output = None;
-        let mut options = ExportOptions::default();
+        let mut options = ExportOptions {
+            plain_text,
+            ..ExportOptions::default()
+        };
+        let mut sampling = SamplingArgs::default();
 
         // Парсим оставшиеся аргументы
         while let Some(arg) = self.current() {
@@ -170,6 +507,60 @@ impl ArgParser {
                     options.include_metrics = true;
                     self.advance();
                 }
+                "--dry-run" => {
+                    options.dry_run = true;
+                    self.advance();
+                }
+                "--all-findings" => {
+                    options.all_findings = true;
+                    self.advance();
+                }
+                "--include-tests" => {
+                    options.include_tests = true;
+                    self.advance();
+                }
+                "--max-nodes" | "--sample" => self.parse_sampling_arg(&mut sampling)?,
+                "--coverage" => {
+                    self.advance();
+                    options.overlays.coverage = self.current().map(PathBuf::from);
+                    if options.overlays.coverage.is_some() {
+                        self.advance();
+                    }
+                }
+                "--profile" => {
+                    self.advance();
+                    options.overlays.profile = self.current().map(PathBuf::from);
+                    if options.overlays.profile.is_some() {
+                        self.advance();
+                    }
+                }
+                "--lang" => {
+                    self.advance();
+                    options.lang = self.current().cloned();
+                    if options.lang.is_some() {
+                        self.advance();
+                    }
+                }
+                "--format" => {
+                    self.advance();
+                    match self.current().map(String::as_str) {
+                        Some("plain") | Some("text") => options.plain_text = true,
+                        Some("markdown") | Some("md") => options.plain_text = false,
+                        Some("list") => format = ExportFormat::List,
+                        other => match other.and_then(|name| registry.get(name)) {
+                            Some(exporter) => {
+                                format = ExportFormat::Registry(exporter.name().to_string())
+                            }
+                            None => {
+                                return Err(format!(
+                                    "Неподдерживаемое значение --format: {} (plain|markdown|list|формат графа)",
+                                    other.unwrap_or("")
+                                ))
+                            }
+                        },
+                    }
+                    self.advance();
+                }
                 _ => {
                     // Если не флаг, считаем это выходным файлом
                     if output.is_none() && !arg.starts_with("-") {
@@ -180,6 +571,8 @@ impl ArgParser {
             }
         }
 
+        options.sampling = sampling.sampler();
+
         Ok(CliCommand::Export {
             project_path: project_path.unwrap_or_else(|| {
                 crate::get_default_project_path()
@@ -243,6 +636,9 @@ impl ArgParser {
             "mermaid" => DiagramType::Mermaid,
             "dot" => DiagramType::Dot,
             "svg" => DiagramType::Svg,
+            "class" | "classDiagram" | "class-diagram" => DiagramType::Class,
+            "erd" => DiagramType::Erd,
+            "erd-plantuml" | "plantuml-erd" => DiagramType::ErdPlantUml,
             _ => {
                 return Err(format!(
                     "Неподдерживаемый тип диаграммы: {}",
@@ -255,6 +651,12 @@ impl ArgParser {
 
         let mut output = None;
         let mut include_metrics = false;
+        let mut scope = None;
+        let mut fresh_layout = false;
+        let mut sampling = SamplingArgs::default();
+        let mut level = None;
+        let mut tags = Vec::new();
+        let mut condensed = false;
 
         while let Some(arg) = self.current() {
             match arg.as_str() {
@@ -269,6 +671,47 @@ impl ArgParser {
                     include_metrics = true;
                     self.advance();
                 }
+                "--scope" => {
+                    self.advance();
+                    scope = self.current().cloned();
+                    if scope.is_some() {
+                        self.advance();
+                    }
+                }
+                "--fresh-layout" => {
+                    fresh_layout = true;
+                    self.advance();
+                }
+                "--max-nodes" | "--sample" => self.parse_sampling_arg(&mut sampling)?,
+                "--level" => {
+                    self.advance();
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указан уровень для --level".to_string())?;
+                    level = Some(crate::hierarchy::HierarchyLevel::parse(&value).ok_or_else(
+                        || {
+                            format!(
+                                "Неизвестный уровень: {} (directory|file|type|function)",
+                                value
+                            )
+                        },
+                    )?);
+                    self.advance();
+                }
+                "--tag" => {
+                    self.advance();
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указан тег для --tag".to_string())?;
+                    tags.push(value);
+                    self.advance();
+                }
+                "--condensed" => {
+                    condensed = true;
+                    self.advance();
+                }
                 _ => {
                     if output.is_none() && !arg.starts_with("-") {
                         output = Some(arg.clone());
@@ -287,6 +730,754 @@ impl ArgParser {
             diagram_type,
             output,
             include_metrics,
+            scope,
+            fresh_layout,
+            sampling: sampling.sampler(),
+            level,
+            tags,
+            condensed,
+        })
+    }
+
+    /// `--max-nodes N` или `--sample <strategy>`
+    fn parse_sampling_arg(&mut self, sampling: &mut SamplingArgs) -> Result<(), String> {
+        let flag = self.current().cloned().unwrap_or_default();
+        self.advance();
+        let value = self
+            .current()
+            .cloned()
+            .ok_or_else(|| format!("Не указано значение {}", flag))?;
+        self.advance();
+        if flag == "--max-nodes" {
+            let limit = value
+                .parse::<usize>()
+                .map_err(|_| format!("Некорректное значение --max-nodes: {}", value))?;
+            sampling.limit = Some(limit);
+        } else {
+            sampling.strategy = Some(value.parse::<SamplingStrategy>()?);
+        }
+        Ok(())
+    }
+
+    fn parse_overlay(&mut self) -> Result<CliCommand, String> {
+        let project_path = self.current().cloned();
+        self.advance();
+
+        let kind_str = self
+            .current()
+            .ok_or_else(|| "Не указан тип оверлея".to_string())?;
+
+        let kind = match kind_str.as_str() {
+            "bloat" | "binary-size" => OverlayKind::Bloat,
+            "timings" | "build-time" => OverlayKind::Timings,
+            "tests" | "junit" => OverlayKind::Tests,
+            "traces" | "otlp" => OverlayKind::Traces,
+            "coverage" | "lcov" | "cobertura" => OverlayKind::Coverage,
+            "profile" | "perf" | "pprof" => OverlayKind::Profile,
+            _ => return Err(format!("Неподдерживаемый тип оверлея: {}", kind_str)),
+        };
+
+        self.advance();
+
+        let mut input = None;
+        let mut output = None;
+
+        while let Some(arg) = self.current() {
+            match arg.as_str() {
+                "--input" | "-i" => {
+                    self.advance();
+                    input = self.current().cloned();
+                    if input.is_some() {
+                        self.advance();
+                    }
+                }
+                "--output" | "-o" => {
+                    self.advance();
+                    output = self.current().cloned();
+                    if output.is_some() {
+                        self.advance();
+                    }
+                }
+                _ => {
+                    if input.is_none() && !arg.starts_with("-") {
+                        input = Some(arg.clone());
+                    }
+                    self.advance();
+                }
+            }
+        }
+
+        Ok(CliCommand::Overlay {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            kind,
+            input,
+            output,
+        })
+    }
+
+    fn parse_release(&mut self) -> Result<CliCommand, String> {
+        let mut positional = Vec::new();
+        let mut output = None;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--output" | "-o" => {
+                    output = self.current().cloned();
+                    if output.is_some() {
+                        self.advance();
+                    }
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let project_path = positional.next();
+        let from = positional
+            .next()
+            .ok_or_else(|| "Не указана начальная ревизия (тег)".to_string())?;
+        Ok(CliCommand::Release {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            from,
+            to: positional.next().unwrap_or_else(|| "HEAD".to_string()),
+            output,
+        })
+    }
+
+    fn parse_diff(&mut self) -> Result<CliCommand, String> {
+        let mut positional = Vec::new();
+        let mut base = None;
+        let mut output = None;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--base" | "-b" => {
+                    base = self.current().cloned();
+                    if base.is_none() {
+                        return Err("Не указана базовая ревизия для --base".to_string());
+                    }
+                    self.advance();
+                }
+                "--output" | "-o" => {
+                    output = self.current().cloned();
+                    if output.is_some() {
+                        self.advance();
+                    }
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let project_path = positional.next();
+        let from = positional
+            .next()
+            .ok_or_else(|| "Не указана ревизия для сравнения".to_string())?;
+        Ok(CliCommand::Diff {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            from,
+            to: positional.next().unwrap_or_else(|| "HEAD".to_string()),
+            base,
+            output,
+        })
+    }
+
+    fn parse_merge_risk(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut target = None;
+        let mut branches = Vec::new();
+        let mut json = false;
+        let mut output = None;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--target" | "-t" => {
+                    target = self.current().cloned();
+                    if target.is_some() {
+                        self.advance();
+                    }
+                }
+                "--branches" | "-b" => {
+                    if let Some(list) = self.current().cloned() {
+                        branches.extend(
+                            list.split(',')
+                                .map(str::trim)
+                                .filter(|b| !b.is_empty())
+                                .map(str::to_string),
+                        );
+                        self.advance();
+                    }
+                }
+                "--json" => json = true,
+                "--output" | "-o" => {
+                    output = self.current().cloned();
+                    if output.is_some() {
+                        self.advance();
+                    }
+                }
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::MergeRisk {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            target,
+            branches,
+            json,
+            output,
+        })
+    }
+
+    fn parse_module_docs(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut stdout = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--stdout" => stdout = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::ModuleDocs {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            stdout,
+        })
+    }
+
+    fn parse_erosion(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut days = 180;
+        let mut json = false;
+        let mut output = None;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--days" | "-d" => {
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указано число дней для --days".to_string())?;
+                    days = value
+                        .parse()
+                        .ok()
+                        .filter(|d| *d > 0)
+                        .ok_or_else(|| format!("Некорректное число дней: {}", value))?;
+                    self.advance();
+                }
+                "--json" => json = true,
+                "--output" | "-o" => {
+                    output = self.current().cloned();
+                    if output.is_some() {
+                        self.advance();
+                    }
+                }
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Erosion {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            days,
+            json,
+            output,
+        })
+    }
+
+    fn parse_entry_points(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::EntryPoints {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            json,
+        })
+    }
+
+    fn parse_hotspots(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut top = crate::advanced_metrics::hotspots::DEFAULT_HOTSPOT_LIMIT;
+        let mut cognitive = false;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--top" | "-n" => {
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указано число для --top".to_string())?;
+                    top = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("Некорректное число: {}", value))?;
+                    self.advance();
+                }
+                "--cognitive" => cognitive = true,
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Hotspots {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            top,
+            cognitive,
+            json,
+        })
+    }
+
+    fn parse_todos(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut layer = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--layer" => {
+                    layer = Some(
+                        self.current()
+                            .cloned()
+                            .ok_or_else(|| "Не указан слой для --layer".to_string())?,
+                    );
+                    self.advance();
+                }
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Todos {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            layer,
+            json,
+        })
+    }
+
+    fn parse_watch(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut debounce_ms = crate::file_scanner::watcher::DEFAULT_DEBOUNCE.as_millis() as u64;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--debounce-ms" => {
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указано значение для --debounce-ms".to_string())?;
+                    debounce_ms = value
+                        .parse()
+                        .map_err(|_| format!("Некорректное значение --debounce-ms: {}", value))?;
+                    self.advance();
+                }
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Watch {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            debounce_ms,
+            json,
+        })
+    }
+
+    fn parse_init(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut yes = false;
+        let mut force = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--yes" | "-y" => yes = true,
+                "--force" => force = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Init {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            yes,
+            force,
+        })
+    }
+
+    fn parse_view(&mut self) -> Result<CliCommand, String> {
+        let mut file = None;
+        let mut format = None;
+        let mut output = None;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--html" => format = Some("interactive-html".to_string()),
+                "--format" => {
+                    format = Some(
+                        self.current()
+                            .cloned()
+                            .ok_or_else(|| "Не указан формат для --format".to_string())?,
+                    );
+                    self.advance();
+                }
+                "--output" | "-o" => {
+                    output = Some(
+                        self.current()
+                            .cloned()
+                            .ok_or_else(|| "Не указан файл для --output".to_string())?,
+                    );
+                    self.advance();
+                }
+                _ if file.is_none() && !arg.starts_with('-') => file = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        let file = file.ok_or_else(|| "Не указан файл архива .archlens.graph".to_string())?;
+        // HTML открывают в браузере — по умолчанию он пишется рядом с архивом
+        if output.is_none() && format.as_deref() == Some("interactive-html") {
+            let stem = file
+                .strip_suffix(&format!(".{}", crate::graph_archive::ARCHIVE_EXTENSION))
+                .unwrap_or(&file);
+            output = Some(format!("{}.html", stem));
+        }
+        Ok(CliCommand::View {
+            file,
+            format,
+            output,
+        })
+    }
+
+    fn parse_conformance(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut template = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--template" => {
+                    template = Some(
+                        self.current()
+                            .cloned()
+                            .ok_or_else(|| "Не указан файл шаблона для --template".to_string())?,
+                    );
+                    self.advance();
+                }
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Conformance {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            template,
+            json,
+        })
+    }
+
+    fn parse_resemblance(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Resemblance {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            json,
+        })
+    }
+
+    fn parse_self_check(&mut self) -> Result<CliCommand, String> {
+        let mut project_path = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--json" => json = true,
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::SelfCheck { project_path, json })
+    }
+
+    fn parse_audit(&mut self) -> Result<CliCommand, String> {
+        let mut project = None;
+        let mut command = None;
+        let mut user = None;
+        let mut limit = 50;
+        let mut json = false;
+        let mut verify = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--project" | "-p" => {
+                    project = self.current().cloned();
+                    if project.is_some() {
+                        self.advance();
+                    }
+                }
+                "--command" | "-c" => {
+                    command = self.current().cloned();
+                    if command.is_some() {
+                        self.advance();
+                    }
+                }
+                "--user" | "-u" => {
+                    user = self.current().cloned();
+                    if user.is_some() {
+                        self.advance();
+                    }
+                }
+                "--limit" | "-n" => {
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указано число записей для --limit".to_string())?;
+                    limit = value
+                        .parse()
+                        .map_err(|_| format!("Некорректное число записей: {}", value))?;
+                    self.advance();
+                }
+                "--json" => json = true,
+                "--verify" => verify = true,
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Audit {
+            project,
+            command,
+            user,
+            limit,
+            json,
+            verify,
+        })
+    }
+
+    fn parse_ask(&mut self) -> Result<CliCommand, String> {
+        let mut question = None;
+        let mut project_path = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--json" => json = true,
+                "--path" | "-p" => {
+                    project_path = self.current().cloned();
+                    if project_path.is_some() {
+                        self.advance();
+                    }
+                }
+                _ if question.is_none() => question = Some(arg),
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Ask {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            question: question
+                .filter(|q| !q.trim().is_empty())
+                .ok_or_else(|| "Не указан вопрос".to_string())?,
+            json,
+        })
+    }
+
+    fn parse_impact(&mut self) -> Result<CliCommand, String> {
+        let mut target = None;
+        let mut project_path = None;
+        let mut depth = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--json" => json = true,
+                "--path" | "-p" => {
+                    project_path = self.current().cloned();
+                    if project_path.is_some() {
+                        self.advance();
+                    }
+                }
+                "--depth" => {
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указано значение для --depth".to_string())?;
+                    self.advance();
+                    depth = Some(
+                        value
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|d| *d > 0)
+                            .ok_or_else(|| format!("Некорректная глубина: {}", value))?,
+                    );
+                }
+                _ if target.is_none() => target = Some(arg),
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        Ok(CliCommand::Impact {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            target: target
+                .filter(|t| !t.trim().is_empty())
+                .ok_or_else(|| "Не указан файл или капсула".to_string())?,
+            depth,
+            json,
+        })
+    }
+
+    fn parse_path(&mut self) -> Result<CliCommand, String> {
+        let mut from = None;
+        let mut to = None;
+        let mut project_path = None;
+        let mut max_paths = None;
+        let mut json = false;
+
+        while let Some(arg) = self.current().cloned() {
+            self.advance();
+            match arg.as_str() {
+                "--json" => json = true,
+                "--path" | "-p" => {
+                    project_path = self.current().cloned();
+                    if project_path.is_some() {
+                        self.advance();
+                    }
+                }
+                "--max-paths" => {
+                    let value = self
+                        .current()
+                        .cloned()
+                        .ok_or_else(|| "Не указано значение для --max-paths".to_string())?;
+                    self.advance();
+                    max_paths = Some(
+                        value
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|n| *n > 0)
+                            .ok_or_else(|| format!("Некорректное число путей: {}", value))?,
+                    );
+                }
+                _ if from.is_none() => from = Some(arg),
+                _ if to.is_none() => to = Some(arg),
+                _ if project_path.is_none() => project_path = Some(arg),
+                _ => return Err(format!("Неизвестный аргумент: {}", arg)),
+            }
+        }
+
+        let named = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+        Ok(CliCommand::DependencyPath {
+            project_path: project_path.unwrap_or_else(|| {
+                crate::get_default_project_path()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            from: named(from).ok_or_else(|| "Не указана исходная капсула".to_string())?,
+            to: named(to).ok_or_else(|| "Не указана целевая капсула".to_string())?,
+            max_paths,
+            json,
         })
     }
 
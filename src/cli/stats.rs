@@ -1,3 +1,5 @@
+use crate::file_scanner::build_artifacts::{build_output, ExcludedDir};
+use crate::file_scanner::{ScanLimits, SkippedFiles};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -11,6 +13,12 @@ pub struct ProjectStats {
     pub file_types: HashMap<String, usize>,
     pub project_path: String,
     pub scanned_at: String,
+    /// Файлы кода, не прочитанные из-за размера, бинарного или минифицированного содержимого
+    #[serde(default)]
+    pub skipped_files: SkippedFiles,
+    /// Каталоги вывода сборки, исключённые по манифестам экосистем (пути от корня)
+    #[serde(default)]
+    pub auto_excluded: Vec<ExcludedDir>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -37,15 +45,26 @@ pub fn get_project_stats(project_path: &str) -> std::result::Result<ProjectStats
     let mut file_types = HashMap::new();
     let mut total_files = 0;
     let mut total_lines = 0;
+    let mut skipped_files = SkippedFiles::default();
+    let mut auto_excluded = Vec::new();
 
     let root_path = Path::new(project_path);
     scan_directory(
         root_path,
+        &ScanLimits::from_env(),
         &mut file_types,
         &mut total_files,
         &mut total_lines,
+        &mut skipped_files,
+        &mut auto_excluded,
     )
     .map_err(|e| format!("Ошибка сканирования директории: {}", e))?;
+    let relative = |path: &Path| path.strip_prefix(root_path).unwrap_or(path).to_path_buf();
+    for excluded in &mut auto_excluded {
+        excluded.path = relative(&excluded.path);
+        excluded.manifest = excluded.manifest.as_deref().map(relative);
+    }
+    auto_excluded.sort_by(|a, b| a.path.cmp(&b.path));
 
     Ok(ProjectStats {
         total_files,
@@ -53,14 +72,19 @@ pub fn get_project_stats(project_path: &str) -> std::result::Result<ProjectStats
         file_types,
         project_path: project_path.to_string(),
         scanned_at: chrono::Utc::now().to_rfc3339(),
+        skipped_files,
+        auto_excluded,
     })
 }
 
 fn scan_directory(
     dir: &Path,
+    limits: &ScanLimits,
     file_types: &mut HashMap<String, usize>,
     total_files: &mut usize,
     total_lines: &mut usize,
+    skipped: &mut SkippedFiles,
+    auto_excluded: &mut Vec<ExcludedDir>,
 ) -> std::result::Result<(), std::io::Error> {
     if dir.is_dir() {
         for entry in fs::read_dir(dir)? {
@@ -69,9 +93,22 @@ fn scan_directory(
 
             if path.is_dir() {
                 if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                    if !should_skip_directory(dir_name) {
-                        scan_directory(&path, file_types, total_files, total_lines)?;
+                    if should_skip_directory(dir_name) {
+                        continue;
+                    }
+                    if let Some(excluded) = build_output(&path) {
+                        auto_excluded.push(excluded);
+                        continue;
                     }
+                    scan_directory(
+                        &path,
+                        limits,
+                        file_types,
+                        total_files,
+                        total_lines,
+                        skipped,
+                        auto_excluded,
+                    )?;
                 }
             } else {
                 *total_files += 1;
@@ -81,8 +118,16 @@ fn scan_directory(
                     *file_types.entry(ext_lower.clone()).or_insert(0) += 1;
 
                     if is_code_file(&ext_lower) {
-                        if let Ok(content) = fs::read_to_string(&path) {
-                            *total_lines += content.lines().count();
+                        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                        if let Some(reason) = limits.check_size(size) {
+                            skipped.record(reason);
+                        } else if let Ok(bytes) = fs::read(&path) {
+                            match limits.check_content(&path, &bytes) {
+                                Some(reason) => skipped.record(reason),
+                                None => {
+                                    *total_lines += String::from_utf8_lossy(&bytes).lines().count()
+                                }
+                            }
                         }
                     }
                 }
@@ -147,7 +192,7 @@ fn scan_directory_structure(
 
         if path.is_dir() {
             if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                if !should_skip_directory(dir_name) {
+                if !should_skip_directory(dir_name) && build_output(&path).is_none() {
                     scan_directory_structure(
                         &path,
                         file_types,
@@ -227,21 +272,9 @@ pub fn determine_layer(path: &Path) -> String {
     }
 }
 
+/// Служебные каталоги; вывод сборки определяет `build_output` по манифестам
 fn should_skip_directory(dir_name: &str) -> bool {
-    matches!(
-        dir_name,
-        "node_modules"
-            | "target"
-            | ".git"
-            | ".svn"
-            | "dist"
-            | "build"
-            | ".next"
-            | ".nuxt"
-            | "coverage"
-            | "__pycache__"
-            | "backup"
-    )
+    matches!(dir_name, "node_modules" | ".git" | ".svn" | "backup")
 }
 
 fn is_code_file(ext: &str) -> bool {
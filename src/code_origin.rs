@@ -0,0 +1,537 @@
+// Происхождение кода: тестовые капсулы (каталоги `tests/`, `__tests__`,
+// `*_test.go`, `*.spec.ts`, блоки `#[cfg(test)]` и `describe(`, функции
+// `#[test]` и `def test_*`) и вспомогательные (`benches/`,
+// `examples/`, демо-приложения) помечаются, их связи отличаются от
+// производственных и не раздувают метрики основной архитектуры
+
+use crate::capsule_graph_builder::CapsuleGraphBuilder;
+use crate::types::{Capsule, CapsuleGraph, CapsuleRelation, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Ключ метаданных капсулы с происхождением кода
+pub const ORIGIN_KEY: &str = "origin";
+/// Тег тестовых капсул
+pub const TEST_TAG: &str = "test";
+/// Тег вспомогательных капсул (бенчмарки, примеры)
+pub const AUXILIARY_TAG: &str = "auxiliary";
+
+/// Каталоги, содержимое которых считается тестами
+const TEST_DIRS: [&str; 5] = ["tests", "test", "__tests__", "spec", "testdata"];
+/// Каталоги бенчмарков, примеров и демо-приложений
+const AUXILIARY_DIRS: [&str; 10] = [
+    "benches",
+    "bench",
+    "benchmarks",
+    "examples",
+    "example",
+    "samples",
+    "sample",
+    "demo",
+    "demos",
+    "playground",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeOrigin {
+    Production,
+    Test,
+    /// Бенчмарки, примеры, демо: видны в графе, но не в метриках ядра
+    Auxiliary,
+}
+
+impl CodeOrigin {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CodeOrigin::Production => "production",
+            CodeOrigin::Test => "test",
+            CodeOrigin::Auxiliary => "auxiliary",
+        }
+    }
+
+    /// Происхождение размеченной капсулы; без метки — производственный код
+    pub fn of(capsule: &Capsule) -> Self {
+        match capsule.metadata.get(ORIGIN_KEY).map(String::as_str) {
+            Some("test") => CodeOrigin::Test,
+            Some("auxiliary") => CodeOrigin::Auxiliary,
+            _ => CodeOrigin::Production,
+        }
+    }
+}
+
+/// Происхождение файла по пути относительно корня проекта
+pub fn origin_of_path(relative: &Path) -> CodeOrigin {
+    if is_test_path(relative) {
+        CodeOrigin::Test
+    } else if is_auxiliary_path(relative) {
+        CodeOrigin::Auxiliary
+    } else {
+        CodeOrigin::Production
+    }
+}
+
+/// Лежит ли файл в одном из каталогов `dirs`
+fn in_dir(relative: &Path, dirs: &[&str]) -> bool {
+    relative
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .any(|c| dirs.contains(&c.as_os_str().to_string_lossy().as_ref()))
+}
+
+/// Бенчмарк, пример или демо-приложение
+pub fn is_auxiliary_path(relative: &Path) -> bool {
+    in_dir(relative, &AUXILIARY_DIRS)
+}
+
+/// Тестовый ли файл по пути относительно корня проекта
+pub fn is_test_path(relative: &Path) -> bool {
+    if in_dir(relative, &TEST_DIRS) {
+        return true;
+    }
+    let name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let stem = name.split('.').next().unwrap_or_default();
+    // JUnit: FooTest.java, FooTests.kt
+    let original_stem = relative
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    stem.ends_with("_test")
+        || stem.ends_with("_tests")
+        || stem.ends_with("_spec")
+        || (stem.starts_with("test_") && name.ends_with(".py"))
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || ((name.ends_with(".java") || name.ends_with(".kt"))
+            && (original_stem.ends_with("Test") || original_stem.ends_with("Tests")))
+}
+
+/// Строки (с 1, включительно) блоков под `#[cfg(test)]` в исходнике Rust
+pub fn cfg_test_spans(content: &str) -> Vec<(usize, usize)> {
+    marked_block_spans(content, &["#[cfg(test)]"], false)
+}
+
+/// Атрибуты тестовых функций Rust
+const RUST_TEST_ATTRIBUTES: [&str; 4] = ["#[test]", "#[tokio::test", "#[rstest", "#[test_case"];
+/// Тестовые блоки Jest, Mocha, Vitest
+const JS_TEST_CALLS: [&str; 6] = ["describe(", "describe.", "it(", "test(", "test.", "suite("];
+/// Расширения JavaScript и TypeScript
+const JS_EXTENSIONS: [&str; 6] = ["js", "jsx", "mjs", "cjs", "ts", "tsx"];
+
+/// Строки (с 1, включительно) тестового кода внутри файла с расширением
+/// `extension`: `#[cfg(test)]` и `#[test]`-функции Rust, блоки `describe(`/`it(`
+/// в JS/TS, `def test_*` и классы `Test*` в Python, `def test_*` в Ruby
+pub fn test_block_spans(content: &str, extension: &str) -> Vec<(usize, usize)> {
+    let mut spans = match extension {
+        "rs" => {
+            let mut spans = cfg_test_spans(content);
+            spans.extend(marked_block_spans(content, &RUST_TEST_ATTRIBUTES, false));
+            spans
+        }
+        ext if JS_EXTENSIONS.contains(&ext) => marked_block_spans(content, &JS_TEST_CALLS, true),
+        "py" => indented_block_spans(content, &["def test_", "async def test_", "class Test"]),
+        "rb" => indented_block_spans(content, &["def test_"]),
+        _ => Vec::new(),
+    };
+    spans.sort_unstable();
+    spans
+}
+
+/// Блоки, открытые строкой с одним из маркеров: до парной `}` или до `;` у
+/// элемента без тела. Скобки в строковых литералах и комментариях не считаются;
+/// `single_quoted_strings` — `'...'` строка (JS), а не символ или время жизни (Rust)
+fn marked_block_spans(
+    content: &str,
+    markers: &[&str],
+    single_quoted_strings: bool,
+) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(marker) = markers
+            .iter()
+            .find(|m| lines[i].trim_start().starts_with(**m))
+        else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        let mut depth = 0i32;
+        let mut opened = false;
+        let mut end = None;
+        let mut masker = LiteralMasker::new(single_quoted_strings);
+        for (j, line) in lines.iter().enumerate().skip(i) {
+            // атрибут может стоять на одной строке с элементом
+            let code = masker.mask(if j == i {
+                line.trim_start().trim_start_matches(marker)
+            } else {
+                line
+            });
+            for ch in code.chars() {
+                match ch {
+                    '{' => {
+                        depth += 1;
+                        opened = true;
+                    }
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            // `#[cfg(test)] use ...;` — элемент без тела
+            if !opened && code.trim_end().ends_with(';') {
+                end = Some(j);
+                break;
+            }
+            if opened && depth <= 0 {
+                end = Some(j);
+                break;
+            }
+        }
+        let end = end.unwrap_or(lines.len() - 1);
+        spans.push((start + 1, end + 1));
+        i = end + 1;
+    }
+    spans
+}
+
+/// Где продолжается разбор исходника на следующей строке
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiteralState {
+    Code,
+    /// Строка с закрывающей кавычкой `'`, `"` или `` ` ``
+    Quoted(char),
+    /// Сырая строка Rust `r#"..."#` с числом `#`
+    Raw(usize),
+    /// Блочный комментарий с глубиной вложенности
+    Comment(usize),
+}
+
+/// Заменяет пробелами строковые литералы и комментарии, сохраняя код;
+/// состояние переносится между строками (многострочные строки и `/* */`)
+struct LiteralMasker {
+    state: LiteralState,
+    single_quoted_strings: bool,
+}
+
+impl LiteralMasker {
+    fn new(single_quoted_strings: bool) -> Self {
+        Self {
+            state: LiteralState::Code,
+            single_quoted_strings,
+        }
+    }
+
+    fn mask(&mut self, line: &str) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut out = String::with_capacity(line.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let ch = chars[i];
+            let next = chars.get(i + 1).copied();
+            // сколько символов поглощено литералом или комментарием
+            let skipped = match self.state {
+                LiteralState::Comment(depth) => match (ch, next) {
+                    ('*', Some('/')) => {
+                        self.state = match depth {
+                            1 => LiteralState::Code,
+                            _ => LiteralState::Comment(depth - 1),
+                        };
+                        2
+                    }
+                    ('/', Some('*')) => {
+                        self.state = LiteralState::Comment(depth + 1);
+                        2
+                    }
+                    _ => 1,
+                },
+                LiteralState::Quoted(quote) => {
+                    if ch == '\\' {
+                        2
+                    } else {
+                        if ch == quote {
+                            self.state = LiteralState::Code;
+                        }
+                        1
+                    }
+                }
+                LiteralState::Raw(hashes) => {
+                    let closes = ch == '"'
+                        && chars[i + 1..].iter().take_while(|c| **c == '#').count() >= hashes;
+                    if closes {
+                        self.state = LiteralState::Code;
+                        1 + hashes
+                    } else {
+                        1
+                    }
+                }
+                LiteralState::Code => self.literal_start(&chars, i),
+            };
+            if skipped == 0 {
+                if ch == '/' && next == Some('/') {
+                    break;
+                }
+                out.push(ch);
+                i += 1;
+            } else {
+                out.extend(std::iter::repeat_n(' ', skipped));
+                i += skipped;
+            }
+        }
+        out
+    }
+
+    /// Длина начала литерала или комментария в позиции `i` (0 — обычный код);
+    /// символьные литералы Rust пропускаются целиком
+    fn literal_start(&mut self, chars: &[char], i: usize) -> usize {
+        let ch = chars[i];
+        let next = chars.get(i + 1).copied();
+        match ch {
+            '/' if next == Some('*') => {
+                self.state = LiteralState::Comment(1);
+                2
+            }
+            '"' | '`' => {
+                self.state = LiteralState::Quoted(ch);
+                1
+            }
+            '\'' if self.single_quoted_strings => {
+                self.state = LiteralState::Quoted(ch);
+                1
+            }
+            // `'\''`, `'\u{7d}'` — до закрывающей кавычки после экранированного символа
+            '\'' if next == Some('\\') => chars
+                .iter()
+                .skip(i + 3)
+                .position(|c| *c == '\'')
+                .map_or(0, |p| p + 4),
+            // `'}'`; иначе это время жизни `'a`
+            '\'' if chars.get(i + 2) == Some(&'\'') => 3,
+            'r' if i == 0 || !(chars[i - 1].is_alphanumeric() || chars[i - 1] == '_') => {
+                let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+                if chars.get(i + 1 + hashes) == Some(&'"') {
+                    self.state = LiteralState::Raw(hashes);
+                    2 + hashes
+                } else {
+                    0
+                }
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Блоки Python/Ruby, открытые строкой с одним из префиксов: до первой
+/// непустой строки с отступом не больше, чем у заголовка (`end` Ruby входит)
+fn indented_block_spans(content: &str, prefixes: &[&str]) -> Vec<(usize, usize)> {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if !prefixes
+            .iter()
+            .any(|p| lines[i].trim_start().starts_with(p))
+        {
+            i += 1;
+            continue;
+        }
+        let header = indent(lines[i]);
+        let mut end = i;
+        let mut open_quote = None;
+        for (j, line) in lines.iter().enumerate().skip(i + 1) {
+            // строки многострочного литерала и комментарии не закрывают блок
+            let inside_literal = open_quote.is_some();
+            open_quote = triple_quote_after(line, open_quote);
+            if inside_literal {
+                end = j;
+                continue;
+            }
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if indent(line) <= header {
+                if indent(line) == header && line.trim() == "end" {
+                    end = j;
+                }
+                break;
+            }
+            end = j;
+        }
+        spans.push((i + 1, end + 1));
+        i = end + 1;
+    }
+    spans
+}
+
+/// Незакрытая к концу строки тройная кавычка Python (`"""` или `'''`)
+fn triple_quote_after(line: &str, mut open: Option<&'static str>) -> Option<&'static str> {
+    let mut rest = line;
+    loop {
+        let found = match open {
+            Some(quote) => rest.find(quote).map(|at| (at, None)),
+            None => ["\"\"\"", "'''"]
+                .into_iter()
+                .filter_map(|quote| rest.find(quote).map(|at| (at, Some(quote))))
+                .min_by_key(|(at, _)| *at),
+        };
+        let Some((at, next)) = found else {
+            return open;
+        };
+        open = next;
+        rest = &rest[at + 3..];
+    }
+}
+
+/// Размечает происхождение капсул: по пути относительно `project_root` и по
+/// тестовым блокам внутри файлов (`test_block_spans`)
+pub fn classify_capsules(project_root: &Path, capsules: &mut [Capsule]) {
+    let mut spans: HashMap<std::path::PathBuf, Vec<(usize, usize)>> = HashMap::new();
+    for capsule in capsules.iter_mut() {
+        let relative = capsule
+            .file_path
+            .strip_prefix(project_root)
+            .unwrap_or(&capsule.file_path);
+        let mut origin = origin_of_path(relative);
+        if origin == CodeOrigin::Production {
+            let file_spans = spans.entry(capsule.file_path.clone()).or_insert_with(|| {
+                let extension = capsule
+                    .file_path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or_default();
+                std::fs::read_to_string(&capsule.file_path)
+                    .map(|content| test_block_spans(&content, extension))
+                    .unwrap_or_default()
+            });
+            if file_spans
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&capsule.line_start))
+            {
+                origin = CodeOrigin::Test;
+            }
+        }
+        mark(capsule, origin);
+    }
+}
+
+fn mark(capsule: &mut Capsule, origin: CodeOrigin) {
+    capsule
+        .metadata
+        .insert(ORIGIN_KEY.to_string(), origin.as_str().to_string());
+    let tag = match origin {
+        CodeOrigin::Production => return,
+        CodeOrigin::Test => TEST_TAG,
+        CodeOrigin::Auxiliary => AUXILIARY_TAG,
+    };
+    if !capsule.tags.iter().any(|t| t == tag) {
+        capsule.tags.push(tag.to_string());
+    }
+}
+
+/// Капсулы тестового кода
+pub fn test_capsule_ids(graph: &CapsuleGraph) -> HashSet<Uuid> {
+    graph
+        .capsules
+        .values()
+        .filter(|c| CodeOrigin::of(c) == CodeOrigin::Test)
+        .map(|c| c.id)
+        .collect()
+}
+
+/// Связь исходит из тестового кода или ведёт в него
+pub fn is_test_relation(graph: &CapsuleGraph, relation: &CapsuleRelation) -> bool {
+    [relation.from_id, relation.to_id].iter().any(|id| {
+        graph
+            .capsules
+            .get(id)
+            .is_some_and(|c| CodeOrigin::of(c) == CodeOrigin::Test)
+    })
+}
+
+/// Сколько тестового кода в графе
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TestCodeSummary {
+    pub test_capsules: usize,
+    pub test_relations: usize,
+}
+
+pub fn test_code_summary(graph: &CapsuleGraph) -> TestCodeSummary {
+    TestCodeSummary {
+        test_capsules: test_capsule_ids(graph).len(),
+        test_relations: graph
+            .relations
+            .iter()
+            .filter(|r| is_test_relation(graph, r))
+            .count(),
+    }
+}
+
+/// Вспомогательные капсулы (бенчмарки, примеры) по файлу и строке
+pub fn auxiliary_capsules(graph: &CapsuleGraph) -> Vec<&Capsule> {
+    let mut capsules: Vec<&Capsule> = graph
+        .capsules
+        .values()
+        .filter(|c| CodeOrigin::of(c) == CodeOrigin::Auxiliary)
+        .collect();
+    capsules.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_start.cmp(&b.line_start))
+    });
+    capsules
+}
+
+/// Пересчитывает `graph.metrics` только по производственным капсулам и связям
+/// между ними; тестовые и вспомогательные капсулы остаются в графе
+pub fn recalculate_production_metrics(graph: &mut CapsuleGraph) -> Result<()> {
+    let non_core: HashSet<Uuid> = graph
+        .capsules
+        .values()
+        .filter(|c| CodeOrigin::of(c) != CodeOrigin::Production)
+        .map(|c| c.id)
+        .collect();
+    if non_core.is_empty() {
+        return Ok(());
+    }
+    let capsules: HashMap<Uuid, Capsule> = graph
+        .capsules
+        .iter()
+        .filter(|(id, _)| !non_core.contains(id))
+        .map(|(id, c)| (*id, c.clone()))
+        .collect();
+    let relations: Vec<CapsuleRelation> = graph
+        .relations
+        .iter()
+        .filter(|r| !non_core.contains(&r.from_id) && !non_core.contains(&r.to_id))
+        .cloned()
+        .collect();
+    graph.metrics = CapsuleGraphBuilder::new().calculate_advanced_metrics(&capsules, &relations)?;
+    Ok(())
+}
+
+/// Убирает тестовые капсулы и их связи из графа; метрики пересчитываются
+pub fn exclude_test_code(graph: &mut CapsuleGraph) -> Result<TestCodeSummary> {
+    let summary = test_code_summary(graph);
+    recalculate_production_metrics(graph)?;
+    let tests = test_capsule_ids(graph);
+    graph.capsules.retain(|id, _| !tests.contains(id));
+    graph
+        .relations
+        .retain(|r| !tests.contains(&r.from_id) && !tests.contains(&r.to_id));
+    for capsule in graph.capsules.values_mut() {
+        capsule.dependencies.retain(|id| !tests.contains(id));
+        capsule.dependents.retain(|id| !tests.contains(id));
+    }
+    for ids in graph.layers.values_mut() {
+        ids.retain(|id| !tests.contains(id));
+    }
+    graph.layers.retain(|_, ids| !ids.is_empty());
+    Ok(summary)
+}
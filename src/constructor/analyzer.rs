@@ -1,5 +1,9 @@
-use crate::types::Capsule;
+use crate::file_scanner::glob_to_regex;
+use crate::types::{AnalysisError, Capsule, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Capsule analyzer - provides analysis capabilities for capsules
 pub struct CapsuleAnalyzer;
@@ -75,6 +79,54 @@ impl CapsuleAnalyzer {
             .collect()
     }
 
+    /// Adds tags from `rules` to matching capsules; returns how many capsules
+    /// received at least one new tag. Paths are matched relative to `project_root`
+    pub fn apply_tag_rules(
+        project_root: &Path,
+        capsules: &mut [Capsule],
+        rules: &TagRules,
+    ) -> usize {
+        let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+        let mut tagged = 0;
+        for capsule in capsules.iter_mut() {
+            let relative = capsule
+                .file_path
+                .strip_prefix(project_root)
+                .unwrap_or(&capsule.file_path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let mut added = false;
+            for rule in &rules.rules {
+                if rule
+                    .glob
+                    .as_ref()
+                    .is_some_and(|glob| !glob.is_match(&relative))
+                {
+                    continue;
+                }
+                if let Some(content) = &rule.content {
+                    let source = sources
+                        .entry(capsule.file_path.clone())
+                        .or_insert_with(|| std::fs::read_to_string(&capsule.file_path).ok());
+                    let Some(source) = source else {
+                        continue;
+                    };
+                    if !content.is_match(&capsule_source(source, capsule)) {
+                        continue;
+                    }
+                }
+                for tag in &rule.tags {
+                    if !capsule.tags.contains(tag) {
+                        capsule.tags.push(tag.clone());
+                        added = true;
+                    }
+                }
+            }
+            tagged += usize::from(added);
+        }
+        tagged
+    }
+
     /// Calculates overall project metrics
     pub fn calculate_project_metrics(capsules: &[Capsule]) -> ProjectMetrics {
         let total_capsules = capsules.len();
@@ -112,3 +164,123 @@ pub struct ProjectMetrics {
     pub avg_complexity: f64,
     pub avg_quality: f64,
 }
+
+/// Tag rule: capsules whose file matches `glob` and whose own source matches
+/// `content` (both optional, at least one required) receive `tags`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glob: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// Tag rules from `.archlens/tags.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagRuleConfig {
+    #[serde(default)]
+    pub rules: Vec<TagRule>,
+}
+
+impl TagRuleConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid tag rules: {e}")))
+    }
+
+    /// `.archlens/tags.json`; `Ok(None)` when the file is absent
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(".archlens").join("tags.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+
+    /// Compiles globs and content patterns
+    pub fn compile(&self) -> Result<TagRules> {
+        let invalid = |index: usize, reason: String| {
+            AnalysisError::ParsingError(format!("Tag rule #{}: {reason}", index + 1))
+        };
+        let rules = self
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                if rule.glob.is_none() && rule.content.is_none() {
+                    return Err(invalid(i, "needs a glob or a content pattern".into()));
+                }
+                if rule.tags.iter().all(|t| t.trim().is_empty()) {
+                    return Err(invalid(i, "has no tags".into()));
+                }
+                let glob = rule
+                    .glob
+                    .as_deref()
+                    .map(|glob| {
+                        let regex = glob_to_regex(glob).map_err(|e| invalid(i, e.to_string()))?;
+                        Regex::new(&format!("^(?:{})$", regex.as_str()))
+                            .map_err(|e| invalid(i, e.to_string()))
+                    })
+                    .transpose()?;
+                let content = rule
+                    .content
+                    .as_deref()
+                    .map(|p| Regex::new(p).map_err(|e| invalid(i, e.to_string())))
+                    .transpose()?;
+                Ok(CompiledTagRule {
+                    glob,
+                    content,
+                    tags: rule
+                        .tags
+                        .iter()
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(TagRules { rules })
+    }
+}
+
+/// Compiled tag rules, applied by `CapsuleAnalyzer::apply_tag_rules`
+#[derive(Debug, Clone, Default)]
+pub struct TagRules {
+    rules: Vec<CompiledTagRule>,
+}
+
+impl TagRules {
+    /// Rules of the project; `Ok(None)` without `.archlens/tags.json`
+    pub fn for_project(project_root: &Path) -> Result<Option<Self>> {
+        TagRuleConfig::load(project_root)?
+            .map(|config| config.compile())
+            .transpose()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledTagRule {
+    glob: Option<Regex>,
+    content: Option<Regex>,
+    tags: Vec<String>,
+}
+
+/// Source lines of the capsule (the whole file if its span is unknown)
+fn capsule_source(source: &str, capsule: &Capsule) -> String {
+    if capsule.line_start == 0 || capsule.line_end < capsule.line_start {
+        return source.to_string();
+    }
+    source
+        .lines()
+        .skip(capsule.line_start - 1)
+        .take(capsule.line_end - capsule.line_start + 1)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,7 +1,7 @@
-use crate::parser_ast::ASTElement;
-use crate::types::{Capsule, CapsuleStatus, CapsuleType, Priority, Result};
+use crate::parser_ast::{ASTElement, IMPL_FOR_KEY};
+use crate::types::{stable_capsule_id, Capsule, CapsuleStatus, CapsuleType, Priority, Result};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Core capsule constructor - creates architectural capsules from AST elements
@@ -47,6 +47,9 @@ pub struct CapsuleConstructor {
     pub min_complexity_threshold: u32,
     /// Maximum allowed capsule size in lines
     pub max_capsule_size: usize,
+    /// Project root; capsule IDs hash file paths relative to it, so the same
+    /// project checked out in another directory keeps its IDs
+    pub project_root: Option<PathBuf>,
 }
 
 impl CapsuleConstructor {
@@ -59,9 +62,25 @@ impl CapsuleConstructor {
         Self {
             min_complexity_threshold: 5,
             max_capsule_size: 1000,
+            project_root: None,
         }
     }
 
+    /// Hashes file paths relative to `root` in capsule IDs
+    pub fn with_project_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.project_root = Some(root.into());
+        self
+    }
+
+    /// File path as it enters capsule IDs: relative to the project root when
+    /// the file lies under it
+    fn id_path<'a>(&self, file_path: &'a Path) -> &'a Path {
+        self.project_root
+            .as_deref()
+            .and_then(|root| file_path.strip_prefix(root).ok())
+            .unwrap_or(file_path)
+    }
+
     /// Creates capsules from a collection of AST elements
     ///
     /// This method processes each AST element and creates corresponding capsules
@@ -95,9 +114,10 @@ impl CapsuleConstructor {
         file_path: &Path,
     ) -> Result<Vec<Capsule>> {
         let mut capsules = Vec::new();
+        let ids = self.stable_ids(ast_elements, file_path);
 
         for element in ast_elements {
-            if let Some(capsule) = self.create_capsule_from_element(element, file_path)? {
+            if let Some(capsule) = self.create_capsule_from_element(element, file_path, &ids)? {
                 capsules.push(capsule);
             }
         }
@@ -105,6 +125,43 @@ impl CapsuleConstructor {
         Ok(capsules)
     }
 
+    /// Stable capsule IDs for the elements of one file, keyed by AST element ID
+    ///
+    /// The name is qualified by the owning type (`impl` target or parent element),
+    /// and repeated names of the same type get an occurrence suffix, so IDs only
+    /// change when the element itself is renamed, moved or retyped.
+    fn stable_ids(&self, ast_elements: &[ASTElement], file_path: &Path) -> HashMap<Uuid, Uuid> {
+        let names: HashMap<Uuid, &str> = ast_elements
+            .iter()
+            .map(|e| (e.id, e.name.as_str()))
+            .collect();
+        let mut seen: HashMap<(String, CapsuleType), usize> = HashMap::new();
+        ast_elements
+            .iter()
+            .map(|element| {
+                let capsule_type = self.convert_ast_type_to_capsule_type(&element.element_type);
+                let owner = element
+                    .metadata
+                    .get(IMPL_FOR_KEY)
+                    .map(String::as_str)
+                    .or_else(|| element.parent_id.and_then(|p| names.get(&p).copied()));
+                let mut name = match owner {
+                    Some(owner) => format!("{owner}::{}", element.name),
+                    None => element.name.clone(),
+                };
+                let occurrence = seen.entry((name.clone(), capsule_type)).or_default();
+                *occurrence += 1;
+                if *occurrence > 1 {
+                    name = format!("{name}#{occurrence}");
+                }
+                (
+                    element.id,
+                    stable_capsule_id(self.id_path(file_path), &name, capsule_type),
+                )
+            })
+            .collect()
+    }
+
     /// Creates a capsule from a single AST element
     ///
     /// This method applies various analysis techniques to determine if an AST element
@@ -113,6 +170,7 @@ impl CapsuleConstructor {
         &self,
         element: &ASTElement,
         file_path: &Path,
+        ids: &HashMap<Uuid, Uuid>,
     ) -> Result<Option<Capsule>> {
         // Filter elements by significance
         if !self.is_significant_element(element) {
@@ -125,9 +183,20 @@ impl CapsuleConstructor {
         let layer = self.determine_layer(file_path);
         let slogan = self.generate_slogan(element);
         let warnings = super::warnings::WarningAnalyzer::analyze_warnings(element);
+        let mut metadata = element.metadata.clone();
+        metadata.insert(
+            crate::diff_analyzer::CONTENT_HASH_KEY.to_string(),
+            crate::diff_analyzer::content_hash(&element.name, &element.content),
+        );
+        if let Some(parent_id) = element.parent_id {
+            metadata.insert(
+                crate::hierarchy::PARENT_KEY.to_string(),
+                ids.get(&parent_id).unwrap_or(&parent_id).to_string(),
+            );
+        }
 
         let capsule = Capsule {
-            id: element.id,
+            id: ids.get(&element.id).copied().unwrap_or(element.id),
             name: element.name.clone(),
             capsule_type,
             file_path: file_path.to_path_buf(),
@@ -146,7 +215,7 @@ impl CapsuleConstructor {
             status,
             priority,
             tags: vec![layer.to_lowercase()],
-            metadata: element.metadata.clone(),
+            metadata,
             quality_score: if element.complexity > 10 { 0.5 } else { 0.8 },
             slogan: Some(slogan),
             dependents: vec![],
@@ -170,7 +239,7 @@ impl CapsuleConstructor {
     ///
     /// A `Result` containing the created capsule
     pub fn create_capsule_from_node(&self, node: &ASTElement, file_path: &Path) -> Result<Capsule> {
-        let id = Uuid::new_v4();
+        let id = stable_capsule_id(self.id_path(file_path), &node.name, CapsuleType::Module);
 
         let capsule = Capsule {
             id,
@@ -4,7 +4,7 @@ pub mod core;
 pub mod optimizer;
 pub mod warnings;
 
-pub use analyzer::CapsuleAnalyzer;
+pub use analyzer::{CapsuleAnalyzer, TagRule, TagRuleConfig, TagRules};
 pub use core::CapsuleConstructor;
 pub use optimizer::CapsuleOptimizer;
 pub use warnings::WarningAnalyzer;
@@ -14,7 +14,7 @@ impl CapsuleOptimizer {
         Self::merge_small_capsules(capsules)?;
 
         // Sort by priority
-        capsules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        capsules.sort_by_key(|c| std::cmp::Reverse(c.priority));
 
         Ok(())
     }
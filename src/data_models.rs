@@ -0,0 +1,1009 @@
+// Обнаружение моделей данных (ORM-сущности, схемы БД) и ER-диаграммы
+// в форматах Mermaid erDiagram и PlantUML
+
+use crate::types::{AnalysisError, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Откуда взято описание сущности
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelSource {
+    Sql,
+    Prisma,
+    Diesel,
+    /// Rust-структуры с ORM-derive (Queryable, FromRow, DeriveEntityModel…)
+    Rust,
+    SqlAlchemy,
+    Django,
+    TypeOrm,
+    Jpa,
+}
+
+impl ModelSource {
+    pub fn key(&self) -> &'static str {
+        match self {
+            ModelSource::Sql => "sql",
+            ModelSource::Prisma => "prisma",
+            ModelSource::Diesel => "diesel",
+            ModelSource::Rust => "rust",
+            ModelSource::SqlAlchemy => "sqlalchemy",
+            ModelSource::Django => "django",
+            ModelSource::TypeOrm => "typeorm",
+            ModelSource::Jpa => "jpa",
+        }
+    }
+
+    /// Явные схемы надёжнее производных структур (NewUser, UserForm…)
+    fn rank(&self) -> u8 {
+        match self {
+            ModelSource::Rust => 0,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Cardinality {
+    ManyToOne,
+    OneToOne,
+    ManyToMany,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityField {
+    pub name: String,
+    pub data_type: String,
+    pub primary_key: bool,
+    pub nullable: bool,
+    /// Сущность, на которую ссылается поле (после разрешения имён)
+    pub references: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataEntity {
+    pub name: String,
+    pub table: String,
+    pub source: ModelSource,
+    pub file: PathBuf,
+    pub fields: Vec<EntityField>,
+}
+
+/// Связь в стиле внешнего ключа: `from.via` → `to`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EntityRelation {
+    pub from: String,
+    pub to: String,
+    pub via: String,
+    pub cardinality: Cardinality,
+}
+
+/// Обнаруженная модель данных проекта
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataModel {
+    pub entities: Vec<DataEntity>,
+    pub relations: Vec<EntityRelation>,
+}
+
+impl DataModel {
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Mermaid erDiagram
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("erDiagram\n");
+        for entity in &self.entities {
+            out.push_str(&format!("    {} {{\n", diagram_id(&entity.name)));
+            for field in &entity.fields {
+                let keys = key_markers(field);
+                out.push_str(&format!(
+                    "        {} {}{}\n",
+                    diagram_id(&field.data_type),
+                    diagram_id(&field.name),
+                    if keys.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" {}", keys.join(", "))
+                    }
+                ));
+            }
+            out.push_str("    }\n");
+        }
+        for relation in &self.relations {
+            out.push_str(&format!(
+                "    {} {} {} : \"{}\"\n",
+                diagram_id(&relation.from),
+                self.connector(relation),
+                diagram_id(&relation.to),
+                relation.via
+            ));
+        }
+        out
+    }
+
+    /// PlantUML (IE-нотация): первичные ключи над разделителем
+    pub fn to_plantuml(&self) -> String {
+        let mut out = String::from("@startuml\nhide circle\nskinparam linetype ortho\n\n");
+        for entity in &self.entities {
+            out.push_str(&format!(
+                "entity \"{}\" as {} {{\n",
+                entity.name,
+                diagram_id(&entity.name)
+            ));
+            let (keys, rest): (Vec<&EntityField>, Vec<&EntityField>) =
+                entity.fields.iter().partition(|f| f.primary_key);
+            for field in &keys {
+                out.push_str(&format!("  * {} : {}\n", field.name, plantuml_type(field)));
+            }
+            out.push_str("  --\n");
+            for field in &rest {
+                let required = if field.nullable { "" } else { "* " };
+                out.push_str(&format!(
+                    "  {}{} : {}\n",
+                    required,
+                    field.name,
+                    plantuml_type(field)
+                ));
+            }
+            out.push_str("}\n\n");
+        }
+        for relation in &self.relations {
+            out.push_str(&format!(
+                "{} {} {} : {}\n",
+                diagram_id(&relation.from),
+                self.connector(relation),
+                diagram_id(&relation.to),
+                relation.via
+            ));
+        }
+        out.push_str("@enduml\n");
+        out
+    }
+
+    /// Crow's foot: Mermaid и PlantUML понимают одинаковые обозначения
+    fn connector(&self, relation: &EntityRelation) -> &'static str {
+        let optional = self
+            .entities
+            .iter()
+            .find(|e| e.name == relation.from)
+            .and_then(|e| e.fields.iter().find(|f| f.name == relation.via))
+            .is_none_or(|f| f.nullable);
+        match (relation.cardinality, optional) {
+            (Cardinality::ManyToOne, false) => "}o--||",
+            (Cardinality::ManyToOne, true) => "}o--o|",
+            (Cardinality::OneToOne, false) => "|o--||",
+            (Cardinality::OneToOne, true) => "|o--o|",
+            (Cardinality::ManyToMany, _) => "}o--o{",
+        }
+    }
+}
+
+/// Ссылка, найденная при разборе; цель разрешается после обхода всех файлов
+#[derive(Debug, Clone)]
+struct PendingLink {
+    from: String,
+    via: String,
+    target: String,
+    cardinality: Cardinality,
+}
+
+#[derive(Debug, Default)]
+struct ParsedSource {
+    entities: Vec<DataEntity>,
+    links: Vec<PendingLink>,
+}
+
+impl ParsedSource {
+    fn link(&mut self, from: &str, via: &str, target: &str, cardinality: Cardinality) {
+        self.links.push(PendingLink {
+            from: from.to_string(),
+            via: via.to_string(),
+            target: target.to_string(),
+            cardinality,
+        });
+    }
+}
+
+/// Детектор моделей данных по исходникам и схемам проекта
+#[derive(Debug)]
+pub struct DataModelDetector {
+    root: PathBuf,
+    /// Предел файлов для обхода больших репозиториев
+    pub max_files: usize,
+}
+
+impl DataModelDetector {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            max_files: 20_000,
+        }
+    }
+
+    pub fn detect(&self) -> Result<DataModel> {
+        if !self.root.exists() {
+            return Err(AnalysisError::IoError(format!(
+                "Путь не существует: {}",
+                self.root.display()
+            )));
+        }
+        let mut files: Vec<PathBuf> = walkdir::WalkDir::new(&self.root)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                !(e.file_type().is_dir()
+                    && e.depth() > 0
+                    && matches!(
+                        name.as_ref(),
+                        "target"
+                            | "node_modules"
+                            | ".git"
+                            | "dist"
+                            | "build"
+                            | "vendor"
+                            | ".venv"
+                            | "__pycache__"
+                    ))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .take(self.max_files)
+            .collect();
+        files.sort();
+
+        let mut parsed = ParsedSource::default();
+        for file in files {
+            let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !matches!(ext, "sql" | "prisma" | "rs" | "py" | "ts" | "java") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            let relative = file.strip_prefix(&self.root).unwrap_or(&file).to_path_buf();
+            match ext {
+                "sql" => parse_sql(&relative, &content, &mut parsed),
+                "prisma" => parse_prisma(&relative, &content, &mut parsed),
+                "rs" => parse_rust(&relative, &content, &mut parsed),
+                "py" => parse_python(&relative, &content, &mut parsed),
+                _ => parse_annotated(&relative, &content, &mut parsed),
+            }
+        }
+        Ok(resolve(parsed))
+    }
+}
+
+/// Схлопывает дубликаты таблиц и превращает ссылки в связи между сущностями
+fn resolve(parsed: ParsedSource) -> DataModel {
+    let mut entities: Vec<DataEntity> = Vec::new();
+    for entity in parsed.entities {
+        let key = normalize(&entity.table);
+        match entities.iter_mut().find(|e| normalize(&e.table) == key) {
+            Some(existing) => {
+                let better = (entity.source.rank(), entity.fields.len())
+                    > (existing.source.rank(), existing.fields.len());
+                if better {
+                    *existing = entity;
+                }
+            }
+            None => entities.push(entity),
+        }
+    }
+    entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let find = |name: &str, entities: &[DataEntity]| -> Option<String> {
+        let key = normalize(name);
+        entities
+            .iter()
+            .find(|e| normalize(&e.name) == key || normalize(&e.table) == key)
+            .map(|e| e.name.clone())
+    };
+
+    let mut relations: BTreeSet<EntityRelation> = BTreeSet::new();
+    for link in &parsed.links {
+        let (Some(from), Some(to)) = (find(&link.from, &entities), find(&link.target, &entities))
+        else {
+            continue;
+        };
+        if let Some(entity) = entities.iter_mut().find(|e| e.name == from) {
+            if let Some(field) = entity.fields.iter_mut().find(|f| f.name == link.via) {
+                field.references = Some(to.clone());
+            }
+        }
+        relations.insert(EntityRelation {
+            from,
+            to,
+            via: link.via.clone(),
+            cardinality: link.cardinality,
+        });
+    }
+
+    // Поля вида author_id / authorId без явной ссылки — связь по соглашению об именах
+    let mut implicit = Vec::new();
+    for entity in &entities {
+        for field in entity.fields.iter().filter(|f| f.references.is_none()) {
+            let Some(stem) = field
+                .name
+                .strip_suffix("_id")
+                .or_else(|| field.name.strip_suffix("Id"))
+                .filter(|s| !s.is_empty())
+            else {
+                continue;
+            };
+            if let Some(to) = find(stem, &entities).filter(|to| *to != entity.name) {
+                implicit.push((entity.name.clone(), field.name.clone(), to));
+            }
+        }
+    }
+    for (from, via, to) in implicit {
+        if let Some(field) = entities
+            .iter_mut()
+            .find(|e| e.name == from)
+            .and_then(|e| e.fields.iter_mut().find(|f| f.name == via))
+        {
+            field.references = Some(to.clone());
+        }
+        relations.insert(EntityRelation {
+            from,
+            to,
+            via,
+            cardinality: Cardinality::ManyToOne,
+        });
+    }
+
+    DataModel {
+        entities,
+        relations: relations.into_iter().collect(),
+    }
+}
+
+// ============================================================================
+// SQL / PRISMA / DIESEL
+// ============================================================================
+
+fn parse_sql(file: &Path, content: &str, parsed: &mut ParsedSource) {
+    let create = Regex::new(
+        r#"(?is)CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?(?:[`"\[]?\w+[`"\]]?\.)?[`"\[]?(\w+)[`"\]]?\s*\((.*?)\)\s*;"#,
+    )
+    .unwrap();
+    let column = Regex::new(r#"^[`"\[]?(\w+)[`"\]]?\s+(\w+)"#).unwrap();
+    let references =
+        Regex::new(r#"(?i)REFERENCES\s+(?:[`"\[]?\w+[`"\]]?\.)?[`"\[]?(\w+)"#).unwrap();
+    let key_list = Regex::new(r"(?i)(PRIMARY|FOREIGN)\s+KEY\s*\(([^)]*)\)").unwrap();
+
+    for caps in create.captures_iter(content) {
+        let table = caps[1].to_string();
+        let mut entity = entity(&table, &table, ModelSource::Sql, file);
+        for part in split_top_level(&caps[2]) {
+            let upper = part.to_uppercase();
+            if let Some(keys) = key_list.captures(part) {
+                let names: Vec<String> = keys[2]
+                    .split(',')
+                    .map(|k| k.trim().trim_matches(['`', '"', '[', ']']).to_string())
+                    .collect();
+                if keys[1].eq_ignore_ascii_case("PRIMARY") {
+                    for field in entity.fields.iter_mut() {
+                        if names.contains(&field.name) {
+                            field.primary_key = true;
+                            field.nullable = false;
+                        }
+                    }
+                } else if let Some(target) = references.captures(part) {
+                    for name in &names {
+                        parsed.link(&table, name, &target[1], Cardinality::ManyToOne);
+                    }
+                }
+                continue;
+            }
+            if ["CONSTRAINT", "UNIQUE", "INDEX", "KEY ", "CHECK"]
+                .iter()
+                .any(|k| upper.starts_with(k))
+            {
+                continue;
+            }
+            let Some(col) = column.captures(part) else {
+                continue;
+            };
+            let primary_key = upper.contains("PRIMARY KEY");
+            let name = col[1].to_string();
+            if let Some(target) = references.captures(part) {
+                let cardinality = if upper.contains("UNIQUE") || primary_key {
+                    Cardinality::OneToOne
+                } else {
+                    Cardinality::ManyToOne
+                };
+                parsed.link(&table, &name, &target[1], cardinality);
+            }
+            entity.fields.push(EntityField {
+                name,
+                data_type: col[2].to_lowercase(),
+                primary_key,
+                nullable: !primary_key && !upper.contains("NOT NULL"),
+                references: None,
+            });
+        }
+        parsed.entities.push(entity);
+    }
+}
+
+fn parse_prisma(file: &Path, content: &str, parsed: &mut ParsedSource) {
+    let model = Regex::new(r"^model\s+(\w+)\s*\{").unwrap();
+    let field = Regex::new(r"^(\w+)\s+(\w+)(\[\])?(\?)?(.*)$").unwrap();
+    let relation = Regex::new(r"@relation\([^)]*fields:\s*\[([^\]]*)\]").unwrap();
+    let map = Regex::new(r#"@@map\(\s*"(\w+)""#).unwrap();
+    let compound_id = Regex::new(r"@@id\(\s*\[([^\]]*)\]").unwrap();
+
+    // Поля-навигации (author User) не являются колонками
+    let models: Vec<String> = content
+        .lines()
+        .filter_map(|l| model.captures(l.trim()).map(|c| c[1].to_string()))
+        .collect();
+
+    let first_link = parsed.links.len();
+    let mut current: Option<DataEntity> = None;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if let Some(caps) = model.captures(line) {
+            current = Some(entity(&caps[1], &caps[1], ModelSource::Prisma, file));
+            continue;
+        }
+        let Some(entity) = current.as_mut() else {
+            continue;
+        };
+        if line.starts_with('}') {
+            parsed.entities.extend(current.take());
+            continue;
+        }
+        if let Some(caps) = map.captures(line) {
+            entity.table = caps[1].to_string();
+        } else if let Some(caps) = compound_id.captures(line) {
+            let keys: Vec<&str> = caps[1].split(',').map(str::trim).collect();
+            for field in entity.fields.iter_mut() {
+                field.primary_key |= keys.contains(&field.name.as_str());
+            }
+        } else if let Some(caps) = field.captures(line) {
+            let attrs = &caps[5];
+            if models.iter().any(|m| m == &caps[2]) {
+                if let Some(rel) = relation.captures(attrs) {
+                    let name = entity.name.clone();
+                    for via in rel[1].split(',').map(str::trim) {
+                        parsed.link(&name, via, &caps[2], Cardinality::ManyToOne);
+                    }
+                }
+                continue;
+            }
+            entity.fields.push(EntityField {
+                name: caps[1].to_string(),
+                data_type: format!("{}{}", &caps[2], caps.get(3).map_or("", |m| m.as_str())),
+                primary_key: attrs.contains("@id"),
+                nullable: caps.get(4).is_some(),
+                references: None,
+            });
+        }
+    }
+
+    // @unique на колонке внешнего ключа делает связь один-к-одному
+    for link in parsed.links[first_link..].iter_mut() {
+        let unique = content.lines().any(|l| {
+            let l = l.trim();
+            l.starts_with(&format!("{} ", link.via)) && l.contains("@unique")
+        });
+        if unique {
+            link.cardinality = Cardinality::OneToOne;
+        }
+    }
+}
+
+/// Diesel `table!`/`joinable!` и структуры с ORM-derive
+fn parse_rust(file: &Path, content: &str, parsed: &mut ParsedSource) {
+    let table_header = Regex::new(r"^(?:\w+\.)?(\w+)\s*\(([^)]*)\)\s*\{").unwrap();
+    let column = Regex::new(r"^(\w+)\s*->\s*([^,]+),?").unwrap();
+    let joinable = Regex::new(r"joinable!\s*\(\s*(\w+)\s*->\s*(\w+)\s*\((\w+)\)\s*\)").unwrap();
+    let derive = Regex::new(
+        r"#\[derive\([^)]*\b(Queryable|Identifiable|Selectable|Insertable|FromRow|DeriveEntityModel)\b",
+    )
+    .unwrap();
+    let table_name = Regex::new(r#"table_name\s*=\s*"?(\w+)"?"#).unwrap();
+    let belongs_to =
+        Regex::new(r"belongs_to\(\s*(\w+)(?:\s*,\s*foreign_key\s*=\s*(\w+))?").unwrap();
+    let struct_decl = Regex::new(r"^(?:pub(?:\([^)]*\))?\s+)?struct\s+(\w+)\s*\{").unwrap();
+    let field = Regex::new(r"^(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:\s*(.+?),?$").unwrap();
+
+    for caps in joinable.captures_iter(content) {
+        parsed.link(&caps[1], &caps[3], &caps[2], Cardinality::ManyToOne);
+    }
+
+    let mut in_table_macro = false;
+    let mut table_keys: Vec<String> = Vec::new();
+    let mut current: Option<DataEntity> = None;
+    // Атрибуты, накопленные перед объявлением структуры/поля
+    let mut pending: Vec<String> = Vec::new();
+    let mut orm_struct = false;
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.starts_with("//") || line.is_empty() {
+            continue;
+        }
+
+        if line.contains("table!") && line.ends_with('{') {
+            in_table_macro = true;
+            continue;
+        }
+        if in_table_macro {
+            if let Some(entity) = current.as_mut() {
+                if line.starts_with('}') {
+                    parsed.entities.extend(current.take());
+                } else if let Some(caps) = column.captures(line) {
+                    let ty = caps[2].trim();
+                    let inner = ty
+                        .strip_prefix("Nullable<")
+                        .and_then(|t| t.strip_suffix('>'));
+                    entity.fields.push(EntityField {
+                        name: caps[1].to_string(),
+                        data_type: inner.unwrap_or(ty).to_string(),
+                        primary_key: table_keys.iter().any(|k| k == &caps[1]),
+                        nullable: inner.is_some(),
+                        references: None,
+                    });
+                }
+            } else if let Some(caps) = table_header.captures(line) {
+                table_keys = caps[2].split(',').map(|k| k.trim().to_string()).collect();
+                current = Some(entity(&caps[1], &caps[1], ModelSource::Diesel, file));
+            } else if line.starts_with('}') {
+                in_table_macro = false;
+            }
+            continue;
+        }
+
+        if line.starts_with("#[") {
+            if derive.is_match(line) {
+                orm_struct = true;
+            }
+            pending.push(line.to_string());
+            continue;
+        }
+
+        if let Some(entity) = current.as_mut() {
+            if line.starts_with('}') {
+                parsed.entities.extend(current.take());
+            } else if let Some(caps) = field.captures(line) {
+                let ty = caps[2].trim();
+                let inner = ty.strip_prefix("Option<").and_then(|t| t.strip_suffix('>'));
+                let attrs = pending.join(" ");
+                entity.fields.push(EntityField {
+                    name: caps[1].to_string(),
+                    data_type: inner.unwrap_or(ty).to_string(),
+                    primary_key: attrs.contains("primary_key") || &caps[1] == "id",
+                    nullable: inner.is_some(),
+                    references: None,
+                });
+            }
+            pending.clear();
+            continue;
+        }
+
+        if let Some(caps) = struct_decl.captures(line) {
+            if orm_struct {
+                let attrs = pending.join(" ");
+                let name = caps[1].to_string();
+                let table = table_name
+                    .captures(&attrs)
+                    .map(|c| c[1].to_string())
+                    .unwrap_or_else(|| format!("{}s", snake_case(&name)));
+                // у SeaORM структура всегда называется Model
+                let display = if name == "Model" { table.clone() } else { name };
+                for caps in belongs_to.captures_iter(&attrs) {
+                    let via = caps
+                        .get(2)
+                        .map(|m| m.as_str().to_string())
+                        .unwrap_or_else(|| format!("{}_id", snake_case(&caps[1])));
+                    parsed.link(&display, &via, &caps[1], Cardinality::ManyToOne);
+                }
+                current = Some(entity(&display, &table, ModelSource::Rust, file));
+            }
+        }
+        orm_struct = false;
+        pending.clear();
+    }
+}
+
+// ============================================================================
+// PYTHON: SQLALCHEMY / DJANGO
+// ============================================================================
+
+fn parse_python(file: &Path, content: &str, parsed: &mut ParsedSource) {
+    let class_decl = Regex::new(r"^class\s+(\w+)\s*\(([^)]*)\)\s*:").unwrap();
+    let tablename = Regex::new(r#"^__tablename__\s*=\s*["'](\w+)["']"#).unwrap();
+    let sa_field = Regex::new(
+        r"^(\w+)\s*(?::\s*Mapped\[(.+)\])?\s*=\s*(?:sa\.|db\.|orm\.)?(Column|mapped_column|relationship)\((.*)",
+    )
+    .unwrap();
+    let django_field = Regex::new(r"^(\w+)\s*=\s*models\.(\w+)\((.*)").unwrap();
+    let foreign_key = Regex::new(r#"ForeignKey\(\s*["']([\w.]+)["']"#).unwrap();
+    let first_arg = Regex::new(r#"^\s*(?:sa\.|db\.)?["']?([\w.]+)"#).unwrap();
+
+    // (класс, отступ класса, django?)
+    let mut current: Option<(DataEntity, usize, bool)> = None;
+    let flush = |current: &mut Option<(DataEntity, usize, bool)>, parsed: &mut ParsedSource| {
+        if let Some((mut entity, _, django)) = current.take() {
+            if django && !entity.fields.iter().any(|f| f.primary_key) {
+                entity.fields.insert(0, key_field("id", "AutoField"));
+            }
+            // SQLAlchemy-классы без колонок — миксины и базовые классы
+            if django || !entity.fields.is_empty() {
+                parsed.entities.push(entity);
+            }
+        }
+    };
+
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let indent = raw.len() - raw.trim_start().len();
+        if matches!(&current, Some((_, class_indent, _)) if indent <= *class_indent) {
+            flush(&mut current, parsed);
+        }
+
+        if let Some(caps) = class_decl.captures(line) {
+            if current.is_none() {
+                let bases = &caps[2];
+                let django = bases.contains("models.Model");
+                let sqlalchemy = ["Base", "db.Model", "DeclarativeBase", "SQLModel"]
+                    .iter()
+                    .any(|b| bases.split(',').any(|x| x.trim().starts_with(b)));
+                if django || sqlalchemy {
+                    let source = if django {
+                        ModelSource::Django
+                    } else {
+                        ModelSource::SqlAlchemy
+                    };
+                    let name = caps[1].to_string();
+                    let table = snake_case(&name);
+                    current = Some((entity(&name, &table, source, file), indent, django));
+                }
+            }
+            continue;
+        }
+
+        let Some((entity, _, django)) = current.as_mut() else {
+            continue;
+        };
+        if let Some(caps) = tablename.captures(line) {
+            entity.table = caps[1].to_string();
+        } else if *django {
+            let Some(caps) = django_field.captures(line) else {
+                continue;
+            };
+            let (name, kind, args) = (&caps[1], &caps[2], &caps[3]);
+            let null = args.contains("null=True");
+            let cardinality = match kind {
+                "ForeignKey" => Some(Cardinality::ManyToOne),
+                "OneToOneField" => Some(Cardinality::OneToOne),
+                "ManyToManyField" => Some(Cardinality::ManyToMany),
+                _ => None,
+            };
+            if let Some(cardinality) = cardinality {
+                let target = first_arg
+                    .captures(args)
+                    .map(|c| c[1].rsplit('.').next().unwrap_or("").to_string())
+                    .unwrap_or_default();
+                let target = if target == "self" {
+                    entity.name.clone()
+                } else {
+                    target
+                };
+                let from = entity.name.clone();
+                parsed.link(&from, name, &target, cardinality);
+                if cardinality == Cardinality::ManyToMany {
+                    continue; // отдельная таблица связей, не колонка
+                }
+            }
+            entity.fields.push(EntityField {
+                name: name.to_string(),
+                data_type: kind.to_string(),
+                primary_key: args.contains("primary_key=True"),
+                nullable: null,
+                references: None,
+            });
+        } else if let Some(caps) = sa_field.captures(line) {
+            if &caps[3] == "relationship" {
+                continue;
+            }
+            let name = caps[1].to_string();
+            let args = &caps[4];
+            let mapped = caps.get(2).map(|m| m.as_str());
+            let optional = mapped.and_then(|m| {
+                m.strip_prefix("Optional[")
+                    .and_then(|t| t.strip_suffix(']'))
+            });
+            let data_type = optional
+                .or(mapped)
+                .map(str::to_string)
+                .or_else(|| {
+                    first_arg
+                        .captures(args)
+                        .map(|c| c[1].to_string())
+                        .filter(|t| t != "ForeignKey")
+                })
+                .unwrap_or_else(|| "column".to_string());
+            if let Some(fk) = foreign_key.captures(args) {
+                let target = fk[1].split('.').next().unwrap_or("").to_string();
+                let from = entity.name.clone();
+                parsed.link(&from, &name, &target, Cardinality::ManyToOne);
+            }
+            let primary_key = args.contains("primary_key=True");
+            entity.fields.push(EntityField {
+                name,
+                data_type,
+                primary_key,
+                nullable: if args.contains("nullable=False") {
+                    false
+                } else if args.contains("nullable=True") {
+                    true
+                } else {
+                    !primary_key && (mapped.is_none() || optional.is_some())
+                },
+                references: None,
+            });
+        }
+    }
+    flush(&mut current, parsed);
+}
+
+// ============================================================================
+// TYPESCRIPT (TYPEORM) / JAVA (JPA)
+// ============================================================================
+
+/// Классы с `@Entity`: декораторы полей копятся до строки объявления поля
+fn parse_annotated(file: &Path, content: &str, parsed: &mut ParsedSource) {
+    let java = file.extension().and_then(|e| e.to_str()) == Some("java");
+    let source = if java {
+        ModelSource::Jpa
+    } else {
+        ModelSource::TypeOrm
+    };
+    let class_decl = Regex::new(r"\bclass\s+(\w+)").unwrap();
+    let table_name = Regex::new(r#"(?:name\s*[=:]\s*)?["'](\w+)["']"#).unwrap();
+    let ts_field = Regex::new(
+        r"^(?:(?:public|private|protected|readonly|declare)\s+)*(\w+)([?!])?\s*:\s*([^;=]+?)\s*(?:=[^;]*)?;",
+    )
+    .unwrap();
+    let java_field = Regex::new(
+        r"^(?:(?:private|protected|public|final)\s+)*([\w<>, ?]+?)\s+(\w+)\s*(?:=[^;]*)?;",
+    )
+    .unwrap();
+    let arrow_target = Regex::new(r"=>\s*(\w+)").unwrap();
+
+    let mut entity_pending = false;
+    let mut table: Option<String> = None;
+    let mut current: Option<(DataEntity, i32)> = None;
+    let mut decorators: Vec<String> = Vec::new();
+    let mut depth = 0;
+    for raw in content.lines() {
+        let line = raw.trim();
+        let opens = line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        if line.starts_with("//") || line.starts_with('*') || line.starts_with("/*") {
+            continue;
+        }
+
+        if current.is_none() {
+            if line.starts_with("@Entity") {
+                entity_pending = true;
+                table = table_name.captures(line).map(|c| c[1].to_string());
+            } else if entity_pending && line.starts_with("@Table") {
+                table = table_name.captures(line).map(|c| c[1].to_string());
+            } else if entity_pending {
+                if let Some(caps) = class_decl.captures(line) {
+                    let name = caps[1].to_string();
+                    let table = table.take().unwrap_or_else(|| snake_case(&name));
+                    current = Some((entity(&name, &table, source, file), depth));
+                    entity_pending = false;
+                }
+            }
+            depth += opens;
+            continue;
+        }
+
+        let (entity, class_depth) = current.as_mut().unwrap();
+        if depth == *class_depth + 1 {
+            if line.starts_with('@') {
+                decorators.push(line.to_string());
+            } else if !line.contains('(') {
+                let parsed_field = if java {
+                    java_field
+                        .captures(line)
+                        .map(|c| (c[2].to_string(), c[1].trim().to_string(), false))
+                } else {
+                    ts_field.captures(line).map(|c| {
+                        (
+                            c[1].to_string(),
+                            c[3].trim().to_string(),
+                            c.get(2).is_some_and(|m| m.as_str() == "?"),
+                        )
+                    })
+                };
+                if let Some((name, ty, optional)) = parsed_field {
+                    let attrs = decorators.join(" ");
+                    let cardinality = if attrs.contains("@ManyToOne") {
+                        Some(Cardinality::ManyToOne)
+                    } else if attrs.contains("@OneToOne") {
+                        Some(Cardinality::OneToOne)
+                    } else if attrs.contains("@ManyToMany") {
+                        Some(Cardinality::ManyToMany)
+                    } else {
+                        None
+                    };
+                    // обратные стороны связей и непостоянные поля не являются колонками
+                    let skip = attrs.contains("@OneToMany")
+                        || attrs.contains("@Transient")
+                        || line.contains(" static ")
+                        || line.starts_with("static ");
+                    if !skip {
+                        let target = arrow_target
+                            .captures(&attrs)
+                            .map(|c| c[1].to_string())
+                            .unwrap_or_else(|| element_type(&ty));
+                        if let Some(cardinality) = cardinality {
+                            let from = entity.name.clone();
+                            parsed.link(&from, &name, &target, cardinality);
+                        }
+                        if cardinality != Some(Cardinality::ManyToMany) {
+                            entity.fields.push(EntityField {
+                                name,
+                                data_type: ty,
+                                primary_key: attrs.contains("PrimaryGeneratedColumn")
+                                    || attrs.contains("@PrimaryColumn")
+                                    || attrs.contains("@Id")
+                                    || attrs.contains("@EmbeddedId"),
+                                nullable: optional
+                                    || attrs.contains("nullable: true")
+                                    || attrs.contains("nullable = true")
+                                    || (cardinality.is_some()
+                                        && !attrs.contains("nullable: false")
+                                        && !attrs.contains("optional = false")),
+                                references: None,
+                            });
+                        }
+                    }
+                }
+                decorators.clear();
+            } else {
+                decorators.clear();
+            }
+        }
+        depth += opens;
+        if depth <= *class_depth {
+            parsed.entities.extend(current.take().map(|(e, _)| e));
+        }
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+fn entity(name: &str, table: &str, source: ModelSource, file: &Path) -> DataEntity {
+    DataEntity {
+        name: name.to_string(),
+        table: table.to_string(),
+        source,
+        file: file.to_path_buf(),
+        fields: Vec::new(),
+    }
+}
+
+fn key_field(name: &str, data_type: &str) -> EntityField {
+    EntityField {
+        name: name.to_string(),
+        data_type: data_type.to_string(),
+        primary_key: true,
+        nullable: false,
+        references: None,
+    }
+}
+
+/// Части определения таблицы, разделённые запятыми вне скобок
+fn split_top_level(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, ch) in body.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(body[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(body[start..].trim());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// `List<Tag>` / `Tag[]` / `Set<Tag>` → `Tag`
+fn element_type(ty: &str) -> String {
+    let ty = ty.trim().trim_end_matches("[]");
+    match (ty.find('<'), ty.rfind('>')) {
+        (Some(start), Some(end)) if start < end => ty[start + 1..end].trim().to_string(),
+        _ => ty.to_string(),
+    }
+}
+
+/// Имена сравниваются без регистра, подчёркиваний и окончания множественного числа
+fn normalize(name: &str) -> String {
+    let lower: String = name
+        .chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect();
+    if let Some(stem) = lower.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if lower.ends_with("ss") {
+        lower
+    } else {
+        lower.strip_suffix('s').map(str::to_string).unwrap_or(lower)
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn key_markers(field: &EntityField) -> Vec<&'static str> {
+    let mut keys = Vec::new();
+    if field.primary_key {
+        keys.push("PK");
+    }
+    if field.references.is_some() {
+        keys.push("FK");
+    }
+    keys
+}
+
+fn plantuml_type(field: &EntityField) -> String {
+    let mut ty = field.data_type.clone();
+    if field.references.is_some() {
+        ty.push_str(" <<FK>>");
+    }
+    ty
+}
+
+/// Идентификатор для диаграмм: только буквы, цифры и `_`
+fn diagram_id(name: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let id = id.trim_matches('_');
+    if id.is_empty() {
+        "unknown".to_string()
+    } else {
+        id.to_string()
+    }
+}
@@ -0,0 +1,286 @@
+// Связь архитектурных решений (ADR) с кодом: в `.archlens/decisions.json` решения
+// привязываются к капсулам и связям (запрещённые и обязательные зависимости).
+// При diff двух версий сообщается, когда изменения противоречат решению:
+// запрещённая зависимость появилась, обязательная пропала, связанная капсула удалена
+
+use crate::file_scanner::glob_to_regex;
+use crate::types::{AnalysisError, Capsule, CapsuleGraph, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Связь между капсулами, на которую ссылается решение.
+/// Селекторы — имя капсулы или glob пути файла (`src/domain/**`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeRule {
+    pub from: String,
+    pub to: String,
+}
+
+/// Решение и элементы кода, на которые оно влияет
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionLink {
+    /// Путь к файлу ADR относительно корня проекта
+    pub adr: String,
+    /// Заголовок; по умолчанию — первый заголовок файла ADR
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Капсулы, реализующие решение
+    #[serde(default)]
+    pub capsules: Vec<String>,
+    /// Зависимости, которые решение запрещает
+    #[serde(default)]
+    pub forbid: Vec<EdgeRule>,
+    /// Зависимости, которые решение предписывает
+    #[serde(default)]
+    pub require: Vec<EdgeRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionConfig {
+    #[serde(default)]
+    pub decisions: Vec<DecisionLink>,
+}
+
+impl DecisionConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid decisions config: {e}")))
+    }
+
+    /// `.archlens/decisions.json`; `Ok(None)`, если файла нет
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(".archlens").join("decisions.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    ForbiddenDependencyAdded,
+    RequiredDependencyRemoved,
+    LinkedCapsuleRemoved,
+}
+
+/// Изменение, противоречащее решению
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DecisionConflict {
+    pub adr: String,
+    pub title: String,
+    pub kind: ConflictKind,
+    /// `путь#Имя` капсулы (для связей — источник)
+    pub from: String,
+    /// `путь#Имя` цели связи
+    pub to: Option<String>,
+}
+
+impl DecisionConflict {
+    pub fn message(&self) -> String {
+        let to = self.to.as_deref().unwrap_or_default();
+        match self.kind {
+            ConflictKind::ForbiddenDependencyAdded => format!(
+                "forbidden dependency `{}` -> `{}` introduced",
+                self.from, to
+            ),
+            ConflictKind::RequiredDependencyRemoved => {
+                format!("required dependency `{}` -> `{}` removed", self.from, to)
+            }
+            ConflictKind::LinkedCapsuleRemoved => {
+                format!("linked component `{}` removed or renamed", self.from)
+            }
+        }
+    }
+}
+
+/// Сторона сравнения: граф и корень дерева, относительно которого берутся пути
+#[derive(Debug, Clone, Copy)]
+pub struct DecisionSide<'a> {
+    pub graph: &'a CapsuleGraph,
+    pub root: &'a Path,
+}
+
+/// Проверка изменений на соответствие решениям
+#[derive(Debug)]
+pub struct DecisionChecker {
+    root: PathBuf,
+    config: DecisionConfig,
+}
+
+impl DecisionChecker {
+    /// `root` — корень проекта, относительно которого указаны файлы ADR
+    pub fn new(root: &Path, config: DecisionConfig) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config,
+        }
+    }
+
+    pub fn decisions(&self) -> &[DecisionLink] {
+        &self.config.decisions
+    }
+
+    /// Конфликты изменений `previous` → `current` с решениями
+    pub fn check(
+        &self,
+        previous: DecisionSide<'_>,
+        current: DecisionSide<'_>,
+    ) -> Result<Vec<DecisionConflict>> {
+        let mut conflicts = BTreeSet::new();
+        for decision in &self.config.decisions {
+            let title = self.title(decision);
+            let conflict = |kind, from: String, to: Option<String>| DecisionConflict {
+                adr: decision.adr.clone(),
+                title: title.clone(),
+                kind,
+                from,
+                to,
+            };
+
+            for rule in &decision.forbid {
+                let before = edges(previous, rule)?;
+                for (from, to) in edges(current, rule)?.difference(&before) {
+                    conflicts.insert(conflict(
+                        ConflictKind::ForbiddenDependencyAdded,
+                        from.clone(),
+                        Some(to.clone()),
+                    ));
+                }
+            }
+            for rule in &decision.require {
+                let after = edges(current, rule)?;
+                for (from, to) in edges(previous, rule)?.difference(&after) {
+                    conflicts.insert(conflict(
+                        ConflictKind::RequiredDependencyRemoved,
+                        from.clone(),
+                        Some(to.clone()),
+                    ));
+                }
+            }
+            for selector in &decision.capsules {
+                let selector = Selector::parse(selector)?;
+                let after = matching(current, &selector);
+                for key in matching(previous, &selector).difference(&after) {
+                    conflicts.insert(conflict(
+                        ConflictKind::LinkedCapsuleRemoved,
+                        key.clone(),
+                        None,
+                    ));
+                }
+            }
+        }
+        Ok(conflicts.into_iter().collect())
+    }
+
+    /// Заголовок решения: из конфигурации, первый `# ` файла ADR или имя файла
+    fn title(&self, decision: &DecisionLink) -> String {
+        if let Some(title) = decision.title.as_ref().filter(|t| !t.trim().is_empty()) {
+            return title.clone();
+        }
+        fs::read_to_string(self.root.join(&decision.adr))
+            .ok()
+            .and_then(|content| {
+                content
+                    .lines()
+                    .find_map(|l| l.strip_prefix("# ").map(|t| t.trim().to_string()))
+            })
+            .unwrap_or_else(|| {
+                Path::new(&decision.adr)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| decision.adr.clone())
+            })
+    }
+}
+
+/// Селектор капсул: glob пути (если есть `/`, `*` или `.`) или имя капсулы
+enum Selector {
+    Path(Regex),
+    Name(String),
+}
+
+impl Selector {
+    fn parse(selector: &str) -> Result<Self> {
+        let selector = selector.trim();
+        if selector.contains(['/', '*', '?', '.']) {
+            let regex = glob_to_regex(selector)?;
+            return Ok(Self::Path(Regex::new(&format!(
+                "^(?:{})$",
+                regex.as_str()
+            ))?));
+        }
+        Ok(Self::Name(selector.to_string()))
+    }
+
+    fn matches(&self, capsule: &Capsule, relative: &str) -> bool {
+        match self {
+            Self::Path(regex) => regex.is_match(relative),
+            Self::Name(name) => capsule.name == *name,
+        }
+    }
+}
+
+fn relative(side: DecisionSide<'_>, capsule: &Capsule) -> String {
+    capsule
+        .file_path
+        .strip_prefix(side.root)
+        .unwrap_or(&capsule.file_path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn key(side: DecisionSide<'_>, capsule: &Capsule) -> String {
+    format!("{}#{}", relative(side, capsule), capsule.name)
+}
+
+fn matching(side: DecisionSide<'_>, selector: &Selector) -> BTreeSet<String> {
+    side.graph
+        .capsules
+        .values()
+        .filter(|c| selector.matches(c, &relative(side, c)))
+        .map(|c| key(side, c))
+        .collect()
+}
+
+/// Связи стороны между капсулами, подходящими под селекторы правила
+fn edges(side: DecisionSide<'_>, rule: &EdgeRule) -> Result<BTreeSet<(String, String)>> {
+    let from = Selector::parse(&rule.from)?;
+    let to = Selector::parse(&rule.to)?;
+    Ok(side
+        .graph
+        .relations
+        .iter()
+        .filter_map(|r| {
+            let a = side.graph.capsules.get(&r.from_id)?;
+            let b = side.graph.capsules.get(&r.to_id)?;
+            (from.matches(a, &relative(side, a)) && to.matches(b, &relative(side, b)))
+                .then(|| (key(side, a), key(side, b)))
+        })
+        .collect())
+}
+
+/// Раздел markdown-отчёта diff
+pub fn conflicts_markdown(conflicts: &[DecisionConflict]) -> String {
+    let mut md = String::from("\n### Decision conflicts\n\n");
+    if conflicts.is_empty() {
+        md.push_str("_No changes contradict linked architecture decisions._\n");
+        return md;
+    }
+    for conflict in conflicts {
+        md.push_str(&format!(
+            "- **{}** ([{}]({})): {}\n",
+            conflict.title,
+            conflict.adr,
+            conflict.adr,
+            conflict.message()
+        ));
+    }
+    md
+}
@@ -0,0 +1,487 @@
+// Свежесть зависимостей: сравнение закреплённых версий из манифестов
+// с локальным кэшем реестров (Cargo registry cache + снимок реестров)
+
+use crate::types::{AnalysisError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Экосистема пакетов, для которой распознаётся манифест
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Pypi,
+}
+
+impl Ecosystem {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Pypi => "pypi",
+        }
+    }
+}
+
+/// Зависимость, объявленная в манифесте
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    pub name: String,
+    pub requirement: String,
+    pub ecosystem: Ecosystem,
+    pub manifest: PathBuf,
+}
+
+/// Устаревшая зависимость: требование не допускает последнюю известную версию
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutdatedDependency {
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    pub requirement: String,
+    pub latest: String,
+    pub manifest: PathBuf,
+}
+
+/// Итог проверки свежести зависимостей проекта
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FreshnessReport {
+    pub manifests: Vec<PathBuf>,
+    pub total_dependencies: usize,
+    /// Зависимости, для которых известна последняя версия
+    pub checked: usize,
+    pub outdated: Vec<OutdatedDependency>,
+}
+
+impl FreshnessReport {
+    pub fn outdated_count(&self) -> usize {
+        self.outdated.len()
+    }
+
+    /// Компактный блок для scorecard (ai_summary_json)
+    pub fn to_summary_json(&self) -> serde_json::Value {
+        let top: Vec<serde_json::Value> = self
+            .outdated
+            .iter()
+            .take(10)
+            .map(|d| {
+                serde_json::json!({
+                    "name": d.name,
+                    "ecosystem": d.ecosystem,
+                    "requirement": d.requirement,
+                    "latest": d.latest
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "total": self.total_dependencies,
+            "checked": self.checked,
+            "outdated": self.outdated_count(),
+            "top_outdated": top
+        })
+    }
+
+    /// Markdown-секция для ai_compact
+    pub fn to_compact_section(&self) -> String {
+        let mut out = String::from("## Dependencies\n");
+        out.push_str(&format!(
+            "- Declared: {} (checked: {})\n- Outdated: {}\n",
+            self.total_dependencies,
+            self.checked,
+            self.outdated_count()
+        ));
+        for d in self.outdated.iter().take(10) {
+            out.push_str(&format!(
+                "- {} [{}] {} -> {}\n",
+                d.name,
+                d.ecosystem.key(),
+                d.requirement,
+                d.latest
+            ));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Анализатор свежести зависимостей.
+///
+/// Работает офлайн: последние версии берутся из снимка реестров
+/// (`.archlens/registry.json` в проекте или файл из `ARCHLENS_REGISTRY_SNAPSHOT`)
+/// и из локального кэша Cargo (`$CARGO_HOME/registry/cache`). Снимок можно
+/// обновлять внешним инструментом, если сетевой доступ разрешён.
+#[derive(Debug)]
+pub struct DependencyFreshnessAnalyzer {
+    latest: HashMap<Ecosystem, HashMap<String, String>>,
+    use_cargo_cache: bool,
+}
+
+impl DependencyFreshnessAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            latest: HashMap::new(),
+            use_cargo_cache: true,
+        }
+    }
+
+    /// Явно заданные последние версии (приоритетнее кэшей)
+    pub fn with_latest_versions(
+        mut self,
+        ecosystem: Ecosystem,
+        versions: HashMap<String, String>,
+    ) -> Self {
+        self.latest.entry(ecosystem).or_default().extend(versions);
+        self
+    }
+
+    /// Отключает чтение локального кэша Cargo (детерминированные тесты)
+    pub fn without_cargo_cache(mut self) -> Self {
+        self.use_cargo_cache = false;
+        self
+    }
+
+    /// Полный анализ проекта: манифесты → требования → сравнение с реестром
+    pub fn analyze_project(&self, project_root: &Path) -> Result<FreshnessReport> {
+        if !project_root.exists() {
+            return Err(AnalysisError::IoError(format!(
+                "Путь не существует: {}",
+                project_root.display()
+            )));
+        }
+        let manifests = find_manifests(project_root);
+        let mut declared = Vec::new();
+        for manifest in &manifests {
+            declared.extend(parse_manifest(manifest));
+        }
+
+        let mut latest = self.load_snapshot(project_root);
+        for (eco, versions) in &self.latest {
+            latest.entry(*eco).or_default().extend(versions.clone());
+        }
+        let cargo_cache =
+            if self.use_cargo_cache && declared.iter().any(|d| d.ecosystem == Ecosystem::Cargo) {
+                scan_cargo_registry_cache()
+            } else {
+                HashMap::new()
+            };
+
+        let mut report = FreshnessReport {
+            manifests,
+            total_dependencies: declared.len(),
+            ..Default::default()
+        };
+        for dep in declared {
+            let known = latest
+                .get(&dep.ecosystem)
+                .and_then(|m| m.get(&dep.name))
+                .cloned()
+                .or_else(|| {
+                    if dep.ecosystem == Ecosystem::Cargo {
+                        cargo_cache.get(&dep.name).cloned()
+                    } else {
+                        None
+                    }
+                });
+            let Some(latest_version) = known else {
+                continue;
+            };
+            report.checked += 1;
+            if is_outdated(dep.ecosystem, &dep.requirement, &latest_version) {
+                report.outdated.push(OutdatedDependency {
+                    name: dep.name,
+                    ecosystem: dep.ecosystem,
+                    requirement: dep.requirement,
+                    latest: latest_version,
+                    manifest: dep.manifest,
+                });
+            }
+        }
+        report.outdated.sort_by(|a, b| {
+            a.ecosystem
+                .key()
+                .cmp(b.ecosystem.key())
+                .then(a.name.cmp(&b.name))
+        });
+        Ok(report)
+    }
+
+    fn load_snapshot(&self, project_root: &Path) -> HashMap<Ecosystem, HashMap<String, String>> {
+        let path = std::env::var("ARCHLENS_REGISTRY_SNAPSHOT")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| project_root.join(".archlens").join("registry.json"));
+        let Ok(text) = fs::read_to_string(&path) else {
+            return HashMap::new();
+        };
+        serde_json::from_str(&text).unwrap_or_default()
+    }
+}
+
+impl Default for DependencyFreshnessAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Поиск манифестов в проекте (без build-артефактов и vendor-каталогов)
+pub fn find_manifests(project_root: &Path) -> Vec<PathBuf> {
+    let mut manifests: Vec<PathBuf> = walkdir::WalkDir::new(project_root)
+        .max_depth(4)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !(e.file_type().is_dir()
+                && e.depth() > 0
+                && matches!(
+                    name.as_ref(),
+                    "target" | "node_modules" | ".git" | "dist" | "build" | "vendor" | ".venv"
+                ))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            matches!(
+                e.file_name().to_string_lossy().as_ref(),
+                "Cargo.toml" | "package.json" | "requirements.txt"
+            )
+        })
+        .map(|e| e.into_path())
+        .collect();
+    manifests.sort();
+    manifests
+}
+
+/// Разбор манифеста в список объявленных зависимостей
+pub fn parse_manifest(path: &Path) -> Vec<DeclaredDependency> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let pairs = match file_name.as_str() {
+        "Cargo.toml" => parse_cargo_toml(&content),
+        "package.json" => parse_package_json(&content),
+        "requirements.txt" => parse_requirements_txt(&content),
+        _ => Vec::new(),
+    };
+    let ecosystem = match file_name.as_str() {
+        "Cargo.toml" => Ecosystem::Cargo,
+        "package.json" => Ecosystem::Npm,
+        _ => Ecosystem::Pypi,
+    };
+    pairs
+        .into_iter()
+        .map(|(name, requirement)| DeclaredDependency {
+            name,
+            requirement,
+            ecosystem,
+            manifest: path.to_path_buf(),
+        })
+        .collect()
+}
+
+/// Упрощённый разбор секций зависимостей Cargo.toml (без path/git без версии)
+fn parse_cargo_toml(content: &str) -> Vec<(String, String)> {
+    let mut deps = Vec::new();
+    let mut in_deps = false;
+    for raw in content.lines() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.starts_with('[') {
+            let section = line.trim_matches(|c| c == '[' || c == ']');
+            in_deps = section.ends_with("dependencies") && !section.contains("dependencies.");
+            continue;
+        }
+        if !in_deps || line.is_empty() {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().trim_matches('"').to_string();
+        let value = value.trim();
+        let version = if value.starts_with('"') {
+            Some(value.trim_matches('"').to_string())
+        } else {
+            extract_inline_version(value)
+        };
+        if let Some(v) = version {
+            deps.push((name, v));
+        }
+    }
+    deps
+}
+
+fn extract_inline_version(value: &str) -> Option<String> {
+    let idx = value.find("version")?;
+    let rest = value[idx + "version".len()..]
+        .trim_start()
+        .strip_prefix('=')?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    rest.split('"').next().map(|s| s.to_string())
+}
+
+fn parse_package_json(content: &str) -> Vec<(String, String)> {
+    let Ok(v) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let mut deps = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        if let Some(map) = v.get(section).and_then(|m| m.as_object()) {
+            for (name, req) in map {
+                if let Some(req) = req.as_str() {
+                    deps.push((name.clone(), req.to_string()));
+                }
+            }
+        }
+    }
+    deps
+}
+
+fn parse_requirements_txt(content: &str) -> Vec<(String, String)> {
+    let mut deps = Vec::new();
+    for raw in content.lines() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with('-') {
+            continue;
+        }
+        let Some(pos) = line.find(['=', '>', '<', '~', '!']) else {
+            continue;
+        };
+        let name = line[..pos].split('[').next().unwrap_or("").trim();
+        if name.is_empty() {
+            continue;
+        }
+        deps.push((name.to_lowercase(), line[pos..].trim().to_string()));
+    }
+    deps
+}
+
+/// Последние версии крейтов, скачанных в локальный кэш Cargo
+fn scan_cargo_registry_cache() -> HashMap<String, String> {
+    let cargo_home = std::env::var("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|h| PathBuf::from(h).join(".cargo")));
+    let Ok(cargo_home) = cargo_home else {
+        return HashMap::new();
+    };
+    let mut latest: HashMap<String, String> = HashMap::new();
+    let Ok(indexes) = fs::read_dir(cargo_home.join("registry").join("cache")) else {
+        return latest;
+    };
+    for index in indexes.flatten() {
+        let Ok(entries) = fs::read_dir(index.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file = entry.file_name().to_string_lossy().to_string();
+            let Some(stem) = file.strip_suffix(".crate") else {
+                continue;
+            };
+            let Some((name, version)) = split_crate_file_stem(stem) else {
+                continue;
+            };
+            // pre-release версии не считаются «последними»
+            if version.contains('-') {
+                continue;
+            }
+            let newer = latest
+                .get(name)
+                .map(|cur| parse_version(version) > parse_version(cur))
+                .unwrap_or(true);
+            if newer {
+                latest.insert(name.to_string(), version.to_string());
+            }
+        }
+    }
+    latest
+}
+
+fn split_crate_file_stem(stem: &str) -> Option<(&str, &str)> {
+    stem.match_indices('-')
+        .map(|(i, _)| i)
+        .find(|&i| {
+            stem[i + 1..]
+                .chars()
+                .next()
+                .map(|c| c.is_ascii_digit())
+                .unwrap_or(false)
+        })
+        .map(|i| (&stem[..i], &stem[i + 1..]))
+}
+
+/// Разбор версии в (major, minor, patch); недостающие части = 0
+pub fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    let core = text.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or("");
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().map(|p| p.ok()).unwrap_or(Some(0))?;
+    let patch = parts.next().map(|p| p.ok()).unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+/// Допускает ли требование последнюю версию (семантика зависит от экосистемы)
+pub fn is_outdated(ecosystem: Ecosystem, requirement: &str, latest: &str) -> bool {
+    let Some(latest) = parse_version(latest) else {
+        return false;
+    };
+    let req = requirement.split(',').next().unwrap_or("").trim();
+    let (op, version) = split_operator(req);
+    let Some(base) = parse_version(version) else {
+        return false;
+    };
+    if latest <= base {
+        return false;
+    }
+    let caret_compatible = match base {
+        (0, 0, _) => latest == base,
+        (0, minor, _) => latest.0 == 0 && latest.1 == minor,
+        (major, _, _) => latest.0 == major,
+    };
+    match op {
+        ">=" | ">" | "*" => false,
+        "~" | "~=" => latest.0 != base.0 || latest.1 != base.1,
+        "=" | "==" => true,
+        "^" => !caret_compatible,
+        // Без оператора: Cargo трактует как ^, npm и pip — как точную версию
+        _ => match ecosystem {
+            Ecosystem::Cargo => !caret_compatible,
+            Ecosystem::Npm | Ecosystem::Pypi => true,
+        },
+    }
+}
+
+fn split_operator(req: &str) -> (&str, &str) {
+    for op in ["==", ">=", "~=", "^", "~", "=", ">", "*"] {
+        if let Some(rest) = req.strip_prefix(op) {
+            return (op, rest.trim());
+        }
+    }
+    ("", req)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caret_and_exact_requirements() {
+        assert!(!is_outdated(Ecosystem::Cargo, "1.0", "1.9.3"));
+        assert!(is_outdated(Ecosystem::Cargo, "0.20", "0.22.1"));
+        assert!(is_outdated(Ecosystem::Npm, "4.17.0", "4.17.21"));
+        assert!(!is_outdated(Ecosystem::Npm, "^4.17.0", "4.17.21"));
+        assert!(is_outdated(Ecosystem::Pypi, "==2.0.0", "2.1.0"));
+        assert!(!is_outdated(Ecosystem::Pypi, ">=2.0", "3.0.0"));
+    }
+
+    #[test]
+    fn splits_crate_file_names() {
+        assert_eq!(
+            split_crate_file_stem("tree-sitter-rust-0.20.4"),
+            Some(("tree-sitter-rust", "0.20.4"))
+        );
+    }
+}
@@ -0,0 +1,288 @@
+// Стабильная раскладка диаграмм: позиции узлов кэшируются по отпечатку графа,
+// и при небольших изменениях графа существующие узлы остаются на своих местах
+
+use crate::stable_hash::Fnv;
+use crate::types::{AnalysisError, Capsule, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Шаг сетки раскладки
+pub const LAYOUT_CELL_WIDTH: u32 = 240;
+pub const LAYOUT_CELL_HEIGHT: u32 = 50;
+/// Отступ сверху под заголовок диаграммы
+pub const LAYOUT_TOP: u32 = 120;
+pub const LAYOUT_LEFT: u32 = 20;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodePosition {
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Раскладка: позиции узлов по стабильному ключу (файл + имя капсулы)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagramLayout {
+    pub fingerprint: String,
+    pub created_at: String,
+    /// Колонка (x) каждого слоя
+    pub columns: BTreeMap<String, u32>,
+    pub nodes: BTreeMap<String, NodePosition>,
+}
+
+impl DiagramLayout {
+    pub fn width(&self) -> u32 {
+        self.nodes.values().map(|p| p.x).max().unwrap_or(0) + LAYOUT_CELL_WIDTH + LAYOUT_LEFT
+    }
+
+    pub fn height(&self) -> u32 {
+        self.nodes.values().map(|p| p.y).max().unwrap_or(LAYOUT_TOP) + LAYOUT_CELL_HEIGHT * 2
+    }
+
+    fn keys(&self) -> BTreeSet<&str> {
+        self.nodes.keys().map(String::as_str).collect()
+    }
+}
+
+/// Строит раскладку: колонки по слоям, строки по ключу узла
+#[derive(Debug, Default)]
+pub struct LayoutPlanner {
+    root: Option<PathBuf>,
+}
+
+impl LayoutPlanner {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    /// Ключи узлов — относительно корня проекта, чтобы кэш переживал перенос репозитория
+    pub fn with_root(mut self, root: &Path) -> Self {
+        self.root = Some(root.to_path_buf());
+        self
+    }
+
+    pub fn node_key(&self, capsule: &Capsule) -> String {
+        let path = self
+            .root
+            .as_deref()
+            .and_then(|root| capsule.file_path.strip_prefix(root).ok())
+            .unwrap_or(&capsule.file_path);
+        format!(
+            "{}#{}",
+            path.to_string_lossy().replace('\\', "/"),
+            capsule.name
+        )
+    }
+
+    /// Отпечаток структуры графа: набор узлов и связей (без метрик)
+    pub fn fingerprint(&self, graph: &CapsuleGraph) -> String {
+        let keys: BTreeSet<String> = graph.capsules.values().map(|c| self.node_key(c)).collect();
+        let edges: BTreeSet<(String, String)> = graph
+            .relations
+            .iter()
+            .filter_map(|r| {
+                let from = graph.capsules.get(&r.from_id)?;
+                let to = graph.capsules.get(&r.to_id)?;
+                Some((self.node_key(from), self.node_key(to)))
+            })
+            .collect();
+        let mut hasher = Fnv::new();
+        for key in &keys {
+            hasher.write_str(key);
+        }
+        for (from, to) in &edges {
+            hasher.write_str(from);
+            hasher.write_str(to);
+        }
+        hasher.hex()
+    }
+
+    /// Новая раскладка; узлы, уже бывшие в `previous`, сохраняют позиции,
+    /// новые занимают первую свободную ячейку колонки своего слоя
+    pub fn compute(&self, graph: &CapsuleGraph, previous: Option<&DiagramLayout>) -> DiagramLayout {
+        let mut by_layer: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            by_layer
+                .entry(layer_of(capsule))
+                .or_default()
+                .push(self.node_key(capsule));
+        }
+        for keys in by_layer.values_mut() {
+            keys.sort();
+            keys.dedup();
+        }
+
+        let mut columns: BTreeMap<String, u32> = BTreeMap::new();
+        let mut nodes: BTreeMap<String, NodePosition> = BTreeMap::new();
+        if let Some(previous) = previous {
+            for keys in by_layer.values() {
+                for key in keys {
+                    if let Some(position) = previous.nodes.get(key) {
+                        nodes.insert(key.clone(), *position);
+                    }
+                }
+            }
+            for layer in by_layer.keys() {
+                if let Some(x) = previous.columns.get(layer) {
+                    columns.insert(layer.clone(), *x);
+                }
+            }
+        }
+        // слои без колонки — правее существующих
+        let mut next_x = columns
+            .values()
+            .chain(nodes.values().map(|p| &p.x))
+            .max()
+            .map(|x| x + LAYOUT_CELL_WIDTH)
+            .unwrap_or(LAYOUT_LEFT);
+        for layer in by_layer.keys() {
+            if !columns.contains_key(layer) {
+                columns.insert(layer.clone(), next_x);
+                next_x += LAYOUT_CELL_WIDTH;
+            }
+        }
+
+        let mut occupied: BTreeSet<NodePosition> = nodes.values().copied().collect();
+        for (layer, keys) in &by_layer {
+            let x = columns[layer];
+            let mut y = LAYOUT_TOP;
+            let fresh: Vec<&String> = keys.iter().filter(|k| !nodes.contains_key(*k)).collect();
+            for key in fresh {
+                while occupied.contains(&NodePosition { x, y }) {
+                    y += LAYOUT_CELL_HEIGHT;
+                }
+                let position = NodePosition { x, y };
+                occupied.insert(position);
+                nodes.insert(key.clone(), position);
+            }
+        }
+        columns.retain(|layer, _| by_layer.contains_key(layer));
+
+        DiagramLayout {
+            fingerprint: self.fingerprint(graph),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            columns,
+            nodes,
+        }
+    }
+
+    /// Позиции капсул графа по раскладке
+    pub fn positions(
+        &self,
+        graph: &CapsuleGraph,
+        layout: &DiagramLayout,
+    ) -> HashMap<Uuid, NodePosition> {
+        graph
+            .capsules
+            .values()
+            .filter_map(|c| Some((c.id, *layout.nodes.get(&self.node_key(c))?)))
+            .collect()
+    }
+}
+
+/// Кэш раскладок на диске: `<dir>/<fingerprint>.json`
+#[derive(Debug)]
+pub struct LayoutCache {
+    dir: PathBuf,
+    /// Сколько раскладок хранить; старые удаляются
+    pub max_entries: usize,
+}
+
+impl LayoutCache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            max_entries: 20,
+        }
+    }
+
+    /// `.archlens/layouts` в корне проекта
+    pub fn for_project(root: &Path) -> Self {
+        Self::new(&root.join(".archlens").join("layouts"))
+    }
+
+    pub fn load(&self, fingerprint: &str) -> Option<DiagramLayout> {
+        let content = fs::read_to_string(self.dir.join(format!("{}.json", fingerprint))).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Раскладка с наибольшим пересечением узлов (Жаккар); при равенстве — свежая
+    pub fn closest(&self, keys: &BTreeSet<String>) -> Option<DiagramLayout> {
+        self.entries()
+            .into_iter()
+            .filter_map(|layout| {
+                let (shared, union) = {
+                    let cached = layout.keys();
+                    let shared = keys.iter().filter(|k| cached.contains(k.as_str())).count();
+                    (shared, keys.len() + cached.len() - shared)
+                };
+                (shared > 0).then(|| (shared as f64 / union as f64, layout))
+            })
+            .max_by(|(a, la), (b, lb)| {
+                a.partial_cmp(b)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| la.created_at.cmp(&lb.created_at))
+            })
+            .map(|(_, layout)| layout)
+    }
+
+    pub fn store(&self, layout: &DiagramLayout) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        let json = serde_json::to_string_pretty(layout)
+            .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))?;
+        fs::write(self.dir.join(format!("{}.json", layout.fingerprint)), json)
+            .map_err(|e| AnalysisError::IoError(e.to_string()))?;
+
+        let mut entries = self.entries();
+        if entries.len() > self.max_entries {
+            entries.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            for stale in &entries[..entries.len() - self.max_entries] {
+                let _ = fs::remove_file(self.dir.join(format!("{}.json", stale.fingerprint)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Раскладка для графа: из кэша по отпечатку, иначе новая поверх ближайшей
+    /// кэшированной (сохраняется в кэш)
+    pub fn layout_for(
+        &self,
+        planner: &LayoutPlanner,
+        graph: &CapsuleGraph,
+    ) -> Result<DiagramLayout> {
+        let fingerprint = planner.fingerprint(graph);
+        if let Some(layout) = self.load(&fingerprint) {
+            return Ok(layout);
+        }
+        let keys: BTreeSet<String> = graph
+            .capsules
+            .values()
+            .map(|c| planner.node_key(c))
+            .collect();
+        let previous = self.closest(&keys);
+        let layout = planner.compute(graph, previous.as_ref());
+        self.store(&layout)?;
+        Ok(layout)
+    }
+
+    fn entries(&self) -> Vec<DiagramLayout> {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|e| fs::read_to_string(e.path()).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect()
+    }
+}
+
+fn layer_of(capsule: &Capsule) -> String {
+    capsule
+        .layer
+        .clone()
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| "Other".to_string())
+}
@@ -1,14 +1,64 @@
 // Анализатор изменений архитектуры между версиями
 
+use crate::stable_hash::Fnv;
 use crate::types::Result;
 use crate::types::*;
 use std::collections::{HashMap, HashSet};
-// use uuid::Uuid;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Ключ метаданных капсулы: отпечаток содержимого без учёта имени и пробелов
+pub const CONTENT_HASH_KEY: &str = "content_hash";
+
+/// Отпечаток содержимого элемента (FNV-1a): не зависит от имени и форматирования,
+/// поэтому совпадает у перенесённого или переименованного без изменений кода
+pub fn content_hash(name: &str, content: &str) -> String {
+    let normalized = if name.is_empty() {
+        content.to_string()
+    } else {
+        replace_identifier(content, name, "_")
+    };
+    let mut hasher = Fnv::new();
+    for word in normalized.split_whitespace() {
+        hasher.write(word.as_bytes());
+        hasher.write(b" ");
+    }
+    hasher.hex()
+}
+
+/// Замена идентификатора целиком: `id` в `idle` или `valid` не трогается
+fn replace_identifier(content: &str, name: &str, replacement: &str) -> String {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(content.len());
+    let mut token_start: Option<usize> = None;
+    for (i, c) in content
+        .char_indices()
+        .chain(std::iter::once((content.len(), ' ')))
+    {
+        if is_ident(c) && i < content.len() {
+            token_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = token_start.take() {
+            let token = &content[start..i];
+            out.push_str(if token == name { replacement } else { token });
+        }
+        if i < content.len() {
+            out.push(c);
+        }
+    }
+    out
+}
 
 /// Анализатор diff между версиями архитектуры
 #[derive(Debug)]
 pub struct DiffAnalyzer {
     change_threshold: f32,
+    /// Минимальное сходство пары удалённый/добавленный для переноса или переименования
+    rename_similarity_threshold: f32,
+    /// Корни деревьев версий: пути капсул сравниваются относительно них
+    previous_root: Option<PathBuf>,
+    current_root: Option<PathBuf>,
     impact_calculator: ImpactCalculator,
 }
 
@@ -27,10 +77,20 @@ impl DiffAnalyzer {
     pub fn new() -> Self {
         Self {
             change_threshold: 0.1,
+            rename_similarity_threshold: 0.75,
+            previous_root: None,
+            current_root: None,
             impact_calculator: ImpactCalculator::new(),
         }
     }
 
+    /// Сравнивать пути капсул относительно корней версий (разные checkout'ы)
+    pub fn with_roots(mut self, previous: &Path, current: &Path) -> Self {
+        self.previous_root = Some(previous.to_path_buf());
+        self.current_root = Some(current.to_path_buf());
+        self
+    }
+
     /// Основной метод для анализа различий между двумя состояниями архитектуры
     pub fn analyze_diff(
         &self,
@@ -39,11 +99,11 @@ impl DiffAnalyzer {
     ) -> Result<DiffAnalysis> {
         let mut changes = Vec::new();
 
-        // Анализ изменений в компонентах
-        self.analyze_component_changes(current, previous, &mut changes)?;
+        // Анализ изменений в компонентах (с учётом переносов и переименований)
+        let renamed = self.analyze_component_changes(current, previous, &mut changes)?;
 
         // Анализ изменений в связях
-        self.analyze_relation_changes(current, previous, &mut changes)?;
+        self.analyze_relation_changes(current, previous, &renamed, &mut changes)?;
 
         // Расчет различий в метриках
         let metrics_diff = self.calculate_metrics_diff(current, previous)?;
@@ -67,67 +127,196 @@ impl DiffAnalyzer {
         })
     }
 
-    /// Анализ изменений компонентов
+    /// Анализ изменений компонентов.
+    /// Возвращает соответствие id капсулы прежней версии → имя в текущей для
+    /// перенесённых/переименованных капсул.
     fn analyze_component_changes(
         &self,
         current: &CapsuleGraph,
         previous: &CapsuleGraph,
         changes: &mut Vec<ArchitectureChange>,
-    ) -> Result<()> {
-        let current_components: HashMap<String, &Capsule> = current
+    ) -> Result<HashMap<Uuid, String>> {
+        // Идентичность компонента: (путь относительно корня версии, имя)
+        let current_components: HashMap<(String, String), &Capsule> = current
             .capsules
             .values()
-            .map(|c| (c.name.clone(), c))
+            .map(|c| (self.identity(c, self.current_root.as_deref()), c))
             .collect();
 
-        let previous_components: HashMap<String, &Capsule> = previous
+        let previous_components: HashMap<(String, String), &Capsule> = previous
             .capsules
             .values()
-            .map(|c| (c.name.clone(), c))
+            .map(|c| (self.identity(c, self.previous_root.as_deref()), c))
             .collect();
 
-        // Найти добавленные компоненты
-        for (name, capsule) in &current_components {
-            if !previous_components.contains_key(name) {
-                changes.push(ArchitectureChange {
-                    change_type: ChangeType::Added,
-                    component: name.clone(),
-                    description: format!(
-                        "Добавлен новый компонент '{}' типа {:?}",
-                        name, capsule.capsule_type
+        let mut added: Vec<&Capsule> = current_components
+            .iter()
+            .filter(|(key, _)| !previous_components.contains_key(*key))
+            .map(|(_, c)| *c)
+            .collect();
+        let mut removed: Vec<&Capsule> = previous_components
+            .iter()
+            .filter(|(key, _)| !current_components.contains_key(*key))
+            .map(|(_, c)| *c)
+            .collect();
+        added.sort_by(|a, b| (&a.file_path, &a.name).cmp(&(&b.file_path, &b.name)));
+        removed.sort_by(|a, b| (&a.file_path, &a.name).cmp(&(&b.file_path, &b.name)));
+
+        // Перенос/переименование вместо пары «удалён + добавлен»
+        let pairs = self.match_relocated(&removed, &added);
+        let paired_prev: HashSet<Uuid> = pairs.iter().map(|(p, _)| p.id).collect();
+        let paired_curr: HashSet<Uuid> = pairs.iter().map(|(_, c)| c.id).collect();
+        let mut renamed = HashMap::new();
+        for (prev, curr) in &pairs {
+            let from = self.relative_path(prev, self.previous_root.as_deref());
+            let to = self.relative_path(curr, self.current_root.as_deref());
+            let (change_type, description) = if prev.name == curr.name {
+                (
+                    ChangeType::Moved,
+                    format!(
+                        "Компонент '{}' перенесен из '{}' в '{}'",
+                        curr.name, from, to
                     ),
-                    impact: self.impact_calculator.calculate_add_impact(capsule),
-                    related_components: self.find_related_components(capsule, current),
-                });
-            }
+                )
+            } else if from == to {
+                (
+                    ChangeType::Renamed,
+                    format!("Компонент '{}' переименован в '{}'", prev.name, curr.name),
+                )
+            } else {
+                (
+                    ChangeType::Renamed,
+                    format!(
+                        "Компонент '{}' переименован в '{}' и перенесен из '{}' в '{}'",
+                        prev.name, curr.name, from, to
+                    ),
+                )
+            };
+            changes.push(ArchitectureChange {
+                change_type,
+                component: curr.name.clone(),
+                description,
+                impact: ChangeImpact::Refactoring,
+                related_components: vec![prev.name.clone()],
+            });
+            self.analyze_component_modifications(curr, prev, changes)?;
+            renamed.insert(prev.id, curr.name.clone());
+        }
+
+        // Найти добавленные компоненты
+        for capsule in added.iter().filter(|c| !paired_curr.contains(&c.id)) {
+            changes.push(ArchitectureChange {
+                change_type: ChangeType::Added,
+                component: capsule.name.clone(),
+                description: format!(
+                    "Добавлен новый компонент '{}' типа {:?}",
+                    capsule.name, capsule.capsule_type
+                ),
+                impact: self.impact_calculator.calculate_add_impact(capsule),
+                related_components: self.find_related_components(capsule, current),
+            });
         }
 
         // Найти удаленные компоненты
-        for (name, capsule) in &previous_components {
-            if !current_components.contains_key(name) {
-                changes.push(ArchitectureChange {
-                    change_type: ChangeType::Removed,
-                    component: name.clone(),
-                    description: format!(
-                        "Удален компонент '{}' типа {:?}",
-                        name, capsule.capsule_type
-                    ),
-                    impact: self
-                        .impact_calculator
-                        .calculate_remove_impact(capsule, previous),
-                    related_components: self.find_related_components(capsule, previous),
-                });
-            }
+        for capsule in removed.iter().filter(|c| !paired_prev.contains(&c.id)) {
+            changes.push(ArchitectureChange {
+                change_type: ChangeType::Removed,
+                component: capsule.name.clone(),
+                description: format!(
+                    "Удален компонент '{}' типа {:?}",
+                    capsule.name, capsule.capsule_type
+                ),
+                impact: self
+                    .impact_calculator
+                    .calculate_remove_impact(capsule, previous),
+                related_components: self.find_related_components(capsule, previous),
+            });
         }
 
         // Найти измененные компоненты
-        for (name, current_capsule) in &current_components {
-            if let Some(previous_capsule) = previous_components.get(name) {
+        for (key, current_capsule) in &current_components {
+            if let Some(previous_capsule) = previous_components.get(key) {
                 self.analyze_component_modifications(current_capsule, previous_capsule, changes)?;
             }
         }
 
-        Ok(())
+        Ok(renamed)
+    }
+
+    /// Пары (удалён, добавлен): сначала по однозначному совпадению отпечатка
+    /// содержимого, затем жадно по убыванию сходства выше порога
+    fn match_relocated<'a>(
+        &self,
+        removed: &[&'a Capsule],
+        added: &[&'a Capsule],
+    ) -> Vec<(&'a Capsule, &'a Capsule)> {
+        let mut pairs = Vec::new();
+        let mut used_prev: HashSet<Uuid> = HashSet::new();
+        let mut used_curr: HashSet<Uuid> = HashSet::new();
+
+        // Отпечаток учитывается, только если он уникален с обеих сторон
+        let by_hash = |capsules: &[&'a Capsule]| {
+            let mut map: HashMap<(CapsuleType, String), Vec<&'a Capsule>> = HashMap::new();
+            for c in capsules {
+                if let Some(hash) = c.metadata.get(CONTENT_HASH_KEY) {
+                    map.entry((c.capsule_type, hash.clone()))
+                        .or_default()
+                        .push(*c);
+                }
+            }
+            map
+        };
+        let prev_hashes = by_hash(removed);
+        let curr_hashes = by_hash(added);
+        for prev in removed {
+            let Some(hash) = prev.metadata.get(CONTENT_HASH_KEY) else {
+                continue;
+            };
+            let key = (prev.capsule_type, hash.clone());
+            if let (Some([_]), Some([curr])) = (
+                prev_hashes.get(&key).map(Vec::as_slice),
+                curr_hashes.get(&key).map(Vec::as_slice),
+            ) {
+                used_prev.insert(prev.id);
+                used_curr.insert(curr.id);
+                pairs.push((*prev, *curr));
+            }
+        }
+
+        let mut candidates: Vec<(f32, &'a Capsule, &'a Capsule)> = Vec::new();
+        for prev in removed.iter().filter(|c| !used_prev.contains(&c.id)) {
+            for curr in added.iter().filter(|c| !used_curr.contains(&c.id)) {
+                let score = similarity(prev, curr);
+                if score >= self.rename_similarity_threshold {
+                    candidates.push((score, *prev, *curr));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| (&a.1.file_path, &a.1.name).cmp(&(&b.1.file_path, &b.1.name)))
+                .then_with(|| (&a.2.file_path, &a.2.name).cmp(&(&b.2.file_path, &b.2.name)))
+        });
+        for (_, prev, curr) in candidates {
+            if used_prev.contains(&prev.id) || used_curr.contains(&curr.id) {
+                continue;
+            }
+            used_prev.insert(prev.id);
+            used_curr.insert(curr.id);
+            pairs.push((prev, curr));
+        }
+        pairs
+    }
+
+    fn relative_path(&self, capsule: &Capsule, root: Option<&Path>) -> String {
+        let path = root
+            .and_then(|r| capsule.file_path.strip_prefix(r).ok())
+            .unwrap_or(&capsule.file_path);
+        path.to_string_lossy().replace('\\', "/")
+    }
+
+    fn identity(&self, capsule: &Capsule, root: Option<&Path>) -> (String, String) {
+        (self.relative_path(capsule, root), capsule.name.clone())
     }
 
     /// Анализ модификаций компонента
@@ -203,6 +392,7 @@ impl DiffAnalyzer {
         &self,
         current: &CapsuleGraph,
         previous: &CapsuleGraph,
+        renamed: &HashMap<Uuid, String>,
         changes: &mut Vec<ArchitectureChange>,
     ) -> Result<()> {
         let current_relations: HashSet<(String, String)> = current
@@ -215,14 +405,17 @@ impl DiffAnalyzer {
             })
             .collect();
 
+        // Связи переименованных капсул сравниваются под новыми именами
+        let previous_name = |id: &Uuid| {
+            renamed
+                .get(id)
+                .cloned()
+                .or_else(|| previous.capsules.get(id).map(|c| c.name.clone()))
+        };
         let previous_relations: HashSet<(String, String)> = previous
             .relations
             .iter()
-            .filter_map(|r| {
-                let from_name = previous.capsules.get(&r.from_id)?.name.clone();
-                let to_name = previous.capsules.get(&r.to_id)?.name.clone();
-                Some((from_name, to_name))
-            })
+            .filter_map(|r| Some((previous_name(&r.from_id)?, previous_name(&r.to_id)?)))
             .collect();
 
         // Новые зависимости
@@ -423,15 +616,21 @@ impl DiffAnalyzer {
                 )
             })
             .count();
+        let relocated_components = changes
+            .iter()
+            .filter(|c| matches!(c.change_type, ChangeType::Moved | ChangeType::Renamed))
+            .count();
 
         let summary = format!(
-            "Обнаружено {} изменений: {} добавлено, {} удалено, {} изменено. \
+            "Обнаружено {} изменений: {} добавлено, {} удалено, {} изменено, \
+            {} перенесено/переименовано. \
             Сложность: {:+.1}, Связанность: {:+.2}, Сплоченность: {:+.2}. \
             Тренд качества: {:?}.",
             total_changes,
             added_components,
             removed_components,
             modified_components,
+            relocated_components,
             metrics_diff.complexity_delta,
             metrics_diff.coupling_delta,
             metrics_diff.cohesion_delta,
@@ -459,6 +658,61 @@ impl DiffAnalyzer {
     }
 }
 
+/// Сходство удалённой и добавленной капсулы одного типа (0.0–1.0):
+/// имя, размер, сложность и имя файла
+fn similarity(previous: &Capsule, current: &Capsule) -> f32 {
+    if previous.capsule_type != current.capsule_type {
+        return 0.0;
+    }
+    let ratio = |a: f32, b: f32| {
+        if a.max(b) <= 0.0 {
+            1.0
+        } else {
+            a.min(b) / a.max(b)
+        }
+    };
+    let file_name = |c: &Capsule| c.file_path.file_name().map(|n| n.to_ascii_lowercase());
+    let same_file_name = file_name(previous) == file_name(current);
+    0.45 * name_similarity(&previous.name, &current.name)
+        + 0.2 * ratio(previous.size as f32, current.size as f32)
+        + 0.15
+            * ratio(
+                previous.complexity as f32 + 1.0,
+                current.complexity as f32 + 1.0,
+            )
+        + if same_file_name { 0.2 } else { 0.0 }
+}
+
+/// Сходство имён по словам (`parse_file` / `parseFile` / `ParseFile` — одно и то же)
+fn name_similarity(a: &str, b: &str) -> f32 {
+    fn words(name: &str) -> HashSet<String> {
+        let mut words = HashSet::new();
+        let mut word = String::new();
+        for ch in name.chars() {
+            let boundary = !ch.is_alphanumeric() || ch.is_uppercase();
+            if boundary && !word.is_empty() {
+                words.insert(std::mem::take(&mut word));
+            }
+            if ch.is_alphanumeric() {
+                word.extend(ch.to_lowercase());
+            }
+        }
+        if !word.is_empty() {
+            words.insert(word);
+        }
+        words
+    }
+    if a == b {
+        return 1.0;
+    }
+    let (wa, wb) = (words(a), words(b));
+    let union = wa.union(&wb).count();
+    if union == 0 {
+        return 0.0;
+    }
+    wa.intersection(&wb).count() as f32 / union as f32
+}
+
 impl ImpactCalculator {
     fn new() -> Self {
         Self {
@@ -0,0 +1,244 @@
+// Матрица структурных зависимостей (DSM): слои × слои и модули × модули.
+// Строка — кто зависит, столбец — от кого; в ячейке число связей. Ячейки,
+// чьи строка и столбец лежат в одном цикле зависимостей, помечаются
+
+use crate::remediation_backlog::csv_field;
+use crate::types::CapsuleGraph;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Группа без слоя
+const UNASSIGNED_LAYER: &str = "Unassigned";
+/// Модуль файлов в корне проекта
+const ROOT_MODULE: &str = ".";
+
+/// Уровень агрегации матрицы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DsmLevel {
+    Layer,
+    Module,
+}
+
+impl DsmLevel {
+    fn title(self) -> &'static str {
+        match self {
+            DsmLevel::Layer => "Layers",
+            DsmLevel::Module => "Modules",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyMatrix {
+    pub level: DsmLevel,
+    /// Имена строк и столбцов в одном порядке
+    pub labels: Vec<String>,
+    /// `cells[i][j]` — число связей из `labels[i]` в `labels[j]`
+    pub cells: Vec<Vec<usize>>,
+    /// `cyclic[i][j]` — `i` и `j` взаимно достижимы (`i != j`)
+    pub cyclic: Vec<Vec<bool>>,
+}
+
+impl DependencyMatrix {
+    pub fn build(graph: &CapsuleGraph, level: DsmLevel) -> Self {
+        let group_of: HashMap<_, String> = match level {
+            DsmLevel::Layer => graph
+                .capsules
+                .values()
+                .map(|c| {
+                    let layer = c.layer.as_deref().unwrap_or(UNASSIGNED_LAYER);
+                    (c.id, layer.to_string())
+                })
+                .collect(),
+            DsmLevel::Module => {
+                let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+                graph
+                    .capsules
+                    .values()
+                    .map(|c| (c.id, module_of(&c.file_path, &root)))
+                    .collect()
+            }
+        };
+
+        let labels: Vec<String> = group_of
+            .values()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index: HashMap<&str, usize> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_str(), i))
+            .collect();
+
+        let n = labels.len();
+        let mut cells = vec![vec![0; n]; n];
+        for relation in &graph.relations {
+            let (Some(from), Some(to)) = (
+                group_of.get(&relation.from_id),
+                group_of.get(&relation.to_id),
+            ) else {
+                continue;
+            };
+            cells[index[from.as_str()]][index[to.as_str()]] += 1;
+        }
+
+        // транзитивное замыкание (Уоршелл): групп немного
+        let mut reach: Vec<Vec<bool>> = cells
+            .iter()
+            .map(|row| row.iter().map(|&count| count > 0).collect())
+            .collect();
+        for k in 0..n {
+            let via = reach[k].clone();
+            for row in reach.iter_mut().filter(|row| row[k]) {
+                for (cell, &through) in row.iter_mut().zip(&via) {
+                    *cell |= through;
+                }
+            }
+        }
+        let cyclic = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| i != j && reach[i][j] && reach[j][i])
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            level,
+            labels,
+            cells,
+            cyclic,
+        }
+    }
+
+    /// Группы, входящие хотя бы в один цикл
+    pub fn cyclic_labels(&self) -> Vec<&str> {
+        self.labels
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.cyclic[*i].iter().any(|&c| c))
+            .map(|(_, label)| label.as_str())
+            .collect()
+    }
+
+    /// CSV: первая строка — заголовки столбцов, затем строка на группу.
+    /// Ячейка в цикле помечается `*`, например `3*`
+    pub fn to_csv(&self) -> String {
+        let mut header = vec![csv_field(self.level.title())];
+        header.extend(self.labels.iter().map(|l| csv_field(l)));
+        let mut csv = header.join(",");
+        csv.push('\n');
+        for (i, label) in self.labels.iter().enumerate() {
+            let mut row = vec![csv_field(label)];
+            for j in 0..self.labels.len() {
+                row.push(match (self.cells[i][j], self.cyclic[i][j]) {
+                    (0, _) => String::new(),
+                    (count, true) => format!("{count}*"),
+                    (count, false) => count.to_string(),
+                });
+            }
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// HTML-таблица (фрагмент без `<html>`), ячейки циклов с классом `cycle`
+    pub fn to_html_table(&self) -> String {
+        let mut html = format!(
+            "<table class=\"dsm\">\n<caption>{}</caption>\n<tr><th></th>",
+            self.level.title()
+        );
+        for (j, label) in self.labels.iter().enumerate() {
+            html.push_str(&format!(
+                "<th title=\"{}\">{}</th>",
+                escape_html(label),
+                j + 1
+            ));
+        }
+        html.push_str("</tr>\n");
+        for (i, label) in self.labels.iter().enumerate() {
+            html.push_str(&format!("<tr><th>{}. {}</th>", i + 1, escape_html(label)));
+            for j in 0..self.labels.len() {
+                let mut classes = Vec::new();
+                if i == j {
+                    classes.push("diagonal");
+                }
+                if self.cyclic[i][j] && self.cells[i][j] > 0 {
+                    classes.push("cycle");
+                }
+                let class = if classes.is_empty() {
+                    String::new()
+                } else {
+                    format!(" class=\"{}\"", classes.join(" "))
+                };
+                let value = match self.cells[i][j] {
+                    0 => String::new(),
+                    count => count.to_string(),
+                };
+                html.push_str(&format!("<td{class}>{value}</td>"));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        html
+    }
+}
+
+/// HTML-страница с матрицами слоёв и модулей
+pub fn dsm_html(graph: &CapsuleGraph) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Dependency Structure Matrix</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         table.dsm { border-collapse: collapse; margin-bottom: 2em; }\n\
+         table.dsm caption { font-weight: bold; text-align: left; padding: 0.5em 0; }\n\
+         table.dsm th, table.dsm td { border: 1px solid #ccc; padding: 4px 8px; text-align: center; }\n\
+         table.dsm tr th:first-child { text-align: left; }\n\
+         table.dsm td.diagonal { background: #eee; }\n\
+         table.dsm td.cycle { background: #f8d7da; color: #842029; font-weight: bold; }\n\
+         </style>\n</head>\n<body>\n<h1>Dependency Structure Matrix</h1>\n\
+         <p>Row depends on column; highlighted cells are part of a dependency cycle.</p>\n",
+    );
+    for level in [DsmLevel::Layer, DsmLevel::Module] {
+        html.push_str(&DependencyMatrix::build(graph, level).to_html_table());
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Общий каталог всех файлов графа
+pub(crate) fn common_root<'a>(mut files: impl Iterator<Item = &'a Path>) -> PathBuf {
+    let Some(first) = files.next() else {
+        return PathBuf::new();
+    };
+    let mut root = first.parent().unwrap_or(Path::new("")).to_path_buf();
+    for file in files {
+        while !file.starts_with(&root) {
+            if !root.pop() {
+                break;
+            }
+        }
+    }
+    root
+}
+
+/// Модуль — каталог файла относительно общего корня
+pub(crate) fn module_of(file: &Path, root: &Path) -> String {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    if relative.as_os_str().is_empty() {
+        ROOT_MODULE.to_string()
+    } else {
+        relative.to_string_lossy().replace('\\', "/")
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,8 +1,26 @@
-// Модуль обнаружения запахов кода
+// Модуль обнаружения запахов кода. Правила (шаблоны, пороги, исключения)
+// описаны данными: встроенный набор в `smell_rules.json`, проект может
+// переопределить его в `.archlens/smells.json` без правок кода
 
+use crate::parser_ast::{ASTElementType, BodyMetrics, ParserAST};
 use crate::types::*;
 use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Встроенный набор правил
+const BUILTIN_RULES: &str = include_str!("smell_rules.json");
+
+/// Пороги для PHP: длина метода (операторов) и число параметров
+pub const PHP_MAX_METHOD_LINES: usize = 30;
+pub const PHP_MAX_PARAMETERS: usize = 5;
+/// Пороги для Ruby по умолчанию RuboCop (Metrics/MethodLength, Metrics/ParameterLists);
+/// длина метода — в операторах
+pub const RUBY_MAX_METHOD_LINES: usize = 10;
+pub const RUBY_MAX_PARAMETERS: usize = 5;
 
 /// Обнаружитель запахов кода
 #[derive(Debug, Clone)]
@@ -16,7 +34,8 @@ pub struct CodeSmell {
 }
 
 /// Типы запахов кода
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CodeSmellType {
     LongMethod,
     LongParameterList,
@@ -47,19 +66,184 @@ pub enum CodeSmellType {
 /// Детектор запахов кода
 pub struct CodeSmellDetector {
     smell_rules: HashMap<CodeSmellType, Vec<SmellRule>>,
+    /// Диапазоны функций для длинных методов берутся из разбора, а не из regex
+    parser: Mutex<ParserAST>,
 }
 
 /// Правило для обнаружения запаха кода
 #[derive(Debug, Clone)]
 pub struct SmellRule {
     pub name: String,
-    pub pattern: Regex,
+    /// Для функций первая группа захвата — имя функции
+    pub pattern: Option<Regex>,
     pub threshold: Option<f32>,
+    /// Строки, совпавшие с этим шаблоном, не проверяются
+    pub exclude: Option<Regex>,
+    /// Значения, которые не считаются запахом (например, допустимые числа)
+    pub allow: Vec<String>,
+    /// Фрагменты строки, которые затираются перед поиском (индексы, версии)
+    pub mask: Vec<Regex>,
+    /// Исключения строк для отдельных языков (`rust`, `python`, ...)
+    pub language_exclude: HashMap<String, Regex>,
+    /// Не проверять тестовые файлы и блоки `#[cfg(test)]`
+    pub skip_tests: bool,
     pub severity: Priority,
     pub description: String,
     pub suggestion: String,
 }
 
+impl SmellRule {
+    /// Строка исключена общим или языковым шаблоном
+    fn excludes(&self, line: &str, language: &str) -> bool {
+        self.exclude.as_ref().is_some_and(|e| e.is_match(line))
+            || self
+                .language_exclude
+                .get(language)
+                .is_some_and(|e| e.is_match(line))
+    }
+}
+
+/// Где находится проверяемый код: язык и тестовые участки
+struct SmellContext {
+    path: PathBuf,
+    file_type: FileType,
+    test_file: bool,
+    /// Строки (с 1, включительно) тестовых блоков внутри файла
+    test_spans: Vec<(usize, usize)>,
+}
+
+impl SmellContext {
+    fn is_test_line(&self, line: usize) -> bool {
+        self.test_file
+            || self
+                .test_spans
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&line))
+    }
+}
+
+/// Описание правила в JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmellRuleSpec {
+    pub smell: CodeSmellType,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mask: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub language_exclude: BTreeMap<String, String>,
+    #[serde(default)]
+    pub skip_tests: bool,
+    pub severity: Priority,
+    pub description: String,
+    pub suggestion: String,
+    /// `false` отключает правило (и встроенные правила того же запаха)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl SmellRuleSpec {
+    fn compile(&self) -> Result<SmellRule> {
+        let compile = |source: &str| {
+            Regex::new(source).map_err(|e| {
+                AnalysisError::ParsingError(format!("Smell rule '{}': {e}", self.name))
+            })
+        };
+        let regex = |source: &Option<String>| -> Result<Option<Regex>> {
+            source.as_deref().map(compile).transpose()
+        };
+        let pattern = regex(&self.pattern)?;
+        let needs_pattern = matches!(
+            self.smell,
+            CodeSmellType::LongParameterList | CodeSmellType::MagicNumbers
+        );
+        if needs_pattern && pattern.is_none() {
+            return Err(AnalysisError::ParsingError(format!(
+                "Smell rule '{}' requires a pattern",
+                self.name
+            )));
+        }
+        Ok(SmellRule {
+            name: self.name.clone(),
+            pattern,
+            threshold: self.threshold,
+            exclude: regex(&self.exclude)?,
+            allow: self.allow.clone(),
+            mask: self
+                .mask
+                .iter()
+                .map(|m| compile(m))
+                .collect::<Result<_>>()?,
+            language_exclude: self
+                .language_exclude
+                .iter()
+                .map(|(language, e)| Ok((language.clone(), compile(e)?)))
+                .collect::<Result<_>>()?,
+            skip_tests: self.skip_tests,
+            severity: self.severity,
+            description: self.description.clone(),
+            suggestion: self.suggestion.clone(),
+        })
+    }
+}
+
+/// Набор правил запахов кода
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SmellRuleConfig {
+    #[serde(default)]
+    pub rules: Vec<SmellRuleSpec>,
+}
+
+impl SmellRuleConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid smell rules: {e}")))
+    }
+
+    /// Встроенный набор правил
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN_RULES).expect("built-in smell rules are valid")
+    }
+
+    /// `.archlens/smells.json`; `Ok(None)`, если файла нет
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(".archlens").join("smells.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+
+    /// Порог первого включённого правила запаха
+    pub fn threshold(&self, smell: &CodeSmellType) -> Option<f32> {
+        self.rules
+            .iter()
+            .find(|rule| rule.enabled && rule.smell == *smell)
+            .and_then(|rule| rule.threshold)
+    }
+
+    /// Правила `overrides` заменяют все правила того же запаха
+    pub fn merged_with(mut self, overrides: SmellRuleConfig) -> Self {
+        self.rules
+            .retain(|rule| !overrides.rules.iter().any(|o| o.smell == rule.smell));
+        self.rules.extend(overrides.rules);
+        self
+    }
+}
+
 /// Детектор антипаттернов
 #[derive(Debug)]
 pub struct AntipatternDetector {
@@ -71,20 +255,75 @@ pub struct AntipatternDetector {
 
 impl CodeSmellDetector {
     pub fn new() -> Self {
-        Self {
-            smell_rules: Self::create_smell_rules(),
+        Self::from_config(&SmellRuleConfig::builtin()).expect("built-in smell rules compile")
+    }
+
+    /// Детектор по набору правил; отключённые правила пропускаются
+    pub fn from_config(config: &SmellRuleConfig) -> Result<Self> {
+        let mut smell_rules: HashMap<CodeSmellType, Vec<SmellRule>> = HashMap::new();
+        for spec in config.rules.iter().filter(|spec| spec.enabled) {
+            smell_rules
+                .entry(spec.smell.clone())
+                .or_default()
+                .push(spec.compile()?);
         }
+        // кэш по хэшу содержимого: детектор разбирает разные тексты под одним путём
+        let parser = ParserAST::new()?.with_incremental(true);
+        Ok(Self {
+            smell_rules,
+            parser: Mutex::new(parser),
+        })
+    }
+
+    /// Встроенные правила с переопределениями из `.archlens/smells.json`
+    pub fn for_project(project_root: &Path) -> Result<Self> {
+        let mut config = SmellRuleConfig::builtin();
+        if let Some(overrides) = SmellRuleConfig::load(project_root)? {
+            config = config.merged_with(overrides);
+        }
+        Self::from_config(&config)
     }
 
     pub fn detect_code_smells(&self, content: &str, file_type: FileType) -> Result<Vec<CodeSmell>> {
+        let context = SmellContext {
+            test_spans: test_spans(content, &file_type),
+            path: PathBuf::from("<input>"),
+            file_type,
+            test_file: false,
+        };
+        self.detect_with_context(content, context)
+    }
+
+    /// Как `detect_code_smells`, но с путём файла относительно корня проекта:
+    /// правила с `skip_tests` не проверяют тестовые файлы
+    pub fn detect_code_smells_at(
+        &self,
+        relative_path: &Path,
+        content: &str,
+        file_type: FileType,
+    ) -> Result<Vec<CodeSmell>> {
+        let context = SmellContext {
+            test_spans: test_spans(content, &file_type),
+            path: relative_path.to_path_buf(),
+            file_type,
+            test_file: crate::code_origin::is_test_path(relative_path),
+        };
+        self.detect_with_context(content, context)
+    }
+
+    fn detect_with_context(&self, content: &str, context: SmellContext) -> Result<Vec<CodeSmell>> {
         let mut smells = Vec::new();
 
         for (smell_type, rules) in &self.smell_rules {
             for rule in rules {
-                let detected_smells = self.apply_rule(content, smell_type, rule)?;
+                if rule.skip_tests && context.test_file {
+                    continue;
+                }
+                let detected_smells = self.apply_rule(content, smell_type, rule, &context)?;
                 smells.extend(detected_smells);
             }
         }
+        let file_type = context.file_type;
 
         // Добавляем специфичные для типа файла проверки
         smells.extend(self.detect_file_specific_smells(content, file_type)?);
@@ -108,12 +347,13 @@ impl CodeSmellDetector {
         content: &str,
         smell_type: &CodeSmellType,
         rule: &SmellRule,
+        context: &SmellContext,
     ) -> Result<Vec<CodeSmell>> {
         let mut smells = Vec::new();
 
         match smell_type {
             CodeSmellType::LongMethod => {
-                smells.extend(self.detect_long_methods(content, rule)?);
+                smells.extend(self.detect_long_methods(content, rule, context)?);
             }
             CodeSmellType::LongParameterList => {
                 smells.extend(self.detect_long_parameter_lists(content, rule)?);
@@ -134,7 +374,7 @@ impl CodeSmellDetector {
                 smells.extend(self.detect_deep_nesting(content, rule)?);
             }
             CodeSmellType::MagicNumbers => {
-                smells.extend(self.detect_magic_numbers(content, rule)?);
+                smells.extend(self.detect_magic_numbers(content, rule, context)?);
             }
             CodeSmellType::EmptyExceptionHandling => {
                 smells.extend(self.detect_empty_exception_handling(content, rule)?);
@@ -150,8 +390,7 @@ impl CodeSmellDetector {
             }
             _ => {
                 // Базовая проверка по регулярному выражению
-                let matches: Vec<_> = rule.pattern.captures_iter(content).collect();
-                if !matches.is_empty() {
+                if rule.pattern.as_ref().is_some_and(|p| p.is_match(content)) {
                     smells.push(CodeSmell {
                         smell_type: smell_type.clone(),
                         severity: rule.severity,
@@ -167,38 +406,42 @@ impl CodeSmellDetector {
         Ok(smells)
     }
 
-    fn detect_long_methods(&self, content: &str, rule: &SmellRule) -> Result<Vec<CodeSmell>> {
-        let mut smells = Vec::new();
-        let threshold = rule.threshold.unwrap_or(20.0) as usize;
-
-        // Ищем функции и считаем их длину
-        let fn_pattern = Regex::new(r"fn\s+(\w+)\s*\([^)]*\)\s*\{").unwrap();
-
-        for cap in fn_pattern.captures_iter(content) {
-            let fn_name = cap.get(1).unwrap().as_str();
-            let fn_start = cap.get(0).unwrap().start();
-
-            // Подсчитываем строки функции (упрощенно)
-            let lines_after = content[fn_start..].lines().take(100).count();
+    /// Длинные функции по диапазонам из разбора: число операторов тела сверх
+    /// порога (для PHP и Ruby — свои пороги), находка с точными строками
+    fn detect_long_methods(
+        &self,
+        content: &str,
+        rule: &SmellRule,
+        context: &SmellContext,
+    ) -> Result<Vec<CodeSmell>> {
+        let threshold = match context.file_type {
+            FileType::Php => PHP_MAX_METHOD_LINES,
+            FileType::Ruby => RUBY_MAX_METHOD_LINES,
+            _ => rule.threshold.unwrap_or(20.0) as usize,
+        };
+        let elements = self
+            .parser
+            .lock()
+            .map_err(|_| AnalysisError::GenericError("Smell parser poisoned".to_string()))?
+            .parse_file(&context.path, content, &context.file_type)?;
 
-            if lines_after > threshold {
+        let mut smells = Vec::new();
+        for element in elements.iter().filter(|e| {
+            matches!(
+                e.element_type,
+                ASTElementType::Function | ASTElementType::Method
+            )
+        }) {
+            let Some(metrics) = BodyMetrics::from_metadata(&element.metadata) else {
+                continue;
+            };
+            if metrics.statements > threshold {
                 smells.push(CodeSmell {
-                    smell_type: CodeSmellType::LongMethod,
                     severity: rule.severity,
-                    description: format!(
-                        "Функция '{}' слишком длинная ({} строк)",
-                        fn_name, lines_after
-                    ),
-                    suggestion: format!(
-                        "Разбейте функцию '{}' на несколько более мелких функций",
-                        fn_name
-                    ),
-                    location: Some(format!("Функция: {}", fn_name)),
-                    confidence: 0.9,
+                    ..long_method(&element.name, element.start_line, element.end_line, metrics)
                 });
             }
         }
-
         Ok(smells)
     }
 
@@ -209,26 +452,18 @@ impl CodeSmellDetector {
     ) -> Result<Vec<CodeSmell>> {
         let mut smells = Vec::new();
         let threshold = rule.threshold.unwrap_or(4.0) as usize;
-
-        let fn_pattern = Regex::new(r"fn\s+(\w+)\s*\(([^)]*)\)").unwrap();
+        let Some(fn_pattern) = &rule.pattern else {
+            return Ok(smells);
+        };
 
         for cap in fn_pattern.captures_iter(content) {
-            let fn_name = cap.get(1).unwrap().as_str();
-            let params = cap.get(2).unwrap().as_str();
-
-            let param_count = params.split(',').filter(|p| !p.trim().is_empty()).count();
+            let fn_name = capture_name(&cap);
+            let param_count = count_parameters(&content[cap.get(0).unwrap().end()..]);
 
             if param_count > threshold {
                 smells.push(CodeSmell {
-                    smell_type: CodeSmellType::LongParameterList,
                     severity: rule.severity,
-                    description: format!(
-                        "Функция '{}' имеет слишком много параметров ({})",
-                        fn_name, param_count
-                    ),
-                    suggestion: format!("Сгруппируйте параметры функции '{}' в структуру", fn_name),
-                    location: Some(format!("Функция: {}", fn_name)),
-                    confidence: 0.9,
+                    ..long_parameter_list(fn_name, param_count)
                 });
             }
         }
@@ -345,11 +580,16 @@ impl CodeSmellDetector {
         let threshold = rule.threshold.unwrap_or(120.0) as usize;
 
         for (i, line) in content.lines().enumerate() {
-            if line.len() > threshold {
+            if rule.exclude.as_ref().is_some_and(|e| e.is_match(line)) {
+                continue;
+            }
+            // длина в символах, а не в байтах: кириллица занимает по два байта
+            let length = line.chars().count();
+            if length > threshold {
                 smells.push(CodeSmell {
                     smell_type: CodeSmellType::LongLineLength,
                     severity: rule.severity,
-                    description: format!("Слишком длинная строка ({} символов)", line.len()),
+                    description: format!("Слишком длинная строка ({} символов)", length),
                     suggestion: "Разбейте длинную строку на несколько коротких".to_string(),
                     location: Some(format!("Строка: {}", i + 1)),
                     confidence: 1.0,
@@ -363,26 +603,30 @@ impl CodeSmellDetector {
     fn detect_deep_nesting(&self, content: &str, rule: &SmellRule) -> Result<Vec<CodeSmell>> {
         let mut smells = Vec::new();
         let threshold = rule.threshold.unwrap_or(4.0) as usize;
-        let mut nesting_level: i32 = 0;
-        let mut max_nesting = 0;
+        let mut nesting_level = 0usize;
 
         for (i, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-
-            if trimmed.contains('{') {
-                nesting_level += 1;
-                max_nesting = max_nesting.max(nesting_level);
-            }
-
-            if trimmed.contains('}') {
-                nesting_level = nesting_level.saturating_sub(1);
+            let code = strip_string_literals(line.split("//").next().unwrap_or_default());
+            let was_deep = nesting_level > threshold;
+            // блоки могут открываться и закрываться в пределах одной строки
+            let mut deepest = nesting_level;
+            for ch in code.chars() {
+                match ch {
+                    '{' => {
+                        nesting_level += 1;
+                        deepest = deepest.max(nesting_level);
+                    }
+                    '}' => nesting_level = nesting_level.saturating_sub(1),
+                    _ => {}
+                }
             }
 
-            if nesting_level > threshold as i32 {
+            // одна находка на каждый вход глубже порога, а не на каждую строку
+            if deepest > threshold && !was_deep {
                 smells.push(CodeSmell {
                     smell_type: CodeSmellType::DeepNesting,
                     severity: rule.severity,
-                    description: format!("Глубокая вложенность ({} уровней)", nesting_level),
+                    description: format!("Глубокая вложенность ({} уровней)", deepest),
                     suggestion: "Выделите вложенную логику в отдельные функции".to_string(),
                     location: Some(format!("Строка: {}", i + 1)),
                     confidence: 0.8,
@@ -393,19 +637,46 @@ impl CodeSmellDetector {
         Ok(smells)
     }
 
-    fn detect_magic_numbers(&self, content: &str, rule: &SmellRule) -> Result<Vec<CodeSmell>> {
+    fn detect_magic_numbers(
+        &self,
+        content: &str,
+        rule: &SmellRule,
+        context: &SmellContext,
+    ) -> Result<Vec<CodeSmell>> {
         let mut smells = Vec::new();
-        let magic_pattern = Regex::new(r"\b(\d{2,})\b").unwrap();
+        let Some(magic_pattern) = &rule.pattern else {
+            return Ok(smells);
+        };
+        let language = language_key(&context.file_type);
 
-        for cap in magic_pattern.captures_iter(content) {
-            let number = cap.get(1).unwrap().as_str();
-            if number != "0" && number != "1" && number != "100" {
+        for (i, line) in content.lines().enumerate() {
+            if (rule.skip_tests && context.is_test_line(i + 1)) || rule.excludes(line, language) {
+                continue;
+            }
+            // числа внутри строковых литералов и комментариев не считаются
+            let mut code = strip_code_line(line, &context.file_type);
+            for mask in &rule.mask {
+                code = mask
+                    .replace_all(&code, |m: &regex::Captures| " ".repeat(m[0].len()))
+                    .into_owned();
+            }
+            for cap in magic_pattern.captures_iter(&code) {
+                let number = capture_name(&cap);
+                // `100.0` допускается так же, как `100`
+                let value = number.parse::<f64>().ok();
+                if rule
+                    .allow
+                    .iter()
+                    .any(|allowed| allowed == number || allowed.parse::<f64>().ok() == value)
+                {
+                    continue;
+                }
                 smells.push(CodeSmell {
                     smell_type: CodeSmellType::MagicNumbers,
                     severity: rule.severity,
                     description: format!("Магическое число: {}", number),
                     suggestion: format!("Замените число {} на именованную константу", number),
-                    location: None,
+                    location: Some(format!("Строка: {}", i + 1)),
                     confidence: 0.7,
                 });
             }
@@ -532,6 +803,12 @@ impl CodeSmellDetector {
             FileType::Python => {
                 smells.extend(self.detect_python_specific_smells(content)?);
             }
+            FileType::Php => {
+                smells.extend(self.detect_php_specific_smells(content)?);
+            }
+            FileType::Ruby => {
+                smells.extend(self.detect_ruby_specific_smells(content)?);
+            }
             _ => {}
         }
 
@@ -602,75 +879,87 @@ impl CodeSmellDetector {
         Ok(smells)
     }
 
-    fn create_smell_rules() -> HashMap<CodeSmellType, Vec<SmellRule>> {
-        let mut rules = HashMap::new();
+    fn detect_php_specific_smells(&self, content: &str) -> Result<Vec<CodeSmell>> {
+        let mut smells = Vec::new();
 
-        // Long Method
-        rules.insert(
-            CodeSmellType::LongMethod,
-            vec![SmellRule {
-                name: "Длинная функция".to_string(),
-                pattern: Regex::new(r"fn\s+\w+").unwrap(),
-                threshold: Some(25.0),
-                severity: Priority::Medium,
-                description: "Функция содержит слишком много строк кода".to_string(),
-                suggestion: "Разбейте функцию на несколько более мелких".to_string(),
-            }],
-        );
+        // Списки параметров; длинные методы — общим правилом по разобранным диапазонам
+        let fn_pattern = Regex::new(r"function\s+&?(\w+)\s*\(([^)]*)\)").unwrap();
+        for cap in fn_pattern.captures_iter(content) {
+            let fn_name = cap.get(1).unwrap().as_str();
+            let params = cap.get(2).unwrap().as_str();
+            let param_count = params.split(',').filter(|p| !p.trim().is_empty()).count();
+            if param_count > PHP_MAX_PARAMETERS {
+                smells.push(long_parameter_list(fn_name, param_count));
+            }
+        }
 
-        // Long Parameter List
-        rules.insert(
-            CodeSmellType::LongParameterList,
-            vec![SmellRule {
-                name: "Длинный список параметров".to_string(),
-                pattern: Regex::new(r"fn\s+\w+\s*\([^)]*\)").unwrap(),
-                threshold: Some(5.0),
+        // Пустой catch
+        let empty_catch = Regex::new(r"catch\s*\([^)]*\)\s*\{\s*\}").unwrap();
+        for _cap in empty_catch.find_iter(content) {
+            smells.push(CodeSmell {
+                smell_type: CodeSmellType::EmptyExceptionHandling,
+                severity: Priority::High,
+                description: "Пустой блок catch".to_string(),
+                suggestion: "Обработайте или залогируйте исключение".to_string(),
+                location: None,
+                confidence: 0.9,
+            });
+        }
+
+        // Подавление ошибок оператором @
+        let suppress_pattern = Regex::new(r"[=(,\s]@\$?[\w\\:>-]+\s*\(").unwrap();
+        for _cap in suppress_pattern.find_iter(content) {
+            smells.push(CodeSmell {
+                smell_type: CodeSmellType::EmptyExceptionHandling,
                 severity: Priority::Medium,
-                description: "Функция имеет слишком много параметров".to_string(),
-                suggestion: "Сгруппируйте параметры в структуру".to_string(),
-            }],
-        );
+                description: "Подавление ошибок оператором @".to_string(),
+                suggestion: "Проверяйте результат вызова вместо подавления ошибок".to_string(),
+                location: None,
+                confidence: 0.8,
+            });
+        }
 
-        // Magic Numbers
-        rules.insert(
-            CodeSmellType::MagicNumbers,
-            vec![SmellRule {
-                name: "Магические числа".to_string(),
-                pattern: Regex::new(r"\b\d{2,}\b").unwrap(),
-                threshold: None,
-                severity: Priority::Low,
-                description: "Использование магических чисел в коде".to_string(),
-                suggestion: "Замените числа на именованные константы".to_string(),
-            }],
-        );
+        Ok(smells)
+    }
 
-        // Long Lines
-        rules.insert(
-            CodeSmellType::LongLineLength,
-            vec![SmellRule {
-                name: "Длинные строки".to_string(),
-                pattern: Regex::new(r".{121,}").unwrap(),
-                threshold: Some(120.0),
-                severity: Priority::Low,
-                description: "Строка превышает рекомендуемую длину".to_string(),
-                suggestion: "Разбейте длинную строку на несколько коротких".to_string(),
-            }],
-        );
+    fn detect_ruby_specific_smells(&self, content: &str) -> Result<Vec<CodeSmell>> {
+        let mut smells = Vec::new();
 
-        // Deep Nesting
-        rules.insert(
-            CodeSmellType::DeepNesting,
-            vec![SmellRule {
-                name: "Глубокая вложенность".to_string(),
-                pattern: Regex::new(r"\{").unwrap(),
-                threshold: Some(4.0),
-                severity: Priority::Medium,
-                description: "Слишком глубокая вложенность блоков кода".to_string(),
-                suggestion: "Выделите вложенную логику в отдельные функции".to_string(),
-            }],
-        );
+        // длинные методы — общим правилом по разобранным диапазонам
+        let def_pattern =
+            Regex::new(r"^\s*def\s+(?:self\.)?([\w?!=]+)\s*(?:\(([^)]*)\))?").unwrap();
+        for line in content.lines() {
+            let Some(cap) = def_pattern.captures(line) else {
+                continue;
+            };
+            let fn_name = cap.get(1).unwrap().as_str();
+            if let Some(params) = cap.get(2) {
+                let param_count = params
+                    .as_str()
+                    .split(',')
+                    .filter(|p| !p.trim().is_empty())
+                    .count();
+                if param_count > RUBY_MAX_PARAMETERS {
+                    smells.push(long_parameter_list(fn_name, param_count));
+                }
+            }
+        }
+
+        // rescue Exception перехватывает и системные сигналы
+        let rescue_pattern = Regex::new(r"rescue\s+Exception\b").unwrap();
+        for _cap in rescue_pattern.find_iter(content) {
+            smells.push(CodeSmell {
+                smell_type: CodeSmellType::EmptyExceptionHandling,
+                severity: Priority::High,
+                description: "Использование rescue Exception".to_string(),
+                suggestion: "Перехватывайте StandardError или конкретный тип исключения"
+                    .to_string(),
+                location: None,
+                confidence: 0.9,
+            });
+        }
 
-        rules
+        Ok(smells)
     }
 }
 
@@ -679,3 +968,147 @@ impl Default for CodeSmellDetector {
         Self::new()
     }
 }
+
+fn long_method(fn_name: &str, start: usize, end: usize, metrics: BodyMetrics) -> CodeSmell {
+    CodeSmell {
+        smell_type: CodeSmellType::LongMethod,
+        severity: Priority::Medium,
+        description: format!(
+            "Функция '{}' слишком длинная ({} операторов, вложенность {})",
+            fn_name, metrics.statements, metrics.max_nesting
+        ),
+        suggestion: format!(
+            "Разбейте функцию '{}' на несколько более мелких функций",
+            fn_name
+        ),
+        location: Some(format!("Функция: {} (строки {}-{})", fn_name, start, end)),
+        confidence: 0.9,
+    }
+}
+
+fn long_parameter_list(fn_name: &str, param_count: usize) -> CodeSmell {
+    CodeSmell {
+        smell_type: CodeSmellType::LongParameterList,
+        severity: Priority::Medium,
+        description: format!(
+            "Функция '{}' имеет слишком много параметров ({})",
+            fn_name, param_count
+        ),
+        suggestion: format!("Сгруппируйте параметры функции '{}' в структуру", fn_name),
+        location: Some(format!("Функция: {}", fn_name)),
+        confidence: 0.9,
+    }
+}
+
+/// Первая группа захвата шаблона (имя функции, число), иначе всё совпадение
+fn capture_name<'a>(cap: &regex::Captures<'a>) -> &'a str {
+    cap.get(1).or_else(|| cap.get(0)).unwrap().as_str()
+}
+
+/// Параметры в списке, который начинается сразу после `(`. Запятые внутри
+/// обобщённых типов, кортежей и срезов (`HashMap<K, V>`) не разделяют параметры
+fn count_parameters(after_paren: &str) -> usize {
+    let mut depth = 0usize;
+    let mut count = 0;
+    let mut current_empty = true;
+    for ch in after_paren.chars() {
+        match ch {
+            '(' | '<' | '[' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' | '>' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                if !current_empty {
+                    count += 1;
+                }
+                current_empty = true;
+                continue;
+            }
+            _ => {}
+        }
+        if !ch.is_whitespace() {
+            current_empty = false;
+        }
+    }
+    if !current_empty {
+        count += 1;
+    }
+    count
+}
+
+/// Заменяет содержимое строковых литералов `"..."` пробелами
+fn strip_string_literals(line: &str) -> String {
+    strip_quoted(line, &['"'])
+}
+
+fn strip_quoted(line: &str, quotes: &[char]) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut open: Option<char> = None;
+    let mut escaped = false;
+    for ch in line.chars() {
+        if let Some(quote) = open {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == quote {
+                open = None;
+                out.push(ch);
+                continue;
+            }
+            out.push(' ');
+        } else {
+            if quotes.contains(&ch) {
+                open = Some(ch);
+            }
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Ключ языка для `language_exclude`
+fn language_key(file_type: &FileType) -> &str {
+    match file_type {
+        FileType::Rust => "rust",
+        FileType::JavaScript => "javascript",
+        FileType::TypeScript => "typescript",
+        FileType::Python => "python",
+        FileType::Java => "java",
+        FileType::Go => "go",
+        FileType::Cpp => "cpp",
+        FileType::C => "c",
+        FileType::Php => "php",
+        FileType::Ruby => "ruby",
+        FileType::Other(name) => name.as_str(),
+    }
+}
+
+/// Строка без строковых литералов и однострочного комментария с учётом языка:
+/// `#` в Python и Ruby, одинарные кавычки там, где это строки, а не символы
+fn strip_code_line(line: &str, file_type: &FileType) -> String {
+    let quotes: &[char] = match file_type {
+        FileType::Rust | FileType::C | FileType::Cpp | FileType::Java | FileType::Go => &['"'],
+        _ => &['"', '\''],
+    };
+    let code = strip_quoted(line, quotes);
+    let comment = match file_type {
+        FileType::Python | FileType::Ruby => code.find('#'),
+        FileType::Php => [code.find("//"), code.find('#')]
+            .into_iter()
+            .flatten()
+            .min(),
+        _ => code.find("//"),
+    };
+    match comment {
+        Some(at) => code[..at].to_string(),
+        None => code,
+    }
+}
+
+/// Тестовые блоки внутри файла (`#[cfg(test)]` в Rust)
+fn test_spans(content: &str, file_type: &FileType) -> Vec<(usize, usize)> {
+    match file_type {
+        FileType::Rust => crate::code_origin::cfg_test_spans(content),
+        _ => Vec::new(),
+    }
+}
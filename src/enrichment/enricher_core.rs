@@ -1,4 +1,5 @@
 // Core enrichment logic for capsules
+use crate::panic_isolation::{isolate, log_failure, ANALYSIS_FAILURE_CATEGORY, ENRICH_STAGE};
 use crate::types::*;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
@@ -75,6 +76,8 @@ pub enum PatternType {
     Controller,
     Entity,
     ValueObject,
+    /// Pattern defined in `.archlens/patterns.json`
+    Custom(String),
 }
 
 /// Code smell detector
@@ -163,15 +166,23 @@ impl CapsuleEnricher {
         let mut enriched_relations = graph.relations.clone();
 
         for (id, capsule) in &graph.capsules {
-            let mut enriched_capsule = capsule.clone();
-
-            // Enrich metadata from file content
-            if let Ok(content) = std::fs::read_to_string(&capsule.file_path) {
-                self.enrich_capsule_metadata(&mut enriched_capsule, &content)?;
-                self.analyze_dependencies(&mut enriched_capsule, &content)?;
-                self.extract_exports(&mut enriched_capsule, &content)?;
-                self.generate_warnings(&mut enriched_capsule, &content)?;
-            }
+            // A panic on one file keeps the capsule as parsed, with a warning
+            let enriched = isolate(|| -> Result<Capsule> {
+                let mut enriched_capsule = capsule.clone();
+
+                // Enrich metadata from file content
+                if let Ok(content) = std::fs::read_to_string(&capsule.file_path) {
+                    self.enrich_capsule_metadata(&mut enriched_capsule, &content)?;
+                    self.analyze_dependencies(&mut enriched_capsule, &content)?;
+                    self.extract_exports(&mut enriched_capsule, &content)?;
+                    self.generate_warnings(&mut enriched_capsule, &content)?;
+                }
+                Ok(enriched_capsule)
+            });
+            let enriched_capsule = match enriched {
+                Ok(result) => result?,
+                Err(message) => enrichment_failed(capsule, message),
+            };
 
             enriched_capsules.insert(*id, enriched_capsule);
         }
@@ -493,6 +504,8 @@ impl CapsuleEnricher {
             Some("go") => FileType::Go,
             Some("cpp") | Some("cc") | Some("cxx") => FileType::Cpp,
             Some("c") => FileType::C,
+            Some("php") => FileType::Php,
+            Some("rb") => FileType::Ruby,
             Some("h") | Some("hpp") => FileType::Other("header".to_string()),
             Some("json") => FileType::Other("json".to_string()),
             Some("yaml") | Some("yml") => FileType::Other("yaml".to_string()),
@@ -510,3 +523,17 @@ impl Default for CapsuleEnricher {
         Self::new()
     }
 }
+
+/// Capsule left unenriched after a panic, with the panic message as a warning
+fn enrichment_failed(capsule: &Capsule, message: String) -> Capsule {
+    log_failure(ENRICH_STAGE, &capsule.file_path, &message);
+    let mut capsule = capsule.clone();
+    capsule.warnings.push(AnalysisWarning {
+        message: format!("Enrichment panicked: {message}"),
+        level: Priority::Medium,
+        category: ANALYSIS_FAILURE_CATEGORY.to_string(),
+        capsule_id: Some(capsule.id),
+        suggestion: Some("Report the file so the analyzer can be fixed".to_string()),
+    });
+    capsule
+}
@@ -87,8 +87,14 @@ impl QualityAnalyzer {
 
         // Calculate individual metrics
         let complexity_score = self.calculate_complexity_score(content);
-        let documentation_score = self.calculate_documentation_score(content);
-        let test_coverage_score = self.calculate_test_coverage_score(content);
+        let documentation_score = self.capsule_documentation_score(capsule, content);
+        // Измеренное покрытие (оверлей lcov/Cobertura) приоритетнее эвристики
+        let test_coverage_score = capsule
+            .metadata
+            .get(crate::overlays::LINE_COVERAGE_KEY)
+            .and_then(|v| v.parse::<f32>().ok())
+            .map(|c| (c * 100.0).clamp(0.0, 100.0))
+            .unwrap_or_else(|| self.calculate_test_coverage_score(content));
         let maintainability_score = self.calculate_maintainability_score(content);
         let technical_debt_score = self.calculate_technical_debt_score(content);
 
@@ -225,6 +231,48 @@ impl QualityAnalyzer {
         score.clamp(0.0, 100.0)
     }
 
+    /// Documentation score of the capsule itself from the doc comment extracted by the
+    /// parser; capsules that are not code elements fall back to the content heuristic
+    fn capsule_documentation_score(&self, capsule: &Capsule, content: &str) -> f32 {
+        let documentable = matches!(
+            capsule.capsule_type,
+            CapsuleType::Function
+                | CapsuleType::Method
+                | CapsuleType::Class
+                | CapsuleType::Struct
+                | CapsuleType::Enum
+                | CapsuleType::Interface
+        );
+        match capsule.metadata.get(crate::parser_ast::DOC_KEY) {
+            Some(doc) => {
+                let mut score: f32 = 70.0;
+                // Details beyond the summary line
+                if doc.lines().filter(|l| !l.trim().is_empty()).count() > 1 {
+                    score += 15.0;
+                }
+                // Parameters, return value and errors are described
+                let sections = [
+                    "@param",
+                    "@return",
+                    "@throws",
+                    ":param",
+                    "Args:",
+                    "Returns:",
+                    "Raises:",
+                    "# Arguments",
+                    "# Returns",
+                    "# Errors",
+                ];
+                if sections.iter().any(|s| doc.contains(s)) {
+                    score += 15.0;
+                }
+                score
+            }
+            None if documentable => 0.0,
+            None => self.calculate_documentation_score(content),
+        }
+    }
+
     /// Calculate test coverage score (0-100, higher is better)
     fn calculate_test_coverage_score(&self, content: &str) -> f32 {
         let mut score: f32 = 0.0;
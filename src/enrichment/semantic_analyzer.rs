@@ -1,6 +1,7 @@
 // Advanced semantic analysis for code understanding
 use crate::enrichment::enricher_core::*;
 use crate::types::*;
+use crate::validation::PatternDetector;
 use regex::Regex;
 use std::collections::HashMap;
 
@@ -348,16 +349,31 @@ impl SemanticAnalyzer {
 pub struct SemanticEnricher {
     pub analyzers: HashMap<FileType, SemanticAnalyzer>,
     pub antipattern_detectors: Vec<AntipatternDetector>,
+    /// Statements in a function body above which it is reported as a long method
+    pub long_method_statements: usize,
+    /// Architectural pattern criteria; built-in unless replaced via `with_pattern_detector`
+    pub pattern_detector: PatternDetector,
 }
 
 impl SemanticEnricher {
     pub fn new() -> Self {
+        let long_method_statements = crate::enrichment::code_smells::SmellRuleConfig::builtin()
+            .threshold(&crate::enrichment::code_smells::CodeSmellType::LongMethod)
+            .map_or(DEFAULT_LONG_METHOD_STATEMENTS, |t| t as usize);
         Self {
             analyzers: SemanticAnalyzer::create_analyzers(),
             antipattern_detectors: SemanticAnalyzer::create_antipattern_detectors(),
+            long_method_statements,
+            pattern_detector: PatternDetector::new(),
         }
     }
 
+    /// Use project pattern criteria, e.g. `PatternDetector::for_project(root)?`
+    pub fn with_pattern_detector(mut self, pattern_detector: PatternDetector) -> Self {
+        self.pattern_detector = pattern_detector;
+        self
+    }
+
     /// Perform full semantic analysis
     pub fn perform_semantic_analysis(
         &self,
@@ -373,10 +389,17 @@ impl SemanticEnricher {
             Vec::new()
         };
 
-        let quality_metrics = self.calculate_quality_metrics(content, &semantic_links)?;
-        let architectural_patterns =
-            self.detect_architectural_patterns(content, &semantic_links)?;
-        let code_smells = self.detect_code_smells(content)?;
+        let mut quality_metrics = self.calculate_quality_metrics(content, &semantic_links)?;
+        // Измеренное покрытие (оверлей lcov/Cobertura) заменяет грубую оценку
+        if let Some(measured) = capsule
+            .metadata
+            .get(crate::overlays::LINE_COVERAGE_KEY)
+            .and_then(|v| v.parse::<f32>().ok())
+        {
+            quality_metrics.test_coverage_estimate = measured;
+        }
+        let architectural_patterns = self.detect_architectural_patterns(content, &file_type)?;
+        let code_smells = self.detect_code_smells(capsule, content, &file_type)?;
 
         Ok(EnrichmentResult {
             semantic_links,
@@ -414,78 +437,54 @@ impl SemanticEnricher {
         })
     }
 
-    /// Detect architectural patterns
+    /// Detect architectural patterns from structural evidence (see `validation::patterns`)
     fn detect_architectural_patterns(
         &self,
         content: &str,
-        _semantic_links: &[SemanticLink],
+        file_type: &FileType,
     ) -> Result<Vec<ArchitecturalPattern>> {
-        let mut patterns = Vec::new();
-
-        // Singleton pattern
-        if content.contains("private static") && content.contains("getInstance") {
-            patterns.push(ArchitecturalPattern {
-                pattern_type: PatternType::Singleton,
-                confidence: 0.8,
-                description: "Detected Singleton pattern".to_string(),
-            });
-        }
-
-        // Factory pattern
-        if content.contains("create") && content.contains("new ") {
-            patterns.push(ArchitecturalPattern {
-                pattern_type: PatternType::Factory,
-                confidence: 0.6,
-                description: "Possible Factory pattern usage".to_string(),
-            });
-        }
-
-        // Repository pattern
-        if content.contains("Repository") || content.contains("findBy") || content.contains("save")
-        {
-            patterns.push(ArchitecturalPattern {
-                pattern_type: PatternType::Repository,
-                confidence: 0.7,
-                description: "Detected Repository pattern".to_string(),
-            });
-        }
-
-        // Service pattern
-        if content.contains("Service") || content.contains("@Service") {
-            patterns.push(ArchitecturalPattern {
-                pattern_type: PatternType::Service,
-                confidence: 0.8,
-                description: "Detected Service pattern".to_string(),
-            });
-        }
-
-        // Controller pattern
-        if content.contains("Controller")
-            || content.contains("@Controller")
-            || content.contains("@RestController")
-        {
-            patterns.push(ArchitecturalPattern {
-                pattern_type: PatternType::Controller,
-                confidence: 0.8,
-                description: "Detected Controller pattern".to_string(),
-            });
-        }
-
-        Ok(patterns)
+        Ok(self
+            .pattern_detector
+            .detect_in_source(content, file_type)
+            .into_iter()
+            .map(|found| ArchitecturalPattern {
+                pattern_type: pattern_type(&found.pattern_name),
+                confidence: found.confidence,
+                description: format!(
+                    "Detected {} pattern ({})",
+                    found.pattern_name,
+                    found.evidence.join(", ")
+                ),
+            })
+            .collect())
     }
 
     /// Detect code smells
-    fn detect_code_smells(&self, content: &str) -> Result<Vec<CodeSmell>> {
+    fn detect_code_smells(
+        &self,
+        capsule: &Capsule,
+        content: &str,
+        file_type: &FileType,
+    ) -> Result<Vec<CodeSmell>> {
         let mut smells = Vec::new();
 
-        // Long method
-        if content.len() > 2000 {
-            smells.push(CodeSmell {
-                smell_type: CodeSmellType::LongMethod,
-                severity: Priority::High,
-                description: "Method or file is too long".to_string(),
-                suggestion: "Break down into smaller, focused methods".to_string(),
-            });
+        // Long method: statements within the parsed span, not the text length
+        if let Some(metrics) = function_body_metrics(capsule, content, file_type) {
+            if metrics.statements > self.long_method_statements {
+                smells.push(CodeSmell {
+                    smell_type: CodeSmellType::LongMethod,
+                    severity: Priority::High,
+                    description: format!(
+                        "Function '{}' is too long: {} statements, nesting depth {} (lines {}-{})",
+                        capsule.name,
+                        metrics.statements,
+                        metrics.max_nesting,
+                        capsule.line_start,
+                        capsule.line_end
+                    ),
+                    suggestion: "Break down into smaller, focused methods".to_string(),
+                });
+            }
         }
 
         // God object
@@ -532,6 +531,8 @@ impl SemanticEnricher {
             Some("go") => FileType::Go,
             Some("cpp") | Some("cc") | Some("cxx") => FileType::Cpp,
             Some("c") => FileType::C,
+            Some("php") => FileType::Php,
+            Some("rb") => FileType::Ruby,
             Some(ext) => FileType::Other(ext.to_string()),
             None => FileType::Other("unknown".to_string()),
         }
@@ -674,3 +675,55 @@ impl Default for SemanticEnricher {
         Self::new()
     }
 }
+
+/// Fallback when the built-in smell rules carry no long-method threshold
+const DEFAULT_LONG_METHOD_STATEMENTS: usize = 25;
+
+/// Built-in pattern names map to their types; others are project-defined
+fn pattern_type(name: &str) -> PatternType {
+    match name {
+        "Singleton" => PatternType::Singleton,
+        "Factory" => PatternType::Factory,
+        "Observer" => PatternType::Observer,
+        "Strategy" => PatternType::Strategy,
+        "Command" => PatternType::Command,
+        "Builder" => PatternType::Builder,
+        "Adapter" => PatternType::Adapter,
+        "Repository" => PatternType::Repository,
+        "Service" => PatternType::Service,
+        "Controller" => PatternType::Controller,
+        "Entity" => PatternType::Entity,
+        "Value Object" | "ValueObject" => PatternType::ValueObject,
+        other => PatternType::Custom(other.to_string()),
+    }
+}
+
+/// Body metrics of a function or method capsule: recorded by the parser, or
+/// measured over the capsule's line span when the capsule predates them.
+/// `content` is either the whole file or just the capsule's own lines
+fn function_body_metrics(
+    capsule: &Capsule,
+    content: &str,
+    file_type: &FileType,
+) -> Option<crate::parser_ast::BodyMetrics> {
+    if !matches!(
+        capsule.capsule_type,
+        CapsuleType::Function | CapsuleType::Method
+    ) {
+        return None;
+    }
+    if let Some(metrics) = crate::parser_ast::BodyMetrics::from_metadata(&capsule.metadata) {
+        return Some(metrics);
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let end = capsule.line_end.max(capsule.line_start);
+    let span = if capsule.line_start >= 1 && lines.len() >= end {
+        lines[capsule.line_start - 1..end].join("\n")
+    } else {
+        content.to_string()
+    };
+    if span.trim().is_empty() {
+        return None;
+    }
+    Some(crate::parser_ast::body_metrics(&span, file_type))
+}
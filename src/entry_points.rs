@@ -0,0 +1,396 @@
+// Точки входа проекта (бинарники, обработчики, экспортируемое API) из
+// `.archlens/entry_points.json`: какие капсулы недостижимы ни из одной точки
+// входа (кандидаты в мёртвый код) и какие точки входа затрагивают больше всего кода
+
+use crate::types::{AnalysisError, AnalysisWarning, CapsuleGraph, CapsuleType, Priority, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Категория предупреждения о капсуле, недостижимой из точек входа
+pub const UNREACHABLE_CATEGORY: &str = "unreachable";
+/// Ключ метаданных капсулы-точки входа (значение — имя точки входа)
+pub const ENTRY_POINT_KEY: &str = "entry_point";
+
+/// Имя точки входа и её капсулы
+type EntryPoint = (String, Vec<Uuid>);
+
+fn default_true() -> bool {
+    true
+}
+
+/// Одна объявленная точка входа: капсулы, совпавшие по файлу и/или имени
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryPointSpec {
+    /// Имя в отчёте; по умолчанию — `file` или `capsule`
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Префикс пути относительно корня проекта (`src/bin/`, `src/handlers/orders.rs`)
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Регулярное выражение имени капсулы (`^handle_`, `^Api`)
+    #[serde(default)]
+    pub capsule: Option<String>,
+}
+
+impl EntryPointSpec {
+    fn label(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.file.clone())
+            .or_else(|| self.capsule.clone())
+            .unwrap_or_else(|| "entry point".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPointConfig {
+    #[serde(default)]
+    pub entry_points: Vec<EntryPointSpec>,
+    /// Считать точками входа все функции `main`
+    #[serde(default = "default_true")]
+    pub detect_main: bool,
+}
+
+impl Default for EntryPointConfig {
+    fn default() -> Self {
+        Self {
+            entry_points: Vec::new(),
+            detect_main: true,
+        }
+    }
+}
+
+impl EntryPointConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid entry points config: {e}")))
+    }
+
+    /// `.archlens/entry_points.json`; `Ok(None)`, если файла нет
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(".archlens").join("entry_points.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+}
+
+/// Охват одной точки входа
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPointReach {
+    pub name: String,
+    /// Капсулы, из которых состоит точка входа
+    pub capsules: Vec<String>,
+    /// Сколько капсул достижимо из точки входа (включая её собственные)
+    pub reachable: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreachableCapsule {
+    pub name: String,
+    pub kind: CapsuleType,
+    /// `путь:строка` относительно корня проекта
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPointReport {
+    /// Точки входа по убыванию охвата
+    pub entry_points: Vec<EntryPointReach>,
+    /// Объявления, не совпавшие ни с одной капсулой
+    pub unmatched: Vec<String>,
+    pub unreachable: Vec<UnreachableCapsule>,
+    pub total_capsules: usize,
+    /// Доля капсул, достижимых хотя бы из одной точки входа
+    pub reachable_share: f32,
+}
+
+impl EntryPointReport {
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Точки входа: {}, достижимо капсул: {:.0}% из {}\n",
+            self.entry_points.len(),
+            self.reachable_share * 100.0,
+            self.total_capsules
+        );
+        for entry in &self.entry_points {
+            out.push_str(&format!(
+                "  {:<30} охват: {} ({})\n",
+                entry.name,
+                entry.reachable,
+                entry.capsules.join(", ")
+            ));
+        }
+        for name in &self.unmatched {
+            out.push_str(&format!("  {:<30} не найдено капсул\n", name));
+        }
+        if !self.unreachable.is_empty() {
+            out.push_str(&format!(
+                "Недостижимы из точек входа ({}):\n",
+                self.unreachable.len()
+            ));
+            for capsule in &self.unreachable {
+                out.push_str(&format!(
+                    "  {} ({:?}) {}\n",
+                    capsule.name, capsule.kind, capsule.location
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Поиск точек входа и достижимости капсул
+#[derive(Debug)]
+pub struct EntryPointAnalyzer {
+    root: PathBuf,
+    specs: Vec<(EntryPointSpec, Option<Regex>)>,
+    detect_main: bool,
+}
+
+impl EntryPointAnalyzer {
+    pub fn new(root: &Path, config: &EntryPointConfig) -> Result<Self> {
+        let specs = config
+            .entry_points
+            .iter()
+            .map(|spec| {
+                let pattern = spec
+                    .capsule
+                    .as_deref()
+                    .map(|p| {
+                        Regex::new(p).map_err(|e| {
+                            AnalysisError::ParsingError(format!(
+                                "Invalid entry point pattern `{p}`: {e}"
+                            ))
+                        })
+                    })
+                    .transpose()?;
+                Ok((spec.clone(), pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            specs,
+            detect_main: config.detect_main,
+        })
+    }
+
+    /// Правила проекта; `Ok(None)`, если точки входа не объявлены
+    pub fn for_project(root: &Path) -> Result<Option<Self>> {
+        match EntryPointConfig::load(root)? {
+            Some(config) => Self::new(root, &config).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Точки входа: имя → капсулы
+    fn entry_points(&self, graph: &CapsuleGraph) -> Vec<EntryPoint> {
+        let mut entries = Vec::new();
+        for (spec, pattern) in &self.specs {
+            let file = spec
+                .file
+                .as_deref()
+                .map(|f| f.trim().trim_start_matches("./").replace('\\', "/"));
+            let mut ids: Vec<Uuid> = graph
+                .capsules
+                .values()
+                .filter(|c| {
+                    file.as_deref()
+                        .is_none_or(|f| self.relative(&c.file_path).starts_with(f))
+                        && pattern.as_ref().is_none_or(|p| p.is_match(&c.name))
+                })
+                .map(|c| c.id)
+                .collect();
+            // пустое объявление ничего не выбирает
+            if file.is_none() && pattern.is_none() {
+                ids.clear();
+            }
+            entries.push((spec.label(), ids));
+        }
+        if self.detect_main {
+            let mut mains: Vec<_> = graph
+                .capsules
+                .values()
+                .filter(|c| c.name == "main" && matches!(c.capsule_type, CapsuleType::Function))
+                .collect();
+            mains.sort_by_key(|c| c.file_path.clone());
+            for capsule in mains {
+                entries.push((
+                    format!("main ({})", self.relative(&capsule.file_path)),
+                    vec![capsule.id],
+                ));
+            }
+        }
+        entries
+    }
+
+    pub fn analyze(&self, graph: &CapsuleGraph) -> EntryPointReport {
+        self.analyze_ids(graph).0
+    }
+
+    /// Отчёт, точки входа и множество достижимых капсул
+    fn analyze_ids(
+        &self,
+        graph: &CapsuleGraph,
+    ) -> (EntryPointReport, Vec<EntryPoint>, HashSet<Uuid>) {
+        let entries = self.entry_points(graph);
+        let mut outgoing: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for relation in &graph.relations {
+            outgoing
+                .entry(relation.from_id)
+                .or_default()
+                .push(relation.to_id);
+        }
+
+        let mut reachable_any = HashSet::new();
+        let mut reaches = Vec::new();
+        let mut unmatched = Vec::new();
+        for (name, ids) in &entries {
+            if ids.is_empty() {
+                unmatched.push(name.clone());
+                continue;
+            }
+            let reached = reachable_from(graph, &outgoing, ids);
+            let mut capsules: Vec<String> = ids
+                .iter()
+                .filter_map(|id| graph.capsules.get(id).map(|c| c.name.clone()))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            capsules.truncate(5);
+            reaches.push(EntryPointReach {
+                name: name.clone(),
+                capsules,
+                reachable: reached.len(),
+            });
+            reachable_any.extend(reached);
+        }
+        reaches.sort_by(|a, b| b.reachable.cmp(&a.reachable).then(a.name.cmp(&b.name)));
+
+        let mut unreachable: Vec<_> = graph
+            .capsules
+            .values()
+            .filter(|c| !reachable_any.contains(&c.id))
+            .collect();
+        unreachable.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then(a.line_start.cmp(&b.line_start))
+        });
+        let total = graph.capsules.len();
+        let report = EntryPointReport {
+            entry_points: reaches,
+            unmatched,
+            unreachable: unreachable
+                .into_iter()
+                .map(|c| UnreachableCapsule {
+                    name: c.name.clone(),
+                    kind: c.capsule_type,
+                    location: format!("{}:{}", self.relative(&c.file_path), c.line_start),
+                })
+                .collect(),
+            total_capsules: total,
+            reachable_share: if total == 0 {
+                0.0
+            } else {
+                reachable_any.len() as f32 / total as f32
+            },
+        };
+        (report, entries, reachable_any)
+    }
+
+    /// Помечает точки входа в метаданных и добавляет предупреждения недостижимым
+    /// капсулам. Без найденных точек входа граф не меняется: иначе весь код
+    /// оказался бы «мёртвым»
+    pub fn annotate(&self, graph: &mut CapsuleGraph) -> EntryPointReport {
+        let (report, entries, reachable) = self.analyze_ids(graph);
+        if report.entry_points.is_empty() {
+            return report;
+        }
+        for (name, ids) in entries {
+            for id in ids {
+                if let Some(capsule) = graph.capsules.get_mut(&id) {
+                    capsule
+                        .metadata
+                        .entry(ENTRY_POINT_KEY.to_string())
+                        .or_insert_with(|| name.clone());
+                }
+            }
+        }
+        for capsule in graph.capsules.values_mut() {
+            if reachable.contains(&capsule.id) {
+                continue;
+            }
+            capsule.warnings.push(AnalysisWarning {
+                message: format!(
+                    "'{}' is not reachable from any declared entry point",
+                    capsule.name
+                ),
+                level: Priority::Low,
+                category: UNREACHABLE_CATEGORY.to_string(),
+                capsule_id: Some(capsule.id),
+                suggestion: Some(
+                    "Remove it if unused, or declare the code that calls it as an entry point"
+                        .to_string(),
+                ),
+            });
+        }
+        report
+    }
+}
+
+/// Капсулы, достижимые из `start` по зависимостям; вложенные в достижимую
+/// капсулу (методы используемого класса) тоже считаются достижимыми
+fn reachable_from(
+    graph: &CapsuleGraph,
+    outgoing: &HashMap<Uuid, Vec<Uuid>>,
+    start: &[Uuid],
+) -> HashSet<Uuid> {
+    let mut reached: HashSet<Uuid> = start.iter().copied().collect();
+    let mut queue: Vec<Uuid> = start.to_vec();
+    loop {
+        while let Some(id) = queue.pop() {
+            for next in outgoing.get(&id).into_iter().flatten() {
+                if graph.capsules.contains_key(next) && reached.insert(*next) {
+                    queue.push(*next);
+                }
+            }
+        }
+        let nested: Vec<Uuid> = graph
+            .capsules
+            .values()
+            .filter(|c| !reached.contains(&c.id))
+            .filter(|c| {
+                reached
+                    .iter()
+                    .filter_map(|id| graph.capsules.get(id))
+                    .any(|outer| {
+                        outer.file_path == c.file_path
+                            && outer.line_end > outer.line_start
+                            && outer.line_start < c.line_start
+                            && c.line_end <= outer.line_end
+                    })
+            })
+            .map(|c| c.id)
+            .collect();
+        if nested.is_empty() {
+            return reached;
+        }
+        reached.extend(nested.iter().copied());
+        queue = nested;
+    }
+}
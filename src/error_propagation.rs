@@ -0,0 +1,554 @@
+// Распространение ошибок в Rust: тип ошибки в сигнатуре функции (с раскрытием
+// псевдонимов `Result`), `?` в её теле и функции других модулей, чьи ошибки
+// через `?` уходят выше. Публичные функции с широкими типами ошибок
+// (`Box<dyn Error>`, `String`, `anyhow::Error`) — находка качества API
+
+use crate::code_origin::CodeOrigin;
+use crate::dsm::common_root;
+use crate::types::{AnalysisWarning, Capsule, CapsuleGraph, CapsuleType, Priority};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Ключ метаданных: поток ошибок функции (JSON `ErrorFlow`)
+pub const ERROR_FLOW_KEY: &str = "error_flow";
+/// Категория находок о качестве публичного API
+pub const API_QUALITY_CATEGORY: &str = "api_quality";
+/// Сколько модулей показывать в ai_compact
+const COMPACT_SHOWN: usize = 10;
+/// Функции-адаптеры ошибок: `?` относится к вызову перед ними
+const ERROR_ADAPTERS: &[&str] = &[
+    "map_err",
+    "context",
+    "with_context",
+    "wrap_err",
+    "ok_or",
+    "ok_or_else",
+    "into",
+];
+/// Имя, объявленное в большем числе модулей, не указывает на источник
+const AMBIGUOUS_DEFINITIONS: usize = 3;
+
+/// Функция другого модуля, чья ошибка пробрасывается через `?`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ErrorSource {
+    pub module: String,
+    pub function: String,
+    /// Тип ошибки вызванной функции, если он известен
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+}
+
+/// Ошибки функции: тип из сигнатуры и откуда они приходят
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorFlow {
+    pub public: bool,
+    pub error_type: String,
+    /// `Box<dyn Error>`, `String`, `anyhow::Error` и подобные
+    pub broad: bool,
+    /// Число `?` в теле
+    pub propagations: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ErrorSource>,
+}
+
+/// Тип ошибки, не позволяющий вызывающему различать случаи
+pub fn is_broad_error_type(error_type: &str) -> bool {
+    let normalized: String = error_type.split_whitespace().collect();
+    let normalized = normalized.trim_start_matches("::");
+    (normalized.starts_with("Box<dyn")
+        && (normalized.contains("Error") || normalized.contains("Any")))
+        || matches!(
+            normalized,
+            "String"
+                | "std::string::String"
+                | "&str"
+                | "&'staticstr"
+                | "()"
+                | "anyhow::Error"
+                | "eyre::Report"
+                | "color_eyre::Report"
+        )
+}
+
+/// Содержимое угловых скобок, начинающихся с `open`, и разбиение по верхним запятым
+fn generic_args(text: &str, open: usize) -> Vec<String> {
+    let mut depth = 0;
+    let mut args = Vec::new();
+    let mut current = String::new();
+    for ch in text[open..].chars() {
+        match ch {
+            '<' | '(' | '[' => {
+                depth += 1;
+                if depth == 1 {
+                    continue;
+                }
+            }
+            '>' | ')' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            ',' if depth == 1 => {
+                args.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    if !current.trim().is_empty() {
+        args.push(current.trim().to_string());
+    }
+    args
+}
+
+fn result_type() -> &'static Regex {
+    static RESULT: OnceLock<Regex> = OnceLock::new();
+    RESULT.get_or_init(|| Regex::new(r"((?:\w+::)*)(\w*Result)\b\s*(<)?").unwrap())
+}
+
+/// Тип ошибки возвращаемого типа; `aliases` — псевдонимы `type XResult<T> = ...` крейта
+pub fn error_type_of(return_type: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    let cap = result_type().captures_iter(return_type).last()?;
+    let path = cap.get(1).map_or("", |m| m.as_str());
+    let name = &cap[2];
+    let args = match cap.get(3) {
+        Some(open) => generic_args(return_type, open.start()),
+        None => Vec::new(),
+    };
+    if args.len() >= 2 {
+        return args.last().cloned();
+    }
+    let module = path
+        .trim_end_matches("::")
+        .rsplit("::")
+        .next()
+        .unwrap_or("");
+    match (module, name) {
+        ("io", "Result") => Some("std::io::Error".into()),
+        ("fmt", "Result") => Some("std::fmt::Error".into()),
+        ("anyhow", "Result") => Some("anyhow::Error".into()),
+        ("eyre", "Result") => Some("eyre::Report".into()),
+        ("thread", "Result") => Some("Box<dyn Any + Send>".into()),
+        _ => {
+            let target = aliases.get(name)?;
+            // псевдоним раскрывается в полный `Result<T, E>`
+            error_type_of(target, &HashMap::new())
+        }
+    }
+}
+
+/// Псевдонимы `type Result<T> = ...;` в исходнике
+fn result_aliases(source: &str) -> Vec<(String, String)> {
+    static ALIAS: OnceLock<Regex> = OnceLock::new();
+    let alias = ALIAS.get_or_init(|| {
+        Regex::new(
+            r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?type\s+(\w*Result)\s*(?:<[^=]*>)?\s*=\s*([^;]+);",
+        )
+        .unwrap()
+    });
+    alias
+        .captures_iter(source)
+        .map(|cap| (cap[1].to_string(), cap[2].trim().to_string()))
+        .collect()
+}
+
+/// Сигнатура функции от строки `fn` до тела; видимость и возвращаемый тип
+fn signature(lines: &[&str]) -> Option<(bool, String, usize)> {
+    let start = lines.iter().position(|l| {
+        let l = l.trim_start();
+        !l.starts_with("#[") && !l.starts_with("//") && l.contains("fn ")
+    })?;
+    let mut text = String::new();
+    let mut body_line = start;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        body_line = i;
+        let end = line.find(['{', ';']);
+        text.push_str(&line[..end.unwrap_or(line.len())]);
+        text.push(' ');
+        if end.is_some() {
+            break;
+        }
+    }
+    let head = text.trim_start();
+    let public = head.starts_with("pub ") && !head.starts_with("pub (");
+    let return_type = text
+        .split_once("->")
+        .map(|(_, rest)| {
+            rest.split(" where ")
+                .next()
+                .unwrap_or(rest)
+                .trim()
+                .to_string()
+        })
+        .unwrap_or_default();
+    Some((public, return_type, body_line))
+}
+
+/// Строка без строковых литералов и комментария
+fn code_of(line: &str) -> String {
+    let mut out = String::new();
+    let mut in_string = false;
+    let mut previous = ' ';
+    for ch in line.chars() {
+        if in_string {
+            if ch == '"' && previous != '\\' {
+                in_string = false;
+            }
+            out.push(' ');
+        } else if ch == '"' {
+            in_string = true;
+            out.push(' ');
+        } else if ch == '/' && previous == '/' {
+            out.pop();
+            break;
+        } else {
+            out.push(ch);
+        }
+        previous = ch;
+    }
+    out
+}
+
+/// Имя вызова, к которому относится `?` в позиции `question`
+fn callee_before(code: &str, mut question: usize) -> Option<&str> {
+    let bytes = code.as_bytes();
+    loop {
+        let mut end = question;
+        while end > 0 && bytes[end - 1] == b' ' {
+            end -= 1;
+        }
+        if end == 0 || bytes[end - 1] != b')' {
+            return None;
+        }
+        let mut depth = 0;
+        let mut open = None;
+        for i in (0..end).rev() {
+            match bytes[i] {
+                b')' => depth += 1,
+                b'(' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        open = Some(i);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let open = open?;
+        let mut name_start = open;
+        while name_start > 0
+            && (bytes[name_start - 1].is_ascii_alphanumeric() || bytes[name_start - 1] == b'_')
+        {
+            name_start -= 1;
+        }
+        let name = &code[name_start..open];
+        if name.is_empty() {
+            return None;
+        }
+        if ERROR_ADAPTERS.contains(&name) && name_start > 0 && bytes[name_start - 1] == b'.' {
+            question = name_start - 1;
+            continue;
+        }
+        return Some(name);
+    }
+}
+
+/// Функция-капсула Rust и её модуль
+struct RustFunction {
+    index: usize,
+    module: String,
+    public: bool,
+    error_type: Option<String>,
+    body: Vec<String>,
+}
+
+/// Размечает функции Rust потоками ошибок и добавляет находки `api_quality`
+/// публичным функциям с широкими типами ошибок. Возвращает число находок
+pub fn annotate_error_propagation(capsules: &mut [Capsule]) -> usize {
+    let root = common_root(
+        capsules
+            .iter()
+            .filter(|c| c.file_path.extension().is_some_and(|e| e == "rs"))
+            .map(|c| c.file_path.as_path()),
+    );
+    let mut sources: BTreeMap<PathBuf, String> = BTreeMap::new();
+    for capsule in capsules.iter() {
+        if capsule.file_path.extension().is_some_and(|e| e == "rs")
+            && !sources.contains_key(&capsule.file_path)
+        {
+            if let Ok(source) = std::fs::read_to_string(&capsule.file_path) {
+                sources.insert(capsule.file_path.clone(), source);
+            }
+        }
+    }
+    // псевдоним того же файла важнее псевдонима из другого модуля
+    let mut crate_aliases: HashMap<String, String> = HashMap::new();
+    for source in sources.values() {
+        for (name, target) in result_aliases(source) {
+            crate_aliases.entry(name).or_insert(target);
+        }
+    }
+
+    let mut functions: Vec<RustFunction> = Vec::new();
+    for (index, capsule) in capsules.iter().enumerate() {
+        if !matches!(
+            capsule.capsule_type,
+            CapsuleType::Function | CapsuleType::Method
+        ) {
+            continue;
+        }
+        let Some(source) = sources.get(&capsule.file_path) else {
+            continue;
+        };
+        let lines: Vec<&str> = source
+            .lines()
+            .skip(capsule.line_start.saturating_sub(1))
+            .take(capsule.line_end.max(capsule.line_start) + 1 - capsule.line_start.max(1))
+            .collect();
+        let Some((public, return_type, body_line)) = signature(&lines) else {
+            continue;
+        };
+        let mut aliases = crate_aliases.clone();
+        aliases.extend(result_aliases(source));
+        functions.push(RustFunction {
+            index,
+            module: module_name(&capsule.file_path, &root),
+            public,
+            error_type: error_type_of(&return_type, &aliases),
+            body: lines[body_line..].iter().map(|l| code_of(l)).collect(),
+        });
+    }
+
+    let mut definitions: HashMap<&str, Vec<&RustFunction>> = HashMap::new();
+    for function in &functions {
+        definitions
+            .entry(capsules[function.index].name.as_str())
+            .or_default()
+            .push(function);
+    }
+
+    let mut flows: Vec<(usize, ErrorFlow)> = Vec::new();
+    for function in &functions {
+        let Some(error_type) = &function.error_type else {
+            continue;
+        };
+        let mut propagations = 0;
+        let mut found: BTreeSet<ErrorSource> = BTreeSet::new();
+        for code in &function.body {
+            for (question, _) in code.match_indices('?') {
+                propagations += 1;
+                let Some(callee) = callee_before(code, question) else {
+                    continue;
+                };
+                let Some(defined) = definitions.get(callee) else {
+                    continue;
+                };
+                if defined.len() > AMBIGUOUS_DEFINITIONS
+                    || defined.iter().any(|d| d.module == function.module)
+                {
+                    continue;
+                }
+                found.extend(defined.iter().map(|d| ErrorSource {
+                    module: d.module.clone(),
+                    function: callee.to_string(),
+                    error_type: d.error_type.clone(),
+                }));
+            }
+        }
+        if !function.public && propagations == 0 {
+            continue;
+        }
+        flows.push((
+            function.index,
+            ErrorFlow {
+                public: function.public,
+                broad: is_broad_error_type(error_type),
+                error_type: error_type.clone(),
+                propagations,
+                sources: found.into_iter().collect(),
+            },
+        ));
+    }
+
+    let mut findings = 0;
+    for (index, flow) in flows {
+        let capsule = &mut capsules[index];
+        if flow.public && flow.broad && CodeOrigin::of(capsule) != CodeOrigin::Test {
+            let mut message = format!(
+                "Public API {} returns broad error type {}",
+                capsule.name, flow.error_type
+            );
+            if !flow.sources.is_empty() {
+                let sources: Vec<String> = flow
+                    .sources
+                    .iter()
+                    .map(|s| format!("{}::{}", s.module, s.function))
+                    .collect();
+                message.push_str(&format!("; propagates errors from {}", sources.join(", ")));
+            }
+            capsule.warnings.push(AnalysisWarning {
+                level: Priority::Low,
+                message,
+                category: API_QUALITY_CATEGORY.to_string(),
+                capsule_id: Some(capsule.id),
+                suggestion: Some(format!(
+                    "Return a module-specific error type instead of {}",
+                    flow.error_type
+                )),
+            });
+            findings += 1;
+        }
+        if let Ok(json) = serde_json::to_string(&flow) {
+            capsule.metadata.insert(ERROR_FLOW_KEY.to_string(), json);
+        }
+    }
+    findings
+}
+
+/// Модуль Rust по пути файла: `src/cli/mod.rs` → `cli`, `src/lib.rs` → `crate`
+fn module_name(file: &Path, root: &Path) -> String {
+    let relative = file.strip_prefix(root).unwrap_or(file).with_extension("");
+    let mut parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if parts.first().is_some_and(|p| p == "src") {
+        parts.remove(0);
+    }
+    if parts
+        .last()
+        .is_some_and(|p| matches!(p.as_str(), "mod" | "lib" | "main"))
+    {
+        parts.pop();
+    }
+    if parts.is_empty() {
+        "crate".to_string()
+    } else {
+        parts.join("::")
+    }
+}
+
+/// Поток ошибок капсулы из метаданных
+pub fn capsule_error_flow(capsule: &Capsule) -> Option<ErrorFlow> {
+    serde_json::from_str(capsule.metadata.get(ERROR_FLOW_KEY)?).ok()
+}
+
+/// Публичная функция с широким типом ошибки
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BroadErrorApi {
+    pub function: String,
+    pub line: usize,
+    pub error_type: String,
+    pub sources: Vec<ErrorSource>,
+}
+
+/// Модуль, публичный API которого отдаёт широкие типы ошибок
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleErrorTypes {
+    pub file: PathBuf,
+    /// Публичных функций, возвращающих `Result`
+    pub public_functions: usize,
+    pub broad: Vec<BroadErrorApi>,
+    /// Все типы ошибок публичного API модуля
+    pub error_types: Vec<String>,
+}
+
+/// Модули с широкими типами ошибок в публичном API, от худших
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ErrorPropagationReport {
+    pub modules: Vec<ModuleErrorTypes>,
+}
+
+impl ErrorPropagationReport {
+    /// Отчёт по размеченному графу без тестового кода; `None`, если широких типов нет
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        let mut modules: BTreeMap<PathBuf, ModuleErrorTypes> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            let Some(flow) = capsule_error_flow(capsule).filter(|f| f.public) else {
+                continue;
+            };
+            if CodeOrigin::of(capsule) == CodeOrigin::Test {
+                continue;
+            }
+            let file = capsule
+                .file_path
+                .strip_prefix(&root)
+                .unwrap_or(&capsule.file_path)
+                .to_path_buf();
+            let module = modules
+                .entry(file.clone())
+                .or_insert_with(|| ModuleErrorTypes {
+                    file,
+                    public_functions: 0,
+                    broad: Vec::new(),
+                    error_types: Vec::new(),
+                });
+            module.public_functions += 1;
+            if !module.error_types.contains(&flow.error_type) {
+                module.error_types.push(flow.error_type.clone());
+            }
+            if flow.broad {
+                module.broad.push(BroadErrorApi {
+                    function: capsule.name.clone(),
+                    line: capsule.line_start,
+                    error_type: flow.error_type,
+                    sources: flow.sources,
+                });
+            }
+        }
+        let mut modules: Vec<ModuleErrorTypes> = modules
+            .into_values()
+            .filter(|m| !m.broad.is_empty())
+            .map(|mut m| {
+                m.broad.sort_by_key(|b| b.line);
+                m.error_types.sort();
+                m
+            })
+            .collect();
+        if modules.is_empty() {
+            return None;
+        }
+        modules.sort_by(|a, b| b.broad.len().cmp(&a.broad.len()).then(a.file.cmp(&b.file)));
+        Some(Self { modules })
+    }
+
+    /// Раздел ai_compact: модули и их функции с широкими ошибками
+    pub fn compact_section(&self) -> String {
+        let mut out = String::from("\n## Broad Error Types (public API)\n");
+        for module in self.modules.iter().take(COMPACT_SHOWN) {
+            out.push_str(&format!(
+                "- {}: {}/{} public fns\n",
+                module.file.display(),
+                module.broad.len(),
+                module.public_functions
+            ));
+            for api in &module.broad {
+                out.push_str(&format!("  - {} -> {}", api.function, api.error_type));
+                if !api.sources.is_empty() {
+                    let sources: Vec<String> = api
+                        .sources
+                        .iter()
+                        .map(|s| format!("{}::{}", s.module, s.function))
+                        .collect();
+                    out.push_str(&format!(" (from {})", sources.join(", ")));
+                }
+                out.push('\n');
+            }
+        }
+        if self.modules.len() > COMPACT_SHOWN {
+            out.push_str(&format!(
+                "- ... and {} more\n",
+                self.modules.len() - COMPACT_SHOWN
+            ));
+        }
+        out
+    }
+}
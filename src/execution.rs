@@ -0,0 +1,50 @@
+// Режим выполнения: детерминированный однопоточный прогон (`--single-thread`)
+// для отладки конвейера и воспроизведения отчётов пользователей. Флаг
+// переносится в переменную окружения, чтобы его видели все модули и дочерние
+// процессы без протаскивания через сигнатуры
+
+use std::path::Path;
+
+/// Флаг командной строки; допустим в любой позиции
+pub const SINGLE_THREAD_FLAG: &str = "--single-thread";
+/// Переменная окружения режима: `1`/`true` включают его
+pub const SINGLE_THREAD_ENV: &str = "ARCHLENS_SINGLE_THREAD";
+
+/// Включён ли однопоточный режим
+pub fn single_threaded() -> bool {
+    std::env::var(SINGLE_THREAD_ENV)
+        .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// Включает режим, если среди аргументов есть `--single-thread`; вызывается
+/// до создания рантайма и запуска потоков
+pub fn configure_from_args<I, S>(args: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    if args.into_iter().any(|a| a.as_ref() == SINGLE_THREAD_FLAG) {
+        std::env::set_var(SINGLE_THREAD_ENV, "1");
+    }
+}
+
+/// Рантайм tokio: в однопоточном режиме — `current_thread`, без пула потоков
+pub fn runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    if single_threaded() {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+    } else {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+    }
+}
+
+/// Фиксированный порядок файлов в однопоточном режиме: порядок `read_dir`
+/// зависит от файловой системы
+pub fn fix_order<T>(items: &mut [T], path: impl Fn(&T) -> &Path) {
+    if single_threaded() {
+        items.sort_by(|a, b| path(a).cmp(path(b)));
+    }
+}
@@ -0,0 +1,259 @@
+// Оценка размера экспорта (`export ... --dry-run`): сколько капсул, разделов,
+// символов и токенов получит потребитель на каждом уровне детализации. Уровни
+// повторяют `detail_level` MCP-сервера: summary и standard без блоков кода и с
+// лимитом символов, full — полный отчёт. Уровень `auto` выбирает один из них
+// по размеру проекта и бюджету символов
+
+use crate::types::CapsuleGraph;
+use serde::{Deserialize, Serialize};
+
+/// Примерное число символов на токен для английского текста и кода
+const CHARS_PER_TOKEN: usize = 4;
+/// До скольких файлов/капсул `auto` отдаёт full и standard
+const AUTO_FULL_MAX_SIZE: usize = 150;
+const AUTO_STANDARD_MAX_SIZE: usize = 1_500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetailLevel {
+    Summary,
+    Standard,
+    Full,
+}
+
+impl DetailLevel {
+    pub const ALL: [DetailLevel; 3] = [Self::Summary, Self::Standard, Self::Full];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Summary => "summary",
+            Self::Standard => "standard",
+            Self::Full => "full",
+        }
+    }
+
+    /// `summary`, `standard` или `full`; `auto` и неизвестные значения — `None`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "summary" => Some(Self::Summary),
+            "standard" => Some(Self::Standard),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+
+    /// Уровень `auto` для проекта из `size` файлов (или капсул) и бюджета символов:
+    /// небольшие проекты получают полный отчёт, крупные — сводку с короткими списками
+    pub fn auto(size: usize, budget: Option<usize>) -> AutoDetail {
+        let mut level = if size <= AUTO_FULL_MAX_SIZE {
+            Self::Full
+        } else if size <= AUTO_STANDARD_MAX_SIZE {
+            Self::Standard
+        } else {
+            Self::Summary
+        };
+        if let Some(budget) = budget {
+            while level != Self::Summary && level.expected_chars(size) > budget {
+                level = level.lower();
+            }
+        }
+        let top_n = match level {
+            Self::Full => None,
+            Self::Standard => Some(20),
+            Self::Summary if size > AUTO_STANDARD_MAX_SIZE * 10 => Some(5),
+            Self::Summary => Some(10),
+        };
+        AutoDetail { level, top_n }
+    }
+
+    fn lower(&self) -> Self {
+        match self {
+            Self::Full => Self::Standard,
+            _ => Self::Summary,
+        }
+    }
+
+    /// Ожидаемый объём отчёта: символов на файл уровня, но не больше его лимита
+    fn expected_chars(&self, size: usize) -> usize {
+        let per_unit = match self {
+            Self::Summary => 100,
+            Self::Standard => 300,
+            Self::Full => 800,
+        };
+        size.saturating_mul(per_unit).min(self.char_limit())
+    }
+
+    /// Лимит символов уровня
+    pub fn char_limit(&self) -> usize {
+        match self {
+            Self::Summary => 30_000,
+            Self::Standard => 60_000,
+            Self::Full => 1_000_000,
+        }
+    }
+
+    /// Содержимое на этом уровне и признак обрезки по лимиту
+    pub fn apply(&self, content: &str) -> (String, bool) {
+        let content = match self {
+            Self::Full => content.to_string(),
+            _ => strip_code_blocks(content),
+        };
+        let limit = self.char_limit();
+        if content.chars().count() <= limit {
+            return (content, false);
+        }
+        (content.chars().take(limit).collect(), true)
+    }
+}
+
+/// Выбор уровня `auto`: уровень и лимит элементов в списочных разделах
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoDetail {
+    pub level: DetailLevel,
+    pub top_n: Option<usize>,
+}
+
+/// Оценка одного уровня детализации
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LevelEstimate {
+    pub level: DetailLevel,
+    /// Капсулы графа, упомянутые в тексте уровня
+    pub capsules: usize,
+    /// Заголовки markdown/HTML или строки данных CSV
+    pub sections: usize,
+    pub characters: usize,
+    pub tokens: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportEstimate {
+    pub format: String,
+    pub total_capsules: usize,
+    pub levels: Vec<LevelEstimate>,
+    /// Что выберет `auto` без бюджета
+    pub auto: AutoDetail,
+}
+
+impl ExportEstimate {
+    /// Оценивает отрисованный в памяти отчёт `content` формата `format`
+    pub fn new(format: &str, content: &str, graph: &CapsuleGraph) -> Self {
+        let levels = DetailLevel::ALL
+            .iter()
+            .map(|level| {
+                let (text, truncated) = level.apply(content);
+                let characters = text.chars().count();
+                LevelEstimate {
+                    level: *level,
+                    capsules: graph
+                        .capsules
+                        .values()
+                        .filter(|c| !c.name.is_empty() && text.contains(c.name.as_str()))
+                        .count(),
+                    sections: count_sections(format, &text),
+                    characters,
+                    tokens: estimate_tokens(characters),
+                    truncated,
+                }
+            })
+            .collect();
+        Self {
+            format: format.to_string(),
+            total_capsules: graph.capsules.len(),
+            levels,
+            auto: DetailLevel::auto(graph.capsules.len(), None),
+        }
+    }
+
+    pub fn level(&self, level: DetailLevel) -> Option<&LevelEstimate> {
+        self.levels.iter().find(|l| l.level == level)
+    }
+
+    /// Таблица для терминала
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Dry run: {} export, {} capsules in graph (nothing written)\n\n",
+            self.format, self.total_capsules
+        );
+        out.push_str(&format!(
+            "{:<10} {:>9} {:>9} {:>12} {:>10}\n",
+            "level", "capsules", "sections", "characters", "~tokens"
+        ));
+        for level in &self.levels {
+            out.push_str(&format!(
+                "{:<10} {:>9} {:>9} {:>12} {:>10}{}\n",
+                level.level.as_str(),
+                level.capsules,
+                level.sections,
+                level.characters,
+                level.tokens,
+                if level.truncated { "  (truncated)" } else { "" }
+            ));
+        }
+        out.push_str(&format!("auto       -> {}", self.auto.level.as_str()));
+        if let Some(top_n) = self.auto.top_n {
+            out.push_str(&format!(" (top {} items per section)", top_n));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Токены по числу символов (округление вверх)
+pub fn estimate_tokens(characters: usize) -> usize {
+    characters.div_ceil(CHARS_PER_TOKEN)
+}
+
+fn count_sections(format: &str, text: &str) -> usize {
+    match format {
+        "html" | "email" => ["<h1", "<h2", "<h3"]
+            .iter()
+            .map(|tag| text.matches(tag).count())
+            .sum(),
+        // первая строка — заголовок CSV
+        "backlog" => text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .count()
+            .saturating_sub(1),
+        _ => {
+            let mut in_code = false;
+            text.lines()
+                .filter(|line| {
+                    if line.trim_start().starts_with("```") {
+                        in_code = !in_code;
+                        return false;
+                    }
+                    !in_code && line.starts_with('#')
+                })
+                .count()
+        }
+    }
+}
+
+/// Блоки ``` убираются целиком, как в ответах MCP-сервера
+fn strip_code_blocks(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_code = false;
+    let mut blank_run = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        // не больше одной пустой строки подряд
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+    }
+    out
+}
@@ -1,31 +1,114 @@
+use crate::advanced_metrics::hotspots::HotspotAnalyzer;
+use crate::api_surface::{capsule_api_surface, ApiSurfaceReport, ApiSymbol};
+use crate::code_origin::CodeOrigin;
+use crate::diagram_layout::{DiagramLayout, LayoutPlanner, NodePosition, LAYOUT_CELL_WIDTH};
+use crate::error_propagation::ErrorPropagationReport;
+use crate::external_deps::ExternalDependencyReport;
+use crate::finding_caps::FindingCaps;
+use crate::graph::{CyclesDetail, MetricsCalculator, ModularizationReport, SinglePointsOfFailure};
+use crate::graph_archive::{GraphArchive, ARCHIVE_EXTENSION, ARCHIVE_FORMAT};
+use crate::hierarchy::{CapsuleHierarchy, HierarchyLevel, HierarchyNode};
+use crate::overlays::{
+    cpu_hotspots, risk_quadrants, CoverageOverlay, CoverageRiskQuadrants, ProfileOverlay,
+};
+use crate::ownership::{OwnershipEntry, OwnershipReport, SINGLE_OWNER_THRESHOLD};
+use crate::panic_isolation::AnalysisCoverage;
+use crate::relation_locations::RelationLocator;
+use crate::state_machines::StateMachineDetector;
+use crate::todo_report::TodoReport;
 use crate::types::Result;
 use crate::types::*;
+use crate::validation::InterfaceUsageReport;
+use crate::visibility::VisibilityReport;
 use serde_json;
-use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use uuid::Uuid;
 
 /// Экспортер результатов анализа в различные форматы
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Exporter {
     #[allow(dead_code)]
     mermaid_theme: String,
+    /// Обзор архитектуры от LLM (`crate::llm`) для отчётов
+    overview: Option<String>,
+    /// Список находок в ai_compact с лимитами; `None` — только агрегаты
+    finding_caps: Option<FindingCaps>,
+    /// Тестовые капсулы в агрегатах сложности и связности (`--include-tests`)
+    include_test_code: bool,
+    /// Покрытие анализа: файлы, упавшие при разборе
+    coverage: Option<AnalysisCoverage>,
 }
 
 impl Exporter {
     pub fn new() -> Self {
         Self {
             mermaid_theme: "default".to_string(),
+            overview: None,
+            finding_caps: None,
+            include_test_code: false,
+            coverage: None,
         }
     }
 
     pub fn with_theme(theme: String) -> Self {
         Self {
             mermaid_theme: theme,
+            overview: None,
+            finding_caps: None,
+            include_test_code: false,
+            coverage: None,
         }
     }
 
+    pub fn with_overview(mut self, overview: Option<String>) -> Self {
+        self.overview = overview;
+        self
+    }
+
+    /// Перечислять находки в ai_compact с лимитами на капсулу и категорию
+    /// Учитывать тестовый код в рейтингах сложности и связности
+    pub fn with_test_code(mut self, include: bool) -> Self {
+        self.include_test_code = include;
+        self
+    }
+
+    /// Капсулы для агрегатов сложности и связности: без тестовых, если не включены
+    fn counts_in_aggregates(&self, capsule: &Capsule) -> bool {
+        self.include_test_code || CodeOrigin::of(capsule) != CodeOrigin::Test
+    }
+
+    /// Степень связности капсул по связям между учитываемыми капсулами
+    fn coupling_degrees(&self, graph: &CapsuleGraph) -> HashMap<Uuid, usize> {
+        let counted = |id: &Uuid| {
+            graph
+                .capsules
+                .get(id)
+                .is_some_and(|c| self.counts_in_aggregates(c))
+        };
+        let mut degree: HashMap<Uuid, usize> = HashMap::new();
+        for r in graph
+            .relations
+            .iter()
+            .filter(|r| counted(&r.from_id) && counted(&r.to_id))
+        {
+            *degree.entry(r.from_id).or_insert(0) += 1;
+            *degree.entry(r.to_id).or_insert(0) += 1;
+        }
+        degree
+    }
+
+    pub fn with_finding_caps(mut self, caps: FindingCaps) -> Self {
+        self.finding_caps = Some(caps);
+        self
+    }
+
+    /// Перечислять в ai_compact файлы, анализ которых упал
+    pub fn with_coverage(mut self, coverage: AnalysisCoverage) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
+
     /// Основной метод экспорта
     pub fn export(
         &self,
@@ -33,30 +116,37 @@ impl Exporter {
         format: ExportFormat,
         output_path: &Path,
     ) -> Result<String> {
-        let content = match format {
-            ExportFormat::JSON => self.export_to_json(graph)?,
-            ExportFormat::YAML => self.export_to_yaml(graph)?,
-            ExportFormat::Mermaid => self.export_to_mermaid(graph)?,
-            ExportFormat::DOT => self.export_to_dot(graph)?,
-            ExportFormat::GraphML => self.export_to_graphml(graph)?,
-            ExportFormat::SVG => self.export_to_svg(graph)?,
-            ExportFormat::InteractiveHTML => self.export_to_interactive_html(graph)?,
-            ExportFormat::ChainOfThought => self.export_to_chain_of_thought(graph)?,
-            ExportFormat::LLMPrompt => self.export_to_llm_prompt(graph)?,
-            ExportFormat::AICompact => self.export_to_ai_compact(graph)?,
-        };
+        let content = self.registry().export(format.name(), graph)?;
         std::fs::write(output_path, &content)?;
         Ok(content)
     }
 
+    /// Реестр встроенных форматов с настройками этого экспортера (тема, обзор)
+    pub fn registry(&self) -> ExporterRegistry {
+        ExporterRegistry::builtin(self.clone())
+    }
+
+    /// Покрытие анализа для отчётов; `None`, если упавших файлов нет
+    fn failed_coverage(&self) -> Option<&AnalysisCoverage> {
+        self.coverage.as_ref().filter(|c| !c.failed.is_empty())
+    }
+
     /// Экспорт в JSON формат
     pub fn export_to_json(&self, graph: &CapsuleGraph) -> Result<String> {
-        let json_graph = JsonGraph::from_capsule_graph(graph);
+        let mut json_graph = JsonGraph::from_capsule_graph(graph);
+        json_graph.analysis_coverage = self.failed_coverage().cloned();
         let json = serde_json::to_string_pretty(&json_graph)
             .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))?;
         Ok(json)
     }
 
+    /// Переносимый архив графа (`.archlens.graph`) для `archlens view`; корень
+    /// проекта — общий каталог файлов капсул
+    pub fn export_to_archive(&self, graph: &CapsuleGraph) -> Result<String> {
+        let root = crate::dsm::common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        GraphArchive::new(graph.clone(), &root).to_json()
+    }
+
     pub fn export_to_yaml(&self, graph: &CapsuleGraph) -> Result<String> {
         let mut yaml = String::new();
 
@@ -94,6 +184,10 @@ impl Exporter {
             graph.metrics.cyclomatic_complexity
         ));
         yaml.push_str(&format!("  depth_levels: {}\n", graph.metrics.depth_levels));
+        yaml.push_str(&format!(
+            "  layering_violation_index: {:.2}\n",
+            graph.metrics.layering_violation_index
+        ));
         yaml.push('\n');
 
         // Слои
@@ -155,40 +249,53 @@ impl Exporter {
         mermaid.push_str("    classDef classClass fill:#fff3e0,stroke:#e65100,stroke-width:2px\n");
         mermaid.push('\n');
 
-        // Группируем по слоям
-        for (layer_name, capsule_ids) in &graph.layers {
-            mermaid.push_str(&format!("    subgraph \"Слой: {layer_name}\"\n"));
+        // Группируем по топологическим уровням: зависящие компоненты сверху,
+        // основание (уровень 0, без зависимостей) внизу
+        let levels = MetricsCalculator::new().calculate_topological_levels(graph);
+        let mut by_level: BTreeMap<usize, Vec<&Capsule>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            by_level
+                .entry(levels.get(&capsule.id).copied().unwrap_or(0))
+                .or_default()
+                .push(capsule);
+        }
+        for (level, capsules) in by_level.iter_mut().rev() {
+            capsules.sort_by(|a, b| {
+                (&a.layer, &a.name, &a.file_path, a.line_start).cmp(&(
+                    &b.layer,
+                    &b.name,
+                    &b.file_path,
+                    b.line_start,
+                ))
+            });
+            mermaid.push_str(&format!(
+                "    subgraph level_{level}[\"Уровень {level}\"]\n"
+            ));
+            mermaid.push_str("        direction LR\n");
 
-            for capsule_id in capsule_ids {
-                if let Some(capsule) = graph.capsules.get(capsule_id) {
-                    let node_id = self.sanitize_node_id(&capsule.name);
-                    let display_name = self.truncate_name(&capsule.name, 20);
-
-                    match capsule.capsule_type {
-                        CapsuleType::Module => {
-                            mermaid
-                                .push_str(&format!("        {node_id}[\"📦 {display_name}\"]\n"));
-                            mermaid.push_str(&format!("        {node_id}:::moduleClass\n"));
-                        }
-                        CapsuleType::Function | CapsuleType::Method => {
-                            mermaid
-                                .push_str(&format!("        {node_id}[\"⚙️ {display_name}\"]\n"));
-                            mermaid.push_str(&format!("        {node_id}:::functionClass\n"));
-                        }
-                        CapsuleType::Struct | CapsuleType::Enum => {
-                            mermaid
-                                .push_str(&format!("        {node_id}[\"🏗️ {display_name}\"]\n"));
-                            mermaid.push_str(&format!("        {node_id}:::structClass\n"));
-                        }
-                        CapsuleType::Class | CapsuleType::Interface => {
-                            mermaid
-                                .push_str(&format!("        {node_id}[\"🎯 {display_name}\"]\n"));
-                            mermaid.push_str(&format!("        {node_id}:::classClass\n"));
-                        }
-                        _ => {
-                            mermaid
-                                .push_str(&format!("        {node_id}[\"⚪ {display_name}\"]\n"));
-                        }
+            for capsule in capsules.iter() {
+                let node_id = self.sanitize_node_id(&capsule.name);
+                let display_name = self.truncate_name(&capsule.name, 20);
+
+                match capsule.capsule_type {
+                    CapsuleType::Module => {
+                        mermaid.push_str(&format!("        {node_id}[\"📦 {display_name}\"]\n"));
+                        mermaid.push_str(&format!("        {node_id}:::moduleClass\n"));
+                    }
+                    CapsuleType::Function | CapsuleType::Method => {
+                        mermaid.push_str(&format!("        {node_id}[\"⚙️ {display_name}\"]\n"));
+                        mermaid.push_str(&format!("        {node_id}:::functionClass\n"));
+                    }
+                    CapsuleType::Struct | CapsuleType::Enum => {
+                        mermaid.push_str(&format!("        {node_id}[\"🏗️ {display_name}\"]\n"));
+                        mermaid.push_str(&format!("        {node_id}:::structClass\n"));
+                    }
+                    CapsuleType::Class | CapsuleType::Interface => {
+                        mermaid.push_str(&format!("        {node_id}[\"🎯 {display_name}\"]\n"));
+                        mermaid.push_str(&format!("        {node_id}:::classClass\n"));
+                    }
+                    _ => {
+                        mermaid.push_str(&format!("        {node_id}[\"⚪ {display_name}\"]\n"));
                     }
                 }
             }
@@ -198,6 +305,8 @@ impl Exporter {
 
         // Добавляем связи
         mermaid.push_str("    %% Связи между компонентами\n");
+        let mut link_styles = Vec::new();
+        let mut link_index = 0;
         for relation in &graph.relations {
             if let (Some(from_capsule), Some(to_capsule)) = (
                 graph.capsules.get(&relation.from_id),
@@ -205,36 +314,64 @@ impl Exporter {
             ) {
                 let from_id = self.sanitize_node_id(&from_capsule.name);
                 let to_id = self.sanitize_node_id(&to_capsule.name);
+                let style = relation_style(relation.relation_type);
 
-                let arrow_style = match relation.relation_type {
-                    RelationType::Depends => "-->",
-                    RelationType::Uses => "-.->",
-                    RelationType::Implements => "==>",
-                    RelationType::Extends => "===>",
-                    RelationType::Aggregates => "--o",
-                    RelationType::Composes => "-->",
-                    RelationType::Calls => "-.->",
-                    RelationType::References => "-.->",
-                };
-
-                let label = if relation.strength > 0.7 {
+                // структурные связи подписаны силой, найденные в коде — типом
+                let label = if !relation.relation_type.is_structural() {
+                    relation.relation_type.as_str()
+                } else if relation.strength > 0.7 {
                     "strong"
                 } else if relation.strength > 0.4 {
                     "medium"
                 } else {
                     "weak"
                 };
-                mermaid.push_str(&format!("    {from_id} {arrow_style}|{label}| {to_id}\n"));
+                mermaid.push_str(&format!(
+                    "    {from_id} {}|{label}| {to_id}\n",
+                    style.mermaid_arrow
+                ));
+                if !relation.relation_type.is_structural() {
+                    link_styles.push(format!(
+                        "    linkStyle {link_index} stroke:{}\n",
+                        style.color
+                    ));
+                }
+                link_index += 1;
             }
         }
+        for link_style in link_styles {
+            mermaid.push_str(&link_style);
+        }
 
         Ok(mermaid)
     }
 
     pub fn export_to_dot(&self, graph: &CapsuleGraph) -> Result<String> {
+        self.dot_with_positions(graph, None)
+    }
+
+    /// DOT с закреплёнными позициями узлов (`neato -n` сохраняет раскладку)
+    pub fn export_to_dot_with_layout(
+        &self,
+        graph: &CapsuleGraph,
+        planner: &LayoutPlanner,
+        layout: &DiagramLayout,
+    ) -> Result<String> {
+        let positions = planner.positions(graph, layout);
+        self.dot_with_positions(graph, Some((&positions, layout.height())))
+    }
+
+    fn dot_with_positions(
+        &self,
+        graph: &CapsuleGraph,
+        positions: Option<(&HashMap<Uuid, NodePosition>, u32)>,
+    ) -> Result<String> {
         let mut dot = String::new();
 
         dot.push_str("digraph architecture {\n");
+        if positions.is_some() {
+            dot.push_str("    layout=neato;\n");
+        }
         dot.push_str("    rankdir=TB;\n");
         dot.push_str("    node [shape=box, style=filled];\n");
         dot.push_str("    edge [fontsize=10];\n\n");
@@ -251,15 +388,24 @@ impl Exporter {
             };
 
             let node_id = self.sanitize_node_id(&capsule.name);
+            // у Graphviz ось y направлена вверх
+            let pos = positions
+                .and_then(|(positions, height)| {
+                    let p = positions.get(&capsule.id)?;
+                    Some(format!(", pos=\"{},{}!\"", p.x, height - p.y))
+                })
+                .unwrap_or_default();
             dot.push_str(&format!(
-                "    \"{}\" [fillcolor={}, label=\"{}\"];\n",
+                "    \"{}\" [fillcolor={}, label=\"{}\"{}];\n",
                 node_id,
                 color,
-                self.escape_label(&capsule.name)
+                self.escape_label(&capsule.name),
+                pos
             ));
         }
 
         dot.push_str("\n    // Связи\n");
+        let mut locator = RelationLocator::new(graph);
         for relation in &graph.relations {
             if let (Some(from_capsule), Some(to_capsule)) = (
                 graph.capsules.get(&relation.from_id),
@@ -267,18 +413,32 @@ impl Exporter {
             ) {
                 let from_id = self.sanitize_node_id(&from_capsule.name);
                 let to_id = self.sanitize_node_id(&to_capsule.name);
-
-                let style = match relation.relation_type {
-                    RelationType::Depends => "solid",
-                    RelationType::Uses => "dashed",
-                    RelationType::Implements => "bold",
-                    _ => "dotted",
-                };
-
-                dot.push_str(&format!(
-                    "    \"{}\" -> \"{}\" [style={}, label=\"{:.1}\"];\n",
-                    from_id, to_id, style, relation.strength
-                ));
+                // место в коде — во всплывающей подсказке ребра
+                let tooltip = locator
+                    .locate(relation)
+                    .map(|location| {
+                        format!(", tooltip=\"{}\"", self.escape_label(&location.to_string()))
+                    })
+                    .unwrap_or_default();
+
+                let style = relation_style(relation.relation_type);
+                if relation.relation_type.is_structural() {
+                    dot.push_str(&format!(
+                        "    \"{}\" -> \"{}\" [style={}, label=\"{:.1}\"{}];\n",
+                        from_id, to_id, style.dot_style, relation.strength, tooltip
+                    ));
+                } else {
+                    dot.push_str(&format!(
+                        "    \"{}\" -> \"{}\" [style={}, color=\"{}\", arrowhead={}, label=\"{}\"{}];\n",
+                        from_id,
+                        to_id,
+                        style.dot_style,
+                        style.color,
+                        style.dot_arrowhead,
+                        relation.relation_type.as_str(),
+                        tooltip
+                    ));
+                }
             }
         }
 
@@ -304,6 +464,9 @@ impl Exporter {
         graphml.push_str(
             "  <key id=\"strength\" for=\"edge\" attr.name=\"strength\" attr.type=\"double\"/>\n",
         );
+        graphml.push_str(
+            "  <key id=\"location\" for=\"edge\" attr.name=\"location\" attr.type=\"string\"/>\n",
+        );
         graphml.push_str("  <graph id=\"architecture\" edgedefault=\"directed\">\n");
 
         // Узлы
@@ -325,6 +488,7 @@ impl Exporter {
         }
 
         // Ребра
+        let mut locator = RelationLocator::new(graph);
         for relation in &graph.relations {
             graphml.push_str(&format!(
                 "    <edge source=\"{}\" target=\"{}\">\n",
@@ -338,6 +502,12 @@ impl Exporter {
                 "      <data key=\"strength\">{}</data>\n",
                 relation.strength
             ));
+            if let Some(location) = locator.locate(relation) {
+                graphml.push_str(&format!(
+                    "      <data key=\"location\">{}</data>\n",
+                    self.escape_xml(&location.to_string())
+                ));
+            }
             graphml.push_str("    </edge>\n");
         }
 
@@ -347,44 +517,125 @@ impl Exporter {
     }
 
     pub fn export_to_svg(&self, graph: &CapsuleGraph) -> Result<String> {
+        let planner = LayoutPlanner::new();
+        let layout = planner.compute(graph, None);
+        self.export_to_svg_with_layout(graph, &planner, &layout)
+    }
+
+    /// SVG по готовой раскладке (например, из LayoutCache)
+    pub fn export_to_svg_with_layout(
+        &self,
+        graph: &CapsuleGraph,
+        planner: &LayoutPlanner,
+        layout: &DiagramLayout,
+    ) -> Result<String> {
+        let positions = planner.positions(graph, layout);
+        let width = layout.width().max(800);
+        let height = layout.height().max(600);
+        let node_width = LAYOUT_CELL_WIDTH - 40;
         let mut svg = String::new();
 
         svg.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
-        svg.push_str("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 800 600\" width=\"800\" height=\"600\">\n");
-        svg.push_str("  <text x=\"400\" y=\"50\" text-anchor=\"middle\" font-family=\"Arial\" font-size=\"16\">Архитектурная диаграмма</text>\n");
         svg.push_str(&format!(
-            "  <text x=\"400\" y=\"80\" text-anchor=\"middle\" font-family=\"Arial\" font-size=\"12\">Компонентов: {}, Связей: {}</text>\n",
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {w} {h}\" width=\"{w}\" height=\"{h}\">\n",
+            w = width,
+            h = height
+        ));
+        svg.push_str(&format!("  <text x=\"{}\" y=\"50\" text-anchor=\"middle\" font-family=\"Arial\" font-size=\"16\">Архитектурная диаграмма</text>\n", width / 2));
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"80\" text-anchor=\"middle\" font-family=\"Arial\" font-size=\"12\">Компонентов: {}, Связей: {}</text>\n",
+            width / 2,
             graph.capsules.len(),
             graph.relations.len()
         ));
 
-        let mut y = 120;
-        for capsule in graph.capsules.values() {
-            svg.push_str(&format!("  <rect x=\"100\" y=\"{}\" width=\"600\" height=\"30\" fill=\"lightblue\" stroke=\"black\"/>\n", y));
+        let mut edges: Vec<(&NodePosition, &NodePosition)> = graph
+            .relations
+            .iter()
+            .filter_map(|r| Some((positions.get(&r.from_id)?, positions.get(&r.to_id)?)))
+            .collect();
+        edges.sort();
+        edges.dedup();
+        for (from, to) in edges {
             svg.push_str(&format!(
-                "  <text x=\"110\" y=\"{}\" font-family=\"Arial\" font-size=\"12\">{}</text>\n",
-                y + 20,
-                capsule.name
+                "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#999\"/>\n",
+                from.x + node_width / 2,
+                from.y + 15,
+                to.x + node_width / 2,
+                to.y + 15
+            ));
+        }
+
+        // Порядок вывода — по позиции, чтобы SVG одинаковых раскладок совпадали
+        let mut nodes: Vec<(&NodePosition, &Capsule)> = graph
+            .capsules
+            .values()
+            .filter_map(|c| Some((positions.get(&c.id)?, c)))
+            .collect();
+        nodes.sort_by_key(|(p, c)| (p.x, p.y, c.name.clone()));
+        for (position, capsule) in nodes {
+            svg.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"30\" fill=\"lightblue\" stroke=\"black\"/>\n", position.x, position.y, node_width));
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" font-family=\"Arial\" font-size=\"12\">{}</text>\n",
+                position.x + 10,
+                position.y + 20,
+                self.escape_xml(&capsule.name)
             ));
-            y += 40;
         }
 
         svg.push_str("</svg>\n");
         Ok(svg)
     }
 
-    /// Экспорт в интерактивный HTML
+    /// Экспорт в интерактивный HTML: поиск, фильтры по важности и слою,
+    /// панель проблем и постоянные ссылки (#якорь) на капсулы и находки
     pub fn export_to_interactive_html(&self, graph: &CapsuleGraph) -> Result<String> {
         let mut html = String::new();
+        let state_machines = StateMachineDetector::new().detect(graph);
+
+        let mut capsules: Vec<&Capsule> = graph.capsules.values().collect();
+        capsules.sort_by(|a, b| {
+            (&a.layer, &a.file_path, a.line_start, &a.name).cmp(&(
+                &b.layer,
+                &b.file_path,
+                b.line_start,
+                &b.name,
+            ))
+        });
+        let anchors = capsule_anchors(&capsules);
+        let layers: std::collections::BTreeSet<&str> =
+            capsules.iter().map(|c| layer_name(c)).collect();
 
         html.push_str("<!DOCTYPE html>\n");
         html.push_str("<html>\n");
         html.push_str("<head>\n");
+        html.push_str("  <meta charset=\"utf-8\">\n");
         html.push_str("  <title>Архитектурная диаграмма</title>\n");
         html.push_str("  <style>\n");
         html.push_str("    body { font-family: Arial, sans-serif; margin: 20px; }\n");
+        html.push_str("    .layout { display: flex; gap: 20px; align-items: flex-start; }\n");
+        html.push_str("    main { flex: 1; }\n");
+        html.push_str("    #problems { width: 340px; position: sticky; top: 10px; max-height: 95vh; overflow-y: auto; }\n");
+        html.push_str("    #problems li { margin-bottom: 6px; }\n");
+        html.push_str("    .filters { display: flex; gap: 10px; margin-bottom: 10px; }\n");
         html.push_str("    .component { margin: 10px; padding: 10px; border: 1px solid #ccc; }\n");
+        html.push_str(
+            "    .component:target, .warnings li:target { outline: 3px solid #f0b400; }\n",
+        );
+        html.push_str("    .permalink { color: #999; text-decoration: none; margin-left: 6px; }\n");
+        html.push_str("    .sev-4 { color: #b00020; } .sev-3 { color: #d35400; } .sev-2 { color: #b7950b; } .sev-1 { color: #555; }\n");
+        html.push_str(
+            "    .state-machine { margin-top: 8px; padding: 8px; background: #f7f7fb; }\n",
+        );
         html.push_str("  </style>\n");
+        if !state_machines.is_empty() {
+            html.push_str("  <script type=\"module\">\n");
+            html.push_str(
+                "    import mermaid from 'https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.esm.min.mjs';\n",
+            );
+            html.push_str("    mermaid.initialize({ startOnLoad: true });\n");
+            html.push_str("  </script>\n");
+        }
         html.push_str("</head>\n");
         html.push_str("<body>\n");
         html.push_str("  <h1>Архитектурная диаграмма</h1>\n");
@@ -393,18 +644,392 @@ impl Exporter {
             graph.capsules.len(),
             graph.relations.len()
         ));
+        if let Some(overview) = &self.overview {
+            html.push_str(&format!(
+                "  <section id=\"overview\"><h2>Обзор</h2><p>{}</p></section>\n",
+                self.escape_xml(overview)
+            ));
+        }
 
-        for capsule in graph.capsules.values() {
-            html.push_str("  <div class=\"component\">\n");
-            html.push_str(&format!("    <h3>{}</h3>\n", capsule.name));
-            html.push_str(&format!("    <p>Сложность: {}</p>\n", capsule.complexity));
+        html.push_str("  <div class=\"filters\">\n");
+        html.push_str("    <input id=\"search\" type=\"search\" placeholder=\"Поиск по имени, файлу, проблеме\">\n");
+        html.push_str("    <select id=\"severity\">\n");
+        html.push_str("      <option value=\"0\">Все компоненты</option>\n");
+        for (rank, label) in [(1, "Low+"), (2, "Medium+"), (3, "High+"), (4, "Critical")] {
             html.push_str(&format!(
-                "    <p>Файл: {}</p>\n",
-                capsule.file_path.display()
+                "      <option value=\"{}\">{}</option>\n",
+                rank, label
             ));
-            html.push_str("  </div>\n");
+        }
+        html.push_str("    </select>\n");
+        html.push_str("    <select id=\"layer\">\n");
+        html.push_str("      <option value=\"\">Все слои</option>\n");
+        for layer in &layers {
+            html.push_str(&format!(
+                "      <option value=\"{0}\">{0}</option>\n",
+                self.escape_xml(layer)
+            ));
+        }
+        html.push_str("    </select>\n");
+        html.push_str("  </div>\n");
+        html.push_str("  <div class=\"layout\">\n");
+
+        // Панель проблем: все находки по убыванию важности со ссылками на них
+        let mut problems: Vec<(&Capsule, usize, &AnalysisWarning)> = capsules
+            .iter()
+            .flat_map(|c| c.warnings.iter().enumerate().map(move |(i, w)| (*c, i, w)))
+            .collect();
+        problems.sort_by_key(|(c, i, w)| {
+            (
+                std::cmp::Reverse(severity_rank(&w.level)),
+                anchors[&c.id].clone(),
+                *i,
+            )
+        });
+        html.push_str("    <aside id=\"problems\">\n");
+        html.push_str(&format!("      <h2>Проблемы ({})</h2>\n", problems.len()));
+        html.push_str("      <ul>\n");
+        for (capsule, index, warning) in &problems {
+            html.push_str(&format!(
+                "        <li class=\"sev-{rank}\" data-severity=\"{rank}\" data-capsule=\"{anchor}\"><a href=\"#{anchor}-w{index}\">[{level:?}] {message}</a> — {name}</li>\n",
+                rank = severity_rank(&warning.level),
+                anchor = anchors[&capsule.id],
+                index = index,
+                level = warning.level,
+                message = self.escape_xml(&warning.message),
+                name = self.escape_xml(&capsule.name)
+            ));
+        }
+        html.push_str("      </ul>\n");
+        html.push_str("    </aside>\n");
+        html.push_str("    <main>\n");
+
+        for capsule in &capsules {
+            let anchor = &anchors[&capsule.id];
+            let severity = capsule
+                .warnings
+                .iter()
+                .map(|w| severity_rank(&w.level))
+                .max()
+                .unwrap_or(0);
+            let search_text = std::iter::once(capsule.name.clone())
+                .chain(std::iter::once(capsule.file_path.display().to_string()))
+                .chain(capsule.warnings.iter().map(|w| w.message.clone()))
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase();
+            html.push_str(&format!(
+                "      <div class=\"component\" id=\"{}\" data-layer=\"{}\" data-severity=\"{}\" data-search=\"{}\">\n",
+                anchor,
+                self.escape_xml(layer_name(capsule)),
+                severity,
+                self.escape_xml(&search_text)
+            ));
+            html.push_str(&format!(
+                "        <h3>{}<a class=\"permalink\" href=\"#{}\" title=\"Постоянная ссылка\">#</a></h3>\n",
+                self.escape_xml(&capsule.name),
+                anchor
+            ));
+            html.push_str(&format!(
+                "        <p>Слой: {}, Сложность: {}</p>\n",
+                self.escape_xml(layer_name(capsule)),
+                capsule.complexity
+            ));
+            html.push_str(&format!(
+                "        <p>Файл: {}:{}</p>\n",
+                self.escape_xml(&capsule.file_path.display().to_string()),
+                capsule.line_start
+            ));
+            if let Some(summary) = capsule.metadata.get(crate::llm::LLM_SUMMARY_KEY) {
+                html.push_str(&format!(
+                    "        <p class=\"summary\">{}</p>\n",
+                    self.escape_xml(summary)
+                ));
+            }
+            if !capsule.warnings.is_empty() {
+                html.push_str("        <ul class=\"warnings\">\n");
+                for (index, warning) in capsule.warnings.iter().enumerate() {
+                    html.push_str(&format!(
+                        "          <li id=\"{anchor}-w{index}\" class=\"sev-{rank}\">[{level:?}] {message}{suggestion}<a class=\"permalink\" href=\"#{anchor}-w{index}\">#</a></li>\n",
+                        anchor = anchor,
+                        index = index,
+                        rank = severity_rank(&warning.level),
+                        level = warning.level,
+                        message = self.escape_xml(&warning.message),
+                        suggestion = warning
+                            .suggestion
+                            .as_ref()
+                            .map(|s| format!(" — {}", self.escape_xml(s)))
+                            .unwrap_or_default()
+                    ));
+                }
+                html.push_str("        </ul>\n");
+            }
+            for machine in state_machines.get(&capsule.id).into_iter().flatten() {
+                html.push_str("        <div class=\"state-machine\">\n");
+                html.push_str(&format!(
+                    "          <h4>Автомат состояний: {} ({} состояний, {} переходов)</h4>\n",
+                    self.escape_xml(&machine.name),
+                    machine.states.len(),
+                    machine.transitions.len()
+                ));
+                html.push_str(&format!(
+                    "          <pre class=\"mermaid\">\n{}          </pre>\n",
+                    self.escape_xml(&machine.to_mermaid())
+                ));
+                html.push_str("        </div>\n");
+            }
+            html.push_str("      </div>\n");
         }
 
+        html.push_str("    </main>\n");
+        html.push_str("  </div>\n");
+        html.push_str(INTERACTIVE_HTML_SCRIPT);
+        html.push_str("</body>\n");
+        html.push_str("</html>\n");
+        Ok(html)
+    }
+
+    /// Матрицы структурных зависимостей слоёв и модулей, циклы подсвечены
+    pub fn export_to_dsm_html(&self, graph: &CapsuleGraph) -> Result<String> {
+        Ok(crate::dsm::dsm_html(graph))
+    }
+
+    pub fn export_to_module_dsm_csv(&self, graph: &CapsuleGraph) -> Result<String> {
+        Ok(crate::dsm::DependencyMatrix::build(graph, crate::dsm::DsmLevel::Module).to_csv())
+    }
+
+    pub fn export_to_layer_dsm_csv(&self, graph: &CapsuleGraph) -> Result<String> {
+        Ok(crate::dsm::DependencyMatrix::build(graph, crate::dsm::DsmLevel::Layer).to_csv())
+    }
+
+    /// Сводка для email-рассылки (еженедельный дайджест): только таблицы и inline CSS,
+    /// без `<style>`, скриптов и внешних ресурсов — почтовые клиенты их вырезают
+    pub fn export_to_email_summary(&self, graph: &CapsuleGraph) -> Result<String> {
+        const FONT: &str = "font-family: Arial, Helvetica, sans-serif;";
+        const CELL: &str = "padding: 6px 8px; border-bottom: 1px solid #e5e5e5;";
+        let heading = |title: &str| {
+            format!(
+                "        <tr><td style=\"{} padding: 20px 24px 6px; font-size: 16px; font-weight: bold; color: #222222;\">{}</td></tr>\n",
+                FONT,
+                self.escape_xml(title)
+            )
+        };
+        // Таблица внутри строки письма; пустые секции не выводятся
+        let table = |headers: &[&str], rows: Vec<Vec<String>>| -> String {
+            let mut out = String::new();
+            out.push_str("        <tr><td style=\"padding: 0 24px;\">\n");
+            out.push_str(&format!(
+                "          <table role=\"presentation\" width=\"100%\" cellpadding=\"0\" cellspacing=\"0\" border=\"0\" style=\"{} font-size: 13px; border-collapse: collapse;\">\n",
+                FONT
+            ));
+            out.push_str("            <tr>");
+            for header in headers {
+                out.push_str(&format!(
+                    "<th align=\"left\" style=\"{} color: #666666; font-weight: bold;\">{}</th>",
+                    CELL,
+                    self.escape_xml(header)
+                ));
+            }
+            out.push_str("</tr>\n");
+            for row in rows {
+                out.push_str("            <tr>");
+                for cell in row {
+                    out.push_str(&format!(
+                        "<td style=\"{} color: #222222;\">{}</td>",
+                        CELL, cell
+                    ));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("          </table>\n");
+            out.push_str("        </td></tr>\n");
+            out
+        };
+
+        let warnings: Vec<(&Capsule, &AnalysisWarning)> = graph
+            .capsules
+            .values()
+            .flat_map(|c| c.warnings.iter().map(move |w| (c, w)))
+            .collect();
+        let mut by_severity = [0usize; 4];
+        for (_, w) in &warnings {
+            by_severity[4 - severity_rank(&w.level) as usize] += 1;
+        }
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n");
+        html.push_str("<html>\n");
+        html.push_str("<head>\n");
+        html.push_str("  <meta charset=\"utf-8\">\n");
+        html.push_str(
+            "  <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n",
+        );
+        html.push_str("  <title>Architecture digest</title>\n");
+        html.push_str("</head>\n");
+        html.push_str(&format!(
+            "<body style=\"margin: 0; padding: 0; background-color: #f4f4f4; {}\">\n",
+            FONT
+        ));
+        html.push_str("  <table role=\"presentation\" width=\"100%\" cellpadding=\"0\" cellspacing=\"0\" border=\"0\" style=\"background-color: #f4f4f4;\">\n");
+        html.push_str("    <tr><td align=\"center\" style=\"padding: 20px 0;\">\n");
+        html.push_str("      <table role=\"presentation\" width=\"600\" cellpadding=\"0\" cellspacing=\"0\" border=\"0\" style=\"width: 600px; max-width: 600px; background-color: #ffffff; border: 1px solid #dddddd;\">\n");
+        html.push_str(&format!(
+            "        <tr><td style=\"{} padding: 20px 24px; background-color: #2c3e50; color: #ffffff;\"><div style=\"font-size: 20px; font-weight: bold;\">Architecture digest</div><div style=\"font-size: 12px; color: #cfd8dc;\">{}</div></td></tr>\n",
+            FONT,
+            graph.created_at.format("%Y-%m-%d %H:%M UTC")
+        ));
+        if let Some(overview) = &self.overview {
+            html.push_str(&format!(
+                "        <tr><td style=\"{} padding: 16px 24px 0; font-size: 14px; line-height: 1.5; color: #222222;\">{}</td></tr>\n",
+                FONT,
+                self.escape_xml(overview)
+            ));
+        }
+
+        // Ключевые показатели
+        html.push_str(&heading("Summary"));
+        html.push_str(&table(
+            &["Metric", "Value"],
+            vec![
+                vec![
+                    "Components".into(),
+                    graph.metrics.total_capsules.to_string(),
+                ],
+                vec![
+                    "Relations".into(),
+                    graph.metrics.total_relations.to_string(),
+                ],
+                vec![
+                    "Complexity (avg)".into(),
+                    format!("{:.2}", graph.metrics.complexity_average),
+                ],
+                vec![
+                    "Coupling index".into(),
+                    format!("{:.2}", graph.metrics.coupling_index),
+                ],
+                vec![
+                    "Cohesion index".into(),
+                    format!("{:.2}", graph.metrics.cohesion_index),
+                ],
+                vec![
+                    "Warnings".into(),
+                    format!(
+                        "{} (critical {}, high {}, medium {}, low {})",
+                        warnings.len(),
+                        by_severity[0],
+                        by_severity[1],
+                        by_severity[2],
+                        by_severity[3]
+                    ),
+                ],
+            ],
+        ));
+
+        // Важнейшие проблемы
+        if !warnings.is_empty() {
+            let mut top = warnings.clone();
+            top.sort_by(|(ca, a), (cb, b)| {
+                severity_rank(&b.level)
+                    .cmp(&severity_rank(&a.level))
+                    .then_with(|| (&ca.file_path, &ca.name).cmp(&(&cb.file_path, &cb.name)))
+                    .then_with(|| a.message.cmp(&b.message))
+            });
+            let severity_color = |level: &Priority| match level {
+                Priority::Critical => "#b00020",
+                Priority::High => "#d35400",
+                Priority::Medium => "#b7950b",
+                Priority::Low => "#555555",
+            };
+            html.push_str(&heading("Top problems"));
+            html.push_str(&table(
+                &["Severity", "Component", "Problem"],
+                top.iter()
+                    .take(10)
+                    .map(|(capsule, w)| {
+                        vec![
+                            format!(
+                                "<span style=\"color: {}; font-weight: bold;\">{:?}</span>",
+                                severity_color(&w.level),
+                                w.level
+                            ),
+                            self.escape_xml(&capsule.name),
+                            self.escape_xml(&w.message),
+                        ]
+                    })
+                    .collect(),
+            ));
+        }
+
+        // Циклы зависимостей
+        let mut cycles = crate::graph::CycleDetector::new().find_cycles(graph);
+        if !cycles.is_empty() {
+            cycles.sort_by_key(|c| c.len());
+            html.push_str(&heading("Dependency cycles"));
+            html.push_str(&table(
+                &["Cycle"],
+                cycles
+                    .iter()
+                    .take(5)
+                    .map(|cycle| {
+                        let names: Vec<String> = cycle
+                            .iter()
+                            .filter_map(|id| graph.capsules.get(id))
+                            .map(|c| self.escape_xml(&c.name))
+                            .collect();
+                        vec![names.join(" &rarr; ")]
+                    })
+                    .collect(),
+            ));
+        }
+
+        // Самые сложные компоненты
+        let mut complex: Vec<&Capsule> = graph.capsules.values().collect();
+        complex.sort_by(|a, b| {
+            b.complexity
+                .cmp(&a.complexity)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        if !complex.is_empty() {
+            html.push_str(&heading("Most complex components"));
+            html.push_str(&table(
+                &["Component", "Type", "Complexity"],
+                complex
+                    .iter()
+                    .take(5)
+                    .map(|c| {
+                        vec![
+                            self.escape_xml(&c.name),
+                            format!("{:?}", c.capsule_type),
+                            c.complexity.to_string(),
+                        ]
+                    })
+                    .collect(),
+            ));
+        }
+
+        if !graph.layers.is_empty() {
+            let mut layers: Vec<(&String, usize)> =
+                graph.layers.iter().map(|(k, v)| (k, v.len())).collect();
+            layers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            html.push_str(&heading("Layers"));
+            html.push_str(&table(
+                &["Layer", "Components"],
+                layers
+                    .into_iter()
+                    .take(8)
+                    .map(|(name, count)| vec![self.escape_xml(name), count.to_string()])
+                    .collect(),
+            ));
+        }
+
+        html.push_str(&format!(
+            "        <tr><td style=\"{} padding: 20px 24px; font-size: 11px; color: #999999;\">Generated by ArchLens</td></tr>\n",
+            FONT
+        ));
+        html.push_str("      </table>\n");
+        html.push_str("    </td></tr>\n");
+        html.push_str("  </table>\n");
         html.push_str("</body>\n");
         html.push_str("</html>\n");
         Ok(html)
@@ -467,6 +1092,9 @@ impl Exporter {
             graph.metrics.total_relations,
             graph.metrics.complexity_average
         ));
+        if let Some(overview) = &self.overview {
+            compact.push_str(&format!("## Executive Overview\n{}\n\n", overview));
+        }
 
         // Краткие проблемы (эвристики)
         compact.push_str("## Problems (Heuristic)\n");
@@ -495,6 +1123,12 @@ impl Exporter {
         if let Some(validated) = self.build_validated_problems_section(graph) {
             compact.push_str(&validated);
         }
+        if let Some(findings) = self
+            .finding_caps
+            .and_then(|caps| caps.compact_section(graph))
+        {
+            compact.push_str(&findings);
+        }
 
         // Циклы (топ-5 по длине)
         if let Some(cycles_section) = self.build_cycles_section(graph) {
@@ -505,10 +1139,38 @@ impl Exporter {
         if let Some(coupling_section) = self.build_top_coupling_section(graph) {
             compact.push_str(&coupling_section);
         }
+        if let Some(by_type) = self.build_relation_type_section(graph) {
+            compact.push_str(&by_type);
+        }
+        // Ключевые компоненты по радиусу поражения
+        if let Some(key) = self.build_key_components_section(graph) {
+            compact.push_str(&key);
+        }
+        // Архитектурные хабы (PageRank) и узкие места (betweenness)
+        if let Some(hubs) = self.build_top_hubs_section(graph) {
+            compact.push_str(&hubs);
+        }
+        // Горячие точки: частота изменений × сложность (при наличии git-истории)
+        if let Some(hot) = self.build_hotspots_section(graph) {
+            compact.push_str(&hot);
+        }
+        // Бенчмарки и примеры — вне метрик ядра
+        if let Some(auxiliary) = self.build_auxiliary_section(graph) {
+            compact.push_str(&auxiliary);
+        }
 
         // Топ-капсулы по сложности
-        let mut top: Vec<_> = graph.capsules.values().collect();
-        top.sort_by_key(|c| Reverse(c.complexity));
+        let mut top: Vec<_> = graph
+            .capsules
+            .values()
+            .filter(|c| self.counts_in_aggregates(c))
+            .collect();
+        top.sort_by(|a, b| {
+            b.complexity
+                .cmp(&a.complexity)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
         let top = top.into_iter().take(10);
         compact.push_str("## Top Complexity Components\n");
         for capsule in top {
@@ -518,6 +1180,77 @@ impl Exporter {
             ));
         }
 
+        // Сводки компонентов от LLM (если включены)
+        let mut summarized: Vec<(&Capsule, &String)> = graph
+            .capsules
+            .values()
+            .filter_map(|c| Some((c, c.metadata.get(crate::llm::LLM_SUMMARY_KEY)?)))
+            .collect();
+        if !summarized.is_empty() {
+            summarized.sort_by(|(a, _), (b, _)| {
+                b.complexity
+                    .cmp(&a.complexity)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            compact.push_str("\n## Component Summaries\n");
+            for (capsule, summary) in summarized.into_iter().take(10) {
+                compact.push_str(&format!("- {}: {}\n", capsule.name, summary));
+            }
+        }
+
+        // Квадрант риска: сложность × реальное покрытие (если наложен отчёт покрытия)
+        if let Some(risk) = self.build_coverage_risk_section(graph) {
+            compact.push_str(&risk);
+        }
+        // Горячие точки CPU из наложенного профиля
+        if let Some(cpu) = self.build_cpu_hotspots_section(graph) {
+            compact.push_str(&cpu);
+        }
+        // Владение кодом по git blame
+        if let Some(bus) = self.build_bus_factor_section(graph) {
+            compact.push_str(&bus);
+        }
+        // Метрики, свёрнутые по каталогам
+        if let Some(rollup) = self.build_directory_rollup_section(graph) {
+            compact.push_str(&rollup);
+        }
+        // Нарушения SOLID и метрика разделения интерфейсов
+        if let Some(solid) = self.build_solid_section(graph) {
+            compact.push_str(&solid);
+        }
+        // Незавершённая работа: TODO/FIXME по слоям
+        if let Some(todos) = TodoReport::from_graph(graph) {
+            compact.push_str(&todos.compact_section());
+        }
+        // Публичный API по файлам
+        if let Some(api) = ApiSurfaceReport::from_graph(graph) {
+            compact.push_str(&api.compact_section());
+        }
+        // Широкие типы ошибок в публичном API
+        if let Some(errors) = ErrorPropagationReport::from_graph(graph) {
+            compact.push_str(&errors.compact_section());
+        }
+        // Публичные символы, видимость которых можно сузить
+        if let Some(visibility) = VisibilityReport::from_graph(graph) {
+            compact.push_str(&visibility.compact_section());
+        }
+        // Внешние пакеты и модули, которые их импортируют
+        if let Some(external) = ExternalDependencyReport::from_graph(graph) {
+            compact.push_str(&external.compact_section());
+        }
+        // Все группы циклов с местами импортов, образующих их рёбра
+        if let Some(cycles) = CyclesDetail::from_graph(graph) {
+            compact.push_str(&cycles.compact_section());
+        }
+        // Сообщества связанного кода в сравнении с раскладкой по каталогам
+        if let Some(modularization) = ModularizationReport::from_graph(graph) {
+            compact.push_str(&modularization.compact_section());
+        }
+        // Файлы, пропущенные из-за паники при разборе
+        if let Some(section) = self.failed_coverage().and_then(|c| c.compact_section()) {
+            compact.push_str(&section);
+        }
+
         // Краткие слои
         if !graph.layers.is_empty() {
             compact.push_str("\n## Layers\n");
@@ -526,7 +1259,7 @@ impl Exporter {
                 .iter()
                 .map(|(k, v)| (k.clone(), v.len()))
                 .collect();
-            layers.sort_by_key(|(_, n)| Reverse(*n));
+            layers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
             for (name, count) in layers.into_iter().take(8) {
                 compact.push_str(&format!("- {}: {}\n", name, count));
             }
@@ -543,7 +1276,7 @@ impl Exporter {
             .iter()
             .map(|(k, v)| (k.clone(), v.len()))
             .collect();
-        layers_vec.sort_by_key(|(_, n)| Reverse(*n));
+        layers_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         let layers: Vec<serde_json::Value> = layers_vec
             .into_iter()
             .take(8)
@@ -578,10 +1311,10 @@ impl Exporter {
             }
         }
         let mut cats: Vec<(String, usize)> = category_counts.into_iter().collect();
-        cats.sort_by_key(|(_, c)| Reverse(*c));
+        cats.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         let problems_validated: Vec<serde_json::Value> = cats.into_iter().take(6).map(|(cat, cnt)| {
             let mut comps: Vec<(Uuid, usize)> = category_components.get(&cat).cloned().unwrap_or_default().into_iter().collect();
-            comps.sort_by_key(|(_, n)| Reverse(*n));
+            comps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
             let top_components: Vec<String> = comps.into_iter().take(3).filter_map(|(cid, _)| graph.capsules.get(&cid).map(|c| c.name.clone())).collect();
             let sev = category_severity.get(&cat).cloned().unwrap_or((0,0,0));
             let hint = category_suggestion.get(&cat).cloned();
@@ -609,11 +1342,7 @@ impl Exporter {
 
         // Top coupling
         let top_coupling: Vec<serde_json::Value> = {
-            let mut degree: HashMap<Uuid, usize> = HashMap::new();
-            for r in &graph.relations {
-                *degree.entry(r.from_id).or_insert(0) += 1;
-                *degree.entry(r.to_id).or_insert(0) += 1;
-            }
+            let degree = self.coupling_degrees(graph);
             // Map to (name, degree) for deterministic tie-breaking
             let mut items: Vec<(String, usize)> = degree
                 .into_iter()
@@ -629,10 +1358,32 @@ impl Exporter {
         };
 
         // Top complexity components
-        let mut top_cmp: Vec<_> = graph.capsules.values().collect();
-        top_cmp.sort_by_key(|c| Reverse(c.complexity));
+        let mut top_cmp: Vec<_> = graph
+            .capsules
+            .values()
+            .filter(|c| self.counts_in_aggregates(c))
+            .collect();
+        top_cmp.sort_by(|a, b| {
+            b.complexity
+                .cmp(&a.complexity)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
         let top_complexity_components: Vec<serde_json::Value> = top_cmp.into_iter().take(10).map(|c| serde_json::json!({"component": c.name, "type": format!("{:?}", c.capsule_type), "complexity": c.complexity})).collect();
 
+        let key_components: Vec<serde_json::Value> = self
+            .key_components(graph)
+            .into_iter()
+            .map(|(c, reach)| {
+                serde_json::json!({
+                    "component": c.name,
+                    "type": format!("{:?}", c.capsule_type),
+                    "blast_radius": reach.blast_radius,
+                    "transitive_dependencies": reach.transitive_dependencies,
+                })
+            })
+            .collect();
+
         let summary = serde_json::json!({
             "components": graph.metrics.total_capsules,
             "relations": graph.metrics.total_relations,
@@ -640,16 +1391,239 @@ impl Exporter {
             "coupling_index": graph.metrics.coupling_index,
             "cohesion_index": graph.metrics.cohesion_index,
             "cyclomatic_complexity": graph.metrics.cyclomatic_complexity,
+            "layering_violation_index": graph.metrics.layering_violation_index,
             "layers": layers
         });
 
-        Ok(serde_json::json!({
+        let mut result = serde_json::json!({
             "summary": summary,
             "problems_validated": problems_validated,
             "cycles_top": cycles_top,
             "top_coupling": top_coupling,
-            "top_complexity_components": top_complexity_components
-        }))
+            "top_complexity_components": top_complexity_components,
+            "key_components": key_components
+        });
+        if let Some(coverage) = self.failed_coverage() {
+            result["analysis_coverage"] = serde_json::to_value(coverage)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(risk) = self.coverage_risk(graph) {
+            let mut risk = serde_json::to_value(risk)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+            if let Some(items) = risk.get_mut("high_risk").and_then(|v| v.as_array_mut()) {
+                items.truncate(10);
+            }
+            result["coverage_risk"] = risk;
+        }
+        let hotspots = cpu_hotspots(graph, ProfileOverlay::new().complexity_threshold);
+        if !hotspots.is_empty() {
+            let top: Vec<_> = hotspots.into_iter().take(10).collect();
+            result["cpu_hotspots"] = serde_json::to_value(top)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(mut ownership) = OwnershipReport::from_graph(graph) {
+            let single_owner_modules = ownership.single_owner_modules().count();
+            ownership.modules.truncate(10);
+            let mut bus_factor = serde_json::to_value(ownership)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+            bus_factor["single_owner_modules"] = single_owner_modules.into();
+            result["bus_factor"] = bus_factor;
+        }
+        if let Some(directories) = directory_rollup(graph) {
+            let top: Vec<serde_json::Value> = directories
+                .iter()
+                .take(10)
+                .map(|node| {
+                    serde_json::json!({
+                        "directory": node.name,
+                        "capsules": node.rollup.capsules,
+                        "complexity": node.rollup.complexity,
+                        "max_complexity": node.rollup.max_complexity,
+                        "warnings": node.rollup.warnings,
+                    })
+                })
+                .collect();
+            result["directories"] = serde_json::Value::Array(top);
+        }
+        if let Some(mut todos) = TodoReport::from_graph(graph) {
+            let total = todos.items.len();
+            todos.items.truncate(10);
+            let mut value = serde_json::to_value(todos)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+            value["total"] = total.into();
+            result["todos"] = value;
+        }
+        if let Some(usage) = InterfaceUsageReport::from_graph(graph) {
+            result["interface_usage"] = serde_json::to_value(usage.interfaces)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(api) = ApiSurfaceReport::from_graph(graph) {
+            result["public_api"] = serde_json::to_value(api.entries)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(errors) = ErrorPropagationReport::from_graph(graph) {
+            result["broad_error_types"] = serde_json::to_value(errors.modules)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(visibility) = VisibilityReport::from_graph(graph) {
+            result["visibility_candidates"] = serde_json::to_value(visibility.modules)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(external) = ExternalDependencyReport::from_graph(graph) {
+            result["external_dependencies"] = serde_json::to_value(external.packages)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(cycles) = CyclesDetail::from_graph(graph) {
+            result["cycles_detail"] = serde_json::to_value(cycles.groups)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(mut failures) = SinglePointsOfFailure::from_graph(graph) {
+            failures.articulation_points.truncate(10);
+            failures.bridges.truncate(10);
+            result["single_points_of_failure"] = serde_json::to_value(failures)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        if let Some(modularization) = ModularizationReport::from_graph(graph) {
+            result["suggested_modularization"] = serde_json::to_value(modularization)
+                .map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        }
+        Ok(result)
+    }
+
+    /// Квадранты «сложность × покрытие»; None, если покрытие не накладывалось
+    fn coverage_risk(&self, graph: &CapsuleGraph) -> Option<CoverageRiskQuadrants> {
+        let overlay = CoverageOverlay::new();
+        let quadrants = risk_quadrants(
+            graph,
+            overlay.complexity_threshold,
+            overlay.coverage_threshold,
+        );
+        let measured = quadrants.high_risk.len()
+            + quadrants.complex_covered
+            + quadrants.simple_uncovered
+            + quadrants.healthy;
+        (measured > 0).then_some(quadrants)
+    }
+
+    fn build_coverage_risk_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let risk = self.coverage_risk(graph)?;
+        let mut out = String::from("\n## Coverage Risk (complexity × coverage)\n");
+        out.push_str(&format!(
+            "- High complexity + low coverage: {}\n- Complex, covered: {}\n- Simple, uncovered: {}\n- Healthy: {}\n",
+            risk.high_risk.len(),
+            risk.complex_covered,
+            risk.simple_uncovered,
+            risk.healthy
+        ));
+        for item in risk.high_risk.iter().take(10) {
+            out.push_str(&format!(
+                "- {} : complexity {}, coverage {:.0}%\n",
+                item.component,
+                item.complexity,
+                item.line_coverage * 100.0
+            ));
+        }
+        Some(out)
+    }
+
+    fn build_directory_rollup_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let directories = directory_rollup(graph)?;
+        let mut out = String::from("\n## Directory Rollup\n");
+        for node in directories.iter().take(10) {
+            out.push_str(&format!(
+                "- {} : {} components, complexity {} (max {}), {} warnings\n",
+                node.name,
+                node.rollup.capsules,
+                node.rollup.complexity,
+                node.rollup.max_complexity,
+                node.rollup.warnings
+            ));
+        }
+        Some(out)
+    }
+
+    fn build_bus_factor_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let ownership = OwnershipReport::from_graph(graph)?;
+        let line = |entry: &OwnershipEntry| {
+            format!(
+                "bus factor {}, {} owns {:.0}% of {} lines{}\n",
+                entry.bus_factor,
+                entry.top_author,
+                entry.top_share * 100.0,
+                entry.lines,
+                if entry.single_owner {
+                    " [single owner]"
+                } else {
+                    ""
+                }
+            )
+        };
+        let mut out = String::from("\n## Bus Factor (Ownership)\n");
+        out.push_str(&format!(
+            "- Modules owned >{:.0}% by one author: {}\n",
+            SINGLE_OWNER_THRESHOLD * 100.0,
+            ownership.single_owner_modules().count()
+        ));
+        for module in ownership.modules.iter().take(10) {
+            out.push_str(&format!("- {} : {}", module.name, line(module)));
+        }
+        for layer in &ownership.layers {
+            out.push_str(&format!("- layer {} : {}", layer.name, line(layer)));
+        }
+        Some(out)
+    }
+
+    fn build_solid_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let mut warnings: Vec<&AnalysisWarning> = graph
+            .capsules
+            .values()
+            .flat_map(|c| &c.warnings)
+            .filter(|w| w.category == "solid")
+            .collect();
+        warnings.sort_by(|a, b| {
+            a.level
+                .cmp(&b.level)
+                .then_with(|| a.message.cmp(&b.message))
+        });
+        warnings.dedup_by(|a, b| a.message == b.message);
+        let interfaces = InterfaceUsageReport::from_graph(graph)
+            .map(|report| report.compact_lines())
+            .unwrap_or_default();
+        if warnings.is_empty() && interfaces.is_empty() {
+            return None;
+        }
+        let mut out = String::from("\n## SOLID\n");
+        for warning in warnings.iter().take(10) {
+            out.push_str(&format!("- [{:?}] {}\n", warning.level, warning.message));
+        }
+        if warnings.len() > 10 {
+            out.push_str(&format!("- ... and {} more\n", warnings.len() - 10));
+        }
+        out.push_str(&interfaces);
+        Some(out)
+    }
+
+    fn build_cpu_hotspots_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let hotspots = cpu_hotspots(graph, ProfileOverlay::new().complexity_threshold);
+        if hotspots.is_empty() {
+            return None;
+        }
+        let mut out = String::from("\n## CPU Hotspots (sampled)\n");
+        for h in hotspots.iter().take(10) {
+            out.push_str(&format!(
+                "- {} : self {:.1}%, total {:.1}%, complexity {}{}\n",
+                h.component,
+                h.self_share * 100.0,
+                h.total_share * 100.0,
+                h.complexity,
+                if h.architectural {
+                    " (architectural hotspot)"
+                } else {
+                    ""
+                }
+            ));
+        }
+        Some(out)
     }
 
     fn build_validated_problems_section(&self, graph: &CapsuleGraph) -> Option<String> {
@@ -688,7 +1662,7 @@ impl Exporter {
         }
         // Сортируем категории по количеству
         let mut cats: Vec<(String, usize)> = category_counts.into_iter().collect();
-        cats.sort_by_key(|(_, c)| Reverse(*c));
+        cats.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         let mut out = String::new();
         out.push_str("## Problems (Validated)\n");
         for (cat, cnt) in cats.into_iter().take(6) {
@@ -699,7 +1673,7 @@ impl Exporter {
                 .unwrap_or_default()
                 .into_iter()
                 .collect();
-            comps.sort_by_key(|(_, n)| Reverse(*n));
+            comps.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
             let mut top_names: Vec<String> = Vec::new();
             for (cid, _n) in comps.into_iter().take(3) {
                 if let Some(c) = graph.capsules.get(&cid) {
@@ -779,16 +1753,12 @@ impl Exporter {
         if graph.capsules.is_empty() {
             return None;
         }
-        let mut degree: HashMap<Uuid, usize> = HashMap::new();
-        for r in &graph.relations {
-            *degree.entry(r.from_id).or_insert(0) += 1;
-            *degree.entry(r.to_id).or_insert(0) += 1;
-        }
+        let degree = self.coupling_degrees(graph);
         if degree.is_empty() {
             return None;
         }
         let mut items: Vec<(Uuid, usize)> = degree.into_iter().collect();
-        items.sort_by_key(|(_, d)| Reverse(*d));
+        items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         let mut s = String::new();
         s.push_str("## Top Coupling\n");
         for (id, d) in items.into_iter().take(10) {
@@ -800,6 +1770,140 @@ impl Exporter {
         Some(s)
     }
 
+    /// Ключевые компоненты: больше всего капсул зависят от них транзитивно
+    fn key_components<'a>(
+        &self,
+        graph: &'a CapsuleGraph,
+    ) -> Vec<(&'a Capsule, crate::graph::Reachability)> {
+        let reach = crate::graph::MetricsCalculator::new()
+            .calculate_reachability(&graph.capsules, &graph.relations);
+        let mut items: Vec<_> = graph
+            .capsules
+            .values()
+            .filter_map(|c| Some((c, *reach.get(&c.id)?)))
+            .filter(|(_, r)| r.blast_radius > 0)
+            .collect();
+        items.sort_by(|(a, ra), (b, rb)| {
+            rb.blast_radius
+                .cmp(&ra.blast_radius)
+                .then_with(|| rb.transitive_dependencies.cmp(&ra.transitive_dependencies))
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.line_start.cmp(&b.line_start))
+        });
+        items.truncate(10);
+        items
+    }
+
+    fn build_key_components_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let items = self.key_components(graph);
+        if items.is_empty() {
+            return None;
+        }
+        let mut s = String::from("## Key Components (Blast Radius)\n");
+        for (capsule, reach) in items {
+            s.push_str(&format!(
+                "- {} ({:?}) : blast radius {}, depends on {}\n",
+                capsule.name,
+                capsule.capsule_type,
+                reach.blast_radius,
+                reach.transitive_dependencies
+            ));
+        }
+        s.push('\n');
+        Some(s)
+    }
+
+    fn build_top_hubs_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let rankings = crate::graph::MetricsCalculator::new().calculate_centrality_rankings(
+            &graph.capsules,
+            &graph.relations,
+            10,
+        );
+        if rankings.hubs.is_empty() {
+            return None;
+        }
+        let mut s = String::from("## Top Hubs (PageRank)\n");
+        for hub in &rankings.hubs {
+            s.push_str(&format!(
+                "- {} ({:?}) : {:.3}\n",
+                hub.name, hub.capsule_type, hub.score
+            ));
+        }
+        if !rankings.bottlenecks.is_empty() {
+            s.push_str("- Bottlenecks (betweenness):\n");
+            for bottleneck in &rankings.bottlenecks {
+                s.push_str(&format!(
+                    "  - {} ({:?}) : {:.3}\n",
+                    bottleneck.name, bottleneck.capsule_type, bottleneck.score
+                ));
+            }
+        }
+        s.push('\n');
+        Some(s)
+    }
+
+    fn build_hotspots_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let hotspots = HotspotAnalyzer::new().rank(graph);
+        if hotspots.is_empty() {
+            return None;
+        }
+        let mut s = String::from("## Top Hotspots (churn × complexity)\n");
+        for h in hotspots {
+            s.push_str(&format!(
+                "- {} ({:?}) : score {:.1}, {} commits, complexity {}\n",
+                h.component, h.capsule_type, h.score, h.commits, h.complexity
+            ));
+        }
+        s.push('\n');
+        Some(s)
+    }
+
+    fn build_auxiliary_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let capsules = crate::code_origin::auxiliary_capsules(graph);
+        if capsules.is_empty() {
+            return None;
+        }
+        let mut s = format!(
+            "## Auxiliary Code (benches, examples; excluded from core metrics)\n- capsules: {}\n",
+            capsules.len()
+        );
+        for capsule in capsules.iter().take(10) {
+            s.push_str(&format!(
+                "- {} ({:?}) : {}:{}\n",
+                capsule.name,
+                capsule.capsule_type,
+                capsule.file_path.display(),
+                capsule.line_start
+            ));
+        }
+        s.push('\n');
+        Some(s)
+    }
+
+    /// Связность по каждому типу связей
+    fn build_relation_type_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let coupling = crate::graph::MetricsCalculator::new()
+            .calculate_coupling_metrics(&graph.capsules, &graph.relations);
+        if coupling.by_relation_type.is_empty() {
+            return None;
+        }
+        let mut s = String::new();
+        s.push_str("## Coupling By Relation Type\n");
+        for (relation_type, c) in &coupling.by_relation_type {
+            s.push_str(&format!(
+                "- {} : {} (sources {}, targets {}, avg out {:.2})\n",
+                relation_type.as_str(),
+                c.relations,
+                c.sources,
+                c.targets,
+                c.average_efferent_coupling
+            ));
+        }
+        s.push('\n');
+        Some(s)
+    }
+
     // Вспомогательные методы
     fn sanitize_node_id(&self, name: &str) -> String {
         name.chars()
@@ -834,13 +1938,344 @@ impl Exporter {
     }
 }
 
+/// Фильтрация на клиенте; переход по постоянной ссылке сбрасывает фильтры,
+/// чтобы целевая капсула не оказалась скрытой
+const INTERACTIVE_HTML_SCRIPT: &str = r#"  <script>
+    const search = document.getElementById('search');
+    const severity = document.getElementById('severity');
+    const layer = document.getElementById('layer');
+    function applyFilters() {
+      const query = search.value.trim().toLowerCase();
+      const minSeverity = Number(severity.value);
+      const visible = new Set();
+      document.querySelectorAll('.component').forEach((node) => {
+        const show = (!query || node.dataset.search.includes(query))
+          && Number(node.dataset.severity) >= minSeverity
+          && (!layer.value || node.dataset.layer === layer.value);
+        node.style.display = show ? '' : 'none';
+        if (show) visible.add(node.id);
+      });
+      document.querySelectorAll('#problems li').forEach((item) => {
+        const show = visible.has(item.dataset.capsule) && Number(item.dataset.severity) >= minSeverity;
+        item.style.display = show ? '' : 'none';
+      });
+    }
+    function revealTarget() {
+      if (!location.hash) return;
+      search.value = '';
+      severity.value = '0';
+      layer.value = '';
+      applyFilters();
+      const target = document.getElementById(decodeURIComponent(location.hash.slice(1)));
+      if (target) target.scrollIntoView();
+    }
+    [search, severity, layer].forEach((el) => el.addEventListener('input', applyFilters));
+    window.addEventListener('hashchange', revealTarget);
+    revealTarget();
+  </script>
+"#;
+
+/// Оформление связи в диаграммах
+struct RelationStyle {
+    mermaid_arrow: &'static str,
+    dot_style: &'static str,
+    dot_arrowhead: &'static str,
+    color: &'static str,
+}
+
+fn relation_style(relation_type: RelationType) -> RelationStyle {
+    let (mermaid_arrow, dot_style, dot_arrowhead, color) = match relation_type {
+        RelationType::Depends => ("-->", "solid", "normal", "#555555"),
+        RelationType::Uses => ("-.->", "dashed", "normal", "#888888"),
+        RelationType::References => ("-.->", "dotted", "normal", "#aaaaaa"),
+        RelationType::Implements => ("==>", "bold", "empty", "#2ca02c"),
+        RelationType::Extends => ("===>", "bold", "empty", "#17becf"),
+        RelationType::Inherits => ("===>", "bold", "empty", "#1f77b4"),
+        RelationType::Aggregates => ("--o", "solid", "odiamond", "#8c564b"),
+        RelationType::Composes => ("--o", "bold", "diamond", "#9467bd"),
+        RelationType::Calls => ("-->", "solid", "normal", "#ff7f0e"),
+        RelationType::Reads => ("-.->", "dashed", "normal", "#7f7f7f"),
+        RelationType::Writes => ("==>", "solid", "normal", "#d62728"),
+        RelationType::Emits => ("-.->", "dashed", "vee", "#e377c2"),
+        RelationType::Handles => ("-.->", "dashed", "inv", "#bcbd22"),
+        RelationType::Imports => ("-.->", "dashed", "vee", "#393b79"),
+        RelationType::Contains => ("--o", "solid", "odot", "#c49c94"),
+    };
+    RelationStyle {
+        mermaid_arrow,
+        dot_style,
+        dot_arrowhead,
+        color,
+    }
+}
+
+fn severity_rank(level: &Priority) -> u8 {
+    match level {
+        Priority::Critical => 4,
+        Priority::High => 3,
+        Priority::Medium => 2,
+        Priority::Low => 1,
+    }
+}
+
+fn layer_name(capsule: &Capsule) -> &str {
+    capsule
+        .layer
+        .as_deref()
+        .filter(|l| !l.is_empty())
+        .unwrap_or("Other")
+}
+
+/// Стабильные якоря капсул: `<файл>-<имя>` (не uuid), чтобы ссылки
+/// оставались рабочими в отчётах следующих запусков
+fn capsule_anchors(capsules: &[&Capsule]) -> HashMap<Uuid, String> {
+    let slug = |text: &str| -> String {
+        let mut out = String::new();
+        for ch in text.chars() {
+            if ch.is_alphanumeric() {
+                out.extend(ch.to_lowercase());
+            } else if !out.ends_with('-') {
+                out.push('-');
+            }
+        }
+        out.trim_matches('-').to_string()
+    };
+    let mut used: HashMap<String, usize> = HashMap::new();
+    let mut anchors = HashMap::new();
+    for capsule in capsules {
+        let file = capsule
+            .file_path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let base = format!("c-{}-{}", slug(&file), slug(&capsule.name));
+        let count = used.entry(base.clone()).or_insert(0);
+        *count += 1;
+        let anchor = if *count == 1 {
+            base
+        } else {
+            format!("{}-{}", base, count)
+        };
+        anchors.insert(capsule.id, anchor);
+    }
+    anchors
+}
+
+/// Формат экспорта графа: встроенный или добавленный плагином через `ExporterRegistry::register`
+pub trait GraphExporter: Send + Sync {
+    /// Имя формата в реестре и в `--format`
+    fn name(&self) -> &str;
+    /// Дополнительные имена формата
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+    fn description(&self) -> &str;
+    fn file_extension(&self) -> &str;
+    fn export(&self, graph: &CapsuleGraph) -> Result<String>;
+}
+
+/// Встроенный формат — метод `Exporter`
+struct BuiltinFormat {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    extension: &'static str,
+    render: fn(&Exporter, &CapsuleGraph) -> Result<String>,
+    exporter: std::sync::Arc<Exporter>,
+}
+
+impl GraphExporter for BuiltinFormat {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> &[&str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn file_extension(&self) -> &str {
+        self.extension
+    }
+
+    fn export(&self, graph: &CapsuleGraph) -> Result<String> {
+        (self.render)(&self.exporter, graph)
+    }
+}
+
+/// Реестр форматов экспорта по имени; формат с уже занятым именем заменяет прежний
+#[derive(Default)]
+pub struct ExporterRegistry {
+    exporters: Vec<Box<dyn GraphExporter>>,
+}
+
+impl ExporterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Встроенные форматы `Exporter`
+    pub fn builtin(exporter: Exporter) -> Self {
+        type Entry = (
+            &'static str,
+            &'static [&'static str],
+            &'static str,
+            &'static str,
+            fn(&Exporter, &CapsuleGraph) -> Result<String>,
+        );
+        const BUILTIN: [Entry; 15] = [
+            ("json", &[], "Граф в JSON", "json", Exporter::export_to_json),
+            (
+                ARCHIVE_FORMAT,
+                &["graph-archive"],
+                "Архив графа для офлайн-ревью (archlens view)",
+                ARCHIVE_EXTENSION,
+                Exporter::export_to_archive,
+            ),
+            (
+                "yaml",
+                &["yml"],
+                "Граф в YAML",
+                "yaml",
+                Exporter::export_to_yaml,
+            ),
+            (
+                "mermaid",
+                &[],
+                "Диаграмма Mermaid",
+                "mmd",
+                Exporter::export_to_mermaid,
+            ),
+            (
+                "dot",
+                &["graphviz"],
+                "Graphviz DOT",
+                "dot",
+                Exporter::export_to_dot,
+            ),
+            (
+                "graphml",
+                &[],
+                "GraphML для yEd/Gephi",
+                "graphml",
+                Exporter::export_to_graphml,
+            ),
+            ("svg", &[], "SVG-диаграмма", "svg", Exporter::export_to_svg),
+            (
+                "interactive-html",
+                &["interactive_html"],
+                "Интерактивный HTML-отчёт",
+                "html",
+                Exporter::export_to_interactive_html,
+            ),
+            (
+                "chain-of-thought",
+                &["chain_of_thought"],
+                "Пошаговый разбор архитектуры для LLM",
+                "md",
+                Exporter::export_to_chain_of_thought,
+            ),
+            (
+                "llm-prompt",
+                &["llm_prompt"],
+                "Промпт для LLM",
+                "md",
+                Exporter::export_to_llm_prompt,
+            ),
+            (
+                "ai-compact",
+                &["ai_compact"],
+                "Компактная сводка для ИИ",
+                "md",
+                Exporter::export_to_ai_compact,
+            ),
+            (
+                "email-summary",
+                &["email_summary"],
+                "HTML-сводка для email",
+                "html",
+                Exporter::export_to_email_summary,
+            ),
+            (
+                "dsm-html",
+                &["dsm"],
+                "Матрица зависимостей слоёв и модулей (HTML)",
+                "html",
+                Exporter::export_to_dsm_html,
+            ),
+            (
+                "dsm-csv",
+                &["dsm-modules-csv"],
+                "Матрица зависимостей модулей (CSV)",
+                "csv",
+                Exporter::export_to_module_dsm_csv,
+            ),
+            (
+                "dsm-layers-csv",
+                &[],
+                "Матрица зависимостей слоёв (CSV)",
+                "csv",
+                Exporter::export_to_layer_dsm_csv,
+            ),
+        ];
+        let exporter = std::sync::Arc::new(exporter);
+        let mut registry = Self::new();
+        for (name, aliases, description, extension, render) in BUILTIN {
+            registry.register(Box::new(BuiltinFormat {
+                name,
+                aliases,
+                description,
+                extension,
+                render,
+                exporter: exporter.clone(),
+            }));
+        }
+        registry
+    }
+
+    pub fn register(&mut self, exporter: Box<dyn GraphExporter>) {
+        self.exporters.retain(|e| e.name() != exporter.name());
+        self.exporters.push(exporter);
+    }
+
+    /// Поиск по имени или псевдониму без учёта регистра
+    pub fn get(&self, name: &str) -> Option<&dyn GraphExporter> {
+        self.exporters
+            .iter()
+            .find(|e| {
+                e.name().eq_ignore_ascii_case(name)
+                    || e.aliases().iter().any(|a| a.eq_ignore_ascii_case(name))
+            })
+            .map(|e| e.as_ref())
+    }
+
+    /// Форматы в порядке регистрации
+    pub fn formats(&self) -> impl Iterator<Item = &dyn GraphExporter> {
+        self.exporters.iter().map(|e| e.as_ref())
+    }
+
+    pub fn export(&self, name: &str, graph: &CapsuleGraph) -> Result<String> {
+        let exporter = self.get(name).ok_or_else(|| {
+            AnalysisError::GenericError(format!("Неизвестный формат экспорта: {name}"))
+        })?;
+        exporter.export(graph)
+    }
+}
+
 // Структура для JSON экспорта
 #[derive(serde::Serialize)]
 struct JsonGraph {
+    format_version: u32,
     created_at: String,
     metrics: JsonMetrics,
     layers: std::collections::HashMap<String, Vec<JsonCapsule>>,
     relations: Vec<JsonRelation>,
+    /// Файлы, анализ которых упал; только если такие есть
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analysis_coverage: Option<AnalysisCoverage>,
 }
 
 #[derive(serde::Serialize)]
@@ -852,6 +2287,7 @@ struct JsonMetrics {
     cohesion_index: f32,
     cyclomatic_complexity: u32,
     depth_levels: u32,
+    layering_violation_index: f32,
 }
 
 #[derive(serde::Serialize)]
@@ -862,6 +2298,12 @@ struct JsonCapsule {
     complexity: u32,
     file_path: String,
     warnings: Vec<String>,
+    /// Теги капсулы, в том числе из `.archlens/tags.json`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Экспортируемые символы капсулы
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    api_surface: Vec<ApiSymbol>,
 }
 
 #[derive(serde::Serialize)]
@@ -871,6 +2313,9 @@ struct JsonRelation {
     relation_type: String,
     strength: f32,
     description: Option<String>,
+    /// `файл:строка`, где возникает связь
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
 }
 
 impl JsonGraph {
@@ -888,11 +2333,14 @@ impl JsonGraph {
                     complexity: capsule.complexity,
                     file_path: capsule.file_path.display().to_string(),
                     warnings: capsule.warnings.iter().map(|w| w.message.clone()).collect(),
+                    tags: capsule.tags.clone(),
+                    api_surface: capsule_api_surface(capsule),
                 })
                 .collect();
             layers.insert(layer_name.clone(), layer_capsules);
         }
 
+        let mut locator = RelationLocator::new(graph);
         let relations: Vec<JsonRelation> = graph
             .relations
             .iter()
@@ -905,11 +2353,13 @@ impl JsonGraph {
                     relation_type: format!("{:?}", relation.relation_type),
                     strength: relation.strength,
                     description: relation.description.clone(),
+                    location: locator.locate(relation).map(|l| l.to_string()),
                 })
             })
             .collect();
 
         Self {
+            format_version: crate::graph_store::REPORT_FORMAT_VERSION,
             created_at: graph.created_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
             metrics: JsonMetrics {
                 total_capsules: graph.metrics.total_capsules,
@@ -919,9 +2369,11 @@ impl JsonGraph {
                 cohesion_index: graph.metrics.cohesion_index,
                 cyclomatic_complexity: graph.metrics.cyclomatic_complexity,
                 depth_levels: graph.metrics.depth_levels,
+                layering_violation_index: graph.metrics.layering_violation_index,
             },
             layers,
             relations,
+            analysis_coverage: None,
         }
     }
 }
@@ -931,3 +2383,15 @@ impl Default for Exporter {
         Self::new()
     }
 }
+
+/// Каталоги с агрегированными метриками по убыванию сложности; `None`, если
+/// каталог всего один и сворачивать нечего
+fn directory_rollup(graph: &CapsuleGraph) -> Option<Vec<HierarchyNode>> {
+    let hierarchy = CapsuleHierarchy::from_graph(graph);
+    let directories: Vec<HierarchyNode> = hierarchy
+        .at_level(HierarchyLevel::Directory)
+        .into_iter()
+        .cloned()
+        .collect();
+    (directories.len() > 1).then_some(directories)
+}
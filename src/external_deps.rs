@@ -0,0 +1,436 @@
+// Внешние зависимости как узлы графа: пакеты из Cargo.toml, package.json и
+// requirements.txt становятся синтетическими капсулами слоя `External`, а файлы,
+// которые их импортируют, получают связи `Imports`. Экспорт показывает, какие
+// модули зависят от каких библиотек, а валидатор слоёв — доменный код,
+// импортирующий инфраструктурные библиотеки напрямую
+
+use crate::dependency_freshness::{find_manifests, parse_manifest, DeclaredDependency, Ecosystem};
+use crate::types::*;
+use crate::validation::LayerValidator;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Ключ метаданных внешней капсулы; значение — экосистема (`cargo`, `npm`, `pypi`)
+pub const EXTERNAL_PACKAGE_KEY: &str = "external_package";
+/// Ключ метаданных инфраструктурной библиотеки (БД, HTTP, брокеры, облако)
+pub const INFRASTRUCTURE_KEY: &str = "infrastructure";
+/// Слой внешних капсул
+pub const EXTERNAL_LAYER: &str = "External";
+
+/// Инфраструктурные библиотеки: доступ к БД, HTTP-клиенты и серверы, брокеры, облако
+const INFRASTRUCTURE_CARGO: [&str; 18] = [
+    "sqlx",
+    "diesel",
+    "sea-orm",
+    "rusqlite",
+    "postgres",
+    "tokio-postgres",
+    "mongodb",
+    "redis",
+    "reqwest",
+    "hyper",
+    "axum",
+    "actix-web",
+    "rocket",
+    "warp",
+    "tonic",
+    "lapin",
+    "rdkafka",
+    "aws-sdk-s3",
+];
+const INFRASTRUCTURE_NPM: [&str; 18] = [
+    "express",
+    "koa",
+    "fastify",
+    "axios",
+    "node-fetch",
+    "pg",
+    "mysql",
+    "mysql2",
+    "mongoose",
+    "mongodb",
+    "redis",
+    "ioredis",
+    "sequelize",
+    "typeorm",
+    "@prisma/client",
+    "knex",
+    "aws-sdk",
+    "kafkajs",
+];
+const INFRASTRUCTURE_PYPI: [&str; 14] = [
+    "sqlalchemy",
+    "psycopg2",
+    "psycopg2-binary",
+    "pymongo",
+    "redis",
+    "requests",
+    "httpx",
+    "aiohttp",
+    "django",
+    "flask",
+    "fastapi",
+    "boto3",
+    "celery",
+    "pika",
+];
+/// Пакеты PyPI, импортируемые под другим именем
+const PYPI_IMPORT_NAMES: [(&str, &str); 6] = [
+    ("psycopg2-binary", "psycopg2"),
+    ("pyyaml", "yaml"),
+    ("beautifulsoup4", "bs4"),
+    ("scikit-learn", "sklearn"),
+    ("pillow", "PIL"),
+    ("python-dateutil", "dateutil"),
+];
+
+/// Инфраструктурная ли библиотека
+pub fn is_infrastructure(ecosystem: Ecosystem, name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    match ecosystem {
+        Ecosystem::Cargo => INFRASTRUCTURE_CARGO.contains(&name.replace('_', "-").as_str()),
+        Ecosystem::Npm => INFRASTRUCTURE_NPM.contains(&name.as_str()),
+        Ecosystem::Pypi => INFRASTRUCTURE_PYPI.contains(&name.replace('_', "-").as_str()),
+    }
+}
+
+/// Экосистема, пакеты которой может импортировать файл
+fn ecosystem_of(path: &Path) -> Option<Ecosystem> {
+    match path.extension()?.to_str()? {
+        "rs" => Some(Ecosystem::Cargo),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Ecosystem::Npm),
+        "py" => Some(Ecosystem::Pypi),
+        _ => None,
+    }
+}
+
+/// Имя, под которым пакет виден в коде: `serde-json` → `serde_json`,
+/// `PyYAML` → `yaml`
+fn import_name(ecosystem: Ecosystem, package: &str) -> String {
+    match ecosystem {
+        Ecosystem::Cargo => package.replace('-', "_"),
+        Ecosystem::Npm => package.to_string(),
+        Ecosystem::Pypi => {
+            let lower = package.to_ascii_lowercase();
+            PYPI_IMPORT_NAMES
+                .iter()
+                .find(|(name, _)| *name == lower)
+                .map(|(_, import)| import.to_string())
+                .unwrap_or_else(|| lower.replace('-', "_"))
+        }
+    }
+}
+
+/// Корневые имена пакетов, импортируемых файлом
+fn imported_packages(ecosystem: Ecosystem, source: &str) -> BTreeSet<String> {
+    static RUST_PATH: OnceLock<Regex> = OnceLock::new();
+    static RUST_EXTERN: OnceLock<Regex> = OnceLock::new();
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    static PY_IMPORT: OnceLock<Regex> = OnceLock::new();
+    static PY_FROM: OnceLock<Regex> = OnceLock::new();
+    let mut packages = BTreeSet::new();
+    match ecosystem {
+        Ecosystem::Cargo => {
+            let path = RUST_PATH.get_or_init(|| Regex::new(r"\b([A-Za-z_]\w*)::").unwrap());
+            let extern_crate = RUST_EXTERN
+                .get_or_init(|| Regex::new(r"(?m)^[ \t]*extern[ \t]+crate[ \t]+(\w+)").unwrap());
+            for cap in path
+                .captures_iter(source)
+                .chain(extern_crate.captures_iter(source))
+            {
+                packages.insert(cap[1].to_string());
+            }
+        }
+        Ecosystem::Npm => {
+            let pattern = SCRIPT.get_or_init(|| {
+                Regex::new(r#"(?:\bfrom|\bimport|\brequire\()\s*\(?\s*['"]([^'"]+)['"]"#).unwrap()
+            });
+            for cap in pattern.captures_iter(source) {
+                let specifier = &cap[1];
+                if specifier.starts_with('.') || specifier.starts_with('/') {
+                    continue;
+                }
+                let segments = if specifier.starts_with('@') { 2 } else { 1 };
+                let package: Vec<&str> =
+                    specifier.splitn(segments + 1, '/').take(segments).collect();
+                packages.insert(package.join("/"));
+            }
+        }
+        Ecosystem::Pypi => {
+            let import = PY_IMPORT.get_or_init(|| {
+                Regex::new(r"(?m)^[ \t]*import[ \t]+([\w.]+(?:[ \t]*,[ \t]*[\w.]+)*)").unwrap()
+            });
+            let from = PY_FROM.get_or_init(|| {
+                Regex::new(r"(?m)^[ \t]*from[ \t]+(\w[\w.]*)[ \t]+import").unwrap()
+            });
+            for cap in import
+                .captures_iter(source)
+                .chain(from.captures_iter(source))
+            {
+                for module in cap[1].split(',') {
+                    if let Some(root) = module.trim().split('.').next() {
+                        packages.insert(root.to_string());
+                    }
+                }
+            }
+        }
+    }
+    packages
+}
+
+/// Объявленные зависимости проекта, добавляемые в граф
+#[derive(Debug, Clone)]
+pub struct ExternalDependencies {
+    packages: Vec<DeclaredDependency>,
+}
+
+impl ExternalDependencies {
+    pub fn new(packages: Vec<DeclaredDependency>) -> Self {
+        Self { packages }
+    }
+
+    /// Зависимости из манифестов проекта; `None`, если их нет
+    pub fn for_project(project_root: &Path) -> Option<Self> {
+        let packages: Vec<DeclaredDependency> = find_manifests(project_root)
+            .iter()
+            .flat_map(|manifest| parse_manifest(manifest))
+            .collect();
+        (!packages.is_empty()).then(|| Self::new(packages))
+    }
+
+    /// Добавляет капсулы используемых пакетов и связи `Imports` к ним от
+    /// файлов-импортёров (от модуля файла или первой его капсулы). Пакет
+    /// манифеста виден только файлам в каталоге манифеста. Неиспользуемые
+    /// пакеты в граф не попадают. Возвращает число добавленных пакетов
+    pub fn annotate(&self, graph: &mut CapsuleGraph) -> usize {
+        // файл → капсула, представляющая его в связях
+        let mut files: BTreeMap<PathBuf, &Capsule> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            if capsule.metadata.contains_key(EXTERNAL_PACKAGE_KEY) {
+                continue;
+            }
+            let key = |c: &Capsule| {
+                (
+                    c.capsule_type != CapsuleType::Module,
+                    c.line_start,
+                    c.name.clone(),
+                )
+            };
+            files
+                .entry(capsule.file_path.clone())
+                .and_modify(|current| {
+                    if key(capsule) < key(current) {
+                        *current = capsule;
+                    }
+                })
+                .or_insert(capsule);
+        }
+
+        let mut imports: BTreeMap<(Ecosystem, &str), Vec<Uuid>> = BTreeMap::new();
+        let mut declared: HashMap<(Ecosystem, &str), &DeclaredDependency> = HashMap::new();
+        for (file, capsule) in &files {
+            let Some(ecosystem) = ecosystem_of(file) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read_to_string(file) else {
+                continue;
+            };
+            let used = imported_packages(ecosystem, &source);
+            let mut seen = BTreeSet::new();
+            for package in &self.packages {
+                let in_scope = package
+                    .manifest
+                    .parent()
+                    .is_some_and(|dir| file.starts_with(dir));
+                if package.ecosystem != ecosystem
+                    || !in_scope
+                    || !used.contains(&import_name(ecosystem, &package.name))
+                    || !seen.insert(package.name.as_str())
+                {
+                    continue;
+                }
+                let key = (ecosystem, package.name.as_str());
+                declared.entry(key).or_insert(package);
+                imports.entry(key).or_default().push(capsule.id);
+            }
+        }
+
+        let added = imports.len();
+        for ((ecosystem, name), importers) in imports {
+            let package = declared[&(ecosystem, name)];
+            let external = external_capsule(package);
+            let id = external.id;
+            for from_id in importers {
+                graph.relations.push(CapsuleRelation {
+                    from_id,
+                    to_id: id,
+                    relation_type: RelationType::Imports,
+                    strength: 1.0,
+                    description: Some(format!("imports {} ({})", name, ecosystem.key())),
+                });
+                if let Some(importer) = graph.capsules.get_mut(&from_id) {
+                    if !importer.dependencies.contains(&id) {
+                        importer.dependencies.push(id);
+                    }
+                }
+                graph
+                    .capsules
+                    .entry(id)
+                    .or_insert_with(|| external.clone())
+                    .dependents
+                    .push(from_id);
+            }
+            graph
+                .layers
+                .entry(EXTERNAL_LAYER.to_string())
+                .or_default()
+                .push(id);
+        }
+
+        // доменный код, импортирующий инфраструктуру напрямую
+        let mut warnings = Vec::new();
+        LayerValidator::new().validate_infrastructure_imports(graph, &mut warnings);
+        for warning in warnings {
+            if let Some(capsule) = warning
+                .capsule_id
+                .and_then(|id| graph.capsules.get_mut(&id))
+            {
+                capsule.warnings.push(warning);
+            }
+        }
+        added
+    }
+}
+
+/// Синтетическая капсула пакета; путь — манифест, объявивший пакет.
+/// Пакет один на экосистему и имя, поэтому `id` от них и не зависит от
+/// расположения проекта
+fn external_capsule(package: &DeclaredDependency) -> Capsule {
+    let infrastructure = is_infrastructure(package.ecosystem, &package.name);
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        EXTERNAL_PACKAGE_KEY.to_string(),
+        package.ecosystem.key().to_string(),
+    );
+    metadata.insert("requirement".to_string(), package.requirement.clone());
+    let mut tags = vec!["external".to_string()];
+    if infrastructure {
+        metadata.insert(INFRASTRUCTURE_KEY.to_string(), "true".to_string());
+        tags.push(INFRASTRUCTURE_KEY.to_string());
+    }
+    Capsule {
+        id: stable_capsule_id(
+            Path::new(&format!("external:{}", package.ecosystem.key())),
+            &package.name,
+            CapsuleType::Module,
+        ),
+        name: package.name.clone(),
+        capsule_type: CapsuleType::Module,
+        file_path: package.manifest.clone(),
+        line_start: 1,
+        line_end: 1,
+        size: 0,
+        complexity: 0,
+        dependencies: Vec::new(),
+        layer: Some(EXTERNAL_LAYER.to_string()),
+        summary: None,
+        description: Some(format!(
+            "External {} package {} ({})",
+            package.ecosystem.key(),
+            package.name,
+            package.requirement
+        )),
+        warnings: Vec::new(),
+        status: CapsuleStatus::Active,
+        priority: Priority::Low,
+        tags,
+        metadata,
+        quality_score: 0.0,
+        slogan: None,
+        dependents: Vec::new(),
+        created_at: None,
+    }
+}
+
+/// Пакет и модули, которые его импортируют
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalPackageUsage {
+    pub name: String,
+    pub ecosystem: Ecosystem,
+    pub infrastructure: bool,
+    pub modules: Vec<PathBuf>,
+}
+
+/// Внешние пакеты графа с их импортёрами
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExternalDependencyReport {
+    pub packages: Vec<ExternalPackageUsage>,
+}
+
+impl ExternalDependencyReport {
+    /// `None`, если внешних капсул в графе нет
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let mut packages: Vec<ExternalPackageUsage> = graph
+            .capsules
+            .values()
+            .filter_map(|capsule| {
+                let ecosystem = match capsule.metadata.get(EXTERNAL_PACKAGE_KEY)?.as_str() {
+                    "cargo" => Ecosystem::Cargo,
+                    "npm" => Ecosystem::Npm,
+                    "pypi" => Ecosystem::Pypi,
+                    _ => return None,
+                };
+                let modules: BTreeSet<PathBuf> = capsule
+                    .dependents
+                    .iter()
+                    .filter_map(|id| graph.capsules.get(id))
+                    .map(|c| c.file_path.clone())
+                    .collect();
+                Some(ExternalPackageUsage {
+                    name: capsule.name.clone(),
+                    ecosystem,
+                    infrastructure: capsule.metadata.contains_key(INFRASTRUCTURE_KEY),
+                    modules: modules.into_iter().collect(),
+                })
+            })
+            .collect();
+        if packages.is_empty() {
+            return None;
+        }
+        packages.sort_by(|a, b| {
+            b.modules
+                .len()
+                .cmp(&a.modules.len())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Some(Self { packages })
+    }
+
+    /// Раздел ai_compact: пакет → импортирующие модули
+    pub fn compact_section(&self) -> String {
+        let mut out = format!("\n## External Dependencies ({})\n", self.packages.len());
+        for package in &self.packages {
+            let modules: Vec<String> = package
+                .modules
+                .iter()
+                .map(|m| m.display().to_string())
+                .collect();
+            out.push_str(&format!(
+                "- {} ({}{}): {}\n",
+                package.name,
+                package.ecosystem.key(),
+                if package.infrastructure {
+                    ", infrastructure"
+                } else {
+                    ""
+                },
+                modules.join(", ")
+            ));
+        }
+        out
+    }
+}
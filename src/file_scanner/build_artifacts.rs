@@ -0,0 +1,150 @@
+// Каталоги сборки определяются по манифестам экосистем, а не по общему списку
+// имён: `target/` рядом с `Cargo.toml` или `pom.xml`, `bin/` и `obj/` рядом с
+// `.csproj`, `build/` и `.gradle/` рядом со скриптом Gradle и т. д. Каталог
+// `build/` без манифеста рядом — обычный код и сканируется
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Экосистема, по манифесту которой каталог признан выводом сборки
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Ecosystem {
+    Rust,
+    Node,
+    Dotnet,
+    Gradle,
+    Maven,
+    Python,
+    Cmake,
+}
+
+/// Манифесты экосистемы (точное имя или расширение с точкой) и каталоги,
+/// которые её сборка создаёт рядом с манифестом
+struct Convention {
+    ecosystem: Ecosystem,
+    manifests: &'static [&'static str],
+    outputs: &'static [&'static str],
+}
+
+const CONVENTIONS: &[Convention] = &[
+    Convention {
+        ecosystem: Ecosystem::Rust,
+        manifests: &["Cargo.toml"],
+        outputs: &["target"],
+    },
+    Convention {
+        ecosystem: Ecosystem::Node,
+        manifests: &["package.json"],
+        outputs: &[
+            "dist", "build", "out", ".next", ".nuxt", ".turbo", "coverage",
+        ],
+    },
+    Convention {
+        ecosystem: Ecosystem::Dotnet,
+        manifests: &[".csproj", ".fsproj", ".vbproj", ".sln"],
+        outputs: &["bin", "obj"],
+    },
+    Convention {
+        ecosystem: Ecosystem::Gradle,
+        manifests: &[
+            "build.gradle",
+            "build.gradle.kts",
+            "settings.gradle",
+            "settings.gradle.kts",
+        ],
+        outputs: &["build", ".gradle"],
+    },
+    Convention {
+        ecosystem: Ecosystem::Maven,
+        manifests: &["pom.xml"],
+        outputs: &["target"],
+    },
+    Convention {
+        ecosystem: Ecosystem::Python,
+        manifests: &["pyproject.toml", "setup.py", "setup.cfg"],
+        outputs: &["build", "dist", ".tox", ".pytest_cache", ".mypy_cache"],
+    },
+    Convention {
+        ecosystem: Ecosystem::Cmake,
+        manifests: &["CMakeLists.txt"],
+        outputs: &["build"],
+    },
+];
+
+/// Каталог, исключённый из сканирования как вывод сборки
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ExcludedDir {
+    pub path: PathBuf,
+    pub ecosystem: Ecosystem,
+    /// Манифест, рядом с которым найден каталог; `None` для каталогов,
+    /// которые создаёт только сборка (`__pycache__`, `*.egg-info`)
+    pub manifest: Option<PathBuf>,
+}
+
+/// Вывод сборки ли каталог `dir`: кэш байткода Python где угодно, иначе
+/// каталог с именем вывода рядом с манифестом соответствующей экосистемы
+pub fn build_output(dir: &Path) -> Option<ExcludedDir> {
+    let name = dir.file_name()?.to_str()?;
+    if name == "__pycache__" || name.ends_with(".egg-info") {
+        return Some(ExcludedDir {
+            path: dir.to_path_buf(),
+            ecosystem: Ecosystem::Python,
+            manifest: None,
+        });
+    }
+    let parent = dir.parent()?;
+    CONVENTIONS
+        .iter()
+        .filter(|c| c.outputs.contains(&name))
+        .find_map(|c| {
+            let manifest = find_manifest(parent, c.manifests)?;
+            Some(ExcludedDir {
+                path: dir.to_path_buf(),
+                ecosystem: c.ecosystem,
+                manifest: Some(manifest),
+            })
+        })
+}
+
+/// Лежит ли путь внутри вывода сборки: проверяются родительские каталоги
+/// ниже корня проекта, сам корень и всё выше него — нет
+pub fn is_inside_build_output(path: &Path, project_root: &Path) -> bool {
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| *dir != project_root && dir.starts_with(project_root))
+        .any(|dir| build_output(dir).is_some())
+}
+
+fn find_manifest(dir: &Path, manifests: &[&str]) -> Option<PathBuf> {
+    for manifest in manifests.iter().filter(|m| !m.starts_with('.')) {
+        let path = dir.join(manifest);
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+    let extensions: Vec<&str> = manifests
+        .iter()
+        .filter_map(|m| m.strip_prefix('.'))
+        .collect();
+    if extensions.is_empty() {
+        return None;
+    }
+    let mut found: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| extensions.contains(&e))
+        })
+        .collect();
+    found.sort();
+    found.into_iter().next()
+}
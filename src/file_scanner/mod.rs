@@ -1,11 +1,132 @@
 use crate::types::{AnalysisError, CapsuleStatus, FileMetadata, FileType, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::{fs, path::Path};
 
+/// Каталоги вывода сборки по соглашениям экосистем
+pub mod build_artifacts;
+pub mod watcher;
+
+/// Файлы больше лимита не читаются: минифицированные бандлы, дампы, сгенерированный код
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Переопределение лимита размера в байтах (`0` снимает лимит)
+pub const MAX_FILE_SIZE_ENV: &str = "ARCHLENS_MAX_FILE_SIZE";
+/// Сколько первых байт проверяется на NUL (как в git)
+const BINARY_SNIFF_BYTES: usize = 8000;
+/// Средняя длина строки, начиная с которой JS считается минифицированным
+const MINIFIED_AVG_LINE_LENGTH: usize = 250;
+/// Меньшие файлы не проверяются на минификацию
+const MINIFIED_MIN_SIZE: usize = 2048;
+
+/// Ограничения на читаемые файлы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanLimits {
+    /// `None` — без лимита
+    pub max_file_size: Option<u64>,
+    pub detect_binary: bool,
+    pub skip_minified: bool,
+}
+
+impl Default for ScanLimits {
+    fn default() -> Self {
+        Self {
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+            detect_binary: true,
+            skip_minified: true,
+        }
+    }
+}
+
+/// Почему файл пропущен
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    TooLarge,
+    Binary,
+    Minified,
+}
+
+/// Счётчики пропущенных файлов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SkippedFiles {
+    pub too_large: usize,
+    pub binary: usize,
+    pub minified: usize,
+}
+
+impl SkippedFiles {
+    pub fn total(&self) -> usize {
+        self.too_large + self.binary + self.minified
+    }
+
+    pub fn record(&mut self, reason: SkipReason) {
+        match reason {
+            SkipReason::TooLarge => self.too_large += 1,
+            SkipReason::Binary => self.binary += 1,
+            SkipReason::Minified => self.minified += 1,
+        }
+    }
+}
+
+impl ScanLimits {
+    /// Значения по умолчанию с учётом переменной окружения
+    pub fn from_env() -> Self {
+        let max_file_size = match std::env::var(MAX_FILE_SIZE_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            Some(0) => None,
+            Some(limit) => Some(limit),
+            None => Some(DEFAULT_MAX_FILE_SIZE),
+        };
+        Self {
+            max_file_size,
+            ..Self::default()
+        }
+    }
+
+    /// Проверка по размеру до чтения файла
+    pub fn check_size(&self, size: u64) -> Option<SkipReason> {
+        self.max_file_size
+            .filter(|limit| size > *limit)
+            .map(|_| SkipReason::TooLarge)
+    }
+
+    /// Проверка прочитанного содержимого: NUL в начале или не UTF-8 — бинарный
+    /// файл; очень длинные строки в JS/TS или `.min.` в имени — минифицированный
+    pub fn check_content(&self, path: &Path, bytes: &[u8]) -> Option<SkipReason> {
+        if self.detect_binary {
+            let head = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+            if head.contains(&0) || std::str::from_utf8(bytes).is_err() {
+                return Some(SkipReason::Binary);
+            }
+        }
+        if self.skip_minified && is_script(path) {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let lines = bytes.split(|b| *b == b'\n').count().max(1);
+            if name.contains(".min.")
+                || (bytes.len() >= MINIFIED_MIN_SIZE
+                    && bytes.len() / lines > MINIFIED_AVG_LINE_LENGTH)
+            {
+                return Some(SkipReason::Minified);
+            }
+        }
+        None
+    }
+}
+
+fn is_script(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx")
+    )
+}
+
 /// Сканер файлов проекта
 pub struct FileScanner {
     include_patterns: Vec<regex::Regex>,
     exclude_patterns: Vec<regex::Regex>,
     max_depth: Option<usize>,
+    limits: ScanLimits,
 }
 
 impl FileScanner {
@@ -30,9 +151,20 @@ impl FileScanner {
             include_patterns,
             exclude_patterns,
             max_depth,
+            limits: ScanLimits::from_env(),
         })
     }
 
+    /// Ограничения на размер и содержимое читаемых файлов
+    pub fn with_limits(mut self, limits: ScanLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn limits(&self) -> ScanLimits {
+        self.limits
+    }
+
     /// Сканирует проект и возвращает метаданные всех подходящих файлов
     pub fn scan_project(&self, project_path: &Path) -> Result<Vec<FileMetadata>> {
         self.scan_files(project_path)
@@ -40,9 +172,31 @@ impl FileScanner {
 
     /// Сканирует файлы в директории (основной метод)
     pub fn scan_files(&self, project_path: &Path) -> Result<Vec<FileMetadata>> {
+        Ok(self.scan_files_with_skipped(project_path)?.0)
+    }
+
+    /// Сканирует файлы и считает пропущенные по размеру, бинарные и минифицированные
+    pub fn scan_files_with_skipped(
+        &self,
+        project_path: &Path,
+    ) -> Result<(Vec<FileMetadata>, SkippedFiles)> {
         let mut files = Vec::new();
-        self.scan_directory_recursive(project_path, &mut files, 0)?;
-        Ok(files)
+        let mut skipped = SkippedFiles::default();
+        self.scan_directory_recursive(project_path, &mut files, &mut skipped, 0)?;
+        crate::execution::fix_order(&mut files, |f| f.path.as_path());
+        Ok((files, skipped))
+    }
+
+    /// Метаданные одного файла проекта `project_root`; `None` — файла нет, он
+    /// не подходит по шаблонам, лежит в выводе сборки или пропущен по лимитам
+    pub fn scan_file(&self, path: &Path, project_root: &Path) -> Option<FileMetadata> {
+        if !path.is_file()
+            || !self.should_include_file(path)
+            || build_artifacts::is_inside_build_output(path, project_root)
+        {
+            return None;
+        }
+        self.extract_file_metadata(path).ok()?.ok()
     }
 
     /// Версия scan_files без параметров (для совместимости)
@@ -57,6 +211,7 @@ impl FileScanner {
         &self,
         dir: &Path,
         files: &mut Vec<FileMetadata>,
+        skipped: &mut SkippedFiles,
         depth: usize,
     ) -> Result<()> {
         if let Some(max_depth) = self.max_depth {
@@ -96,20 +251,20 @@ impl FileScanner {
             let path = entry.path();
 
             if path.is_dir() {
+                if build_artifacts::build_output(&path).is_some() {
+                    continue;
+                }
                 // Рекурсивно сканируем поддиректории, но не прерываем работу при ошибках
-                if let Err(e) = self.scan_directory_recursive(&path, files, depth + 1) {
+                if let Err(e) = self.scan_directory_recursive(&path, files, skipped, depth + 1) {
                     eprintln!(
                         "⚠️ Предупреждение: Ошибка сканирования директории {:?}: {}",
                         path, e
                     );
                 }
-            } else {
+            } else if self.should_include_file(&path) {
                 match self.extract_file_metadata(&path) {
-                    Ok(metadata) => {
-                        if self.should_include_file(&metadata) {
-                            files.push(metadata);
-                        }
-                    }
+                    Ok(Ok(metadata)) => files.push(metadata),
+                    Ok(Err(reason)) => skipped.record(reason),
                     Err(e) => {
                         // Более детальная информация об ошибках доступа к файлам
                         eprintln!(
@@ -124,8 +279,11 @@ impl FileScanner {
         Ok(())
     }
 
-    /// Извлекает метаданные из файла
-    fn extract_file_metadata(&self, path: &Path) -> Result<FileMetadata> {
+    /// Извлекает метаданные из файла; `Err(reason)` — файл пропущен по лимитам
+    fn extract_file_metadata(
+        &self,
+        path: &Path,
+    ) -> Result<std::result::Result<FileMetadata, SkipReason>> {
         let metadata = match fs::metadata(path) {
             Ok(metadata) => metadata,
             Err(e) => {
@@ -136,19 +294,28 @@ impl FileScanner {
             }
         };
 
+        if let Some(reason) = self.limits.check_size(metadata.len()) {
+            return Ok(Err(reason));
+        }
+
         let file_type = self.detect_file_type(path);
 
-        let content = match fs::read_to_string(path) {
-            Ok(content) => content,
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
             Err(e) => {
                 // Логируем ошибку, но не прерываем работу
                 eprintln!(
                     "⚠️ Предупреждение: Не удалось прочитать содержимое файла {:?}: {}",
                     path, e
                 );
-                String::new()
+                Vec::new()
             }
         };
+        if let Some(reason) = self.limits.check_content(path, &bytes) {
+            return Ok(Err(reason));
+        }
+        let content = String::from_utf8(bytes)
+            .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
 
         let lines_count = content.lines().count();
 
@@ -165,7 +332,7 @@ impl FileScanner {
 
         let (imports, exports) = self.extract_imports_exports(&content, &file_type);
 
-        Ok(FileMetadata {
+        Ok(Ok(FileMetadata {
             path: path.to_path_buf(),
             file_type,
             size: metadata.len(),
@@ -177,7 +344,7 @@ impl FileScanner {
             dependencies: Vec::new(), // Будет заполнено позже
             exports,
             imports,
-        })
+        }))
     }
 
     /// Определяет тип файла по расширению
@@ -193,6 +360,8 @@ impl FileScanner {
             Some("go") => FileType::Go,
             Some("cpp") | Some("cxx") | Some("cc") => FileType::Cpp,
             Some("c") => FileType::C,
+            Some("php") => FileType::Php,
+            Some("rb") => FileType::Ruby,
             Some(ext) => FileType::Other(ext.to_string()),
             None => FileType::Other("unknown".to_string()),
         }
@@ -280,6 +449,8 @@ impl FileScanner {
             FileType::Python => self.extract_python_imports_exports(content),
             FileType::Java => self.extract_java_imports_exports(content),
             FileType::Cpp | FileType::C => self.extract_cpp_imports_exports(content),
+            FileType::Php => self.extract_php_imports_exports(content),
+            FileType::Ruby => self.extract_ruby_imports_exports(content),
             _ => (Vec::new(), Vec::new()),
         }
     }
@@ -411,9 +582,76 @@ impl FileScanner {
         (imports, exports)
     }
 
-    /// Проверяет, должен ли файл быть включен в анализ
-    fn should_include_file(&self, metadata: &FileMetadata) -> bool {
-        let path_str = metadata.path.to_string_lossy();
+    fn extract_php_imports_exports(&self, content: &str) -> (Vec<String>, Vec<String>) {
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            // PHP namespaces imports and file includes
+            if let Some(import) = trimmed.strip_prefix("use ") {
+                let import_clean = import.trim_end_matches(';').trim();
+                let import_clean = import_clean
+                    .split(" as ")
+                    .next()
+                    .unwrap_or(import_clean)
+                    .trim_start_matches('\\');
+                imports.push(import_clean.to_string());
+            }
+            for keyword in ["require_once", "include_once", "require", "include"] {
+                if let Some(rest) = trimmed.strip_prefix(keyword) {
+                    if let Some(path) = extract_quoted(rest) {
+                        imports.push(path);
+                    }
+                    break;
+                }
+            }
+
+            // PHP exports (top-level classes, interfaces, traits, functions)
+            if !line.starts_with(char::is_whitespace) {
+                if let Some(export) = extract_php_export_name(trimmed) {
+                    exports.push(export);
+                }
+            }
+        }
+
+        (imports, exports)
+    }
+
+    fn extract_ruby_imports_exports(&self, content: &str) -> (Vec<String>, Vec<String>) {
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            // Ruby requires
+            for keyword in ["require_relative", "require", "load"] {
+                if let Some(rest) = trimmed.strip_prefix(keyword) {
+                    if let Some(path) = extract_quoted(rest) {
+                        imports.push(path);
+                    }
+                    break;
+                }
+            }
+
+            // Ruby exports (classes and modules)
+            for keyword in ["class ", "module "] {
+                if trimmed.starts_with(keyword) && !trimmed.starts_with("class <<") {
+                    if let Some(name) = extract_name_after(trimmed, keyword) {
+                        exports.push(name.trim_end_matches(';').to_string());
+                    }
+                }
+            }
+        }
+
+        (imports, exports)
+    }
+
+    /// Проверяет, должен ли файл быть включен в анализ (до чтения содержимого)
+    fn should_include_file(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
 
         // Проверяем exclude patterns
         for pattern in &self.exclude_patterns {
@@ -423,15 +661,11 @@ impl FileScanner {
         }
 
         // Упрощенная проверка: включаем файлы с нужными расширениями
-        let file_extension = metadata
-            .path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
+        let file_extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
 
         let supported_extensions = [
             "rs", "js", "ts", "tsx", "jsx", "py", "java", "cpp", "cc", "cxx", "c", "h", "hpp",
-            "hxx",
+            "hxx", "php", "rb",
         ];
         let extension_match = supported_extensions.contains(&file_extension);
 
@@ -449,7 +683,7 @@ impl FileScanner {
 }
 
 /// Конвертирует glob паттерн в regex
-fn glob_to_regex(pattern: &str) -> std::result::Result<regex::Regex, regex::Error> {
+pub(crate) fn glob_to_regex(pattern: &str) -> std::result::Result<regex::Regex, regex::Error> {
     let mut regex_pattern = String::new();
     let chars: Vec<char> = pattern.chars().collect();
     let mut i = 0;
@@ -604,6 +838,31 @@ fn extract_java_export_name(line: &str) -> Option<String> {
     }
 }
 
+/// Извлекает имя экспорта из PHP строки
+fn extract_php_export_name(line: &str) -> Option<String> {
+    let line = line
+        .trim_start_matches("final ")
+        .trim_start_matches("abstract ")
+        .trim_start_matches("readonly ");
+    if line.starts_with("function ") {
+        extract_function_name(line, "function ").map(|n| n.trim_start_matches('&').to_string())
+    } else {
+        ["class ", "interface ", "trait ", "enum "]
+            .iter()
+            .find(|prefix| line.starts_with(*prefix))
+            .and_then(|prefix| extract_name_after(line, prefix))
+    }
+}
+
+/// Первая строка в кавычках: `'lib/db.php'` или `("config")`
+fn extract_quoted(text: &str) -> Option<String> {
+    let start = text.find(['\'', '"'])?;
+    let quote = text[start..].chars().next()?;
+    let rest = &text[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
 /// Извлекает имя экспорта из C++ строки
 fn extract_cpp_export_name(line: &str) -> Option<String> {
     if line.contains("class ") {
@@ -0,0 +1,260 @@
+// Наблюдение за проектом: события `notify` собираются в наборы изменений,
+// пересканируются только затронутые пути, а анализ обновляется по изменённым
+// файлам — остальные капсулы берутся из предыдущего прохода
+
+use super::FileScanner;
+use crate::capsule_constructor::CapsuleConstructor;
+use crate::capsule_graph_builder::CapsuleGraphBuilder;
+use crate::panic_isolation::{AnalysisCoverage, FailedFile};
+use crate::parser_ast::ParserAST;
+use crate::types::{AnalysisError, Capsule, CapsuleGraph, FileMetadata, Result};
+use crate::validator_optimizer::ValidatorOptimizer;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Пауза без событий, после которой накопленные изменения отдаются анализу
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Изменения файлов проекта за одну серию событий
+#[derive(Debug, Clone, Default)]
+pub struct ChangeSet {
+    /// Новые и изменённые файлы с пересчитанными метаданными
+    pub changed: Vec<FileMetadata>,
+    /// Удалённые (или ставшие неподходящими) файлы
+    pub removed: Vec<PathBuf>,
+}
+
+impl ChangeSet {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changed.len() + self.removed.len()
+    }
+}
+
+/// Рекурсивное наблюдение за корнем проекта
+pub struct ProjectWatcher {
+    root: PathBuf,
+    scanner: FileScanner,
+    files: BTreeMap<PathBuf, FileMetadata>,
+    debounce: Duration,
+    events: Receiver<notify::Result<notify::Event>>,
+    // события приходят, пока наблюдатель жив
+    _watcher: RecommendedWatcher,
+}
+
+impl ProjectWatcher {
+    /// Полное сканирование проекта и подписка на события файловой системы
+    pub fn new(root: &Path, scanner: FileScanner) -> Result<Self> {
+        // notify сообщает канонические пути: сканируем от канонического корня
+        let root = root.canonicalize()?;
+        let files = scanner
+            .scan_files(&root)?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(watch_error)?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(watch_error)?;
+
+        Ok(Self {
+            root,
+            scanner,
+            files,
+            debounce: DEFAULT_DEBOUNCE,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Текущие файлы проекта
+    pub fn files(&self) -> impl Iterator<Item = &FileMetadata> {
+        self.files.values()
+    }
+
+    /// Ждёт следующую серию изменений подходящих файлов. `timeout` ограничивает
+    /// ожидание первого события; `None` в результате — изменений не было
+    pub fn next_changes(&mut self, timeout: Option<Duration>) -> Result<Option<ChangeSet>> {
+        loop {
+            let first = match timeout {
+                Some(timeout) => match self.events.recv_timeout(timeout) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => return Ok(None),
+                    Err(RecvTimeoutError::Disconnected) => return Err(disconnected()),
+                },
+                None => self.events.recv().map_err(|_| disconnected())?,
+            };
+            let mut paths = BTreeSet::new();
+            collect_paths(first, &mut paths);
+            // редактор пишет файл несколькими событиями: ждём затишья
+            while let Ok(event) = self.events.recv_timeout(self.debounce) {
+                collect_paths(event, &mut paths);
+            }
+
+            let changes = self.rescan(paths);
+            if !changes.is_empty() {
+                return Ok(Some(changes));
+            }
+        }
+    }
+
+    /// Пересканирует только указанные пути: существующие подходящие файлы попадают
+    /// в `changed`, исчезнувшие — в `removed`; каталог сканируется целиком
+    pub fn rescan<I: IntoIterator<Item = PathBuf>>(&mut self, paths: I) -> ChangeSet {
+        let mut changed = BTreeMap::new();
+        let mut removed = BTreeSet::new();
+        for path in paths {
+            if path.is_dir() {
+                for file in self.scanner.scan_files(&path).unwrap_or_default() {
+                    changed.insert(file.path.clone(), file);
+                }
+            } else if let Some(file) = self.scanner.scan_file(&path, &self.root) {
+                changed.insert(path, file);
+            } else {
+                // удалён файл или каталог целиком
+                removed.extend(
+                    self.files
+                        .keys()
+                        .filter(|known| known.starts_with(&path))
+                        .cloned(),
+                );
+            }
+        }
+
+        for path in &removed {
+            self.files.remove(path);
+        }
+        for (path, file) in &changed {
+            self.files.insert(path.clone(), file.clone());
+        }
+        ChangeSet {
+            changed: changed.into_values().collect(),
+            removed: removed.into_iter().collect(),
+        }
+    }
+}
+
+fn collect_paths(event: notify::Result<notify::Event>, paths: &mut BTreeSet<PathBuf>) {
+    match event {
+        // чтение файла ничего не меняет
+        Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+        Ok(event) => paths.extend(event.paths),
+        Err(e) => eprintln!("⚠️ Предупреждение: ошибка наблюдения за файлами: {}", e),
+    }
+}
+
+fn watch_error(e: notify::Error) -> AnalysisError {
+    AnalysisError::GenericError(format!("Не удалось начать наблюдение за файлами: {}", e))
+}
+
+fn disconnected() -> AnalysisError {
+    AnalysisError::GenericError("Наблюдение за файлами прервано".to_string())
+}
+
+/// Анализ, который при изменениях разбирает заново только затронутые файлы
+pub struct IncrementalAnalysis {
+    parser: ParserAST,
+    constructor: CapsuleConstructor,
+    capsules: BTreeMap<PathBuf, Vec<Capsule>>,
+    /// Исход разбора каждого файла: `None` — разобран, иначе паника
+    outcomes: BTreeMap<PathBuf, Option<FailedFile>>,
+}
+
+impl IncrementalAnalysis {
+    /// Идентификаторы капсул считаются от путей относительно `project_root`
+    pub fn new(project_root: &Path) -> Result<Self> {
+        Ok(Self {
+            parser: ParserAST::new()?.with_incremental(true),
+            constructor: CapsuleConstructor::new().with_project_root(project_root),
+            capsules: BTreeMap::new(),
+            outcomes: BTreeMap::new(),
+        })
+    }
+
+    /// Разбирает файлы заново; возвращает число файлов, давших капсулы
+    pub fn update<'a, I>(&mut self, files: I) -> usize
+    where
+        I: IntoIterator<Item = &'a FileMetadata>,
+    {
+        let mut analyzed = 0;
+        for file in files {
+            // паника в файле не останавливает наблюдение: файл остаётся без
+            // капсул до следующего изменения и перечисляется в `coverage`
+            let mut coverage = AnalysisCoverage::default();
+            let capsules = crate::panic_isolation::parse_file_isolated(
+                &mut self.parser,
+                &self.constructor,
+                file,
+                &mut coverage,
+            )
+            .unwrap_or_default();
+            match (coverage.failed.pop(), coverage.analyzed_files) {
+                (Some(failed), _) => {
+                    self.outcomes.insert(file.path.clone(), Some(failed));
+                }
+                (None, 0) => {
+                    self.outcomes.remove(&file.path);
+                }
+                (None, _) => {
+                    self.outcomes.insert(file.path.clone(), None);
+                }
+            }
+            if capsules.is_empty() {
+                self.capsules.remove(&file.path);
+            } else {
+                self.capsules.insert(file.path.clone(), capsules);
+                analyzed += 1;
+            }
+        }
+        analyzed
+    }
+
+    /// Применяет набор изменений наблюдателя
+    pub fn apply(&mut self, changes: &ChangeSet) -> usize {
+        for path in &changes.removed {
+            self.capsules.remove(path);
+            self.outcomes.remove(path);
+        }
+        self.update(&changes.changed)
+    }
+
+    /// Покрытие по текущим файлам: упавший файл пропадает из списка, как
+    /// только следующее изменение разберётся без паники
+    pub fn coverage(&self) -> AnalysisCoverage {
+        AnalysisCoverage {
+            analyzed_files: self.outcomes.values().filter(|o| o.is_none()).count(),
+            failed: self.outcomes.values().flatten().cloned().collect(),
+        }
+    }
+
+    pub fn capsule_count(&self) -> usize {
+        self.capsules.values().map(Vec::len).sum()
+    }
+
+    /// Граф по текущим капсулам: связи и валидация пересчитываются целиком
+    pub fn graph(&self) -> Result<CapsuleGraph> {
+        let capsules: Vec<Capsule> = self.capsules.values().flatten().cloned().collect();
+        let graph = CapsuleGraphBuilder::new().build_graph(&capsules)?;
+        ValidatorOptimizer::new().validate_and_optimize(&graph)
+    }
+}
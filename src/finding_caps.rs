@@ -0,0 +1,170 @@
+// Ограничение числа находок в отчётах: не больше N на капсулу и M на категорию,
+// остальное сворачивается в «N more suppressed». На запущенных legacy-проектах
+// это удерживает ai_compact в бюджете; `--all-findings` снимает ограничения
+
+use crate::types::{AnalysisWarning, Capsule, CapsuleGraph};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Находок на одну капсулу по умолчанию
+pub const DEFAULT_MAX_PER_CAPSULE: usize = 3;
+/// Находок одной категории по умолчанию
+pub const DEFAULT_MAX_PER_CATEGORY: usize = 20;
+/// Сколько капсул со свёрнутыми находками перечислять поимённо
+const NOISY_COMPONENTS_SHOWN: usize = 5;
+
+/// Лимиты; `None` — без ограничения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindingCaps {
+    pub max_per_capsule: Option<usize>,
+    pub max_per_category: Option<usize>,
+}
+
+impl Default for FindingCaps {
+    fn default() -> Self {
+        Self {
+            max_per_capsule: Some(DEFAULT_MAX_PER_CAPSULE),
+            max_per_category: Some(DEFAULT_MAX_PER_CATEGORY),
+        }
+    }
+}
+
+/// Находки после ограничения и счётчики свёрнутых
+#[derive(Debug, Clone, Default)]
+pub struct CappedFindings<'a> {
+    /// От самых серьёзных, затем по файлу и имени капсулы
+    pub kept: Vec<(&'a Capsule, &'a AnalysisWarning)>,
+    pub suppressed: SuppressedFindings,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SuppressedFindings {
+    /// Свёрнуто лимитом на капсулу, по именам капсул
+    pub by_capsule: BTreeMap<String, usize>,
+    /// Свёрнуто лимитом на категорию
+    pub by_category: BTreeMap<String, usize>,
+}
+
+impl SuppressedFindings {
+    pub fn total(&self) -> usize {
+        self.by_capsule.values().sum::<usize>() + self.by_category.values().sum::<usize>()
+    }
+}
+
+impl FindingCaps {
+    /// Без ограничений (`--all-findings`)
+    pub fn unlimited() -> Self {
+        Self {
+            max_per_capsule: None,
+            max_per_category: None,
+        }
+    }
+
+    pub fn with_max_per_capsule(mut self, max: usize) -> Self {
+        self.max_per_capsule = Some(max);
+        self
+    }
+
+    pub fn with_max_per_category(mut self, max: usize) -> Self {
+        self.max_per_category = Some(max);
+        self
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_per_capsule.is_none() && self.max_per_category.is_none()
+    }
+
+    /// Отбирает находки графа: более серьёзные занимают лимиты первыми
+    pub fn apply<'a>(&self, graph: &'a CapsuleGraph) -> CappedFindings<'a> {
+        let mut findings: Vec<(&Capsule, &AnalysisWarning)> = graph
+            .capsules
+            .values()
+            .flat_map(|c| c.warnings.iter().map(move |w| (c, w)))
+            .collect();
+        findings.sort_by(|(ca, a), (cb, b)| {
+            a.level
+                .cmp(&b.level)
+                .then_with(|| (&ca.file_path, &ca.name).cmp(&(&cb.file_path, &cb.name)))
+                .then_with(|| a.message.cmp(&b.message))
+        });
+
+        let mut per_capsule: BTreeMap<uuid::Uuid, usize> = BTreeMap::new();
+        let mut per_category: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut capped = CappedFindings::default();
+        for (capsule, warning) in findings {
+            let capsule_count = per_capsule.entry(capsule.id).or_insert(0);
+            if self
+                .max_per_capsule
+                .is_some_and(|max| *capsule_count >= max)
+            {
+                *capped
+                    .suppressed
+                    .by_capsule
+                    .entry(capsule.name.clone())
+                    .or_insert(0) += 1;
+                continue;
+            }
+            let category_count = per_category.entry(&warning.category).or_insert(0);
+            if self
+                .max_per_category
+                .is_some_and(|max| *category_count >= max)
+            {
+                *capped
+                    .suppressed
+                    .by_category
+                    .entry(warning.category.clone())
+                    .or_insert(0) += 1;
+                continue;
+            }
+            *capsule_count += 1;
+            *category_count += 1;
+            capped.kept.push((capsule, warning));
+        }
+        capped
+    }
+
+    /// Раздел «Findings» для ai_compact; `None`, если находок нет
+    pub fn compact_section(&self, graph: &CapsuleGraph) -> Option<String> {
+        let capped = self.apply(graph);
+        if capped.kept.is_empty() {
+            return None;
+        }
+        let limit = |max: Option<usize>| max.map_or("all".to_string(), |m| m.to_string());
+        let mut out = if self.is_unlimited() {
+            String::from("## Findings (all)\n")
+        } else {
+            format!(
+                "## Findings (max {} per component, {} per category)\n",
+                limit(self.max_per_capsule),
+                limit(self.max_per_category)
+            )
+        };
+        for (capsule, warning) in &capped.kept {
+            out.push_str(&format!(
+                "- [{:?}] {} ({}): {}\n",
+                warning.level, capsule.name, warning.category, warning.message
+            ));
+        }
+        let suppressed = &capped.suppressed;
+        // капсул со свёрнутыми находками может быть много: только самые шумные
+        let mut noisy: Vec<(&String, &usize)> = suppressed.by_capsule.iter().collect();
+        noisy.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (name, count) in noisy.iter().take(NOISY_COMPONENTS_SHOWN) {
+            out.push_str(&format!("- {}: {} more suppressed\n", name, count));
+        }
+        for (category, count) in &suppressed.by_category {
+            out.push_str(&format!(
+                "- category {}: {} more suppressed\n",
+                category, count
+            ));
+        }
+        if suppressed.total() > 0 {
+            out.push_str(&format!(
+                "- {} more findings suppressed (use --all-findings for the full list)\n",
+                suppressed.total()
+            ));
+        }
+        out.push('\n');
+        Some(out)
+    }
+}
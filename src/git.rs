@@ -0,0 +1,23 @@
+// Запуск git-команд для модулей, читающих историю репозитория
+// (метаданные файлов, владение, прогноз изменений, релизные отчёты)
+
+use crate::types::{AnalysisError, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Запуск git в репозитории; ненулевой код выхода — ошибка со stderr
+pub(crate) fn run_git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(AnalysisError::GenericError(format!(
+            "git {}: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
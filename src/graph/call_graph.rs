@@ -0,0 +1,155 @@
+// Call-graph edges: method calls found by the semantic analyzers
+// (`recv.method()`, `Type::method()`, `self.method()`) resolved to the
+// capsules that declare them
+use crate::enrichment::enricher_core::{SemanticLink, SemanticLinkType};
+use crate::enrichment::SemanticAnalyzer;
+use crate::graph::relation_typing::{mentions, module_name, owner, resolve, snippet, Snippet};
+use crate::types::*;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Receivers that refer to the enclosing type
+const SELF_RECEIVERS: [&str; 3] = ["self", "this", "Self"];
+
+/// Turns method calls inside function bodies into `Calls` relations
+pub struct CallGraphAnalyzer {
+    analyzers: HashMap<FileType, SemanticAnalyzer>,
+}
+
+impl CallGraphAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            analyzers: SemanticAnalyzer::create_analyzers(),
+        }
+    }
+
+    /// `Calls` relations for method calls; pairs already linked in `known`
+    /// are skipped, so at most one relation per ordered pair
+    pub fn analyze(&self, capsules: &[Capsule], known: &[CapsuleRelation]) -> Vec<CapsuleRelation> {
+        let mut by_name: HashMap<&str, Vec<&Capsule>> = HashMap::new();
+        for capsule in capsules {
+            by_name
+                .entry(capsule.name.as_str())
+                .or_default()
+                .push(capsule);
+        }
+        let by_id: HashMap<Uuid, &Capsule> = capsules.iter().map(|c| (c.id, c)).collect();
+
+        let mut seen: HashSet<(Uuid, Uuid)> = known.iter().map(|r| (r.from_id, r.to_id)).collect();
+        let mut files: HashMap<&Path, Vec<String>> = HashMap::new();
+        let mut relations = Vec::new();
+        for source in capsules {
+            if !is_callable(&source.capsule_type) {
+                continue;
+            }
+            let Some(analyzer) = self.analyzers.get(&file_type(&source.file_path)) else {
+                continue;
+            };
+            let lines = files.entry(source.file_path.as_path()).or_insert_with(|| {
+                std::fs::read_to_string(&source.file_path)
+                    .map(|text| text.lines().map(str::to_string).collect())
+                    .unwrap_or_default()
+            });
+            let Some(snippet) = snippet(lines, source) else {
+                continue;
+            };
+            let Ok(links) = analyzer.extract_semantic_links(&snippet.body) else {
+                continue;
+            };
+            for link in links {
+                let Some((target, description)) =
+                    resolve_call(&link, source, &snippet, &by_name, &by_id)
+                else {
+                    continue;
+                };
+                if target.id != source.id && seen.insert((source.id, target.id)) {
+                    relations.push(CapsuleRelation {
+                        from_id: source.id,
+                        to_id: target.id,
+                        relation_type: RelationType::Calls,
+                        strength: link.strength,
+                        description: Some(description),
+                    });
+                }
+            }
+        }
+        relations
+    }
+}
+
+impl Default for CallGraphAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_callable(kind: &CapsuleType) -> bool {
+    matches!(kind, CapsuleType::Function | CapsuleType::Method)
+}
+
+fn file_type(path: &Path) -> FileType {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("rs") => FileType::Rust,
+        Some("ts") | Some("tsx") => FileType::TypeScript,
+        Some("js") | Some("jsx") | Some("mjs") | Some("cjs") => FileType::JavaScript,
+        Some("py") => FileType::Python,
+        Some("java") => FileType::Java,
+        Some("cpp") | Some("cc") | Some("cxx") | Some("hpp") => FileType::Cpp,
+        Some(ext) => FileType::Other(ext.to_string()),
+        None => FileType::Other("unknown".to_string()),
+    }
+}
+
+/// Declaring capsule of a method call:
+/// - `self.m()` / `this.m()` / `Self::m()` — a method of the caller's own type;
+/// - `Type::m()` / `Type.m()` — a method of `Type`;
+/// - `module::m()` — a function of the file named after the module;
+/// - `value.m()` — the only method named `m` (same file first), if the caller
+///   mentions its type: `count.parse()` on a number is not `Overlay::parse`
+fn resolve_call<'a>(
+    link: &SemanticLink,
+    source: &Capsule,
+    snippet: &Snippet,
+    by_name: &HashMap<&str, Vec<&'a Capsule>>,
+    by_id: &HashMap<Uuid, &'a Capsule>,
+) -> Option<(&'a Capsule, String)> {
+    if !matches!(link.link_type, SemanticLinkType::MethodCall) {
+        return None;
+    }
+    let (receiver, method) = link.target_name.split_once('.')?;
+    let path_call = link.context.contains("::");
+    let call = format!(
+        "calls {}{}{}()",
+        receiver,
+        if path_call { "::" } else { "." },
+        method
+    );
+    let methods = by_name.get(method)?;
+    let owned_by = |type_name: &str| {
+        methods
+            .iter()
+            .copied()
+            .filter(|c| c.id != source.id && owner(c, by_id) == Some(type_name))
+            .min_by_key(|c| c.file_path != source.file_path)
+    };
+
+    let target = if SELF_RECEIVERS.contains(&receiver) {
+        owned_by(owner(source, by_id)?)
+    } else if receiver.starts_with(char::is_uppercase) {
+        owned_by(receiver)
+    } else if path_call {
+        methods.iter().copied().find(|c| {
+            c.id != source.id
+                && is_callable(&c.capsule_type)
+                && module_name(&c.file_path) == Some(receiver)
+        })
+    } else {
+        resolve(by_name, method, source, |t| *t == CapsuleType::Method).filter(|target| {
+            owner(target, by_id).is_none_or(|type_name| {
+                mentions(&snippet.signature, type_name) || mentions(&snippet.body, type_name)
+            })
+        })
+    }?;
+    Some((target, call))
+}
@@ -0,0 +1,226 @@
+// Centrality metrics: PageRank marks architectural hubs (capsules that many
+// important capsules depend on), betweenness marks bottlenecks (capsules that
+// sit on many shortest dependency paths)
+use crate::types::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+/// PageRank damping factor
+const DAMPING: f64 = 0.85;
+/// PageRank stops after this many iterations even without convergence
+const MAX_ITERATIONS: usize = 100;
+/// PageRank converges when the L1 change of one iteration drops below this
+const TOLERANCE: f64 = 1e-10;
+/// Larger graphs estimate betweenness from an evenly spaced sample of sources
+const MAX_EXACT_SOURCES: usize = 2000;
+
+/// Centrality of a single capsule
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Centrality {
+    /// Share of PageRank; sums to 1 over the graph
+    pub pagerank: f64,
+    /// Normalized betweenness in `0..=1`
+    pub betweenness: f64,
+}
+
+/// Capsule with its score in a ranking
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedCapsule {
+    pub capsule_id: Uuid,
+    pub name: String,
+    pub capsule_type: CapsuleType,
+    pub score: f64,
+}
+
+/// Architectural hubs (by PageRank) and bottlenecks (by betweenness)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CentralityRankings {
+    pub hubs: Vec<RankedCapsule>,
+    pub bottlenecks: Vec<RankedCapsule>,
+}
+
+/// Dependency graph as adjacency lists over indices sorted by capsule id,
+/// so results do not depend on `HashMap` order
+struct IndexedGraph {
+    ids: Vec<Uuid>,
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl IndexedGraph {
+    fn new(capsules: &HashMap<Uuid, Capsule>, relations: &[CapsuleRelation]) -> Self {
+        let mut ids: Vec<Uuid> = capsules.keys().copied().collect();
+        ids.sort();
+        let index: HashMap<Uuid, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut edges = HashSet::new();
+        let mut outgoing = vec![Vec::new(); ids.len()];
+        for relation in relations {
+            if let (Some(&from), Some(&to)) =
+                (index.get(&relation.from_id), index.get(&relation.to_id))
+            {
+                if from != to && edges.insert((from, to)) {
+                    outgoing[from].push(to);
+                }
+            }
+        }
+        for targets in &mut outgoing {
+            targets.sort_unstable();
+        }
+        Self { ids, outgoing }
+    }
+
+    fn pagerank(&self) -> Vec<f64> {
+        let n = self.ids.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..MAX_ITERATIONS {
+            // rank of capsules without dependencies is spread evenly
+            let dangling: f64 = (0..n)
+                .filter(|&i| self.outgoing[i].is_empty())
+                .map(|i| rank[i])
+                .sum();
+            let base = (1.0 - DAMPING) / n as f64 + DAMPING * dangling / n as f64;
+            let mut next = vec![base; n];
+            for (from, targets) in self.outgoing.iter().enumerate() {
+                if targets.is_empty() {
+                    continue;
+                }
+                let share = DAMPING * rank[from] / targets.len() as f64;
+                for &to in targets {
+                    next[to] += share;
+                }
+            }
+            let delta: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if delta < TOLERANCE {
+                break;
+            }
+        }
+        rank
+    }
+
+    /// Brandes' algorithm on the unweighted directed graph
+    fn betweenness(&self) -> Vec<f64> {
+        let n = self.ids.len();
+        let mut centrality = vec![0.0; n];
+        if n < 3 {
+            return centrality;
+        }
+        let step = n.div_ceil(MAX_EXACT_SOURCES);
+        let mut sources = 0;
+
+        let mut stack = Vec::with_capacity(n);
+        let mut queue = VecDeque::new();
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0f64; n];
+        let mut distance = vec![usize::MAX; n];
+        let mut dependency = vec![0.0f64; n];
+        for source in (0..n).step_by(step) {
+            sources += 1;
+            for v in 0..n {
+                predecessors[v].clear();
+                paths[v] = 0.0;
+                distance[v] = usize::MAX;
+                dependency[v] = 0.0;
+            }
+            paths[source] = 1.0;
+            distance[source] = 0;
+            queue.push_back(source);
+            while let Some(v) = queue.pop_front() {
+                stack.push(v);
+                for &w in &self.outgoing[v] {
+                    if distance[w] == usize::MAX {
+                        distance[w] = distance[v] + 1;
+                        queue.push_back(w);
+                    }
+                    if distance[w] == distance[v] + 1 {
+                        paths[w] += paths[v];
+                        predecessors[w].push(v);
+                    }
+                }
+            }
+            while let Some(w) = stack.pop() {
+                for &v in &predecessors[w] {
+                    dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+                }
+                if w != source {
+                    centrality[w] += dependency[w];
+                }
+            }
+        }
+
+        // sampled sources are scaled up to the full graph
+        let scale = n as f64 / sources as f64 / ((n - 1) * (n - 2)) as f64;
+        for value in &mut centrality {
+            *value *= scale;
+        }
+        centrality
+    }
+}
+
+/// PageRank and betweenness of every capsule
+pub(crate) fn calculate_centrality(
+    capsules: &HashMap<Uuid, Capsule>,
+    relations: &[CapsuleRelation],
+) -> HashMap<Uuid, Centrality> {
+    let graph = IndexedGraph::new(capsules, relations);
+    let pagerank = graph.pagerank();
+    let betweenness = graph.betweenness();
+    graph
+        .ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            (
+                *id,
+                Centrality {
+                    pagerank: pagerank[i],
+                    betweenness: betweenness[i],
+                },
+            )
+        })
+        .collect()
+}
+
+/// Top `limit` hubs and bottlenecks. Hubs need at least one dependent and
+/// bottlenecks a non-zero betweenness, so an isolated capsule never ranks
+pub(crate) fn rank_centrality(
+    capsules: &HashMap<Uuid, Capsule>,
+    relations: &[CapsuleRelation],
+    limit: usize,
+) -> CentralityRankings {
+    let centrality = calculate_centrality(capsules, relations);
+    let depended_on: HashSet<Uuid> = relations
+        .iter()
+        .filter(|r| r.from_id != r.to_id)
+        .map(|r| r.to_id)
+        .collect();
+    let ranking = |score: &dyn Fn(&Centrality) -> f64, keep: &dyn Fn(&Uuid, f64) -> bool| {
+        let mut ranked: Vec<RankedCapsule> = centrality
+            .iter()
+            .filter_map(|(id, c)| {
+                let capsule = capsules.get(id)?;
+                let score = score(c);
+                keep(id, score).then(|| RankedCapsule {
+                    capsule_id: *id,
+                    name: capsule.name.clone(),
+                    capsule_type: capsule.capsule_type,
+                    score,
+                })
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.capsule_id.cmp(&b.capsule_id))
+        });
+        ranked.truncate(limit);
+        ranked
+    };
+    CentralityRankings {
+        hubs: ranking(&|c| c.pagerank, &|id, _| depended_on.contains(id)),
+        bottlenecks: ranking(&|c| c.betweenness, &|_, score| score > 0.0),
+    }
+}
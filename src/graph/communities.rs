@@ -0,0 +1,346 @@
+// Community detection (Louvain modularity optimization) over code-level
+// dependencies. Detected communities are natural module boundaries; comparing
+// them with the directory layout shows capsules that live far from the code
+// they work with
+use crate::dsm::common_root;
+use crate::external_deps::EXTERNAL_PACKAGE_KEY;
+use crate::types::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Communities listed in ai_compact; JSON keeps all of them
+const COMPACT_COMMUNITIES: usize = 10;
+/// Capsules suggested for a move, listed per community in ai_compact
+const COMPACT_MOVES: usize = 5;
+/// Local-moving passes per Louvain level
+const MAX_PASSES: usize = 50;
+/// Smallest modularity gain treated as an improvement
+const MIN_GAIN: f64 = 1e-12;
+
+/// Detects communities in the dependency graph of a project
+#[derive(Debug, Default)]
+pub struct CommunityDetector;
+
+impl CommunityDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Communities as capsule id lists, each sorted by id, largest community
+    /// first. Heuristic directory/layer relations are ignored, so the result
+    /// reflects the code and can be compared with the layout; external
+    /// package capsules are left out
+    pub fn detect(&self, graph: &CapsuleGraph) -> Vec<Vec<Uuid>> {
+        let mut ids: Vec<Uuid> = graph
+            .capsules
+            .values()
+            .filter(|c| !c.metadata.contains_key(EXTERNAL_PACKAGE_KEY))
+            .map(|c| c.id)
+            .collect();
+        ids.sort();
+        let index: HashMap<Uuid, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let mut adjacency: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); ids.len()];
+        for relation in &graph.relations {
+            if relation.relation_type.is_structural() {
+                continue;
+            }
+            if let (Some(&a), Some(&b)) = (index.get(&relation.from_id), index.get(&relation.to_id))
+            {
+                if a != b {
+                    let weight = f64::from(relation.strength.max(0.0)).max(f64::EPSILON);
+                    *adjacency[a].entry(b).or_default() += weight;
+                    *adjacency[b].entry(a).or_default() += weight;
+                }
+            }
+        }
+
+        let assignment = louvain(adjacency);
+        let mut groups: BTreeMap<usize, Vec<Uuid>> = BTreeMap::new();
+        for (node, community) in assignment.into_iter().enumerate() {
+            groups.entry(community).or_default().push(ids[node]);
+        }
+        let mut communities: Vec<Vec<Uuid>> = groups.into_values().collect();
+        communities.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+        communities
+    }
+}
+
+/// Louvain: move nodes between communities while modularity grows, then
+/// collapse communities into nodes and repeat until nothing moves. Returns the
+/// community of every original node
+fn louvain(mut adjacency: Vec<BTreeMap<usize, f64>>) -> Vec<usize> {
+    let mut assignment: Vec<usize> = (0..adjacency.len()).collect();
+    loop {
+        let (communities, moved) = local_moving(&adjacency);
+        if !moved {
+            return assignment;
+        }
+        // renumber communities densely in order of first appearance
+        let mut renumbered: HashMap<usize, usize> = HashMap::new();
+        let dense: Vec<usize> = communities
+            .iter()
+            .map(|c| {
+                let next = renumbered.len();
+                *renumbered.entry(*c).or_insert(next)
+            })
+            .collect();
+        for community in &mut assignment {
+            *community = dense[*community];
+        }
+        if renumbered.len() == adjacency.len() {
+            // nodes only swapped communities, nothing to collapse
+            return assignment;
+        }
+        let mut collapsed: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); renumbered.len()];
+        for (node, neighbours) in adjacency.iter().enumerate() {
+            for (&other, &weight) in neighbours {
+                *collapsed[dense[node]].entry(dense[other]).or_default() += weight;
+            }
+        }
+        adjacency = collapsed;
+    }
+}
+
+/// One Louvain level; returns the community of every node and whether any
+/// node changed its community
+fn local_moving(adjacency: &[BTreeMap<usize, f64>]) -> (Vec<usize>, bool) {
+    let n = adjacency.len();
+    let degree: Vec<f64> = adjacency.iter().map(|a| a.values().sum()).collect();
+    let total: f64 = degree.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    if total <= 0.0 {
+        return (community, false);
+    }
+    let mut community_degree = degree.clone();
+    let mut moved = false;
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+        for node in 0..n {
+            let current = community[node];
+            let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+            for (&other, &weight) in &adjacency[node] {
+                if other != node {
+                    *links.entry(community[other]).or_default() += weight;
+                }
+            }
+            community_degree[current] -= degree[node];
+            let gain = |c: usize, link: f64| link - community_degree[c] * degree[node] / total;
+            let mut best = current;
+            let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+            for (&candidate, &link) in &links {
+                let candidate_gain = gain(candidate, link);
+                if candidate_gain > best_gain + MIN_GAIN {
+                    best = candidate;
+                    best_gain = candidate_gain;
+                }
+            }
+            community_degree[best] += degree[node];
+            if best != current {
+                community[node] = best;
+                improved = true;
+                moved = true;
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    (community, moved)
+}
+
+/// Modularity of a partition over the same code-level edges as `detect`
+fn modularity(graph: &CapsuleGraph, community_of: &HashMap<Uuid, usize>) -> f64 {
+    let mut inside = 0.0;
+    let mut degree: HashMap<usize, f64> = HashMap::new();
+    let mut total = 0.0;
+    for relation in &graph.relations {
+        if relation.relation_type.is_structural() || relation.from_id == relation.to_id {
+            continue;
+        }
+        let (Some(&a), Some(&b)) = (
+            community_of.get(&relation.from_id),
+            community_of.get(&relation.to_id),
+        ) else {
+            continue;
+        };
+        let weight = f64::from(relation.strength.max(0.0)).max(f64::EPSILON);
+        total += 2.0 * weight;
+        *degree.entry(a).or_default() += weight;
+        *degree.entry(b).or_default() += weight;
+        if a == b {
+            inside += 2.0 * weight;
+        }
+    }
+    if total <= 0.0 {
+        return 0.0;
+    }
+    inside / total - degree.values().map(|d| (d / total).powi(2)).sum::<f64>()
+}
+
+/// Capsule whose directory differs from the dominant one of its community
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MoveCandidate {
+    pub name: String,
+    pub file: PathBuf,
+    /// Current directory relative to the project root
+    pub directory: String,
+}
+
+/// Detected community compared with the directory layout
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestedModule {
+    pub capsules: usize,
+    /// Directory holding most capsules of the community
+    pub dominant_directory: String,
+    /// Share of capsules in the dominant directory
+    pub directory_share: f64,
+    /// Other directories the community spans, with capsule counts
+    pub other_directories: Vec<(String, usize)>,
+    pub move_candidates: Vec<MoveCandidate>,
+}
+
+/// Suggested modularization: communities with more than one capsule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModularizationReport {
+    pub modularity: f64,
+    /// Share of capsules already in the dominant directory of their community
+    pub layout_agreement: f64,
+    pub modules: Vec<SuggestedModule>,
+}
+
+impl ModularizationReport {
+    /// `None` unless at least two communities have more than one capsule:
+    /// a single cluster says nothing about module boundaries
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let communities = CommunityDetector::new().detect(graph);
+        let clustered: Vec<&Vec<Uuid>> = communities.iter().filter(|c| c.len() > 1).collect();
+        if clustered.len() < 2 {
+            return None;
+        }
+        let community_of: HashMap<Uuid, usize> = communities
+            .iter()
+            .enumerate()
+            .flat_map(|(i, members)| members.iter().map(move |id| (*id, i)))
+            .collect();
+        let root = common_root(
+            clustered
+                .iter()
+                .flat_map(|c| c.iter())
+                .filter_map(|id| graph.capsules.get(id))
+                .map(|c| c.file_path.as_path()),
+        );
+
+        let mut agreeing = 0;
+        let mut total = 0;
+        let modules: Vec<SuggestedModule> = clustered
+            .iter()
+            .map(|members| {
+                let capsules: Vec<&Capsule> = members
+                    .iter()
+                    .filter_map(|id| graph.capsules.get(id))
+                    .collect();
+                let mut directories: BTreeMap<String, usize> = BTreeMap::new();
+                for capsule in &capsules {
+                    *directories
+                        .entry(directory_of(&capsule.file_path, &root))
+                        .or_default() += 1;
+                }
+                let mut ranked: Vec<(String, usize)> = directories.into_iter().collect();
+                ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                let (dominant_directory, dominant) = ranked.remove(0);
+                agreeing += dominant;
+                total += capsules.len();
+
+                let mut move_candidates: Vec<MoveCandidate> = capsules
+                    .iter()
+                    .filter_map(|capsule| {
+                        let directory = directory_of(&capsule.file_path, &root);
+                        (directory != dominant_directory).then(|| MoveCandidate {
+                            name: capsule.name.clone(),
+                            file: capsule.file_path.clone(),
+                            directory,
+                        })
+                    })
+                    .collect();
+                move_candidates.sort_by(|a, b| a.file.cmp(&b.file).then(a.name.cmp(&b.name)));
+
+                SuggestedModule {
+                    capsules: capsules.len(),
+                    directory_share: dominant as f64 / capsules.len() as f64,
+                    dominant_directory,
+                    other_directories: ranked,
+                    move_candidates,
+                }
+            })
+            .collect();
+
+        Some(Self {
+            modularity: modularity(graph, &community_of),
+            layout_agreement: agreeing as f64 / total as f64,
+            modules,
+        })
+    }
+
+    /// ai_compact section: each community with its dominant directory and
+    /// the capsules living elsewhere
+    pub fn compact_section(&self) -> String {
+        let mut out = format!(
+            "\n## Suggested Modularization ({} communities, modularity {:.2}, layout agreement {:.0}%)\n",
+            self.modules.len(),
+            self.modularity,
+            self.layout_agreement * 100.0
+        );
+        for (i, module) in self.modules.iter().take(COMPACT_COMMUNITIES).enumerate() {
+            out.push_str(&format!(
+                "- Community {} ({} capsules): {} ({:.0}%)",
+                i + 1,
+                module.capsules,
+                module.dominant_directory,
+                module.directory_share * 100.0
+            ));
+            if !module.other_directories.is_empty() {
+                let others: Vec<String> = module
+                    .other_directories
+                    .iter()
+                    .map(|(dir, count)| format!("{dir} ({count})"))
+                    .collect();
+                out.push_str(&format!(", also {}", others.join(", ")));
+            }
+            out.push('\n');
+            for candidate in module.move_candidates.iter().take(COMPACT_MOVES) {
+                out.push_str(&format!(
+                    "  - move {} from {} to {}\n",
+                    candidate.name, candidate.directory, module.dominant_directory
+                ));
+            }
+            if module.move_candidates.len() > COMPACT_MOVES {
+                out.push_str(&format!(
+                    "  - ... and {} more\n",
+                    module.move_candidates.len() - COMPACT_MOVES
+                ));
+            }
+        }
+        if self.modules.len() > COMPACT_COMMUNITIES {
+            out.push_str(&format!(
+                "- ... and {} more communities\n",
+                self.modules.len() - COMPACT_COMMUNITIES
+            ));
+        }
+        out
+    }
+}
+
+/// Directory of a file relative to the project root, `.` for the root itself
+fn directory_of(file: &Path, root: &Path) -> String {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let relative = dir.strip_prefix(root).unwrap_or(dir);
+    if relative.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        relative.to_string_lossy().replace('\\', "/")
+    }
+}
@@ -0,0 +1,164 @@
+// Condensation graph: every strongly connected component becomes one node
+use crate::types::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Metadata key with the number of capsules merged into a condensed node
+pub const CONDENSED_KEY: &str = "condensed_capsules";
+/// Tag of condensed nodes
+pub const CONDENSED_TAG: &str = "cycle";
+/// How many member names a condensed node lists before "+N"
+const NAMED_MEMBERS: usize = 3;
+
+/// Collapses every component with more than one member into a single node.
+/// Relations inside a component disappear, relations between components are
+/// merged per type keeping the strongest; the result is acyclic with respect
+/// to the relations the components were computed over
+pub fn condense(graph: &CapsuleGraph, components: &[Vec<Uuid>]) -> CapsuleGraph {
+    let mut mapping: HashMap<Uuid, Uuid> = graph.capsules.keys().map(|id| (*id, *id)).collect();
+    let mut capsules: HashMap<Uuid, Capsule> = HashMap::new();
+    let mut merged_ids: HashSet<Uuid> = HashSet::new();
+    for component in components {
+        let members: Vec<&Capsule> = component
+            .iter()
+            .filter_map(|id| graph.capsules.get(id))
+            .collect();
+        if members.len() < 2 {
+            continue;
+        }
+        let node = condensed_capsule(&members);
+        for member in &members {
+            mapping.insert(member.id, node.id);
+            merged_ids.insert(member.id);
+        }
+        capsules.insert(node.id, node);
+    }
+    for (id, capsule) in &graph.capsules {
+        if !merged_ids.contains(id) {
+            capsules.insert(*id, capsule.clone());
+        }
+    }
+
+    let mut merged: BTreeMap<(Uuid, Uuid, RelationType), CapsuleRelation> = BTreeMap::new();
+    for relation in &graph.relations {
+        let (Some(&from), Some(&to)) =
+            (mapping.get(&relation.from_id), mapping.get(&relation.to_id))
+        else {
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+        merged
+            .entry((from, to, relation.relation_type))
+            .and_modify(|r| r.strength = r.strength.max(relation.strength))
+            .or_insert_with(|| CapsuleRelation {
+                from_id: from,
+                to_id: to,
+                ..relation.clone()
+            });
+    }
+    let relations: Vec<CapsuleRelation> = merged.into_values().collect();
+
+    for capsule in capsules.values_mut() {
+        capsule.dependencies.clear();
+        capsule.dependents.clear();
+    }
+    let mut seen = HashSet::new();
+    for relation in &relations {
+        if !seen.insert((relation.from_id, relation.to_id)) {
+            continue;
+        }
+        if let Some(c) = capsules.get_mut(&relation.from_id) {
+            c.dependencies.push(relation.to_id);
+        }
+        if let Some(c) = capsules.get_mut(&relation.to_id) {
+            c.dependents.push(relation.from_id);
+        }
+    }
+    let mut layers: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for capsule in capsules.values() {
+        if let Some(layer) = &capsule.layer {
+            layers.entry(layer.clone()).or_default().push(capsule.id);
+        }
+    }
+    for ids in layers.values_mut() {
+        ids.sort();
+    }
+    CapsuleGraph {
+        capsules,
+        relations,
+        layers,
+        metrics: graph.metrics.clone(),
+        created_at: graph.created_at,
+        previous_analysis: graph.previous_analysis.clone(),
+    }
+}
+
+/// Node standing for one component; its id is derived from the member ids,
+/// so the same cycle gets the same node across runs
+fn condensed_capsule(members: &[&Capsule]) -> Capsule {
+    let mut member_ids: Vec<String> = members.iter().map(|c| c.id.to_string()).collect();
+    member_ids.sort();
+    let id = Uuid::new_v5(
+        &CAPSULE_ID_NAMESPACE,
+        format!("scc:{}", member_ids.join(",")).as_bytes(),
+    );
+    let first = members[0];
+    let mut names: Vec<&str> = members
+        .iter()
+        .take(NAMED_MEMBERS)
+        .map(|c| c.name.as_str())
+        .collect();
+    let rest = members.len().saturating_sub(NAMED_MEMBERS);
+    let more = format!("+{rest}");
+    if rest > 0 {
+        names.push(&more);
+    }
+    let mut metadata = HashMap::new();
+    metadata.insert(CONDENSED_KEY.to_string(), members.len().to_string());
+    let mut layers: BTreeMap<&str, usize> = BTreeMap::new();
+    for member in members {
+        if let Some(layer) = &member.layer {
+            *layers.entry(layer.as_str()).or_insert(0) += 1;
+        }
+    }
+    Capsule {
+        id,
+        name: format!("SCC: {}", names.join(", ")),
+        capsule_type: CapsuleType::Module,
+        file_path: first.file_path.clone(),
+        line_start: first.line_start,
+        line_end: first.line_end,
+        size: members.iter().map(|c| c.size).sum(),
+        complexity: members.iter().map(|c| c.complexity).sum(),
+        dependencies: Vec::new(),
+        layer: layers
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(layer, _)| layer.to_string()),
+        summary: None,
+        description: Some(format!(
+            "Strongly connected component of {} capsules: {}",
+            members.len(),
+            members
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        warnings: members.iter().flat_map(|c| c.warnings.clone()).collect(),
+        status: CapsuleStatus::Active,
+        priority: members
+            .iter()
+            .map(|c| c.priority)
+            .min()
+            .unwrap_or(Priority::Medium),
+        tags: vec![CONDENSED_TAG.to_string()],
+        metadata,
+        quality_score: members.iter().map(|c| c.quality_score).sum::<f64>() / members.len() as f64,
+        slogan: None,
+        dependents: Vec::new(),
+        created_at: None,
+    }
+}
@@ -0,0 +1,238 @@
+// Single points of failure: capsules (articulation points) and dependencies
+// (bridges) whose removal splits the dependency graph into disconnected parts.
+// Connectivity ignores direction; only relations found in code count
+use crate::external_deps::EXTERNAL_PACKAGE_KEY;
+use crate::packages::PACKAGE_KEY;
+use crate::types::*;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// A cut is reported when at least this many capsules lose their connection to
+/// the rest; a single leaf hanging off one dependency is not a finding
+pub const MIN_SEPARATED: usize = 2;
+
+/// Capsule whose removal disconnects the graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArticulationPoint {
+    #[serde(skip)]
+    pub capsule_id: Uuid,
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+    /// Capsules cut off from the largest remaining part
+    pub separated: usize,
+}
+
+/// Dependency that is the only link between two parts of the graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bridge {
+    #[serde(skip)]
+    pub from_id: Uuid,
+    #[serde(skip)]
+    pub to_id: Uuid,
+    pub from: String,
+    pub to: String,
+    pub relation_type: RelationType,
+    /// Capsules on the smaller side of the bridge
+    pub separated: usize,
+}
+
+/// Articulation points and bridges, most capsules separated first
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SinglePointsOfFailure {
+    pub articulation_points: Vec<ArticulationPoint>,
+    pub bridges: Vec<Bridge>,
+}
+
+impl SinglePointsOfFailure {
+    /// `None` when removing no single capsule or dependency disconnects the graph
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let undirected = Undirected::new(graph);
+        let cuts = undirected.cuts();
+
+        let mut articulation_points: Vec<ArticulationPoint> = cuts
+            .articulation
+            .iter()
+            .filter(|(_, separated)| **separated >= MIN_SEPARATED)
+            .filter_map(|(&v, &separated)| {
+                let capsule = graph.capsules.get(&undirected.ids[v])?;
+                Some(ArticulationPoint {
+                    capsule_id: capsule.id,
+                    name: capsule.name.clone(),
+                    file: capsule.file_path.clone(),
+                    line: capsule.line_start,
+                    separated,
+                })
+            })
+            .collect();
+        articulation_points.sort_by(|a, b| {
+            b.separated
+                .cmp(&a.separated)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut bridges: Vec<Bridge> =
+            cuts.bridges
+                .iter()
+                .filter(|(_, separated)| **separated >= MIN_SEPARATED)
+                .filter_map(|(&(a, b), &separated)| {
+                    let (a, b) = (undirected.ids[a], undirected.ids[b]);
+                    let relation = graph.relations.iter().filter(|r| counts(r)).find(|r| {
+                        (r.from_id, r.to_id) == (a, b) || (r.from_id, r.to_id) == (b, a)
+                    })?;
+                    Some(Bridge {
+                        from_id: relation.from_id,
+                        to_id: relation.to_id,
+                        from: graph.capsules.get(&relation.from_id)?.name.clone(),
+                        to: graph.capsules.get(&relation.to_id)?.name.clone(),
+                        relation_type: relation.relation_type,
+                        separated,
+                    })
+                })
+                .collect();
+        bridges.sort_by(|a, b| {
+            b.separated
+                .cmp(&a.separated)
+                .then_with(|| a.from.cmp(&b.from))
+                .then_with(|| a.to.cmp(&b.to))
+        });
+
+        if articulation_points.is_empty() && bridges.is_empty() {
+            return None;
+        }
+        Some(Self {
+            articulation_points,
+            bridges,
+        })
+    }
+}
+
+/// Relations that connect capsules: structural ones (shared directory or
+/// layer) and containment say nothing about dependencies
+fn counts(relation: &CapsuleRelation) -> bool {
+    !relation.relation_type.is_structural()
+        && relation.relation_type != RelationType::Contains
+        && relation.from_id != relation.to_id
+}
+
+/// Found cuts by vertex index: capsules separated by each articulation point
+/// and by each bridge `(lower, higher)`
+#[derive(Default)]
+struct Cuts {
+    articulation: HashMap<usize, usize>,
+    bridges: HashMap<(usize, usize), usize>,
+}
+
+/// Simple undirected graph over indices sorted by capsule id, so results do
+/// not depend on `HashMap` order. Package and external dependency capsules
+/// aggregate other capsules and are left out
+struct Undirected {
+    ids: Vec<Uuid>,
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl Undirected {
+    fn new(graph: &CapsuleGraph) -> Self {
+        let mut ids: Vec<Uuid> = graph
+            .capsules
+            .values()
+            .filter(|c| {
+                !c.metadata.contains_key(PACKAGE_KEY)
+                    && !c.metadata.contains_key(EXTERNAL_PACKAGE_KEY)
+            })
+            .map(|c| c.id)
+            .collect();
+        ids.sort();
+        let index: HashMap<Uuid, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut edges = BTreeSet::new();
+        for relation in graph.relations.iter().filter(|r| counts(r)) {
+            if let (Some(&a), Some(&b)) = (index.get(&relation.from_id), index.get(&relation.to_id))
+            {
+                edges.insert((a.min(b), a.max(b)));
+            }
+        }
+        let mut adjacent = vec![Vec::new(); ids.len()];
+        for (a, b) in edges {
+            adjacent[a].push(b);
+            adjacent[b].push(a);
+        }
+        Self { ids, adjacent }
+    }
+
+    /// Tarjan's low-link search, iterative so deep call chains do not
+    /// overflow the stack
+    fn cuts(&self) -> Cuts {
+        const UNSEEN: usize = usize::MAX;
+        let n = self.ids.len();
+        let mut discovered = vec![UNSEEN; n];
+        let mut low = vec![0; n];
+        let mut subtree = vec![0; n];
+        let mut cuts = Cuts::default();
+        let mut time = 0;
+
+        for root in 0..n {
+            if discovered[root] != UNSEEN || self.adjacent[root].is_empty() {
+                continue;
+            }
+            // pieces left by removing a vertex: subtrees of the children it cuts off
+            let mut pieces: HashMap<usize, Vec<usize>> = HashMap::new();
+            let mut bridges: Vec<((usize, usize), usize)> = Vec::new();
+            let mut component = 0;
+            let mut stack: Vec<(usize, usize, usize)> = vec![(root, UNSEEN, 0)];
+            discovered[root] = time;
+            low[root] = time;
+            subtree[root] = 1;
+            time += 1;
+
+            while let Some((v, parent, next)) = stack.last_mut() {
+                let v = *v;
+                if let Some(&w) = self.adjacent[v].get(*next) {
+                    *next += 1;
+                    if discovered[w] == UNSEEN {
+                        discovered[w] = time;
+                        low[w] = time;
+                        subtree[w] = 1;
+                        time += 1;
+                        stack.push((w, v, 0));
+                    } else if w != *parent {
+                        low[v] = low[v].min(discovered[w]);
+                    }
+                    continue;
+                }
+                stack.pop();
+                component += 1;
+                if let Some(&(p, _, _)) = stack.last() {
+                    low[p] = low[p].min(low[v]);
+                    subtree[p] += subtree[v];
+                    if low[v] > discovered[p] {
+                        bridges.push(((p.min(v), p.max(v)), subtree[v]));
+                    }
+                    if low[v] >= discovered[p] {
+                        pieces.entry(p).or_default().push(subtree[v]);
+                    }
+                }
+            }
+
+            for (v, mut parts) in pieces {
+                let cut_off: usize = parts.iter().sum();
+                if v != root {
+                    // the part still attached through the parent
+                    parts.push(component - 1 - cut_off);
+                }
+                if parts.len() < 2 {
+                    continue;
+                }
+                let largest = parts.iter().copied().max().unwrap_or(0);
+                cuts.articulation.insert(v, component - 1 - largest);
+            }
+            for (edge, below) in bridges {
+                cuts.bridges.insert(edge, below.min(component - below));
+            }
+        }
+        cuts
+    }
+}
@@ -1,6 +1,6 @@
 // Cycle detection for dependency graphs
 use crate::types::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// Cycle detector for dependency analysis
@@ -24,7 +24,10 @@ impl CycleDetector {
         self.visited.clear();
         self.recursion_stack.clear();
 
-        for capsule_id in graph.capsules.keys() {
+        // The cycles found depend on where the search starts: go in id order
+        let mut ids: Vec<Uuid> = graph.capsules.keys().copied().collect();
+        ids.sort();
+        for capsule_id in &ids {
             if !self.visited.contains(capsule_id) {
                 if let Some(cycle) = self.dfs_cycle_detection(*capsule_id, graph, &mut Vec::new()) {
                     cycles.push(cycle);
@@ -93,79 +96,110 @@ impl CycleDetector {
         !self.find_cycles(graph).is_empty()
     }
 
-    /// Get strongly connected components
+    /// Strongly connected components with a cycle (Tarjan): every group of
+    /// capsules that depend on each other through relations found in code
+    /// (imports, calls, composition…), plus capsules depending on themselves.
+    /// Structural relations (shared directory or layer) link whole layers both
+    /// ways and are ignored. Members and groups are ordered by file, line and name
     pub fn get_strongly_connected_components(&mut self, graph: &CapsuleGraph) -> Vec<Vec<Uuid>> {
-        let mut components = Vec::new();
-        let mut visited = HashSet::new();
-
-        // First pass: collect finish times
-        let mut finish_order = Vec::new();
-        for &capsule_id in graph.capsules.keys() {
-            if !visited.contains(&capsule_id) {
-                self.dfs_finish_time(capsule_id, graph, &mut visited, &mut finish_order);
+        let order = |id: &Uuid| {
+            graph
+                .capsules
+                .get(id)
+                .map(|c| (c.file_path.clone(), c.line_start, c.name.clone(), *id))
+        };
+        let mut nodes: Vec<Uuid> = graph.capsules.keys().copied().collect();
+        nodes.sort_by_key(order);
+        let mut edges: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for relation in &graph.relations {
+            if relation.relation_type.is_structural()
+                || relation.relation_type == RelationType::Contains
+                || !graph.capsules.contains_key(&relation.to_id)
+            {
+                continue;
             }
-        }
-
-        // Second pass: find components in reverse finish order
-        visited.clear();
-        finish_order.reverse();
-
-        for &capsule_id in &finish_order {
-            if !visited.contains(&capsule_id) {
-                let mut component = Vec::new();
-                self.dfs_component(capsule_id, graph, &mut visited, &mut component);
-                if component.len() > 1 {
-                    components.push(component);
-                }
+            let targets = edges.entry(relation.from_id).or_default();
+            if !targets.contains(&relation.to_id) {
+                targets.push(relation.to_id);
             }
         }
 
+        let mut components: Vec<Vec<Uuid>> = strongly_connected(&nodes, &edges)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || edges
+                        .get(&component[0])
+                        .is_some_and(|targets| targets.contains(&component[0]))
+            })
+            .collect();
+        for component in &mut components {
+            component.sort_by_key(order);
+        }
+        components.sort_by_key(|c| c.first().and_then(order));
         components
     }
+}
 
-    /// DFS for finish time calculation
-    #[allow(clippy::only_used_in_recursion)]
-    fn dfs_finish_time(
-        &self,
-        capsule_id: Uuid,
-        graph: &CapsuleGraph,
-        visited: &mut HashSet<Uuid>,
-        finish_order: &mut Vec<Uuid>,
-    ) {
-        visited.insert(capsule_id);
-
-        if let Some(capsule) = graph.capsules.get(&capsule_id) {
-            for &dependency_id in &capsule.dependencies {
-                if !visited.contains(&dependency_id) {
-                    self.dfs_finish_time(dependency_id, graph, visited, finish_order);
+/// Tarjan's algorithm over `edges`, visiting roots in the order of `nodes`.
+/// Returns every component, single nodes included, dependencies first: a
+/// component comes after all components it has edges to
+pub(crate) fn strongly_connected(
+    nodes: &[Uuid],
+    edges: &HashMap<Uuid, Vec<Uuid>>,
+) -> Vec<Vec<Uuid>> {
+    let mut index: HashMap<Uuid, usize> = HashMap::new();
+    let mut low: HashMap<Uuid, usize> = HashMap::new();
+    let mut stack: Vec<Uuid> = Vec::new();
+    let mut on_stack: HashSet<Uuid> = HashSet::new();
+    let mut components = Vec::new();
+
+    for &root in nodes {
+        if index.contains_key(&root) {
+            continue;
+        }
+        // Iterative DFS: (node, next dependency to visit)
+        let mut work: Vec<(Uuid, usize)> = vec![(root, 0)];
+        while let Some(&mut (node, ref mut next)) = work.last_mut() {
+            if *next == 0 && !index.contains_key(&node) {
+                let i = index.len();
+                index.insert(node, i);
+                low.insert(node, i);
+                stack.push(node);
+                on_stack.insert(node);
+            }
+            let dependencies = edges.get(&node).map(Vec::as_slice).unwrap_or_default();
+            if let Some(&dependency) = dependencies.get(*next) {
+                *next += 1;
+                if !index.contains_key(&dependency) {
+                    work.push((dependency, 0));
+                } else if on_stack.contains(&dependency) {
+                    let lowest = low[&node].min(index[&dependency]);
+                    low.insert(node, lowest);
                 }
+                continue;
             }
-        }
 
-        finish_order.push(capsule_id);
-    }
-
-    /// DFS for component collection
-    #[allow(clippy::only_used_in_recursion)]
-    fn dfs_component(
-        &self,
-        capsule_id: Uuid,
-        graph: &CapsuleGraph,
-        visited: &mut HashSet<Uuid>,
-        component: &mut Vec<Uuid>,
-    ) {
-        visited.insert(capsule_id);
-        component.push(capsule_id);
-
-        // Follow reverse dependencies (dependents)
-        if let Some(capsule) = graph.capsules.get(&capsule_id) {
-            for &dependent_id in &capsule.dependents {
-                if !visited.contains(&dependent_id) {
-                    self.dfs_component(dependent_id, graph, visited, component);
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                let lowest = low[&parent].min(low[&node]);
+                low.insert(parent, lowest);
+            }
+            if low[&node] != index[&node] {
+                continue;
+            }
+            let mut component = Vec::new();
+            while let Some(member) = stack.pop() {
+                on_stack.remove(&member);
+                component.push(member);
+                if member == node {
+                    break;
                 }
             }
+            components.push(component);
         }
     }
+    components
 }
 
 impl Default for CycleDetector {
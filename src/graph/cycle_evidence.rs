@@ -0,0 +1,140 @@
+// Every dependency cycle group (strongly connected component) with the
+// source lines that form its edges, so a cycle can be broken at a known
+// `file:line` instead of being rediscovered by hand
+use crate::graph::CycleDetector;
+use crate::relation_locations::{RelationLocator, SourceLocation};
+use crate::types::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Groups listed in ai_compact; JSON keeps all of them
+const COMPACT_GROUPS: usize = 10;
+/// Edges listed per group in ai_compact
+const COMPACT_EDGES: usize = 8;
+
+/// Capsule of a cycle group
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CycleMember {
+    pub name: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Dependency inside a cycle group and where it is written
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+    pub relation_type: RelationType,
+    pub location: Option<SourceLocation>,
+}
+
+/// One strongly connected component
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CycleGroup {
+    pub members: Vec<CycleMember>,
+    pub edges: Vec<CycleEdge>,
+}
+
+/// All cycle groups of a graph, largest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CyclesDetail {
+    pub groups: Vec<CycleGroup>,
+}
+
+impl CyclesDetail {
+    /// `None` when the graph has no cycles
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let components = CycleDetector::new().get_strongly_connected_components(graph);
+        if components.is_empty() {
+            return None;
+        }
+        let mut locator = RelationLocator::new(graph);
+        let mut groups: Vec<CycleGroup> = components
+            .iter()
+            .map(|component| {
+                let ids: HashSet<Uuid> = component.iter().copied().collect();
+                let members = component
+                    .iter()
+                    .filter_map(|id| graph.capsules.get(id))
+                    .map(|c| CycleMember {
+                        name: c.name.clone(),
+                        file: c.file_path.clone(),
+                        line: c.line_start,
+                    })
+                    .collect();
+                // one edge per ordered pair, relations found in code only
+                let mut seen = HashSet::new();
+                let mut edges: Vec<CycleEdge> = graph
+                    .relations
+                    .iter()
+                    .filter(|r| {
+                        !r.relation_type.is_structural()
+                            && r.relation_type != RelationType::Contains
+                            && ids.contains(&r.from_id)
+                            && ids.contains(&r.to_id)
+                            && seen.insert((r.from_id, r.to_id))
+                    })
+                    .map(|r| CycleEdge {
+                        from: graph.capsules[&r.from_id].name.clone(),
+                        to: graph.capsules[&r.to_id].name.clone(),
+                        relation_type: r.relation_type,
+                        location: locator.locate(r),
+                    })
+                    .collect();
+                edges.sort_by(|a, b| {
+                    let place =
+                        |e: &CycleEdge| e.location.as_ref().map(|l| (l.file.clone(), l.line));
+                    place(a)
+                        .cmp(&place(b))
+                        .then_with(|| a.from.cmp(&b.from))
+                        .then_with(|| a.to.cmp(&b.to))
+                });
+                CycleGroup { members, edges }
+            })
+            .collect();
+        groups.sort_by_key(|g| std::cmp::Reverse(g.members.len()));
+        Some(Self { groups })
+    }
+
+    /// ai_compact section: members and `file:line` of every edge per group
+    pub fn compact_section(&self) -> String {
+        let mut out = format!("\n## Cycles Detail ({} groups)\n", self.groups.len());
+        for (i, group) in self.groups.iter().take(COMPACT_GROUPS).enumerate() {
+            let names: Vec<&str> = group.members.iter().map(|m| m.name.as_str()).collect();
+            out.push_str(&format!(
+                "- Group {} ({} capsules): {}\n",
+                i + 1,
+                group.members.len(),
+                names.join(", ")
+            ));
+            for edge in group.edges.iter().take(COMPACT_EDGES) {
+                out.push_str(&format!(
+                    "  - {} -> {} ({:?}){}\n",
+                    edge.from,
+                    edge.to,
+                    edge.relation_type,
+                    edge.location
+                        .as_ref()
+                        .map(|l| format!(" at {l}"))
+                        .unwrap_or_default()
+                ));
+            }
+            if group.edges.len() > COMPACT_EDGES {
+                out.push_str(&format!(
+                    "  - ... and {} more edges\n",
+                    group.edges.len() - COMPACT_EDGES
+                ));
+            }
+        }
+        if self.groups.len() > COMPACT_GROUPS {
+            out.push_str(&format!(
+                "- ... and {} more groups\n",
+                self.groups.len() - COMPACT_GROUPS
+            ));
+        }
+        out
+    }
+}
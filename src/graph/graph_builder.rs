@@ -65,9 +65,19 @@ impl CapsuleGraphBuilder {
                 .add_cycle_warnings(&mut graph, &cycles)?;
         }
 
+        // Topological levels over the condensed graph
+        self.metrics_calculator
+            .assign_topological_levels(&mut graph);
+
         Ok(graph)
     }
 
+    /// Condensation graph: each strongly connected component becomes one node
+    pub fn condense_graph(&mut self, graph: &CapsuleGraph) -> CapsuleGraph {
+        let components = self.cycle_detector.get_strongly_connected_components(graph);
+        crate::graph::condense(graph, &components)
+    }
+
     /// Get detailed graph analysis
     pub fn analyze_graph(&mut self, graph: &CapsuleGraph) -> Result<GraphAnalysis> {
         let cycles = self.cycle_detector.find_cycles(graph);
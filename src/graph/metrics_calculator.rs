@@ -1,8 +1,14 @@
 // Metrics calculation for capsule graphs
+use crate::graph::centrality::{self, Centrality, CentralityRankings};
+use crate::graph::cycle_detector::strongly_connected;
+use crate::graph::CycleDetector;
 use crate::types::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use uuid::Uuid;
 
+/// Metadata key with the topological level of a capsule
+pub const TOPOLOGICAL_LEVEL_KEY: &str = "topological_level";
+
 /// Calculates various metrics for capsule graphs
 pub struct MetricsCalculator {
     // Configuration for metric calculation
@@ -59,6 +65,10 @@ impl MetricsCalculator {
         // Depth levels
         let depth_levels = self.calculate_depth_levels(capsules, relations);
 
+        // Layering violations weighted by the number of layers crossed
+        let layering_violation_index =
+            crate::validation::LayerValidator::new().violation_index(capsules, relations);
+
         Ok(GraphMetrics {
             total_capsules,
             total_relations,
@@ -67,6 +77,7 @@ impl MetricsCalculator {
             cohesion_index,
             cyclomatic_complexity,
             depth_levels,
+            layering_violation_index,
         })
     }
 
@@ -93,22 +104,27 @@ impl MetricsCalculator {
         }
 
         // Calculate intra-layer connections
-        let mut total_internal_connections = 0;
-        let mut total_possible_connections = 0;
-
-        for group in layer_groups.values() {
-            let group_size = group.len();
-            if group_size > 1 {
-                total_possible_connections += group_size * (group_size - 1);
-
-                // Count actual connections within the group
-                for relation in relations {
-                    if group.contains(&relation.from_id) && group.contains(&relation.to_id) {
-                        total_internal_connections += 1;
-                    }
+        let total_possible_connections: usize = layer_groups
+            .values()
+            .map(|group| group.len())
+            .filter(|size| *size > 1)
+            .map(|size| size * (size - 1))
+            .sum();
+
+        // Count actual connections within the groups
+        let layer_of = capsule_layers(capsules);
+        let total_internal_connections = relations
+            .iter()
+            .filter(|relation| {
+                match (
+                    layer_of.get(&relation.from_id),
+                    layer_of.get(&relation.to_id),
+                ) {
+                    (Some(from), Some(to)) => from == to && layer_groups[*from].len() > 1,
+                    _ => false,
                 }
-            }
-        }
+            })
+            .count();
 
         if total_possible_connections == 0 {
             return 0.0;
@@ -127,12 +143,31 @@ impl MetricsCalculator {
         let edges = relations.len() as u32;
 
         // Approximate number of connected components
+        let mut neighbours: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for relation in relations {
+            neighbours
+                .entry(relation.from_id)
+                .or_default()
+                .push(relation.to_id);
+            neighbours
+                .entry(relation.to_id)
+                .or_default()
+                .push(relation.from_id);
+        }
+
         let mut components = 0;
         let mut visited: HashSet<Uuid> = HashSet::new();
 
         for capsule_id in capsules.keys() {
-            if !visited.contains(capsule_id) {
-                self.dfs_component_visit(*capsule_id, relations, &mut visited);
+            if visited.insert(*capsule_id) {
+                let mut queue = vec![*capsule_id];
+                while let Some(node) = queue.pop() {
+                    for next in neighbours.get(&node).into_iter().flatten() {
+                        if visited.insert(*next) {
+                            queue.push(*next);
+                        }
+                    }
+                }
                 components += 1;
             }
         }
@@ -151,68 +186,22 @@ impl MetricsCalculator {
         capsules: &HashMap<Uuid, Capsule>,
         relations: &[CapsuleRelation],
     ) -> u32 {
-        let mut max_depth = 0;
-
-        for capsule_id in capsules.keys() {
-            let depth = self.calculate_dependency_depth(*capsule_id, relations, &mut Vec::new());
-            max_depth = max_depth.max(depth);
-        }
-
-        max_depth
-    }
-
-    /// DFS for component connectivity
-    #[allow(clippy::only_used_in_recursion)]
-    fn dfs_component_visit(
-        &self,
-        capsule_id: Uuid,
-        relations: &[CapsuleRelation],
-        visited: &mut HashSet<Uuid>,
-    ) {
-        visited.insert(capsule_id);
-
-        // Find all connected capsules
-        for relation in relations {
-            let connected_id = if relation.from_id == capsule_id {
-                relation.to_id
-            } else if relation.to_id == capsule_id {
-                relation.from_id
-            } else {
-                continue;
-            };
-
-            if !visited.contains(&connected_id) {
-                self.dfs_component_visit(connected_id, relations, visited);
-            }
-        }
+        dependency_depths(capsules.keys().copied(), relations)
+            .into_values()
+            .max()
+            .unwrap_or(0)
     }
 
     /// Calculate dependency depth for a capsule
-    #[allow(clippy::only_used_in_recursion)]
     pub fn calculate_dependency_depth(
         &self,
         capsule_id: Uuid,
         relations: &[CapsuleRelation],
-        visited: &mut Vec<Uuid>,
     ) -> u32 {
-        if visited.contains(&capsule_id) {
-            return 0; // Avoid infinite recursion
-        }
-
-        visited.push(capsule_id);
-
-        let mut max_depth = 0;
-
-        // Find all dependencies
-        for relation in relations {
-            if relation.from_id == capsule_id {
-                let depth = 1 + self.calculate_dependency_depth(relation.to_id, relations, visited);
-                max_depth = max_depth.max(depth);
-            }
-        }
-
-        visited.pop();
-        max_depth
+        dependency_depths([capsule_id], relations)
+            .get(&capsule_id)
+            .copied()
+            .unwrap_or(0)
     }
 
     /// Calculate coupling metrics
@@ -229,11 +218,34 @@ impl MetricsCalculator {
         // Efferent coupling (Ce) - outgoing dependencies
         let mut efferent_coupling: HashMap<Uuid, u32> = HashMap::new();
 
+        // Per relation type: relation count, distinct sources and targets
+        let mut by_type: BTreeMap<RelationType, (usize, HashSet<Uuid>, HashSet<Uuid>)> =
+            BTreeMap::new();
+
         for relation in relations {
             *efferent_coupling.entry(relation.from_id).or_insert(0) += 1;
             *afferent_coupling.entry(relation.to_id).or_insert(0) += 1;
+
+            let entry = by_type.entry(relation.relation_type).or_default();
+            entry.0 += 1;
+            entry.1.insert(relation.from_id);
+            entry.2.insert(relation.to_id);
         }
 
+        let by_relation_type = by_type
+            .into_iter()
+            .map(|(relation_type, (count, sources, targets))| {
+                let coupling = RelationTypeCoupling {
+                    relations: count,
+                    sources: sources.len(),
+                    targets: targets.len(),
+                    average_efferent_coupling: count as f32 / sources.len() as f32,
+                    average_afferent_coupling: count as f32 / targets.len() as f32,
+                };
+                (relation_type, coupling)
+            })
+            .collect();
+
         // Calculate instability (I = Ce / (Ca + Ce))
         let mut instabilities = Vec::new();
         for capsule_id in capsules.keys() {
@@ -262,6 +274,7 @@ impl MetricsCalculator {
                 / total_capsules as f32,
             average_instability,
             total_coupling: relations.len(),
+            by_relation_type,
         }
     }
 
@@ -286,20 +299,25 @@ impl MetricsCalculator {
         let mut layer_cohesion_sum = 0.0;
         let mut layer_count = 0;
 
-        for capsule_ids in layer_groups.values() {
-            let mut internal_relations = 0;
-            let mut external_relations = 0;
-
-            for relation in relations {
-                let from_in_layer = capsule_ids.contains(&relation.from_id);
-                let to_in_layer = capsule_ids.contains(&relation.to_id);
-
-                if from_in_layer && to_in_layer {
-                    internal_relations += 1;
-                } else if from_in_layer || to_in_layer {
-                    external_relations += 1;
+        // Internal and external relations of every layer in one pass
+        let layer_of = capsule_layers(capsules);
+        let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+        for relation in relations {
+            let from = layer_of.get(&relation.from_id);
+            let to = layer_of.get(&relation.to_id);
+            match (from, to) {
+                (Some(from), Some(to)) if from == to => counts.entry(from).or_default().0 += 1,
+                _ => {
+                    for layer in [from, to].into_iter().flatten() {
+                        counts.entry(layer).or_default().1 += 1;
+                    }
                 }
             }
+        }
+
+        for layer in layer_groups.keys() {
+            let (internal_relations, external_relations) =
+                counts.get(layer.as_str()).copied().unwrap_or_default();
 
             let total_relations = internal_relations + external_relations;
             if total_relations > 0 {
@@ -357,6 +375,171 @@ impl MetricsCalculator {
             high_complexity_count: high_complexity,
         }
     }
+
+    /// Transitive dependents (blast radius) and transitive dependencies of every
+    /// capsule, computed in one pass over the graph
+    pub fn calculate_reachability(
+        &self,
+        capsules: &HashMap<Uuid, Capsule>,
+        relations: &[CapsuleRelation],
+    ) -> HashMap<Uuid, Reachability> {
+        let ids: Vec<Uuid> = capsules.keys().copied().collect();
+        let index: HashMap<Uuid, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut outgoing: Vec<HashSet<usize>> = vec![HashSet::new(); ids.len()];
+        let mut incoming: Vec<HashSet<usize>> = vec![HashSet::new(); ids.len()];
+        for relation in relations {
+            if let (Some(&from), Some(&to)) =
+                (index.get(&relation.from_id), index.get(&relation.to_id))
+            {
+                if from != to {
+                    outgoing[from].insert(to);
+                    incoming[to].insert(from);
+                }
+            }
+        }
+
+        // BFS with a per-start stamp instead of clearing a visited set each time
+        let mut stamp = vec![usize::MAX; ids.len()];
+        let mut reachable = |start: usize, edges: &[HashSet<usize>], round: usize| {
+            let mut queue = vec![start];
+            stamp[start] = round;
+            let mut count = 0;
+            while let Some(node) = queue.pop() {
+                for &next in &edges[node] {
+                    if stamp[next] != round {
+                        stamp[next] = round;
+                        count += 1;
+                        queue.push(next);
+                    }
+                }
+            }
+            count
+        };
+
+        ids.iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let blast_radius = reachable(i, &incoming, 2 * i);
+                let transitive_dependencies = reachable(i, &outgoing, 2 * i + 1);
+                (
+                    *id,
+                    Reachability {
+                        blast_radius,
+                        transitive_dependencies,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Topological level of every capsule: 0 for capsules that depend on
+    /// nothing, otherwise one above their highest dependency. Cycles are
+    /// condensed first, so all members of a strongly connected component share
+    /// a level. Structural and containment relations are not dependencies
+    pub fn calculate_topological_levels(&self, graph: &CapsuleGraph) -> HashMap<Uuid, usize> {
+        let mut representative: HashMap<Uuid, Uuid> =
+            graph.capsules.keys().map(|id| (*id, *id)).collect();
+        for component in CycleDetector::new().get_strongly_connected_components(graph) {
+            if let Some(&first) = component.first() {
+                for member in component {
+                    representative.insert(member, first);
+                }
+            }
+        }
+
+        let mut edges: BTreeSet<(Uuid, Uuid)> = BTreeSet::new();
+        for relation in &graph.relations {
+            if relation.relation_type.is_structural()
+                || relation.relation_type == RelationType::Contains
+            {
+                continue;
+            }
+            if let (Some(&from), Some(&to)) = (
+                representative.get(&relation.from_id),
+                representative.get(&relation.to_id),
+            ) {
+                if from != to {
+                    edges.insert((from, to));
+                }
+            }
+        }
+
+        // Kahn's algorithm from the capsules without dependencies upwards
+        let mut pending: HashMap<Uuid, usize> = HashMap::new();
+        let mut dependents: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for &(from, to) in &edges {
+            *pending.entry(from).or_insert(0) += 1;
+            dependents.entry(to).or_default().push(from);
+        }
+        let mut level: HashMap<Uuid, usize> = HashMap::new();
+        let mut ready: Vec<Uuid> = representative
+            .values()
+            .filter(|id| !pending.contains_key(id))
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        while let Some(node) = ready.pop() {
+            let node_level = *level.entry(node).or_insert(0);
+            for dependent in dependents.get(&node).into_iter().flatten() {
+                let entry = level.entry(*dependent).or_insert(0);
+                *entry = (*entry).max(node_level + 1);
+                let remaining = pending.get_mut(dependent).expect("counted dependency");
+                *remaining -= 1;
+                if *remaining == 0 {
+                    ready.push(*dependent);
+                }
+            }
+        }
+
+        representative
+            .into_iter()
+            .map(|(id, rep)| (id, level.get(&rep).copied().unwrap_or(0)))
+            .collect()
+    }
+
+    /// Stores the topological level of every capsule in its metadata under
+    /// [`TOPOLOGICAL_LEVEL_KEY`]; returns the number of levels
+    pub fn assign_topological_levels(&self, graph: &mut CapsuleGraph) -> usize {
+        let levels = self.calculate_topological_levels(graph);
+        for (id, level) in &levels {
+            if let Some(capsule) = graph.capsules.get_mut(id) {
+                capsule
+                    .metadata
+                    .insert(TOPOLOGICAL_LEVEL_KEY.to_string(), level.to_string());
+            }
+        }
+        levels.values().max().map_or(0, |max| max + 1)
+    }
+
+    /// PageRank and betweenness of every capsule
+    pub fn calculate_centrality(
+        &self,
+        capsules: &HashMap<Uuid, Capsule>,
+        relations: &[CapsuleRelation],
+    ) -> HashMap<Uuid, Centrality> {
+        centrality::calculate_centrality(capsules, relations)
+    }
+
+    /// Architectural hubs (highest PageRank) and bottlenecks (highest
+    /// betweenness), at most `limit` of each
+    pub fn calculate_centrality_rankings(
+        &self,
+        capsules: &HashMap<Uuid, Capsule>,
+        relations: &[CapsuleRelation],
+        limit: usize,
+    ) -> CentralityRankings {
+        centrality::rank_centrality(capsules, relations, limit)
+    }
+}
+
+/// Transitive reach of a capsule in the dependency graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reachability {
+    /// Capsules that directly or transitively depend on this one
+    pub blast_radius: usize,
+    /// Capsules this one directly or transitively depends on
+    pub transitive_dependencies: usize,
 }
 
 /// Coupling metrics
@@ -366,6 +549,22 @@ pub struct CouplingMetrics {
     pub average_efferent_coupling: f32,
     pub average_instability: f32,
     pub total_coupling: usize,
+    /// Coupling broken down by relation type
+    pub by_relation_type: BTreeMap<RelationType, RelationTypeCoupling>,
+}
+
+/// Coupling through relations of a single type
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelationTypeCoupling {
+    pub relations: usize,
+    /// Capsules with outgoing relations of this type
+    pub sources: usize,
+    /// Capsules with incoming relations of this type
+    pub targets: usize,
+    /// Relations per source capsule
+    pub average_efferent_coupling: f32,
+    /// Relations per target capsule
+    pub average_afferent_coupling: f32,
 }
 
 /// Cohesion metrics
@@ -418,3 +617,55 @@ impl Default for MetricsCalculator {
         Self::new()
     }
 }
+
+/// Layer of every capsule that has one
+fn capsule_layers(capsules: &HashMap<Uuid, Capsule>) -> HashMap<Uuid, &str> {
+    capsules
+        .values()
+        .filter_map(|c| Some((c.id, c.layer.as_deref()?)))
+        .collect()
+}
+
+fn outgoing_edges(relations: &[CapsuleRelation]) -> HashMap<Uuid, Vec<Uuid>> {
+    let mut outgoing: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for relation in relations {
+        outgoing
+            .entry(relation.from_id)
+            .or_default()
+            .push(relation.to_id);
+    }
+    outgoing
+}
+
+/// Longest dependency chain from every capsule. Cycles are condensed first,
+/// so all members of a strongly connected component share a depth and the
+/// result does not depend on where a traversal entered the cycle
+fn dependency_depths(
+    capsules: impl IntoIterator<Item = Uuid>,
+    relations: &[CapsuleRelation],
+) -> HashMap<Uuid, u32> {
+    let outgoing = outgoing_edges(relations);
+    let mut nodes: Vec<Uuid> = capsules
+        .into_iter()
+        .chain(outgoing.keys().copied())
+        .collect();
+    nodes.sort();
+    nodes.dedup();
+
+    // Components arrive dependencies first, so every edge leaving the
+    // component points at a capsule whose depth is already known
+    let mut depths: HashMap<Uuid, u32> = HashMap::new();
+    for component in strongly_connected(&nodes, &outgoing) {
+        let depth = component
+            .iter()
+            .flat_map(|id| outgoing.get(id).into_iter().flatten())
+            .filter_map(|next| depths.get(next))
+            .map(|depth| depth + 1)
+            .max()
+            .unwrap_or(0);
+        for member in component {
+            depths.insert(member, depth);
+        }
+    }
+    depths
+}
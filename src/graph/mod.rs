@@ -1,12 +1,30 @@
 // Graph building module - organizes all graph construction components
 
+pub mod call_graph;
+pub mod centrality;
+pub mod communities;
+pub mod condensation;
+pub mod cut_points;
 pub mod cycle_detector;
+pub mod cycle_evidence;
 pub mod graph_builder;
 pub mod metrics_calculator;
+pub mod query;
 pub mod relation_analyzer;
+pub mod relation_typing;
+pub mod symbol_table;
 
 // Re-export main types for convenience
+pub use call_graph::*;
+pub use centrality::*;
+pub use communities::*;
+pub use condensation::*;
+pub use cut_points::*;
 pub use cycle_detector::*;
+pub use cycle_evidence::*;
 pub use graph_builder::*;
 pub use metrics_calculator::*;
+pub use query::*;
 pub use relation_analyzer::*;
+pub use relation_typing::*;
+pub use symbol_table::*;
@@ -0,0 +1,475 @@
+// Graph queries: impact analysis ("what breaks if I change X", reverse
+// reachability) and shortest dependency paths ("why does ui depend on database?")
+use crate::graph_query::GraphIndex;
+use crate::relation_locations::RelationLocator;
+use crate::types::{Capsule, CapsuleGraph, CapsuleRelation, CapsuleType, RelationType};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// A capsule affected by the change
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImpactedCapsule {
+    pub name: String,
+    pub kind: CapsuleType,
+    /// `path:line` relative to the project root
+    pub location: String,
+    /// Number of dependency hops from the changed code (1 = direct dependent)
+    pub distance: usize,
+    /// Capsule one hop closer to the change that this one depends on
+    pub via: String,
+    /// Relation type of that dependency
+    pub relation: RelationType,
+}
+
+/// Transitive dependents of a file or capsule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactReport {
+    pub target: String,
+    /// `path:line name` of the capsules matched by the target
+    pub changed: Vec<String>,
+    /// Ordered by distance, then location
+    pub impacted: Vec<ImpactedCapsule>,
+    /// Number of distinct files among the impacted capsules
+    pub files: usize,
+    /// True if `max_depth` stopped the traversal before it ran out of dependents
+    pub truncated: bool,
+}
+
+impl ImpactReport {
+    /// Impacted capsules at exactly `distance` hops
+    pub fn at_distance(&self, distance: usize) -> impl Iterator<Item = &ImpactedCapsule> {
+        self.impacted.iter().filter(move |c| c.distance == distance)
+    }
+
+    pub fn max_distance(&self) -> usize {
+        self.impacted.iter().map(|c| c.distance).max().unwrap_or(0)
+    }
+
+    pub fn to_text(&self) -> String {
+        if self.changed.is_empty() {
+            return format!("Nothing in the graph matches '{}'.\n", self.target);
+        }
+        let mut out = format!(
+            "Changing {} ({} capsule(s)):\n",
+            self.target,
+            self.changed.len()
+        );
+        for changed in &self.changed {
+            out.push_str(&format!("  {}\n", changed));
+        }
+        if self.impacted.is_empty() {
+            out.push_str("No other components depend on it.\n");
+            return out;
+        }
+        out.push_str(&format!(
+            "Impacts {} component(s) in {} file(s):\n",
+            self.impacted.len(),
+            self.files
+        ));
+        for distance in 1..=self.max_distance() {
+            out.push_str(&format!("- distance {}:\n", distance));
+            for capsule in self.at_distance(distance) {
+                out.push_str(&format!(
+                    "  - {} ({:?}) {} — {:?} {}\n",
+                    capsule.name, capsule.kind, capsule.location, capsule.relation, capsule.via
+                ));
+            }
+        }
+        if self.truncated {
+            out.push_str("(stopped at the depth limit; more dependents exist)\n");
+        }
+        out
+    }
+}
+
+/// Reverse-reachability queries over the capsule graph
+pub struct ImpactAnalyzer<'a> {
+    graph: &'a CapsuleGraph,
+    root: PathBuf,
+    max_depth: Option<usize>,
+}
+
+impl<'a> ImpactAnalyzer<'a> {
+    pub fn new(graph: &'a CapsuleGraph, project_root: &Path) -> Self {
+        Self {
+            graph,
+            root: project_root.to_path_buf(),
+            max_depth: None,
+        }
+    }
+
+    /// Stop after `depth` hops (unlimited by default)
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Capsules of the changed file (relative or absolute path), otherwise the
+    /// capsules the name resolves to the way `archlens ask` resolves subjects
+    pub fn resolve(&self, target: &str) -> Vec<&'a Capsule> {
+        resolve_target(self.graph, &self.root, target)
+    }
+
+    /// All transitive dependents of `target` with their distance. Structural
+    /// relations (shared directory or layer) and containment do not propagate
+    /// a change and are not followed
+    pub fn analyze(&self, target: &str) -> ImpactReport {
+        let changed = self.resolve(target);
+        let start: BTreeSet<Uuid> = changed.iter().map(|c| c.id).collect();
+
+        let mut dependents: HashMap<Uuid, Vec<(Uuid, RelationType)>> = HashMap::new();
+        for relation in &self.graph.relations {
+            if relation.relation_type.is_structural()
+                || relation.relation_type == RelationType::Contains
+                || relation.from_id == relation.to_id
+            {
+                continue;
+            }
+            dependents
+                .entry(relation.to_id)
+                .or_default()
+                .push((relation.from_id, relation.relation_type));
+        }
+        for edges in dependents.values_mut() {
+            edges.sort_by_key(|(id, relation)| (self.order(id), *relation));
+            edges.dedup_by_key(|(id, _)| *id);
+        }
+
+        let mut reached: HashMap<Uuid, (usize, Uuid, RelationType)> = HashMap::new();
+        let mut queue: VecDeque<(Uuid, usize)> = start.iter().map(|id| (*id, 0)).collect();
+        let mut truncated = false;
+        while let Some((id, distance)) = queue.pop_front() {
+            for &(dependent, relation) in dependents.get(&id).map(Vec::as_slice).unwrap_or(&[]) {
+                if start.contains(&dependent) || reached.contains_key(&dependent) {
+                    continue;
+                }
+                if self.max_depth.is_some_and(|max| distance >= max) {
+                    truncated = true;
+                    break;
+                }
+                reached.insert(dependent, (distance + 1, id, relation));
+                queue.push_back((dependent, distance + 1));
+            }
+        }
+
+        let mut impacted: Vec<ImpactedCapsule> = reached
+            .iter()
+            .filter_map(|(id, &(distance, via, relation))| {
+                let capsule = self.graph.capsules.get(id)?;
+                Some(ImpactedCapsule {
+                    name: capsule.name.clone(),
+                    kind: capsule.capsule_type,
+                    location: self.location(capsule),
+                    distance,
+                    via: self
+                        .graph
+                        .capsules
+                        .get(&via)
+                        .map(|c| c.name.clone())
+                        .unwrap_or_default(),
+                    relation,
+                })
+            })
+            .collect();
+        impacted.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then_with(|| a.location.cmp(&b.location))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        let files = reached
+            .keys()
+            .filter_map(|id| self.graph.capsules.get(id))
+            .map(|c| &c.file_path)
+            .collect::<BTreeSet<_>>()
+            .len();
+
+        ImpactReport {
+            target: target.trim().to_string(),
+            changed: changed
+                .iter()
+                .map(|c| format!("{} {}", self.location(c), c.name))
+                .collect(),
+            impacted,
+            files,
+            truncated,
+        }
+    }
+
+    fn order(&self, id: &Uuid) -> Option<(PathBuf, usize, String)> {
+        order(self.graph, id)
+    }
+
+    fn location(&self, capsule: &Capsule) -> String {
+        location(&self.root, capsule)
+    }
+}
+
+/// One dependency of a path and where it is written
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PathEdge {
+    pub from: String,
+    pub to: String,
+    pub relation: RelationType,
+    /// `path:line` relative to the project root
+    pub location: Option<String>,
+}
+
+/// Chain of dependencies from a source capsule to a target capsule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DependencyPath {
+    pub edges: Vec<PathEdge>,
+}
+
+/// Shortest dependency paths between two files or capsules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathReport {
+    pub from: String,
+    pub to: String,
+    /// `path:line name` of the capsules matched by `from`
+    pub from_matches: Vec<String>,
+    /// `path:line name` of the capsules matched by `to`
+    pub to_matches: Vec<String>,
+    /// Number of hops of every path (0 when there is none)
+    pub length: usize,
+    pub paths: Vec<DependencyPath>,
+    /// True if more shortest paths exist than `max_paths`
+    pub truncated: bool,
+}
+
+impl PathReport {
+    pub fn to_text(&self) -> String {
+        for (subject, matches) in [
+            (&self.from, &self.from_matches),
+            (&self.to, &self.to_matches),
+        ] {
+            if matches.is_empty() {
+                return format!("Nothing in the graph matches '{}'.\n", subject);
+            }
+        }
+        if self.paths.is_empty() {
+            return format!(
+                "{} does not depend on {}: no dependency path.\n",
+                self.from, self.to
+            );
+        }
+        let mut out = format!(
+            "{} depends on {} via {} shortest path(s) of {} hop(s):\n",
+            self.from,
+            self.to,
+            self.paths.len(),
+            self.length
+        );
+        for (i, path) in self.paths.iter().enumerate() {
+            out.push_str(&format!("- path {}:\n", i + 1));
+            for edge in &path.edges {
+                out.push_str(&format!(
+                    "  - {} -> {} ({:?}) {}\n",
+                    edge.from,
+                    edge.to,
+                    edge.relation,
+                    edge.location.as_deref().unwrap_or("?")
+                ));
+            }
+        }
+        if self.truncated {
+            out.push_str("(more shortest paths exist)\n");
+        }
+        out
+    }
+}
+
+/// Shortest-path queries over the capsule graph
+pub struct PathFinder<'a> {
+    graph: &'a CapsuleGraph,
+    root: PathBuf,
+    max_paths: usize,
+}
+
+impl<'a> PathFinder<'a> {
+    /// Shortest paths listed by default
+    pub const DEFAULT_MAX_PATHS: usize = 5;
+
+    pub fn new(graph: &'a CapsuleGraph, project_root: &Path) -> Self {
+        Self {
+            graph,
+            root: project_root.to_path_buf(),
+            max_paths: Self::DEFAULT_MAX_PATHS,
+        }
+    }
+
+    /// List at most `max_paths` of the shortest paths
+    pub fn with_max_paths(mut self, max_paths: usize) -> Self {
+        self.max_paths = max_paths.max(1);
+        self
+    }
+
+    /// Shortest paths from any capsule matched by `from` to any capsule matched
+    /// by `to`; both resolve like the target of impact analysis. Follows the
+    /// same relations as impact analysis, in the dependency direction
+    pub fn find(&self, from: &str, to: &str) -> PathReport {
+        let sources = resolve_target(self.graph, &self.root, from);
+        let targets = resolve_target(self.graph, &self.root, to);
+        let start: BTreeSet<Uuid> = sources.iter().map(|c| c.id).collect();
+        let goal: BTreeSet<Uuid> = targets.iter().map(|c| c.id).collect();
+
+        let mut dependencies: HashMap<Uuid, Vec<&CapsuleRelation>> = HashMap::new();
+        for relation in &self.graph.relations {
+            if relation.relation_type.is_structural()
+                || relation.relation_type == RelationType::Contains
+                || relation.from_id == relation.to_id
+            {
+                continue;
+            }
+            dependencies
+                .entry(relation.from_id)
+                .or_default()
+                .push(relation);
+        }
+        for edges in dependencies.values_mut() {
+            edges.sort_by_key(|r| (order(self.graph, &r.to_id), r.relation_type));
+            edges.dedup_by_key(|r| r.to_id);
+        }
+
+        // breadth-first layers; every edge into the next layer is kept so all
+        // shortest paths can be walked back from the target
+        let mut distance: HashMap<Uuid, usize> = start.iter().map(|id| (*id, 0)).collect();
+        let mut incoming: HashMap<Uuid, Vec<&CapsuleRelation>> = HashMap::new();
+        let mut frontier: Vec<Uuid> = start.iter().copied().collect();
+        let mut reached: Vec<Uuid> = Vec::new();
+        let mut depth = 0;
+        while !frontier.is_empty() && reached.is_empty() {
+            depth += 1;
+            let mut next = Vec::new();
+            for id in &frontier {
+                for &relation in dependencies.get(id).map(Vec::as_slice).unwrap_or(&[]) {
+                    let to = relation.to_id;
+                    match distance.get(&to) {
+                        Some(&d) if d < depth => continue,
+                        Some(_) => {}
+                        None => {
+                            distance.insert(to, depth);
+                            next.push(to);
+                            if goal.contains(&to) {
+                                reached.push(to);
+                            }
+                        }
+                    }
+                    incoming.entry(to).or_default().push(relation);
+                }
+            }
+            frontier = next;
+        }
+        reached.sort_by_key(|id| order(self.graph, id));
+
+        let mut locator = RelationLocator::new(self.graph);
+        let mut paths = Vec::new();
+        let mut truncated = false;
+        for target in &reached {
+            let mut stack: Vec<Vec<&CapsuleRelation>> = vec![Vec::new()];
+            while let Some(chain) = stack.pop() {
+                let head = chain.last().map_or(*target, |r| r.from_id);
+                if start.contains(&head) && !chain.is_empty() {
+                    if paths.len() == self.max_paths {
+                        truncated = true;
+                        break;
+                    }
+                    let edges = chain
+                        .iter()
+                        .rev()
+                        .map(|relation| self.edge(relation, &mut locator))
+                        .collect();
+                    paths.push(DependencyPath { edges });
+                    continue;
+                }
+                let mut previous = incoming.get(&head).cloned().unwrap_or_default();
+                previous.sort_by_key(|r| std::cmp::Reverse(order(self.graph, &r.from_id)));
+                for relation in previous {
+                    let mut longer = chain.clone();
+                    longer.push(relation);
+                    stack.push(longer);
+                }
+            }
+            if truncated {
+                break;
+            }
+        }
+
+        let describe = |capsules: &[&Capsule]| {
+            capsules
+                .iter()
+                .map(|c| format!("{} {}", location(&self.root, c), c.name))
+                .collect()
+        };
+        PathReport {
+            from: from.trim().to_string(),
+            to: to.trim().to_string(),
+            from_matches: describe(&sources),
+            to_matches: describe(&targets),
+            length: if paths.is_empty() { 0 } else { depth },
+            paths,
+            truncated,
+        }
+    }
+
+    fn edge(&self, relation: &CapsuleRelation, locator: &mut RelationLocator) -> PathEdge {
+        let name = |id: &Uuid| {
+            self.graph
+                .capsules
+                .get(id)
+                .map(|c| c.name.clone())
+                .unwrap_or_default()
+        };
+        PathEdge {
+            from: name(&relation.from_id),
+            to: name(&relation.to_id),
+            relation: relation.relation_type,
+            location: locator
+                .locate(relation)
+                .map(|l| format!("{}:{}", relative(&self.root, &l.file), l.line)),
+        }
+    }
+}
+
+/// Capsules of a file (relative or absolute path), otherwise the capsules the
+/// name resolves to the way `archlens ask` resolves subjects
+fn resolve_target<'a>(graph: &'a CapsuleGraph, root: &Path, target: &str) -> Vec<&'a Capsule> {
+    let wanted = target.trim().trim_start_matches("./").replace('\\', "/");
+    let mut in_file: Vec<&Capsule> = graph
+        .capsules
+        .values()
+        .filter(|c| {
+            let path = c.file_path.to_string_lossy().replace('\\', "/");
+            !wanted.is_empty() && (path == wanted || relative(root, &c.file_path) == wanted)
+        })
+        .collect();
+    if in_file.is_empty() {
+        return GraphIndex::new(graph, root).resolve(target.trim());
+    }
+    in_file.sort_by_key(|c| (c.line_start, c.name.clone()));
+    in_file
+}
+
+fn order(graph: &CapsuleGraph, id: &Uuid) -> Option<(PathBuf, usize, String)> {
+    graph
+        .capsules
+        .get(id)
+        .map(|c| (c.file_path.clone(), c.line_start, c.name.clone()))
+}
+
+fn location(root: &Path, capsule: &Capsule) -> String {
+    format!(
+        "{}:{}",
+        relative(root, &capsule.file_path),
+        capsule.line_start
+    )
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
@@ -1,7 +1,10 @@
 // Relation analysis for capsule connections
+use crate::graph::{CallGraphAnalyzer, SymbolTable, TypedRelationAnalyzer};
+use crate::hierarchy::PARENT_KEY;
+use crate::parser_ast::{IMPLEMENTS_KEY, IMPL_FOR_KEY, IMPL_TRAIT_KEY};
 use crate::types::*;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// Analyzes relations between capsules
@@ -9,6 +12,9 @@ pub struct RelationAnalyzer {
     import_patterns: HashMap<FileType, Vec<Regex>>,
     export_patterns: HashMap<FileType, Vec<Regex>>,
     relation_strength_threshold: f32,
+    structural_relations: bool,
+    typed_relations: TypedRelationAnalyzer,
+    call_graph: CallGraphAnalyzer,
 }
 
 impl RelationAnalyzer {
@@ -17,9 +23,19 @@ impl RelationAnalyzer {
             import_patterns: Self::create_import_patterns(),
             export_patterns: Self::create_export_patterns(),
             relation_strength_threshold: 0.1,
+            structural_relations: true,
+            typed_relations: TypedRelationAnalyzer::new(),
+            call_graph: CallGraphAnalyzer::new(),
         }
     }
 
+    /// Skip relations through file structure and architectural layers. They
+    /// link nearly every pair of capsules, while cycles and levels ignore them
+    pub fn without_structural_relations(mut self) -> Self {
+        self.structural_relations = false;
+        self
+    }
+
     /// Create import patterns for different file types
     fn create_import_patterns() -> HashMap<FileType, Vec<Regex>> {
         let mut patterns = HashMap::new();
@@ -73,6 +89,21 @@ impl RelationAnalyzer {
             vec![Regex::new(r#"import\s+(?:\(\s*)?["']([^"']+)["']"#).unwrap()],
         );
 
+        // PHP imports
+        patterns.insert(
+            FileType::Php,
+            vec![
+                Regex::new(r"(?m)^\s*use\s+\\?([\w\\]+)").unwrap(),
+                Regex::new(r#"(?:require|include)(?:_once)?\s*\(?\s*['"]([^'"]+)['"]"#).unwrap(),
+            ],
+        );
+
+        // Ruby imports
+        patterns.insert(
+            FileType::Ruby,
+            vec![Regex::new(r#"require(?:_relative)?\s*\(?\s*['"]([^'"]+)['"]"#).unwrap()],
+        );
+
         patterns
     }
 
@@ -124,12 +155,25 @@ impl RelationAnalyzer {
             vec![Regex::new(r"(?:func|type|var|const)\s+([A-Z]\w*)").unwrap()],
         );
 
+        // PHP exports
+        patterns.insert(
+            FileType::Php,
+            vec![Regex::new(r"(?m)^(?:(?:final|abstract|readonly)\s+)*(?:class|interface|trait|enum|function)\s+(\w+)").unwrap()],
+        );
+
+        // Ruby exports
+        patterns.insert(
+            FileType::Ruby,
+            vec![Regex::new(r"(?m)^\s*(?:class|module)\s+([A-Z]\w*)").unwrap()],
+        );
+
         patterns
     }
 
     /// Build advanced relations between capsules
     pub fn build_advanced_relations(&self, capsules: &[Capsule]) -> Result<Vec<CapsuleRelation>> {
         let mut relations = Vec::new();
+        let symbols = SymbolTable::build(capsules);
 
         for capsule in capsules {
             // Relations through dependencies
@@ -146,7 +190,7 @@ impl RelationAnalyzer {
             }
 
             // Relations through file structure
-            for other_capsule in capsules {
+            for other_capsule in capsules.iter().filter(|_| self.structural_relations) {
                 if capsule.id != other_capsule.id {
                     if let Some(strength) =
                         self.calculate_file_relation_strength(capsule, other_capsule)
@@ -165,7 +209,7 @@ impl RelationAnalyzer {
             }
 
             // Relations through architectural layers
-            for other_capsule in capsules {
+            for other_capsule in capsules.iter().filter(|_| self.structural_relations) {
                 if capsule.id != other_capsule.id {
                     if let Some(strength) =
                         self.calculate_layer_relation_strength(capsule, other_capsule)
@@ -183,7 +227,11 @@ impl RelationAnalyzer {
                 }
             }
 
-            // Relations through semantic analysis
+            // Relations through import/export name matching, for languages
+            // the symbol table does not resolve
+            if SymbolTable::resolves(&capsule.file_path) {
+                continue;
+            }
             if let Ok(content) = std::fs::read_to_string(&capsule.file_path) {
                 if let Some(semantic_relations) =
                     self.analyze_semantic_relations(capsule, &content, capsules)
@@ -193,9 +241,129 @@ impl RelationAnalyzer {
             }
         }
 
+        // Imports resolved to the declaring capsules
+        relations.extend(symbols.import_relations());
+
+        // Relations through trait implementations
+        relations.extend(self.build_implementation_relations(capsules));
+
+        // Nesting: containers and their members
+        relations.extend(self.build_containment_relations(capsules));
+
+        // Typed relations: calls, inheritance, composition, state access, events
+        let typed = self.typed_relations.analyze(capsules);
+
+        // Method calls from semantic analysis: `recv.method()`, `Type::method()`
+        relations.extend(self.call_graph.analyze(capsules, &typed));
+        relations.extend(typed);
+
         Ok(relations)
     }
 
+    /// Connect implementors to their traits using `impl Trait for Type` metadata
+    /// recorded by the parser (on the type itself or on methods of the impl block)
+    fn build_implementation_relations(&self, capsules: &[Capsule]) -> Vec<CapsuleRelation> {
+        let mut pairs: Vec<(&Capsule, &str)> = Vec::new();
+        for capsule in capsules {
+            if let Some(traits) = capsule.metadata.get(IMPLEMENTS_KEY) {
+                pairs.extend(
+                    traits
+                        .split(',')
+                        .filter(|t| !t.is_empty())
+                        .map(|t| (capsule, t)),
+                );
+            }
+            if let (Some(trait_name), Some(type_name)) = (
+                capsule.metadata.get(IMPL_TRAIT_KEY),
+                capsule.metadata.get(IMPL_FOR_KEY),
+            ) {
+                let implementor = Self::find_by_name(capsules, type_name, capsule, |t| {
+                    matches!(
+                        t,
+                        CapsuleType::Struct | CapsuleType::Enum | CapsuleType::Class
+                    )
+                });
+                if let Some(implementor) = implementor {
+                    pairs.push((implementor, trait_name.as_str()));
+                }
+            }
+        }
+
+        let mut seen: HashSet<(Uuid, Uuid)> = HashSet::new();
+        let mut relations = Vec::new();
+        for (implementor, trait_name) in pairs {
+            let Some(interface) = Self::find_by_name(capsules, trait_name, implementor, |t| {
+                matches!(t, CapsuleType::Interface)
+            }) else {
+                continue;
+            };
+            if interface.id == implementor.id || !seen.insert((implementor.id, interface.id)) {
+                continue;
+            }
+            relations.push(CapsuleRelation {
+                from_id: implementor.id,
+                to_id: interface.id,
+                relation_type: RelationType::Implements,
+                strength: 0.9,
+                description: Some(format!("impl {} for {}", trait_name, implementor.name)),
+            });
+        }
+        relations
+    }
+
+    /// Connect containers to nested declarations: the parent recorded by the
+    /// constructor or, for methods of a Rust `impl` block, the implemented type
+    fn build_containment_relations(&self, capsules: &[Capsule]) -> Vec<CapsuleRelation> {
+        let ids: HashSet<Uuid> = capsules.iter().map(|c| c.id).collect();
+        let mut relations = Vec::new();
+        for capsule in capsules {
+            let parent = capsule
+                .metadata
+                .get(PARENT_KEY)
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .filter(|id| ids.contains(id) && *id != capsule.id)
+                .or_else(|| {
+                    let type_name = capsule.metadata.get(IMPL_FOR_KEY)?;
+                    Self::find_by_name(capsules, type_name, capsule, |t| {
+                        matches!(
+                            t,
+                            CapsuleType::Struct | CapsuleType::Enum | CapsuleType::Class
+                        )
+                    })
+                    .filter(|owner| owner.file_path == capsule.file_path && owner.id != capsule.id)
+                    .map(|owner| owner.id)
+                });
+            if let Some(parent) = parent {
+                relations.push(CapsuleRelation {
+                    from_id: parent,
+                    to_id: capsule.id,
+                    relation_type: RelationType::Contains,
+                    strength: 1.0,
+                    description: Some(format!("Contains {}", capsule.name)),
+                });
+            }
+        }
+        relations
+    }
+
+    /// Capsule of matching kind by name, preferring the file of `near`
+    fn find_by_name<'a>(
+        capsules: &'a [Capsule],
+        name: &str,
+        near: &Capsule,
+        kind: impl Fn(&CapsuleType) -> bool,
+    ) -> Option<&'a Capsule> {
+        capsules
+            .iter()
+            .filter(|c| c.name == name && kind(&c.capsule_type))
+            .min_by(|a, b| {
+                (a.file_path != near.file_path)
+                    .cmp(&(b.file_path != near.file_path))
+                    .then_with(|| a.file_path.cmp(&b.file_path))
+                    .then(a.line_start.cmp(&b.line_start))
+            })
+    }
+
     /// Calculate relation strength based on file structure
     fn calculate_file_relation_strength(
         &self,
@@ -274,7 +442,7 @@ impl RelationAnalyzer {
                     relations.push(CapsuleRelation {
                         from_id: capsule.id,
                         to_id: other_capsule.id,
-                        relation_type: RelationType::Uses,
+                        relation_type: RelationType::Imports,
                         strength,
                         description: Some("Semantic import-export relation".to_string()),
                     });
@@ -389,6 +557,8 @@ impl RelationAnalyzer {
             Some("go") => FileType::Go,
             Some("cpp") | Some("cc") | Some("cxx") => FileType::Cpp,
             Some("c") => FileType::C,
+            Some("php") => FileType::Php,
+            Some("rb") => FileType::Ruby,
             Some(ext) => FileType::Other(ext.to_string()),
             None => FileType::Other("unknown".to_string()),
         }
@@ -402,17 +572,30 @@ impl RelationAnalyzer {
     ) -> Result<HashMap<Uuid, Capsule>> {
         let mut updated_capsules = capsules.clone();
 
+        // Already linked pairs; scanning the vectors is quadratic on dense graphs
+        let mut dependencies: HashSet<(Uuid, Uuid)> = HashSet::new();
+        let mut dependents: HashSet<(Uuid, Uuid)> = HashSet::new();
+        for capsule in updated_capsules.values() {
+            dependencies.extend(capsule.dependencies.iter().map(|dep| (capsule.id, *dep)));
+            dependents.extend(capsule.dependents.iter().map(|dep| (capsule.id, *dep)));
+        }
+
         for relation in relations {
+            // Nesting is not a dependency between container and member
+            if relation.relation_type == RelationType::Contains {
+                continue;
+            }
+
             // Update dependencies
             if let Some(from_capsule) = updated_capsules.get_mut(&relation.from_id) {
-                if !from_capsule.dependencies.contains(&relation.to_id) {
+                if dependencies.insert((relation.from_id, relation.to_id)) {
                     from_capsule.dependencies.push(relation.to_id);
                 }
             }
 
             // Update dependents
             if let Some(to_capsule) = updated_capsules.get_mut(&relation.to_id) {
-                if !to_capsule.dependents.contains(&relation.from_id) {
+                if dependents.insert((relation.to_id, relation.from_id)) {
                     to_capsule.dependents.push(relation.from_id);
                 }
             }
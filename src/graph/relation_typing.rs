@@ -0,0 +1,605 @@
+// Typed relation analysis: calls, inheritance, composition, state access and
+// events, detected in capsule source snippets so every language handled by the
+// regex parser gets the same treatment
+use crate::hierarchy::PARENT_KEY;
+use crate::parser_ast::IMPL_FOR_KEY;
+use crate::types::*;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Upper bound for a capsule snippet when its end line is unknown
+const MAX_SNIPPET_LINES: usize = 400;
+
+/// Call names that publish an event passed as the first argument
+const EMIT_VERBS: [&str; 8] = [
+    "emit",
+    "publish",
+    "dispatch",
+    "send",
+    "broadcast",
+    "notify",
+    "trigger",
+    "fire",
+];
+
+/// Receivers that refer to the enclosing type
+const SELF_RECEIVERS: [&str; 3] = ["self", "this", "Self"];
+
+/// Methods that mutate the receiver when called on shared state
+const WRITE_METHODS: [&str; 14] = [
+    "push",
+    "insert",
+    "remove",
+    "clear",
+    "extend",
+    "append",
+    "set",
+    "store",
+    "write",
+    "lock",
+    "borrow_mut",
+    "fetch_add",
+    "fetch_sub",
+    "swap",
+];
+
+/// Source text of a capsule split into its declaration and body
+pub(super) struct Snippet {
+    pub(super) signature: String,
+    pub(super) body: String,
+}
+
+/// Finds typed relations between capsules
+pub struct TypedRelationAnalyzer {
+    identifier: Regex,
+    python_bases: Regex,
+    class_extends: Regex,
+    class_implements: Regex,
+    class_colon_bases: Regex,
+    rust_supertraits: Regex,
+    interface_extends: Regex,
+}
+
+impl TypedRelationAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            identifier: Regex::new(r"[A-Za-z_]\w*").unwrap(),
+            python_bases: Regex::new(r"^\s*class\s+\w+\s*\(([^)]*)\)\s*:").unwrap(),
+            class_extends: Regex::new(r"\bclass\s+\w+(?:<[^>]*>)?\s+extends\s+([\w.$]+)").unwrap(),
+            class_implements: Regex::new(r"\bimplements\s+([\w\s,.<>]+)").unwrap(),
+            class_colon_bases: Regex::new(r"\bclass\s+\w+\s*:\s*([^{]+)").unwrap(),
+            rust_supertraits: Regex::new(r"\btrait\s+\w+(?:<[^>]*>)?\s*:\s*([^{]+)").unwrap(),
+            interface_extends: Regex::new(r"\binterface\s+\w+(?:<[^>]*>)?\s+extends\s+([^{]+)")
+                .unwrap(),
+        }
+    }
+
+    /// Typed relations for all capsules; at most one relation per ordered pair
+    pub fn analyze(&self, capsules: &[Capsule]) -> Vec<CapsuleRelation> {
+        let mut by_name: HashMap<&str, Vec<&Capsule>> = HashMap::new();
+        for capsule in capsules {
+            by_name
+                .entry(capsule.name.as_str())
+                .or_default()
+                .push(capsule);
+        }
+        let by_id: HashMap<Uuid, &Capsule> = capsules.iter().map(|c| (c.id, c)).collect();
+
+        let mut files: HashMap<&Path, Vec<String>> = HashMap::new();
+        let mut seen: HashSet<(Uuid, Uuid)> = HashSet::new();
+        let mut relations = Vec::new();
+        for source in capsules {
+            if !is_source_kind(&source.capsule_type) {
+                continue;
+            }
+            let lines = files.entry(source.file_path.as_path()).or_insert_with(|| {
+                std::fs::read_to_string(&source.file_path)
+                    .map(|text| text.lines().map(str::to_string).collect())
+                    .unwrap_or_default()
+            });
+            let Some(snippet) = snippet(lines, source) else {
+                continue;
+            };
+
+            let mut push = |target: &Capsule, relation_type: RelationType, description: String| {
+                if target.id != source.id && seen.insert((source.id, target.id)) {
+                    relations.push(CapsuleRelation {
+                        from_id: source.id,
+                        to_id: target.id,
+                        relation_type,
+                        strength: strength(relation_type),
+                        description: Some(description),
+                    });
+                }
+            };
+
+            let declares_supertypes = matches!(
+                source.capsule_type,
+                CapsuleType::Class | CapsuleType::Struct | CapsuleType::Interface
+            );
+            let supertypes = if declares_supertypes {
+                self.declared_supertypes(&snippet.signature)
+            } else {
+                Vec::new()
+            };
+            for (name, relation_type, kind) in supertypes {
+                if let Some(target) = resolve(&by_name, &name, source, kind) {
+                    let verb = relation_type.as_str();
+                    push(
+                        target,
+                        relation_type,
+                        format!("{} {verb} {name}", source.name),
+                    );
+                }
+            }
+
+            // handlers name their event in the signature, everything else is in the body
+            let tokens: BTreeSet<&str> = self
+                .identifier
+                .find_iter(&snippet.signature)
+                .chain(self.identifier.find_iter(&snippet.body))
+                .map(|m| m.as_str())
+                .filter(|t| *t != source.name && by_name.contains_key(t))
+                .collect();
+            for token in tokens {
+                if let Some((target, relation_type, description)) =
+                    classify_use(source, &snippet, token, &by_name, &by_id)
+                {
+                    push(target, relation_type, description);
+                }
+            }
+        }
+        relations
+    }
+
+    /// Base classes, implemented interfaces and supertraits from a declaration,
+    /// with the capsule kinds each name may resolve to
+    fn declared_supertypes(&self, signature: &str) -> Vec<(String, RelationType, KindFilter)> {
+        let mut found = Vec::new();
+        let bases = self
+            .python_bases
+            .captures(signature)
+            .or_else(|| self.class_extends.captures(signature))
+            .or_else(|| self.class_colon_bases.captures(signature));
+        if let Some(caps) = bases {
+            // a base may be a class or an interface; the resolved capsule decides
+            for base in split_names(&caps[1], ',') {
+                found.push((base.clone(), RelationType::Inherits, is_class as KindFilter));
+                found.push((base, RelationType::Implements, is_interface));
+            }
+        }
+        if let Some(caps) = self.class_implements.captures(signature) {
+            found.extend(split_names(&caps[1], ',').map(|name| {
+                (
+                    name,
+                    RelationType::Implements,
+                    is_interface_like as KindFilter,
+                )
+            }));
+        }
+        if let Some(caps) = self.rust_supertraits.captures(signature) {
+            let bounds = caps[1].split(" where ").next().unwrap_or_default();
+            found.extend(
+                split_names(bounds, '+')
+                    .map(|name| (name, RelationType::Extends, is_interface as KindFilter)),
+            );
+        } else if let Some(caps) = self.interface_extends.captures(signature) {
+            found.extend(
+                split_names(&caps[1], ',')
+                    .map(|name| (name, RelationType::Extends, is_interface_like as KindFilter)),
+            );
+        }
+        found
+    }
+}
+
+impl Default for TypedRelationAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+type KindFilter = fn(&CapsuleType) -> bool;
+
+fn is_class(kind: &CapsuleType) -> bool {
+    matches!(kind, CapsuleType::Class | CapsuleType::Struct)
+}
+
+fn is_interface(kind: &CapsuleType) -> bool {
+    matches!(kind, CapsuleType::Interface)
+}
+
+/// TypeScript interfaces come out of the regex parser as structs
+fn is_interface_like(kind: &CapsuleType) -> bool {
+    matches!(kind, CapsuleType::Interface | CapsuleType::Struct)
+}
+
+fn is_source_kind(kind: &CapsuleType) -> bool {
+    matches!(
+        kind,
+        CapsuleType::Function
+            | CapsuleType::Method
+            | CapsuleType::Class
+            | CapsuleType::Struct
+            | CapsuleType::Enum
+            | CapsuleType::Interface
+    )
+}
+
+fn strength(relation_type: RelationType) -> f32 {
+    match relation_type {
+        RelationType::Inherits | RelationType::Implements | RelationType::Extends => 0.9,
+        RelationType::Composes | RelationType::Handles => 0.85,
+        RelationType::Emits | RelationType::Writes => 0.8,
+        RelationType::Calls => 0.75,
+        _ => 0.7,
+    }
+}
+
+/// Capsule of a matching kind by name: the only candidate in the same file,
+/// otherwise the only candidate in the project (ambiguous names give no relation)
+pub(super) fn resolve<'a>(
+    by_name: &HashMap<&str, Vec<&'a Capsule>>,
+    name: &str,
+    near: &Capsule,
+    kind: impl Fn(&CapsuleType) -> bool,
+) -> Option<&'a Capsule> {
+    resolve_where(by_name, name, near, |c| kind(&c.capsule_type))
+}
+
+/// [`resolve`] with an arbitrary filter on the candidates
+fn resolve_where<'a>(
+    by_name: &HashMap<&str, Vec<&'a Capsule>>,
+    name: &str,
+    near: &Capsule,
+    accept: impl Fn(&Capsule) -> bool,
+) -> Option<&'a Capsule> {
+    let candidates: Vec<&Capsule> = by_name
+        .get(name)?
+        .iter()
+        .copied()
+        .filter(|c| c.id != near.id && accept(c))
+        .collect();
+    let local: Vec<&Capsule> = candidates
+        .iter()
+        .copied()
+        .filter(|c| c.file_path == near.file_path)
+        .collect();
+    match (local.as_slice(), candidates.as_slice()) {
+        ([only], _) => Some(*only),
+        ([], [only]) => Some(*only),
+        _ => None,
+    }
+}
+
+/// Type a method belongs to: the `impl` block type or the enclosing class
+pub(super) fn owner<'a>(
+    capsule: &'a Capsule,
+    by_id: &HashMap<Uuid, &'a Capsule>,
+) -> Option<&'a str> {
+    if let Some(type_name) = capsule.metadata.get(IMPL_FOR_KEY) {
+        return Some(type_name.as_str());
+    }
+    let parent = Uuid::parse_str(capsule.metadata.get(PARENT_KEY)?).ok()?;
+    by_id.get(&parent).map(|p| p.name.as_str())
+}
+
+/// Rust module a file defines: its stem, or the directory for `mod.rs`
+pub(super) fn module_name(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    if stem == "mod" {
+        return path.parent()?.file_name()?.to_str();
+    }
+    Some(stem)
+}
+
+/// Callee of a call to `name` from `source`, honoring the call's qualifier:
+/// - `name()` — a free function or a method of the caller's own type;
+/// - `self.name()` / `this.name()` / `Self::name()` — a method of the caller's type;
+/// - `Type::name()` / `Type.name()` — a method of `Type`;
+/// - `module::name()` / `module.name()` — a free function of that module.
+///
+/// Calls on other receivers (`value.name()`) are left to the call graph
+fn resolve_call<'a>(
+    by_name: &HashMap<&str, Vec<&'a Capsule>>,
+    by_id: &HashMap<Uuid, &'a Capsule>,
+    name: &str,
+    source: &Capsule,
+    qualifier: Option<&str>,
+) -> Option<&'a Capsule> {
+    let callable =
+        |c: &Capsule| matches!(c.capsule_type, CapsuleType::Function | CapsuleType::Method);
+    let own_type = owner(source, by_id);
+    match qualifier {
+        None => resolve_where(by_name, name, source, |c| {
+            callable(c) && owner(c, by_id).is_none_or(|o| Some(o) == own_type)
+        }),
+        Some(receiver) if SELF_RECEIVERS.contains(&receiver) => {
+            let own_type = own_type?;
+            resolve_where(by_name, name, source, |c| {
+                callable(c) && owner(c, by_id) == Some(own_type)
+            })
+        }
+        Some(receiver) if receiver.starts_with(char::is_uppercase) => {
+            resolve_where(by_name, name, source, |c| {
+                callable(c) && owner(c, by_id) == Some(receiver)
+            })
+        }
+        Some(module) => resolve_where(by_name, name, source, |c| {
+            callable(c) && owner(c, by_id).is_none() && module_name(&c.file_path) == Some(module)
+        }),
+    }
+}
+
+/// How `source` uses the capsule named `token`, in order of specificity
+fn classify_use<'a>(
+    source: &Capsule,
+    snippet: &Snippet,
+    token: &str,
+    by_name: &HashMap<&str, Vec<&'a Capsule>>,
+    by_id: &HashMap<Uuid, &'a Capsule>,
+) -> Option<(&'a Capsule, RelationType, String)> {
+    let body = snippet.body.as_str();
+    let is_type = |t: &CapsuleType| {
+        matches!(
+            t,
+            CapsuleType::Class | CapsuleType::Struct | CapsuleType::Enum
+        )
+    };
+
+    if let Some(target) = resolve(by_name, token, source, is_type) {
+        let callable = matches!(
+            source.capsule_type,
+            CapsuleType::Function | CapsuleType::Method
+        );
+        if callable && (is_handler_name(&source.name) && mentions(&snippet.signature, token)) {
+            return Some((target, RelationType::Handles, format!("handles {token}")));
+        }
+        if mentions(&snippet.signature, &format!("Handler<{token}")) {
+            return Some((target, RelationType::Handles, format!("handles {token}")));
+        }
+        if let Some(verb) = word_positions(body, token).find_map(|pos| emit_verb(body, pos)) {
+            return Some((target, RelationType::Emits, format!("{verb}({token})")));
+        }
+        if matches!(
+            source.capsule_type,
+            CapsuleType::Class | CapsuleType::Struct
+        ) && word_positions(body, token).any(|pos| is_field_type(body, pos))
+        {
+            return Some((
+                target,
+                RelationType::Composes,
+                format!("field of type {token}"),
+            ));
+        }
+    }
+
+    let callee = word_positions(body, token)
+        .filter_map(|pos| call_qualifier(body, pos, token))
+        .find_map(|(qualifier, callee)| {
+            resolve_call(by_name, by_id, token, source, qualifier).map(|t| (t, callee))
+        });
+    if let Some((target, callee)) = callee {
+        return Some((target, RelationType::Calls, format!("calls {callee}()")));
+    }
+
+    if let Some(target) = resolve(by_name, token, source, |t| {
+        matches!(t, CapsuleType::Constant | CapsuleType::Variable)
+    }) {
+        let writes = target.capsule_type == CapsuleType::Variable
+            && word_positions(body, token).any(|pos| is_write(body, pos + token.len()));
+        return Some(if writes {
+            (target, RelationType::Writes, format!("writes {token}"))
+        } else {
+            (target, RelationType::Reads, format!("reads {token}"))
+        });
+    }
+    None
+}
+
+/// Snippet of a capsule: its line range, or — when the parser only knows the
+/// first line — up to the matching brace or the end of the indented block
+pub(super) fn snippet(lines: &[String], capsule: &Capsule) -> Option<Snippet> {
+    let start = capsule.line_start.checked_sub(1)?;
+    let first = lines.get(start)?;
+    let end = if capsule.line_end > capsule.line_start {
+        capsule.line_end.min(lines.len())
+    } else if first.trim_end().ends_with(':') {
+        let indent = indentation(first);
+        let mut end = start + 1;
+        while end < lines.len()
+            && end - start < MAX_SNIPPET_LINES
+            && (lines[end].trim().is_empty() || indentation(&lines[end]) > indent)
+        {
+            end += 1;
+        }
+        end
+    } else {
+        brace_block_end(lines, start)
+    };
+
+    let text = lines[start..end].join("\n");
+    let split = if first.trim_end().ends_with(':') {
+        first.len()
+    } else {
+        text.find('{').map_or(text.len(), |i| i + 1)
+    };
+    Some(Snippet {
+        signature: text[..split].to_string(),
+        body: text[split..].to_string(),
+    })
+}
+
+/// Line after the brace that closes the block opened at or after `start`
+fn brace_block_end(lines: &[String], start: usize) -> usize {
+    let limit = (start + MAX_SNIPPET_LINES).min(lines.len());
+    let mut depth = 0i32;
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().take(limit).skip(start) {
+        for ch in line.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        if opened && depth <= 0 {
+            return i + 1;
+        }
+        if !opened && line.trim_end().ends_with(';') {
+            return i + 1;
+        }
+    }
+    if opened {
+        limit
+    } else {
+        start + 1
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte offsets of whole-word occurrences of `name`
+fn word_positions<'a>(text: &'a str, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+    text.match_indices(name)
+        .filter(move |(i, _)| {
+            let before = text[..*i].chars().next_back();
+            let after = text[i + name.len()..].chars().next();
+            !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+        })
+        .map(|(i, _)| i)
+}
+
+pub(super) fn mentions(text: &str, name: &str) -> bool {
+    if name.ends_with(is_ident_char) {
+        word_positions(text, name).next().is_some()
+    } else {
+        text.contains(name)
+    }
+}
+
+/// `handle_*`, `handleX`, `on_*`, `onX`
+fn is_handler_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    if lower.starts_with("handle") || lower.starts_with("on_") {
+        return true;
+    }
+    name.strip_prefix("on")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(char::is_uppercase)
+}
+
+/// Text of the line before `pos`
+fn line_prefix(text: &str, pos: usize) -> &str {
+    let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    &text[start..pos]
+}
+
+/// Emitting call whose first argument is the value at `pos`: `emit(Event {..})`,
+/// `bus.publish(&Event::new())`, `dispatch(new Event())`
+fn emit_verb(text: &str, pos: usize) -> Option<&'static str> {
+    let prefix = line_prefix(text, pos);
+    let open = prefix.rfind('(')?;
+    let argument = prefix[open + 1..].trim().trim_start_matches('&');
+    let argument = argument.strip_prefix("new ").unwrap_or(argument).trim();
+    let path_only = argument
+        .chars()
+        .all(|c| is_ident_char(c) || matches!(c, ':' | '.'));
+    if !path_only {
+        return None;
+    }
+    let callee = prefix[..open].trim_end();
+    let callee_start = callee
+        .rfind(|c: char| !is_ident_char(c))
+        .map_or(0, |i| i + 1);
+    let callee = callee[callee_start..].to_lowercase();
+    EMIT_VERBS.into_iter().find(|verb| callee.starts_with(verb))
+}
+
+/// Field declaration typed with the value at `pos`: `items: Vec<Item>`,
+/// `self.repo = Repo()`, but not parameters or locals in method signatures
+fn is_field_type(text: &str, pos: usize) -> bool {
+    let prefix = line_prefix(text, pos);
+    let declaration = prefix.contains(':') || prefix.contains('=');
+    let in_signature = prefix.contains('(')
+        || ["fn ", "def ", "function "]
+            .iter()
+            .any(|keyword| prefix.contains(keyword));
+    declaration && !in_signature
+}
+
+/// Call of `name` at `pos` that is not its own definition: its qualifier (`q` of
+/// `q::name(`, `q.name(`, `q->name(`; none for a bare call) and the callee text.
+/// Calls on expressions (`make().name(`) have no usable qualifier and give `None`
+fn call_qualifier<'t>(text: &'t str, pos: usize, name: &str) -> Option<(Option<&'t str>, &'t str)> {
+    let end = pos + name.len();
+    if !text[end..].trim_start().starts_with('(') {
+        return None;
+    }
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = text[line_start..pos].trim_end();
+    let Some(path) = prefix
+        .strip_suffix("::")
+        .or_else(|| prefix.strip_suffix('.'))
+        .or_else(|| prefix.strip_suffix("->"))
+    else {
+        let definition = ["fn", "def", "function", "func"]
+            .iter()
+            .any(|keyword| prefix.ends_with(keyword));
+        return (!definition).then_some((None, &text[pos..end]));
+    };
+    // `Type<T>::name(` and `module::Type::name(` are qualified by `Type`
+    let path = path.trim_end();
+    let path = match path.strip_suffix('>') {
+        Some(generic) => &generic[..generic.find('<')?],
+        None => path,
+    };
+    let start = path.rfind(|c: char| !is_ident_char(c)).map_or(0, |i| i + 1);
+    let qualifier = &path[start..];
+    (!qualifier.is_empty() && !qualifier.starts_with(|c: char| c.is_ascii_digit()))
+        .then_some((Some(qualifier), &text[line_start + start..end]))
+}
+
+/// Assignment or mutating method call right after a name ending at `end`
+fn is_write(text: &str, end: usize) -> bool {
+    let rest = text[end..].trim_start();
+    if let Some(method) = rest.strip_prefix('.') {
+        let method: String = method.chars().take_while(|c| is_ident_char(*c)).collect();
+        return WRITE_METHODS.contains(&method.as_str());
+    }
+    let op_len = rest
+        .find(|c: char| !matches!(c, '+' | '-' | '*' | '/' | '%' | '|' | '&' | '^'))
+        .unwrap_or(rest.len());
+    let after_op = &rest[op_len..];
+    op_len <= 2 && after_op.starts_with('=') && !after_op[1..].starts_with(['=', '>'])
+}
+
+/// Names from a list of bases/bounds: generics, paths and modifiers stripped
+fn split_names(list: &str, separator: char) -> impl Iterator<Item = String> + '_ {
+    list.split(separator).filter_map(|item| {
+        let item = item
+            .split_whitespace()
+            .filter(|w| !matches!(*w, "public" | "protected" | "private" | "virtual"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let item = item.split(['<', '(', '[', '=']).next().unwrap_or_default();
+        let name = item
+            .rsplit(['.', ':'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_start_matches('?');
+        (!name.is_empty() && name.chars().all(is_ident_char)).then(|| name.to_string())
+    })
+}
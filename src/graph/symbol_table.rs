@@ -0,0 +1,718 @@
+// Project symbol table: resolves imports (Rust `use` paths, relative JS/TS
+// imports, Python modules) to the capsules they name, so import edges connect
+// the capsules that actually use an imported symbol with its declaration
+use crate::hierarchy::PARENT_KEY;
+use crate::parser_ast::IMPL_FOR_KEY;
+use crate::types::*;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Upper bound for a capsule span when its end line is unknown
+const MAX_SPAN_LINES: usize = 400;
+/// How many re-exports (`pub use`, `export ... from`, `__init__.py`) to follow
+const MAX_REEXPORT_DEPTH: usize = 4;
+/// Extensions tried for extensionless relative JS/TS imports
+const SCRIPT_EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Rust,
+    Script,
+    Python,
+}
+
+impl Language {
+    fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            ext if SCRIPT_EXTENSIONS.contains(&ext) => Some(Self::Script),
+            _ => None,
+        }
+    }
+}
+
+/// What an imported name refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ImportTarget {
+    /// A declaration in another file (`default` for a JS default export)
+    Symbol { file: PathBuf, name: String },
+    /// A whole module used through qualified names (`module::Item`, `module.item`)
+    Module { file: PathBuf },
+    /// Every top-level declaration of a module (`use x::*`, `from x import *`)
+    Glob { file: PathBuf },
+}
+
+#[derive(Debug, Clone)]
+struct Import {
+    /// Name the import binds in the importing file
+    local: String,
+    target: ImportTarget,
+}
+
+/// Import-relevant facts about one source file
+#[derive(Default)]
+struct SourceFile {
+    lines: Vec<String>,
+    imports: Vec<Import>,
+    /// Lines (1-based) taken by import statements
+    import_lines: HashSet<usize>,
+}
+
+/// Rust crates of the project: module paths per crate root and crate names
+#[derive(Default)]
+struct RustCrates {
+    modules: HashMap<(PathBuf, Vec<String>), PathBuf>,
+    names: HashMap<String, PathBuf>,
+}
+
+/// Resolves imports of Rust, JS/TS and Python files to capsules
+pub struct SymbolTable<'a> {
+    capsules: &'a [Capsule],
+    by_file: BTreeMap<PathBuf, Vec<usize>>,
+    files: HashMap<PathBuf, SourceFile>,
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Whether imports of this file are resolved by the table
+    pub fn resolves(path: &Path) -> bool {
+        Language::of(path).is_some()
+    }
+
+    /// Reads every supported file once and resolves its imports
+    pub fn build(capsules: &'a [Capsule]) -> Self {
+        let mut by_file: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+        for (i, capsule) in capsules.iter().enumerate() {
+            if Self::resolves(&capsule.file_path) {
+                by_file
+                    .entry(capsule.file_path.clone())
+                    .or_default()
+                    .push(i);
+            }
+        }
+        let mut table = Self {
+            capsules,
+            by_file,
+            files: HashMap::new(),
+        };
+
+        let sources: Vec<(PathBuf, String)> = table
+            .by_file
+            .keys()
+            .filter_map(|f| Some((f.clone(), std::fs::read_to_string(f).ok()?)))
+            .collect();
+        let crates = table.rust_crates();
+        for (file, source) in sources {
+            let mut parsed = SourceFile {
+                lines: source.lines().map(str::to_string).collect(),
+                ..SourceFile::default()
+            };
+            match Language::of(&file) {
+                Some(Language::Rust) => table.rust_imports(&crates, &file, &source, &mut parsed),
+                Some(Language::Script) => table.script_imports(&file, &source, &mut parsed),
+                Some(Language::Python) => table.python_imports(&file, &source, &mut parsed),
+                None => {}
+            }
+            table.files.insert(file, parsed);
+        }
+        table
+    }
+
+    /// `Imports` relations from the capsules that use an imported name to the
+    /// capsules declaring it; at most one relation per ordered pair
+    pub fn import_relations(&self) -> Vec<CapsuleRelation> {
+        let mut seen: HashSet<(Uuid, Uuid)> = HashSet::new();
+        let mut relations = Vec::new();
+        let mut qualified: HashMap<&str, Regex> = HashMap::new();
+        for (file, indices) in &self.by_file {
+            let Some(source) = self.files.get(file).filter(|s| !s.imports.is_empty()) else {
+                continue;
+            };
+            let spans: Vec<(usize, usize)> =
+                indices.iter().map(|&i| self.span(i, indices)).collect();
+            for (position, &from) in indices.iter().enumerate() {
+                let text = own_text(spans[position], &spans, source);
+                let mut targets: Vec<usize> = Vec::new();
+                for import in &source.imports {
+                    match &import.target {
+                        ImportTarget::Symbol { file, name } => {
+                            if mentions(&text, &import.local) {
+                                targets.extend(self.declared(file, name, 0));
+                            }
+                        }
+                        ImportTarget::Module { file } => {
+                            let pattern =
+                                qualified.entry(import.local.as_str()).or_insert_with(|| {
+                                    Regex::new(&format!(
+                                        r"(?:^|[^\w.]){}(?:\.|::)(\w+)",
+                                        regex::escape(&import.local)
+                                    ))
+                                    .unwrap()
+                                });
+                            for cap in pattern.captures_iter(&text) {
+                                targets.extend(self.declared(file, &cap[1], 0));
+                            }
+                        }
+                        ImportTarget::Glob { file } => {
+                            targets.extend(
+                                self.top_level(file)
+                                    .filter(|&i| mentions(&text, &self.capsules[i].name)),
+                            );
+                        }
+                    }
+                }
+                let source_capsule = &self.capsules[from];
+                for to in targets {
+                    let target = &self.capsules[to];
+                    if target.file_path == source_capsule.file_path
+                        || !seen.insert((source_capsule.id, target.id))
+                    {
+                        continue;
+                    }
+                    relations.push(CapsuleRelation {
+                        from_id: source_capsule.id,
+                        to_id: target.id,
+                        relation_type: RelationType::Imports,
+                        strength: 0.9,
+                        description: Some(format!("imports {}", target.name)),
+                    });
+                }
+            }
+        }
+        relations
+    }
+
+    /// Lines of a capsule; an unknown end runs to the next capsule of the file
+    fn span(&self, index: usize, siblings: &[usize]) -> (usize, usize) {
+        let capsule = &self.capsules[index];
+        let start = capsule.line_start.max(1);
+        if capsule.line_end > capsule.line_start {
+            return (start, capsule.line_end);
+        }
+        let next = siblings
+            .iter()
+            .map(|&j| self.capsules[j].line_start)
+            .filter(|&s| s > start)
+            .min()
+            .map_or(start + MAX_SPAN_LINES, |s| s - 1);
+        (start, next.min(start + MAX_SPAN_LINES))
+    }
+
+    /// Top-level declarations of a file: no parent capsule and no `impl` owner
+    fn top_level(&self, file: &Path) -> impl Iterator<Item = usize> + '_ {
+        self.by_file
+            .get(file)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&i| {
+                let metadata = &self.capsules[i].metadata;
+                !metadata.contains_key(PARENT_KEY) && !metadata.contains_key(IMPL_FOR_KEY)
+            })
+    }
+
+    /// Capsules declaring `name` in a file, following re-exports
+    fn declared(&self, file: &Path, name: &str, depth: usize) -> Vec<usize> {
+        if depth > MAX_REEXPORT_DEPTH {
+            return Vec::new();
+        }
+        let found: Vec<usize> = if name == "default" {
+            let lines = self
+                .files
+                .get(file)
+                .map(|s| s.lines.as_slice())
+                .unwrap_or_default();
+            // `export default Name;` after the declaration
+            if let Some(exported) = lines.iter().find_map(|l| {
+                let name = l
+                    .trim()
+                    .strip_prefix("export default ")?
+                    .trim_end_matches(';');
+                name.chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+                    .then_some(name)
+            }) {
+                return self.declared(file, exported, depth + 1);
+            }
+            self.top_level(file)
+                .filter(|&i| {
+                    lines
+                        .get(self.capsules[i].line_start.saturating_sub(1))
+                        .is_some_and(|l| l.contains("export default"))
+                })
+                .take(1)
+                .collect()
+        } else {
+            self.top_level(file)
+                .filter(|&i| self.capsules[i].name == name)
+                .collect()
+        };
+        if !found.is_empty() {
+            return found;
+        }
+        let Some(source) = self.files.get(file) else {
+            return Vec::new();
+        };
+        for import in &source.imports {
+            let reexported = match &import.target {
+                ImportTarget::Symbol {
+                    file,
+                    name: original,
+                } if import.local == name => self.declared(file, original, depth + 1),
+                ImportTarget::Glob { file } => self.declared(file, name, depth + 1),
+                _ => Vec::new(),
+            };
+            if !reexported.is_empty() {
+                return reexported;
+            }
+        }
+        Vec::new()
+    }
+
+    fn rust_crates(&self) -> RustCrates {
+        let mut crates = RustCrates::default();
+        for file in self.by_file.keys() {
+            if Language::of(file) != Some(Language::Rust) {
+                continue;
+            }
+            let (root, module) = rust_module(file);
+            if !crates.names.values().any(|r| r == &root) {
+                if let Some(name) = root
+                    .parent()
+                    .and_then(|dir| std::fs::read_to_string(dir.join("Cargo.toml")).ok())
+                    .and_then(|manifest| package_name(&manifest))
+                {
+                    crates.names.insert(name.replace('-', "_"), root.clone());
+                }
+            }
+            crates.modules.insert((root, module), file.clone());
+        }
+        crates
+    }
+
+    fn rust_imports(&self, crates: &RustCrates, file: &Path, source: &str, out: &mut SourceFile) {
+        static USE: OnceLock<Regex> = OnceLock::new();
+        let pattern = USE.get_or_init(|| {
+            Regex::new(r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?use[ \t]+([^;]+);").unwrap()
+        });
+        for cap in pattern.captures_iter(source) {
+            mark_lines(source, cap.get(0).unwrap(), &mut out.import_lines);
+            let mut paths = Vec::new();
+            expand_use_tree(&[], &cap[1], &mut paths);
+            for (path, alias) in paths {
+                let Some(target) = resolve_rust_path(crates, file, &path) else {
+                    continue;
+                };
+                let local = match (&target, alias) {
+                    (ImportTarget::Glob { .. }, _) => String::new(),
+                    (_, Some(alias)) => alias,
+                    (_, None) => path.last().cloned().unwrap_or_default(),
+                };
+                if local != "_" {
+                    out.imports.push(Import { local, target });
+                }
+            }
+        }
+    }
+
+    fn script_imports(&self, file: &Path, source: &str, out: &mut SourceFile) {
+        static IMPORT: OnceLock<Regex> = OnceLock::new();
+        static REEXPORT: OnceLock<Regex> = OnceLock::new();
+        static REQUIRE: OnceLock<Regex> = OnceLock::new();
+        let import = IMPORT.get_or_init(|| {
+            Regex::new(r#"(?m)^[ \t]*import[ \t]+(?:type[ \t]+)?([\w$*{},\s]+?)\s+from\s+['"]([^'"]+)['"]"#)
+                .unwrap()
+        });
+        let reexport = REEXPORT.get_or_init(|| {
+            Regex::new(r#"(?m)^[ \t]*export[ \t]+(?:type[ \t]+)?(\*(?:\s+as\s+[\w$]+)?|\{[^}]*\})\s+from\s+['"]([^'"]+)['"]"#)
+                .unwrap()
+        });
+        let require = REQUIRE.get_or_init(|| {
+            Regex::new(
+                r#"(?:const|let|var)\s+(\{[^}]*\}|[\w$]+)\s*=\s*require\(\s*['"]([^'"]+)['"]\s*\)"#,
+            )
+            .unwrap()
+        });
+
+        for (pattern, separator) in [(import, " as "), (reexport, " as "), (require, ":")] {
+            for cap in pattern.captures_iter(source) {
+                mark_lines(source, cap.get(0).unwrap(), &mut out.import_lines);
+                let Some(target) = self.resolve_script_module(file, &cap[2]) else {
+                    continue;
+                };
+                let clause = cap[1].trim();
+                let (outside, braces) = match (clause.find('{'), clause.rfind('}')) {
+                    (Some(open), Some(close)) => (
+                        format!("{}{}", &clause[..open], &clause[close + 1..]),
+                        &clause[open + 1..close],
+                    ),
+                    _ => (clause.to_string(), ""),
+                };
+                for item in braces.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+                    let item = item.trim_start_matches("type ").trim();
+                    let (name, local) = item
+                        .split_once(separator)
+                        .map_or((item, item), |(n, l)| (n.trim(), l.trim()));
+                    out.imports.push(Import {
+                        local: local.to_string(),
+                        target: ImportTarget::Symbol {
+                            file: target.clone(),
+                            name: name.to_string(),
+                        },
+                    });
+                }
+                for item in outside.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+                    let import = if item == "*" {
+                        Import {
+                            local: String::new(),
+                            target: ImportTarget::Glob {
+                                file: target.clone(),
+                            },
+                        }
+                    } else if let Some(namespace) = item.strip_prefix('*') {
+                        Import {
+                            local: namespace.trim().trim_start_matches("as").trim().to_string(),
+                            target: ImportTarget::Module {
+                                file: target.clone(),
+                            },
+                        }
+                    } else if separator == ":" {
+                        // `const module = require('./module')`
+                        Import {
+                            local: item.to_string(),
+                            target: ImportTarget::Module {
+                                file: target.clone(),
+                            },
+                        }
+                    } else {
+                        Import {
+                            local: item.to_string(),
+                            target: ImportTarget::Symbol {
+                                file: target.clone(),
+                                name: "default".to_string(),
+                            },
+                        }
+                    };
+                    out.imports.push(import);
+                }
+            }
+        }
+    }
+
+    /// Relative specifier to a project file; packages are external
+    fn resolve_script_module(&self, from: &Path, specifier: &str) -> Option<PathBuf> {
+        if !specifier.starts_with('.') {
+            return None;
+        }
+        let base = normalize(&from.parent()?.join(specifier));
+        let mut candidates = vec![base.clone()];
+        // ESM sources import `./x.js` that is compiled from `./x.ts`
+        if let Some(ext) = base.extension().and_then(|e| e.to_str()) {
+            if matches!(ext, "js" | "jsx" | "mjs") {
+                candidates.push(base.with_extension("ts"));
+                candidates.push(base.with_extension("tsx"));
+            }
+        }
+        for ext in SCRIPT_EXTENSIONS {
+            let mut with_ext = base.clone().into_os_string();
+            with_ext.push(format!(".{ext}"));
+            candidates.push(PathBuf::from(with_ext));
+            candidates.push(base.join(format!("index.{ext}")));
+        }
+        candidates
+            .into_iter()
+            .find(|c| self.by_file.contains_key(c))
+    }
+
+    fn python_imports(&self, file: &Path, source: &str, out: &mut SourceFile) {
+        static FROM: OnceLock<Regex> = OnceLock::new();
+        static IMPORT: OnceLock<Regex> = OnceLock::new();
+        let from = FROM.get_or_init(|| {
+            Regex::new(r"(?m)^[ \t]*from[ \t]+(\.*)([\w.]*)[ \t]+import[ \t]+(\([^)]*\)|[^\n#]+)")
+                .unwrap()
+        });
+        let import = IMPORT.get_or_init(|| {
+            Regex::new(r"(?m)^[ \t]*import[ \t]+([\w.]+(?:[ \t]+as[ \t]+\w+)?(?:[ \t]*,[ \t]*[\w.]+(?:[ \t]+as[ \t]+\w+)?)*)")
+                .unwrap()
+        });
+
+        for cap in from.captures_iter(source) {
+            mark_lines(source, cap.get(0).unwrap(), &mut out.import_lines);
+            let dots = cap[1].len();
+            let module = &cap[2];
+            let names = cap[3].trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace());
+            let package = self.resolve_python_module(file, dots, module);
+            for item in names.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+                let (name, local) = item
+                    .split_once(" as ")
+                    .map_or((item, item), |(n, l)| (n.trim(), l.trim()));
+                let target = if name == "*" {
+                    package.clone().map(|file| ImportTarget::Glob { file })
+                } else if let Some(file) = package
+                    .clone()
+                    .filter(|f| self.top_level(f).any(|i| self.capsules[i].name == name))
+                {
+                    Some(ImportTarget::Symbol {
+                        file,
+                        name: name.to_string(),
+                    })
+                } else {
+                    // `from package import submodule`
+                    let submodule = if module.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{module}.{name}")
+                    };
+                    self.resolve_python_module(file, dots, &submodule)
+                        .map(|file| ImportTarget::Module { file })
+                        .or_else(|| {
+                            package.clone().map(|file| ImportTarget::Symbol {
+                                file,
+                                name: name.to_string(),
+                            })
+                        })
+                };
+                if let Some(target) = target {
+                    let local = if name == "*" { "" } else { local };
+                    out.imports.push(Import {
+                        local: local.to_string(),
+                        target,
+                    });
+                }
+            }
+        }
+        for cap in import.captures_iter(source) {
+            mark_lines(source, cap.get(0).unwrap(), &mut out.import_lines);
+            for item in cap[1].split(',').map(str::trim) {
+                let (module, local) = item
+                    .split_once(" as ")
+                    .map_or((item, item), |(m, l)| (m.trim(), l.trim()));
+                if let Some(file) = self.resolve_python_module(file, 0, module) {
+                    out.imports.push(Import {
+                        local: local.to_string(),
+                        target: ImportTarget::Module { file },
+                    });
+                }
+            }
+        }
+    }
+
+    /// Dotted module to a project file: relative to the importing package for
+    /// leading dots, otherwise the matching file closest to the importer
+    fn resolve_python_module(&self, from: &Path, dots: usize, dotted: &str) -> Option<PathBuf> {
+        let segments: Vec<&str> = dotted.split('.').filter(|s| !s.is_empty()).collect();
+        let known = |path: PathBuf| self.by_file.contains_key(&path).then_some(path);
+        if dots > 0 {
+            let mut dir = from.parent()?.to_path_buf();
+            for _ in 1..dots {
+                dir = dir.parent()?.to_path_buf();
+            }
+            let module: PathBuf = segments.iter().fold(dir, |path, s| path.join(s));
+            return known(module.with_extension("py"))
+                .filter(|_| !segments.is_empty())
+                .or_else(|| known(module.join("__init__.py")));
+        }
+        if segments.is_empty() {
+            return None;
+        }
+        self.by_file
+            .keys()
+            .filter(|f| f.extension().is_some_and(|e| e == "py"))
+            .filter(|f| {
+                let without_ext = f.with_extension("");
+                let mut parts: Vec<&str> = without_ext
+                    .components()
+                    .filter_map(|c| c.as_os_str().to_str())
+                    .collect();
+                if parts.last() == Some(&"__init__") {
+                    parts.pop();
+                }
+                parts.ends_with(&segments)
+            })
+            .max_by_key(|f| {
+                let shared = f
+                    .components()
+                    .zip(from.components())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                (shared, std::cmp::Reverse(f.components().count()))
+            })
+            .cloned()
+    }
+}
+
+/// Capsule source without nested capsules and import statements
+fn own_text((start, end): (usize, usize), spans: &[(usize, usize)], source: &SourceFile) -> String {
+    let nested: Vec<&(usize, usize)> = spans
+        .iter()
+        .filter(|&&(s, e)| start <= s && e <= end && (s, e) != (start, end))
+        .collect();
+    (start..=end.min(source.lines.len()))
+        .filter(|line| !source.import_lines.contains(line))
+        .filter(|line| !nested.iter().any(|(s, e)| (s..=e).contains(&line)))
+        .filter_map(|line| source.lines.get(line - 1).map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Crate root (`src` directory, or the file's directory) and module path of a Rust file
+fn rust_module(file: &Path) -> (PathBuf, Vec<String>) {
+    let root = file
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.file_name().is_some_and(|n| n == "src"))
+        .or_else(|| file.parent())
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    let relative = file.strip_prefix(&root).unwrap_or(file).with_extension("");
+    let mut module: Vec<String> = relative
+        .components()
+        .filter_map(|c| c.as_os_str().to_str().map(str::to_string))
+        .collect();
+    if module.last().is_some_and(|m| m == "mod") {
+        module.pop();
+    }
+    if module.len() == 1 && matches!(module[0].as_str(), "lib" | "main") {
+        module.clear();
+    }
+    (root, module)
+}
+
+fn package_name(manifest: &str) -> Option<String> {
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    let name = NAME.get_or_init(|| Regex::new(r#"(?m)^\s*name\s*=\s*"([^"]+)""#).unwrap());
+    let package = manifest.find("[package]")?;
+    Some(name.captures(&manifest[package..])?[1].to_string())
+}
+
+/// Flattens a `use` tree into paths with optional aliases
+fn expand_use_tree(prefix: &[String], tree: &str, out: &mut Vec<(Vec<String>, Option<String>)>) {
+    let tree = tree.trim().trim_start_matches("::");
+    if let Some(open) = tree.find('{') {
+        let close = tree.rfind('}').unwrap_or(tree.len());
+        let mut base = prefix.to_vec();
+        base.extend(path_segments(&tree[..open]));
+        let mut depth = 0;
+        let mut item_start = open + 1;
+        for (i, ch) in tree.char_indices().take(close).skip(open + 1) {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    expand_use_tree(&base, &tree[item_start..i], out);
+                    item_start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if item_start < close {
+            expand_use_tree(&base, &tree[item_start..close], out);
+        }
+        return;
+    }
+    if tree.is_empty() {
+        return;
+    }
+    let (path, alias) = match tree.split_once(" as ") {
+        Some((path, alias)) => (path, Some(alias.trim().to_string())),
+        None => (tree, None),
+    };
+    let mut full = prefix.to_vec();
+    for segment in path_segments(path) {
+        // `use module::{self, Item}` imports the module itself
+        if segment == "self" && !full.is_empty() {
+            continue;
+        }
+        full.push(segment);
+    }
+    out.push((full, alias));
+}
+
+fn path_segments(path: &str) -> Vec<String> {
+    path.split("::")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Resolves a `use` path against the crate module tree; external crates give `None`
+fn resolve_rust_path(crates: &RustCrates, file: &Path, path: &[String]) -> Option<ImportTarget> {
+    let (root, module) = rust_module(file);
+    let first = path.first()?.as_str();
+    let mut bases: Vec<(PathBuf, Vec<String>, &[String], bool)> = Vec::new();
+    match first {
+        "crate" => bases.push((root, Vec::new(), &path[1..], true)),
+        "self" => bases.push((root, module, &path[1..], true)),
+        "super" => {
+            let supers = path.iter().take_while(|s| *s == "super").count();
+            let base = module[..module.len().checked_sub(supers)?].to_vec();
+            bases.push((root, base, &path[supers..], true));
+        }
+        name if crates.names.contains_key(name) => {
+            bases.push((crates.names[name].clone(), Vec::new(), &path[1..], true))
+        }
+        _ => {
+            // 2018 paths: a child module of the current one, then the crate root
+            bases.push((root.clone(), module, path, false));
+            bases.push((root, Vec::new(), path, false));
+        }
+    }
+    for (root, base, rest, explicit) in bases {
+        let shortest = usize::from(!explicit);
+        for k in (shortest..=rest.len()).rev() {
+            let mut candidate = base.clone();
+            candidate.extend(rest[..k].iter().cloned());
+            let Some(file) = crates.modules.get(&(root.clone(), candidate)) else {
+                continue;
+            };
+            let file = file.clone();
+            return Some(match rest.get(k).map(String::as_str) {
+                None => ImportTarget::Module { file },
+                Some("*") => ImportTarget::Glob { file },
+                Some(name) => ImportTarget::Symbol {
+                    file,
+                    name: name.to_string(),
+                },
+            });
+        }
+    }
+    None
+}
+
+/// Lexical normalization of `.` and `..`
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Records the lines (1-based) covered by a match
+fn mark_lines(source: &str, found: regex::Match, lines: &mut HashSet<usize>) {
+    let first = source[..found.start()].matches('\n').count() + 1;
+    let last = first + found.as_str().matches('\n').count();
+    lines.extend(first..=last);
+}
+
+fn mentions(text: &str, word: &str) -> bool {
+    if word.is_empty() {
+        return false;
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    text.match_indices(word).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
@@ -0,0 +1,116 @@
+// Переносимый архив графа для офлайн-ревью: `archlens export graph --format archive`
+// пишет один файл `.archlens.graph`, который на другой машине открывает
+// `archlens view <файл>` без доступа к исходникам. Внутри — сохранённый граф
+// (`graph_store`, с версией формата и миграциями) и заголовок проекта; пути
+// капсул записаны относительно корня проекта
+
+use crate::graph_store::{self, StoredGraph};
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Имя формата архива в реестре экспортёров и в `--format`
+pub const ARCHIVE_FORMAT: &str = "archive";
+/// Расширение файла архива
+pub const ARCHIVE_EXTENSION: &str = "archlens.graph";
+
+/// Заголовок архива: откуда и когда экспортирован граф
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveHeader {
+    /// Имя проекта — последний компонент пути корня; сам путь в архив не пишется
+    pub project: String,
+    pub exported_at: Option<DateTime<Utc>>,
+}
+
+/// Архив графа: заголовок и сохранённый граф в одном JSON-документе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphArchive {
+    #[serde(flatten)]
+    pub header: ArchiveHeader,
+    #[serde(flatten)]
+    pub stored: StoredGraph,
+}
+
+impl GraphArchive {
+    /// Архив графа проекта `project_root`; пути капсул внутри корня становятся относительными
+    pub fn new(mut graph: CapsuleGraph, project_root: &Path) -> Self {
+        let canonical = project_root
+            .canonicalize()
+            .unwrap_or_else(|_| project_root.to_path_buf());
+        for capsule in graph.capsules.values_mut() {
+            let relative = capsule
+                .file_path
+                .strip_prefix(project_root)
+                .or_else(|_| capsule.file_path.strip_prefix(&canonical));
+            if let Ok(relative) = relative {
+                capsule.file_path = relative.to_path_buf();
+            }
+        }
+        Self {
+            header: ArchiveHeader {
+                project: project_name(project_root),
+                exported_at: Some(Utc::now()),
+            },
+            stored: StoredGraph::new(graph),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))
+    }
+
+    /// Читает архив; сохранённый граф без заголовка (любой версии формата)
+    /// тоже открывается — с пустым заголовком
+    pub fn from_json(json: &str) -> Result<Self> {
+        let document: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| AnalysisError::Parse(format!("graph archive: {e}")))?;
+        serde_json::from_value(graph_store::migrate(document)?)
+            .map_err(|e| AnalysisError::Parse(format!("graph archive: {e}")))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn graph(&self) -> &CapsuleGraph {
+        &self.stored.graph
+    }
+
+    /// Строка о происхождении архива для вывода перед отчётом
+    pub fn describe(&self) -> String {
+        let project = if self.header.project.is_empty() {
+            "(без заголовка)"
+        } else {
+            self.header.project.as_str()
+        };
+        let exported = self
+            .header
+            .exported_at
+            .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "дата неизвестна".to_string());
+        format!(
+            "{}: {} капсул, {} связей; экспорт {}, ArchLens {}",
+            project,
+            self.graph().capsules.len(),
+            self.graph().relations.len(),
+            exported,
+            self.stored.archlens_version
+        )
+    }
+}
+
+/// Имя файла архива по умолчанию: `<проект>.archlens.graph`
+pub fn default_archive_name(project_root: &Path) -> String {
+    format!("{}.{}", project_name(project_root), ARCHIVE_EXTENSION)
+}
+
+fn project_name(project_root: &Path) -> String {
+    project_root
+        .canonicalize()
+        .ok()
+        .and_then(|root| root.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "project".to_string())
+}
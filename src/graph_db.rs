@@ -0,0 +1,401 @@
+// Граф в SQLite для больших проектов: капсулы, связи и метрики хранятся по
+// снимкам (рабочая копия, коммиты) в `.archlens/graph.db`. Пока отпечаток
+// рабочей копии не изменился, MCP-сервер читает граф из базы, а diff берёт
+// графы уже проанализированных коммитов, не разбирая исходники заново.
+// База создаётся явно: `archlens export <path> sqlite`
+
+use crate::graph_store::GRAPH_FORMAT_VERSION;
+use crate::types::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// База графа проекта относительно его корня
+pub const GRAPH_DB_FILE: &str = ".archlens/graph.db";
+/// Снимок рабочей копии проекта
+pub const WORKTREE_SNAPSHOT: &str = "worktree";
+
+/// Снимок коммита: дерево коммита не меняется, поэтому отпечаток дерева не нужен
+pub fn commit_snapshot(sha: &str) -> String {
+    format!("commit:{}", sha)
+}
+
+/// Отпечаток, при совпадении которого снимок считается актуальным: дерево
+/// рабочей копии (для коммитов не нужно), настройки анализа и версия ArchLens
+pub fn snapshot_fingerprint(project_root: &Path, key: &str) -> String {
+    let settings = settings_fingerprint(project_root);
+    if key == WORKTREE_SNAPSHOT {
+        format!(
+            "{}/{}",
+            crate::audit::project_fingerprint(project_root),
+            settings
+        )
+    } else {
+        settings
+    }
+}
+
+/// Версия ArchLens и содержимое `.archlens.toml` и файлов `*.json`/`*.toml`
+/// в `.archlens/` (кроме кешей `*-cache.json`): отпечаток дерева их не видит
+fn settings_fingerprint(project_root: &Path) -> String {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(project_root.join(".archlens"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            (name.ends_with(".json") || name.ends_with(".toml"))
+                && !name.ends_with("-cache.json")
+                && path.is_file()
+        })
+        .collect();
+    files.push(project_root.join(".archlens.toml"));
+    files.sort();
+
+    let mut hasher = crate::stable_hash::Fnv::new();
+    hasher.write_str(env!("CARGO_PKG_VERSION"));
+    for path in &files {
+        if let Ok(content) = std::fs::read(path) {
+            hasher.write_str(&path.file_name().unwrap_or_default().to_string_lossy());
+            hasher.write(&content);
+        }
+    }
+    format!("v{}:{}", env!("CARGO_PKG_VERSION"), hasher.hex())
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    key TEXT PRIMARY KEY,
+    format_version INTEGER NOT NULL,
+    archlens_version TEXT NOT NULL,
+    fingerprint TEXT,
+    created_at TEXT NOT NULL,
+    metrics TEXT NOT NULL,
+    previous_analysis TEXT
+);
+CREATE TABLE IF NOT EXISTS capsules (
+    snapshot TEXT NOT NULL,
+    id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    capsule_type TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    layer TEXT,
+    complexity INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (snapshot, id)
+);
+CREATE INDEX IF NOT EXISTS capsules_by_name ON capsules (snapshot, name);
+CREATE INDEX IF NOT EXISTS capsules_by_file ON capsules (snapshot, file_path);
+CREATE TABLE IF NOT EXISTS relations (
+    snapshot TEXT NOT NULL,
+    from_id TEXT NOT NULL,
+    to_id TEXT NOT NULL,
+    relation_type TEXT NOT NULL,
+    strength REAL NOT NULL,
+    description TEXT
+);
+CREATE INDEX IF NOT EXISTS relations_by_from ON relations (snapshot, from_id);
+CREATE INDEX IF NOT EXISTS relations_by_to ON relations (snapshot, to_id);
+";
+
+/// Сохранённый снимок без загрузки графа
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SnapshotInfo {
+    pub key: String,
+    pub archlens_version: String,
+    pub created_at: String,
+    pub capsules: usize,
+    pub relations: usize,
+}
+
+/// База графов проекта
+pub struct GraphDatabase {
+    connection: Connection,
+}
+
+fn db_error(error: rusqlite::Error) -> AnalysisError {
+    AnalysisError::GenericError(format!("graph database: {error}"))
+}
+
+fn json_error(error: serde_json::Error) -> AnalysisError {
+    AnalysisError::Parse(format!("graph database: {error}"))
+}
+
+impl GraphDatabase {
+    /// Открывает базу, создавая файл и схему при необходимости
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path).map_err(db_error)?;
+        connection.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(Self { connection })
+    }
+
+    /// База проекта, если она уже создана
+    pub fn for_project(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(GRAPH_DB_FILE);
+        if path.is_file() {
+            Self::open(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Заменяет снимок `key` графом; пути капсул внутри `root` хранятся относительными
+    pub fn save(
+        &mut self,
+        key: &str,
+        graph: &CapsuleGraph,
+        root: &Path,
+        fingerprint: Option<&str>,
+    ) -> Result<()> {
+        let transaction = self.connection.transaction().map_err(db_error)?;
+        for statement in [
+            "DELETE FROM snapshots WHERE key = ?1",
+            "DELETE FROM capsules WHERE snapshot = ?1",
+            "DELETE FROM relations WHERE snapshot = ?1",
+        ] {
+            transaction
+                .execute(statement, params![key])
+                .map_err(db_error)?;
+        }
+        transaction
+            .execute(
+                "INSERT INTO snapshots (key, format_version, archlens_version, fingerprint, created_at, metrics, previous_analysis)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    key,
+                    GRAPH_FORMAT_VERSION,
+                    env!("CARGO_PKG_VERSION"),
+                    fingerprint,
+                    graph.created_at.to_rfc3339(),
+                    serde_json::to_string(&graph.metrics).map_err(json_error)?,
+                    graph
+                        .previous_analysis
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()
+                        .map_err(json_error)?,
+                ],
+            )
+            .map_err(db_error)?;
+        {
+            let mut insert = transaction
+                .prepare(
+                    "INSERT INTO capsules (snapshot, id, name, capsule_type, file_path, layer, complexity, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                )
+                .map_err(db_error)?;
+            for capsule in graph.capsules.values() {
+                let mut stored = capsule.clone();
+                if let Ok(relative) = capsule.file_path.strip_prefix(root) {
+                    stored.file_path = relative.to_path_buf();
+                }
+                insert
+                    .execute(params![
+                        key,
+                        capsule.id.to_string(),
+                        capsule.name,
+                        format!("{:?}", capsule.capsule_type),
+                        stored.file_path.to_string_lossy(),
+                        capsule.layer,
+                        capsule.complexity,
+                        serde_json::to_string(&stored).map_err(json_error)?,
+                    ])
+                    .map_err(db_error)?;
+            }
+            let mut insert = transaction
+                .prepare(
+                    "INSERT INTO relations (snapshot, from_id, to_id, relation_type, strength, description)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )
+                .map_err(db_error)?;
+            for relation in &graph.relations {
+                insert
+                    .execute(params![
+                        key,
+                        relation.from_id.to_string(),
+                        relation.to_id.to_string(),
+                        serde_json::to_value(relation.relation_type)
+                            .map_err(json_error)?
+                            .as_str(),
+                        relation.strength,
+                        relation.description,
+                    ])
+                    .map_err(db_error)?;
+            }
+        }
+        transaction.commit().map_err(db_error)
+    }
+
+    /// Граф снимка `key`; относительные пути капсул продолжаются от `root`.
+    /// `None` — снимка нет или он записан другой версией формата
+    pub fn load(&self, key: &str, root: &Path) -> Result<Option<CapsuleGraph>> {
+        let snapshot = self
+            .connection
+            .query_row(
+                "SELECT format_version, created_at, metrics, previous_analysis FROM snapshots WHERE key = ?1",
+                params![key],
+                |row| {
+                    Ok((
+                        row.get::<_, u32>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(db_error)?;
+        let Some((format_version, created_at, metrics, previous_analysis)) = snapshot else {
+            return Ok(None);
+        };
+        if format_version != GRAPH_FORMAT_VERSION {
+            return Ok(None);
+        }
+
+        let mut capsules: HashMap<Uuid, Capsule> = HashMap::new();
+        let mut layers: HashMap<String, Vec<Uuid>> = HashMap::new();
+        let mut select = self
+            .connection
+            .prepare("SELECT data FROM capsules WHERE snapshot = ?1")
+            .map_err(db_error)?;
+        let rows = select
+            .query_map(params![key], |row| row.get::<_, String>(0))
+            .map_err(db_error)?;
+        for data in rows {
+            let mut capsule: Capsule =
+                serde_json::from_str(&data.map_err(db_error)?).map_err(json_error)?;
+            if capsule.file_path.is_relative() {
+                capsule.file_path = root.join(&capsule.file_path);
+            }
+            if let Some(layer) = &capsule.layer {
+                layers.entry(layer.clone()).or_default().push(capsule.id);
+            }
+            capsules.insert(capsule.id, capsule);
+        }
+        for ids in layers.values_mut() {
+            ids.sort();
+        }
+
+        let mut select = self
+            .connection
+            .prepare(
+                "SELECT from_id, to_id, relation_type, strength, description
+                 FROM relations WHERE snapshot = ?1 ORDER BY rowid",
+            )
+            .map_err(db_error)?;
+        let rows = select
+            .query_map(params![key], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, f32>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })
+            .map_err(db_error)?;
+        let mut relations = Vec::new();
+        for row in rows {
+            let (from_id, to_id, relation_type, strength, description) = row.map_err(db_error)?;
+            relations.push(CapsuleRelation {
+                from_id: parse_id(&from_id)?,
+                to_id: parse_id(&to_id)?,
+                relation_type: serde_json::from_value(serde_json::Value::String(relation_type))
+                    .map_err(json_error)?,
+                strength,
+                description,
+            });
+        }
+
+        Ok(Some(CapsuleGraph {
+            capsules,
+            relations,
+            layers,
+            metrics: serde_json::from_str(&metrics).map_err(json_error)?,
+            created_at: chrono::DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|e| AnalysisError::Parse(format!("graph database: {e}")))?
+                .with_timezone(&chrono::Utc),
+            previous_analysis: previous_analysis
+                .map(|json| serde_json::from_str(&json))
+                .transpose()
+                .map_err(json_error)?,
+        }))
+    }
+
+    /// Отпечаток, с которым сохранён снимок
+    pub fn fingerprint(&self, key: &str) -> Result<Option<String>> {
+        self.connection
+            .query_row(
+                "SELECT fingerprint FROM snapshots WHERE key = ?1",
+                params![key],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map(Option::flatten)
+            .map_err(db_error)
+    }
+
+    /// Сохранённые снимки по ключу
+    pub fn snapshots(&self) -> Result<Vec<SnapshotInfo>> {
+        let mut select = self
+            .connection
+            .prepare(
+                "SELECT key, archlens_version, created_at,
+                    (SELECT COUNT(*) FROM capsules WHERE snapshot = key),
+                    (SELECT COUNT(*) FROM relations WHERE snapshot = key)
+                 FROM snapshots ORDER BY key",
+            )
+            .map_err(db_error)?;
+        let rows = select
+            .query_map([], |row| {
+                Ok(SnapshotInfo {
+                    key: row.get(0)?,
+                    archlens_version: row.get(1)?,
+                    created_at: row.get(2)?,
+                    capsules: row.get(3)?,
+                    relations: row.get(4)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>().map_err(db_error)
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid> {
+    Uuid::parse_str(id).map_err(|e| AnalysisError::Parse(format!("graph database: {e}")))
+}
+
+/// Граф снимка из базы проекта, если она создана и снимок актуален (совпадает
+/// [`snapshot_fingerprint`]); иначе `build`, и результат
+/// обновляет снимок. Без базы просто вызывает `build`. Ошибки базы не
+/// прерывают анализ: граф строится заново
+pub fn cached_graph(
+    project_root: &Path,
+    key: &str,
+    root: &Path,
+    build: impl FnOnce() -> std::result::Result<CapsuleGraph, String>,
+) -> std::result::Result<CapsuleGraph, String> {
+    let Ok(Some(mut database)) = GraphDatabase::for_project(project_root) else {
+        return build();
+    };
+    let fingerprint = snapshot_fingerprint(project_root, key);
+    if database.fingerprint(key).ok().flatten().as_deref() == Some(fingerprint.as_str()) {
+        if let Ok(Some(graph)) = database.load(key, root) {
+            return Ok(graph);
+        }
+    }
+    let graph = build()?;
+    if let Err(err) = database.save(key, &graph, root, Some(&fingerprint)) {
+        eprintln!("⚠️ Граф не сохранён в {}: {}", GRAPH_DB_FILE, err);
+    }
+    Ok(graph)
+}
+
+/// Путь базы по умолчанию для проекта
+pub fn default_path(project_root: &Path) -> PathBuf {
+    project_root.join(GRAPH_DB_FILE)
+}
@@ -0,0 +1,414 @@
+// Локальные ответы на структурные вопросы о графе (`archlens ask "who uses PaymentService?"`):
+// индекс идентификаторов и фрагментов путей -> капсулы плюс разбор вопроса по шаблонам.
+// Ответ детерминирован и не требует LLM
+
+use crate::types::{Capsule, CapsuleGraph, CapsuleType, RelationType};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Тип вопроса
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryIntent {
+    /// Кто использует / вызывает / зависит от
+    Dependents,
+    /// От чего зависит
+    Dependencies,
+    /// Где определён
+    Location,
+    /// Что лежит в каталоге/файле
+    Contents,
+    /// Вопрос не распознан — краткая справка по найденному
+    Describe,
+}
+
+/// Шаблоны вопросов (регистр не важен); первая группа — предмет вопроса
+const QUESTION_PATTERNS: &[(QueryIntent, &str)] = &[
+    (
+        QueryIntent::Dependents,
+        r"^(?:who|what)\s+(?:uses|calls|depends\s+on|references|imports|needs)\s+(.+)$",
+    ),
+    (
+        QueryIntent::Dependents,
+        r"^(?:users|callers|dependents|consumers)\s+of\s+(.+)$",
+    ),
+    (
+        QueryIntent::Dependents,
+        r"^кто\s+(?:использует|вызывает|зависит\s+от|ссылается\s+на)\s+(.+)$",
+    ),
+    (
+        QueryIntent::Dependencies,
+        r"^what\s+does\s+(.+?)\s+(?:use|call|depend\s+on|import|need)$",
+    ),
+    (
+        QueryIntent::Dependencies,
+        r"^(?:dependencies|deps)\s+of\s+(.+)$",
+    ),
+    (
+        QueryIntent::Dependencies,
+        r"^(?:от\s+чего\s+зависит|что\s+использует|что\s+вызывает)\s+(.+)$",
+    ),
+    (
+        QueryIntent::Location,
+        r"^where\s+(?:is|are)\s+(.+?)(?:\s+(?:defined|declared|implemented))?$",
+    ),
+    (
+        QueryIntent::Location,
+        r"^где\s+(?:находится\s+|определ[её]н[аоы]?\s+)?(.+)$",
+    ),
+    (
+        QueryIntent::Contents,
+        r"^(?:what(?:'s|\s+is)\s+in|(?:list|show)\s+(?:capsules|components|everything)\s+in)\s+(.+)$",
+    ),
+    (
+        QueryIntent::Contents,
+        r"^что\s+(?:лежит\s+|есть\s+)?в\s+(.+)$",
+    ),
+];
+
+/// Капсула в ответе
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapsuleRef {
+    pub name: String,
+    pub kind: CapsuleType,
+    /// `путь:строка` относительно корня проекта
+    pub location: String,
+    /// Тип связи с предметом вопроса (для Dependents/Dependencies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<RelationType>,
+}
+
+/// Ответ на вопрос
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryAnswer {
+    pub question: String,
+    pub intent: QueryIntent,
+    pub subject: String,
+    /// Капсулы, которые удалось сопоставить с предметом вопроса
+    pub subjects: Vec<CapsuleRef>,
+    pub results: Vec<CapsuleRef>,
+}
+
+impl QueryAnswer {
+    pub fn to_text(&self) -> String {
+        if self.subjects.is_empty() {
+            return format!("Nothing in the graph matches '{}'.\n", self.subject);
+        }
+        let mut out = String::new();
+        let names = self.subject_names();
+        match self.intent {
+            QueryIntent::Dependents => {
+                if self.results.is_empty() {
+                    out.push_str(&format!("No components use {}.\n", names));
+                } else {
+                    out.push_str(&format!(
+                        "{} used by {} component(s):\n",
+                        names,
+                        self.results.len()
+                    ));
+                }
+            }
+            QueryIntent::Dependencies => {
+                if self.results.is_empty() {
+                    out.push_str(&format!("{} has no dependencies in the graph.\n", names));
+                } else {
+                    out.push_str(&format!(
+                        "{} depends on {} component(s):\n",
+                        names,
+                        self.results.len()
+                    ));
+                }
+            }
+            QueryIntent::Location | QueryIntent::Describe => {}
+            QueryIntent::Contents => out.push_str(&format!(
+                "'{}' contains {} component(s):\n",
+                self.subject,
+                self.results.len()
+            )),
+        }
+        let listed = match self.intent {
+            QueryIntent::Location | QueryIntent::Describe => &self.subjects,
+            _ => &self.results,
+        };
+        for item in listed {
+            out.push_str(&format!(
+                "- {} ({:?}) — {}",
+                item.name, item.kind, item.location
+            ));
+            if let Some(relation) = &item.relation {
+                out.push_str(&format!(" [{:?}]", relation));
+            }
+            out.push('\n');
+        }
+        if self.intent == QueryIntent::Describe && !self.results.is_empty() {
+            out.push_str(&format!(
+                "Used by {} component(s); ask \"who uses {}?\" for the list.\n",
+                self.results.len(),
+                self.subject
+            ));
+        }
+        out
+    }
+
+    fn subject_names(&self) -> String {
+        let names: BTreeSet<&str> = self.subjects.iter().map(|s| s.name.as_str()).collect();
+        names.into_iter().collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Индекс графа: идентификаторы и фрагменты путей -> капсулы
+#[derive(Debug)]
+pub struct GraphIndex<'a> {
+    graph: &'a CapsuleGraph,
+    root: PathBuf,
+    /// Полное имя в нижнем регистре -> капсулы
+    names: HashMap<String, BTreeSet<Uuid>>,
+    /// Части идентификаторов (camelCase/snake_case) -> капсулы
+    tokens: HashMap<String, BTreeSet<Uuid>>,
+    /// Компоненты путей и имена файлов без расширения -> капсулы
+    paths: HashMap<String, BTreeSet<Uuid>>,
+}
+
+impl<'a> GraphIndex<'a> {
+    pub fn new(graph: &'a CapsuleGraph, project_root: &Path) -> Self {
+        let mut index = Self {
+            graph,
+            root: project_root.to_path_buf(),
+            names: HashMap::new(),
+            tokens: HashMap::new(),
+            paths: HashMap::new(),
+        };
+        for capsule in graph.capsules.values() {
+            index
+                .names
+                .entry(capsule.name.to_lowercase())
+                .or_default()
+                .insert(capsule.id);
+            for token in identifier_tokens(&capsule.name) {
+                index.tokens.entry(token).or_default().insert(capsule.id);
+            }
+            let relative = index.relative(&capsule.file_path);
+            for component in relative.split('/').filter(|c| !c.is_empty()) {
+                let component = component.to_lowercase();
+                if let Some((stem, _)) = component.rsplit_once('.') {
+                    index
+                        .paths
+                        .entry(stem.to_string())
+                        .or_default()
+                        .insert(capsule.id);
+                }
+                index.paths.entry(component).or_default().insert(capsule.id);
+            }
+        }
+        index
+    }
+
+    /// Капсулы, соответствующие предмету вопроса: точное имя, затем все части
+    /// идентификатора, затем фрагмент пути
+    pub fn resolve(&self, subject: &str) -> Vec<&'a Capsule> {
+        let key = subject.to_lowercase();
+        if let Some(ids) = self.names.get(&key) {
+            return self.capsules(ids);
+        }
+        if !key.contains('/') && !key.contains('.') {
+            let parts = identifier_tokens(subject);
+            let mut matched: Option<BTreeSet<Uuid>> = None;
+            for part in &parts {
+                let ids = self.tokens.get(part).cloned().unwrap_or_default();
+                matched = Some(match matched {
+                    Some(acc) => acc.intersection(&ids).copied().collect(),
+                    None => ids,
+                });
+            }
+            if let Some(ids) = matched.filter(|ids| !ids.is_empty()) {
+                return self.capsules(&ids);
+            }
+        }
+        self.in_path(subject)
+    }
+
+    /// Капсулы, в относительном пути которых встречается фрагмент
+    pub fn in_path(&self, fragment: &str) -> Vec<&'a Capsule> {
+        let fragment = fragment.trim_matches('/').replace('\\', "/").to_lowercase();
+        if fragment.is_empty() {
+            return Vec::new();
+        }
+        if let Some(ids) = self.paths.get(&fragment) {
+            return self.capsules(ids);
+        }
+        let mut found: Vec<&Capsule> = self
+            .graph
+            .capsules
+            .values()
+            .filter(|c| {
+                self.relative(&c.file_path)
+                    .to_lowercase()
+                    .contains(&fragment)
+            })
+            .collect();
+        found.sort_by_key(|c| (self.relative(&c.file_path), c.line_start, c.name.clone()));
+        found
+    }
+
+    pub fn ask(&self, question: &str) -> QueryAnswer {
+        let (intent, subject) = parse_question(question);
+        let subjects = if intent == QueryIntent::Contents {
+            self.in_path(&subject)
+        } else {
+            self.resolve(&subject)
+        };
+        let ids: BTreeSet<Uuid> = subjects.iter().map(|c| c.id).collect();
+
+        let results = match intent {
+            QueryIntent::Contents => subjects.iter().map(|c| self.reference(c, None)).collect(),
+            QueryIntent::Dependencies => self.related(&ids, false),
+            QueryIntent::Dependents | QueryIntent::Describe => self.related(&ids, true),
+            QueryIntent::Location => Vec::new(),
+        };
+        QueryAnswer {
+            question: question.trim().to_string(),
+            intent,
+            subject,
+            subjects: subjects.iter().map(|c| self.reference(c, None)).collect(),
+            results,
+        }
+    }
+
+    /// Связанные капсулы вне `ids`: входящие связи (`incoming`) или исходящие
+    fn related(&self, ids: &BTreeSet<Uuid>, incoming: bool) -> Vec<CapsuleRef> {
+        let mut found: BTreeMap<(String, usize, String), CapsuleRef> = BTreeMap::new();
+        for relation in &self.graph.relations {
+            let (own, other) = if incoming {
+                (relation.to_id, relation.from_id)
+            } else {
+                (relation.from_id, relation.to_id)
+            };
+            if !ids.contains(&own) || ids.contains(&other) {
+                continue;
+            }
+            let Some(capsule) = self.graph.capsules.get(&other) else {
+                continue;
+            };
+            let key = (
+                self.relative(&capsule.file_path),
+                capsule.line_start,
+                capsule.name.clone(),
+            );
+            found
+                .entry(key)
+                .or_insert_with(|| self.reference(capsule, Some(relation.relation_type)));
+        }
+        found.into_values().collect()
+    }
+
+    fn capsules(&self, ids: &BTreeSet<Uuid>) -> Vec<&'a Capsule> {
+        let mut capsules: Vec<&Capsule> = ids
+            .iter()
+            .filter_map(|id| self.graph.capsules.get(id))
+            .collect();
+        capsules.sort_by_key(|c| (self.relative(&c.file_path), c.line_start, c.name.clone()));
+        capsules
+    }
+
+    fn reference(&self, capsule: &Capsule, relation: Option<RelationType>) -> CapsuleRef {
+        CapsuleRef {
+            name: capsule.name.clone(),
+            kind: capsule.capsule_type,
+            location: format!(
+                "{}:{}",
+                self.relative(&capsule.file_path),
+                capsule.line_start
+            ),
+            relation,
+        }
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// Тип вопроса и его предмет (без кавычек и знаков препинания)
+pub fn parse_question(question: &str) -> (QueryIntent, String) {
+    let normalized = question
+        .trim()
+        .trim_end_matches(['?', '.', '!'])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    for (intent, pattern) in QUESTION_PATTERNS {
+        let Ok(regex) = Regex::new(&format!("(?i){}", pattern)) else {
+            continue;
+        };
+        if let Some(subject) = regex.captures(&normalized).and_then(|c| c.get(1)) {
+            return (*intent, clean_subject(subject.as_str()));
+        }
+    }
+    (QueryIntent::Describe, clean_subject(&normalized))
+}
+
+fn clean_subject(subject: &str) -> String {
+    let subject = subject
+        .trim()
+        .trim_matches(|c| matches!(c, '`' | '"' | '\'' | '«' | '»'));
+    // "the PaymentService class" -> "PaymentService"
+    let words: Vec<&str> = subject
+        .split_whitespace()
+        .filter(|w| {
+            !matches!(
+                w.to_lowercase().as_str(),
+                "the"
+                    | "a"
+                    | "an"
+                    | "class"
+                    | "struct"
+                    | "function"
+                    | "method"
+                    | "module"
+                    | "file"
+                    | "trait"
+                    | "interface"
+                    | "enum"
+                    | "type"
+            )
+        })
+        .collect();
+    if words.is_empty() {
+        subject.to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+/// Части идентификатора в нижнем регистре: `PaymentService::charge_card` ->
+/// payment, service, charge, card
+pub fn identifier_tokens(identifier: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in identifier.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = word.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0
+                && c.is_uppercase()
+                && (chars[i - 1].is_lowercase()
+                    || chars[i - 1].is_ascii_digit()
+                    || chars.get(i + 1).is_some_and(|n| n.is_lowercase()));
+            if boundary && !current.is_empty() {
+                tokens.push(std::mem::take(&mut current).to_lowercase());
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            tokens.push(current.to_lowercase());
+        }
+    }
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
@@ -0,0 +1,314 @@
+// Выборка капсул для огромных графов: когда узлов больше лимита, диаграммы и
+// экспортёры берут осмысленное подмножество вместо первых N узлов в порядке
+// обхода HashMap. Стратегии: самые связанные узлы, пропорционально по слоям,
+// проблемные капсулы и их соседи
+
+use crate::types::{CapsuleGraph, Priority};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Лимит узлов, если стратегия выбрана без `--max-nodes`
+pub const DEFAULT_SAMPLE_LIMIT: usize = 150;
+/// Слой капсул без `layer`
+const UNLAYERED: &str = "(none)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SamplingStrategy {
+    /// Наибольшая степень связности (входящие + исходящие связи)
+    Centrality,
+    /// Квоты по слоям пропорционально их размеру, внутри слоя — по связности
+    Stratified,
+    /// Капсулы с предупреждениями, затем их соседи, затем по связности
+    ProblemAdjacent,
+}
+
+impl SamplingStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Centrality => "centrality",
+            Self::Stratified => "stratified",
+            Self::ProblemAdjacent => "problem-adjacent",
+        }
+    }
+}
+
+impl FromStr for SamplingStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "centrality" | "top" | "central" => Ok(Self::Centrality),
+            "stratified" | "layers" | "per-layer" => Ok(Self::Stratified),
+            "problem-adjacent" | "problems" | "problem_adjacent" => Ok(Self::ProblemAdjacent),
+            other => Err(format!(
+                "Unknown sampling strategy `{other}` (centrality, stratified, problem-adjacent)"
+            )),
+        }
+    }
+}
+
+/// Результат выборки: подграф и исходное число капсул
+#[derive(Debug, Clone)]
+pub struct GraphSample {
+    pub graph: CapsuleGraph,
+    pub total_capsules: usize,
+    pub strategy: SamplingStrategy,
+}
+
+impl GraphSample {
+    pub fn omitted(&self) -> usize {
+        self.total_capsules - self.graph.capsules.len()
+    }
+
+    /// Однострочное пояснение для отчётов и диаграмм
+    pub fn note(&self) -> String {
+        format!(
+            "showing {} of {} components ({} sampling)",
+            self.graph.capsules.len(),
+            self.total_capsules,
+            self.strategy.as_str()
+        )
+    }
+}
+
+/// Выборка не более `limit` капсул выбранной стратегией
+#[derive(Debug, Clone, Copy)]
+pub struct GraphSampler {
+    strategy: SamplingStrategy,
+    limit: usize,
+}
+
+impl GraphSampler {
+    pub fn new(strategy: SamplingStrategy, limit: usize) -> Self {
+        Self {
+            strategy,
+            limit: limit.max(1),
+        }
+    }
+
+    pub fn strategy(&self) -> SamplingStrategy {
+        self.strategy
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Подграф, если капсул больше лимита; `None` — граф помещается целиком
+    pub fn sample(&self, graph: &CapsuleGraph) -> Option<GraphSample> {
+        if graph.capsules.len() <= self.limit {
+            return None;
+        }
+        let selected: HashSet<Uuid> = self.select(graph).into_iter().collect();
+        Some(GraphSample {
+            graph: subgraph(graph, &selected),
+            total_capsules: graph.capsules.len(),
+            strategy: self.strategy,
+        })
+    }
+
+    /// Выбранные капсулы в порядке приоритета (детерминированно)
+    pub fn select(&self, graph: &CapsuleGraph) -> Vec<Uuid> {
+        let ranked = ranked_by_centrality(graph);
+        let mut selected = match self.strategy {
+            SamplingStrategy::Centrality => ranked,
+            SamplingStrategy::Stratified => self.stratified(graph, &ranked),
+            SamplingStrategy::ProblemAdjacent => problem_adjacent(graph, &ranked),
+        };
+        selected.truncate(self.limit);
+        selected
+    }
+
+    /// Квоты слоёв методом наибольших остатков; каждый слой получает хотя бы
+    /// один узел, пока лимит это позволяет
+    fn stratified(&self, graph: &CapsuleGraph, ranked: &[Uuid]) -> Vec<Uuid> {
+        let mut layers: BTreeMap<&str, Vec<Uuid>> = BTreeMap::new();
+        for id in ranked {
+            let layer = graph.capsules[id].layer.as_deref().unwrap_or(UNLAYERED);
+            layers.entry(layer).or_default().push(*id);
+        }
+        let total = ranked.len();
+        let mut quotas: Vec<(&str, usize, usize)> = layers
+            .iter()
+            .map(|(name, ids)| {
+                let exact = ids.len() * self.limit;
+                (*name, exact / total, exact % total)
+            })
+            .collect();
+        if self.limit >= quotas.len() {
+            for quota in quotas.iter_mut().filter(|q| q.1 == 0) {
+                quota.1 = 1;
+                quota.2 = 0;
+            }
+        }
+        let mut assigned: usize = quotas.iter().map(|q| q.1).sum();
+        let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+        by_remainder.sort_by_key(|&i| (Reverse(quotas[i].2), quotas[i].0));
+        for i in by_remainder {
+            if assigned >= self.limit {
+                break;
+            }
+            if quotas[i].2 > 0 {
+                quotas[i].1 += 1;
+                assigned += 1;
+            }
+        }
+
+        // по кругу, чтобы при обрезке до лимита слои теряли узлы равномерно
+        let mut picks: Vec<std::slice::Iter<'_, Uuid>> = quotas
+            .iter()
+            .map(|(name, quota, _)| layers[name][..(*quota).min(layers[name].len())].iter())
+            .collect();
+        let mut selected = Vec::with_capacity(self.limit);
+        loop {
+            let before = selected.len();
+            for iter in &mut picks {
+                if let Some(id) = iter.next() {
+                    selected.push(*id);
+                }
+            }
+            if selected.len() == before {
+                break;
+            }
+        }
+        selected
+    }
+}
+
+/// Степень узлов по различным соседям
+fn degrees(graph: &CapsuleGraph) -> HashMap<Uuid, usize> {
+    let mut neighbours: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+    for relation in &graph.relations {
+        if relation.from_id == relation.to_id
+            || !graph.capsules.contains_key(&relation.from_id)
+            || !graph.capsules.contains_key(&relation.to_id)
+        {
+            continue;
+        }
+        neighbours
+            .entry(relation.from_id)
+            .or_default()
+            .insert(relation.to_id);
+        neighbours
+            .entry(relation.to_id)
+            .or_default()
+            .insert(relation.from_id);
+    }
+    neighbours
+        .into_iter()
+        .map(|(id, set)| (id, set.len()))
+        .collect()
+}
+
+/// Все капсулы по убыванию связности, затем сложности; ничьи — по пути и строке
+fn ranked_by_centrality(graph: &CapsuleGraph) -> Vec<Uuid> {
+    let degrees = degrees(graph);
+    let mut capsules: Vec<_> = graph.capsules.values().collect();
+    capsules.sort_by(|a, b| {
+        let da = degrees.get(&a.id).copied().unwrap_or(0);
+        let db = degrees.get(&b.id).copied().unwrap_or(0);
+        db.cmp(&da)
+            .then(b.complexity.cmp(&a.complexity))
+            .then_with(|| a.file_path.cmp(&b.file_path))
+            .then(a.line_start.cmp(&b.line_start))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    capsules.into_iter().map(|c| c.id).collect()
+}
+
+fn severity_weight(level: &Priority) -> u32 {
+    match level {
+        Priority::Critical => 8,
+        Priority::High => 4,
+        Priority::Medium => 2,
+        Priority::Low => 1,
+    }
+}
+
+/// Проблемные капсулы по тяжести, затем их соседи, затем остальные по связности
+fn problem_adjacent(graph: &CapsuleGraph, ranked: &[Uuid]) -> Vec<Uuid> {
+    let position: HashMap<Uuid, usize> =
+        ranked.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut problems: Vec<Uuid> = ranked
+        .iter()
+        .copied()
+        .filter(|id| !graph.capsules[id].warnings.is_empty())
+        .collect();
+    problems.sort_by_key(|id| {
+        let weight: u32 = graph.capsules[id]
+            .warnings
+            .iter()
+            .map(|w| severity_weight(&w.level))
+            .sum();
+        (Reverse(weight), position[id])
+    });
+
+    let problem_set: HashSet<Uuid> = problems.iter().copied().collect();
+    let mut adjacent: HashSet<Uuid> = HashSet::new();
+    for relation in &graph.relations {
+        if problem_set.contains(&relation.from_id) {
+            adjacent.insert(relation.to_id);
+        }
+        if problem_set.contains(&relation.to_id) {
+            adjacent.insert(relation.from_id);
+        }
+    }
+    let neighbours = ranked
+        .iter()
+        .filter(|id| adjacent.contains(id) && !problem_set.contains(id));
+    let rest = ranked
+        .iter()
+        .filter(|id| !adjacent.contains(id) && !problem_set.contains(id));
+    problems
+        .iter()
+        .chain(neighbours)
+        .chain(rest)
+        .copied()
+        .collect()
+}
+
+/// Подграф из выбранных капсул: связи, слои и ссылки капсул только внутри выборки.
+/// Метрики остаются метриками всего графа
+pub(crate) fn subgraph(graph: &CapsuleGraph, selected: &HashSet<Uuid>) -> CapsuleGraph {
+    let capsules = graph
+        .capsules
+        .iter()
+        .filter(|(id, _)| selected.contains(id))
+        .map(|(id, capsule)| {
+            let mut capsule = capsule.clone();
+            capsule.dependencies.retain(|d| selected.contains(d));
+            capsule.dependents.retain(|d| selected.contains(d));
+            (*id, capsule)
+        })
+        .collect();
+    let relations = graph
+        .relations
+        .iter()
+        .filter(|r| selected.contains(&r.from_id) && selected.contains(&r.to_id))
+        .cloned()
+        .collect();
+    let layers = graph
+        .layers
+        .iter()
+        .filter_map(|(name, ids)| {
+            let ids: Vec<Uuid> = ids
+                .iter()
+                .filter(|id| selected.contains(id))
+                .copied()
+                .collect();
+            (!ids.is_empty()).then(|| (name.clone(), ids))
+        })
+        .collect();
+    CapsuleGraph {
+        capsules,
+        relations,
+        layers,
+        metrics: graph.metrics.clone(),
+        created_at: graph.created_at,
+        previous_analysis: graph.previous_analysis.clone(),
+    }
+}
@@ -0,0 +1,93 @@
+// Версионированный формат сохранённых графов: сохранённые ранее графы и базовые
+// линии читаются новыми версиями через цепочку миграций над JSON
+
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde_json::{json, Value};
+
+/// Текущая версия формата сохранённого графа
+pub const GRAPH_FORMAT_VERSION: u32 = 1;
+/// Версия формата JSON-отчёта (`Exporter::export_to_json`)
+pub const REPORT_FORMAT_VERSION: u32 = 1;
+
+/// Сохранённый граф с версией формата и версией ArchLens, записавшей его
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredGraph {
+    pub format_version: u32,
+    pub archlens_version: String,
+    pub graph: CapsuleGraph,
+}
+
+impl StoredGraph {
+    pub fn new(graph: CapsuleGraph) -> Self {
+        Self {
+            format_version: GRAPH_FORMAT_VERSION,
+            archlens_version: env!("CARGO_PKG_VERSION").to_string(),
+            graph,
+        }
+    }
+}
+
+/// Миграция документа с версии `i` на `i + 1`
+type Migration = fn(Value) -> Value;
+
+const MIGRATIONS: [Migration; GRAPH_FORMAT_VERSION as usize] = [migrate_v0_to_v1];
+
+pub fn to_stored_json(graph: &CapsuleGraph) -> Result<String> {
+    serde_json::to_string_pretty(&StoredGraph::new(graph.clone()))
+        .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))
+}
+
+/// Читает граф любой поддерживаемой версии формата, включая «голый» `CapsuleGraph`
+/// версий до введения версионирования
+pub fn from_stored_json(json: &str) -> Result<CapsuleGraph> {
+    let document: Value = serde_json::from_str(json)
+        .map_err(|e| AnalysisError::Parse(format!("stored graph: {e}")))?;
+    let stored: StoredGraph = serde_json::from_value(migrate(document)?)
+        .map_err(|e| AnalysisError::Parse(format!("stored graph: {e}")))?;
+    Ok(stored.graph)
+}
+
+/// Версия документа: без поля `format_version` — формат 0
+pub fn format_version(document: &Value) -> u32 {
+    document
+        .get("format_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v as u32)
+}
+
+/// Приводит документ к текущей версии формата
+pub fn migrate(mut document: Value) -> Result<Value> {
+    let version = format_version(&document);
+    if version > GRAPH_FORMAT_VERSION {
+        return Err(AnalysisError::Parse(format!(
+            "stored graph format {version} is newer than supported {GRAPH_FORMAT_VERSION}; upgrade ArchLens"
+        )));
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        document = migration(document);
+    }
+    Ok(document)
+}
+
+/// 0 → 1: граф оборачивается в конверт с версией; коллекции, появившиеся
+/// в капсулах позже, получают пустые значения
+fn migrate_v0_to_v1(mut graph: Value) -> Value {
+    if let Some(capsules) = graph.get_mut("capsules").and_then(Value::as_object_mut) {
+        for capsule in capsules.values_mut().filter_map(Value::as_object_mut) {
+            for key in ["dependencies", "dependents", "warnings", "tags"] {
+                capsule.entry(key).or_insert_with(|| json!([]));
+            }
+            capsule.entry("metadata").or_insert_with(|| json!({}));
+            capsule.entry("quality_score").or_insert(json!(0.0));
+        }
+    }
+    if let Some(graph) = graph.as_object_mut() {
+        graph.entry("layers").or_insert_with(|| json!({}));
+        graph.entry("relations").or_insert_with(|| json!([]));
+    }
+    json!({
+        "format_version": 1,
+        "archlens_version": "unknown",
+        "graph": graph,
+    })
+}
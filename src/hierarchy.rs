@@ -0,0 +1,439 @@
+// Иерархия капсул: каталог → файл → тип → функция. Родитель капсулы берётся
+// из AST (`parent_id` в метаданных), иначе — самый вложенный тип того же файла,
+// содержащий капсулу. Узлы каталогов и файлов агрегируют метрики потомков;
+// граф можно свернуть до уровня для диаграмм
+
+use crate::dsm::common_root;
+use crate::types::{
+    Capsule, CapsuleGraph, CapsuleRelation, CapsuleStatus, CapsuleType, Priority,
+    CAPSULE_ID_NAMESPACE,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Ключ метаданных: id родительского элемента AST
+pub const PARENT_KEY: &str = "parent_id";
+/// Ключ метаданных свёрнутой капсулы: уровень иерархии
+pub const LEVEL_KEY: &str = "hierarchy_level";
+/// Ключ метаданных свёрнутой капсулы: сколько капсул в неё вошло
+pub const COLLAPSED_KEY: &str = "collapsed_capsules";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HierarchyLevel {
+    Directory,
+    File,
+    Type,
+    Function,
+}
+
+impl HierarchyLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HierarchyLevel::Directory => "directory",
+            HierarchyLevel::File => "file",
+            HierarchyLevel::Type => "type",
+            HierarchyLevel::Function => "function",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "directory" | "dir" => Some(HierarchyLevel::Directory),
+            "file" => Some(HierarchyLevel::File),
+            "type" => Some(HierarchyLevel::Type),
+            "function" | "fn" => Some(HierarchyLevel::Function),
+            _ => None,
+        }
+    }
+
+    /// Уровень капсулы: типы и модули — контейнеры, остальное — функции
+    pub fn of(capsule: &Capsule) -> Self {
+        match capsule.capsule_type {
+            CapsuleType::Module
+            | CapsuleType::Struct
+            | CapsuleType::Enum
+            | CapsuleType::Interface
+            | CapsuleType::Class => HierarchyLevel::Type,
+            _ => HierarchyLevel::Function,
+        }
+    }
+}
+
+/// Метрики узла вместе со всеми потомками
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Rollup {
+    pub capsules: usize,
+    pub complexity: u32,
+    pub max_complexity: u32,
+    pub warnings: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HierarchyNode {
+    /// `dir:<путь>`, `file:<путь>` или id капсулы
+    pub key: String,
+    pub name: String,
+    pub level: HierarchyLevel,
+    /// Капсула узла; у каталогов и файлов её нет
+    pub capsule: Option<Uuid>,
+    pub parent: Option<String>,
+    pub children: Vec<String>,
+    pub rollup: Rollup,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CapsuleHierarchy {
+    pub root: PathBuf,
+    pub nodes: BTreeMap<String, HierarchyNode>,
+}
+
+fn dir_key(relative: &Path) -> String {
+    format!("dir:{}", display(relative))
+}
+
+fn file_key(relative: &Path) -> String {
+    format!("file:{}", display(relative))
+}
+
+fn display(relative: &Path) -> String {
+    let shown = relative.to_string_lossy().replace('\\', "/");
+    if shown.is_empty() {
+        ".".to_string()
+    } else {
+        shown
+    }
+}
+
+impl CapsuleHierarchy {
+    pub fn from_graph(graph: &CapsuleGraph) -> Self {
+        let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        let mut hierarchy = Self {
+            root: root.clone(),
+            nodes: BTreeMap::new(),
+        };
+
+        let mut capsules: Vec<&Capsule> = graph.capsules.values().collect();
+        capsules.sort_by(|a, b| {
+            (&a.file_path, a.line_start, &a.name).cmp(&(&b.file_path, b.line_start, &b.name))
+        });
+        let mut by_file: HashMap<&Path, Vec<&Capsule>> = HashMap::new();
+        for capsule in &capsules {
+            by_file
+                .entry(capsule.file_path.as_path())
+                .or_default()
+                .push(capsule);
+        }
+
+        for capsule in &capsules {
+            let relative = capsule
+                .file_path
+                .strip_prefix(&root)
+                .unwrap_or(&capsule.file_path);
+            let file = hierarchy.ensure_file(relative);
+            let parent = parent_capsule(graph, capsule, &by_file[capsule.file_path.as_path()])
+                .map(|p| p.id.to_string())
+                .unwrap_or(file);
+            hierarchy.nodes.insert(
+                capsule.id.to_string(),
+                HierarchyNode {
+                    key: capsule.id.to_string(),
+                    name: capsule.name.clone(),
+                    level: HierarchyLevel::of(capsule),
+                    capsule: Some(capsule.id),
+                    parent: Some(parent),
+                    children: Vec::new(),
+                    rollup: Rollup::default(),
+                },
+            );
+        }
+
+        // дети и агрегаты: каждая капсула учитывается в себе и во всех предках
+        let links: Vec<(String, String)> = hierarchy
+            .nodes
+            .values()
+            .filter_map(|n| Some((n.parent.clone()?, n.key.clone())))
+            .collect();
+        for (parent, child) in links {
+            if let Some(node) = hierarchy.nodes.get_mut(&parent) {
+                node.children.push(child);
+            }
+        }
+        let depth_limit = hierarchy.nodes.len();
+        for capsule in &capsules {
+            let mut key = Some(capsule.id.to_string());
+            // защита от циклов в некорректно размеченных `parent_id`
+            for _ in 0..depth_limit {
+                let Some(current) = key else {
+                    break;
+                };
+                let Some(node) = hierarchy.nodes.get_mut(&current) else {
+                    break;
+                };
+                node.rollup.capsules += 1;
+                node.rollup.complexity += capsule.complexity;
+                node.rollup.max_complexity = node.rollup.max_complexity.max(capsule.complexity);
+                node.rollup.warnings += capsule.warnings.len();
+                key = node.parent.clone();
+            }
+        }
+        hierarchy
+    }
+
+    /// Узлы каталогов от корня до файла и узел файла; возвращает ключ файла
+    fn ensure_file(&mut self, relative: &Path) -> String {
+        let key = file_key(relative);
+        if self.nodes.contains_key(&key) {
+            return key;
+        }
+        let dir = relative.parent().unwrap_or(Path::new(""));
+        let parent = self.ensure_dir(dir);
+        self.nodes.insert(
+            key.clone(),
+            HierarchyNode {
+                key: key.clone(),
+                name: display(relative),
+                level: HierarchyLevel::File,
+                capsule: None,
+                parent: Some(parent),
+                children: Vec::new(),
+                rollup: Rollup::default(),
+            },
+        );
+        key
+    }
+
+    fn ensure_dir(&mut self, relative: &Path) -> String {
+        let key = dir_key(relative);
+        if self.nodes.contains_key(&key) {
+            return key;
+        }
+        // корень проекта — отдельный узел только для файлов прямо в нём
+        let parent = relative
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| self.ensure_dir(p));
+        self.nodes.insert(
+            key.clone(),
+            HierarchyNode {
+                key: key.clone(),
+                name: display(relative),
+                level: HierarchyLevel::Directory,
+                capsule: None,
+                parent,
+                children: Vec::new(),
+                rollup: Rollup::default(),
+            },
+        );
+        key
+    }
+
+    /// Узлы верхнего уровня
+    pub fn roots(&self) -> impl Iterator<Item = &HierarchyNode> {
+        self.nodes.values().filter(|n| n.parent.is_none())
+    }
+
+    /// Узлы уровня по убыванию суммарной сложности
+    pub fn at_level(&self, level: HierarchyLevel) -> Vec<&HierarchyNode> {
+        let mut nodes: Vec<&HierarchyNode> =
+            self.nodes.values().filter(|n| n.level == level).collect();
+        nodes.sort_by(|a, b| {
+            b.rollup
+                .complexity
+                .cmp(&a.rollup.complexity)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        nodes
+    }
+
+    /// Ближайший узел капсулы (сама капсула или предок) не глубже `level`
+    pub fn representative(&self, capsule: Uuid, level: HierarchyLevel) -> Option<&HierarchyNode> {
+        let mut node = self.nodes.get(&capsule.to_string())?;
+        for _ in 0..self.nodes.len() {
+            if node.level <= level {
+                return Some(node);
+            }
+            node = self.nodes.get(node.parent.as_ref()?)?;
+        }
+        None
+    }
+
+    /// Граф, свёрнутый до `level`: капсулы глубже уровня входят в узел-предка,
+    /// каталоги и файлы становятся капсулами-модулями, связи между свёрнутыми
+    /// узлами объединяются, связи внутри узла отбрасываются
+    pub fn collapse(&self, graph: &CapsuleGraph, level: HierarchyLevel) -> CapsuleGraph {
+        if level == HierarchyLevel::Function {
+            return graph.clone();
+        }
+        let mut ids: HashMap<&str, Uuid> = HashMap::new();
+        let mut capsules: HashMap<Uuid, Capsule> = HashMap::new();
+        let mut mapping: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut members: HashMap<Uuid, Vec<&Capsule>> = HashMap::new();
+        for capsule in graph.capsules.values() {
+            let Some(node) = self.representative(capsule.id, level) else {
+                continue;
+            };
+            let id = *ids.entry(node.key.as_str()).or_insert_with(|| {
+                node.capsule
+                    .unwrap_or_else(|| Uuid::new_v5(&CAPSULE_ID_NAMESPACE, node.key.as_bytes()))
+            });
+            mapping.insert(capsule.id, id);
+            members.entry(id).or_default().push(capsule);
+            if node.capsule.is_none() {
+                capsules
+                    .entry(id)
+                    .or_insert_with(|| self.synthetic_capsule(id, node));
+            } else if let Some(original) = graph.capsules.get(&id) {
+                capsules.entry(id).or_insert_with(|| original.clone());
+            }
+        }
+        for (id, group) in &members {
+            let Some(capsule) = capsules.get_mut(id) else {
+                continue;
+            };
+            if capsule.metadata.contains_key(LEVEL_KEY) {
+                capsule.layer = majority_layer(group);
+                capsule.line_end = group.iter().map(|c| c.line_end).max().unwrap_or(1);
+            }
+            capsule
+                .metadata
+                .insert(COLLAPSED_KEY.to_string(), group.len().to_string());
+        }
+
+        let mut merged: BTreeMap<(Uuid, Uuid, crate::types::RelationType), CapsuleRelation> =
+            BTreeMap::new();
+        for relation in &graph.relations {
+            let (Some(&from), Some(&to)) =
+                (mapping.get(&relation.from_id), mapping.get(&relation.to_id))
+            else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            merged
+                .entry((from, to, relation.relation_type))
+                .and_modify(|r| r.strength = r.strength.max(relation.strength))
+                .or_insert_with(|| CapsuleRelation {
+                    from_id: from,
+                    to_id: to,
+                    ..relation.clone()
+                });
+        }
+        let relations: Vec<CapsuleRelation> = merged.into_values().collect();
+
+        for capsule in capsules.values_mut() {
+            capsule.dependencies.clear();
+            capsule.dependents.clear();
+        }
+        let mut seen = HashSet::new();
+        for relation in &relations {
+            if !seen.insert((relation.from_id, relation.to_id)) {
+                continue;
+            }
+            if let Some(c) = capsules.get_mut(&relation.from_id) {
+                c.dependencies.push(relation.to_id);
+            }
+            if let Some(c) = capsules.get_mut(&relation.to_id) {
+                c.dependents.push(relation.from_id);
+            }
+        }
+        let mut layers: HashMap<String, Vec<Uuid>> = HashMap::new();
+        for capsule in capsules.values() {
+            if let Some(layer) = &capsule.layer {
+                layers.entry(layer.clone()).or_default().push(capsule.id);
+            }
+        }
+        CapsuleGraph {
+            capsules,
+            relations,
+            layers,
+            metrics: graph.metrics.clone(),
+            created_at: graph.created_at,
+            previous_analysis: graph.previous_analysis.clone(),
+        }
+    }
+
+    fn synthetic_capsule(&self, id: Uuid, node: &HierarchyNode) -> Capsule {
+        let relative = node
+            .key
+            .split_once(':')
+            .map(|(_, path)| path)
+            .unwrap_or_default();
+        let mut metadata = HashMap::new();
+        metadata.insert(LEVEL_KEY.to_string(), node.level.as_str().to_string());
+        Capsule {
+            id,
+            name: node.name.clone(),
+            capsule_type: CapsuleType::Module,
+            file_path: self.root.join(relative),
+            line_start: 1,
+            line_end: 1,
+            size: node.rollup.capsules,
+            complexity: node.rollup.complexity,
+            dependencies: Vec::new(),
+            layer: None,
+            summary: None,
+            description: Some(format!(
+                "{} {}: {} components",
+                node.level.as_str(),
+                node.name,
+                node.rollup.capsules
+            )),
+            warnings: Vec::new(),
+            status: CapsuleStatus::Active,
+            priority: Priority::Medium,
+            tags: vec![node.level.as_str().to_string()],
+            metadata,
+            quality_score: 0.0,
+            slogan: None,
+            dependents: Vec::new(),
+            created_at: None,
+        }
+    }
+}
+
+/// Родитель из AST, если он тоже капсула; иначе самый вложенный тип файла,
+/// строки которого охватывают капсулу
+fn parent_capsule<'a>(
+    graph: &'a CapsuleGraph,
+    capsule: &Capsule,
+    same_file: &[&'a Capsule],
+) -> Option<&'a Capsule> {
+    let declared = capsule
+        .metadata
+        .get(PARENT_KEY)
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .filter(|id| *id != capsule.id)
+        .and_then(|id| graph.capsules.get(&id));
+    if declared.is_some() {
+        return declared;
+    }
+    same_file
+        .iter()
+        .filter(|c| c.id != capsule.id && HierarchyLevel::of(c) == HierarchyLevel::Type)
+        .filter(|c| {
+            let span = c.line_end.max(c.line_start) - c.line_start;
+            let own = capsule.line_end.max(capsule.line_start) - capsule.line_start;
+            c.line_start <= capsule.line_start
+                && capsule.line_end <= c.line_end
+                && (span > own || (span == own && c.line_start < capsule.line_start))
+        })
+        .min_by_key(|c| c.line_end.max(c.line_start) - c.line_start)
+        .copied()
+}
+
+fn majority_layer(group: &[&Capsule]) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for capsule in group {
+        if let Some(layer) = &capsule.layer {
+            *counts.entry(layer.as_str()).or_insert(0) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(layer, _)| layer.to_string())
+}
@@ -0,0 +1,264 @@
+// Локализация находок: описания и рекомендации предупреждений/запахов кода переводятся
+// по каталогам сообщений (`locales/<lang>.json`). Анализаторы по-прежнему пишут текст
+// на исходном языке; локализатор узнаёт сообщение по шаблону любого известного каталога
+// (`{0}`, `{1}` — подставляемые значения) и подставляет значения в шаблон целевого языка.
+// Каталоги проекта из `.archlens/locales` дополняют и переопределяют встроенные
+
+use crate::enrichment::EnrichmentResult;
+use crate::types::{AnalysisError, AnalysisWarning, CapsuleGraph, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Переменная окружения с языком отчётов по умолчанию
+pub const LANG_ENV: &str = "ARCHLENS_LANG";
+
+/// Встроенные каталоги: (код языка, JSON)
+const BUILTIN_CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("ru", include_str!("../locales/ru.json")),
+    ("es", include_str!("../locales/es.json")),
+    ("de", include_str!("../locales/de.json")),
+];
+
+/// Каталог сообщений одного языка: ключ -> шаблон
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageCatalog {
+    pub language: String,
+    #[serde(default)]
+    pub name: String,
+    pub messages: BTreeMap<String, String>,
+}
+
+impl MessageCatalog {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid message catalog: {e}")))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content)
+    }
+
+    pub fn builtin(language: &str) -> Option<Self> {
+        BUILTIN_CATALOGS
+            .iter()
+            .find(|(code, _)| *code == language)
+            .and_then(|(_, json)| Self::from_json(json).ok())
+    }
+
+    pub fn builtin_languages() -> Vec<&'static str> {
+        BUILTIN_CATALOGS.iter().map(|(code, _)| *code).collect()
+    }
+
+    /// Расхождения с эталонным каталогом: отсутствующие и лишние ключи,
+    /// несовпадающие наборы подстановок. Пустой список — каталог полный
+    pub fn validate_against(&self, reference: &MessageCatalog) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (key, template) in &reference.messages {
+            match self.messages.get(key) {
+                None => problems.push(format!("missing key '{}'", key)),
+                Some(own) if placeholders(own) != placeholders(template) => problems.push(format!(
+                    "placeholders of '{}' differ from '{}'",
+                    key, reference.language
+                )),
+                Some(_) => {}
+            }
+        }
+        for key in self.messages.keys() {
+            if !reference.messages.contains_key(key) {
+                problems.push(format!("unknown key '{}'", key));
+            }
+        }
+        problems
+    }
+
+    /// Ключи каталога поверх текущих (каталог проекта переопределяет встроенный)
+    fn merge(&mut self, other: MessageCatalog) {
+        if !other.name.is_empty() {
+            self.name = other.name;
+        }
+        self.messages.extend(other.messages);
+    }
+}
+
+/// Переводит находки на язык целевого каталога
+#[derive(Debug)]
+pub struct Localizer {
+    target: MessageCatalog,
+    /// Шаблоны без подстановок: текст -> ключ
+    exact: HashMap<String, String>,
+    /// Шаблоны с подстановками: (regex, номера подстановок по порядку, ключ)
+    patterns: Vec<(Regex, Vec<usize>, String)>,
+}
+
+impl Localizer {
+    /// `sources` — каталоги, по шаблонам которых узнаются исходные сообщения
+    pub fn new(target: MessageCatalog, sources: &[MessageCatalog]) -> Self {
+        let mut exact = HashMap::new();
+        let mut patterns: Vec<(usize, Regex, Vec<usize>, String)> = Vec::new();
+        for catalog in sources.iter().chain(std::iter::once(&target)) {
+            for (key, template) in &catalog.messages {
+                let order: Vec<usize> = placeholder_regex()
+                    .captures_iter(template)
+                    .filter_map(|c| c[1].parse().ok())
+                    .collect();
+                if order.is_empty() {
+                    exact.entry(template.clone()).or_insert_with(|| key.clone());
+                    continue;
+                }
+                let literal = placeholder_regex().split(template).map(str::len).sum();
+                let pattern = placeholder_regex()
+                    .split(template)
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join("(.+?)");
+                if let Ok(regex) = Regex::new(&format!("^{}$", pattern)) {
+                    patterns.push((literal, regex, order, key.clone()));
+                }
+            }
+        }
+        // более конкретные шаблоны (больше постоянного текста) проверяются первыми
+        patterns.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.3.cmp(&b.3)));
+        Self {
+            target,
+            exact,
+            patterns: patterns
+                .into_iter()
+                .map(|(_, regex, order, key)| (regex, order, key))
+                .collect(),
+        }
+    }
+
+    /// Встроенные каталоги + каталоги проекта (`.archlens/locales/*.json`)
+    pub fn for_language(language: &str, project_root: Option<&Path>) -> Result<Self> {
+        let mut sources: Vec<MessageCatalog> = Self::builtin_catalogs();
+        let project: Vec<MessageCatalog> = project_root
+            .map(|root| Self::project_catalogs(&root.join(".archlens").join("locales")))
+            .unwrap_or_default();
+
+        let mut target = MessageCatalog::builtin(language);
+        for catalog in project.iter().filter(|c| c.language == language) {
+            match target.as_mut() {
+                Some(target) => target.merge(catalog.clone()),
+                None => target = Some(catalog.clone()),
+            }
+        }
+        let Some(target) = target else {
+            let mut available: BTreeSet<String> = MessageCatalog::builtin_languages()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            available.extend(project.iter().map(|c| c.language.clone()));
+            return Err(AnalysisError::GenericError(format!(
+                "Unknown report language '{}' (available: {})",
+                language,
+                available.into_iter().collect::<Vec<_>>().join(", ")
+            )));
+        };
+        sources.extend(project);
+        Ok(Self::new(target, &sources))
+    }
+
+    pub fn language(&self) -> &str {
+        &self.target.language
+    }
+
+    /// Перевод одного сообщения; неизвестные сообщения возвращаются как есть
+    pub fn localize(&self, text: &str) -> String {
+        if let Some(template) = self
+            .exact
+            .get(text)
+            .and_then(|key| self.target.messages.get(key))
+        {
+            return template.clone();
+        }
+        for (regex, order, key) in &self.patterns {
+            let Some(captures) = regex.captures(text) else {
+                continue;
+            };
+            let Some(template) = self.target.messages.get(key) else {
+                return text.to_string();
+            };
+            let mut values: HashMap<usize, &str> = HashMap::new();
+            for (group, index) in order.iter().enumerate() {
+                if let Some(value) = captures.get(group + 1) {
+                    values.insert(*index, value.as_str());
+                }
+            }
+            return placeholder_regex()
+                .replace_all(template, |c: &regex::Captures| {
+                    c[1].parse::<usize>()
+                        .ok()
+                        .and_then(|i| values.get(&i))
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| c[0].to_string())
+                })
+                .into_owned();
+        }
+        text.to_string()
+    }
+
+    pub fn localize_warning(&self, warning: &mut AnalysisWarning) {
+        warning.message = self.localize(&warning.message);
+        if let Some(suggestion) = warning.suggestion.as_mut() {
+            *suggestion = self.localize(suggestion);
+        }
+    }
+
+    /// Предупреждения всех капсул графа
+    pub fn localize_graph(&self, graph: &mut CapsuleGraph) {
+        for capsule in graph.capsules.values_mut() {
+            for warning in &mut capsule.warnings {
+                self.localize_warning(warning);
+            }
+        }
+    }
+
+    /// Описания и рекомендации запахов кода
+    pub fn localize_enrichment(&self, result: &mut EnrichmentResult) {
+        for smell in &mut result.code_smells {
+            smell.description = self.localize(&smell.description);
+            smell.suggestion = self.localize(&smell.suggestion);
+        }
+    }
+
+    fn builtin_catalogs() -> Vec<MessageCatalog> {
+        MessageCatalog::builtin_languages()
+            .into_iter()
+            .filter_map(MessageCatalog::builtin)
+            .collect()
+    }
+
+    fn project_catalogs(dir: &Path) -> Vec<MessageCatalog> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        paths
+            .iter()
+            .filter_map(|p| MessageCatalog::load(p).ok())
+            .collect()
+    }
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{(\d+)\}").expect("valid placeholder regex"))
+}
+
+fn placeholders(template: &str) -> BTreeSet<usize> {
+    placeholder_regex()
+        .captures_iter(template)
+        .filter_map(|c| c[1].parse().ok())
+        .collect()
+}
@@ -38,6 +38,9 @@ pub mod file_scanner;
 /// Abstract Syntax Tree parsing
 pub mod parser_ast;
 
+/// Bounded LRU cache of parsed files used by the AST parser
+pub mod parser_cache;
+
 /// Metadata extraction from files
 pub mod metadata_extractor;
 
@@ -68,6 +71,105 @@ pub mod diff_analyzer;
 /// Advanced metrics calculation
 pub mod advanced_metrics;
 
+/// Dependency freshness against local registry caches
+pub mod dependency_freshness;
+
+/// Overlays of external reports onto the capsule graph
+pub mod overlays;
+
+/// Architecture release notes between two git revisions
+pub mod release_report;
+
+/// Three-way architecture diff for long-lived branches
+pub mod branch_diff;
+
+/// Merge-conflict risk across active branches
+pub mod merge_risk;
+
+/// Prioritized remediation backlog for sprint planning
+pub mod remediation_backlog;
+
+/// Public API surface (exported symbols) per capsule and its export section
+pub mod api_surface;
+/// Append-only audit log of analysis and export invocations
+pub mod audit;
+/// Change-frequency forecast per capsule and erosion early warning from git history
+pub mod change_forecast;
+/// Code origin: test and auxiliary (bench/example) capsules kept out of core metrics
+pub mod code_origin;
+/// Data-model (ORM/schema) detection and ER diagrams
+pub mod data_models;
+/// Links between architecture decision records and capsules, checked on diff
+pub mod decision_links;
+/// Cached, stable diagram layouts keyed by graph fingerprint
+pub mod diagram_layout;
+/// Dependency structure matrix (layers × layers, modules × modules) with cycle highlighting
+pub mod dsm;
+/// Declared entry points, reachability from them and unreachable capsules
+pub mod entry_points;
+/// Rust error propagation (`?` chains) and broad error types in public APIs
+pub mod error_propagation;
+/// Deterministic single-threaded execution mode (`--single-thread`)
+pub mod execution;
+/// Export size estimation per detail level (`export --dry-run`)
+pub mod export_estimate;
+/// Third-party packages from manifests as external capsules with import edges
+pub mod external_deps;
+/// Per-component and per-category caps on findings listed in reports
+pub mod finding_caps;
+/// Git command runner shared by history-based analyses
+pub mod git;
+/// Portable `.archlens.graph` archives for offline review (`archlens view`)
+pub mod graph_archive;
+/// SQLite graph snapshots (`.archlens/graph.db`) reused by MCP and diff instead of re-analysis
+pub mod graph_db;
+/// Local question answering over the graph (`archlens ask`)
+pub mod graph_query;
+/// Capsule sampling strategies for diagrams and exports of huge graphs
+pub mod graph_sampling;
+/// Versioned persisted graphs with migrations from older formats
+pub mod graph_store;
+/// Directory → file → type → function capsule hierarchy with metric roll-ups
+pub mod hierarchy;
+/// Localization of findings (warnings, suggestions, code smells) via message catalogs
+pub mod i18n;
+/// Optional LLM summaries via an OpenAI-compatible endpoint (off by default)
+pub mod llm;
+/// Per-capsule metric history (`.archlens/history.jsonl`) and sparkline series
+pub mod metric_history;
+/// MODULE.md generation for top-level modules
+pub mod module_docs;
+/// Code ownership from git blame: bus factor per module and layer
+pub mod ownership;
+/// Package/namespace capsules for Java, Kotlin, Scala and C# sources
+pub mod packages;
+/// Per-file panic isolation for parsing and enrichment; failed files go to the coverage report
+pub mod panic_isolation;
+/// Accessible plain-text rendering of markdown reports
+pub mod plain_report;
+/// Project settings in `.archlens.toml`, proposed and written by `archlens init`
+pub mod project_config;
+/// Prompt-pack export: summary, code excerpts and prompts for external AI review
+pub mod prompt_pack;
+/// Redaction rules applied to reports before they leave the organization
+pub mod redaction;
+/// Similarity to reference architectures (hexagonal, layered, MVC, clean, microkernel)
+pub mod reference_architectures;
+/// Source locations (`file:line`) of relations for exports
+pub mod relation_locations;
+/// Shallow clones of remote git repositories passed instead of a project path
+pub mod remote_repo;
+/// Self-analysis of ArchLens sources against pinned capsule and cycle expectations
+pub mod self_check;
+/// Stable FNV-1a hash for cache keys and persisted fingerprints
+pub mod stable_hash;
+/// State-machine detection (enum match/switch, XState) and state diagrams
+pub mod state_machines;
+/// TODO/FIXME markers per capsule and layer, aged via git blame
+pub mod todo_report;
+/// `pub` items unused outside their module or crate, with narrower visibility suggestions
+pub mod visibility;
+
 /// Command handling and execution
 pub mod commands;
 
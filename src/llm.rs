@@ -0,0 +1,381 @@
+// Необязательные LLM-сводки: описания капсул на естественном языке и общий обзор
+// архитектуры через OpenAI-совместимый endpoint (`/chat/completions`).
+// По умолчанию выключено: без `.archlens/llm.json` с `"enabled": true` сетевых
+// запросов нет. Ответы кэшируются по хэшу содержимого в `.archlens/llm-cache.json`
+
+use crate::redaction::Redactor;
+use crate::stable_hash::Fnv;
+use crate::types::{AnalysisError, Capsule, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Ключ метаданных капсулы со сводкой от LLM
+pub const LLM_SUMMARY_KEY: &str = "llm_summary";
+
+/// Строк исходника капсулы в запросе
+const SOURCE_LINES_LIMIT: usize = 80;
+
+const SYSTEM_PROMPT: &str = "You are a senior software architect. Answer concisely in plain prose, without markdown headings.";
+
+/// Конфигурация `.archlens/llm.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Базовый URL API, например `https://api.openai.com/v1` или `http://localhost:11434/v1`
+    pub endpoint: String,
+    pub model: String,
+    /// Переменная окружения с API-ключом (сам ключ в конфиг не пишется)
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+    /// Сколько самых сложных капсул описывать
+    #[serde(default = "default_max_capsules")]
+    pub max_capsules: usize,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+
+fn default_max_capsules() -> usize {
+    30
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl LlmConfig {
+    pub fn config_path(project_root: &Path) -> PathBuf {
+        project_root.join(".archlens").join("llm.json")
+    }
+
+    /// Конфиг проекта; `None`, если файла нет или интеграция выключена
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = Self::config_path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let config: Self = serde_json::from_str(&content)
+            .map_err(|e| AnalysisError::ParsingError(format!("{}: {}", path.display(), e)))?;
+        Ok(config.enabled.then_some(config))
+    }
+
+    fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.endpoint.trim_end_matches('/'))
+    }
+}
+
+/// Транспорт HTTP-запросов к endpoint (подменяется в тестах)
+pub trait LlmTransport {
+    fn post_json(
+        &self,
+        url: &str,
+        api_key: Option<&str>,
+        body: &str,
+        timeout_secs: u64,
+    ) -> Result<String>;
+}
+
+/// Запросы через системный `curl` — без дополнительных сетевых зависимостей
+#[derive(Debug, Default)]
+pub struct CurlTransport;
+
+impl LlmTransport for CurlTransport {
+    fn post_json(
+        &self,
+        url: &str,
+        api_key: Option<&str>,
+        body: &str,
+        timeout_secs: u64,
+    ) -> Result<String> {
+        let mut command = Command::new("curl");
+        command
+            .args(["-sS", "--fail-with-body", "-X", "POST"])
+            .args(["-H", "Content-Type: application/json"])
+            .args(["--max-time", &timeout_secs.to_string()]);
+        let Some(key) = api_key else {
+            command.args(["--data-binary", "@-"]).arg(url);
+            return run_curl(command, body);
+        };
+        // ключ и тело передаются конфигом curl через stdin, а не аргументами процесса
+        command.args(["-K", "-"]).arg(url);
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let input = format!(
+            "header = \"Authorization: Bearer {}\"\ndata-binary = \"{}\"\n",
+            escape(key),
+            escape(body)
+        );
+        run_curl(command, &input)
+    }
+}
+
+fn run_curl(mut command: Command, input: &str) -> Result<String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AnalysisError::IoError(format!("curl: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(AnalysisError::GenericError(format!(
+            "LLM request failed: {}{}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+            String::from_utf8_lossy(&output.stdout).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Итог прогона сводок
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LlmReport {
+    pub overview: Option<String>,
+    pub summarized: usize,
+    pub cached: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+/// Генератор сводок с кэшем ответов по хэшу запроса
+pub struct LlmSummarizer {
+    config: LlmConfig,
+    transport: Box<dyn LlmTransport>,
+    cache_path: Option<PathBuf>,
+    cache: BTreeMap<String, String>,
+    redactor: Option<Redactor>,
+}
+
+impl std::fmt::Debug for LlmSummarizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmSummarizer")
+            .field("config", &self.config)
+            .field("cache_path", &self.cache_path)
+            .field("cached", &self.cache.len())
+            .field("redacted", &self.redactor.is_some())
+            .finish()
+    }
+}
+
+impl LlmSummarizer {
+    pub fn new(config: LlmConfig) -> Self {
+        Self {
+            config,
+            transport: Box::new(CurlTransport),
+            cache_path: None,
+            cache: BTreeMap::new(),
+            redactor: None,
+        }
+    }
+
+    pub fn with_transport(mut self, transport: Box<dyn LlmTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Правила редактирования применяются к каждому запросу до отправки
+    pub fn with_redactor(mut self, redactor: Option<Redactor>) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Кэш ответов в `.archlens/llm-cache.json` проекта
+    pub fn with_project_cache(mut self, project_root: &Path) -> Self {
+        let path = project_root.join(".archlens").join("llm-cache.json");
+        self.cache = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        self.cache_path = Some(path);
+        self
+    }
+
+    /// Сводки для самых сложных капсул (в метаданные `llm_summary`) и общий обзор.
+    /// Ошибки запросов не прерывают экспорт — они собираются в отчёт
+    pub fn summarize_graph(&mut self, graph: &mut CapsuleGraph) -> LlmReport {
+        let mut report = LlmReport::default();
+        let mut ranked: Vec<&Capsule> = graph.capsules.values().collect();
+        ranked.sort_by_key(|c| (Reverse(c.complexity), c.file_path.clone(), c.name.clone()));
+        let selected: Vec<_> = ranked
+            .into_iter()
+            .take(self.config.max_capsules)
+            .map(|c| (c.id, capsule_prompt(c)))
+            .collect();
+
+        let mut summaries: Vec<(String, String)> = Vec::new();
+        for (id, prompt) in selected {
+            match self.complete(&prompt, &mut report) {
+                Ok(summary) => {
+                    if let Some(capsule) = graph.capsules.get_mut(&id) {
+                        summaries.push((capsule.name.clone(), summary.clone()));
+                        capsule
+                            .metadata
+                            .insert(LLM_SUMMARY_KEY.to_string(), summary);
+                    }
+                }
+                Err(err) => {
+                    // endpoint недоступен — не ждём таймаут на каждой капсуле
+                    report.failed += 1;
+                    report.errors.push(err.to_string());
+                    break;
+                }
+            }
+        }
+
+        if report.failed == 0 {
+            let prompt = overview_prompt(graph, &summaries);
+            match self.complete(&prompt, &mut report) {
+                Ok(overview) => report.overview = Some(overview),
+                Err(err) => {
+                    report.failed += 1;
+                    report.errors.push(err.to_string());
+                }
+            }
+        }
+        if let Err(err) = self.save_cache() {
+            report.errors.push(err.to_string());
+        }
+        report
+    }
+
+    fn complete(&mut self, prompt: &str, report: &mut LlmReport) -> Result<String> {
+        let redacted = self.redactor.as_ref().map(|r| r.redact_text(prompt));
+        let prompt = redacted.as_deref().unwrap_or(prompt);
+        let key = self.cache_key(prompt);
+        if let Some(answer) = self.cache.get(&key) {
+            report.cached += 1;
+            return Ok(answer.clone());
+        }
+        let body = serde_json::json!({
+            "model": self.config.model,
+            "temperature": 0.2,
+            "messages": [
+                {"role": "system", "content": SYSTEM_PROMPT},
+                {"role": "user", "content": prompt},
+            ],
+        })
+        .to_string();
+        let api_key = std::env::var(&self.config.api_key_env)
+            .ok()
+            .filter(|k| !k.is_empty());
+        let response = self.transport.post_json(
+            &self.config.completions_url(),
+            api_key.as_deref(),
+            &body,
+            self.config.timeout_secs,
+        )?;
+        let answer = parse_completion(&response)?;
+        self.cache.insert(key, answer.clone());
+        report.summarized += 1;
+        Ok(answer)
+    }
+
+    /// Хэш модели и текста запроса: изменение кода капсулы инвалидирует ответ
+    fn cache_key(&self, prompt: &str) -> String {
+        let mut hasher = Fnv::new();
+        hasher.write_str(&self.config.model);
+        hasher.write_str(prompt);
+        hasher.hex()
+    }
+
+    fn save_cache(&self) -> Result<()> {
+        let Some(path) = &self.cache_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&self.cache)
+            .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// `choices[0].message.content` ответа chat/completions
+fn parse_completion(response: &str) -> Result<String> {
+    let value: serde_json::Value = serde_json::from_str(response)
+        .map_err(|e| AnalysisError::ParsingError(format!("LLM response: {e}")))?;
+    value["choices"][0]["message"]["content"]
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| {
+            let detail = value["error"]["message"].as_str().unwrap_or("no content");
+            AnalysisError::GenericError(format!("LLM response: {detail}"))
+        })
+}
+
+fn capsule_prompt(capsule: &Capsule) -> String {
+    let source = fs::read_to_string(&capsule.file_path)
+        .map(|content| {
+            content
+                .lines()
+                .skip(capsule.line_start.saturating_sub(1))
+                .take(
+                    (capsule.line_end.saturating_sub(capsule.line_start) + 1)
+                        .min(SOURCE_LINES_LIMIT),
+                )
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default();
+    let mut prompt = format!(
+        "Summarize in 1-2 sentences what the {:?} `{}` ({}:{}) is responsible for.\n",
+        capsule.capsule_type,
+        capsule.name,
+        capsule.file_path.display(),
+        capsule.line_start
+    );
+    if !capsule.warnings.is_empty() {
+        let warnings: Vec<&str> = capsule
+            .warnings
+            .iter()
+            .map(|w| w.message.as_str())
+            .collect();
+        prompt.push_str(&format!("Known issues: {}\n", warnings.join("; ")));
+    }
+    if !source.is_empty() {
+        prompt.push_str(&format!("Source:\n```\n{}\n```\n", source));
+    }
+    prompt
+}
+
+fn overview_prompt(graph: &CapsuleGraph, summaries: &[(String, String)]) -> String {
+    let mut layers: Vec<(&String, usize)> =
+        graph.layers.iter().map(|(k, v)| (k, v.len())).collect();
+    layers.sort();
+    let warnings: usize = graph.capsules.values().map(|c| c.warnings.len()).sum();
+    let mut prompt = format!(
+        "Write a short executive overview (at most 5 sentences) of this codebase's architecture for engineering leadership: main responsibilities, structure and the most important risks.\nComponents: {}, relations: {}, average complexity: {:.2}, coupling: {:.2}, cohesion: {:.2}, warnings: {}.\n",
+        graph.metrics.total_capsules,
+        graph.metrics.total_relations,
+        graph.metrics.complexity_average,
+        graph.metrics.coupling_index,
+        graph.metrics.cohesion_index,
+        warnings
+    );
+    if !layers.is_empty() {
+        let layers: Vec<String> = layers.iter().map(|(l, n)| format!("{l} ({n})")).collect();
+        prompt.push_str(&format!("Layers: {}\n", layers.join(", ")));
+    }
+    if !summaries.is_empty() {
+        prompt.push_str("Key components:\n");
+        for (name, summary) in summaries {
+            prompt.push_str(&format!("- {}: {}\n", name, summary));
+        }
+    }
+    prompt
+}
@@ -1,14 +1,21 @@
 // Обеспечиваем работу как CLI, так и GUI
 // #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use archlens::cli;
+use archlens::{cli, execution};
 use std::env;
 
-#[tokio::main]
-async fn main() {
-    let _args: Vec<String> = env::args().collect();
+fn main() {
+    // `--single-thread` выбирает рантайм, поэтому читается до его создания
+    execution::configure_from_args(env::args());
+    let runtime = match execution::runtime() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ CLI error: {}", e);
+            std::process::exit(1);
+        }
+    };
     // Always run CLI
-    match cli::run().await {
+    match runtime.block_on(cli::run()) {
         Ok(_) => std::process::exit(0),
         Err(e) => {
             eprintln!("❌ CLI error: {}", e);
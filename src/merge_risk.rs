@@ -0,0 +1,368 @@
+// Прогноз конфликтов слияния: файлы и капсулы, которые параллельно меняются
+// в нескольких активных ветках (и в целевой ветке), с учётом churn-горячих точек
+
+use crate::git::run_git;
+use crate::types::{CapsuleGraph, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Расхождение ветки с целевой веткой
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDivergence {
+    pub name: String,
+    pub merge_base: String,
+    /// Коммитов в ветке, которых нет в целевой
+    pub ahead: usize,
+    /// Коммитов в целевой ветке после точки ответвления
+    pub behind: usize,
+    pub files_changed: usize,
+}
+
+/// Файл, который меняется параллельно в нескольких ветках
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileConflictRisk {
+    pub path: String,
+    /// Оценка вероятности конфликта 0..1
+    pub likelihood: f64,
+    /// Пары веток, обе изменившие файл от общей базы (`a ↔ b`)
+    pub branch_pairs: Vec<String>,
+    /// Пересекающиеся (или соседние) ханки в координатах общей базы
+    pub overlapping_hunks: usize,
+    /// Коммитов в целевой ветке, затронувших файл за окно churn
+    pub churn: usize,
+    /// Капсулы, попавшие в изменённые фрагменты
+    pub capsules: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRiskReport {
+    pub target: String,
+    pub churn_days: u32,
+    pub branches: Vec<BranchDivergence>,
+    pub files: Vec<FileConflictRisk>,
+}
+
+/// Ханк `git diff -U0` в координатах старой стороны (общей базы)
+#[derive(Debug, Clone)]
+struct Hunk {
+    start: u32,
+    end: u32,
+    context: String,
+}
+
+/// Накопленные по файлу пары веток, изменивших его от общей базы
+#[derive(Debug, Default)]
+struct FilePairs {
+    branch_pairs: Vec<String>,
+    /// Вероятность конфликта для каждой пары
+    scores: Vec<f64>,
+    overlapping_hunks: usize,
+    /// Имена объявлений из контекста ханков
+    symbols: BTreeSet<String>,
+}
+
+/// Анализатор риска конфликтов для git-репозитория
+#[derive(Debug)]
+pub struct MergeRiskAnalyzer {
+    repo: PathBuf,
+    /// Окно churn и активности веток, дней
+    pub churn_days: u32,
+    pub max_branches: usize,
+    /// Ханки ближе этого числа строк считаются пересекающимися
+    pub hunk_proximity: u32,
+    pub top_n: usize,
+}
+
+impl MergeRiskAnalyzer {
+    pub fn new(repo: &Path) -> Self {
+        Self {
+            repo: repo.to_path_buf(),
+            churn_days: 90,
+            max_branches: 20,
+            hunk_proximity: 3,
+            top_n: 25,
+        }
+    }
+
+    /// Целевая ветка по умолчанию: main, master или текущий HEAD
+    pub fn default_target(&self) -> String {
+        ["main", "master"]
+            .iter()
+            .find(|b| self.git(&["rev-parse", "--verify", "--quiet", b]).is_ok())
+            .map(|b| b.to_string())
+            .unwrap_or_else(|| "HEAD".to_string())
+    }
+
+    /// Локальные ветки с коммитами в окне churn, от свежих к старым
+    pub fn active_branches(&self, target: &str) -> Result<Vec<String>> {
+        let out = self.git(&[
+            "for-each-ref",
+            "--sort=-committerdate",
+            "--format=%(refname:short)%09%(committerdate:unix)",
+            "refs/heads",
+        ])?;
+        let cutoff = chrono::Utc::now().timestamp() - self.churn_days as i64 * 86_400;
+        Ok(out
+            .lines()
+            .filter_map(|l| l.split_once('\t'))
+            .filter(|(name, _)| *name != target)
+            .filter(|(_, ts)| ts.parse::<i64>().map(|t| t >= cutoff).unwrap_or(false))
+            .map(|(name, _)| name.to_string())
+            .take(self.max_branches)
+            .collect())
+    }
+
+    /// Попарно сравнивает целевую ветку и активные ветки от их общих баз.
+    /// `graph` (если есть) ограничивает имена капсул реально существующими
+    pub fn analyze(
+        &self,
+        target: &str,
+        branches: &[String],
+        graph: Option<&CapsuleGraph>,
+    ) -> Result<MergeRiskReport> {
+        let mut divergence = Vec::new();
+        for branch in branches {
+            let merge_base = self.merge_base(target, branch)?;
+            let counts = self.git(&[
+                "rev-list",
+                "--left-right",
+                "--count",
+                &format!("{}...{}", target, branch),
+            ])?;
+            let mut counts = counts.split_whitespace().map(|n| n.parse().unwrap_or(0));
+            let behind = counts.next().unwrap_or(0);
+            let ahead = counts.next().unwrap_or(0);
+            let files_changed = self
+                .git(&["diff", "--name-only", &merge_base, branch])?
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .count();
+            divergence.push(BranchDivergence {
+                name: branch.clone(),
+                merge_base: merge_base.chars().take(10).collect(),
+                ahead,
+                behind,
+                files_changed,
+            });
+        }
+
+        let churn = self.churn(target)?;
+        let sides: Vec<&str> = std::iter::once(target)
+            .chain(branches.iter().map(String::as_str))
+            .collect();
+        let mut hunk_cache: HashMap<(String, String), HashMap<String, Vec<Hunk>>> = HashMap::new();
+        let mut per_file: BTreeMap<String, FilePairs> = BTreeMap::new();
+
+        for (i, a) in sides.iter().enumerate() {
+            for b in &sides[i + 1..] {
+                let Ok(base) = self.merge_base(a, b) else {
+                    continue;
+                };
+                for side in [a, b] {
+                    let key = (base.clone(), side.to_string());
+                    if let Entry::Vacant(slot) = hunk_cache.entry(key) {
+                        slot.insert(self.diff_hunks(&base, side)?);
+                    }
+                }
+                let hunks_a = &hunk_cache[&(base.clone(), a.to_string())];
+                let hunks_b = &hunk_cache[&(base.clone(), b.to_string())];
+                for (path, ha) in hunks_a {
+                    let Some(hb) = hunks_b.get(path) else {
+                        continue;
+                    };
+                    let overlapping = self.overlapping(ha, hb);
+                    let file_churn = churn.get(path).copied().unwrap_or(0) as f64;
+                    let pair_score = 0.2
+                        + if overlapping.is_empty() { 0.0 } else { 0.5 }
+                        + 0.3 * file_churn / (file_churn + 5.0);
+
+                    let entry = per_file.entry(path.clone()).or_default();
+                    entry.branch_pairs.push(format!("{} ↔ {}", a, b));
+                    entry.scores.push(pair_score);
+                    entry.overlapping_hunks += overlapping.len();
+                    // Капсулы — из пересечений, а если их нет — из всех изменений пары
+                    let contexts: Vec<&Hunk> = if overlapping.is_empty() {
+                        ha.iter().chain(hb.iter()).collect()
+                    } else {
+                        overlapping.iter().flat_map(|(x, y)| [*x, *y]).collect()
+                    };
+                    entry
+                        .symbols
+                        .extend(contexts.iter().flat_map(|h| context_symbols(&h.context)));
+                }
+            }
+        }
+
+        let mut files: Vec<FileConflictRisk> = per_file
+            .into_iter()
+            .map(|(path, pairs)| {
+                // независимые пары: P(конфликт) = 1 - Π(1 - p)
+                let likelihood = 1.0 - pairs.scores.iter().map(|p| 1.0 - p).product::<f64>();
+                let capsules = match graph {
+                    Some(graph) => {
+                        let mut names: Vec<String> = graph
+                            .capsules
+                            .values()
+                            .filter(|c| c.file_path.ends_with(&path))
+                            .filter(|c| pairs.symbols.contains(&c.name))
+                            .map(|c| c.name.clone())
+                            .collect();
+                        names.sort();
+                        names.dedup();
+                        names
+                    }
+                    None => pairs.symbols.into_iter().collect(),
+                };
+                FileConflictRisk {
+                    churn: churn.get(&path).copied().unwrap_or(0),
+                    likelihood: (likelihood * 100.0).round() / 100.0,
+                    path,
+                    branch_pairs: pairs.branch_pairs,
+                    overlapping_hunks: pairs.overlapping_hunks,
+                    capsules,
+                }
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            b.likelihood
+                .partial_cmp(&a.likelihood)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        files.truncate(self.top_n);
+
+        Ok(MergeRiskReport {
+            target: target.to_string(),
+            churn_days: self.churn_days,
+            branches: divergence,
+            files,
+        })
+    }
+
+    fn merge_base(&self, a: &str, b: &str) -> Result<String> {
+        Ok(self.git(&["merge-base", a, b])?.trim().to_string())
+    }
+
+    /// Число коммитов целевой ветки по файлам за окно churn
+    fn churn(&self, target: &str) -> Result<HashMap<String, usize>> {
+        let out = self.git(&[
+            "log",
+            "--no-merges",
+            "--format=",
+            "--name-only",
+            &format!("--since={} days ago", self.churn_days),
+            target,
+        ])?;
+        let mut churn = HashMap::new();
+        for file in out.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            *churn.entry(file.to_string()).or_insert(0) += 1;
+        }
+        Ok(churn)
+    }
+
+    fn diff_hunks(&self, base: &str, rev: &str) -> Result<HashMap<String, Vec<Hunk>>> {
+        let out = self.git(&["diff", "-U0", "--no-color", "--no-ext-diff", base, rev])?;
+        Ok(parse_hunks(&out))
+    }
+
+    /// Пары ханков двух сторон, которые пересекаются или почти соприкасаются
+    fn overlapping<'a>(&self, a: &'a [Hunk], b: &'a [Hunk]) -> Vec<(&'a Hunk, &'a Hunk)> {
+        a.iter()
+            .flat_map(|ha| b.iter().map(move |hb| (ha, hb)))
+            .filter(|(ha, hb)| {
+                ha.start <= hb.end + self.hunk_proximity && hb.start <= ha.end + self.hunk_proximity
+            })
+            .collect()
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        run_git(&self.repo, args)
+    }
+}
+
+/// Разбор `git diff -U0`: путь файла → ханки в координатах старой стороны
+fn parse_hunks(diff: &str) -> HashMap<String, Vec<Hunk>> {
+    let header = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+\d+(?:,\d+)? @@ ?(.*)$").unwrap();
+    let mut hunks: HashMap<String, Vec<Hunk>> = HashMap::new();
+    let mut old_path: Option<String> = None;
+    let mut path: Option<String> = None;
+    for line in diff.lines() {
+        if let Some(p) = line.strip_prefix("--- ") {
+            old_path = p.strip_prefix("a/").map(str::to_string);
+        } else if let Some(p) = line.strip_prefix("+++ ") {
+            // удалённый файл: новой стороны нет, берём старый путь
+            path = p
+                .strip_prefix("b/")
+                .map(str::to_string)
+                .or(old_path.clone());
+        } else if let Some(caps) = header.captures(line) {
+            let Some(path) = &path else {
+                continue;
+            };
+            let start: u32 = caps[1].parse().unwrap_or(0);
+            let len: u32 = caps
+                .get(2)
+                .map(|m| m.as_str().parse().unwrap_or(1))
+                .unwrap_or(1);
+            hunks.entry(path.clone()).or_default().push(Hunk {
+                start,
+                // len == 0 — вставка после строки start
+                end: start + len.saturating_sub(1),
+                context: caps[3].to_string(),
+            });
+        }
+    }
+    hunks
+}
+
+/// Имена объявлений из контекста ханка (`fn parse(`, `def load`, `class Foo`)
+fn context_symbols(context: &str) -> Vec<String> {
+    let re = Regex::new(
+        r"\b(?:fn|def|func|class|struct|enum|trait|impl|interface|function|type)\s+(?:\([^)]*\)\s*)?([A-Za-z_][A-Za-z0-9_]*)",
+    )
+    .unwrap();
+    re.captures_iter(context)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+impl MergeRiskReport {
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("## Merge-conflict risk (target {})\n\n", self.target);
+        if self.branches.is_empty() {
+            md.push_str("_No active branches._\n");
+            return md;
+        }
+        md.push_str("| Branch | Ahead | Behind | Files changed |\n|---|---|---|---|\n");
+        for b in &self.branches {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                b.name, b.ahead, b.behind, b.files_changed
+            ));
+        }
+
+        if self.files.is_empty() {
+            md.push_str("\n_No files are changed in parallel._\n");
+            return md;
+        }
+        md.push_str(&format!(
+            "\n### Files at risk\n\n| File | Likelihood | Branches | Overlapping hunks | Churn ({}d) | Capsules |\n|---|---|---|---|---|---|\n",
+            self.churn_days
+        ));
+        for f in &self.files {
+            md.push_str(&format!(
+                "| `{}` | {:.0}% | {} | {} | {} | {} |\n",
+                f.path,
+                f.likelihood * 100.0,
+                f.branch_pairs.join(", "),
+                f.overlapping_hunks,
+                f.churn,
+                f.capsules.join(", ")
+            ));
+        }
+        md
+    }
+}
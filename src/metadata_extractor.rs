@@ -1,7 +1,92 @@
+use crate::git::run_git;
 use crate::parser_ast::{ASTElement, ASTElementType};
-use crate::types::Result;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::types::{Capsule, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Ключ метаданных: число коммитов, менявших файл капсулы
+pub const GIT_COMMITS_KEY: &str = "git_commits";
+/// Ключ метаданных: дата последнего изменения файла (RFC 3339)
+pub const GIT_LAST_MODIFIED_KEY: &str = "git_last_modified";
+/// Ключ метаданных: число различных авторов файла
+pub const GIT_AUTHORS_KEY: &str = "git_authors";
+/// `0`/`off` отключает сбор git-истории при построении графа
+pub const GIT_METADATA_ENV: &str = "ARCHLENS_GIT_METADATA";
+
+/// История файла по `git log --follow`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileHistory {
+    pub commits: usize,
+    /// Дата автора последнего коммита (RFC 3339)
+    pub last_modified: Option<String>,
+    pub authors: usize,
+}
+
+/// Разбор вывода `git log --format=%aI%x1f%aE` (новые коммиты первыми)
+pub fn parse_file_history(log: &str) -> FileHistory {
+    let mut history = FileHistory::default();
+    let mut authors = HashSet::new();
+    for line in log.lines().filter(|l| !l.trim().is_empty()) {
+        let (date, email) = line.split_once('\u{1f}').unwrap_or((line, ""));
+        history.commits += 1;
+        if history.last_modified.is_none() {
+            history.last_modified = Some(date.trim().to_string());
+        }
+        authors.insert(email.trim().to_lowercase());
+    }
+    history.authors = authors.len();
+    history
+}
+
+/// Разбор вывода `git log -M --name-status --format=%x1e%aI%x1f%aE` по всему
+/// репозиторию: история каждого файла под его текущим путём. Коммиты до
+/// переименования засчитываются новому пути, как у `git log --follow`
+pub fn parse_repository_history(log: &str) -> HashMap<String, FileHistory> {
+    let mut histories: HashMap<String, FileHistory> = HashMap::new();
+    let mut authors: HashMap<String, HashSet<String>> = HashMap::new();
+    // Старый путь → текущий; коммиты идут от новых к старым
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    for commit in log.split('\u{1e}').filter(|c| !c.trim().is_empty()) {
+        let mut lines = commit.lines();
+        let header = lines.next().unwrap_or_default();
+        let (date, email) = header.split_once('\u{1f}').unwrap_or((header, ""));
+        for line in lines.filter(|l| !l.trim().is_empty()) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let (status, path) = match fields.as_slice() {
+                [status, _, new] if status.starts_with(['R', 'C']) => (*status, *new),
+                [status, path] => (*status, *path),
+                _ => continue,
+            };
+            let current = renamed
+                .get(path)
+                .cloned()
+                .unwrap_or_else(|| path.to_string());
+            if status.starts_with('R') {
+                renamed.insert(fields[1].to_string(), current.clone());
+            }
+            let history = histories.entry(current.clone()).or_default();
+            history.commits += 1;
+            if history.last_modified.is_none() {
+                history.last_modified = Some(date.trim().to_string());
+            }
+            let file_authors = authors.entry(current).or_default();
+            file_authors.insert(email.trim().to_lowercase());
+            history.authors = file_authors.len();
+        }
+    }
+    histories
+}
+
+/// Сбор git-истории включён, если не отключён переменной окружения
+pub fn git_metadata_enabled() -> bool {
+    !matches!(
+        std::env::var(GIT_METADATA_ENV)
+            .ok()
+            .as_deref()
+            .map(str::trim),
+        Some("0" | "off" | "false")
+    )
+}
 
 /// Экстрактор метаданных - извлекает дополнительную информацию из элементов
 #[derive(Debug)]
@@ -142,6 +227,80 @@ impl MetadataExtractor {
         Ok(())
     }
 
+    /// История файла; `None`, если файл не под git
+    pub fn file_history(&self, repo: &Path, file: &Path) -> Option<FileHistory> {
+        let file = file.to_string_lossy();
+        let log = run_git(
+            repo,
+            &[
+                "log",
+                "--follow",
+                "--format=%aI%x1f%aE",
+                "--",
+                file.as_ref(),
+            ],
+        )
+        .ok()?;
+        let history = parse_file_history(&log);
+        (history.commits > 0).then_some(history)
+    }
+
+    /// Дополняет метаданные капсул числом коммитов, датой последнего изменения
+    /// и числом авторов их файлов. История всего репозитория читается одним
+    /// `git log` с учётом переименований, а не отдельным процессом на файл.
+    /// Возвращает число файлов с историей; вне git-репозитория ничего не делает
+    pub fn enrich_with_git_history(&self, repo: &Path, capsules: &mut [Capsule]) -> usize {
+        let Ok(toplevel) = run_git(repo, &["rev-parse", "--show-toplevel"]) else {
+            return 0;
+        };
+        let toplevel = PathBuf::from(toplevel.trim());
+        let toplevel = toplevel.canonicalize().unwrap_or(toplevel);
+        let Ok(log) = run_git(
+            &toplevel,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "log",
+                "-M",
+                "--name-status",
+                "--format=%x1e%aI%x1f%aE",
+            ],
+        ) else {
+            return 0;
+        };
+        let histories = parse_repository_history(&log);
+
+        let mut found = HashSet::new();
+        for capsule in capsules.iter_mut() {
+            let path = if capsule.file_path.is_absolute() {
+                capsule.file_path.clone()
+            } else {
+                repo.join(&capsule.file_path)
+            };
+            let path = path.canonicalize().unwrap_or(path);
+            let Ok(relative) = path.strip_prefix(&toplevel) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let Some(history) = histories.get(&relative) else {
+                continue;
+            };
+            found.insert(relative);
+            capsule
+                .metadata
+                .insert(GIT_COMMITS_KEY.to_string(), history.commits.to_string());
+            capsule
+                .metadata
+                .insert(GIT_AUTHORS_KEY.to_string(), history.authors.to_string());
+            if let Some(date) = &history.last_modified {
+                capsule
+                    .metadata
+                    .insert(GIT_LAST_MODIFIED_KEY.to_string(), date.clone());
+            }
+        }
+        found.len()
+    }
+
     /// Генерирует автоматический слоган для элемента
     pub fn generate_auto_slogan(&self, element: &ASTElement) -> Result<String> {
         let name = &element.name;
@@ -0,0 +1,196 @@
+// История метрик капсул: каждый полный анализ дописывает в
+// `.archlens/history.jsonl` строку с метриками всех капсул (по стабильным id).
+// Хранилище включается созданием файла; пока его нет, ничего не пишется.
+// Из последних запусков строятся ряды для спарклайнов: становится ли горячая
+// точка лучше или хуже
+
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Сколько последних запусков хранится в файле
+const MAX_STORED_RUNS: usize = 100;
+/// Сколько последних запусков попадает в ряды по умолчанию
+pub const DEFAULT_TREND_RUNS: usize = 10;
+/// Сколько капсул с рядами попадает в JSON-сводку
+const SUMMARY_TRENDS: usize = 20;
+
+/// Метрики капсулы в одном запуске
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapsuleMetrics {
+    pub complexity: u32,
+    pub size: usize,
+    pub warnings: usize,
+}
+
+/// Один запуск анализа
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRun {
+    pub recorded_at: String,
+    pub capsules: HashMap<Uuid, CapsuleMetrics>,
+}
+
+impl HistoryRun {
+    pub fn from_graph(graph: &CapsuleGraph) -> Self {
+        Self {
+            recorded_at: graph.created_at.to_rfc3339(),
+            capsules: graph
+                .capsules
+                .values()
+                .map(|c| {
+                    (
+                        c.id,
+                        CapsuleMetrics {
+                            complexity: c.complexity,
+                            size: c.size,
+                            warnings: c.warnings.len(),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Направление изменения сложности за окно запусков
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendDirection {
+    Improving,
+    Worsening,
+    Stable,
+}
+
+/// Ряды метрик капсулы, от старых запусков к новым; запуски без капсулы пропущены
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CapsuleTrend {
+    pub component: String,
+    pub file: PathBuf,
+    pub direction: TrendDirection,
+    pub recorded_at: Vec<String>,
+    pub complexity: Vec<u32>,
+    pub size: Vec<usize>,
+    pub warnings: Vec<usize>,
+}
+
+/// Хранилище истории проекта
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricHistory {
+    pub runs: Vec<HistoryRun>,
+}
+
+/// Путь хранилища истории проекта
+pub fn history_path(project_root: &Path) -> PathBuf {
+    project_root.join(".archlens").join("history.jsonl")
+}
+
+impl MetricHistory {
+    /// `Ok(None)`, если хранилища нет. Повреждённые строки пропускаются
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = history_path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        let runs = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Ok(Some(Self { runs }))
+    }
+
+    /// Дописывает запуск, если хранилище существует; старые запуски сверх
+    /// `MAX_STORED_RUNS` вытесняются. Возвращает, был ли запуск записан
+    pub fn record(project_root: &Path, graph: &CapsuleGraph) -> Result<bool> {
+        let Some(mut history) = Self::load(project_root)? else {
+            return Ok(false);
+        };
+        let path = history_path(project_root);
+        let run = HistoryRun::from_graph(graph);
+        let line =
+            serde_json::to_string(&run).map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        history.runs.push(run);
+        if history.runs.len() > MAX_STORED_RUNS {
+            let excess = history.runs.len() - MAX_STORED_RUNS;
+            history.runs.drain(..excess);
+            let mut content = String::new();
+            for run in &history.runs {
+                content.push_str(
+                    &serde_json::to_string(run)
+                        .map_err(|e| AnalysisError::GenericError(e.to_string()))?,
+                );
+                content.push('\n');
+            }
+            fs::write(&path, content).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        } else {
+            let mut file = OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .map_err(|e| AnalysisError::IoError(e.to_string()))?;
+            writeln!(file, "{line}").map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        }
+        Ok(true)
+    }
+
+    /// Ряды за последние `last_runs` запусков для капсул текущего графа,
+    /// встречающихся хотя бы в двух из них; самые сложные сейчас — первыми
+    pub fn trends(&self, graph: &CapsuleGraph, last_runs: usize) -> Vec<CapsuleTrend> {
+        let window = &self.runs[self.runs.len().saturating_sub(last_runs)..];
+        let mut trends: Vec<CapsuleTrend> = graph
+            .capsules
+            .values()
+            .filter_map(|capsule| {
+                let points: Vec<(&str, CapsuleMetrics)> = window
+                    .iter()
+                    .filter_map(|run| {
+                        Some((run.recorded_at.as_str(), *run.capsules.get(&capsule.id)?))
+                    })
+                    .collect();
+                if points.len() < 2 {
+                    return None;
+                }
+                let first = points[0].1.complexity;
+                let last = points[points.len() - 1].1.complexity;
+                Some(CapsuleTrend {
+                    component: capsule.name.clone(),
+                    file: capsule.file_path.clone(),
+                    direction: match last.cmp(&first) {
+                        std::cmp::Ordering::Less => TrendDirection::Improving,
+                        std::cmp::Ordering::Greater => TrendDirection::Worsening,
+                        std::cmp::Ordering::Equal => TrendDirection::Stable,
+                    },
+                    recorded_at: points.iter().map(|(at, _)| at.to_string()).collect(),
+                    complexity: points.iter().map(|(_, m)| m.complexity).collect(),
+                    size: points.iter().map(|(_, m)| m.size).collect(),
+                    warnings: points.iter().map(|(_, m)| m.warnings).collect(),
+                })
+            })
+            .collect();
+        trends.sort_by(|a, b| {
+            b.complexity
+                .last()
+                .cmp(&a.complexity.last())
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.component.cmp(&b.component))
+        });
+        trends
+    }
+
+    /// Добавляет в JSON-сводку `metric_trends` — ряды самых сложных капсул
+    pub fn attach_to_summary(&self, summary: &mut serde_json::Value, graph: &CapsuleGraph) {
+        let mut trends = self.trends(graph, DEFAULT_TREND_RUNS);
+        if trends.is_empty() {
+            return;
+        }
+        trends.truncate(SUMMARY_TRENDS);
+        if let (Some(obj), Ok(value)) = (summary.as_object_mut(), serde_json::to_value(trends)) {
+            obj.insert("metric_trends".into(), value);
+        }
+    }
+}
@@ -0,0 +1,509 @@
+// Генератор MODULE.md для модулей верхнего уровня: назначение (из doc-комментариев),
+// публичный API, зависимости, зависимые модули и текущие метрики.
+// Результат детерминирован — повторный запуск перезаписывает только изменившиеся файлы
+
+use crate::types::{Capsule, CapsuleGraph, CapsuleType, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Первая строка сгенерированного файла
+pub const MODULE_DOC_MARKER: &str =
+    "<!-- Generated by `archlens module-docs`; re-run the command to refresh. Manual edits are overwritten. -->";
+
+/// Файлы-корни пакета: не образуют отдельного модуля
+const ROOT_FILES: &[&str] = &[
+    "lib.rs",
+    "main.rs",
+    "mod.rs",
+    "__init__.py",
+    "__main__.py",
+    "index.ts",
+    "index.js",
+    "main.go",
+];
+
+/// Файлы, из которых в первую очередь берётся описание модуля-каталога
+const ENTRY_FILES: &[&str] = &[
+    "mod.rs",
+    "lib.rs",
+    "__init__.py",
+    "doc.go",
+    "index.ts",
+    "index.js",
+    "package-info.java",
+];
+
+/// Элемент публичного API модуля
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiItem {
+    pub name: String,
+    pub kind: CapsuleType,
+    /// `путь:строка` относительно корня проекта
+    pub location: String,
+}
+
+/// Метрики модуля
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModuleMetrics {
+    pub files: usize,
+    pub components: usize,
+    pub public_items: usize,
+    pub average_complexity: f32,
+    pub max_complexity: u32,
+    pub warnings: usize,
+    /// Ca — число модулей, зависящих от этого
+    pub afferent_coupling: usize,
+    /// Ce — число модулей, от которых зависит этот
+    pub efferent_coupling: usize,
+    /// Ce / (Ca + Ce)
+    pub instability: f32,
+}
+
+/// Содержимое MODULE.md одного модуля
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleDoc {
+    pub name: String,
+    /// Куда пишется MODULE.md
+    pub doc_path: PathBuf,
+    pub purpose: Option<String>,
+    pub public_api: Vec<ApiItem>,
+    /// Модуль → число связей
+    pub dependencies: BTreeMap<String, usize>,
+    pub dependents: BTreeMap<String, usize>,
+    pub metrics: ModuleMetrics,
+}
+
+/// Генератор документации модулей проекта
+#[derive(Debug)]
+pub struct ModuleDocGenerator {
+    root: PathBuf,
+    /// Каталог исходников: `src/`, если есть, иначе корень проекта
+    source_root: PathBuf,
+}
+
+impl ModuleDocGenerator {
+    pub fn new(root: &Path) -> Self {
+        let src = root.join("src");
+        Self {
+            root: root.to_path_buf(),
+            source_root: if src.is_dir() {
+                src
+            } else {
+                root.to_path_buf()
+            },
+        }
+    }
+
+    /// Модули верхнего уровня в алфавитном порядке
+    pub fn modules(&self, graph: &CapsuleGraph) -> Vec<ModuleDoc> {
+        let module_of: HashMap<_, _> = graph
+            .capsules
+            .values()
+            .filter_map(|c| Some((c.id, self.module_name(&c.file_path)?)))
+            .collect();
+
+        let mut members: BTreeMap<String, Vec<&Capsule>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            if let Some(module) = module_of.get(&capsule.id) {
+                members.entry(module.clone()).or_default().push(capsule);
+            }
+        }
+
+        let mut dependencies: HashMap<&str, BTreeMap<String, usize>> = HashMap::new();
+        let mut dependents: HashMap<&str, BTreeMap<String, usize>> = HashMap::new();
+        for relation in &graph.relations {
+            let (Some(from), Some(to)) = (
+                module_of.get(&relation.from_id),
+                module_of.get(&relation.to_id),
+            ) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            *dependencies
+                .entry(from.as_str())
+                .or_default()
+                .entry(to.clone())
+                .or_insert(0) += 1;
+            *dependents
+                .entry(to.as_str())
+                .or_default()
+                .entry(from.clone())
+                .or_insert(0) += 1;
+        }
+
+        let mut sources = SourceCache::default();
+        members
+            .iter()
+            .map(|(name, capsules)| {
+                let mut capsules = capsules.clone();
+                capsules.sort_by(|a, b| {
+                    (&a.file_path, a.line_start).cmp(&(&b.file_path, b.line_start))
+                });
+
+                let public_api: Vec<ApiItem> = capsules
+                    .iter()
+                    .filter(|c| is_api_kind(&c.capsule_type))
+                    .filter(|c| sources.is_public(c))
+                    .map(|c| ApiItem {
+                        name: c.name.clone(),
+                        kind: c.capsule_type,
+                        location: format!("{}:{}", self.relative(&c.file_path), c.line_start),
+                    })
+                    .collect();
+
+                let deps = dependencies.remove(name.as_str()).unwrap_or_default();
+                let users = dependents.remove(name.as_str()).unwrap_or_default();
+                let mut files: Vec<&PathBuf> = capsules.iter().map(|c| &c.file_path).collect();
+                files.dedup();
+                let complexity: Vec<u32> = capsules.iter().map(|c| c.complexity).collect();
+                let (ca, ce) = (users.len(), deps.len());
+                let metrics = ModuleMetrics {
+                    files: files.len(),
+                    components: capsules.len(),
+                    public_items: public_api.len(),
+                    average_complexity: round2(
+                        complexity.iter().sum::<u32>() as f32 / complexity.len().max(1) as f32,
+                    ),
+                    max_complexity: complexity.iter().copied().max().unwrap_or(0),
+                    warnings: capsules.iter().map(|c| c.warnings.len()).sum(),
+                    afferent_coupling: ca,
+                    efferent_coupling: ce,
+                    instability: if ca + ce == 0 {
+                        0.0
+                    } else {
+                        round2(ce as f32 / (ca + ce) as f32)
+                    },
+                };
+
+                ModuleDoc {
+                    name: name.clone(),
+                    doc_path: self.doc_path(name),
+                    purpose: self.purpose(name),
+                    public_api,
+                    dependencies: deps,
+                    dependents: users,
+                    metrics,
+                }
+            })
+            .collect()
+    }
+
+    /// Пишет MODULE.md всех модулей; возвращает пути реально изменённых файлов
+    pub fn write_all(&self, graph: &CapsuleGraph) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for doc in self.modules(graph) {
+            let content = doc.to_markdown();
+            if std::fs::read_to_string(&doc.doc_path).ok().as_deref() == Some(content.as_str()) {
+                continue;
+            }
+            std::fs::write(&doc.doc_path, content)?;
+            written.push(doc.doc_path);
+        }
+        Ok(written)
+    }
+
+    /// Имя модуля верхнего уровня: первый компонент пути внутри каталога исходников
+    fn module_name(&self, file: &Path) -> Option<String> {
+        let relative = file.strip_prefix(&self.source_root).ok()?;
+        let mut components = relative.components();
+        let first = components.next()?.as_os_str().to_string_lossy().to_string();
+        if components.next().is_some() {
+            return Some(first);
+        }
+        // файл прямо в каталоге исходников: `foo.rs` — модуль `foo`
+        if ROOT_FILES.contains(&first.as_str()) {
+            return None;
+        }
+        Path::new(&first)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+    }
+
+    /// Каталог модуля — `<dir>/MODULE.md`, модуль-файл — `<name>.MODULE.md` рядом с ним
+    fn doc_path(&self, module: &str) -> PathBuf {
+        let dir = self.source_root.join(module);
+        if dir.is_dir() {
+            dir.join("MODULE.md")
+        } else {
+            self.source_root.join(format!("{}.MODULE.md", module))
+        }
+    }
+
+    /// Назначение модуля: ведущий комментарий файла-входа, для Rust — ещё и
+    /// doc-комментарий над `mod <name>;` в корне крейта
+    fn purpose(&self, module: &str) -> Option<String> {
+        let dir = self.source_root.join(module);
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        if dir.is_dir() {
+            candidates.extend(ENTRY_FILES.iter().map(|f| dir.join(f)));
+        }
+        if let Ok(entries) = std::fs::read_dir(&self.source_root) {
+            let mut files: Vec<PathBuf> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.file_stem().is_some_and(|s| s == module))
+                .collect();
+            files.sort();
+            candidates.extend(files);
+        }
+        candidates
+            .iter()
+            .find_map(|path| {
+                let content = std::fs::read_to_string(path).ok()?;
+                let hash_comments = path
+                    .extension()
+                    .is_some_and(|e| e == "py" || e == "rb" || e == "sh");
+                leading_comment(&content, hash_comments)
+            })
+            .or_else(|| {
+                ["lib.rs", "main.rs"].iter().find_map(|root| {
+                    let content = std::fs::read_to_string(self.source_root.join(root)).ok()?;
+                    mod_declaration_doc(&content, module)
+                })
+            })
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+impl ModuleDoc {
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("{}\n\n# Module `{}`\n\n", MODULE_DOC_MARKER, self.name);
+
+        md.push_str("## Purpose\n\n");
+        match &self.purpose {
+            Some(purpose) => md.push_str(&format!("{}\n", purpose)),
+            None => md.push_str("_No module-level documentation found._\n"),
+        }
+
+        md.push_str("\n## Public API\n\n");
+        if self.public_api.is_empty() {
+            md.push_str("_No public items._\n");
+        } else {
+            md.push_str("| Name | Kind | Location |\n|---|---|---|\n");
+            for item in &self.public_api {
+                md.push_str(&format!(
+                    "| `{}` | {:?} | `{}` |\n",
+                    item.name, item.kind, item.location
+                ));
+            }
+        }
+
+        let links = |title: &str, modules: &BTreeMap<String, usize>, empty: &str| {
+            let mut section = format!("\n## {}\n\n", title);
+            if modules.is_empty() {
+                section.push_str(&format!("_{}_\n", empty));
+            }
+            for (module, count) in modules {
+                section.push_str(&format!(
+                    "- `{}` ({} reference{})\n",
+                    module,
+                    count,
+                    if *count == 1 { "" } else { "s" }
+                ));
+            }
+            section
+        };
+        md.push_str(&links(
+            "Dependencies",
+            &self.dependencies,
+            "No dependencies on other modules.",
+        ));
+        md.push_str(&links(
+            "Dependents",
+            &self.dependents,
+            "No other module depends on this one.",
+        ));
+
+        let m = &self.metrics;
+        md.push_str("\n## Metrics\n\n| Metric | Value |\n|---|---|\n");
+        for (name, value) in [
+            ("Files", m.files.to_string()),
+            ("Components", m.components.to_string()),
+            ("Public items", m.public_items.to_string()),
+            ("Average complexity", format!("{:.2}", m.average_complexity)),
+            ("Max complexity", m.max_complexity.to_string()),
+            ("Warnings", m.warnings.to_string()),
+            ("Afferent coupling (Ca)", m.afferent_coupling.to_string()),
+            ("Efferent coupling (Ce)", m.efferent_coupling.to_string()),
+            ("Instability", format!("{:.2}", m.instability)),
+        ] {
+            md.push_str(&format!("| {} | {} |\n", name, value));
+        }
+        md
+    }
+}
+
+/// Строки исходников для проверки видимости объявлений
+#[derive(Default)]
+struct SourceCache {
+    files: HashMap<PathBuf, Vec<String>>,
+}
+
+impl SourceCache {
+    /// Видимость по соглашениям языка; парсер помечает публичным всё без модификатора
+    fn is_public(&mut self, capsule: &Capsule) -> bool {
+        let lines = self
+            .files
+            .entry(capsule.file_path.clone())
+            .or_insert_with(|| {
+                std::fs::read_to_string(&capsule.file_path)
+                    .map(|c| c.lines().map(str::to_string).collect())
+                    .unwrap_or_default()
+            });
+        let start = capsule.line_start.saturating_sub(1);
+        let Some(line) = lines
+            .iter()
+            .skip(start)
+            .take(2)
+            .find(|l| l.contains(&capsule.name))
+        else {
+            return true;
+        };
+        let trimmed = line.trim_start();
+        let extension = capsule
+            .file_path
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match extension.as_str() {
+            // `pub(crate)` и `pub(super)` — не внешний API
+            "rs" => trimmed.starts_with("pub "),
+            "py" => !capsule.name.starts_with('_'),
+            "go" => capsule.name.starts_with(|c: char| c.is_ascii_uppercase()),
+            "ts" | "tsx" | "js" | "jsx" | "mjs" => trimmed.starts_with("export "),
+            "java" | "kt" | "cs" => trimmed.contains("public "),
+            "c" | "cpp" | "cc" | "h" | "hpp" => !trimmed.starts_with("static "),
+            _ => true,
+        }
+    }
+}
+
+/// Объявления, которые имеет смысл перечислять в API
+fn is_api_kind(kind: &CapsuleType) -> bool {
+    !matches!(
+        kind,
+        CapsuleType::Import | CapsuleType::Export | CapsuleType::Variable | CapsuleType::Other
+    )
+}
+
+/// Ведущий блок комментариев файла (`//!`, `///`, `//`, docstring, `/** */`;
+/// `#` — только для языков, где это комментарий)
+fn leading_comment(content: &str, hash_comments: bool) -> Option<String> {
+    let mut lines = content.lines().map(str::trim).peekable();
+    // shebang и пустые строки в начале
+    while let Some(line) = lines.peek() {
+        if line.is_empty() || line.starts_with("#!") {
+            lines.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut text: Vec<String> = Vec::new();
+    let first = *lines.peek()?;
+    if first.starts_with("\"\"\"") || first.starts_with("'''") {
+        let quote = &first[..3];
+        let rest = &first[3..];
+        if let Some(end) = rest.find(quote) {
+            text.push(rest[..end].to_string());
+        } else {
+            text.push(rest.to_string());
+            lines.next();
+            for line in lines.by_ref() {
+                if let Some(end) = line.find(quote) {
+                    text.push(line[..end].to_string());
+                    break;
+                }
+                text.push(line.to_string());
+            }
+        }
+    } else if first.starts_with("/*") {
+        for line in lines.by_ref() {
+            let done = line.contains("*/");
+            let cleaned = line
+                .trim_start_matches("/**")
+                .trim_start_matches("/*")
+                .split("*/")
+                .next()
+                .unwrap_or("")
+                .trim_start_matches('*')
+                .trim();
+            text.push(cleaned.to_string());
+            if done {
+                break;
+            }
+        }
+    } else {
+        for line in lines {
+            let stripped = ["//!", "///", "//"]
+                .iter()
+                .find_map(|marker| line.strip_prefix(marker))
+                .or_else(|| line.strip_prefix('#').filter(|_| hash_comments));
+            match stripped {
+                Some(rest) => text.push(rest.trim().to_string()),
+                None => break,
+            }
+        }
+    }
+    normalize_comment(&text)
+}
+
+/// `///`-комментарий над `mod <name>;` в корне крейта
+fn mod_declaration_doc(content: &str, module: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let decl = lines.iter().position(|l| {
+        let l = l
+            .trim_start_matches("pub ")
+            .trim_start_matches("pub(crate) ");
+        l == format!("mod {};", module) || l == format!("mod {} {{", module)
+    })?;
+    let mut text: Vec<String> = lines[..decl]
+        .iter()
+        .rev()
+        .skip_while(|l| l.starts_with("#["))
+        .take_while(|l| l.starts_with("///"))
+        .map(|l| l.trim_start_matches("///").trim().to_string())
+        .collect();
+    text.reverse();
+    normalize_comment(&text)
+}
+
+/// Склеивает строки абзацев; лицензионные заголовки отбрасываются
+fn normalize_comment(lines: &[String]) -> Option<String> {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs.retain(|p| !p.contains("SPDX-License-Identifier") && !p.contains("Copyright"));
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+fn round2(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
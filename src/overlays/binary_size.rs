@@ -0,0 +1,225 @@
+// Атрибуция размера бинаря (Rust): отчёт `cargo bloat --message-format json`
+// раскладывается по капсулам, файлам и слоям графа
+
+use super::{rank_entries, symbol_segments, CapsuleLocator, OverlayEntry};
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// Ключ метаданных капсулы с размером в байтах
+pub const BINARY_SIZE_KEY: &str = "binary_size_bytes";
+
+/// Отчёт `cargo bloat` (функции или крейты)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BloatReport {
+    #[serde(rename = "file-size", default)]
+    pub file_size: u64,
+    #[serde(rename = "text-section-size", default)]
+    pub text_section_size: u64,
+    #[serde(default)]
+    pub functions: Vec<BloatFunction>,
+    #[serde(default)]
+    pub crates: Vec<BloatCrate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BloatFunction {
+    #[serde(rename = "crate", default)]
+    pub crate_name: Option<String>,
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BloatCrate {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Итог атрибуции размера
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BinarySizeReport {
+    pub file_size: u64,
+    pub text_section_size: u64,
+    /// Байты собственного крейта, сопоставленные с капсулами
+    pub attributed_bytes: u64,
+    /// Байты собственного крейта без найденной капсулы
+    pub unattributed_bytes: u64,
+    pub by_crate: Vec<OverlayEntry>,
+    pub by_file: Vec<OverlayEntry>,
+    pub by_layer: Vec<OverlayEntry>,
+    pub by_capsule: Vec<OverlayEntry>,
+}
+
+/// Анализатор вклада компонентов в размер бинаря
+#[derive(Debug)]
+pub struct BinarySizeAnalyzer {
+    pub top_n: usize,
+}
+
+impl BinarySizeAnalyzer {
+    pub fn new() -> Self {
+        Self { top_n: 20 }
+    }
+
+    pub fn parse_report(&self, json: &str) -> Result<BloatReport> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::Parse(format!("cargo bloat JSON: {}", e)))
+    }
+
+    /// Запуск `cargo bloat` (опционально; требует установленного cargo-bloat)
+    pub fn run_cargo_bloat(&self, project_path: &Path) -> Result<String> {
+        let output = Command::new("cargo")
+            .args(["bloat", "--release", "--message-format", "json", "-n", "0"])
+            .current_dir(project_path)
+            .output()
+            .map_err(|e| AnalysisError::IoError(format!("cargo bloat: {}", e)))?;
+        if !output.status.success() {
+            return Err(AnalysisError::GenericError(format!(
+                "cargo bloat завершился с ошибкой: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Раскладывает размер функций собственного крейта по капсулам
+    /// и записывает `binary_size_bytes` в метаданные капсул
+    pub fn attribute(
+        &self,
+        graph: &mut CapsuleGraph,
+        report: &BloatReport,
+        crate_name: &str,
+    ) -> BinarySizeReport {
+        let locator = CapsuleLocator::new(graph);
+        let own_crate = crate_name.replace('-', "_");
+
+        let mut by_crate: HashMap<String, f64> = HashMap::new();
+        let mut by_capsule: HashMap<uuid::Uuid, u64> = HashMap::new();
+        let mut unattributed = 0u64;
+
+        for crate_entry in &report.crates {
+            *by_crate.entry(crate_entry.name.clone()).or_insert(0.0) += crate_entry.size as f64;
+        }
+        for func in &report.functions {
+            let krate = func
+                .crate_name
+                .clone()
+                .unwrap_or_else(|| "[Unknown]".to_string());
+            if report.crates.is_empty() {
+                *by_crate.entry(krate.clone()).or_insert(0.0) += func.size as f64;
+            }
+            if krate != own_crate {
+                continue;
+            }
+            match self.locate(graph, &locator, &func.name, &own_crate) {
+                Some(id) => *by_capsule.entry(id).or_insert(0) += func.size,
+                None => unattributed += func.size,
+            }
+        }
+
+        let mut by_file: HashMap<String, f64> = HashMap::new();
+        let mut by_layer: HashMap<String, f64> = HashMap::new();
+        let mut by_capsule_named: HashMap<String, f64> = HashMap::new();
+        for (id, bytes) in &by_capsule {
+            if let Some(capsule) = graph.capsules.get_mut(id) {
+                capsule
+                    .metadata
+                    .insert(BINARY_SIZE_KEY.to_string(), bytes.to_string());
+                *by_file
+                    .entry(capsule.file_path.display().to_string())
+                    .or_insert(0.0) += *bytes as f64;
+                *by_layer
+                    .entry(capsule.layer.clone().unwrap_or_else(|| "Unknown".into()))
+                    .or_insert(0.0) += *bytes as f64;
+                *by_capsule_named.entry(capsule.name.clone()).or_insert(0.0) += *bytes as f64;
+            }
+        }
+
+        let attributed: u64 = by_capsule.values().sum();
+        let own_total = (attributed + unattributed) as f64;
+        let text_total = if report.text_section_size > 0 {
+            report.text_section_size as f64
+        } else {
+            by_crate.values().sum()
+        };
+        BinarySizeReport {
+            file_size: report.file_size,
+            text_section_size: report.text_section_size,
+            attributed_bytes: attributed,
+            unattributed_bytes: unattributed,
+            by_crate: rank_entries(by_crate, text_total, self.top_n),
+            by_file: rank_entries(by_file, own_total, self.top_n),
+            by_layer: rank_entries(by_layer, own_total, self.top_n),
+            by_capsule: rank_entries(by_capsule_named, own_total, self.top_n),
+        }
+    }
+
+    fn locate(
+        &self,
+        graph: &CapsuleGraph,
+        locator: &CapsuleLocator,
+        symbol: &str,
+        own_crate: &str,
+    ) -> Option<uuid::Uuid> {
+        if let Some(id) = locator.capsule_for_symbol(graph, symbol) {
+            return Some(id);
+        }
+        // Нет капсулы с таким именем: относим к файлу модуля (a::b → a/b.rs)
+        let segments = symbol_segments(symbol);
+        let module: Vec<&String> = segments
+            .iter()
+            .skip_while(|s| s.as_str() == own_crate)
+            .collect();
+        for len in (1..module.len()).rev() {
+            let path = module[..len]
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join("/");
+            let ids = locator.capsules_for_path_suffix(&format!("{}.rs", path));
+            let ids = if ids.is_empty() {
+                locator.capsules_for_path_suffix(&format!("{}/mod.rs", path))
+            } else {
+                ids
+            };
+            if let Some(id) = ids
+                .into_iter()
+                .filter_map(|id| graph.capsules.get(&id))
+                .min_by_key(|c| (c.line_start, c.name.clone()))
+                .map(|c| c.id)
+            {
+                return Some(id);
+            }
+        }
+        None
+    }
+}
+
+impl Default for BinarySizeAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Имя пакета из `[package]` Cargo.toml (для отделения своего кода от зависимостей)
+pub fn rust_crate_name(project_path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(project_path.join("Cargo.toml")).ok()?;
+    let mut in_package = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if in_package {
+            if let Some(value) = line.strip_prefix("name") {
+                let value = value.trim_start().strip_prefix('=')?.trim();
+                return Some(value.trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
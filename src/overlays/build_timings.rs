@@ -0,0 +1,290 @@
+// Атрибуция времени сборки: `cargo build --timings` (HTML или JSON-сообщения)
+// и webpack `stats.json` с профилем модулей накладываются на капсулы графа
+
+use super::{rank_entries, CapsuleLocator, OverlayEntry};
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Ключ метаданных капсулы с оценкой времени компиляции (мс)
+pub const BUILD_TIME_KEY: &str = "build_time_ms";
+
+/// Время сборки единицы компиляции (крейта)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitTiming {
+    pub name: String,
+    pub duration_secs: f64,
+}
+
+/// Время сборки отдельного модуля (файла) бандлера
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleTiming {
+    pub path: String,
+    pub millis: f64,
+}
+
+/// Разобранный отчёт о времени сборки
+#[derive(Debug, Clone)]
+pub enum BuildTimings {
+    Units(Vec<UnitTiming>),
+    Modules(Vec<ModuleTiming>),
+}
+
+/// Кандидат на разделение: тяжёлый узел сборки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitCandidate {
+    pub name: String,
+    pub seconds: f64,
+    pub capsules: usize,
+    pub heaviest_files: Vec<String>,
+}
+
+/// Итог атрибуции времени сборки
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildTimingsReport {
+    pub total_seconds: f64,
+    /// Время собственных крейтов/модулей проекта
+    pub local_seconds: f64,
+    pub by_unit: Vec<OverlayEntry>,
+    pub by_file: Vec<OverlayEntry>,
+    pub by_capsule: Vec<OverlayEntry>,
+    pub split_candidates: Vec<SplitCandidate>,
+}
+
+/// Анализатор стоимости компиляции по компонентам
+#[derive(Debug)]
+pub struct BuildTimingsAnalyzer {
+    /// Порог (сек), начиная с которого локальный узел считается тяжёлым
+    pub split_threshold_secs: f64,
+    pub top_n: usize,
+}
+
+impl BuildTimingsAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            split_threshold_secs: 10.0,
+            top_n: 20,
+        }
+    }
+
+    /// Автоопределение формата: HTML cargo-timing, JSON-строки cargo или webpack stats
+    pub fn parse(&self, text: &str) -> Result<BuildTimings> {
+        if let Some(units) = parse_cargo_timing_html(text)? {
+            return Ok(BuildTimings::Units(units));
+        }
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+            if let Some(modules) = value.get("modules").and_then(|m| m.as_array()) {
+                return Ok(BuildTimings::Modules(parse_webpack_modules(modules)));
+            }
+        }
+        let units: Vec<UnitTiming> = text
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|v| v.get("reason").and_then(|r| r.as_str()) == Some("timing-info"))
+            .filter_map(|v| {
+                Some(UnitTiming {
+                    name: v.get("target")?.get("name")?.as_str()?.to_string(),
+                    duration_secs: v.get("duration")?.as_f64()?,
+                })
+            })
+            .collect();
+        if units.is_empty() {
+            return Err(AnalysisError::Parse(
+                "Не распознан формат отчёта о времени сборки".into(),
+            ));
+        }
+        Ok(BuildTimings::Units(units))
+    }
+
+    /// Накладывает время сборки на граф, записывая `build_time_ms` в метаданные капсул.
+    /// Время крейта распределяется по его капсулам пропорционально размеру.
+    pub fn attribute(
+        &self,
+        graph: &mut CapsuleGraph,
+        timings: &BuildTimings,
+        project_root: &Path,
+    ) -> BuildTimingsReport {
+        let mut by_unit: HashMap<String, f64> = HashMap::new();
+        // (узел сборки, секунды, капсулы узла)
+        let mut local_units: Vec<(String, f64, Vec<Uuid>)> = Vec::new();
+
+        match timings {
+            BuildTimings::Units(units) => {
+                let crates = local_crate_dirs(project_root);
+                let mut members: HashMap<String, Vec<Uuid>> = HashMap::new();
+                for capsule in graph.capsules.values() {
+                    if let Some(name) = owning_crate(&crates, &capsule.file_path) {
+                        members.entry(name).or_default().push(capsule.id);
+                    }
+                }
+                for unit in units {
+                    let key = unit.name.replace('-', "_");
+                    *by_unit.entry(unit.name.clone()).or_insert(0.0) += unit.duration_secs;
+                    if let Some(ids) = members.get(&key) {
+                        match local_units.iter_mut().find(|(n, _, _)| *n == unit.name) {
+                            Some(entry) => entry.1 += unit.duration_secs,
+                            None => local_units.push((
+                                unit.name.clone(),
+                                unit.duration_secs,
+                                ids.clone(),
+                            )),
+                        }
+                    }
+                }
+            }
+            BuildTimings::Modules(modules) => {
+                let locator = CapsuleLocator::new(graph);
+                for module in modules {
+                    let secs = module.millis / 1000.0;
+                    *by_unit.entry(module.path.clone()).or_insert(0.0) += secs;
+                    let ids = locator.capsules_for_file(&module.path);
+                    if !ids.is_empty() {
+                        local_units.push((module.path.clone(), secs, ids));
+                    }
+                }
+            }
+        }
+
+        let mut per_capsule: HashMap<Uuid, f64> = HashMap::new();
+        for (_, secs, ids) in &local_units {
+            let total_size: usize = ids
+                .iter()
+                .filter_map(|id| graph.capsules.get(id))
+                .map(|c| c.size.max(1))
+                .sum();
+            for id in ids {
+                if let Some(c) = graph.capsules.get(id) {
+                    let share = c.size.max(1) as f64 / total_size.max(1) as f64;
+                    *per_capsule.entry(*id).or_insert(0.0) += secs * share;
+                }
+            }
+        }
+
+        let mut by_file: HashMap<String, f64> = HashMap::new();
+        let mut by_capsule: HashMap<String, f64> = HashMap::new();
+        for (id, secs) in &per_capsule {
+            if let Some(capsule) = graph.capsules.get_mut(id) {
+                capsule
+                    .metadata
+                    .insert(BUILD_TIME_KEY.to_string(), format!("{:.0}", secs * 1000.0));
+                *by_file
+                    .entry(capsule.file_path.display().to_string())
+                    .or_insert(0.0) += secs;
+                *by_capsule.entry(capsule.name.clone()).or_insert(0.0) += secs;
+            }
+        }
+
+        let mut split_candidates: Vec<SplitCandidate> = local_units
+            .iter()
+            .filter(|(_, secs, _)| *secs >= self.split_threshold_secs)
+            .map(|(name, secs, ids)| {
+                let mut files: HashMap<String, f64> = HashMap::new();
+                for id in ids {
+                    if let (Some(c), Some(t)) = (graph.capsules.get(id), per_capsule.get(id)) {
+                        *files
+                            .entry(c.file_path.display().to_string())
+                            .or_insert(0.0) += t;
+                    }
+                }
+                SplitCandidate {
+                    name: name.clone(),
+                    seconds: *secs,
+                    capsules: ids.len(),
+                    heaviest_files: rank_entries(files, *secs, 3)
+                        .into_iter()
+                        .map(|e| e.name)
+                        .collect(),
+                }
+            })
+            .collect();
+        split_candidates.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+
+        let total: f64 = by_unit.values().sum();
+        let local: f64 = local_units.iter().map(|(_, s, _)| s).sum();
+        BuildTimingsReport {
+            total_seconds: total,
+            local_seconds: local,
+            by_unit: rank_entries(by_unit, total, self.top_n),
+            by_file: rank_entries(by_file, local, self.top_n),
+            by_capsule: rank_entries(by_capsule, local, self.top_n),
+            split_candidates,
+        }
+    }
+}
+
+impl Default for BuildTimingsAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `const UNIT_DATA = [...]` из `cargo-timing.html`
+fn parse_cargo_timing_html(text: &str) -> Result<Option<Vec<UnitTiming>>> {
+    let Some(start) = text.find("UNIT_DATA") else {
+        return Ok(None);
+    };
+    let rest = &text[start..];
+    let (Some(open), Some(close)) = (rest.find('['), rest.find("];")) else {
+        return Ok(None);
+    };
+    if close < open {
+        return Ok(None);
+    }
+    #[derive(Deserialize)]
+    struct RawUnit {
+        name: String,
+        duration: f64,
+    }
+    let raw: Vec<RawUnit> = serde_json::from_str(&rest[open..=close])
+        .map_err(|e| AnalysisError::Parse(format!("UNIT_DATA: {}", e)))?;
+    Ok(Some(
+        raw.into_iter()
+            .map(|u| UnitTiming {
+                name: u.name,
+                duration_secs: u.duration,
+            })
+            .collect(),
+    ))
+}
+
+/// Модули webpack с `profile` (`webpack --profile --json`)
+fn parse_webpack_modules(modules: &[serde_json::Value]) -> Vec<ModuleTiming> {
+    modules
+        .iter()
+        .filter_map(|m| {
+            let path = m.get("name")?.as_str()?;
+            let profile = m.get("profile")?.as_object()?;
+            let millis: f64 = profile.values().filter_map(|v| v.as_f64()).sum();
+            Some(ModuleTiming {
+                path: path.trim_start_matches("./").to_string(),
+                millis,
+            })
+        })
+        .collect()
+}
+
+/// Каталоги собственных крейтов проекта: имя пакета (через `_`) → каталог
+fn local_crate_dirs(project_root: &Path) -> Vec<(String, PathBuf)> {
+    let mut crates: Vec<(String, PathBuf)> =
+        crate::dependency_freshness::find_manifests(project_root)
+            .into_iter()
+            .filter(|m| m.file_name().map(|n| n == "Cargo.toml").unwrap_or(false))
+            .filter_map(|m| {
+                let dir = m.parent()?.to_path_buf();
+                let name = super::rust_crate_name(&dir)?;
+                Some((name.replace('-', "_"), dir))
+            })
+            .collect();
+    // Вложенные крейты проверяются раньше родительских
+    crates.sort_by_key(|(_, dir)| std::cmp::Reverse(dir.components().count()));
+    crates
+}
+
+fn owning_crate(crates: &[(String, PathBuf)], file: &Path) -> Option<String> {
+    crates
+        .iter()
+        .find(|(_, dir)| file.starts_with(dir))
+        .map(|(name, _)| name.clone())
+}
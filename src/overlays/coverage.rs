@@ -0,0 +1,292 @@
+// Оверлей покрытия: lcov (`lcov.info`) и Cobertura XML.
+// Реальное покрытие строк/ветвей записывается в капсулы и заменяет эвристическую оценку
+
+use super::CapsuleLocator;
+use crate::enrichment::QualityAnalyzer;
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Ключи метаданных капсулы (доля 0.0–1.0)
+pub const LINE_COVERAGE_KEY: &str = "line_coverage";
+pub const BRANCH_COVERAGE_KEY: &str = "branch_coverage";
+
+/// Покрытие одного файла
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileCoverage {
+    pub path: String,
+    /// строка → число исполнений
+    pub lines: BTreeMap<usize, u64>,
+    /// строка → (покрыто ветвей, всего ветвей)
+    pub branches: BTreeMap<usize, (usize, usize)>,
+}
+
+/// Капсула в квадранте риска
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskItem {
+    pub component: String,
+    pub complexity: u32,
+    pub line_coverage: f64,
+}
+
+/// Квадранты «сложность × покрытие»
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageRiskQuadrants {
+    /// Сложные и слабо покрытые — главный риск
+    pub high_risk: Vec<RiskItem>,
+    pub complex_covered: usize,
+    pub simple_uncovered: usize,
+    pub healthy: usize,
+}
+
+/// Итог наложения покрытия
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub files: usize,
+    pub mapped_files: usize,
+    pub capsules_with_coverage: usize,
+    pub line_coverage: f64,
+    pub branch_coverage: Option<f64>,
+    pub quadrants: CoverageRiskQuadrants,
+}
+
+/// Наложение отчётов покрытия на граф
+#[derive(Debug)]
+pub struct CoverageOverlay {
+    pub complexity_threshold: u32,
+    pub coverage_threshold: f64,
+}
+
+impl CoverageOverlay {
+    pub fn new() -> Self {
+        Self {
+            complexity_threshold: 10,
+            coverage_threshold: 0.5,
+        }
+    }
+
+    /// Автоопределение формата: Cobertura XML или lcov tracefile
+    pub fn parse(&self, text: &str) -> Result<Vec<FileCoverage>> {
+        if text.contains("<coverage") {
+            parse_cobertura(text)
+        } else if text.contains("SF:") {
+            Ok(parse_lcov(text))
+        } else {
+            Err(AnalysisError::Parse(
+                "Не распознан формат покрытия (ожидается lcov или Cobertura)".into(),
+            ))
+        }
+    }
+
+    /// Записывает покрытие в капсулы и пересчитывает их `quality_score`
+    /// по измеренному покрытию вместо эвристики
+    pub fn apply(&self, graph: &mut CapsuleGraph, files: &[FileCoverage]) -> CoverageReport {
+        let locator = CapsuleLocator::new(graph);
+        let quality = QualityAnalyzer::new();
+        let mut report = CoverageReport {
+            files: files.len(),
+            ..Default::default()
+        };
+        let (mut covered_total, mut lines_total) = (0usize, 0usize);
+        let (mut br_covered_total, mut br_total) = (0usize, 0usize);
+
+        for file in files {
+            covered_total += file.lines.values().filter(|h| **h > 0).count();
+            lines_total += file.lines.len();
+            for (c, t) in file.branches.values() {
+                br_covered_total += c;
+                br_total += t;
+            }
+            let ids = locator.capsules_for_file(&file.path);
+            if ids.is_empty() {
+                continue;
+            }
+            report.mapped_files += 1;
+            // исходник нужен для оценки качества; без него остаётся прежняя оценка
+            let source = graph
+                .capsules
+                .get(&ids[0])
+                .and_then(|c| std::fs::read_to_string(&c.file_path).ok());
+            for id in ids {
+                let Some(capsule) = graph.capsules.get_mut(&id) else {
+                    continue;
+                };
+                let range = capsule.line_start..=capsule.line_end.max(capsule.line_start);
+                let hits: Vec<u64> = file.lines.range(range.clone()).map(|(_, h)| *h).collect();
+                if hits.is_empty() {
+                    continue;
+                }
+                let covered = hits.iter().filter(|h| **h > 0).count();
+                capsule.metadata.insert(
+                    LINE_COVERAGE_KEY.to_string(),
+                    format!("{:.3}", covered as f64 / hits.len() as f64),
+                );
+                let (bc, bt) = file
+                    .branches
+                    .range(range)
+                    .fold((0, 0), |acc, (_, (c, t))| (acc.0 + c, acc.1 + t));
+                if bt > 0 {
+                    capsule.metadata.insert(
+                        BRANCH_COVERAGE_KEY.to_string(),
+                        format!("{:.3}", bc as f64 / bt as f64),
+                    );
+                }
+                if let Some(source) = &source {
+                    let body = capsule_source(source, capsule.line_start, capsule.line_end);
+                    if let Ok(assessment) = quality.analyze_quality(capsule, &body) {
+                        capsule.quality_score = f64::from(assessment.overall_score) / 100.0;
+                    }
+                }
+                report.capsules_with_coverage += 1;
+            }
+        }
+
+        report.line_coverage = if lines_total > 0 {
+            covered_total as f64 / lines_total as f64
+        } else {
+            0.0
+        };
+        report.branch_coverage = (br_total > 0).then(|| br_covered_total as f64 / br_total as f64);
+        report.quadrants =
+            risk_quadrants(graph, self.complexity_threshold, self.coverage_threshold);
+        report
+    }
+}
+
+impl Default for CoverageOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Строки капсулы `start..=end` (нумерация с 1)
+fn capsule_source(source: &str, start: usize, end: usize) -> String {
+    source
+        .lines()
+        .skip(start.saturating_sub(1))
+        .take(end.max(start) + 1 - start.max(1))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Квадранты риска по капсулам, у которых есть измеренное покрытие
+pub fn risk_quadrants(
+    graph: &CapsuleGraph,
+    complexity_threshold: u32,
+    coverage_threshold: f64,
+) -> CoverageRiskQuadrants {
+    let mut q = CoverageRiskQuadrants::default();
+    for capsule in graph.capsules.values() {
+        let Some(coverage) = capsule
+            .metadata
+            .get(LINE_COVERAGE_KEY)
+            .and_then(|v| v.parse::<f64>().ok())
+        else {
+            continue;
+        };
+        let complex = capsule.complexity >= complexity_threshold;
+        let covered = coverage >= coverage_threshold;
+        match (complex, covered) {
+            (true, false) => q.high_risk.push(RiskItem {
+                component: capsule.name.clone(),
+                complexity: capsule.complexity,
+                line_coverage: coverage,
+            }),
+            (true, true) => q.complex_covered += 1,
+            (false, false) => q.simple_uncovered += 1,
+            (false, true) => q.healthy += 1,
+        }
+    }
+    q.high_risk.sort_by(|a, b| {
+        b.complexity
+            .cmp(&a.complexity)
+            .then(a.line_coverage.total_cmp(&b.line_coverage))
+            .then(a.component.cmp(&b.component))
+    });
+    q
+}
+
+/// Разбор lcov tracefile (`SF`, `DA`, `BRDA`, `end_of_record`)
+fn parse_lcov(text: &str) -> Vec<FileCoverage> {
+    let mut files = Vec::new();
+    let mut current: Option<FileCoverage> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some(FileCoverage {
+                path: path.to_string(),
+                ..Default::default()
+            });
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let mut parts = data.split(',');
+            if let (Some(Ok(n)), Some(Ok(h)), Some(file)) = (
+                parts.next().map(str::parse::<usize>),
+                parts.next().map(str::parse::<u64>),
+                current.as_mut(),
+            ) {
+                *file.lines.entry(n).or_insert(0) += h;
+            }
+        } else if let Some(data) = line.strip_prefix("BRDA:") {
+            let parts: Vec<&str> = data.split(',').collect();
+            if let (Some(Ok(n)), Some(taken), Some(file)) = (
+                parts.first().map(|s| s.parse::<usize>()),
+                parts.get(3),
+                current.as_mut(),
+            ) {
+                let hit = *taken != "-" && taken.parse::<u64>().map(|t| t > 0).unwrap_or(false);
+                let e = file.branches.entry(n).or_insert((0, 0));
+                e.0 += hit as usize;
+                e.1 += 1;
+            }
+        } else if line == "end_of_record" {
+            if let Some(file) = current.take() {
+                files.push(file);
+            }
+        }
+    }
+    if let Some(file) = current {
+        files.push(file);
+    }
+    files
+}
+
+/// Разбор Cobertura XML (`<class filename>` + `<line number hits condition-coverage>`)
+fn parse_cobertura(text: &str) -> Result<Vec<FileCoverage>> {
+    let class_re = Regex::new(r#"(?s)<class\b[^>]*\bfilename="([^"]+)"[^>]*>(.*?)</class>"#)?;
+    let line_re = Regex::new(r#"<line\b([^>]*)/?>"#)?;
+    let attr_re = Regex::new(r#"([\w-]+)="([^"]*)""#)?;
+    let cond_re = Regex::new(r"\((\d+)/(\d+)\)")?;
+    let mut by_path: HashMap<String, FileCoverage> = HashMap::new();
+    for class in class_re.captures_iter(text) {
+        let path = class[1].to_string();
+        let file = by_path.entry(path.clone()).or_insert_with(|| FileCoverage {
+            path,
+            ..Default::default()
+        });
+        for line in line_re.captures_iter(&class[2]) {
+            let attrs: HashMap<&str, &str> = attr_re
+                .captures_iter(&line[1])
+                .filter_map(|a| Some((a.get(1)?.as_str(), a.get(2)?.as_str())))
+                .collect();
+            let (Some(Ok(n)), Some(Ok(h))) = (
+                attrs.get("number").map(|v| v.parse::<usize>()),
+                attrs.get("hits").map(|v| v.parse::<u64>()),
+            ) else {
+                continue;
+            };
+            *file.lines.entry(n).or_insert(0) += h;
+            if let Some(cond) = attrs
+                .get("condition-coverage")
+                .and_then(|c| cond_re.captures(c))
+            {
+                let covered = cond[1].parse().unwrap_or(0);
+                let total = cond[2].parse().unwrap_or(0);
+                file.branches.insert(n, (covered, total));
+            }
+        }
+    }
+    let mut files: Vec<FileCoverage> = by_path.into_values().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
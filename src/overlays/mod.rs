@@ -0,0 +1,216 @@
+// Оверлеи внешних данных поверх статического графа капсул.
+// Каждый оверлей разбирает отчёт внешнего инструмента, сопоставляет его записи
+// с капсулами и записывает результат в `Capsule::metadata`.
+
+pub mod binary_size;
+pub mod build_timings;
+pub mod coverage;
+pub mod profile;
+pub mod test_reports;
+pub mod traces;
+
+pub use binary_size::*;
+pub use build_timings::*;
+pub use coverage::*;
+pub use profile::*;
+pub use test_reports::*;
+pub use traces::*;
+
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Доля величины в общем итоге (для отчётов оверлеев)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverlayEntry {
+    pub name: String,
+    pub value: f64,
+    pub share: f64,
+}
+
+/// Сортирует агрегаты по убыванию и превращает их в записи отчёта
+pub fn rank_entries(values: HashMap<String, f64>, total: f64, limit: usize) -> Vec<OverlayEntry> {
+    let mut items: Vec<(String, f64)> = values.into_iter().collect();
+    items.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    items
+        .into_iter()
+        .take(limit)
+        .map(|(name, value)| OverlayEntry {
+            share: if total > 0.0 { value / total } else { 0.0 },
+            name,
+            value,
+        })
+        .collect()
+}
+
+/// Отчёты внешних инструментов для графа analyze/export
+/// (`--coverage <file>`, `--profile <file>`)
+#[derive(Debug, Clone, Default)]
+pub struct OverlayInputs {
+    /// lcov или Cobertura XML
+    pub coverage: Option<PathBuf>,
+    /// `perf script`, свёрнутые стеки или pprof
+    pub profile: Option<PathBuf>,
+}
+
+/// Накладывает на граф пайплайна отчёты из флагов analyze/export
+pub fn apply_configured(graph: &mut CapsuleGraph, inputs: &OverlayInputs) -> Result<()> {
+    if let Some(path) = &inputs.coverage {
+        let overlay = CoverageOverlay::new();
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| AnalysisError::Io(format!("{}: {}", path.display(), e)))?;
+        let files = overlay.parse(&raw)?;
+        overlay.apply(graph, &files);
+    }
+    if let Some(path) = &inputs.profile {
+        let overlay = ProfileOverlay::new();
+        // pprof — бинарный (обычно gzip), поэтому читаем байты
+        let raw = std::fs::read(path)
+            .map_err(|e| AnalysisError::Io(format!("{}: {}", path.display(), e)))?;
+        let samples = overlay.parse(&raw)?;
+        overlay.apply(graph, &samples);
+    }
+    Ok(())
+}
+
+/// Индекс капсул для сопоставления путей, модулей и символов из внешних отчётов
+pub struct CapsuleLocator {
+    /// Компоненты пути файла (в нижнем регистре, без расширения у последнего) → капсулы
+    files: Vec<(Vec<String>, Vec<Uuid>)>,
+    by_name: HashMap<String, Vec<Uuid>>,
+}
+
+impl CapsuleLocator {
+    pub fn new(graph: &CapsuleGraph) -> Self {
+        let mut per_file: HashMap<Vec<String>, Vec<Uuid>> = HashMap::new();
+        let mut by_name: HashMap<String, Vec<Uuid>> = HashMap::new();
+        for capsule in graph.capsules.values() {
+            per_file
+                .entry(path_components(&capsule.file_path))
+                .or_default()
+                .push(capsule.id);
+            by_name
+                .entry(capsule.name.clone())
+                .or_default()
+                .push(capsule.id);
+        }
+        let mut files: Vec<(Vec<String>, Vec<Uuid>)> = per_file.into_iter().collect();
+        files.sort();
+        Self { files, by_name }
+    }
+
+    /// Капсулы файла; сравнение по наибольшему общему суффиксу пути
+    pub fn capsules_for_file(&self, path: &str) -> Vec<Uuid> {
+        self.lookup_file(path, false)
+    }
+
+    /// Капсулы файла, путь которого целиком оканчивается на `path` (`cli/handlers.rs`)
+    pub fn capsules_for_path_suffix(&self, path: &str) -> Vec<Uuid> {
+        self.lookup_file(path, true)
+    }
+
+    fn lookup_file(&self, path: &str, require_full: bool) -> Vec<Uuid> {
+        let wanted = path_components(Path::new(path));
+        if wanted.is_empty() {
+            return Vec::new();
+        }
+        let mut best: Option<(usize, &Vec<Uuid>)> = None;
+        for (components, ids) in &self.files {
+            let common = common_suffix(components, &wanted);
+            // имя файла должно совпадать целиком
+            if common == 0 || (require_full && common < wanted.len()) {
+                continue;
+            }
+            if best.map(|(n, _)| common > n).unwrap_or(true) {
+                best = Some((common, ids));
+            }
+        }
+        best.map(|(_, ids)| ids.clone()).unwrap_or_default()
+    }
+
+    /// Капсула, содержащая строку `line` файла `path` (самый узкий диапазон)
+    pub fn capsule_at(&self, graph: &CapsuleGraph, path: &str, line: usize) -> Option<Uuid> {
+        self.capsules_for_file(path)
+            .into_iter()
+            .filter_map(|id| graph.capsules.get(&id))
+            .filter(|c| c.line_start <= line && line <= c.line_end.max(c.line_start))
+            .min_by_key(|c| (c.line_end.saturating_sub(c.line_start), c.line_start))
+            .map(|c| c.id)
+    }
+
+    /// Капсула по квалифицированному символу (`crate::module::Type::method`).
+    /// Кандидаты по последнему сегменту ранжируются совпадением остальных сегментов с путём.
+    pub fn capsule_for_symbol(&self, graph: &CapsuleGraph, symbol: &str) -> Option<Uuid> {
+        let segments = symbol_segments(symbol);
+        let (last, rest) = segments.split_last()?;
+        let candidates = self.by_name.get(last)?;
+        candidates
+            .iter()
+            .filter_map(|id| graph.capsules.get(id))
+            .map(|c| {
+                let comps = path_components(&c.file_path);
+                let score = rest
+                    .iter()
+                    .filter(|s| comps.contains(&s.to_lowercase()))
+                    .count();
+                (score, c.id)
+            })
+            .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)))
+            .map(|(_, id)| id)
+    }
+}
+
+/// Сегменты символа без generic-параметров и хэш-суффикса (`::h0123abcd…`)
+pub fn symbol_segments(symbol: &str) -> Vec<String> {
+    let mut plain = String::with_capacity(symbol.len());
+    let mut depth = 0usize;
+    for ch in symbol.chars() {
+        match ch {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => plain.push(ch),
+            _ => {}
+        }
+    }
+    let mut segments: Vec<String> = plain
+        .split("::")
+        .map(|s| s.trim().to_string())
+        // `{{closure}}`, `{vtable.shim}` и т.п. не являются именами
+        .filter(|s| !s.is_empty() && !s.starts_with('{'))
+        .collect();
+    if let Some(last) = segments.last() {
+        let is_hash = last.len() == 17
+            && last.starts_with('h')
+            && last[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if is_hash {
+            segments.pop();
+        }
+    }
+    segments
+}
+
+fn path_components(path: &Path) -> Vec<String> {
+    let mut comps: Vec<String> = path
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(s) => Some(s.to_string_lossy().to_lowercase()),
+            _ => None,
+        })
+        .collect();
+    if let Some(last) = comps.last_mut() {
+        if let Some((stem, _)) = last.rsplit_once('.') {
+            *last = stem.to_string();
+        }
+    }
+    comps
+}
+
+fn common_suffix(a: &[String], b: &[String]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
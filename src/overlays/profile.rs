@@ -0,0 +1,482 @@
+// Оверлей профиля CPU: `perf script`, свёрнутые стеки (inferno/stackcollapse, py-spy)
+// и pprof (protobuf, обычно gzip). Доля сэмплов записывается в капсулы,
+// чтобы горячие точки производительности были видны рядом с архитектурными
+
+use super::{path_components, rank_entries, symbol_segments, CapsuleLocator, OverlayEntry};
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use uuid::Uuid;
+
+/// Ключи метаданных капсулы (доля сэмплов 0.0–1.0)
+pub const CPU_SELF_SHARE_KEY: &str = "cpu_self_share";
+pub const CPU_TOTAL_SHARE_KEY: &str = "cpu_total_share";
+
+/// Кадр стека
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileFrame {
+    pub function: String,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+/// Сэмпл: стек от листа к корню и его вес
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSample {
+    pub frames: Vec<ProfileFrame>,
+    pub weight: u64,
+}
+
+/// Капсула, горячая по CPU, вместе с её архитектурными метриками
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuHotspot {
+    pub component: String,
+    pub self_share: f64,
+    pub total_share: f64,
+    pub complexity: u32,
+    pub fan_in: usize,
+    pub fan_out: usize,
+    /// Совпадает со сложностным (архитектурным) хотспотом
+    pub architectural: bool,
+}
+
+/// Итог наложения профиля
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileReport {
+    pub samples: usize,
+    pub total_weight: u64,
+    /// Вес сэмплов, в стеке которых нашлась хотя бы одна капсула проекта
+    pub mapped_weight: u64,
+    pub by_capsule_self: Vec<OverlayEntry>,
+    pub by_capsule_total: Vec<OverlayEntry>,
+    pub hotspots: Vec<CpuHotspot>,
+}
+
+/// Наложение сэмплированного профиля CPU на граф
+#[derive(Debug)]
+pub struct ProfileOverlay {
+    pub top_n: usize,
+    /// Порог сложности архитектурного хотспота
+    pub complexity_threshold: u32,
+}
+
+impl ProfileOverlay {
+    pub fn new() -> Self {
+        Self {
+            top_n: 20,
+            complexity_threshold: 10,
+        }
+    }
+
+    /// Автоопределение формата: pprof (gzip/protobuf), `perf script` или свёрнутые стеки
+    pub fn parse(&self, data: &[u8]) -> Result<Vec<ProfileSample>> {
+        if data.starts_with(&[0x1f, 0x8b]) {
+            let mut raw = Vec::new();
+            flate2::read::GzDecoder::new(data)
+                .read_to_end(&mut raw)
+                .map_err(|e| AnalysisError::Parse(format!("pprof gzip: {}", e)))?;
+            return parse_pprof(&raw);
+        }
+        let Ok(text) = std::str::from_utf8(data) else {
+            return parse_pprof(data);
+        };
+        let samples = if is_perf_script(text) {
+            parse_perf_script(text)
+        } else {
+            parse_folded(text)
+        };
+        if samples.is_empty() {
+            return Err(AnalysisError::Parse(
+                "Не распознан формат профиля (ожидается perf script, свёрнутые стеки или pprof)"
+                    .into(),
+            ));
+        }
+        Ok(samples)
+    }
+
+    /// Self-время достаётся ближайшей к листу капсуле проекта (время в std/рантайме
+    /// засчитывается вызвавшему коду), total — каждой капсуле стека один раз
+    pub fn apply(&self, graph: &mut CapsuleGraph, samples: &[ProfileSample]) -> ProfileReport {
+        let locator = CapsuleLocator::new(graph);
+        let mut cache: HashMap<(String, Option<String>, Option<usize>), Option<Uuid>> =
+            HashMap::new();
+        let mut self_weight: HashMap<Uuid, u64> = HashMap::new();
+        let mut total_weight: HashMap<Uuid, u64> = HashMap::new();
+        let mut report = ProfileReport {
+            samples: samples.len(),
+            total_weight: samples.iter().map(|s| s.weight).sum(),
+            ..Default::default()
+        };
+
+        for sample in samples {
+            let stack: Vec<Uuid> = sample
+                .frames
+                .iter()
+                .filter_map(|f| {
+                    *cache
+                        .entry((f.function.clone(), f.file.clone(), f.line))
+                        .or_insert_with(|| locate(graph, &locator, f))
+                })
+                .collect();
+            let Some(leaf) = stack.first() else {
+                continue;
+            };
+            report.mapped_weight += sample.weight;
+            *self_weight.entry(*leaf).or_insert(0) += sample.weight;
+            for id in stack.iter().collect::<HashSet<_>>() {
+                *total_weight.entry(*id).or_insert(0) += sample.weight;
+            }
+        }
+
+        let total = report.total_weight.max(1) as f64;
+        let mut by_self: HashMap<String, f64> = HashMap::new();
+        let mut by_total: HashMap<String, f64> = HashMap::new();
+        for (id, weight) in &total_weight {
+            let Some(capsule) = graph.capsules.get_mut(id) else {
+                continue;
+            };
+            let own = self_weight.get(id).copied().unwrap_or(0);
+            capsule.metadata.insert(
+                CPU_SELF_SHARE_KEY.to_string(),
+                format!("{:.4}", own as f64 / total),
+            );
+            capsule.metadata.insert(
+                CPU_TOTAL_SHARE_KEY.to_string(),
+                format!("{:.4}", *weight as f64 / total),
+            );
+            *by_self.entry(capsule.name.clone()).or_insert(0.0) += own as f64;
+            *by_total.entry(capsule.name.clone()).or_insert(0.0) += *weight as f64;
+        }
+        by_self.retain(|_, w| *w > 0.0);
+
+        report.by_capsule_self = rank_entries(by_self, total, self.top_n);
+        report.by_capsule_total = rank_entries(by_total, total, self.top_n);
+        report.hotspots = cpu_hotspots(graph, self.complexity_threshold);
+        report.hotspots.truncate(self.top_n);
+        report
+    }
+}
+
+impl Default for ProfileOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Капсулы с измеренной долей CPU, по убыванию self-доли
+pub fn cpu_hotspots(graph: &CapsuleGraph, complexity_threshold: u32) -> Vec<CpuHotspot> {
+    let share = |c: &crate::types::Capsule, key: &str| {
+        c.metadata
+            .get(key)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+    let mut hotspots: Vec<CpuHotspot> = graph
+        .capsules
+        .values()
+        .filter(|c| c.metadata.contains_key(CPU_TOTAL_SHARE_KEY))
+        .map(|c| CpuHotspot {
+            component: c.name.clone(),
+            self_share: share(c, CPU_SELF_SHARE_KEY),
+            total_share: share(c, CPU_TOTAL_SHARE_KEY),
+            complexity: c.complexity,
+            fan_in: graph.relations.iter().filter(|r| r.to_id == c.id).count(),
+            fan_out: graph.relations.iter().filter(|r| r.from_id == c.id).count(),
+            architectural: c.complexity >= complexity_threshold,
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.self_share
+            .total_cmp(&a.self_share)
+            .then(b.total_share.total_cmp(&a.total_share))
+            .then(a.component.cmp(&b.component))
+    });
+    hotspots
+}
+
+/// Капсула кадра: по файлу/строке, иначе по символу, у которого путь модуля
+/// пересекается с путём файла (чтобы `std::vec::Vec::push` не попал в чужой `push`)
+fn locate(graph: &CapsuleGraph, locator: &CapsuleLocator, frame: &ProfileFrame) -> Option<Uuid> {
+    if let (Some(file), Some(line)) = (&frame.file, frame.line) {
+        if let Some(id) = locator.capsule_at(graph, file, line) {
+            return Some(id);
+        }
+    }
+    let symbol = normalize_symbol(&frame.function);
+    let segments = symbol_segments(&symbol);
+    let id = locator.capsule_for_symbol(graph, &symbol)?;
+    if segments.len() < 2 {
+        return Some(id);
+    }
+    let comps = path_components(&graph.capsules.get(&id)?.file_path);
+    let (_, module) = segments.split_last()?;
+    module
+        .iter()
+        .any(|s| comps.contains(&s.to_lowercase()))
+        .then_some(id)
+}
+
+/// `main.(*Server).Handle`, `com.acme.Foo.bar` → `main::Server::Handle`, `com::acme::Foo::bar`
+fn normalize_symbol(function: &str) -> String {
+    let name = function
+        .split_once(" (")
+        .map(|(n, _)| n)
+        .unwrap_or(function)
+        .trim();
+    let name = name.replace(['(', ')', '*', '&'], "");
+    if name.contains("::") {
+        name
+    } else {
+        name.replace('.', "::")
+    }
+}
+
+/// `perf script`: заголовок сэмпла без отступа, кадры с отступом, блоки через пустую строку
+fn is_perf_script(text: &str) -> bool {
+    text.lines().any(|l| {
+        l.starts_with(|c: char| c.is_whitespace())
+            && l.split_whitespace()
+                .next()
+                .map(|t| t.len() >= 4 && t.chars().all(|c| c.is_ascii_hexdigit()))
+                .unwrap_or(false)
+    })
+}
+
+fn parse_perf_script(text: &str) -> Vec<ProfileSample> {
+    let mut samples = Vec::new();
+    let mut frames: Vec<ProfileFrame> = Vec::new();
+    let mut in_sample = false;
+    for line in text.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            if in_sample && !frames.is_empty() {
+                samples.push(ProfileSample {
+                    frames: std::mem::take(&mut frames),
+                    weight: 1,
+                });
+            }
+            in_sample = false;
+            frames.clear();
+            continue;
+        }
+        if !line.starts_with(|c: char| c.is_whitespace()) {
+            in_sample = !line.starts_with('#');
+            continue;
+        }
+        // `    55d0c0a1b2c3 archlens::parser_ast::parse+0x23 (/path/to/bin)`
+        let rest = line.trim();
+        let rest = rest.split_once(' ').map(|(_, r)| r).unwrap_or(rest);
+        let symbol = match rest.rfind(" (") {
+            Some(i) if rest.ends_with(')') => &rest[..i],
+            _ => rest,
+        };
+        let symbol = match symbol.rfind("+0x") {
+            Some(i) => &symbol[..i],
+            None => symbol,
+        };
+        if symbol != "[unknown]" && !symbol.is_empty() {
+            frames.push(ProfileFrame {
+                function: symbol.to_string(),
+                file: None,
+                line: None,
+            });
+        }
+    }
+    samples
+}
+
+/// Свёрнутые стеки `root;...;leaf <count>`; кадры py-spy `func (file.py:42)`
+fn parse_folded(text: &str) -> Vec<ProfileSample> {
+    text.lines()
+        .filter_map(|line| {
+            let (stack, count) = line.trim().rsplit_once(' ')?;
+            let weight = count.parse::<u64>().ok()?;
+            let frames = stack
+                .split(';')
+                .rev()
+                .filter(|f| !f.is_empty())
+                .map(|f| {
+                    let location = f
+                        .rsplit_once(" (")
+                        .and_then(|(_, loc)| loc.strip_suffix(')'))
+                        .and_then(|loc| loc.rsplit_once(':'))
+                        .and_then(|(file, line)| Some((file.to_string(), line.parse().ok()?)));
+                    ProfileFrame {
+                        function: f.to_string(),
+                        file: location.as_ref().map(|(file, _)| file.clone()),
+                        line: location.map(|(_, line)| line),
+                    }
+                })
+                .collect();
+            Some(ProfileSample { frames, weight })
+        })
+        .collect()
+}
+
+/// Поле protobuf: varint (в т.ч. fixed) или length-delimited
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf
+            .get(*pos)
+            .ok_or_else(|| AnalysisError::Parse("pprof: обрыв varint".into()))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(AnalysisError::Parse("pprof: слишком длинный varint".into()))
+}
+
+fn read_fields(buf: &[u8]) -> Result<Vec<(u64, Field<'_>)>> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    let truncated = || AnalysisError::Parse("pprof: обрыв сообщения".into());
+    while pos < buf.len() {
+        let key = read_varint(buf, &mut pos)?;
+        let field = match key & 7 {
+            0 => Field::Varint(read_varint(buf, &mut pos)?),
+            1 | 5 => {
+                let width = if key & 7 == 1 { 8 } else { 4 };
+                let bytes = buf.get(pos..pos + width).ok_or_else(truncated)?;
+                pos += width;
+                Field::Varint(
+                    bytes
+                        .iter()
+                        .rev()
+                        .fold(0u64, |acc, b| (acc << 8) | u64::from(*b)),
+                )
+            }
+            2 => {
+                let len = read_varint(buf, &mut pos)? as usize;
+                let bytes = buf.get(pos..pos + len).ok_or_else(truncated)?;
+                pos += len;
+                Field::Bytes(bytes)
+            }
+            wire => {
+                return Err(AnalysisError::Parse(format!(
+                    "pprof: неподдерживаемый wire type {}",
+                    wire
+                )))
+            }
+        };
+        fields.push((key >> 3, field));
+    }
+    Ok(fields)
+}
+
+/// Повторяемое числовое поле: упакованное или по одному значению
+fn push_varints(field: &Field, out: &mut Vec<u64>) -> Result<()> {
+    match field {
+        Field::Varint(v) => out.push(*v),
+        Field::Bytes(bytes) => {
+            let mut pos = 0;
+            while pos < bytes.len() {
+                out.push(read_varint(bytes, &mut pos)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn varint_field(fields: &[(u64, Field)], number: u64) -> u64 {
+    fields
+        .iter()
+        .find_map(|(n, f)| match f {
+            Field::Varint(v) if *n == number => Some(*v),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+/// Разбор `profile.proto` (pprof): sample/location/function/string_table
+fn parse_pprof(data: &[u8]) -> Result<Vec<ProfileSample>> {
+    let fields = read_fields(data)?;
+    let mut strings: Vec<String> = Vec::new();
+    let mut sample_types: Vec<u64> = Vec::new();
+    // function id → (name, filename)
+    let mut functions: HashMap<u64, (u64, u64)> = HashMap::new();
+    // location id → [(function id, line)], от встроенной функции к внешней
+    let mut locations: HashMap<u64, Vec<(u64, u64)>> = HashMap::new();
+    let mut raw_samples: Vec<(Vec<u64>, Vec<u64>)> = Vec::new();
+
+    for (number, field) in &fields {
+        let Field::Bytes(bytes) = field else {
+            continue;
+        };
+        match number {
+            1 => sample_types.push(varint_field(&read_fields(bytes)?, 1)),
+            2 => {
+                let (mut locs, mut values) = (Vec::new(), Vec::new());
+                for (n, f) in read_fields(bytes)? {
+                    match n {
+                        1 => push_varints(&f, &mut locs)?,
+                        2 => push_varints(&f, &mut values)?,
+                        _ => {}
+                    }
+                }
+                raw_samples.push((locs, values));
+            }
+            4 => {
+                let loc = read_fields(bytes)?;
+                let mut lines = Vec::new();
+                for (n, f) in &loc {
+                    if let (4, Field::Bytes(line)) = (n, f) {
+                        let line = read_fields(line)?;
+                        lines.push((varint_field(&line, 1), varint_field(&line, 2)));
+                    }
+                }
+                locations.insert(varint_field(&loc, 1), lines);
+            }
+            5 => {
+                let func = read_fields(bytes)?;
+                functions.insert(
+                    varint_field(&func, 1),
+                    (varint_field(&func, 2), varint_field(&func, 4)),
+                );
+            }
+            6 => strings.push(String::from_utf8_lossy(bytes).into_owned()),
+            _ => {}
+        }
+    }
+    if raw_samples.is_empty() && strings.is_empty() {
+        return Err(AnalysisError::Parse("pprof: профиль пуст".into()));
+    }
+
+    let string = |i: u64| strings.get(i as usize).cloned().unwrap_or_default();
+    // CPU-время предпочтительнее числа сэмплов
+    let value_index = ["cpu", "samples"]
+        .iter()
+        .find_map(|wanted| sample_types.iter().position(|t| string(*t) == *wanted))
+        .unwrap_or(0);
+
+    Ok(raw_samples
+        .into_iter()
+        .map(|(locs, values)| {
+            let frames = locs
+                .iter()
+                .flat_map(|id| locations.get(id).cloned().unwrap_or_default())
+                .filter_map(|(function_id, line)| {
+                    let (name, file) = functions.get(&function_id)?;
+                    let file = string(*file);
+                    Some(ProfileFrame {
+                        function: string(*name),
+                        file: (!file.is_empty()).then_some(file),
+                        line: (line > 0).then_some(line as usize),
+                    })
+                })
+                .collect();
+            ProfileSample {
+                frames,
+                weight: values.get(value_index).copied().unwrap_or(0),
+            }
+        })
+        .filter(|s| s.weight > 0)
+        .collect())
+}
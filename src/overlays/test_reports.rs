@@ -0,0 +1,312 @@
+// Нестабильные границы: отчёты JUnit / cargo-nextest (несколько прогонов)
+// сопоставляются с капсулами, нестабильность агрегируется по файлам-областям
+// вместе с метриками связанности этих областей
+
+use super::{symbol_segments, CapsuleLocator};
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Ключи метаданных капсулы
+pub const TEST_FAILURE_RATE_KEY: &str = "test_failure_rate";
+pub const FLAKY_TESTS_KEY: &str = "flaky_tests";
+
+/// Исход тестового случая в одном прогоне
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Тестовый случай из отчёта
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCaseResult {
+    pub classname: String,
+    pub name: String,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub outcome: TestOutcome,
+    /// nextest/surefire: тест упал и прошёл при перезапуске в этом же прогоне
+    pub retried: bool,
+}
+
+/// Нестабильность архитектурной области (файла)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstabilityArea {
+    pub area: String,
+    pub tests: usize,
+    pub runs: usize,
+    pub failures: usize,
+    pub flaky_tests: usize,
+    pub failure_rate: f64,
+    pub chronic: bool,
+    /// Связи, входящие в область извне (afferent coupling)
+    pub fan_in: usize,
+    /// Связи, выходящие из области (efferent coupling)
+    pub fan_out: usize,
+    /// Ce / (Ca + Ce)
+    pub instability_index: f64,
+}
+
+/// Итог анализа тестовых отчётов
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlakyBoundaryReport {
+    pub reports: usize,
+    pub test_cases: usize,
+    pub unmapped_tests: usize,
+    pub flaky_tests: Vec<String>,
+    pub areas: Vec<InstabilityArea>,
+}
+
+/// Детектор нестабильных границ по истории тестовых прогонов
+#[derive(Debug)]
+pub struct FlakyBoundaryDetector {
+    /// Доля падений, начиная с которой область считается хронически нестабильной
+    pub chronic_failure_rate: f64,
+}
+
+impl FlakyBoundaryDetector {
+    pub fn new() -> Self {
+        Self {
+            chronic_failure_rate: 0.25,
+        }
+    }
+
+    /// Разбор JUnit XML (в том числе вывода `cargo nextest --profile ci`)
+    pub fn parse_junit(&self, xml: &str) -> Result<Vec<TestCaseResult>> {
+        let case_re = Regex::new(r"(?s)<testcase\b([^>]*?)(/>|>(.*?)</testcase>)")?;
+        let attr_re = Regex::new(r#"([\w:-]+)\s*=\s*"([^"]*)""#)?;
+        if !xml.contains("<testsuite") && !xml.contains("<testcase") {
+            return Err(AnalysisError::Parse("Не найден JUnit <testsuite>".into()));
+        }
+        let mut cases = Vec::new();
+        for cap in case_re.captures_iter(xml) {
+            let attrs: HashMap<&str, String> = attr_re
+                .captures_iter(&cap[1])
+                .map(|a| {
+                    (
+                        a.get(1).map(|m| m.as_str()).unwrap_or(""),
+                        unescape_xml(&a[2]),
+                    )
+                })
+                .collect();
+            let body = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+            let outcome = if body.contains("<failure") || body.contains("<error") {
+                TestOutcome::Failed
+            } else if body.contains("<skipped") {
+                TestOutcome::Skipped
+            } else {
+                TestOutcome::Passed
+            };
+            let retried = body.contains("<flakyFailure")
+                || body.contains("<flakyError")
+                || body.contains("<rerunFailure");
+            cases.push(TestCaseResult {
+                classname: attrs.get("classname").cloned().unwrap_or_default(),
+                name: attrs.get("name").cloned().unwrap_or_default(),
+                file: attrs.get("file").cloned(),
+                line: attrs.get("line").and_then(|l| l.parse().ok()),
+                outcome,
+                retried,
+            });
+        }
+        Ok(cases)
+    }
+
+    /// Агрегирует прогоны (каждый элемент — один отчёт) по областям графа
+    pub fn analyze(
+        &self,
+        graph: &mut CapsuleGraph,
+        runs: &[Vec<TestCaseResult>],
+    ) -> FlakyBoundaryReport {
+        let locator = CapsuleLocator::new(graph);
+
+        // История каждого теста: (прогоны, падения, перезапуски) + капсулы области
+        #[derive(Default)]
+        struct History {
+            runs: usize,
+            failures: usize,
+            passes: usize,
+            retried: bool,
+            capsules: Vec<Uuid>,
+        }
+        let mut history: HashMap<String, History> = HashMap::new();
+        for run in runs {
+            for case in run {
+                if case.outcome == TestOutcome::Skipped {
+                    continue;
+                }
+                let key = format!("{}::{}", case.classname, case.name);
+                let entry = history.entry(key).or_default();
+                if entry.runs == 0 {
+                    entry.capsules = self.locate(graph, &locator, case);
+                }
+                entry.runs += 1;
+                match case.outcome {
+                    TestOutcome::Failed => entry.failures += 1,
+                    _ => entry.passes += 1,
+                }
+                entry.retried |= case.retried;
+            }
+        }
+
+        let mut report = FlakyBoundaryReport {
+            reports: runs.len(),
+            test_cases: history.len(),
+            ..Default::default()
+        };
+
+        // Область = файл капсул, к которым относится тест
+        #[derive(Default)]
+        struct AreaAcc {
+            tests: usize,
+            runs: usize,
+            failures: usize,
+            flaky: usize,
+            capsules: HashSet<Uuid>,
+        }
+        let mut areas: HashMap<String, AreaAcc> = HashMap::new();
+        let mut names: Vec<&String> = history.keys().collect();
+        names.sort();
+        for name in names {
+            let h = &history[name];
+            let flaky = h.retried || (h.failures > 0 && h.passes > 0);
+            if flaky {
+                report.flaky_tests.push(name.clone());
+            }
+            let Some(first) = h.capsules.first().and_then(|id| graph.capsules.get(id)) else {
+                report.unmapped_tests += 1;
+                continue;
+            };
+            let acc = areas
+                .entry(first.file_path.display().to_string())
+                .or_default();
+            acc.tests += 1;
+            acc.runs += h.runs;
+            acc.failures += h.failures;
+            acc.flaky += flaky as usize;
+            acc.capsules.extend(h.capsules.iter().copied());
+        }
+
+        for (area, acc) in areas {
+            let all_in_file: HashSet<Uuid> = locator.capsules_for_file(&area).into_iter().collect();
+            let fan_in = graph
+                .relations
+                .iter()
+                .filter(|r| all_in_file.contains(&r.to_id) && !all_in_file.contains(&r.from_id))
+                .count();
+            let fan_out = graph
+                .relations
+                .iter()
+                .filter(|r| all_in_file.contains(&r.from_id) && !all_in_file.contains(&r.to_id))
+                .count();
+            let failure_rate = if acc.runs > 0 {
+                acc.failures as f64 / acc.runs as f64
+            } else {
+                0.0
+            };
+            for id in &all_in_file {
+                if let Some(c) = graph.capsules.get_mut(id) {
+                    c.metadata.insert(
+                        TEST_FAILURE_RATE_KEY.to_string(),
+                        format!("{:.3}", failure_rate),
+                    );
+                    c.metadata
+                        .insert(FLAKY_TESTS_KEY.to_string(), acc.flaky.to_string());
+                }
+            }
+            report.areas.push(InstabilityArea {
+                area,
+                tests: acc.tests,
+                runs: acc.runs,
+                failures: acc.failures,
+                flaky_tests: acc.flaky,
+                failure_rate,
+                chronic: acc.flaky > 0 || failure_rate >= self.chronic_failure_rate,
+                fan_in,
+                fan_out,
+                instability_index: if fan_in + fan_out > 0 {
+                    fan_out as f64 / (fan_in + fan_out) as f64
+                } else {
+                    0.0
+                },
+            });
+        }
+        report.areas.sort_by(|a, b| {
+            b.chronic
+                .cmp(&a.chronic)
+                .then(b.failure_rate.total_cmp(&a.failure_rate))
+                .then(a.area.cmp(&b.area))
+        });
+        report
+    }
+
+    /// Капсулы, которые покрывает тест: по `file`/`line` или по пути модуля
+    /// из `classname`/`name` (`crate::parser_ast::tests::x`, `com.acme.OrderServiceTest`)
+    fn locate(
+        &self,
+        graph: &CapsuleGraph,
+        locator: &CapsuleLocator,
+        case: &TestCaseResult,
+    ) -> Vec<Uuid> {
+        if let Some(file) = &case.file {
+            if let Some(id) = case.line.and_then(|l| locator.capsule_at(graph, file, l)) {
+                return vec![id];
+            }
+            let ids = locator.capsules_for_file(file);
+            if !ids.is_empty() {
+                return ids;
+            }
+        }
+        let mut segments: Vec<String> = case
+            .classname
+            .split(['.', '/'])
+            .flat_map(symbol_segments)
+            .chain(symbol_segments(&case.name))
+            .collect();
+        // имя самого теста не является частью пути модуля
+        segments.pop();
+        let module: Vec<String> = segments
+            .into_iter()
+            .filter(|s| !matches!(s.as_str(), "tests" | "test" | "it"))
+            .map(|s| strip_test_affix(&s))
+            .filter(|s| !s.is_empty())
+            .collect();
+        for start in 0..module.len() {
+            for end in (start + 1..=module.len()).rev() {
+                let ids = locator.capsules_for_path_suffix(&module[start..end].join("/"));
+                if !ids.is_empty() {
+                    return ids;
+                }
+            }
+        }
+        Vec::new()
+    }
+}
+
+impl Default for FlakyBoundaryDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn strip_test_affix(segment: &str) -> String {
+    let s = segment
+        .strip_suffix("Tests")
+        .or_else(|| segment.strip_suffix("Test"))
+        .or_else(|| segment.strip_suffix("_test"))
+        .or_else(|| segment.strip_prefix("test_"))
+        .unwrap_or(segment);
+    s.to_string()
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
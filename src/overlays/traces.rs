@@ -0,0 +1,283 @@
+// Runtime-оверлей: спаны OpenTelemetry (OTLP/JSON) сопоставляются с капсулами,
+// наблюдаемые вызовы parent → child сравниваются со статическими связями графа
+
+use super::{rank_entries, CapsuleLocator, OverlayEntry};
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Ключи метаданных капсулы
+pub const RUNTIME_CALLS_KEY: &str = "runtime_calls";
+pub const RUNTIME_AVG_MS_KEY: &str = "runtime_avg_ms";
+
+/// Спан, приведённый к полям, нужным для сопоставления
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceSpan {
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    pub service: Option<String>,
+    pub duration_ms: f64,
+    pub function: Option<String>,
+    pub namespace: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+/// Наблюдаемое ребро вызова между капсулами
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeEdge {
+    pub from: String,
+    pub to: String,
+    pub calls: usize,
+    pub avg_ms: f64,
+    /// Есть ли соответствующая статическая связь в графе
+    pub static_relation: bool,
+}
+
+/// Итог наложения трасс
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceOverlayReport {
+    pub spans: usize,
+    pub mapped_spans: usize,
+    /// Горячие пути: наблюдаемые рёбра, упорядоченные по числу вызовов
+    pub hot_edges: Vec<RuntimeEdge>,
+    /// Рёбра, которых нет в статическом графе (динамическая диспетчеризация, рефлексия)
+    pub dynamic_only_edges: usize,
+    /// Статические связи между наблюдаемыми капсулами, не встреченные в трассах
+    pub cold_static_relations: usize,
+    pub by_capsule_calls: Vec<OverlayEntry>,
+    pub by_capsule_latency_ms: Vec<OverlayEntry>,
+}
+
+/// Наложение трасс OpenTelemetry на статический граф
+#[derive(Debug)]
+pub struct TraceOverlay {
+    pub top_n: usize,
+}
+
+impl TraceOverlay {
+    pub fn new() -> Self {
+        Self { top_n: 20 }
+    }
+
+    /// Разбор OTLP/JSON: один документ `{"resourceSpans": [...]}`
+    /// или JSON-строки (формат file exporter коллектора)
+    pub fn parse_otlp_json(&self, text: &str) -> Result<Vec<TraceSpan>> {
+        let documents: Vec<serde_json::Value> = match serde_json::from_str(text) {
+            Ok(v) => vec![v],
+            Err(_) => text
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<_, _>>()
+                .map_err(|e| AnalysisError::Parse(format!("OTLP JSON: {}", e)))?,
+        };
+        let mut spans = Vec::new();
+        for doc in &documents {
+            let Some(resource_spans) = doc.get("resourceSpans").and_then(|v| v.as_array()) else {
+                continue;
+            };
+            for rs in resource_spans {
+                let service = rs
+                    .get("resource")
+                    .and_then(|r| r.get("attributes"))
+                    .and_then(|a| attribute(a, "service.name"));
+                let scopes = rs
+                    .get("scopeSpans")
+                    .or_else(|| rs.get("instrumentationLibrarySpans"))
+                    .and_then(|v| v.as_array());
+                for scope in scopes.into_iter().flatten() {
+                    for span in scope
+                        .get("spans")
+                        .and_then(|v| v.as_array())
+                        .into_iter()
+                        .flatten()
+                    {
+                        spans.push(parse_span(span, service.clone()));
+                    }
+                }
+            }
+        }
+        if documents.iter().all(|d| d.get("resourceSpans").is_none()) {
+            return Err(AnalysisError::Parse("Не найден resourceSpans".into()));
+        }
+        Ok(spans)
+    }
+
+    pub fn apply(&self, graph: &mut CapsuleGraph, spans: &[TraceSpan]) -> TraceOverlayReport {
+        let locator = CapsuleLocator::new(graph);
+        let span_capsule: HashMap<&str, Uuid> = spans
+            .iter()
+            .filter_map(|s| {
+                self.locate(graph, &locator, s)
+                    .map(|id| (s.span_id.as_str(), id))
+            })
+            .collect();
+
+        let mut per_capsule: HashMap<Uuid, (usize, f64)> = HashMap::new();
+        let mut edges: HashMap<(Uuid, Uuid), (usize, f64)> = HashMap::new();
+        for span in spans {
+            let Some(&id) = span_capsule.get(span.span_id.as_str()) else {
+                continue;
+            };
+            let acc = per_capsule.entry(id).or_insert((0, 0.0));
+            acc.0 += 1;
+            acc.1 += span.duration_ms;
+            let parent = span
+                .parent_span_id
+                .as_deref()
+                .and_then(|p| span_capsule.get(p));
+            if let Some(&parent_id) = parent {
+                if parent_id != id {
+                    let e = edges.entry((parent_id, id)).or_insert((0, 0.0));
+                    e.0 += 1;
+                    e.1 += span.duration_ms;
+                }
+            }
+        }
+
+        let static_edges: HashSet<(Uuid, Uuid)> = graph
+            .relations
+            .iter()
+            .map(|r| (r.from_id, r.to_id))
+            .collect();
+        let observed: HashSet<Uuid> = per_capsule.keys().copied().collect();
+        let cold_static_relations = static_edges
+            .iter()
+            .filter(|(a, b)| observed.contains(a) && observed.contains(b))
+            .filter(|e| !edges.contains_key(e))
+            .count();
+
+        let name_of = |id: &Uuid| {
+            graph
+                .capsules
+                .get(id)
+                .map(|c| c.name.clone())
+                .unwrap_or_default()
+        };
+        let mut hot_edges: Vec<RuntimeEdge> = edges
+            .iter()
+            .map(|((from, to), (calls, total))| RuntimeEdge {
+                from: name_of(from),
+                to: name_of(to),
+                calls: *calls,
+                avg_ms: total / *calls as f64,
+                static_relation: static_edges.contains(&(*from, *to)),
+            })
+            .collect();
+        hot_edges.sort_by(|a, b| {
+            b.calls
+                .cmp(&a.calls)
+                .then(a.from.cmp(&b.from))
+                .then(a.to.cmp(&b.to))
+        });
+        let dynamic_only_edges = hot_edges.iter().filter(|e| !e.static_relation).count();
+        hot_edges.truncate(self.top_n);
+
+        let mut calls: HashMap<String, f64> = HashMap::new();
+        let mut latency: HashMap<String, f64> = HashMap::new();
+        for (id, (count, total_ms)) in &per_capsule {
+            if let Some(c) = graph.capsules.get_mut(id) {
+                let avg = total_ms / *count as f64;
+                c.metadata
+                    .insert(RUNTIME_CALLS_KEY.to_string(), count.to_string());
+                c.metadata
+                    .insert(RUNTIME_AVG_MS_KEY.to_string(), format!("{:.3}", avg));
+                *calls.entry(c.name.clone()).or_insert(0.0) += *count as f64;
+                *latency.entry(c.name.clone()).or_insert(0.0) += total_ms;
+            }
+        }
+        let total_calls: f64 = calls.values().sum();
+        let total_latency: f64 = latency.values().sum();
+
+        TraceOverlayReport {
+            spans: spans.len(),
+            mapped_spans: span_capsule.len(),
+            hot_edges,
+            dynamic_only_edges,
+            cold_static_relations,
+            by_capsule_calls: rank_entries(calls, total_calls, self.top_n),
+            by_capsule_latency_ms: rank_entries(latency, total_latency, self.top_n),
+        }
+    }
+
+    /// Сопоставление по семантическим атрибутам `code.*`, иначе по имени спана
+    fn locate(
+        &self,
+        graph: &CapsuleGraph,
+        locator: &CapsuleLocator,
+        span: &TraceSpan,
+    ) -> Option<Uuid> {
+        if let (Some(file), Some(line)) = (&span.file, span.line) {
+            if let Some(id) = locator.capsule_at(graph, file, line) {
+                return Some(id);
+            }
+        }
+        if let Some(function) = &span.function {
+            let symbol = match &span.namespace {
+                Some(ns) => format!("{}::{}", ns.replace('.', "::"), function),
+                None => function.clone(),
+            };
+            if let Some(id) = locator.capsule_for_symbol(graph, &symbol) {
+                return Some(id);
+            }
+        }
+        let symbol = span.name.replace(['.', '/', ' '], "::");
+        locator.capsule_for_symbol(graph, &symbol)
+    }
+}
+
+impl Default for TraceOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_span(span: &serde_json::Value, service: Option<String>) -> TraceSpan {
+    let text = |key: &str| span.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let nanos = |key: &str| {
+        span.get(key).and_then(|v| {
+            v.as_str()
+                .and_then(|s| s.parse::<f64>().ok())
+                .or_else(|| v.as_f64())
+        })
+    };
+    let duration_ms = match (nanos("startTimeUnixNano"), nanos("endTimeUnixNano")) {
+        (Some(start), Some(end)) if end >= start => (end - start) / 1_000_000.0,
+        _ => 0.0,
+    };
+    let attrs = span.get("attributes");
+    let attr = |key: &str| attrs.and_then(|a| attribute(a, key));
+    TraceSpan {
+        span_id: text("spanId").unwrap_or_default(),
+        parent_span_id: text("parentSpanId").filter(|p| !p.is_empty()),
+        name: text("name").unwrap_or_default(),
+        service,
+        duration_ms,
+        function: attr("code.function"),
+        namespace: attr("code.namespace"),
+        file: attr("code.filepath"),
+        line: attr("code.lineno").and_then(|l| l.parse().ok()),
+    }
+}
+
+/// Значение OTLP-атрибута (`stringValue` / `intValue`) по ключу
+fn attribute(attributes: &serde_json::Value, key: &str) -> Option<String> {
+    attributes.as_array()?.iter().find_map(|a| {
+        if a.get("key")?.as_str()? != key {
+            return None;
+        }
+        let value = a.get("value")?;
+        value
+            .get("stringValue")
+            .and_then(|v| v.as_str().map(str::to_string))
+            .or_else(|| {
+                value.get("intValue").map(|v| match v.as_str() {
+                    Some(s) => s.to_string(),
+                    None => v.to_string(),
+                })
+            })
+    })
+}
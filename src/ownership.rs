@@ -0,0 +1,232 @@
+// Владение кодом по `git blame`: доли авторов по строкам каждой капсулы,
+// агрегаты по модулям (каталогам) и слоям, «bus factor» и модули, которыми
+// больше чем на 80% владеет один автор
+
+use crate::dsm::{common_root, module_of};
+use crate::git::run_git;
+use crate::types::{Capsule, CapsuleGraph};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Ключ метаданных: строки капсулы по авторам, `email=lines;email=lines`
+pub const OWNERSHIP_KEY: &str = "ownership";
+/// Ключ метаданных: основной автор капсулы
+pub const OWNER_KEY: &str = "owner";
+/// Ключ метаданных: доля строк основного автора (0..1)
+pub const OWNER_SHARE_KEY: &str = "owner_share";
+/// Доля одного автора, начиная с которой модуль считается «единоличным»
+pub const SINGLE_OWNER_THRESHOLD: f32 = 0.8;
+
+/// Разбор `git blame --line-porcelain`: автор (email) каждой строки по порядку
+pub fn parse_blame(porcelain: &str) -> Vec<String> {
+    let mut authors = Vec::new();
+    let mut email = String::new();
+    for line in porcelain.lines() {
+        if let Some(mail) = line.strip_prefix("author-mail ") {
+            email = mail
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_lowercase();
+        } else if line.starts_with('\t') {
+            authors.push(email.clone());
+        }
+    }
+    authors
+}
+
+/// Сбор blame по файлам капсул
+pub struct OwnershipAnalyzer {
+    repo: PathBuf,
+}
+
+impl OwnershipAnalyzer {
+    pub fn new(repo: &Path) -> Self {
+        Self {
+            repo: repo.to_path_buf(),
+        }
+    }
+
+    /// Авторы строк файла; `None`, если файл не под git
+    pub fn blame(&self, file: &Path) -> Option<Vec<String>> {
+        let file = file.to_string_lossy();
+        let out = run_git(
+            &self.repo,
+            &["blame", "-w", "--line-porcelain", "--", file.as_ref()],
+        )
+        .ok()?;
+        let authors = parse_blame(&out);
+        (!authors.is_empty()).then_some(authors)
+    }
+
+    /// Записывает владение в метаданные капсул (один `git blame` на файл).
+    /// Возвращает число файлов с blame; вне git-репозитория ничего не делает
+    pub fn annotate(&self, capsules: &mut [Capsule]) -> usize {
+        if run_git(&self.repo, &["rev-parse", "--is-inside-work-tree"]).is_err() {
+            return 0;
+        }
+        let mut blames: HashMap<PathBuf, Option<Vec<String>>> = HashMap::new();
+        for capsule in capsules.iter_mut() {
+            let blame = blames
+                .entry(capsule.file_path.clone())
+                .or_insert_with(|| self.blame(&capsule.file_path));
+            let Some(blame) = blame else {
+                continue;
+            };
+            let start = capsule.line_start.max(1) - 1;
+            let end = capsule.line_end.max(capsule.line_start).min(blame.len());
+            let mut lines: HashMap<&str, usize> = HashMap::new();
+            for author in blame.get(start..end).unwrap_or_default() {
+                *lines.entry(author.as_str()).or_insert(0) += 1;
+            }
+            let shares = sorted_shares(lines.into_iter().map(|(a, n)| (a.to_string(), n)));
+            let Some((owner, owned)) = shares.first() else {
+                continue;
+            };
+            let total: usize = shares.iter().map(|(_, n)| n).sum();
+            capsule.metadata.insert(
+                OWNER_SHARE_KEY.to_string(),
+                format!("{:.2}", *owned as f32 / total as f32),
+            );
+            capsule
+                .metadata
+                .insert(OWNER_KEY.to_string(), owner.clone());
+            capsule.metadata.insert(
+                OWNERSHIP_KEY.to_string(),
+                shares
+                    .iter()
+                    .map(|(author, n)| format!("{author}={n}"))
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            );
+        }
+        blames.values().filter(|b| b.is_some()).count()
+    }
+}
+
+/// Авторы по убыванию строк, при равенстве — по имени
+fn sorted_shares(lines: impl IntoIterator<Item = (String, usize)>) -> Vec<(String, usize)> {
+    let mut shares: Vec<(String, usize)> = lines.into_iter().filter(|(_, n)| *n > 0).collect();
+    shares.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    shares
+}
+
+/// Строки капсулы по авторам из метаданных
+fn capsule_ownership(capsule: &Capsule) -> Vec<(String, usize)> {
+    capsule
+        .metadata
+        .get(OWNERSHIP_KEY)
+        .map(|value| {
+            value
+                .split(';')
+                .filter_map(|part| {
+                    let (author, lines) = part.rsplit_once('=')?;
+                    Some((author.to_string(), lines.parse().ok()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Владение модулем или слоем
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OwnershipEntry {
+    pub name: String,
+    pub lines: usize,
+    pub authors: usize,
+    pub top_author: String,
+    pub top_share: f32,
+    /// Сколько основных авторов вместе владеют больше чем половиной строк
+    pub bus_factor: usize,
+    /// Доля основного автора больше `SINGLE_OWNER_THRESHOLD`
+    pub single_owner: bool,
+}
+
+impl OwnershipEntry {
+    fn from_lines(name: String, lines: BTreeMap<String, usize>) -> Option<Self> {
+        let shares = sorted_shares(lines);
+        let total: usize = shares.iter().map(|(_, n)| n).sum();
+        let (top_author, top_lines) = shares.first()?.clone();
+        let mut covered = 0;
+        let bus_factor = shares
+            .iter()
+            .take_while(|(_, n)| {
+                let below_half = covered * 2 <= total;
+                covered += n;
+                below_half
+            })
+            .count();
+        let top_share = top_lines as f32 / total as f32;
+        Some(Self {
+            name,
+            lines: total,
+            authors: shares.len(),
+            top_author,
+            top_share,
+            bus_factor,
+            single_owner: top_share > SINGLE_OWNER_THRESHOLD,
+        })
+    }
+}
+
+/// Владение по модулям и слоям
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OwnershipReport {
+    /// Сначала единоличные модули, затем по числу строк
+    pub modules: Vec<OwnershipEntry>,
+    pub layers: Vec<OwnershipEntry>,
+}
+
+impl OwnershipReport {
+    /// Агрегаты по метаданным капсул; `None`, если blame не собирался
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let owned: Vec<&Capsule> = graph
+            .capsules
+            .values()
+            .filter(|c| c.metadata.contains_key(OWNERSHIP_KEY))
+            .collect();
+        if owned.is_empty() {
+            return None;
+        }
+        let root = common_root(owned.iter().map(|c| c.file_path.as_path()));
+        let mut modules: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        let mut layers: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for capsule in owned {
+            let module = modules
+                .entry(module_of(&capsule.file_path, &root))
+                .or_default();
+            let mut layer = capsule
+                .layer
+                .as_ref()
+                .map(|layer| layers.entry(layer.clone()).or_default());
+            for (author, lines) in capsule_ownership(capsule) {
+                *module.entry(author.clone()).or_insert(0) += lines;
+                if let Some(layer) = layer.as_mut() {
+                    *layer.entry(author).or_insert(0) += lines;
+                }
+            }
+        }
+        let collect = |groups: BTreeMap<String, BTreeMap<String, usize>>| {
+            let mut entries: Vec<OwnershipEntry> = groups
+                .into_iter()
+                .filter_map(|(name, lines)| OwnershipEntry::from_lines(name, lines))
+                .collect();
+            entries.sort_by(|a, b| {
+                b.single_owner
+                    .cmp(&a.single_owner)
+                    .then(b.lines.cmp(&a.lines))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            entries
+        };
+        Some(Self {
+            modules: collect(modules),
+            layers: collect(layers),
+        })
+    }
+
+    pub fn single_owner_modules(&self) -> impl Iterator<Item = &OwnershipEntry> {
+        self.modules.iter().filter(|m| m.single_owner)
+    }
+}
@@ -0,0 +1,304 @@
+// Пакеты и пространства имён как капсулы: в Java, Kotlin, Scala и C# архитектуру
+// обсуждают на уровне пакетов, а не файлов. Файлы с одним объявленным `package`
+// (`namespace`) объединяются в капсулу-модуль пакета: она содержит верхнеуровневые
+// капсулы файлов, а импорты пакетов и связи между капсулами разных пакетов
+// сворачиваются в связи пакет → пакет. Диаграмма только пакетов: `diagram <path> mermaid --tag package`
+
+use crate::external_deps::EXTERNAL_PACKAGE_KEY;
+use crate::types::*;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Ключ метаданных капсулы пакета; значение — язык (`java`, `kotlin`, `scala`, `csharp`)
+pub const PACKAGE_KEY: &str = "package";
+/// Тег капсул пакетов
+pub const PACKAGE_TAG: &str = "package";
+
+/// Язык с явными пакетами по расширению файла
+fn language_of(path: &Path) -> Option<&'static str> {
+    match path.extension().and_then(|e| e.to_str())? {
+        "java" => Some("java"),
+        "kt" | "kts" => Some("kotlin"),
+        "scala" => Some("scala"),
+        "cs" => Some("csharp"),
+        _ => None,
+    }
+}
+
+/// Объявленный пакет файла: `package a.b;` (Java, Kotlin, Scala) или первое
+/// `namespace A.B` (C#, блочное или файловое)
+pub fn declared_package(path: &Path, source: &str) -> Option<String> {
+    static PACKAGE: OnceLock<Regex> = OnceLock::new();
+    static NAMESPACE: OnceLock<Regex> = OnceLock::new();
+    let regex = if language_of(path)? == "csharp" {
+        NAMESPACE.get_or_init(|| Regex::new(r"(?m)^\s*namespace\s+([\w.]+)").unwrap())
+    } else {
+        PACKAGE.get_or_init(|| Regex::new(r"(?m)^\s*package\s+([\w.]+)\s*;?\s*$").unwrap())
+    };
+    regex.captures(source).map(|c| c[1].to_string())
+}
+
+/// Импортированные пути файла: `import a.b.C;`, `import a.b.*` или `using A.B;`
+pub fn imported_paths(path: &Path, source: &str) -> Vec<String> {
+    static IMPORT: OnceLock<Regex> = OnceLock::new();
+    static USING: OnceLock<Regex> = OnceLock::new();
+    let Some(language) = language_of(path) else {
+        return Vec::new();
+    };
+    let regex = if language == "csharp" {
+        USING.get_or_init(|| {
+            Regex::new(r"(?m)^\s*(?:global\s+)?using\s+(?:static\s+)?([\w.]+)\s*;").unwrap()
+        })
+    } else {
+        IMPORT.get_or_init(|| Regex::new(r"(?m)^\s*import\s+(?:static\s+)?([\w.]+)").unwrap())
+    };
+    regex
+        .captures_iter(source)
+        .map(|c| c[1].trim_end_matches('.').to_string())
+        .collect()
+}
+
+/// Самый длинный известный пакет, которым начинается импортированный путь
+fn package_of_import<'a>(import: &str, packages: &'a BTreeSet<&str>) -> Option<&'a str> {
+    packages
+        .iter()
+        .filter(|package| {
+            import == **package
+                || import
+                    .strip_prefix(**package)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+        .max_by_key(|package| package.len())
+        .copied()
+}
+
+/// Строит капсулы пакетов по исходникам файлов графа
+#[derive(Debug, Default)]
+pub struct PackageAggregator;
+
+impl PackageAggregator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Добавляет капсулы пакетов, связи `Contains` к верхнеуровневым капсулам их
+    /// файлов и связи между пакетами. Вызывается после расчёта метрик: капсулы
+    /// пакетов агрегируют файлы и в метрики архитектуры не входят. Возвращает
+    /// число добавленных пакетов
+    pub fn annotate(&self, graph: &mut CapsuleGraph) -> usize {
+        let mut files: BTreeMap<PathBuf, Vec<Uuid>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            if language_of(&capsule.file_path).is_none()
+                || capsule.metadata.contains_key(EXTERNAL_PACKAGE_KEY)
+                || capsule.metadata.contains_key(PACKAGE_KEY)
+            {
+                continue;
+            }
+            files
+                .entry(capsule.file_path.clone())
+                .or_default()
+                .push(capsule.id);
+        }
+
+        let mut packages: BTreeMap<(&'static str, String), Vec<&PathBuf>> = BTreeMap::new();
+        let mut imports: Vec<(&'static str, String, Vec<String>)> = Vec::new();
+        for file in files.keys() {
+            let Some(language) = language_of(file) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read_to_string(file) else {
+                continue;
+            };
+            if let Some(package) = declared_package(file, &source) {
+                imports.push((language, package.clone(), imported_paths(file, &source)));
+                packages.entry((language, package)).or_default().push(file);
+            }
+        }
+        if packages.is_empty() {
+            return 0;
+        }
+
+        // капсулы, вложенные в другую капсулу того же файла, пакет не содержит напрямую
+        let nested: HashSet<Uuid> = graph
+            .relations
+            .iter()
+            .filter(|r| r.relation_type == RelationType::Contains)
+            .filter(
+                |r| match (graph.capsules.get(&r.from_id), graph.capsules.get(&r.to_id)) {
+                    (Some(from), Some(to)) => from.file_path == to.file_path,
+                    _ => false,
+                },
+            )
+            .map(|r| r.to_id)
+            .collect();
+
+        let mut package_ids: HashMap<(&str, &str), Uuid> = HashMap::new();
+        let mut package_of: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut added: Vec<Capsule> = Vec::new();
+        let mut relations: Vec<CapsuleRelation> = Vec::new();
+        for ((language, name), package_files) in &packages {
+            let members: Vec<&Capsule> = package_files
+                .iter()
+                .flat_map(|file| &files[*file])
+                .filter_map(|id| graph.capsules.get(id))
+                .collect();
+            let capsule = package_capsule(language, name, package_files, &members);
+            package_ids.insert((language, name.as_str()), capsule.id);
+            for member in &members {
+                package_of.insert(member.id, capsule.id);
+                if nested.contains(&member.id) {
+                    continue;
+                }
+                relations.push(CapsuleRelation {
+                    from_id: capsule.id,
+                    to_id: member.id,
+                    relation_type: RelationType::Contains,
+                    strength: 1.0,
+                    description: Some(format!("package {}", name)),
+                });
+            }
+            added.push(capsule);
+        }
+
+        let mut between: BTreeMap<(Uuid, Uuid, RelationType), CapsuleRelation> = BTreeMap::new();
+        for (language, package, paths) in &imports {
+            let known: BTreeSet<&str> = packages
+                .keys()
+                .filter(|(l, _)| l == language)
+                .map(|(_, name)| name.as_str())
+                .collect();
+            let from = package_ids[&(*language, package.as_str())];
+            for path in paths {
+                let Some(target) = package_of_import(path, &known) else {
+                    continue;
+                };
+                let to = package_ids[&(*language, target)];
+                if from != to {
+                    between
+                        .entry((from, to, RelationType::Imports))
+                        .or_insert_with(|| CapsuleRelation {
+                            from_id: from,
+                            to_id: to,
+                            relation_type: RelationType::Imports,
+                            strength: 1.0,
+                            description: Some(format!("imports {}", target)),
+                        });
+                }
+            }
+        }
+        for relation in &graph.relations {
+            if relation.relation_type.is_structural()
+                || relation.relation_type == RelationType::Contains
+            {
+                continue;
+            }
+            let (Some(&from), Some(&to)) = (
+                package_of.get(&relation.from_id),
+                package_of.get(&relation.to_id),
+            ) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            between
+                .entry((from, to, relation.relation_type))
+                .and_modify(|r| r.strength = r.strength.max(relation.strength))
+                .or_insert_with(|| CapsuleRelation {
+                    from_id: from,
+                    to_id: to,
+                    relation_type: relation.relation_type,
+                    strength: relation.strength,
+                    description: Some("package dependency".to_string()),
+                });
+        }
+        relations.extend(between.into_values());
+
+        let count = added.len();
+        for capsule in added {
+            if let Some(layer) = &capsule.layer {
+                graph
+                    .layers
+                    .entry(layer.clone())
+                    .or_default()
+                    .push(capsule.id);
+            }
+            graph.capsules.insert(capsule.id, capsule);
+        }
+        let mut linked = BTreeSet::new();
+        for relation in &relations {
+            if relation.relation_type == RelationType::Contains
+                || !linked.insert((relation.from_id, relation.to_id))
+            {
+                continue;
+            }
+            if let Some(c) = graph.capsules.get_mut(&relation.from_id) {
+                c.dependencies.push(relation.to_id);
+            }
+            if let Some(c) = graph.capsules.get_mut(&relation.to_id) {
+                c.dependents.push(relation.from_id);
+            }
+        }
+        graph.relations.extend(relations);
+        count
+    }
+}
+
+/// Капсула пакета; путь — общий каталог его файлов. Пакет определяется
+/// языком и именем, от них и `id`: он не зависит от расположения проекта
+fn package_capsule(
+    language: &str,
+    name: &str,
+    files: &[&PathBuf],
+    members: &[&Capsule],
+) -> Capsule {
+    let dir = crate::dsm::common_root(files.iter().map(|f| f.as_path()));
+    let mut metadata = HashMap::new();
+    metadata.insert(PACKAGE_KEY.to_string(), language.to_string());
+    metadata.insert("package_files".to_string(), files.len().to_string());
+    let mut layers: BTreeMap<&str, usize> = BTreeMap::new();
+    for member in members {
+        if let Some(layer) = &member.layer {
+            *layers.entry(layer.as_str()).or_insert(0) += 1;
+        }
+    }
+    Capsule {
+        id: stable_capsule_id(
+            Path::new(&format!("package:{language}")),
+            name,
+            CapsuleType::Module,
+        ),
+        name: name.to_string(),
+        capsule_type: CapsuleType::Module,
+        file_path: dir,
+        line_start: 1,
+        line_end: 1,
+        size: members.iter().map(|c| c.size).sum(),
+        complexity: members.iter().map(|c| c.complexity).sum(),
+        dependencies: Vec::new(),
+        layer: layers
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(layer, _)| layer.to_string()),
+        summary: None,
+        description: Some(format!(
+            "{} package {}: {} files, {} components",
+            language,
+            name,
+            files.len(),
+            members.len()
+        )),
+        warnings: Vec::new(),
+        status: CapsuleStatus::Active,
+        priority: Priority::Medium,
+        tags: vec![PACKAGE_TAG.to_string()],
+        metadata,
+        quality_score: 0.0,
+        slogan: None,
+        dependents: Vec::new(),
+        created_at: None,
+    }
+}
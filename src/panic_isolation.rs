@@ -0,0 +1,121 @@
+// Изоляция паник по файлам: разбор или обогащение одного патологического
+// файла не должны ронять весь анализ или MCP-сервер. Упавшие файлы
+// пропускаются и перечисляются в отчёте о покрытии анализа с текстом паники
+
+use crate::capsule_constructor::CapsuleConstructor;
+use crate::parser_ast::ParserAST;
+use crate::types::{Capsule, FileMetadata, Result};
+use serde::Serialize;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+/// Этап, на котором упал файл
+pub const PARSE_STAGE: &str = "parse";
+pub const ENRICH_STAGE: &str = "enrich";
+/// Категория предупреждения капсулы, обогащение которой упало
+pub const ANALYSIS_FAILURE_CATEGORY: &str = "analysis_failure";
+
+/// Файл, анализ которого завершился паникой
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedFile {
+    pub path: PathBuf,
+    pub stage: String,
+    pub message: String,
+}
+
+/// Покрытие анализа: сколько файлов разобрано и какие упали
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AnalysisCoverage {
+    pub analyzed_files: usize,
+    pub failed: Vec<FailedFile>,
+}
+
+/// Текст паники: `panic!("...")` даёт `&str` или `String`
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Выполняет `f`, превращая панику в ошибку с её текстом. Состояние, которое
+/// `f` меняла до паники, может остаться частичным — вызывающий отбрасывает
+/// результат файла целиком
+pub fn isolate<T>(f: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Сообщение об упавшем файле в stderr (stdout занят отчётом или JSON-RPC)
+pub(crate) fn log_failure(stage: &str, path: &Path, message: &str) {
+    eprintln!(
+        "⚠️ Файл пропущен из-за паники ({}): {}: {}",
+        stage,
+        path.display(),
+        message
+    );
+}
+
+impl AnalysisCoverage {
+    pub fn record_failure(&mut self, path: PathBuf, stage: &str, message: String) {
+        log_failure(stage, &path, &message);
+        self.failed.push(FailedFile {
+            path,
+            stage: stage.to_string(),
+            message,
+        });
+    }
+
+    /// Раздел ai_compact; `None`, если упавших файлов нет
+    pub fn compact_section(&self) -> Option<String> {
+        if self.failed.is_empty() {
+            return None;
+        }
+        let mut out = format!(
+            "\n## Analysis Coverage\n- Analyzed files: {}\n- Failed files: {}\n",
+            self.analyzed_files,
+            self.failed.len()
+        );
+        for failed in &self.failed {
+            out.push_str(&format!(
+                "  - {} ({}): {}\n",
+                failed.path.display(),
+                failed.stage,
+                failed.message
+            ));
+        }
+        Some(out)
+    }
+}
+
+/// Капсулы файла с изоляцией паники: ошибки разбора, как и раньше, дают
+/// пустой список, паника — пустой список и запись в `coverage`; ошибки
+/// конструктора возвращаются вызывающему
+pub fn parse_file_isolated(
+    parser: &mut ParserAST,
+    constructor: &CapsuleConstructor,
+    file: &FileMetadata,
+    coverage: &mut AnalysisCoverage,
+) -> Result<Vec<Capsule>> {
+    let Ok(content) = std::fs::read_to_string(&file.path) else {
+        return Ok(Vec::new());
+    };
+    let parsed = isolate(|| {
+        parser
+            .parse_file(&file.path, &content, &file.file_type)
+            .ok()
+            .map(|nodes| constructor.create_capsules(&nodes, &file.path))
+    });
+    match parsed {
+        Ok(Some(capsules)) => {
+            coverage.analyzed_files += 1;
+            capsules
+        }
+        Ok(None) => Ok(Vec::new()),
+        Err(message) => {
+            coverage.record_failure(file.path.clone(), PARSE_STAGE, message);
+            Ok(Vec::new())
+        }
+    }
+}
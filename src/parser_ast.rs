@@ -1,11 +1,31 @@
+use crate::parser_cache::{CacheLimits, CacheStats, ElementCache};
 use crate::types::{FileType, Result};
 use regex::Regex;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::path::Path;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 // Продвинутый парсер с комбинированным подходом: tree-sitter + regex fallback
 // Обеспечивает высокое качество анализа с максимальной совместимостью
 
+/// Трейты, реализованные типом в том же файле (через запятую)
+pub const IMPLEMENTS_KEY: &str = "implements";
+/// Трейт и тип блока `impl Trait for Type`, к которому относится метод
+pub const IMPL_TRAIT_KEY: &str = "impl_trait";
+pub const IMPL_FOR_KEY: &str = "impl_for";
+/// Число методов, объявленных в трейте
+pub const TRAIT_METHODS_KEY: &str = "trait_methods";
+/// Текст документирующего комментария элемента без маркеров (`///`, JSDoc, docstring)
+pub const DOC_KEY: &str = "doc";
+/// Операторов в теле функции или метода (строки кода без комментариев и закрывающих скобок)
+pub const STATEMENTS_KEY: &str = "statements";
+/// Наибольшая вложенность блоков внутри тела функции
+pub const MAX_NESTING_KEY: &str = "max_nesting";
+/// `"true"` у функций, тело которых — только заглушка (`todo!()`, `raise NotImplementedError`)
+pub const STUB_KEY: &str = "stub";
+
 /// Элемент AST (структурная единица кода)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ASTElement {
@@ -44,20 +64,58 @@ pub enum ASTElementType {
     Other(String),
 }
 
+/// Парсер одного языка. Встроенные языки реализуют его через `RegexLanguageParser`,
+/// внешние крейты — для своих DSL, регистрируя через `ParserAST::register_parser`
+pub trait LanguageParser: Send + Sync + std::fmt::Debug {
+    /// Имя для диагностики (`ParserAST::parser_names`)
+    fn name(&self) -> &str;
+
+    /// Берётся ли парсер за файл; для своих расширений тип приходит как `FileType::Other(ext)`
+    fn supports(&self, file_path: &Path, file_type: &FileType) -> bool;
+
+    fn parse_file(
+        &self,
+        file_path: &Path,
+        content: &str,
+        file_type: &FileType,
+    ) -> Result<Vec<ASTElement>>;
+}
+
 /// Продвинутый парсер AST с поддержкой множественных стратегий
 #[derive(Debug)]
 pub struct ParserAST {
-    // Паттерны для различных языков (regex путь)
-    rust_patterns: LanguagePatterns,
-    js_patterns: LanguagePatterns,
-    ts_patterns: LanguagePatterns,
-    python_patterns: LanguagePatterns,
-    java_patterns: LanguagePatterns,
-    cpp_patterns: LanguagePatterns,
-    go_patterns: LanguagePatterns,
-
-    // Кеш для оптимизации
-    pattern_cache: HashMap<String, Vec<ASTElement>>,
+    // Встроенные языки (regex путь) и подключённые извне парсеры
+    builtin: Vec<RegexLanguageParser>,
+    plugins: Vec<Box<dyn LanguageParser>>,
+
+    // Кеш для оптимизации (LRU с лимитами, см. `parser_cache`)
+    pattern_cache: ElementCache,
+
+    // Инкрементальный режим: кеш по хешу содержимого и деревья tree-sitter по файлам
+    incremental: bool,
+    incremental_stats: IncrementalStats,
+    #[cfg(feature = "tree_sitter")]
+    trees: HashMap<PathBuf, ParsedTree>,
+}
+
+/// Последнее разобранное состояние файла для `Tree::edit`
+#[cfg(feature = "tree_sitter")]
+#[derive(Debug)]
+struct ParsedTree {
+    content: String,
+    file_type: FileType,
+    tree: tree_sitter::Tree,
+}
+
+/// Счётчики инкрементального режима
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct IncrementalStats {
+    /// Содержимое не изменилось: элементы взяты из кеша
+    pub unchanged: usize,
+    /// Изменённый файл разобран заново с переиспользованием старого дерева
+    pub reparsed: usize,
+    /// Полный разбор: файл встречен впервые или язык без tree-sitter
+    pub full: usize,
 }
 
 /// Паттерны для конкретного языка
@@ -80,21 +138,44 @@ pub struct LanguagePatterns {
 impl ParserAST {
     pub fn new() -> Result<Self> {
         Ok(Self {
-            rust_patterns: Self::create_rust_patterns()?,
-            js_patterns: Self::create_js_patterns()?,
-            ts_patterns: Self::create_ts_patterns()?,
-            python_patterns: Self::create_python_patterns()?,
-            java_patterns: Self::create_java_patterns()?,
-            cpp_patterns: Self::create_cpp_patterns()?,
-            go_patterns: Self::create_go_patterns()?,
-            pattern_cache: HashMap::new(),
+            builtin: vec![
+                RegexLanguageParser::new("rust", FileType::Rust, Self::create_rust_patterns()?),
+                RegexLanguageParser::new(
+                    "javascript",
+                    FileType::JavaScript,
+                    Self::create_js_patterns()?,
+                ),
+                RegexLanguageParser::new(
+                    "typescript",
+                    FileType::TypeScript,
+                    Self::create_ts_patterns()?,
+                ),
+                RegexLanguageParser::new(
+                    "python",
+                    FileType::Python,
+                    Self::create_python_patterns()?,
+                ),
+                RegexLanguageParser::new("java", FileType::Java, Self::create_java_patterns()?),
+                RegexLanguageParser::new("cpp", FileType::Cpp, Self::create_cpp_patterns()?),
+                RegexLanguageParser::new("go", FileType::Go, Self::create_go_patterns()?),
+                RegexLanguageParser::new("php", FileType::Php, Self::create_php_patterns()?),
+                RegexLanguageParser::new("ruby", FileType::Ruby, Self::create_ruby_patterns()?),
+            ],
+            plugins: Vec::new(),
+            pattern_cache: ElementCache::new(CacheLimits::from_env()),
+            incremental: false,
+            incremental_stats: IncrementalStats::default(),
+            #[cfg(feature = "tree_sitter")]
+            trees: HashMap::new(),
         })
     }
 
     fn create_rust_patterns() -> Result<LanguagePatterns> {
         Ok(LanguagePatterns {
             functions: Regex::new(
-                r"(?m)^[\s]*(?:pub\s+)?(?:async\s+)?fn\s+(\w+)\s*\([^)]*\)\s*(?:->\s*[^{]+)?\s*\{",
+                // обобщения, `pub(crate)`, квалификаторы и сигнатура на нескольких строках;
+                // объявления без тела (`fn f();`) — не функции
+                r#"(?m)^[\s]*(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern\s+"[^"]*")\s+)*fn\s+(\w+)\s*(?:<[^(]*>)?\s*\((?:[^)]*\)[^;{]*\{|[^;{]*$)"#,
             )?,
             classes: Regex::new(r"(?m)^[\s]*(?:pub\s+)?(?:struct|enum|union)\s+(\w+)")?,
             structs: Regex::new(r"(?m)^[\s]*(?:pub\s+)?struct\s+(\w+)")?,
@@ -313,35 +394,205 @@ impl ParserAST {
         })
     }
 
-    /// Парсит файл: если включён feature `tree_sitter`, используем парсер tree-sitter для поддерживаемых языков,
-    /// иначе — regex fallback. На ошибки — безопасный откат к regex.
+    fn create_php_patterns() -> Result<LanguagePatterns> {
+        Ok(LanguagePatterns {
+            functions: Regex::new(
+                r"(?m)^[\s]*(?:(?:public|private|protected|static|final|abstract)\s+)*function\s+&?(\w+)\s*\(",
+            )?,
+            classes: Regex::new(r"(?m)^[\s]*(?:(?:abstract|final|readonly)\s+)*class\s+(\w+)")?,
+            structs: Regex::new(r"(?m)^[\s]*trait\s+(\w+)")?,
+            enums: Regex::new(r"(?m)^[\s]*enum\s+(\w+)")?,
+            interfaces: Regex::new(r"(?m)^[\s]*interface\s+(\w+)")?,
+            modules: Regex::new(r"(?m)^[\s]*namespace\s+([\w\\]+)")?,
+            imports: Regex::new(
+                r#"(?m)^[\s]*(?:use\s+\\?([\w\\]+)|(?:require|include)(?:_once)?\s*\(?\s*['"]([^'"]+)['"])"#,
+            )?,
+            exports: Regex::new(
+                r"(?m)^(?:(?:final|abstract|readonly)\s+)*(?:class|interface|trait|enum|function)\s+(\w+)",
+            )?,
+            variables: Regex::new(r"(?m)^[\s]*\$(\w+)\s*=")?,
+            constants: Regex::new(
+                r#"(?m)^[\s]*(?:(?:(?:public|private|protected|final)\s+)*const\s+(\w+)|define\s*\(\s*['"](\w+)['"])"#,
+            )?,
+            comments: Regex::new(r"(?m)^[\s]*(?://|#(?:[^\[]|$)|/\*|\*)")?,
+            complexity_indicators: vec![
+                Regex::new(r"\bif\b")?,
+                Regex::new(r"\belseif\b")?,
+                Regex::new(r"\belse\b")?,
+                Regex::new(r"\bfor\b")?,
+                Regex::new(r"\bforeach\b")?,
+                Regex::new(r"\bwhile\b")?,
+                Regex::new(r"\bswitch\b")?,
+                Regex::new(r"\bcase\b")?,
+                Regex::new(r"\bmatch\b")?,
+                Regex::new(r"\bcatch\b")?,
+                Regex::new(r"&&")?,
+                Regex::new(r"\|\|")?,
+            ],
+        })
+    }
+
+    fn create_ruby_patterns() -> Result<LanguagePatterns> {
+        Ok(LanguagePatterns {
+            functions: Regex::new(r"(?m)^[\s]*def\s+(?:self\.)?(\w+[?!=]?)")?,
+            classes: Regex::new(r"(?m)^[\s]*class\s+([A-Z]\w*(?:::\w+)*)")?,
+            structs: Regex::new(r"(?m)^[\s]*([A-Z]\w*)\s*=\s*(?:Struct|Data)\.(?:new|define)")?,
+            // Rails: `enum status: { ... }` / `enum :status, [...]`
+            enums: Regex::new(r"(?m)^[\s]*enum\s+:?(\w+)")?,
+            // интерфейсы есть только в сигнатурах RBS
+            interfaces: Regex::new(r"(?m)^[\s]*interface\s+(_?\w+)")?,
+            modules: Regex::new(r"(?m)^[\s]*module\s+([A-Z]\w*(?:::\w+)*)")?,
+            imports: Regex::new(
+                r#"(?m)^[\s]*(?:require|require_relative|load)\s*\(?\s*['"]([^'"]+)['"]"#,
+            )?,
+            exports: Regex::new(r"(?m)^[\s]*(?:class|module)\s+([A-Z]\w*)")?,
+            variables: Regex::new(r"(?m)^[\s]*\$(\w+)\s*=")?,
+            constants: Regex::new(r"(?m)^[\s]*([A-Z][A-Z0-9_]*)\s*=")?,
+            comments: Regex::new(r"(?m)^[\s]*#")?,
+            complexity_indicators: vec![
+                Regex::new(r"\bif\b")?,
+                Regex::new(r"\belsif\b")?,
+                Regex::new(r"\belse\b")?,
+                Regex::new(r"\bunless\b")?,
+                Regex::new(r"\bcase\b")?,
+                Regex::new(r"\bwhen\b")?,
+                Regex::new(r"\bwhile\b")?,
+                Regex::new(r"\buntil\b")?,
+                Regex::new(r"\brescue\b")?,
+                Regex::new(r"&&")?,
+                Regex::new(r"\|\|")?,
+            ],
+        })
+    }
+
+    /// Инкрементальный режим для повторных анализов одного дерева файлов (сервер MCP):
+    /// кеш элементов по хешу содержимого, а с feature `tree_sitter` изменённые файлы
+    /// разбираются заново через `Tree::edit` с переиспользованием прежнего дерева
+    pub fn with_incremental(mut self, enabled: bool) -> Self {
+        self.incremental = enabled;
+        self
+    }
+
+    /// Подключает парсер поверх встроенных: он проверяется раньше tree-sitter и regex,
+    /// а среди подключённых побеждает зарегистрированный последним
+    pub fn register_parser(&mut self, parser: Box<dyn LanguageParser>) {
+        self.plugins.push(parser);
+        // разобранное прежним парсером больше не актуально
+        self.clear_cache();
+    }
+
+    pub fn with_parser(mut self, parser: impl LanguageParser + 'static) -> Self {
+        self.register_parser(Box::new(parser));
+        self
+    }
+
+    /// Имена парсеров в порядке приоритета: подключённые, затем встроенные
+    pub fn parser_names(&self) -> Vec<&str> {
+        self.plugins
+            .iter()
+            .rev()
+            .map(|p| p.name())
+            .chain(self.builtin.iter().map(|p| p.name()))
+            .collect()
+    }
+
+    pub fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+
+    pub fn incremental_stats(&self) -> IncrementalStats {
+        self.incremental_stats
+    }
+
+    /// Лимиты кеша разобранных файлов; лишние записи вытесняются сразу
+    pub fn with_cache_limits(mut self, limits: CacheLimits) -> Self {
+        let evicted = self.pattern_cache.set_limits(limits);
+        self.forget_trees(&evicted);
+        self
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.pattern_cache.stats()
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.pattern_cache.clear();
+        #[cfg(feature = "tree_sitter")]
+        self.trees.clear();
+    }
+
+    /// Деревья tree-sitter живут не дольше записей кеша
+    fn forget_trees(&mut self, paths: &[PathBuf]) {
+        #[cfg(feature = "tree_sitter")]
+        for path in paths {
+            self.trees.remove(path);
+        }
+        #[cfg(not(feature = "tree_sitter"))]
+        let _ = paths;
+    }
+
+    /// Отпечаток содержимого для кеша: хеш в инкрементальном режиме, иначе длина
+    fn fingerprint(&self, content: &str) -> String {
+        if self.incremental {
+            let mut hasher = DefaultHasher::new();
+            content.hash(&mut hasher);
+            format!("{:016x}", hasher.finish())
+        } else {
+            content.len().to_string()
+        }
+    }
+
+    /// Парсит файл: сначала подключённые парсеры, затем (с feature `tree_sitter`) tree-sitter
+    /// для поддерживаемых языков, иначе — regex fallback. На ошибки — безопасный откат к regex.
     pub fn parse_file(
         &mut self,
         file_path: &Path,
         content: &str,
         file_type: &FileType,
     ) -> Result<Vec<ASTElement>> {
-        let cache_key = format!("{}:{}", file_path.display(), content.len());
-        if let Some(cached) = self.pattern_cache.get(&cache_key) {
-            return Ok(cached.clone());
+        let fingerprint = self.fingerprint(content);
+        if let Some(cached) = self.pattern_cache.get(file_path, &fingerprint) {
+            if self.incremental {
+                self.incremental_stats.unchanged += 1;
+            }
+            return Ok(cached);
         }
+        let reparsed_before = self.incremental_stats.reparsed;
 
+        let plugin = self
+            .plugins
+            .iter()
+            .rev()
+            .find(|p| p.supports(file_path, file_type));
+        let parsed = match plugin {
+            Some(plugin) => Some(plugin.parse_file(file_path, content, file_type)?),
+            None => None,
+        };
         #[cfg(feature = "tree_sitter")]
-        {
-            if let Some(elements) = self.try_tree_sitter_parse(file_path, content, file_type)? {
-                self.pattern_cache.insert(cache_key, elements.clone());
-                return Ok(elements);
-            }
-        }
+        let parsed = match parsed {
+            Some(elements) => Some(elements),
+            None => self.try_tree_sitter_parse(file_path, content, file_type)?,
+        };
         // Fallback regex
-        let elements = self.parse_file_regex(file_path, content, file_type)?;
-        self.pattern_cache.insert(cache_key, elements.clone());
+        let mut elements = match parsed {
+            Some(elements) => elements,
+            None => self.parse_file_regex(file_path, content, file_type)?,
+        };
+        attach_docs(content, file_type, &mut elements);
+        attach_body_metrics(file_type, &mut elements);
+        if self.incremental && self.incremental_stats.reparsed == reparsed_before {
+            self.incremental_stats.full += 1;
+        }
+        let evicted = self
+            .pattern_cache
+            .insert(file_path, fingerprint, elements.clone());
+        self.forget_trees(&evicted);
         Ok(elements)
     }
 
     #[cfg(feature = "tree_sitter")]
     fn try_tree_sitter_parse(
-        &self,
+        &mut self,
         file_path: &Path,
         content: &str,
         file_type: &FileType,
@@ -359,7 +610,7 @@ impl ParserAST {
                     .map_err(|e| {
                         crate::types::AnalysisError::Parse(format!("tree-sitter rust: {e:?}"))
                     })?;
-                let tree = match parser.parse(content, None) {
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
                     Some(t) => t,
                     None => return Ok(None),
                 };
@@ -372,7 +623,7 @@ impl ParserAST {
                     .map_err(|e| {
                         crate::types::AnalysisError::Parse(format!("tree-sitter js: {e:?}"))
                     })?;
-                let tree = match parser.parse(content, None) {
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
                     Some(t) => t,
                     None => return Ok(None),
                 };
@@ -380,12 +631,15 @@ impl ParserAST {
                 Ok(Some(elements))
             }
             FileType::TypeScript => {
-                parser
-                    .set_language(tree_sitter_typescript::language_typescript())
-                    .map_err(|e| {
-                        crate::types::AnalysisError::Parse(format!("tree-sitter ts: {e:?}"))
-                    })?;
-                let tree = match parser.parse(content, None) {
+                let language = if file_path.extension().is_some_and(|e| e == "tsx") {
+                    tree_sitter_typescript::language_tsx()
+                } else {
+                    tree_sitter_typescript::language_typescript()
+                };
+                parser.set_language(language).map_err(|e| {
+                    crate::types::AnalysisError::Parse(format!("tree-sitter ts: {e:?}"))
+                })?;
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
                     Some(t) => t,
                     None => return Ok(None),
                 };
@@ -398,33 +652,103 @@ impl ParserAST {
                     .map_err(|e| {
                         crate::types::AnalysisError::Parse(format!("tree-sitter py: {e:?}"))
                     })?;
-                let tree = match parser.parse(content, None) {
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
                     Some(t) => t,
                     None => return Ok(None),
                 };
                 self.ts_collect_py_nodes(content, file_path, tree.root_node(), &mut elements)?;
                 Ok(Some(elements))
             }
+            FileType::Go => {
+                parser
+                    .set_language(tree_sitter_go::language())
+                    .map_err(|e| {
+                        crate::types::AnalysisError::Parse(format!("tree-sitter go: {e:?}"))
+                    })?;
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
+                    Some(t) => t,
+                    None => return Ok(None),
+                };
+                self.ts_collect_go_nodes(content, tree.root_node(), &mut elements)?;
+                Ok(Some(elements))
+            }
+            FileType::Java => {
+                parser
+                    .set_language(tree_sitter_java::language())
+                    .map_err(|e| {
+                        crate::types::AnalysisError::Parse(format!("tree-sitter java: {e:?}"))
+                    })?;
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
+                    Some(t) => t,
+                    None => return Ok(None),
+                };
+                self.ts_collect_java_nodes(content, tree.root_node(), &mut elements)?;
+                Ok(Some(elements))
+            }
+            // грамматика C++ разбирает и C
+            FileType::Cpp | FileType::C => {
+                parser
+                    .set_language(tree_sitter_cpp::language())
+                    .map_err(|e| {
+                        crate::types::AnalysisError::Parse(format!("tree-sitter cpp: {e:?}"))
+                    })?;
+                let tree = match self.ts_parse(&mut parser, file_path, content, file_type) {
+                    Some(t) => t,
+                    None => return Ok(None),
+                };
+                self.ts_collect_cpp_nodes(content, tree.root_node(), &mut elements)?;
+                Ok(Some(elements))
+            }
             _ => Ok(None),
         }
     }
 
+    /// Разбор встроенным regex-парсером языка (пустой список, если язык не поддерживается)
     fn parse_file_regex(
         &self,
-        _file_path: &Path,
+        file_path: &Path,
         content: &str,
         file_type: &FileType,
     ) -> Result<Vec<ASTElement>> {
-        let patterns = match file_type {
-            FileType::Rust => &self.rust_patterns,
-            FileType::JavaScript => &self.js_patterns,
-            FileType::TypeScript => &self.ts_patterns,
-            FileType::Python => &self.python_patterns,
-            FileType::Java => &self.java_patterns,
-            FileType::Cpp => &self.cpp_patterns,
-            FileType::Go => &self.go_patterns,
-            _ => return Ok(vec![]),
-        };
+        match self
+            .builtin
+            .iter()
+            .find(|p| p.supports(file_path, file_type))
+        {
+            Some(parser) => parser.parse_file(file_path, content, file_type),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Сложность фрагмента по индикаторам встроенного языка
+    #[cfg(feature = "tree_sitter")]
+    fn complexity(&self, content: &str, file_type: &FileType) -> u32 {
+        self.builtin
+            .iter()
+            .find(|p| &p.file_type == file_type)
+            .map_or(1, |p| p.calculate_complexity(content))
+    }
+}
+
+/// Встроенный regex-парсер одного языка — тот же контракт, что и у подключаемых
+#[derive(Debug)]
+pub struct RegexLanguageParser {
+    name: String,
+    file_type: FileType,
+    patterns: LanguagePatterns,
+}
+
+impl RegexLanguageParser {
+    pub fn new(name: impl Into<String>, file_type: FileType, patterns: LanguagePatterns) -> Self {
+        Self {
+            name: name.into(),
+            file_type,
+            patterns,
+        }
+    }
+
+    fn parse_lines(&self, content: &str) -> Vec<ASTElement> {
+        let (patterns, file_type) = (&self.patterns, &self.file_type);
         let mut elements = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
         for (line_num, line) in lines.iter().enumerate() {
@@ -433,10 +757,15 @@ impl ParserAST {
                 elements.push(element);
             }
         }
+        span_regex_elements(&lines, file_type, &mut elements);
+        nest_regex_elements(&mut elements);
         for element in &mut elements {
-            element.complexity = self.calculate_complexity(&element.content, patterns);
+            element.complexity = self.calculate_complexity(&element.content);
         }
-        Ok(elements)
+        if matches!(file_type, FileType::Rust) {
+            annotate_rust_impls(content, &mut elements);
+        }
+        elements
     }
 
     /// Продвинутый анализ строки с использованием regex паттернов
@@ -525,9 +854,10 @@ impl ParserAST {
             ));
         }
 
+        // у PHP импорт и константа — альтернативы с разными группами захвата
         if let Some(caps) = patterns.imports.captures(trimmed) {
             return Some(self.create_element(
-                caps.get(1)?.as_str(),
+                caps.iter().skip(1).flatten().next()?.as_str(),
                 ASTElementType::Import,
                 line,
                 line_num,
@@ -537,6 +867,38 @@ impl ParserAST {
             ));
         }
 
+        // Константы и глобальное состояние верхнего уровня (цели связей Reads/Writes)
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(caps) = patterns.constants.captures(trimmed) {
+                return Some(self.create_element(
+                    caps.iter().skip(1).flatten().next()?.as_str(),
+                    ASTElementType::Constant,
+                    line,
+                    line_num,
+                    self.extract_visibility(trimmed),
+                    vec![],
+                    None,
+                ));
+            }
+            let module_state = matches!(
+                file_type,
+                FileType::Rust | FileType::JavaScript | FileType::TypeScript | FileType::Go
+            );
+            if module_state {
+                if let Some(caps) = patterns.variables.captures(trimmed) {
+                    return Some(self.create_element(
+                        caps.get(1)?.as_str(),
+                        ASTElementType::Variable,
+                        line,
+                        line_num,
+                        self.extract_visibility(trimmed),
+                        vec![],
+                        None,
+                    ));
+                }
+            }
+        }
+
         None
     }
 
@@ -627,10 +989,10 @@ impl ParserAST {
     }
 
     /// Вычисляет реальную сложность на основе содержимого
-    fn calculate_complexity(&self, content: &str, patterns: &LanguagePatterns) -> u32 {
+    fn calculate_complexity(&self, content: &str) -> u32 {
         let mut complexity = 1;
 
-        for indicator in &patterns.complexity_indicators {
+        for indicator in &self.patterns.complexity_indicators {
             complexity += indicator.find_iter(content).count() as u32;
         }
 
@@ -667,114 +1029,964 @@ impl ParserAST {
     }
 }
 
-#[cfg(feature = "tree_sitter")]
-impl ParserAST {
-    fn ts_collect_rust_nodes(
-        &self,
-        content: &str,
-        file_path: &Path,
-        node: tree_sitter::Node,
-        out: &mut Vec<ASTElement>,
-    ) -> Result<()> {
-        // DFS with ancestor flag: inside impl/trait
-        let mut stack: Vec<(tree_sitter::Node, bool)> = vec![(node, false)];
-        while let Some((n, in_impl_trait)) = stack.pop() {
-            let kind = n.kind();
-            let now_in_impl_trait = in_impl_trait || kind == "impl_item" || kind == "trait_item";
-            match kind {
-                "function_item" => {
-                    if let Some(el) =
-                        self.ts_build_fn_element(content, &n, file_path, now_in_impl_trait)?
-                    {
-                        out.push(el);
-                    }
-                }
-                "struct_item" => {
-                    if let Some(el) =
-                        self.ts_build_named_element(content, &n, file_path, ASTElementType::Struct)?
-                    {
-                        out.push(el);
-                    }
-                }
-                "enum_item" => {
-                    if let Some(el) =
-                        self.ts_build_named_element(content, &n, file_path, ASTElementType::Enum)?
-                    {
-                        out.push(el);
-                    }
-                }
-                "trait_item" => {
-                    if let Some(el) = self.ts_build_named_element(
-                        content,
-                        &n,
-                        file_path,
-                        ASTElementType::Interface,
-                    )? {
-                        out.push(el);
-                    }
-                }
-                "mod_item" => {
-                    if let Some(el) =
-                        self.ts_build_named_element(content, &n, file_path, ASTElementType::Module)?
-                    {
-                        out.push(el);
-                    }
-                }
-                "use_declaration" => {
-                    if let Some(el) = self.ts_build_import_element(content, &n, file_path)? {
-                        out.push(el);
-                    }
-                }
-                _ => {}
-            }
-            for i in 0..n.child_count() {
-                if let Some(ch) = n.child(i) {
-                    stack.push((ch, now_in_impl_trait));
-                }
-            }
-        }
-        Ok(())
+impl LanguageParser for RegexLanguageParser {
+    fn name(&self) -> &str {
+        &self.name
     }
 
-    fn ts_text<'a>(&self, content: &'a str, node: &tree_sitter::Node) -> &'a str {
-        let range = node.byte_range();
-        let bytes = content.as_bytes();
-        let start = range.start.min(bytes.len());
-        let end = range.end.min(bytes.len());
-        &content[start..end]
+    fn supports(&self, _file_path: &Path, file_type: &FileType) -> bool {
+        &self.file_type == file_type
     }
 
-    fn ts_find_child<'a>(
+    fn parse_file(
         &self,
-        node: &'a tree_sitter::Node,
-        kind: &str,
-    ) -> Option<tree_sitter::Node<'a>> {
-        for i in 0..node.child_count() {
-            if let Some(ch) = node.child(i) {
-                if ch.kind() == kind {
-                    return Some(ch);
-                }
-            }
-        }
-        None
+        _file_path: &Path,
+        content: &str,
+        _file_type: &FileType,
+    ) -> Result<Vec<ASTElement>> {
+        Ok(self.parse_lines(content))
     }
+}
 
-    fn ts_identifier<'a>(&self, node: &'a tree_sitter::Node) -> Option<tree_sitter::Node<'a>> {
-        self.ts_find_child(node, "identifier")
-            .or_else(|| self.ts_find_child(node, "type_identifier"))
+/// Значение-функция у объявления (`value` у переменной, поля класса или пары объекта)
+#[cfg(feature = "tree_sitter")]
+fn ts_js_fn_value<'a>(node: &tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+    node.child_by_field_name("value").filter(|v| {
+        matches!(
+            v.kind(),
+            "arrow_function" | "function" | "function_expression" | "generator_function"
+        )
+    })
+}
+
+/// Видимость по соглашению Python: `__name` — private, `_name` — protected
+#[cfg(feature = "tree_sitter")]
+fn py_visibility(name: &str) -> &'static str {
+    if name.starts_with("__") && !name.ends_with("__") {
+        "private"
+    } else if name.starts_with('_') && !name.starts_with("__") {
+        "protected"
+    } else {
+        "public"
     }
+}
 
-    fn ts_visibility(&self, node: &tree_sitter::Node, content: &str) -> String {
-        let text = self.ts_text(content, node);
-        if text.trim_start().starts_with("pub") {
-            "public".to_string()
-        } else {
-            "private".to_string()
+/// Видимость по соглашению Go: экспортируются имена с заглавной буквы
+#[cfg(feature = "tree_sitter")]
+fn go_visibility(name: &str) -> &'static str {
+    if name.starts_with(|c: char| c.is_uppercase()) {
+        "public"
+    } else {
+        "private"
+    }
+}
+
+/// Go реализует интерфейсы неявно: тип реализует интерфейс файла, если среди методов
+/// его ресиверов есть все методы интерфейса. Методы ресивера вкладываются в свой тип.
+#[cfg(feature = "tree_sitter")]
+fn link_go_methods(
+    elements: &mut [ASTElement],
+    interfaces: &[(String, Vec<String>)],
+    receivers: &[(usize, String)],
+) {
+    let mut impls: Vec<(String, String)> = Vec::new();
+    let mut types: Vec<&str> = receivers.iter().map(|(_, ty)| ty.as_str()).collect();
+    types.sort_unstable();
+    types.dedup();
+    for ty in types {
+        let methods: Vec<&str> = receivers
+            .iter()
+            .filter(|(_, t)| t == ty)
+            .map(|(i, _)| elements[*i].name.as_str())
+            .collect();
+        for (iface, required) in interfaces {
+            if !required.is_empty() && required.iter().all(|m| methods.contains(&m.as_str())) {
+                impls.push((iface.clone(), ty.to_string()));
+            }
+        }
+    }
+    for (i, ty) in receivers {
+        if let Some((iface, _)) = impls.iter().find(|(iface, t)| {
+            t == ty
+                && interfaces
+                    .iter()
+                    .any(|(name, ms)| name == iface && ms.contains(&elements[*i].name))
+        }) {
+            elements[*i]
+                .metadata
+                .insert(IMPL_TRAIT_KEY.to_string(), iface.clone());
+            elements[*i]
+                .metadata
+                .insert(IMPL_FOR_KEY.to_string(), ty.clone());
         }
     }
+    let links: Vec<(usize, usize)> = receivers
+        .iter()
+        .filter_map(|(i, ty)| {
+            elements
+                .iter()
+                .position(|e| e.element_type == ASTElementType::Struct && e.name == *ty)
+                .map(|p| (*i, p))
+        })
+        .collect();
+    link_parents(elements, &links);
+    link_type_impls(elements, &impls);
+}
 
-    fn ts_build_named_element(
-        &self,
+/// Простое имя Java-типа: `java.util.List<T>` → `List`
+#[cfg(feature = "tree_sitter")]
+fn java_type_name(text: &str) -> String {
+    let base = rust_type_name(text);
+    base.rsplit('.').next().unwrap_or(&base).to_string()
+}
+
+/// `function_declarator` объявления, в том числе под указателем/ссылкой: `Foo* make()`
+#[cfg(feature = "tree_sitter")]
+fn cpp_function_declarator<'a>(node: &tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while declarator.kind() != "function_declarator" {
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    Some(declarator)
+}
+
+/// Связывает пары (потомок, родитель) индексов `elements` через `parent_id`/`children`
+fn link_parents(elements: &mut [ASTElement], links: &[(usize, usize)]) {
+    for &(child, parent) in links {
+        let parent_id = elements[parent].id;
+        let child_id = elements[child].id;
+        elements[child].parent_id = Some(parent_id);
+        elements[parent].children.push(child_id);
+    }
+}
+
+/// Правка между версиями файла: общий префикс и суффикс остаются, середина заменена
+#[cfg(feature = "tree_sitter")]
+fn ts_input_edit(old: &str, new: &str) -> tree_sitter::InputEdit {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+    let mut start = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(start) || !new.is_char_boundary(start) {
+        start -= 1;
+    }
+    let max_suffix = old_bytes.len().min(new_bytes.len()) - start;
+    let mut suffix = old_bytes
+        .iter()
+        .rev()
+        .zip(new_bytes.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old_bytes.len() - suffix)
+        || !new.is_char_boundary(new_bytes.len() - suffix)
+    {
+        suffix -= 1;
+    }
+    let old_end = old_bytes.len() - suffix;
+    let new_end = new_bytes.len() - suffix;
+    tree_sitter::InputEdit {
+        start_byte: start,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: ts_point(old, start),
+        old_end_position: ts_point(old, old_end),
+        new_end_position: ts_point(new, new_end),
+    }
+}
+
+/// Узел объявлен внутри тела функции (локальные const/static не капсулы)
+#[cfg(feature = "tree_sitter")]
+fn ts_inside_function(node: &tree_sitter::Node) -> bool {
+    let mut parent = node.parent();
+    while let Some(p) = parent {
+        if matches!(p.kind(), "function_item" | "closure_expression") {
+            return true;
+        }
+        parent = p.parent();
+    }
+    false
+}
+
+/// Строка и байтовый столбец смещения
+#[cfg(feature = "tree_sitter")]
+fn ts_point(text: &str, byte: usize) -> tree_sitter::Point {
+    let before = &text.as_bytes()[..byte];
+    let row = before.iter().filter(|b| **b == b'\n').count();
+    let column = before
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(byte, |newline| byte - newline - 1);
+    tree_sitter::Point::new(row, column)
+}
+
+/// Имя типа без пути, параметров и ссылок: `&mut fmt::Display<T>` -> `Display`
+fn rust_type_name(text: &str) -> String {
+    let text = text
+        .trim()
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim_start_matches("dyn ")
+        .trim();
+    let base = text.split('<').next().unwrap_or(text);
+    base.rsplit("::").next().unwrap_or(base).trim().to_string()
+}
+
+/// Заголовки `impl<..> Trait<..> for Type<..>` и `trait Name`, строка `fn` (regex путь)
+fn rust_impl_patterns() -> &'static [Regex; 4] {
+    static PATTERNS: std::sync::OnceLock<[Regex; 4]> = std::sync::OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            r"^\s*(?:unsafe\s+)?impl(?:\s*<.*?>)?\s+!?([\w:]+(?:<.*?>)?)\s+for\s+([&\w:]+)",
+            r"^\s*(?:unsafe\s+)?impl(?:\s*<.*?>)?\s+([\w:]+)(?:<.*?>)?\s*(?:\{|where\b|$)",
+            r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:unsafe\s+)?trait\s+\w+",
+            r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?fn\s+\w+",
+        ]
+        .map(|pattern| Regex::new(pattern).expect("valid rust impl pattern"))
+    })
+}
+
+/// Отмечает типы файла трейтами (интерфейсами), которые они реализуют
+fn link_type_impls(elements: &mut [ASTElement], impls: &[(String, String)]) {
+    for element in elements.iter_mut() {
+        if !matches!(
+            element.element_type,
+            ASTElementType::Struct | ASTElementType::Enum | ASTElementType::Class
+        ) {
+            continue;
+        }
+        let mut traits: Vec<&str> = impls
+            .iter()
+            .filter(|(tr, ty)| !tr.is_empty() && *ty == element.name)
+            .map(|(t, _)| t.as_str())
+            .collect();
+        if traits.is_empty() {
+            continue;
+        }
+        traits.sort_unstable();
+        traits.dedup();
+        element
+            .metadata
+            .insert(IMPLEMENTS_KEY.to_string(), traits.join(","));
+    }
+}
+
+/// Размер тела функции по её разобранному диапазону
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BodyMetrics {
+    pub statements: usize,
+    pub max_nesting: usize,
+}
+
+impl BodyMetrics {
+    /// Метрики из `metadata` элемента или капсулы
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        Some(Self {
+            statements: metadata.get(STATEMENTS_KEY)?.parse().ok()?,
+            max_nesting: metadata.get(MAX_NESTING_KEY)?.parse().ok()?,
+        })
+    }
+}
+
+/// Операторы и вложенность тела функции по её исходнику (от сигнатуры до конца).
+/// Блоки считаются по фигурным скобкам, в Python и Ruby — по отступам
+pub fn body_metrics(source: &str, file_type: &FileType) -> BodyMetrics {
+    let lines: Vec<&str> = source.lines().collect();
+    let comment = |t: &str| match file_type {
+        FileType::Python | FileType::Ruby => t.starts_with('#'),
+        FileType::Php => t.starts_with("//") || t.starts_with('#') || t.starts_with('*'),
+        _ => t.starts_with("//") || t.starts_with("/*") || t.starts_with('*'),
+    };
+    let closing = |t: &str| t.chars().all(|c| "})];,".contains(c)) || t == "end";
+    let mut metrics = BodyMetrics::default();
+
+    if matches!(file_type, FileType::Python | FileType::Ruby) {
+        // сигнатура Python может занимать несколько строк до `:`
+        let body_start = match file_type {
+            FileType::Python => lines
+                .iter()
+                .position(|l| l.split('#').next().unwrap_or("").trim_end().ends_with(':'))
+                .map_or(1, |i| i + 1),
+            _ => 1,
+        };
+        let indent = |l: &str| l.len() - l.trim_start().len();
+        let body: Vec<&str> = lines
+            .iter()
+            .skip(body_start)
+            .copied()
+            .filter(|l| {
+                let t = l.trim();
+                !t.is_empty() && !comment(t) && !closing(t)
+            })
+            .collect();
+        let Some(base) = body.iter().map(|l| indent(l)).min() else {
+            return metrics;
+        };
+        let unit = body
+            .iter()
+            .map(|l| indent(l) - base)
+            .filter(|d| *d > 0)
+            .min()
+            .unwrap_or(1);
+        metrics.statements = body.len();
+        metrics.max_nesting = body
+            .iter()
+            .map(|l| (indent(l) - base) / unit)
+            .max()
+            .unwrap_or(0);
+        return metrics;
+    }
+
+    // тело начинается после первой `{`; глубина 1 — уровень самого тела
+    let mut depth = 0usize;
+    let mut opened = false;
+    for line in lines {
+        let code = line.split("//").next().unwrap_or("");
+        let mut rest_after_open = None;
+        let mut in_string: Option<char> = None;
+        let mut escaped = false;
+        for (i, ch) in code.char_indices() {
+            if let Some(quote) = in_string {
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' {
+                    escaped = true;
+                } else if ch == quote {
+                    in_string = None;
+                }
+                continue;
+            }
+            match ch {
+                '"' => in_string = Some(ch),
+                '{' => {
+                    depth += 1;
+                    if !opened {
+                        opened = true;
+                        rest_after_open = Some(i + 1);
+                    }
+                    metrics.max_nesting = metrics.max_nesting.max(depth.saturating_sub(1));
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        let counted = match rest_after_open {
+            // строка с открывающей скобкой тела: считается только код после неё
+            Some(at) => &code[at..],
+            None if opened => code,
+            None => continue,
+        };
+        let t = counted.trim();
+        if !t.is_empty() && !comment(t) && !closing(t) {
+            metrics.statements += 1;
+        }
+        if opened && depth == 0 {
+            break;
+        }
+    }
+    metrics
+}
+
+/// Метрики тела в `metadata` функций и методов любого пути разбора
+fn attach_body_metrics(file_type: &FileType, elements: &mut [ASTElement]) {
+    for element in elements.iter_mut() {
+        if !matches!(
+            element.element_type,
+            ASTElementType::Function | ASTElementType::Method
+        ) {
+            continue;
+        }
+        let metrics = body_metrics(&element.content, file_type);
+        element
+            .metadata
+            .insert(STATEMENTS_KEY.to_string(), metrics.statements.to_string());
+        element
+            .metadata
+            .insert(MAX_NESTING_KEY.to_string(), metrics.max_nesting.to_string());
+        if is_stub_body(&element.content, &metrics) {
+            element
+                .metadata
+                .insert(STUB_KEY.to_string(), "true".to_string());
+        }
+    }
+}
+
+/// Тело из одного-двух операторов, один из которых сообщает «не реализовано»
+pub fn is_stub_body(source: &str, metrics: &BodyMetrics) -> bool {
+    static NOT_IMPLEMENTED: OnceLock<Regex> = OnceLock::new();
+    let pattern = NOT_IMPLEMENTED.get_or_init(|| {
+        Regex::new(
+            r#"\b(?:todo!|unimplemented!|NotImplementedError|NotImplementedException|UnsupportedOperationException|BadMethodCallException)|(?i:throw\s+new\s+Error\(\s*["'`]not\s+implemented)"#,
+        )
+        .unwrap()
+    });
+    metrics.statements <= 2 && pattern.is_match(source)
+}
+
+/// Документирующие комментарии в `metadata["doc"]` для элементов любого пути разбора;
+/// уже заполненный парсером-плагином `doc` не перезаписывается
+fn attach_docs(content: &str, file_type: &FileType, elements: &mut [ASTElement]) {
+    let lines: Vec<&str> = content.lines().collect();
+    for element in elements.iter_mut() {
+        let skip = matches!(
+            element.element_type,
+            ASTElementType::Import | ASTElementType::Export | ASTElementType::Comment
+        );
+        if skip || element.metadata.contains_key(DOC_KEY) || element.start_line == 0 {
+            continue;
+        }
+        let start = element.start_line - 1;
+        let doc = match file_type {
+            FileType::Python => python_docstring(&lines, start, element.end_line),
+            _ => leading_doc(&lines, start, file_type),
+        };
+        if let Some(doc) = doc.filter(|d| !d.is_empty()) {
+            element.metadata.insert(DOC_KEY.to_string(), doc);
+        }
+    }
+}
+
+/// Комментарий непосредственно перед заголовком (атрибуты и аннотации между ними
+/// пропускаются): блок `/** */` для всех C-подобных языков, строчные `///` в Rust
+/// и C/C++, `//` в Go, `#` в Ruby
+fn leading_doc(lines: &[&str], start: usize, file_type: &FileType) -> Option<String> {
+    let mut idx = start.min(lines.len());
+    while idx > 0 && is_annotation(lines[idx - 1].trim(), file_type) {
+        idx -= 1;
+    }
+    let prev = lines.get(idx.checked_sub(1)?)?.trim();
+    if prev.ends_with("*/") {
+        let first = (0..idx).rev().find(|&i| lines[i].contains("/*"))?;
+        if !lines[first].trim_start().starts_with("/**") {
+            return None;
+        }
+        let text: Vec<&str> = lines[first..idx]
+            .iter()
+            .map(|l| {
+                let l = l.trim().trim_start_matches("/**").trim_end_matches("*/");
+                l.trim().trim_start_matches('*').trim()
+            })
+            .collect();
+        return Some(text.join("\n").trim().to_string());
+    }
+    let marker = match file_type {
+        FileType::Rust | FileType::Cpp | FileType::C => "///",
+        FileType::Go => "//",
+        FileType::Ruby => "#",
+        _ => return None,
+    };
+    let mut text: Vec<&str> = lines[..idx]
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with(marker) && !l.starts_with("////") && !l.starts_with("#!"))
+        .map(|l| l[marker.len()..].trim())
+        .collect();
+    text.reverse();
+    Some(text.join("\n").trim().to_string())
+}
+
+fn is_annotation(line: &str, file_type: &FileType) -> bool {
+    match file_type {
+        FileType::Rust | FileType::Php => line.starts_with("#["),
+        FileType::Java | FileType::TypeScript | FileType::JavaScript => line.starts_with('@'),
+        _ => false,
+    }
+}
+
+/// Docstring Python: первая инструкция тела после (возможно многострочного) заголовка
+fn python_docstring(lines: &[&str], start: usize, end_line: usize) -> Option<String> {
+    let end = end_line.min(lines.len());
+    let header_end = (start..end).find(|&i| {
+        let line = lines[i].split('#').next().unwrap_or("").trim_end();
+        !lines[i].trim_start().starts_with('@') && line.ends_with(':')
+    })?;
+    let first = (header_end + 1..end).find(|&i| !lines[i].trim().is_empty())?;
+    let opening = lines[first].trim().trim_start_matches(['r', 'u', 'R', 'U']);
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|q| opening.starts_with(q))?;
+    let rest = &opening[quote.len()..];
+    if let Some(close) = rest.find(quote) {
+        return Some(rest[..close].trim().to_string());
+    }
+    let mut text = vec![rest.trim()];
+    for line in &lines[first + 1..end] {
+        match line.find(quote) {
+            Some(close) => {
+                text.push(line[..close].trim());
+                return Some(text.join("\n").trim().to_string());
+            }
+            None => text.push(line.trim()),
+        }
+    }
+    None
+}
+
+/// Границы блоков для regex пути: `end_line` и `content` охватывают всё тело
+/// заголовка, чтобы размер и сложность считались по реальному телу
+fn span_regex_elements(lines: &[&str], file_type: &FileType, elements: &mut [ASTElement]) {
+    for element in elements.iter_mut() {
+        let scoped = matches!(
+            element.element_type,
+            ASTElementType::Class
+                | ASTElementType::Struct
+                | ASTElementType::Interface
+                | ASTElementType::Enum
+                | ASTElementType::Module
+                | ASTElementType::Function
+        );
+        if !scoped {
+            continue;
+        }
+        let start = element.start_line - 1;
+        if let Some(end) = block_end(lines, start, file_type).filter(|&end| end > start) {
+            element.end_line = end + 1;
+            element.end_column = lines[end].len();
+            element.content = lines[start..=end].join("\n");
+        }
+    }
+}
+
+/// Вложенность для regex пути: элемент принадлежит ближайшему объемлющему блоку
+/// класса/модуля/функции; функции внутри типов становятся методами
+fn nest_regex_elements(elements: &mut [ASTElement]) {
+    // индексы элементов, чей блок ещё не закрыт
+    let mut open: Vec<usize> = Vec::new();
+    let mut links: Vec<(usize, usize)> = Vec::new();
+    for idx in 0..elements.len() {
+        let line = elements[idx].start_line;
+        while open.last().is_some_and(|&p| elements[p].end_line < line) {
+            open.pop();
+        }
+        if let Some(&parent) = open.last() {
+            links.push((idx, parent));
+            let in_type = matches!(
+                elements[parent].element_type,
+                ASTElementType::Class
+                    | ASTElementType::Struct
+                    | ASTElementType::Interface
+                    | ASTElementType::Enum
+            );
+            if in_type && elements[idx].element_type == ASTElementType::Function {
+                elements[idx].element_type = ASTElementType::Method;
+            }
+        }
+        if elements[idx].end_line > line {
+            open.push(idx);
+        }
+    }
+    link_parents(elements, &links);
+}
+
+/// Последняя строка (индекс с нуля) блока, открытого заголовком в строке `start`:
+/// по отступам для Python/Ruby, по фигурным скобкам для остальных языков
+fn block_end(lines: &[&str], start: usize, file_type: &FileType) -> Option<usize> {
+    if matches!(file_type, FileType::Python | FileType::Ruby) {
+        let indent = indent_width(lines[start]);
+        let mut end = start;
+        for (i, line) in lines.iter().enumerate().skip(start + 1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // `rescue`/`ensure`/`else` Ruby на уровне заголовка продолжают блок
+            let continues = matches!(file_type, FileType::Ruby)
+                && indent_width(line) == indent
+                && ["rescue", "ensure", "else", "elsif", "when"]
+                    .iter()
+                    .any(|kw| {
+                        line.trim_start().strip_prefix(kw).is_some_and(|rest| {
+                            !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                        })
+                    });
+            if continues {
+                end = i;
+                continue;
+            }
+            if indent_width(line) <= indent {
+                // закрывающий `end` Ruby на уровне заголовка входит в блок
+                let closes = matches!(file_type, FileType::Ruby)
+                    && indent_width(line) == indent
+                    && line.trim_start().strip_prefix("end").is_some_and(|rest| {
+                        !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+                    });
+                if closes {
+                    end = i;
+                }
+                break;
+            }
+            end = i;
+        }
+        return (end > start).then_some(end);
+    }
+    let mut depth = 0;
+    let mut opened = false;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        for ch in code_delimiters(line) {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' if opened => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                // `mod foo;`, `class Foo;`, абстрактный метод — блока нет
+                ';' if !opened => return None,
+                _ => {}
+            }
+        }
+    }
+    // незакрытый блок тянется до конца файла
+    opened.then(|| lines.len() - 1)
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Скобки и `;` строки вне строковых литералов (в том числе сырых `r#"…"#`),
+/// символьных литералов и `//`-комментариев
+fn code_delimiters(line: &str) -> impl Iterator<Item = char> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut delimiters = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if chars.get(i + 1) == Some(&'/') => break,
+            '"' => i = literal_end(&chars, i + 1, None),
+            'r' if i == 0 || chars[i - 1] == 'b' || !is_ident(chars[i - 1]) => {
+                let hashes = chars[i + 1..].iter().take_while(|&&c| c == '#').count();
+                if chars.get(i + 1 + hashes) == Some(&'"') {
+                    i = literal_end(&chars, i + 2 + hashes, Some(hashes));
+                }
+            }
+            // `'{'` и `'\''`, но не время жизни `'a`
+            '\'' => {
+                if chars.get(i + 1) == Some(&'\\') {
+                    i += chars[i + 2..]
+                        .iter()
+                        .position(|&c| c == '\'')
+                        .map_or(0, |p| p + 2);
+                } else if chars.get(i + 2) == Some(&'\'') {
+                    i += 2;
+                }
+            }
+            ch @ ('{' | '}' | ';') => delimiters.push(ch),
+            _ => {}
+        }
+        i += 1;
+    }
+    delimiters.into_iter()
+}
+
+/// Индекс закрывающей кавычки литерала, начатого перед `from`; для сырой строки
+/// `raw` — число `#` в её ограничителе. Незакрытый литерал тянется до конца строки
+fn literal_end(chars: &[char], from: usize, raw: Option<usize>) -> usize {
+    let mut i = from;
+    while i < chars.len() {
+        match (chars[i], raw) {
+            ('\\', None) => i += 1,
+            ('"', None) => return i,
+            ('"', Some(hashes)) => {
+                let closing = chars[i + 1..].iter().take(hashes);
+                if closing.filter(|&&c| c == '#').count() == hashes {
+                    return i + hashes;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+fn is_ident(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// `impl Trait for Type`, `impl Type` и методы трейтов для regex пути: блоки
+/// отслеживаются по глубине фигурных скобок, вложенные блоки не рассматриваются.
+/// Собственные `impl Type` попадают в список с пустым именем трейта
+fn annotate_rust_impls(content: &str, elements: &mut [ASTElement]) {
+    enum Block {
+        Impl(usize),
+        Trait(usize),
+    }
+    let [impl_header, inherent_header, trait_header, fn_line] = rust_impl_patterns();
+
+    let mut impls: Vec<(String, String)> = Vec::new();
+    let mut method_impls: HashMap<usize, usize> = HashMap::new();
+    let mut trait_methods: HashMap<usize, usize> = HashMap::new();
+    let mut open: Option<(Block, i32, bool)> = None;
+    let mut depth: i32 = 0;
+    for (idx, line) in content.lines().enumerate() {
+        let line_num = idx + 1;
+        if open.is_none() {
+            if let Some(caps) = impl_header.captures(line) {
+                impls.push((rust_type_name(&caps[1]), rust_type_name(&caps[2])));
+                open = Some((Block::Impl(impls.len() - 1), depth, false));
+            } else if let Some(caps) = inherent_header.captures(line) {
+                impls.push((String::new(), rust_type_name(&caps[1])));
+                open = Some((Block::Impl(impls.len() - 1), depth, false));
+            } else if trait_header.is_match(line) {
+                open = Some((Block::Trait(line_num), depth, false));
+            }
+        } else if fn_line.is_match(line) {
+            match open {
+                Some((Block::Impl(i), start, _)) if depth == start + 1 => {
+                    method_impls.insert(line_num, i);
+                }
+                Some((Block::Trait(l), start, _)) if depth == start + 1 => {
+                    *trait_methods.entry(l).or_default() += 1;
+                }
+                _ => {}
+            }
+        }
+        for ch in line.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    if let Some((_, _, entered)) = open.as_mut() {
+                        *entered = true;
+                    }
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        if let Some((_, start, true)) = open {
+            if depth <= start {
+                open = None;
+            }
+        }
+    }
+
+    for element in elements.iter_mut() {
+        if let Some(i) = method_impls.get(&element.start_line) {
+            let (trait_name, type_name) = &impls[*i];
+            if !trait_name.is_empty() {
+                element
+                    .metadata
+                    .insert(IMPL_TRAIT_KEY.to_string(), trait_name.clone());
+            }
+            element
+                .metadata
+                .insert(IMPL_FOR_KEY.to_string(), type_name.clone());
+        }
+        if element.element_type == ASTElementType::Interface {
+            let count = trait_methods.get(&element.start_line).copied().unwrap_or(0);
+            element
+                .metadata
+                .insert(TRAIT_METHODS_KEY.to_string(), count.to_string());
+        }
+    }
+    link_type_impls(elements, &impls);
+}
+
+#[cfg(feature = "tree_sitter")]
+impl ParserAST {
+    /// Разбор с переиспользованием прошлого дерева файла в инкрементальном режиме
+    fn ts_parse(
+        &mut self,
+        parser: &mut tree_sitter::Parser,
+        file_path: &Path,
+        content: &str,
+        file_type: &FileType,
+    ) -> Option<tree_sitter::Tree> {
+        let previous = if self.incremental {
+            self.trees.remove(file_path)
+        } else {
+            None
+        };
+        let tree = match previous {
+            Some(mut previous) if previous.file_type == *file_type => {
+                previous
+                    .tree
+                    .edit(&ts_input_edit(&previous.content, content));
+                let tree = parser.parse(content, Some(&previous.tree))?;
+                self.incremental_stats.reparsed += 1;
+                tree
+            }
+            _ => parser.parse(content, None)?,
+        };
+        if self.incremental {
+            self.trees.insert(
+                file_path.to_path_buf(),
+                ParsedTree {
+                    content: content.to_string(),
+                    file_type: file_type.clone(),
+                    tree: tree.clone(),
+                },
+            );
+        }
+        Some(tree)
+    }
+
+    fn ts_collect_rust_nodes(
+        &self,
+        content: &str,
+        file_path: &Path,
+        node: tree_sitter::Node,
+        out: &mut Vec<ASTElement>,
+    ) -> Result<()> {
+        // DFS with ancestor flag: inside impl/trait, plus the enclosing `impl Trait for Type`
+        let mut impls: Vec<(String, String)> = Vec::new();
+        let mut stack: Vec<(tree_sitter::Node, bool, Option<usize>)> = vec![(node, false, None)];
+        while let Some((n, in_impl_trait, impl_idx)) = stack.pop() {
+            let kind = n.kind();
+            let now_in_impl_trait = in_impl_trait || kind == "impl_item" || kind == "trait_item";
+            let mut now_impl_idx = impl_idx;
+            match kind {
+                "impl_item" => {
+                    if let Some(ty) = n.child_by_field_name("type") {
+                        let trait_name = n
+                            .child_by_field_name("trait")
+                            .map(|tr| rust_type_name(self.ts_text(content, &tr)))
+                            .unwrap_or_default();
+                        impls.push((trait_name, rust_type_name(self.ts_text(content, &ty))));
+                        now_impl_idx = Some(impls.len() - 1);
+                    }
+                }
+                "function_item" => {
+                    if let Some(mut el) =
+                        self.ts_build_fn_element(content, &n, file_path, now_in_impl_trait)?
+                    {
+                        if let Some(i) = impl_idx {
+                            let (trait_name, type_name) = &impls[i];
+                            if !trait_name.is_empty() {
+                                el.metadata
+                                    .insert(IMPL_TRAIT_KEY.to_string(), trait_name.clone());
+                            }
+                            el.metadata
+                                .insert(IMPL_FOR_KEY.to_string(), type_name.clone());
+                        }
+                        out.push(el);
+                    }
+                }
+                "struct_item" => {
+                    if let Some(el) =
+                        self.ts_build_named_element(content, &n, file_path, ASTElementType::Struct)?
+                    {
+                        out.push(el);
+                    }
+                }
+                "enum_item" => {
+                    if let Some(el) =
+                        self.ts_build_named_element(content, &n, file_path, ASTElementType::Enum)?
+                    {
+                        out.push(el);
+                    }
+                }
+                "trait_item" => {
+                    if let Some(mut el) = self.ts_build_named_element(
+                        content,
+                        &n,
+                        file_path,
+                        ASTElementType::Interface,
+                    )? {
+                        let methods = n
+                            .child_by_field_name("body")
+                            .map(|body| {
+                                (0..body.named_child_count())
+                                    .filter_map(|i| body.named_child(i))
+                                    .filter(|ch| {
+                                        matches!(
+                                            ch.kind(),
+                                            "function_signature_item" | "function_item"
+                                        )
+                                    })
+                                    .count()
+                            })
+                            .unwrap_or(0);
+                        el.metadata
+                            .insert(TRAIT_METHODS_KEY.to_string(), methods.to_string());
+                        out.push(el);
+                    }
+                }
+                "const_item" | "static_item" if !now_in_impl_trait && !ts_inside_function(&n) => {
+                    let element_type = if kind == "const_item" {
+                        ASTElementType::Constant
+                    } else {
+                        ASTElementType::Variable
+                    };
+                    if let Some(el) =
+                        self.ts_build_named_element(content, &n, file_path, element_type)?
+                    {
+                        out.push(el);
+                    }
+                }
+                "mod_item" => {
+                    if let Some(el) =
+                        self.ts_build_named_element(content, &n, file_path, ASTElementType::Module)?
+                    {
+                        out.push(el);
+                    }
+                }
+                "use_declaration" => {
+                    if let Some(el) = self.ts_build_import_element(content, &n, file_path)? {
+                        out.push(el);
+                    }
+                }
+                _ => {}
+            }
+            for i in 0..n.child_count() {
+                if let Some(ch) = n.child(i) {
+                    stack.push((ch, now_in_impl_trait, now_impl_idx));
+                }
+            }
+        }
+        link_type_impls(out, &impls);
+        Ok(())
+    }
+
+    fn ts_text<'a>(&self, content: &'a str, node: &tree_sitter::Node) -> &'a str {
+        let range = node.byte_range();
+        let bytes = content.as_bytes();
+        let start = range.start.min(bytes.len());
+        let end = range.end.min(bytes.len());
+        &content[start..end]
+    }
+
+    fn ts_find_child<'a>(
+        &self,
+        node: &'a tree_sitter::Node,
+        kind: &str,
+    ) -> Option<tree_sitter::Node<'a>> {
+        for i in 0..node.child_count() {
+            if let Some(ch) = node.child(i) {
+                if ch.kind() == kind {
+                    return Some(ch);
+                }
+            }
+        }
+        None
+    }
+
+    fn ts_identifier<'a>(&self, node: &'a tree_sitter::Node) -> Option<tree_sitter::Node<'a>> {
+        self.ts_find_child(node, "identifier")
+            .or_else(|| self.ts_find_child(node, "type_identifier"))
+    }
+
+    fn ts_visibility(&self, node: &tree_sitter::Node, content: &str) -> String {
+        let text = self.ts_text(content, node);
+        if text.trim_start().starts_with("pub") {
+            "public".to_string()
+        } else {
+            "private".to_string()
+        }
+    }
+
+    fn ts_build_named_element(
+        &self,
         content: &str,
         node: &tree_sitter::Node,
         _file_path: &Path,
@@ -874,8 +2086,7 @@ impl ParserAST {
             metadata: HashMap::new(),
         };
         // complexity from regex indicators
-        let patterns = &self.rust_patterns;
-        element.complexity = self.calculate_complexity(&element.content, patterns);
+        element.complexity = self.complexity(&element.content, &FileType::Rust);
         Ok(Some(element))
     }
 
@@ -915,53 +2126,289 @@ impl ParserAST {
             parent_id: None,
             metadata: HashMap::new(),
         };
-        Ok(Some(element))
+        Ok(Some(element))
+    }
+
+    // JavaScript / TypeScript collection
+    fn ts_collect_js_nodes(
+        &self,
+        content: &str,
+        file_path: &Path,
+        node: tree_sitter::Node,
+        out: &mut Vec<ASTElement>,
+    ) -> Result<()> {
+        let file_type = if file_path
+            .extension()
+            .is_some_and(|e| e == "js" || e == "jsx")
+        {
+            FileType::JavaScript
+        } else {
+            FileType::TypeScript
+        };
+        // DFS с индексом ближайшего объемлющего класса/функции в `out`
+        let mut links: Vec<(usize, usize)> = Vec::new();
+        let mut stack: Vec<(tree_sitter::Node, Option<usize>)> = vec![(node, None)];
+        while let Some((n, parent)) = stack.pop() {
+            // ключевые слова `class`/`function` — безымянные узлы с тем же kind
+            if !n.is_named() {
+                continue;
+            }
+            let built = match n.kind() {
+                "function_declaration" | "generator_function_declaration" => {
+                    self.ts_js_function(content, &n, &n, ASTElementType::Function, &file_type)
+                }
+                "method_definition" | "method_signature" | "abstract_method_signature" => {
+                    self.ts_js_function(content, &n, &n, ASTElementType::Method, &file_type)
+                }
+                // `const f = () => {}`, `const g = function () {}`
+                "variable_declarator" => ts_js_fn_value(&n).and_then(|f| {
+                    self.ts_js_function(content, &n, &f, ASTElementType::Function, &file_type)
+                }),
+                // поле класса со стрелочной функцией: `handle = (e) => {}`
+                "public_field_definition" | "field_definition" => {
+                    ts_js_fn_value(&n).and_then(|f| {
+                        self.ts_js_function(content, &n, &f, ASTElementType::Method, &file_type)
+                    })
+                }
+                // метод объектного литерала: `{ load: function () {}, save: () => {} }`
+                "pair" => ts_js_fn_value(&n).and_then(|f| {
+                    self.ts_js_function(content, &n, &f, ASTElementType::Method, &file_type)
+                }),
+                "class_declaration" | "abstract_class_declaration" | "class" => {
+                    self.ts_js_class(content, &n, ASTElementType::Class)
+                }
+                "interface_declaration" => self
+                    .ts_js_class(content, &n, ASTElementType::Interface)
+                    .map(|mut el| {
+                        let methods = n
+                            .child_by_field_name("body")
+                            .map(|body| {
+                                (0..body.named_child_count())
+                                    .filter_map(|i| body.named_child(i))
+                                    .filter(|ch| ch.kind() == "method_signature")
+                                    .count()
+                            })
+                            .unwrap_or(0);
+                        el.metadata
+                            .insert(TRAIT_METHODS_KEY.to_string(), methods.to_string());
+                        el
+                    }),
+                "import_statement" => self.ts_js_import(content, &n)?,
+                _ => None,
+            };
+            let mut now_parent = parent;
+            if let Some(el) = built {
+                let is_import = el.element_type == ASTElementType::Import;
+                out.push(el);
+                if let Some(p) = parent.filter(|_| !is_import) {
+                    links.push((out.len() - 1, p));
+                }
+                now_parent = Some(out.len() - 1);
+            }
+            for i in 0..n.child_count() {
+                if let Some(ch) = n.child(i) {
+                    stack.push((ch, now_parent));
+                }
+            }
+        }
+        link_parents(out, &links);
+        Ok(())
+    }
+
+    fn ts_js_ident(&self, node: &tree_sitter::Node, content: &str) -> Option<String> {
+        if let Some(name) = node
+            .child_by_field_name("name")
+            .or_else(|| node.child_by_field_name("property"))
+            .or_else(|| node.child_by_field_name("key"))
+        {
+            return Some(self.ts_text(content, &name).trim().to_string());
+        }
+        // try identifier child (TypeScript class names are type identifiers)
+        for i in 0..node.child_count() {
+            if let Some(ch) = node.child(i) {
+                if matches!(ch.kind(), "identifier" | "type_identifier") {
+                    return Some(self.ts_text(content, &ch).trim().to_string());
+                }
+            }
+        }
+        None
+    }
+
+    /// Видимость члена класса: модификатор TS или `#private`-имя
+    fn ts_js_visibility(&self, node: &tree_sitter::Node, content: &str) -> String {
+        if let Some(modifier) = self.ts_find_child(node, "accessibility_modifier") {
+            return self.ts_text(content, &modifier).trim().to_string();
+        }
+        let private_name = node
+            .child_by_field_name("name")
+            .or_else(|| node.child_by_field_name("property"))
+            .is_some_and(|name| name.kind() == "private_property_identifier");
+        if private_name {
+            "private".into()
+        } else {
+            "public".into()
+        }
+    }
+
+    /// Элемент функции/метода: `decl` задаёт имя и диапазон, `func` — параметры и тип результата
+    fn ts_js_function(
+        &self,
+        content: &str,
+        decl: &tree_sitter::Node,
+        func: &tree_sitter::Node,
+        element_type: ASTElementType,
+        file_type: &FileType,
+    ) -> Option<ASTElement> {
+        let name = self
+            .ts_js_ident(decl, content)
+            .unwrap_or_else(|| "<anon>".into());
+        let text = self.ts_text(content, decl).to_string();
+        let start = decl.start_position();
+        let end = decl.end_position();
+        let parameters = match func.child_by_field_name("parameters") {
+            Some(list) => (0..list.named_child_count())
+                .filter_map(|i| list.named_child(i))
+                .filter(|p| p.kind() != "comment")
+                .map(|p| self.ts_text(content, &p).trim().to_string())
+                .collect(),
+            // `x => x * 2`
+            None => func
+                .child_by_field_name("parameter")
+                .map(|p| vec![self.ts_text(content, &p).trim().to_string()])
+                .unwrap_or_default(),
+        };
+        let return_type = func.child_by_field_name("return_type").map(|t| {
+            self.ts_text(content, &t)
+                .trim()
+                .trim_start_matches(':')
+                .trim()
+                .to_string()
+        });
+        let mut metadata = HashMap::new();
+        if func.kind() == "arrow_function" {
+            metadata.insert("arrow".to_string(), "true".to_string());
+        }
+        let mut elem = ASTElement {
+            id: uuid::Uuid::new_v4(),
+            name,
+            element_type,
+            content: text,
+            start_line: start.row + 1,
+            end_line: end.row + 1,
+            start_column: start.column,
+            end_column: end.column,
+            complexity: 1,
+            visibility: self.ts_js_visibility(decl, content),
+            parameters,
+            return_type,
+            children: Vec::new(),
+            parent_id: None,
+            metadata,
+        };
+        elem.complexity = self.complexity(&elem.content, file_type);
+        Some(elem)
+    }
+
+    fn ts_js_class(
+        &self,
+        content: &str,
+        node: &tree_sitter::Node,
+        element_type: ASTElementType,
+    ) -> Option<ASTElement> {
+        let name = self
+            .ts_js_ident(node, content)
+            .unwrap_or_else(|| "<anon>".into());
+        let text = self.ts_text(content, node).to_string();
+        let start = node.start_position();
+        let end = node.end_position();
+        Some(ASTElement {
+            id: uuid::Uuid::new_v4(),
+            name,
+            element_type,
+            content: text,
+            start_line: start.row + 1,
+            end_line: end.row + 1,
+            start_column: start.column,
+            end_column: end.column,
+            complexity: 1,
+            visibility: "public".into(),
+            parameters: Vec::new(),
+            return_type: None,
+            children: Vec::new(),
+            parent_id: None,
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn ts_js_import(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
+        let text = self.ts_text(content, node).to_string();
+        let start = node.start_position();
+        let end = node.end_position();
+        let name = text.lines().next().unwrap_or("").trim().to_string();
+        let elem = ASTElement {
+            id: uuid::Uuid::new_v4(),
+            name,
+            element_type: ASTElementType::Import,
+            content: text,
+            start_line: start.row + 1,
+            end_line: end.row + 1,
+            start_column: start.column,
+            end_column: end.column,
+            complexity: 1,
+            visibility: "public".into(),
+            parameters: Vec::new(),
+            return_type: None,
+            children: Vec::new(),
+            parent_id: None,
+            metadata: HashMap::new(),
+        };
+        Ok(Some(elem))
     }
 
-    // JavaScript / TypeScript collection
-    fn ts_collect_js_nodes(
+    // Python collection
+    fn ts_collect_py_nodes(
         &self,
         content: &str,
-        file_path: &Path,
+        _file_path: &Path,
         node: tree_sitter::Node,
         out: &mut Vec<ASTElement>,
     ) -> Result<()> {
-        let mut stack: Vec<tree_sitter::Node> = vec![node];
-        while let Some(n) = stack.pop() {
-            match n.kind() {
-                "function_declaration" => {
-                    if let Some(el) = self.ts_js_fn_or_method(content, &n, false)? {
-                        out.push(el);
-                    }
-                }
-                "method_definition" => {
-                    if let Some(el) = self.ts_js_fn_or_method(content, &n, true)? {
-                        out.push(el);
-                    }
+        // DFS с индексом ближайшего объемлющего класса/функции: `def` прямо в классе — метод
+        let mut links: Vec<(usize, usize)> = Vec::new();
+        let mut stack: Vec<(tree_sitter::Node, Option<usize>)> = vec![(node, None)];
+        while let Some((n, parent)) = stack.pop() {
+            let built = match n.kind() {
+                "function_definition" => {
+                    let in_class =
+                        parent.is_some_and(|p| out[p].element_type == ASTElementType::Class);
+                    self.ts_py_function(content, &n, in_class)?
                 }
-                "class_declaration" => {
-                    if let Some(el) = self.ts_js_class(content, &n)? {
-                        out.push(el);
-                    }
+                "class_definition" => self.ts_py_class(content, &n)?,
+                "import_statement" | "import_from_statement" => self.ts_py_import(content, &n)?,
+                _ => None,
+            };
+            let mut now_parent = parent;
+            if let Some(el) = built {
+                let is_import = el.element_type == ASTElementType::Import;
+                out.push(el);
+                if let Some(p) = parent.filter(|_| !is_import) {
+                    links.push((out.len() - 1, p));
                 }
-                "import_declaration" => {
-                    if let Some(el) = self.ts_js_import(content, &n)? {
-                        out.push(el);
-                    }
+                if !is_import {
+                    now_parent = Some(out.len() - 1);
                 }
-                _ => {}
             }
             for i in 0..n.child_count() {
                 if let Some(ch) = n.child(i) {
-                    stack.push(ch);
+                    stack.push((ch, now_parent));
                 }
             }
         }
+        link_parents(out, &links);
         Ok(())
     }
 
-    fn ts_js_ident(&self, node: &tree_sitter::Node, content: &str) -> Option<String> {
-        // try identifier child else derive from text
+    fn ts_py_ident(&self, node: &tree_sitter::Node, content: &str) -> Option<String> {
         for i in 0..node.child_count() {
             if let Some(ch) = node.child(i) {
                 if ch.kind() == "identifier" {
@@ -972,32 +2419,69 @@ impl ParserAST {
         None
     }
 
-    fn ts_js_fn_or_method(
+    /// Определение вместе с декораторами (`@decorator` входит в диапазон элемента)
+    fn ts_py_decorated<'a>(&self, node: &tree_sitter::Node<'a>) -> tree_sitter::Node<'a> {
+        node.parent()
+            .filter(|p| p.kind() == "decorated_definition")
+            .unwrap_or(*node)
+    }
+
+    /// Имена декораторов без `@` и аргументов: `@app.route("/")` → `app.route`
+    fn ts_py_decorators(&self, node: &tree_sitter::Node, content: &str) -> Vec<String> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .filter(|ch| ch.kind() == "decorator")
+            .map(|ch| {
+                let text = self.ts_text(content, &ch).trim().trim_start_matches('@');
+                text.split('(').next().unwrap_or("").trim().to_string()
+            })
+            .collect()
+    }
+
+    fn ts_py_function(
         &self,
         content: &str,
         node: &tree_sitter::Node,
         is_method: bool,
     ) -> Result<Option<ASTElement>> {
         let name = self
-            .ts_js_ident(node, content)
+            .ts_py_ident(node, content)
             .unwrap_or_else(|| "<anon>".into());
-        let text = self.ts_text(content, node).to_string();
-        let start = node.start_position();
-        let end = node.end_position();
-        let mut params: Vec<String> = Vec::new();
-        if let Some(lp) = text.find('(') {
-            if let Some(rp) = text[lp..].find(')') {
-                let inside = &text[lp + 1..lp + rp];
-                params = inside
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-            }
+        let outer = self.ts_py_decorated(node);
+        let text = self.ts_text(content, &outer).to_string();
+        let start = outer.start_position();
+        let end = outer.end_position();
+        let decorators = self.ts_py_decorators(&outer, content);
+        let is_static = decorators.iter().any(|d| d == "staticmethod");
+        let params: Vec<String> = node
+            .child_by_field_name("parameters")
+            .map(|list| {
+                (0..list.named_child_count())
+                    .filter_map(|i| list.named_child(i))
+                    .filter(|p| p.kind() != "comment")
+                    .map(|p| self.ts_text(content, &p).trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        // `self`/`cls` не считаем параметрами метода
+        let params = if is_method && !is_static {
+            params.into_iter().skip(1).collect()
+        } else {
+            params
+        };
+        let return_type = node
+            .child_by_field_name("return_type")
+            .map(|t| self.ts_text(content, &t).trim().to_string());
+        let mut metadata = HashMap::new();
+        if self.ts_find_child(node, "async").is_some() {
+            metadata.insert("async".to_string(), "true".to_string());
+        }
+        if !decorators.is_empty() {
+            metadata.insert("decorators".to_string(), decorators.join(","));
         }
         let mut elem = ASTElement {
             id: uuid::Uuid::new_v4(),
-            name,
+            name: name.clone(),
             element_type: if is_method {
                 ASTElementType::Method
             } else {
@@ -1009,28 +2493,43 @@ impl ParserAST {
             start_column: start.column,
             end_column: end.column,
             complexity: 1,
-            visibility: "public".into(),
+            visibility: py_visibility(&name).into(),
             parameters: params,
-            return_type: None,
+            return_type,
             children: Vec::new(),
             parent_id: None,
-            metadata: HashMap::new(),
+            metadata,
         };
-        let patterns = &self.js_patterns;
-        elem.complexity = self.calculate_complexity(&elem.content, patterns);
+        elem.complexity = self.complexity(&elem.content, &FileType::Python);
         Ok(Some(elem))
     }
 
-    fn ts_js_class(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
+    fn ts_py_class(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
         let name = self
-            .ts_js_ident(node, content)
+            .ts_py_ident(node, content)
             .unwrap_or_else(|| "<anon>".into());
-        let text = self.ts_text(content, node).to_string();
-        let start = node.start_position();
-        let end = node.end_position();
+        let outer = self.ts_py_decorated(node);
+        let text = self.ts_text(content, &outer).to_string();
+        let start = outer.start_position();
+        let end = outer.end_position();
+        let mut metadata = HashMap::new();
+        let decorators = self.ts_py_decorators(&outer, content);
+        if !decorators.is_empty() {
+            metadata.insert("decorators".to_string(), decorators.join(","));
+        }
+        if let Some(bases) = node.child_by_field_name("superclasses") {
+            let bases: Vec<String> = (0..bases.named_child_count())
+                .filter_map(|i| bases.named_child(i))
+                .filter(|b| b.kind() != "keyword_argument")
+                .map(|b| self.ts_text(content, &b).trim().to_string())
+                .collect();
+            if !bases.is_empty() {
+                metadata.insert("bases".to_string(), bases.join(","));
+            }
+        }
         let elem = ASTElement {
             id: uuid::Uuid::new_v4(),
-            name,
+            name: name.clone(),
             element_type: ASTElementType::Class,
             content: text,
             start_line: start.row + 1,
@@ -1038,17 +2537,17 @@ impl ParserAST {
             start_column: start.column,
             end_column: end.column,
             complexity: 1,
-            visibility: "public".into(),
+            visibility: py_visibility(&name).into(),
             parameters: Vec::new(),
             return_type: None,
             children: Vec::new(),
             parent_id: None,
-            metadata: HashMap::new(),
+            metadata,
         };
         Ok(Some(elem))
     }
 
-    fn ts_js_import(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
+    fn ts_py_import(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
         let text = self.ts_text(content, node).to_string();
         let start = node.start_position();
         let end = node.end_position();
@@ -1073,29 +2572,66 @@ impl ParserAST {
         Ok(Some(elem))
     }
 
-    // Python collection
-    fn ts_collect_py_nodes(
+    // Go collection
+    fn ts_collect_go_nodes(
         &self,
         content: &str,
-        file_path: &Path,
         node: tree_sitter::Node,
         out: &mut Vec<ASTElement>,
     ) -> Result<()> {
+        // методы интерфейсов и ресиверов для структурной проверки реализаций
+        let mut interfaces: Vec<(String, Vec<String>)> = Vec::new();
+        let mut receivers: Vec<(usize, String)> = Vec::new();
         let mut stack: Vec<tree_sitter::Node> = vec![node];
         while let Some(n) = stack.pop() {
             match n.kind() {
-                "function_definition" => {
-                    if let Some(el) = self.ts_py_function(content, &n)? {
+                "function_declaration" => {
+                    if let Some(el) = self.ts_go_function(content, &n, ASTElementType::Function) {
                         out.push(el);
                     }
                 }
-                "class_definition" => {
-                    if let Some(el) = self.ts_py_class(content, &n)? {
+                "method_declaration" => {
+                    if let Some(mut el) = self.ts_go_function(content, &n, ASTElementType::Method) {
+                        if let Some(receiver) = self.ts_go_receiver(content, &n) {
+                            el.metadata.insert("receiver".to_string(), receiver.clone());
+                            receivers.push((out.len(), receiver));
+                        }
                         out.push(el);
                     }
                 }
-                "import_statement" | "import_from_statement" => {
-                    if let Some(el) = self.ts_py_import(content, &n)? {
+                "type_spec" => {
+                    let kind = n.child_by_field_name("type").map(|t| t.kind());
+                    let element_type = match kind {
+                        Some("struct_type") => ASTElementType::Struct,
+                        Some("interface_type") => ASTElementType::Interface,
+                        _ => continue,
+                    };
+                    if let Some(mut el) = self.ts_go_element(content, &n, element_type) {
+                        if el.element_type == ASTElementType::Interface {
+                            let methods: Vec<String> = n
+                                .child_by_field_name("type")
+                                .map(|body| {
+                                    (0..body.named_child_count())
+                                        .filter_map(|i| body.named_child(i))
+                                        .filter(|ch| ch.kind() == "method_spec")
+                                        .filter_map(|ch| ch.child_by_field_name("name"))
+                                        .map(|name| self.ts_text(content, &name).to_string())
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            el.metadata
+                                .insert(TRAIT_METHODS_KEY.to_string(), methods.len().to_string());
+                            interfaces.push((el.name.clone(), methods));
+                        }
+                        out.push(el);
+                    }
+                }
+                "import_spec" => {
+                    if let Some(mut el) = self.ts_go_element(content, &n, ASTElementType::Import) {
+                        if let Some(path) = n.child_by_field_name("path") {
+                            el.name = self.ts_text(content, &path).trim_matches('"').to_string();
+                        }
+                        el.visibility = "public".into();
                         out.push(el);
                     }
                 }
@@ -1107,76 +2643,157 @@ impl ParserAST {
                 }
             }
         }
+        link_go_methods(out, &interfaces, &receivers);
         Ok(())
     }
 
-    fn ts_py_ident(&self, node: &tree_sitter::Node, content: &str) -> Option<String> {
-        for i in 0..node.child_count() {
-            if let Some(ch) = node.child(i) {
-                if ch.kind() == "identifier" {
-                    return Some(self.ts_text(content, &ch).trim().to_string());
-                }
-            }
-        }
-        None
-    }
-
-    fn ts_py_function(
+    fn ts_go_element(
         &self,
         content: &str,
         node: &tree_sitter::Node,
-    ) -> Result<Option<ASTElement>> {
-        let name = self
-            .ts_py_ident(node, content)
-            .unwrap_or_else(|| "<anon>".into());
-        let text = self.ts_text(content, node).to_string();
+        element_type: ASTElementType,
+    ) -> Option<ASTElement> {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| self.ts_text(content, &n).trim().to_string())
+            .unwrap_or_default();
+        if name.is_empty() && element_type != ASTElementType::Import {
+            return None;
+        }
         let start = node.start_position();
         let end = node.end_position();
-        let mut params: Vec<String> = Vec::new();
-        if let Some(lp) = text.find('(') {
-            if let Some(rp) = text[lp..].find(')') {
-                let inside = &text[lp + 1..lp + rp];
-                params = inside
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-            }
-        }
-        let mut elem = ASTElement {
+        Some(ASTElement {
             id: uuid::Uuid::new_v4(),
+            visibility: go_visibility(&name).into(),
             name,
-            element_type: ASTElementType::Function,
-            content: text.clone(),
+            element_type,
+            content: self.ts_text(content, node).to_string(),
             start_line: start.row + 1,
             end_line: end.row + 1,
             start_column: start.column,
             end_column: end.column,
             complexity: 1,
-            visibility: "public".into(),
-            parameters: params,
+            parameters: Vec::new(),
             return_type: None,
             children: Vec::new(),
             parent_id: None,
             metadata: HashMap::new(),
-        };
-        let patterns = &self.python_patterns;
-        elem.complexity = self.calculate_complexity(&elem.content, patterns);
-        Ok(Some(elem))
+        })
     }
 
-    fn ts_py_class(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
+    fn ts_go_function(
+        &self,
+        content: &str,
+        node: &tree_sitter::Node,
+        element_type: ASTElementType,
+    ) -> Option<ASTElement> {
+        let mut elem = self.ts_go_element(content, node, element_type)?;
+        elem.parameters = node
+            .child_by_field_name("parameters")
+            .map(|list| {
+                (0..list.named_child_count())
+                    .filter_map(|i| list.named_child(i))
+                    .filter(|p| p.kind() != "comment")
+                    .map(|p| self.ts_text(content, &p).trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        elem.return_type = node
+            .child_by_field_name("result")
+            .map(|r| self.ts_text(content, &r).trim().to_string());
+        elem.complexity = self.complexity(&elem.content, &FileType::Go);
+        Some(elem)
+    }
+
+    /// Тип ресивера без указателя и параметров типа: `(s *Store[T])` → `Store`
+    fn ts_go_receiver(&self, content: &str, node: &tree_sitter::Node) -> Option<String> {
+        let list = node.child_by_field_name("receiver")?;
+        let param = list.named_child(0)?;
+        let ty = param.child_by_field_name("type")?;
         let name = self
-            .ts_py_ident(node, content)
-            .unwrap_or_else(|| "<anon>".into());
-        let text = self.ts_text(content, node).to_string();
+            .ts_text(content, &ty)
+            .trim_start_matches('*')
+            .split('[')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        (!name.is_empty()).then_some(name)
+    }
+
+    // Java collection
+    fn ts_collect_java_nodes(
+        &self,
+        content: &str,
+        node: tree_sitter::Node,
+        out: &mut Vec<ASTElement>,
+    ) -> Result<()> {
+        let mut links: Vec<(usize, usize)> = Vec::new();
+        let mut stack: Vec<(tree_sitter::Node, Option<usize>)> = vec![(node, None)];
+        while let Some((n, parent)) = stack.pop() {
+            let built = match n.kind() {
+                "class_declaration" => self.ts_java_type(content, &n, ASTElementType::Class),
+                "record_declaration" => self.ts_java_type(content, &n, ASTElementType::Struct),
+                "enum_declaration" => self.ts_java_type(content, &n, ASTElementType::Enum),
+                "interface_declaration" | "annotation_type_declaration" => {
+                    self.ts_java_type(content, &n, ASTElementType::Interface)
+                }
+                "method_declaration" | "constructor_declaration" => {
+                    self.ts_java_method(content, &n)
+                }
+                "import_declaration" => {
+                    let mut el =
+                        self.ts_element(content, &n, String::new(), ASTElementType::Import);
+                    el.name = el
+                        .content
+                        .trim()
+                        .trim_start_matches("import")
+                        .trim()
+                        .trim_start_matches("static ")
+                        .trim_end_matches(';')
+                        .trim()
+                        .to_string();
+                    el.visibility = "public".into();
+                    Some(el)
+                }
+                _ => None,
+            };
+            let mut now_parent = parent;
+            if let Some(el) = built {
+                let is_import = el.element_type == ASTElementType::Import;
+                out.push(el);
+                if !is_import {
+                    if let Some(p) = parent {
+                        links.push((out.len() - 1, p));
+                    }
+                    now_parent = Some(out.len() - 1);
+                }
+            }
+            for i in 0..n.child_count() {
+                if let Some(ch) = n.child(i) {
+                    stack.push((ch, now_parent));
+                }
+            }
+        }
+        link_parents(out, &links);
+        Ok(())
+    }
+
+    /// Элемент с именем и диапазоном узла; остальные поля заполняет вызывающий
+    fn ts_element(
+        &self,
+        content: &str,
+        node: &tree_sitter::Node,
+        name: String,
+        element_type: ASTElementType,
+    ) -> ASTElement {
         let start = node.start_position();
         let end = node.end_position();
-        let elem = ASTElement {
+        ASTElement {
             id: uuid::Uuid::new_v4(),
             name,
-            element_type: ASTElementType::Class,
-            content: text,
+            element_type,
+            content: self.ts_text(content, node).to_string(),
             start_line: start.row + 1,
             end_line: end.row + 1,
             start_column: start.column,
@@ -1188,166 +2805,482 @@ impl ParserAST {
             children: Vec::new(),
             parent_id: None,
             metadata: HashMap::new(),
+        }
+    }
+
+    fn ts_named_text(
+        &self,
+        content: &str,
+        node: &tree_sitter::Node,
+        field: &str,
+    ) -> Option<String> {
+        node.child_by_field_name(field)
+            .map(|n| self.ts_text(content, &n).trim().to_string())
+    }
+
+    /// Тексты именованных потомков узла-списка (параметры, базовые типы), без комментариев
+    fn ts_list_texts(&self, content: &str, list: Option<tree_sitter::Node>) -> Vec<String> {
+        list.map(|list| {
+            (0..list.named_child_count())
+                .filter_map(|i| list.named_child(i))
+                .filter(|p| p.kind() != "comment")
+                .map(|p| self.ts_text(content, &p).trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    /// Видимость Java по модификаторам; без модификатора — package-private
+    fn ts_java_visibility(&self, content: &str, node: &tree_sitter::Node) -> String {
+        let modifiers = self
+            .ts_find_child(node, "modifiers")
+            .map(|m| self.ts_text(content, &m))
+            .unwrap_or("");
+        modifiers
+            .split_whitespace()
+            .find(|m| matches!(*m, "public" | "protected" | "private"))
+            .unwrap_or("package")
+            .to_string()
+    }
+
+    fn ts_java_type(
+        &self,
+        content: &str,
+        node: &tree_sitter::Node,
+        element_type: ASTElementType,
+    ) -> Option<ASTElement> {
+        let name = self.ts_named_text(content, node, "name")?;
+        let mut el = self.ts_element(content, node, name, element_type);
+        el.visibility = self.ts_java_visibility(content, node);
+        // `implements A, B<T>` → A,B
+        let interfaces = node
+            .child_by_field_name("interfaces")
+            .and_then(|clause| clause.named_child(0));
+        let mut implemented: Vec<String> = self
+            .ts_list_texts(content, interfaces)
+            .iter()
+            .map(|t| java_type_name(t))
+            .collect();
+        implemented.sort();
+        implemented.dedup();
+        if !implemented.is_empty() {
+            el.metadata
+                .insert(IMPLEMENTS_KEY.to_string(), implemented.join(","));
+        }
+        if let Some(superclass) = node
+            .child_by_field_name("superclass")
+            .and_then(|s| s.named_child(0))
+        {
+            el.metadata.insert(
+                "bases".to_string(),
+                java_type_name(self.ts_text(content, &superclass)),
+            );
+        }
+        if el.element_type == ASTElementType::Interface {
+            let methods = node
+                .child_by_field_name("body")
+                .map(|body| {
+                    (0..body.named_child_count())
+                        .filter_map(|i| body.named_child(i))
+                        .filter(|ch| ch.kind() == "method_declaration")
+                        .count()
+                })
+                .unwrap_or(0);
+            el.metadata
+                .insert(TRAIT_METHODS_KEY.to_string(), methods.to_string());
+        }
+        Some(el)
+    }
+
+    fn ts_java_method(&self, content: &str, node: &tree_sitter::Node) -> Option<ASTElement> {
+        let name = self.ts_named_text(content, node, "name")?;
+        let mut el = self.ts_element(content, node, name, ASTElementType::Method);
+        // члены интерфейса публичны без модификатора
+        let in_interface = node
+            .parent()
+            .is_some_and(|body| body.kind() == "interface_body");
+        el.visibility = match self.ts_java_visibility(content, node) {
+            v if v == "package" && in_interface => "public".into(),
+            v => v,
         };
-        Ok(Some(elem))
+        el.parameters = self.ts_list_texts(content, node.child_by_field_name("parameters"));
+        el.return_type = self.ts_named_text(content, node, "type");
+        if node.kind() == "constructor_declaration" {
+            el.metadata
+                .insert("constructor".to_string(), "true".to_string());
+        }
+        el.complexity = self.complexity(&el.content, &FileType::Java);
+        Some(el)
     }
 
-    fn ts_py_import(&self, content: &str, node: &tree_sitter::Node) -> Result<Option<ASTElement>> {
-        let text = self.ts_text(content, node).to_string();
-        let start = node.start_position();
-        let end = node.end_position();
-        let name = text.lines().next().unwrap_or("").trim().to_string();
-        let elem = ASTElement {
-            id: uuid::Uuid::new_v4(),
-            name,
-            element_type: ASTElementType::Import,
-            content: text,
-            start_line: start.row + 1,
-            end_line: end.row + 1,
-            start_column: start.column,
-            end_column: end.column,
-            complexity: 1,
-            visibility: "public".into(),
-            parameters: Vec::new(),
-            return_type: None,
-            children: Vec::new(),
-            parent_id: None,
-            metadata: HashMap::new(),
+    // C/C++ collection
+    fn ts_collect_cpp_nodes(
+        &self,
+        content: &str,
+        node: tree_sitter::Node,
+        out: &mut Vec<ASTElement>,
+    ) -> Result<()> {
+        let mut links: Vec<(usize, usize)> = Vec::new();
+        // методы, определённые вне класса: `void Foo::bar() {}`
+        let mut out_of_line: Vec<(usize, String)> = Vec::new();
+        let mut stack: Vec<(tree_sitter::Node, Option<usize>)> = vec![(node, None)];
+        while let Some((n, parent)) = stack.pop() {
+            let built = match n.kind() {
+                "class_specifier" | "struct_specifier" | "enum_specifier"
+                    if n.child_by_field_name("body").is_some() =>
+                {
+                    self.ts_cpp_type(content, &n)
+                }
+                "function_definition" => self.ts_cpp_function(content, &n, &mut out_of_line, out),
+                // прототипы методов в теле класса (заголовки)
+                "field_declaration" | "declaration"
+                    if n.parent()
+                        .is_some_and(|p| p.kind() == "field_declaration_list")
+                        && cpp_function_declarator(&n).is_some() =>
+                {
+                    self.ts_cpp_function(content, &n, &mut out_of_line, out)
+                }
+                "namespace_definition" => self
+                    .ts_named_text(content, &n, "name")
+                    .map(|name| self.ts_element(content, &n, name, ASTElementType::Module)),
+                "preproc_include" => self.ts_named_text(content, &n, "path").map(|path| {
+                    let name = path
+                        .trim_matches(|c| matches!(c, '"' | '<' | '>'))
+                        .to_string();
+                    self.ts_element(content, &n, name, ASTElementType::Import)
+                }),
+                _ => None,
+            };
+            let mut now_parent = parent;
+            if let Some(el) = built {
+                let kind = el.element_type.clone();
+                out.push(el);
+                if !matches!(kind, ASTElementType::Import) {
+                    if let Some(p) = parent {
+                        links.push((out.len() - 1, p));
+                    }
+                    now_parent = Some(out.len() - 1);
+                }
+            }
+            for i in 0..n.child_count() {
+                if let Some(ch) = n.child(i) {
+                    stack.push((ch, now_parent));
+                }
+            }
+        }
+        for (i, owner) in &out_of_line {
+            if let Some(p) = out.iter().position(|e| {
+                matches!(
+                    e.element_type,
+                    ASTElementType::Class | ASTElementType::Struct
+                ) && e.name == *owner
+            }) {
+                links.push((*i, p));
+            }
+        }
+        link_parents(out, &links);
+        Ok(())
+    }
+
+    fn ts_cpp_type(&self, content: &str, node: &tree_sitter::Node) -> Option<ASTElement> {
+        let name = self.ts_named_text(content, node, "name")?;
+        let element_type = match node.kind() {
+            "class_specifier" => ASTElementType::Class,
+            "struct_specifier" => ASTElementType::Struct,
+            _ => ASTElementType::Enum,
         };
-        Ok(Some(elem))
+        let mut el = self.ts_element(content, node, name, element_type);
+        el.visibility = self.ts_cpp_visibility(content, node);
+        let bases: Vec<String> = self
+            .ts_find_child(node, "base_class_clause")
+            .map(|clause| {
+                (0..clause.named_child_count())
+                    .filter_map(|i| clause.named_child(i))
+                    .filter(|b| !matches!(b.kind(), "access_specifier" | "virtual"))
+                    .map(|b| rust_type_name(self.ts_text(content, &b)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !bases.is_empty() {
+            el.metadata.insert("bases".to_string(), bases.join(","));
+        }
+        Some(el)
+    }
+
+    fn ts_cpp_function(
+        &self,
+        content: &str,
+        node: &tree_sitter::Node,
+        out_of_line: &mut Vec<(usize, String)>,
+        out: &[ASTElement],
+    ) -> Option<ASTElement> {
+        let declarator = cpp_function_declarator(node)?;
+        let name_node = declarator.child_by_field_name("declarator")?;
+        let (name, owner) = if name_node.kind() == "qualified_identifier" {
+            let scope = self.ts_named_text(content, &name_node, "scope");
+            let name = self.ts_named_text(content, &name_node, "name")?;
+            (name, scope.map(|s| rust_type_name(&s)))
+        } else {
+            (self.ts_text(content, &name_node).trim().to_string(), None)
+        };
+        let in_class = node
+            .parent()
+            .is_some_and(|p| p.kind() == "field_declaration_list");
+        let element_type = if in_class || owner.is_some() {
+            ASTElementType::Method
+        } else {
+            ASTElementType::Function
+        };
+        let mut el = self.ts_element(content, node, name, element_type);
+        el.visibility = self.ts_cpp_visibility(content, node);
+        el.parameters = self.ts_list_texts(content, declarator.child_by_field_name("parameters"));
+        el.return_type = self.ts_named_text(content, node, "type");
+        if node.child_by_field_name("body").is_none() {
+            el.metadata
+                .insert("declaration".to_string(), "true".to_string());
+        }
+        if let Some(owner) = owner {
+            el.metadata.insert("owner".to_string(), owner.clone());
+            out_of_line.push((out.len(), owner));
+        }
+        el.complexity = self.complexity(&el.content, &FileType::Cpp);
+        Some(el)
+    }
+
+    /// Видимость C++: ближайший предшествующий `public:`/`private:` в теле класса,
+    /// иначе умолчание (`class` — private, `struct` — public); `static` вне класса — private
+    fn ts_cpp_visibility(&self, content: &str, node: &tree_sitter::Node) -> String {
+        let Some(body) = node
+            .parent()
+            .filter(|p| p.kind() == "field_declaration_list")
+        else {
+            let is_static = self
+                .ts_find_child(node, "storage_class_specifier")
+                .is_some_and(|s| self.ts_text(content, &s) == "static");
+            return if is_static { "private" } else { "public" }.into();
+        };
+        let mut sibling = node.prev_sibling();
+        while let Some(s) = sibling {
+            if s.kind() == "access_specifier" {
+                return self.ts_text(content, &s).trim().to_string();
+            }
+            sibling = s.prev_sibling();
+        }
+        let is_class = body.parent().is_some_and(|c| c.kind() == "class_specifier");
+        if is_class { "private" } else { "public" }.into()
     }
 }
 
 impl Default for ParserAST {
     fn default() -> Self {
         Self::new().unwrap_or_else(|_| ParserAST {
-            rust_patterns: LanguagePatterns {
-                functions: Regex::new(r"fn\s+(\w+)").unwrap(),
-                classes: Regex::new(r"struct\s+(\w+)").unwrap(),
-                structs: Regex::new(r"struct\s+(\w+)").unwrap(),
-                enums: Regex::new(r"enum\s+(\w+)").unwrap(),
-                interfaces: Regex::new(r"trait\s+(\w+)").unwrap(),
-                modules: Regex::new(r"mod\s+(\w+)").unwrap(),
-                imports: Regex::new(r"use\s+([^;]+)").unwrap(),
-                exports: Regex::new(r"pub\s+\w+\s+(\w+)").unwrap(),
-                variables: Regex::new(r"let\s+(\w+)").unwrap(),
-                constants: Regex::new(r"const\s+(\w+)").unwrap(),
-                comments: Regex::new(r"//").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bwhile\b").unwrap(),
-                ],
-            },
-            js_patterns: LanguagePatterns {
-                functions: Regex::new(r"function\s+(\w+)").unwrap(),
-                classes: Regex::new(r"class\s+(\w+)").unwrap(),
-                structs: Regex::new(r"interface\s+(\w+)").unwrap(),
-                enums: Regex::new(r"enum\s+(\w+)").unwrap(),
-                interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
-                modules: Regex::new(r"namespace\s+(\w+)").unwrap(),
-                imports: Regex::new(r"import.*from").unwrap(),
-                exports: Regex::new(r"export.*(\w+)").unwrap(),
-                variables: Regex::new(r"(?:const|let|var)\s+(\w+)").unwrap(),
-                constants: Regex::new(r"const\s+(\w+)").unwrap(),
-                comments: Regex::new(r"//").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bwhile\b").unwrap(),
-                ],
-            },
-            ts_patterns: LanguagePatterns {
-                functions: Regex::new(r"function\s+(\w+)").unwrap(),
-                classes: Regex::new(r"class\s+(\w+)").unwrap(),
-                structs: Regex::new(r"interface\s+(\w+)").unwrap(),
-                enums: Regex::new(r"enum\s+(\w+)").unwrap(),
-                interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
-                modules: Regex::new(r"namespace\s+(\w+)").unwrap(),
-                imports: Regex::new(r"import.*from").unwrap(),
-                exports: Regex::new(r"export.*(\w+)").unwrap(),
-                variables: Regex::new(r"(?:const|let|var)\s+(\w+)").unwrap(),
-                constants: Regex::new(r"const\s+(\w+)").unwrap(),
-                comments: Regex::new(r"//").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bwhile\b").unwrap(),
-                ],
-            },
-            python_patterns: LanguagePatterns {
-                functions: Regex::new(r"def\s+(\w+)").unwrap(),
-                classes: Regex::new(r"class\s+(\w+)").unwrap(),
-                structs: Regex::new(r"class\s+(\w+)").unwrap(),
-                enums: Regex::new(r"class\s+(\w+)").unwrap(),
-                interfaces: Regex::new(r"class\s+(\w+)").unwrap(),
-                modules: Regex::new(r"import\s+(\w+)").unwrap(),
-                imports: Regex::new(r"import").unwrap(),
-                exports: Regex::new(r"__all__").unwrap(),
-                variables: Regex::new(r"(\w+)\s*=").unwrap(),
-                constants: Regex::new(r"([A-Z_][A-Z0-9_]*)\s*=").unwrap(),
-                comments: Regex::new(r"#").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bwhile\b").unwrap(),
-                ],
-            },
-            java_patterns: LanguagePatterns {
-                functions: Regex::new(r"(?:public|private|protected)?\s*\w+\s+(\w+)\s*\(").unwrap(),
-                classes: Regex::new(r"class\s+(\w+)").unwrap(),
-                structs: Regex::new(r"record\s+(\w+)").unwrap(),
-                enums: Regex::new(r"enum\s+(\w+)").unwrap(),
-                interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
-                modules: Regex::new(r"package\s+(\w+)").unwrap(),
-                imports: Regex::new(r"import\s+(\w+)").unwrap(),
-                exports: Regex::new(r"public.*(\w+)").unwrap(),
-                variables: Regex::new(r"\w+\s+(\w+)").unwrap(),
-                constants: Regex::new(r"final\s+\w+\s+([A-Z_][A-Z0-9_]*)").unwrap(),
-                comments: Regex::new(r"//").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bwhile\b").unwrap(),
-                ],
-            },
-            cpp_patterns: LanguagePatterns {
-                functions: Regex::new(r"\w+\s+(\w+)\s*\(").unwrap(),
-                classes: Regex::new(r"class\s+(\w+)").unwrap(),
-                structs: Regex::new(r"struct\s+(\w+)").unwrap(),
-                enums: Regex::new(r"enum\s+(\w+)").unwrap(),
-                interfaces: Regex::new(r"class\s+(\w+)").unwrap(),
-                modules: Regex::new(r"namespace\s+(\w+)").unwrap(),
-                imports: Regex::new(r#"#include\s+[<"]([^>"]+)[>"]"#).unwrap(),
-                exports: Regex::new(r"extern.*(\w+)").unwrap(),
-                variables: Regex::new(r"\w+\s+(\w+)").unwrap(),
-                constants: Regex::new(r"const\s+\w+\s+([A-Z_][A-Z0-9_]*)").unwrap(),
-                comments: Regex::new(r"//").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bwhile\b").unwrap(),
-                ],
-            },
-            go_patterns: LanguagePatterns {
-                functions: Regex::new(r"func\s+(\w+)").unwrap(),
-                classes: Regex::new(r"type\s+(\w+)\s+struct").unwrap(),
-                structs: Regex::new(r"type\s+(\w+)\s+struct").unwrap(),
-                enums: Regex::new(r"type\s+(\w+)\s+\w+").unwrap(),
-                interfaces: Regex::new(r"type\s+(\w+)\s+interface").unwrap(),
-                modules: Regex::new(r"package\s+(\w+)").unwrap(),
-                imports: Regex::new(r#"import.*"([^"]+)""#).unwrap(),
-                exports: Regex::new(r"(?:func|type|var|const)\s+([A-Z]\w*)").unwrap(),
-                variables: Regex::new(r"var\s+(\w+)").unwrap(),
-                constants: Regex::new(r"const\s+(\w+)").unwrap(),
-                comments: Regex::new(r"//").unwrap(),
-                complexity_indicators: vec![
-                    Regex::new(r"\bif\b").unwrap(),
-                    Regex::new(r"\bfor\b").unwrap(),
-                    Regex::new(r"\bswitch\b").unwrap(),
-                ],
-            },
-            pattern_cache: HashMap::new(),
+            builtin: vec![
+                RegexLanguageParser::new(
+                    "rust",
+                    FileType::Rust,
+                    LanguagePatterns {
+                        functions: Regex::new(r"fn\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"struct\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"struct\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"enum\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"trait\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"mod\s+(\w+)").unwrap(),
+                        imports: Regex::new(r"use\s+([^;]+)").unwrap(),
+                        exports: Regex::new(r"pub\s+\w+\s+(\w+)").unwrap(),
+                        variables: Regex::new(r"let\s+(\w+)").unwrap(),
+                        constants: Regex::new(r"const\s+(\w+)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "javascript",
+                    FileType::JavaScript,
+                    LanguagePatterns {
+                        functions: Regex::new(r"function\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"enum\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"namespace\s+(\w+)").unwrap(),
+                        imports: Regex::new(r"import.*from").unwrap(),
+                        exports: Regex::new(r"export.*(\w+)").unwrap(),
+                        variables: Regex::new(r"(?:const|let|var)\s+(\w+)").unwrap(),
+                        constants: Regex::new(r"const\s+(\w+)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "typescript",
+                    FileType::TypeScript,
+                    LanguagePatterns {
+                        functions: Regex::new(r"function\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"enum\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"namespace\s+(\w+)").unwrap(),
+                        imports: Regex::new(r"import.*from").unwrap(),
+                        exports: Regex::new(r"export.*(\w+)").unwrap(),
+                        variables: Regex::new(r"(?:const|let|var)\s+(\w+)").unwrap(),
+                        constants: Regex::new(r"const\s+(\w+)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "python",
+                    FileType::Python,
+                    LanguagePatterns {
+                        functions: Regex::new(r"def\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"class\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"class\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"class\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"import\s+(\w+)").unwrap(),
+                        imports: Regex::new(r"import").unwrap(),
+                        exports: Regex::new(r"__all__").unwrap(),
+                        variables: Regex::new(r"(\w+)\s*=").unwrap(),
+                        constants: Regex::new(r"([A-Z_][A-Z0-9_]*)\s*=").unwrap(),
+                        comments: Regex::new(r"#").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "java",
+                    FileType::Java,
+                    LanguagePatterns {
+                        functions: Regex::new(r"(?:public|private|protected)?\s*\w+\s+(\w+)\s*\(")
+                            .unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"record\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"enum\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"package\s+(\w+)").unwrap(),
+                        imports: Regex::new(r"import\s+(\w+)").unwrap(),
+                        exports: Regex::new(r"public.*(\w+)").unwrap(),
+                        variables: Regex::new(r"\w+\s+(\w+)").unwrap(),
+                        constants: Regex::new(r"final\s+\w+\s+([A-Z_][A-Z0-9_]*)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "cpp",
+                    FileType::Cpp,
+                    LanguagePatterns {
+                        functions: Regex::new(r"\w+\s+(\w+)\s*\(").unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"struct\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"enum\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"class\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"namespace\s+(\w+)").unwrap(),
+                        imports: Regex::new(r#"#include\s+[<"]([^>"]+)[>"]"#).unwrap(),
+                        exports: Regex::new(r"extern.*(\w+)").unwrap(),
+                        variables: Regex::new(r"\w+\s+(\w+)").unwrap(),
+                        constants: Regex::new(r"const\s+\w+\s+([A-Z_][A-Z0-9_]*)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "go",
+                    FileType::Go,
+                    LanguagePatterns {
+                        functions: Regex::new(r"func\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"type\s+(\w+)\s+struct").unwrap(),
+                        structs: Regex::new(r"type\s+(\w+)\s+struct").unwrap(),
+                        enums: Regex::new(r"type\s+(\w+)\s+\w+").unwrap(),
+                        interfaces: Regex::new(r"type\s+(\w+)\s+interface").unwrap(),
+                        modules: Regex::new(r"package\s+(\w+)").unwrap(),
+                        imports: Regex::new(r#"import.*"([^"]+)""#).unwrap(),
+                        exports: Regex::new(r"(?:func|type|var|const)\s+([A-Z]\w*)").unwrap(),
+                        variables: Regex::new(r"var\s+(\w+)").unwrap(),
+                        constants: Regex::new(r"const\s+(\w+)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bswitch\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "php",
+                    FileType::Php,
+                    LanguagePatterns {
+                        functions: Regex::new(r"function\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"trait\s+(\w+)").unwrap(),
+                        enums: Regex::new(r"enum\s+(\w+)").unwrap(),
+                        interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"namespace\s+([\w\\]+)").unwrap(),
+                        imports: Regex::new(r"use\s+([\w\\]+)").unwrap(),
+                        exports: Regex::new(r"class\s+(\w+)").unwrap(),
+                        variables: Regex::new(r"\$(\w+)\s*=").unwrap(),
+                        constants: Regex::new(r"const\s+(\w+)").unwrap(),
+                        comments: Regex::new(r"//").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bfor\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+                RegexLanguageParser::new(
+                    "ruby",
+                    FileType::Ruby,
+                    LanguagePatterns {
+                        functions: Regex::new(r"def\s+(\w+)").unwrap(),
+                        classes: Regex::new(r"class\s+(\w+)").unwrap(),
+                        structs: Regex::new(r"(\w+)\s*=\s*Struct\.new").unwrap(),
+                        enums: Regex::new(r"enum\s+:?(\w+)").unwrap(),
+                        interfaces: Regex::new(r"interface\s+(\w+)").unwrap(),
+                        modules: Regex::new(r"module\s+(\w+)").unwrap(),
+                        imports: Regex::new(r#"require\s+['"]([^'"]+)['"]"#).unwrap(),
+                        exports: Regex::new(r"class\s+(\w+)").unwrap(),
+                        variables: Regex::new(r"\$(\w+)\s*=").unwrap(),
+                        constants: Regex::new(r"([A-Z][A-Z0-9_]*)\s*=").unwrap(),
+                        comments: Regex::new(r"#").unwrap(),
+                        complexity_indicators: vec![
+                            Regex::new(r"\bif\b").unwrap(),
+                            Regex::new(r"\bunless\b").unwrap(),
+                            Regex::new(r"\bwhile\b").unwrap(),
+                        ],
+                    },
+                ),
+            ],
+            plugins: Vec::new(),
+            pattern_cache: ElementCache::new(CacheLimits::from_env()),
+            incremental: false,
+            incremental_stats: IncrementalStats::default(),
+            #[cfg(feature = "tree_sitter")]
+            trees: HashMap::new(),
         })
     }
 }
@@ -0,0 +1,222 @@
+// Кеш разобранных файлов для ParserAST: одна запись на файл с отпечатком
+// содержимого, вытеснение давно не использованных записей по лимитам числа
+// записей и примерного объёма в байтах. Лимиты по умолчанию можно задать
+// переменными окружения, не меняя код вызова
+
+use crate::parser_ast::ASTElement;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 4_096;
+pub const DEFAULT_CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
+/// Переопределения лимитов по умолчанию (`0` отключает кеш)
+pub const CACHE_MAX_ENTRIES_ENV: &str = "ARCHLENS_PARSER_CACHE_ENTRIES";
+pub const CACHE_MAX_BYTES_ENV: &str = "ARCHLENS_PARSER_CACHE_BYTES";
+
+/// Лимиты кеша; `0` в любом из них отключает кеширование
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CacheLimits {
+    pub max_entries: usize,
+    pub max_bytes: usize,
+}
+
+impl Default for CacheLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            max_bytes: DEFAULT_CACHE_MAX_BYTES,
+        }
+    }
+}
+
+impl CacheLimits {
+    /// Значения по умолчанию с учётом переменных окружения
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            max_entries: read(CACHE_MAX_ENTRIES_ENV, DEFAULT_CACHE_MAX_ENTRIES),
+            max_bytes: read(CACHE_MAX_BYTES_ENV, DEFAULT_CACHE_MAX_BYTES),
+        }
+    }
+}
+
+/// Состояние кеша для диагностики
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub limits: CacheLimits,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedFile {
+    fingerprint: String,
+    elements: Vec<ASTElement>,
+    bytes: usize,
+    used: u64,
+}
+
+/// LRU-кеш элементов по пути файла
+#[derive(Debug)]
+pub struct ElementCache {
+    entries: HashMap<PathBuf, CachedFile>,
+    /// Порядок использования: такт -> путь, первым вытесняется наименьший такт
+    order: BTreeMap<u64, PathBuf>,
+    tick: u64,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+    limits: CacheLimits,
+}
+
+impl ElementCache {
+    pub fn new(limits: CacheLimits) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            bytes: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+            limits,
+        }
+    }
+
+    /// Элементы файла, если отпечаток совпадает с сохранённым
+    pub fn get(&mut self, path: &Path, fingerprint: &str) -> Option<Vec<ASTElement>> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.entries.get_mut(path) {
+            Some(entry) if entry.fingerprint == fingerprint => {
+                self.order.remove(&entry.used);
+                self.order.insert(tick, path.to_path_buf());
+                entry.used = tick;
+                self.hits += 1;
+                Some(entry.elements.clone())
+            }
+            _ => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Сохраняет элементы файла (прежняя версия заменяется) и возвращает пути
+    /// вытесненных файлов
+    pub fn insert(
+        &mut self,
+        path: &Path,
+        fingerprint: String,
+        elements: Vec<ASTElement>,
+    ) -> Vec<PathBuf> {
+        self.remove(path);
+        let bytes = estimate_bytes(path, &elements);
+        if self.limits.max_entries == 0 || bytes > self.limits.max_bytes {
+            return Vec::new();
+        }
+        self.tick += 1;
+        self.order.insert(self.tick, path.to_path_buf());
+        self.entries.insert(
+            path.to_path_buf(),
+            CachedFile {
+                fingerprint,
+                elements,
+                bytes,
+                used: self.tick,
+            },
+        );
+        self.bytes += bytes;
+        self.evict_over_limits()
+    }
+
+    pub fn remove(&mut self, path: &Path) -> bool {
+        match self.entries.remove(path) {
+            Some(entry) => {
+                self.order.remove(&entry.used);
+                self.bytes -= entry.bytes;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.bytes = 0;
+    }
+
+    /// Новые лимиты применяются сразу; возвращает вытесненные пути
+    pub fn set_limits(&mut self, limits: CacheLimits) -> Vec<PathBuf> {
+        self.limits = limits;
+        self.evict_over_limits()
+    }
+
+    fn evict_over_limits(&mut self) -> Vec<PathBuf> {
+        let mut evicted = Vec::new();
+        while self.entries.len() > self.limits.max_entries || self.bytes > self.limits.max_bytes {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.bytes -= entry.bytes;
+                self.evictions += 1;
+                evicted.push(oldest);
+            }
+        }
+        evicted
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            bytes: self.bytes,
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            limits: self.limits,
+        }
+    }
+}
+
+/// Примерный объём записи: структуры элементов и их строки в куче
+fn estimate_bytes(path: &Path, elements: &[ASTElement]) -> usize {
+    let strings: usize = elements
+        .iter()
+        .map(|e| {
+            e.name.len()
+                + e.content.len()
+                + e.visibility.len()
+                + e.parameters.iter().map(String::len).sum::<usize>()
+                + e.return_type.as_ref().map_or(0, String::len)
+                + e.children.len() * std::mem::size_of::<uuid::Uuid>()
+                + e.metadata
+                    .iter()
+                    .map(|(k, v)| k.len() + v.len())
+                    .sum::<usize>()
+        })
+        .sum();
+    path.as_os_str().len() + std::mem::size_of_val(elements) + strings
+}
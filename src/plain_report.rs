@@ -0,0 +1,276 @@
+// Доступный текстовый отчёт: markdown-отчёты переводятся в простой текст без эмодзи,
+// ANSI-цветов и markdown-разметки; таблицы выравниваются по ширине колонок.
+// Предназначен для экранных дикторов и терминалов, где markdown отображается плохо
+
+/// Ширина горизонтальной черты (`---` в markdown)
+const RULE_WIDTH: usize = 60;
+
+/// Символы, у которых есть понятная ASCII-замена
+const REPLACEMENTS: &[(char, &str)] = &[
+    ('→', "->"),
+    ('←', "<-"),
+    ('↔', "<->"),
+    ('⇒', "=>"),
+    ('•', "-"),
+    ('…', "..."),
+    ('≥', ">="),
+    ('≤', "<="),
+    ('×', "x"),
+];
+
+#[derive(Debug, Default)]
+pub struct PlainTextRenderer;
+
+impl PlainTextRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Переводит markdown-отчёт в простой текст
+    pub fn render(&self, markdown: &str) -> String {
+        let mut out: Vec<String> = Vec::new();
+        let mut table: Vec<Vec<String>> = Vec::new();
+        let mut in_code = false;
+
+        for raw in markdown.lines() {
+            let line = strip_symbols(&strip_ansi(raw));
+            let trimmed = line.trim();
+
+            if trimmed.starts_with("```") {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                out.push(format!("    {}", line.trim_end()));
+                continue;
+            }
+
+            if trimmed.starts_with('|') {
+                if !is_separator_row(trimmed) {
+                    table.push(split_row(trimmed));
+                }
+                continue;
+            }
+            if !table.is_empty() {
+                out.extend(render_table(&std::mem::take(&mut table)));
+            }
+
+            out.push(render_line(&line));
+        }
+        if !table.is_empty() {
+            out.extend(render_table(&table));
+        }
+
+        // без пустых строк подряд и в конце
+        let mut result = String::new();
+        let mut blank = true;
+        for line in out.into_iter().flat_map(|l| {
+            l.split('\n')
+                .map(|s| s.trim_end().to_string())
+                .collect::<Vec<_>>()
+        }) {
+            if line.is_empty() {
+                if blank {
+                    continue;
+                }
+                blank = true;
+            } else {
+                blank = false;
+            }
+            result.push_str(&line);
+            result.push('\n');
+        }
+        while result.ends_with("\n\n") {
+            result.pop();
+        }
+        result
+    }
+}
+
+fn render_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        let title = strip_inline(trimmed[level..].trim());
+        let width = title.chars().count();
+        return match level {
+            1 => format!("\n{}\n{}\n", title, "=".repeat(width)),
+            2 => format!("\n{}\n{}\n", title, "-".repeat(width)),
+            _ => format!("\n{}\n", title),
+        };
+    }
+
+    if trimmed.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|r| trimmed.chars().all(|c| c == *r))
+    {
+        return "-".repeat(RULE_WIDTH);
+    }
+
+    if let Some(rest) = trimmed
+        .strip_prefix("* ")
+        .or_else(|| trimmed.strip_prefix("+ "))
+    {
+        return format!("{}- {}", indent, strip_inline(rest));
+    }
+    if let Some(rest) = trimmed.strip_prefix("> ") {
+        return format!("{}  {}", indent, strip_inline(rest));
+    }
+    format!("{}{}", indent, strip_inline(trimmed))
+}
+
+/// Убирает inline-разметку: `**жирный**`, `__жирный__`, `` `код` ``, ссылки `[текст](url)`
+fn strip_inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '`' => {}
+            '*' if chars.get(i + 1) == Some(&'*') => i += 1,
+            '_' if chars.get(i + 1) == Some(&'_') => i += 1,
+            '[' => {
+                // [текст](url) -> текст (url)
+                let close = chars[i..].iter().position(|&c| c == ']').map(|p| p + i);
+                if let Some(close) = close.filter(|&p| chars.get(p + 1) == Some(&'(')) {
+                    if let Some(end) = chars[close..]
+                        .iter()
+                        .position(|&c| c == ')')
+                        .map(|p| p + close)
+                    {
+                        let label: String = chars[i + 1..close].iter().collect();
+                        let url: String = chars[close + 2..end].iter().collect();
+                        let label = strip_inline(&label);
+                        if url.is_empty() || url.starts_with('#') || label == url {
+                            out.push_str(&label);
+                        } else {
+                            out.push_str(&format!("{} ({})", label, url));
+                        }
+                        i = end + 1;
+                        continue;
+                    }
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Эмодзи и служебные символы удаляются; стрелки и пр. заменяются на ASCII
+fn strip_symbols(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if let Some((_, replacement)) = REPLACEMENTS.iter().find(|(from, _)| *from == c) {
+            out.push_str(replacement);
+        } else if !is_emoji(c) {
+            out.push(c);
+        }
+    }
+    // "## 🔥 Заголовок" -> "##  Заголовок": схлопываем образовавшиеся двойные пробелы
+    let mut collapsed = String::with_capacity(out.len());
+    let indent_len = out.len() - out.trim_start().len();
+    collapsed.push_str(&out[..indent_len]);
+    let mut prev_space = false;
+    for c in out[indent_len..].chars() {
+        if c == ' ' {
+            if prev_space {
+                continue;
+            }
+            prev_space = true;
+        } else {
+            prev_space = false;
+        }
+        collapsed.push(c);
+    }
+    collapsed
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF      // эмодзи, пиктограммы, флаги
+        | 0x2600..=0x27BF      // разные символы и dingbats (✅ ❌ ⚠)
+        | 0x2B00..=0x2BFF      // стрелки и звёзды (⭐ ⬆)
+        | 0x2300..=0x23FF      // технические символы (⏱ ⌛)
+        | 0x25A0..=0x25FF      // геометрические фигуры (▶ ●)
+        | 0xFE00..=0xFE0F      // селекторы вариантов
+        | 0x200D               // zero-width joiner
+        | 0x20E3               // keycap
+    )
+}
+
+/// Удаляет ANSI escape-последовательности (`\x1b[...m`)
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn is_separator_row(row: &str) -> bool {
+    row.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+fn split_row(row: &str) -> Vec<String> {
+    let inner = row.trim().trim_start_matches('|').trim_end_matches('|');
+    inner
+        .split('|')
+        .map(|cell| strip_inline(cell.trim()))
+        .collect()
+}
+
+/// Таблица фиксированной ширины: колонки выровнены пробелами, под заголовком — черта
+fn render_table(rows: &[Vec<String>]) -> Vec<String> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut widths = vec![0usize; columns];
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+                let pad = widths[i] - cell.chars().count();
+                format!("{}{}", cell, " ".repeat(pad))
+            })
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 3);
+    lines.push(String::new());
+    if let Some((header, body)) = rows.split_first() {
+        lines.push(format_row(header));
+        lines.push(
+            widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        lines.extend(body.iter().map(format_row));
+    }
+    lines.push(String::new());
+    lines
+}
@@ -0,0 +1,355 @@
+// Настройки проекта в `.archlens.toml`: какие файлы сканировать, какие каталоги
+// к какому слою относятся и пороги валидаторов. Файл предлагает и записывает
+// `archlens init` по осмотру проекта; без файла действуют встроенные значения
+
+use crate::file_scanner::build_artifacts::build_output;
+use crate::file_scanner::FileScanner;
+use crate::types::{AnalysisError, Capsule, Result};
+use crate::validation::ValidationThresholds;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Имя файла настроек в корне проекта
+pub const CONFIG_FILE: &str = ".archlens.toml";
+
+/// Шаблоны исходников по умолчанию
+pub const DEFAULT_INCLUDE: &[&str] = &[
+    "**/*.rs",
+    "**/*.ts",
+    "**/*.js",
+    "**/*.py",
+    "**/*.java",
+    "**/*.go",
+    "**/*.cpp",
+    "**/*.c",
+    "**/*.php",
+    "**/*.rb",
+];
+/// Исключения по умолчанию: зависимости и VCS; вывод сборки дополнительно
+/// определяется по манифестам экосистем
+pub const DEFAULT_EXCLUDE: &[&str] = &[
+    "**/target/**",
+    "**/node_modules/**",
+    "**/.git/**",
+    "**/dist/**",
+    "**/build/**",
+];
+/// Глубина осмотра каталогов при составлении предложения
+const MAX_INSPECT_DEPTH: usize = 10;
+/// Каталоги глубже не получают собственного слоя в предложении
+const MAX_LAYER_DEPTH: usize = 3;
+
+/// Расширения, которые читает сканер
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "py", "java", "cpp", "cc", "cxx", "c", "h", "hpp", "hxx",
+    "php", "rb",
+];
+
+/// Содержимое `.archlens.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Каталог от корня → слой; побеждает самый длинный подходящий каталог
+    pub layers: BTreeMap<String, String>,
+    #[serde(serialize_with = "serialize_thresholds")]
+    pub thresholds: ValidationThresholds,
+}
+
+/// Пороги `f32` пишутся короткими десятичными числами: 0.7, а не 0.699999988
+fn serialize_thresholds<S: serde::Serializer>(
+    thresholds: &ValidationThresholds,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Written {
+        max_complexity: u32,
+        coupling: f64,
+        cohesion: f64,
+    }
+    let short = |value: f32| value.to_string().parse().unwrap_or(value as f64);
+    Written {
+        max_complexity: thresholds.max_complexity,
+        coupling: short(thresholds.coupling),
+        cohesion: short(thresholds.cohesion),
+    }
+    .serialize(serializer)
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        Self {
+            include: DEFAULT_INCLUDE.iter().map(|p| p.to_string()).collect(),
+            exclude: DEFAULT_EXCLUDE.iter().map(|p| p.to_string()).collect(),
+            layers: BTreeMap::new(),
+            thresholds: ValidationThresholds::default(),
+        }
+    }
+}
+
+impl ProjectConfig {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        toml::from_str(text)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid {CONFIG_FILE}: {e}")))
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        let body =
+            toml::to_string_pretty(self).map_err(|e| AnalysisError::GenericError(e.to_string()))?;
+        Ok(format!(
+            "# Настройки ArchLens; создано `archlens init`\n\
+             # include/exclude — glob-шаблоны файлов, layers — каталог → слой\n\
+             # (UI, API, Business, Data, Core), thresholds — пороги валидаторов\n\n{body}"
+        ))
+    }
+
+    /// `.archlens.toml`; `Ok(None)`, если файла нет
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_toml(&content).map(Some)
+    }
+
+    /// Настройки проекта или встроенные значения
+    pub fn for_project(project_root: &Path) -> Result<Self> {
+        Ok(Self::load(project_root)?.unwrap_or_default())
+    }
+
+    /// Сканер исходников по шаблонам настроек
+    pub fn scanner(&self, max_depth: Option<usize>) -> Result<FileScanner> {
+        FileScanner::new(self.include.clone(), self.exclude.clone(), max_depth)
+    }
+
+    /// Слой файла по самому длинному каталогу из `layers`
+    pub fn layer_for(&self, project_root: &Path, file: &Path) -> Option<&str> {
+        let relative = file.strip_prefix(project_root).unwrap_or(file);
+        self.layers
+            .iter()
+            .filter(|(dir, _)| relative.starts_with(dir.trim_end_matches('/')))
+            .max_by_key(|(dir, _)| dir.len())
+            .map(|(_, layer)| layer.as_str())
+    }
+
+    /// Назначает капсулам слои каталогов из `layers`; остальные не меняются
+    pub fn apply_layers(&self, project_root: &Path, capsules: &mut [Capsule]) {
+        if self.layers.is_empty() {
+            return;
+        }
+        for capsule in capsules {
+            if let Some(layer) = self.layer_for(project_root, &capsule.file_path) {
+                capsule.layer = Some(layer.to_string());
+            }
+        }
+    }
+
+    /// Предложение по осмотру проекта: шаблоны найденных языков, исключения
+    /// с обнаруженными каталогами сборки, слои по именам каталогов с исходниками
+    /// и пороги по умолчанию
+    pub fn propose(project_root: &Path) -> Self {
+        let mut inspection = Inspection::default();
+        inspection.walk(project_root, project_root, 0);
+
+        let mut config = Self::default();
+        if !inspection.extensions.is_empty() {
+            config.include = inspection
+                .extensions
+                .iter()
+                .map(|ext| format!("**/*.{ext}"))
+                .collect();
+        }
+        for dir in &inspection.build_dirs {
+            let pattern = format!("**/{dir}/**");
+            if !config.exclude.contains(&pattern) {
+                config.exclude.push(pattern);
+            }
+        }
+        config.layers = inspection
+            .source_dirs
+            .iter()
+            .filter(|dir| dir.matches('/').count() < MAX_LAYER_DEPTH)
+            .filter_map(|dir| {
+                let layer = layer_of_dir(&dir.rsplit('/').next()?.to_lowercase())?;
+                Some((dir.clone(), layer.to_string()))
+            })
+            .collect();
+        config
+    }
+}
+
+/// Слой иерархии `LayerValidator` по имени каталога
+fn layer_of_dir(name: &str) -> Option<&'static str> {
+    match name {
+        "ui" | "components" | "views" | "pages" | "frontend" | "web" => Some("UI"),
+        "api" | "controllers" | "routes" | "handlers" | "endpoints" | "cli" | "commands" => {
+            Some("API")
+        }
+        "services" | "business" | "domain" | "models" | "entities" | "usecases" | "application" => {
+            Some("Business")
+        }
+        "data" | "database" | "db" | "repository" | "repositories" | "persistence" | "storage"
+        | "infrastructure" | "infra" => Some("Data"),
+        "core" | "kernel" | "shared" | "common" => Some("Core"),
+        "utils" | "helpers" => Some("Utils"),
+        "tests" | "test" | "spec" => Some("Tests"),
+        _ => None,
+    }
+}
+
+/// Что нашёл осмотр проекта; пути от корня через `/`
+#[derive(Default)]
+struct Inspection {
+    extensions: BTreeSet<String>,
+    build_dirs: BTreeSet<String>,
+    /// Каталоги, в которых (или глубже) есть исходники
+    source_dirs: BTreeSet<String>,
+}
+
+impl Inspection {
+    /// Возвращает, есть ли исходники в `dir` или глубже
+    fn walk(&mut self, root: &Path, dir: &Path, depth: usize) -> bool {
+        if depth >= MAX_INSPECT_DEPTH {
+            return false;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return false;
+        };
+        let mut has_sources = false;
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if path.is_dir() {
+                if name.starts_with('.') || name == "node_modules" {
+                    continue;
+                }
+                if build_output(&path).is_some() {
+                    self.build_dirs.insert(relative(root, &path));
+                    continue;
+                }
+                has_sources |= self.walk(root, &path, depth + 1);
+            } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                if SOURCE_EXTENSIONS.contains(&ext) {
+                    self.extensions.insert(ext.to_string());
+                    has_sources = true;
+                }
+            }
+        }
+        if has_sources && dir != root {
+            self.source_dirs.insert(relative(root, dir));
+        }
+        has_sources
+    }
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Пошаговое подтверждение предложения: каждый раздел можно принять или
+/// заменить значениями по умолчанию, пороги — изменить. `Ok(None)` — запись
+/// отменена
+pub fn confirm_interactively<R: BufRead, W: Write>(
+    proposal: ProjectConfig,
+    input: &mut R,
+    output: &mut W,
+) -> std::io::Result<Option<ProjectConfig>> {
+    let defaults = ProjectConfig::default();
+    let mut config = proposal;
+
+    writeln!(output, "Файлы для анализа (include):")?;
+    print_list(output, &config.include)?;
+    if !ask_yes(input, output, "Принять шаблоны?")? {
+        config.include = defaults.include.clone();
+    }
+
+    writeln!(output, "Исключения (exclude):")?;
+    print_list(output, &config.exclude)?;
+    if !ask_yes(input, output, "Принять исключения?")? {
+        config.exclude = defaults.exclude.clone();
+    }
+
+    if config.layers.is_empty() {
+        writeln!(
+            output,
+            "Слои по каталогам: не найдены, слои определятся по путям"
+        )?;
+    } else {
+        writeln!(output, "Слои по каталогам (layers):")?;
+        for (dir, layer) in &config.layers {
+            writeln!(output, "  {dir} → {layer}")?;
+        }
+        if !ask_yes(input, output, "Принять слои?")? {
+            config.layers.clear();
+        }
+    }
+
+    writeln!(output, "Пороги валидаторов (Enter — оставить):")?;
+    let thresholds = &mut config.thresholds;
+    thresholds.max_complexity =
+        ask_value(input, output, "max_complexity", thresholds.max_complexity)?;
+    thresholds.coupling = ask_value(input, output, "coupling", thresholds.coupling)?;
+    thresholds.cohesion = ask_value(input, output, "cohesion", thresholds.cohesion)?;
+
+    if ask_yes(input, output, &format!("Записать {CONFIG_FILE}?"))? {
+        Ok(Some(config))
+    } else {
+        Ok(None)
+    }
+}
+
+fn print_list<W: Write>(output: &mut W, items: &[String]) -> std::io::Result<()> {
+    for item in items {
+        writeln!(output, "  {item}")?;
+    }
+    Ok(())
+}
+
+/// Вопрос «да/нет»; пустой ответ и конец ввода — «да»
+fn ask_yes<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    question: &str,
+) -> std::io::Result<bool> {
+    write!(output, "{question} [Y/n] ")?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(!matches!(
+        answer.trim().to_lowercase().as_str(),
+        "n" | "no" | "н" | "нет"
+    ))
+}
+
+/// Значение с подсказкой текущего; пустой или некорректный ответ оставляет его
+fn ask_value<R: BufRead, W: Write, T: std::str::FromStr + std::fmt::Display + Copy>(
+    input: &mut R,
+    output: &mut W,
+    name: &str,
+    current: T,
+) -> std::io::Result<T> {
+    write!(output, "  {name} [{current}]: ")?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Ok(current);
+    }
+    match answer.parse() {
+        Ok(value) => Ok(value),
+        Err(_) => {
+            writeln!(output, "  некорректное значение, оставлено {current}")?;
+            Ok(current)
+        }
+    }
+}
@@ -0,0 +1,397 @@
+// Пакет для внешнего AI-ревью: сводка анализа в JSON, фрагменты кода самых проблемных
+// капсул и рекомендуемые промпты — в одной папке или zip-архиве, который можно
+// целиком передать ассистенту в сессии ревью, ведомой человеком
+
+use crate::exporter::Exporter;
+use crate::graph::CycleDetector;
+use crate::types::{AnalysisError, Capsule, CapsuleGraph, Priority, Result};
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Рекомендуемый промпт пакета
+#[derive(Debug, Clone)]
+pub struct ReviewPrompt {
+    pub title: String,
+    /// Файлы пакета, которые нужно приложить к промпту
+    pub attachments: Vec<String>,
+    pub text: String,
+}
+
+/// Содержимое пакета: относительный путь -> текст
+#[derive(Debug, Clone, Default)]
+pub struct PromptPack {
+    pub files: BTreeMap<String, String>,
+}
+
+impl PromptPack {
+    /// Записывает пакет в каталог; возвращает пути созданных файлов
+    pub fn write_dir(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for (name, content) in &self.files {
+            let path = dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, content)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Zip-архив (deflate) со всеми файлами пакета
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut central: Vec<u8> = Vec::new();
+        let (time, date) = dos_timestamp(chrono::Utc::now());
+
+        for (name, content) in &self.files {
+            let data = content.as_bytes();
+            let mut crc = Crc::new();
+            crc.update(data);
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            let compressed = encoder.finish()?;
+            let offset = zip_u32(out.len())?;
+            let entry = ZipEntry {
+                name: name.as_bytes(),
+                crc: crc.sum(),
+                compressed: zip_u32(compressed.len())?,
+                size: zip_u32(data.len())?,
+                time,
+                date,
+            };
+
+            out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+            entry.write_common(&mut out);
+            out.extend_from_slice(entry.name);
+            out.extend_from_slice(&compressed);
+
+            central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            entry.write_common(&mut central);
+            central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+            central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+            central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(entry.name);
+        }
+
+        let count = u16::try_from(self.files.len())
+            .map_err(|_| AnalysisError::GenericError("Too many files for zip".into()))?;
+        let central_offset = zip_u32(out.len())?;
+        let central_size = zip_u32(central.len())?;
+        out.extend_from_slice(&central);
+        out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // disk
+        out.extend_from_slice(&0u16.to_le_bytes()); // disk with central directory
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&central_size.to_le_bytes());
+        out.extend_from_slice(&central_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        Ok(out)
+    }
+
+    pub fn write_zip(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_zip()?)?;
+        Ok(())
+    }
+}
+
+/// Сборщик пакета
+#[derive(Debug)]
+pub struct PromptPackBuilder {
+    root: PathBuf,
+    /// Сколько проблемных капсул включать фрагментами кода
+    pub max_capsules: usize,
+    /// Предел строк одного фрагмента
+    pub max_excerpt_lines: usize,
+}
+
+impl PromptPackBuilder {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            root: project_root.to_path_buf(),
+            max_capsules: 10,
+            max_excerpt_lines: 120,
+        }
+    }
+
+    pub fn with_max_capsules(mut self, max_capsules: usize) -> Self {
+        self.max_capsules = max_capsules;
+        self
+    }
+
+    pub fn build(&self, graph: &CapsuleGraph) -> Result<PromptPack> {
+        let mut pack = PromptPack::default();
+
+        let summary = Exporter::new().export_to_ai_summary_json(graph)?;
+        let summary = serde_json::to_string_pretty(&summary)
+            .map_err(|e| AnalysisError::GenericError(format!("JSON serialization error: {e}")))?;
+        pack.files.insert("summary.json".into(), summary);
+
+        let capsules = self.problem_capsules(graph);
+        let mut excerpts = Vec::new();
+        for (index, capsule) in capsules.iter().enumerate() {
+            let name = format!("excerpts/{:02}-{}.md", index + 1, slug(&capsule.name));
+            pack.files.insert(name.clone(), self.excerpt(capsule));
+            excerpts.push(name);
+        }
+
+        let prompts = recommended_prompts(graph, &excerpts);
+        pack.files
+            .insert("prompts.md".into(), prompts_markdown(&prompts));
+        pack.files
+            .insert("README.md".into(), self.readme(graph, &excerpts, &prompts));
+        Ok(pack)
+    }
+
+    /// Капсулы по убыванию суммарной важности предупреждений, затем сложности
+    fn problem_capsules<'a>(&self, graph: &'a CapsuleGraph) -> Vec<&'a Capsule> {
+        let mut capsules: Vec<&Capsule> = graph
+            .capsules
+            .values()
+            .filter(|c| !c.warnings.is_empty())
+            .collect();
+        capsules.sort_by_key(|c| {
+            let weight: u32 = c.warnings.iter().map(|w| severity_weight(&w.level)).sum();
+            (
+                Reverse(weight),
+                Reverse(c.complexity),
+                c.file_path.clone(),
+                c.line_start,
+            )
+        });
+        capsules.truncate(self.max_capsules);
+        capsules
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    fn excerpt(&self, capsule: &Capsule) -> String {
+        let file = self.relative(&capsule.file_path);
+        let mut md = format!("# {} ({:?})\n\n", capsule.name, capsule.capsule_type);
+        md.push_str(&format!(
+            "- File: `{}` lines {}-{}\n- Layer: {}\n- Complexity: {}\n\n",
+            file,
+            capsule.line_start,
+            capsule.line_end,
+            capsule.layer.as_deref().unwrap_or("-"),
+            capsule.complexity
+        ));
+        md.push_str("## Findings\n\n");
+        let mut warnings: Vec<_> = capsule.warnings.iter().collect();
+        warnings.sort_by_key(|w| Reverse(severity_weight(&w.level)));
+        for warning in warnings {
+            md.push_str(&format!(
+                "- [{:?}] {} ({})",
+                warning.level, warning.message, warning.category
+            ));
+            if let Some(suggestion) = &warning.suggestion {
+                md.push_str(&format!(" — {}", suggestion));
+            }
+            md.push('\n');
+        }
+
+        let source = fs::read_to_string(&capsule.file_path).unwrap_or_default();
+        let start = capsule.line_start.max(1);
+        let end = capsule.line_end.max(start);
+        let lines: Vec<&str> = source
+            .lines()
+            .skip(start - 1)
+            .take((end - start + 1).min(self.max_excerpt_lines))
+            .collect();
+        if !lines.is_empty() {
+            let language = capsule
+                .file_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("");
+            md.push_str(&format!("\n## Code\n\n```{}\n", language));
+            for line in &lines {
+                md.push_str(line);
+                md.push('\n');
+            }
+            md.push_str("```\n");
+            if end - start + 1 > lines.len() {
+                md.push_str(&format!(
+                    "\n_Excerpt truncated to {} lines._\n",
+                    lines.len()
+                ));
+            }
+        }
+        md
+    }
+
+    fn readme(
+        &self,
+        graph: &CapsuleGraph,
+        excerpts: &[String],
+        prompts: &[ReviewPrompt],
+    ) -> String {
+        let project = self
+            .root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.root.display().to_string());
+        let mut md = format!("# AI review pack: {}\n\n", project);
+        md.push_str(&format!(
+            "Generated by ArchLens on {} — {} components, {} relations.\n\n",
+            graph.created_at.format("%Y-%m-%d %H:%M UTC"),
+            graph.metrics.total_capsules,
+            graph.metrics.total_relations
+        ));
+        md.push_str("## How to use\n\n");
+        md.push_str("1. Start a session with your AI assistant and attach `summary.json`.\n");
+        md.push_str(
+            "2. Work through `prompts.md` in order; each prompt lists the files to attach.\n",
+        );
+        md.push_str("3. Verify every suggestion against the code before applying it.\n\n");
+        md.push_str("## Contents\n\n");
+        md.push_str("- `summary.json` — metrics, validated problems, cycles, coupling and complexity hot spots\n");
+        md.push_str(&format!(
+            "- `prompts.md` — {} recommended prompts\n",
+            prompts.len()
+        ));
+        for excerpt in excerpts {
+            md.push_str(&format!("- `{}`\n", excerpt));
+        }
+        md
+    }
+}
+
+fn severity_weight(level: &Priority) -> u32 {
+    match level {
+        Priority::Critical => 8,
+        Priority::High => 4,
+        Priority::Medium => 2,
+        Priority::Low => 1,
+    }
+}
+
+/// Промпты под найденные проблемы: общий обзор всегда, остальные — по признакам
+pub fn recommended_prompts(graph: &CapsuleGraph, excerpts: &[String]) -> Vec<ReviewPrompt> {
+    let summary = vec!["summary.json".to_string()];
+    let mut prompts = vec![ReviewPrompt {
+        title: "Architecture health check".into(),
+        attachments: summary.clone(),
+        text: "You are reviewing the architecture analysis in summary.json. Give a concise health check: the 3-5 most important risks, why they matter, and quick wins that can be done this week.".into(),
+    }];
+
+    let has_cycles = !CycleDetector::new().find_cycles(graph).is_empty();
+    if has_cycles {
+        prompts.push(ReviewPrompt {
+            title: "Break dependency cycles".into(),
+            attachments: summary.clone(),
+            text: "Look at `cycles_top` in summary.json. For each cycle explain which dependency is most likely accidental and propose the smallest change that breaks it (interface extraction, dependency inversion or moving code).".into(),
+        });
+    }
+    if !excerpts.is_empty() {
+        let mut attachments = summary.clone();
+        attachments.extend(excerpts.iter().cloned());
+        prompts.push(ReviewPrompt {
+            title: "Review problem components".into(),
+            attachments,
+            text: "Each excerpt file contains a component, the findings reported for it and its code. For every component confirm or reject each finding based on the code, and propose a concrete refactoring with a short code sketch where it helps.".into(),
+        });
+    }
+    let high_severity = graph.capsules.values().any(|c| {
+        c.warnings
+            .iter()
+            .any(|w| matches!(w.level, Priority::High | Priority::Critical))
+    });
+    if high_severity {
+        prompts.push(ReviewPrompt {
+            title: "Prioritized refactoring plan".into(),
+            attachments: summary,
+            text: "Using `problems_validated` from summary.json, produce a prioritized refactoring plan: order work by risk reduction per effort, group related fixes, and note which items block others.".into(),
+        });
+    }
+    prompts
+}
+
+fn prompts_markdown(prompts: &[ReviewPrompt]) -> String {
+    let mut md = String::from("# Recommended prompts\n");
+    for (index, prompt) in prompts.iter().enumerate() {
+        md.push_str(&format!("\n## {}. {}\n\n", index + 1, prompt.title));
+        let attachments: Vec<String> = prompt
+            .attachments
+            .iter()
+            .map(|a| format!("`{}`", a))
+            .collect();
+        md.push_str(&format!("Attach: {}\n\n", attachments.join(", ")));
+        md.push_str("```text\n");
+        md.push_str(&prompt.text);
+        md.push_str("\n```\n");
+    }
+    md
+}
+
+fn slug(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    let out = out.trim_matches('-');
+    if out.is_empty() {
+        "capsule".to_string()
+    } else {
+        out.to_string()
+    }
+}
+
+/// Общая часть локального и центрального заголовков zip
+struct ZipEntry<'a> {
+    name: &'a [u8],
+    crc: u32,
+    compressed: u32,
+    size: u32,
+    time: u16,
+    date: u16,
+}
+
+impl ZipEntry<'_> {
+    fn write_common(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        out.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        out.extend_from_slice(&8u16.to_le_bytes()); // deflate
+        out.extend_from_slice(&self.time.to_le_bytes());
+        out.extend_from_slice(&self.date.to_le_bytes());
+        out.extend_from_slice(&self.crc.to_le_bytes());
+        out.extend_from_slice(&self.compressed.to_le_bytes());
+        out.extend_from_slice(&self.size.to_le_bytes());
+        out.extend_from_slice(&(self.name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+    }
+}
+
+fn zip_u32(value: usize) -> Result<u32> {
+    u32::try_from(value)
+        .map_err(|_| AnalysisError::GenericError("Prompt pack exceeds 4 GiB".into()))
+}
+
+fn dos_timestamp(now: chrono::DateTime<chrono::Utc>) -> (u16, u16) {
+    use chrono::{Datelike, Timelike};
+    let time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+    let year = now.year().clamp(1980, 2107) as u32 - 1980;
+    let date = ((year << 9) | (now.month() << 5) | now.day()) as u16;
+    (time, date)
+}
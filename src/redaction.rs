@@ -0,0 +1,292 @@
+// Редактирование данных перед экспортом: правила из `.archlens/redaction.json`
+// (или файла из `ARCHLENS_REDACTION`) маскируют пути файлов по префиксам,
+// внутренние имена по регулярным выражениям и, при необходимости, фрагменты кода.
+// Применяется к графу до построения отчёта, к готовому тексту отчёта и к запросам
+// во внешние LLM, поэтому ни один из форматов не выносит скрытые данные
+
+use crate::stable_hash::Fnv;
+use crate::types::{AnalysisError, CapsuleGraph, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Переменная окружения с путём к общим правилам (например, политике организации)
+pub const REDACTION_ENV: &str = "ARCHLENS_REDACTION";
+/// Каталог, в который «переносятся» замаскированные файлы
+const MASKED_DIR: &str = "redacted";
+const SNIPPET_PLACEHOLDER: &str = "[code redacted]";
+/// Соль проекта для масок путей, создаётся при первом редактировании
+const SALT_FILE: &str = "redaction.salt";
+
+fn default_replacement() -> String {
+    "[REDACTED]".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    /// Префиксы путей относительно корня проекта (`src/internal/`): пути маскируются,
+    /// код этих файлов в отчёты не попадает
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Регулярные выражения внутренних имён (`Acme\w+`, `(?i)project[-_ ]?falcon`)
+    #[serde(default)]
+    pub identifiers: Vec<String>,
+    /// Замена для совпадений `identifiers`
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+    /// Убирать все фрагменты кода (блоки ``` в отчётах и исходники в запросах LLM)
+    #[serde(default)]
+    pub snippets: bool,
+    /// Соль хеша масок путей: без неё короткие пути восстанавливаются перебором.
+    /// Если не задана, берётся из `.archlens/redaction.salt` (создаётся автоматически)
+    #[serde(default)]
+    pub salt: Option<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            identifiers: Vec::new(),
+            replacement: default_replacement(),
+            snippets: false,
+            salt: None,
+        }
+    }
+}
+
+impl RedactionConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid redaction config: {e}")))
+    }
+
+    /// `$ARCHLENS_REDACTION` или `.archlens/redaction.json`; `Ok(None)`, если правил нет
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = std::env::var_os(REDACTION_ENV)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| project_root.join(".archlens").join("redaction.json"));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.identifiers.is_empty() && !self.snippets
+    }
+}
+
+/// Скомпилированные правила редактирования для проекта
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    root: PathBuf,
+    prefixes: Vec<String>,
+    /// Путь под скрытым префиксом: абсолютный или относительный, до конца токена
+    path_pattern: Option<Regex>,
+    identifiers: Vec<Regex>,
+    replacement: String,
+    snippets: bool,
+    salt: String,
+}
+
+impl Redactor {
+    pub fn new(root: &Path, config: &RedactionConfig) -> Result<Self> {
+        let prefixes: Vec<String> = config
+            .paths
+            .iter()
+            .map(|p| p.trim().trim_start_matches("./").replace('\\', "/"))
+            .filter(|p| !p.is_empty())
+            .collect();
+        let path_pattern = if prefixes.is_empty() {
+            None
+        } else {
+            let alternatives: Vec<String> = prefixes.iter().map(|p| regex::escape(p)).collect();
+            let root = regex::escape(&root.to_string_lossy().replace('\\', "/"));
+            Some(Regex::new(&format!(
+                r#"(?:{}/)?(?:{})[^\s"'`<>()\[\]{{}},;:|#]*"#,
+                root,
+                alternatives.join("|")
+            ))?)
+        };
+        let identifiers = config
+            .identifiers
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    AnalysisError::ParsingError(format!(
+                        "Invalid redaction pattern `{pattern}`: {e}"
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            prefixes,
+            path_pattern,
+            identifiers,
+            replacement: config.replacement.clone(),
+            snippets: config.snippets,
+            salt: config.salt.clone().unwrap_or_default(),
+        })
+    }
+
+    /// Правила проекта (см. [`RedactionConfig::load`]); `Ok(None)`, если их нет
+    pub fn for_project(root: &Path) -> Result<Option<Self>> {
+        match RedactionConfig::load(root)? {
+            Some(mut config) if !config.is_empty() => {
+                if config.salt.is_none() {
+                    config.salt = Some(project_salt(root));
+                }
+                Self::new(root, &config).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Убираются ли все фрагменты кода
+    pub fn redacts_snippets(&self) -> bool {
+        self.snippets
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Файл под одним из скрытых префиксов
+    pub fn is_redacted_path(&self, path: &Path) -> bool {
+        let relative = self.relative(path);
+        self.prefixes
+            .iter()
+            .any(|p| relative.starts_with(p.as_str()))
+    }
+
+    /// Стабильная замена пути: `redacted/<хеш>.<расширение>`, хеш FNV-1a 64
+    /// от соли проекта и относительного пути
+    pub fn mask_path(&self, path: &Path) -> String {
+        let relative = self.relative(path);
+        let mut hasher = Fnv::new();
+        hasher.write_str(&self.salt);
+        hasher.write_str(&relative);
+        let hash = hasher.hex();
+        match Path::new(&relative).extension() {
+            Some(ext) => format!("{}/{}.{}", MASKED_DIR, hash, ext.to_string_lossy()),
+            None => format!("{}/{}", MASKED_DIR, hash),
+        }
+    }
+
+    /// Скрывает пути, внутренние имена и (если включено) блоки кода в тексте
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut text = if self.snippets {
+            strip_code_blocks(text)
+        } else {
+            text.to_string()
+        };
+        if let Some(pattern) = &self.path_pattern {
+            text = pattern
+                .replace_all(&text, |caps: &regex::Captures| {
+                    self.mask_path(Path::new(&caps[0]))
+                })
+                .into_owned();
+        }
+        for identifier in &self.identifiers {
+            text = identifier
+                .replace_all(&text, regex::NoExpand(&self.replacement))
+                .into_owned();
+        }
+        text
+    }
+
+    /// Редактирует граф до построения отчёта: файлы под скрытыми префиксами
+    /// получают замаскированные пути (их код больше не читается экспортёрами),
+    /// в именах, описаниях и находках скрываются внутренние имена
+    pub fn redact_graph(&self, graph: &mut CapsuleGraph) {
+        for capsule in graph.capsules.values_mut() {
+            if self.is_redacted_path(&capsule.file_path) {
+                capsule.file_path = self.root.join(self.mask_path(&capsule.file_path));
+            }
+            capsule.name = self.redact_text(&capsule.name);
+            for field in [
+                &mut capsule.summary,
+                &mut capsule.description,
+                &mut capsule.slogan,
+                &mut capsule.layer,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *field = self.redact_text(field);
+            }
+            for tag in &mut capsule.tags {
+                *tag = self.redact_text(tag);
+            }
+            for value in capsule.metadata.values_mut() {
+                *value = self.redact_text(value);
+            }
+            for warning in &mut capsule.warnings {
+                warning.message = self.redact_text(&warning.message);
+                if let Some(suggestion) = &mut warning.suggestion {
+                    *suggestion = self.redact_text(suggestion);
+                }
+            }
+        }
+        for relation in &mut graph.relations {
+            if let Some(description) = &mut relation.description {
+                *description = self.redact_text(description);
+            }
+        }
+        graph.layers = std::mem::take(&mut graph.layers)
+            .into_iter()
+            .map(|(name, ids)| (self.redact_text(&name), ids))
+            .collect();
+    }
+}
+
+/// Соль из `.archlens/redaction.salt`; при первом вызове генерируется случайная.
+/// Если каталог недоступен для записи — путь корня (маски стабильны, но предсказуемы)
+fn project_salt(root: &Path) -> String {
+    let path = root.join(".archlens").join(SALT_FILE);
+    if let Ok(salt) = fs::read_to_string(&path) {
+        let salt = salt.trim();
+        if !salt.is_empty() {
+            return salt.to_string();
+        }
+    }
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, &salt));
+    match written {
+        Ok(()) => salt,
+        Err(_) => root.to_string_lossy().into_owned(),
+    }
+}
+
+/// Заменяет содержимое блоков ``` на заглушку, сохраняя сами блоки
+fn strip_code_blocks(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_block = false;
+    for line in text.split_inclusive('\n') {
+        let fence = line.trim_start().starts_with("```");
+        if fence && !in_block {
+            in_block = true;
+            out.push_str(line);
+            out.push_str(SNIPPET_PLACEHOLDER);
+            out.push('\n');
+        } else if fence {
+            in_block = false;
+            out.push_str(line);
+        } else if !in_block {
+            out.push_str(line);
+        }
+    }
+    out
+}
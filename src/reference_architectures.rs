@@ -0,0 +1,381 @@
+// Сравнение проекта с эталонными архитектурами: встроенные профили
+// (гексагональный сервис, слоистое веб-приложение, MVC, чистая архитектура,
+// микроядро) описывают роли каталогов и разрешённые направления зависимостей
+// между ролями. Оценка показывает, на какой стиль проект похож больше всего и
+// где от него отходит, — подсказка при знакомстве с унаследованным кодом
+
+use crate::dsm::common_root;
+use crate::types::{AnalysisError, CapsuleGraph, RelationType, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use uuid::Uuid;
+
+const BUILTIN_PROFILES: &str = include_str!("reference_architectures.json");
+/// Веса составляющих оценки: покрытие ролей, доля распознанных капсул,
+/// доля разрешённых зависимостей между ролями (она умножается на долю
+/// распознанных капсул: почти нераспознанный проект не получает её даром)
+const COVERAGE_WEIGHT: f64 = 0.4;
+const CLASSIFIED_WEIGHT: f64 = 0.3;
+const CONFORMITY_WEIGHT: f64 = 0.3;
+/// Сколько нераспознанных каталогов показывать в отклонениях
+const UNCLASSIFIED_SHOWN: usize = 5;
+
+/// Роль профиля и имена каталогов (или файлов без расширения), которые её выдают
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceRole {
+    pub name: String,
+    pub directories: Vec<String>,
+}
+
+/// Эталонная архитектура; зависимости внутри одной роли разрешены всегда,
+/// между ролями — только перечисленные в `allowed` пары `[откуда, куда]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceProfile {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub roles: Vec<ReferenceRole>,
+    #[serde(default)]
+    pub allowed: Vec<(String, String)>,
+}
+
+impl ReferenceProfile {
+    /// Роль файла: ближайший к файлу компонент пути, совпавший с каталогом роли
+    fn role_of(&self, relative: &Path) -> Option<usize> {
+        let stem = relative
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase());
+        let dirs = relative
+            .parent()
+            .into_iter()
+            .flat_map(|p| p.components())
+            .map(|c| c.as_os_str().to_string_lossy().to_lowercase());
+        let components: Vec<String> = dirs.chain(stem).collect();
+        components.iter().rev().find_map(|component| {
+            self.roles
+                .iter()
+                .position(|role| role.directories.iter().any(|d| d == component))
+        })
+    }
+
+    fn allows(&self, from: &str, to: &str) -> bool {
+        from == to || self.allowed.iter().any(|(f, t)| f == from && t == to)
+    }
+}
+
+/// Набор профилей: встроенные или из `.archlens/reference_architectures.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReferenceProfiles {
+    #[serde(default)]
+    pub profiles: Vec<ReferenceProfile>,
+}
+
+impl ReferenceProfiles {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| {
+            AnalysisError::ParsingError(format!("Invalid reference architectures: {e}"))
+        })
+    }
+
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN_PROFILES).expect("built-in reference architectures are valid")
+    }
+
+    /// `.archlens/reference_architectures.json`; `Ok(None)`, если файла нет
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root
+            .join(".archlens")
+            .join("reference_architectures.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+
+    /// Профили проекта заменяют встроенные с тем же `id`
+    pub fn merged_with(mut self, overrides: ReferenceProfiles) -> Self {
+        self.profiles
+            .retain(|profile| !overrides.profiles.iter().any(|o| o.id == profile.id));
+        self.profiles.extend(overrides.profiles);
+        self
+    }
+
+    /// Встроенные профили вместе с профилями проекта
+    pub fn for_project(project_root: &Path) -> Result<Self> {
+        let builtin = Self::builtin();
+        Ok(match Self::load(project_root)? {
+            Some(overrides) => builtin.merged_with(overrides),
+            None => builtin,
+        })
+    }
+
+    /// Оценивает граф по каждому профилю; лучшие совпадения — первыми
+    pub fn compare(&self, graph: &CapsuleGraph) -> ResemblanceReport {
+        let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        let mut matches: Vec<ProfileMatch> = self
+            .profiles
+            .iter()
+            .map(|profile| score_profile(profile, graph, &root))
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.profile.cmp(&b.profile))
+        });
+        ResemblanceReport {
+            capsules: graph.capsules.len(),
+            matches,
+        }
+    }
+}
+
+/// Сколько капсул проекта получили роль профиля
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoleMatch {
+    pub role: String,
+    pub capsules: usize,
+}
+
+/// Отклонение проекта от профиля
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Deviation {
+    /// В проекте нет каталогов роли
+    MissingRole {
+        role: String,
+        directories: Vec<String>,
+    },
+    /// Зависимость между ролями против разрешённого направления
+    ForbiddenDependency {
+        from: String,
+        to: String,
+        count: usize,
+        example: String,
+    },
+    /// Каталоги, капсулы которых не попали ни в одну роль
+    Unclassified { directory: String, capsules: usize },
+}
+
+impl Deviation {
+    fn to_text(&self) -> String {
+        match self {
+            Deviation::MissingRole { role, directories } => format!(
+                "missing role `{role}` (expected directories: {})",
+                directories.join(", ")
+            ),
+            Deviation::ForbiddenDependency {
+                from,
+                to,
+                count,
+                example,
+            } => format!(
+                "`{from}` → `{to}`: {count} dependencies against the allowed direction, \
+                 e.g. {example}"
+            ),
+            Deviation::Unclassified {
+                directory,
+                capsules,
+            } => format!("`{directory}` ({capsules} capsules) fits no role"),
+        }
+    }
+}
+
+/// Совпадение с одним профилем; `score` — от 0 до 1
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileMatch {
+    pub profile: String,
+    pub title: String,
+    pub description: String,
+    pub score: f64,
+    /// Доля ролей профиля, найденных в проекте
+    pub coverage: f64,
+    /// Доля капсул проекта, получивших роль
+    pub classified: f64,
+    /// Доля зависимостей между ролями в разрешённом направлении
+    pub conformity: f64,
+    pub roles: Vec<RoleMatch>,
+    pub deviations: Vec<Deviation>,
+}
+
+/// Оценки проекта по всем профилям
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResemblanceReport {
+    pub capsules: usize,
+    pub matches: Vec<ProfileMatch>,
+}
+
+impl ResemblanceReport {
+    /// Самый похожий профиль, если хоть одна капсула получила роль
+    pub fn best(&self) -> Option<&ProfileMatch> {
+        self.matches.first().filter(|m| m.classified > 0.0)
+    }
+
+    pub fn to_text(&self) -> String {
+        let Some(best) = self.best() else {
+            return format!(
+                "No reference architecture recognized: none of {} capsules is in a known role directory\n",
+                self.capsules
+            );
+        };
+        let mut out = format!(
+            "Closest reference architecture: {} ({:.0}%)\n{}\n\nRanking:\n",
+            best.title,
+            best.score * 100.0,
+            best.description
+        );
+        for m in &self.matches {
+            out.push_str(&format!(
+                "  {:>3.0}%  {:<40} roles {:.0}%, classified {:.0}%, allowed dependencies {:.0}%\n",
+                m.score * 100.0,
+                m.title,
+                m.coverage * 100.0,
+                m.classified * 100.0,
+                m.conformity * 100.0
+            ));
+        }
+        out.push_str("\nRoles:\n");
+        for role in &best.roles {
+            out.push_str(&format!("  {} — {} capsules\n", role.role, role.capsules));
+        }
+        if best.deviations.is_empty() {
+            out.push_str("\nNo deviations from the reference\n");
+        } else {
+            out.push_str("\nDeviations:\n");
+            for deviation in &best.deviations {
+                out.push_str(&format!("  - {}\n", deviation.to_text()));
+            }
+        }
+        out
+    }
+}
+
+fn score_profile(profile: &ReferenceProfile, graph: &CapsuleGraph, root: &Path) -> ProfileMatch {
+    let mut role_of: HashMap<Uuid, usize> = HashMap::new();
+    let mut counts = vec![0usize; profile.roles.len()];
+    let mut unclassified: BTreeMap<String, usize> = BTreeMap::new();
+    for capsule in graph.capsules.values() {
+        let relative = capsule
+            .file_path
+            .strip_prefix(root)
+            .unwrap_or(&capsule.file_path);
+        match profile.role_of(relative) {
+            Some(role) => {
+                role_of.insert(capsule.id, role);
+                counts[role] += 1;
+            }
+            None => {
+                let directory = relative
+                    .parent()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .filter(|p| !p.is_empty())
+                    .unwrap_or_else(|| ".".to_string());
+                *unclassified.entry(directory).or_default() += 1;
+            }
+        }
+    }
+
+    let mut forbidden: BTreeMap<(usize, usize), (usize, String)> = BTreeMap::new();
+    let (mut between, mut allowed) = (0usize, 0usize);
+    for relation in &graph.relations {
+        if relation.relation_type.is_structural()
+            || relation.relation_type == RelationType::Contains
+        {
+            continue;
+        }
+        let (Some(&from), Some(&to)) =
+            (role_of.get(&relation.from_id), role_of.get(&relation.to_id))
+        else {
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+        between += 1;
+        if profile.allows(&profile.roles[from].name, &profile.roles[to].name) {
+            allowed += 1;
+            continue;
+        }
+        let entry = forbidden.entry((from, to)).or_insert_with(|| {
+            let example = format!(
+                "`{}` → `{}`",
+                graph.capsules[&relation.from_id].name, graph.capsules[&relation.to_id].name
+            );
+            (0, example)
+        });
+        entry.0 += 1;
+    }
+
+    let present = counts.iter().filter(|&&n| n > 0).count();
+    let classified_count: usize = counts.iter().sum();
+    let coverage = ratio(present, profile.roles.len());
+    let classified = ratio(classified_count, graph.capsules.len());
+    let conformity = if between == 0 {
+        1.0
+    } else {
+        ratio(allowed, between)
+    };
+    let score = COVERAGE_WEIGHT * coverage
+        + CLASSIFIED_WEIGHT * classified
+        + CONFORMITY_WEIGHT * conformity * classified;
+
+    let mut deviations: Vec<Deviation> = profile
+        .roles
+        .iter()
+        .zip(&counts)
+        .filter(|(_, &n)| n == 0)
+        .map(|(role, _)| Deviation::MissingRole {
+            role: role.name.clone(),
+            directories: role.directories.clone(),
+        })
+        .collect();
+    let mut forbidden: Vec<_> = forbidden.into_iter().collect();
+    forbidden.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(&b.0)));
+    deviations.extend(forbidden.into_iter().map(|((from, to), (count, example))| {
+        Deviation::ForbiddenDependency {
+            from: profile.roles[from].name.clone(),
+            to: profile.roles[to].name.clone(),
+            count,
+            example,
+        }
+    }));
+    let mut unclassified: Vec<(String, usize)> = unclassified.into_iter().collect();
+    unclassified.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    deviations.extend(unclassified.into_iter().take(UNCLASSIFIED_SHOWN).map(
+        |(directory, capsules)| Deviation::Unclassified {
+            directory,
+            capsules,
+        },
+    ));
+
+    ProfileMatch {
+        profile: profile.id.clone(),
+        title: profile.title.clone(),
+        description: profile.description.clone(),
+        score,
+        coverage,
+        classified,
+        conformity,
+        roles: profile
+            .roles
+            .iter()
+            .zip(&counts)
+            .map(|(role, &capsules)| RoleMatch {
+                role: role.name.clone(),
+                capsules,
+            })
+            .collect(),
+        deviations,
+    }
+}
+
+fn ratio(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
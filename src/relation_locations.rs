@@ -0,0 +1,146 @@
+// Места в коде, из-за которых возникла связь: строка, где исходная капсула
+// упоминает целевую. Если упоминания нет (связь по структуре каталогов или
+// слоям), ссылкой служит объявление исходной капсулы — у каждой связи в
+// экспорте есть хотя бы одна ссылка `файл:строка`
+
+use crate::types::{CapsuleGraph, CapsuleRelation, RelationType};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Сколько строк просматривать после объявления, когда конец капсулы неизвестен
+const MAX_SCAN_LINES: usize = 400;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    /// Номер строки с единицы
+    pub line: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+/// Ищет места связей в исходниках; файлы читаются один раз
+pub struct RelationLocator<'a> {
+    graph: &'a CapsuleGraph,
+    /// Начала капсул по файлам — граница капсулы, чей конец парсер не знает
+    starts: HashMap<&'a Path, Vec<usize>>,
+    files: HashMap<&'a Path, Option<Vec<String>>>,
+}
+
+impl<'a> RelationLocator<'a> {
+    pub fn new(graph: &'a CapsuleGraph) -> Self {
+        let mut starts: HashMap<&Path, Vec<usize>> = HashMap::new();
+        for capsule in graph.capsules.values() {
+            starts
+                .entry(capsule.file_path.as_path())
+                .or_default()
+                .push(capsule.line_start);
+        }
+        for lines in starts.values_mut() {
+            lines.sort_unstable();
+            lines.dedup();
+        }
+        Self {
+            graph,
+            starts,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Место связи: для импорта — строка `use`/`import` с целью; иначе
+    /// упоминание цели внутри исходной капсулы, затем в её файле (импорт),
+    /// иначе объявление исходной капсулы. `None` — капсулы нет в графе
+    pub fn locate(&mut self, relation: &CapsuleRelation) -> Option<SourceLocation> {
+        let from = self.graph.capsules.get(&relation.from_id)?;
+        let to = self.graph.capsules.get(&relation.to_id)?;
+        let file = from.file_path.as_path();
+        let declaration = SourceLocation {
+            file: file.to_path_buf(),
+            line: from.line_start.max(1),
+        };
+        if to.name.is_empty() {
+            return Some(declaration);
+        }
+
+        let first = from.line_start.max(1);
+        let last = if from.line_end > from.line_start {
+            from.line_end
+        } else {
+            // до следующей капсулы того же файла
+            self.starts
+                .get(file)
+                .and_then(|starts| starts.iter().find(|s| **s > first))
+                .map_or(first + MAX_SCAN_LINES, |next| next - 1)
+                .min(first + MAX_SCAN_LINES)
+        };
+
+        let Some(lines) = self
+            .files
+            .entry(file)
+            .or_insert_with(|| {
+                std::fs::read_to_string(file)
+                    .ok()
+                    .map(|text| text.lines().map(str::to_string).collect())
+            })
+            .as_ref()
+        else {
+            return Some(declaration);
+        };
+        let mentions = |line: &String| contains_word(line, &to.name);
+        if relation.relation_type == RelationType::Imports {
+            if let Some(index) = lines
+                .iter()
+                .position(|line| is_import_statement(line) && mentions(line))
+            {
+                return Some(SourceLocation {
+                    file: file.to_path_buf(),
+                    line: index + 1,
+                });
+            }
+        }
+        let in_capsule = lines
+            .iter()
+            .enumerate()
+            .take(last.min(lines.len()))
+            .skip(first - 1)
+            .find(|(_, line)| mentions(line));
+        let found = in_capsule.or_else(|| lines.iter().enumerate().find(|(_, l)| mentions(l)));
+        Some(match found {
+            Some((index, _)) => SourceLocation {
+                file: file.to_path_buf(),
+                line: index + 1,
+            },
+            None => declaration,
+        })
+    }
+}
+
+/// Строка импорта: `use`, `import`, `from … import`, `require(…)`, `#include`,
+/// реэкспорт `export … from`
+fn is_import_statement(line: &str) -> bool {
+    let line = line.trim_start();
+    let line = line
+        .strip_prefix("pub ")
+        .or_else(|| line.strip_prefix("pub(crate) "))
+        .unwrap_or(line);
+    ["use ", "import ", "from ", "#include"]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+        || (line.starts_with("export ") && line.contains(" from "))
+        || line.contains("require(")
+}
+
+fn contains_word(line: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word).any(|(i, _)| {
+        let before = line[..i].chars().next_back();
+        let after = line[i + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
@@ -0,0 +1,475 @@
+// Отчёт об архитектурных изменениях релиза: diff графов двух тегов
+// вместе с сообщениями коммитов, готовый для вставки в release notes
+
+use crate::dependency_freshness::{find_manifests, parse_manifest};
+use crate::diff_analyzer::DiffAnalyzer;
+use crate::git::run_git;
+use crate::types::{CapsuleGraph, ChangeImpact, QualityTrend, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Сколько элементов списка выводить в markdown
+const MARKDOWN_LIST_LIMIT: usize = 25;
+
+/// Коммит диапазона релиза
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub subject: String,
+    pub files: Vec<String>,
+}
+
+/// Добавленный или удалённый модуль (файл)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleChange {
+    pub path: String,
+    pub components: usize,
+    /// Коммиты, затронувшие модуль (`hash subject`)
+    pub commits: Vec<String>,
+}
+
+/// Внешняя зависимость из манифеста
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExternalDependency {
+    pub name: String,
+    pub ecosystem: String,
+    pub requirement: String,
+}
+
+/// Метрика графа до и после релиза
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub name: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+/// Состояние проекта на одной ревизии
+#[derive(Debug)]
+pub struct ReleaseSide {
+    pub rev: String,
+    /// Корень дерева ревизии: пути капсул берутся относительно него
+    pub root: PathBuf,
+    pub graph: CapsuleGraph,
+}
+
+/// Архитектурные изменения между двумя ревизиями
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseReport {
+    pub from: String,
+    pub to: String,
+    pub commits: Vec<CommitInfo>,
+    pub new_modules: Vec<ModuleChange>,
+    pub removed_modules: Vec<ModuleChange>,
+    pub new_external_dependencies: Vec<ExternalDependency>,
+    pub removed_external_dependencies: Vec<ExternalDependency>,
+    /// Новые связи между модулями (`from` → `to`)
+    pub new_module_dependencies: Vec<(String, String)>,
+    pub removed_module_dependencies: Vec<(String, String)>,
+    pub metrics: Vec<MetricDelta>,
+    pub quality_trend: QualityTrend,
+    /// Крупные и ломающие изменения компонентов по данным diff-анализа
+    pub notable_changes: Vec<String>,
+}
+
+/// Генератор отчёта о релизе для git-репозитория
+#[derive(Debug)]
+pub struct ReleaseReportGenerator {
+    repo: PathBuf,
+}
+
+impl ReleaseReportGenerator {
+    pub fn new(repo: &Path) -> Self {
+        Self {
+            repo: repo.to_path_buf(),
+        }
+    }
+
+    /// Коммиты `from..to` без merge-коммитов, от старых к новым
+    pub fn commits(&self, from: &str, to: &str) -> Result<Vec<CommitInfo>> {
+        let out = self.git(&[
+            "log",
+            "--no-merges",
+            "--reverse",
+            "--format=%x1e%h%x1f%s",
+            "--name-only",
+            &format!("{}..{}", from, to),
+        ])?;
+        Ok(out
+            .split('\u{1e}')
+            .filter_map(|record| {
+                let mut lines = record.lines();
+                let (hash, subject) = lines.next()?.split_once('\u{1f}')?;
+                Some(CommitInfo {
+                    hash: hash.to_string(),
+                    subject: subject.to_string(),
+                    files: lines
+                        .map(str::trim)
+                        .filter(|l| !l.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                })
+            })
+            .collect())
+    }
+
+    /// Полный хеш коммита ревизии
+    pub fn resolve(&self, rev: &str) -> Result<String> {
+        Ok(self
+            .git(&["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])?
+            .trim()
+            .to_string())
+    }
+
+    /// Извлекает ревизию во временный worktree (удаляется при drop)
+    pub fn checkout(&self, rev: &str) -> Result<RevisionCheckout> {
+        let path = std::env::temp_dir().join(format!("archlens-release-{}", uuid::Uuid::new_v4()));
+        self.git(&[
+            "worktree",
+            "add",
+            "--detach",
+            "--quiet",
+            &path.to_string_lossy(),
+            rev,
+        ])?;
+        Ok(RevisionCheckout {
+            repo: self.repo.clone(),
+            path,
+        })
+    }
+
+    pub fn generate(
+        &self,
+        previous: &ReleaseSide,
+        current: &ReleaseSide,
+        commits: Vec<CommitInfo>,
+    ) -> Result<ReleaseReport> {
+        let diff = DiffAnalyzer::new()
+            .with_roots(&previous.root, &current.root)
+            .analyze_diff(&current.graph, &previous.graph)?;
+
+        let before = modules(previous);
+        let after = modules(current);
+        let touching = |path: &str| -> Vec<String> {
+            commits
+                .iter()
+                .filter(|c| c.files.iter().any(|f| f == path))
+                .map(|c| format!("{} {}", c.hash, c.subject))
+                .collect()
+        };
+        let module_changes = |from: &BTreeMap<String, usize>, other: &BTreeMap<String, usize>| {
+            from.iter()
+                .filter(|(path, _)| !other.contains_key(*path))
+                .map(|(path, components)| ModuleChange {
+                    path: path.clone(),
+                    components: *components,
+                    commits: touching(path),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let deps_before = module_dependencies(previous);
+        let deps_after = module_dependencies(current);
+        let ext_before = external_dependencies(&previous.root);
+        let ext_after = external_dependencies(&current.root);
+        let ext_names = |deps: &BTreeSet<ExternalDependency>| -> BTreeSet<(String, String)> {
+            deps.iter()
+                .map(|d| (d.ecosystem.clone(), d.name.clone()))
+                .collect()
+        };
+        let (names_before, names_after) = (ext_names(&ext_before), ext_names(&ext_after));
+
+        let mut notable_changes: Vec<String> = diff
+            .changes
+            .iter()
+            .filter(|c| matches!(c.impact, ChangeImpact::Breaking | ChangeImpact::Major))
+            .map(|c| c.description.clone())
+            .collect();
+        notable_changes.sort();
+
+        Ok(ReleaseReport {
+            from: previous.rev.clone(),
+            to: current.rev.clone(),
+            new_modules: module_changes(&after, &before),
+            removed_modules: module_changes(&before, &after),
+            new_external_dependencies: ext_after
+                .iter()
+                .filter(|d| !names_before.contains(&(d.ecosystem.clone(), d.name.clone())))
+                .cloned()
+                .collect(),
+            removed_external_dependencies: ext_before
+                .iter()
+                .filter(|d| !names_after.contains(&(d.ecosystem.clone(), d.name.clone())))
+                .cloned()
+                .collect(),
+            new_module_dependencies: deps_after.difference(&deps_before).cloned().collect(),
+            removed_module_dependencies: deps_before.difference(&deps_after).cloned().collect(),
+            metrics: metric_deltas(&previous.graph, &current.graph),
+            quality_trend: diff.quality_trend,
+            notable_changes,
+            commits,
+        })
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        run_git(&self.repo, args)
+    }
+}
+
+/// Временный worktree ревизии
+#[derive(Debug)]
+pub struct RevisionCheckout {
+    repo: PathBuf,
+    path: PathBuf,
+}
+
+impl RevisionCheckout {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RevisionCheckout {
+    fn drop(&mut self) {
+        let _ = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args(["worktree", "remove", "--force"])
+            .arg(&self.path)
+            .output();
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+impl ReleaseReport {
+    /// Раздел release notes «Architecture changes in <to>»
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("## Architecture changes in {}\n\n", self.to);
+        md.push_str(&format!(
+            "_{}…{}: {} commits, quality trend: {:?}_\n",
+            self.from,
+            self.to,
+            self.commits.len(),
+            self.quality_trend
+        ));
+
+        md.push_str(&format!(
+            "\n### Metrics\n\n| Metric | {} | {} | Δ |\n|---|---|---|---|\n",
+            self.from, self.to
+        ));
+        for m in &self.metrics {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                m.name,
+                format_metric(m.before),
+                format_metric(m.after),
+                format_delta(m.after - m.before)
+            ));
+        }
+
+        let module_line = |m: &ModuleChange| {
+            let mut line = format!("`{}` ({} components)", m.path, m.components);
+            if let Some(first) = m.commits.first() {
+                line.push_str(&format!(" — {}", first));
+            }
+            line
+        };
+        push_list(
+            &mut md,
+            "New modules",
+            self.new_modules.iter().map(module_line).collect(),
+        );
+        push_list(
+            &mut md,
+            "Removed modules",
+            self.removed_modules.iter().map(module_line).collect(),
+        );
+
+        let external =
+            |d: &ExternalDependency| format!("`{}` {} ({})", d.name, d.requirement, d.ecosystem);
+        let internal = |(from, to): &(String, String)| format!("`{}` → `{}`", from, to);
+        push_list(
+            &mut md,
+            "New dependencies",
+            self.new_external_dependencies
+                .iter()
+                .map(external)
+                .chain(self.new_module_dependencies.iter().map(internal))
+                .collect(),
+        );
+        push_list(
+            &mut md,
+            "Removed dependencies",
+            self.removed_external_dependencies
+                .iter()
+                .map(external)
+                .chain(self.removed_module_dependencies.iter().map(internal))
+                .collect(),
+        );
+        push_list(
+            &mut md,
+            "Notable component changes",
+            self.notable_changes.clone(),
+        );
+
+        // Коммиты по типам conventional commits
+        let mut groups: BTreeMap<usize, (&str, Vec<String>)> = BTreeMap::new();
+        for c in &self.commits {
+            let (order, title) = commit_group(&c.subject);
+            groups
+                .entry(order)
+                .or_insert((title, Vec::new()))
+                .1
+                .push(format!("{} {}", c.hash, c.subject));
+        }
+        if !groups.is_empty() {
+            md.push_str("\n### Commits\n");
+            for (title, items) in groups.values() {
+                md.push_str(&format!("\n**{}**\n", title));
+                for item in items.iter().take(MARKDOWN_LIST_LIMIT) {
+                    md.push_str(&format!("- {}\n", item));
+                }
+                if items.len() > MARKDOWN_LIST_LIMIT {
+                    md.push_str(&format!(
+                        "- … and {} more\n",
+                        items.len() - MARKDOWN_LIST_LIMIT
+                    ));
+                }
+            }
+        }
+        md
+    }
+}
+
+pub(crate) fn push_list(md: &mut String, title: &str, items: Vec<String>) {
+    if items.is_empty() {
+        return;
+    }
+    md.push_str(&format!("\n### {}\n\n", title));
+    for item in items.iter().take(MARKDOWN_LIST_LIMIT) {
+        md.push_str(&format!("- {}\n", item));
+    }
+    if items.len() > MARKDOWN_LIST_LIMIT {
+        md.push_str(&format!(
+            "- … and {} more\n",
+            items.len() - MARKDOWN_LIST_LIMIT
+        ));
+    }
+}
+
+fn commit_group(subject: &str) -> (usize, &'static str) {
+    let kind = subject
+        .split([':', '(', '!'])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+    match kind.as_str() {
+        "feat" => (0, "Features"),
+        "fix" => (1, "Fixes"),
+        "refactor" | "perf" => (2, "Refactoring"),
+        _ => (3, "Other"),
+    }
+}
+
+fn format_metric(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+fn format_delta(delta: f64) -> String {
+    if delta.fract() == 0.0 {
+        format!("{:+}", delta)
+    } else {
+        format!("{:+.2}", delta)
+    }
+}
+
+fn relative(side: &ReleaseSide, path: &Path) -> String {
+    path.strip_prefix(&side.root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Модули ревизии: относительный путь файла → число компонентов
+fn modules(side: &ReleaseSide) -> BTreeMap<String, usize> {
+    let mut modules = BTreeMap::new();
+    for capsule in side.graph.capsules.values() {
+        *modules
+            .entry(relative(side, &capsule.file_path))
+            .or_insert(0) += 1;
+    }
+    modules
+}
+
+/// Связи между разными модулями
+fn module_dependencies(side: &ReleaseSide) -> BTreeSet<(String, String)> {
+    let file_of: HashMap<_, _> = side
+        .graph
+        .capsules
+        .values()
+        .map(|c| (c.id, relative(side, &c.file_path)))
+        .collect();
+    side.graph
+        .relations
+        .iter()
+        .filter_map(|r| Some((file_of.get(&r.from_id)?, file_of.get(&r.to_id)?)))
+        .filter(|(from, to)| from != to)
+        .map(|(from, to)| (from.clone(), to.clone()))
+        .collect()
+}
+
+fn external_dependencies(root: &Path) -> BTreeSet<ExternalDependency> {
+    find_manifests(root)
+        .iter()
+        .flat_map(|m| parse_manifest(m))
+        .map(|d| ExternalDependency {
+            name: d.name,
+            ecosystem: d.ecosystem.key().to_string(),
+            requirement: d.requirement,
+        })
+        .collect()
+}
+
+fn metric_deltas(previous: &CapsuleGraph, current: &CapsuleGraph) -> Vec<MetricDelta> {
+    let (a, b) = (&previous.metrics, &current.metrics);
+    let round = |v: f32| (v as f64 * 100.0).round() / 100.0;
+    vec![
+        MetricDelta {
+            name: "Components".into(),
+            before: a.total_capsules as f64,
+            after: b.total_capsules as f64,
+        },
+        MetricDelta {
+            name: "Relations".into(),
+            before: a.total_relations as f64,
+            after: b.total_relations as f64,
+        },
+        MetricDelta {
+            name: "Average complexity".into(),
+            before: round(a.complexity_average),
+            after: round(b.complexity_average),
+        },
+        MetricDelta {
+            name: "Coupling index".into(),
+            before: round(a.coupling_index),
+            after: round(b.coupling_index),
+        },
+        MetricDelta {
+            name: "Cohesion index".into(),
+            before: round(a.cohesion_index),
+            after: round(b.cohesion_index),
+        },
+        MetricDelta {
+            name: "Cyclomatic complexity".into(),
+            before: a.cyclomatic_complexity as f64,
+            after: b.cyclomatic_complexity as f64,
+        },
+    ]
+}
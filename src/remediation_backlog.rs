@@ -0,0 +1,369 @@
+// Приоритизированный бэклог исправлений для планирования спринтов:
+// проблема, капсула, оценка трудозатрат, влияние и зависимости между задачами
+
+use crate::enrichment::quality_analyzer::EffortLevel;
+use crate::graph::CycleDetector;
+use crate::types::{AnalysisWarning, Capsule, CapsuleGraph, Priority};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Заголовок CSV-экспорта
+pub const BACKLOG_CSV_HEADER: &str =
+    "id,problem,capsule,file,category,effort_hours,effort,impact,priority_score,depends_on";
+
+/// Задача бэклога: все предупреждения одной категории на одной капсуле
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationItem {
+    pub id: String,
+    pub problem: String,
+    pub capsule: String,
+    pub file: String,
+    pub category: String,
+    pub effort_hours: f32,
+    pub impact: Priority,
+    /// Влияние на час работы: чем выше, тем раньше в спринт
+    pub priority_score: f32,
+    /// id задач, которые стоит закрыть раньше
+    pub depends_on: Vec<String>,
+    #[serde(skip)]
+    capsule_id: Uuid,
+}
+
+impl RemediationItem {
+    /// Размер задачи по шкале EffortLevel
+    pub fn effort_level(&self) -> EffortLevel {
+        match self.effort_hours {
+            h if h < 1.0 => EffortLevel::Low,
+            h if h <= 4.0 => EffortLevel::Medium,
+            h if h <= 16.0 => EffortLevel::High,
+            _ => EffortLevel::Critical,
+        }
+    }
+}
+
+/// Планировщик бэклога по предупреждениям валидаторов
+#[derive(Debug)]
+pub struct RemediationPlanner {
+    root: Option<PathBuf>,
+    /// Базовая оценка, часов, по категории предупреждения
+    pub base_hours: HashMap<&'static str, f32>,
+    pub default_hours: f32,
+}
+
+impl RemediationPlanner {
+    pub fn new() -> Self {
+        let base_hours = [
+            ("naming", 0.5),
+            ("documentation", 1.0),
+            ("maintenance", 1.5),
+            ("code_quality", 2.0),
+            ("api_quality", 2.0),
+            ("duplication", 3.0),
+            ("complexity", 4.0),
+            ("size", 4.0),
+            ("cohesion", 4.0),
+            ("solid", 4.0),
+            ("coupling", 6.0),
+            ("layers", 6.0),
+            ("connectivity", 6.0),
+            ("architecture", 8.0),
+            ("cycles", 8.0),
+        ]
+        .into_iter()
+        .collect();
+        Self {
+            root: None,
+            base_hours,
+            default_hours: 2.0,
+        }
+    }
+
+    /// Пути файлов в экспорте — относительно корня проекта
+    pub fn with_root(mut self, root: &Path) -> Self {
+        self.root = Some(root.to_path_buf());
+        self
+    }
+
+    /// Бэклог, отсортированный по убыванию priority_score
+    pub fn plan(&self, graph: &CapsuleGraph) -> Vec<RemediationItem> {
+        // (капсула, категория) → предупреждения
+        let mut groups: BTreeMap<(String, String, Uuid), Vec<&AnalysisWarning>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            for warning in &capsule.warnings {
+                let id = warning.capsule_id.unwrap_or(capsule.id);
+                let Some(owner) = graph.capsules.get(&id) else {
+                    continue;
+                };
+                groups
+                    .entry((
+                        owner.file_path.to_string_lossy().to_string(),
+                        warning.category.clone(),
+                        id,
+                    ))
+                    .or_default()
+                    .push(warning);
+            }
+        }
+
+        let fan_in = fan_in(graph);
+        let mut items: Vec<RemediationItem> = groups
+            .into_iter()
+            .filter_map(|((_, category, id), warnings)| {
+                let capsule = graph.capsules.get(&id)?;
+                let impact = warnings
+                    .iter()
+                    .map(|w| w.level)
+                    .min_by_key(priority_rank)
+                    .unwrap_or(Priority::Low);
+                let effort_hours = self.estimate_hours(&category, capsule, warnings.len());
+                // Влияние растёт с числом зависящих от капсулы компонентов
+                let dependents = fan_in.get(&id).copied().unwrap_or(0) as f32;
+                let impact_score = impact_weight(&impact) * (1.0 + (1.0 + dependents).ln());
+                let mut problem = warnings[0].message.clone();
+                if warnings.len() > 1 {
+                    problem.push_str(&format!(" (+{} more)", warnings.len() - 1));
+                }
+                Some(RemediationItem {
+                    id: String::new(),
+                    problem,
+                    capsule: capsule.name.clone(),
+                    file: self.relative(&capsule.file_path),
+                    category,
+                    effort_hours,
+                    impact,
+                    priority_score: (impact_score / effort_hours * 100.0).round() / 100.0,
+                    depends_on: Vec::new(),
+                    capsule_id: id,
+                })
+            })
+            .collect();
+
+        items.sort_by(|a, b| {
+            b.priority_score
+                .partial_cmp(&a.priority_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.capsule.cmp(&b.capsule))
+        });
+        // Порядок бэклога: по priority_score, но зависимости — раньше зависящих задач
+        let depends = self.link_dependencies(graph, &items);
+        let order = dependency_order(items.len(), &depends);
+        let mut position = vec![0; items.len()];
+        for (pos, &i) in order.iter().enumerate() {
+            position[i] = pos;
+        }
+        for (i, item) in items.iter_mut().enumerate() {
+            item.id = format!("R{}", position[i] + 1);
+        }
+        for (from, targets) in &depends {
+            let mut ids: Vec<usize> = targets.iter().map(|t| position[*t] + 1).collect();
+            ids.sort();
+            items[*from].depends_on = ids.iter().map(|n| format!("R{}", n)).collect();
+        }
+        let mut slots: Vec<Option<RemediationItem>> = items.into_iter().map(Some).collect();
+        order.iter().filter_map(|&i| slots[i].take()).collect()
+    }
+
+    /// CSV для импорта в трекеры (Jira, Linear, GitHub Projects)
+    pub fn to_csv(&self, items: &[RemediationItem]) -> String {
+        let mut csv = String::from(BACKLOG_CSV_HEADER);
+        csv.push('\n');
+        for item in items {
+            let fields = [
+                item.id.clone(),
+                item.problem.clone(),
+                item.capsule.clone(),
+                item.file.clone(),
+                item.category.clone(),
+                format!("{:.1}", item.effort_hours),
+                format!("{:?}", item.effort_level()),
+                format!("{:?}", item.impact),
+                format!("{:.2}", item.priority_score),
+                item.depends_on.join(";"),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    fn estimate_hours(&self, category: &str, capsule: &Capsule, occurrences: usize) -> f32 {
+        let base = self
+            .base_hours
+            .get(category)
+            .copied()
+            .unwrap_or(self.default_hours);
+        // Крупные и сложные капсулы дороже править; повторы — дополнительная работа
+        let scale = 1.0 + capsule.complexity as f32 / 20.0 + capsule.size as f32 / 400.0;
+        let repeats = 1.0 + 0.25 * (occurrences.saturating_sub(1)) as f32;
+        ((base * scale.min(4.0) * repeats) * 2.0).round() / 2.0
+    }
+
+    /// Зависимости задач:
+    /// - задачи на капсулах цикла ждут задачу разрыва цикла;
+    /// - структурная задача на капсуле ждёт структурные задачи на капсулах,
+    ///   которые она использует;
+    /// - документирование капсулы — после прочих её исправлений
+    fn link_dependencies(
+        &self,
+        graph: &CapsuleGraph,
+        items: &[RemediationItem],
+    ) -> BTreeMap<usize, Vec<usize>> {
+        let mut by_capsule: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        for (i, item) in items.iter().enumerate() {
+            by_capsule.entry(item.capsule_id).or_default().push(i);
+        }
+        let mut edges: HashSet<(usize, usize)> = HashSet::new();
+
+        let mut in_cycle: HashSet<Uuid> = HashSet::new();
+        for cycle in CycleDetector::new().find_cycles(graph) {
+            in_cycle.extend(cycle.iter().copied());
+            let breaker = cycle.iter().find_map(|id| {
+                by_capsule
+                    .get(id)?
+                    .iter()
+                    .copied()
+                    .find(|i| items[*i].category == "cycles")
+            });
+            let Some(breaker) = breaker else {
+                continue;
+            };
+            for id in &cycle {
+                for &i in by_capsule.get(id).into_iter().flatten() {
+                    if i != breaker {
+                        edges.insert((i, breaker));
+                    }
+                }
+            }
+        }
+
+        for relation in &graph.relations {
+            // внутри цикла порядок задаёт задача разрыва цикла
+            if in_cycle.contains(&relation.from_id) && in_cycle.contains(&relation.to_id) {
+                continue;
+            }
+            let (Some(users), Some(used)) = (
+                by_capsule.get(&relation.from_id),
+                by_capsule.get(&relation.to_id),
+            ) else {
+                continue;
+            };
+            for &u in users {
+                for &d in used {
+                    if u != d
+                        && is_structural(&items[u].category)
+                        && is_structural(&items[d].category)
+                    {
+                        edges.insert((u, d));
+                    }
+                }
+            }
+        }
+
+        for indices in by_capsule.values() {
+            for &doc in indices
+                .iter()
+                .filter(|i| items[**i].category == "documentation")
+            {
+                for &other in indices.iter().filter(|i| **i != doc) {
+                    edges.insert((doc, other));
+                }
+            }
+        }
+
+        // Взаимные зависимости бессмысленны для планирования — убираем обе
+        let mut depends: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for &(from, to) in &edges {
+            if !edges.contains(&(to, from)) {
+                depends.entry(from).or_default().push(to);
+            }
+        }
+        depends
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        self.root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+impl Default for RemediationPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Топологический порядок задач; среди готовых — с меньшим индексом
+/// (т.е. с большим priority_score). Оставшиеся циклы разрываются по индексу
+fn dependency_order(len: usize, depends: &BTreeMap<usize, Vec<usize>>) -> Vec<usize> {
+    let mut placed = vec![false; len];
+    let mut order = Vec::with_capacity(len);
+    while order.len() < len {
+        let ready = (0..len).find(|&i| {
+            !placed[i]
+                && depends
+                    .get(&i)
+                    .is_none_or(|targets| targets.iter().all(|t| placed[*t]))
+        });
+        let next = ready.unwrap_or_else(|| (0..len).find(|&i| !placed[i]).unwrap_or(0));
+        placed[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+/// Исправления, меняющие интерфейс или структуру капсулы
+fn is_structural(category: &str) -> bool {
+    matches!(
+        category,
+        "complexity"
+            | "size"
+            | "cohesion"
+            | "solid"
+            | "coupling"
+            | "layers"
+            | "architecture"
+            | "api_quality"
+    )
+}
+
+fn fan_in(graph: &CapsuleGraph) -> HashMap<Uuid, usize> {
+    let mut fan_in = HashMap::new();
+    for relation in &graph.relations {
+        *fan_in.entry(relation.to_id).or_insert(0) += 1;
+    }
+    fan_in
+}
+
+fn priority_rank(priority: &Priority) -> u8 {
+    match priority {
+        Priority::Critical => 0,
+        Priority::High => 1,
+        Priority::Medium => 2,
+        Priority::Low => 3,
+    }
+}
+
+fn impact_weight(priority: &Priority) -> f32 {
+    match priority {
+        Priority::Critical => 4.0,
+        Priority::High => 3.0,
+        Priority::Medium => 2.0,
+        Priority::Low => 1.0,
+    }
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,89 @@
+// Анализ удалённого репозитория: вместо пути проекта передаётся git URL,
+// репозиторий клонируется без истории во временный каталог, который
+// удаляется вместе с `RemoteCheckout`
+
+use crate::types::{AnalysisError, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Префиксы адресов, которые считаются git-репозиториями
+const URL_SCHEMES: [&str; 5] = ["https://", "http://", "ssh://", "git://", "file://"];
+
+/// Клоны, ещё живые в процессе: `process::exit` не вызывает `drop`
+static ACTIVE: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Похож ли аргумент на адрес git-репозитория, а не на локальный путь
+pub fn is_git_url(value: &str) -> bool {
+    if URL_SCHEMES.iter().any(|scheme| value.starts_with(scheme)) {
+        return true;
+    }
+    // scp-синтаксис: git@github.com:owner/repo.git
+    match value.split_once(':') {
+        Some((host, path)) => {
+            host.contains('@')
+                && !host.contains('/')
+                && !path.is_empty()
+                && !Path::new(value).exists()
+        }
+        None => false,
+    }
+}
+
+/// Неглубокий клон во временном каталоге; каталог удаляется при `drop`
+#[derive(Debug)]
+pub struct RemoteCheckout {
+    url: String,
+    path: PathBuf,
+}
+
+impl RemoteCheckout {
+    /// `git clone --depth 1` во временный каталог
+    pub fn clone(url: &str) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("archlens-remote-{}", uuid::Uuid::new_v4()));
+        let output = Command::new("git")
+            .args(["clone", "--depth", "1", "--quiet", "--"])
+            .arg(url)
+            .arg(&path)
+            .output()?;
+        if !output.status.success() {
+            let _ = std::fs::remove_dir_all(&path);
+            return Err(AnalysisError::GenericError(format!(
+                "git clone {}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        active().push(path.clone());
+        Ok(Self {
+            url: url.to_string(),
+            path,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RemoteCheckout {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+        active().retain(|path| path != &self.path);
+    }
+}
+
+/// Удаляет все клоны процесса; вызывается перед `process::exit`
+pub fn remove_checkouts() {
+    for path in active().drain(..) {
+        let _ = std::fs::remove_dir_all(path);
+    }
+}
+
+fn active() -> std::sync::MutexGuard<'static, Vec<PathBuf>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
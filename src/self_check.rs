@@ -0,0 +1,149 @@
+// Проверка сборки на собственном коде ("dogfood"): ArchLens анализирует свои
+// исходники и сверяет результат с закреплёнными ожиданиями — диапазоном числа
+// капсул и списком известных групп циклов. Новая группа циклов или выход
+// числа капсул за диапазон означает регрессию анализатора или архитектуры.
+// Граф строится без структурных связей (общая директория или слой), которые
+// добавляет export: циклов они не образуют, а на собственных исходниках
+// связывают почти все пары капсул и замедляют проверку в десятки раз. Поэтому
+// число связей здесь меньше, чем в export, и сверяется с отдельным диапазоном
+
+use crate::graph::CyclesDetail;
+use crate::types::CapsuleGraph;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Закреплённые ожидания для исходников ArchLens
+const PINNED_EXPECTATIONS: &str = include_str!("self_check.json");
+
+/// Ожидаемые результаты анализа
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfCheckExpectations {
+    pub min_capsules: usize,
+    pub max_capsules: usize,
+    /// Диапазон числа связей графа без структурных
+    pub min_relations: usize,
+    pub max_relations: usize,
+    /// Известные группы циклов: отсортированные имена капсул группы
+    #[serde(default)]
+    pub known_cycles: Vec<Vec<String>>,
+}
+
+impl SelfCheckExpectations {
+    /// Ожидания, закреплённые для исходников ArchLens
+    pub fn pinned() -> Self {
+        serde_json::from_str(PINNED_EXPECTATIONS).expect("self_check.json должен быть корректным")
+    }
+}
+
+/// Исходники ArchLens, из которых собран бинарник; `None`, если их нет
+/// на этой машине (например, у установленного пакета)
+pub fn own_source_dir() -> Option<PathBuf> {
+    let dir = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src"));
+    dir.is_dir().then_some(dir)
+}
+
+/// Результат сверки графа с ожиданиями
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelfCheckReport {
+    pub capsules: usize,
+    pub min_capsules: usize,
+    pub max_capsules: usize,
+    pub relations: usize,
+    pub min_relations: usize,
+    pub max_relations: usize,
+    pub cycle_groups: usize,
+    /// Группы циклов, которых нет среди известных
+    pub new_cycles: Vec<Vec<String>>,
+    pub failures: Vec<String>,
+}
+
+impl SelfCheckReport {
+    pub fn evaluate(graph: &CapsuleGraph, expected: &SelfCheckExpectations) -> Self {
+        let capsules = graph.capsules.len();
+        let relations = graph.relations.len();
+        let groups: Vec<Vec<String>> = CyclesDetail::from_graph(graph)
+            .map(|detail| {
+                detail
+                    .groups
+                    .into_iter()
+                    .map(|g| {
+                        let mut names: Vec<String> =
+                            g.members.into_iter().map(|m| m.name).collect();
+                        names.sort();
+                        names
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let known: Vec<Vec<String>> = expected
+            .known_cycles
+            .iter()
+            .map(|names| {
+                let mut names = names.clone();
+                names.sort();
+                names
+            })
+            .collect();
+        let new_cycles: Vec<Vec<String>> = groups
+            .iter()
+            .filter(|g| !known.contains(g))
+            .cloned()
+            .collect();
+
+        let mut failures = Vec::new();
+        if capsules < expected.min_capsules || capsules > expected.max_capsules {
+            failures.push(format!(
+                "число капсул {} вне диапазона {}..={}",
+                capsules, expected.min_capsules, expected.max_capsules
+            ));
+        }
+        if relations < expected.min_relations || relations > expected.max_relations {
+            failures.push(format!(
+                "число связей {} вне диапазона {}..={}",
+                relations, expected.min_relations, expected.max_relations
+            ));
+        }
+        for cycle in &new_cycles {
+            failures.push(format!("новый цикл: {}", cycle.join(", ")));
+        }
+
+        Self {
+            capsules,
+            min_capsules: expected.min_capsules,
+            max_capsules: expected.max_capsules,
+            relations,
+            min_relations: expected.min_relations,
+            max_relations: expected.max_relations,
+            cycle_groups: groups.len(),
+            new_cycles,
+            failures,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Текстовая сводка для терминала
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Капсул: {} (ожидается {}..={})\nСвязей: {} (ожидается {}..={})\nГрупп циклов: {} (новых: {})\n",
+            self.capsules,
+            self.min_capsules,
+            self.max_capsules,
+            self.relations,
+            self.min_relations,
+            self.max_relations,
+            self.cycle_groups,
+            self.new_cycles.len()
+        );
+        if self.passed() {
+            out.push_str("✅ Самопроверка пройдена\n");
+        } else {
+            for failure in &self.failures {
+                out.push_str(&format!("❌ {}\n", failure));
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,35 @@
+// Стабильный некриптографический хеш (FNV-1a 64) для ключей кешей, отпечатков
+// и цепочки журнала аудита: результат не зависит от версии Rust и платформы
+
+/// FNV-1a 64: стабилен между версиями Rust, в отличие от `DefaultHasher`;
+/// общий для ключей кешей и отпечатков, сохраняемых на диск
+pub struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fnv {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    /// Строка с разделителем, чтобы `("ab", "c")` и `("a", "bc")` различались
+    pub fn write_str(&mut self, value: &str) {
+        self.write(value.as_bytes());
+        self.write(&[0xff]);
+    }
+
+    pub fn hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
@@ -0,0 +1,604 @@
+// Обнаружение конечных автоматов: enum состояний с переходами в match/switch
+// и конфигурации XState; диаграммы состояний в Mermaid stateDiagram-v2
+
+use crate::types::{Capsule, CapsuleGraph};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StateMachineKind {
+    /// enum состояний + переходы в match (Rust) или switch (TS/JS)
+    EnumMatch,
+    XState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StateTransition {
+    pub from: String,
+    pub to: String,
+    pub event: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateMachine {
+    pub name: String,
+    pub kind: StateMachineKind,
+    pub file: PathBuf,
+    /// Строка объявления enum или вызова createMachine (с 1)
+    pub line: usize,
+    pub states: Vec<String>,
+    pub initial: Option<String>,
+    pub transitions: Vec<StateTransition>,
+}
+
+impl StateMachine {
+    /// Mermaid stateDiagram-v2; конечные — состояния без исходящих переходов
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("stateDiagram-v2\n");
+        if let Some(initial) = &self.initial {
+            out.push_str(&format!("    [*] --> {}\n", state_id(initial)));
+        }
+        for transition in &self.transitions {
+            out.push_str(&format!(
+                "    {} --> {}",
+                state_id(&transition.from),
+                state_id(&transition.to)
+            ));
+            if let Some(event) = &transition.event {
+                out.push_str(&format!(" : {}", event));
+            }
+            out.push('\n');
+        }
+        for state in self.final_states() {
+            out.push_str(&format!("    {} --> [*]\n", state_id(&state)));
+        }
+        out
+    }
+
+    pub fn final_states(&self) -> Vec<String> {
+        self.states
+            .iter()
+            .filter(|s| {
+                self.transitions.iter().any(|t| &t.to == *s)
+                    && !self.transitions.iter().any(|t| &t.from == *s)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Детектор автоматов по исходникам капсул
+#[derive(Debug)]
+pub struct StateMachineDetector {
+    /// Минимум переходов между разными состояниями
+    pub min_transitions: usize,
+}
+
+impl StateMachineDetector {
+    pub fn new() -> Self {
+        Self { min_transitions: 1 }
+    }
+
+    /// Автоматы, сгруппированные по капсуле, в которой они объявлены
+    pub fn detect(&self, graph: &CapsuleGraph) -> HashMap<Uuid, Vec<StateMachine>> {
+        let mut by_file: HashMap<&Path, Vec<&Capsule>> = HashMap::new();
+        for capsule in graph.capsules.values() {
+            by_file
+                .entry(capsule.file_path.as_path())
+                .or_default()
+                .push(capsule);
+        }
+
+        let mut result: HashMap<Uuid, Vec<StateMachine>> = HashMap::new();
+        for (file, capsules) in by_file {
+            let Ok(content) = fs::read_to_string(file) else {
+                continue;
+            };
+            for machine in self.detect_in_source(file, &content) {
+                if let Some(owner) = owning_capsule(&capsules, machine.line) {
+                    result.entry(owner).or_default().push(machine);
+                }
+            }
+        }
+        for machines in result.values_mut() {
+            machines.sort_by_key(|m| m.line);
+        }
+        result
+    }
+
+    pub fn detect_in_source(&self, file: &Path, content: &str) -> Vec<StateMachine> {
+        let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let mut machines = match ext {
+            "rs" => enum_machines(file, content, Syntax::Rust),
+            "ts" | "tsx" | "js" | "jsx" | "mjs" => {
+                let mut found = enum_machines(file, content, Syntax::Switch);
+                found.extend(xstate_machines(file, content));
+                found
+            }
+            _ => Vec::new(),
+        };
+        machines.retain(|m| {
+            m.transitions.iter().filter(|t| t.from != t.to).count() >= self.min_transitions
+        });
+        machines
+    }
+}
+
+impl Default for StateMachineDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Наименьшая капсула, содержащая строку; иначе — первая капсула файла
+fn owning_capsule(capsules: &[&Capsule], line: usize) -> Option<Uuid> {
+    capsules
+        .iter()
+        .filter(|c| c.line_start <= line && line <= c.line_end)
+        .min_by_key(|c| (c.line_end - c.line_start, c.line_start))
+        .or_else(|| capsules.iter().min_by_key(|c| c.line_start))
+        .map(|c| c.id)
+}
+
+// ============================================================================
+// ENUM + MATCH / SWITCH
+// ============================================================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Syntax {
+    /// `State::A => ...`
+    Rust,
+    /// `case State.A: ...`
+    Switch,
+}
+
+struct StateEnum {
+    name: String,
+    line: usize,
+    variants: Vec<String>,
+    default: Option<String>,
+}
+
+fn enum_machines(file: &Path, content: &str, syntax: Syntax) -> Vec<StateMachine> {
+    let enums = find_enums(content, syntax);
+    if enums.is_empty() {
+        return Vec::new();
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    let arms = match syntax {
+        Syntax::Rust => rust_arms(&lines),
+        Syntax::Switch => switch_arms(&lines),
+    };
+    let separator = if syntax == Syntax::Rust { "::" } else { "." };
+
+    let mut machines = Vec::new();
+    for state_enum in enums {
+        let reference = Regex::new(&format!(
+            r"\b({}|Self){}(\w+)",
+            regex::escape(&state_enum.name),
+            regex::escape(separator)
+        ))
+        .unwrap();
+        let variants_in = |text: &str, impl_target: Option<&str>| -> Vec<String> {
+            reference
+                .captures_iter(text)
+                .filter(|c| &c[1] != "Self" || impl_target == Some(state_enum.name.as_str()))
+                .map(|c| c[2].to_string())
+                .filter(|v| state_enum.variants.contains(v))
+                .collect()
+        };
+
+        let mut transitions = BTreeSet::new();
+        for arm in &arms {
+            let from = variants_in(&arm.pattern, arm.impl_target.as_deref());
+            if from.is_empty() {
+                continue;
+            }
+            let to = variants_in(&arm.body, arm.impl_target.as_deref());
+            // событие — вариант другого enum в том же шаблоне: (State::Idle, Event::Start)
+            let event = arm
+                .event_pattern(&state_enum.name, separator)
+                .filter(|e| !state_enum.variants.contains(e));
+            for from in &from {
+                for to in &to {
+                    transitions.insert(StateTransition {
+                        from: from.clone(),
+                        to: to.clone(),
+                        event: event.clone(),
+                    });
+                }
+            }
+        }
+        if transitions.is_empty() {
+            continue;
+        }
+        // начальное: #[default], иначе исток без входящих переходов,
+        // иначе первый объявленный вариант с исходящими переходами
+        let has_outgoing = |v: &String| transitions.iter().any(|t| &t.from == v);
+        let initial = state_enum
+            .default
+            .clone()
+            .or_else(|| {
+                state_enum
+                    .variants
+                    .iter()
+                    .find(|v| {
+                        has_outgoing(v) && !transitions.iter().any(|t| &t.to == *v && &t.from != *v)
+                    })
+                    .cloned()
+            })
+            .or_else(|| {
+                state_enum
+                    .variants
+                    .iter()
+                    .find(|v| has_outgoing(v))
+                    .cloned()
+            });
+        machines.push(StateMachine {
+            name: state_enum.name,
+            kind: StateMachineKind::EnumMatch,
+            file: file.to_path_buf(),
+            line: state_enum.line,
+            states: state_enum.variants,
+            initial,
+            transitions: transitions.into_iter().collect(),
+        });
+    }
+    machines
+}
+
+fn find_enums(content: &str, syntax: Syntax) -> Vec<StateEnum> {
+    let decl = match syntax {
+        Syntax::Rust => Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?enum\s+(\w+)\s*\{").unwrap(),
+        Syntax::Switch => {
+            Regex::new(r"^\s*(?:export\s+)?(?:declare\s+)?(?:const\s+)?enum\s+(\w+)\s*\{").unwrap()
+        }
+    };
+    let variant = Regex::new(r"^\s*([A-Za-z_]\w*)").unwrap();
+    let default_impl = Regex::new(r"impl\s+Default\s+for\s+(\w+)").unwrap();
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut enums = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(caps) = decl.captures(lines[i]) else {
+            i += 1;
+            continue;
+        };
+        let mut state = StateEnum {
+            name: caps[1].to_string(),
+            line: i + 1,
+            variants: Vec::new(),
+            default: None,
+        };
+        let mut depth = brace_delta(lines[i]);
+        let mut default_next = false;
+        // однострочный enum: enum E { A, B }
+        let inline = lines[i].split_once('{').map(|(_, rest)| rest).unwrap_or("");
+        let mut body: Vec<&str> = inline.split(',').collect();
+        i += 1;
+        while depth > 0 && i < lines.len() {
+            // варианты только на первом уровне тела
+            if depth == 1 {
+                body.push(lines[i]);
+            }
+            depth += brace_delta(lines[i]);
+            i += 1;
+        }
+        for part in body {
+            let part = part.trim().trim_end_matches('}');
+            if part.starts_with("#[default]") {
+                default_next = true;
+                continue;
+            }
+            if part.starts_with("//") || part.starts_with('#') || part.starts_with('}') {
+                continue;
+            }
+            if let Some(v) = variant.captures(part) {
+                let name = v[1].to_string();
+                if default_next {
+                    state.default = Some(name.clone());
+                    default_next = false;
+                }
+                if !state.variants.contains(&name) {
+                    state.variants.push(name);
+                }
+            }
+        }
+        if state.variants.len() >= 2 {
+            enums.push(state);
+        }
+    }
+
+    // impl Default { fn default() -> Self { State::Idle } }
+    if syntax == Syntax::Rust {
+        for caps in default_impl.captures_iter(content) {
+            let Some(state) = enums.iter_mut().find(|e| e.name == caps[1]) else {
+                continue;
+            };
+            let start = caps.get(0).unwrap().end();
+            let body: String = content[start..].chars().take(400).collect();
+            let variant_ref =
+                Regex::new(&format!(r"(?:{}|Self)::(\w+)", regex::escape(&state.name))).unwrap();
+            if let Some(v) = variant_ref.captures(&body) {
+                if state.variants.contains(&v[1].to_string()) && state.default.is_none() {
+                    state.default = Some(v[1].to_string());
+                }
+            }
+        }
+    }
+    enums
+}
+
+/// Ветвь match/switch: шаблон и тело
+struct Arm {
+    pattern: String,
+    body: String,
+    /// Тип из окружающего `impl X` — для разрешения `Self::`
+    impl_target: Option<String>,
+}
+
+impl Arm {
+    /// Вариант другого enum в шаблоне ветви (событие перехода)
+    fn event_pattern(&self, state_enum: &str, separator: &str) -> Option<String> {
+        let other = Regex::new(&format!(r"\b(\w+){}(\w+)", regex::escape(separator))).unwrap();
+        let event = other
+            .captures_iter(&self.pattern)
+            .find(|c| &c[1] != state_enum && &c[1] != "Self")
+            .map(|c| c[2].to_string());
+        event
+    }
+}
+
+fn rust_arms(lines: &[&str]) -> Vec<Arm> {
+    let arm = Regex::new(r"^\s*(.+?)\s*=>\s*(.*)$").unwrap();
+    let impl_decl = Regex::new(r"^\s*impl(?:<[^>]*>)?\s+(?:[\w:<>]+\s+for\s+)?(\w+)").unwrap();
+
+    let mut arms = Vec::new();
+    let mut impl_target: Option<(String, i32)> = None;
+    let mut depth = 0;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(caps) = impl_decl.captures(line) {
+            impl_target = Some((caps[1].to_string(), depth));
+        }
+        if let Some(caps) = arm.captures(line) {
+            let mut body = caps[2].to_string();
+            // многострочное тело ветви — до закрытия его блока
+            let mut balance = brace_delta(&body);
+            let mut j = i + 1;
+            while balance > 0 && j < lines.len() {
+                body.push('\n');
+                body.push_str(lines[j]);
+                balance += brace_delta(lines[j]);
+                j += 1;
+            }
+            arms.push(Arm {
+                pattern: caps[1].to_string(),
+                body,
+                impl_target: impl_target.as_ref().map(|(t, _)| t.clone()),
+            });
+        }
+        depth += brace_delta(line);
+        if matches!(&impl_target, Some((_, d)) if depth <= *d && line.contains('}')) {
+            impl_target = None;
+        }
+    }
+    arms
+}
+
+fn switch_arms(lines: &[&str]) -> Vec<Arm> {
+    let case = Regex::new(r"^\s*case\s+(.+?)\s*:(.*)$").unwrap();
+    let mut arms: Vec<Arm> = Vec::new();
+    let mut open: Option<Arm> = None;
+    for line in lines {
+        let trimmed = line.trim();
+        if let Some(caps) = case.captures(line) {
+            // case A: case B: — проваливание объединяет шаблоны
+            match open.as_mut() {
+                Some(arm) if arm.body.trim().is_empty() => {
+                    arm.pattern.push_str(" | ");
+                    arm.pattern.push_str(&caps[1]);
+                }
+                _ => {
+                    arms.extend(open.take());
+                    open = Some(Arm {
+                        pattern: caps[1].to_string(),
+                        body: String::new(),
+                        impl_target: None,
+                    });
+                }
+            }
+            if let Some(arm) = open.as_mut() {
+                arm.body.push_str(&caps[2]);
+            }
+        } else if trimmed.starts_with("default:") || trimmed.starts_with("switch") {
+            arms.extend(open.take());
+        } else if let Some(arm) = open.as_mut() {
+            arm.body.push('\n');
+            arm.body.push_str(trimmed);
+        }
+    }
+    arms.extend(open);
+    arms
+}
+
+// ============================================================================
+// XSTATE
+// ============================================================================
+
+fn xstate_machines(file: &Path, content: &str) -> Vec<StateMachine> {
+    let call = Regex::new(r"\b(?:createMachine|Machine)\s*\(").unwrap();
+    let mut machines = Vec::new();
+    for m in call.find_iter(content) {
+        let rest = &content[m.end()..];
+        let Some(open) = rest.find('{') else {
+            continue;
+        };
+        let Some(config) = balanced(&rest[open..]) else {
+            continue;
+        };
+        let entries = object_entries(config);
+        let value = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        let Some(states_body) = value("states") else {
+            continue;
+        };
+        let line = content[..m.start()].matches('\n').count() + 1;
+        let name = value("id")
+            .map(unquote)
+            .unwrap_or_else(|| format!("machine@{}", line));
+
+        let mut states = Vec::new();
+        let mut transitions = BTreeSet::new();
+        for (state, body) in object_entries(states_body) {
+            states.push(state.clone());
+            let state_entries = object_entries(&body);
+            for (key, handlers) in &state_entries {
+                match key.as_str() {
+                    "on" => {
+                        for (event, target) in object_entries(handlers) {
+                            for to in xstate_targets(&target) {
+                                transitions.insert(StateTransition {
+                                    from: state.clone(),
+                                    to,
+                                    event: Some(event.clone()),
+                                });
+                            }
+                        }
+                    }
+                    "always" | "after" | "onDone" | "invoke" => {
+                        for to in xstate_targets(handlers) {
+                            transitions.insert(StateTransition {
+                                from: state.clone(),
+                                to,
+                                event: Some(key.clone()),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        transitions.retain(|t: &StateTransition| states.contains(&t.to));
+        machines.push(StateMachine {
+            name,
+            kind: StateMachineKind::XState,
+            file: file.to_path_buf(),
+            line,
+            initial: value("initial").map(unquote),
+            states,
+            transitions: transitions.into_iter().collect(),
+        });
+    }
+    machines
+}
+
+/// Цели перехода: `'b'`, `{ target: 'b' }`, `[{ target: 'b', guard }, ...]`
+fn xstate_targets(value: &str) -> Vec<String> {
+    let target = Regex::new(r#"target\s*:\s*["'`]([^"'`]+)["'`]"#).unwrap();
+    let value = value.trim();
+    let raw: Vec<String> = if value.starts_with(['\'', '"', '`']) {
+        vec![unquote(value)]
+    } else {
+        target
+            .captures_iter(value)
+            .map(|c| c[1].to_string())
+            .collect()
+    };
+    // '#machine.state' и '.child' → имя состояния
+    raw.into_iter()
+        .map(|t| t.rsplit('.').next().unwrap_or(&t).to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Текст от открывающей скобки до парной (включительно)
+fn balanced(text: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for (i, ch) in text.char_indices() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(ch),
+            (None, '{' | '[' | '(') => depth += 1,
+            (None, '}' | ']' | ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Пары ключ/значение верхнего уровня объектного литерала `{ a: 1, 'b': {...} }`
+fn object_entries(object: &str) -> Vec<(String, String)> {
+    let inner = object
+        .trim()
+        .strip_prefix('{')
+        .and_then(|o| o.strip_suffix('}'))
+        .unwrap_or("");
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    let mut quote: Option<char> = None;
+    for (i, ch) in inner.char_indices() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(ch),
+            (None, '{' | '[' | '(') => depth += 1,
+            (None, '}' | ']' | ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&inner[start..]);
+
+    parts
+        .into_iter()
+        .filter_map(|part| {
+            let (key, value) = part.split_once(':')?;
+            let key = unquote(key.trim());
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return None;
+            }
+            Some((key, value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches(['\'', '"', '`']).to_string()
+}
+
+fn brace_delta(line: &str) -> i32 {
+    let code = line.split("//").next().unwrap_or(line);
+    code.matches('{').count() as i32 - code.matches('}').count() as i32
+}
+
+fn state_id(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
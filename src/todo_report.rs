@@ -0,0 +1,326 @@
+// Отчёт о незавершённой работе: маркеры TODO/FIXME/HACK/XXX в комментариях
+// привязываются к капсулам (самой вложенной, содержащей строку) и
+// агрегируются по слоям; возраст маркера берётся из `git blame`
+
+use crate::dsm::common_root;
+use crate::git::run_git;
+use crate::types::{Capsule, CapsuleGraph};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Ключ метаданных: маркеры капсулы (JSON-массив `TodoMarker`)
+pub const TODOS_KEY: &str = "todos";
+/// Сколько самых старых маркеров показывать в ai_compact
+const OLDEST_SHOWN: usize = 10;
+/// Слой капсул без назначенного слоя
+const UNASSIGNED_LAYER: &str = "Unassigned";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TodoKind {
+    Todo,
+    Fixme,
+    Hack,
+    Xxx,
+}
+
+impl TodoKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TodoKind::Todo => "TODO",
+            TodoKind::Fixme => "FIXME",
+            TodoKind::Hack => "HACK",
+            TodoKind::Xxx => "XXX",
+        }
+    }
+
+    fn parse(marker: &str) -> Option<Self> {
+        match marker {
+            "TODO" => Some(TodoKind::Todo),
+            "FIXME" => Some(TodoKind::Fixme),
+            "HACK" => Some(TodoKind::Hack),
+            "XXX" => Some(TodoKind::Xxx),
+            _ => None,
+        }
+    }
+}
+
+/// Маркер в исходнике
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TodoMarker {
+    pub line: usize,
+    pub kind: TodoKind,
+    pub text: String,
+    /// Время авторства строки по `git blame` (Unix, секунды)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authored_at: Option<i64>,
+}
+
+fn marker_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // маркер только в комментарии: `//`, `#`, `/*`, `*`, `--`, `<!--`
+        Regex::new(
+            r"(?://+|#|/\*+|^\s*\*|--|<!--)\s*@?(TODO|FIXME|HACK|XXX)\b(?:\([^)]*\))?:?\s*(.*)",
+        )
+        .unwrap()
+    })
+}
+
+/// Маркеры в содержимом файла (строки с 1)
+pub fn scan_markers(content: &str) -> Vec<TodoMarker> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let cap = marker_pattern().captures(line)?;
+            let text = cap[2]
+                .trim()
+                .trim_end_matches("*/")
+                .trim_end_matches("-->")
+                .trim();
+            Some(TodoMarker {
+                line: i + 1,
+                kind: TodoKind::parse(&cap[1])?,
+                text: text.to_string(),
+                authored_at: None,
+            })
+        })
+        .collect()
+}
+
+/// Разбор `git blame --line-porcelain`: время авторства каждой строки по порядку
+pub fn parse_blame_times(porcelain: &str) -> Vec<Option<i64>> {
+    let mut times = Vec::new();
+    let mut time = None;
+    for line in porcelain.lines() {
+        if let Some(value) = line.strip_prefix("author-time ") {
+            time = value.trim().parse().ok();
+        } else if line.starts_with('\t') {
+            times.push(time);
+        }
+    }
+    times
+}
+
+/// Записывает маркеры в метаданные капсул; маркер достаётся самой вложенной
+/// капсуле, содержащей строку, а вне капсул — самой широкой капсуле файла.
+/// С `with_git` маркеры получают время авторства из `git blame` (один вызов на
+/// файл с маркерами). Возвращает число найденных маркеров
+pub fn annotate_todos(repo: &Path, capsules: &mut [Capsule], with_git: bool) -> usize {
+    let mut files: BTreeMap<PathBuf, Vec<usize>> = BTreeMap::new();
+    for (i, capsule) in capsules.iter().enumerate() {
+        files.entry(capsule.file_path.clone()).or_default().push(i);
+    }
+    let in_git = with_git && run_git(repo, &["rev-parse", "--is-inside-work-tree"]).is_ok();
+
+    let mut found = 0;
+    let mut assigned: HashMap<usize, Vec<TodoMarker>> = HashMap::new();
+    for (file, indices) in files {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let mut markers = scan_markers(&content);
+        if markers.is_empty() {
+            continue;
+        }
+        if in_git {
+            let path = file.to_string_lossy();
+            if let Ok(out) = run_git(repo, &["blame", "--line-porcelain", "--", path.as_ref()]) {
+                let times = parse_blame_times(&out);
+                for marker in &mut markers {
+                    marker.authored_at = times.get(marker.line - 1).copied().flatten();
+                }
+            }
+        }
+        for marker in markers {
+            let span = |i: &usize| {
+                let c = &capsules[*i];
+                c.line_end.max(c.line_start) - c.line_start
+            };
+            let owner = indices
+                .iter()
+                .filter(|i| {
+                    let c = &capsules[**i];
+                    (c.line_start..=c.line_end.max(c.line_start)).contains(&marker.line)
+                })
+                .min_by_key(|i| span(i))
+                .or_else(|| indices.iter().max_by_key(|i| span(i)));
+            if let Some(owner) = owner {
+                assigned.entry(*owner).or_default().push(marker);
+                found += 1;
+            }
+        }
+    }
+    for (index, markers) in assigned {
+        if let Ok(json) = serde_json::to_string(&markers) {
+            capsules[index].metadata.insert(TODOS_KEY.to_string(), json);
+        }
+    }
+    found
+}
+
+/// Маркеры капсулы из метаданных
+pub fn capsule_todos(capsule: &Capsule) -> Vec<TodoMarker> {
+    capsule
+        .metadata
+        .get(TODOS_KEY)
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Строка отчёта: маркер с капсулой, слоем и возрастом
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TodoItem {
+    pub kind: TodoKind,
+    pub capsule: String,
+    pub layer: String,
+    pub file: PathBuf,
+    pub line: usize,
+    pub text: String,
+    /// Дней с момента авторства строки; `None` без git-истории
+    pub age_days: Option<i64>,
+}
+
+/// Маркеры одного слоя по видам
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LayerTodos {
+    pub layer: String,
+    pub total: usize,
+    pub by_kind: BTreeMap<TodoKind, usize>,
+    /// Возраст самого старого маркера слоя, дней
+    pub oldest_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TodoReport {
+    /// От самых старых; маркеры без возраста — в конце, по файлу и строке
+    pub items: Vec<TodoItem>,
+    /// Слои по убыванию числа маркеров
+    pub layers: Vec<LayerTodos>,
+}
+
+impl TodoReport {
+    /// Отчёт по размеченному графу; `None`, если маркеров нет
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        Self::from_graph_at(graph, chrono::Utc::now().timestamp())
+    }
+
+    /// Отчёт с возрастом относительно момента `now` (Unix, секунды)
+    pub fn from_graph_at(graph: &CapsuleGraph, now: i64) -> Option<Self> {
+        let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        let mut items: Vec<TodoItem> = graph
+            .capsules
+            .values()
+            .flat_map(|capsule| {
+                let file = capsule
+                    .file_path
+                    .strip_prefix(&root)
+                    .unwrap_or(&capsule.file_path)
+                    .to_path_buf();
+                capsule_todos(capsule).into_iter().map(move |m| TodoItem {
+                    kind: m.kind,
+                    capsule: capsule.name.clone(),
+                    layer: capsule
+                        .layer
+                        .clone()
+                        .unwrap_or_else(|| UNASSIGNED_LAYER.to_string()),
+                    file: file.clone(),
+                    line: m.line,
+                    text: m.text,
+                    age_days: m.authored_at.map(|t| (now - t).max(0) / 86_400),
+                })
+            })
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+        items.sort_by(|a, b| {
+            b.age_days
+                .cmp(&a.age_days)
+                .then_with(|| (&a.file, a.line).cmp(&(&b.file, b.line)))
+        });
+
+        let mut layers: BTreeMap<&str, LayerTodos> = BTreeMap::new();
+        for item in &items {
+            let layer = layers
+                .entry(item.layer.as_str())
+                .or_insert_with(|| LayerTodos {
+                    layer: item.layer.clone(),
+                    ..LayerTodos::default()
+                });
+            layer.total += 1;
+            *layer.by_kind.entry(item.kind).or_insert(0) += 1;
+            layer.oldest_days = layer.oldest_days.max(item.age_days);
+        }
+        let mut layers: Vec<LayerTodos> = layers.into_values().collect();
+        layers.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.layer.cmp(&b.layer)));
+        Some(Self { items, layers })
+    }
+
+    /// Раздел ai_compact: счётчики по слоям и самые старые маркеры
+    pub fn compact_section(&self) -> String {
+        let mut out = format!("\n## TODO/FIXME Debt ({} markers)\n", self.items.len());
+        for layer in &self.layers {
+            out.push_str(&format!(
+                "- layer {} : {}{}\n",
+                layer.layer,
+                kinds_summary(&layer.by_kind),
+                layer
+                    .oldest_days
+                    .map(|d| format!(", oldest {d} days"))
+                    .unwrap_or_default()
+            ));
+        }
+        for item in self.items.iter().take(OLDEST_SHOWN) {
+            out.push_str(&format!("- {}\n", item_line(item)));
+        }
+        out
+    }
+}
+
+fn kinds_summary(by_kind: &BTreeMap<TodoKind, usize>) -> String {
+    by_kind
+        .iter()
+        .map(|(kind, n)| format!("{} {}", kind.as_str(), n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn item_line(item: &TodoItem) -> String {
+    format!(
+        "[{}] {} — {}:{}{}: {}",
+        item.kind.as_str(),
+        item.capsule,
+        item.file.display(),
+        item.line,
+        item.age_days
+            .map(|d| format!(" ({d} days)"))
+            .unwrap_or_default(),
+        item.text
+    )
+}
+
+/// Текстовый отчёт `archlens todos`
+pub fn todos_to_text(report: Option<&TodoReport>) -> String {
+    let Some(report) = report else {
+        return "Маркеров TODO/FIXME не найдено\n".to_string();
+    };
+    let mut out = format!("TODO/FIXME: {} markers\n\nBy layer:\n", report.items.len());
+    for layer in &report.layers {
+        out.push_str(&format!(
+            "  {} — {} ({})\n",
+            layer.layer,
+            layer.total,
+            kinds_summary(&layer.by_kind)
+        ));
+    }
+    out.push_str("\nMarkers:\n");
+    for item in &report.items {
+        out.push_str(&format!("  {}\n", item_line(item)));
+    }
+    out
+}
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Основные типы файлов для анализа
@@ -16,6 +16,8 @@ pub enum FileType {
     Go,
     Cpp,
     C,
+    Php,
+    Ruby,
     Other(String),
 }
 
@@ -90,6 +92,7 @@ pub struct Capsule {
     pub status: CapsuleStatus,
     pub priority: Priority,
     pub tags: Vec<String>,
+    #[serde(serialize_with = "serialize_sorted")]
     pub metadata: HashMap<String, String>,
     pub quality_score: f64,
     pub slogan: Option<String>,
@@ -97,40 +100,121 @@ pub struct Capsule {
     pub created_at: Option<String>,
 }
 
+/// Пространство имён UUIDv5 для идентификаторов капсул
+pub const CAPSULE_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6a3f_51c2_8e0d_4b7a_9c14_2d5e_7f80_a1b3);
+
+/// Детерминированный идентификатор капсулы: UUIDv5 пути, имени и типа.
+/// Не меняется между запусками, поэтому снимки, диффы и внешние ссылки
+/// сопоставляют капсулы по `id`. Путь — от корня проекта: клоны в разных
+/// каталогах дают одинаковые идентификаторы
+pub fn stable_capsule_id(file_path: &Path, name: &str, capsule_type: CapsuleType) -> Uuid {
+    let path = file_path.to_string_lossy().replace('\\', "/");
+    let key = format!("{path}\0{capsule_type:?}\0{name}");
+    Uuid::new_v5(&CAPSULE_ID_NAMESPACE, key.as_bytes())
+}
+
 /// Связь между капсулами
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapsuleRelation {
     pub from_id: Uuid,
     pub to_id: Uuid,
     pub relation_type: RelationType,
-    pub strength: f32, // сила связи 0.0-1.0
+    pub strength: f32, // сила (вес) связи 0.0-1.0
     pub description: Option<String>,
 }
 
 /// Типы связей между капсулами
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RelationType {
     Depends,    // зависимость
     Uses,       // использование
-    Implements, // реализация
-    Extends,    // наследование
+    Implements, // реализация трейта/интерфейса
+    Extends,    // расширение интерфейса (супертрейт, interface extends)
     Aggregates, // агрегация
-    Composes,   // композиция
+    Composes,   // композиция (поле другого типа)
     Calls,      // вызов
     References, // ссылка
+    Inherits,   // наследование класса
+    Reads,      // чтение константы/глобального состояния
+    Writes,     // запись глобального состояния
+    Emits,      // отправка события/сообщения
+    Handles,    // обработка события/сообщения
+    Imports,    // импорт символов другого модуля
+    Contains,   // вложенность: тип содержит метод, модуль — функцию
+}
+
+impl RelationType {
+    pub const ALL: [RelationType; 15] = [
+        Self::Depends,
+        Self::Uses,
+        Self::Implements,
+        Self::Extends,
+        Self::Aggregates,
+        Self::Composes,
+        Self::Calls,
+        Self::References,
+        Self::Inherits,
+        Self::Reads,
+        Self::Writes,
+        Self::Emits,
+        Self::Handles,
+        Self::Imports,
+        Self::Contains,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Depends => "depends",
+            Self::Uses => "uses",
+            Self::Implements => "implements",
+            Self::Extends => "extends",
+            Self::Aggregates => "aggregates",
+            Self::Composes => "composes",
+            Self::Calls => "calls",
+            Self::References => "references",
+            Self::Inherits => "inherits",
+            Self::Reads => "reads",
+            Self::Writes => "writes",
+            Self::Emits => "emits",
+            Self::Handles => "handles",
+            Self::Imports => "imports",
+            Self::Contains => "contains",
+        }
+    }
+
+    /// Структурные связи (общий каталог/слой) в отличие от связей, найденных
+    /// в коде: импортов, вызовов, наследования, вложенности, доступа к данным, событий
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Depends | Self::Uses | Self::References)
+    }
 }
 
 /// Граф капсул
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapsuleGraph {
+    #[serde(serialize_with = "serialize_sorted")]
     pub capsules: HashMap<Uuid, Capsule>,
     pub relations: Vec<CapsuleRelation>,
+    #[serde(serialize_with = "serialize_sorted")]
     pub layers: HashMap<String, Vec<Uuid>>,
     pub metrics: GraphMetrics,
     pub created_at: DateTime<Utc>,
     pub previous_analysis: Option<Box<ComparisonSnapshot>>, // Для дифф-анализа
 }
 
+/// Хеш-таблицы пишутся в порядке ключей: одинаковый граф — одинаковый JSON
+fn serialize_sorted<K, V, S>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: serde::Serializer,
+{
+    serializer.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
+}
+
 /// Снимок предыдущего анализа для сравнения
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComparisonSnapshot {
@@ -145,7 +229,7 @@ pub struct ComparisonSnapshot {
 }
 
 /// Метрики графа
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GraphMetrics {
     pub total_capsules: usize,
     pub total_relations: usize,
@@ -154,6 +238,10 @@ pub struct GraphMetrics {
     pub cohesion_index: f32,
     pub cyclomatic_complexity: u32,
     pub depth_levels: u32,
+    /// Индекс нарушений слоёв: 0 — слои соблюдены, 1 — все межслойные
+    /// зависимости идут снизу вверх через всю иерархию
+    #[serde(default)]
+    pub layering_violation_index: f32,
 }
 
 /// Результат анализа
@@ -188,6 +276,26 @@ pub enum ExportFormat {
     ChainOfThought,
     LLMPrompt,
     AICompact,
+    EmailSummary,
+}
+
+impl ExportFormat {
+    /// Имя формата в `exporter::ExporterRegistry`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::JSON => "json",
+            Self::YAML => "yaml",
+            Self::Mermaid => "mermaid",
+            Self::DOT => "dot",
+            Self::GraphML => "graphml",
+            Self::SVG => "svg",
+            Self::InteractiveHTML => "interactive-html",
+            Self::ChainOfThought => "chain-of-thought",
+            Self::LLMPrompt => "llm-prompt",
+            Self::AICompact => "ai-compact",
+            Self::EmailSummary => "email-summary",
+        }
+    }
 }
 
 /// Конфигурация анализа
@@ -11,6 +11,10 @@ impl CohesionValidator {
         Self { threshold: 0.3 }
     }
 
+    pub fn with_threshold(threshold: f32) -> Self {
+        Self { threshold }
+    }
+
     pub fn validate(
         &self,
         graph: &CapsuleGraph,
@@ -12,6 +12,10 @@ impl ComplexityValidator {
         Self { max_threshold: 15 }
     }
 
+    pub fn with_threshold(max_threshold: u32) -> Self {
+        Self { max_threshold }
+    }
+
     pub fn validate(
         &self,
         graph: &CapsuleGraph,
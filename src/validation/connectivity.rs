@@ -0,0 +1,65 @@
+use crate::graph::SinglePointsOfFailure;
+use crate::types::Result;
+use crate::types::*;
+
+/// Findings of each kind reported, largest cuts first
+const MAX_FINDINGS: usize = 10;
+
+/// Reports capsules and dependencies whose removal disconnects the dependency graph
+#[derive(Debug)]
+pub struct ConnectivityValidator;
+
+impl ConnectivityValidator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn validate(
+        &self,
+        graph: &CapsuleGraph,
+        warnings: &mut Vec<AnalysisWarning>,
+    ) -> Result<()> {
+        let Some(failures) = SinglePointsOfFailure::from_graph(graph) else {
+            return Ok(());
+        };
+
+        for point in failures.articulation_points.iter().take(MAX_FINDINGS) {
+            warnings.push(AnalysisWarning {
+                level: Priority::Medium,
+                message: format!(
+                    "Single point of failure: removing '{}' disconnects {} components",
+                    point.name, point.separated
+                ),
+                category: "connectivity".to_string(),
+                capsule_id: Some(point.capsule_id),
+                suggestion: Some(
+                    "Add an alternative path or split the component so the graph does not hinge on it"
+                        .to_string(),
+                ),
+            });
+        }
+        for bridge in failures.bridges.iter().take(MAX_FINDINGS) {
+            warnings.push(AnalysisWarning {
+                level: Priority::Low,
+                message: format!(
+                    "Bridge dependency '{}' -> '{}' is the only link to {} components",
+                    bridge.from, bridge.to, bridge.separated
+                ),
+                category: "connectivity".to_string(),
+                capsule_id: Some(bridge.from_id),
+                suggestion: Some(
+                    "Depend on an abstraction shared by both sides instead of a single link"
+                        .to_string(),
+                ),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ConnectivityValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,10 +4,30 @@ use crate::types::*;
 // use uuid::Uuid;
 
 use super::{
-    CohesionValidator, ComplexityValidator, CouplingValidator, CycleValidator, GraphOptimizer,
-    LayerValidator, NamingValidator, PatternDetector,
+    CohesionValidator, ComplexityValidator, ConnectivityValidator, CouplingValidator,
+    CycleValidator, GraphOptimizer, LayerValidator, NamingValidator, PatternDetector,
+    SolidAnalyzer, SolidPrinciple,
 };
 
+/// Validator thresholds that can be tuned without reparsing; defaults match `ValidatorOptimizer::new`
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ValidationThresholds {
+    pub max_complexity: u32,
+    pub coupling: f32,
+    pub cohesion: f32,
+}
+
+impl Default for ValidationThresholds {
+    fn default() -> Self {
+        Self {
+            max_complexity: 15,
+            coupling: 0.7,
+            cohesion: 0.3,
+        }
+    }
+}
+
 /// Main validator and optimizer for capsule graphs
 #[derive(Debug)]
 pub struct ValidatorOptimizer {
@@ -22,8 +42,10 @@ pub struct ValidatorOptimizer {
     cohesion_validator: CohesionValidator,
     pattern_detector: PatternDetector,
     cycle_validator: CycleValidator,
+    connectivity_validator: ConnectivityValidator,
     layer_validator: LayerValidator,
     naming_validator: NamingValidator,
+    solid_analyzers: Vec<SolidAnalyzer>,
     optimizer: GraphOptimizer,
 }
 
@@ -40,12 +62,31 @@ impl ValidatorOptimizer {
             cohesion_validator: CohesionValidator::new(),
             pattern_detector: PatternDetector::new(),
             cycle_validator: CycleValidator::new(),
+            connectivity_validator: ConnectivityValidator::new(),
             layer_validator: LayerValidator::new(),
             naming_validator: NamingValidator::new(),
+            solid_analyzers: vec![
+                SolidAnalyzer::new(SolidPrinciple::SingleResponsibility),
+                SolidAnalyzer::new(SolidPrinciple::InterfaceSegregation),
+                SolidAnalyzer::new(SolidPrinciple::DependencyInversion),
+            ],
             optimizer: GraphOptimizer::new(),
         }
     }
 
+    /// Validator with custom thresholds for complexity, coupling and cohesion checks
+    pub fn with_thresholds(thresholds: &ValidationThresholds) -> Self {
+        Self {
+            max_complexity_threshold: thresholds.max_complexity,
+            coupling_threshold: thresholds.coupling,
+            cohesion_threshold: thresholds.cohesion,
+            complexity_validator: ComplexityValidator::with_threshold(thresholds.max_complexity),
+            coupling_validator: CouplingValidator::with_threshold(thresholds.coupling),
+            cohesion_validator: CohesionValidator::with_threshold(thresholds.cohesion),
+            ..Self::new()
+        }
+    }
+
     /// Main validation and optimization entry point
     pub fn validate_and_optimize(&self, graph: &CapsuleGraph) -> Result<CapsuleGraph> {
         let mut optimized_graph = graph.clone();
@@ -60,12 +101,17 @@ impl ValidatorOptimizer {
             .validate(&optimized_graph, &mut warnings)?;
         self.cycle_validator
             .validate(&optimized_graph, &mut warnings)?;
+        self.connectivity_validator
+            .validate(&optimized_graph, &mut warnings)?;
         self.layer_validator
             .validate(&optimized_graph, &mut warnings)?;
         self.naming_validator
             .validate(&optimized_graph, &mut warnings)?;
         self.pattern_detector
             .validate(&optimized_graph, &mut warnings)?;
+        for analyzer in &self.solid_analyzers {
+            warnings.extend(analyzer.analyze_graph(&optimized_graph)?);
+        }
 
         // Optimize the graph
         self.optimizer.optimize(&mut optimized_graph)?;
@@ -13,6 +13,10 @@ impl CouplingValidator {
         Self { threshold: 0.7 }
     }
 
+    pub fn with_threshold(threshold: f32) -> Self {
+        Self { threshold }
+    }
+
     pub fn validate(
         &self,
         graph: &CapsuleGraph,
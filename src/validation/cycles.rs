@@ -1,6 +1,6 @@
 use crate::types::Result;
 use crate::types::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -42,12 +42,30 @@ impl CycleValidator {
         let mut cycles = Vec::new();
         let mut visited = HashSet::new();
 
-        for capsule_id in graph.capsules.keys() {
+        // Outgoing edges in relation order, so the walk does not rescan every relation
+        let mut outgoing: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for relation in &graph.relations {
+            outgoing
+                .entry(relation.from_id)
+                .or_default()
+                .push(relation.to_id);
+        }
+
+        // The cycles found depend on where the search starts: go in id order
+        let mut ids: Vec<Uuid> = graph.capsules.keys().copied().collect();
+        ids.sort();
+        for capsule_id in &ids {
             if !visited.contains(capsule_id) {
                 let mut rec_stack = HashSet::new();
                 let mut path = Vec::new();
 
-                if self.has_cycle_dfs(*capsule_id, graph, &mut visited, &mut rec_stack, &mut path) {
+                if self.has_cycle_dfs(
+                    *capsule_id,
+                    &outgoing,
+                    &mut visited,
+                    &mut rec_stack,
+                    &mut path,
+                ) {
                     cycles.push(path);
                 }
             }
@@ -60,7 +78,7 @@ impl CycleValidator {
     fn has_cycle_dfs(
         &self,
         capsule_id: Uuid,
-        graph: &CapsuleGraph,
+        outgoing: &HashMap<Uuid, Vec<Uuid>>,
         visited: &mut HashSet<Uuid>,
         rec_stack: &mut HashSet<Uuid>,
         path: &mut Vec<Uuid>,
@@ -69,17 +87,13 @@ impl CycleValidator {
         rec_stack.insert(capsule_id);
         path.push(capsule_id);
 
-        for relation in &graph.relations {
-            if relation.from_id == capsule_id {
-                let next_id = relation.to_id;
-
-                if !visited.contains(&next_id) {
-                    if self.has_cycle_dfs(next_id, graph, visited, rec_stack, path) {
-                        return true;
-                    }
-                } else if rec_stack.contains(&next_id) {
+        for &next_id in outgoing.get(&capsule_id).into_iter().flatten() {
+            if !visited.contains(&next_id) {
+                if self.has_cycle_dfs(next_id, outgoing, visited, rec_stack, path) {
                     return true;
                 }
+            } else if rec_stack.contains(&next_id) {
+                return true;
             }
         }
 
@@ -1,6 +1,11 @@
+use crate::external_deps::{EXTERNAL_PACKAGE_KEY, INFRASTRUCTURE_KEY};
 use crate::types::Result;
 use crate::types::*;
 use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Layer of domain code (models, entities, services)
+const DOMAIN_LAYER: &str = "Business";
 
 #[derive(Debug)]
 pub struct LayerValidator;
@@ -24,25 +29,112 @@ impl LayerValidator {
             ) {
                 if let (Some(from_layer), Some(to_layer)) = (&from_capsule.layer, &to_capsule.layer)
                 {
-                    if self.violates_layer_hierarchy(from_layer, to_layer, &hierarchy) {
+                    if let Some(distance) =
+                        self.violation_distance(from_layer, to_layer, &hierarchy)
+                    {
                         warnings.push(AnalysisWarning {
-                            level: Priority::Medium,
+                            level: violation_priority(distance),
                             message: format!(
                                 "Layer violation: {} -> {} (from {} to {})",
                                 from_capsule.name, to_capsule.name, from_layer, to_layer
                             ),
                             category: "layers".to_string(),
                             capsule_id: Some(from_capsule.id),
-                            suggestion: Some("Respect architectural layers".to_string()),
+                            suggestion: Some(if distance > 1 {
+                                format!(
+                                    "Respect architectural layers: the dependency crosses {} layers",
+                                    distance
+                                )
+                            } else {
+                                "Respect architectural layers".to_string()
+                            }),
                         });
                     }
                 }
             }
         }
+        self.validate_infrastructure_imports(graph, warnings);
 
         Ok(())
     }
 
+    /// Domain code importing an infrastructure library (database, HTTP,
+    /// message broker) directly instead of going through the Data or API layer
+    pub fn validate_infrastructure_imports(
+        &self,
+        graph: &CapsuleGraph,
+        warnings: &mut Vec<AnalysisWarning>,
+    ) {
+        for relation in &graph.relations {
+            let (Some(from_capsule), Some(package)) = (
+                graph.capsules.get(&relation.from_id),
+                graph.capsules.get(&relation.to_id),
+            ) else {
+                continue;
+            };
+            if from_capsule.layer.as_deref() != Some(DOMAIN_LAYER)
+                || !package.metadata.contains_key(EXTERNAL_PACKAGE_KEY)
+                || !package.metadata.contains_key(INFRASTRUCTURE_KEY)
+            {
+                continue;
+            }
+            warnings.push(AnalysisWarning {
+                level: Priority::Medium,
+                message: format!(
+                    "Layer violation: {} ({}) imports infrastructure library {} directly",
+                    from_capsule.name, DOMAIN_LAYER, package.name
+                ),
+                category: "layers".to_string(),
+                capsule_id: Some(from_capsule.id),
+                suggestion: Some(format!(
+                    "Hide {} behind an interface implemented in the Data or API layer",
+                    package.name
+                )),
+            });
+        }
+    }
+
+    /// Layering violation index: layers crossed by violating dependencies relative
+    /// to the worst case, where every dependency between two ranked layers points
+    /// from the bottom layer to the top one. 0 means the layering is respected
+    pub fn violation_index(
+        &self,
+        capsules: &HashMap<Uuid, Capsule>,
+        relations: &[CapsuleRelation],
+    ) -> f32 {
+        let hierarchy = self.get_layer_hierarchy();
+        let max_distance = hierarchy.len().saturating_sub(1);
+        let mut crossed = 0usize;
+        let mut worst = 0usize;
+        for relation in relations {
+            let (Some(from_layer), Some(to_layer)) = (
+                capsules
+                    .get(&relation.from_id)
+                    .and_then(|c| c.layer.as_deref()),
+                capsules
+                    .get(&relation.to_id)
+                    .and_then(|c| c.layer.as_deref()),
+            ) else {
+                continue;
+            };
+            if from_layer == to_layer
+                || !hierarchy.contains_key(from_layer)
+                || !hierarchy.contains_key(to_layer)
+            {
+                continue;
+            }
+            worst += max_distance;
+            crossed += self
+                .violation_distance(from_layer, to_layer, &hierarchy)
+                .unwrap_or(0);
+        }
+        if worst == 0 {
+            0.0
+        } else {
+            crossed as f32 / worst as f32
+        }
+    }
+
     fn get_layer_hierarchy(&self) -> HashMap<String, usize> {
         let mut hierarchy = HashMap::new();
         hierarchy.insert("UI".to_string(), 0);
@@ -53,19 +145,30 @@ impl LayerValidator {
         hierarchy
     }
 
-    fn violates_layer_hierarchy(
+    /// How many layers a dependency climbs against the hierarchy (Data -> UI
+    /// is worse than Data -> Business); `None` if it respects the hierarchy
+    /// or a layer is not ranked
+    fn violation_distance(
         &self,
         from_layer: &str,
         to_layer: &str,
         hierarchy: &HashMap<String, usize>,
-    ) -> bool {
-        if let (Some(&from_level), Some(&to_level)) =
+    ) -> Option<usize> {
+        let (Some(&from_level), Some(&to_level)) =
             (hierarchy.get(from_layer), hierarchy.get(to_layer))
-        {
-            from_level > to_level
-        } else {
-            false
-        }
+        else {
+            return None;
+        };
+        (from_level > to_level).then(|| from_level - to_level)
+    }
+}
+
+/// Priority of a violation grows with the number of layers it crosses
+fn violation_priority(distance: usize) -> Priority {
+    match distance {
+        0 | 1 => Priority::Medium,
+        2 => Priority::High,
+        _ => Priority::Critical,
     }
 }
 
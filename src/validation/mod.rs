@@ -1,5 +1,7 @@
 pub mod cohesion;
 pub mod complexity;
+/// Single points of failure of the dependency graph
+pub mod connectivity;
 /// Validation module - validates and optimizes capsule graphs
 pub mod core;
 pub mod coupling;
@@ -8,15 +10,28 @@ pub mod layers;
 pub mod naming;
 pub mod optimizer;
 pub mod patterns;
+/// Recomputing metrics and validator findings of a stored graph
+pub mod recompute;
 pub mod solid;
+/// Drift of each service from an organization-wide project template
+pub mod template;
 
 pub use cohesion::CohesionValidator;
 pub use complexity::ComplexityValidator;
-pub use core::ValidatorOptimizer;
+pub use connectivity::ConnectivityValidator;
+pub use core::{ValidationThresholds, ValidatorOptimizer};
 pub use coupling::CouplingValidator;
 pub use cycles::CycleValidator;
 pub use layers::LayerValidator;
 pub use naming::NamingValidator;
 pub use optimizer::GraphOptimizer;
-pub use patterns::{ArchitecturePatternDetector, PatternCriteria, PatternDetector};
-pub use solid::{SolidAnalyzer, SolidPrinciple};
+pub use patterns::{
+    ArchitecturePatternDetector, PatternConfig, PatternCriteria, PatternDetector, PatternMatch,
+    PatternScope,
+};
+pub use recompute::{recompute_graph, recompute_graph_json};
+pub use solid::{
+    ImplementorUsage, InterfaceUsage, InterfaceUsageReport, SolidAnalyzer, SolidPrinciple,
+    FAT_INTERFACE_RATIO,
+};
+pub use template::{ProjectTemplate, ServiceDrift, TemplateDriftReport, TemplateValidator};
@@ -16,8 +16,13 @@ impl GraphOptimizer {
     }
 
     fn optimize_relations(&self, graph: &mut CapsuleGraph) -> Result<()> {
-        // Remove duplicate relations
-        graph.relations.sort_by_key(|r| (r.from_id, r.to_id));
+        // Remove duplicate relations, keeping the strongest one per pair
+        // (typed relations like Implements win over structural References)
+        graph.relations.sort_by(|a, b| {
+            (a.from_id, a.to_id)
+                .cmp(&(b.from_id, b.to_id))
+                .then(b.strength.total_cmp(&a.strength))
+        });
         graph.relations.dedup_by_key(|r| (r.from_id, r.to_id));
         Ok(())
     }
@@ -1,30 +1,262 @@
 use crate::types::Result;
 use crate::types::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::LazyLock;
 
-#[derive(Debug, Clone)]
+const BUILTIN_PATTERNS: &str = include_str!("pattern_rules.json");
+
+/// A pattern fires when the weights of its satisfied criteria, relative to the
+/// total weight, reach `confidence_threshold`
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchitecturePatternDetector {
     pub pattern_name: String,
+    #[serde(default)]
+    pub scope: PatternScope,
     pub detection_criteria: Vec<PatternCriteria>,
     pub confidence_threshold: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
 }
 
-#[derive(Debug, Clone)]
+/// `matcher` compares one metric with a number, e.g. `interface_creators >= 2`
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternCriteria {
     pub name: String,
     pub weight: f32,
     pub matcher: String,
 }
 
+/// What a pattern is evaluated on: source files or capsules of the graph
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternScope {
+    #[default]
+    Source,
+    Capsule,
+}
+
+/// Metrics available to `Source` criteria
+pub const SOURCE_METRICS: &[&str] = &[
+    "creator_methods",
+    "interface_creators",
+    "interface_types",
+    "static_instances",
+    "instance_accessors",
+    "persistence_methods",
+    "repository_types",
+    "service_types",
+    "controller_types",
+    "route_handlers",
+];
+
+/// Metrics available to `Capsule` criteria
+pub const CAPSULE_METRICS: &[&str] = &["complexity", "size", "dependencies", "dependents"];
+
+fn default_true() -> bool {
+    true
+}
+
+/// Pattern definitions: built-in, or from `.archlens/patterns.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternConfig {
+    #[serde(default)]
+    pub patterns: Vec<ArchitecturePatternDetector>,
+}
+
+impl PatternConfig {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid pattern rules: {e}")))
+    }
+
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN_PATTERNS).expect("built-in pattern rules are valid")
+    }
+
+    /// `.archlens/patterns.json`; `Ok(None)` when the file is absent
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(".archlens").join("patterns.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content).map(Some)
+    }
+
+    /// Project patterns replace built-in ones with the same name
+    pub fn merged_with(mut self, overrides: PatternConfig) -> Self {
+        self.patterns.retain(|pattern| {
+            !overrides
+                .patterns
+                .iter()
+                .any(|o| o.pattern_name == pattern.pattern_name)
+        });
+        self.patterns.extend(overrides.patterns);
+        self
+    }
+}
+
+/// A detected pattern with the criteria that supported it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternMatch {
+    pub pattern_name: String,
+    pub confidence: f32,
+    pub evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+}
+
+#[derive(Debug, Clone)]
+struct Matcher {
+    metric: String,
+    comparison: Comparison,
+    value: f32,
+}
+
+impl Matcher {
+    fn parse(source: &str, scope: PatternScope) -> Option<Self> {
+        static MATCHER: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^\s*(\w+)\s*(>=|<=|==|>|<)\s*(-?\d+(?:\.\d+)?)\s*$").unwrap()
+        });
+        let caps = MATCHER.captures(source)?;
+        let metric = caps[1].to_string();
+        let known = match scope {
+            PatternScope::Source => SOURCE_METRICS,
+            PatternScope::Capsule => CAPSULE_METRICS,
+        };
+        if !known.contains(&metric.as_str()) {
+            return None;
+        }
+        let comparison = match &caps[2] {
+            ">" => Comparison::Greater,
+            ">=" => Comparison::GreaterOrEqual,
+            "<" => Comparison::Less,
+            "<=" => Comparison::LessOrEqual,
+            _ => Comparison::Equal,
+        };
+        Some(Self {
+            metric,
+            comparison,
+            value: caps[3].parse().ok()?,
+        })
+    }
+
+    fn matches(&self, actual: f32) -> bool {
+        match self.comparison {
+            Comparison::Greater => actual > self.value,
+            Comparison::GreaterOrEqual => actual >= self.value,
+            Comparison::Less => actual < self.value,
+            Comparison::LessOrEqual => actual <= self.value,
+            Comparison::Equal => (actual - self.value).abs() < f32::EPSILON,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledPattern {
+    spec: ArchitecturePatternDetector,
+    matchers: Vec<Matcher>,
+}
+
+impl CompiledPattern {
+    /// Confidence and evidence, if the pattern reaches its threshold
+    fn evaluate(&self, metrics: &HashMap<&str, f32>) -> Option<PatternMatch> {
+        let total: f32 = self.spec.detection_criteria.iter().map(|c| c.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut satisfied = 0.0;
+        let mut evidence = Vec::new();
+        for (criteria, matcher) in self.spec.detection_criteria.iter().zip(&self.matchers) {
+            let actual = metrics.get(matcher.metric.as_str()).copied().unwrap_or(0.0);
+            if matcher.matches(actual) {
+                satisfied += criteria.weight;
+                evidence.push(format!("{}: {}", criteria.name, actual));
+            }
+        }
+        let confidence = satisfied / total;
+        (confidence + f32::EPSILON >= self.spec.confidence_threshold).then(|| PatternMatch {
+            pattern_name: self.spec.pattern_name.clone(),
+            confidence: confidence.min(1.0),
+            evidence,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct PatternDetector {
-    detectors: Vec<ArchitecturePatternDetector>,
+    detectors: Vec<CompiledPattern>,
 }
 
 impl PatternDetector {
     pub fn new() -> Self {
-        Self {
-            detectors: Self::create_pattern_detectors(),
+        Self::from_config(&PatternConfig::builtin()).expect("built-in pattern rules compile")
+    }
+
+    pub fn from_config(config: &PatternConfig) -> Result<Self> {
+        let detectors = config
+            .patterns
+            .iter()
+            .filter(|spec| spec.enabled)
+            .map(|spec| {
+                if spec.detection_criteria.is_empty() {
+                    return Err(AnalysisError::ParsingError(format!(
+                        "Pattern '{}' has no detection criteria",
+                        spec.pattern_name
+                    )));
+                }
+                let matchers = spec
+                    .detection_criteria
+                    .iter()
+                    .map(|criteria| {
+                        Matcher::parse(&criteria.matcher, spec.scope).ok_or_else(|| {
+                            AnalysisError::ParsingError(format!(
+                                "Pattern '{}': invalid matcher '{}'",
+                                spec.pattern_name, criteria.matcher
+                            ))
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(CompiledPattern {
+                    spec: spec.clone(),
+                    matchers,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { detectors })
+    }
+
+    /// Built-in patterns merged with `.archlens/patterns.json`
+    pub fn for_project(project_root: &Path) -> Result<Self> {
+        let mut config = PatternConfig::builtin();
+        if let Some(overrides) = PatternConfig::load(project_root)? {
+            config = config.merged_with(overrides);
         }
+        Self::from_config(&config)
+    }
+
+    /// Source-scoped patterns supported by structural evidence in `content`
+    pub fn detect_in_source(&self, content: &str, file_type: &FileType) -> Vec<PatternMatch> {
+        let facts = SourceFacts::collect(content, file_type);
+        let metrics = facts.metrics();
+        self.detectors
+            .iter()
+            .filter(|d| d.spec.scope == PatternScope::Source)
+            .filter_map(|d| d.evaluate(&metrics))
+            .collect()
     }
 
     pub fn validate(
@@ -32,38 +264,34 @@ impl PatternDetector {
         graph: &CapsuleGraph,
         warnings: &mut Vec<AnalysisWarning>,
     ) -> Result<()> {
-        for detector in &self.detectors {
-            // Simplified pattern detection
-            if detector.pattern_name == "God Object" {
-                for capsule in graph.capsules.values() {
-                    if capsule.complexity > 20 {
-                        warnings.push(AnalysisWarning {
-                            level: Priority::High,
-                            message: format!("Potential God Object: {}", capsule.name),
-                            category: "pattern".to_string(),
-                            capsule_id: Some(capsule.id),
-                            suggestion: Some(
-                                "Break down into smaller, focused classes".to_string(),
-                            ),
-                        });
-                    }
+        for detector in self
+            .detectors
+            .iter()
+            .filter(|d| d.spec.scope == PatternScope::Capsule)
+        {
+            for capsule in graph.capsules.values() {
+                let metrics = HashMap::from([
+                    ("complexity", capsule.complexity as f32),
+                    ("size", capsule.size as f32),
+                    ("dependencies", capsule.dependencies.len() as f32),
+                    ("dependents", capsule.dependents.len() as f32),
+                ]);
+                if detector.evaluate(&metrics).is_some() {
+                    warnings.push(AnalysisWarning {
+                        level: Priority::High,
+                        message: format!(
+                            "Potential {}: {}",
+                            detector.spec.pattern_name, capsule.name
+                        ),
+                        category: "pattern".to_string(),
+                        capsule_id: Some(capsule.id),
+                        suggestion: detector.spec.suggestion.clone(),
+                    });
                 }
             }
         }
         Ok(())
     }
-
-    fn create_pattern_detectors() -> Vec<ArchitecturePatternDetector> {
-        vec![ArchitecturePatternDetector {
-            pattern_name: "God Object".to_string(),
-            detection_criteria: vec![PatternCriteria {
-                name: "High Complexity".to_string(),
-                weight: 0.8,
-                matcher: "complexity > 20".to_string(),
-            }],
-            confidence_threshold: 0.7,
-        }]
-    }
 }
 
 impl Default for PatternDetector {
@@ -71,3 +299,226 @@ impl Default for PatternDetector {
         Self::new()
     }
 }
+
+/// A function definition with its declared return type, if any
+#[derive(Debug, Clone)]
+struct Signature {
+    name: String,
+    returns: Option<String>,
+}
+
+/// Structural facts of one source file that pattern criteria are evaluated on
+#[derive(Debug, Default)]
+struct SourceFacts {
+    signatures: Vec<Signature>,
+    types: Vec<String>,
+    interfaces: HashSet<String>,
+    static_instances: usize,
+    route_handlers: usize,
+    annotated_services: usize,
+    annotated_controllers: usize,
+}
+
+static RUST_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\bfn\s+(\w+)\s*(?:<[^(]*>)?\s*\([^)]*\)\s*(?:->\s*([^{;]+?)\s*)?(?:\bwhere\b|\{|;)",
+    )
+    .unwrap()
+});
+static PYTHON_DEF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:async\s+)?def\s+(\w+)\s*\([^)]*\)\s*(?:->\s*([^:]+?)\s*)?:").unwrap()
+});
+static GO_FUNC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^func\s+(?:\([^)]*\)\s*)?(\w+)\s*\([^)]*\)\s*\(?([\w.*\[\]]+)?").unwrap()
+});
+static SCRIPT_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:export\s+)?(?:(?:public|private|protected|static|async|function)\s+)*(\w+)\s*(?:<[^(]*>)?\s*\([^)]*\)\s*(?::\s*([\w.<>\[\]| ]+?)\s*)?\{")
+        .unwrap()
+});
+static C_LIKE_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:(?:public|protected|private|internal|static|final|abstract|virtual|override|synchronized|inline)\s+)*([\w:.<>\[\],?*&]+)\s+\**(\w+)\s*\([^)]*\)\s*(?:const\s*)?(?:throws\s+[\w.,\s]+)?\{")
+        .unwrap()
+});
+static PHP_FN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bfunction\s+(\w+)\s*\([^)]*\)\s*(?::\s*\??([\w\\]+))?").unwrap()
+});
+static RUBY_DEF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*def\s+(?:self\.)?(\w+[?!]?)").unwrap());
+static TYPE_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:class|struct|interface|trait|enum|protocol|record|type|impl(?:<[^>]*>)?)\s+(\w+)",
+    )
+    .unwrap()
+});
+static INTERFACE_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:interface|trait|protocol|abstract\s+class)\s+(\w+)|\btype\s+(\w+)\s+interface\b|\bclass\s+(\w+)\s*\([^)]*\b(?:ABC|Protocol)\b",
+    )
+    .unwrap()
+});
+static STATIC_INSTANCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)\bstatic\s+(?:(?:final|readonly|volatile|ref|mut)\s+)*(?:[\w<>]+\s+)?(?:_?instance|INSTANCE|_?shared|SHARED)\b|^\s+_instance\s*=\s*None\b|@@instance\b")
+        .unwrap()
+});
+static ROUTE_HANDLER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"@(?:Get|Post|Put|Delete|Patch|Request)Mapping\b|@(?:app|router|bp|blueprint)\.(?:route|get|post|put|delete|patch)\(|#\[(?:get|post|put|delete|patch|route)\(|\b(?:router|app)\.(?:get|post|put|delete|patch)\(")
+        .unwrap()
+});
+static CREATOR_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:[Cc]reate|[Mm]ake|[Bb]uild|[Nn]ew|[Pp]roduce)(?:_|[A-Z])").unwrap()
+});
+static PERSISTENCE_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:find|save|insert|delete|remove|update|persist|fetch|store|load)(?:_|[A-Z]|$)|^get_?[Bb]y(?:_|[A-Z])")
+        .unwrap()
+});
+static IDENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").unwrap());
+
+/// Control-flow keywords a C-like signature regex can mistake for names
+const KEYWORDS: &[&str] = &[
+    "if", "for", "while", "switch", "catch", "return", "else", "new", "do", "try",
+];
+const INSTANCE_ACCESSORS: &[&str] = &[
+    "getInstance",
+    "get_instance",
+    "instance",
+    "sharedInstance",
+    "shared_instance",
+    "shared",
+    "global",
+];
+
+impl SourceFacts {
+    fn collect(content: &str, file_type: &FileType) -> Self {
+        let signature = |name: &str, returns: Option<&str>| Signature {
+            name: name.to_string(),
+            returns: returns
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty()),
+        };
+        let signatures: Vec<Signature> = match file_type {
+            FileType::Rust => RUST_FN
+                .captures_iter(content)
+                .map(|c| signature(&c[1], c.get(2).map(|m| m.as_str())))
+                .collect(),
+            FileType::Python => PYTHON_DEF
+                .captures_iter(content)
+                .map(|c| signature(&c[1], c.get(2).map(|m| m.as_str())))
+                .collect(),
+            FileType::Go => GO_FUNC
+                .captures_iter(content)
+                .map(|c| signature(&c[1], c.get(2).map(|m| m.as_str())))
+                .collect(),
+            FileType::JavaScript | FileType::TypeScript => SCRIPT_FN
+                .captures_iter(content)
+                .filter(|c| !KEYWORDS.contains(&&c[1]))
+                .map(|c| signature(&c[1], c.get(2).map(|m| m.as_str())))
+                .collect(),
+            FileType::Php => PHP_FN
+                .captures_iter(content)
+                .map(|c| signature(&c[1], c.get(2).map(|m| m.as_str())))
+                .collect(),
+            FileType::Ruby => RUBY_DEF
+                .captures_iter(content)
+                .map(|c| signature(&c[1], None))
+                .collect(),
+            FileType::Java | FileType::Cpp | FileType::C | FileType::Other(_) => C_LIKE_FN
+                .captures_iter(content)
+                .filter(|c| !KEYWORDS.contains(&&c[1]) && !KEYWORDS.contains(&&c[2]))
+                .map(|c| signature(&c[2], Some(&c[1])))
+                .collect(),
+        };
+        let interfaces = INTERFACE_DECL
+            .captures_iter(content)
+            .filter_map(|c| c.get(1).or(c.get(2)).or(c.get(3)))
+            .map(|m| m.as_str().to_string())
+            .collect();
+        Self {
+            signatures,
+            types: TYPE_DECL
+                .captures_iter(content)
+                .map(|c| c[1].to_string())
+                .collect(),
+            interfaces,
+            static_instances: STATIC_INSTANCE.find_iter(content).count(),
+            route_handlers: ROUTE_HANDLER.find_iter(content).count(),
+            annotated_services: content.matches("@Service").count(),
+            annotated_controllers: content.matches("@Controller").count()
+                + content.matches("@RestController").count(),
+        }
+    }
+
+    /// Trait objects, `impl Trait`, interfaces declared in the file or named `IFoo`
+    fn is_interface_type(&self, returns: &str) -> bool {
+        IDENT.find_iter(returns).any(|ident| {
+            let ident = ident.as_str();
+            matches!(ident, "dyn" | "impl")
+                || self.interfaces.contains(ident)
+                || (ident.len() > 2
+                    && ident.starts_with('I')
+                    && ident[1..2].chars().all(|c| c.is_ascii_uppercase())
+                    && ident[2..3].chars().all(|c| c.is_ascii_lowercase()))
+        })
+    }
+
+    fn types_ending_with(&self, suffixes: &[&str]) -> usize {
+        self.types
+            .iter()
+            .filter(|name| {
+                suffixes
+                    .iter()
+                    .any(|s| name.ends_with(s) && name.len() > s.len())
+            })
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn metrics(&self) -> HashMap<&'static str, f32> {
+        let creators: Vec<&Signature> = self
+            .signatures
+            .iter()
+            .filter(|s| CREATOR_NAME.is_match(&s.name))
+            .collect();
+        let interface_creators = creators
+            .iter()
+            .filter(|s| {
+                s.returns
+                    .as_deref()
+                    .is_some_and(|r| self.is_interface_type(r))
+            })
+            .count();
+        let names: HashSet<&str> = self.signatures.iter().map(|s| s.name.as_str()).collect();
+        HashMap::from([
+            ("creator_methods", creators.len() as f32),
+            ("interface_creators", interface_creators as f32),
+            ("interface_types", self.interfaces.len() as f32),
+            ("static_instances", self.static_instances as f32),
+            (
+                "instance_accessors",
+                names
+                    .iter()
+                    .filter(|n| INSTANCE_ACCESSORS.contains(n))
+                    .count() as f32,
+            ),
+            (
+                "persistence_methods",
+                names
+                    .iter()
+                    .filter(|n| PERSISTENCE_NAME.is_match(n))
+                    .count() as f32,
+            ),
+            (
+                "repository_types",
+                self.types_ending_with(&["Repository", "Repo", "Dao", "DAO"]) as f32,
+            ),
+            (
+                "service_types",
+                (self.types_ending_with(&["Service"]) + self.annotated_services) as f32,
+            ),
+            (
+                "controller_types",
+                (self.types_ending_with(&["Controller"]) + self.annotated_controllers) as f32,
+            ),
+            ("route_handlers", self.route_handlers as f32),
+        ])
+    }
+}
@@ -0,0 +1,46 @@
+use crate::graph::MetricsCalculator;
+use crate::types::Result;
+use crate::types::*;
+
+use super::{ValidationThresholds, ValidatorOptimizer};
+
+/// Warning categories emitted by the validators run in `ValidatorOptimizer`
+const VALIDATOR_CATEGORIES: &[&str] = &[
+    "complexity",
+    "coupling",
+    "cohesion",
+    "cycles",
+    "connectivity",
+    "layers",
+    "naming",
+    "pattern",
+    "solid",
+];
+
+/// Recomputes graph metrics and validator findings of an already built graph
+/// (e.g. loaded from a stored analysis) with new thresholds, without reparsing sources.
+///
+/// Findings of a previous validation run are the capsule warnings of validator categories
+/// bound to a capsule (`capsule_id` is set when validators distribute them); they are
+/// replaced, while per-element warnings from capsule construction and cycle warnings
+/// from graph building are kept.
+pub fn recompute_graph(
+    graph: &CapsuleGraph,
+    thresholds: &ValidationThresholds,
+) -> Result<CapsuleGraph> {
+    let mut graph = graph.clone();
+    for capsule in graph.capsules.values_mut() {
+        capsule.warnings.retain(|w| {
+            w.capsule_id.is_none() || !VALIDATOR_CATEGORIES.contains(&w.category.as_str())
+        });
+    }
+    graph.metrics =
+        MetricsCalculator::new().calculate_advanced_metrics(&graph.capsules, &graph.relations)?;
+    ValidatorOptimizer::with_thresholds(thresholds).validate_and_optimize(&graph)
+}
+
+/// Same as [`recompute_graph`] for a stored graph of any supported format version
+pub fn recompute_graph_json(json: &str, thresholds: &ValidationThresholds) -> Result<CapsuleGraph> {
+    let graph = crate::graph_store::from_stored_json(json)?;
+    recompute_graph(&graph, thresholds)
+}
@@ -1,11 +1,30 @@
+use crate::hierarchy::{CapsuleHierarchy, HierarchyLevel};
+use crate::parser_ast::{
+    IMPL_FOR_KEY, IMPL_TRAIT_KEY, STATEMENTS_KEY, STUB_KEY, TRAIT_METHODS_KEY,
+};
 use crate::types::Result;
 use crate::types::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+/// Сколько названий членов перечислять в сообщении
+const NAMED_MEMBERS: usize = 5;
 
 #[derive(Debug, Clone)]
 pub struct SolidAnalyzer {
     pub principle: SolidPrinciple,
     pub detection_patterns: Vec<String>,
     pub violation_threshold: f32,
+    /// Сколько методов трейта допустимо до предупреждения ISP
+    pub max_interface_methods: usize,
+    /// Сколько методов типа допустимо до предупреждения SRP
+    pub max_type_methods: usize,
+    /// Сколько внешних типов, с которыми работают члены типа, допустимо до предупреждения SRP
+    pub max_collaborators: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -17,12 +36,180 @@ pub enum SolidPrinciple {
     DependencyInversion,
 }
 
+/// Члены типов: вложенные капсулы по иерархии и методы из `impl Type` того же файла
+struct TypeMembers<'a> {
+    members: HashMap<Uuid, Vec<&'a Capsule>>,
+    /// Капсула → тип-владелец (тип владеет сам собой)
+    owner: HashMap<Uuid, Uuid>,
+}
+
+impl<'a> TypeMembers<'a> {
+    fn of(graph: &'a CapsuleGraph) -> Self {
+        let hierarchy = CapsuleHierarchy::from_graph(graph);
+        let mut members: HashMap<Uuid, Vec<&Capsule>> = HashMap::new();
+        let mut owner = HashMap::new();
+        for capsule in graph.capsules.values() {
+            if HierarchyLevel::of(capsule) == HierarchyLevel::Type {
+                owner.insert(capsule.id, capsule.id);
+            }
+        }
+        for node in hierarchy.nodes.values() {
+            let (Some(id), Some(parent)) = (node.capsule, node.parent.as_deref()) else {
+                continue;
+            };
+            let Some(parent) = Uuid::parse_str(parent)
+                .ok()
+                .filter(|p| graph.capsules.contains_key(p))
+            else {
+                continue;
+            };
+            if let Some(member) = graph.capsules.get(&id) {
+                members.entry(parent).or_default().push(member);
+                owner.entry(id).or_insert(parent);
+            }
+        }
+        // методы из `impl` лежат вне строк типа, их связывает `IMPL_FOR_KEY`
+        let types_by_name: HashMap<(&Path, &str), Uuid> = graph
+            .capsules
+            .values()
+            .filter(|c| HierarchyLevel::of(c) == HierarchyLevel::Type)
+            .map(|c| ((c.file_path.as_path(), c.name.as_str()), c.id))
+            .collect();
+        for capsule in graph.capsules.values() {
+            let Some(type_id) = capsule
+                .metadata
+                .get(IMPL_FOR_KEY)
+                .and_then(|ty| types_by_name.get(&(capsule.file_path.as_path(), ty.as_str())))
+            else {
+                continue;
+            };
+            if owner.get(&capsule.id) != Some(type_id) {
+                members.entry(*type_id).or_default().push(capsule);
+                owner.insert(capsule.id, *type_id);
+            }
+        }
+        for list in members.values_mut() {
+            list.sort_by(|a, b| (a.line_start, &a.name).cmp(&(b.line_start, &b.name)));
+        }
+        Self { members, owner }
+    }
+
+    fn members_of(&self, id: Uuid) -> &[&'a Capsule] {
+        self.members.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    fn methods_of(&self, id: Uuid) -> Vec<&'a Capsule> {
+        self.members_of(id)
+            .iter()
+            .filter(|c| matches!(c.capsule_type, CapsuleType::Function | CapsuleType::Method))
+            .copied()
+            .collect()
+    }
+
+    /// Тип, к которому относится капсула; сама капсула, если типа нет
+    fn owner_of(&self, id: Uuid) -> Uuid {
+        self.owner.get(&id).copied().unwrap_or(id)
+    }
+}
+
+/// Упоминания типов в исходнике: (тип-владелец, упомянутый тип) → члены,
+/// в строках которых он встречается (сам владелец — пустое имя не пишется).
+/// Связи `References`/`Uses` графа строятся по файлу и слою и сюда не годятся
+fn type_mentions(
+    graph: &CapsuleGraph,
+    types: &TypeMembers,
+) -> BTreeMap<(Uuid, Uuid), BTreeSet<String>> {
+    let mut by_name: HashMap<&str, Vec<&Capsule>> = HashMap::new();
+    for capsule in graph.capsules.values() {
+        if HierarchyLevel::of(capsule) == HierarchyLevel::Type {
+            by_name
+                .entry(capsule.name.as_str())
+                .or_default()
+                .push(capsule);
+        }
+    }
+    let mut sources: HashMap<&Path, Option<String>> = HashMap::new();
+    let mut mentions: BTreeMap<(Uuid, Uuid), BTreeSet<String>> = BTreeMap::new();
+    for capsule in graph.capsules.values() {
+        let owner = types.owner_of(capsule.id);
+        let Some(source) = sources
+            .entry(capsule.file_path.as_path())
+            .or_insert_with(|| std::fs::read_to_string(&capsule.file_path).ok())
+        else {
+            continue;
+        };
+        let lines = source
+            .lines()
+            .skip(capsule.line_start.saturating_sub(1))
+            .take(capsule.line_end.max(capsule.line_start) + 1 - capsule.line_start.max(1));
+        let words: BTreeSet<&str> = lines
+            .flat_map(|line| line.split(|c: char| !c.is_alphanumeric() && c != '_'))
+            .collect();
+        for word in words {
+            let Some(candidates) = by_name.get(word) else {
+                continue;
+            };
+            // одноимённые типы: предпочтителен тип из того же файла
+            let Some(target) = candidates
+                .iter()
+                .find(|t| t.file_path == capsule.file_path)
+                .or_else(|| {
+                    candidates
+                        .iter()
+                        .min_by(|a, b| a.file_path.cmp(&b.file_path))
+                })
+            else {
+                continue;
+            };
+            if target.id == owner || target.id == capsule.id {
+                continue;
+            }
+            let members = mentions.entry((owner, target.id)).or_default();
+            if capsule.id != owner {
+                members.insert(capsule.name.clone());
+            }
+        }
+    }
+    mentions
+}
+
+fn named(names: impl IntoIterator<Item = String>) -> String {
+    let names: Vec<String> = names.into_iter().collect();
+    let mut shown = names
+        .iter()
+        .take(NAMED_MEMBERS)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > NAMED_MEMBERS {
+        shown.push_str(&format!(" and {} more", names.len() - NAMED_MEMBERS));
+    }
+    shown
+}
+
+fn sorted_types(graph: &CapsuleGraph, matches: impl Fn(&CapsuleType) -> bool) -> Vec<&Capsule> {
+    let mut types: Vec<&Capsule> = graph
+        .capsules
+        .values()
+        .filter(|c| matches(&c.capsule_type))
+        .collect();
+    types.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.line_start.cmp(&b.line_start))
+    });
+    types
+}
+
 impl SolidAnalyzer {
     pub fn new(principle: SolidPrinciple) -> Self {
         Self {
             principle,
             detection_patterns: vec![],
             violation_threshold: 0.5,
+            max_interface_methods: 7,
+            max_type_methods: 20,
+            max_collaborators: 8,
         }
     }
 
@@ -43,4 +230,414 @@ impl SolidAnalyzer {
 
         Ok(warnings)
     }
+
+    /// Проверки по структуре графа: члены типов из иерархии капсул, связи
+    /// реализации и использования. В сообщениях названы члены-нарушители
+    pub fn analyze_graph(&self, graph: &CapsuleGraph) -> Result<Vec<AnalysisWarning>> {
+        Ok(match self.principle {
+            SolidPrinciple::SingleResponsibility => self.single_responsibility(graph),
+            SolidPrinciple::InterfaceSegregation => self.interface_segregation(graph),
+            SolidPrinciple::DependencyInversion => self.dependency_inversion(graph),
+            SolidPrinciple::OpenClosed | SolidPrinciple::LiskovSubstitution => Vec::new(),
+        })
+    }
+
+    /// SRP: у типа слишком много методов или его члены упоминают слишком
+    /// много других типов
+    fn single_responsibility(&self, graph: &CapsuleGraph) -> Vec<AnalysisWarning> {
+        let types = TypeMembers::of(graph);
+        let mentions = type_mentions(graph, &types);
+        let mut warnings = Vec::new();
+        for capsule in sorted_types(graph, |t| {
+            matches!(t, CapsuleType::Class | CapsuleType::Struct)
+        }) {
+            let mut methods = types.methods_of(capsule.id);
+            let collaborators: BTreeSet<String> = mentions
+                .range((capsule.id, Uuid::nil())..=(capsule.id, Uuid::max()))
+                .filter_map(|((_, target), _)| graph.capsules.get(target))
+                .map(|target| target.name.clone())
+                .collect();
+            let too_many_methods = methods.len() > self.max_type_methods;
+            let too_many_collaborators = collaborators.len() > self.max_collaborators;
+            if !too_many_methods && !too_many_collaborators {
+                continue;
+            }
+            methods.sort_by(|a, b| b.complexity.cmp(&a.complexity).then(a.name.cmp(&b.name)));
+            let mut message = format!(
+                "Possible SRP violation: {} has {} methods and {} collaborators",
+                capsule.name,
+                methods.len(),
+                collaborators.len()
+            );
+            if too_many_methods {
+                message.push_str(&format!(
+                    "; most complex members: {}",
+                    named(methods.iter().map(|m| m.name.clone()))
+                ));
+            }
+            if too_many_collaborators {
+                message.push_str(&format!("; collaborators: {}", named(collaborators)));
+            }
+            warnings.push(AnalysisWarning {
+                level: if too_many_methods && too_many_collaborators {
+                    Priority::Medium
+                } else {
+                    Priority::Low
+                },
+                message,
+                category: "solid".to_string(),
+                capsule_id: Some(capsule.id),
+                suggestion: Some(
+                    "Split the type along the groups of members that share collaborators"
+                        .to_string(),
+                ),
+            });
+        }
+        warnings
+    }
+
+    /// ISP: трейт с большим числом методов, у которого есть реализации (связи
+    /// `Implements`), заставляет каждого реализатора поддерживать весь набор.
+    /// Заглушки (`todo!()`, `NotImplementedError`) в реализациях — прямое свидетельство
+    fn interface_segregation(&self, graph: &CapsuleGraph) -> Vec<AnalysisWarning> {
+        let types = TypeMembers::of(graph);
+        let mentions = type_mentions(graph, &types);
+        let mut warnings = Vec::new();
+        for interface in sorted_types(graph, |t| matches!(t, CapsuleType::Interface)) {
+            let implementors: Vec<&Capsule> = graph
+                .relations
+                .iter()
+                .filter(|r| r.to_id == interface.id && r.relation_type == RelationType::Implements)
+                .filter_map(|r| graph.capsules.get(&r.from_id))
+                .collect();
+            if implementors.is_empty() {
+                continue;
+            }
+            let methods = interface
+                .metadata
+                .get(TRAIT_METHODS_KEY)
+                .and_then(|m| m.parse::<usize>().ok())
+                .filter(|methods| *methods > self.max_interface_methods);
+            let stubs: Vec<String> = implementors
+                .iter()
+                .flat_map(|implementor| {
+                    types
+                        .methods_of(implementor.id)
+                        .into_iter()
+                        .filter(|m| m.metadata.get(STUB_KEY).is_some_and(|s| s == "true"))
+                        .filter(|m| {
+                            m.metadata
+                                .get(IMPL_TRAIT_KEY)
+                                .is_none_or(|t| *t == interface.name)
+                        })
+                        .map(move |m| format!("{}::{}", implementor.name, m.name))
+                })
+                .collect();
+            let message = match methods {
+                Some(methods) => format!(
+                    "Possible ISP violation: {} declares {} methods and has {} implementor(s)",
+                    interface.name,
+                    methods,
+                    implementors.len()
+                ),
+                None if !stubs.is_empty() => format!(
+                    "Possible ISP violation: {} forces stub implementations",
+                    interface.name
+                ),
+                None => continue,
+            };
+            let message = if stubs.is_empty() {
+                message
+            } else {
+                format!("{message}; stubs: {}", named(stubs))
+            };
+            // реализации и упоминающие интерфейс типы
+            let fan_in = implementors
+                .iter()
+                .map(|c| c.id)
+                .chain(
+                    mentions
+                        .keys()
+                        .filter(|(_, target)| *target == interface.id)
+                        .map(|(owner, _)| *owner),
+                )
+                .collect::<BTreeSet<_>>()
+                .len();
+            warnings.push(AnalysisWarning {
+                level: if fan_in > 1 {
+                    Priority::Medium
+                } else {
+                    Priority::Low
+                },
+                message,
+                category: "solid".to_string(),
+                capsule_id: Some(interface.id),
+                suggestion: Some("Split the trait into smaller role-specific traits".to_string()),
+            });
+        }
+        warnings
+    }
+
+    /// DIP: тип (или его члены) упоминает конкретный тип из другого файла,
+    /// хотя у того есть абстракция, которую он реализует
+    fn dependency_inversion(&self, graph: &CapsuleGraph) -> Vec<AnalysisWarning> {
+        let types = TypeMembers::of(graph);
+        let mut abstractions: HashMap<Uuid, Vec<&Capsule>> = HashMap::new();
+        for relation in graph
+            .relations
+            .iter()
+            .filter(|r| r.relation_type == RelationType::Implements)
+        {
+            if let Some(interface) = graph.capsules.get(&relation.to_id) {
+                abstractions
+                    .entry(relation.from_id)
+                    .or_default()
+                    .push(interface);
+            }
+        }
+
+        let uses = type_mentions(graph, &types)
+            .into_iter()
+            .filter(|((source, target), _)| {
+                let (Some(source_type), Some(target_type), Some(interfaces)) = (
+                    graph.capsules.get(source),
+                    graph.capsules.get(target),
+                    abstractions.get(target),
+                ) else {
+                    return false;
+                };
+                let concrete = matches!(
+                    target_type.capsule_type,
+                    CapsuleType::Class | CapsuleType::Struct
+                );
+                // декоратор или адаптер той же абстракции зависит от конкретного типа законно
+                let shared = abstractions
+                    .get(source)
+                    .is_some_and(|own| own.iter().any(|i| interfaces.iter().any(|t| t.id == i.id)));
+                concrete && !shared && source_type.file_path != target_type.file_path
+            });
+
+        uses.into_iter()
+            .filter_map(|((source, target), members)| {
+                let source = graph.capsules.get(&source)?;
+                let target = graph.capsules.get(&target)?;
+                let mut interfaces: Vec<&str> = abstractions[&target.id]
+                    .iter()
+                    .map(|i| i.name.as_str())
+                    .collect();
+                interfaces.sort_unstable();
+                interfaces.dedup();
+                let mut message = format!(
+                    "Possible DIP violation: {} depends on concrete {} instead of {}",
+                    source.name,
+                    target.name,
+                    interfaces.join(" / ")
+                );
+                if !members.is_empty() {
+                    message.push_str(&format!("; members: {}", named(members)));
+                }
+                Some(AnalysisWarning {
+                    level: Priority::Low,
+                    message,
+                    category: "solid".to_string(),
+                    capsule_id: Some(source.id),
+                    suggestion: Some(format!(
+                        "Depend on {} and inject {}",
+                        interfaces.join(" / "),
+                        target.name
+                    )),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Доля используемых методов, ниже которой интерфейс считается «толстым»
+pub const FAT_INTERFACE_RATIO: f64 = 0.5;
+
+/// Как реализатор использует обязательные методы интерфейса
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImplementorUsage {
+    pub implementor: String,
+    /// Обязательные методы с настоящей реализацией
+    pub used: usize,
+    pub used_ratio: f64,
+    /// Методы-заглушки, пустые или не найденные у реализатора
+    pub unused_methods: Vec<String>,
+}
+
+/// Обязательные методы интерфейса и их использование реализаторами
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InterfaceUsage {
+    pub interface: String,
+    pub file: PathBuf,
+    pub line: usize,
+    /// Методы без реализации по умолчанию
+    pub methods: Vec<String>,
+    pub implementors: Vec<ImplementorUsage>,
+    /// Хотя бы один реализатор использует меньше `FAT_INTERFACE_RATIO` методов
+    pub fat: bool,
+}
+
+/// Метрика ISP по всем интерфейсам с реализациями
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InterfaceUsageReport {
+    pub interfaces: Vec<InterfaceUsage>,
+}
+
+/// Объявления методов на первом уровне тела интерфейса: `(имя, есть тело)`
+fn declared_methods(lines: &[&str]) -> Vec<(String, bool)> {
+    static DECLARATION: OnceLock<Regex> = OnceLock::new();
+    let declaration = DECLARATION.get_or_init(|| {
+        Regex::new(r"^\s*(?:(?:pub|public|abstract|default|static|async|unsafe|virtual|readonly)\s+)*(?:(?:fn|def|func|function)\s+(\w+)|(?:[\w<>\[\],.?]+\s+)?(\w+)\s*\??\s*[(<])").unwrap()
+    });
+    const NOT_METHODS: &[&str] = &["if", "for", "while", "switch", "return", "match", "new"];
+    let Some((_, body)) = lines.split_first() else {
+        return Vec::new();
+    };
+    // в Python уровень задаёт отступ, в остальных языках — фигурные скобки
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let base_indent = body
+        .iter()
+        .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with('}'))
+        .map(|l| indent(l))
+        .min()
+        .unwrap_or(0);
+    let mut depth = 0i32;
+    let mut methods: Vec<(String, bool)> = Vec::new();
+    for line in body {
+        let top_level = depth == 0 && indent(line) == base_indent;
+        if top_level {
+            if let Some(cap) = declaration.captures(line) {
+                let name = cap.get(1).or(cap.get(2)).map_or("", |m| m.as_str());
+                let has_body = line.contains('{') || line.trim_start().starts_with("def ");
+                if !NOT_METHODS.contains(&name) && methods.iter().all(|(m, _)| m != name) {
+                    methods.push((name.to_string(), has_body));
+                }
+            }
+        }
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+    }
+    methods
+}
+
+fn capsule_lines<'s>(source: &'s str, capsule: &Capsule) -> Vec<&'s str> {
+    source
+        .lines()
+        .skip(capsule.line_start.saturating_sub(1))
+        .take(capsule.line_end.max(capsule.line_start) + 1 - capsule.line_start.max(1))
+        .collect()
+}
+
+impl InterfaceUsageReport {
+    /// Отчёт по графу; `None`, если у интерфейсов нет реализаторов с найденными методами.
+    /// Метод считается неиспользуемым, если реализатор оставил заглушку, пустое
+    /// тело или не реализовал его вовсе
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let types = TypeMembers::of(graph);
+        let mut interfaces = Vec::new();
+        for interface in sorted_types(graph, |t| matches!(t, CapsuleType::Interface)) {
+            let Ok(source) = std::fs::read_to_string(&interface.file_path) else {
+                continue;
+            };
+            let declared = declared_methods(&capsule_lines(&source, interface));
+            // методы Python-интерфейсов (ABC) всегда с телом — обязательны все
+            let python = interface.file_path.extension().is_some_and(|e| e == "py");
+            let methods: Vec<String> = declared
+                .into_iter()
+                .filter(|(_, has_body)| python || !has_body)
+                .map(|(name, _)| name)
+                .collect();
+            if methods.is_empty() {
+                continue;
+            }
+            let mut implementors: Vec<&Capsule> = graph
+                .relations
+                .iter()
+                .filter(|r| r.to_id == interface.id && r.relation_type == RelationType::Implements)
+                .filter_map(|r| graph.capsules.get(&r.from_id))
+                .collect();
+            implementors.sort_by(|a, b| a.name.cmp(&b.name));
+            implementors.dedup_by_key(|c| c.id);
+            let usage: Vec<ImplementorUsage> = implementors
+                .into_iter()
+                .filter_map(|implementor| {
+                    let own: HashMap<&str, &Capsule> = types
+                        .methods_of(implementor.id)
+                        .into_iter()
+                        .filter(|m| {
+                            m.metadata
+                                .get(IMPL_TRAIT_KEY)
+                                .is_none_or(|t| *t == interface.name)
+                        })
+                        .map(|m| (m.name.as_str(), m))
+                        .collect();
+                    // без найденных методов судить о реализации не по чему
+                    if !methods.iter().any(|m| own.contains_key(m.as_str())) {
+                        return None;
+                    }
+                    let unused_methods: Vec<String> = methods
+                        .iter()
+                        .filter(|name| {
+                            own.get(name.as_str()).is_none_or(|m| {
+                                m.metadata.get(STUB_KEY).is_some_and(|s| s == "true")
+                                    || m.metadata.get(STATEMENTS_KEY).is_some_and(|s| s == "0")
+                            })
+                        })
+                        .cloned()
+                        .collect();
+                    let used = methods.len() - unused_methods.len();
+                    Some(ImplementorUsage {
+                        implementor: implementor.name.clone(),
+                        used,
+                        used_ratio: used as f64 / methods.len() as f64,
+                        unused_methods,
+                    })
+                })
+                .collect();
+            if usage.is_empty() {
+                continue;
+            }
+            interfaces.push(InterfaceUsage {
+                fat: methods.len() > 1 && usage.iter().any(|u| u.used_ratio < FAT_INTERFACE_RATIO),
+                interface: interface.name.clone(),
+                file: interface.file_path.clone(),
+                line: interface.line_start,
+                methods,
+                implementors: usage,
+            });
+        }
+        (!interfaces.is_empty()).then_some(Self { interfaces })
+    }
+
+    /// «Толстые» интерфейсы
+    pub fn fat(&self) -> impl Iterator<Item = &InterfaceUsage> {
+        self.interfaces.iter().filter(|i| i.fat)
+    }
+
+    /// Строки раздела SOLID: толстые интерфейсы и неиспользуемые методы реализаторов
+    pub fn compact_lines(&self) -> String {
+        let mut out = String::new();
+        for interface in self.fat() {
+            out.push_str(&format!(
+                "- Fat interface {} ({} methods)\n",
+                interface.interface,
+                interface.methods.len()
+            ));
+            for usage in interface
+                .implementors
+                .iter()
+                .filter(|u| u.used_ratio < FAT_INTERFACE_RATIO)
+            {
+                out.push_str(&format!(
+                    "  - {} uses {}/{}; unused: {}\n",
+                    usage.implementor,
+                    usage.used,
+                    interface.methods.len(),
+                    named(usage.unused_methods.iter().cloned())
+                ));
+            }
+        }
+        out
+    }
 }
@@ -0,0 +1,211 @@
+use crate::dependency_freshness::find_manifests;
+use crate::types::Result;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Organization-wide project template: the shape every service should have
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    /// Directories every service must contain, relative to the service root
+    #[serde(default)]
+    pub required_dirs: Vec<String>,
+    /// Layers every service must have at least one capsule in
+    #[serde(default)]
+    pub required_layers: Vec<String>,
+    /// Directories that must not appear at the top level of a service
+    #[serde(default)]
+    pub forbidden_top_level_dirs: Vec<String>,
+}
+
+impl ProjectTemplate {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| AnalysisError::ParsingError(format!("Invalid project template: {e}")))
+    }
+
+    /// Template from a file shared between repositories
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| AnalysisError::IoError(e.to_string()))?;
+        Self::from_json(&content)
+    }
+
+    /// `.archlens/template.json`; `Ok(None)` when the file is absent
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(".archlens").join("template.json");
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::from_file(&path).map(Some)
+    }
+}
+
+/// How one service deviates from the template
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServiceDrift {
+    /// Service root relative to the project root (`.` for the project itself)
+    pub service: PathBuf,
+    pub missing_dirs: Vec<String>,
+    pub missing_layers: Vec<String>,
+    pub forbidden_dirs: Vec<String>,
+}
+
+impl ServiceDrift {
+    pub fn is_conformant(&self) -> bool {
+        self.missing_dirs.is_empty()
+            && self.missing_layers.is_empty()
+            && self.forbidden_dirs.is_empty()
+    }
+}
+
+/// Drift of every analyzed service, conformant services included
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TemplateDriftReport {
+    pub services: Vec<ServiceDrift>,
+}
+
+impl TemplateDriftReport {
+    pub fn is_conformant(&self) -> bool {
+        self.services.iter().all(ServiceDrift::is_conformant)
+    }
+
+    /// One warning per deviation, category `template`
+    pub fn warnings(&self) -> Vec<AnalysisWarning> {
+        let mut warnings = Vec::new();
+        for drift in &self.services {
+            let service = drift.service.display();
+            let mut push = |message: String, suggestion: &str| {
+                warnings.push(AnalysisWarning {
+                    level: Priority::Low,
+                    message,
+                    category: "template".to_string(),
+                    capsule_id: None,
+                    suggestion: Some(suggestion.to_string()),
+                });
+            };
+            for dir in &drift.missing_dirs {
+                push(
+                    format!("Template drift: {service} is missing required directory {dir}"),
+                    "Add the directory required by the project template",
+                );
+            }
+            for layer in &drift.missing_layers {
+                push(
+                    format!("Template drift: {service} has no capsules in required layer {layer}"),
+                    "Add the layer required by the project template",
+                );
+            }
+            for dir in &drift.forbidden_dirs {
+                push(
+                    format!(
+                        "Template drift: {service} contains forbidden top-level directory {dir}"
+                    ),
+                    "Move or remove the directory forbidden by the project template",
+                );
+            }
+        }
+        warnings
+    }
+
+    /// Plain-text report, one block per service
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for drift in &self.services {
+            if drift.is_conformant() {
+                out.push_str(&format!(
+                    "✅ {}: matches the template\n",
+                    drift.service.display()
+                ));
+                continue;
+            }
+            out.push_str(&format!("❌ {}:\n", drift.service.display()));
+            for dir in &drift.missing_dirs {
+                out.push_str(&format!("  - missing directory: {dir}\n"));
+            }
+            for layer in &drift.missing_layers {
+                out.push_str(&format!("  - missing layer: {layer}\n"));
+            }
+            for dir in &drift.forbidden_dirs {
+                out.push_str(&format!("  - forbidden top-level directory: {dir}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// Compares every service of a project with a `ProjectTemplate`
+pub struct TemplateValidator {
+    template: ProjectTemplate,
+}
+
+impl TemplateValidator {
+    pub fn new(template: ProjectTemplate) -> Self {
+        Self { template }
+    }
+
+    /// Services are directories with a manifest (`Cargo.toml`, `package.json`,
+    /// `requirements.txt`); a project without manifests is a single service.
+    /// Each capsule counts towards the deepest service containing its file
+    pub fn validate(&self, project_root: &Path, graph: &CapsuleGraph) -> TemplateDriftReport {
+        let roots = service_roots(project_root);
+
+        let mut layers: Vec<BTreeSet<&str>> = vec![BTreeSet::new(); roots.len()];
+        for capsule in graph.capsules.values() {
+            let owner = roots
+                .iter()
+                .enumerate()
+                .filter(|(_, root)| capsule.file_path.starts_with(root))
+                .max_by_key(|(_, root)| root.components().count());
+            if let (Some((i, _)), Some(layer)) = (owner, capsule.layer.as_deref()) {
+                layers[i].insert(layer);
+            }
+        }
+
+        let services = roots
+            .iter()
+            .zip(&layers)
+            .map(|(root, layers)| ServiceDrift {
+                service: match root.strip_prefix(project_root) {
+                    Ok(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+                    _ => PathBuf::from("."),
+                },
+                missing_dirs: self
+                    .template
+                    .required_dirs
+                    .iter()
+                    .filter(|dir| !root.join(dir).is_dir())
+                    .cloned()
+                    .collect(),
+                missing_layers: self
+                    .template
+                    .required_layers
+                    .iter()
+                    .filter(|layer| !layers.contains(layer.as_str()))
+                    .cloned()
+                    .collect(),
+                forbidden_dirs: self
+                    .template
+                    .forbidden_top_level_dirs
+                    .iter()
+                    .filter(|dir| root.join(dir).is_dir())
+                    .cloned()
+                    .collect(),
+            })
+            .collect();
+        TemplateDriftReport { services }
+    }
+}
+
+fn service_roots(project_root: &Path) -> Vec<PathBuf> {
+    let roots: BTreeSet<PathBuf> = find_manifests(project_root)
+        .iter()
+        .filter_map(|manifest| manifest.parent().map(Path::to_path_buf))
+        .collect();
+    if roots.is_empty() {
+        vec![project_root.to_path_buf()]
+    } else {
+        roots.into_iter().collect()
+    }
+}
@@ -8,6 +8,7 @@
 /// - validation/patterns: Pattern detection
 /// - validation/solid: SOLID principles analysis
 /// - validation/cycles: Circular dependency detection
+/// - validation/connectivity: Single points of failure (articulation points, bridges)
 /// - validation/layers: Layer hierarchy validation
 /// - validation/naming: Naming convention validation
 /// - validation/optimizer: Graph optimization
@@ -0,0 +1,259 @@
+// Сужение видимости: публичные символы (`pub` в Rust, `export` в JS/TS), которые
+// не используются вне своего модуля или крейта. Использованием считается
+// связь из другого файла, найденная в коде (импорт, вызов, наследование…), или
+// упоминание имени в другом файле того же языка — совпадение имён делает
+// отчёт консервативным: лучше пропустить кандидата, чем предложить сломать сборку
+
+use crate::api_surface::capsule_api_surface;
+use crate::code_origin::CodeOrigin;
+use crate::dsm::common_root;
+use crate::types::{CapsuleGraph, RelationType};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Сколько кандидатов показывать в ai_compact
+const COMPACT_SHOWN: usize = 30;
+/// Расширения JS/TS: видимость задаёт `export`
+const SCRIPT_EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+/// Виды объявлений, видимость которых имеет смысл сужать
+const NARROWABLE_KINDS: [&str; 13] = [
+    "fn",
+    "struct",
+    "enum",
+    "trait",
+    "type",
+    "const",
+    "static",
+    "union",
+    "function",
+    "class",
+    "interface",
+    "let",
+    "var",
+];
+
+/// Предлагаемая видимость
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestedVisibility {
+    /// Не используется вне своего файла: убрать `pub`/`export`
+    Private,
+    /// Используется только внутри крейта: `pub(crate)`
+    Crate,
+}
+
+impl SuggestedVisibility {
+    /// Запись видимости в коде; для JS/TS приватность — отсутствие `export`
+    pub fn as_str(self, script: bool) -> &'static str {
+        match (self, script) {
+            (Self::Private, true) => "no export",
+            (Self::Private, false) => "private",
+            (Self::Crate, _) => "pub(crate)",
+        }
+    }
+}
+
+/// Символ, видимость которого можно сузить
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VisibilityCandidate {
+    pub name: String,
+    pub kind: String,
+    pub line: usize,
+    pub suggested: SuggestedVisibility,
+    /// Символ библиотечного крейта: вне проекта у него могут быть потребители
+    pub library: bool,
+}
+
+/// Кандидаты одного модуля (файла)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModuleVisibility {
+    pub file: PathBuf,
+    pub candidates: Vec<VisibilityCandidate>,
+}
+
+/// Публичные символы без внешних пользователей, по модулям
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VisibilityReport {
+    pub modules: Vec<ModuleVisibility>,
+}
+
+/// Корень крейта Rust: ближайший каталог `src`, иначе каталог файла.
+/// `tests/`, `benches/` и `examples/` — отдельные крейты
+fn crate_root(file: &Path) -> &Path {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.file_name().is_some_and(|n| n == "src"))
+        .or_else(|| file.parent())
+        .unwrap_or(file)
+}
+
+/// Бинарная цель крейта с `lib.rs`: `src/main.rs` и `src/bin/*` — отдельные
+/// крейты, библиотеку они видят только через её публичный API
+fn is_binary_target(file: &Path, root: &Path) -> bool {
+    file.file_name().is_some_and(|n| n == "main.rs")
+        || file
+            .strip_prefix(root)
+            .is_ok_and(|rest| rest.starts_with("bin"))
+}
+
+fn is_script(file: &Path) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SCRIPT_EXTENSIONS.contains(&e))
+}
+
+fn identifiers(source: &str) -> HashSet<String> {
+    source
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl VisibilityReport {
+    /// Отчёт по графу с публичным API капсул; `None`, если кандидатов нет
+    pub fn from_graph(graph: &CapsuleGraph) -> Option<Self> {
+        let language = |file: &Path| -> Option<bool> {
+            match file.extension()?.to_str()? {
+                "rs" => Some(false),
+                _ if is_script(file) => Some(true),
+                _ => None,
+            }
+        };
+        // идентификаторы каждого файла поддерживаемых языков, файл читается один раз
+        let mut words: BTreeMap<&Path, HashSet<String>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            let file = capsule.file_path.as_path();
+            if language(file).is_some() && !words.contains_key(file) {
+                let source = std::fs::read_to_string(file).unwrap_or_default();
+                words.insert(file, identifiers(&source));
+            }
+        }
+        // файлы, из которых на капсулу ведут связи, найденные в коде
+        let mut used_from: HashMap<Uuid, HashSet<&Path>> = HashMap::new();
+        for relation in &graph.relations {
+            if relation.relation_type.is_structural()
+                || relation.relation_type == RelationType::Contains
+            {
+                continue;
+            }
+            if let Some(from) = graph.capsules.get(&relation.from_id) {
+                used_from
+                    .entry(relation.to_id)
+                    .or_default()
+                    .insert(from.file_path.as_path());
+            }
+        }
+        let libraries: HashSet<&Path> = words
+            .keys()
+            .filter(|f| f.file_name().is_some_and(|n| n == "lib.rs"))
+            .filter_map(|f| f.parent())
+            .collect();
+
+        let root = common_root(graph.capsules.values().map(|c| c.file_path.as_path()));
+        let mut modules: BTreeMap<PathBuf, Vec<VisibilityCandidate>> = BTreeMap::new();
+        for capsule in graph.capsules.values() {
+            let file = capsule.file_path.as_path();
+            let Some(script) = language(file) else {
+                continue;
+            };
+            // корень крейта — его API по определению
+            let crate_file = file
+                .file_name()
+                .is_some_and(|n| n == "lib.rs" || n == "main.rs");
+            if crate_file || CodeOrigin::of(capsule) == CodeOrigin::Test {
+                continue;
+            }
+            for symbol in capsule_api_surface(capsule) {
+                if !NARROWABLE_KINDS.contains(&symbol.kind.as_str()) || symbol.name == "main" {
+                    continue;
+                }
+                let mut users: HashSet<&Path> = words
+                    .iter()
+                    .filter(|(other, names)| {
+                        **other != file
+                            && language(other) == Some(script)
+                            && names.contains(&symbol.name)
+                    })
+                    .map(|(other, _)| *other)
+                    .collect();
+                if let Some(from) = used_from.get(&capsule.id) {
+                    users.extend(from.iter().filter(|f| **f != file));
+                }
+                let suggested = if users.is_empty() {
+                    SuggestedVisibility::Private
+                } else if !script
+                    && users.iter().all(|user| {
+                        let root = crate_root(file);
+                        crate_root(user) == root
+                            && !(libraries.contains(root) && is_binary_target(user, root))
+                    })
+                {
+                    SuggestedVisibility::Crate
+                } else {
+                    continue;
+                };
+                let relative = file.strip_prefix(&root).unwrap_or(file).to_path_buf();
+                modules
+                    .entry(relative)
+                    .or_default()
+                    .push(VisibilityCandidate {
+                        name: symbol.name,
+                        kind: symbol.kind,
+                        line: symbol.line,
+                        suggested,
+                        library: !script && libraries.contains(crate_root(file)),
+                    });
+            }
+        }
+        if modules.is_empty() {
+            return None;
+        }
+        let modules = modules
+            .into_iter()
+            .map(|(file, mut candidates)| {
+                candidates.sort_by(|a, b| (a.line, &a.name).cmp(&(b.line, &b.name)));
+                candidates.dedup_by(|a, b| a.line == b.line && a.name == b.name);
+                ModuleVisibility { file, candidates }
+            })
+            .collect();
+        Some(Self { modules })
+    }
+
+    pub fn total(&self) -> usize {
+        self.modules.iter().map(|m| m.candidates.len()).sum()
+    }
+
+    /// Раздел ai_compact: кандидаты по модулям
+    pub fn compact_section(&self) -> String {
+        let mut out = format!("\n## Visibility Candidates ({})\n", self.total());
+        let mut shown = 0;
+        for module in &self.modules {
+            if shown >= COMPACT_SHOWN {
+                break;
+            }
+            out.push_str(&format!("- {}\n", module.file.display()));
+            let script = is_script(&module.file);
+            for candidate in module.candidates.iter().take(COMPACT_SHOWN - shown) {
+                out.push_str(&format!(
+                    "  - {} {} -> {}{}\n",
+                    candidate.kind,
+                    candidate.name,
+                    candidate.suggested.as_str(script),
+                    if candidate.library {
+                        " (library API?)"
+                    } else {
+                        ""
+                    }
+                ));
+                shown += 1;
+            }
+        }
+        if self.total() > shown {
+            out.push_str(&format!("- ... and {} more\n", self.total() - shown));
+        }
+        out
+    }
+}
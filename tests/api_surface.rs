@@ -0,0 +1,105 @@
+use archlens::api_surface::*;
+use archlens::cli::handlers::build_project_graph;
+use archlens::exporter::Exporter;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_api_surface_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn names(symbols: &[ApiSymbol]) -> Vec<&str> {
+    symbols.iter().map(|s| s.name.as_str()).collect()
+}
+
+#[test]
+fn public_symbols_are_scanned_per_language() {
+    let rust = "pub fn open(path: &str) -> File {\n    todo!()\n}\npub(crate) fn helper() {}\nfn private() {}\npub struct File;\npub async fn read() {}\n";
+    let symbols = scan_public_symbols(rust, "rs");
+    assert_eq!(names(&symbols), ["open", "File", "read"]);
+    assert_eq!(symbols[0].kind, "fn");
+    assert_eq!(symbols[0].line, 1);
+    assert_eq!(symbols[0].signature, "pub fn open(path: &str) -> File");
+
+    let ts = "export function parse(input: string): Ast {\n  return {};\n}\nfunction internal() {}\nconst a = 1, b = 2;\nexport { a, b as beta };\nexport default class Parser {}\n";
+    assert_eq!(
+        names(&scan_public_symbols(ts, "ts")),
+        ["parse", "Parser", "a", "beta"]
+    );
+
+    let python = "def run():\n    pass\n\ndef _private():\n    pass\n\nclass Client:\n    def method(self):\n        pass\n";
+    assert_eq!(names(&scan_public_symbols(python, "py")), ["run", "Client"]);
+    // `__all__` задаёт API модуля целиком
+    let with_all = "__all__ = [\"Client\", 'VERSION']\nVERSION = '1'\n\ndef run():\n    pass\n\nclass Client:\n    pass\n";
+    let symbols = scan_public_symbols(with_all, "py");
+    assert_eq!(names(&symbols), ["Client", "VERSION"]);
+    assert_eq!(symbols[0].kind, "class");
+    assert_eq!(symbols[1].kind, "export");
+
+    let go = "func Serve(addr string) error {\n\treturn nil\n}\nfunc (s *Server) Close() {}\nfunc helper() {}\ntype Server struct{}\n";
+    assert_eq!(
+        names(&scan_public_symbols(go, "go")),
+        ["Serve", "Close", "Server"]
+    );
+
+    let java = "public class Api {\n    public String get(int id) {\n        return null;\n    }\n    private void hidden() {}\n}\n";
+    assert_eq!(names(&scan_public_symbols(java, "java")), ["Api", "get"]);
+    assert!(scan_public_symbols("pub fn x() {}", "txt").is_empty());
+}
+
+#[test]
+fn api_surface_is_attached_to_capsules_and_exported() {
+    let dir = temp_dir("export");
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("src/lib.rs"),
+        "pub fn connect(url: &str) -> bool {\n    check(url)\n}\n\nfn check(url: &str) -> bool {\n    !url.is_empty()\n}\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.join("tests")).unwrap();
+    std::fs::write(
+        dir.join("tests/api.rs"),
+        "pub fn fixture() -> u32 {\n    1\n}\n",
+    )
+    .unwrap();
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let connect = graph
+        .capsules
+        .values()
+        .find(|c| c.name == "connect")
+        .unwrap();
+    assert_eq!(names(&capsule_api_surface(connect)), ["connect"]);
+    let check = graph.capsules.values().find(|c| c.name == "check").unwrap();
+    assert!(capsule_api_surface(check).is_empty());
+
+    // тестовый код в публичный API не входит
+    let report = ApiSurfaceReport::from_graph(&graph).unwrap();
+    assert_eq!(report.entries.len(), 1);
+    assert_eq!(report.entries[0].file, PathBuf::from("src/lib.rs"));
+    assert_eq!(report.entries[0].capsule, "connect");
+
+    let exporter = Exporter::new();
+    let compact = exporter.export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Public API (1 symbols)\n- src/lib.rs\n  - fn connect\n"));
+    let json: serde_json::Value =
+        serde_json::from_str(&exporter.export_to_json(&graph).unwrap()).unwrap();
+    let exported = json["layers"]
+        .as_object()
+        .unwrap()
+        .values()
+        .flat_map(|capsules| capsules.as_array().unwrap())
+        .find(|c| c["name"] == "connect")
+        .unwrap();
+    assert_eq!(
+        exported["api_surface"][0]["signature"],
+        "pub fn connect(url: &str) -> bool"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
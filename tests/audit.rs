@@ -0,0 +1,138 @@
+use archlens::audit::{project_fingerprint, AuditFilter, AuditLog, AuditRecord};
+use assert_cmd::prelude::*;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("archlens_audit_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn record(command: &str, project: &str) -> AuditRecord {
+    AuditRecord {
+        user: "alice".into(),
+        command: command.into(),
+        project: project.into(),
+        args: vec![command.into(), project.into()],
+        fingerprint: "1:0000000000000000".into(),
+    }
+}
+
+#[test]
+fn entries_form_a_verifiable_chain() {
+    let dir = temp_dir("chain");
+    let log = AuditLog::new(&dir.join("audit.jsonl"));
+    assert!(log.entries().unwrap().is_empty());
+
+    let first = log.append(record("export", "/srv/payments")).unwrap();
+    let second = log.append(record("diff", "/srv/payments")).unwrap();
+    log.append(record("export", "/srv/search")).unwrap();
+    assert_eq!((first.seq, second.seq), (1, 2));
+    assert_eq!(second.prev_hash, first.hash);
+    assert!(log.verify().unwrap().is_empty());
+
+    let exports = log
+        .query(&AuditFilter {
+            command: Some("export".into()),
+            ..AuditFilter::default()
+        })
+        .unwrap();
+    assert_eq!(exports.len(), 2);
+    let last_payments = log
+        .query(&AuditFilter {
+            project: Some("payments".into()),
+            limit: Some(1),
+            ..AuditFilter::default()
+        })
+        .unwrap();
+    assert_eq!(last_payments.len(), 1);
+    assert_eq!(last_payments[0].command, "diff");
+
+    // правка записи и удаление строки обнаруживаются
+    let path = log.path().to_path_buf();
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, content.replacen("alice", "mallory", 1)).unwrap();
+    assert_eq!(log.verify().unwrap(), vec!["entry 1: content was modified"]);
+
+    let lines: Vec<&str> = content.lines().collect();
+    std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+    assert_eq!(
+        log.verify().unwrap(),
+        vec![
+            "entry 3: expected sequence number 2",
+            "entry 3: does not follow the previous entry",
+        ]
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn fingerprint_tracks_project_files() {
+    let dir = temp_dir("fingerprint");
+    std::fs::write(dir.join("lib.rs"), "fn a() {}\n").unwrap();
+    std::fs::create_dir_all(dir.join("target")).unwrap();
+    let before = project_fingerprint(&dir);
+    assert!(before.starts_with("1:"));
+
+    // build-артефакты не влияют на отпечаток
+    std::fs::write(dir.join("target/out.o"), "binary").unwrap();
+    assert_eq!(project_fingerprint(&dir), before);
+
+    std::fs::write(dir.join("lib.rs"), "fn a() { b() }\n").unwrap();
+    assert_ne!(project_fingerprint(&dir), before);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn cli_invocations_are_recorded_and_viewable() {
+    let data = temp_dir("cli");
+    let run_with = |args: &[&str], audit: Option<&str>| {
+        let mut command = Command::cargo_bin("archlens").unwrap();
+        command.args(args).env("ARCHLENS_DATA_DIR", &data);
+        match audit {
+            Some(value) => command.env("ARCHLENS_AUDIT", value),
+            None => command.env_remove("ARCHLENS_AUDIT"),
+        };
+        command.output().unwrap()
+    };
+    let run = |args: &[&str]| run_with(args, Some("1"));
+
+    // журнал включается явно
+    assert!(
+        run_with(&["structure", "tests/fixtures/small_project"], None)
+            .status
+            .success()
+    );
+    assert!(!data.join("audit.jsonl").exists());
+
+    assert!(run(&["structure", "tests/fixtures/small_project"])
+        .status
+        .success());
+    assert!(run(&["version"]).status.success());
+
+    let output = run(&["audit", "--json"]);
+    assert!(output.status.success());
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = entries.as_array().unwrap();
+    // version не журналируется, просмотр журнала — тоже
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["command"], "structure");
+    assert!(entries[0]["project"]
+        .as_str()
+        .unwrap()
+        .ends_with("small_project"));
+    assert_eq!(
+        entries[0]["args"],
+        serde_json::json!(["structure", "tests/fixtures/small_project"])
+    );
+
+    let output = run(&["audit", "--verify"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is intact (1 entries)"));
+
+    let _ = std::fs::remove_dir_all(&data);
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::exporter::Exporter;
+use archlens::graph::{MetricsCalculator, Reachability};
+use archlens::types::*;
+
+fn capsule(name: &str) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Module,
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn depends(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Depends,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// api → service → {repo, log}; repo → db; worker → repo; db ↔ pool (цикл)
+fn graph() -> CapsuleGraph {
+    let [api, service, repo, log, db, pool, worker] =
+        ["api", "service", "repo", "log", "db", "pool", "worker"].map(capsule);
+    let relations = vec![
+        depends(&api, &service),
+        depends(&service, &repo),
+        depends(&service, &log),
+        depends(&repo, &db),
+        depends(&worker, &repo),
+        depends(&db, &pool),
+        depends(&pool, &db),
+        // повтор связи не увеличивает счётчики
+        depends(&api, &service),
+    ];
+    let mut graph = CapsuleGraphBuilder::new()
+        .build_graph(&[api, service, repo, log, db, pool, worker])
+        .unwrap();
+    graph.relations = relations;
+    graph
+}
+
+fn reach_of(graph: &CapsuleGraph, name: &str) -> Reachability {
+    let id = graph.capsules.values().find(|c| c.name == name).unwrap().id;
+    MetricsCalculator::new().calculate_reachability(&graph.capsules, &graph.relations)[&id]
+}
+
+#[test]
+fn transitive_counts_per_capsule() {
+    let graph = graph();
+    let expected = [
+        ("api", 0, 5),
+        ("service", 1, 4),
+        ("repo", 3, 2),
+        ("log", 2, 0),
+        ("db", 5, 1),
+        ("pool", 5, 1),
+        ("worker", 0, 3),
+    ];
+    for (name, blast_radius, transitive_dependencies) in expected {
+        assert_eq!(
+            reach_of(&graph, name),
+            Reachability {
+                blast_radius,
+                transitive_dependencies
+            },
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn key_components_sorted_by_blast_radius() {
+    let graph = graph();
+    let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    let key: Vec<(&str, u64)> = summary["key_components"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            (
+                c["component"].as_str().unwrap(),
+                c["blast_radius"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        key,
+        [
+            ("db", 5),
+            ("pool", 5),
+            ("repo", 3),
+            ("log", 2),
+            ("service", 1)
+        ]
+    );
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    let section = compact
+        .split("## Key Components (Blast Radius)\n")
+        .nth(1)
+        .unwrap();
+    assert!(section.starts_with("- db (Module) : blast radius 5, depends on 1\n"));
+}
+
+#[test]
+fn key_components_with_equal_counts_keep_file_order() {
+    for _ in 0..5 {
+        let app = capsule("app");
+        let in_b = Capsule {
+            file_path: "/repo/src/b.rs".into(),
+            ..capsule("handler")
+        };
+        let in_a = Capsule {
+            capsule_type: CapsuleType::Struct,
+            file_path: "/repo/src/a.rs".into(),
+            ..capsule("handler")
+        };
+        let relations = vec![depends(&app, &in_b), depends(&app, &in_a)];
+        let mut graph = CapsuleGraphBuilder::new()
+            .build_graph(&[app, in_b, in_a])
+            .unwrap();
+        graph.relations = relations;
+
+        let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+        let types: Vec<&str> = summary["key_components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["Struct", "Module"]);
+    }
+}
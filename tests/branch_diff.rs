@@ -0,0 +1,103 @@
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("git");
+    assert!(status.success(), "git {:?}", args);
+}
+
+fn write(repo: &Path, file: &str, content: &str) {
+    let path = repo.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn commit(repo: &Path, message: &str) {
+    git(repo, &["add", "-A"]);
+    git(repo, &["commit", "--quiet", "-m", message]);
+}
+
+#[test]
+fn three_way_diff_separates_branch_changes_from_main() {
+    let repo =
+        std::env::temp_dir().join(format!("archlens-branch-diff-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+
+    write(&repo, "src/lib.rs", "pub fn run() -> u32 {\n    1\n}\n");
+    commit(&repo, "initial");
+    git(&repo, &["tag", "base"]);
+
+    // общий модуль попадает и в main, и в ветку (cherry-pick)
+    let shared = "pub fn shared_helper() -> u32 {\n    3\n}\n";
+    write(&repo, "src/shared.rs", shared);
+    write(
+        &repo,
+        "src/metrics.rs",
+        "pub fn main_only_metric() -> u32 {\n    4\n}\n",
+    );
+    commit(&repo, "main work");
+
+    git(&repo, &["checkout", "--quiet", "-b", "feature", "base"]);
+    write(&repo, "src/shared.rs", shared);
+    write(
+        &repo,
+        "src/feature.rs",
+        "pub fn branch_only_feature() -> u32 {\n    5\n}\n",
+    );
+    commit(&repo, "feature work");
+
+    let output = Command::cargo_bin("archlens")
+        .unwrap()
+        .args([
+            "diff",
+            repo.to_str().unwrap(),
+            "main",
+            "feature",
+            "--base",
+            "base",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(stdout.contains("## Branch diff: feature vs main (base base)"));
+    let section = |title: &str| -> String {
+        stdout
+            .split("\n### ")
+            .find(|s| s.starts_with(title))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let unique = section("Unique to branch");
+    assert!(unique.contains("branch_only_feature"), "{}", stdout);
+    assert!(!unique.contains("**Added** `shared_helper`"));
+    assert!(section("Already on main").contains("shared_helper"));
+    assert!(section("Only on main").contains("main_only_metric"));
+
+    // без --base — обычный diff двух ревизий
+    let output = Command::cargo_bin("archlens")
+        .unwrap()
+        .args(["diff", repo.to_str().unwrap(), "base", "feature"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("## Architecture diff base…feature"));
+    assert!(stdout.contains("branch_only_feature"));
+    assert!(!stdout.contains("main_only_metric"));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
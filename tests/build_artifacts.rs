@@ -0,0 +1,155 @@
+use archlens::cli::stats::get_project_stats;
+use archlens::file_scanner::build_artifacts::{build_output, Ecosystem};
+use archlens::file_scanner::FileScanner;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_build_artifacts_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Rust-крейт с target/, сервис .NET с bin/ и obj/, пакет Python с кэшем
+/// байткода и каталог build/ без манифеста рядом — обычный код
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write(&dir, "Cargo.toml", "[package]\nname = \"demo\"\n");
+    write(&dir, "src/lib.rs", "pub fn live() {}\n");
+    write(
+        &dir,
+        "target/debug/build/out/generated.rs",
+        "pub fn generated() {}\n",
+    );
+    write(&dir, "svc/Service.csproj", "<Project />\n");
+    write(
+        &dir,
+        "svc/obj/Generated.js",
+        "export function generated() {}\n",
+    );
+    write(&dir, "svc/bin/app.js", "export function bundled() {}\n");
+    write(
+        &dir,
+        "tools/pkg/__pycache__/mod.py",
+        "def cached():\n    pass\n",
+    );
+    write(&dir, "tools/pkg/mod.py", "def live():\n    pass\n");
+    write(&dir, "docs/build/example.rs", "pub fn example() {}\n");
+    dir
+}
+
+#[test]
+fn build_output_follows_manifests() {
+    let dir = project("detect");
+    let target = build_output(&dir.join("target")).unwrap();
+    assert_eq!(target.ecosystem, Ecosystem::Rust);
+    assert_eq!(target.manifest, Some(dir.join("Cargo.toml")));
+    assert_eq!(
+        build_output(&dir.join("svc/obj")).unwrap().ecosystem,
+        Ecosystem::Dotnet
+    );
+    assert_eq!(
+        build_output(&dir.join("tools/pkg/__pycache__"))
+            .unwrap()
+            .ecosystem,
+        Ecosystem::Python
+    );
+    // build/ без манифеста рядом и bin/ без проекта .NET — не вывод сборки
+    assert!(build_output(&dir.join("docs/build")).is_none());
+    assert!(build_output(&dir.join("src")).is_none());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn scanner_skips_detected_build_output() {
+    let dir = project("scan");
+    let scanner = FileScanner::new(
+        vec!["**/*.rs".into(), "**/*.js".into(), "**/*.py".into()],
+        Vec::new(),
+        None,
+    )
+    .unwrap();
+    let mut files: Vec<String> = scanner
+        .scan_files(&dir)
+        .unwrap()
+        .into_iter()
+        .map(|f| {
+            f.path
+                .strip_prefix(&dir)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        vec!["docs/build/example.rs", "src/lib.rs", "tools/pkg/mod.py"]
+    );
+    assert!(scanner
+        .scan_file(&dir.join("target/debug/build/out/generated.rs"), &dir)
+        .is_none());
+    assert!(scanner.scan_file(&dir.join("src/lib.rs"), &dir).is_some());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn build_output_above_the_project_root_is_ignored() {
+    // проект, распакованный в target/ другого крейта, сканируется целиком
+    let outer = temp_dir("outer");
+    write(&outer, "Cargo.toml", "[package]\nname = \"outer\"\n");
+    let root = outer.join("target/package/inner");
+    write(&root, "Cargo.toml", "[package]\nname = \"inner\"\n");
+    write(&root, "src/lib.rs", "pub fn inner() {}\n");
+    write(
+        &root,
+        "target/debug/generated.rs",
+        "pub fn generated() {}\n",
+    );
+    let scanner = FileScanner::new(vec!["**/*.rs".into()], Vec::new(), None).unwrap();
+    assert!(scanner.scan_file(&root.join("src/lib.rs"), &root).is_some());
+    assert!(scanner
+        .scan_file(&root.join("target/debug/generated.rs"), &root)
+        .is_none());
+    assert!(scanner
+        .scan_file(&root.join("src/lib.rs"), &outer)
+        .is_none());
+    let _ = std::fs::remove_dir_all(&outer);
+}
+
+#[test]
+fn project_stats_report_auto_excluded_dirs() {
+    let dir = project("stats");
+    let stats = get_project_stats(dir.to_str().unwrap()).unwrap();
+    let excluded: Vec<(String, Ecosystem)> = stats
+        .auto_excluded
+        .iter()
+        .map(|d| (d.path.to_string_lossy().replace('\\', "/"), d.ecosystem))
+        .collect();
+    assert_eq!(
+        excluded,
+        vec![
+            ("svc/bin".to_string(), Ecosystem::Dotnet),
+            ("svc/obj".to_string(), Ecosystem::Dotnet),
+            ("target".to_string(), Ecosystem::Rust),
+            ("tools/pkg/__pycache__".to_string(), Ecosystem::Python),
+        ]
+    );
+    assert_eq!(
+        stats.auto_excluded[2].manifest,
+        Some(PathBuf::from("Cargo.toml"))
+    );
+    // docs/build/example.rs считается, вывод сборки — нет
+    assert_eq!(stats.file_types.get("rs"), Some(&2));
+    let _ = std::fs::remove_dir_all(&dir);
+}
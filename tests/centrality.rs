@@ -0,0 +1,101 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::exporter::Exporter;
+use archlens::graph::MetricsCalculator;
+use archlens::types::*;
+
+fn capsule(name: &str) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Module,
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn depends(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Depends,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// a, b, c → gateway → core; lonely ни с чем не связан
+fn graph() -> CapsuleGraph {
+    let [a, b, c, gateway, core, lonely] =
+        ["a", "b", "c", "gateway", "core", "lonely"].map(capsule);
+    let relations = vec![
+        depends(&a, &gateway),
+        depends(&b, &gateway),
+        depends(&c, &gateway),
+        depends(&gateway, &core),
+    ];
+    let mut graph = CapsuleGraphBuilder::new()
+        .build_graph(&[a, b, c, gateway, core, lonely])
+        .unwrap();
+    graph.relations = relations;
+    graph
+}
+
+#[test]
+fn pagerank_and_betweenness_per_capsule() {
+    let graph = graph();
+    let centrality =
+        MetricsCalculator::new().calculate_centrality(&graph.capsules, &graph.relations);
+    let total: f64 = centrality.values().map(|c| c.pagerank).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    let of = |name: &str| {
+        let id = graph.capsules.values().find(|c| c.name == name).unwrap().id;
+        centrality[&id]
+    };
+    assert!(of("core").pagerank > of("gateway").pagerank);
+    assert!(of("gateway").pagerank > of("a").pagerank);
+    // gateway лежит на путях a/b/c → core: 3 из 5 * 4 упорядоченных пар
+    assert!((of("gateway").betweenness - 3.0 / 20.0).abs() < 1e-9);
+    assert_eq!(of("core").betweenness, 0.0);
+    assert_eq!(of("lonely").betweenness, 0.0);
+}
+
+#[test]
+fn hubs_and_bottlenecks_are_ranked() {
+    let graph = graph();
+    let rankings = MetricsCalculator::new().calculate_centrality_rankings(
+        &graph.capsules,
+        &graph.relations,
+        10,
+    );
+    let hubs: Vec<&str> = rankings.hubs.iter().map(|h| h.name.as_str()).collect();
+    assert_eq!(hubs, ["core", "gateway"]);
+    let bottlenecks: Vec<&str> = rankings
+        .bottlenecks
+        .iter()
+        .map(|b| b.name.as_str())
+        .collect();
+    assert_eq!(bottlenecks, ["gateway"]);
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    let section = compact.split("## Top Hubs (PageRank)\n").nth(1).unwrap();
+    assert!(section.starts_with("- core (Module) : "));
+    assert!(section.contains("- Bottlenecks (betweenness):\n  - gateway (Module) : 0.150\n"));
+}
+
+#[test]
+fn graphs_without_dependencies_have_no_hubs() {
+    let [a, b] = ["a", "b"].map(capsule);
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&[a, b]).unwrap();
+    graph.relations.clear();
+    let rankings = MetricsCalculator::new().calculate_centrality_rankings(
+        &graph.capsules,
+        &graph.relations,
+        10,
+    );
+    assert!(rankings.hubs.is_empty());
+    assert!(!Exporter::new()
+        .export_to_ai_compact(&graph)
+        .unwrap()
+        .contains("## Top Hubs"));
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use archlens::change_forecast::{linear_forecast, ChangeForecaster};
+use archlens::types::*;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn git(repo: &Path, args: &[&str], days_ago: i64) {
+    let date = format!("@{} +0000", Utc::now().timestamp() - days_ago * 86_400);
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .env("GIT_AUTHOR_DATE", &date)
+        .env("GIT_COMMITTER_DATE", &date)
+        .status()
+        .expect("git");
+    assert!(status.success(), "git {:?}", args);
+}
+
+fn commit(repo: &Path, file: &str, content: &str, message: &str, days_ago: i64) {
+    std::fs::write(repo.join(file), content).unwrap();
+    git(repo, &["add", "-A"], days_ago);
+    git(repo, &["commit", "--quiet", "-m", message], days_ago);
+}
+
+/// Функция `name` в строках 1..=lines с телом из `lines - 2` строк
+fn function(name: &str, lines: usize, seed: usize) -> String {
+    let mut body = format!("pub fn {}() -> usize {{\n", name);
+    for i in 0..lines - 2 {
+        body.push_str(&format!("    let _v{} = {};\n", i, seed));
+    }
+    body.push_str("}\n");
+    body
+}
+
+fn capsule(path: PathBuf, name: &str, lines: usize, complexity: u32) -> Capsule {
+    Capsule {
+        file_path: path,
+        line_end: lines,
+        size: lines,
+        complexity,
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+fn setup(name: &str) -> (PathBuf, CapsuleGraph) {
+    let repo =
+        std::env::temp_dir().join(format!("archlens-erosion-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet", "--initial-branch=main"], 0);
+
+    commit(&repo, "hot.rs", &function("hot", 4, 0), "add hot", 400);
+    commit(
+        &repo,
+        "stable.rs",
+        &function("stable", 4, 0),
+        "add stable",
+        400,
+    );
+    // hot.rs: одно изменение в начале окна, затем частые исправления и рост кода;
+    // stable.rs: изменения равномерно по окну. Коммиты — в хронологическом порядке
+    let mut history = vec![(170, "hot.rs", function("hot", 4, 9), "tidy hot".to_string())];
+    for (i, days_ago) in [40, 30, 20, 10, 5].into_iter().enumerate() {
+        let content = function("hot", 6 + i * 2, i + 1);
+        history.push((days_ago, "hot.rs", content, format!("fix: hot bug {}", i)));
+    }
+    for (i, days_ago) in [150, 120, 90, 60, 31].into_iter().enumerate() {
+        let content = function("stable", 4, i + 1);
+        history.push((days_ago, "stable.rs", content, "tweak stable".to_string()));
+    }
+    history.sort_by_key(|(days_ago, ..)| std::cmp::Reverse(*days_ago));
+    for (days_ago, file, content, message) in history {
+        commit(&repo, file, &content, &message, days_ago);
+    }
+
+    let capsules = [
+        capsule(repo.join("hot.rs"), "hot", 14, 12),
+        capsule(repo.join("stable.rs"), "stable", 4, 1),
+    ];
+    let graph = common::graph(capsules, vec![]);
+    (repo, graph)
+}
+
+#[test]
+fn linear_forecast_follows_trend() {
+    let (slope, forecast) = linear_forecast(&[0, 1, 2, 3]);
+    assert!((slope - 1.0).abs() < 1e-9);
+    assert!((forecast - 4.0).abs() < 1e-9);
+    // убывающий тренд не прогнозирует отрицательных изменений
+    assert_eq!(linear_forecast(&[5, 0, 0, 0, 0, 0]).1, 0.0);
+    assert_eq!(linear_forecast(&[]), (0.0, 0.0));
+}
+
+#[test]
+fn accelerating_module_with_rising_fixes_is_flagged() {
+    let (repo, graph) = setup("flagged");
+    let report = ChangeForecaster::new(&repo).analyze(&graph).unwrap();
+
+    assert_eq!(report.window_days, 180);
+    assert_eq!(report.bucket_days, 30);
+    assert_eq!(report.commits, 11);
+
+    let hot = &report.modules[0];
+    assert_eq!(hot.path, "hot.rs");
+    assert_eq!(hot.changes, vec![1, 0, 0, 0, 2, 3]);
+    assert!(hot.accelerating);
+    assert!(hot.erosion_warning);
+    assert!(hot.forecast > 2.0);
+    assert!(hot
+        .quality_signals
+        .iter()
+        .any(|s| s == "bug-fix share rose from 0% to 100%"));
+    assert!(hot
+        .quality_signals
+        .iter()
+        .any(|s| s == "grew by 10 lines (+250%)"));
+    assert_eq!(hot.capsules[0].name, "hot");
+    assert_eq!(hot.capsules[0].net_growth, 10);
+
+    let stable = report
+        .modules
+        .iter()
+        .find(|m| m.path == "stable.rs")
+        .unwrap();
+    assert!(!stable.accelerating);
+    assert!(!stable.erosion_warning);
+
+    let md = report.to_markdown();
+    assert!(
+        md.starts_with("## Erosion early warning (last 180 days, 30-day intervals, 11 commits)")
+    );
+    assert!(md.contains("| `hot.rs` | 1 → 0 → 0 → 0 → 2 → 3 |"));
+    assert!(!md.contains("`stable.rs`"));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn short_window_ignores_older_history() {
+    let (repo, graph) = setup("window");
+    let report = ChangeForecaster::new(&repo)
+        .with_window_days(35)
+        .analyze(&graph)
+        .unwrap();
+
+    // в окно попадают коммиты 30, 20, 10, 5 дней назад (hot) и 31 день назад (stable)
+    assert_eq!(report.commits, 5);
+    assert!(report.warnings().all(|m| m.path == "hot.rs"));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
@@ -0,0 +1,87 @@
+use archlens::cli::diagram::generate_class_diagram;
+use std::fs;
+use std::path::PathBuf;
+
+fn project(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("archlens-class-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src/shapes")).unwrap();
+    fs::write(
+        root.join("src/shapes/mod.rs"),
+        r#"pub trait Named {
+    fn name(&self) -> String;
+}
+
+pub trait Shape: Named {
+    fn area(&self) -> f64;
+}
+
+pub struct Circle {
+    pub radius: f64,
+    center: (f64, f64),
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14 * self.radius * self.radius
+    }
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Self { radius, center: (0.0, 0.0) }
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/models.py"),
+        r#"class Base:
+    def __init__(self, name):
+        self.name = name
+
+    def describe(self) -> str:
+        return self.name
+
+
+class Child(Base):
+    def run(self, times: int):
+        pass
+"#,
+    )
+    .unwrap();
+    root
+}
+
+#[test]
+fn class_diagram_shows_members_and_relationships() {
+    let root = project("full");
+    let diagram = generate_class_diagram(root.to_str().unwrap(), None).unwrap();
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(diagram.starts_with("classDiagram\n"));
+    assert!(diagram.contains("class Circle {"));
+    assert!(diagram.contains("+radius: f64"));
+    assert!(diagram.contains("-center: [f64, f64]"));
+    assert!(diagram.contains("+new(radius: f64) Self"));
+    assert!(diagram.contains("+area() f64"));
+    assert!(diagram.contains("<<trait>>"));
+    assert!(diagram.contains("Shape <|.. Circle"));
+    assert!(diagram.contains("Named <|-- Shape"));
+    assert!(diagram.contains("+describe() str"));
+    assert!(diagram.contains("Base <|-- Child"));
+    assert!(!diagram.contains("__init__"));
+}
+
+#[test]
+fn class_diagram_respects_scope() {
+    let root = project("scope");
+    let diagram = generate_class_diagram(root.to_str().unwrap(), Some("src/shapes")).unwrap();
+    let missing = generate_class_diagram(root.to_str().unwrap(), Some("src/nope"));
+    let _ = fs::remove_dir_all(&root);
+
+    assert!(diagram.contains("class Circle {"));
+    assert!(!diagram.contains("class Base"));
+    assert!(missing.is_err());
+}
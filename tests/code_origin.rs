@@ -0,0 +1,265 @@
+mod common;
+
+use archlens::code_origin::*;
+use archlens::exporter::Exporter;
+use archlens::types::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+fn capsule(name: &str, file: &Path, line: usize) -> Capsule {
+    Capsule {
+        file_path: file.to_path_buf(),
+        line_start: line,
+        line_end: line + 2,
+        size: 3,
+        layer: Some("Core".into()),
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Calls,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+#[test]
+fn test_paths_are_recognized() {
+    for path in [
+        "tests/api.rs",
+        "src/__tests__/button.tsx",
+        "pkg/server/handler_test.go",
+        "web/cart.spec.ts",
+        "web/cart.test.js",
+        "app/test_models.py",
+        "src/test/java/com/acme/OrderServiceTest.java",
+    ] {
+        assert!(is_test_path(Path::new(path)), "{path}");
+    }
+    for path in [
+        "src/lib.rs",
+        "src/testing_utils.rs",
+        "web/contest.ts",
+        "app/models.py",
+    ] {
+        assert!(!is_test_path(Path::new(path)), "{path}");
+    }
+}
+
+#[test]
+fn cfg_test_blocks_are_spanned() {
+    let source = "pub fn run() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn works() {}\n}\n\n#[cfg(test)]\nuse std::fmt;\npub fn after() {}\n";
+    assert_eq!(cfg_test_spans(source), vec![(3, 7), (9, 10)]);
+}
+
+#[test]
+fn test_edges_do_not_inflate_production_graph() {
+    let root = std::env::temp_dir().join(format!("archlens-origin-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    let lib: PathBuf = root.join("src/lib.rs");
+    std::fs::write(
+        &lib,
+        "pub fn core() {}\npub fn api() {}\n#[cfg(test)]\nmod tests {\n    fn helper() {}\n}\n",
+    )
+    .unwrap();
+    let integration = root.join("tests/api.rs");
+    std::fs::write(&integration, "fn api_works() {}\n").unwrap();
+
+    let mut capsules = vec![
+        capsule("core", &lib, 1),
+        capsule("api", &lib, 2),
+        capsule("helper", &lib, 5),
+        capsule("api_works", &integration, 1),
+    ];
+    classify_capsules(&root, &mut capsules);
+    let origins: Vec<CodeOrigin> = capsules.iter().map(CodeOrigin::of).collect();
+    assert_eq!(
+        origins,
+        [
+            CodeOrigin::Production,
+            CodeOrigin::Production,
+            CodeOrigin::Test,
+            CodeOrigin::Test
+        ]
+    );
+    assert!(capsules[3].tags.contains(&TEST_TAG.to_string()));
+
+    let relations = vec![
+        relation(&capsules[1], &capsules[0]),
+        relation(&capsules[2], &capsules[0]),
+        relation(&capsules[3], &capsules[1]),
+    ];
+    let mut graph = CapsuleGraph {
+        layers: HashMap::from([("Core".to_string(), capsules.iter().map(|c| c.id).collect())]),
+        ..common::graph(capsules.to_vec(), relations)
+    };
+    assert!(!is_test_relation(&graph, &graph.relations[0]));
+    assert!(is_test_relation(&graph, &graph.relations[1]));
+
+    recalculate_production_metrics(&mut graph).unwrap();
+    assert_eq!(graph.metrics.total_capsules, 2);
+    assert_eq!(graph.metrics.total_relations, 1);
+    assert_eq!(graph.capsules.len(), 4);
+
+    let summary = exclude_test_code(&mut graph).unwrap();
+    assert_eq!(
+        summary,
+        TestCodeSummary {
+            test_capsules: 2,
+            test_relations: 2
+        }
+    );
+    assert_eq!(graph.capsules.len(), 2);
+    assert_eq!(graph.relations.len(), 1);
+    assert_eq!(graph.layers["Core"].len(), 2);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn benches_and_examples_are_auxiliary() {
+    assert_eq!(
+        origin_of_path(Path::new("benches/parse.rs")),
+        CodeOrigin::Auxiliary
+    );
+    assert_eq!(
+        origin_of_path(Path::new("examples/basic/main.go")),
+        CodeOrigin::Auxiliary
+    );
+    assert_eq!(
+        origin_of_path(Path::new("demos/todo/app.ts")),
+        CodeOrigin::Auxiliary
+    );
+    assert_eq!(
+        origin_of_path(Path::new("src/example.rs")),
+        CodeOrigin::Production
+    );
+    assert_eq!(origin_of_path(Path::new("tests/api.rs")), CodeOrigin::Test);
+}
+
+#[test]
+fn auxiliary_code_is_listed_but_not_counted() {
+    let root = PathBuf::from("/repo");
+    let mut capsules = vec![
+        capsule("parse", &root.join("src/lib.rs"), 1),
+        capsule("bench_parse", &root.join("benches/parse.rs"), 1),
+        capsule("basic", &root.join("examples/basic.rs"), 1),
+    ];
+    classify_capsules(&root, &mut capsules);
+    assert!(capsules[1].tags.contains(&AUXILIARY_TAG.to_string()));
+    let relations = vec![
+        relation(&capsules[1], &capsules[0]),
+        relation(&capsules[2], &capsules[0]),
+    ];
+    let mut graph = common::graph(capsules.to_vec(), relations);
+    recalculate_production_metrics(&mut graph).unwrap();
+    assert_eq!(graph.metrics.total_capsules, 1);
+    assert_eq!(graph.metrics.total_relations, 0);
+
+    let names: Vec<&str> = auxiliary_capsules(&graph)
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, ["bench_parse", "basic"]);
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Auxiliary Code (benches, examples; excluded from core metrics)"));
+    assert!(compact.contains("- bench_parse (Function) : /repo/benches/parse.rs:1"));
+}
+
+#[test]
+fn test_functions_and_blocks_are_spanned_per_language() {
+    let rust = "pub fn run() {}\n#[test]\nfn runs() {\n    run();\n}\n#[tokio::test]\nasync fn runs_async() {}\n";
+    assert_eq!(test_block_spans(rust, "rs"), vec![(2, 5), (6, 7)]);
+
+    let js = "export function sum(a, b) {\n  return a + b;\n}\n\ndescribe('sum', () => {\n  it('adds', () => {\n    expect(sum(1, 2)).toBe(3);\n  });\n});\n";
+    assert_eq!(test_block_spans(js, "ts"), vec![(5, 9)]);
+
+    let python = "def total(xs):\n    return sum(xs)\n\n\ndef test_total():\n    assert total([1]) == 1\n\n\nclass TestTotal:\n    def test_empty(self):\n        assert total([]) == 0\n\ndef helper():\n    pass\n";
+    assert_eq!(test_block_spans(python, "py"), vec![(5, 6), (9, 11)]);
+
+    let ruby = "class Cart\n  def total\n    1\n  end\n\n  def test_total\n    assert_equal 1, total\n  end\nend\n";
+    assert_eq!(test_block_spans(ruby, "rb"), vec![(6, 8)]);
+    assert!(test_block_spans(rust, "go").is_empty());
+}
+
+#[test]
+fn braces_in_literals_and_comments_do_not_shift_spans() {
+    let rust = "#[cfg(test)]\nmod tests {\n    const CLOSE: &str = \"}\";\n    const OPEN: char = '{';\n    // } закрытие в комментарии\n    /* { */\n    fn parse<'a>(s: &'a str) -> &'a str {\n        s.trim_end_matches(r#\"\"}\"#)\n    }\n}\npub fn after() {}\n";
+    assert_eq!(cfg_test_spans(rust), vec![(1, 10)]);
+
+    let js = "describe('braces', () => {\n  it('keeps }', () => {\n    expect(`${'{'}`).toBe('{');\n  });\n});\nexport const after = 1;\n";
+    assert_eq!(test_block_spans(js, "js"), vec![(1, 5)]);
+
+    let python = "def test_doc():\n    text = \"\"\"\nnot the end\n\"\"\"\n# comment\n    assert text\n\ndef helper():\n    pass\n";
+    assert_eq!(test_block_spans(python, "py"), vec![(1, 6)]);
+}
+
+#[test]
+fn colocated_tests_are_classified_as_test_code() {
+    let root = std::env::temp_dir().join(format!("archlens-origin-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("app")).unwrap();
+    let module = root.join("app/cart.py");
+    std::fs::write(
+        &module,
+        "def total(xs):\n    return sum(xs)\n\n\ndef test_total():\n    assert total([1]) == 1\n",
+    )
+    .unwrap();
+    let mut capsules = vec![
+        capsule("total", &module, 1),
+        capsule("test_total", &module, 5),
+    ];
+    classify_capsules(&root, &mut capsules);
+    assert_eq!(CodeOrigin::of(&capsules[0]), CodeOrigin::Production);
+    assert_eq!(CodeOrigin::of(&capsules[1]), CodeOrigin::Test);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_capsules_stay_out_of_complexity_and_coupling_rankings() {
+    let lib = PathBuf::from("/project/src/lib.rs");
+    let mut core = capsule("core", &lib, 1);
+    core.complexity = 5;
+    let api = capsule("api", &lib, 10);
+    let mut fixture = capsule("giant_fixture", &lib, 20);
+    fixture.complexity = 40;
+    fixture
+        .metadata
+        .insert(ORIGIN_KEY.to_string(), TEST_TAG.to_string());
+    let relations = vec![
+        relation(&api, &core),
+        relation(&fixture, &core),
+        relation(&fixture, &api),
+    ];
+    let capsules = [core, api, fixture];
+    let graph = common::graph(capsules.to_vec(), relations);
+
+    let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    let ranked = |key: &str| -> Vec<String> {
+        summary[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["component"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert!(!ranked("top_complexity_components").contains(&"giant_fixture".to_string()));
+    assert!(!ranked("top_coupling").contains(&"giant_fixture".to_string()));
+    // без тестовых связей у core и api по одной связи
+    assert_eq!(summary["top_coupling"][0]["degree"], 1);
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(!compact.contains("giant_fixture"));
+
+    let with_tests = Exporter::new()
+        .with_test_code(true)
+        .export_to_ai_summary_json(&graph)
+        .unwrap();
+    assert_eq!(
+        with_tests["top_complexity_components"][0]["component"],
+        "giant_fixture"
+    );
+}
@@ -0,0 +1,280 @@
+use archlens::enrichment::code_smells::{CodeSmellDetector, CodeSmellType, SmellRuleConfig};
+use archlens::types::FileType;
+use std::path::{Path, PathBuf};
+
+/// Минимальные точность и полнота каждого правила на размеченном корпусе
+const MIN_PRECISION: f32 = 0.9;
+const MIN_RECALL: f32 = 0.9;
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/smells")
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("archlens_smells_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Тип файла корпуса по расширению
+fn file_type_of(name: &str) -> FileType {
+    match Path::new(name).extension().and_then(|e| e.to_str()) {
+        Some("py") => FileType::Python,
+        Some("js") => FileType::JavaScript,
+        Some("ts") => FileType::TypeScript,
+        _ => FileType::Rust,
+    }
+}
+
+fn samples(rule: &Path, label: &str) -> Vec<(String, String)> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(rule.join(label))
+        .map(|entries| entries.map(|e| e.unwrap().path()).collect())
+        .unwrap_or_default();
+    files.sort();
+    files
+        .into_iter()
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect()
+}
+
+fn detects(detector: &CodeSmellDetector, smell: &CodeSmellType, content: &str) -> bool {
+    detects_in(detector, smell, "src/lib.rs", content)
+}
+
+fn detects_in(
+    detector: &CodeSmellDetector,
+    smell: &CodeSmellType,
+    file: &str,
+    content: &str,
+) -> bool {
+    detector
+        .detect_code_smells_at(Path::new(file), content, file_type_of(file))
+        .unwrap()
+        .iter()
+        .any(|s| s.smell_type == *smell)
+}
+
+fn smell_of(dir_name: &str) -> CodeSmellType {
+    serde_json::from_value(serde_json::Value::String(dir_name.to_string())).unwrap()
+}
+
+#[test]
+fn every_rule_meets_precision_and_recall_on_corpus() {
+    let detector = CodeSmellDetector::new();
+    let mut rules: Vec<PathBuf> = std::fs::read_dir(corpus_dir())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.is_dir())
+        .collect();
+    rules.sort();
+    assert!(rules.len() >= 5, "corpus covers the built-in rules");
+
+    for rule in rules {
+        let name = rule.file_name().unwrap().to_string_lossy().into_owned();
+        let smell = smell_of(&name);
+        let positives = samples(&rule, "positive");
+        let negatives = samples(&rule, "negative");
+        assert!(!positives.is_empty() && !negatives.is_empty(), "{name}");
+
+        let mut misses = Vec::new();
+        let mut false_alarms = Vec::new();
+        for (file, content) in &positives {
+            if !detects_in(&detector, &smell, &format!("src/{file}"), content) {
+                misses.push(file.clone());
+            }
+        }
+        for (file, content) in &negatives {
+            if detects_in(&detector, &smell, &format!("src/{file}"), content) {
+                false_alarms.push(file.clone());
+            }
+        }
+        let true_positives = (positives.len() - misses.len()) as f32;
+        let precision = true_positives / (true_positives + false_alarms.len() as f32).max(1.0);
+        let recall = true_positives / positives.len() as f32;
+        assert!(
+            precision >= MIN_PRECISION,
+            "{name}: precision {precision:.2}, false alarms {false_alarms:?}"
+        );
+        assert!(
+            recall >= MIN_RECALL,
+            "{name}: recall {recall:.2}, missed {misses:?}"
+        );
+    }
+}
+
+#[test]
+fn magic_numbers_are_not_reported_in_test_files() {
+    let detector = CodeSmellDetector::new();
+    let content = "fn connect() {\n    start_server(8080);\n}\n";
+    let smell = CodeSmellType::MagicNumbers;
+    assert!(detects_in(&detector, &smell, "src/server.rs", content));
+    assert!(!detects_in(&detector, &smell, "tests/server.rs", content));
+    assert!(!detects_in(
+        &detector,
+        &smell,
+        "src/server_test.go",
+        content
+    ));
+    // без пути тестовыми считаются только блоки `#[cfg(test)]`
+    assert!(detects(&detector, &smell, content));
+}
+
+#[test]
+fn language_exclusions_apply_only_to_their_language() {
+    let detector = CodeSmellDetector::new();
+    let smell = CodeSmellType::MagicNumbers;
+    let constant = "MAX_RETRIES = 25\n";
+    assert!(!detects_in(&detector, &smell, "src/settings.py", constant));
+    assert!(!detects_in(&detector, &smell, "src/settings.rb", constant));
+    let js = "const MAX_RETRIES = 25;\nexport const TIMEOUT_MS = 250;\n";
+    assert!(!detects_in(&detector, &smell, "src/settings.js", js));
+    // `#` в Python — комментарий, в Rust — нет
+    assert!(!detects_in(
+        &detector,
+        &smell,
+        "src/app.py",
+        "x = call()  # 250 ms\n"
+    ));
+    assert!(detects_in(
+        &detector,
+        &smell,
+        "src/app.py",
+        "x = call(250)\n"
+    ));
+}
+
+#[test]
+fn builtin_rules_cover_corpus_and_compile() {
+    let config = SmellRuleConfig::builtin();
+    for entry in std::fs::read_dir(corpus_dir()).unwrap() {
+        let name = entry.unwrap().file_name().to_string_lossy().into_owned();
+        let smell = smell_of(&name);
+        assert!(
+            config.rules.iter().any(|r| r.smell == smell && r.enabled),
+            "{name} has a built-in rule"
+        );
+    }
+    assert!(CodeSmellDetector::from_config(&config).is_ok());
+}
+
+#[test]
+fn project_rules_override_thresholds_without_code_changes() {
+    let dir = temp_dir("override");
+    let six_params =
+        std::fs::read_to_string(corpus_dir().join("long_parameter_list/positive/six_params.rs"))
+            .unwrap();
+    let long_line = "x".repeat(100);
+
+    let default = CodeSmellDetector::for_project(&dir).unwrap();
+    assert!(detects(
+        &default,
+        &CodeSmellType::LongParameterList,
+        &six_params
+    ));
+    assert!(!detects(
+        &default,
+        &CodeSmellType::LongLineLength,
+        &long_line
+    ));
+
+    std::fs::create_dir_all(dir.join(".archlens")).unwrap();
+    std::fs::write(
+        dir.join(".archlens/smells.json"),
+        r#"{"rules": [
+            {"smell": "long_parameter_list", "name": "params", "pattern": "\\bfn\\s+(\\w+)\\s*\\(",
+             "threshold": 8, "severity": "Low", "description": "d", "suggestion": "s"},
+            {"smell": "long_line_length", "name": "lines", "threshold": 80,
+             "severity": "Low", "description": "d", "suggestion": "s"},
+            {"smell": "magic_numbers", "name": "off", "pattern": "\\d+", "enabled": false,
+             "severity": "Low", "description": "d", "suggestion": "s"}
+        ]}"#,
+    )
+    .unwrap();
+    let tuned = CodeSmellDetector::for_project(&dir).unwrap();
+    assert!(!detects(
+        &tuned,
+        &CodeSmellType::LongParameterList,
+        &six_params
+    ));
+    assert!(detects(&tuned, &CodeSmellType::LongLineLength, &long_line));
+    assert!(!detects(
+        &tuned,
+        &CodeSmellType::MagicNumbers,
+        "let x = 250;"
+    ));
+    // правила, не упомянутые в проекте, остаются встроенными
+    assert!(detects(&tuned, &CodeSmellType::DeepNesting, &"{".repeat(6)));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn invalid_rules_are_reported() {
+    let bad_regex = SmellRuleConfig::from_json(
+        r#"{"rules": [{"smell": "magic_numbers", "name": "bad", "pattern": "(",
+            "severity": "Low", "description": "d", "suggestion": "s"}]}"#,
+    )
+    .unwrap();
+    assert!(CodeSmellDetector::from_config(&bad_regex).is_err());
+
+    let missing_pattern = SmellRuleConfig::from_json(
+        r#"{"rules": [{"smell": "long_parameter_list", "name": "no pattern",
+            "severity": "Low", "description": "d", "suggestion": "s"}]}"#,
+    )
+    .unwrap();
+    assert!(CodeSmellDetector::from_config(&missing_pattern).is_err());
+    assert!(SmellRuleConfig::from_json(r#"{"rules": [{"smell": "unknown"}]}"#).is_err());
+}
+
+#[test]
+fn long_method_findings_come_from_parsed_spans() {
+    let detector = CodeSmellDetector::new();
+    // короткая функция в большом файле не длинная, длинная — со своими строками
+    let mut content = String::from("pub fn short() -> u32 {\n    1\n}\n\n");
+    content.push_str("pub fn long(items: &[u32]) -> u32 {\n    let mut total = 0;\n");
+    content.push_str("    for item in items {\n        if *item > 1 {\n");
+    for i in 0..30 {
+        content.push_str(&format!("            total += step({i});\n"));
+    }
+    content.push_str("        }\n    }\n    total\n}\n");
+    let findings: Vec<_> = detector
+        .detect_code_smells(&content, FileType::Rust)
+        .unwrap()
+        .into_iter()
+        .filter(|s| s.smell_type == CodeSmellType::LongMethod)
+        .collect();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].location.as_deref(),
+        Some("Функция: long (строки 5-42)")
+    );
+    let description = &findings[0].description;
+    assert!(
+        description.contains("34 операторов, вложенность 2"),
+        "{description}"
+    );
+}
+
+#[test]
+fn body_metrics_count_statements_and_nesting() {
+    use archlens::parser_ast::{body_metrics, BodyMetrics};
+    let rust = "fn f(x: u32) -> u32 {\n    // комментарий\n    let y = x;\n    if y > 1 {\n        y\n    } else {\n        0\n    }\n}";
+    assert_eq!(
+        body_metrics(rust, &FileType::Rust),
+        BodyMetrics {
+            statements: 5,
+            max_nesting: 1
+        }
+    );
+    let python = "def f(\n    x,\n):\n    total = 0\n    for i in x:\n        if i:\n            total += i\n    return total";
+    assert_eq!(
+        body_metrics(python, &FileType::Python),
+        BodyMetrics {
+            statements: 5,
+            max_nesting: 2
+        }
+    );
+}
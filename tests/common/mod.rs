@@ -0,0 +1,70 @@
+//! Общие фикстуры интеграционных тестов: капсулы, связи и графы.
+//!
+//! Каждый тестовый крейт использует только часть хелперов, поэтому
+//! предупреждения о неиспользуемом коде здесь отключены.
+#![allow(dead_code)]
+
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Функция `name` в `/repo/src/{name}.rs`; отличающиеся поля задаются
+/// через `Capsule { .., ..capsule(name) }`
+pub fn capsule(name: &str) -> Capsule {
+    Capsule {
+        id: Uuid::new_v4(),
+        name: name.into(),
+        capsule_type: CapsuleType::Function,
+        file_path: PathBuf::from(format!("/repo/src/{name}.rs")),
+        line_start: 1,
+        line_end: 10,
+        size: 10,
+        complexity: 1,
+        dependencies: vec![],
+        layer: None,
+        summary: None,
+        description: None,
+        warnings: vec![],
+        status: CapsuleStatus::Active,
+        priority: Priority::Medium,
+        tags: vec![],
+        metadata: HashMap::new(),
+        quality_score: 0.8,
+        slogan: None,
+        dependents: vec![],
+        created_at: None,
+    }
+}
+
+/// Связь `from → to` с силой 1.0 и без описания
+pub fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// Граф без слоёв; в метриках заполнены только счётчики капсул и связей
+pub fn graph(
+    capsules: impl IntoIterator<Item = Capsule>,
+    relations: Vec<CapsuleRelation>,
+) -> CapsuleGraph {
+    let capsules: HashMap<_, _> = capsules.into_iter().map(|c| (c.id, c)).collect();
+    CapsuleGraph {
+        metrics: GraphMetrics {
+            total_capsules: capsules.len(),
+            total_relations: relations.len(),
+            ..Default::default()
+        },
+        capsules,
+        relations,
+        layers: HashMap::new(),
+        created_at: Utc::now(),
+        previous_analysis: None,
+    }
+}
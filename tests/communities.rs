@@ -0,0 +1,118 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::exporter::Exporter;
+use archlens::graph::{CommunityDetector, ModularizationReport};
+use archlens::types::*;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn capsule(path: &str) -> Capsule {
+    let (dir, name) = path.split_once('/').unwrap();
+    Capsule {
+        file_path: PathBuf::from(format!("/repo/src/{dir}/{name}.rs")),
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn calls(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Calls,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// Два плотных кластера (billing, shipping); tracker лежит в billing,
+/// но работает только с shipping
+fn graph() -> CapsuleGraph {
+    let capsules = [
+        "billing/invoice",
+        "billing/payment",
+        "billing/ledger",
+        "billing/tracker",
+        "shipping/parcel",
+        "shipping/route",
+        "shipping/courier",
+    ]
+    .map(capsule);
+    let [invoice, payment, ledger, tracker, parcel, route, courier] = &capsules;
+    let relations = vec![
+        calls(invoice, payment),
+        calls(payment, ledger),
+        calls(invoice, ledger),
+        calls(parcel, route),
+        calls(route, courier),
+        calls(parcel, courier),
+        calls(tracker, parcel),
+        calls(tracker, route),
+        calls(tracker, courier),
+        calls(invoice, parcel),
+    ];
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = relations;
+    graph
+}
+
+fn names(graph: &CapsuleGraph, ids: &[Uuid]) -> Vec<String> {
+    let mut names: Vec<String> = ids
+        .iter()
+        .map(|id| graph.capsules[id].name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn dense_clusters_become_communities() {
+    let graph = graph();
+    let communities = CommunityDetector::new().detect(&graph);
+    let groups: Vec<Vec<String>> = communities.iter().map(|c| names(&graph, c)).collect();
+    assert_eq!(
+        groups,
+        [
+            vec!["courier", "parcel", "route", "tracker"],
+            vec!["invoice", "ledger", "payment"],
+        ]
+    );
+}
+
+#[test]
+fn communities_are_compared_with_directories() {
+    let graph = graph();
+    let report = ModularizationReport::from_graph(&graph).unwrap();
+    assert!(report.modularity > 0.3, "{}", report.modularity);
+    assert!((report.layout_agreement - 6.0 / 7.0).abs() < 1e-9);
+
+    let shipping = &report.modules[0];
+    assert_eq!(shipping.dominant_directory, "shipping");
+    assert_eq!(shipping.other_directories, [("billing".to_string(), 1)]);
+    assert_eq!(shipping.move_candidates.len(), 1);
+    assert_eq!(shipping.move_candidates[0].name, "tracker");
+    assert!(report.modules[1].move_candidates.is_empty());
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains(
+        "- Community 1 (4 capsules): shipping (75%), also billing (1)\n  - move tracker from billing to shipping\n"
+    ));
+    let json = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(
+        json["suggested_modularization"]["modules"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn a_single_cluster_suggests_nothing() {
+    let capsules = ["core/a", "core/b", "core/c"].map(capsule);
+    let [a, b, c] = &capsules;
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = vec![calls(a, b), calls(b, c), calls(c, a)];
+    assert!(ModularizationReport::from_graph(&graph).is_none());
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::exporter::Exporter;
+use archlens::graph::{CycleDetector, CONDENSED_KEY};
+use archlens::types::*;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn capsule(path: &str) -> Capsule {
+    let (dir, name) = path.split_once('/').unwrap();
+    Capsule {
+        file_path: PathBuf::from(format!("/repo/src/{dir}/{name}.rs")),
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// Цикл a → b → c → a, из него выходит связь к d, в него входит связь от e
+fn graph() -> CapsuleGraph {
+    let capsules = ["core/a", "core/b", "core/c", "util/d", "api/e"].map(capsule);
+    let [a, b, c, d, e] = &capsules;
+    let relations = vec![
+        relation(a, b, RelationType::Calls),
+        relation(b, c, RelationType::Calls),
+        relation(c, a, RelationType::Imports),
+        relation(c, d, RelationType::Calls),
+        relation(a, d, RelationType::Calls),
+        relation(b, d, RelationType::Imports),
+        relation(e, a, RelationType::Calls),
+    ];
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = relations;
+    graph
+}
+
+fn node<'a>(graph: &'a CapsuleGraph, name: &str) -> &'a Capsule {
+    graph
+        .capsules
+        .values()
+        .find(|c| c.name == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+}
+
+#[test]
+fn each_cycle_becomes_one_node() {
+    let graph = graph();
+    let condensed = CapsuleGraphBuilder::new().condense_graph(&graph);
+
+    assert_eq!(condensed.capsules.len(), 3);
+    let scc = node(&condensed, "SCC: a, b, c");
+    assert_eq!(scc.metadata[CONDENSED_KEY], "3");
+    assert_eq!(scc.size, 30);
+    let d = node(&condensed, "d");
+    let e = node(&condensed, "e");
+
+    // связи внутри цикла исчезают, параллельные связи сливаются по типу
+    let mut edges: Vec<(Uuid, Uuid, RelationType)> = condensed
+        .relations
+        .iter()
+        .map(|r| (r.from_id, r.to_id, r.relation_type))
+        .collect();
+    edges.sort();
+    let mut expected = vec![
+        (scc.id, d.id, RelationType::Calls),
+        (scc.id, d.id, RelationType::Imports),
+        (e.id, scc.id, RelationType::Calls),
+    ];
+    expected.sort();
+    assert_eq!(edges, expected);
+    assert_eq!(scc.dependencies, vec![d.id]);
+    assert_eq!(scc.dependents, vec![e.id]);
+    assert!(CycleDetector::new()
+        .get_strongly_connected_components(&condensed)
+        .is_empty());
+
+    // узел цикла не меняется между запусками
+    let again = CapsuleGraphBuilder::new().condense_graph(&graph);
+    assert!(again.capsules.contains_key(&scc.id));
+}
+
+#[test]
+fn acyclic_graph_is_unchanged_and_diagram_shows_cycle_node() {
+    let mut acyclic = graph();
+    acyclic
+        .relations
+        .retain(|r| r.relation_type != RelationType::Imports);
+    let condensed = CapsuleGraphBuilder::new().condense_graph(&acyclic);
+    assert_eq!(condensed.capsules.len(), acyclic.capsules.len());
+    assert_eq!(condensed.relations.len(), acyclic.relations.len());
+
+    let condensed = CapsuleGraphBuilder::new().condense_graph(&graph());
+    let mermaid = Exporter::new().export_to_mermaid(&condensed).unwrap();
+    assert!(mermaid.contains("e -->|calls| SCC__a__b__c"), "{mermaid}");
+}
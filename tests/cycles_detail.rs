@@ -0,0 +1,112 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::exporter::Exporter;
+use archlens::graph::{CycleDetector, CyclesDetail};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_cycles_detail_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn every_cycle_group_is_listed_with_edge_locations() {
+    let dir = temp_dir("project");
+    write(
+        &dir,
+        "src/order.rs",
+        "use crate::customer::Customer;\n\npub struct Order {\n    pub buyer: Customer,\n}\n",
+    );
+    write(
+        &dir,
+        "src/customer.rs",
+        "use crate::order::Order;\n\npub struct Customer {\n    pub orders: Vec<Order>,\n}\n",
+    );
+    write(
+        &dir,
+        "src/ring/first.rs",
+        "use crate::ring::second::Second;\n\npub struct First {\n    pub next: Second,\n}\n",
+    );
+    write(
+        &dir,
+        "src/ring/second.rs",
+        "use crate::ring::third::Third;\n\npub struct Second {\n    pub next: Third,\n}\n",
+    );
+    write(
+        &dir,
+        "src/ring/third.rs",
+        "use crate::ring::first::First;\n\npub struct Third {\n    pub next: Box<First>,\n}\n",
+    );
+    write(
+        &dir,
+        "src/standalone.rs",
+        "pub struct Standalone {\n    pub id: u32,\n}\n",
+    );
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let components = CycleDetector::new().get_strongly_connected_components(&graph);
+    assert_eq!(components.len(), 2);
+
+    let detail = CyclesDetail::from_graph(&graph).unwrap();
+    let members: Vec<Vec<&str>> = detail
+        .groups
+        .iter()
+        .map(|g| g.members.iter().map(|m| m.name.as_str()).collect())
+        .collect();
+    assert_eq!(
+        members,
+        [vec!["First", "Second", "Third"], vec!["Customer", "Order"]]
+    );
+    let ring = &detail.groups[0];
+    assert_eq!(ring.edges.len(), 3);
+    let third_to_first = ring
+        .edges
+        .iter()
+        .find(|e| e.from == "Third" && e.to == "First")
+        .unwrap();
+    let location = third_to_first.location.as_ref().unwrap();
+    assert_eq!(location.file, dir.join("src/ring/third.rs"));
+    assert_eq!(location.line, 1);
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact
+        .contains("## Cycles Detail (2 groups)\n- Group 1 (3 capsules): First, Second, Third\n"));
+    assert!(compact.contains(&format!(
+        "  - Third -> First (Imports) at {}:1\n",
+        dir.join("src/ring/third.rs").display()
+    )));
+    let json = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(json["cycles_detail"].as_array().unwrap().len(), 2);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn acyclic_graphs_have_no_cycles_detail() {
+    let dir = temp_dir("acyclic");
+    write(
+        &dir,
+        "src/a.rs",
+        "use crate::b::B;\n\npub struct A {\n    pub b: B,\n}\n",
+    );
+    write(&dir, "src/b.rs", "pub struct B {\n    pub id: u32,\n}\n");
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    assert!(CycleDetector::new()
+        .get_strongly_connected_components(&graph)
+        .is_empty());
+    assert!(CyclesDetail::from_graph(&graph).is_none());
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(!compact.contains("## Cycles Detail"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,115 @@
+use archlens::data_models::{Cardinality, DataModelDetector, ModelSource};
+use std::fs;
+use std::path::PathBuf;
+
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("archlens-erd-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    root
+}
+
+#[test]
+fn sql_schema_becomes_mermaid_erd_with_foreign_keys() {
+    let root = project(
+        "sql",
+        &[(
+            "db/schema.sql",
+            "CREATE TABLE authors (\n  id SERIAL PRIMARY KEY,\n  name VARCHAR(100) NOT NULL\n);\n\
+             CREATE TABLE books (\n  id INTEGER NOT NULL,\n  author_id INTEGER NOT NULL REFERENCES authors(id),\n  \
+             editor_id INTEGER,\n  PRIMARY KEY (id),\n  CONSTRAINT fk_editor FOREIGN KEY (editor_id) REFERENCES authors(id)\n);\n",
+        )],
+    );
+    let model = DataModelDetector::new(&root).detect().unwrap();
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(model.entities.len(), 2);
+    assert!(model.entities.iter().all(|e| e.source == ModelSource::Sql));
+    assert_eq!(model.relations.len(), 2);
+
+    let mermaid = model.to_mermaid();
+    assert!(mermaid.starts_with("erDiagram\n"));
+    assert!(mermaid.contains("serial id PK"));
+    assert!(mermaid.contains("integer author_id FK"));
+    assert!(mermaid.contains("books }o--|| authors : \"author_id\""));
+    // editor_id допускает NULL — необязательная сторона связи
+    assert!(mermaid.contains("books }o--o| authors : \"editor_id\""));
+}
+
+#[test]
+fn orm_models_are_linked_across_languages() {
+    let root = project(
+        "orm",
+        &[
+            (
+                "app/models.py",
+                "from django.db import models\n\nclass Tag(models.Model):\n    label = models.CharField(max_length=20)\n\n\
+                 class Article(models.Model):\n    owner = models.ForeignKey(\"Customer\", on_delete=models.CASCADE)\n    \
+                 tags = models.ManyToManyField(Tag)\n",
+            ),
+            (
+                "web/customer.entity.ts",
+                "@Entity(\"customers\")\nexport class Customer {\n  @PrimaryGeneratedColumn()\n  id: number;\n\n  \
+                 @OneToMany(() => Article, (a) => a.owner)\n  articles: Article[];\n}\n",
+            ),
+            (
+                "src/models.rs",
+                "#[derive(sqlx::FromRow)]\npub struct Session {\n    pub id: i64,\n    pub customer_id: i64,\n    \
+                 pub expires: Option<String>,\n}\n",
+            ),
+        ],
+    );
+    let model = DataModelDetector::new(&root).detect().unwrap();
+    let _ = fs::remove_dir_all(&root);
+
+    let names: Vec<&str> = model.entities.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["Article", "Customer", "Session", "Tag"]);
+    let customer = model
+        .entities
+        .iter()
+        .find(|e| e.name == "Customer")
+        .unwrap();
+    // обратная сторона @OneToMany не колонка
+    assert_eq!(customer.fields.len(), 1);
+
+    let has = |from: &str, to: &str, via: &str, cardinality: Cardinality| {
+        model
+            .relations
+            .iter()
+            .any(|r| r.from == from && r.to == to && r.via == via && r.cardinality == cardinality)
+    };
+    assert!(has("Article", "Customer", "owner", Cardinality::ManyToOne));
+    assert!(has("Article", "Tag", "tags", Cardinality::ManyToMany));
+    // связь по соглашению об именах *_id
+    assert!(has(
+        "Session",
+        "Customer",
+        "customer_id",
+        Cardinality::ManyToOne
+    ));
+}
+
+#[test]
+fn prisma_relations_render_as_plantuml() {
+    let root = project(
+        "prisma",
+        &[(
+            "prisma/schema.prisma",
+            "model Profile {\n  id     Int    @id\n  userId Int    @unique\n  user   Member @relation(fields: [userId], references: [id])\n}\n\n\
+             model Member {\n  id      Int      @id\n  profile Profile?\n  @@map(\"members\")\n}\n",
+        )],
+    );
+    let model = DataModelDetector::new(&root).detect().unwrap();
+    let _ = fs::remove_dir_all(&root);
+
+    let plantuml = model.to_plantuml();
+    assert!(plantuml.starts_with("@startuml\n"));
+    assert!(plantuml.contains("entity \"Member\" as Member {"));
+    assert!(plantuml.contains("* userId : Int <<FK>>"));
+    assert!(plantuml.contains("Profile |o--|| Member : userId"));
+    assert!(plantuml.trim_end().ends_with("@enduml"));
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use archlens::decision_links::{
+    conflicts_markdown, ConflictKind, DecisionChecker, DecisionConfig, DecisionSide,
+};
+use archlens::types::*;
+use std::path::{Path, PathBuf};
+
+fn capsule(root: &Path, file: &str, name: &str) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Struct,
+        file_path: root.join(file),
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+/// Граф из капсул (файл, имя) и связей по индексам капсул
+fn graph(root: &Path, capsules: &[(&str, &str)], edges: &[(usize, usize)]) -> CapsuleGraph {
+    let capsules: Vec<Capsule> = capsules
+        .iter()
+        .map(|(file, name)| capsule(root, file, name))
+        .collect();
+    let relations = edges
+        .iter()
+        .map(|(from, to)| CapsuleRelation {
+            from_id: capsules[*from].id,
+            to_id: capsules[*to].id,
+            relation_type: RelationType::Depends,
+            strength: 1.0,
+            description: None,
+        })
+        .collect();
+    common::graph(capsules, relations)
+}
+
+const CONFIG: &str = r#"{
+  "decisions": [
+    {
+      "adr": "docs/adr/0003-domain-isolation.md",
+      "capsules": ["Order", "src/domain/*.rs"],
+      "forbid": [{ "from": "src/domain/**", "to": "src/db/**" }],
+      "require": [{ "from": "OrderService", "to": "OrderRepository" }]
+    },
+    {
+      "adr": "docs/adr/0007-events.md",
+      "title": "Use the event bus",
+      "forbid": [{ "from": "Billing", "to": "Mailer" }]
+    }
+  ]
+}"#;
+
+fn project_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "archlens_decisions_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("docs/adr")).unwrap();
+    std::fs::write(
+        root.join("docs/adr/0003-domain-isolation.md"),
+        "# ADR 3: Domain does not touch the database\n\nStatus: accepted\n",
+    )
+    .unwrap();
+    root
+}
+
+#[test]
+fn reports_changes_that_contradict_decisions() {
+    let root = project_root("diff");
+    let checker = DecisionChecker::new(&root, DecisionConfig::from_json(CONFIG).unwrap());
+    let (old_root, new_root) = (PathBuf::from("/rev/old"), PathBuf::from("/rev/new"));
+
+    let files = [
+        ("src/domain/order.rs", "Order"),
+        ("src/app/service.rs", "OrderService"),
+        ("src/app/repository.rs", "OrderRepository"),
+        ("src/db/pool.rs", "Pool"),
+        ("src/billing.rs", "Billing"),
+        ("src/mailer.rs", "Mailer"),
+    ];
+    let previous = graph(&old_root, &files, &[(1, 2), (1, 3)]);
+    // Order -> Pool снова появилась, OrderService больше не использует репозиторий,
+    // Billing -> Mailer появилась
+    let current = graph(&new_root, &files, &[(0, 3), (1, 3), (4, 5)]);
+
+    let conflicts = checker
+        .check(
+            DecisionSide {
+                graph: &previous,
+                root: &old_root,
+            },
+            DecisionSide {
+                graph: &current,
+                root: &new_root,
+            },
+        )
+        .unwrap();
+
+    let summary: Vec<(ConflictKind, &str, String)> = conflicts
+        .iter()
+        .map(|c| (c.kind, c.title.as_str(), c.message()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                ConflictKind::ForbiddenDependencyAdded,
+                "ADR 3: Domain does not touch the database",
+                "forbidden dependency `src/domain/order.rs#Order` -> `src/db/pool.rs#Pool` introduced"
+                    .to_string()
+            ),
+            (
+                ConflictKind::RequiredDependencyRemoved,
+                "ADR 3: Domain does not touch the database",
+                "required dependency `src/app/service.rs#OrderService` -> `src/app/repository.rs#OrderRepository` removed"
+                    .to_string()
+            ),
+            (
+                ConflictKind::ForbiddenDependencyAdded,
+                "Use the event bus",
+                "forbidden dependency `src/billing.rs#Billing` -> `src/mailer.rs#Mailer` introduced"
+                    .to_string()
+            ),
+        ]
+    );
+
+    let md = conflicts_markdown(&conflicts);
+    assert!(md.contains("### Decision conflicts"));
+    assert!(md.contains(
+        "- **Use the event bus** ([docs/adr/0007-events.md](docs/adr/0007-events.md)): forbidden dependency"
+    ));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn removed_linked_capsules_and_existing_violations() {
+    let root = project_root("linked");
+    let checker = DecisionChecker::new(&root, DecisionConfig::from_json(CONFIG).unwrap());
+    let side_root = PathBuf::from("/repo");
+
+    // запрещённая связь уже была — это не новое противоречие
+    let previous = graph(
+        &side_root,
+        &[
+            ("src/domain/order.rs", "Order"),
+            ("src/domain/line.rs", "OrderLine"),
+            ("src/db/pool.rs", "Pool"),
+        ],
+        &[(0, 2)],
+    );
+    let current = graph(
+        &side_root,
+        &[("src/domain/order.rs", "Order"), ("src/db/pool.rs", "Pool")],
+        &[(0, 1)],
+    );
+    let side = |graph| DecisionSide {
+        graph,
+        root: &side_root,
+    };
+
+    let conflicts = checker.check(side(&previous), side(&current)).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].kind, ConflictKind::LinkedCapsuleRemoved);
+    assert_eq!(conflicts[0].from, "src/domain/line.rs#OrderLine");
+
+    let unchanged = checker.check(side(&current), side(&current)).unwrap();
+    assert!(unchanged.is_empty());
+    assert!(conflicts_markdown(&unchanged).contains("_No changes contradict"));
+
+    assert!(DecisionConfig::load(&root).unwrap().is_none());
+    assert!(DecisionConfig::from_json("{\"decisions\": [{}]}").is_err());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
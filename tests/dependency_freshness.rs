@@ -0,0 +1,29 @@
+use archlens::dependency_freshness::{DependencyFreshnessAnalyzer, Ecosystem};
+use std::path::Path;
+
+#[test]
+fn counts_outdated_dependencies_from_snapshot() {
+    let report = DependencyFreshnessAnalyzer::new()
+        .without_cargo_cache()
+        .analyze_project(Path::new("tests/fixtures/stale_deps"))
+        .expect("analysis");
+
+    assert_eq!(report.manifests.len(), 3);
+    // path-зависимость без версии не учитывается
+    assert_eq!(report.total_dependencies, 8);
+    assert_eq!(report.checked, 8);
+
+    let names: Vec<&str> = report.outdated.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["predicates", "tree-sitter", "lodash", "requests"]
+    );
+    assert!(report
+        .outdated
+        .iter()
+        .any(|d| d.ecosystem == Ecosystem::Pypi && d.latest == "2.32.3"));
+
+    let summary = report.to_summary_json();
+    assert_eq!(summary["outdated"], 4);
+    assert!(report.to_compact_section().contains("- Outdated: 4"));
+}
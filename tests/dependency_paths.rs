@@ -0,0 +1,151 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::graph::PathFinder;
+use archlens::types::*;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+fn capsule(path: &str, name: &str) -> Capsule {
+    Capsule {
+        file_path: PathBuf::from(format!("/repo/{path}")),
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// ui → service → repository → database; ui → cache → repository (тоже 3 шага);
+/// ui → admin → audit → repository → database (длиннее); ui связан с database
+/// ещё и структурно — такая связь пути не образует
+fn graph() -> CapsuleGraph {
+    let capsules = [
+        capsule("src/ui.rs", "ui"),
+        capsule("src/service.rs", "service"),
+        capsule("src/cache.rs", "cache"),
+        capsule("src/repository.rs", "repository"),
+        capsule("src/database.rs", "database"),
+        capsule("src/admin.rs", "admin"),
+        capsule("src/audit.rs", "audit"),
+    ];
+    let [ui, service, cache, repo, db, admin, audit] = &capsules;
+    let relations = vec![
+        relation(ui, service, RelationType::Calls),
+        relation(service, repo, RelationType::Calls),
+        relation(repo, db, RelationType::Imports),
+        relation(ui, cache, RelationType::Uses),
+        relation(ui, cache, RelationType::Calls),
+        relation(cache, repo, RelationType::Calls),
+        relation(ui, admin, RelationType::Calls),
+        relation(admin, audit, RelationType::Calls),
+        relation(audit, repo, RelationType::Calls),
+        relation(ui, db, RelationType::Depends),
+    ];
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = relations;
+    graph
+}
+
+fn chain(path: &archlens::graph::DependencyPath) -> Vec<&str> {
+    let mut names = vec![path.edges[0].from.as_str()];
+    names.extend(path.edges.iter().map(|e| e.to.as_str()));
+    names
+}
+
+#[test]
+fn all_shortest_paths_are_listed_with_edge_kinds() {
+    let graph = graph();
+    let report = PathFinder::new(&graph, Path::new("/repo")).find("ui", "database");
+
+    assert_eq!(report.length, 3);
+    let chains: Vec<Vec<&str>> = report.paths.iter().map(chain).collect();
+    assert_eq!(
+        chains,
+        vec![
+            vec!["ui", "cache", "repository", "database"],
+            vec!["ui", "service", "repository", "database"],
+        ]
+    );
+    assert!(!report.truncated);
+    // ui → cache есть и вызовом, и структурной связью: путь идёт по вызову
+    assert_eq!(report.paths[0].edges[0].relation, RelationType::Calls);
+    assert_eq!(report.paths[0].edges[2].relation, RelationType::Imports);
+    assert_eq!(
+        report.paths[0].edges[0].location.as_deref(),
+        Some("src/ui.rs:1")
+    );
+    let text = report.to_text();
+    assert!(text.contains("2 shortest path(s) of 3 hop(s)"), "{text}");
+    assert!(text.contains("cache -> repository (Calls)"), "{text}");
+}
+
+#[test]
+fn limits_missing_paths_and_unknown_names() {
+    let graph = graph();
+    let one = PathFinder::new(&graph, Path::new("/repo"))
+        .with_max_paths(1)
+        .find("src/ui.rs", "src/database.rs");
+    assert_eq!(one.paths.len(), 1);
+    assert!(one.truncated);
+
+    let backwards = PathFinder::new(&graph, Path::new("/repo")).find("database", "ui");
+    assert!(backwards.paths.is_empty());
+    assert_eq!(backwards.length, 0);
+    assert!(backwards.to_text().contains("no dependency path"));
+
+    let unknown = PathFinder::new(&graph, Path::new("/repo")).find("ui", "nonexistent_thing");
+    assert!(unknown.to_matches.is_empty());
+    assert!(unknown.to_text().contains("Nothing in the graph matches"));
+}
+
+#[test]
+fn stdio_path_find_returns_edges() {
+    let dir = std::env::temp_dir().join(format!("archlens_path_mcp_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("src/lib.rs"),
+        "pub fn base() -> u32 {\n    1\n}\n\npub fn middle() -> u32 {\n    base() + 1\n}\n\npub fn top() -> u32 {\n    middle() + 1\n}\n",
+    )
+    .unwrap();
+
+    let mut child = match Command::new(env!("CARGO_BIN_EXE_archlens-mcp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(_) => {
+            eprintln!("archlens-mcp not built; skipping stdio path.find e2e");
+            return;
+        }
+    };
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        let call = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "path_find", "arguments": {"project_path": dir, "from": "top", "to": "base"}}
+        });
+        stdin.write_all(format!("{call}\n").as_bytes()).unwrap();
+    }
+    let mut out = String::new();
+    let _ = child.stdout.take().unwrap().read_to_string(&mut out);
+    let _ = child.kill();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(out.contains("\"paths\""), "{out}");
+    assert!(out.contains(r#""from":"middle""#), "{out}");
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use archlens::diagram_layout::{LayoutCache, LayoutPlanner, LAYOUT_CELL_HEIGHT, LAYOUT_TOP};
+use archlens::exporter::Exporter;
+use archlens::types::*;
+use chrono::Utc;
+use std::path::Path;
+
+fn capsule(file: &str, name: &str, layer: &str) -> Capsule {
+    Capsule {
+        file_path: Path::new("/project").join(file),
+        line_end: 5,
+        size: 5,
+        complexity: 2,
+        layer: Some(layer.into()),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+/// Граф заново строится при каждом анализе — id капсул каждый раз новые
+fn graph(names: &[(&str, &str, &str)], edges: &[(usize, usize)]) -> CapsuleGraph {
+    let capsules: Vec<Capsule> = names
+        .iter()
+        .map(|(file, name, layer)| capsule(file, name, layer))
+        .collect();
+    let relations = edges
+        .iter()
+        .map(|(from, to)| CapsuleRelation {
+            from_id: capsules[*from].id,
+            to_id: capsules[*to].id,
+            relation_type: RelationType::Uses,
+            strength: 1.0,
+            description: None,
+        })
+        .collect();
+    common::graph(capsules, relations)
+}
+
+const BEFORE: &[(&str, &str, &str)] = &[
+    ("src/api.rs", "handler", "API"),
+    ("src/api.rs", "router", "API"),
+    ("src/core.rs", "parse", "Core"),
+    ("src/core.rs", "validate", "Core"),
+];
+
+#[test]
+fn cached_layout_keeps_positions_after_small_change() {
+    let dir = std::env::temp_dir().join(format!("archlens-layouts-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let cache = LayoutCache::new(&dir);
+    let planner = LayoutPlanner::new().with_root(Path::new("/project"));
+
+    let before = cache
+        .layout_for(&planner, &graph(BEFORE, &[(0, 2), (1, 3)]))
+        .unwrap();
+    assert_eq!(before.nodes["src/api.rs#handler"].y, LAYOUT_TOP);
+    assert_eq!(
+        before.nodes["src/core.rs#validate"].y,
+        LAYOUT_TOP + LAYOUT_CELL_HEIGHT
+    );
+
+    // Тот же граф при повторном анализе — та же раскладка из кэша
+    let again = cache
+        .layout_for(&planner, &graph(BEFORE, &[(0, 2), (1, 3)]))
+        .unwrap();
+    assert_eq!(again.fingerprint, before.fingerprint);
+    assert_eq!(again.created_at, before.created_at);
+
+    // `handler` удалён, добавлены `auth` (сортируется первым) и новый слой
+    let after = graph(
+        &[
+            ("src/api.rs", "auth", "API"),
+            ("src/api.rs", "router", "API"),
+            ("src/core.rs", "parse", "Core"),
+            ("src/core.rs", "validate", "Core"),
+            ("src/db.rs", "query", "Infrastructure"),
+        ],
+        &[(1, 2), (2, 4)],
+    );
+    let changed = cache.layout_for(&planner, &after).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_ne!(changed.fingerprint, before.fingerprint);
+    for key in [
+        "src/api.rs#router",
+        "src/core.rs#parse",
+        "src/core.rs#validate",
+    ] {
+        assert_eq!(changed.nodes[key], before.nodes[key], "{} moved", key);
+    }
+    // новый узел занимает освободившуюся ячейку своего слоя
+    assert_eq!(
+        changed.nodes["src/api.rs#auth"],
+        before.nodes["src/api.rs#handler"]
+    );
+    assert!(!changed.nodes.contains_key("src/api.rs#handler"));
+    let infra_x = changed.columns["Infrastructure"];
+    assert!(before.columns.values().all(|x| *x < infra_x));
+}
+
+#[test]
+fn svg_and_dot_follow_the_layout() {
+    let planner = LayoutPlanner::new().with_root(Path::new("/project"));
+    let first = graph(BEFORE, &[(0, 2), (1, 3), (0, 1)]);
+    let second = graph(BEFORE, &[(0, 1), (1, 3), (0, 2)]);
+    let layout = planner.compute(&first, None);
+    assert_eq!(planner.fingerprint(&second), layout.fingerprint);
+
+    let exporter = Exporter::new();
+    let svg = exporter
+        .export_to_svg_with_layout(&first, &planner, &layout)
+        .unwrap();
+    // порядок связей и id капсул не влияют на результат
+    assert_eq!(
+        svg,
+        exporter
+            .export_to_svg_with_layout(&second, &planner, &layout)
+            .unwrap()
+    );
+    assert!(svg.contains(&format!(
+        "<rect x=\"{}\" y=\"{}\"",
+        layout.nodes["src/core.rs#parse"].x, layout.nodes["src/core.rs#parse"].y
+    )));
+    assert_eq!(svg.matches("<line ").count(), 3);
+
+    let dot = exporter
+        .export_to_dot_with_layout(&first, &planner, &layout)
+        .unwrap();
+    assert!(dot.contains("layout=neato;"));
+    assert_eq!(dot.matches("pos=\"").count(), 4);
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use archlens::diff_analyzer::{content_hash, DiffAnalyzer, CONTENT_HASH_KEY};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+fn capsule(name: &str, file: &str, complexity: u32, body: &str) -> Capsule {
+    let mut metadata = HashMap::new();
+    metadata.insert(CONTENT_HASH_KEY.to_string(), content_hash(name, body));
+    Capsule {
+        file_path: file.into(),
+        complexity,
+        layer: Some("Core".into()),
+        metadata,
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn graph_of(capsules: Vec<Capsule>, edges: &[(usize, usize)]) -> CapsuleGraph {
+    let ids: Vec<Uuid> = capsules.iter().map(|c| c.id).collect();
+    let relations = edges
+        .iter()
+        .map(|(from, to)| CapsuleRelation {
+            from_id: ids[*from],
+            to_id: ids[*to],
+            relation_type: RelationType::Uses,
+            strength: 1.0,
+            description: None,
+        })
+        .collect();
+    common::graph(capsules, relations)
+}
+
+fn count(diff: &DiffAnalysis, kind: ChangeType) -> usize {
+    diff.changes
+        .iter()
+        .filter(|c| c.change_type == kind)
+        .count()
+}
+
+#[test]
+fn moved_directory_is_not_reported_as_added_and_removed() {
+    let previous = graph_of(
+        vec![
+            capsule("parse", "/p/src/legacy/parser.rs", 4, "fn parse(s: &str)"),
+            capsule(
+                "tokenize",
+                "/p/src/legacy/parser.rs",
+                2,
+                "fn tokenize(s: &str)",
+            ),
+            capsule(
+                "render",
+                "/p/src/legacy/output.rs",
+                3,
+                "fn render(g: &Graph)",
+            ),
+        ],
+        &[(0, 1), (2, 0)],
+    );
+    let current = graph_of(
+        vec![
+            capsule("parse", "/p/src/core/parser.rs", 4, "fn parse(s: &str)"),
+            capsule(
+                "tokenize",
+                "/p/src/core/parser.rs",
+                2,
+                "fn tokenize(s: &str)",
+            ),
+            capsule("render", "/p/src/core/output.rs", 3, "fn render(g: &Graph)"),
+        ],
+        &[(0, 1), (2, 0)],
+    );
+
+    let diff = DiffAnalyzer::new()
+        .analyze_diff(&current, &previous)
+        .unwrap();
+    assert_eq!(count(&diff, ChangeType::Moved), 3);
+    assert_eq!(count(&diff, ChangeType::Added), 0);
+    assert_eq!(count(&diff, ChangeType::Removed), 0);
+    assert_eq!(count(&diff, ChangeType::NewDependency), 0);
+    assert!(diff
+        .changes
+        .iter()
+        .any(|c| c.description.contains("src/legacy/parser.rs")
+            && c.description.contains("src/core/parser.rs")));
+}
+
+#[test]
+fn identical_body_under_new_name_is_a_rename() {
+    let previous = graph_of(
+        vec![
+            capsule(
+                "load_cfg",
+                "/p/src/config.rs",
+                5,
+                "pub fn load_cfg(path: &Path) -> Config",
+            ),
+            capsule("main", "/p/src/main.rs", 1, "fn main()"),
+        ],
+        &[(1, 0)],
+    );
+    let current = graph_of(
+        vec![
+            capsule(
+                "read_settings",
+                "/p/src/config.rs",
+                5,
+                "pub fn read_settings(path: &Path) -> Config",
+            ),
+            capsule("main", "/p/src/main.rs", 1, "fn main()"),
+        ],
+        &[(1, 0)],
+    );
+
+    let diff = DiffAnalyzer::new()
+        .analyze_diff(&current, &previous)
+        .unwrap();
+    let renamed: Vec<_> = diff
+        .changes
+        .iter()
+        .filter(|c| c.change_type == ChangeType::Renamed)
+        .collect();
+    assert_eq!(renamed.len(), 1);
+    assert_eq!(renamed[0].component, "read_settings");
+    assert_eq!(renamed[0].related_components, vec!["load_cfg".to_string()]);
+    assert_eq!(count(&diff, ChangeType::Added), 0);
+    assert_eq!(count(&diff, ChangeType::NewDependency), 0);
+    assert_eq!(count(&diff, ChangeType::RemovedDependency), 0);
+}
+
+#[test]
+fn unrelated_components_stay_added_and_removed() {
+    let previous = graph_of(
+        vec![capsule(
+            "export_csv",
+            "/p/src/csv.rs",
+            9,
+            "fn export_csv(rows: &[Row])",
+        )],
+        &[],
+    );
+    let current = graph_of(
+        vec![capsule(
+            "connect",
+            "/p/src/net.rs",
+            1,
+            "async fn connect(addr: &str)",
+        )],
+        &[],
+    );
+
+    let diff = DiffAnalyzer::new()
+        .analyze_diff(&current, &previous)
+        .unwrap();
+    assert_eq!(count(&diff, ChangeType::Added), 1);
+    assert_eq!(count(&diff, ChangeType::Removed), 1);
+    assert_eq!(count(&diff, ChangeType::Renamed), 0);
+}
+
+#[test]
+fn checkouts_in_different_roots_compare_relative_paths() {
+    let previous = graph_of(
+        vec![capsule(
+            "parse",
+            "/tmp/rev-a/src/parser.rs",
+            4,
+            "fn parse()",
+        )],
+        &[],
+    );
+    let current = graph_of(
+        vec![capsule(
+            "parse",
+            "/tmp/rev-b/src/parser.rs",
+            4,
+            "fn parse()",
+        )],
+        &[],
+    );
+
+    let diff = DiffAnalyzer::new()
+        .with_roots(Path::new("/tmp/rev-a"), Path::new("/tmp/rev-b"))
+        .analyze_diff(&current, &previous)
+        .unwrap();
+    assert!(diff.changes.is_empty());
+}
+
+#[test]
+fn content_hash_ignores_only_whole_identifier_matches() {
+    // Переименование `id` → `key` не должно задевать `idle` и `valid`
+    assert_eq!(
+        content_hash("id", "fn id(idle: bool) -> bool { valid(idle) }"),
+        content_hash("key", "fn key(idle: bool) -> bool { valid(idle) }"),
+    );
+    assert_ne!(
+        content_hash("id", "fn id(idle: bool) -> bool { valid(idle) }"),
+        content_hash("id", "fn id(_le: bool) -> bool { val_(_le) }"),
+    );
+}
@@ -0,0 +1,94 @@
+use archlens::constructor::CapsuleConstructor;
+use archlens::enrichment::QualityAnalyzer;
+use archlens::parser_ast::{ASTElement, ParserAST, DOC_KEY};
+use archlens::types::FileType;
+use std::path::Path;
+
+fn parse(name: &str, source: &str, file_type: FileType) -> Vec<ASTElement> {
+    ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new(name), source, &file_type)
+        .unwrap()
+}
+
+fn doc<'a>(elements: &'a [ASTElement], name: &str) -> Option<&'a str> {
+    elements
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+        .metadata
+        .get(DOC_KEY)
+        .map(String::as_str)
+}
+
+const STORE_RS: &str = r#"/// Хранилище заказов.
+///
+/// Потокобезопасно.
+#[derive(Debug)]
+pub struct Store {
+    items: Vec<u32>,
+}
+
+// обычный комментарий — не документация
+pub fn undocumented() {}
+
+/** Считает сумму. */
+pub fn total(store: &Store) -> u32 {
+    store.items.iter().sum()
+}
+"#;
+
+#[test]
+fn rust_doc_comments_skip_attributes() {
+    let elements = parse("store.rs", STORE_RS, FileType::Rust);
+    assert_eq!(
+        doc(&elements, "Store"),
+        Some("Хранилище заказов.\n\nПотокобезопасно.")
+    );
+    assert_eq!(doc(&elements, "undocumented"), None);
+    assert_eq!(doc(&elements, "total"), Some("Считает сумму."));
+}
+
+#[test]
+fn docstrings_jsdoc_and_line_comments() {
+    let python = "class Repo:\n    \"\"\"Репозиторий пользователей.\"\"\"\n\n    def load(self, key):\n        \"\"\"\n        Загружает запись.\n\n        Args:\n            key: ключ\n        \"\"\"\n        return key\n\n    def save(self):\n        pass\n";
+    let elements = parse("repo.py", python, FileType::Python);
+    assert_eq!(doc(&elements, "Repo"), Some("Репозиторий пользователей."));
+    assert_eq!(
+        doc(&elements, "load"),
+        Some("Загружает запись.\n\nArgs:\nkey: ключ")
+    );
+    assert_eq!(doc(&elements, "save"), None);
+
+    let js = "/**\n * Форматирует цену.\n * @param {number} value\n */\nexport function formatPrice(value) {\n  return value.toFixed(2);\n}\n";
+    let elements = parse("price.js", js, FileType::JavaScript);
+    assert_eq!(
+        doc(&elements, "formatPrice"),
+        Some("Форматирует цену.\n@param {number} value")
+    );
+
+    let ruby = "# Корзина покупок\nclass Cart\n  # Добавляет товар\n  def add(item)\n  end\nend\n";
+    let elements = parse("cart.rb", ruby, FileType::Ruby);
+    assert_eq!(doc(&elements, "Cart"), Some("Корзина покупок"));
+    assert_eq!(doc(&elements, "add"), Some("Добавляет товар"));
+}
+
+#[test]
+fn quality_uses_per_capsule_documentation() {
+    let elements = parse("store.rs", STORE_RS, FileType::Rust);
+    let capsules = CapsuleConstructor::new()
+        .create_capsules(&elements, Path::new("src/store.rs"))
+        .unwrap();
+    let analyzer = QualityAnalyzer::new();
+    let score = |name: &str| {
+        let capsule = capsules.iter().find(|c| c.name == name).unwrap();
+        analyzer
+            .analyze_quality(capsule, STORE_RS)
+            .unwrap()
+            .documentation_score
+    };
+    // один и тот же файл, но разные капсулы
+    assert_eq!(score("undocumented"), 0.0);
+    assert_eq!(score("total"), 70.0);
+    assert_eq!(score("Store"), 85.0);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::dsm::{dsm_html, DependencyMatrix, DsmLevel};
+use archlens::exporter::Exporter;
+use archlens::types::*;
+use std::path::PathBuf;
+
+fn capsule(name: &str, file: &str, layer: &str) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Struct,
+        file_path: PathBuf::from(file),
+        complexity: 2,
+        layer: Some(layer.into()),
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Depends,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// api → domain → storage → domain (цикл модулей domain/storage), core вне цикла
+fn graph() -> CapsuleGraph {
+    let api = capsule("Handler", "/repo/src/api/handler.rs", "API");
+    let domain = capsule("Order", "/repo/src/domain/order.rs", "Domain");
+    let storage = capsule("Repo", "/repo/src/storage/repo.rs", "Domain");
+    let lib = capsule("Config", "/repo/src/config.rs", "Core");
+    let relations = vec![
+        relation(&api, &domain),
+        relation(&api, &lib),
+        relation(&domain, &storage),
+        relation(&storage, &domain),
+    ];
+    let mut graph = CapsuleGraphBuilder::new()
+        .build_graph(&[api, domain, storage, lib])
+        .unwrap();
+    graph.relations = relations;
+    graph
+}
+
+#[test]
+fn module_matrix_counts_and_cycles() {
+    let matrix = DependencyMatrix::build(&graph(), DsmLevel::Module);
+    assert_eq!(matrix.labels, [".", "api", "domain", "storage"]);
+    assert_eq!(matrix.cells[1], [1, 0, 1, 0]);
+    assert_eq!(matrix.cyclic_labels(), ["domain", "storage"]);
+    assert_eq!(
+        matrix.to_csv(),
+        "Modules,.,api,domain,storage\n.,,,,\napi,1,,1,\ndomain,,,,1*\nstorage,,,1*,\n"
+    );
+}
+
+#[test]
+fn layer_matrix_and_html() {
+    let matrix = DependencyMatrix::build(&graph(), DsmLevel::Layer);
+    assert_eq!(matrix.labels, ["API", "Core", "Domain"]);
+    // связи внутри слоя — на диагонали, циклом не считаются
+    assert_eq!(matrix.cells[2][2], 2);
+    assert!(matrix.cyclic_labels().is_empty());
+
+    let html = dsm_html(&graph());
+    assert!(html.contains("<caption>Layers</caption>"));
+    assert!(html.contains("<td class=\"cycle\">1</td>"));
+    assert_eq!(
+        Exporter::new().registry().export("dsm", &graph()).unwrap(),
+        html
+    );
+}
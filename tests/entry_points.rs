@@ -0,0 +1,130 @@
+mod common;
+
+use archlens::entry_points::{
+    EntryPointAnalyzer, EntryPointConfig, ENTRY_POINT_KEY, UNREACHABLE_CATEGORY,
+};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+fn capsule(name: &str, kind: CapsuleType, file: &str, lines: (usize, usize)) -> Capsule {
+    Capsule {
+        capsule_type: kind,
+        file_path: PathBuf::from("/repo").join(file),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn depends(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Depends,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// main → Store (метод save вложен); handle_order → Store; legacy_export ни откуда не вызывается
+fn graph() -> CapsuleGraph {
+    let main = capsule("main", CapsuleType::Function, "src/main.rs", (1, 5));
+    let store = capsule("Store", CapsuleType::Struct, "src/store.rs", (1, 30));
+    let save = capsule("save", CapsuleType::Method, "src/store.rs", (10, 20));
+    let handler = capsule(
+        "handle_order",
+        CapsuleType::Function,
+        "src/handlers/orders.rs",
+        (1, 10),
+    );
+    let legacy = capsule(
+        "legacy_export",
+        CapsuleType::Function,
+        "src/legacy.rs",
+        (1, 8),
+    );
+    let relations = vec![depends(&main, &store), depends(&handler, &store)];
+    let capsules: HashMap<Uuid, Capsule> = [main, store, save, handler, legacy]
+        .into_iter()
+        .map(|c| (c.id, c))
+        .collect();
+    CapsuleGraph {
+        metrics: GraphMetrics {
+            total_capsules: capsules.len(),
+            total_relations: relations.len(),
+            complexity_average: 2.0,
+            depth_levels: 1,
+            ..Default::default()
+        },
+        capsules,
+        relations,
+        layers: HashMap::new(),
+        created_at: Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+fn analyzer(json: &str) -> EntryPointAnalyzer {
+    let config = EntryPointConfig::from_json(json).unwrap();
+    EntryPointAnalyzer::new(Path::new("/repo"), &config).unwrap()
+}
+
+#[test]
+fn reachability_from_declared_entry_points() {
+    let analyzer = analyzer(
+        r#"{"entry_points": [
+            {"name": "orders api", "file": "src/handlers/", "capsule": "^handle_"},
+            {"name": "reports", "capsule": "^report_"}
+        ]}"#,
+    );
+    let report = analyzer.analyze(&graph());
+
+    let reach: Vec<(&str, usize)> = report
+        .entry_points
+        .iter()
+        .map(|e| (e.name.as_str(), e.reachable))
+        .collect();
+    assert_eq!(reach, [("main (src/main.rs)", 3), ("orders api", 3)]);
+    assert_eq!(report.unmatched, ["reports"]);
+    let unreachable: Vec<&str> = report
+        .unreachable
+        .iter()
+        .map(|c| c.location.as_str())
+        .collect();
+    assert_eq!(unreachable, ["src/legacy.rs:1"]);
+    assert!((report.reachable_share - 0.8).abs() < 1e-6);
+}
+
+#[test]
+fn annotate_marks_entry_points_and_unreachable_capsules() {
+    let mut graph = graph();
+    analyzer(r#"{"entry_points": [{"file": "src/handlers/orders.rs"}], "detect_main": false}"#)
+        .annotate(&mut graph);
+    let by_name = |name: &str| graph.capsules.values().find(|c| c.name == name).unwrap();
+
+    assert_eq!(
+        by_name("handle_order").metadata[ENTRY_POINT_KEY],
+        "src/handlers/orders.rs"
+    );
+    for name in ["main", "legacy_export"] {
+        assert!(
+            by_name(name)
+                .warnings
+                .iter()
+                .any(|w| w.category == UNREACHABLE_CATEGORY),
+            "{name}"
+        );
+    }
+    assert!(by_name("save").warnings.is_empty());
+
+    // без найденных точек входа ничего не помечается
+    let mut graph = self::graph();
+    analyzer(r#"{"entry_points": [{"capsule": "^nothing$"}], "detect_main": false}"#)
+        .annotate(&mut graph);
+    assert!(graph.capsules.values().all(|c| c.warnings.is_empty()));
+}
@@ -0,0 +1,107 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::error_propagation::*;
+use archlens::exporter::Exporter;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_error_propagation_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn error_types_are_resolved_through_aliases() {
+    let aliases = HashMap::from([(
+        "Result".to_string(),
+        "std::result::Result<T, Box<dyn std::error::Error>>".to_string(),
+    )]);
+    let error = error_type_of("Result<Config>", &aliases).unwrap();
+    assert_eq!(error, "Box<dyn std::error::Error>");
+    assert!(is_broad_error_type(&error));
+
+    assert_eq!(
+        error_type_of("Result<Vec<u8>, ParseError>", &HashMap::new()).as_deref(),
+        Some("ParseError")
+    );
+    assert_eq!(
+        error_type_of("io::Result<()>", &aliases).as_deref(),
+        Some("std::io::Error")
+    );
+    assert_eq!(
+        error_type_of("anyhow::Result<u32>", &HashMap::new()).as_deref(),
+        Some("anyhow::Error")
+    );
+    assert_eq!(error_type_of("Option<u32>", &aliases), None);
+
+    assert!(is_broad_error_type("String"));
+    assert!(is_broad_error_type("Box<dyn Error + Send + Sync>"));
+    assert!(!is_broad_error_type("ParseError"));
+    assert!(!is_broad_error_type("std::io::Error"));
+}
+
+#[test]
+fn broad_public_errors_are_traced_across_modules() {
+    let dir = temp_dir("project");
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("src/parse.rs"),
+        "pub fn parse_port(text: &str) -> Result<u16, String> {\n    text.parse().map_err(|_| \"bad port\".to_string())\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("src/config.rs"),
+        "pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;\n\npub fn load(path: &str) -> Result<u16> {\n    let text = std::fs::read_to_string(path)?;\n    let port = parse_port(&text).map_err(|e| e.into())?;\n    Ok(port)\n}\n\npub fn checked(port: u16) -> std::result::Result<u16, ConfigError> {\n    Ok(port)\n}\n\npub struct ConfigError;\n",
+    )
+    .unwrap();
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let load = graph.capsules.values().find(|c| c.name == "load").unwrap();
+    let flow = capsule_error_flow(load).unwrap();
+    assert!(flow.public && flow.broad);
+    assert_eq!(flow.error_type, "Box<dyn std::error::Error>");
+    assert_eq!(flow.propagations, 2);
+    assert_eq!(flow.sources.len(), 1);
+    assert_eq!(flow.sources[0].module, "parse");
+    assert_eq!(flow.sources[0].function, "parse_port");
+    assert_eq!(flow.sources[0].error_type.as_deref(), Some("String"));
+    let warning = load
+        .warnings
+        .iter()
+        .find(|w| w.category == API_QUALITY_CATEGORY)
+        .unwrap();
+    assert_eq!(
+        warning.message,
+        "Public API load returns broad error type Box<dyn std::error::Error>; propagates errors from parse::parse_port"
+    );
+
+    let checked = graph
+        .capsules
+        .values()
+        .find(|c| c.name == "checked")
+        .unwrap();
+    assert!(!capsule_error_flow(checked).unwrap().broad);
+    assert!(checked
+        .warnings
+        .iter()
+        .all(|w| w.category != API_QUALITY_CATEGORY));
+
+    let report = ErrorPropagationReport::from_graph(&graph).unwrap();
+    let files: Vec<&PathBuf> = report.modules.iter().map(|m| &m.file).collect();
+    assert_eq!(
+        files,
+        [&PathBuf::from("config.rs"), &PathBuf::from("parse.rs")]
+    );
+    assert_eq!(report.modules[0].public_functions, 2);
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains(
+        "## Broad Error Types (public API)\n- config.rs: 1/2 public fns\n  - load -> Box<dyn std::error::Error> (from parse::parse_port)\n"
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,134 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::execution::*;
+use archlens::exporter::Exporter;
+use archlens::file_scanner::FileScanner;
+use std::path::PathBuf;
+use tokio::runtime::RuntimeFlavor;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_execution_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Режим задаётся переменной окружения процесса, поэтому внутри процесса его меняет один тест
+#[test]
+fn single_thread_flag_fixes_runtime_and_file_order() {
+    configure_from_args(["archlens", "export", "."]);
+    assert!(!single_threaded());
+    assert_eq!(
+        runtime().unwrap().handle().runtime_flavor(),
+        RuntimeFlavor::MultiThread
+    );
+
+    configure_from_args(["archlens", "export", ".", SINGLE_THREAD_FLAG, "ai_compact"]);
+    assert!(single_threaded());
+    assert_eq!(
+        runtime().unwrap().handle().runtime_flavor(),
+        RuntimeFlavor::CurrentThread
+    );
+
+    let dir = temp_dir("order");
+    for name in [
+        "zeta.rs",
+        "alpha.rs",
+        "mid/beta.rs",
+        "mid/alpha.rs",
+        "gamma.rs",
+    ] {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "pub fn f() {}\n").unwrap();
+    }
+    let scanner = FileScanner::new(vec!["**/*.rs".into()], Vec::new(), None).unwrap();
+    let files: Vec<PathBuf> = scanner
+        .scan_files(&dir)
+        .unwrap()
+        .into_iter()
+        .map(|f| f.path.strip_prefix(&dir).unwrap().to_path_buf())
+        .collect();
+    assert_eq!(
+        files,
+        [
+            "alpha.rs",
+            "gamma.rs",
+            "mid/alpha.rs",
+            "mid/beta.rs",
+            "zeta.rs"
+        ]
+        .map(PathBuf::from)
+    );
+
+    // Циклы и равные счётчики не зависят от порядка обхода хеш-таблиц
+    let project = temp_dir("repeat");
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    for (name, next) in [("a", "b"), ("b", "c"), ("c", "a"), ("d", "a"), ("e", "b")] {
+        std::fs::write(
+            project.join(format!("src/{}.rs", name)),
+            format!(
+                "use crate::{next}::{next}_run;\n\npub fn {name}_run() {{\n    if true {{\n        {next}_run();\n    }}\n}}\n"
+            ),
+        )
+        .unwrap();
+    }
+    let export = || {
+        let graph = build_project_graph(project.to_str().unwrap()).unwrap();
+        Exporter::new().export_to_ai_compact(&graph).unwrap()
+    };
+    let first = export();
+    for _ in 0..3 {
+        assert_eq!(export(), first);
+    }
+
+    std::env::remove_var(SINGLE_THREAD_ENV);
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&project);
+}
+
+// Хеш-таблицы засеиваются заново в каждом процессе: сравниваются запуски бинарника
+#[test]
+fn single_thread_export_is_identical_across_processes() {
+    let project = temp_dir("processes");
+    std::fs::create_dir_all(project.join("src")).unwrap();
+    // модуль `handler` и функция `a::handler` с равными счётчиками зависимостей
+    let names = ["handler", "store", "worker"];
+    let mut lib = String::new();
+    let mut main = String::from("mod a;\n");
+    for name in names {
+        std::fs::write(
+            project.join(format!("src/{}.rs", name)),
+            "pub fn start() -> u32 {\n    1\n}\n",
+        )
+        .unwrap();
+        lib.push_str(&format!("pub fn {}() -> u32 {{\n    2\n}}\n\n", name));
+        main.push_str(&format!("mod {};\n", name));
+    }
+    main.push_str("\nfn main() {\n");
+    for name in names {
+        main.push_str(&format!("    a::{name}();\n    {name}::start();\n"));
+    }
+    main.push_str("}\n");
+    std::fs::write(project.join("src/a.rs"), lib).unwrap();
+    std::fs::write(project.join("src/main.rs"), main).unwrap();
+    let export = || {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_archlens"))
+            .args(["export", project.to_str().unwrap(), "ai_compact"])
+            .arg(SINGLE_THREAD_FLAG)
+            .env("ARCHLENS_GIT_METADATA", "0")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let first = export();
+    assert!(first.contains("- handler (Function) : blast radius"));
+    for _ in 0..4 {
+        assert_eq!(export(), first);
+    }
+    let _ = std::fs::remove_dir_all(&project);
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use archlens::export_estimate::{estimate_tokens, DetailLevel, ExportEstimate};
+use archlens::types::*;
+use assert_cmd::prelude::*;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn graph(names: &[&str]) -> CapsuleGraph {
+    let capsules: Vec<Capsule> = names
+        .iter()
+        .map(|name| Capsule {
+            capsule_type: CapsuleType::Module,
+            file_path: PathBuf::from(format!("src/{}.rs", name)),
+            quality_score: 0.5,
+            ..common::capsule(name)
+        })
+        .collect();
+    common::graph(capsules, vec![])
+}
+
+#[test]
+fn levels_drop_code_and_respect_limits() {
+    let graph = graph(&["parser", "renderer", "ledger"]);
+    let mut report = String::from("# Report\n\n## parser\n\n```rust\nfn ledger() {}\n```\n\n");
+    report.push_str("## renderer\n\n");
+    // хвост отчёта не помещается в лимит summary
+    report.push_str(&"x".repeat(40_000));
+    report.push_str("\n## ledger\n");
+
+    let estimate = ExportEstimate::new("ai_compact", &report, &graph);
+    assert_eq!(estimate.total_capsules, 3);
+
+    let full = estimate.level(DetailLevel::Full).unwrap();
+    assert_eq!(full.characters, report.chars().count());
+    assert_eq!(
+        (full.capsules, full.sections, full.truncated),
+        (3, 4, false)
+    );
+    assert_eq!(full.tokens, estimate_tokens(full.characters));
+
+    let standard = estimate.level(DetailLevel::Standard).unwrap();
+    assert!(standard.characters < full.characters);
+    assert_eq!((standard.capsules, standard.sections), (3, 4));
+
+    let summary = estimate.level(DetailLevel::Summary).unwrap();
+    assert!(summary.truncated);
+    assert_eq!(summary.characters, DetailLevel::Summary.char_limit());
+    // код и хвост с `ledger` отрезаны
+    assert_eq!((summary.capsules, summary.sections), (2, 3));
+
+    let text = estimate.to_text();
+    assert!(text.starts_with("Dry run: ai_compact export, 3 capsules in graph"));
+    assert!(text.contains("(truncated)"));
+}
+
+#[test]
+fn sections_follow_the_format() {
+    let graph = graph(&["Order"]);
+    let csv = "priority,component\nhigh,Order\nlow,Order\n";
+    let html = "<h1>Report</h1><h2>Order</h2><h3>Findings</h3><h2>Other</h2>";
+    assert_eq!(
+        ExportEstimate::new("backlog", csv, &graph).levels[0].sections,
+        2
+    );
+    assert_eq!(
+        ExportEstimate::new("email", html, &graph).levels[0].sections,
+        4
+    );
+    assert_eq!(estimate_tokens(0), 0);
+    assert_eq!(estimate_tokens(9), 3);
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let output = std::env::temp_dir().join(format!("archlens_dry_run_{}.html", std::process::id()));
+    let _ = std::fs::remove_file(&output);
+    let result = Command::cargo_bin("archlens")
+        .unwrap()
+        .args([
+            "export",
+            "tests/fixtures/small_project",
+            "html",
+            "--dry-run",
+            "--output",
+        ])
+        .arg(&output)
+        .env("ARCHLENS_AUDIT", "off")
+        .output()
+        .unwrap();
+    assert!(result.status.success());
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(stdout.starts_with("Dry run: html export"), "{stdout}");
+    for level in ["summary", "standard", "full"] {
+        assert!(stdout.lines().any(|l| l.starts_with(level)), "{stdout}");
+    }
+    assert!(!output.exists());
+}
+
+#[test]
+fn auto_level_scales_with_project_size_and_budget() {
+    assert_eq!(DetailLevel::parse(" Full "), Some(DetailLevel::Full));
+    assert_eq!(DetailLevel::parse("auto"), None);
+
+    let small = DetailLevel::auto(20, None);
+    assert_eq!((small.level, small.top_n), (DetailLevel::Full, None));
+    let medium = DetailLevel::auto(800, None);
+    assert_eq!(
+        (medium.level, medium.top_n),
+        (DetailLevel::Standard, Some(20))
+    );
+    let large = DetailLevel::auto(5_000, None);
+    assert_eq!((large.level, large.top_n), (DetailLevel::Summary, Some(10)));
+    assert_eq!(DetailLevel::auto(50_000, None).top_n, Some(5));
+
+    // тесный бюджет понижает уровень даже для небольшого проекта
+    assert_eq!(
+        DetailLevel::auto(100, Some(100_000)).level,
+        DetailLevel::Full
+    );
+    assert_eq!(
+        DetailLevel::auto(100, Some(50_000)).level,
+        DetailLevel::Standard
+    );
+    assert_eq!(
+        DetailLevel::auto(100, Some(20_000)).level,
+        DetailLevel::Summary
+    );
+}
@@ -129,6 +129,7 @@ fn build_test_graph() -> CapsuleGraph {
         cohesion_index: 0.25,
         cyclomatic_complexity: 6,
         depth_levels: 2,
+        layering_violation_index: 0.0,
     };
 
     CapsuleGraph {
@@ -0,0 +1,83 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn capsule(name: &str, complexity: u32, warnings: &[(Priority, &str)]) -> Capsule {
+    let id = Uuid::new_v4();
+    Capsule {
+        id,
+        file_path: PathBuf::from(format!("src/{}.rs", name.to_lowercase())),
+        line_end: 20,
+        size: 20,
+        complexity,
+        layer: Some("Core".into()),
+        warnings: warnings
+            .iter()
+            .map(|(level, message)| AnalysisWarning {
+                message: message.to_string(),
+                level: *level,
+                category: "complexity".into(),
+                capsule_id: Some(id),
+                suggestion: None,
+            })
+            .collect(),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn digest() -> String {
+    let capsules = vec![
+        capsule(
+            "Router<T>",
+            12,
+            &[
+                (Priority::Low, "Missing docs"),
+                (Priority::Critical, "Cyclomatic complexity 42 > 10"),
+            ],
+        ),
+        capsule("Parser", 3, &[]),
+    ];
+    let ids: Vec<Uuid> = capsules.iter().map(|c| c.id).collect();
+    let graph = CapsuleGraph {
+        layers: HashMap::from([("Core".to_string(), ids)]),
+        ..common::graph(capsules, vec![])
+    };
+    Exporter::new().export_to_email_summary(&graph).unwrap()
+}
+
+#[test]
+fn email_summary_is_self_contained_with_inline_styles() {
+    let html = digest();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("<style"));
+    assert!(!html.contains("<link"));
+    assert!(!html.contains("class=\""));
+    assert!(!html.contains("http://") && !html.contains("https://"));
+    assert!(html.contains("<body style=\""));
+    assert!(html.contains("role=\"presentation\""));
+}
+
+#[test]
+fn email_summary_lists_metrics_and_problems_by_severity() {
+    let html = digest();
+
+    assert!(html.contains("Architecture digest"));
+    assert!(html.contains("2 (critical 1, high 0, medium 0, low 1)"));
+    // имена и сообщения экранируются
+    assert!(html.contains("Router&lt;T&gt;"));
+    assert!(html.contains("Cyclomatic complexity 42 &gt; 10"));
+    let critical = html.find("Cyclomatic complexity 42").unwrap();
+    let low = html.find("Missing docs").unwrap();
+    assert!(critical < low, "problems must be ordered by severity");
+    assert!(html.contains("Most complex components"));
+    assert!(html.contains("<td style=\"padding: 6px 8px; border-bottom: 1px solid #e5e5e5; color: #222222;\">Core</td>"));
+}
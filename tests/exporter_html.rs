@@ -0,0 +1,88 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::types::*;
+use chrono::Utc;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn capsule(file: &str, name: &str, layer: &str, warnings: &[(Priority, &str)]) -> Capsule {
+    let id = Uuid::new_v4();
+    Capsule {
+        id,
+        file_path: PathBuf::from(file),
+        line_start: 3,
+        line_end: 9,
+        size: 7,
+        complexity: 4,
+        layer: Some(layer.into()),
+        warnings: warnings
+            .iter()
+            .map(|(level, message)| AnalysisWarning {
+                message: message.to_string(),
+                level: *level,
+                category: "complexity".into(),
+                capsule_id: Some(id),
+                suggestion: Some("split it".into()),
+            })
+            .collect(),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn report() -> String {
+    let capsules = vec![
+        capsule(
+            "src/api/routes.rs",
+            "handle_request",
+            "API",
+            &[
+                (Priority::Medium, "Function is too long"),
+                (Priority::Critical, "Cyclomatic complexity 42"),
+            ],
+        ),
+        capsule("src/core/parse.rs", "Parser<T>", "Core", &[]),
+    ];
+    let graph = common::graph(capsules, vec![]);
+    Exporter::new().export_to_interactive_html(&graph).unwrap()
+}
+
+#[test]
+fn interactive_html_has_filters_sidebar_and_permalinks() {
+    let html = report();
+
+    assert!(html.contains("<input id=\"search\" type=\"search\""));
+    assert!(html.contains("<option value=\"4\">Critical</option>"));
+    assert!(html.contains("<option value=\"API\">API</option>"));
+    assert!(html.contains("<option value=\"Core\">Core</option>"));
+    assert!(html.contains("function applyFilters()"));
+
+    let anchor = "c-routes-rs-handle-request";
+    assert!(html.contains(&format!(
+        "id=\"{}\" data-layer=\"API\" data-severity=\"4\"",
+        anchor
+    )));
+    assert!(html.contains(&format!("<a class=\"permalink\" href=\"#{}\"", anchor)));
+    // находки: якорь на каждой, панель проблем — по убыванию важности
+    assert!(html.contains(&format!("<li id=\"{}-w1\" class=\"sev-4\">", anchor)));
+    assert!(html.contains("<h2>Проблемы (2)</h2>"));
+    let critical = html
+        .find(&format!("href=\"#{}-w1\">[Critical]", anchor))
+        .unwrap();
+    let medium = html
+        .find(&format!("href=\"#{}-w0\">[Medium]", anchor))
+        .unwrap();
+    assert!(critical < medium);
+
+    // имена экранируются, капсула без проблем имеет нулевую важность
+    assert!(html.contains("<h3>Parser&lt;T&gt;<a"));
+    assert!(html.contains("id=\"c-parse-rs-parser-t\" data-layer=\"Core\" data-severity=\"0\""));
+}
+
+#[test]
+fn permalinks_are_stable_between_runs() {
+    // id капсул меняются между запусками, якоря — нет
+    assert_eq!(report(), report());
+}
@@ -0,0 +1,75 @@
+use archlens::exporter::{Exporter, ExporterRegistry, GraphExporter};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+
+fn graph() -> CapsuleGraph {
+    CapsuleGraph {
+        capsules: HashMap::new(),
+        relations: vec![],
+        layers: HashMap::new(),
+        metrics: GraphMetrics::default(),
+        created_at: Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+struct CsvExporter(&'static str);
+
+impl GraphExporter for CsvExporter {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["comma"]
+    }
+
+    fn description(&self) -> &str {
+        "Capsules as CSV"
+    }
+
+    fn file_extension(&self) -> &str {
+        "csv"
+    }
+
+    fn export(&self, graph: &CapsuleGraph) -> Result<String> {
+        Ok(format!("{}{}", self.0, graph.capsules.len()))
+    }
+}
+
+#[test]
+fn builtin_formats_are_registered() {
+    let registry = Exporter::new().registry();
+    let names: Vec<_> = registry.formats().map(|e| e.name().to_string()).collect();
+    for name in ["json", "yaml", "mermaid", "dot", "graphml", "svg"] {
+        assert!(names.iter().any(|n| n == name), "{name}");
+    }
+    assert_eq!(registry.get("YML").unwrap().name(), "yaml");
+    assert_eq!(registry.get("graphviz").unwrap().file_extension(), "dot");
+    assert!(registry.export("docx", &graph()).is_err());
+
+    let via_registry = registry.export("mermaid", &graph()).unwrap();
+    assert_eq!(
+        via_registry,
+        Exporter::new().export_to_mermaid(&graph()).unwrap()
+    );
+    let path = std::env::temp_dir().join(format!("archlens-registry-{}.mmd", std::process::id()));
+    let written = Exporter::new()
+        .export(&graph(), ExportFormat::Mermaid, &path)
+        .unwrap();
+    assert_eq!(written, via_registry);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), via_registry);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn custom_exporter_is_registered_and_replaced() {
+    let mut registry = ExporterRegistry::new();
+    registry.register(Box::new(CsvExporter("v1:")));
+    assert_eq!(registry.export("comma", &graph()).unwrap(), "v1:0");
+
+    registry.register(Box::new(CsvExporter("v2:")));
+    assert_eq!(registry.formats().count(), 1);
+    assert_eq!(registry.export("CSV", &graph()).unwrap(), "v2:0");
+}
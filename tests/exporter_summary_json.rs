@@ -82,6 +82,7 @@ fn build_small_graph() -> CapsuleGraph {
         cohesion_index: 0.25,
         cyclomatic_complexity: 4,
         depth_levels: 2,
+        layering_violation_index: 0.0,
     };
     CapsuleGraph {
         capsules,
@@ -195,6 +195,7 @@ fn build_graph_layers_highsev() -> CapsuleGraph {
         cohesion_index: 0.4,
         cyclomatic_complexity: 7,
         depth_levels: 3,
+        layering_violation_index: 0.0,
     };
 
     CapsuleGraph {
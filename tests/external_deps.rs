@@ -0,0 +1,107 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::dependency_freshness::Ecosystem;
+use archlens::exporter::Exporter;
+use archlens::external_deps::*;
+use archlens::types::RelationType;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_external_deps_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn manifest_packages_become_capsules_linked_to_importing_modules() {
+    let dir = temp_dir("project");
+    write(
+        &dir,
+        "Cargo.toml",
+        "[package]\nname = \"shop\"\n\n[dependencies]\nserde-json = \"1\"\nsqlx = \"0.7\"\nunused = \"2\"\n",
+    );
+    write(
+        &dir,
+        "src/lib.rs",
+        "pub fn encode(v: &str) -> String {\n    serde_json::to_string(v).unwrap()\n}\n",
+    );
+    write(
+        &dir,
+        "src/domain/order.rs",
+        "use sqlx::PgPool;\n\npub struct Order {\n    pub id: u32,\n}\n\npub fn load(pool: &PgPool) -> Order {\n    Order { id: 1 }\n}\n",
+    );
+    write(
+        &dir,
+        "web/package.json",
+        r#"{"dependencies": {"axios": "^1.0.0", "@scope/ui": "2.0.0"}}"#,
+    );
+    write(
+        &dir,
+        "web/app.ts",
+        "import axios from 'axios';\nimport { Button } from '@scope/ui/button';\nimport { local } from './local';\n\nexport function fetchAll(): void {\n  axios.get('/api');\n}\n",
+    );
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let external: Vec<_> = graph
+        .capsules
+        .values()
+        .filter(|c| c.metadata.contains_key(EXTERNAL_PACKAGE_KEY))
+        .collect();
+    let mut names: Vec<&str> = external.iter().map(|c| c.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["@scope/ui", "axios", "serde-json", "sqlx"]);
+    let sqlx = external.iter().find(|c| c.name == "sqlx").unwrap();
+    assert_eq!(sqlx.layer.as_deref(), Some(EXTERNAL_LAYER));
+    assert!(sqlx.metadata.contains_key(INFRASTRUCTURE_KEY));
+    assert!(graph.relations.iter().any(|r| r.to_id == sqlx.id
+        && r.relation_type == RelationType::Imports
+        && graph.capsules[&r.from_id]
+            .file_path
+            .ends_with("src/domain/order.rs")));
+
+    // доменный модуль, импортирующий sqlx напрямую, получает предупреждение слоя
+    let flagged: Vec<_> = graph
+        .capsules
+        .values()
+        .flat_map(|c| &c.warnings)
+        .filter(|w| w.message.contains("imports infrastructure library"))
+        .collect();
+    assert_eq!(flagged.len(), 1);
+    assert!(flagged[0].message.contains("sqlx"));
+
+    let report = ExternalDependencyReport::from_graph(&graph).unwrap();
+    let axios = report.packages.iter().find(|p| p.name == "axios").unwrap();
+    assert_eq!(axios.ecosystem, Ecosystem::Npm);
+    assert!(axios.infrastructure);
+    assert_eq!(axios.modules, [dir.join("web/app.ts")]);
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## External Dependencies (4)\n"));
+    assert!(compact.contains(&format!(
+        "- serde-json (cargo): {}\n",
+        dir.join("src/lib.rs").display()
+    )));
+    let json = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(json["external_dependencies"].as_array().unwrap().len(), 4);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn infrastructure_libraries_are_recognized_per_ecosystem() {
+    assert!(is_infrastructure(Ecosystem::Cargo, "tokio-postgres"));
+    assert!(is_infrastructure(Ecosystem::Cargo, "tokio_postgres"));
+    assert!(!is_infrastructure(Ecosystem::Cargo, "serde"));
+    assert!(is_infrastructure(Ecosystem::Npm, "@prisma/client"));
+    assert!(is_infrastructure(Ecosystem::Pypi, "SQLAlchemy"));
+    assert!(!is_infrastructure(Ecosystem::Pypi, "numpy"));
+}
@@ -0,0 +1,121 @@
+use archlens::cli::stats::get_project_stats;
+use archlens::file_scanner::{FileScanner, ScanLimits, SkipReason, SkippedFiles};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_scanner_limits_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Проект с обычным кодом и четырьмя файлами, которые не стоит разбирать
+fn write_project(dir: &Path) {
+    std::fs::write(dir.join("lib.rs"), "pub fn run() {}\n").unwrap();
+    std::fs::write(
+        dir.join("app.js"),
+        "export function start() {\n  return 1;\n}\n",
+    )
+    .unwrap();
+    let bundle = format!("var a={};", "1,".repeat(2_000));
+    std::fs::write(dir.join("bundle.js"), bundle).unwrap();
+    std::fs::write(dir.join("vendor.min.js"), "var b=1;\n").unwrap();
+    std::fs::write(dir.join("blob.rs"), b"pub fn x() {}\0\x01\x02").unwrap();
+    std::fs::write(dir.join("huge.rs"), "// filler\n".repeat(600)).unwrap();
+}
+
+fn scanner(limits: ScanLimits) -> FileScanner {
+    FileScanner::new(vec!["**/*.rs".into(), "**/*.js".into()], vec![], None)
+        .unwrap()
+        .with_limits(limits)
+}
+
+fn names(files: &[archlens::types::FileMetadata]) -> Vec<String> {
+    let mut names: Vec<String> = files
+        .iter()
+        .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn scanner_skips_large_binary_and_minified_files() {
+    let dir = temp_dir("scan");
+    write_project(&dir);
+
+    let limits = ScanLimits {
+        max_file_size: Some(5_000),
+        ..ScanLimits::default()
+    };
+    let (files, skipped) = scanner(limits).scan_files_with_skipped(&dir).unwrap();
+    assert_eq!(names(&files), ["app.js", "lib.rs"]);
+    assert_eq!(
+        skipped,
+        SkippedFiles {
+            too_large: 1,
+            binary: 1,
+            minified: 2,
+        }
+    );
+
+    // без ограничений читаются все файлы
+    let unlimited = ScanLimits {
+        max_file_size: None,
+        detect_binary: false,
+        skip_minified: false,
+    };
+    let (files, skipped) = scanner(unlimited).scan_files_with_skipped(&dir).unwrap();
+    assert_eq!(files.len(), 6);
+    assert_eq!(skipped.total(), 0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn content_checks_classify_files() {
+    let limits = ScanLimits::default();
+    assert_eq!(
+        limits.check_size(archlens::file_scanner::DEFAULT_MAX_FILE_SIZE + 1),
+        Some(SkipReason::TooLarge)
+    );
+    assert_eq!(limits.check_size(10), None);
+    assert_eq!(
+        limits.check_content(Path::new("a.py"), &[0xff, 0xfe, b'x']),
+        Some(SkipReason::Binary)
+    );
+    // длинные строки допустимы вне JS/TS
+    let long_line = "x".repeat(5_000);
+    assert_eq!(
+        limits.check_content(Path::new("data.py"), long_line.as_bytes()),
+        None
+    );
+    assert_eq!(
+        limits.check_content(Path::new("data.ts"), long_line.as_bytes()),
+        Some(SkipReason::Minified)
+    );
+}
+
+#[test]
+fn project_stats_report_skipped_files() {
+    let dir = temp_dir("stats");
+    std::fs::write(dir.join("lib.rs"), "pub fn run() {}\n").unwrap();
+    std::fs::write(dir.join("vendor.min.js"), "var b=1;\n").unwrap();
+    std::fs::write(dir.join("blob.rs"), b"\0\0\0").unwrap();
+
+    let stats = get_project_stats(dir.to_str().unwrap()).unwrap();
+    assert_eq!(stats.total_files, 3);
+    assert_eq!(stats.total_lines, 1);
+    assert_eq!(
+        (stats.skipped_files.binary, stats.skipped_files.minified),
+        (1, 1)
+    );
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["skipped_files"]["minified"], 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,97 @@
+use archlens::file_scanner::watcher::{IncrementalAnalysis, ProjectWatcher};
+use archlens::file_scanner::FileScanner;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("archlens_watch_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    dir.canonicalize().unwrap()
+}
+
+fn watcher(dir: &Path) -> ProjectWatcher {
+    let scanner =
+        FileScanner::new(vec!["**/*.rs".into()], vec!["**/target/**".into()], None).unwrap();
+    ProjectWatcher::new(dir, scanner)
+        .unwrap()
+        .with_debounce(Duration::from_millis(100))
+}
+
+fn names(paths: impl Iterator<Item = PathBuf>) -> Vec<String> {
+    let mut names: Vec<String> = paths
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn rescan_touches_only_given_paths() {
+    let dir = temp_dir("rescan");
+    std::fs::write(dir.join("src/lib.rs"), "pub struct Store;\n").unwrap();
+    std::fs::write(dir.join("src/api.rs"), "pub fn handle() {}\n").unwrap();
+    let mut watcher = watcher(&dir);
+    assert_eq!(
+        names(watcher.files().map(|f| f.path.clone())),
+        ["api.rs", "lib.rs"]
+    );
+
+    let mut analysis = IncrementalAnalysis::new(watcher.root()).unwrap();
+    analysis.update(watcher.files());
+    assert_eq!(analysis.capsule_count(), 2);
+    assert_eq!(analysis.coverage().analyzed_files, 2);
+
+    std::fs::write(
+        dir.join("src/api.rs"),
+        "pub fn handle() {}\npub fn route() {}\n",
+    )
+    .unwrap();
+    std::fs::remove_file(dir.join("src/lib.rs")).unwrap();
+    std::fs::create_dir_all(dir.join("target")).unwrap();
+    std::fs::write(dir.join("target/gen.rs"), "pub fn generated() {}\n").unwrap();
+    let changes = watcher.rescan([
+        dir.join("src/api.rs"),
+        dir.join("src/lib.rs"),
+        dir.join("target/gen.rs"),
+    ]);
+    assert_eq!(
+        names(changes.changed.iter().map(|f| f.path.clone())),
+        ["api.rs"]
+    );
+    assert_eq!(names(changes.removed.iter().cloned()), ["lib.rs"]);
+    assert_eq!(names(watcher.files().map(|f| f.path.clone())), ["api.rs"]);
+
+    analysis.apply(&changes);
+    let coverage = analysis.coverage();
+    assert_eq!((coverage.analyzed_files, coverage.failed.len()), (1, 0));
+    let graph = analysis.graph().unwrap();
+    let mut capsules: Vec<&str> = graph.capsules.values().map(|c| c.name.as_str()).collect();
+    capsules.sort();
+    assert_eq!(capsules, ["handle", "route"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn file_events_produce_change_sets() {
+    let dir = temp_dir("events");
+    std::fs::write(dir.join("src/lib.rs"), "pub struct Store;\n").unwrap();
+    let mut watcher = watcher(&dir);
+    assert!(watcher
+        .next_changes(Some(Duration::from_millis(200)))
+        .unwrap()
+        .is_none());
+
+    std::fs::write(dir.join("src/orders.rs"), "pub fn place() {}\n").unwrap();
+    std::fs::write(dir.join("README.md"), "не код\n").unwrap();
+    let changes = watcher
+        .next_changes(Some(Duration::from_secs(10)))
+        .unwrap()
+        .expect("change set");
+    assert_eq!(
+        names(changes.changed.iter().map(|f| f.path.clone())),
+        ["orders.rs"]
+    );
+    assert!(changes.removed.is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
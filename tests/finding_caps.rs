@@ -0,0 +1,100 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::finding_caps::*;
+use archlens::types::*;
+
+fn warning(level: Priority, category: &str, message: &str) -> AnalysisWarning {
+    AnalysisWarning {
+        message: message.into(),
+        level,
+        category: category.into(),
+        capsule_id: None,
+        suggestion: None,
+    }
+}
+
+fn capsule(name: &str, warnings: Vec<AnalysisWarning>) -> Capsule {
+    Capsule {
+        complexity: 3,
+        warnings,
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+fn legacy_graph() -> CapsuleGraph {
+    let noisy = capsule(
+        "LegacyService",
+        (0..6)
+            .map(|i| warning(Priority::Medium, "complexity", &format!("issue {i}")))
+            .chain([warning(Priority::Critical, "security", "hardcoded secret")])
+            .collect(),
+    );
+    let others: Vec<Capsule> = (0..4)
+        .map(|i| {
+            capsule(
+                &format!("Handler{i}"),
+                vec![warning(Priority::Low, "naming", "short name")],
+            )
+        })
+        .collect();
+    let capsules: Vec<Capsule> = std::iter::once(noisy).chain(others).collect();
+    common::graph(capsules, vec![])
+}
+
+#[test]
+fn caps_keep_most_severe_findings_first() {
+    let graph = legacy_graph();
+    let capped = FindingCaps::default()
+        .with_max_per_category(3)
+        .apply(&graph);
+
+    // критическая находка занимает первое место в лимите капсулы
+    assert_eq!(capped.kept[0].1.message, "hardcoded secret");
+    let legacy_kept = capped
+        .kept
+        .iter()
+        .filter(|(c, _)| c.name == "LegacyService")
+        .count();
+    assert_eq!(legacy_kept, DEFAULT_MAX_PER_CAPSULE);
+    assert_eq!(capped.suppressed.by_capsule["LegacyService"], 4);
+    assert_eq!(capped.suppressed.by_category["naming"], 1);
+    assert_eq!(capped.suppressed.total(), 5);
+    assert_eq!(capped.kept.len() + capped.suppressed.total(), 11);
+}
+
+#[test]
+fn unlimited_caps_keep_everything() {
+    let graph = legacy_graph();
+    let capped = FindingCaps::unlimited().apply(&graph);
+    assert_eq!(capped.kept.len(), 11);
+    assert_eq!(capped.suppressed, SuppressedFindings::default());
+}
+
+#[test]
+fn ai_compact_lists_capped_findings_with_overflow_note() {
+    let graph = legacy_graph();
+    let plain = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(!plain.contains("## Findings"));
+
+    let compact = Exporter::new()
+        .with_finding_caps(FindingCaps::default().with_max_per_category(3))
+        .export_to_ai_compact(&graph)
+        .unwrap();
+    assert!(compact.contains("## Findings (max 3 per component, 3 per category)\n"));
+    assert!(compact.contains("- [Critical] LegacyService (security): hardcoded secret\n"));
+    assert!(compact.contains("- LegacyService: 4 more suppressed\n"));
+    assert!(compact.contains("- category naming: 1 more suppressed\n"));
+    assert!(
+        compact.contains("- 5 more findings suppressed (use --all-findings for the full list)\n")
+    );
+
+    let full = Exporter::new()
+        .with_finding_caps(FindingCaps::unlimited())
+        .export_to_ai_compact(&graph)
+        .unwrap();
+    assert!(full.contains("## Findings (all)\n"));
+    assert!(!full.contains("suppressed"));
+    assert_eq!(full.matches("] LegacyService (").count(), 7);
+}
@@ -0,0 +1,5 @@
+pub fn template(name: &str) -> String {
+    let open = "{{{{{{";
+    // закрывающие скобки в комментарии: }}}} и открывающие {{{{{{
+    format!("{open}{name}{}", "}}}}}}")
+}
@@ -0,0 +1,10 @@
+impl Grid {
+    pub fn scan(&self) {
+        for cell in self.rows.iter().flatten() {
+            if !cell.active || cell.value <= self.limit {
+                continue;
+            }
+            self.flag(cell);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+impl Grid {
+    pub fn scan(&self) {
+        for row in &self.rows {
+            for cell in row {
+                if cell.active {
+                    if cell.value > self.limit {
+                        self.flag(cell);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub fn check(items: &[Item]) {
+    for item in items { if item.ok { for part in &item.parts { if part.dirty { if part.size > 0 { part.flush(); } } } } }
+}
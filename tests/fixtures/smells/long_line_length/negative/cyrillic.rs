@@ -0,0 +1,2 @@
+// Комментарий на русском языке: сто символов кириллицы занимают двести байт, но строка всё равно короткая.
+pub fn noop() {}
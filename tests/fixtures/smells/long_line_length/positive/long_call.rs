@@ -0,0 +1,3 @@
+pub fn report(out: &mut String, name: &str, value: u64) {
+    out.push_str(&format!("component {} has value {} which exceeds the configured limit for this particular kind of check", name, value));
+}
@@ -0,0 +1,2 @@
+// This comment is deliberately written as a single very long line so that it easily goes past one hundred and twenty columns.
+pub fn noop() {}
@@ -0,0 +1,49 @@
+impl Counter {
+    pub fn inc_0(&mut self) {
+        self.value += 0;
+    }
+
+    pub fn inc_1(&mut self) {
+        self.value += 1;
+    }
+
+    pub fn inc_2(&mut self) {
+        self.value += 2;
+    }
+
+    pub fn inc_3(&mut self) {
+        self.value += 3;
+    }
+
+    pub fn inc_4(&mut self) {
+        self.value += 4;
+    }
+
+    pub fn inc_5(&mut self) {
+        self.value += 5;
+    }
+
+    pub fn inc_6(&mut self) {
+        self.value += 6;
+    }
+
+    pub fn inc_7(&mut self) {
+        self.value += 7;
+    }
+
+    pub fn inc_8(&mut self) {
+        self.value += 8;
+    }
+
+    pub fn inc_9(&mut self) {
+        self.value += 9;
+    }
+
+    pub fn inc_10(&mut self) {
+        self.value += 10;
+    }
+
+    pub fn inc_11(&mut self) {
+        self.value += 11;
+    }
+}
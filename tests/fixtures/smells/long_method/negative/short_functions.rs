@@ -0,0 +1,47 @@
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+pub fn double(x: u32) -> u32 {
+    add(x, x)
+}
+// заполнитель строки 0
+// заполнитель строки 1
+// заполнитель строки 2
+// заполнитель строки 3
+// заполнитель строки 4
+// заполнитель строки 5
+// заполнитель строки 6
+// заполнитель строки 7
+// заполнитель строки 8
+// заполнитель строки 9
+// заполнитель строки 10
+// заполнитель строки 11
+// заполнитель строки 12
+// заполнитель строки 13
+// заполнитель строки 14
+// заполнитель строки 15
+// заполнитель строки 16
+// заполнитель строки 17
+// заполнитель строки 18
+// заполнитель строки 19
+// заполнитель строки 20
+// заполнитель строки 21
+// заполнитель строки 22
+// заполнитель строки 23
+// заполнитель строки 24
+// заполнитель строки 25
+// заполнитель строки 26
+// заполнитель строки 27
+// заполнитель строки 28
+// заполнитель строки 29
+// заполнитель строки 30
+// заполнитель строки 31
+// заполнитель строки 32
+// заполнитель строки 33
+// заполнитель строки 34
+// заполнитель строки 35
+// заполнитель строки 36
+// заполнитель строки 37
+// заполнитель строки 38
+// заполнитель строки 39
@@ -0,0 +1,44 @@
+pub trait Storage {
+    fn load(&self, key: &str) -> Option<Vec<u8>>;
+    fn store(&mut self, key: &str, value: Vec<u8>);
+}
+pub const SLOT_0: usize = 0;
+pub const SLOT_1: usize = 1;
+pub const SLOT_2: usize = 2;
+pub const SLOT_3: usize = 3;
+pub const SLOT_4: usize = 4;
+pub const SLOT_5: usize = 5;
+pub const SLOT_6: usize = 6;
+pub const SLOT_7: usize = 7;
+pub const SLOT_8: usize = 8;
+pub const SLOT_9: usize = 9;
+pub const SLOT_10: usize = 10;
+pub const SLOT_11: usize = 11;
+pub const SLOT_12: usize = 12;
+pub const SLOT_13: usize = 13;
+pub const SLOT_14: usize = 14;
+pub const SLOT_15: usize = 15;
+pub const SLOT_16: usize = 16;
+pub const SLOT_17: usize = 17;
+pub const SLOT_18: usize = 18;
+pub const SLOT_19: usize = 19;
+pub const SLOT_20: usize = 20;
+pub const SLOT_21: usize = 21;
+pub const SLOT_22: usize = 22;
+pub const SLOT_23: usize = 23;
+pub const SLOT_24: usize = 24;
+pub const SLOT_25: usize = 25;
+pub const SLOT_26: usize = 26;
+pub const SLOT_27: usize = 27;
+pub const SLOT_28: usize = 28;
+pub const SLOT_29: usize = 29;
+pub const SLOT_30: usize = 30;
+pub const SLOT_31: usize = 31;
+pub const SLOT_32: usize = 32;
+pub const SLOT_33: usize = 33;
+pub const SLOT_34: usize = 34;
+pub const SLOT_35: usize = 35;
+pub const SLOT_36: usize = 36;
+pub const SLOT_37: usize = 37;
+pub const SLOT_38: usize = 38;
+pub const SLOT_39: usize = 39;
@@ -0,0 +1,33 @@
+impl Dispatcher {
+    pub fn dispatch<T: Into<Kind>>(&self, kind: T) -> Outcome {
+        let kind = kind.into();
+        match kind {
+        Kind::K0 => handle("k0"),
+        Kind::K1 => handle("k1"),
+        Kind::K2 => handle("k2"),
+        Kind::K3 => handle("k3"),
+        Kind::K4 => handle("k4"),
+        Kind::K5 => handle("k5"),
+        Kind::K6 => handle("k6"),
+        Kind::K7 => handle("k7"),
+        Kind::K8 => handle("k8"),
+        Kind::K9 => handle("k9"),
+        Kind::K10 => handle("k10"),
+        Kind::K11 => handle("k11"),
+        Kind::K12 => handle("k12"),
+        Kind::K13 => handle("k13"),
+        Kind::K14 => handle("k14"),
+        Kind::K15 => handle("k15"),
+        Kind::K16 => handle("k16"),
+        Kind::K17 => handle("k17"),
+        Kind::K18 => handle("k18"),
+        Kind::K19 => handle("k19"),
+        Kind::K20 => handle("k20"),
+        Kind::K21 => handle("k21"),
+        Kind::K22 => handle("k22"),
+        Kind::K23 => handle("k23"),
+        Kind::K24 => handle("k24"),
+        Kind::K25 => handle("k25"),
+        }
+    }
+}
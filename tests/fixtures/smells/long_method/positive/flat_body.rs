@@ -0,0 +1,34 @@
+pub fn accumulate() -> u32 {
+    let mut total = 0;
+    total += step(0);
+    total += step(1);
+    total += step(2);
+    total += step(3);
+    total += step(4);
+    total += step(5);
+    total += step(6);
+    total += step(7);
+    total += step(8);
+    total += step(9);
+    total += step(10);
+    total += step(11);
+    total += step(12);
+    total += step(13);
+    total += step(14);
+    total += step(15);
+    total += step(16);
+    total += step(17);
+    total += step(18);
+    total += step(19);
+    total += step(20);
+    total += step(21);
+    total += step(22);
+    total += step(23);
+    total += step(24);
+    total += step(25);
+    total += step(26);
+    total += step(27);
+    total += step(28);
+    total += step(29);
+    total
+}
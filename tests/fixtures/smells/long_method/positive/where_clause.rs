@@ -0,0 +1,34 @@
+fn render<W>(out: &mut W, rows: &[Row]) -> std::io::Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(out, "{}", rows[0].cell)?;
+    writeln!(out, "{}", rows[1].cell)?;
+    writeln!(out, "{}", rows[2].cell)?;
+    writeln!(out, "{}", rows[3].cell)?;
+    writeln!(out, "{}", rows[4].cell)?;
+    writeln!(out, "{}", rows[5].cell)?;
+    writeln!(out, "{}", rows[6].cell)?;
+    writeln!(out, "{}", rows[7].cell)?;
+    writeln!(out, "{}", rows[8].cell)?;
+    writeln!(out, "{}", rows[9].cell)?;
+    writeln!(out, "{}", rows[10].cell)?;
+    writeln!(out, "{}", rows[11].cell)?;
+    writeln!(out, "{}", rows[12].cell)?;
+    writeln!(out, "{}", rows[13].cell)?;
+    writeln!(out, "{}", rows[14].cell)?;
+    writeln!(out, "{}", rows[15].cell)?;
+    writeln!(out, "{}", rows[16].cell)?;
+    writeln!(out, "{}", rows[17].cell)?;
+    writeln!(out, "{}", rows[18].cell)?;
+    writeln!(out, "{}", rows[19].cell)?;
+    writeln!(out, "{}", rows[20].cell)?;
+    writeln!(out, "{}", rows[21].cell)?;
+    writeln!(out, "{}", rows[22].cell)?;
+    writeln!(out, "{}", rows[23].cell)?;
+    writeln!(out, "{}", rows[24].cell)?;
+    writeln!(out, "{}", rows[25].cell)?;
+    writeln!(out, "{}", rows[26].cell)?;
+    writeln!(out, "{}", rows[27].cell)?;
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub fn blend(r: u8, g: u8, b: u8, a: u8, gamma: f32) -> Color {
+    Color::new(r, g, b, a).gamma(gamma)
+}
@@ -0,0 +1,3 @@
+pub fn index(map: HashMap<String, Vec<(u32, u32)>>, fallback: Result<u8, String>) -> usize {
+    map.len() + fallback.map(usize::from).unwrap_or(0)
+}
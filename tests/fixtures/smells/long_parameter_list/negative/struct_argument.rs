@@ -0,0 +1,8 @@
+pub struct ConnectOptions {
+    pub host: String,
+    pub port: u16,
+}
+
+pub fn connect(options: &ConnectOptions) -> Connection {
+    Connection::open(&options.host, options.port)
+}
@@ -0,0 +1,3 @@
+pub fn merge<K: Ord, V: Clone>(a: &BTreeMap<K, V>, b: &BTreeMap<K, V>, c: &BTreeMap<K, V>, d: usize, e: bool, f: bool) {
+    let _ = (a, b, c, d, e, f);
+}
@@ -0,0 +1,12 @@
+impl Renderer {
+    pub fn draw(
+        &self,
+        canvas: &mut Canvas,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) {
+        canvas.rect(x, y, width, height);
+    }
+}
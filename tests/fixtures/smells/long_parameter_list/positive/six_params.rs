@@ -0,0 +1,3 @@
+pub fn connect(host: &str, port: u16, user: &str, password: &str, timeout: u64, retries: u8) -> Connection {
+    Connection::open(host, port, user, password, timeout, retries)
+}
@@ -0,0 +1,7 @@
+pub fn percent(part: f64, whole: f64) -> f64 {
+    part / whole * 100.0
+}
+
+pub fn kib(bytes: u64) -> u64 {
+    bytes / KIB + 0 * 1000
+}
@@ -0,0 +1,5 @@
+pub fn checksum(bytes: &[u8]) -> u8 {
+    let header = bytes[0] ^ bytes[1];
+    let body = bytes[12] ^ bytes[13];
+    header ^ body ^ bytes[ 42 ]
+}
@@ -0,0 +1,9 @@
+#[derive(Debug)]
+#[repr(u32)]
+pub enum Width {
+    Narrow,
+}
+
+pub fn widen(value: u16) -> u64 {
+    u64::from(value) as u64 + i128::MAX.count_ones() as u64
+}
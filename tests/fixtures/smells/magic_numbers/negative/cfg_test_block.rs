@@ -0,0 +1,15 @@
+pub fn bind_address(port: u16) -> String {
+    format!("127.0.0.1:{port}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_to_test_port() {
+        assert_eq!(bind_address(8080).len(), 14);
+        let timeout = std::time::Duration::from_millis(250);
+        assert!(timeout.as_millis() > 99);
+    }
+}
@@ -0,0 +1,12 @@
+#[repr(u16)]
+pub enum StatusCode {
+    Ok = 200,
+    NotFound = 404,
+    Teapot = 418,
+    Internal = 500,
+}
+
+pub enum Flag {
+    Read = 0x04,
+    Write = 0x02,
+}
@@ -0,0 +1,8 @@
+DEFAULT_PORT = 8080
+RETRY_DELAY: float = 2.5
+MAX_ITEMS = 250
+
+
+def fetch(items):
+    # первые 50 элементов считаются в другом месте
+    return items[0], items[1], "timeout=30"
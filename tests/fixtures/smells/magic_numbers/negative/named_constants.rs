@@ -0,0 +1,7 @@
+/// Таймаут ожидания в миллисекундах
+pub const WAIT_MS: u64 = 250;
+pub(crate) static RETRIES: u32 = 42;
+
+pub fn wait(client: &Client) {
+    client.sleep_ms(WAIT_MS);
+}
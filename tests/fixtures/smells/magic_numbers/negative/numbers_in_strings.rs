@@ -0,0 +1,4 @@
+pub fn banner() -> String {
+    // версия 2024 года, порт 8080 в комментарии
+    format!("listening on port 8080 since 2024: {}", VERSION)
+}
@@ -0,0 +1,5 @@
+pub fn check_toolchain() -> bool {
+    let minimum = rustc_version!(1.70.0);
+    let tested = rustc_version!(v1.82.1-beta);
+    minimum <= tested
+}
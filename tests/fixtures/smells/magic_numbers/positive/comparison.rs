@@ -0,0 +1,3 @@
+pub fn is_adult(age: u32) -> bool {
+    age >= 18
+}
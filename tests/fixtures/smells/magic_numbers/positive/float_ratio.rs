@@ -0,0 +1,3 @@
+pub fn tax(amount: f64) -> f64 {
+    amount * 0.2 + amount * 13.5 / 100.0
+}
@@ -0,0 +1,9 @@
+import time
+
+
+def wait_for(service):
+    for _ in range(3):
+        if service.ready():
+            return True
+        time.sleep(45)
+    return False
@@ -0,0 +1,3 @@
+pub fn wait(client: &Client) {
+    client.sleep_ms(250);
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use archlens::metadata_extractor::*;
+use archlens::types::*;
+use std::path::Path;
+use std::process::Command;
+use uuid::Uuid;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn commit_as(dir: &Path, email: &str, message: &str) {
+    git(dir, &["add", "."]);
+    git(
+        dir,
+        &[
+            "-c",
+            "user.name=dev",
+            "-c",
+            &format!("user.email={email}"),
+            "commit",
+            "--quiet",
+            "-m",
+            message,
+        ],
+    );
+}
+
+fn capsule(file: &Path, complexity: u32) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Struct,
+        file_path: file.to_path_buf(),
+        line_end: 1,
+        size: 1,
+        complexity,
+        ..common::capsule("Store")
+    }
+}
+
+#[test]
+fn history_log_is_parsed() {
+    let log = "2024-05-02T10:00:00+02:00\u{1f}Ann@example.com\n\
+               2024-04-01T09:00:00+02:00\u{1f}bob@example.com\n\
+               2024-03-01T09:00:00+02:00\u{1f}ann@example.com\n";
+    assert_eq!(
+        parse_file_history(log),
+        FileHistory {
+            commits: 3,
+            last_modified: Some("2024-05-02T10:00:00+02:00".into()),
+            authors: 2,
+        }
+    );
+    assert_eq!(parse_file_history(""), FileHistory::default());
+}
+
+#[test]
+fn repository_log_attributes_renamed_history_to_current_path() {
+    let log = "\u{1e}2024-05-02T10:00:00+02:00\u{1f}bob@example.com\n\n\
+               M\tsrc/store.rs\n\
+               A\tsrc/cache.rs\n\
+               \u{1e}2024-04-01T09:00:00+02:00\u{1f}ann@example.com\n\n\
+               R100\told.rs\tsrc/store.rs\n\
+               \u{1e}2024-03-01T09:00:00+02:00\u{1f}Ann@example.com\n\n\
+               A\told.rs\n";
+    let histories = parse_repository_history(log);
+    assert_eq!(
+        histories["src/store.rs"],
+        FileHistory {
+            commits: 3,
+            last_modified: Some("2024-05-02T10:00:00+02:00".into()),
+            authors: 2,
+        }
+    );
+    assert_eq!(histories["src/cache.rs"].commits, 1);
+    assert!(!histories.contains_key("old.rs"));
+}
+
+#[test]
+fn capsules_get_churn_and_authors_across_renames() {
+    let repo = std::env::temp_dir().join(format!("archlens-churn-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet"]);
+    std::fs::write(repo.join("old.rs"), "pub struct Store;\n").unwrap();
+    commit_as(&repo, "ann@example.com", "add store");
+    git(&repo, &["mv", "old.rs", "store.rs"]);
+    commit_as(&repo, "ann@example.com", "rename store");
+    std::fs::write(
+        repo.join("store.rs"),
+        "pub struct Store {\n    id: u32,\n}\n",
+    )
+    .unwrap();
+    commit_as(&repo, "bob@example.com", "add id");
+    std::fs::write(repo.join("untracked.rs"), "pub struct Draft;\n").unwrap();
+
+    let mut capsules = vec![
+        capsule(&repo.join("store.rs"), 4),
+        capsule(&repo.join("untracked.rs"), 2),
+    ];
+    let files = MetadataExtractor::new().enrich_with_git_history(&repo, &mut capsules);
+    assert_eq!(files, 1);
+
+    let store = &capsules[0].metadata;
+    assert_eq!(store[GIT_COMMITS_KEY], "3");
+    assert_eq!(store[GIT_AUTHORS_KEY], "2");
+    assert!(store.contains_key(GIT_LAST_MODIFIED_KEY));
+    assert!(capsules[1].metadata.is_empty());
+    std::fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn outside_git_nothing_is_added() {
+    let dir = std::env::temp_dir().join(format!("archlens-nogit-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.rs"), "pub struct A;\n").unwrap();
+    let mut capsules = vec![capsule(&dir.join("a.rs"), 1)];
+    assert_eq!(
+        MetadataExtractor::new().enrich_with_git_history(&dir, &mut capsules),
+        0
+    );
+    assert!(capsules[0].metadata.is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
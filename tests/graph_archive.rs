@@ -0,0 +1,103 @@
+use archlens::cli::handlers::{build_project_graph, run_registry_export, run_view};
+use archlens::exporter::Exporter;
+use archlens::graph_archive::{default_archive_name, GraphArchive, ARCHIVE_EXTENSION};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_graph_archive_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn project(root: &Path) -> PathBuf {
+    let dir = root.join("shop");
+    write(&dir, "Cargo.toml", "[package]\nname = \"shop\"\n");
+    write(&dir, "src/lib.rs", "pub mod orders;\npub mod store;\n");
+    write(
+        &dir,
+        "src/orders.rs",
+        "pub fn place_order() -> u32 {\n    crate::store::save()\n}\n",
+    );
+    write(&dir, "src/store.rs", "pub fn save() -> u32 {\n    1\n}\n");
+    dir
+}
+
+#[test]
+fn archive_keeps_graph_with_project_relative_paths() {
+    let root = temp_dir("relative");
+    let dir = project(&root);
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+
+    let archive = GraphArchive::new(graph.clone(), &dir);
+    assert_eq!(archive.header.project, "shop");
+    assert!(archive.header.exported_at.is_some());
+    let json = archive.to_json().unwrap();
+    assert!(!json.contains(dir.to_str().unwrap()), "{json}");
+
+    let loaded = GraphArchive::from_json(&json).unwrap();
+    assert_eq!(loaded.header, archive.header);
+    assert_eq!(loaded.graph().capsules.len(), graph.capsules.len());
+    assert_eq!(loaded.graph().relations.len(), graph.relations.len());
+    let order = loaded
+        .graph()
+        .capsules
+        .values()
+        .find(|c| c.name == "place_order")
+        .unwrap();
+    assert_eq!(order.file_path, Path::new("src/orders.rs"));
+    assert_eq!(
+        default_archive_name(&dir),
+        format!("shop.{ARCHIVE_EXTENSION}")
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn plain_stored_graph_opens_without_header() {
+    let root = temp_dir("stored");
+    let graph = build_project_graph(project(&root).to_str().unwrap()).unwrap();
+    let stored = archlens::graph_store::to_stored_json(&graph).unwrap();
+
+    let archive = GraphArchive::from_json(&stored).unwrap();
+    assert!(archive.header.project.is_empty());
+    assert_eq!(archive.graph().capsules.len(), graph.capsules.len());
+    assert!(archive.describe().starts_with("(без заголовка):"));
+    assert!(GraphArchive::from_json("{\"graph\": 1}").is_err());
+
+    let registry = Exporter::new().registry();
+    assert_eq!(registry.get("graph-archive").unwrap().name(), "archive");
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn exported_archive_is_viewed_without_sources() {
+    let root = temp_dir("offline");
+    let dir = project(&root);
+    let content = run_registry_export(dir.to_str().unwrap(), None, "archive").unwrap();
+    let file = root.join(format!("shop.{ARCHIVE_EXTENSION}"));
+    std::fs::write(&file, content).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let summary = run_view(file.to_str().unwrap(), None).unwrap();
+    assert!(summary.contains("# AI Compact Analysis"), "{summary}");
+    let mermaid = run_view(file.to_str().unwrap(), Some("mermaid")).unwrap();
+    assert!(mermaid.contains("place_order"), "{mermaid}");
+    let html = run_view(file.to_str().unwrap(), Some("interactive-html")).unwrap();
+    assert!(html.contains("place_order"));
+    assert!(run_view(file.to_str().unwrap(), Some("docx")).is_err());
+    assert!(run_view(root.join("missing.archlens.graph").to_str().unwrap(), None).is_err());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
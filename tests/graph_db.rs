@@ -0,0 +1,174 @@
+use archlens::cli::handlers::{build_project_graph, run_diff, run_sqlite_export};
+use archlens::graph_db::{
+    cached_graph, commit_snapshot, GraphDatabase, GRAPH_DB_FILE, WORKTREE_SNAPSHOT,
+};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("archlens_graph_db_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn git(repo: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .expect("git");
+    assert!(output.status.success(), "git {:?}", args);
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn project(dir: &Path) {
+    write(dir, "Cargo.toml", "[package]\nname = \"shop\"\n");
+    write(dir, "src/lib.rs", "pub mod orders;\npub mod store;\n");
+    write(
+        dir,
+        "src/orders.rs",
+        "pub fn place_order() -> u32 {\n    crate::store::save()\n}\n",
+    );
+    write(dir, "src/store.rs", "pub fn save() -> u32 {\n    1\n}\n");
+}
+
+fn edges(graph: &archlens::types::CapsuleGraph) -> BTreeSet<String> {
+    graph
+        .relations
+        .iter()
+        .map(|r| {
+            format!(
+                "{}-{:?}->{}",
+                graph.capsules[&r.from_id].name, r.relation_type, graph.capsules[&r.to_id].name
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn graph_round_trips_through_sqlite() {
+    let dir = temp_dir("round_trip");
+    project(&dir);
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+
+    let path = dir.join("elsewhere/graph.db");
+    let mut database = GraphDatabase::open(&path).unwrap();
+    database.save("snapshot", &graph, &dir, None).unwrap();
+    assert!(database.load("missing", &dir).unwrap().is_none());
+
+    let moved = Path::new("/checkout");
+    let loaded = database.load("snapshot", moved).unwrap().unwrap();
+    assert_eq!(loaded.capsules.len(), graph.capsules.len());
+    assert_eq!(edges(&loaded), edges(&graph));
+    assert_eq!(loaded.metrics.total_capsules, graph.metrics.total_capsules);
+    assert_eq!(loaded.created_at, graph.created_at);
+    assert_eq!(loaded.layers.len(), graph.layers.len());
+    for (id, capsule) in &graph.capsules {
+        let relative = capsule.file_path.strip_prefix(&dir).unwrap();
+        assert_eq!(loaded.capsules[id].file_path, moved.join(relative));
+        assert_eq!(loaded.capsules[id].dependencies, capsule.dependencies);
+    }
+
+    let snapshots = database.snapshots().unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].capsules, graph.capsules.len());
+    assert_eq!(snapshots[0].relations, graph.relations.len());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn worktree_graph_is_reused_until_files_change() {
+    let dir = temp_dir("worktree");
+    project(&dir);
+    let analyze = || build_project_graph(dir.to_str().unwrap());
+    let fail = || -> Result<archlens::types::CapsuleGraph, String> { Err("analyzed".into()) };
+
+    // без базы граф строится всегда
+    assert_eq!(
+        cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).unwrap_err(),
+        "analyzed"
+    );
+
+    let message = run_sqlite_export(dir.to_str().unwrap(), None).unwrap();
+    assert!(message.contains(GRAPH_DB_FILE), "{message}");
+    let cached = cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).unwrap();
+    assert_eq!(edges(&cached), edges(&analyze().unwrap()));
+
+    write(
+        &dir,
+        "src/store.rs",
+        "pub fn save() -> u32 {\n    1\n}\n\npub fn load() -> u32 {\n    2\n}\n",
+    );
+    assert!(cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).is_err());
+    let rebuilt = cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, analyze).unwrap();
+    assert!(rebuilt.capsules.values().any(|c| c.name == "load"));
+    let reused = cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).unwrap();
+    assert_eq!(reused.capsules.len(), rebuilt.capsules.len());
+
+    // настройки в .archlens/ не входят в отпечаток дерева, но меняют граф
+    write(&dir, ".archlens/tags.json", "{\"tags\": {}}\n");
+    assert!(cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).is_err());
+    cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, analyze).unwrap();
+    assert!(cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).is_ok());
+    // кеш LLM-сводок и сама база на отпечаток не влияют
+    write(&dir, ".archlens/llm-cache.json", "{}\n");
+    assert!(cached_graph(&dir, WORKTREE_SNAPSHOT, &dir, fail).is_ok());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn diff_stores_analyzed_commits_in_project_database() {
+    let repo = temp_dir("diff");
+    git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+    project(&repo);
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "--quiet", "-m", "initial"]);
+    write(
+        &repo,
+        "src/audit.rs",
+        "pub fn record() -> u32 {\n    3\n}\n",
+    );
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "--quiet", "-m", "audit"]);
+    let (first, second) = (
+        git(&repo, &["rev-parse", "HEAD~1"]),
+        git(&repo, &["rev-parse", "HEAD"]),
+    );
+
+    let added = "- **Added** `record`";
+    let diff = || run_diff(repo.to_str().unwrap(), "HEAD~1", "HEAD", None).unwrap();
+    assert!(diff().contains(added));
+    assert!(!repo.join(GRAPH_DB_FILE).exists());
+
+    run_sqlite_export(repo.to_str().unwrap(), None).unwrap();
+    assert!(diff().contains(added));
+    let keys: Vec<String> = GraphDatabase::for_project(&repo)
+        .unwrap()
+        .unwrap()
+        .snapshots()
+        .unwrap()
+        .into_iter()
+        .map(|s| s.key)
+        .collect();
+    assert!(keys.contains(&commit_snapshot(&first)), "{keys:?}");
+    assert!(keys.contains(&commit_snapshot(&second)), "{keys:?}");
+    assert!(keys.contains(&WORKTREE_SNAPSHOT.to_string()));
+
+    // второй diff читает оба коммита из базы
+    assert!(diff().contains(added));
+
+    let _ = std::fs::remove_dir_all(&repo);
+}
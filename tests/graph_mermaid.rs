@@ -0,0 +1,36 @@
+use archlens::cli::handlers::{build_graph_mermaid, build_project_graph};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_graph_mermaid_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn mermaid_keeps_the_shallow_scan_depth() {
+    let dir = temp_dir("depth");
+    write(&dir, "src/shallow.rs", "pub struct ShallowStore;\n");
+    write(&dir, "a/b/c/d/e/f/deep.rs", "pub struct DeepStore;\n");
+    let path = dir.to_string_lossy();
+
+    // полный пайплайн обходит 10 уровней каталогов, диаграмма — только 6
+    let graph = build_project_graph(&path).unwrap();
+    assert!(graph.capsules.values().any(|c| c.name == "DeepStore"));
+
+    let mermaid = build_graph_mermaid(&path).unwrap();
+    assert!(mermaid.contains("ShallowStore"), "{mermaid}");
+    assert!(!mermaid.contains("DeepStore"), "{mermaid}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
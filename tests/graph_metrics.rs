@@ -0,0 +1,97 @@
+use archlens::graph::MetricsCalculator;
+use archlens::types::*;
+use archlens::validation::CycleValidator;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn capsule(name: &str, layer: Option<&str>) -> Capsule {
+    Capsule {
+        id: Uuid::new_v4(),
+        name: name.into(),
+        capsule_type: CapsuleType::Function,
+        file_path: PathBuf::from(format!("/repo/src/{name}.rs")),
+        line_start: 1,
+        line_end: 10,
+        size: 10,
+        complexity: 2,
+        dependencies: vec![],
+        layer: layer.map(String::from),
+        summary: None,
+        description: None,
+        warnings: vec![],
+        status: CapsuleStatus::Active,
+        priority: Priority::Medium,
+        tags: vec![],
+        metadata: HashMap::new(),
+        quality_score: 0.8,
+        slogan: None,
+        dependents: vec![],
+        created_at: None,
+    }
+}
+
+fn depends(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Depends,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// Core: a ↔ b → c; UI: e → d → a; f без слоя и связей
+fn graph() -> CapsuleGraph {
+    let [a, b, c] = ["a", "b", "c"].map(|n| capsule(n, Some("Core")));
+    let [d, e] = ["d", "e"].map(|n| capsule(n, Some("UI")));
+    let f = capsule("f", None);
+    let relations = vec![
+        depends(&a, &b),
+        depends(&b, &c),
+        depends(&b, &a),
+        depends(&d, &a),
+        depends(&e, &d),
+    ];
+    let capsules: HashMap<Uuid, Capsule> =
+        [a, b, c, d, e, f].into_iter().map(|c| (c.id, c)).collect();
+    let metrics = MetricsCalculator::new()
+        .calculate_advanced_metrics(&capsules, &relations)
+        .unwrap();
+    CapsuleGraph {
+        capsules,
+        relations,
+        layers: HashMap::new(),
+        metrics,
+        created_at: chrono::Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+#[test]
+fn layer_cohesion_and_components() {
+    let graph = graph();
+    let metrics = &graph.metrics;
+    // внутрислойных связей 4 из 3·2 + 2·1 возможных
+    assert_eq!(metrics.cohesion_index, 0.5);
+    // E − N (5 − 6) обрезается до нуля, плюс 2 · 2 компоненты связности
+    assert_eq!(metrics.cyclomatic_complexity, 4);
+
+    let cohesion =
+        MetricsCalculator::new().calculate_cohesion_metrics(&graph.capsules, &graph.relations);
+    assert_eq!(cohesion.layer_count, 2);
+    // Core: 3 внутренних из 4, UI: 1 из 2
+    assert_eq!(cohesion.average_layer_cohesion, 0.625);
+}
+
+#[test]
+fn cycle_validator_reports_the_cycle() {
+    let graph = graph();
+    let mut warnings = Vec::new();
+    CycleValidator::new()
+        .validate(&graph, &mut warnings)
+        .unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].category, "cycles");
+    assert!(warnings[0].message.contains("a -> b") || warnings[0].message.contains("b -> a"));
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use archlens::graph_query::{identifier_tokens, parse_question, GraphIndex, QueryIntent};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn capsule(name: &str, kind: CapsuleType, file: &str, line: usize) -> Capsule {
+    Capsule {
+        capsule_type: kind,
+        file_path: PathBuf::from("/repo").join(file),
+        line_start: line,
+        line_end: line + 10,
+        layer: Some("Core".into()),
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+fn graph() -> CapsuleGraph {
+    let payment = capsule(
+        "PaymentService",
+        CapsuleType::Struct,
+        "src/billing/payment.rs",
+        5,
+    );
+    let gateway = capsule(
+        "StripeGateway",
+        CapsuleType::Struct,
+        "src/billing/stripe.rs",
+        1,
+    );
+    let checkout = capsule(
+        "checkout_handler",
+        CapsuleType::Function,
+        "src/api/checkout.rs",
+        12,
+    );
+    let refunds = capsule("RefundJob", CapsuleType::Struct, "src/jobs/refunds.rs", 3);
+    let relations = vec![
+        relation(&checkout, &payment, RelationType::Calls),
+        relation(&refunds, &payment, RelationType::Uses),
+        relation(&refunds, &payment, RelationType::Calls),
+        relation(&payment, &gateway, RelationType::Depends),
+    ];
+    CapsuleGraph {
+        capsules: [payment, gateway, checkout, refunds]
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect(),
+        relations,
+        layers: HashMap::new(),
+        metrics: GraphMetrics {
+            total_capsules: 4,
+            total_relations: 4,
+            complexity_average: 1.0,
+            cyclomatic_complexity: 4,
+            depth_levels: 1,
+            ..Default::default()
+        },
+        created_at: Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+#[test]
+fn parses_question_forms() {
+    assert_eq!(
+        parse_question("Who uses `PaymentService`?"),
+        (QueryIntent::Dependents, "PaymentService".to_string())
+    );
+    assert_eq!(
+        parse_question("what does the PaymentService class depend on?"),
+        (QueryIntent::Dependencies, "PaymentService".to_string())
+    );
+    assert_eq!(
+        parse_question("where is checkout_handler defined"),
+        (QueryIntent::Location, "checkout_handler".to_string())
+    );
+    assert_eq!(
+        parse_question("what's in src/billing?"),
+        (QueryIntent::Contents, "src/billing".to_string())
+    );
+    assert_eq!(
+        parse_question("кто использует PaymentService?"),
+        (QueryIntent::Dependents, "PaymentService".to_string())
+    );
+    assert_eq!(
+        parse_question("PaymentService"),
+        (QueryIntent::Describe, "PaymentService".to_string())
+    );
+    assert_eq!(
+        identifier_tokens("PaymentService::charge_card"),
+        vec!["card", "charge", "payment", "service"]
+    );
+}
+
+#[test]
+fn answers_who_uses_with_deduplicated_callers() {
+    let graph = graph();
+    let index = GraphIndex::new(&graph, Path::new("/repo"));
+    let answer = index.ask("who uses PaymentService?");
+
+    assert_eq!(answer.subjects.len(), 1);
+    let users: Vec<(&str, &str)> = answer
+        .results
+        .iter()
+        .map(|r| (r.name.as_str(), r.location.as_str()))
+        .collect();
+    assert_eq!(
+        users,
+        vec![
+            ("checkout_handler", "src/api/checkout.rs:12"),
+            ("RefundJob", "src/jobs/refunds.rs:3"),
+        ]
+    );
+    assert_eq!(answer.results[0].relation, Some(RelationType::Calls));
+
+    let text = answer.to_text();
+    assert!(text.starts_with("PaymentService used by 2 component(s):\n"));
+    assert!(text.contains("- RefundJob (Struct) — src/jobs/refunds.rs:3"));
+}
+
+#[test]
+fn resolves_partial_identifiers_and_path_fragments() {
+    let graph = graph();
+    let index = GraphIndex::new(&graph, Path::new("/repo"));
+
+    // части идентификатора в любом регистре/стиле
+    let answer = index.ask("what does payment_service use");
+    assert_eq!(answer.subjects[0].name, "PaymentService");
+    assert_eq!(answer.results.len(), 1);
+    assert_eq!(answer.results[0].name, "StripeGateway");
+
+    let answer = index.ask("where is stripe.rs");
+    assert_eq!(answer.subjects.len(), 1);
+    assert_eq!(answer.subjects[0].location, "src/billing/stripe.rs:1");
+
+    let answer = index.ask("what is in billing");
+    let names: Vec<&str> = answer.results.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["PaymentService", "StripeGateway"]);
+
+    let answer = index.ask("who uses InvoiceService?");
+    assert!(answer.subjects.is_empty());
+    assert_eq!(
+        answer.to_text(),
+        "Nothing in the graph matches 'InvoiceService'.\n"
+    );
+}
@@ -0,0 +1,196 @@
+mod common;
+
+use archlens::graph_sampling::{GraphSampler, SamplingStrategy};
+use archlens::types::*;
+use assert_cmd::prelude::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use uuid::Uuid;
+
+struct Node {
+    name: &'static str,
+    layer: &'static str,
+    warning: Option<Priority>,
+}
+
+fn node(name: &'static str, layer: &'static str) -> Node {
+    Node {
+        name,
+        layer,
+        warning: None,
+    }
+}
+
+/// Граф из узлов и связей по индексам
+fn graph(nodes: &[Node], edges: &[(usize, usize)]) -> CapsuleGraph {
+    let capsules: Vec<Capsule> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| Capsule {
+            capsule_type: CapsuleType::Module,
+            file_path: PathBuf::from(format!("src/{}.rs", n.name)),
+            line_start: i + 1,
+            line_end: i + 10,
+            layer: Some(n.layer.into()),
+            warnings: n
+                .warning
+                .iter()
+                .map(|level| AnalysisWarning {
+                    message: "problem".into(),
+                    level: *level,
+                    category: "quality".into(),
+                    capsule_id: None,
+                    suggestion: None,
+                })
+                .collect(),
+            quality_score: 0.5,
+            ..common::capsule(n.name)
+        })
+        .collect();
+    let relations = edges
+        .iter()
+        .map(|(from, to)| CapsuleRelation {
+            from_id: capsules[*from].id,
+            to_id: capsules[*to].id,
+            relation_type: RelationType::Depends,
+            strength: 1.0,
+            description: None,
+        })
+        .collect();
+    let mut layers: HashMap<String, Vec<Uuid>> = HashMap::new();
+    for capsule in &capsules {
+        layers
+            .entry(capsule.layer.clone().unwrap())
+            .or_default()
+            .push(capsule.id);
+    }
+    CapsuleGraph {
+        layers,
+        ..common::graph(capsules, relations)
+    }
+}
+
+fn names(graph: &CapsuleGraph, ids: &[Uuid]) -> Vec<String> {
+    ids.iter()
+        .map(|id| graph.capsules[id].name.clone())
+        .collect()
+}
+
+/// core — хаб слоя domain; api/ui — тонкий слой presentation; db и cache — infra
+fn sample_graph() -> CapsuleGraph {
+    let mut nodes = vec![
+        node("core", "domain"),
+        node("orders", "domain"),
+        node("billing", "domain"),
+        node("users", "domain"),
+        node("api", "presentation"),
+        node("db", "infra"),
+        node("cache", "infra"),
+    ];
+    nodes[6].warning = Some(Priority::Critical);
+    graph(
+        &nodes,
+        &[(1, 0), (2, 0), (3, 0), (4, 0), (0, 5), (4, 1), (5, 6)],
+    )
+}
+
+#[test]
+fn centrality_prefers_hubs() {
+    let graph = sample_graph();
+    let ids = GraphSampler::new(SamplingStrategy::Centrality, 3).select(&graph);
+    // core: 5 соседей; api, orders, db: по 2 — ничьи по пути файла
+    assert_eq!(names(&graph, &ids), ["core", "api", "db"]);
+}
+
+#[test]
+fn stratified_keeps_every_layer() {
+    let graph = sample_graph();
+    let ids = GraphSampler::new(SamplingStrategy::Stratified, 4).select(&graph);
+    let mut picked = names(&graph, &ids);
+    picked.sort();
+    // domain (4 из 7) получает 2 места, presentation и infra — по одному
+    assert_eq!(picked, ["api", "core", "db", "orders"]);
+
+    // лимит меньше числа слоёв: по одному узлу из первых слоёв
+    let ids = GraphSampler::new(SamplingStrategy::Stratified, 2).select(&graph);
+    assert_eq!(ids.len(), 2);
+}
+
+#[test]
+fn problem_adjacent_starts_from_findings() {
+    let graph = sample_graph();
+    let ids = GraphSampler::new(SamplingStrategy::ProblemAdjacent, 3).select(&graph);
+    // cache с критической находкой, его сосед db, затем самый связанный core
+    assert_eq!(names(&graph, &ids), ["cache", "db", "core"]);
+}
+
+#[test]
+fn sample_builds_consistent_subgraph() {
+    let graph = sample_graph();
+    let sampler = GraphSampler::new(SamplingStrategy::Centrality, 3);
+    assert!(GraphSampler::new(SamplingStrategy::Centrality, 7)
+        .sample(&graph)
+        .is_none());
+
+    let sample = sampler.sample(&graph).unwrap();
+    assert_eq!(sample.graph.capsules.len(), 3);
+    assert_eq!(sample.omitted(), 4);
+    assert_eq!(
+        sample.note(),
+        "showing 3 of 7 components (centrality sampling)"
+    );
+    // только связи внутри выборки: api -> core, core -> db
+    assert_eq!(sample.graph.relations.len(), 2);
+    assert!(sample
+        .graph
+        .layers
+        .values()
+        .flatten()
+        .all(|id| sample.graph.capsules.contains_key(id)));
+    assert_eq!(sample.graph.metrics.total_capsules, 7);
+
+    assert_eq!(
+        "problems".parse::<SamplingStrategy>(),
+        Ok(SamplingStrategy::ProblemAdjacent)
+    );
+    assert!("random".parse::<SamplingStrategy>().is_err());
+}
+
+#[test]
+fn diagram_command_samples_large_graphs() {
+    let output = Command::cargo_bin("archlens")
+        .unwrap()
+        .args([
+            "diagram",
+            "tests/fixtures/small_project",
+            "mermaid",
+            "--max-nodes",
+            "2",
+            "--sample",
+            "stratified",
+        ])
+        .env("ARCHLENS_AUDIT", "off")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("%% showing 2 of ") && stdout.contains("(stratified sampling)"),
+        "{stdout}"
+    );
+
+    let invalid = Command::cargo_bin("archlens")
+        .unwrap()
+        .args([
+            "diagram",
+            "tests/fixtures/small_project",
+            "mermaid",
+            "--sample",
+            "random",
+        ])
+        .env("ARCHLENS_AUDIT", "off")
+        .output()
+        .unwrap();
+    assert!(!invalid.status.success());
+}
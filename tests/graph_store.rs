@@ -0,0 +1,87 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::graph_store::{
+    format_version, from_stored_json, to_stored_json, GRAPH_FORMAT_VERSION,
+};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+
+fn capsule(name: &str) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Struct,
+        complexity: 3,
+        layer: Some("Core".into()),
+        tags: vec!["core".into()],
+        metadata: HashMap::from([("doc".to_string(), "Store".to_string())]),
+        ..common::capsule(name)
+    }
+}
+
+fn graph() -> CapsuleGraph {
+    let store = capsule("Store");
+    CapsuleGraph {
+        layers: HashMap::from([("Core".to_string(), vec![store.id])]),
+        capsules: HashMap::from([(store.id, store)]),
+        relations: vec![],
+        metrics: GraphMetrics {
+            total_capsules: 1,
+            complexity_average: 3.0,
+            cohesion_index: 1.0,
+            cyclomatic_complexity: 3,
+            depth_levels: 1,
+            ..Default::default()
+        },
+        created_at: Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+#[test]
+fn stored_graph_round_trip_carries_version() {
+    let json = to_stored_json(&graph()).unwrap();
+    let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(format_version(&document), GRAPH_FORMAT_VERSION);
+    assert_eq!(document["archlens_version"], env!("CARGO_PKG_VERSION"));
+
+    let loaded = from_stored_json(&json).unwrap();
+    let store = loaded.capsules.values().next().unwrap();
+    assert_eq!(store.name, "Store");
+    assert_eq!(store.metadata["doc"], "Store");
+}
+
+#[test]
+fn legacy_unversioned_graph_is_migrated() {
+    // граф в формате до версионирования: без конверта и без части полей капсул
+    let mut legacy = serde_json::to_value(graph()).unwrap();
+    for capsule in legacy["capsules"].as_object_mut().unwrap().values_mut() {
+        let capsule = capsule.as_object_mut().unwrap();
+        for key in ["dependents", "tags", "metadata", "quality_score"] {
+            capsule.remove(key);
+        }
+    }
+    let loaded = from_stored_json(&legacy.to_string()).unwrap();
+    let store = loaded.capsules.values().next().unwrap();
+    assert_eq!(store.name, "Store");
+    assert!(store.tags.is_empty() && store.metadata.is_empty());
+    assert_eq!(loaded.metrics.total_capsules, 1);
+}
+
+#[test]
+fn newer_format_is_rejected() {
+    let json = serde_json::json!({
+        "format_version": GRAPH_FORMAT_VERSION + 1,
+        "archlens_version": "99.0.0",
+        "graph": serde_json::to_value(graph()).unwrap(),
+    });
+    let err = from_stored_json(&json.to_string()).unwrap_err();
+    assert!(format!("{err:?}").contains("newer than supported"));
+}
+
+#[test]
+fn json_report_declares_format_version() {
+    let report: serde_json::Value =
+        serde_json::from_str(&Exporter::new().export_to_json(&graph()).unwrap()).unwrap();
+    assert_eq!(report["format_version"], 1);
+}
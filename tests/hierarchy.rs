@@ -0,0 +1,188 @@
+mod common;
+
+use archlens::constructor::CapsuleConstructor;
+use archlens::exporter::Exporter;
+use archlens::hierarchy::*;
+use archlens::parser_ast::ParserAST;
+use archlens::types::*;
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+fn capsule(name: &str, kind: CapsuleType, file: &str, lines: (usize, usize)) -> Capsule {
+    Capsule {
+        capsule_type: kind,
+        file_path: Path::new("/project").join(file),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        complexity: 2,
+        layer: Some(if file.contains("cli") { "Api" } else { "Core" }.into()),
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Uses,
+        strength: 0.5,
+        description: None,
+    }
+}
+
+/// src/store.rs: Store (1-20) с методом save (3-8), свободная функция helper;
+/// src/cli/run.rs: run, вызывающий save и helper
+fn sample() -> (CapsuleGraph, [Capsule; 4]) {
+    let store = capsule("Store", CapsuleType::Struct, "src/store.rs", (1, 20));
+    let mut save = capsule("save", CapsuleType::Method, "src/store.rs", (3, 8));
+    save.metadata
+        .insert(PARENT_KEY.to_string(), store.id.to_string());
+    let mut helper = capsule("helper", CapsuleType::Function, "src/store.rs", (22, 25));
+    helper.complexity = 5;
+    helper.warnings.push(AnalysisWarning {
+        message: "too complex".into(),
+        level: Priority::High,
+        category: "complexity".into(),
+        capsule_id: Some(helper.id),
+        suggestion: None,
+    });
+    let run = capsule("run", CapsuleType::Function, "src/cli/run.rs", (1, 10));
+    let capsules = [store, save, helper, run];
+    let graph = common::graph(
+        capsules.to_vec(),
+        vec![
+            relation(&capsules[3], &capsules[1]),
+            relation(&capsules[3], &capsules[2]),
+            relation(&capsules[2], &capsules[1]),
+        ],
+    );
+    (graph, capsules)
+}
+
+#[test]
+fn capsules_nest_under_types_files_and_directories() {
+    let (graph, [store, save, helper, run]) = sample();
+    let hierarchy = CapsuleHierarchy::from_graph(&graph);
+    let node = |key: &str| &hierarchy.nodes[key];
+
+    assert_eq!(
+        node(&save.id.to_string()).parent,
+        Some(store.id.to_string())
+    );
+    assert_eq!(
+        node(&helper.id.to_string()).parent.as_deref(),
+        Some("file:store.rs")
+    );
+    let src = node("dir:.");
+    assert_eq!(src.level, HierarchyLevel::Directory);
+    assert_eq!(
+        src.rollup,
+        Rollup {
+            capsules: 3,
+            complexity: 9,
+            max_complexity: 5,
+            warnings: 1
+        }
+    );
+    let cli = node("dir:cli");
+    assert_eq!(cli.rollup.capsules, 1);
+    // корень и каталоги верхнего уровня — корни иерархии
+    assert_eq!(cli.parent, None);
+    assert_eq!(node("file:cli/run.rs").parent.as_deref(), Some("dir:cli"));
+    assert_eq!(node(&store.id.to_string()).rollup.capsules, 2);
+
+    assert_eq!(
+        hierarchy
+            .representative(save.id, HierarchyLevel::Type)
+            .unwrap()
+            .key,
+        store.id.to_string()
+    );
+    assert_eq!(
+        hierarchy
+            .representative(run.id, HierarchyLevel::Type)
+            .unwrap()
+            .key,
+        "file:cli/run.rs"
+    );
+    let dirs: Vec<&str> = hierarchy
+        .at_level(HierarchyLevel::Directory)
+        .iter()
+        .map(|n| n.name.as_str())
+        .collect();
+    assert_eq!(dirs, [".", "cli"]);
+}
+
+#[test]
+fn graph_collapses_per_level() {
+    let (graph, [store, save, helper, _run]) = sample();
+    let hierarchy = CapsuleHierarchy::from_graph(&graph);
+
+    let files = hierarchy.collapse(&graph, HierarchyLevel::File);
+    assert_eq!(files.capsules.len(), 2);
+    // три связи сводятся к одной: cli/run.rs → store.rs, связь внутри файла отброшена
+    assert_eq!(files.relations.len(), 1);
+    let names: HashMap<Uuid, &str> = files
+        .capsules
+        .values()
+        .map(|c| (c.id, c.name.as_str()))
+        .collect();
+    let r = &files.relations[0];
+    assert_eq!(
+        (names[&r.from_id], names[&r.to_id]),
+        ("cli/run.rs", "store.rs")
+    );
+    let store_file = files
+        .capsules
+        .values()
+        .find(|c| c.name == "store.rs")
+        .unwrap();
+    assert_eq!(store_file.metadata[LEVEL_KEY], "file");
+    assert_eq!(store_file.metadata[COLLAPSED_KEY], "3");
+    assert_eq!(store_file.complexity, 9);
+    assert_eq!(store_file.layer.as_deref(), Some("Core"));
+
+    let types = hierarchy.collapse(&graph, HierarchyLevel::Type);
+    assert!(types.capsules.contains_key(&store.id));
+    assert!(!types.capsules.contains_key(&save.id));
+    assert!(!types.capsules.contains_key(&helper.id));
+    assert_eq!(types.capsules.len(), 3);
+
+    let functions = hierarchy.collapse(&graph, HierarchyLevel::Function);
+    assert_eq!(functions.capsules.len(), 4);
+    assert_eq!(
+        HierarchyLevel::parse("dir"),
+        Some(HierarchyLevel::Directory)
+    );
+    assert_eq!(HierarchyLevel::parse("module"), None);
+}
+
+#[test]
+fn exports_roll_metrics_up_by_directory() {
+    let (graph, _) = sample();
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Directory Rollup"));
+    assert!(compact.contains("- . : 3 components, complexity 9 (max 5), 1 warnings"));
+    let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(summary["directories"][1]["directory"], "cli");
+    assert_eq!(summary["directories"][1]["capsules"], 1);
+}
+
+#[test]
+fn constructor_keeps_ast_parent() {
+    let source = "class Store:\n    def save(self, item):\n        self.items.append(item)\n        return len(self.items)\n";
+    let path = Path::new("store.py");
+    let elements = ParserAST::new()
+        .unwrap()
+        .parse_file(path, source, &FileType::Python)
+        .unwrap();
+    let capsules = CapsuleConstructor::new()
+        .create_capsules(&elements, path)
+        .unwrap();
+    let save = capsules.iter().find(|c| c.name == "save").unwrap();
+    let parent = save.metadata.get(PARENT_KEY).expect("parent recorded");
+    let store = capsules.iter().find(|c| c.name == "Store").unwrap();
+    assert_eq!(*parent, store.id.to_string());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use archlens::advanced_metrics::hotspots::*;
+use archlens::code_origin::ORIGIN_KEY;
+use archlens::exporter::Exporter;
+use archlens::metadata_extractor::GIT_COMMITS_KEY;
+use archlens::types::*;
+use std::collections::HashMap;
+
+fn capsule(name: &str, complexity: u32, commits: Option<usize>) -> Capsule {
+    let mut metadata = HashMap::new();
+    if let Some(commits) = commits {
+        metadata.insert(GIT_COMMITS_KEY.to_string(), commits.to_string());
+    }
+    Capsule {
+        complexity,
+        metadata,
+        ..common::capsule(name)
+    }
+}
+
+fn graph(capsules: Vec<Capsule>) -> CapsuleGraph {
+    common::graph(capsules, vec![])
+}
+
+#[test]
+fn churn_is_normalized_and_multiplied_by_complexity() {
+    let mut test_helper = capsule("fixture", 50, Some(40));
+    test_helper
+        .metadata
+        .insert(ORIGIN_KEY.to_string(), "test".to_string());
+    let graph = graph(vec![
+        capsule("parser", 20, Some(10)),
+        capsule("router", 8, Some(20)),
+        capsule("config", 30, Some(1)),
+        capsule("untracked", 99, None),
+        test_helper,
+    ]);
+
+    let hotspots = HotspotAnalyzer::new().rank(&graph);
+    let ranked: Vec<(&str, f32)> = hotspots
+        .iter()
+        .map(|h| (h.component.as_str(), h.score))
+        .collect();
+    assert_eq!(ranked, [("parser", 10.0), ("router", 8.0), ("config", 1.5)]);
+    assert_eq!(hotspots[1].churn, 1.0);
+
+    let top = HotspotAnalyzer::new().with_limit(1).rank(&graph);
+    assert_eq!(top.len(), 1);
+
+    let text = hotspots_to_text(&hotspots, ComplexityBasis::Cyclomatic);
+    assert!(text.starts_with("Top Hotspots (churn × cyclomatic complexity)\n"));
+    assert!(text.contains(" 1. parser (Function) score 10.0 : 10 commits, complexity 20"));
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains(
+        "## Top Hotspots (churn × complexity)\n- parser (Function) : score 10.0, 10 commits, complexity 20\n"
+    ));
+}
+
+#[test]
+fn without_git_history_there_are_no_hotspots() {
+    let graph = graph(vec![capsule("parser", 20, None)]);
+    assert!(HotspotAnalyzer::new().rank(&graph).is_empty());
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(!compact.contains("Top Hotspots"));
+    assert!(hotspots_to_text(&[], ComplexityBasis::Cognitive).contains("git"));
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use archlens::i18n::{Localizer, MessageCatalog};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::fs;
+use uuid::Uuid;
+
+fn localizer(lang: &str) -> Localizer {
+    Localizer::for_language(lang, None).unwrap()
+}
+
+#[test]
+fn builtin_catalogs_cover_every_english_key() {
+    let reference = MessageCatalog::builtin("en").unwrap();
+    for lang in MessageCatalog::builtin_languages() {
+        let catalog = MessageCatalog::builtin(lang).unwrap();
+        assert_eq!(catalog.language, lang);
+        assert_eq!(
+            catalog.validate_against(&reference),
+            Vec::<String>::new(),
+            "{lang}"
+        );
+    }
+}
+
+#[test]
+fn translates_warnings_with_substituted_values() {
+    let ru = localizer("ru");
+    assert_eq!(
+        ru.localize("Component 'Foo' has high complexity: 42"),
+        "Компонент 'Foo' имеет высокую сложность: 42"
+    );
+    assert_eq!(
+        ru.localize("Layer violation: api -> db (from API to Data)"),
+        "Нарушение слоёв: api -> db (из API в Data)"
+    );
+    assert_eq!(
+        localizer("es").localize("Consider applying Facade pattern"),
+        "Considere aplicar el patrón Fachada"
+    );
+    // сообщения на русском (запахи кода) тоже узнаются
+    assert_eq!(
+        localizer("de").localize("Функция 'run' слишком длинная (80 строк)"),
+        "Funktion 'run' ist zu lang (80 Zeilen)"
+    );
+    assert_eq!(
+        localizer("en").localize("Магическое число: 3600"),
+        "Magic number: 3600"
+    );
+}
+
+#[test]
+fn unknown_messages_and_languages() {
+    assert_eq!(
+        localizer("ru").localize("Something brand new: 7"),
+        "Something brand new: 7"
+    );
+    let err = Localizer::for_language("xx", None).unwrap_err().to_string();
+    assert!(err.contains("'xx' (available: de, en, es, ru)"), "{err}");
+}
+
+#[test]
+fn localizes_graph_warnings() {
+    let id = Uuid::new_v4();
+    let capsule = Capsule {
+        id,
+        file_path: "src/foo.rs".into(),
+        line_end: 5,
+        size: 5,
+        complexity: 42,
+        warnings: vec![AnalysisWarning {
+            message: "High complexity: 42".into(),
+            level: Priority::High,
+            category: "complexity".into(),
+            capsule_id: Some(id),
+            suggestion: Some("Break into smaller functions".into()),
+        }],
+        quality_score: 0.5,
+        ..common::capsule("Foo")
+    };
+    let mut graph = CapsuleGraph {
+        capsules: HashMap::from([(id, capsule)]),
+        relations: vec![],
+        layers: HashMap::new(),
+        metrics: GraphMetrics {
+            total_capsules: 1,
+            complexity_average: 42.0,
+            cohesion_index: 1.0,
+            cyclomatic_complexity: 42,
+            depth_levels: 1,
+            ..Default::default()
+        },
+        created_at: Utc::now(),
+        previous_analysis: None,
+    };
+
+    localizer("de").localize_graph(&mut graph);
+    let warning = &graph.capsules[&id].warnings[0];
+    assert_eq!(warning.message, "Hohe Komplexität: 42");
+    assert_eq!(
+        warning.suggestion.as_deref(),
+        Some("In kleinere Funktionen aufteilen")
+    );
+}
+
+#[test]
+fn project_catalogs_add_languages_and_override_builtin_strings() {
+    let root = std::env::temp_dir().join(format!("archlens_i18n_{}", std::process::id()));
+    let locales = root.join(".archlens").join("locales");
+    fs::create_dir_all(&locales).unwrap();
+    fs::write(
+        locales.join("fr.json"),
+        r#"{"language":"fr","name":"Français","messages":{"warning.high_complexity":"Complexité élevée : {0}"}}"#,
+    )
+    .unwrap();
+    fs::write(
+        locales.join("ru-override.json"),
+        r#"{"language":"ru","messages":{"warning.missing_docs":"Нет документации"}}"#,
+    )
+    .unwrap();
+
+    let fr = Localizer::for_language("fr", Some(&root)).unwrap();
+    assert_eq!(fr.language(), "fr");
+    assert_eq!(fr.localize("High complexity: 12"), "Complexité élevée : 12");
+    // ключа нет в каталоге — текст остаётся исходным
+    assert_eq!(
+        fr.localize("Missing documentation"),
+        "Missing documentation"
+    );
+
+    let ru = Localizer::for_language("ru", Some(&root)).unwrap();
+    assert_eq!(ru.localize("Missing documentation"), "Нет документации");
+    assert_eq!(ru.localize("Contains TODO"), "Содержит TODO");
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::graph::ImpactAnalyzer;
+use archlens::types::*;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+fn capsule(path: &str, name: &str) -> Capsule {
+    Capsule {
+        file_path: PathBuf::from(format!("/repo/{path}")),
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// handler → service → repository → db; report → service; cli ↔ handler;
+/// sibling связан с repository только структурной связью
+fn graph() -> CapsuleGraph {
+    let capsules = [
+        capsule("src/db.rs", "connect"),
+        capsule("src/repository.rs", "load_user"),
+        capsule("src/service.rs", "user_profile"),
+        capsule("src/api/handler.rs", "get_user"),
+        capsule("src/report.rs", "weekly_report"),
+        capsule("src/cli.rs", "run_cli"),
+        capsule("src/sibling.rs", "sibling"),
+    ];
+    let [db, repo, service, handler, report, cli, sibling] = &capsules;
+    let relations = vec![
+        relation(repo, db, RelationType::Calls),
+        relation(service, repo, RelationType::Calls),
+        relation(handler, service, RelationType::Calls),
+        relation(report, service, RelationType::Imports),
+        relation(cli, handler, RelationType::Calls),
+        relation(handler, cli, RelationType::Calls),
+        relation(sibling, repo, RelationType::Depends),
+    ];
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = relations;
+    graph
+}
+
+#[test]
+fn dependents_are_reported_with_distance() {
+    let graph = graph();
+    let report = ImpactAnalyzer::new(&graph, Path::new("/repo")).analyze("src/repository.rs");
+
+    assert_eq!(report.changed, vec!["src/repository.rs:1 load_user"]);
+    let found: Vec<(&str, usize, &str)> = report
+        .impacted
+        .iter()
+        .map(|c| (c.name.as_str(), c.distance, c.via.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("user_profile", 1, "load_user"),
+            ("get_user", 2, "user_profile"),
+            ("weekly_report", 2, "user_profile"),
+            ("run_cli", 3, "get_user"),
+        ]
+    );
+    assert_eq!(report.files, 4);
+    assert!(!report.truncated);
+    assert_eq!(report.impacted[2].relation, RelationType::Imports);
+    assert!(
+        report.to_text().contains("distance 3"),
+        "{}",
+        report.to_text()
+    );
+}
+
+#[test]
+fn depth_limit_and_capsule_names() {
+    let graph = graph();
+    let report = ImpactAnalyzer::new(&graph, Path::new("/repo"))
+        .with_max_depth(1)
+        .analyze("load_user");
+    assert_eq!(report.changed.len(), 1);
+    assert_eq!(report.impacted.len(), 1);
+    assert!(report.truncated);
+
+    let leaf = ImpactAnalyzer::new(&graph, Path::new("/repo")).analyze("weekly_report");
+    assert!(leaf.impacted.is_empty());
+    assert!(leaf.to_text().contains("No other components depend on it"));
+
+    let missing = ImpactAnalyzer::new(&graph, Path::new("/repo")).analyze("nonexistent_thing");
+    assert!(missing.changed.is_empty());
+}
+
+#[test]
+fn stdio_impact_analyze_returns_dependents() {
+    let dir = std::env::temp_dir().join(format!("archlens_impact_mcp_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("src/lib.rs"),
+        "pub fn base() -> u32 {\n    1\n}\n\npub fn top() -> u32 {\n    base() + 1\n}\n",
+    )
+    .unwrap();
+
+    let mut child = match Command::new(env!("CARGO_BIN_EXE_archlens-mcp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(_) => {
+            eprintln!("archlens-mcp not built; skipping stdio impact.analyze e2e");
+            return;
+        }
+    };
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        let call = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "impact_analyze", "arguments": {"project_path": dir, "target": "base"}}
+        });
+        stdin.write_all(format!("{call}\n").as_bytes()).unwrap();
+    }
+    let mut out = String::new();
+    let _ = child.stdout.take().unwrap().read_to_string(&mut out);
+    let _ = child.kill();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(out.contains("\"impact\""), "{out}");
+    assert!(out.contains(r#""name":"top""#), "{out}");
+}
+
+#[test]
+fn stdio_impact_analyze_applies_redaction() {
+    let dir = std::env::temp_dir().join(format!("archlens_impact_redact_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::create_dir_all(dir.join(".archlens")).unwrap();
+    std::fs::write(
+        dir.join("src/lib.rs"),
+        "pub fn base() -> u32 {\n    1\n}\n\npub fn acme_top() -> u32 {\n    base() + 1\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join(".archlens/redaction.json"),
+        r#"{"identifiers": ["acme_\\w+"], "replacement": "HIDDEN"}"#,
+    )
+    .unwrap();
+
+    let mut child = match Command::new(env!("CARGO_BIN_EXE_archlens-mcp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(_) => {
+            eprintln!("archlens-mcp not built; skipping stdio impact.analyze e2e");
+            return;
+        }
+    };
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        let call = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "impact_analyze", "arguments": {"project_path": dir, "target": "base"}}
+        });
+        stdin.write_all(format!("{call}\n").as_bytes()).unwrap();
+    }
+    let mut out = String::new();
+    let _ = child.stdout.take().unwrap().read_to_string(&mut out);
+    let _ = child.kill();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(out.contains(r#""name":"HIDDEN""#), "{out}");
+    assert!(!out.contains("acme_top"), "{out}");
+}
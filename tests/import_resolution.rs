@@ -0,0 +1,139 @@
+use archlens::capsule_constructor::CapsuleConstructor;
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::parser_ast::ParserAST;
+use archlens::types::*;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_import_resolution_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Связи `Imports` между капсулами проекта из файлов `(путь, исходник)`
+fn import_edges(dir: &Path, files: &[(&str, &str)]) -> BTreeSet<(String, String)> {
+    let mut capsules = Vec::new();
+    for (relative, source) in files {
+        let path = dir.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        let file_type = match path.extension().unwrap().to_str().unwrap() {
+            "rs" => FileType::Rust,
+            "ts" => FileType::TypeScript,
+            "py" => FileType::Python,
+            other => FileType::Other(other.to_string()),
+        };
+        let elements = ParserAST::new()
+            .unwrap()
+            .parse_file(&path, source, &file_type)
+            .unwrap();
+        capsules.extend(
+            CapsuleConstructor::new()
+                .create_capsules(&elements, &path)
+                .unwrap(),
+        );
+    }
+    let names: HashMap<_, _> = capsules.iter().map(|c| (c.id, c.name.clone())).collect();
+    CapsuleGraphBuilder::new()
+        .build_advanced_relations(&capsules)
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.relation_type == RelationType::Imports)
+        .map(|r| (names[&r.from_id].clone(), names[&r.to_id].clone()))
+        .collect()
+}
+
+fn edges(pairs: &[(&str, &str)]) -> BTreeSet<(String, String)> {
+    pairs
+        .iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect()
+}
+
+#[test]
+fn rust_use_paths_resolve_through_modules_and_reexports() {
+    let dir = temp_dir("rust");
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"demo-app\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    let found = import_edges(
+        &dir,
+        &[
+            ("src/store/mod.rs", "mod memory;\n\npub use memory::MemoryStore;\n"),
+            (
+                "src/store/memory.rs",
+                "pub struct MemoryStore {\n    items: Vec<u32>,\n}\n",
+            ),
+            (
+                "src/legacy.rs",
+                "pub struct MemoryStore {\n    data: Vec<u8>,\n}\n",
+            ),
+            (
+                "src/api.rs",
+                "use std::collections::HashMap;\nuse crate::store::{self, MemoryStore};\n\npub fn handler(store: &MemoryStore) -> usize {\n    store.items.len()\n}\n\npub fn fresh() -> store::MemoryStore {\n    todo!()\n}\n\npub fn helper(map: HashMap<u32, u32>) -> usize {\n    map.len()\n}\n",
+            ),
+            (
+                "src/bin/tool.rs",
+                "use demo_app::store::MemoryStore;\n\nfn run(store: MemoryStore) {\n    drop(store);\n}\n",
+            ),
+        ],
+    );
+    assert_eq!(
+        found,
+        edges(&[
+            ("handler", "MemoryStore"),
+            ("fresh", "MemoryStore"),
+            ("run", "MemoryStore"),
+        ])
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn relative_script_imports_resolve_to_declarations() {
+    let dir = temp_dir("script");
+    let found = import_edges(
+        &dir,
+        &[
+            (
+                "src/util/math.ts",
+                "export function add(a: number, b: number): number {\n  return a + b;\n}\n\nclass Calc {\n  total = 0;\n}\n\nexport default Calc;\n",
+            ),
+            ("src/util/index.ts", "export { add } from './math';\n\nexport function noop(): void {}\n"),
+            (
+                "src/app.ts",
+                "import React from 'react';\nimport Calc from './util/math';\nimport { add as plus } from './util';\n\nexport function sum(): number {\n  return plus(1, 2);\n}\n\nexport function make(): Calc {\n  return new Calc();\n}\n\nexport function view(): string {\n  return React.version;\n}\n",
+            ),
+        ],
+    );
+    assert_eq!(found, edges(&[("sum", "add"), ("make", "Calc")]));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn python_modules_resolve_relative_and_absolute_imports() {
+    let dir = temp_dir("python");
+    let found = import_edges(
+        &dir,
+        &[
+            (
+                "pkg/models.py",
+                "class User:\n    pass\n\n\nclass Order:\n    pass\n",
+            ),
+            (
+                "pkg/service.py",
+                "import os\nimport pkg.models as m\nfrom .models import User\n\n\ndef create():\n    return User()\n\n\ndef load():\n    return m.Order()\n\n\ndef cwd():\n    return os.getcwd()\n",
+            ),
+        ],
+    );
+    assert_eq!(found, edges(&[("create", "User"), ("load", "Order")]));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,86 @@
+use archlens::parser_ast::{ASTElement, IncrementalStats, ParserAST};
+use archlens::types::FileType;
+use std::path::Path;
+
+const V1: &str = "pub struct Store {\n    items: Vec<String>,\n}\n\nfn load() -> Store {\n    Store { items: vec![] }\n}\n";
+// та же длина, другое имя функции: ключ `путь:длина` вернул бы старые элементы
+const V2: &str = "pub struct Store {\n    items: Vec<String>,\n}\n\nfn save() -> Store {\n    Store { items: vec![] }\n}\n";
+const V3: &str = "pub struct Store {\n    items: Vec<String>,\n}\n\n/// Загрузка\nfn load() -> Store {\n    Store { items: vec![] }\n}\n\nfn clear(store: &mut Store) {\n    store.items.clear();\n}\n";
+
+fn outline(elements: &[ASTElement]) -> Vec<(String, usize, usize)> {
+    let mut outline: Vec<_> = elements
+        .iter()
+        .map(|e| (e.name.clone(), e.start_line, e.end_line))
+        .collect();
+    outline.sort();
+    outline
+}
+
+fn fresh(content: &str) -> Vec<(String, usize, usize)> {
+    let elements = ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new("src/store.rs"), content, &FileType::Rust)
+        .unwrap();
+    outline(&elements)
+}
+
+#[test]
+fn incremental_mode_follows_file_changes() {
+    let path = Path::new("src/store.rs");
+    let mut parser = ParserAST::new().unwrap().with_incremental(true);
+    assert!(parser.is_incremental());
+
+    let first = parser.parse_file(path, V1, &FileType::Rust).unwrap();
+    assert_eq!(outline(&first), fresh(V1));
+    let again = parser.parse_file(path, V1, &FileType::Rust).unwrap();
+    assert_eq!(outline(&again), outline(&first));
+
+    assert_eq!(V1.len(), V2.len());
+    let renamed = parser.parse_file(path, V2, &FileType::Rust).unwrap();
+    assert_eq!(outline(&renamed), fresh(V2));
+    assert!(renamed.iter().any(|e| e.name == "save"));
+
+    let grown = parser.parse_file(path, V3, &FileType::Rust).unwrap();
+    assert_eq!(outline(&grown), fresh(V3));
+
+    let stats = parser.incremental_stats();
+    assert_eq!(stats.unchanged, 1);
+    assert_eq!(stats.reparsed + stats.full, 3);
+}
+
+#[test]
+fn default_mode_keeps_stats_empty() {
+    let mut parser = ParserAST::new().unwrap();
+    assert!(!parser.is_incremental());
+    parser
+        .parse_file(Path::new("src/store.rs"), V1, &FileType::Rust)
+        .unwrap();
+    parser
+        .parse_file(Path::new("src/store.rs"), V1, &FileType::Rust)
+        .unwrap();
+    assert_eq!(parser.incremental_stats(), IncrementalStats::default());
+}
+
+#[cfg(feature = "tree_sitter")]
+#[test]
+fn changed_files_reuse_previous_trees() {
+    let path = Path::new("src/store.rs");
+    let mut parser = ParserAST::new().unwrap().with_incremental(true);
+    parser.parse_file(path, V1, &FileType::Rust).unwrap();
+    parser.parse_file(path, V2, &FileType::Rust).unwrap();
+    let grown = parser.parse_file(path, V3, &FileType::Rust).unwrap();
+    assert_eq!(outline(&grown), fresh(V3));
+
+    // другой файл разбирается полностью
+    parser
+        .parse_file(Path::new("src/other.rs"), V1, &FileType::Rust)
+        .unwrap();
+    assert_eq!(
+        parser.incremental_stats(),
+        IncrementalStats {
+            unchanged: 0,
+            reparsed: 2,
+            full: 2,
+        }
+    );
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use archlens::graph::MetricsCalculator;
+use archlens::types::*;
+use archlens::validation::LayerValidator;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn capsule(name: &str, layer: &str) -> Capsule {
+    Capsule {
+        complexity: 2,
+        layer: Some(layer.into()),
+        ..common::capsule(name)
+    }
+}
+
+fn imports(from: &Capsule, to: &Capsule) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type: RelationType::Imports,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+fn graph(relations: impl Fn(&[Capsule; 4]) -> Vec<CapsuleRelation>) -> CapsuleGraph {
+    let capsules = [
+        capsule("view", "UI"),
+        capsule("controller", "API"),
+        capsule("service", "Business"),
+        capsule("repository", "Data"),
+    ];
+    let relations = relations(&capsules);
+    let map: HashMap<Uuid, Capsule> = capsules.iter().map(|c| (c.id, c.clone())).collect();
+    let metrics = MetricsCalculator::new()
+        .calculate_advanced_metrics(&map, &relations)
+        .unwrap();
+    CapsuleGraph {
+        layers: HashMap::new(),
+        capsules: map,
+        relations,
+        metrics,
+        created_at: chrono::Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+#[test]
+fn violations_are_weighted_by_layers_crossed() {
+    let graph = graph(|[view, controller, service, repository]| {
+        vec![
+            imports(service, controller),
+            imports(repository, view),
+            imports(view, controller),
+        ]
+    });
+    let mut warnings = Vec::new();
+    LayerValidator::new()
+        .validate(&graph, &mut warnings)
+        .unwrap();
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+
+    let level = |name: &str| {
+        warnings
+            .iter()
+            .find(|w| w.message.contains(&format!("{name} ->")))
+            .map(|w| w.level)
+            .unwrap()
+    };
+    assert_eq!(level("service"), Priority::Medium);
+    assert_eq!(level("repository"), Priority::Critical);
+
+    // 1 + 3 пересечённых слоя из худших 3 × 4
+    let index = graph.metrics.layering_violation_index;
+    assert!((index - 4.0 / 12.0).abs() < 1e-6, "{index}");
+}
+
+#[test]
+fn respected_layering_has_zero_index() {
+    let graph = graph(|[view, controller, service, repository]| {
+        vec![
+            imports(view, controller),
+            imports(controller, service),
+            imports(service, repository),
+            imports(view, repository),
+        ]
+    });
+    let mut warnings = Vec::new();
+    LayerValidator::new()
+        .validate(&graph, &mut warnings)
+        .unwrap();
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(graph.metrics.layering_violation_index, 0.0);
+}
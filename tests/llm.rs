@@ -0,0 +1,265 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::llm::{LlmConfig, LlmSummarizer, LlmTransport, LLM_SUMMARY_KEY};
+use archlens::types::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Отвечает заранее заданным текстом и запоминает запросы
+struct MockTransport {
+    calls: Arc<AtomicUsize>,
+    bodies: Arc<Mutex<Vec<String>>>,
+    fail: bool,
+}
+
+impl LlmTransport for MockTransport {
+    fn post_json(
+        &self,
+        url: &str,
+        _key: Option<&str>,
+        body: &str,
+        _timeout: u64,
+    ) -> Result<String> {
+        assert_eq!(url, "http://localhost:8080/v1/chat/completions");
+        let n = self.calls.fetch_add(1, Ordering::SeqCst);
+        self.bodies.lock().unwrap().push(body.to_string());
+        if self.fail {
+            return Err(AnalysisError::GenericError("connection refused".into()));
+        }
+        Ok(serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": format!(" Answer {n} ")}}]
+        })
+        .to_string())
+    }
+}
+
+fn temp_root(tag: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("archlens_llm_{}_{}", tag, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src")).unwrap();
+    root
+}
+
+fn config() -> LlmConfig {
+    serde_json::from_str(
+        r#"{"enabled": true, "endpoint": "http://localhost:8080/v1/", "model": "local-model", "max_capsules": 2}"#,
+    )
+    .unwrap()
+}
+
+fn capsule(root: &Path, name: &str, complexity: u32) -> Capsule {
+    let file = root.join("src").join(format!("{name}.rs"));
+    fs::write(
+        &file,
+        format!("pub fn {name}() -> u32 {{\n    {complexity}\n}}\n"),
+    )
+    .unwrap();
+    Capsule {
+        file_path: file,
+        line_end: 3,
+        size: 3,
+        complexity,
+        layer: Some("Core".into()),
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+fn graph(root: &Path) -> CapsuleGraph {
+    let capsules = vec![
+        capsule(root, "parse", 9),
+        capsule(root, "render", 5),
+        capsule(root, "helper", 1),
+    ];
+    common::graph(capsules, vec![])
+}
+
+fn mock_summarizer(
+    calls: &Arc<AtomicUsize>,
+    fail: bool,
+) -> (LlmSummarizer, Arc<Mutex<Vec<String>>>) {
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let transport = MockTransport {
+        calls: calls.clone(),
+        bodies: bodies.clone(),
+        fail,
+    };
+    (
+        LlmSummarizer::new(config()).with_transport(Box::new(transport)),
+        bodies,
+    )
+}
+
+fn summary_of<'a>(graph: &'a CapsuleGraph, name: &str) -> Option<&'a String> {
+    graph
+        .capsules
+        .values()
+        .find(|c| c.name == name)
+        .and_then(|c| c.metadata.get(LLM_SUMMARY_KEY))
+}
+
+#[test]
+fn config_is_disabled_unless_explicitly_enabled() {
+    let root = temp_root("config");
+    assert!(LlmConfig::load(&root).unwrap().is_none());
+
+    fs::create_dir_all(root.join(".archlens")).unwrap();
+    let path = LlmConfig::config_path(&root);
+    fs::write(
+        &path,
+        r#"{"endpoint": "http://localhost:8080/v1", "model": "m"}"#,
+    )
+    .unwrap();
+    assert!(LlmConfig::load(&root).unwrap().is_none());
+
+    fs::write(
+        &path,
+        r#"{"enabled": true, "endpoint": "http://localhost:8080/v1", "model": "m"}"#,
+    )
+    .unwrap();
+    let config = LlmConfig::load(&root).unwrap().unwrap();
+    assert_eq!(config.api_key_env, "OPENAI_API_KEY");
+    assert_eq!(config.max_capsules, 30);
+
+    fs::write(&path, "{not json").unwrap();
+    assert!(LlmConfig::load(&root).is_err());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn summarizes_most_complex_capsules_and_caches_by_content() {
+    let root = temp_root("cache");
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut first = graph(&root);
+    let (summarizer, bodies) = mock_summarizer(&calls, false);
+    let report = summarizer
+        .with_project_cache(&root)
+        .summarize_graph(&mut first);
+    assert_eq!((report.summarized, report.cached, report.failed), (3, 0, 0));
+    assert_eq!(
+        summary_of(&first, "parse").map(String::as_str),
+        Some("Answer 0")
+    );
+    assert_eq!(
+        summary_of(&first, "render").map(String::as_str),
+        Some("Answer 1")
+    );
+    assert!(summary_of(&first, "helper").is_none(), "max_capsules = 2");
+    assert_eq!(report.overview.as_deref(), Some("Answer 2"));
+    let bodies = bodies.lock().unwrap();
+    assert!(
+        bodies[0].contains("\"model\":\"local-model\"") && bodies[0].contains("pub fn parse()")
+    );
+    assert!(bodies[2].contains("parse: Answer 0"));
+    assert!(root.join(".archlens").join("llm-cache.json").exists());
+
+    // тот же код — ответы из кэша, без запросов
+    let mut second = graph(&root);
+    let (summarizer, _) = mock_summarizer(&calls, false);
+    let report = summarizer
+        .with_project_cache(&root)
+        .summarize_graph(&mut second);
+    assert_eq!((report.summarized, report.cached), (0, 3));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(
+        summary_of(&second, "parse").map(String::as_str),
+        Some("Answer 0")
+    );
+
+    // изменение исходника инвалидирует сводку этой капсулы (и обзора)
+    let mut third = graph(&root);
+    fs::write(
+        root.join("src").join("parse.rs"),
+        "pub fn parse() -> u32 {\n    10\n}\n",
+    )
+    .unwrap();
+    let (summarizer, _) = mock_summarizer(&calls, false);
+    let report = summarizer
+        .with_project_cache(&root)
+        .summarize_graph(&mut third);
+    assert_eq!((report.summarized, report.cached), (2, 1));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn unreachable_endpoint_stops_after_first_failure() {
+    let root = temp_root("fail");
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut graph = graph(&root);
+    let (mut summarizer, _) = mock_summarizer(&calls, true);
+
+    let report = summarizer.summarize_graph(&mut graph);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(report.failed, 1);
+    assert!(report.overview.is_none());
+    assert!(report.errors[0].contains("connection refused"));
+    assert!(graph.capsules.values().all(|c| c.metadata.is_empty()));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn exports_include_overview_and_capsule_summaries() {
+    let root = temp_root("export");
+    let mut graph = graph(&root);
+    let id = graph
+        .capsules
+        .values()
+        .find(|c| c.name == "parse")
+        .unwrap()
+        .id;
+    graph
+        .capsules
+        .get_mut(&id)
+        .unwrap()
+        .metadata
+        .insert(LLM_SUMMARY_KEY.into(), "Parses <input> files".into());
+    let exporter = Exporter::new().with_overview(Some("A small & tidy parser.".into()));
+
+    let compact = exporter.export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Executive Overview\nA small & tidy parser.\n"));
+    assert!(compact.contains("## Component Summaries\n- parse: Parses <input> files\n"));
+
+    let html = exporter.export_to_interactive_html(&graph).unwrap();
+    assert!(html.contains("<p>A small &amp; tidy parser.</p>"));
+    assert!(html.contains("<p class=\"summary\">Parses &lt;input&gt; files</p>"));
+
+    let email = exporter.export_to_email_summary(&graph).unwrap();
+    assert!(email.contains("A small &amp; tidy parser."));
+
+    let plain = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(!plain.contains("Executive Overview"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn redaction_rules_apply_to_requests() {
+    use archlens::redaction::{RedactionConfig, Redactor};
+
+    let root = temp_root("redaction");
+    let mut graph = graph(&root);
+    let config = RedactionConfig::from_json(
+        r#"{"paths": ["src/parse"], "identifiers": ["render"], "snippets": true}"#,
+    )
+    .unwrap();
+    let redactor = Redactor::new(&root, &config).unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let (summarizer, bodies) = mock_summarizer(&calls, false);
+    summarizer
+        .with_redactor(Some(redactor))
+        .summarize_graph(&mut graph);
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies.len(), 3);
+    for body in bodies.iter() {
+        assert!(!body.contains("src/parse.rs"), "{body}");
+        assert!(!body.contains("render"), "{body}");
+        assert!(!body.contains("-> u32"), "{body}");
+    }
+    assert!(bodies[0].contains("[code redacted]"));
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,44 @@
+use assert_cmd::prelude::*;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn structure_call(arguments: &str) -> String {
+    let mut cmd = match Command::cargo_bin("archlens-mcp") {
+        Ok(b) => b,
+        Err(_) => return String::new(),
+    };
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd.spawn().expect("spawn");
+    let mut stdin = child.stdin.take().unwrap();
+    let call = format!(
+        "{{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/call\",\"params\":{{\"name\":\"structure.get\",\"arguments\":{}}}}}\n",
+        arguments
+    );
+    stdin.write_all(call.as_bytes()).unwrap();
+    drop(stdin);
+    let output = child.wait_with_output().expect("wait");
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn small_project_gets_full_detail_by_default() {
+    let auto = structure_call(r#"{"project_path":"tests/fixtures/small_project"}"#);
+    if auto.is_empty() {
+        eprintln!("archlens-mcp not built; skipping auto detail e2e");
+        return;
+    }
+    let summary = structure_call(
+        r#"{"project_path":"tests/fixtures/small_project","detail_level":"summary"}"#,
+    );
+    // full перечисляет файлы, summary — только сводку
+    assert!(auto.contains("STRUCTURE"), "{auto}");
+    assert!(auto.contains("- `"), "auto should pick full detail: {auto}");
+    assert!(!summary.contains("- `"), "{summary}");
+
+    // тесный бюджет переводит auto на summary
+    let budget =
+        structure_call(r#"{"project_path":"tests/fixtures/small_project","max_output_chars":100}"#);
+    assert!(!budget.contains("- `"), "{budget}");
+}
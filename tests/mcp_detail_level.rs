@@ -24,7 +24,7 @@ fn http_detail_level_affects_size_and_content() {
     // Wait until server is ready
     for _ in 0..20 {
         if let Ok(resp) = client
-            .get(&format!("http://127.0.0.1:{}/schemas/list", port))
+            .get(format!("http://127.0.0.1:{}/schemas/list", port))
             .send()
         {
             if resp.status().is_success() {
@@ -36,12 +36,12 @@ fn http_detail_level_affects_size_and_content() {
 
     // export: summary vs full
     let r_sum = client
-        .post(&format!("http://127.0.0.1:{}/export/ai_compact", port))
+        .post(format!("http://127.0.0.1:{}/export/ai_compact", port))
         .json(&serde_json::json!({"project_path":"tests/fixtures/small_project","detail_level":"summary"}))
         .send()
         .and_then(|r| r.json::<serde_json::Value>());
     let r_full = client
-        .post(&format!("http://127.0.0.1:{}/export/ai_compact", port))
+        .post(format!("http://127.0.0.1:{}/export/ai_compact", port))
         .json(&serde_json::json!({"project_path":"tests/fixtures/small_project","detail_level":"full"}))
         .send()
         .and_then(|r| r.json::<serde_json::Value>());
@@ -58,13 +58,13 @@ fn http_detail_level_affects_size_and_content() {
 
     // structure: standard longer than summary
     let st_sum = client
-        .post(&format!("http://127.0.0.1:{}/structure/get", port))
+        .post(format!("http://127.0.0.1:{}/structure/get", port))
         .json(&serde_json::json!({"project_path":"tests/fixtures/small_project","detail_level":"summary"}))
         .send()
         .and_then(|r| r.json::<serde_json::Value>())
         .ok();
     let st_std = client
-        .post(&format!("http://127.0.0.1:{}/structure/get", port))
+        .post(format!("http://127.0.0.1:{}/structure/get", port))
         .json(&serde_json::json!({"project_path":"tests/fixtures/small_project","detail_level":"standard"}))
         .send()
         .and_then(|r| r.json::<serde_json::Value>())
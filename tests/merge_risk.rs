@@ -0,0 +1,117 @@
+use archlens::merge_risk::MergeRiskAnalyzer;
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("git");
+    assert!(status.success(), "git {:?}", args);
+}
+
+fn write(repo: &Path, file: &str, content: &str) {
+    let path = repo.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn commit(repo: &Path, message: &str) {
+    git(repo, &["add", "-A"]);
+    git(repo, &["commit", "--quiet", "-m", message]);
+}
+
+fn lib_rs(parse: u32, render: u32) -> String {
+    format!(
+        "pub fn parse() -> u32 {{\n    let value = {};\n    value\n}}\n\n\n\n\n\n\npub fn render() -> u32 {{\n    let value = {};\n    value\n}}\n",
+        parse, render
+    )
+}
+
+fn setup(name: &str) -> std::path::PathBuf {
+    let repo = std::env::temp_dir().join(format!("archlens-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+
+    write(&repo, "src/lib.rs", &lib_rs(1, 1));
+    write(&repo, "src/other.rs", "pub fn other() -> u32 {\n    1\n}\n");
+    commit(&repo, "initial");
+
+    git(&repo, &["checkout", "--quiet", "-b", "feature-a"]);
+    write(&repo, "src/lib.rs", &lib_rs(2, 1));
+    commit(&repo, "a: parse");
+
+    git(&repo, &["checkout", "--quiet", "-b", "feature-b", "main"]);
+    write(&repo, "src/lib.rs", &lib_rs(1, 3));
+    write(&repo, "src/other.rs", "pub fn other() -> u32 {\n    3\n}\n");
+    commit(&repo, "b: render");
+
+    git(&repo, &["checkout", "--quiet", "main"]);
+    write(&repo, "src/lib.rs", &lib_rs(4, 1));
+    commit(&repo, "main: parse");
+    repo
+}
+
+#[test]
+fn parallel_edits_of_the_same_function_are_high_risk() {
+    let repo = setup("merge-risk-lib");
+    let analyzer = MergeRiskAnalyzer::new(&repo);
+    assert_eq!(analyzer.default_target(), "main");
+    let mut branches = analyzer.active_branches("main").unwrap();
+    branches.sort();
+    assert_eq!(branches, vec!["feature-a", "feature-b"]);
+
+    let report = analyzer.analyze("main", &branches, None).unwrap();
+    let a = report
+        .branches
+        .iter()
+        .find(|b| b.name == "feature-a")
+        .unwrap();
+    assert_eq!((a.ahead, a.behind, a.files_changed), (1, 1, 1));
+
+    // other.rs меняет только одна ветка — конфликта нет
+    assert!(report.files.iter().all(|f| f.path != "src/other.rs"));
+    let lib = report
+        .files
+        .iter()
+        .find(|f| f.path == "src/lib.rs")
+        .unwrap();
+    assert_eq!(lib.branch_pairs.len(), 3);
+    // parse правят и main, и feature-a; render — только feature-b
+    assert_eq!(lib.overlapping_hunks, 1);
+    assert!(lib.likelihood > 0.7, "{}", lib.likelihood);
+    assert!(lib.capsules.contains(&"parse".to_string()));
+    let _ = std::fs::remove_dir_all(&repo);
+}
+
+#[test]
+fn merge_risk_command_prints_markdown_report() {
+    let repo = setup("merge-risk-cli");
+    let output = Command::cargo_bin("archlens")
+        .unwrap()
+        .args([
+            "merge-risk",
+            repo.to_str().unwrap(),
+            "--branches",
+            "feature-a",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("## Merge-conflict risk (target main)"));
+    assert!(stdout.contains("| feature-a | 1 | 1 | 1 |"));
+    assert!(stdout.contains("`src/lib.rs`"));
+    assert!(stdout.contains("main ↔ feature-a"));
+    assert!(!stdout.contains("feature-b"));
+    let _ = std::fs::remove_dir_all(&repo);
+}
@@ -0,0 +1,67 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::metric_history::*;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_metric_history_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+const SIMPLE: &str = "pub fn price(qty: u32) -> u32 {\n    qty * 10\n}\n";
+const BRANCHY: &str = "pub fn price(qty: u32) -> u32 {\n    if qty > 100 {\n        qty * 8\n    } else if qty > 10 {\n        qty * 9\n    } else {\n        qty * 10\n    }\n}\n";
+
+#[test]
+fn nothing_is_recorded_without_a_store() {
+    let dir = temp_dir("no_store");
+    write(&dir, "src/pricing.rs", SIMPLE);
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    assert!(!MetricHistory::record(&dir, &graph).unwrap());
+    assert!(MetricHistory::load(&dir).unwrap().is_none());
+    assert!(!history_path(&dir).exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn runs_become_per_capsule_series() {
+    let dir = temp_dir("series");
+    write(&dir, ".archlens/history.jsonl", "");
+    write(&dir, "src/pricing.rs", SIMPLE);
+    let first = build_project_graph(dir.to_str().unwrap()).unwrap();
+    assert!(MetricHistory::record(&dir, &first).unwrap());
+
+    write(&dir, "src/pricing.rs", BRANCHY);
+    let second = build_project_graph(dir.to_str().unwrap()).unwrap();
+    assert!(MetricHistory::record(&dir, &second).unwrap());
+
+    let history = MetricHistory::load(&dir).unwrap().unwrap();
+    assert_eq!(history.runs.len(), 2);
+    let trends = history.trends(&second, DEFAULT_TREND_RUNS);
+    let price = trends.iter().find(|t| t.component == "price").unwrap();
+    assert_eq!(price.complexity.len(), 2);
+    assert!(price.complexity[1] > price.complexity[0], "{price:?}");
+    assert_eq!(price.direction, TrendDirection::Worsening);
+    assert_eq!(price.recorded_at.len(), 2);
+
+    // окно ограничивает число точек; одной точки для ряда мало
+    assert!(history.trends(&second, 1).is_empty());
+
+    let mut summary = serde_json::json!({"summary": {}});
+    history.attach_to_summary(&mut summary, &second);
+    let series = summary["metric_trends"].as_array().unwrap();
+    assert!(series
+        .iter()
+        .any(|t| t["component"] == "price" && t["direction"] == "worsening"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
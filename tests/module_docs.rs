@@ -0,0 +1,117 @@
+mod common;
+
+use archlens::module_docs::{ModuleDocGenerator, MODULE_DOC_MARKER};
+use archlens::types::*;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+
+fn capsule(file: &Path, name: &str, kind: CapsuleType, line: usize) -> Capsule {
+    Capsule {
+        capsule_type: kind,
+        file_path: file.to_path_buf(),
+        line_start: line,
+        line_end: line + 2,
+        size: 3,
+        complexity: 2,
+        layer: Some("Core".into()),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn write(root: &Path, file: &str, content: &str) -> PathBuf {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn module_docs_describe_purpose_api_and_dependencies() {
+    let root = std::env::temp_dir().join(format!("archlens-module-docs-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+
+    write(
+        &root,
+        "src/lib.rs",
+        "/// Loading and validating configuration\npub mod config;\npub mod storage;\n",
+    );
+    let config = write(
+        &root,
+        "src/config.rs",
+        "use crate::storage::Disk;\n\npub fn load() -> u32 {\n    Disk::read()\n}\n",
+    );
+    write(
+        &root,
+        "src/storage/mod.rs",
+        "//! Persistent storage for analysis snapshots.\n//!\n//! Backed by plain files.\n\nmod disk;\npub use disk::Disk;\n",
+    );
+    let disk = write(
+        &root,
+        "src/storage/disk.rs",
+        "pub struct Disk;\n\nimpl Disk {\n    pub fn read() -> u32 {\n        helper()\n    }\n}\n\nfn helper() -> u32 {\n    1\n}\n",
+    );
+
+    let capsules = vec![
+        capsule(&config, "load", CapsuleType::Function, 3),
+        capsule(&disk, "Disk", CapsuleType::Struct, 1),
+        capsule(&disk, "read", CapsuleType::Method, 4),
+        capsule(&disk, "helper", CapsuleType::Function, 9),
+    ];
+    let relations = vec![
+        (capsules[0].id, capsules[2].id),
+        (capsules[2].id, capsules[3].id),
+    ]
+    .into_iter()
+    .map(|(from_id, to_id)| CapsuleRelation {
+        from_id,
+        to_id,
+        relation_type: RelationType::Uses,
+        strength: 1.0,
+        description: None,
+    })
+    .collect();
+    let graph = common::graph(capsules, relations);
+
+    let generator = ModuleDocGenerator::new(&root);
+    let docs = generator.modules(&graph);
+    let names: Vec<&str> = docs.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, vec!["config", "storage"]);
+
+    let config_doc = &docs[0];
+    assert_eq!(
+        config_doc.purpose.as_deref(),
+        Some("Loading and validating configuration")
+    );
+    assert_eq!(config_doc.doc_path, root.join("src/config.MODULE.md"));
+    assert_eq!(config_doc.dependencies.get("storage"), Some(&1));
+
+    let storage = &docs[1];
+    assert_eq!(
+        storage.purpose.as_deref(),
+        Some("Persistent storage for analysis snapshots.\n\nBacked by plain files.")
+    );
+    assert_eq!(storage.doc_path, root.join("src/storage/MODULE.md"));
+    let api: Vec<&str> = storage.public_api.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(api, vec!["Disk", "read"]);
+    assert_eq!(storage.public_api[0].location, "src/storage/disk.rs:1");
+    assert_eq!(storage.dependents.get("config"), Some(&1));
+    assert_eq!(storage.metrics.components, 3);
+    assert_eq!(storage.metrics.afferent_coupling, 1);
+    assert_eq!(storage.metrics.instability, 0.0);
+
+    let markdown = storage.to_markdown();
+    assert!(markdown.starts_with(MODULE_DOC_MARKER));
+    assert!(markdown.contains("# Module `storage`"));
+    assert!(markdown.contains("| `read` | Method | `src/storage/disk.rs:4` |"));
+    assert!(markdown.contains("## Dependents\n\n- `config` (1 reference)"));
+
+    // повторный запуск ничего не переписывает
+    let written = generator.write_all(&graph).unwrap();
+    assert_eq!(written.len(), 2);
+    assert!(root.join("src/storage/MODULE.md").exists());
+    assert!(generator.write_all(&graph).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
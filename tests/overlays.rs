@@ -0,0 +1,486 @@
+mod common;
+
+use archlens::overlays::*;
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+
+fn capsule(name: &str, file: &str, lines: (usize, usize), layer: &str) -> Capsule {
+    Capsule {
+        file_path: file.into(),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        complexity: 3,
+        layer: Some(layer.into()),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn graph_of(capsules: Vec<Capsule>) -> CapsuleGraph {
+    let mut map = HashMap::new();
+    for c in capsules {
+        map.insert(c.id, c);
+    }
+    CapsuleGraph {
+        capsules: map,
+        relations: vec![],
+        layers: HashMap::new(),
+        metrics: GraphMetrics::default(),
+        created_at: Utc::now(),
+        previous_analysis: None,
+    }
+}
+
+fn find<'a>(graph: &'a CapsuleGraph, name: &str) -> &'a Capsule {
+    graph.capsules.values().find(|c| c.name == name).unwrap()
+}
+
+#[test]
+fn binary_size_is_attributed_to_capsules() {
+    let mut graph = graph_of(vec![
+        capsule("export_to_json", "/p/src/exporter.rs", (10, 40), "Core"),
+        capsule("parse_file", "/p/src/parser_ast.rs", (5, 90), "Core"),
+        capsule("run", "/p/src/cli/handlers.rs", (1, 20), "UI"),
+    ]);
+    let bloat = r#"{
+        "file-size": 4000, "text-section-size": 2000,
+        "functions": [
+            {"crate": "archlens", "name": "archlens::exporter::Exporter::export_to_json::h0123456789abcdef", "size": 300},
+            {"crate": "archlens", "name": "<archlens::parser_ast::ParserAST>::parse_file", "size": 500},
+            {"crate": "archlens", "name": "archlens::cli::handlers::print_help", "size": 100},
+            {"crate": "archlens", "name": "archlens::unknown::thing", "size": 50},
+            {"crate": "std", "name": "std::io::stdio::_print", "size": 1000}
+        ]
+    }"#;
+    let analyzer = BinarySizeAnalyzer::new();
+    let report = analyzer.parse_report(bloat).unwrap();
+    let result = analyzer.attribute(&mut graph, &report, "archlens");
+
+    assert_eq!(result.attributed_bytes, 900);
+    assert_eq!(result.unattributed_bytes, 50);
+    assert_eq!(result.by_crate[0].name, "std");
+    assert_eq!(result.by_capsule[0].name, "parse_file");
+    assert_eq!(
+        find(&graph, "export_to_json").metadata.get(BINARY_SIZE_KEY),
+        Some(&"300".to_string())
+    );
+    // print_help не является капсулой — размер относится к файлу модуля
+    assert_eq!(
+        find(&graph, "run").metadata.get(BINARY_SIZE_KEY),
+        Some(&"100".to_string())
+    );
+}
+
+#[test]
+fn build_timings_are_spread_over_crate_capsules() {
+    let root = std::env::current_dir().unwrap();
+    let mut graph = graph_of(vec![
+        capsule(
+            "big",
+            root.join("src/big.rs").to_str().unwrap(),
+            (1, 300),
+            "Core",
+        ),
+        capsule(
+            "small",
+            root.join("src/small.rs").to_str().unwrap(),
+            (1, 100),
+            "Core",
+        ),
+    ]);
+    let html = std::fs::read_to_string("tests/fixtures/overlays/cargo-timing.html").unwrap();
+    let analyzer = BuildTimingsAnalyzer::new();
+    let timings = analyzer.parse(&html).unwrap();
+    let report = analyzer.attribute(&mut graph, &timings, &root);
+
+    assert!((report.total_seconds - 36.5).abs() < 1e-9);
+    assert!((report.local_seconds - 30.0).abs() < 1e-9);
+    assert_eq!(report.by_unit[0].name, "archlens");
+    assert_eq!(report.split_candidates.len(), 1);
+    assert_eq!(report.split_candidates[0].capsules, 2);
+    assert_eq!(
+        find(&graph, "big").metadata.get(BUILD_TIME_KEY),
+        Some(&"22500".to_string())
+    );
+}
+
+#[test]
+fn webpack_profile_is_mapped_to_files() {
+    let mut graph = graph_of(vec![capsule("App", "/p/src/app.js", (1, 50), "UI")]);
+    let stats = r#"{"modules":[
+        {"name":"./src/app.js","profile":{"factory":120,"building":380}},
+        {"name":"./node_modules/react/index.js","profile":{"building":50}}
+    ]}"#;
+    let analyzer = BuildTimingsAnalyzer::new();
+    let timings = analyzer.parse(stats).unwrap();
+    let report = analyzer.attribute(&mut graph, &timings, std::path::Path::new("/p"));
+    assert_eq!(report.by_file.len(), 1);
+    assert_eq!(
+        find(&graph, "App").metadata.get(BUILD_TIME_KEY),
+        Some(&"500".to_string())
+    );
+}
+
+#[test]
+fn flaky_areas_are_reported_with_coupling() {
+    let mut graph = graph_of(vec![
+        capsule("parse_file", "/p/src/parser_ast.rs", (1, 80), "Core"),
+        capsule(
+            "OrderService",
+            "/p/src/main/java/com/acme/OrderService.java",
+            (1, 40),
+            "Business",
+        ),
+        capsule("export", "/p/src/exporter.rs", (1, 30), "Core"),
+    ]);
+    let parser_id = find(&graph, "parse_file").id;
+    let export_id = find(&graph, "export").id;
+    graph.relations.push(CapsuleRelation {
+        from_id: export_id,
+        to_id: parser_id,
+        relation_type: RelationType::Uses,
+        strength: 0.5,
+        description: None,
+    });
+
+    let run1 = r#"<testsuites><testsuite name="archlens">
+        <testcase classname="archlens" name="parser_ast::tests::parses_fn" time="0.01"/>
+        <testcase classname="com.acme.OrderServiceTest" name="createsOrder"><failure message="boom"/></testcase>
+    </testsuite></testsuites>"#;
+    let run2 = r#"<testsuites><testsuite name="archlens">
+        <testcase classname="archlens" name="parser_ast::tests::parses_fn"><flakyFailure message="timeout"/></testcase>
+        <testcase classname="com.acme.OrderServiceTest" name="createsOrder"><failure message="boom"/></testcase>
+        <testcase classname="unknown" name="mystery"/>
+    </testsuite></testsuites>"#;
+
+    let detector = FlakyBoundaryDetector::new();
+    let runs = vec![
+        detector.parse_junit(run1).unwrap(),
+        detector.parse_junit(run2).unwrap(),
+    ];
+    let report = detector.analyze(&mut graph, &runs);
+
+    assert_eq!(report.reports, 2);
+    assert_eq!(report.test_cases, 3);
+    assert_eq!(report.unmapped_tests, 1);
+    assert_eq!(
+        report.flaky_tests,
+        vec!["archlens::parser_ast::tests::parses_fn"]
+    );
+    assert_eq!(report.areas.len(), 2);
+    assert!(report.areas.iter().all(|a| a.chronic));
+    let java = &report.areas[0];
+    assert!(java.area.ends_with("OrderService.java"));
+    assert_eq!(java.failure_rate, 1.0);
+    let parser = &report.areas[1];
+    assert_eq!(parser.fan_in, 1);
+    assert_eq!(parser.flaky_tests, 1);
+    assert_eq!(
+        find(&graph, "parse_file").metadata.get(FLAKY_TESTS_KEY),
+        Some(&"1".to_string())
+    );
+}
+
+#[test]
+fn otlp_spans_separate_hot_and_cold_paths() {
+    let mut graph = graph_of(vec![
+        capsule("handle_request", "/p/src/api/handlers.rs", (1, 50), "API"),
+        capsule("save", "/p/src/data/order_repo.rs", (10, 30), "Data"),
+        capsule("record", "/p/src/audit.rs", (1, 10), "Core"),
+        capsule("purge", "/p/src/data/order_repo.rs", (40, 60), "Data"),
+    ]);
+    let id = |g: &CapsuleGraph, n: &str| find(g, n).id;
+    for (from, to) in [("handle_request", "save"), ("save", "purge")] {
+        let relation = CapsuleRelation {
+            from_id: id(&graph, from),
+            to_id: id(&graph, to),
+            relation_type: RelationType::Calls,
+            strength: 0.5,
+            description: None,
+        };
+        graph.relations.push(relation);
+    }
+    let raw = std::fs::read_to_string("tests/fixtures/overlays/otlp_traces.json").unwrap();
+    let overlay = TraceOverlay::new();
+    let spans = overlay.parse_otlp_json(&raw).unwrap();
+    assert_eq!(spans.len(), 4);
+    let report = overlay.apply(&mut graph, &spans);
+
+    assert_eq!(report.mapped_spans, 4);
+    assert_eq!(report.hot_edges.len(), 2);
+    let to_save = report.hot_edges.iter().find(|e| e.to == "save").unwrap();
+    assert!(to_save.static_relation);
+    assert!((to_save.avg_ms - 4.0).abs() < 1e-6);
+    assert_eq!(report.dynamic_only_edges, 1);
+    // save -> purge статически связаны, но purge не встречается в трассах
+    assert_eq!(report.cold_static_relations, 0);
+    assert_eq!(
+        find(&graph, "handle_request")
+            .metadata
+            .get(RUNTIME_CALLS_KEY),
+        Some(&"2".to_string())
+    );
+    assert!(!find(&graph, "purge")
+        .metadata
+        .contains_key(RUNTIME_CALLS_KEY));
+}
+
+fn coverage_graph() -> CapsuleGraph {
+    let mut parse = capsule("parse_file", "/p/src/parser_ast.rs", (1, 4), "Core");
+    parse.complexity = 14;
+    let helper = capsule("helper", "/p/src/parser_ast.rs", (10, 11), "Core");
+    graph_of(vec![parse, helper])
+}
+
+#[test]
+fn lcov_and_cobertura_yield_the_same_coverage() {
+    let overlay = CoverageOverlay::new();
+    for fixture in ["lcov.info", "cobertura.xml"] {
+        let raw = std::fs::read_to_string(format!("tests/fixtures/overlays/{}", fixture)).unwrap();
+        let files = overlay.parse(&raw).unwrap();
+        let mut graph = coverage_graph();
+        let report = overlay.apply(&mut graph, &files);
+
+        assert_eq!(report.capsules_with_coverage, 2, "{}", fixture);
+        assert_eq!(
+            find(&graph, "parse_file").metadata.get(LINE_COVERAGE_KEY),
+            Some(&"0.250".to_string())
+        );
+        assert_eq!(
+            find(&graph, "parse_file").metadata.get(BRANCH_COVERAGE_KEY),
+            Some(&"0.500".to_string())
+        );
+        assert_eq!(report.quadrants.high_risk.len(), 1);
+        assert_eq!(report.quadrants.high_risk[0].component, "parse_file");
+        assert_eq!(report.quadrants.healthy, 1);
+    }
+}
+
+#[test]
+fn coverage_risk_appears_in_exports() {
+    let overlay = CoverageOverlay::new();
+    let raw = std::fs::read_to_string("tests/fixtures/overlays/lcov.info").unwrap();
+    let mut graph = coverage_graph();
+    overlay.apply(&mut graph, &overlay.parse(&raw).unwrap());
+
+    let exporter = archlens::exporter::Exporter::new();
+    let compact = exporter.export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Coverage Risk (complexity × coverage)"));
+    assert!(compact.contains("- parse_file : complexity 14, coverage 25%"));
+    let json = exporter.export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(
+        json["coverage_risk"]["high_risk"][0]["component"],
+        "parse_file"
+    );
+}
+
+fn profile_graph() -> CapsuleGraph {
+    let mut parse = capsule("parse_file", "/p/src/parser_ast.rs", (1, 40), "Core");
+    parse.complexity = 14;
+    let render = capsule("render", "/p/src/exporter.rs", (1, 20), "Core");
+    // одноимённая капсула не должна забирать `alloc::vec::Vec::push`
+    let push = capsule("push", "/p/src/queue.rs", (1, 5), "Core");
+    graph_of(vec![parse, render, push])
+}
+
+fn cpu_share(graph: &CapsuleGraph, name: &str, key: &str) -> f64 {
+    find(graph, name).metadata[key].parse().unwrap()
+}
+
+#[test]
+fn folded_stacks_attribute_cpu_to_nearest_project_frame() {
+    let overlay = ProfileOverlay::new();
+    let raw = std::fs::read("tests/fixtures/overlays/stacks.folded").unwrap();
+    let samples = overlay.parse(&raw).unwrap();
+    let mut graph = profile_graph();
+    let report = overlay.apply(&mut graph, &samples);
+
+    assert_eq!(report.total_weight, 100);
+    assert_eq!(report.mapped_weight, 90);
+    assert_eq!(cpu_share(&graph, "parse_file", CPU_SELF_SHARE_KEY), 0.5);
+    assert_eq!(cpu_share(&graph, "render", CPU_TOTAL_SHARE_KEY), 0.4);
+    assert!(find(&graph, "push").metadata.is_empty());
+    assert_eq!(report.hotspots[0].component, "parse_file");
+    assert!(report.hotspots[0].architectural);
+    assert!(!report.hotspots[1].architectural);
+}
+
+#[test]
+fn perf_script_counts_samples() {
+    let overlay = ProfileOverlay::new();
+    let raw = std::fs::read("tests/fixtures/overlays/perf_script.txt").unwrap();
+    let samples = overlay.parse(&raw).unwrap();
+    assert_eq!(samples.len(), 4);
+    let mut graph = profile_graph();
+    let report = overlay.apply(&mut graph, &samples);
+
+    assert_eq!(report.mapped_weight, 3);
+    assert_eq!(cpu_share(&graph, "parse_file", CPU_SELF_SHARE_KEY), 0.5);
+    assert_eq!(cpu_share(&graph, "render", CPU_SELF_SHARE_KEY), 0.25);
+}
+
+#[test]
+fn pprof_uses_cpu_time_and_source_locations() {
+    let overlay = ProfileOverlay::new();
+    let raw = std::fs::read("tests/fixtures/overlays/cpu.pb.gz").unwrap();
+    let samples = overlay.parse(&raw).unwrap();
+    let parse = capsule("parseFile", "/p/src/parser_ast.go", (1, 8), "Core");
+    let render = capsule("render", "/p/src/exporter.go", (5, 20), "Core");
+    let mut graph = graph_of(vec![parse, render]);
+    let report = overlay.apply(&mut graph, &samples);
+
+    assert_eq!(report.total_weight, 100_000_000);
+    assert_eq!(cpu_share(&graph, "parseFile", CPU_SELF_SHARE_KEY), 0.5);
+    assert_eq!(cpu_share(&graph, "render", CPU_SELF_SHARE_KEY), 0.4);
+
+    let compact = archlens::exporter::Exporter::new()
+        .export_to_ai_compact(&graph)
+        .unwrap();
+    assert!(compact.contains("## CPU Hotspots (sampled)"));
+    assert!(compact.contains("- parseFile : self 50.0%, total 50.0%, complexity 3"));
+}
+
+/// Проект с единственной функцией `classify` сложностью выше порога риска
+fn overlay_project(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("archlens_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    let mut source = String::from("pub fn classify(v: u32) -> u32 {\n");
+    for n in 0..12 {
+        source.push_str(&format!(
+            "    if v == {n} {{\n        return {n};\n    }}\n"
+        ));
+    }
+    source.push_str("    0\n}\n");
+    std::fs::write(dir.join("src/classify.rs"), source).unwrap();
+    dir
+}
+
+fn archlens(args: &[&str]) -> String {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_archlens"))
+        .args(args)
+        .env("ARCHLENS_GIT_METADATA", "0")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn coverage_flag_reaches_exported_reports() {
+    let dir = overlay_project("coverage_export");
+    // `classify` (строки 1–39) исполнена лишь на входе
+    let mut lcov = String::from("SF:src/classify.rs\n");
+    for line in 1..=39 {
+        lcov.push_str(&format!("DA:{},{}\n", line, u32::from(line == 1)));
+    }
+    lcov.push_str("end_of_record\n");
+    let lcov_path = dir.join("lcov.info");
+    std::fs::write(&lcov_path, lcov).unwrap();
+    let project = dir.to_str().unwrap();
+    let lcov_path = lcov_path.to_str().unwrap();
+
+    let compact = archlens(&["export", project, "ai_compact", "--coverage", lcov_path]);
+    assert!(compact.contains("## Coverage Risk (complexity × coverage)"));
+    assert!(compact.contains("- classify : complexity"));
+
+    let pack = dir.join("pack");
+    archlens(&[
+        "export",
+        project,
+        "prompt-pack",
+        "--output",
+        pack.to_str().unwrap(),
+        "--coverage",
+        lcov_path,
+    ]);
+    let summary: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(pack.join("summary.json")).unwrap()).unwrap();
+    assert_eq!(
+        summary["coverage_risk"]["high_risk"][0]["component"],
+        "classify"
+    );
+
+    // оценка качества учитывает измеренное покрытие, а не эвристику
+    let classify = |lcov: &str| {
+        let analysis: serde_json::Value = serde_json::from_str(&archlens(&[
+            "analyze",
+            project,
+            "--deep",
+            "--coverage",
+            lcov,
+        ]))
+        .unwrap();
+        let capsules = analysis["graph"]["capsules"].as_object().unwrap();
+        let capsule = capsules
+            .values()
+            .find(|c| c["name"] == "classify")
+            .unwrap()
+            .clone();
+        (
+            capsule["metadata"][LINE_COVERAGE_KEY].clone(),
+            capsule["quality_score"].as_f64().unwrap(),
+        )
+    };
+    let full_path = dir.join("full.info");
+    let mut full = String::from("SF:src/classify.rs\n");
+    for line in 1..=39 {
+        full.push_str(&format!("DA:{},1\n", line));
+    }
+    full.push_str("end_of_record\n");
+    std::fs::write(&full_path, full).unwrap();
+    let (sparse_coverage, sparse_score) = classify(lcov_path);
+    let (full_coverage, full_score) = classify(full_path.to_str().unwrap());
+    assert_eq!(sparse_coverage, "0.026");
+    assert_eq!(full_coverage, "1.000");
+    assert!(full_score > sparse_score, "{full_score} <= {sparse_score}");
+    // эвристика дала бы сложной функции 0.5
+    assert_ne!(sparse_score, 0.5);
+
+    let without = archlens(&["export", project, "ai_compact"]);
+    assert!(!without.contains("## Coverage Risk"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn profile_flag_reaches_exported_reports() {
+    let dir = overlay_project("profile_export");
+    let stacks = dir.join("stacks.folded");
+    std::fs::write(
+        &stacks,
+        "main;demo::classify::classify 3\nmain;std::io::Write::write_all 1\n",
+    )
+    .unwrap();
+    let project = dir.to_str().unwrap();
+    let stacks = stacks.to_str().unwrap();
+
+    let compact = archlens(&["export", project, "ai_compact", "--profile", stacks]);
+    assert!(compact.contains("## CPU Hotspots (sampled)"));
+    assert!(compact.contains("- classify : self 75.0%, total 75.0%"));
+
+    let pack = dir.join("pack");
+    archlens(&[
+        "export",
+        project,
+        "prompt-pack",
+        "--output",
+        pack.to_str().unwrap(),
+        "--profile",
+        stacks,
+    ]);
+    let summary: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(pack.join("summary.json")).unwrap()).unwrap();
+    assert_eq!(summary["cpu_hotspots"][0]["component"], "classify");
+    assert_eq!(summary["cpu_hotspots"][0]["architectural"], true);
+
+    let without = archlens(&["export", project, "ai_compact"]);
+    assert!(!without.contains("## CPU Hotspots"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
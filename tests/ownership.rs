@@ -0,0 +1,120 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::ownership::*;
+use archlens::types::*;
+use std::path::Path;
+use std::process::Command;
+use uuid::Uuid;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn commit_as(dir: &Path, email: &str) {
+    git(dir, &["add", "."]);
+    git(
+        dir,
+        &[
+            "-c",
+            "user.name=dev",
+            "-c",
+            &format!("user.email={email}"),
+            "commit",
+            "--quiet",
+            "-m",
+            "change",
+        ],
+    );
+}
+
+fn capsule(name: &str, file: &Path, lines: (usize, usize), layer: &str) -> Capsule {
+    Capsule {
+        file_path: file.to_path_buf(),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        layer: Some(layer.into()),
+        ..common::capsule(name)
+    }
+}
+
+#[test]
+fn porcelain_blame_is_parsed() {
+    let porcelain = "1111111111111111111111111111111111111111 1 1 2\n\
+                     author Ann\n\
+                     author-mail <Ann@example.com>\n\
+                     filename a.rs\n\
+                     \tfn a() {}\n\
+                     1111111111111111111111111111111111111111 2 2\n\
+                     author Ann\n\
+                     author-mail <ann@example.com>\n\
+                     filename a.rs\n\
+                     \tfn b() {}\n\
+                     2222222222222222222222222222222222222222 3 3 1\n\
+                     author Bob\n\
+                     author-mail <bob@example.com>\n\
+                     filename a.rs\n\
+                     \tfn c() {}\n";
+    assert_eq!(
+        parse_blame(porcelain),
+        ["ann@example.com", "ann@example.com", "bob@example.com"]
+    );
+}
+
+#[test]
+fn single_owner_modules_are_flagged() {
+    let repo = std::env::temp_dir().join(format!("archlens-owners-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(repo.join("core")).unwrap();
+    std::fs::create_dir_all(repo.join("api")).unwrap();
+    let core = repo.join("core/store.rs");
+    let api = repo.join("api/routes.rs");
+    git(&repo, &["init", "--quiet"]);
+    std::fs::write(&core, "a\nb\nc\nd\ne\n").unwrap();
+    std::fs::write(&api, "a\nb\nc\nd\n").unwrap();
+    commit_as(&repo, "ann@example.com");
+    std::fs::write(&api, "a\nb\nC\nD\n").unwrap();
+    commit_as(&repo, "bob@example.com");
+
+    let mut capsules = vec![
+        capsule("Store", &core, (1, 5), "Core"),
+        capsule("routes", &api, (1, 4), "Api"),
+    ];
+    assert_eq!(OwnershipAnalyzer::new(&repo).annotate(&mut capsules), 2);
+    assert_eq!(capsules[0].metadata[OWNER_KEY], "ann@example.com");
+    assert_eq!(capsules[0].metadata[OWNER_SHARE_KEY], "1.00");
+    assert_eq!(
+        capsules[1].metadata[OWNERSHIP_KEY],
+        "ann@example.com=2;bob@example.com=2"
+    );
+
+    let graph = common::graph(capsules.to_vec(), vec![]);
+    let report = OwnershipReport::from_graph(&graph).unwrap();
+    let core_module = &report.modules[0];
+    assert_eq!(core_module.name, "core");
+    assert!(core_module.single_owner);
+    assert_eq!(core_module.bus_factor, 1);
+    let api_module = &report.modules[1];
+    assert!(!api_module.single_owner);
+    assert_eq!((api_module.authors, api_module.bus_factor), (2, 2));
+    assert_eq!(report.single_owner_modules().count(), 1);
+    assert_eq!(report.layers.len(), 2);
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Bus Factor (Ownership)"));
+    assert!(compact
+        .contains("- core : bus factor 1, ann@example.com owns 100% of 5 lines [single owner]"));
+    let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(summary["bus_factor"]["single_owner_modules"], 1);
+    assert_eq!(
+        summary["bus_factor"]["modules"][0]["top_author"],
+        "ann@example.com"
+    );
+    std::fs::remove_dir_all(&repo).unwrap();
+}
@@ -0,0 +1,103 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::packages::*;
+use archlens::types::*;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("archlens_packages_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn declarations_and_imports_are_read_per_language() {
+    let java = "package com.shop.order;\n\nimport com.shop.billing.Invoice;\nimport static com.shop.util.Money.*;\n";
+    assert_eq!(
+        declared_package(Path::new("Order.java"), java).as_deref(),
+        Some("com.shop.order")
+    );
+    assert_eq!(
+        imported_paths(Path::new("Order.java"), java),
+        vec!["com.shop.billing.Invoice", "com.shop.util.Money"]
+    );
+
+    let csharp =
+        "using System;\nusing Shop.Billing;\n\nnamespace Shop.Ordering;\n\npublic class Order {}\n";
+    assert_eq!(
+        declared_package(Path::new("Order.cs"), csharp).as_deref(),
+        Some("Shop.Ordering")
+    );
+    assert_eq!(
+        imported_paths(Path::new("Order.cs"), csharp),
+        vec!["System", "Shop.Billing"]
+    );
+    assert_eq!(declared_package(Path::new("order.rs"), java), None);
+}
+
+#[test]
+fn java_files_are_grouped_into_package_capsules() {
+    let dir = temp_dir("java");
+    write(
+        &dir,
+        "src/main/java/com/shop/order/Order.java",
+        "package com.shop.order;\n\nimport com.shop.billing.Invoice;\n\npublic class Order {\n    public Invoice bill() {\n        return new Invoice();\n    }\n}\n",
+    );
+    write(
+        &dir,
+        "src/main/java/com/shop/order/OrderLine.java",
+        "package com.shop.order;\n\npublic class OrderLine {\n    public int qty() {\n        return 2;\n    }\n}\n",
+    );
+    write(
+        &dir,
+        "src/main/java/com/shop/billing/Invoice.java",
+        "package com.shop.billing;\n\npublic class Invoice {\n    public int total() {\n        return 1;\n    }\n}\n",
+    );
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+
+    let packages: Vec<&Capsule> = graph
+        .capsules
+        .values()
+        .filter(|c| c.metadata.get(PACKAGE_KEY).map(String::as_str) == Some("java"))
+        .collect();
+    assert_eq!(packages.len(), 2, "{packages:?}");
+    let order = packages
+        .iter()
+        .find(|c| c.name == "com.shop.order")
+        .unwrap();
+    let billing = packages
+        .iter()
+        .find(|c| c.name == "com.shop.billing")
+        .unwrap();
+    assert_eq!(order.metadata["package_files"], "2");
+    assert!(order.tags.contains(&PACKAGE_TAG.to_string()));
+    assert!(order.file_path.ends_with("com/shop/order"));
+
+    let contained: Vec<&str> = graph
+        .relations
+        .iter()
+        .filter(|r| r.from_id == order.id && r.relation_type == RelationType::Contains)
+        .filter_map(|r| graph.capsules.get(&r.to_id))
+        .map(|c| c.name.as_str())
+        .collect();
+    assert!(contained.contains(&"Order"), "{contained:?}");
+    assert!(contained.contains(&"OrderLine"), "{contained:?}");
+    // методы вложены в классы, а не в пакет
+    assert!(!contained.contains(&"bill"), "{contained:?}");
+
+    assert!(graph.relations.iter().any(|r| r.from_id == order.id
+        && r.to_id == billing.id
+        && r.relation_type == RelationType::Imports));
+    assert!(!graph
+        .relations
+        .iter()
+        .any(|r| r.from_id == billing.id && r.to_id == order.id));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,82 @@
+use archlens::capsule_constructor::CapsuleConstructor;
+use archlens::cli::handlers::build_project_graph;
+use archlens::exporter::Exporter;
+use archlens::file_scanner::FileScanner;
+use archlens::panic_isolation::*;
+use archlens::parser_ast::ParserAST;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_panic_isolation_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn panics_become_errors_with_their_message() {
+    assert_eq!(isolate(|| 2 + 2), Ok(4));
+    assert_eq!(
+        isolate(|| -> u32 { panic!("static message") }),
+        Err("static message".to_string())
+    );
+    let file = "huge.rs";
+    assert_eq!(
+        isolate(|| -> u32 { panic!("stack overflow in {file}") }),
+        Err("stack overflow in huge.rs".to_string())
+    );
+}
+
+#[test]
+fn failed_files_are_listed_in_the_coverage_report() {
+    let dir = temp_dir("project");
+    let path = dir.join("lib.rs");
+    std::fs::write(&path, "pub fn ok() -> u32 {\n    1\n}\n").unwrap();
+    let file = FileScanner::new(vec!["**/*.rs".into()], Vec::new(), None)
+        .unwrap()
+        .scan_file(&path, &dir)
+        .unwrap();
+
+    let mut coverage = AnalysisCoverage::default();
+    let capsules = parse_file_isolated(
+        &mut ParserAST::new().unwrap(),
+        &CapsuleConstructor::new(),
+        &file,
+        &mut coverage,
+    )
+    .unwrap();
+    assert!(capsules.iter().any(|c| c.name == "ok"));
+    assert_eq!(coverage.analyzed_files, 1);
+    assert!(coverage.failed.is_empty());
+
+    coverage.record_failure(
+        dir.join("broken.rs"),
+        PARSE_STAGE,
+        "attempt to subtract with overflow".to_string(),
+    );
+    assert_eq!(coverage.failed[0].stage, "parse");
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let plain = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(!plain.contains("## Analysis Coverage"));
+    assert!(!Exporter::new()
+        .export_to_json(&graph)
+        .unwrap()
+        .contains("analysis_coverage"));
+    let exporter = Exporter::new().with_coverage(coverage);
+    let json: serde_json::Value =
+        serde_json::from_str(&exporter.export_to_json(&graph).unwrap()).unwrap();
+    assert_eq!(json["analysis_coverage"]["failed"][0]["stage"], "parse");
+    let summary = exporter.export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(summary["analysis_coverage"]["analyzed_files"], 1);
+    let compact = exporter.export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains(&format!(
+        "## Analysis Coverage\n- Analyzed files: 1\n- Failed files: 1\n  - {} (parse): attempt to subtract with overflow\n",
+        dir.join("broken.rs").display()
+    )));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,112 @@
+use archlens::parser_ast::{ASTElement, ASTElementType, ParserAST};
+use archlens::parser_cache::{CacheLimits, ElementCache};
+use archlens::types::FileType;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn element(name: &str, content: &str) -> ASTElement {
+    ASTElement {
+        id: uuid::Uuid::new_v4(),
+        name: name.into(),
+        element_type: ASTElementType::Function,
+        content: content.into(),
+        start_line: 1,
+        end_line: 1,
+        start_column: 0,
+        end_column: content.len(),
+        complexity: 1,
+        visibility: "public".into(),
+        parameters: vec![],
+        return_type: None,
+        children: vec![],
+        parent_id: None,
+        metadata: HashMap::new(),
+    }
+}
+
+fn limits(max_entries: usize, max_bytes: usize) -> CacheLimits {
+    CacheLimits {
+        max_entries,
+        max_bytes,
+    }
+}
+
+#[test]
+fn least_recently_used_files_are_evicted_first() {
+    let mut cache = ElementCache::new(limits(2, usize::MAX));
+    let (a, b, c) = (Path::new("a.rs"), Path::new("b.rs"), Path::new("c.rs"));
+    assert!(cache
+        .insert(a, "1".into(), vec![element("a", "fn a() {}")])
+        .is_empty());
+    assert!(cache
+        .insert(b, "1".into(), vec![element("b", "fn b() {}")])
+        .is_empty());
+    assert!(cache.get(a, "1").is_some());
+    // другой отпечаток — промах, запись остаётся до замены
+    assert!(cache.get(b, "2").is_none());
+
+    let evicted = cache.insert(c, "1".into(), vec![element("c", "fn c() {}")]);
+    assert_eq!(evicted, vec![PathBuf::from("b.rs")]);
+    assert!(cache.get(b, "1").is_none());
+    assert_eq!(cache.get(a, "1").unwrap()[0].name, "a");
+
+    let stats = cache.stats();
+    assert_eq!(
+        (stats.entries, stats.hits, stats.misses, stats.evictions),
+        (2, 2, 2, 1)
+    );
+    assert!((stats.hit_rate() - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn byte_limit_bounds_the_cache() {
+    let body = "x".repeat(1_000);
+    let mut cache = ElementCache::new(limits(100, 3_000));
+    for name in ["a.rs", "b.rs", "c.rs"] {
+        cache.insert(Path::new(name), "1".into(), vec![element("f", &body)]);
+    }
+    let stats = cache.stats();
+    assert_eq!(stats.entries, 2);
+    assert!(stats.bytes <= 3_000);
+    assert!(cache.get(Path::new("a.rs"), "1").is_none());
+
+    // запись больше лимита не кешируется и вытесняет свою прежнюю версию
+    cache.insert(
+        Path::new("c.rs"),
+        "2".into(),
+        vec![element("f", &"y".repeat(5_000))],
+    );
+    assert_eq!(cache.stats().entries, 1);
+    assert!(cache.get(Path::new("c.rs"), "1").is_none());
+
+    assert_eq!(cache.set_limits(limits(0, 3_000)).len(), 1);
+    assert_eq!((cache.stats().entries, cache.stats().bytes), (0, 0));
+}
+
+#[test]
+fn parser_exposes_cache_stats() {
+    let mut parser = ParserAST::new()
+        .unwrap()
+        .with_cache_limits(limits(2, usize::MAX));
+    for (i, name) in ["a.rs", "b.rs", "c.rs"].iter().enumerate() {
+        let content = format!("pub fn f{i}() {{}}\n");
+        parser
+            .parse_file(Path::new(name), &content, &FileType::Rust)
+            .unwrap();
+    }
+    let elements = parser
+        .parse_file(Path::new("c.rs"), "pub fn f2() {}\n", &FileType::Rust)
+        .unwrap();
+    assert_eq!(elements[0].name, "f2");
+
+    let stats = parser.cache_stats();
+    assert_eq!(stats.limits, limits(2, usize::MAX));
+    assert_eq!(
+        (stats.entries, stats.hits, stats.misses, stats.evictions),
+        (2, 1, 3, 1)
+    );
+    assert!(stats.bytes > 0);
+
+    parser.clear_cache();
+    assert_eq!(parser.cache_stats().entries, 0);
+}
@@ -0,0 +1,114 @@
+use archlens::parser_ast::{ASTElement, ASTElementType, LanguageParser, ParserAST};
+use archlens::types::{FileType, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Игрушечный DSL: строки `step <name>` становятся функциями
+#[derive(Debug)]
+struct FlowParser;
+
+impl LanguageParser for FlowParser {
+    fn name(&self) -> &str {
+        "flow"
+    }
+
+    fn supports(&self, _file_path: &Path, file_type: &FileType) -> bool {
+        *file_type == FileType::Other("flow".to_string())
+    }
+
+    fn parse_file(
+        &self,
+        _file_path: &Path,
+        content: &str,
+        _file_type: &FileType,
+    ) -> Result<Vec<ASTElement>> {
+        Ok(content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let name = line.trim().strip_prefix("step ")?;
+                Some(element(name, i + 1, line))
+            })
+            .collect())
+    }
+}
+
+/// Перехватывает сгенерированные Rust-файлы, остальные оставляет встроенному парсеру
+#[derive(Debug)]
+struct GeneratedRust;
+
+impl LanguageParser for GeneratedRust {
+    fn name(&self) -> &str {
+        "generated-rust"
+    }
+
+    fn supports(&self, file_path: &Path, file_type: &FileType) -> bool {
+        *file_type == FileType::Rust && file_path.to_string_lossy().contains("generated")
+    }
+
+    fn parse_file(&self, _: &Path, _: &str, _: &FileType) -> Result<Vec<ASTElement>> {
+        Ok(vec![element("generated", 1, "")])
+    }
+}
+
+fn element(name: &str, line: usize, content: &str) -> ASTElement {
+    ASTElement {
+        id: uuid::Uuid::new_v4(),
+        name: name.to_string(),
+        element_type: ASTElementType::Function,
+        content: content.to_string(),
+        start_line: line,
+        end_line: line,
+        start_column: 0,
+        end_column: content.len(),
+        complexity: 1,
+        visibility: "public".to_string(),
+        parameters: vec![],
+        return_type: None,
+        children: vec![],
+        parent_id: None,
+        metadata: HashMap::new(),
+    }
+}
+
+#[test]
+fn registered_parser_handles_custom_language() {
+    let flow = FileType::Other("flow".to_string());
+    let path = Path::new("pipeline.flow");
+    let source = "step fetch\n# comment\nstep transform\nstep store\n";
+
+    let mut parser = ParserAST::new().unwrap();
+    assert!(parser.parse_file(path, source, &flow).unwrap().is_empty());
+
+    // регистрация сбрасывает кеш: пустой результат выше не должен залипнуть
+    parser.register_parser(Box::new(FlowParser));
+    let elements = parser.parse_file(path, source, &flow).unwrap();
+    let names: Vec<_> = elements.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["fetch", "transform", "store"]);
+    assert_eq!(elements[1].start_line, 3);
+}
+
+#[test]
+fn plugins_take_precedence_over_builtin_languages() {
+    let mut parser = ParserAST::new()
+        .unwrap()
+        .with_parser(FlowParser)
+        .with_parser(GeneratedRust);
+    let names = parser.parser_names();
+    assert_eq!(&names[..3], ["generated-rust", "flow", "rust"]);
+    assert!(names.contains(&"python") && names.contains(&"ruby"));
+
+    let source = "pub fn handwritten() {}\n";
+    let generated = parser
+        .parse_file(Path::new("src/generated/api.rs"), source, &FileType::Rust)
+        .unwrap();
+    assert_eq!(generated.len(), 1);
+    assert_eq!(generated[0].name, "generated");
+
+    let regular = parser
+        .parse_file(Path::new("src/lib.rs"), source, &FileType::Rust)
+        .unwrap();
+    assert!(regular
+        .iter()
+        .any(|e| e.name == "handwritten" && e.element_type == ASTElementType::Function));
+}
@@ -0,0 +1,110 @@
+use archlens::types::FileType;
+use archlens::validation::{PatternConfig, PatternDetector};
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_pattern_criteria_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn patterns(detector: &PatternDetector, content: &str, file_type: FileType) -> Vec<String> {
+    detector
+        .detect_in_source(content, &file_type)
+        .into_iter()
+        .map(|found| found.pattern_name)
+        .collect()
+}
+
+#[test]
+fn create_and_new_alone_are_not_a_factory() {
+    let detector = PatternDetector::new();
+    let incidental = "pub fn create_user(name: &str) -> User {\n    User::new(name)\n}\n";
+    assert!(!patterns(&detector, incidental, FileType::Rust).contains(&"Factory".to_string()));
+    // несколько создателей, но конкретных типов — тоже не фабрика
+    let concrete = "fn create_circle() -> Circle { Circle::new() }\nfn create_square() -> Square { Square::new() }\n";
+    assert!(!patterns(&detector, concrete, FileType::Rust).contains(&"Factory".to_string()));
+}
+
+#[test]
+fn creators_returning_interfaces_are_a_factory() {
+    let detector = PatternDetector::new();
+    let rust = "pub trait Shape {}\n\npub fn create_circle(r: f32) -> Box<dyn Shape> {\n    Box::new(Circle { r })\n}\n\npub fn create_square<T: Into<f32>>(side: T) -> Box<dyn Shape>\nwhere\n    T: Copy,\n{\n    Box::new(Square { side: side.into() })\n}\n";
+    let found = detector.detect_in_source(rust, &FileType::Rust);
+    let factory = found.iter().find(|p| p.pattern_name == "Factory").unwrap();
+    assert_eq!(factory.confidence, 1.0);
+    assert_eq!(factory.evidence.len(), 2);
+
+    let java = "interface Shape {}\n\nclass ShapeFactory {\n    public static Shape createCircle(double r) {\n        return new Circle(r);\n    }\n    public Shape makeSquare(double side) {\n        return new Square(side);\n    }\n}\n";
+    assert!(patterns(&detector, java, FileType::Java).contains(&"Factory".to_string()));
+    let python = "class Shape(ABC):\n    pass\n\ndef create_circle(r) -> Shape:\n    return Circle(r)\n\ndef create_square(side) -> Shape:\n    return Square(side)\n";
+    assert!(patterns(&detector, python, FileType::Python).contains(&"Factory".to_string()));
+}
+
+#[test]
+fn singletons_and_repositories_need_structure() {
+    let detector = PatternDetector::new();
+    let singleton = "public class Config {\n    private static Config instance;\n    public static Config getInstance() {\n        return instance;\n    }\n}\n";
+    assert!(patterns(&detector, singleton, FileType::Java).contains(&"Singleton".to_string()));
+    let mention = "// getInstance is deprecated, call create() instead\nfn save() {}\n";
+    assert!(patterns(&detector, mention, FileType::Rust).is_empty());
+
+    let repository = "pub struct UserRepository;\n\nimpl UserRepository {\n    pub fn find_by_id(&self, id: u64) -> Option<User> { None }\n    pub fn save(&self, user: &User) {}\n}\n";
+    assert!(patterns(&detector, repository, FileType::Rust).contains(&"Repository".to_string()));
+}
+
+#[test]
+fn project_config_overrides_pattern_criteria() {
+    let dir = temp_dir("override");
+    let rust = "pub trait Shape {}\npub fn create_circle() -> Box<dyn Shape> { todo!() }\n";
+    assert!(
+        !patterns(&PatternDetector::new(), rust, FileType::Rust).contains(&"Factory".to_string())
+    );
+
+    std::fs::create_dir_all(dir.join(".archlens")).unwrap();
+    std::fs::write(
+        dir.join(".archlens/patterns.json"),
+        r#"{"patterns": [
+            {"pattern_name": "Factory", "confidence_threshold": 1.0, "detection_criteria": [
+                {"name": "interface creator", "weight": 1.0, "matcher": "interface_creators >= 1"}]},
+            {"pattern_name": "Service", "enabled": false, "confidence_threshold": 1.0,
+             "detection_criteria": [{"name": "s", "weight": 1.0, "matcher": "service_types >= 1"}]},
+            {"pattern_name": "Plugin", "confidence_threshold": 0.5, "detection_criteria": [
+                {"name": "interfaces", "weight": 1.0, "matcher": "interface_types >= 1"}]}
+        ]}"#,
+    )
+    .unwrap();
+    let tuned = PatternDetector::for_project(&dir).unwrap();
+    let found = patterns(&tuned, rust, FileType::Rust);
+    assert!(found.contains(&"Factory".to_string()));
+    assert!(found.contains(&"Plugin".to_string()));
+    assert!(
+        !patterns(&tuned, "struct UserService;\n", FileType::Rust).contains(&"Service".to_string())
+    );
+    // встроенные паттерны, не упомянутые в проекте, остаются
+    assert!(patterns(&tuned, "struct UserController;\n", FileType::Rust)
+        .contains(&"Controller".to_string()));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn invalid_criteria_are_reported() {
+    for json in [
+        r#"{"patterns": [{"pattern_name": "X", "confidence_threshold": 1.0, "detection_criteria": []}]}"#,
+        r#"{"patterns": [{"pattern_name": "X", "confidence_threshold": 1.0, "detection_criteria": [
+            {"name": "n", "weight": 1.0, "matcher": "unknown_metric > 1"}]}]}"#,
+        r#"{"patterns": [{"pattern_name": "X", "scope": "capsule", "confidence_threshold": 1.0,
+            "detection_criteria": [{"name": "n", "weight": 1.0, "matcher": "creator_methods >= 2"}]}]}"#,
+        r#"{"patterns": [{"pattern_name": "X", "confidence_threshold": 1.0, "detection_criteria": [
+            {"name": "n", "weight": 1.0, "matcher": "creator_methods twice"}]}]}"#,
+    ] {
+        let config = PatternConfig::from_json(json).unwrap();
+        assert!(PatternDetector::from_config(&config).is_err(), "{json}");
+    }
+    assert!(PatternConfig::from_json(r#"{"patterns": [{"pattern_name": "X"}]}"#).is_err());
+}
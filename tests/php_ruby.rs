@@ -0,0 +1,185 @@
+use archlens::enrichment::code_smells::{CodeSmellDetector, CodeSmellType};
+use archlens::file_scanner::FileScanner;
+use archlens::parser_ast::{ASTElementType, ParserAST};
+use archlens::types::FileType;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("archlens_php_ruby_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const USER_CONTROLLER_PHP: &str = r#"<?php
+namespace App\Http\Controllers;
+
+use App\Models\User;
+require_once 'helpers.php';
+
+interface Renders {}
+
+final class UserController extends Controller implements Renders
+{
+    const PER_PAGE = 20;
+
+    public function show(int $id)
+    {
+        $user = @User::find($id);
+        try {
+            return view('user', ['user' => $user]);
+        } catch (Exception $e) {}
+    }
+
+    private function build($a, $b, $c, $d, $e, $f)
+    {
+        return [$a, $b, $c, $d, $e, $f];
+    }
+}
+"#;
+
+const ORDERS_RB: &str = r#"require "json"
+require_relative "models/order"
+
+module Shop
+  class OrderService < BaseService
+    MAX_ITEMS = 50
+
+    def place(order)
+      validate(order)
+      total = 0
+      order.items.each do |item|
+        total += item.price
+      end
+      total -= discount(order)
+      total += shipping(order)
+      total += tax(total)
+      log(total)
+      notify(order)
+      persist(order, total)
+      total
+    rescue Exception => e
+      raise e
+    end
+
+    def self.build(a, b, c, d, e, f)
+      new
+    end
+
+    def ready? = true
+  end
+end
+"#;
+
+fn parse(path: &str, content: &str, file_type: FileType) -> Vec<(String, ASTElementType)> {
+    ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new(path), content, &file_type)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.name, e.element_type))
+        .collect()
+}
+
+#[test]
+fn php_elements_from_regex_patterns() {
+    let elements = parse("app/UserController.php", USER_CONTROLLER_PHP, FileType::Php);
+    for expected in [
+        ("App\\Http\\Controllers", ASTElementType::Module),
+        ("App\\Models\\User", ASTElementType::Import),
+        ("helpers.php", ASTElementType::Import),
+        ("Renders", ASTElementType::Interface),
+        ("UserController", ASTElementType::Class),
+        ("show", ASTElementType::Method),
+        ("build", ASTElementType::Method),
+    ] {
+        assert!(
+            elements.contains(&(expected.0.to_string(), expected.1.clone())),
+            "{expected:?} in {elements:?}"
+        );
+    }
+}
+
+#[test]
+fn ruby_elements_from_regex_patterns() {
+    let elements = parse("lib/shop/order_service.rb", ORDERS_RB, FileType::Ruby);
+    for expected in [
+        ("json", ASTElementType::Import),
+        ("models/order", ASTElementType::Import),
+        ("Shop", ASTElementType::Module),
+        ("OrderService", ASTElementType::Class),
+        ("place", ASTElementType::Method),
+        ("build", ASTElementType::Method),
+        ("ready?", ASTElementType::Method),
+    ] {
+        assert!(
+            elements.contains(&(expected.0.to_string(), expected.1.clone())),
+            "{expected:?} in {elements:?}"
+        );
+    }
+}
+
+#[test]
+fn language_specific_smell_thresholds() {
+    let detector = CodeSmellDetector::new();
+    let kinds = |content: &str, file_type: FileType| -> Vec<(CodeSmellType, Option<String>)> {
+        detector
+            .detect_code_smells(content, file_type)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.smell_type, s.location))
+            .collect()
+    };
+
+    let php = kinds(USER_CONTROLLER_PHP, FileType::Php);
+    let build = Some("Функция: build".to_string());
+    assert!(php.contains(&(CodeSmellType::LongParameterList, build)));
+    let empty_handling = php
+        .iter()
+        .filter(|(t, _)| *t == CodeSmellType::EmptyExceptionHandling)
+        .count();
+    // пустой catch и подавление `@`
+    assert_eq!(empty_handling, 2);
+
+    let ruby = kinds(ORDERS_RB, FileType::Ruby);
+    // 11 строк тела при пороге RuboCop в 10
+    let place = Some("Функция: place (строки 8-23)".to_string());
+    assert!(ruby.contains(&(CodeSmellType::LongMethod, place)));
+    assert!(ruby.contains(&(
+        CodeSmellType::LongParameterList,
+        Some("Функция: build".to_string())
+    )));
+    assert!(ruby
+        .iter()
+        .any(|(t, _)| *t == CodeSmellType::EmptyExceptionHandling));
+    assert!(!ruby.contains(&(
+        CodeSmellType::LongMethod,
+        Some("Функция: ready?".to_string())
+    )));
+}
+
+#[test]
+fn scanner_picks_up_php_and_ruby_sources() {
+    let dir = temp_dir("scan");
+    std::fs::write(dir.join("index.php"), USER_CONTROLLER_PHP).unwrap();
+    std::fs::write(dir.join("orders.rb"), ORDERS_RB).unwrap();
+    let files = FileScanner::new(vec!["**/*.php".into(), "**/*.rb".into()], vec![], None)
+        .unwrap()
+        .scan_files(&dir)
+        .unwrap();
+    let php = files
+        .iter()
+        .find(|f| f.file_type == FileType::Php)
+        .expect("php file scanned");
+    assert!(php.imports.contains(&"App\\Models\\User".to_string()));
+    assert!(php.imports.contains(&"helpers.php".to_string()));
+    assert!(php.exports.contains(&"UserController".to_string()));
+    let ruby = files
+        .iter()
+        .find(|f| f.file_type == FileType::Ruby)
+        .expect("ruby file scanned");
+    assert_eq!(ruby.imports, vec!["json", "models/order"]);
+    assert!(ruby.exports.contains(&"OrderService".to_string()));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,39 @@
+use archlens::plain_report::PlainTextRenderer;
+
+#[test]
+fn strips_emoji_color_and_markdown_markup() {
+    let markdown = "# 🏗️ AI Compact Analysis\n\n## 🔥 Problems\n- ⚠️ **High coupling** in `core`\n- \u{1b}[31mcycle\u{1b}[0m: a → b\n\nSee [docs](https://example.com/docs).\n";
+    let plain = PlainTextRenderer::new().render(markdown);
+
+    assert!(plain.is_ascii(), "non-ASCII left in:\n{plain}");
+    assert!(plain.starts_with("AI Compact Analysis\n===================\n"));
+    assert!(plain.contains("\nProblems\n--------\n"));
+    assert!(plain.contains("- High coupling in core\n"));
+    assert!(plain.contains("- cycle: a -> b\n"));
+    assert!(plain.contains("See docs (https://example.com/docs)."));
+    assert!(!plain.contains("**") && !plain.contains('`') && !plain.contains('#'));
+}
+
+#[test]
+fn renders_markdown_tables_with_fixed_width_columns() {
+    let markdown = "| Metric | Value |\n|---|---:|\n| Components | 4 |\n| ✅ Relations | 198 |\n";
+    let plain = PlainTextRenderer::new().render(markdown);
+    let lines: Vec<&str> = plain.lines().collect();
+
+    assert_eq!(
+        lines,
+        vec![
+            "Metric      Value",
+            "----------  -----",
+            "Components  4",
+            "Relations   198",
+        ]
+    );
+}
+
+#[test]
+fn keeps_code_blocks_indented_and_collapses_blank_lines() {
+    let markdown = "Intro\n\n\n\n```rust\nfn main() {}\n```\n\n";
+    let plain = PlainTextRenderer::new().render(markdown);
+    assert_eq!(plain, "Intro\n\n    fn main() {}\n");
+}
@@ -0,0 +1,121 @@
+use archlens::cli::handlers::{build_project_graph, run_init};
+use archlens::project_config::{confirm_interactively, ProjectConfig, CONFIG_FILE};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_project_config_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write(&dir, "Cargo.toml", "[package]\nname = \"demo\"\n");
+    write(&dir, "src/lib.rs", "pub mod api;\npub mod db;\n");
+    write(
+        &dir,
+        "src/api/handlers.rs",
+        "pub fn get_user() -> u32 {\n    crate::db::store::load()\n}\n",
+    );
+    write(
+        &dir,
+        "src/db/store.rs",
+        "pub fn load() -> u32 {\n    1\n}\n",
+    );
+    write(&dir, "target/debug/out.rs", "pub fn generated() {}\n");
+    write(&dir, "web/app.ts", "export function render() {}\n");
+    write(&dir, "svc/Service.csproj", "<Project />\n");
+    write(&dir, "svc/obj/gen.js", "export function gen() {}\n");
+    dir
+}
+
+#[test]
+fn proposal_follows_the_project() {
+    let dir = project("propose");
+    let config = ProjectConfig::propose(&dir);
+    assert_eq!(config.include, vec!["**/*.rs", "**/*.ts"]);
+    assert!(config.exclude.contains(&"**/svc/obj/**".to_string()));
+    assert_eq!(
+        config
+            .exclude
+            .iter()
+            .filter(|p| *p == "**/target/**")
+            .count(),
+        1
+    );
+    let layers: Vec<(&str, &str)> = config
+        .layers
+        .iter()
+        .map(|(dir, layer)| (dir.as_str(), layer.as_str()))
+        .collect();
+    assert_eq!(
+        layers,
+        vec![("src/api", "API"), ("src/db", "Data"), ("web", "UI")]
+    );
+    assert_eq!(config.thresholds, Default::default());
+
+    let text = config.to_toml().unwrap();
+    assert!(text.contains("\"src/api\" = \"API\""), "{text}");
+    assert_eq!(ProjectConfig::from_toml(&text).unwrap(), config);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn wizard_confirms_sections_and_edits_thresholds() {
+    let dir = project("wizard");
+    let proposal = ProjectConfig::propose(&dir);
+    // include — да, exclude — нет, слои — да, max_complexity = 20, остальное — Enter
+    let mut input = "\nn\ny\n20\n\n\ny\n".as_bytes();
+    let mut output = Vec::new();
+    let config = confirm_interactively(proposal.clone(), &mut input, &mut output)
+        .unwrap()
+        .unwrap();
+    assert_eq!(config.include, proposal.include);
+    assert_eq!(config.exclude, ProjectConfig::default().exclude);
+    assert_eq!(config.layers, proposal.layers);
+    assert_eq!(config.thresholds.max_complexity, 20);
+    assert_eq!(config.thresholds.coupling, proposal.thresholds.coupling);
+    let shown = String::from_utf8(output).unwrap();
+    assert!(shown.contains("src/api → API"), "{shown}");
+
+    let mut cancel = "\n\n\n\n\n\nn\n".as_bytes();
+    assert!(
+        confirm_interactively(proposal, &mut cancel, &mut Vec::new())
+            .unwrap()
+            .is_none()
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn written_config_drives_the_analysis() {
+    let dir = project("apply");
+    run_init(dir.to_str().unwrap(), true, false).unwrap();
+    assert!(dir.join(CONFIG_FILE).exists());
+    // повторный init без --force не перезаписывает файл
+    assert!(run_init(dir.to_str().unwrap(), true, false).is_err());
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let layer_of = |name: &str| {
+        graph
+            .capsules
+            .values()
+            .find(|c| c.name == name)
+            .and_then(|c| c.layer.clone())
+    };
+    assert_eq!(layer_of("get_user").as_deref(), Some("API"));
+    assert_eq!(layer_of("load").as_deref(), Some("Data"));
+    assert!(graph.capsules.values().all(|c| c.name != "generated"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,104 @@
+use archlens::cli::handlers::{build_project_graph, run_conformance};
+use archlens::validation::{ProjectTemplate, TemplateValidator};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_project_template_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+const TEMPLATE: &str = r#"{
+  "required_dirs": ["src"],
+  "required_layers": ["API", "Business"],
+  "forbidden_top_level_dirs": ["legacy"]
+}"#;
+
+/// Корневой Rust-сервис по шаблону и web-сервис, отклонившийся от него
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write(&dir, "Cargo.toml", "[package]\nname = \"shop\"\n");
+    write(
+        &dir,
+        "src/api/handlers.rs",
+        "pub fn list_orders() -> Vec<u32> {\n    Vec::new()\n}\n",
+    );
+    write(
+        &dir,
+        "src/domain/order.rs",
+        "pub struct Order {\n    pub id: u32,\n}\n",
+    );
+    write(&dir, "web/package.json", r#"{"name": "web"}"#);
+    write(
+        &dir,
+        "web/app.ts",
+        "export function render(): string {\n  return 'ok';\n}\n",
+    );
+    write(&dir, "web/legacy/old.ts", "export const OLD = 1;\n");
+    dir
+}
+
+#[test]
+fn drift_is_reported_per_service() {
+    let dir = project("drift");
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let template = ProjectTemplate::from_json(TEMPLATE).unwrap();
+    let report = TemplateValidator::new(template).validate(&dir, &graph);
+
+    let services: Vec<&Path> = report
+        .services
+        .iter()
+        .map(|s| s.service.as_path())
+        .collect();
+    assert_eq!(services, [Path::new("."), Path::new("web")]);
+    assert!(
+        report.services[0].is_conformant(),
+        "{:?}",
+        report.services[0]
+    );
+    let web = &report.services[1];
+    assert_eq!(web.missing_dirs, ["src"]);
+    assert_eq!(web.missing_layers, ["API", "Business"]);
+    assert_eq!(web.forbidden_dirs, ["legacy"]);
+    assert!(!report.is_conformant());
+
+    let warnings = report.warnings();
+    assert_eq!(warnings.len(), 4);
+    assert!(warnings.iter().all(|w| w.category == "template"));
+    assert!(report
+        .to_text()
+        .contains("❌ web:\n  - missing directory: src\n"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn template_is_read_from_project_or_explicit_file() {
+    let dir = project("cli");
+    let path = dir.to_str().unwrap();
+    assert!(run_conformance(path, None, false).is_err());
+
+    write(&dir, ".archlens/template.json", TEMPLATE);
+    let (text, conformant) = run_conformance(path, None, false).unwrap();
+    assert!(!conformant);
+    assert!(text.starts_with("✅ .: matches the template\n"));
+
+    let shared = dir.join("shared_template.json");
+    std::fs::write(&shared, r#"{"required_dirs": ["src"]}"#).unwrap();
+    std::fs::remove_dir_all(dir.join("web")).unwrap();
+    let (json, conformant) = run_conformance(path, shared.to_str(), true).unwrap();
+    assert!(conformant);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["services"].as_array().unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
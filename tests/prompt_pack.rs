@@ -0,0 +1,172 @@
+mod common;
+
+use archlens::prompt_pack::PromptPackBuilder;
+use archlens::types::*;
+use chrono::Utc;
+use flate2::read::DeflateDecoder;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+fn temp_project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_prompt_pack_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    dir
+}
+
+fn capsule(root: &Path, name: &str, complexity: u32, warnings: &[(Priority, &str)]) -> Capsule {
+    let id = Uuid::new_v4();
+    let file_path = root.join("src").join(format!("{}.rs", name.to_lowercase()));
+    let body: String = (1..=30)
+        .map(|i| format!("// {} line {}\n", name, i))
+        .collect();
+    fs::write(&file_path, body).unwrap();
+    Capsule {
+        id,
+        file_path,
+        line_start: 3,
+        line_end: 8,
+        size: 6,
+        complexity,
+        layer: Some("Core".into()),
+        warnings: warnings
+            .iter()
+            .map(|(level, message)| AnalysisWarning {
+                message: message.to_string(),
+                level: *level,
+                category: "complexity".into(),
+                capsule_id: Some(id),
+                suggestion: Some("Split it".into()),
+            })
+            .collect(),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn graph(root: &Path) -> CapsuleGraph {
+    let capsules = vec![
+        capsule(root, "Parser", 30, &[(Priority::Low, "Missing docs")]),
+        capsule(
+            root,
+            "Router",
+            5,
+            &[(Priority::Critical, "Cyclomatic complexity 42 > 10")],
+        ),
+        capsule(root, "Clean", 2, &[]),
+    ];
+    let ids: Vec<Uuid> = capsules.iter().map(|c| c.id).collect();
+    CapsuleGraph {
+        layers: HashMap::from([("Core".to_string(), ids)]),
+        ..common::graph(capsules, vec![])
+    }
+}
+
+#[test]
+fn pack_ranks_problem_capsules_and_includes_code() {
+    let root = temp_project("rank");
+    let pack = PromptPackBuilder::new(&root).build(&graph(&root)).unwrap();
+
+    let names: Vec<&str> = pack.files.keys().map(String::as_str).collect();
+    assert_eq!(
+        names,
+        vec![
+            "README.md",
+            "excerpts/01-router.md",
+            "excerpts/02-parser.md",
+            "prompts.md",
+            "summary.json"
+        ]
+    );
+
+    let router = &pack.files["excerpts/01-router.md"];
+    assert!(router.contains("- File: `src/router.rs` lines 3-8"));
+    assert!(router.contains("[Critical] Cyclomatic complexity 42 > 10 (complexity) — Split it"));
+    assert!(router.contains("```rs\n// Router line 3\n"));
+    assert!(router.contains("// Router line 8\n```"));
+    assert!(!router.contains("// Router line 9"));
+
+    let summary: serde_json::Value = serde_json::from_str(&pack.files["summary.json"]).unwrap();
+    assert!(summary.is_object());
+
+    let prompts = &pack.files["prompts.md"];
+    assert!(prompts.contains("Architecture health check"));
+    assert!(prompts.contains("Review problem components"));
+    assert!(prompts.contains("`excerpts/02-parser.md`"));
+    assert!(prompts.contains("Prioritized refactoring plan"));
+    assert!(!prompts.contains("Break dependency cycles"));
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn excerpts_are_truncated_and_capsules_limited() {
+    let root = temp_project("limit");
+    let mut builder = PromptPackBuilder::new(&root).with_max_capsules(1);
+    builder.max_excerpt_lines = 2;
+    let pack = builder.build(&graph(&root)).unwrap();
+
+    assert!(pack.files.contains_key("excerpts/01-router.md"));
+    assert!(!pack.files.contains_key("excerpts/02-parser.md"));
+    let router = &pack.files["excerpts/01-router.md"];
+    assert!(router.contains("// Router line 4\n```"));
+    assert!(router.contains("Excerpt truncated to 2 lines"));
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn pack_writes_folder_and_readable_zip() {
+    let root = temp_project("write");
+    let pack = PromptPackBuilder::new(&root).build(&graph(&root)).unwrap();
+
+    let dir = root.join("out");
+    let written = pack.write_dir(&dir).unwrap();
+    assert_eq!(written.len(), pack.files.len());
+    assert_eq!(
+        fs::read_to_string(dir.join("excerpts/01-router.md")).unwrap(),
+        pack.files["excerpts/01-router.md"]
+    );
+
+    // локальные заголовки zip: имя и распакованное содержимое каждого файла
+    let zip = pack.to_zip().unwrap();
+    let mut entries = HashMap::new();
+    let mut pos = 0;
+    while zip[pos..].starts_with(&0x0403_4b50u32.to_le_bytes()) {
+        let u16_at = |at: usize| u16::from_le_bytes([zip[at], zip[at + 1]]) as usize;
+        let u32_at =
+            |at: usize| u32::from_le_bytes([zip[at], zip[at + 1], zip[at + 2], zip[at + 3]]);
+        assert_eq!(u16_at(pos + 8), 8, "deflate method");
+        let compressed = u32_at(pos + 18) as usize;
+        let name_len = u16_at(pos + 26);
+        let data_start = pos + 30 + name_len + u16_at(pos + 28);
+        let name = String::from_utf8(zip[pos + 30..pos + 30 + name_len].to_vec()).unwrap();
+        let mut content = String::new();
+        DeflateDecoder::new(&zip[data_start..data_start + compressed])
+            .read_to_string(&mut content)
+            .unwrap();
+        entries.insert(name, content);
+        pos = data_start + compressed;
+    }
+    assert_eq!(entries.len(), pack.files.len());
+    for (name, content) in &pack.files {
+        assert_eq!(&entries[name], content);
+    }
+    // конец центрального каталога с числом записей
+    let eocd = zip.len() - 22;
+    assert_eq!(&zip[eocd..eocd + 4], &0x0605_4b50u32.to_le_bytes());
+    assert_eq!(
+        u16::from_le_bytes([zip[eocd + 10], zip[eocd + 11]]) as usize,
+        pack.files.len()
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
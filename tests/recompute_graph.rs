@@ -0,0 +1,97 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::types::*;
+use archlens::validation::{
+    recompute_graph, recompute_graph_json, ValidationThresholds, ValidatorOptimizer,
+};
+
+fn capsule(name: &str, complexity: u32) -> Capsule {
+    Capsule {
+        line_end: 20,
+        size: 20,
+        complexity,
+        layer: Some("Core".into()),
+        warnings: vec![AnalysisWarning {
+            message: format!("High complexity: {complexity}"),
+            level: Priority::High,
+            category: "complexity".into(),
+            capsule_id: None,
+            suggestion: None,
+        }],
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+fn validated_graph() -> CapsuleGraph {
+    let capsules = vec![capsule("parse", 12), capsule("render", 25)];
+    let graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    ValidatorOptimizer::new()
+        .validate_and_optimize(&graph)
+        .unwrap()
+}
+
+fn complexity_findings(graph: &CapsuleGraph, name: &str) -> Vec<String> {
+    let capsule = graph.capsules.values().find(|c| c.name == name).unwrap();
+    capsule
+        .warnings
+        .iter()
+        .filter(|w| w.capsule_id.is_some() && w.category == "complexity")
+        .map(|w| w.message.clone())
+        .collect()
+}
+
+#[test]
+fn tighter_thresholds_add_findings_without_reparsing() {
+    let graph = validated_graph();
+    assert_eq!(complexity_findings(&graph, "parse"), Vec::<String>::new());
+    assert_eq!(complexity_findings(&graph, "render").len(), 1);
+
+    let strict = ValidationThresholds {
+        max_complexity: 10,
+        ..ValidationThresholds::default()
+    };
+    let json = serde_json::to_string(&graph).unwrap();
+    let recomputed = recompute_graph_json(&json, &strict).unwrap();
+    assert_eq!(
+        complexity_findings(&recomputed, "parse"),
+        ["Component 'parse' has high complexity: 12"]
+    );
+    // предыдущие находки заменяются, а не дублируются
+    assert_eq!(complexity_findings(&recomputed, "render").len(), 1);
+    // предупреждения построения капсул сохраняются
+    let render = recomputed
+        .capsules
+        .values()
+        .find(|c| c.name == "render")
+        .unwrap();
+    assert!(render
+        .warnings
+        .iter()
+        .any(|w| w.capsule_id.is_none() && w.message == "High complexity: 25"));
+    assert_eq!(recomputed.metrics.total_capsules, 2);
+}
+
+#[test]
+fn recompute_with_defaults_is_idempotent() {
+    let graph = validated_graph();
+    let again = recompute_graph(&graph, &ValidationThresholds::default()).unwrap();
+    for name in ["parse", "render"] {
+        let before = graph.capsules.values().find(|c| c.name == name).unwrap();
+        let after = again.capsules.values().find(|c| c.name == name).unwrap();
+        assert_eq!(before.warnings.len(), after.warnings.len(), "{name}");
+    }
+    assert_eq!(
+        graph.metrics.complexity_average,
+        again.metrics.complexity_average
+    );
+
+    let relaxed = ValidationThresholds {
+        max_complexity: 30,
+        ..ValidationThresholds::default()
+    };
+    let relaxed = recompute_graph(&graph, &relaxed).unwrap();
+    assert!(complexity_findings(&relaxed, "render").is_empty());
+    assert!(recompute_graph_json("{", &ValidationThresholds::default()).is_err());
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use archlens::redaction::{RedactionConfig, Redactor};
+use archlens::types::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const CONFIG: &str = r#"{
+  "paths": ["src/internal/", "./secrets"],
+  "identifiers": ["Acme\\w*", "(?i)project[-_ ]?falcon"],
+  "replacement": "<hidden>"
+}"#;
+
+fn capsule(root: &Path, file: &str, name: &str) -> Capsule {
+    Capsule {
+        capsule_type: CapsuleType::Struct,
+        file_path: root.join(file),
+        line_end: 3,
+        size: 3,
+        layer: Some("AcmeCore".into()),
+        summary: Some(format!("Part of Project Falcon, see {}", file)),
+        warnings: vec![AnalysisWarning {
+            message: format!("{} is too large", name),
+            level: Priority::High,
+            category: "size".into(),
+            capsule_id: None,
+            suggestion: Some("Split AcmeBilling".into()),
+        }],
+        quality_score: 0.5,
+        ..common::capsule(name)
+    }
+}
+
+fn redactor(root: &Path) -> Redactor {
+    Redactor::new(root, &RedactionConfig::from_json(CONFIG).unwrap()).unwrap()
+}
+
+#[test]
+fn masks_paths_and_internal_names_in_text() {
+    let root = PathBuf::from("/work/app");
+    let redactor = redactor(&root);
+    let masked = redactor.mask_path(Path::new("src/internal/pricing.rs"));
+    assert!(masked.starts_with("redacted/") && masked.ends_with(".rs"));
+    // абсолютный и относительный путь дают одну и ту же замену
+    assert_eq!(
+        redactor.mask_path(Path::new("/work/app/src/internal/pricing.rs")),
+        masked
+    );
+
+    let text = "- `src/internal/pricing.rs:42` AcmePricing calls /work/app/secrets/keys.json\n\
+                - src/public/api.rs is fine (project_falcon)\n";
+    let redacted = redactor.redact_text(text);
+    assert_eq!(
+        redacted,
+        format!(
+            "- `{}:42` <hidden> calls {}\n- src/public/api.rs is fine (<hidden>)\n",
+            masked,
+            redactor.mask_path(Path::new("secrets/keys.json"))
+        )
+    );
+    assert!(redactor.is_redacted_path(Path::new("/work/app/secrets/keys.json")));
+    assert!(!redactor.is_redacted_path(Path::new("/work/app/src/public/api.rs")));
+}
+
+#[test]
+fn snippets_are_removed_when_configured() {
+    let config = RedactionConfig::from_json(r#"{"snippets": true}"#).unwrap();
+    let redactor = Redactor::new(Path::new("/work/app"), &config).unwrap();
+    let md = "# Pricing\n\n## Code\n\n```rust\nfn secret() {}\n```\n\nAfter.\n";
+    assert_eq!(
+        redactor.redact_text(md),
+        "# Pricing\n\n## Code\n\n```rust\n[code redacted]\n```\n\nAfter.\n"
+    );
+}
+
+#[test]
+fn graph_is_redacted_before_export() {
+    let root = PathBuf::from("/work/app");
+    let redactor = redactor(&root);
+    let capsules = [
+        capsule(&root, "src/internal/pricing.rs", "AcmePricing"),
+        capsule(&root, "src/public/api.rs", "Api"),
+    ];
+    let mut graph = CapsuleGraph {
+        layers: HashMap::from([("AcmeCore".to_string(), vec![])]),
+        ..common::graph(capsules.to_vec(), vec![])
+    };
+    redactor.redact_graph(&mut graph);
+
+    let mut capsules: Vec<&Capsule> = graph.capsules.values().collect();
+    capsules.sort_by_key(|c| c.name.clone());
+    let (hidden, api) = (capsules[0], capsules[1]);
+    assert_eq!(hidden.name, "<hidden>");
+    assert!(hidden.file_path.starts_with("/work/app/redacted"));
+    assert!(!hidden.summary.as_ref().unwrap().contains("internal"));
+    assert!(!hidden.summary.as_ref().unwrap().contains("Falcon"));
+    assert_eq!(hidden.warnings[0].message, "<hidden> is too large");
+    assert_eq!(
+        hidden.warnings[0].suggestion.as_deref(),
+        Some("Split <hidden>")
+    );
+    assert_eq!(api.file_path, root.join("src/public/api.rs"));
+    assert_eq!(api.layer.as_deref(), Some("<hidden>"));
+    assert!(graph.layers.contains_key("<hidden>"));
+}
+
+#[test]
+fn config_is_optional_and_validated() {
+    let root = std::env::temp_dir().join(format!("archlens_redaction_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join(".archlens")).unwrap();
+    assert!(Redactor::for_project(&root).unwrap().is_none());
+
+    std::fs::write(root.join(".archlens/redaction.json"), "{}").unwrap();
+    assert!(Redactor::for_project(&root).unwrap().is_none());
+
+    std::fs::write(
+        root.join(".archlens/redaction.json"),
+        r#"{"identifiers": ["Acme("]}"#,
+    )
+    .unwrap();
+    let err = Redactor::for_project(&root).unwrap_err().to_string();
+    assert!(err.contains("Acme("), "{err}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn masked_paths_depend_on_project_salt() {
+    let root = std::env::temp_dir().join(format!("archlens_redaction_salt_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join(".archlens")).unwrap();
+    std::fs::write(root.join(".archlens/redaction.json"), CONFIG).unwrap();
+    let file = Path::new("src/internal/pricing.rs");
+
+    let first = Redactor::for_project(&root)
+        .unwrap()
+        .unwrap()
+        .mask_path(file);
+    let salt = std::fs::read_to_string(root.join(".archlens/redaction.salt")).unwrap();
+    assert!(!salt.trim().is_empty());
+    // соль сохраняется: маски стабильны между запусками
+    let again = Redactor::for_project(&root)
+        .unwrap()
+        .unwrap()
+        .mask_path(file);
+    assert_eq!(first, again);
+    // полный 64-битный хеш, а не усечённый
+    assert_eq!(first.len(), "redacted/".len() + 16 + ".rs".len());
+    // без соли проекта та же маска не получается
+    assert_ne!(redactor(&root).mask_path(file), first);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
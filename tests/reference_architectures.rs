@@ -0,0 +1,149 @@
+use archlens::cli::handlers::{build_project_graph, run_resemblance};
+use archlens::reference_architectures::{Deviation, ReferenceProfiles};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_reference_architectures_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Слоистое веб-приложение: controllers → services → repositories; один
+/// репозиторий обращается к контроллеру против направления слоёв
+fn layered_web(dir: &Path) {
+    write(dir, "Cargo.toml", "[package]\nname = \"shop\"\n");
+    write(
+        dir,
+        "src/lib.rs",
+        "pub mod controllers;\npub mod services;\npub mod repositories;\n",
+    );
+    write(
+        dir,
+        "src/controllers/mod.rs",
+        "pub fn show_order() -> u32 {\n    crate::services::order_total()\n}\n\npub fn current_user() -> u32 {\n    7\n}\n",
+    );
+    write(
+        dir,
+        "src/services/mod.rs",
+        "pub fn order_total() -> u32 {\n    crate::repositories::load_order()\n}\n",
+    );
+    write(
+        dir,
+        "src/repositories/mod.rs",
+        "pub fn load_order() -> u32 {\n    crate::controllers::current_user()\n}\n",
+    );
+}
+
+#[test]
+fn builtin_profiles_cover_common_styles() {
+    let profiles = ReferenceProfiles::builtin();
+    let ids: Vec<&str> = profiles.profiles.iter().map(|p| p.id.as_str()).collect();
+    for id in ["hexagonal", "layered-web", "mvc", "clean", "microkernel"] {
+        assert!(ids.contains(&id), "{ids:?}");
+    }
+    for profile in &profiles.profiles {
+        let roles: Vec<&str> = profile.roles.iter().map(|r| r.name.as_str()).collect();
+        for (from, to) in &profile.allowed {
+            assert!(roles.contains(&from.as_str()), "{} {from}", profile.id);
+            assert!(roles.contains(&to.as_str()), "{} {to}", profile.id);
+        }
+    }
+}
+
+#[test]
+fn layered_project_resembles_layered_web_and_reports_upward_call() {
+    let dir = temp_dir("layered");
+    layered_web(&dir);
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+
+    let report = ReferenceProfiles::builtin().compare(&graph);
+    let best = report.best().unwrap();
+    assert_eq!(best.profile, "layered-web", "{}", report.to_text());
+    assert_eq!(best.coverage, 1.0);
+    assert!(best.conformity < 1.0);
+    assert!(report.matches.windows(2).all(|w| w[0].score >= w[1].score));
+    assert!(best.deviations.iter().any(|d| matches!(
+        d,
+        Deviation::ForbiddenDependency { from, to, example, .. }
+            if from == "data" && to == "presentation" && example.contains("current_user")
+    )));
+    let hexagonal = report
+        .matches
+        .iter()
+        .find(|m| m.profile == "hexagonal")
+        .unwrap();
+    assert!(hexagonal
+        .deviations
+        .iter()
+        .any(|d| matches!(d, Deviation::MissingRole { role, .. } if role == "domain")));
+
+    let text = run_resemblance(dir.to_str().unwrap(), false).unwrap();
+    assert!(
+        text.starts_with("Closest reference architecture: Layered web application"),
+        "{text}"
+    );
+    assert!(text.contains("`data` → `presentation`"), "{text}");
+    let json: serde_json::Value =
+        serde_json::from_str(&run_resemblance(dir.to_str().unwrap(), true).unwrap()).unwrap();
+    assert_eq!(json["matches"][0]["profile"], "layered-web");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn project_profiles_replace_builtin_ones() {
+    let dir = temp_dir("custom");
+    layered_web(&dir);
+    write(
+        &dir,
+        ".archlens/reference_architectures.json",
+        r#"{"profiles": [{
+            "id": "layered-web",
+            "title": "Relaxed layers",
+            "roles": [
+                { "name": "web", "directories": ["controllers"] },
+                { "name": "logic", "directories": ["services"] },
+                { "name": "storage", "directories": ["repositories"] }
+            ],
+            "allowed": [["web", "logic"], ["logic", "storage"], ["storage", "web"]]
+        }]}"#,
+    );
+    let profiles = ReferenceProfiles::for_project(&dir).unwrap();
+    assert_eq!(
+        profiles
+            .profiles
+            .iter()
+            .filter(|p| p.id == "layered-web")
+            .count(),
+        1
+    );
+
+    let report = profiles.compare(&build_project_graph(dir.to_str().unwrap()).unwrap());
+    let best = report.best().unwrap();
+    assert_eq!(best.title, "Relaxed layers");
+    assert_eq!(best.conformity, 1.0);
+    assert!(!best
+        .deviations
+        .iter()
+        .any(|d| matches!(d, Deviation::ForbiddenDependency { .. })));
+
+    write(
+        &dir,
+        ".archlens/reference_architectures.json",
+        "{\"profiles\": 1}",
+    );
+    assert!(run_resemblance(dir.to_str().unwrap(), false).is_err());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
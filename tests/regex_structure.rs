@@ -0,0 +1,171 @@
+use archlens::parser_ast::{ASTElement, ASTElementType, ParserAST};
+use archlens::types::FileType;
+use std::path::Path;
+
+fn parse(name: &str, source: &str, file_type: FileType) -> Vec<ASTElement> {
+    ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new(name), source, &file_type)
+        .unwrap()
+}
+
+fn find<'a>(elements: &'a [ASTElement], name: &str) -> &'a ASTElement {
+    elements
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("{name} not found"))
+}
+
+fn parent_name<'a>(elements: &'a [ASTElement], element: &ASTElement) -> Option<&'a str> {
+    let parent = element.parent_id?;
+    elements
+        .iter()
+        .find(|e| e.id == parent)
+        .map(|e| e.name.as_str())
+}
+
+const SERVICE_PHP: &str = r#"<?php
+namespace App;
+
+class Billing
+{
+    public function charge($amount)
+    {
+        $fmt = "{";
+        return $amount;
+    }
+
+    private function refund()
+    {
+    }
+}
+
+function helper()
+{
+}
+"#;
+
+#[test]
+fn php_methods_nest_under_class() {
+    let elements = parse("Billing.php", SERVICE_PHP, FileType::Php);
+    let class = find(&elements, "Billing");
+    let charge = find(&elements, "charge");
+    let refund = find(&elements, "refund");
+    let helper = find(&elements, "helper");
+
+    assert_eq!(charge.element_type, ASTElementType::Method);
+    assert_eq!(parent_name(&elements, charge), Some("Billing"));
+    assert_eq!(parent_name(&elements, refund), Some("Billing"));
+    assert_eq!(class.children, vec![charge.id, refund.id]);
+    // `namespace App;` не открывает блок, функция после класса — верхнего уровня
+    assert_eq!(helper.element_type, ASTElementType::Function);
+    assert_eq!(helper.parent_id, None);
+}
+
+const SHOP_RB: &str = r#"require 'json'
+
+module Shop
+  class Cart
+    def add(item)
+      items << item
+    end
+
+    def total
+      items.sum(&:price)
+    end
+  end
+
+  def self.open
+    Cart.new
+  end
+end
+"#;
+
+#[test]
+fn ruby_nesting_follows_indentation() {
+    let elements = parse("shop.rb", SHOP_RB, FileType::Ruby);
+    let cart = find(&elements, "Cart");
+    assert_eq!(parent_name(&elements, cart), Some("Shop"));
+    for method in ["add", "total"] {
+        let m = find(&elements, method);
+        assert_eq!(m.element_type, ASTElementType::Method);
+        assert_eq!(parent_name(&elements, m), Some("Cart"));
+    }
+    // функция модуля остаётся функцией
+    let open = find(&elements, "open");
+    assert_eq!(open.element_type, ASTElementType::Function);
+    assert_eq!(parent_name(&elements, open), Some("Shop"));
+    assert_eq!(find(&elements, "Shop").children, vec![cart.id, open.id]);
+}
+
+#[cfg(not(feature = "tree_sitter"))]
+#[test]
+fn rust_and_python_regex_fallback_nesting() {
+    let rust = "pub mod api {\n    // }\n    pub fn get() {\n        let s = \"}\";\n    }\n}\n\nmod decl;\n\npub fn main() {}\n";
+    let elements = parse("lib.rs", rust, FileType::Rust);
+    assert_eq!(parent_name(&elements, find(&elements, "get")), Some("api"));
+    assert_eq!(
+        find(&elements, "get").element_type,
+        ASTElementType::Function
+    );
+    assert_eq!(find(&elements, "decl").parent_id, None);
+    assert_eq!(find(&elements, "main").parent_id, None);
+
+    let python = "class Repo:\n    def load(self):\n        def inner():\n            pass\n        return inner\n\ndef main():\n    pass\n";
+    let elements = parse("repo.py", python, FileType::Python);
+    let load = find(&elements, "load");
+    assert_eq!(load.element_type, ASTElementType::Method);
+    assert_eq!(parent_name(&elements, load), Some("Repo"));
+    // вложенная функция принадлежит методу, а не классу
+    let inner = find(&elements, "inner");
+    assert_eq!(inner.element_type, ASTElementType::Function);
+    assert_eq!(parent_name(&elements, inner), Some("load"));
+    assert_eq!(find(&elements, "main").parent_id, None);
+}
+
+#[cfg(not(feature = "tree_sitter"))]
+#[test]
+fn rust_literals_do_not_open_blocks() {
+    let rust = "fn patterns() -> Regex {\n    Regex::new(r#\"extern\\s+\"[^\"]*\"\\s*[^;{]*\\{\"#)\n}\n\nfn open() -> char {\n    '{'\n}\n\nfn last() {}\n";
+    let elements = parse("lib.rs", rust, FileType::Rust);
+    let span = |name: &str| {
+        let e = find(&elements, name);
+        (e.start_line, e.end_line)
+    };
+    assert_eq!(span("patterns"), (1, 3));
+    assert_eq!(span("open"), (5, 7));
+    assert_eq!(find(&elements, "last").parent_id, None);
+}
+
+#[test]
+fn regex_elements_span_their_bodies() {
+    let elements = parse("Billing.php", SERVICE_PHP, FileType::Php);
+    let span = |name: &str| {
+        let e = find(&elements, name);
+        (e.start_line, e.end_line)
+    };
+    assert_eq!(span("Billing"), (4, 15));
+    assert_eq!(span("charge"), (6, 10));
+    assert_eq!(span("helper"), (17, 19));
+    assert!(find(&elements, "charge").content.ends_with("    }"));
+
+    let elements = parse("shop.rb", SHOP_RB, FileType::Ruby);
+    let span = |name: &str| {
+        let e = find(&elements, name);
+        (e.start_line, e.end_line)
+    };
+    // закрывающий `end` входит в тело
+    assert_eq!(span("Shop"), (3, 17));
+    assert_eq!(span("Cart"), (4, 12));
+    assert_eq!(span("add"), (5, 7));
+}
+
+#[test]
+fn complexity_counts_whole_body() {
+    let source = "<?php\nfunction route($path)\n{\n    if ($path === '/') {\n        return 'home';\n    }\n    foreach ($this->routes as $r) {\n        if ($r->matches($path) && $r->enabled) {\n            return $r;\n        }\n    }\n}\n";
+    let elements = parse("router.php", source, FileType::Php);
+    let route = find(&elements, "route");
+    assert_eq!((route.start_line, route.end_line), (2, 12));
+    // if ×2, foreach, && и вложенность блоков
+    assert!(route.complexity >= 5, "complexity {}", route.complexity);
+}
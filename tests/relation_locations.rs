@@ -0,0 +1,128 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::relation_locations::{RelationLocator, SourceLocation};
+use archlens::types::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_relation_locations_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn capsule(name: &str, file: &Path, lines: (usize, usize)) -> Capsule {
+    Capsule {
+        file_path: file.to_path_buf(),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        layer: Some("core".into()),
+        quality_score: 1.0,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 0.8,
+        description: None,
+    }
+}
+
+fn graph(capsules: Vec<Capsule>, relations: Vec<CapsuleRelation>) -> CapsuleGraph {
+    let layers = HashMap::from([("core".to_string(), capsules.iter().map(|c| c.id).collect())]);
+    CapsuleGraph {
+        layers,
+        ..common::graph(capsules, relations)
+    }
+}
+
+const SERVICE: &str = "use crate::store::save;\n\npub fn register(id: u64) {\n    let user = build(id);\n    save(user);\n}\n\npub fn build(id: u64) -> u64 {\n    id\n}\n";
+
+#[test]
+fn locations_point_at_the_code_creating_the_relation() {
+    let dir = temp_dir("locate");
+    let service = dir.join("service.rs");
+    std::fs::write(&service, SERVICE).unwrap();
+    let store = dir.join("store.rs");
+    std::fs::write(&store, "pub fn save(user: u64) {}\n").unwrap();
+
+    // register известна только первой строкой, как в regex-парсере
+    let register = capsule("register", &service, (3, 3));
+    let build = capsule("build", &service, (8, 10));
+    let save = capsule("save", &store, (1, 1));
+    let relations = vec![
+        relation(&register, &build, RelationType::Calls),
+        relation(&register, &save, RelationType::Calls),
+        relation(&build, &save, RelationType::References),
+    ];
+    let graph = graph(vec![register, build, save], relations);
+
+    let mut locator = RelationLocator::new(&graph);
+    let located: Vec<SourceLocation> = graph
+        .relations
+        .iter()
+        .map(|r| locator.locate(r).unwrap())
+        .collect();
+    assert_eq!(located[0].to_string(), format!("{}:4", service.display()));
+    assert_eq!(located[1].line, 5);
+    // build не упоминает save — ссылка на импорт в том же файле
+    assert_eq!(located[2].line, 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn every_exported_relation_carries_a_location() {
+    let dir = temp_dir("export");
+    let service = dir.join("service.rs");
+    std::fs::write(&service, SERVICE).unwrap();
+    // файла нет на диске: ссылка на объявление исходной капсулы
+    let missing = dir.join("missing.rs");
+
+    let register = capsule("register", &service, (3, 6));
+    let build = capsule("build", &service, (8, 10));
+    let ghost = capsule("ghost", &missing, (7, 9));
+    let relations = vec![
+        relation(&register, &build, RelationType::Calls),
+        relation(&ghost, &register, RelationType::References),
+    ];
+    let graph = graph(vec![register, build, ghost], relations);
+    let exporter = Exporter::new();
+    let register_build = format!("{}:4", service.display());
+    let ghost_register = format!("{}:7", missing.display());
+
+    let dot = exporter.export_to_dot(&graph).unwrap();
+    assert!(dot.contains(&format!("label=\"calls\", tooltip=\"{register_build}\"]")));
+    assert!(dot.contains(&format!("label=\"0.8\", tooltip=\"{ghost_register}\"]")));
+
+    let graphml = exporter.export_to_graphml(&graph).unwrap();
+    assert!(graphml.contains("<key id=\"location\" for=\"edge\""));
+    assert_eq!(graphml.matches("<data key=\"location\">").count(), 2);
+    assert!(graphml.contains(&format!("<data key=\"location\">{register_build}</data>")));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&exporter.export_to_json(&graph).unwrap()).unwrap();
+    let mut locations: Vec<&str> = json["relations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["location"].as_str().unwrap())
+        .collect();
+    locations.sort_unstable();
+    let mut expected = vec![register_build.as_str(), ghost_register.as_str()];
+    expected.sort_unstable();
+    assert_eq!(locations, expected);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
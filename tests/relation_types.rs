@@ -0,0 +1,377 @@
+mod common;
+
+use archlens::capsule_constructor::CapsuleConstructor;
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::exporter::Exporter;
+use archlens::parser_ast::ParserAST;
+use archlens::types::*;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_relation_types_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Граф из файлов без метрик: только связи, которые видит RelationAnalyzer
+fn files_graph(files: &[(&Path, &str, FileType)]) -> CapsuleGraph {
+    files_graph_with(files, CapsuleGraphBuilder::new())
+}
+
+fn files_graph_with(
+    files: &[(&Path, &str, FileType)],
+    mut builder: CapsuleGraphBuilder,
+) -> CapsuleGraph {
+    let mut capsules = Vec::new();
+    for (path, source, file_type) in files {
+        std::fs::write(path, source).unwrap();
+        let elements = ParserAST::new()
+            .unwrap()
+            .parse_file(path, source, file_type)
+            .unwrap();
+        capsules.extend(
+            CapsuleConstructor::new()
+                .create_capsules(&elements, path)
+                .unwrap(),
+        );
+    }
+    let relations = builder.build_advanced_relations(&capsules).unwrap();
+    common::graph(capsules, relations)
+}
+
+/// Типизированная связь между капсулами с данными именами
+fn typed<'a>(graph: &'a CapsuleGraph, from: &str, to: &str) -> Option<&'a CapsuleRelation> {
+    let id = |name: &str| {
+        graph
+            .capsules
+            .values()
+            .find(|c| c.name == name)
+            .unwrap_or_else(|| panic!("no capsule {name}"))
+            .id
+    };
+    let (from, to) = (id(from), id(to));
+    graph
+        .relations
+        .iter()
+        .find(|r| r.from_id == from && r.to_id == to && !r.relation_type.is_structural())
+}
+
+const USERS: &str = r#"use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const LIMIT: u64 = 10;
+pub static REGISTERED: AtomicUsize = AtomicUsize::new(0);
+
+pub trait Named {
+    fn name(&self) -> String;
+}
+
+pub trait Entity: Named + Send {
+    fn id(&self) -> u64;
+}
+
+pub struct UserCreated {
+    pub id: u64,
+}
+
+pub struct Journal {
+    events: Vec<UserCreated>,
+}
+
+pub fn validate(id: u64) -> bool {
+    id < LIMIT
+}
+
+pub fn register(bus: &Bus, id: u64) {
+    if validate(id) {
+        REGISTERED.fetch_add(1, Ordering::SeqCst);
+        bus.publish(UserCreated { id });
+    }
+}
+
+pub fn handle_user_created(event: &UserCreated) {
+    let _ = event.id;
+}
+"#;
+
+#[test]
+fn rust_code_produces_typed_relations() {
+    let dir = temp_dir("rust");
+    let graph = files_graph(&[(&dir.join("users.rs"), USERS, FileType::Rust)]);
+
+    let expected = [
+        ("register", "validate", RelationType::Calls),
+        ("validate", "LIMIT", RelationType::Reads),
+        ("register", "REGISTERED", RelationType::Writes),
+        ("register", "UserCreated", RelationType::Emits),
+        ("handle_user_created", "UserCreated", RelationType::Handles),
+        ("Journal", "UserCreated", RelationType::Composes),
+        ("Entity", "Named", RelationType::Extends),
+    ];
+    for (from, to, relation_type) in expected {
+        let relation = typed(&graph, from, to).unwrap_or_else(|| panic!("{from} -> {to}"));
+        assert_eq!(relation.relation_type, relation_type, "{from} -> {to}");
+    }
+    assert_eq!(
+        typed(&graph, "register", "validate")
+            .unwrap()
+            .description
+            .as_deref(),
+        Some("calls validate()")
+    );
+    // обработчик только читает событие, но не отправляет его
+    assert!(typed(&graph, "handle_user_created", "register").is_none());
+    assert!(typed(&graph, "validate", "register").is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn class_hierarchies_produce_inherits_and_implements() {
+    let dir = temp_dir("classes");
+    let shapes = "export interface Shape {\n  area(): number;\n}\n\nexport class Base {\n  id: number;\n}\n\nexport class Circle extends Base implements Shape {\n  radius: number;\n  area(): number {\n    return 3.14 * this.radius * this.radius;\n  }\n}\n";
+    let animals = "class Animal:\n    pass\n\n\nclass Dog(Animal):\n    def bark(self):\n        return \"woof\"\n";
+    let graph = files_graph(&[
+        (&dir.join("shapes.ts"), shapes, FileType::TypeScript),
+        (&dir.join("animals.py"), animals, FileType::Python),
+    ]);
+
+    let circle_base = typed(&graph, "Circle", "Base").unwrap();
+    assert_eq!(circle_base.relation_type, RelationType::Inherits);
+    assert_eq!(
+        circle_base.description.as_deref(),
+        Some("Circle inherits Base")
+    );
+    assert_eq!(
+        typed(&graph, "Circle", "Shape").unwrap().relation_type,
+        RelationType::Implements
+    );
+    assert_eq!(
+        typed(&graph, "Dog", "Animal").unwrap().relation_type,
+        RelationType::Inherits
+    );
+    assert!(typed(&graph, "Base", "Circle").is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn coupling_and_diagrams_are_broken_down_by_type() {
+    let dir = temp_dir("coupling");
+    let graph = files_graph(&[(&dir.join("users.rs"), USERS, FileType::Rust)]);
+
+    let coupling =
+        CapsuleGraphBuilder::new().calculate_coupling_metrics(&graph.capsules, &graph.relations);
+    let handles = &coupling.by_relation_type[&RelationType::Handles];
+    assert_eq!(
+        (handles.relations, handles.sources, handles.targets),
+        (1, 1, 1)
+    );
+    let total: usize = coupling
+        .by_relation_type
+        .values()
+        .map(|c| c.relations)
+        .sum();
+    assert_eq!(total, coupling.total_coupling);
+
+    let exporter = Exporter::new();
+    let dot = exporter.export_to_dot(&graph).unwrap();
+    assert!(dot.contains(&format!(
+        "\"register\" -> \"validate\" [style=solid, color=\"#ff7f0e\", arrowhead=normal, label=\"calls\", tooltip=\"{}:27\"];",
+        dir.join("users.rs").display()
+    )));
+    let mermaid = exporter.export_to_mermaid(&graph).unwrap();
+    assert!(mermaid.contains("register -->|calls| validate"));
+    assert!(mermaid.contains("stroke:#ff7f0e"));
+    let compact = exporter.export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## Coupling By Relation Type\n"));
+    assert!(compact.contains("- handles : 1 (sources 1, targets 1, avg out 1.00)"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn nesting_and_imports_are_typed_edges() {
+    let dir = temp_dir("nesting");
+    let store = "pub struct Store {\n    items: Vec<u32>,\n}\n\nimpl Store {\n    pub fn len(&self) -> usize {\n        self.items.len()\n    }\n}\n";
+    let animals = "class Dog:\n    def bark(self):\n        return \"woof\"\n";
+    let helpers = "export function helper(): number {\n  return 1;\n}\n";
+    let app = "import { helper } from './helpers';\n\nexport function run(): number {\n  return helper();\n}\n";
+    let graph = files_graph(&[
+        (&dir.join("store.rs"), store, FileType::Rust),
+        (&dir.join("animals.py"), animals, FileType::Python),
+        (&dir.join("helpers.ts"), helpers, FileType::TypeScript),
+        (&dir.join("app.ts"), app, FileType::TypeScript),
+    ]);
+
+    let contains = typed(&graph, "Store", "len").unwrap();
+    assert_eq!(contains.relation_type, RelationType::Contains);
+    assert_eq!(contains.description.as_deref(), Some("Contains len"));
+    assert_eq!(
+        typed(&graph, "Dog", "bark").unwrap().relation_type,
+        RelationType::Contains
+    );
+    assert!(typed(&graph, "len", "Store").is_none_or(|r| r.relation_type != RelationType::Contains));
+    let imports = graph
+        .relations
+        .iter()
+        .find(|r| r.relation_type == RelationType::Imports)
+        .unwrap();
+    assert_eq!(
+        graph.capsules[&imports.from_id].file_path,
+        dir.join("app.ts")
+    );
+    assert_eq!(
+        graph.capsules[&imports.to_id].file_path,
+        dir.join("helpers.ts")
+    );
+
+    // вложенность — не зависимость
+    let nesting: Vec<CapsuleRelation> = graph
+        .relations
+        .iter()
+        .filter(|r| r.relation_type == RelationType::Contains)
+        .cloned()
+        .collect();
+    let capsules = CapsuleGraphBuilder::new()
+        .update_capsule_dependencies(&graph.capsules, &nesting)
+        .unwrap();
+    assert!(capsules
+        .values()
+        .all(|c| c.dependencies.is_empty() && c.dependents.is_empty()));
+
+    let exporter = Exporter::new();
+    let mermaid = exporter.export_to_mermaid(&graph).unwrap();
+    assert!(mermaid.contains("Store --o|contains| len"));
+    let dot = exporter.export_to_dot(&graph).unwrap();
+    assert!(dot.contains(
+        "\"Dog\" -> \"bark\" [style=solid, color=\"#c49c94\", arrowhead=odot, label=\"contains\""
+    ));
+    let json: serde_json::Value =
+        serde_json::from_str(&exporter.export_to_json(&graph).unwrap()).unwrap();
+    let relations = json["relations"].as_array().unwrap();
+    assert!(relations
+        .iter()
+        .any(|r| r["from"] == "Store" && r["to"] == "len" && r["relation_type"] == "Contains"));
+    assert!(relations.iter().any(|r| r["relation_type"] == "Imports"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn method_calls_become_call_edges() {
+    let dir = temp_dir("calls");
+    let store = "pub struct Store {\n    items: Vec<u32>,\n}\n\nimpl Store {\n    pub fn open() -> Store {\n        Store { items: Vec::new() }\n    }\n\n    pub fn flush(&mut self) {\n        self.compact();\n    }\n\n    fn compact(&mut self) {\n        self.items.clear();\n    }\n}\n";
+    let cache = "pub struct Cache;\n\nimpl Cache {\n    pub fn open() -> Cache {\n        Cache\n    }\n\n    pub fn flush(&mut self) {}\n}\n";
+    let service = "pub fn sync() {\n    let mut store = Store::open();\n    let _cache = Cache::open();\n    store.flush();\n}\n";
+    let graph = files_graph(&[
+        (&dir.join("store.rs"), store, FileType::Rust),
+        (&dir.join("cache.rs"), cache, FileType::Rust),
+        (&dir.join("service.rs"), service, FileType::Rust),
+    ]);
+
+    // одноимённые методы разных типов различаются по получателю вызова
+    let calls = |from: (&str, &str), to: (&str, &str)| {
+        let id = |(name, file): (&str, &str)| {
+            graph
+                .capsules
+                .values()
+                .find(|c| c.name == name && c.file_path == dir.join(file))
+                .unwrap()
+                .id
+        };
+        let (from, to) = (id(from), id(to));
+        graph
+            .relations
+            .iter()
+            .find(|r| r.from_id == from && r.to_id == to && r.relation_type == RelationType::Calls)
+            .and_then(|r| r.description.clone())
+    };
+    assert_eq!(
+        calls(("sync", "service.rs"), ("open", "store.rs")).as_deref(),
+        Some("calls Store::open()")
+    );
+    assert_eq!(
+        calls(("sync", "service.rs"), ("open", "cache.rs")).as_deref(),
+        Some("calls Cache::open()")
+    );
+    assert!(calls(("flush", "store.rs"), ("compact", "store.rs")).is_some());
+    // тип `store` неизвестен, а `flush` есть у обоих типов
+    assert!(calls(("sync", "service.rs"), ("flush", "store.rs")).is_none());
+    assert!(calls(("sync", "service.rs"), ("flush", "cache.rs")).is_none());
+
+    // вызовы — зависимости: циклы и связность учитывают структуру вызовов
+    let capsules = CapsuleGraphBuilder::new()
+        .update_capsule_dependencies(&graph.capsules, &graph.relations)
+        .unwrap();
+    let sync = capsules.values().find(|c| c.name == "sync").unwrap();
+    let opens: Vec<_> = capsules
+        .values()
+        .filter(|c| c.name == "open")
+        .map(|c| c.id)
+        .collect();
+    assert!(opens.iter().all(|id| sync.dependencies.contains(id)));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn qualified_and_ambiguous_calls_do_not_guess() {
+    let dir = temp_dir("qualified");
+    // `WalkDir::new` — внешний тип; одноимённый `new` файла к нему отношения не имеет
+    let audit = "pub struct AuditRecord {\n    hash: String,\n}\n\nimpl AuditRecord {\n    pub fn new() -> AuditRecord {\n        AuditRecord { hash: project_fingerprint() }\n    }\n}\n\npub struct Entry;\n\nimpl Entry {\n    pub fn new() -> Entry {\n        Entry\n    }\n}\n\npub fn project_fingerprint() -> String {\n    let walker = WalkDir::new(\".\");\n    format!(\"{:?}\", walker)\n}\n\npub fn build() -> u32 {\n    let _ = new();\n    1\n}\n";
+    let graph = files_graph(&[(&dir.join("audit.rs"), audit, FileType::Rust)]);
+
+    let find = |name: &str, line: usize| {
+        graph
+            .capsules
+            .values()
+            .find(|c| c.name == name && c.line_start == line)
+            .unwrap()
+            .id
+    };
+    let calls = |from: uuid::Uuid, to: uuid::Uuid| {
+        graph
+            .relations
+            .iter()
+            .any(|r| r.from_id == from && r.to_id == to && r.relation_type == RelationType::Calls)
+    };
+    let fingerprint = find("project_fingerprint", 19);
+    let (record_new, entry_new) = (find("new", 6), find("new", 14));
+    assert!(calls(record_new, fingerprint));
+    assert!(!calls(fingerprint, record_new));
+    assert!(!calls(fingerprint, entry_new));
+    // два локальных `new` — связь не угадывается
+    let build = find("build", 24);
+    assert!(!calls(build, record_new) && !calls(build, entry_new));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// методы с `impl`-владельцем размечает только разбор tree-sitter
+#[cfg(feature = "tree_sitter")]
+#[test]
+fn calls_on_untyped_values_need_the_method_type_in_scope() {
+    let dir = temp_dir("untyped");
+    // `count.parse()` — метод строки, а не единственный в проекте `Overlay::parse`
+    let overlay = "pub struct Overlay;\n\nimpl Overlay {\n    pub fn parse(&self, text: &str) -> u64 {\n        folded(text)\n    }\n}\n\nfn folded(text: &str) -> u64 {\n    let count = text.trim();\n    count.parse().unwrap_or(0)\n}\n\npub fn weigh(overlay: &Overlay, text: &str) -> u64 {\n    overlay.parse(text)\n}\n";
+    let graph = files_graph(&[(&dir.join("overlay.rs"), overlay, FileType::Rust)]);
+
+    let find = |name: &str| graph.capsules.values().find(|c| c.name == name).unwrap().id;
+    let calls = |from: uuid::Uuid, to: uuid::Uuid| {
+        graph
+            .relations
+            .iter()
+            .any(|r| r.from_id == from && r.to_id == to && r.relation_type == RelationType::Calls)
+    };
+    let (parse, folded, weigh) = (find("parse"), find("folded"), find("weigh"));
+    assert!(calls(parse, folded));
+    assert!(!calls(folded, parse));
+    assert!(calls(weigh, parse));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
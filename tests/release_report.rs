@@ -0,0 +1,108 @@
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("git");
+    assert!(status.success(), "git {:?}", args);
+}
+
+fn write(repo: &Path, file: &str, content: &str) {
+    let path = repo.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn release_report_lists_module_and_dependency_changes() {
+    let repo = std::env::temp_dir().join(format!("archlens-release-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&repo);
+    std::fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet"]);
+
+    write(
+        &repo,
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1.0\"\n",
+    );
+    write(
+        &repo,
+        "src/lib.rs",
+        "pub mod legacy;\n\npub fn run() -> u32 {\n    1\n}\n",
+    );
+    write(
+        &repo,
+        "src/legacy.rs",
+        "pub fn old_path() -> u32 {\n    2\n}\n",
+    );
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "--quiet", "-m", "initial"]);
+    git(&repo, &["tag", "v0.1.0"]);
+
+    write(
+        &repo,
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.2.0\"\n\n[dependencies]\nserde = \"1.0\"\nregex = \"1.11\"\n",
+    );
+    git(&repo, &["rm", "--quiet", "src/legacy.rs"]);
+    write(
+        &repo,
+        "src/lib.rs",
+        "pub mod scanner;\n\npub fn run() -> u32 {\n    1\n}\n",
+    );
+    write(
+        &repo,
+        "src/scanner.rs",
+        "pub struct Scanner;\n\nimpl Scanner {\n    pub fn scan(&self) -> usize {\n        0\n    }\n}\n",
+    );
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "--quiet", "-m", "feat: add scanner"]);
+    git(
+        &repo,
+        &["commit", "--quiet", "--allow-empty", "-m", "fix: typo"],
+    );
+    git(&repo, &["tag", "v0.2.0"]);
+
+    let output = Command::cargo_bin("archlens")
+        .unwrap()
+        .args(["release", repo.to_str().unwrap(), "v0.1.0", "v0.2.0"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(stdout.contains("## Architecture changes in v0.2.0"));
+    assert!(stdout.contains("v0.1.0…v0.2.0: 2 commits"));
+    assert!(stdout.contains("| Metric | v0.1.0 | v0.2.0 | Δ |"));
+    assert!(stdout.contains("### New modules"));
+    assert!(stdout.contains("`src/scanner.rs`"));
+    assert!(stdout.contains("feat: add scanner"));
+    assert!(stdout.contains("### Removed modules"));
+    assert!(stdout.contains("`src/legacy.rs`"));
+    assert!(stdout.contains("`regex` 1.11 (cargo)"));
+    assert!(stdout.contains("**Features**"));
+    assert!(stdout.contains("**Fixes**"));
+
+    // временные worktree не остаются зарегистрированными
+    let worktrees = Command::new("git")
+        .arg("-C")
+        .arg(&repo)
+        .args(["worktree", "list"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&worktrees.stdout).lines().count(),
+        1
+    );
+    let _ = std::fs::remove_dir_all(&repo);
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use archlens::remediation_backlog::{RemediationPlanner, BACKLOG_CSV_HEADER};
+use archlens::types::*;
+use chrono::Utc;
+use std::path::Path;
+use uuid::Uuid;
+
+fn capsule(name: &str, complexity: u32, warnings: &[(&str, Priority, &str)]) -> Capsule {
+    let id = Uuid::new_v4();
+    Capsule {
+        id,
+        file_path: format!("/p/src/{}.rs", name).into(),
+        line_end: 40,
+        size: 40,
+        complexity,
+        layer: Some("Core".into()),
+        warnings: warnings
+            .iter()
+            .map(|(category, level, message)| AnalysisWarning {
+                message: message.to_string(),
+                level: *level,
+                category: category.to_string(),
+                capsule_id: Some(id),
+                suggestion: None,
+            })
+            .collect(),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+fn graph_of(capsules: Vec<Capsule>, edges: &[(usize, usize)]) -> CapsuleGraph {
+    let ids: Vec<Uuid> = capsules.iter().map(|c| c.id).collect();
+    let relations = edges
+        .iter()
+        .map(|(from, to)| CapsuleRelation {
+            from_id: ids[*from],
+            to_id: ids[*to],
+            relation_type: RelationType::Uses,
+            strength: 1.0,
+            description: None,
+        })
+        .collect();
+    common::graph(capsules, relations)
+}
+
+#[test]
+fn backlog_orders_fixes_after_their_dependencies() {
+    let graph = graph_of(
+        vec![
+            capsule(
+                "handler",
+                4,
+                &[
+                    ("complexity", Priority::High, "Too complex, split it"),
+                    ("documentation", Priority::Low, "Missing docs"),
+                ],
+            ),
+            capsule(
+                "storage",
+                30,
+                &[
+                    ("complexity", Priority::Medium, "Deep nesting"),
+                    ("complexity", Priority::Medium, "Long method"),
+                ],
+            ),
+        ],
+        &[(0, 1)],
+    );
+
+    let planner = RemediationPlanner::new().with_root(Path::new("/p"));
+    let items = planner.plan(&graph);
+    assert_eq!(items.len(), 3);
+    let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+    assert_eq!(ids, vec!["R1", "R2", "R3"]);
+
+    let find = |capsule: &str, category: &str| {
+        items
+            .iter()
+            .find(|i| i.capsule == capsule && i.category == category)
+            .unwrap()
+    };
+    let storage = find("storage", "complexity");
+    let handler = find("handler", "complexity");
+    let docs = find("handler", "documentation");
+
+    // два предупреждения одной категории — одна задача, дороже одиночной
+    assert_eq!(storage.problem, "Deep nesting (+1 more)");
+    assert_eq!(storage.file, "src/storage.rs");
+    assert!(storage.effort_hours > handler.effort_hours);
+    // handler использует storage: сначала структурная правка storage
+    assert_eq!(handler.depends_on, vec![storage.id.clone()]);
+    assert!(storage.id < handler.id);
+    // документация — после рефакторинга той же капсулы
+    assert_eq!(docs.depends_on, vec![handler.id.clone()]);
+    assert!(docs.priority_score > handler.priority_score);
+    assert_eq!(docs.id, "R3");
+
+    let csv = planner.to_csv(&items);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some(BACKLOG_CSV_HEADER));
+    let handler_row = lines.find(|l| l.contains("split it")).unwrap();
+    assert!(
+        handler_row.contains("\"Too complex, split it\",handler,src/handler.rs,complexity,"),
+        "{}",
+        handler_row
+    );
+    assert!(handler_row.ends_with(&format!(
+        ",High,{:.2},{}",
+        handler.priority_score, storage.id
+    )));
+}
+
+#[test]
+fn export_backlog_command_writes_csv() {
+    use assert_cmd::prelude::*;
+    let output = std::process::Command::cargo_bin("archlens")
+        .unwrap()
+        .args(["export", "tests/fixtures/small_project", "backlog"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with(BACKLOG_CSV_HEADER));
+    assert!(stdout.lines().count() > 1);
+}
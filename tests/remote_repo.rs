@@ -0,0 +1,86 @@
+use archlens::remote_repo::{is_git_url, RemoteCheckout};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+#[test]
+fn git_urls_are_distinguished_from_paths() {
+    assert!(is_git_url("https://github.com/owner/repo.git"));
+    assert!(is_git_url("ssh://git@example.com/owner/repo"));
+    assert!(is_git_url("git@github.com:owner/repo.git"));
+    assert!(is_git_url("file:///srv/repo.git"));
+    assert!(!is_git_url("."));
+    assert!(!is_git_url("/home/user/project"));
+    assert!(!is_git_url("C:\\projects\\app"));
+}
+
+/// Репозиторий с одним коммитом во временном каталоге
+fn origin() -> PathBuf {
+    let origin = std::env::temp_dir().join(format!("archlens-origin-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(origin.join("src")).unwrap();
+    std::fs::write(origin.join("src/lib.rs"), "pub struct Store;\n").unwrap();
+    git(&origin, &["init", "--quiet"]);
+    git(&origin, &["add", "."]);
+    git(
+        &origin,
+        &[
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "init",
+        ],
+    );
+    origin
+}
+
+#[test]
+fn shallow_clone_is_removed_on_drop() {
+    let origin = origin();
+    let url = format!("file://{}", origin.display());
+    let checkout = RemoteCheckout::clone(&url).unwrap();
+    let cloned = checkout.path().to_path_buf();
+    assert!(cloned.join("src/lib.rs").exists());
+    drop(checkout);
+    assert!(!cloned.exists());
+
+    assert!(
+        RemoteCheckout::clone(&format!("file://{}", origin.join("missing").display())).is_err()
+    );
+    std::fs::remove_dir_all(&origin).unwrap();
+}
+
+#[test]
+fn clone_is_removed_when_command_fails() {
+    let origin = origin();
+    let temp = origin.with_extension("tmp");
+    std::fs::create_dir_all(&temp).unwrap();
+    // Неизвестный язык отчёта проверяется уже после клонирования
+    let status = Command::new(env!("CARGO_BIN_EXE_archlens"))
+        .args([
+            "export",
+            &format!("file://{}", origin.display()),
+            "ai_compact",
+        ])
+        .args(["--lang", "xx"])
+        .env("TMPDIR", &temp)
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(1));
+    assert_eq!(std::fs::read_dir(&temp).unwrap().count(), 0);
+    std::fs::remove_dir_all(&origin).unwrap();
+    std::fs::remove_dir_all(&temp).unwrap();
+}
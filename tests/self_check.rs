@@ -0,0 +1,78 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::self_check::*;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_self_check_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn capsule_range_and_new_cycles_are_checked() {
+    let dir = temp_dir("project");
+    write(
+        &dir,
+        "src/order.rs",
+        "use crate::customer::Customer;\n\npub struct Order {\n    pub buyer: Customer,\n}\n",
+    );
+    write(
+        &dir,
+        "src/customer.rs",
+        "use crate::order::Order;\n\npub struct Customer {\n    pub orders: Vec<Order>,\n}\n",
+    );
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let capsules = graph.capsules.len();
+    let relations = graph.relations.len();
+
+    let known = SelfCheckExpectations {
+        min_capsules: capsules,
+        max_capsules: capsules,
+        min_relations: relations,
+        max_relations: relations,
+        known_cycles: vec![vec!["Order".into(), "Customer".into()]],
+    };
+    let report = SelfCheckReport::evaluate(&graph, &known);
+    assert!(report.passed(), "{:?}", report.failures);
+    assert_eq!(report.cycle_groups, 1);
+    assert!(report.to_text().contains("✅"));
+
+    let strict = SelfCheckExpectations {
+        min_capsules: capsules + 1,
+        max_capsules: capsules + 10,
+        min_relations: 0,
+        max_relations: relations - 1,
+        known_cycles: Vec::new(),
+    };
+    let report = SelfCheckReport::evaluate(&graph, &strict);
+    assert!(!report.passed());
+    assert_eq!(report.new_cycles, [vec!["Customer", "Order"]]);
+    assert_eq!(report.failures.len(), 3);
+    assert!(report.failures.iter().any(|f| f.contains("число связей")));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn pinned_expectations_are_a_valid_range() {
+    let pinned = SelfCheckExpectations::pinned();
+    assert!(pinned.min_capsules <= pinned.max_capsules);
+    assert!(pinned.min_relations <= pinned.max_relations);
+    assert!(own_source_dir().is_some());
+}
+
+#[test]
+fn own_sources_pass_self_check() {
+    let (report, passed) = archlens::cli::handlers::run_self_check(None, false).unwrap();
+    assert!(passed, "{report}");
+}
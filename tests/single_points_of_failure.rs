@@ -0,0 +1,102 @@
+mod common;
+
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::exporter::Exporter;
+use archlens::graph::SinglePointsOfFailure;
+use archlens::types::*;
+use archlens::validation::ValidatorOptimizer;
+
+fn capsule(name: &str) -> Capsule {
+    Capsule {
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// Треугольник a-b-c связан с треугольником d-e-f только через hub;
+/// leaf висит на a, а структурная связь a–f связности не добавляет
+fn graph() -> CapsuleGraph {
+    let capsules = ["a", "b", "c", "hub", "d", "e", "f", "leaf"].map(capsule);
+    let [a, b, c, hub, d, e, f, leaf] = &capsules;
+    let relations = vec![
+        relation(a, b, RelationType::Calls),
+        relation(b, c, RelationType::Calls),
+        relation(c, a, RelationType::Calls),
+        relation(c, hub, RelationType::Calls),
+        relation(hub, d, RelationType::Imports),
+        relation(d, e, RelationType::Calls),
+        relation(e, f, RelationType::Calls),
+        relation(f, d, RelationType::Calls),
+        relation(leaf, a, RelationType::Calls),
+        relation(a, f, RelationType::Depends),
+    ];
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = relations;
+    graph
+}
+
+#[test]
+fn articulation_points_and_bridges_are_found() {
+    let failures = SinglePointsOfFailure::from_graph(&graph()).unwrap();
+
+    let points: Vec<(&str, usize)> = failures
+        .articulation_points
+        .iter()
+        .map(|p| (p.name.as_str(), p.separated))
+        .collect();
+    // a отделяет только лист — это не находка
+    assert_eq!(points, vec![("c", 3), ("hub", 3), ("d", 2)]);
+
+    let bridges: Vec<(&str, &str, usize)> = failures
+        .bridges
+        .iter()
+        .map(|b| (b.from.as_str(), b.to.as_str(), b.separated))
+        .collect();
+    assert_eq!(bridges, vec![("c", "hub", 4), ("hub", "d", 3)]);
+    assert_eq!(failures.bridges[1].relation_type, RelationType::Imports);
+}
+
+#[test]
+fn connected_graph_has_no_single_points_of_failure() {
+    let capsules = ["a", "b", "c"].map(capsule);
+    let [a, b, c] = &capsules;
+    let mut graph = CapsuleGraphBuilder::new().build_graph(&capsules).unwrap();
+    graph.relations = vec![
+        relation(a, b, RelationType::Calls),
+        relation(b, c, RelationType::Calls),
+        relation(c, a, RelationType::Calls),
+    ];
+    assert!(SinglePointsOfFailure::from_graph(&graph).is_none());
+}
+
+#[test]
+fn findings_reach_warnings_and_summary_json() {
+    let graph = ValidatorOptimizer::new()
+        .validate_and_optimize(&graph())
+        .unwrap();
+    let hub = graph.capsules.values().find(|c| c.name == "hub").unwrap();
+    assert!(hub
+        .warnings
+        .iter()
+        .any(|w| w.category == "connectivity" && w.message.contains("disconnects 3")));
+    let c = graph.capsules.values().find(|c| c.name == "c").unwrap();
+    assert!(c
+        .warnings
+        .iter()
+        .any(|w| w.category == "connectivity" && w.message.contains("'c' -> 'hub'")));
+
+    let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    let failures = &summary["single_points_of_failure"];
+    assert_eq!(failures["articulation_points"][1]["name"], "hub");
+    assert_eq!(failures["bridges"].as_array().unwrap().len(), 2);
+}
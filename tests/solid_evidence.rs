@@ -0,0 +1,173 @@
+mod common;
+
+use archlens::capsule_constructor::CapsuleConstructor;
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::cli::handlers::build_project_graph;
+use archlens::exporter::Exporter;
+use archlens::parser_ast::{is_stub_body, BodyMetrics, ParserAST};
+use archlens::types::*;
+use archlens::validation::{InterfaceUsageReport, SolidAnalyzer, SolidPrinciple};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_solid_evidence_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Граф одного файла без метрик графа: их расчёт на плотном графе одного файла долог
+fn file_graph(path: &Path, source: &str) -> CapsuleGraph {
+    std::fs::write(path, source).unwrap();
+    let elements = ParserAST::new()
+        .unwrap()
+        .parse_file(path, source, &FileType::Rust)
+        .unwrap();
+    let capsules = CapsuleConstructor::new()
+        .create_capsules(&elements, path)
+        .unwrap();
+    let relations = CapsuleGraphBuilder::new()
+        .build_advanced_relations(&capsules)
+        .unwrap();
+    common::graph(capsules, relations)
+}
+
+fn messages(principle: SolidPrinciple, graph: &CapsuleGraph) -> Vec<String> {
+    SolidAnalyzer::new(principle)
+        .analyze_graph(graph)
+        .unwrap()
+        .into_iter()
+        .map(|w| w.message)
+        .collect()
+}
+
+#[test]
+fn stub_bodies_are_recognized() {
+    let short = BodyMetrics {
+        statements: 1,
+        max_nesting: 0,
+    };
+    assert!(is_stub_body("fn save(&self) {\n    todo!()\n}", &short));
+    assert!(is_stub_body(
+        "def save(self):\n    raise NotImplementedError",
+        &short
+    ));
+    assert!(is_stub_body(
+        "save() {\n  throw new Error(\"Not implemented\");\n}",
+        &short
+    ));
+    assert!(!is_stub_body(
+        "fn save(&self) {\n    self.flush()\n}",
+        &short
+    ));
+    let long = BodyMetrics {
+        statements: 6,
+        max_nesting: 1,
+    };
+    assert!(!is_stub_body(
+        "fn save(&self) {\n    if x { todo!() }\n}",
+        &long
+    ));
+}
+
+#[test]
+fn srp_names_the_members_of_an_overloaded_type() {
+    let dir = temp_dir("srp");
+    let methods: String = (0..22)
+        .map(|i| format!("    pub fn op{i}(&self) -> u32 {{\n        {i}\n    }}\n"))
+        .collect();
+    let graph = file_graph(
+        &dir.join("god.rs"),
+        &format!("pub struct Manager {{\n    small: Small,\n}}\n\nimpl Manager {{\n{methods}}}\n\npub struct Small {{}}\n\nimpl Small {{\n    pub fn one(&self) {{}}\n}}\n"),
+    );
+    let found = messages(SolidPrinciple::SingleResponsibility, &graph);
+    assert_eq!(found.len(), 1, "{found:?}");
+    assert!(
+        found[0].starts_with(
+            "Possible SRP violation: Manager has 22 methods and 1 collaborators; most complex members: op0, "
+        ),
+        "{}",
+        found[0]
+    );
+    assert!(found[0].ends_with(" and 17 more"), "{}", found[0]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn isp_lists_stub_implementations() {
+    let dir = temp_dir("isp");
+    std::fs::write(
+        dir.join("store.rs"),
+        "pub trait Store {\n    fn load(&self) -> u32;\n    fn save(&self, value: u32);\n}\n\npub struct ReadOnly {}\n\nimpl Store for ReadOnly {\n    fn load(&self) -> u32 {\n        1\n    }\n    fn save(&self, value: u32) {\n        todo!()\n    }\n}\n\npub struct Memory {}\n\nimpl Store for Memory {\n    fn load(&self) -> u32 {\n        2\n    }\n    fn save(&self, value: u32) {\n        let _ = value;\n    }\n}\n",
+    )
+    .unwrap();
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let found = messages(SolidPrinciple::InterfaceSegregation, &graph);
+    assert_eq!(
+        found,
+        ["Possible ISP violation: Store forces stub implementations; stubs: ReadOnly::save"]
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn dip_flags_dependencies_on_concrete_implementations() {
+    let dir = temp_dir("dip");
+    std::fs::write(
+        dir.join("storage.rs"),
+        "pub trait Storage {\n    fn put(&self, key: &str);\n}\n\npub struct FileStorage {}\n\nimpl Storage for FileStorage {\n    fn put(&self, key: &str) {\n        let _ = key;\n    }\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("service.rs"),
+        "pub struct Service {}\n\nimpl Service {\n    pub fn persist(&self, storage: &FileStorage) {\n        storage.put(\"k\");\n    }\n}\n",
+    )
+    .unwrap();
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let found = messages(SolidPrinciple::DependencyInversion, &graph);
+    assert_eq!(
+        found,
+        ["Possible DIP violation: Service depends on concrete FileStorage instead of Storage; members: persist"]
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn interface_usage_lists_unused_methods_per_implementor() {
+    let dir = temp_dir("usage");
+    let graph = file_graph(
+        &dir.join("codec.rs"),
+        "pub trait Codec {\n    fn encode(&self) -> u8;\n    fn decode(&self) -> u8;\n    fn flush(&self);\n    fn name(&self) -> &str {\n        \"codec\"\n    }\n}\n\npub struct Json {}\n\nimpl Codec for Json {\n    fn encode(&self) -> u8 {\n        1\n    }\n    fn decode(&self) -> u8 {\n        2\n    }\n    fn flush(&self) {\n        println!(\"flush\");\n    }\n}\n\npub struct Raw {}\n\nimpl Codec for Raw {\n    fn encode(&self) -> u8 {\n        3\n    }\n    fn decode(&self) -> u8 {\n        unimplemented!()\n    }\n    fn flush(&self) {}\n}\n",
+    );
+
+    let report = InterfaceUsageReport::from_graph(&graph).unwrap();
+    assert_eq!(report.interfaces.len(), 1);
+    let codec = &report.interfaces[0];
+    // метод с реализацией по умолчанию не обязателен
+    assert_eq!(codec.methods, ["encode", "decode", "flush"]);
+    assert!(codec.fat);
+    let usage: Vec<(&str, usize, &[String])> = codec
+        .implementors
+        .iter()
+        .map(|u| (u.implementor.as_str(), u.used, u.unused_methods.as_slice()))
+        .collect();
+    assert_eq!(
+        usage,
+        [
+            ("Json", 3, &[][..]),
+            ("Raw", 1, &["decode".to_string(), "flush".to_string()][..])
+        ]
+    );
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## SOLID\n"), "{compact}");
+    assert!(compact
+        .contains("- Fat interface Codec (3 methods)\n  - Raw uses 1/3; unused: decode, flush\n"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,101 @@
+use archlens::capsule_constructor::CapsuleConstructor;
+use archlens::parser_ast::ParserAST;
+use archlens::types::*;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+const SOURCE: &str = r#"pub struct Circle {
+    radius: f64,
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+
+pub struct Square {
+    side: f64,
+}
+
+impl Square {
+    pub fn new(side: f64) -> Self {
+        Self { side }
+    }
+}
+"#;
+
+fn capsules(path: &Path, source: &str) -> Vec<Capsule> {
+    let elements = ParserAST::new()
+        .unwrap()
+        .parse_file(path, source, &FileType::Rust)
+        .unwrap();
+    CapsuleConstructor::new()
+        .create_capsules(&elements, path)
+        .unwrap()
+}
+
+fn ids(capsules: &[Capsule]) -> BTreeSet<(String, uuid::Uuid)> {
+    capsules.iter().map(|c| (c.name.clone(), c.id)).collect()
+}
+
+#[test]
+fn capsule_ids_are_stable_across_runs() {
+    let path = Path::new("src/shapes.rs");
+    let first = capsules(path, SOURCE);
+    let second = capsules(path, SOURCE);
+    assert_eq!(ids(&first), ids(&second));
+
+    // одноимённые методы разных типов различаются
+    let news: BTreeSet<_> = first
+        .iter()
+        .filter(|c| c.name == "new")
+        .map(|c| c.id)
+        .collect();
+    assert_eq!(news.len(), 2);
+
+    let circle = first.iter().find(|c| c.name == "Circle").unwrap();
+    assert_eq!(
+        circle.id,
+        stable_capsule_id(path, "Circle", circle.capsule_type)
+    );
+    // правка тела не меняет идентификатор, другой файл — меняет
+    let edited = capsules(
+        path,
+        &SOURCE.replace("Self { side }", "Self { side: side * 2.0 }"),
+    );
+    assert_eq!(ids(&first), ids(&edited));
+    let moved = capsules(Path::new("src/geometry.rs"), SOURCE);
+    assert!(ids(&first).is_disjoint(&ids(&moved)));
+}
+
+#[test]
+fn capsule_ids_do_not_depend_on_checkout_location() {
+    let elements = |path: &Path| {
+        ParserAST::new()
+            .unwrap()
+            .parse_file(path, SOURCE, &FileType::Rust)
+            .unwrap()
+    };
+    let checkout = |root: &str| {
+        let root = Path::new(root);
+        let path = root.join("src/shapes.rs");
+        CapsuleConstructor::new()
+            .with_project_root(root)
+            .create_capsules(&elements(&path), &path)
+            .unwrap()
+    };
+    let first = checkout("/home/alice/shapes");
+    let second = checkout("/tmp/archlens-remote-1/");
+    assert_eq!(ids(&first), ids(&second));
+    // идентификатор совпадает с вычисленным от пути внутри проекта
+    let circle = first.iter().find(|c| c.name == "Circle").unwrap();
+    assert_eq!(
+        circle.id,
+        stable_capsule_id(Path::new("src/shapes.rs"), "Circle", circle.capsule_type)
+    );
+    assert_eq!(
+        ids(&first),
+        ids(&capsules(Path::new("src/shapes.rs"), SOURCE))
+    );
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::state_machines::{StateMachineDetector, StateMachineKind, StateTransition};
+use archlens::types::*;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const DOOR: &str = r#"#[derive(Default)]
+pub enum DoorState {
+    #[default]
+    Closed,
+    Open,
+    Locked,
+}
+
+pub enum Event {
+    Push,
+    Lock,
+    Unlock,
+}
+
+impl Door {
+    pub fn handle(&mut self, event: Event) {
+        self.state = match (self.state, event) {
+            (DoorState::Closed, Event::Push) => DoorState::Open,
+            (DoorState::Open, Event::Push) => DoorState::Closed,
+            (DoorState::Closed, Event::Lock) => {
+                audit();
+                DoorState::Locked
+            }
+            (DoorState::Locked, Event::Unlock) => DoorState::Closed,
+            (state, _) => state,
+        };
+    }
+}
+
+impl Event {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Push => "push",
+            Self::Lock => "lock",
+            Self::Unlock => "unlock",
+        }
+    }
+}
+"#;
+
+fn transition(from: &str, to: &str, event: &str) -> StateTransition {
+    StateTransition {
+        from: from.into(),
+        to: to.into(),
+        event: Some(event.into()),
+    }
+}
+
+fn capsule(file: &Path, name: &str, kind: CapsuleType, lines: (usize, usize)) -> Capsule {
+    Capsule {
+        capsule_type: kind,
+        file_path: file.to_path_buf(),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        complexity: 2,
+        layer: Some("Core".into()),
+        quality_score: 0.5,
+        created_at: Some(Utc::now().to_rfc3339()),
+        ..common::capsule(name)
+    }
+}
+
+#[test]
+fn rust_enum_match_is_detected_with_events() {
+    let machines = StateMachineDetector::new().detect_in_source(Path::new("door.rs"), DOOR);
+
+    // Event сопоставляется в match, но переходов между своими вариантами не имеет
+    assert_eq!(machines.len(), 1);
+    let door = &machines[0];
+    assert_eq!(door.name, "DoorState");
+    assert_eq!(door.kind, StateMachineKind::EnumMatch);
+    assert_eq!(door.line, 2);
+    assert_eq!(door.initial.as_deref(), Some("Closed"));
+    assert_eq!(
+        door.transitions,
+        vec![
+            transition("Closed", "Locked", "Lock"),
+            transition("Closed", "Open", "Push"),
+            transition("Locked", "Closed", "Unlock"),
+            transition("Open", "Closed", "Push"),
+        ]
+    );
+    let mermaid = door.to_mermaid();
+    assert!(mermaid.starts_with("stateDiagram-v2\n    [*] --> Closed\n"));
+    assert!(mermaid.contains("    Closed --> Locked : Lock\n"));
+}
+
+#[test]
+fn xstate_and_switch_machines_are_detected() {
+    let source = r#"import { createMachine } from "xstate";
+
+export enum Phase { Draft, Review, Published }
+
+export function next(phase: Phase): Phase {
+  switch (phase) {
+    case Phase.Draft:
+      return Phase.Review;
+    case Phase.Review:
+      return approved ? Phase.Published : Phase.Draft;
+    default:
+      return phase;
+  }
+}
+
+export const toggle = createMachine({
+  id: "toggle",
+  initial: "inactive",
+  states: {
+    inactive: { on: { TOGGLE: "active" } },
+    active: {
+      on: {
+        TOGGLE: { target: "inactive" },
+        FINISH: [{ target: "done", guard: "ok" }],
+      },
+    },
+    done: { type: "final" },
+  },
+});
+"#;
+    let machines = StateMachineDetector::new().detect_in_source(Path::new("flow.ts"), source);
+    assert_eq!(machines.len(), 2);
+
+    let phase = &machines[0];
+    assert_eq!(phase.name, "Phase");
+    assert_eq!(phase.states, vec!["Draft", "Review", "Published"]);
+    assert_eq!(phase.initial.as_deref(), Some("Draft"));
+    assert_eq!(phase.final_states(), vec!["Published".to_string()]);
+    assert_eq!(phase.transitions.len(), 3);
+
+    let toggle = &machines[1];
+    assert_eq!(toggle.kind, StateMachineKind::XState);
+    assert_eq!(toggle.name, "toggle");
+    assert_eq!(toggle.line, 16);
+    assert_eq!(toggle.initial.as_deref(), Some("inactive"));
+    assert_eq!(
+        toggle.transitions,
+        vec![
+            transition("active", "done", "FINISH"),
+            transition("active", "inactive", "TOGGLE"),
+            transition("inactive", "active", "TOGGLE"),
+        ]
+    );
+    assert!(toggle.to_mermaid().contains("    done --> [*]\n"));
+}
+
+#[test]
+fn html_report_shows_state_diagram_on_owning_capsule() {
+    let root = std::env::temp_dir().join(format!("archlens-states-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let file: PathBuf = root.join("door.rs");
+    std::fs::write(&file, DOOR).unwrap();
+
+    let state = capsule(&file, "DoorState", CapsuleType::Enum, (1, 7));
+    let handler = capsule(&file, "handle", CapsuleType::Method, (24, 36));
+    let state_id = state.id;
+    let graph = CapsuleGraph {
+        capsules: [state, handler].into_iter().map(|c| (c.id, c)).collect(),
+        relations: vec![],
+        layers: HashMap::new(),
+        metrics: GraphMetrics {
+            total_capsules: 2,
+            complexity_average: 2.0,
+            cohesion_index: 1.0,
+            cyclomatic_complexity: 2,
+            depth_levels: 1,
+            ..Default::default()
+        },
+        created_at: Utc::now(),
+        previous_analysis: None,
+    };
+
+    let machines = StateMachineDetector::new().detect(&graph);
+    let html = Exporter::new().export_to_interactive_html(&graph).unwrap();
+    let _ = std::fs::remove_dir_all(&root);
+
+    assert_eq!(machines.keys().collect::<Vec<_>>(), vec![&state_id]);
+    assert!(html.contains("mermaid.initialize"));
+    assert!(html.contains("Автомат состояний: DoorState"));
+    assert!(html.contains("Closed --&gt; Open : Push"));
+    assert_eq!(html.matches("class=\"state-machine\"").count(), 1);
+}
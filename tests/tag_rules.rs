@@ -0,0 +1,148 @@
+mod common;
+
+use archlens::cli::handlers::{apply_tags, build_project_graph};
+use archlens::constructor::{CapsuleAnalyzer, TagRuleConfig, TagRules};
+use archlens::exporter::Exporter;
+use archlens::types::*;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_tag_rules_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn rules(json: &str) -> TagRules {
+    TagRuleConfig::from_json(json).unwrap().compile().unwrap()
+}
+
+fn tags_of<'a>(graph: &'a CapsuleGraph, name: &str) -> &'a [String] {
+    &graph
+        .capsules
+        .values()
+        .find(|c| c.name == name)
+        .unwrap_or_else(|| panic!("{name}"))
+        .tags
+}
+
+/// Проект с платёжным модулем и функцией авторизации в общем файле
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    std::fs::create_dir_all(dir.join("src/payments")).unwrap();
+    std::fs::write(
+        dir.join("src/payments/charge.rs"),
+        "pub fn charge(amount: u64) -> u64 {\n    amount\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("src/session.rs"),
+        "pub fn login(token: &str) -> bool {\n    verify_jwt(token)\n}\n\npub fn logout() -> bool {\n    true\n}\n\nfn verify_jwt(token: &str) -> bool {\n    !token.is_empty()\n}\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.join(".archlens")).unwrap();
+    std::fs::write(
+        dir.join(".archlens/tags.json"),
+        r#"{"rules": [
+            {"glob": "src/payments/**", "tags": ["payments"]},
+            {"content": "verify_jwt\\(", "tags": ["auth"]},
+            {"glob": "src/**/*.rs", "content": "amount", "tags": ["payments", "money"]}
+        ]}"#,
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn glob_and_content_rules_tag_capsules() {
+    let dir = project("apply");
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let charge = tags_of(&graph, "charge");
+    assert!(charge.ends_with(&["payments".to_string(), "money".to_string()]));
+    assert_eq!(charge.iter().filter(|t| *t == "payments").count(), 1);
+    // содержимое проверяется по строкам самой капсулы, а не всего файла
+    assert!(tags_of(&graph, "login").contains(&"auth".to_string()));
+    assert!(!tags_of(&graph, "logout").contains(&"auth".to_string()));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&Exporter::new().export_to_json(&graph).unwrap()).unwrap();
+    let exported: Vec<&serde_json::Value> = json["layers"]
+        .as_object()
+        .unwrap()
+        .values()
+        .flat_map(|capsules| capsules.as_array().unwrap())
+        .collect();
+    let charge = exported.iter().find(|c| c["name"] == "charge").unwrap();
+    let tags = charge["tags"].as_array().unwrap();
+    assert!(tags.contains(&serde_json::json!("payments")));
+    assert!(tags.contains(&serde_json::json!("money")));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn diagrams_can_be_filtered_by_tag() {
+    let dir = project("filter");
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let total = graph.capsules.len();
+
+    let auth = apply_tags(graph.clone(), &["auth".to_string()]);
+    assert!(!auth.capsules.is_empty());
+    assert!(auth.capsules.len() < total);
+    assert!(auth
+        .capsules
+        .values()
+        .all(|c| c.tags.contains(&"auth".to_string())));
+    assert!(auth
+        .relations
+        .iter()
+        .all(|r| auth.capsules.contains_key(&r.from_id) && auth.capsules.contains_key(&r.to_id)));
+
+    assert_eq!(apply_tags(graph, &[]).capsules.len(), total);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn rules_apply_without_duplicating_tags() {
+    let dir = temp_dir("dedup");
+    let file = dir.join("src/billing.rs");
+    std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+    std::fs::write(&file, "pub fn bill() {}\n").unwrap();
+    let mut capsules = vec![Capsule {
+        id: uuid::Uuid::new_v4(),
+        file_path: file,
+        line_end: 1,
+        size: 1,
+        tags: vec!["payments".into()],
+        ..common::capsule("bill")
+    }];
+    let rules = rules(r#"{"rules": [{"glob": "src/*.rs", "tags": ["payments"]}]}"#);
+    assert_eq!(
+        CapsuleAnalyzer::apply_tag_rules(&dir, &mut capsules, &rules),
+        0
+    );
+    assert_eq!(capsules[0].tags, ["payments"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn invalid_rules_are_reported() {
+    for json in [
+        r#"{"rules": [{"tags": ["x"]}]}"#,
+        r#"{"rules": [{"glob": "src/**", "tags": []}]}"#,
+        r#"{"rules": [{"content": "(", "tags": ["x"]}]}"#,
+    ] {
+        assert!(
+            TagRuleConfig::from_json(json).unwrap().compile().is_err(),
+            "{json}"
+        );
+    }
+    assert!(TagRuleConfig::from_json("{\"rules\": 1}").is_err());
+    assert!(TagRules::for_project(Path::new("/nonexistent"))
+        .unwrap()
+        .is_none());
+}
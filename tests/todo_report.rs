@@ -0,0 +1,182 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::todo_report::*;
+use archlens::types::*;
+use std::path::Path;
+use std::process::Command;
+use uuid::Uuid;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?}");
+}
+
+fn capsule(name: &str, file: &Path, lines: (usize, usize), layer: &str) -> Capsule {
+    Capsule {
+        file_path: file.to_path_buf(),
+        line_start: lines.0,
+        line_end: lines.1,
+        size: lines.1 - lines.0 + 1,
+        layer: Some(layer.into()),
+        ..common::capsule(name)
+    }
+}
+
+fn graph_of(capsules: &[Capsule]) -> CapsuleGraph {
+    common::graph(capsules.to_vec(), vec![])
+}
+
+const STORE_RS: &str = "// TODO: split the store module\n\
+pub struct Store;\n\
+impl Store {\n\
+    pub fn save(&self) {\n\
+        // FIXME(ann): retries are not bounded\n\
+        let marker = \"TODO inside a string\";\n\
+    }\n\
+    /* HACK: temporary workaround */\n\
+}\n";
+
+#[test]
+fn markers_are_found_only_in_comments() {
+    let markers = scan_markers(STORE_RS);
+    let found: Vec<(usize, TodoKind, &str)> = markers
+        .iter()
+        .map(|m| (m.line, m.kind, m.text.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (1, TodoKind::Todo, "split the store module"),
+            (5, TodoKind::Fixme, "retries are not bounded"),
+            (8, TodoKind::Hack, "temporary workaround"),
+        ]
+    );
+    assert_eq!(scan_markers("# XXX python style\n-- TODO sql\n").len(), 2);
+}
+
+#[test]
+fn blame_times_are_parsed_per_line() {
+    let porcelain = "1111111111111111111111111111111111111111 1 1 2\n\
+                     author Ann\n\
+                     author-time 1700000000\n\
+                     \tfn a() {}\n\
+                     1111111111111111111111111111111111111111 2 2\n\
+                     author Ann\n\
+                     author-time 1700000000\n\
+                     \tfn b() {}\n\
+                     2222222222222222222222222222222222222222 3 3 1\n\
+                     author Bob\n\
+                     author-time 1710000000\n\
+                     \tfn c() {}\n";
+    assert_eq!(
+        parse_blame_times(porcelain),
+        [
+            Some(1_700_000_000),
+            Some(1_700_000_000),
+            Some(1_710_000_000)
+        ]
+    );
+}
+
+#[test]
+fn markers_go_to_innermost_capsule_and_aggregate_per_layer() {
+    let dir = std::env::temp_dir().join(format!("archlens-todos-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("domain")).unwrap();
+    std::fs::create_dir_all(dir.join("api")).unwrap();
+    let store = dir.join("domain/store.rs");
+    let routes = dir.join("api/routes.rs");
+    std::fs::write(&store, STORE_RS).unwrap();
+    std::fs::write(&routes, "fn index() {}\n// TODO: pagination\n").unwrap();
+
+    let mut capsules = vec![
+        capsule("Store", &store, (2, 9), "Domain"),
+        capsule("save", &store, (4, 7), "Domain"),
+        capsule("index", &routes, (1, 1), "Api"),
+    ];
+    assert_eq!(annotate_todos(&dir, &mut capsules, false), 4);
+    let kinds =
+        |c: &Capsule| -> Vec<TodoKind> { capsule_todos(c).iter().map(|m| m.kind).collect() };
+    // строка 1 вне капсул — самой широкой капсуле файла
+    assert_eq!(kinds(&capsules[0]), [TodoKind::Todo, TodoKind::Hack]);
+    assert_eq!(kinds(&capsules[1]), [TodoKind::Fixme]);
+    assert_eq!(kinds(&capsules[2]), [TodoKind::Todo]);
+
+    let now = 1_710_000_000;
+    let mut markers = capsule_todos(&capsules[1]);
+    markers[0].authored_at = Some(now - 30 * 86_400);
+    capsules[1].metadata.insert(
+        TODOS_KEY.to_string(),
+        serde_json::to_string(&markers).unwrap(),
+    );
+    let graph = graph_of(&capsules);
+    let report = TodoReport::from_graph_at(&graph, now).unwrap();
+    assert_eq!(report.items.len(), 4);
+    let oldest = &report.items[0];
+    assert_eq!(
+        (oldest.capsule.as_str(), oldest.line, oldest.age_days),
+        ("save", 5, Some(30))
+    );
+    assert_eq!(oldest.file, Path::new("domain/store.rs"));
+    assert_eq!(report.layers[0].layer, "Domain");
+    assert_eq!(report.layers[0].total, 3);
+    assert_eq!(report.layers[0].by_kind[&TodoKind::Fixme], 1);
+    assert_eq!(report.layers[0].oldest_days, Some(30));
+    assert_eq!(
+        (report.layers[1].layer.as_str(), report.layers[1].total),
+        ("Api", 1)
+    );
+
+    let text = todos_to_text(Some(&report));
+    assert!(text.contains("Domain — 3 (TODO 1, FIXME 1, HACK 1)"));
+    assert!(text.contains("[FIXME] save — domain/store.rs:5 (30 days): retries are not bounded"));
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains("## TODO/FIXME Debt (4 markers)"));
+    let summary = Exporter::new().export_to_ai_summary_json(&graph).unwrap();
+    assert_eq!(summary["todos"]["total"], 4);
+    assert_eq!(summary["todos"]["layers"][0]["layer"], "Domain");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn marker_age_comes_from_git_blame() {
+    let repo = std::env::temp_dir().join(format!("archlens-todos-git-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&repo).unwrap();
+    let file = repo.join("lib.rs");
+    std::fs::write(&file, "fn run() {\n    // TODO: old debt\n}\n").unwrap();
+    git(&repo, &["init", "--quiet"]);
+    git(&repo, &["add", "."]);
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(&repo)
+        .args(["-c", "user.name=dev", "-c", "user.email=dev@example.com"])
+        .args(["commit", "--quiet", "-m", "init"])
+        .env("GIT_AUTHOR_DATE", "2020-01-01T00:00:00Z")
+        .env("GIT_COMMITTER_DATE", "2020-01-01T00:00:00Z")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let mut capsules = vec![capsule("run", &file, (1, 3), "Core")];
+    assert_eq!(annotate_todos(&repo, &mut capsules, true), 1);
+    let markers = capsule_todos(&capsules[0]);
+    assert_eq!(markers[0].authored_at, Some(1_577_836_800));
+
+    let report =
+        TodoReport::from_graph_at(&graph_of(&capsules), 1_577_836_800 + 10 * 86_400).unwrap();
+    assert_eq!(report.items[0].age_days, Some(10));
+    std::fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn graph_without_markers_has_no_report() {
+    let graph = graph_of(&[capsule("a", Path::new("/nonexistent/a.rs"), (1, 2), "Core")]);
+    assert!(TodoReport::from_graph(&graph).is_none());
+    assert_eq!(todos_to_text(None), "Маркеров TODO/FIXME не найдено\n");
+}
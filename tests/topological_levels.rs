@@ -0,0 +1,128 @@
+mod common;
+
+use archlens::exporter::Exporter;
+use archlens::graph::{MetricsCalculator, TOPOLOGICAL_LEVEL_KEY};
+use archlens::types::*;
+
+fn capsule(name: &str) -> Capsule {
+    Capsule {
+        complexity: 2,
+        ..common::capsule(name)
+    }
+}
+
+fn relation(from: &Capsule, to: &Capsule, relation_type: RelationType) -> CapsuleRelation {
+    CapsuleRelation {
+        from_id: from.id,
+        to_id: to.id,
+        relation_type,
+        strength: 1.0,
+        description: None,
+    }
+}
+
+/// handler → service → (repo ⇄ cache) → db; config связан с db только
+/// структурно, main — над handler
+fn graph() -> CapsuleGraph {
+    let capsules = [
+        "main", "handler", "service", "repo", "cache", "db", "config",
+    ]
+    .map(capsule);
+    let [main, handler, service, repo, cache, db, config] = &capsules;
+    let relations = vec![
+        relation(main, handler, RelationType::Calls),
+        relation(handler, service, RelationType::Calls),
+        relation(handler, db, RelationType::Imports),
+        relation(service, repo, RelationType::Calls),
+        relation(repo, cache, RelationType::Calls),
+        relation(cache, repo, RelationType::Calls),
+        relation(cache, db, RelationType::Calls),
+        relation(config, db, RelationType::Depends),
+        relation(service, handler, RelationType::Contains),
+    ];
+    common::graph(capsules.to_vec(), relations)
+}
+
+fn levels_by_name(graph: &CapsuleGraph) -> Vec<(String, usize)> {
+    let levels = MetricsCalculator::new().calculate_topological_levels(graph);
+    let mut named: Vec<(String, usize)> = levels
+        .iter()
+        .map(|(id, level)| (graph.capsules[id].name.clone(), *level))
+        .collect();
+    named.sort();
+    named
+}
+
+#[test]
+fn cycle_members_share_a_level_above_their_dependencies() {
+    let levels = levels_by_name(&graph());
+    let expected = [
+        ("cache", 1),
+        ("config", 0),
+        ("db", 0),
+        ("handler", 3),
+        ("main", 4),
+        ("repo", 1),
+        ("service", 2),
+    ];
+    assert_eq!(
+        levels,
+        expected
+            .iter()
+            .map(|(n, l)| (n.to_string(), *l))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn levels_are_stored_on_capsules() {
+    let mut graph = graph();
+    assert_eq!(
+        MetricsCalculator::new().assign_topological_levels(&mut graph),
+        5
+    );
+    let main = graph.capsules.values().find(|c| c.name == "main").unwrap();
+    assert_eq!(main.metadata[TOPOLOGICAL_LEVEL_KEY], "4");
+    assert!(graph
+        .capsules
+        .values()
+        .all(|c| c.metadata.contains_key(TOPOLOGICAL_LEVEL_KEY)));
+}
+
+#[test]
+fn mermaid_lists_levels_from_top_to_foundation() {
+    let graph = graph();
+    let mermaid = Exporter::new().export_to_mermaid(&graph).unwrap();
+    assert_eq!(mermaid, Exporter::new().export_to_mermaid(&graph).unwrap());
+    let position = |text: &str| {
+        mermaid
+            .find(text)
+            .unwrap_or_else(|| panic!("{text}\n{mermaid}"))
+    };
+    let headers: Vec<usize> = (0..5)
+        .rev()
+        .map(|level| position(&format!("subgraph level_{level}[\"Уровень {level}\"]")))
+        .collect();
+    assert!(headers.windows(2).all(|w| w[0] < w[1]), "{mermaid}");
+    assert!(position("main[") < headers[1]);
+    assert!(position("cache[") > headers[3] && position("repo[") > headers[3]);
+    assert!(position("cache[") < headers[4] && position("config[") > headers[4]);
+}
+
+#[test]
+fn dependency_depth_condenses_cycles() {
+    let calculator = MetricsCalculator::new();
+    // Глубина не зависит от того, с какой капсулы цикла начат обход
+    for _ in 0..5 {
+        let graph = graph();
+        let id = |name: &str| graph.capsules.values().find(|c| c.name == name).unwrap().id;
+        let depth = |name: &str| calculator.calculate_dependency_depth(id(name), &graph.relations);
+        assert_eq!(depth("repo"), 1);
+        assert_eq!(depth("cache"), 1);
+        assert_eq!(depth("main"), 3);
+        let metrics = calculator
+            .calculate_advanced_metrics(&graph.capsules, &graph.relations)
+            .unwrap();
+        assert_eq!(metrics.depth_levels, 3);
+    }
+}
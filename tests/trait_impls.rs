@@ -0,0 +1,191 @@
+mod common;
+
+use archlens::capsule_constructor::CapsuleConstructor;
+use archlens::capsule_graph_builder::CapsuleGraphBuilder;
+use archlens::cli::handlers::build_project_graph;
+use archlens::parser_ast::{
+    ASTElementType, ParserAST, IMPLEMENTS_KEY, IMPL_FOR_KEY, IMPL_TRAIT_KEY, TRAIT_METHODS_KEY,
+};
+use archlens::types::*;
+use archlens::validation::{SolidAnalyzer, SolidPrinciple};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_trait_impls_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const SHAPES: &str = r#"use std::fmt;
+
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String;
+    fn scale(&mut self, factor: f64) {
+        let _ = factor;
+    }
+}
+
+pub struct Circle {
+    radius: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.14 * self.radius * self.radius
+    }
+    fn name(&self) -> String {
+        "circle".to_string()
+    }
+}
+
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "circle")
+    }
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+"#;
+
+#[test]
+fn parser_records_trait_implementations() {
+    let mut parser = ParserAST::new().unwrap();
+    let elements = parser
+        .parse_file(Path::new("shapes.rs"), SHAPES, &FileType::Rust)
+        .unwrap();
+
+    let shape = elements
+        .iter()
+        .find(|e| e.name == "Shape" && e.element_type == ASTElementType::Interface)
+        .unwrap();
+    assert_eq!(shape.metadata[TRAIT_METHODS_KEY], "3");
+
+    let circle = elements.iter().find(|e| e.name == "Circle").unwrap();
+    assert_eq!(circle.metadata[IMPLEMENTS_KEY], "Display,Shape");
+
+    let area = elements.iter().find(|e| e.name == "area").unwrap();
+    assert_eq!(area.metadata[IMPL_TRAIT_KEY], "Shape");
+    assert_eq!(area.metadata[IMPL_FOR_KEY], "Circle");
+    let fmt = elements.iter().find(|e| e.name == "fmt").unwrap();
+    assert_eq!(fmt.metadata[IMPL_TRAIT_KEY], "Display");
+
+    // inherent impl и методы трейта по умолчанию не связаны с реализацией
+    let new = elements.iter().find(|e| e.name == "new").unwrap();
+    assert!(!new.metadata.contains_key(IMPL_TRAIT_KEY));
+    let scale = elements.iter().find(|e| e.name == "scale").unwrap();
+    assert!(!scale.metadata.contains_key(IMPL_TRAIT_KEY));
+}
+
+#[test]
+fn graph_connects_implementors_to_traits_across_files() {
+    let dir = temp_dir("graph");
+    std::fs::write(
+        dir.join("store.rs"),
+        "pub trait Store {\n    fn get(&self, key: &str) -> Option<String>;\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("memory.rs"),
+        "pub struct MemoryStore {\n    items: Vec<String>,\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("memory_impl.rs"),
+        "use crate::store::Store;\n\nimpl<'a> store::Store for MemoryStore {\n    fn get(&self, key: &str) -> Option<String> {\n        self.items.iter().find(|i| i.as_str() == key).cloned()\n    }\n}\n",
+    )
+    .unwrap();
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let store = graph
+        .capsules
+        .values()
+        .find(|c| c.name == "Store" && c.capsule_type == CapsuleType::Interface)
+        .unwrap();
+    let memory = graph
+        .capsules
+        .values()
+        .find(|c| c.name == "MemoryStore")
+        .unwrap();
+    let implements: Vec<_> = graph
+        .relations
+        .iter()
+        .filter(|r| r.relation_type == RelationType::Implements)
+        .collect();
+    assert_eq!(implements.len(), 1);
+    assert_eq!(
+        (implements[0].from_id, implements[0].to_id),
+        (memory.id, store.id)
+    );
+    assert_eq!(
+        implements[0].description.as_deref(),
+        Some("impl Store for MemoryStore")
+    );
+    assert!(memory.dependencies.contains(&store.id));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Граф одного файла без метрик: только связи, которые видит RelationAnalyzer
+fn file_graph(path: &Path, source: &str) -> CapsuleGraph {
+    let elements = ParserAST::new()
+        .unwrap()
+        .parse_file(path, source, &FileType::Rust)
+        .unwrap();
+    let capsules = CapsuleConstructor::new()
+        .create_capsules(&elements, path)
+        .unwrap();
+    let relations = CapsuleGraphBuilder::new()
+        .build_advanced_relations(&capsules)
+        .unwrap();
+    common::graph(capsules, relations)
+}
+
+#[test]
+fn wide_implemented_traits_get_isp_warnings() {
+    let dir = temp_dir("isp");
+    let methods: String = (0..9).map(|i| format!("    fn op{i}(&self);\n")).collect();
+    let impl_body: String = (0..9)
+        .map(|i| format!("    fn op{i}(&self) {{}}\n"))
+        .collect();
+    let mut source = format!(
+        "pub trait Repository {{\n{methods}}}\n\npub trait Small {{\n    fn one(&self);\n}}\n"
+    );
+    for ty in ["Users", "Orders"] {
+        source.push_str(&format!(
+            "\npub struct {ty} {{}}\n\nimpl Repository for {ty} {{\n{impl_body}}}\n\nimpl Small for {ty} {{\n    fn one(&self) {{}}\n}}\n"
+        ));
+    }
+    // широкий трейт без реализаций не проверяется
+    source.push_str(&format!("\npub trait Unused {{\n{methods}}}\n"));
+    let path = dir.join("repo.rs");
+    std::fs::write(&path, &source).unwrap();
+
+    let graph = file_graph(&path, &source);
+    let warnings = SolidAnalyzer::new(SolidPrinciple::InterfaceSegregation)
+        .analyze_graph(&graph)
+        .unwrap();
+    assert_eq!(warnings.len(), 1);
+    let repository = graph
+        .capsules
+        .values()
+        .find(|c| c.name == "Repository")
+        .unwrap();
+    assert_eq!(warnings[0].capsule_id, Some(repository.id));
+    assert_eq!(warnings[0].level, Priority::Medium);
+    assert_eq!(
+        warnings[0].message,
+        "Possible ISP violation: Repository declares 9 methods and has 2 implementor(s)"
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "tree_sitter")]
+
+use archlens::parser_ast::{
+    ASTElement, ASTElementType, ParserAST, IMPLEMENTS_KEY, IMPL_FOR_KEY, IMPL_TRAIT_KEY,
+    TRAIT_METHODS_KEY,
+};
+use archlens::types::FileType;
+use std::path::Path;
+
+const STORE_GO: &str = r#"package store
+
+import (
+	"context"
+	db "database/sql"
+)
+
+type Reader interface {
+	Get(ctx context.Context, id string) (Item, error)
+}
+
+type Store interface {
+	Reader
+	Put(ctx context.Context, item Item) error
+	Delete(id string) error
+}
+
+type MemStore struct {
+	items map[string]Item
+}
+
+func (s *MemStore) Get(ctx context.Context, id string) (Item, error) {
+	if item, ok := s.items[id]; ok {
+		return item, nil
+	}
+	return Item{}, ErrNotFound
+}
+
+func (s *MemStore) Put(ctx context.Context, item Item) error {
+	s.items[item.ID] = item
+	return nil
+}
+
+func (s MemStore) size() int { return len(s.items) }
+
+func NewMemStore(conn *db.DB) *MemStore {
+	return &MemStore{items: map[string]Item{}}
+}
+"#;
+
+fn find<'a>(elements: &'a [ASTElement], name: &str) -> &'a ASTElement {
+    elements
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no element {name}"))
+}
+
+fn meta<'a>(element: &'a ASTElement, key: &str) -> Option<&'a str> {
+    element.metadata.get(key).map(String::as_str)
+}
+
+#[test]
+fn receiver_methods_and_implicit_interfaces() {
+    let elements = ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new("store/mem.go"), STORE_GO, &FileType::Go)
+        .unwrap();
+
+    let mem = find(&elements, "MemStore");
+    assert_eq!(mem.element_type, ASTElementType::Struct);
+    assert_eq!(mem.visibility, "public");
+    // Store требует Delete, которого у MemStore нет
+    assert_eq!(meta(mem, IMPLEMENTS_KEY), Some("Reader"));
+
+    let get = find(&elements, "Get");
+    assert_eq!(get.element_type, ASTElementType::Method);
+    assert_eq!(meta(get, "receiver"), Some("MemStore"));
+    assert_eq!(meta(get, IMPL_TRAIT_KEY), Some("Reader"));
+    assert_eq!(meta(get, IMPL_FOR_KEY), Some("MemStore"));
+    assert_eq!(get.parameters, vec!["ctx context.Context", "id string"]);
+    assert_eq!(get.return_type.as_deref(), Some("(Item, error)"));
+    assert_eq!(get.parent_id, Some(mem.id));
+    assert!(get.complexity > 1);
+
+    let put = find(&elements, "Put");
+    assert_eq!(put.element_type, ASTElementType::Method);
+    assert_eq!(meta(put, IMPL_TRAIT_KEY), None);
+
+    let size = find(&elements, "size");
+    assert_eq!(size.visibility, "private");
+    assert!(mem.children.contains(&size.id));
+
+    let ctor = find(&elements, "NewMemStore");
+    assert_eq!(ctor.element_type, ASTElementType::Function);
+    assert_eq!(ctor.parent_id, None);
+
+    let store = find(&elements, "Store");
+    assert_eq!(store.element_type, ASTElementType::Interface);
+    assert_eq!(meta(store, TRAIT_METHODS_KEY), Some("2"));
+
+    let mut imports: Vec<&str> = elements
+        .iter()
+        .filter(|e| e.element_type == ASTElementType::Import)
+        .map(|e| e.name.as_str())
+        .collect();
+    imports.sort();
+    assert_eq!(imports, vec!["context", "database/sql"]);
+}
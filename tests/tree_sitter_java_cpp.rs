@@ -0,0 +1,176 @@
+#![cfg(feature = "tree_sitter")]
+
+use archlens::parser_ast::{ASTElement, ASTElementType, ParserAST, IMPLEMENTS_KEY};
+use archlens::types::FileType;
+use std::path::Path;
+
+const ORDER_JAVA: &str = r#"package shop;
+
+import java.util.List;
+import static java.util.Objects.requireNonNull;
+
+public class OrderService extends BaseService implements Handler<Order>, java.io.Closeable {
+    private final List<Order> orders;
+
+    public OrderService(List<Order> orders) {
+        this.orders = requireNonNull(orders);
+    }
+
+    protected int total(Order order, int discount) {
+        if (discount > 0) {
+            return order.sum() - discount;
+        }
+        return order.sum();
+    }
+
+    void close() {}
+}
+
+interface Handler<T> {
+    void handle(T item);
+}
+"#;
+
+const SHAPE_CPP: &str = r#"#include <vector>
+#include "shape.h"
+
+namespace geo {
+
+class Shape : public Base {
+    int id;
+public:
+    virtual double area() const = 0;
+    Shape* clone();
+protected:
+    void touch() { id++; }
+};
+
+struct Point {
+    double x, y;
+    double norm() const;
+};
+
+double Point::norm() const {
+    return x * x + y * y;
+}
+
+static int helper(int a, int b) {
+    return a + b;
+}
+
+}
+"#;
+
+fn parse(path: &str, content: &str, file_type: FileType) -> Vec<ASTElement> {
+    ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new(path), content, &file_type)
+        .unwrap()
+}
+
+fn named<'a>(elements: &'a [ASTElement], name: &str) -> Vec<&'a ASTElement> {
+    elements.iter().filter(|e| e.name == name).collect()
+}
+
+fn find<'a>(elements: &'a [ASTElement], name: &str) -> &'a ASTElement {
+    named(elements, name)
+        .first()
+        .copied()
+        .unwrap_or_else(|| panic!("no element {name}"))
+}
+
+#[test]
+fn java_classes_methods_and_visibility() {
+    let elements = parse("src/OrderService.java", ORDER_JAVA, FileType::Java);
+    let service = find(&elements, "OrderService");
+    assert_eq!(service.element_type, ASTElementType::Class);
+    assert_eq!(service.visibility, "public");
+    assert_eq!(
+        service.metadata.get(IMPLEMENTS_KEY).map(String::as_str),
+        Some("Closeable,Handler")
+    );
+    assert_eq!(
+        service.metadata.get("bases").map(String::as_str),
+        Some("BaseService")
+    );
+
+    let ctor = named(&elements, "OrderService")
+        .into_iter()
+        .find(|e| e.element_type == ASTElementType::Method)
+        .unwrap();
+    assert_eq!(
+        ctor.metadata.get("constructor").map(String::as_str),
+        Some("true")
+    );
+
+    let total = find(&elements, "total");
+    assert_eq!(total.element_type, ASTElementType::Method);
+    assert_eq!(total.visibility, "protected");
+    assert_eq!(total.parameters, vec!["Order order", "int discount"]);
+    assert_eq!(total.return_type.as_deref(), Some("int"));
+    assert_eq!(total.parent_id, Some(service.id));
+    assert!(total.complexity > 1);
+
+    assert_eq!(find(&elements, "close").visibility, "package");
+    assert_eq!(find(&elements, "handle").visibility, "public");
+    assert_eq!(
+        find(&elements, "Handler").element_type,
+        ASTElementType::Interface
+    );
+
+    // поля и вызовы не распознаются как функции
+    for noise in ["orders", "requireNonNull", "sum"] {
+        assert!(named(&elements, noise).is_empty(), "{noise}");
+    }
+    let imports: Vec<&str> = elements
+        .iter()
+        .filter(|e| e.element_type == ASTElementType::Import)
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(imports.len(), 2);
+    assert!(imports.contains(&"java.util.Objects.requireNonNull"));
+}
+
+#[test]
+fn cpp_classes_methods_and_access_sections() {
+    let elements = parse("src/shape.cpp", SHAPE_CPP, FileType::Cpp);
+    let shape = find(&elements, "Shape");
+    assert_eq!(shape.element_type, ASTElementType::Class);
+    assert_eq!(
+        shape.metadata.get("bases").map(String::as_str),
+        Some("Base")
+    );
+
+    let area = find(&elements, "area");
+    assert_eq!(area.element_type, ASTElementType::Method);
+    assert_eq!(area.visibility, "public");
+    assert_eq!(area.parent_id, Some(shape.id));
+    assert_eq!(find(&elements, "clone").visibility, "public");
+    assert_eq!(find(&elements, "touch").visibility, "protected");
+
+    // определение вне класса привязано к своему типу
+    let point = find(&elements, "Point");
+    assert_eq!(point.element_type, ASTElementType::Struct);
+    let norms = named(&elements, "norm");
+    assert_eq!(norms.len(), 2);
+    let definition = norms
+        .iter()
+        .find(|e| !e.metadata.contains_key("declaration"))
+        .unwrap();
+    assert_eq!(definition.element_type, ASTElementType::Method);
+    assert_eq!(definition.parent_id, Some(point.id));
+    assert_eq!(
+        definition.metadata.get("owner").map(String::as_str),
+        Some("Point")
+    );
+
+    let helper = find(&elements, "helper");
+    assert_eq!(helper.element_type, ASTElementType::Function);
+    assert_eq!(helper.visibility, "private");
+    assert_eq!(helper.parameters, vec!["int a", "int b"]);
+
+    assert_eq!(find(&elements, "geo").element_type, ASTElementType::Module);
+    // член-данные класса — не функция
+    assert!(named(&elements, "id").is_empty());
+    assert!(!named(&elements, "vector").is_empty());
+}
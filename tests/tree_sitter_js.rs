@@ -0,0 +1,147 @@
+#![cfg(feature = "tree_sitter")]
+
+use archlens::parser_ast::{ASTElement, ASTElementType, ParserAST, TRAIT_METHODS_KEY};
+use archlens::types::FileType;
+use std::path::Path;
+
+const SERVICE_TS: &str = r#"import { Repo } from "./repo";
+
+interface Store {
+    load(id: string): Item;
+    save(item: Item): void;
+}
+
+export class Service implements Store {
+    private cache = new Map();
+
+    constructor(private repo: Repo) {}
+
+    load(id: string): Item {
+        const pick = (x: Item) => x.id === id;
+        return this.repo.find(pick);
+    }
+
+    private save(item: Item, force = compute(1)): void {
+        if (force) {
+            this.repo.put(item);
+        }
+    }
+
+    handle = (event: Event): boolean => {
+        return event.ok;
+    };
+}
+
+export const helper = async (a: number, b: number): Promise<number> => a + b;
+
+function outer() {
+    function inner() {}
+    return inner;
+}
+"#;
+
+const HANDLERS_JS: &str = r#"const api = {
+    get: function (url) { return fetch(url); },
+    post: (url, body) => fetch(url, { body }),
+};
+
+class Widget {
+    #secret = () => 1;
+    render() { return null; }
+}
+
+export default function* ids() { yield 1; }
+"#;
+
+fn parse(path: &str, content: &str, file_type: FileType) -> Vec<ASTElement> {
+    ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new(path), content, &file_type)
+        .unwrap()
+}
+
+fn find<'a>(elements: &'a [ASTElement], name: &str) -> &'a ASTElement {
+    elements
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no element {name}"))
+}
+
+#[test]
+fn typescript_methods_and_arrow_functions() {
+    let elements = parse("src/service.ts", SERVICE_TS, FileType::TypeScript);
+    let service = find(&elements, "Service");
+    assert_eq!(service.element_type, ASTElementType::Class);
+
+    // сигнатура интерфейса и реализация в классе — разные элементы
+    let save = elements
+        .iter()
+        .find(|e| e.name == "save" && e.visibility == "private")
+        .unwrap();
+    assert_eq!(save.element_type, ASTElementType::Method);
+    assert_eq!(save.parameters, vec!["item: Item", "force = compute(1)"]);
+    assert_eq!(save.return_type.as_deref(), Some("void"));
+    assert_eq!(save.parent_id, Some(service.id));
+    assert_eq!((save.start_line, save.end_line), (18, 22));
+
+    let handle = find(&elements, "handle");
+    assert_eq!(handle.element_type, ASTElementType::Method);
+    assert_eq!(handle.return_type.as_deref(), Some("boolean"));
+    assert!(service.children.contains(&handle.id));
+
+    // поле без функции — не метод
+    assert!(elements.iter().all(|e| e.name != "cache"));
+
+    let helper = find(&elements, "helper");
+    assert_eq!(helper.element_type, ASTElementType::Function);
+    assert_eq!(helper.parameters, vec!["a: number", "b: number"]);
+    assert_eq!(helper.return_type.as_deref(), Some("Promise<number>"));
+
+    let pick = find(&elements, "pick");
+    assert_eq!(pick.element_type, ASTElementType::Function);
+    let pick_parent = elements
+        .iter()
+        .find(|e| Some(e.id) == pick.parent_id)
+        .unwrap();
+    assert_eq!(pick_parent.name, "load");
+    assert_eq!(pick_parent.element_type, ASTElementType::Method);
+
+    let inner = find(&elements, "inner");
+    assert_eq!(inner.parent_id, Some(find(&elements, "outer").id));
+
+    let store = find(&elements, "Store");
+    assert_eq!(store.element_type, ASTElementType::Interface);
+    assert_eq!(
+        store.metadata.get(TRAIT_METHODS_KEY).map(String::as_str),
+        Some("2")
+    );
+
+    assert!(elements
+        .iter()
+        .any(|e| e.element_type == ASTElementType::Import && e.content.contains("./repo")));
+}
+
+#[test]
+fn javascript_object_methods_and_private_fields() {
+    let elements = parse("web/handlers.js", HANDLERS_JS, FileType::JavaScript);
+    for name in ["get", "post", "render"] {
+        assert_eq!(find(&elements, name).element_type, ASTElementType::Method);
+    }
+    assert_eq!(find(&elements, "post").parameters, vec!["url", "body"]);
+    let secret = find(&elements, "#secret");
+    assert_eq!(secret.visibility, "private");
+    assert_eq!(secret.parent_id, Some(find(&elements, "Widget").id));
+    assert_eq!(
+        find(&elements, "ids").element_type,
+        ASTElementType::Function
+    );
+}
+
+#[test]
+fn tsx_files_use_the_tsx_grammar() {
+    let content = "export const App = () => <div>{items.map((i) => <Row key={i} />)}</div>;\n";
+    let elements = parse("ui/App.tsx", content, FileType::TypeScript);
+    let app = find(&elements, "App");
+    assert_eq!(app.element_type, ASTElementType::Function);
+    assert_eq!(app.metadata.get("arrow").map(String::as_str), Some("true"));
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "tree_sitter")]
+
+use archlens::parser_ast::{ASTElement, ASTElementType, ParserAST};
+use archlens::types::FileType;
+use std::path::Path;
+
+const SERVICE_PY: &str = r#"import os
+from typing import List
+
+
+@dataclass(frozen=True)
+class Service(Base, metaclass=Meta):
+    def __init__(self, repo):
+        self.repo = repo
+
+    @staticmethod
+    def build(config: dict) -> "Service":
+        return Service(config["repo"])
+
+    async def fetch(self, ids: List[int], *, timeout=5) -> List[dict]:
+        def key(item):
+            return item["id"]
+        return sorted(await self.repo.get(ids), key=key)
+
+    def _cache(self):
+        pass
+
+    def __secret(self):
+        pass
+
+
+@app.route("/health")
+async def health():
+    return "ok"
+"#;
+
+fn parse(content: &str) -> Vec<ASTElement> {
+    ParserAST::new()
+        .unwrap()
+        .parse_file(Path::new("app/service.py"), content, &FileType::Python)
+        .unwrap()
+}
+
+fn find<'a>(elements: &'a [ASTElement], name: &str) -> &'a ASTElement {
+    elements
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no element {name}"))
+}
+
+#[test]
+fn class_methods_are_nested_under_their_class() {
+    let elements = parse(SERVICE_PY);
+    let service = find(&elements, "Service");
+    assert_eq!(service.element_type, ASTElementType::Class);
+    assert_eq!(
+        service.metadata.get("bases").map(String::as_str),
+        Some("Base")
+    );
+    assert_eq!(
+        service.metadata.get("decorators").map(String::as_str),
+        Some("dataclass")
+    );
+    // диапазон класса начинается с декоратора
+    assert_eq!(service.start_line, 5);
+
+    for name in ["__init__", "build", "fetch", "_cache", "__secret"] {
+        let method = find(&elements, name);
+        assert_eq!(method.element_type, ASTElementType::Method, "{name}");
+        assert_eq!(method.parent_id, Some(service.id), "{name}");
+        assert!(service.children.contains(&method.id), "{name}");
+    }
+    assert_eq!(find(&elements, "_cache").visibility, "protected");
+    assert_eq!(find(&elements, "__secret").visibility, "private");
+    assert_eq!(find(&elements, "__init__").visibility, "public");
+
+    // функция внутри метода — не метод класса
+    let key = find(&elements, "key");
+    assert_eq!(key.element_type, ASTElementType::Function);
+    assert_eq!(key.parent_id, Some(find(&elements, "fetch").id));
+}
+
+#[test]
+fn decorated_and_async_definitions() {
+    let elements = parse(SERVICE_PY);
+
+    let fetch = find(&elements, "fetch");
+    assert_eq!(
+        fetch.metadata.get("async").map(String::as_str),
+        Some("true")
+    );
+    assert_eq!(fetch.parameters, vec!["ids: List[int]", "*", "timeout=5"]);
+    assert_eq!(fetch.return_type.as_deref(), Some("List[dict]"));
+
+    // у staticmethod первый параметр — не `self`
+    let build = find(&elements, "build");
+    assert_eq!(build.parameters, vec!["config: dict"]);
+    assert_eq!(build.start_line, 10);
+
+    let health = find(&elements, "health");
+    assert_eq!(health.element_type, ASTElementType::Function);
+    assert_eq!(health.parent_id, None);
+    assert_eq!(
+        health.metadata.get("decorators").map(String::as_str),
+        Some("app.route")
+    );
+    assert_eq!((health.start_line, health.end_line), (26, 28));
+
+    let imports = elements
+        .iter()
+        .filter(|e| e.element_type == ASTElementType::Import)
+        .count();
+    assert_eq!(imports, 2);
+}
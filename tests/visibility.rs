@@ -0,0 +1,103 @@
+use archlens::cli::handlers::build_project_graph;
+use archlens::exporter::Exporter;
+use archlens::visibility::*;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "archlens_visibility_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, relative: &str, source: &str) {
+    let path = dir.join(relative);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source).unwrap();
+}
+
+#[test]
+fn unused_pub_items_are_suggested_narrower_visibility() {
+    let dir = temp_dir("project");
+    write(
+        &dir,
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+    );
+    write(&dir, "src/lib.rs", "pub mod store;\npub mod util;\n");
+    write(
+        &dir,
+        "src/util.rs",
+        "pub fn shared() -> u32 {\n    1\n}\n\npub fn lonely() -> u32 {\n    shared() + 1\n}\n\npub fn exposed() -> u32 {\n    3\n}\n",
+    );
+    write(
+        &dir,
+        "src/store.rs",
+        "use crate::util::shared;\n\npub fn load() -> u32 {\n    shared()\n}\n",
+    );
+    write(
+        &dir,
+        "src/main.rs",
+        "fn main() {\n    println!(\"{}\", demo::store::load() + demo::util::exposed());\n}\n",
+    );
+    write(
+        &dir,
+        "web/view.ts",
+        "import { format } from './format';\n\nexport function render(): string {\n  return format(1);\n}\n",
+    );
+    write(
+        &dir,
+        "web/format.ts",
+        "export function format(value: number): string {\n  return `${value}`;\n}\n",
+    );
+
+    let graph = build_project_graph(dir.to_str().unwrap()).unwrap();
+    let report = VisibilityReport::from_graph(&graph).unwrap();
+    let found: Vec<(String, String, SuggestedVisibility, bool)> = report
+        .modules
+        .iter()
+        .flat_map(|m| {
+            m.candidates.iter().map(|c| {
+                (
+                    m.file.display().to_string(),
+                    c.name.clone(),
+                    c.suggested,
+                    c.library,
+                )
+            })
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                "src/util.rs".to_string(),
+                "shared".to_string(),
+                SuggestedVisibility::Crate,
+                true
+            ),
+            (
+                "src/util.rs".to_string(),
+                "lonely".to_string(),
+                SuggestedVisibility::Private,
+                true
+            ),
+            (
+                "web/view.ts".to_string(),
+                "render".to_string(),
+                SuggestedVisibility::Private,
+                false
+            ),
+        ]
+    );
+
+    let compact = Exporter::new().export_to_ai_compact(&graph).unwrap();
+    assert!(compact.contains(
+        "## Visibility Candidates (3)\n- src/util.rs\n  - fn shared -> pub(crate) (library API?)\n  - fn lonely -> private (library API?)\n- web/view.ts\n  - function render -> no export\n"
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+}